@@ -2,7 +2,7 @@
 //!
 //! Access via `db.algorithms` - all the classic algorithms are here:
 //! traversals, shortest paths, centrality measures, community detection,
-//! spanning trees, and network flow.
+//! spanning trees, network flow, and triangle counting.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -664,6 +664,58 @@ impl PyAlgorithms {
         }
     }
 
+    /// Count triangles (treating graph as undirected).
+    ///
+    /// Parallel edges and self-loops are ignored, so three nodes form at
+    /// most one triangle.
+    ///
+    /// Args:
+    ///     per_node: If True, also return the triangle count for each node
+    ///
+    /// Returns:
+    ///     If per_node is False: Total number of triangles
+    ///     If per_node is True: Dict with "total" and "per_node" (node ID -> count)
+    #[pyo3(signature = (per_node=false))]
+    fn triangle_count(&self, per_node: bool, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let db = self.db.read();
+        let store = db.store();
+        let result = algorithms::triangle_count(store);
+
+        if per_node {
+            let counts: HashMap<u64, u64> =
+                result.per_node.into_iter().map(|(n, c)| (n.0, c)).collect();
+            let dict = PyDict::new(py);
+            dict.set_item("total", result.total)?;
+            dict.set_item("per_node", counts.into_pyobject(py)?)?;
+            Ok(dict.into_any().unbind())
+        } else {
+            Ok(result.total.into_pyobject(py)?.into_any().unbind())
+        }
+    }
+
+    /// Local clustering coefficient of each node (treating graph as undirected).
+    ///
+    /// The fraction of a node's neighbor pairs that are themselves linked.
+    /// Nodes with fewer than two neighbors get 0.0.
+    ///
+    /// Returns:
+    ///     Dict with "coefficients" (node ID -> coefficient) and "average"
+    fn clustering_coefficient(&self, py: Python<'_>) -> PyResult<Py<PyAny>> {
+        let db = self.db.read();
+        let store = db.store();
+        let result = algorithms::clustering_coefficient(store);
+
+        let coefficients: HashMap<u64, f64> = result
+            .coefficients
+            .into_iter()
+            .map(|(n, c)| (n.0, c))
+            .collect();
+        let dict = PyDict::new(py);
+        dict.set_item("coefficients", coefficients.into_pyobject(py)?)?;
+        dict.set_item("average", result.average)?;
+        Ok(dict.into_any().unbind())
+    }
+
     fn __repr__(&self) -> String {
         "Algorithms()".to_string()
     }
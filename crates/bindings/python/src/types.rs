@@ -11,17 +11,22 @@
 //! | `dict` | `Map` | Keys must be strings |
 //! | `bytes` | `Bytes` | |
 //! | `datetime` | `Timestamp` | Converted to/from UTC |
+//! | `date` | `Date` | |
+//! | `timedelta` | `Duration` | Microsecond precision |
 
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use pyo3::prelude::*;
-use pyo3::types::{PyBytes, PyDateTime, PyDict, PyList};
+use pyo3::types::{PyBytes, PyDate, PyDateTime, PyDelta, PyDict, PyList};
 
 use grafeo_common::types::{PropertyKey, Timestamp, Value};
 
 use crate::error::{PyGrafeoError, PyGrafeoResult};
 
+/// Proleptic Gregorian ordinal of 1970-01-01, as Python's `date.toordinal()` counts.
+const UNIX_EPOCH_ORDINAL: i64 = 719_163;
+
 /// Wraps a Grafeo value for explicit type handling.
 ///
 /// Usually you don't need this - Python types convert automatically. Use this
@@ -187,6 +192,30 @@ impl PyValue {
             return Ok(Value::Timestamp(Timestamp::from_micros(micros)));
         }
 
+        // Handle date, after datetime since every datetime is also a date
+        if obj.is_instance_of::<PyDate>() {
+            let ordinal: i64 = obj
+                .call_method0("toordinal")
+                .and_then(|ordinal| ordinal.extract())
+                .map_err(|e| PyGrafeoError::Type(format!("Failed to get date ordinal: {}", e)))?;
+            let days = i32::try_from(ordinal - UNIX_EPOCH_ORDINAL)
+                .map_err(|e| PyGrafeoError::Type(format!("Date out of range: {}", e)))?;
+            return Ok(Value::Date(days));
+        }
+
+        // Handle timedelta
+        if obj.is_instance_of::<PyDelta>() {
+            let field = |name: &str| -> PyGrafeoResult<i64> {
+                obj.getattr(name)
+                    .and_then(|v| v.extract())
+                    .map_err(|e| PyGrafeoError::Type(format!("Failed to read timedelta: {}", e)))
+            };
+            let seconds = field("days")? * 86_400 + field("seconds")?;
+            return Ok(Value::Duration(
+                seconds * 1_000_000 + field("microseconds")?,
+            ));
+        }
+
         let type_name = obj
             .get_type()
             .name()
@@ -256,6 +285,36 @@ impl PyValue {
                     .map(|dt| dt.unbind().into_any())
                     .unwrap_or_else(|_| py.None())
             }
+            Value::Date(days) => {
+                let date_class = py
+                    .import("datetime")
+                    .and_then(|module| module.getattr("date"))
+                    .expect("datetime.date should exist");
+                date_class
+                    .call_method1("fromordinal", (i64::from(*days) + UNIX_EPOCH_ORDINAL,))
+                    .map(|date| date.unbind().into_any())
+                    .unwrap_or_else(|_| py.None())
+            }
+            Value::Duration(micros) => {
+                let timedelta_class = py
+                    .import("datetime")
+                    .and_then(|module| module.getattr("timedelta"))
+                    .expect("datetime.timedelta should exist");
+                timedelta_class
+                    .call1((0, 0, *micros))
+                    .map(|delta| delta.unbind().into_any())
+                    .unwrap_or_else(|_| py.None())
+            }
+            Value::Point { x, y, srid } => {
+                let dict = PyDict::new(py);
+                dict.set_item("x", x)
+                    .expect("dict.set_item only fails on memory exhaustion");
+                dict.set_item("y", y)
+                    .expect("dict.set_item only fails on memory exhaustion");
+                dict.set_item("srid", srid)
+                    .expect("dict.set_item only fails on memory exhaustion");
+                dict.unbind().into_any()
+            }
         }
     }
 }
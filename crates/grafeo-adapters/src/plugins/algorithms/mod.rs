@@ -11,6 +11,7 @@
 //! | Centrality | PageRank, betweenness, closeness, degree |
 //! | Community | Louvain, label propagation |
 //! | Structure | K-core, bridges, articulation points |
+//! | Triangles | Triangle counts, clustering coefficient |
 //!
 //! ## Usage
 //!
@@ -41,6 +42,7 @@ mod shortest_path;
 mod structure;
 mod traits;
 mod traversal;
+mod triangles;
 
 // Core traits
 pub use traits::{
@@ -80,6 +82,12 @@ pub use flow::{MaxFlowResult, MinCostFlowResult, max_flow, min_cost_max_flow};
 // Structure analysis algorithms
 pub use structure::{KCoreResult, articulation_points, bridges, k_core, kcore_decomposition};
 
+// Triangle counting and clustering
+pub use triangles::{
+    ClusteringCoefficientResult, TriangleCountResult, clustering_coefficient,
+    clustering_coefficient_parallel, triangle_count, triangle_count_parallel,
+};
+
 // Algorithm wrappers (for future registry integration)
 pub use centrality::{
     BetweennessCentralityAlgorithm, ClosenessCentralityAlgorithm, DegreeCentralityAlgorithm,
@@ -94,3 +102,4 @@ pub use mst::{KruskalAlgorithm, PrimAlgorithm};
 pub use shortest_path::{BellmanFordAlgorithm, DijkstraAlgorithm, FloydWarshallAlgorithm};
 pub use structure::{ArticulationPointsAlgorithm, BridgesAlgorithm, KCoreAlgorithm};
 pub use traversal::{BfsAlgorithm, DfsAlgorithm};
+pub use triangles::{ClusteringCoefficientAlgorithm, TriangleCountAlgorithm};
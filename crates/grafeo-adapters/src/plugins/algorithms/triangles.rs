@@ -0,0 +1,648 @@
+//! Triangle counting and clustering coefficients.
+//!
+//! Triangles are found by intersecting sorted adjacency lists, and the local
+//! clustering coefficient of a node follows directly from its triangle count
+//! and degree. The graph is treated as
+//! simple and undirected: edge direction is ignored, parallel edges between
+//! the same pair of nodes collapse into one, and self-loops are dropped. So
+//! three nodes form at most one triangle no matter how many edges connect them.
+
+use std::sync::{Arc, OnceLock};
+use std::thread;
+
+use parking_lot::Mutex;
+
+use grafeo_common::types::{NodeId, Value};
+use grafeo_common::utils::error::Result;
+use grafeo_common::utils::hash::FxHashMap;
+use grafeo_core::execution::parallel::{MorselScheduler, WorkerHandle, generate_morsels};
+use grafeo_core::graph::Direction;
+use grafeo_core::graph::lpg::LpgStore;
+
+use super::super::{AlgorithmResult, ParameterDef, Parameters};
+use super::traits::{GraphAlgorithm, ParallelGraphAlgorithm};
+
+/// Below this many nodes, counting runs on the calling thread.
+const PARALLEL_THRESHOLD: usize = 50;
+
+/// Number of nodes handed to a worker at a time.
+///
+/// Much smaller than a row morsel: per-node work is proportional to degree,
+/// so small morsels let work-stealing even out hub-heavy ranges.
+const NODES_PER_MORSEL: usize = 256;
+
+// ============================================================================
+// Sorted Adjacency
+// ============================================================================
+
+/// Simple undirected view of the graph with sorted neighbor lists.
+pub(crate) struct SortedAdjacency {
+    /// Node ID for each dense index.
+    pub(crate) nodes: Vec<NodeId>,
+    /// Sorted, deduplicated neighbor indices per node (no self-loops).
+    pub(crate) neighbors: Vec<Vec<usize>>,
+}
+
+impl SortedAdjacency {
+    /// Builds the adjacency from the store, ignoring direction, parallel
+    /// edges and self-loops.
+    pub(crate) fn build(store: &LpgStore) -> Self {
+        let nodes = store.node_ids();
+        let n = nodes.len();
+
+        let mut node_to_idx: FxHashMap<NodeId, usize> = FxHashMap::default();
+        for (idx, &node) in nodes.iter().enumerate() {
+            node_to_idx.insert(node, idx);
+        }
+
+        let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (i, &node) in nodes.iter().enumerate() {
+            for (target, _) in store.edges_from(node, Direction::Outgoing) {
+                if let Some(&j) = node_to_idx.get(&target) {
+                    if i != j {
+                        neighbors[i].push(j);
+                        neighbors[j].push(i);
+                    }
+                }
+            }
+        }
+
+        for list in &mut neighbors {
+            list.sort_unstable();
+            list.dedup();
+        }
+
+        Self { nodes, neighbors }
+    }
+
+    /// Returns the number of nodes.
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+}
+
+/// Calls `on_match` for every value present in both sorted slices.
+///
+/// Linear merge over the two lists, O(|left| + |right|).
+pub(crate) fn intersect_sorted(left: &[usize], right: &[usize], mut on_match: impl FnMut(usize)) {
+    let (mut li, mut ri) = (0, 0);
+    while li < left.len() && ri < right.len() {
+        match left[li].cmp(&right[ri]) {
+            std::cmp::Ordering::Less => li += 1,
+            std::cmp::Ordering::Greater => ri += 1,
+            std::cmp::Ordering::Equal => {
+                on_match(left[li]);
+                li += 1;
+                ri += 1;
+            }
+        }
+    }
+}
+
+/// Counts triangles whose smallest node index lies in `start..end`.
+///
+/// Each triangle `u < v < w` is found exactly once, from `u`, and credited
+/// to all three corners in `counts`.
+fn count_range(adj: &SortedAdjacency, start: usize, end: usize, counts: &mut [u64]) {
+    for u in start..end {
+        let nu = &adj.neighbors[u];
+        let higher = &nu[nu.partition_point(|&x| x <= u)..];
+        for (pos, &v) in higher.iter().enumerate() {
+            // Only w > v is left in `higher` past v
+            let rest = &higher[pos + 1..];
+            let nv = &adj.neighbors[v];
+            let nv_higher = &nv[nv.partition_point(|&x| x <= v)..];
+            intersect_sorted(rest, nv_higher, |w| {
+                counts[u] += 1;
+                counts[v] += 1;
+                counts[w] += 1;
+            });
+        }
+    }
+}
+
+// ============================================================================
+// Triangle Count
+// ============================================================================
+
+/// Result of triangle counting.
+#[derive(Debug, Clone)]
+pub struct TriangleCountResult {
+    /// Number of distinct triangles in the graph.
+    pub total: u64,
+    /// Number of triangles each node participates in.
+    pub per_node: FxHashMap<NodeId, u64>,
+}
+
+impl TriangleCountResult {
+    /// Returns the number of triangles containing `node` (0 if unknown).
+    pub fn count(&self, node: NodeId) -> u64 {
+        self.per_node.get(&node).copied().unwrap_or(0)
+    }
+}
+
+/// Counts triangles in the graph.
+///
+/// Uses all available cores once the graph is large enough to benefit.
+/// The graph is treated as simple and undirected - see the module docs.
+///
+/// # Complexity
+///
+/// O(E^1.5) with sorted-adjacency intersection
+pub fn triangle_count(store: &LpgStore) -> TriangleCountResult {
+    triangle_count_parallel(store, available_threads())
+}
+
+/// Default worker count: one per available core.
+fn available_threads() -> usize {
+    thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+}
+
+/// Counts triangles using up to `num_threads` worker threads.
+///
+/// Nodes are split into morsels and handed out through the morsel
+/// scheduler; each worker accumulates per-node counts locally and merges
+/// them at the end. Small graphs, or `num_threads <= 1`, run sequentially.
+pub fn triangle_count_parallel(store: &LpgStore, num_threads: usize) -> TriangleCountResult {
+    let adj = SortedAdjacency::build(store);
+    let counts = triangles_per_node(&adj, num_threads);
+
+    let total = counts.iter().sum::<u64>() / 3;
+    let per_node = adj.nodes.iter().copied().zip(counts).collect();
+
+    TriangleCountResult { total, per_node }
+}
+
+/// Per-index triangle counts, sequential for small graphs or a single thread.
+fn triangles_per_node(adj: &SortedAdjacency, num_threads: usize) -> Vec<u64> {
+    let n = adj.len();
+    if num_threads <= 1 || n < PARALLEL_THRESHOLD {
+        let mut counts = vec![0u64; n];
+        count_range(adj, 0, n, &mut counts);
+        counts
+    } else {
+        count_with_morsels(adj, num_threads)
+    }
+}
+
+/// Runs [`count_range`] over node morsels on a pool of scoped workers.
+fn count_with_morsels(adj: &SortedAdjacency, num_threads: usize) -> Vec<u64> {
+    let n = adj.len();
+
+    let scheduler = Arc::new(MorselScheduler::new(num_threads));
+    scheduler.submit_batch(generate_morsels(n, NODES_PER_MORSEL, 0));
+    scheduler.finish_submission();
+
+    let merged = Mutex::new(vec![0u64; n]);
+
+    thread::scope(|s| {
+        for _ in 0..num_threads {
+            let scheduler = Arc::clone(&scheduler);
+            let merged = &merged;
+
+            s.spawn(move || {
+                let handle = WorkerHandle::new(scheduler);
+                let mut local = vec![0u64; n];
+
+                while let Some(morsel) = handle.get_work() {
+                    count_range(adj, morsel.start_row, morsel.end_row, &mut local);
+                    handle.complete_morsel();
+                }
+
+                let mut merged = merged.lock();
+                for (total, count) in merged.iter_mut().zip(local) {
+                    *total += count;
+                }
+            });
+        }
+    });
+
+    merged.into_inner()
+}
+
+// ============================================================================
+// Clustering Coefficient
+// ============================================================================
+
+/// Result of clustering coefficient computation.
+#[derive(Debug, Clone)]
+pub struct ClusteringCoefficientResult {
+    /// Local clustering coefficient per node, in `[0, 1]`.
+    pub coefficients: FxHashMap<NodeId, f64>,
+    /// Average of the local coefficients over all nodes.
+    pub average: f64,
+}
+
+/// Computes the local clustering coefficient of every node.
+///
+/// For a node with `k` distinct neighbors and `t` triangles, the coefficient
+/// is `2t / (k * (k - 1))`: the fraction of neighbor pairs that are linked.
+/// Nodes with fewer than two neighbors have no pairs and get `0.0`, so they
+/// pull the average down (the same convention as NetworkX).
+///
+/// The graph is treated as simple and undirected, like [`triangle_count`].
+pub fn clustering_coefficient(store: &LpgStore) -> ClusteringCoefficientResult {
+    clustering_coefficient_parallel(store, available_threads())
+}
+
+/// Computes local clustering coefficients using up to `num_threads` workers.
+pub fn clustering_coefficient_parallel(
+    store: &LpgStore,
+    num_threads: usize,
+) -> ClusteringCoefficientResult {
+    let adj = SortedAdjacency::build(store);
+    let triangles = triangles_per_node(&adj, num_threads);
+
+    let mut coefficients: FxHashMap<NodeId, f64> = FxHashMap::default();
+    let mut sum = 0.0;
+    for (idx, &node) in adj.nodes.iter().enumerate() {
+        let degree = adj.neighbors[idx].len() as f64;
+        let coefficient = if degree < 2.0 {
+            0.0
+        } else {
+            2.0 * triangles[idx] as f64 / (degree * (degree - 1.0))
+        };
+        sum += coefficient;
+        coefficients.insert(node, coefficient);
+    }
+
+    let average = if adj.nodes.is_empty() {
+        0.0
+    } else {
+        sum / adj.nodes.len() as f64
+    };
+
+    ClusteringCoefficientResult {
+        coefficients,
+        average,
+    }
+}
+
+// ============================================================================
+// Algorithm Wrappers for Plugin Registry
+// ============================================================================
+
+/// Static parameter definitions for triangle counting.
+static TRIANGLE_COUNT_PARAMS: OnceLock<Vec<ParameterDef>> = OnceLock::new();
+
+fn triangle_count_params() -> &'static [ParameterDef] {
+    TRIANGLE_COUNT_PARAMS.get_or_init(Vec::new)
+}
+
+/// Triangle counting algorithm wrapper.
+pub struct TriangleCountAlgorithm;
+
+impl TriangleCountAlgorithm {
+    fn to_result(counts: TriangleCountResult) -> AlgorithmResult {
+        let mut result = AlgorithmResult::new(vec!["node_id".to_string(), "triangles".to_string()]);
+
+        for (node, count) in counts.per_node {
+            result.add_row(vec![
+                Value::Int64(node.0 as i64),
+                Value::Int64(count as i64),
+            ]);
+        }
+
+        result
+    }
+}
+
+impl GraphAlgorithm for TriangleCountAlgorithm {
+    fn name(&self) -> &str {
+        "triangle_count"
+    }
+
+    fn description(&self) -> &str {
+        "Number of triangles each node participates in"
+    }
+
+    fn parameters(&self) -> &[ParameterDef] {
+        triangle_count_params()
+    }
+
+    fn execute(&self, store: &LpgStore, _params: &Parameters) -> Result<AlgorithmResult> {
+        Ok(Self::to_result(triangle_count(store)))
+    }
+}
+
+impl ParallelGraphAlgorithm for TriangleCountAlgorithm {
+    fn parallel_threshold(&self) -> usize {
+        PARALLEL_THRESHOLD
+    }
+
+    fn execute_parallel(
+        &self,
+        store: &LpgStore,
+        _params: &Parameters,
+        num_threads: usize,
+    ) -> Result<AlgorithmResult> {
+        Ok(Self::to_result(triangle_count_parallel(store, num_threads)))
+    }
+}
+
+/// Static parameter definitions for clustering coefficient.
+static CLUSTERING_COEFFICIENT_PARAMS: OnceLock<Vec<ParameterDef>> = OnceLock::new();
+
+fn clustering_coefficient_params() -> &'static [ParameterDef] {
+    CLUSTERING_COEFFICIENT_PARAMS.get_or_init(Vec::new)
+}
+
+/// Local clustering coefficient algorithm wrapper.
+pub struct ClusteringCoefficientAlgorithm;
+
+impl ClusteringCoefficientAlgorithm {
+    fn to_result(clustering: ClusteringCoefficientResult) -> AlgorithmResult {
+        let mut result = AlgorithmResult::new(vec![
+            "node_id".to_string(),
+            "clustering_coefficient".to_string(),
+        ]);
+
+        for (node, coefficient) in clustering.coefficients {
+            result.add_row(vec![
+                Value::Int64(node.0 as i64),
+                Value::Float64(coefficient),
+            ]);
+        }
+
+        result
+    }
+}
+
+impl GraphAlgorithm for ClusteringCoefficientAlgorithm {
+    fn name(&self) -> &str {
+        "clustering_coefficient"
+    }
+
+    fn description(&self) -> &str {
+        "Local clustering coefficient of each node"
+    }
+
+    fn parameters(&self) -> &[ParameterDef] {
+        clustering_coefficient_params()
+    }
+
+    fn execute(&self, store: &LpgStore, _params: &Parameters) -> Result<AlgorithmResult> {
+        Ok(Self::to_result(clustering_coefficient(store)))
+    }
+}
+
+impl ParallelGraphAlgorithm for ClusteringCoefficientAlgorithm {
+    fn parallel_threshold(&self) -> usize {
+        PARALLEL_THRESHOLD
+    }
+
+    fn execute_parallel(
+        &self,
+        store: &LpgStore,
+        _params: &Parameters,
+        num_threads: usize,
+    ) -> Result<AlgorithmResult> {
+        Ok(Self::to_result(clustering_coefficient_parallel(
+            store,
+            num_threads,
+        )))
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grafeo_common::utils::hash::FxHashSet;
+
+    /// O(V^3) reference: every unordered triple with all three pairs linked.
+    fn brute_force(store: &LpgStore) -> (u64, FxHashMap<NodeId, u64>) {
+        let nodes = store.node_ids();
+        let mut linked: FxHashSet<(NodeId, NodeId)> = FxHashSet::default();
+        for &node in &nodes {
+            for (target, _) in store.edges_from(node, Direction::Outgoing) {
+                if node != target {
+                    linked.insert((node, target));
+                    linked.insert((target, node));
+                }
+            }
+        }
+
+        let mut total = 0;
+        let mut per_node: FxHashMap<NodeId, u64> = nodes.iter().map(|&n| (n, 0)).collect();
+        for (i, &a) in nodes.iter().enumerate() {
+            for (j, &b) in nodes.iter().enumerate().skip(i + 1) {
+                for &c in nodes.iter().skip(j + 1) {
+                    if linked.contains(&(a, b))
+                        && linked.contains(&(b, c))
+                        && linked.contains(&(a, c))
+                    {
+                        total += 1;
+                        *per_node.get_mut(&a).unwrap() += 1;
+                        *per_node.get_mut(&b).unwrap() += 1;
+                        *per_node.get_mut(&c).unwrap() += 1;
+                    }
+                }
+            }
+        }
+        (total, per_node)
+    }
+
+    fn assert_matches_reference(store: &LpgStore) {
+        let (total, per_node) = brute_force(store);
+        for threads in [1, 4] {
+            let result = triangle_count_parallel(store, threads);
+            assert_eq!(result.total, total, "total with {threads} threads");
+            assert_eq!(result.per_node, per_node, "per-node with {threads} threads");
+        }
+    }
+
+    fn create_k4() -> LpgStore {
+        let store = LpgStore::new();
+        let nodes: Vec<NodeId> = (0..4).map(|_| store.create_node(&["Node"])).collect();
+        for i in 0..4 {
+            for j in (i + 1)..4 {
+                store.create_edge(nodes[i], nodes[j], "EDGE");
+                store.create_edge(nodes[j], nodes[i], "EDGE");
+            }
+        }
+        store
+    }
+
+    /// Deterministic pseudo-random graph, large enough to take the parallel path.
+    fn create_random_graph(n: usize, edges: usize, seed: u64) -> LpgStore {
+        let store = LpgStore::new();
+        let nodes: Vec<NodeId> = (0..n).map(|_| store.create_node(&["Node"])).collect();
+        let mut state = seed;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 33) as usize % n
+        };
+        for _ in 0..edges {
+            let (a, b) = (next(), next());
+            store.create_edge(nodes[a], nodes[b], "EDGE");
+        }
+        store
+    }
+
+    #[test]
+    fn test_triangle_count_empty() {
+        let store = LpgStore::new();
+        let result = triangle_count(&store);
+        assert_eq!(result.total, 0);
+        assert!(result.per_node.is_empty());
+    }
+
+    #[test]
+    fn test_triangle_count_single_triangle() {
+        let store = LpgStore::new();
+        let a = store.create_node(&["Node"]);
+        let b = store.create_node(&["Node"]);
+        let c = store.create_node(&["Node"]);
+        let d = store.create_node(&["Node"]);
+
+        // Directed cycle a -> b -> c -> a, plus a pendant edge c -> d
+        store.create_edge(a, b, "EDGE");
+        store.create_edge(b, c, "EDGE");
+        store.create_edge(c, a, "EDGE");
+        store.create_edge(c, d, "EDGE");
+
+        let result = triangle_count(&store);
+        assert_eq!(result.total, 1);
+        assert_eq!(result.count(a), 1);
+        assert_eq!(result.count(c), 1);
+        assert_eq!(result.count(d), 0);
+        assert_matches_reference(&store);
+    }
+
+    #[test]
+    fn test_triangle_count_complete_graph() {
+        let store = create_k4();
+        let result = triangle_count(&store);
+
+        // K4 has C(4,3) = 4 triangles, each node in 3 of them
+        assert_eq!(result.total, 4);
+        assert!(result.per_node.values().all(|&c| c == 3));
+        assert_matches_reference(&store);
+    }
+
+    #[test]
+    fn test_triangle_count_ignores_multi_edges_and_self_loops() {
+        let store = LpgStore::new();
+        let a = store.create_node(&["Node"]);
+        let b = store.create_node(&["Node"]);
+        let c = store.create_node(&["Node"]);
+
+        // Three parallel a-b edges of different types and directions
+        store.create_edge(a, b, "KNOWS");
+        store.create_edge(a, b, "WORKS_WITH");
+        store.create_edge(b, a, "KNOWS");
+        store.create_edge(b, c, "KNOWS");
+        store.create_edge(c, b, "KNOWS");
+        store.create_edge(a, c, "KNOWS");
+        store.create_edge(a, a, "SELF");
+
+        let result = triangle_count(&store);
+        assert_eq!(result.total, 1);
+        assert_eq!(result.count(a), 1);
+        assert_matches_reference(&store);
+    }
+
+    #[test]
+    fn test_triangle_count_random_graphs_match_reference() {
+        for seed in [1, 7, 42] {
+            let store = create_random_graph(80, 400, seed);
+            assert_matches_reference(&store);
+        }
+    }
+
+    #[test]
+    fn test_intersect_sorted() {
+        let mut common = Vec::new();
+        intersect_sorted(&[1, 3, 5, 7, 9], &[2, 3, 4, 7, 10], |x| common.push(x));
+        assert_eq!(common, vec![3, 7]);
+    }
+
+    #[test]
+    fn test_triangle_count_algorithm_wrapper() {
+        let store = create_k4();
+        let algo = TriangleCountAlgorithm;
+        assert_eq!(algo.name(), "triangle_count");
+
+        let sequential = algo.execute(&store, &Parameters::new()).unwrap();
+        let parallel = algo
+            .execute_parallel(&store, &Parameters::new(), 2)
+            .unwrap();
+        assert_eq!(sequential.columns, vec!["node_id", "triangles"]);
+        assert_eq!(sequential.rows.len(), 4);
+        assert_eq!(parallel.rows.len(), 4);
+    }
+
+    #[test]
+    fn test_clustering_coefficient_complete_graph() {
+        let store = create_k4();
+        let result = clustering_coefficient(&store);
+
+        assert!(
+            result
+                .coefficients
+                .values()
+                .all(|&c| (c - 1.0).abs() < 1e-12)
+        );
+        assert!((result.average - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_clustering_coefficient_known_values() {
+        // Triangle a-b-c with a tail c-d:
+        //   a, b: both neighbors linked -> 1.0
+        //   c: 3 neighbors, 1 linked pair -> 1/3
+        //   d: degree 1 -> 0.0
+        let store = LpgStore::new();
+        let a = store.create_node(&["Node"]);
+        let b = store.create_node(&["Node"]);
+        let c = store.create_node(&["Node"]);
+        let d = store.create_node(&["Node"]);
+        store.create_edge(a, b, "EDGE");
+        store.create_edge(b, c, "EDGE");
+        store.create_edge(c, a, "EDGE");
+        store.create_edge(c, d, "EDGE");
+        // A parallel edge must not inflate c's degree
+        store.create_edge(d, c, "EDGE");
+
+        let result = clustering_coefficient(&store);
+        assert!((result.coefficients[&a] - 1.0).abs() < 1e-12);
+        assert!((result.coefficients[&b] - 1.0).abs() < 1e-12);
+        assert!((result.coefficients[&c] - 1.0 / 3.0).abs() < 1e-12);
+        assert_eq!(result.coefficients[&d], 0.0);
+        assert!((result.average - (7.0 / 3.0) / 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_clustering_coefficient_star_and_isolated() {
+        // Star: center's neighbors are never linked; leaves have degree 1
+        let store = LpgStore::new();
+        let center = store.create_node(&["Node"]);
+        for _ in 0..4 {
+            let leaf = store.create_node(&["Node"]);
+            store.create_edge(center, leaf, "EDGE");
+        }
+        let isolated = store.create_node(&["Node"]);
+
+        let result = clustering_coefficient(&store);
+        assert!(result.coefficients.values().all(|&c| c == 0.0));
+        assert_eq!(result.coefficients[&isolated], 0.0);
+        assert_eq!(result.average, 0.0);
+    }
+
+    #[test]
+    fn test_clustering_coefficient_parallel_matches_sequential() {
+        let store = create_random_graph(80, 400, 3);
+        let sequential = clustering_coefficient_parallel(&store, 1);
+        let parallel = clustering_coefficient_parallel(&store, 4);
+        assert_eq!(sequential.coefficients, parallel.coefficients);
+        assert!((sequential.average - parallel.average).abs() < 1e-12);
+    }
+}
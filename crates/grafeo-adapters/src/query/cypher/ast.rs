@@ -61,6 +61,8 @@ pub enum Clause {
     Set(SetClause),
     /// REMOVE clause.
     Remove(RemoveClause),
+    /// FOREACH clause.
+    Foreach(ForeachClause),
 }
 
 /// A MATCH clause.
@@ -226,6 +228,20 @@ pub struct UnwindClause {
     pub span: Option<SourceSpan>,
 }
 
+/// A FOREACH clause: `FOREACH (x IN list | updates)`.
+#[derive(Debug, Clone)]
+pub struct ForeachClause {
+    /// The variable bound to each element.
+    pub variable: String,
+    /// The list expression to iterate.
+    pub list: Expression,
+    /// Update clauses run once per element (CREATE, MERGE, SET, REMOVE,
+    /// DELETE, or a nested FOREACH).
+    pub clauses: Vec<Clause>,
+    /// Source span.
+    pub span: Option<SourceSpan>,
+}
+
 /// An ORDER BY clause.
 #[derive(Debug, Clone)]
 pub struct OrderByClause {
@@ -454,6 +470,8 @@ pub enum Expression {
         /// ELSE clause.
         else_clause: Option<Box<Expression>>,
     },
+    /// Pattern expression: (a)-[:KNOWS]->(), matched per row of the outer query.
+    PatternExpression(Box<Pattern>),
     /// EXISTS subquery.
     Exists(Box<Query>),
     /// COUNT subquery.
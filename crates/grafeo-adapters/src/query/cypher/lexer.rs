@@ -61,6 +61,8 @@ pub enum TokenKind {
     Set,
     /// REMOVE
     Remove,
+    /// FOREACH
+    Foreach,
     /// ON
     On,
     /// AND
@@ -485,6 +487,7 @@ impl<'a> Lexer<'a> {
             "DETACH" => Some(TokenKind::Detach),
             "SET" => Some(TokenKind::Set),
             "REMOVE" => Some(TokenKind::Remove),
+            "FOREACH" => Some(TokenKind::Foreach),
             "ON" => Some(TokenKind::On),
             "AND" => Some(TokenKind::And),
             "OR" => Some(TokenKind::Or),
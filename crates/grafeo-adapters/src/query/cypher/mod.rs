@@ -25,6 +25,18 @@ pub fn parse(query: &str) -> Result<Statement> {
     parser.parse()
 }
 
+/// Parses a Cypher query string, rejecting expressions nested deeper than
+/// `max_depth`.
+///
+/// # Errors
+///
+/// Returns a `QueryError` if the query is syntactically invalid or nested
+/// too deeply.
+pub fn parse_with_max_depth(query: &str, max_depth: usize) -> Result<Statement> {
+    let mut parser = Parser::new(query).with_max_depth(max_depth);
+    parser.parse()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -76,4 +88,40 @@ mod tests {
         let result = parse("MERGE (n:Person {name: 'Alice'}) RETURN n");
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_parse_foreach() {
+        let result = parse(
+            "MATCH (n) FOREACH (x IN [1, 2] | SET n.last = x FOREACH (y IN [x] | CREATE (:Item {v: y})))",
+        );
+        assert!(result.is_ok());
+
+        // The body must contain an update clause
+        assert!(parse("MATCH (n) FOREACH (x IN [1] | RETURN x)").is_err());
+    }
+
+    #[test]
+    fn test_parse_pattern_expression_in_size() {
+        let result = parse("MATCH (p:Person) RETURN avg(size((p)-->()))");
+        assert!(result.is_ok());
+
+        let result = parse("MATCH (p) WHERE (p)<--() RETURN p");
+        assert!(result.is_ok());
+
+        // A parenthesised expression must still parse as one
+        let result = parse("MATCH (n) RETURN (n.age + 1) * 2");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_parse_namespaced_function() {
+        let result =
+            parse("MATCH (n) WHERE point.withinDistance(n.loc, point({x: 0, y: 0}), 5) RETURN n");
+        let debug = format!("{:?}", result.unwrap());
+        assert!(debug.contains("\"point.withinDistance\""));
+
+        // Property access on a variable is unchanged
+        let debug = format!("{:?}", parse("MATCH (n) RETURN n.loc").unwrap());
+        assert!(debug.contains("PropertyAccess"));
+    }
 }
@@ -4,6 +4,7 @@
 
 use super::ast::*;
 use super::lexer::{Lexer, Token, TokenKind};
+use crate::query::DEFAULT_MAX_NESTING_DEPTH;
 use grafeo_common::utils::error::{QueryError, QueryErrorKind, Result};
 
 /// Cypher query parser.
@@ -11,6 +12,8 @@ pub struct Parser<'a> {
     lexer: Lexer<'a>,
     current: Token,
     previous: Token,
+    depth: usize,
+    max_depth: usize,
 }
 
 impl<'a> Parser<'a> {
@@ -27,9 +30,18 @@ impl<'a> Parser<'a> {
             lexer,
             current,
             previous,
+            depth: 0,
+            max_depth: DEFAULT_MAX_NESTING_DEPTH,
         }
     }
 
+    /// Sets how deeply expressions may nest before parsing fails.
+    #[must_use]
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
     /// Parses the query into a statement.
     pub fn parse(&mut self) -> Result<Statement> {
         let stmt = self.parse_statement()?;
@@ -81,6 +93,9 @@ impl<'a> Parser<'a> {
                 TokenKind::Remove => {
                     clauses.push(Clause::Remove(self.parse_remove_clause()?));
                 }
+                TokenKind::Foreach => {
+                    clauses.push(Clause::Foreach(self.parse_foreach_clause()?));
+                }
                 TokenKind::Order => {
                     clauses.push(Clause::OrderBy(self.parse_order_by_clause()?));
                 }
@@ -191,6 +206,42 @@ impl<'a> Parser<'a> {
         })
     }
 
+    fn parse_foreach_clause(&mut self) -> Result<ForeachClause> {
+        self.expect(TokenKind::Foreach)?;
+        self.expect(TokenKind::LParen)?;
+        let variable = self.expect_identifier()?;
+        self.expect(TokenKind::In)?;
+        let list = self.parse_expression()?;
+        self.expect(TokenKind::Pipe)?;
+
+        let mut clauses = Vec::new();
+        loop {
+            let clause = match self.current.kind {
+                TokenKind::Create => Clause::Create(self.parse_create_clause()?),
+                TokenKind::Merge => Clause::Merge(self.parse_merge_clause()?),
+                TokenKind::Delete | TokenKind::Detach => {
+                    Clause::Delete(self.parse_delete_clause()?)
+                }
+                TokenKind::Set => Clause::Set(self.parse_set_clause()?),
+                TokenKind::Remove => Clause::Remove(self.parse_remove_clause()?),
+                TokenKind::Foreach => Clause::Foreach(self.parse_foreach_clause()?),
+                _ => break,
+            };
+            clauses.push(clause);
+        }
+        if clauses.is_empty() {
+            return Err(self.error("Expected an update clause in FOREACH"));
+        }
+        self.expect(TokenKind::RParen)?;
+
+        Ok(ForeachClause {
+            variable,
+            list,
+            clauses,
+            span: None,
+        })
+    }
+
     fn parse_create_clause(&mut self) -> Result<CreateClause> {
         self.expect(TokenKind::Create)?;
         let patterns = self.parse_pattern_list()?;
@@ -428,12 +479,12 @@ impl<'a> Parser<'a> {
         // Check for path continuation
         if matches!(
             self.current.kind,
-            TokenKind::Arrow | TokenKind::LeftArrow | TokenKind::Minus
+            TokenKind::Arrow | TokenKind::LeftArrow | TokenKind::Minus | TokenKind::DoubleDash
         ) {
             let mut chain = Vec::new();
             while matches!(
                 self.current.kind,
-                TokenKind::Arrow | TokenKind::LeftArrow | TokenKind::Minus
+                TokenKind::Arrow | TokenKind::LeftArrow | TokenKind::Minus | TokenKind::DoubleDash
             ) {
                 chain.push(self.parse_relationship_pattern()?);
             }
@@ -480,12 +531,12 @@ impl<'a> Parser<'a> {
         // Check for path continuation
         if matches!(
             self.current.kind,
-            TokenKind::Arrow | TokenKind::LeftArrow | TokenKind::Minus
+            TokenKind::Arrow | TokenKind::LeftArrow | TokenKind::Minus | TokenKind::DoubleDash
         ) {
             let mut chain = Vec::new();
             while matches!(
                 self.current.kind,
-                TokenKind::Arrow | TokenKind::LeftArrow | TokenKind::Minus
+                TokenKind::Arrow | TokenKind::LeftArrow | TokenKind::Minus | TokenKind::DoubleDash
             ) {
                 chain.push(self.parse_relationship_pattern()?);
             }
@@ -542,10 +593,23 @@ impl<'a> Parser<'a> {
                 (Direction::Outgoing, false)
             }
             TokenKind::LeftArrow => {
-                // <-
+                // <- or <--
                 self.advance();
+                if self.current.kind == TokenKind::Minus {
+                    self.advance();
+                }
                 (Direction::Incoming, false)
             }
+            TokenKind::DoubleDash => {
+                // -- or -->
+                self.advance();
+                if self.current.kind == TokenKind::Gt {
+                    self.advance();
+                    (Direction::Outgoing, false)
+                } else {
+                    (Direction::Undirected, false)
+                }
+            }
             TokenKind::Minus => {
                 // - followed by [ or - or >
                 self.advance();
@@ -705,7 +769,26 @@ impl<'a> Parser<'a> {
 
     // Expression parsing with precedence climbing
     fn parse_expression(&mut self) -> Result<Expression> {
-        self.parse_or_expression()
+        self.nested(Self::parse_or_expression)
+    }
+
+    /// Runs `parse` one nesting level deeper, failing past the depth limit.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.depth >= self.max_depth {
+            return Err(QueryError::new(
+                QueryErrorKind::Complexity,
+                format!(
+                    "Query nesting exceeds the maximum depth of {}",
+                    self.max_depth
+                ),
+            )
+            .with_span(self.current.span.clone())
+            .into());
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
     }
 
     fn parse_or_expression(&mut self) -> Result<Expression> {
@@ -753,7 +836,7 @@ impl<'a> Parser<'a> {
     fn parse_not_expression(&mut self) -> Result<Expression> {
         if self.current.kind == TokenKind::Not {
             self.advance();
-            let operand = self.parse_not_expression()?;
+            let operand = self.nested(Self::parse_not_expression)?;
             Ok(Expression::Unary {
                 op: UnaryOp::Not,
                 operand: Box::new(operand),
@@ -905,7 +988,7 @@ impl<'a> Parser<'a> {
         match self.current.kind {
             TokenKind::Minus => {
                 self.advance();
-                let operand = self.parse_unary_expression()?;
+                let operand = self.nested(Self::parse_unary_expression)?;
                 Ok(Expression::Unary {
                     op: UnaryOp::Neg,
                     operand: Box::new(operand),
@@ -913,7 +996,7 @@ impl<'a> Parser<'a> {
             }
             TokenKind::Plus => {
                 self.advance();
-                let operand = self.parse_unary_expression()?;
+                let operand = self.nested(Self::parse_unary_expression)?;
                 Ok(Expression::Unary {
                     op: UnaryOp::Pos,
                     operand: Box::new(operand),
@@ -931,9 +1014,17 @@ impl<'a> Parser<'a> {
                 TokenKind::Dot => {
                     self.advance();
                     let property = self.expect_identifier()?;
-                    expr = Expression::PropertyAccess {
-                        base: Box::new(expr),
-                        property,
+                    expr = match expr {
+                        // Namespaced function call, e.g. point.withinDistance(...)
+                        Expression::Variable(namespace)
+                            if self.current.kind == TokenKind::LParen =>
+                        {
+                            self.parse_aggregate_function(&format!("{namespace}.{property}"))?
+                        }
+                        base => Expression::PropertyAccess {
+                            base: Box::new(base),
+                            property,
+                        },
                     };
                 }
                 TokenKind::LBracket => {
@@ -1022,6 +1113,9 @@ impl<'a> Parser<'a> {
                 }
             }
             TokenKind::LParen => {
+                if let Some(pattern) = self.try_parse_pattern_expression() {
+                    return Ok(Expression::PatternExpression(Box::new(pattern)));
+                }
                 self.advance();
                 let expr = self.parse_expression()?;
                 self.expect(TokenKind::RParen)?;
@@ -1075,6 +1169,24 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Tries to read a relationship pattern like `(p)-[:KNOWS]->()` in
+    /// expression position, rewinding if the parentheses turn out to hold an
+    /// ordinary expression such as `(a + b)`.
+    fn try_parse_pattern_expression(&mut self) -> Option<Pattern> {
+        let saved = (
+            self.lexer.clone(),
+            self.current.clone(),
+            self.previous.clone(),
+        );
+        match self.parse_inner_pattern() {
+            Ok(pattern @ Pattern::Path(_)) => Some(pattern),
+            _ => {
+                (self.lexer, self.current, self.previous) = saved;
+                None
+            }
+        }
+    }
+
     fn parse_aggregate_function(&mut self, name: &str) -> Result<Expression> {
         self.expect(TokenKind::LParen)?;
 
@@ -56,12 +56,26 @@ pub struct HavingClause {
 pub struct SetClause {
     /// Property assignments.
     pub assignments: Vec<PropertyAssignment>,
+    /// Whole-map assignments (`n = {...}` or `n += {...}`), applied before
+    /// `assignments`.
+    pub map_assignments: Vec<MapAssignment>,
     /// Label operations (add labels to nodes).
     pub label_operations: Vec<LabelOperation>,
     /// Source span.
     pub span: Option<SourceSpan>,
 }
 
+/// An assignment of a map to all of an entity's properties.
+#[derive(Debug, Clone)]
+pub struct MapAssignment {
+    /// Variable name.
+    pub variable: String,
+    /// The map's entries.
+    pub properties: Vec<(String, Expression)>,
+    /// Whether `=` replaces all properties, rather than `+=` merging into them.
+    pub replace: bool,
+}
+
 /// A label operation for adding/removing labels.
 #[derive(Debug, Clone)]
 pub struct LabelOperation {
@@ -328,6 +342,8 @@ pub struct PropertyAssignment {
     pub property: String,
     /// Value expression.
     pub value: Expression,
+    /// Whether the value is added to the current one (`+=`).
+    pub increment: bool,
 }
 
 /// A schema statement.
@@ -415,6 +431,15 @@ pub enum Expression {
     },
     /// A list expression.
     List(Vec<Expression>),
+    /// A map literal, as in `{name: 'Alice', age: 30}`.
+    Map(Vec<(String, Expression)>),
+    /// An index or key lookup (`list[0]`, `map['key']`, `{a: 1}.a`).
+    IndexAccess {
+        /// The list or map being indexed.
+        base: Box<Expression>,
+        /// The list index or map key.
+        index: Box<Expression>,
+    },
     /// A CASE expression.
     Case {
         /// Optional input expression.
@@ -139,6 +139,8 @@ pub enum TokenKind {
     Ge,
     /// + operator.
     Plus,
+    /// += operator.
+    PlusEq,
     /// - operator.
     Minus,
     /// * operator.
@@ -262,7 +264,12 @@ impl<'a> Lexer<'a> {
             }
             '+' => {
                 self.advance();
-                TokenKind::Plus
+                if self.current_char() == '=' {
+                    self.advance();
+                    TokenKind::PlusEq
+                } else {
+                    TokenKind::Plus
+                }
             }
             '*' => {
                 self.advance();
@@ -22,6 +22,18 @@ pub fn parse(query: &str) -> Result<Statement> {
     parser.parse()
 }
 
+/// Parses a GQL query string, rejecting expressions nested deeper than
+/// `max_depth`.
+///
+/// # Errors
+///
+/// Returns a `QueryError` if the query is syntactically invalid or nested
+/// too deeply.
+pub fn parse_with_max_depth(query: &str, max_depth: usize) -> Result<Statement> {
+    let mut parser = Parser::new(query).with_max_depth(max_depth);
+    parser.parse()
+}
+
 #[cfg(test)]
 mod tests {
     #[allow(unused_imports)]
@@ -2,6 +2,7 @@
 
 use super::ast::*;
 use super::lexer::{Lexer, Token, TokenKind};
+use crate::query::DEFAULT_MAX_NESTING_DEPTH;
 use grafeo_common::utils::error::{Error, QueryError, QueryErrorKind, Result, SourceSpan};
 
 /// GQL Parser.
@@ -10,6 +11,8 @@ pub struct Parser<'a> {
     current: Token,
     peeked: Option<Token>,
     source: &'a str,
+    depth: usize,
+    max_depth: usize,
 }
 
 impl<'a> Parser<'a> {
@@ -22,9 +25,18 @@ impl<'a> Parser<'a> {
             current,
             peeked: None,
             source: input,
+            depth: 0,
+            max_depth: DEFAULT_MAX_NESTING_DEPTH,
         }
     }
 
+    /// Sets how deeply expressions may nest before parsing fails.
+    #[must_use]
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
     /// Checks if the current token can be used as a label or type name.
     /// This includes identifiers, quoted identifiers, and certain reserved keywords that are
     /// commonly used as labels (Node, Edge, Type, etc.)
@@ -279,6 +291,7 @@ impl<'a> Parser<'a> {
         self.expect(TokenKind::Set)?;
 
         let mut assignments = Vec::new();
+        let mut map_assignments = Vec::new();
         let mut label_operations = Vec::new();
 
         loop {
@@ -303,7 +316,7 @@ impl<'a> Parser<'a> {
                 }
                 label_operations.push(LabelOperation { variable, labels });
             } else if self.current.kind == TokenKind::Dot {
-                // Property assignment: SET n.prop = value
+                // Property assignment: SET n.prop = value, or SET n.prop += delta
                 self.advance();
 
                 if !self.is_label_or_type_name() {
@@ -312,7 +325,12 @@ impl<'a> Parser<'a> {
                 let property = self.current.text.clone();
                 self.advance();
 
-                self.expect(TokenKind::Eq)?;
+                let increment = match self.current.kind {
+                    TokenKind::Eq => false,
+                    TokenKind::PlusEq => true,
+                    _ => return Err(self.error("Expected '=' or '+=' after property in SET")),
+                };
+                self.advance();
 
                 let value = self.parse_expression()?;
 
@@ -320,9 +338,22 @@ impl<'a> Parser<'a> {
                     variable,
                     property,
                     value,
+                    increment,
+                });
+            } else if matches!(self.current.kind, TokenKind::Eq | TokenKind::PlusEq) {
+                // Map assignment: SET n = {...} replaces, SET n += {...} merges
+                let replace = self.current.kind == TokenKind::Eq;
+                self.advance();
+
+                let properties = self.parse_property_map()?;
+
+                map_assignments.push(MapAssignment {
+                    variable,
+                    properties,
+                    replace,
                 });
             } else {
-                return Err(self.error("Expected '.' or ':' after variable in SET"));
+                return Err(self.error("Expected '.', ':', '=' or '+=' after variable in SET"));
             }
 
             // Check for more assignments/operations
@@ -334,6 +365,7 @@ impl<'a> Parser<'a> {
 
         Ok(SetClause {
             assignments,
+            map_assignments,
             label_operations,
             span: Some(SourceSpan::new(span_start, self.current.span.end, 1, 1)),
         })
@@ -481,6 +513,7 @@ impl<'a> Parser<'a> {
                 variable,
                 property,
                 value,
+                increment: false,
             });
 
             // Check for more assignments
@@ -1019,7 +1052,28 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_expression(&mut self) -> Result<Expression> {
-        self.parse_or_expression()
+        self.nested(Self::parse_or_expression)
+    }
+
+    /// Runs `parse` one nesting level deeper, failing past the depth limit.
+    fn nested<T>(&mut self, parse: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        if self.depth >= self.max_depth {
+            return Err(Error::Query(
+                QueryError::new(
+                    QueryErrorKind::Complexity,
+                    format!(
+                        "Query nesting exceeds the maximum depth of {}",
+                        self.max_depth
+                    ),
+                )
+                .with_span(self.current.span)
+                .with_source(self.source.to_string()),
+            ));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
     }
 
     fn parse_or_expression(&mut self) -> Result<Expression> {
@@ -1109,6 +1163,20 @@ impl<'a> Parser<'a> {
                     right: Box::new(right),
                 });
             }
+            TokenKind::Is => {
+                self.advance(); // consume IS
+                let op = if self.current.kind == TokenKind::Not {
+                    self.advance();
+                    UnaryOp::IsNotNull
+                } else {
+                    UnaryOp::IsNull
+                };
+                self.expect(TokenKind::Null)?;
+                return Ok(Expression::Unary {
+                    op,
+                    operand: Box::new(left),
+                });
+            }
             _ => {}
         }
 
@@ -1162,7 +1230,7 @@ impl<'a> Parser<'a> {
         match self.current.kind {
             TokenKind::Not => {
                 self.advance();
-                let operand = self.parse_unary_expression()?;
+                let operand = self.nested(Self::parse_unary_expression)?;
                 Ok(Expression::Unary {
                     op: UnaryOp::Not,
                     operand: Box::new(operand),
@@ -1170,14 +1238,49 @@ impl<'a> Parser<'a> {
             }
             TokenKind::Minus => {
                 self.advance();
-                let operand = self.parse_unary_expression()?;
+                let operand = self.nested(Self::parse_unary_expression)?;
                 Ok(Expression::Unary {
                     op: UnaryOp::Neg,
                     operand: Box::new(operand),
                 })
             }
-            _ => self.parse_primary_expression(),
+            _ => self.parse_postfix_expression(),
+        }
+    }
+
+    /// Parses a primary expression followed by any number of `[index]` and
+    /// `.key` lookups.
+    fn parse_postfix_expression(&mut self) -> Result<Expression> {
+        let mut expr = self.parse_primary_expression()?;
+
+        loop {
+            let index = match self.current.kind {
+                TokenKind::LBracket => {
+                    self.advance();
+                    let index = self.parse_expression()?;
+                    self.expect(TokenKind::RBracket)?;
+                    index
+                }
+                // `var.prop` is parsed as a property access already; this
+                // covers keys of other values, as in `{a: 1}.a`
+                TokenKind::Dot => {
+                    self.advance();
+                    if !self.is_identifier() {
+                        return Err(self.error("Expected property name"));
+                    }
+                    let key = self.get_identifier_name();
+                    self.advance();
+                    Expression::Literal(Literal::String(key))
+                }
+                _ => break,
+            };
+            expr = Expression::IndexAccess {
+                base: Box::new(expr),
+                index: Box::new(index),
+            };
         }
+
+        Ok(expr)
     }
 
     fn parse_primary_expression(&mut self) -> Result<Expression> {
@@ -1272,41 +1375,35 @@ impl<'a> Parser<'a> {
                 let name = self.get_identifier_name();
                 self.advance();
 
-                if self.current.kind == TokenKind::Dot {
+                if self.current.kind == TokenKind::String && is_temporal_type(&name) {
+                    // Typed literal, e.g. DATE '2024-01-31', built by the
+                    // matching function so bad literals fail the same way
+                    let text = &self.current.text;
+                    let value = text[1..text.len() - 1].to_string();
+                    self.advance();
+                    Ok(Expression::FunctionCall {
+                        name: name.to_lowercase(),
+                        args: vec![Expression::Literal(Literal::String(value))],
+                        distinct: false,
+                    })
+                } else if self.current.kind == TokenKind::Dot {
                     self.advance();
                     if !self.is_identifier() {
                         return Err(self.error("Expected property name"));
                     }
                     let property = self.get_identifier_name();
                     self.advance();
-                    Ok(Expression::PropertyAccess {
-                        variable: name,
-                        property,
-                    })
-                } else if self.current.kind == TokenKind::LParen {
-                    // Function call
-                    self.advance();
-                    // Check for DISTINCT keyword in aggregate functions
-                    let distinct = if self.current.kind == TokenKind::Distinct {
-                        self.advance();
-                        true
+                    if self.current.kind == TokenKind::LParen {
+                        // Namespaced function call, e.g. point.withinDistance(...)
+                        self.parse_function_call(format!("{name}.{property}"))
                     } else {
-                        false
-                    };
-                    let mut args = Vec::new();
-                    if self.current.kind != TokenKind::RParen {
-                        args.push(self.parse_expression()?);
-                        while self.current.kind == TokenKind::Comma {
-                            self.advance();
-                            args.push(self.parse_expression()?);
-                        }
+                        Ok(Expression::PropertyAccess {
+                            variable: name,
+                            property,
+                        })
                     }
-                    self.expect(TokenKind::RParen)?;
-                    Ok(Expression::FunctionCall {
-                        name,
-                        args,
-                        distinct,
-                    })
+                } else if self.current.kind == TokenKind::LParen {
+                    self.parse_function_call(name)
                 } else {
                     Ok(Expression::Variable(name))
                 }
@@ -1330,6 +1427,7 @@ impl<'a> Parser<'a> {
                 self.expect(TokenKind::RBracket)?;
                 Ok(Expression::List(elements))
             }
+            TokenKind::LBrace => Ok(Expression::Map(self.parse_property_map()?)),
             TokenKind::Parameter => {
                 // Parameter token includes the $ prefix, so we extract just the name
                 let full_text = &self.current.text;
@@ -1350,6 +1448,32 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses the argument list of a function call, starting at `(`.
+    fn parse_function_call(&mut self, name: String) -> Result<Expression> {
+        self.expect(TokenKind::LParen)?;
+        // Check for DISTINCT keyword in aggregate functions
+        let distinct = if self.current.kind == TokenKind::Distinct {
+            self.advance();
+            true
+        } else {
+            false
+        };
+        let mut args = Vec::new();
+        if self.current.kind != TokenKind::RParen {
+            args.push(self.parse_expression()?);
+            while self.current.kind == TokenKind::Comma {
+                self.advance();
+                args.push(self.parse_expression()?);
+            }
+        }
+        self.expect(TokenKind::RParen)?;
+        Ok(Expression::FunctionCall {
+            name,
+            args,
+            distinct,
+        })
+    }
+
     /// Parses a CASE expression.
     /// CASE [input] WHEN condition THEN result [WHEN ...] [ELSE default] END
     fn parse_case_expression(&mut self) -> Result<Expression> {
@@ -1721,6 +1845,14 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// Returns true if `name` is a type that takes a typed literal, such as
+/// `DATE '2024-01-31'` or `DURATION 'P1D'`.
+fn is_temporal_type(name: &str) -> bool {
+    ["date", "datetime", "timestamp", "duration"]
+        .iter()
+        .any(|kind| kind.eq_ignore_ascii_case(name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1866,6 +1998,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_is_null() {
+        for (query, expected) in [
+            (
+                "MATCH (n) WHERE n.nickname IS NULL RETURN n",
+                UnaryOp::IsNull,
+            ),
+            (
+                "MATCH (n) WHERE n.nickname IS NOT NULL RETURN n",
+                UnaryOp::IsNotNull,
+            ),
+        ] {
+            let mut parser = Parser::new(query);
+            let Statement::Query(query) = parser.parse().unwrap() else {
+                panic!("Expected Query statement");
+            };
+            let where_clause = query.where_clause.expect("Expected WHERE clause");
+            match where_clause.expression {
+                Expression::Unary { op, operand } => {
+                    assert_eq!(op, expected);
+                    assert!(matches!(*operand, Expression::PropertyAccess { .. }));
+                }
+                other => panic!("Expected unary expression, got {other:?}"),
+            }
+        }
+    }
+
     #[test]
     fn test_parse_insert_with_parameter() {
         let mut parser = Parser::new("INSERT (n:Person {name: $name, age: $age})");
@@ -2051,6 +2210,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_map_literal_and_index_access() {
+        let mut parser = Parser::new("MATCH (n) RETURN {a: [1, 2]}.a[0], n.tags[1]");
+        let result = parser.parse();
+        assert!(result.is_ok(), "Parse error: {:?}", result.err());
+
+        let Statement::Query(query) = result.unwrap() else {
+            panic!("Expected Query statement");
+        };
+        let items = &query.return_clause.items;
+
+        // {a: [1, 2]}.a[0] nests as ((map).a)[0]
+        let Expression::IndexAccess { base, index } = &items[0].expression else {
+            panic!("Expected IndexAccess, got {:?}", items[0].expression);
+        };
+        assert!(matches!(
+            index.as_ref(),
+            Expression::Literal(Literal::Integer(0))
+        ));
+        let Expression::IndexAccess { base, index } = base.as_ref() else {
+            panic!("Expected key lookup, got {base:?}");
+        };
+        assert!(matches!(base.as_ref(), Expression::Map(entries) if entries.len() == 1));
+        assert!(matches!(index.as_ref(), Expression::Literal(Literal::String(key)) if key == "a"));
+
+        assert!(matches!(
+            &items[1].expression,
+            Expression::IndexAccess { base, .. }
+                if matches!(base.as_ref(), Expression::PropertyAccess { .. })
+        ));
+    }
+
     #[test]
     fn test_parse_merge_on_create() {
         let mut parser =
@@ -2120,4 +2311,107 @@ mod tests {
             panic!("Expected Query statement");
         }
     }
+
+    #[test]
+    fn test_parse_set_map_replace_and_merge() {
+        let mut parser =
+            Parser::new("MATCH (n:Person) SET n = {name: 'Bob'}, m += {age: 3} RETURN n");
+        let result = parser.parse();
+        assert!(result.is_ok(), "Parse error: {:?}", result.err());
+
+        if let Statement::Query(query) = result.unwrap() {
+            let set = &query.set_clauses[0];
+            assert!(set.assignments.is_empty());
+            assert_eq!(set.map_assignments.len(), 2);
+            assert_eq!(set.map_assignments[0].variable, "n");
+            assert!(set.map_assignments[0].replace);
+            assert_eq!(set.map_assignments[1].variable, "m");
+            assert!(!set.map_assignments[1].replace);
+        } else {
+            panic!("Expected Query statement");
+        }
+    }
+
+    #[test]
+    fn test_parse_set_increment() {
+        let mut parser = Parser::new("MATCH (n:Page) SET n.views += 1, n.title = 'Home'");
+        let result = parser.parse();
+        assert!(result.is_ok(), "Parse error: {:?}", result.err());
+
+        if let Statement::Query(query) = result.unwrap() {
+            let set = &query.set_clauses[0];
+            assert_eq!(set.assignments.len(), 2);
+            assert_eq!(set.assignments[0].property, "views");
+            assert!(set.assignments[0].increment);
+            assert!(!set.assignments[1].increment);
+        } else {
+            panic!("Expected Query statement");
+        }
+    }
+
+    #[test]
+    fn test_parse_namespaced_function() {
+        let mut parser =
+            Parser::new("MATCH (n) RETURN point.withinDistance(n.loc, point({x: 0, y: 0}), 5)");
+        let result = parser.parse();
+        assert!(result.is_ok(), "Parse error: {:?}", result.err());
+
+        if let Statement::Query(query) = result.unwrap() {
+            let Expression::FunctionCall { name, args, .. } =
+                &query.return_clause.items[0].expression
+            else {
+                panic!("Expected function call");
+            };
+            assert_eq!(name, "point.withinDistance");
+            assert_eq!(args.len(), 3);
+            assert!(matches!(args[0], Expression::PropertyAccess { .. }));
+        } else {
+            panic!("Expected Query statement");
+        }
+    }
+
+    #[test]
+    fn test_parse_temporal_literals() {
+        let mut parser = Parser::new(
+            "MATCH (n) WHERE n.created > DATE '2024-01-01' RETURN timestamp '2024-01-01T12:00:00Z'",
+        );
+        let result = parser.parse();
+        assert!(result.is_ok(), "Parse error: {:?}", result.err());
+
+        if let Statement::Query(query) = result.unwrap() {
+            let Expression::FunctionCall { name, args, .. } =
+                &query.return_clause.items[0].expression
+            else {
+                panic!("Expected function call");
+            };
+            assert_eq!(name, "timestamp");
+            assert!(
+                matches!(&args[..], [Expression::Literal(Literal::String(s))] if s == "2024-01-01T12:00:00Z")
+            );
+        } else {
+            panic!("Expected Query statement");
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_deeply_nested_expression() {
+        let nested = |depth: usize| {
+            format!(
+                "MATCH (n) RETURN {}1{}",
+                "(".repeat(depth),
+                ")".repeat(depth)
+            )
+        };
+
+        assert!(Parser::new(&nested(120)).parse().is_ok());
+
+        let result = Parser::new(&nested(100_000)).parse();
+        match result {
+            Err(Error::Query(err)) => assert_eq!(err.kind, QueryErrorKind::Complexity),
+            other => panic!("Expected complexity error, got {other:?}"),
+        }
+
+        let result = Parser::new(&nested(10)).with_max_depth(5).parse();
+        assert!(matches!(result, Err(Error::Query(err)) if err.kind == QueryErrorKind::Complexity));
+    }
 }
@@ -82,8 +82,8 @@ pub enum Step {
     // === Map Steps ===
     /// .values(keys...)
     Values(Vec<String>),
-    /// .valueMap(keys...)
-    ValueMap(Vec<String>),
+    /// .valueMap([true,] keys...); the flag adds the id and label
+    ValueMap(bool, Vec<String>),
     /// .elementMap(keys...)
     ElementMap(Vec<String>),
     /// .id()
@@ -94,6 +94,8 @@ pub enum Step {
     Properties(Vec<String>),
     /// .constant(value)
     Constant(Value),
+    /// .identity()
+    Identity,
     /// .count()
     Count,
     /// .sum()
@@ -114,6 +116,10 @@ pub enum Step {
     GroupCount(Option<String>),
     /// .path()
     Path,
+    /// .simplePath()
+    SimplePath,
+    /// .cyclicPath()
+    CyclicPath,
     /// .select(keys...)
     Select(Vec<String>),
     /// .project(keys...)
@@ -131,6 +137,14 @@ pub enum Step {
     /// .choose(predicate, true_branch, false_branch)
     Choose(ChooseClause),
 
+    // === Loop Steps ===
+    /// .repeat(traversal)
+    Repeat(Vec<Step>),
+    /// .until(traversal) - stop looping once the traversal matches
+    Until(Vec<Step>),
+    /// .times(n) - loop exactly n times
+    Times(usize),
+
     // === Side Effect Steps ===
     /// .as(label)
     As(String),
@@ -213,7 +227,8 @@ pub enum Predicate {
 /// Where clause variants.
 #[derive(Debug, Clone)]
 pub enum WhereClause {
-    /// .where(P.eq(label))
+    /// .where(P.eq(label)) or .where(start, P.eq(label)) - the start label is
+    /// empty when comparing the current element
     Predicate(String, Predicate),
     /// .where(traversal)
     Traversal(Vec<Step>),
@@ -60,6 +60,7 @@ pub enum TokenKind {
     Label,
     Properties,
     Constant,
+    Identity,
     Count,
     Sum,
     Mean,
@@ -70,6 +71,8 @@ pub enum TokenKind {
     Group,
     GroupCount,
     Path,
+    SimplePath,
+    CyclicPath,
     Select,
     Project,
     By,
@@ -79,6 +82,11 @@ pub enum TokenKind {
     Union,
     Choose,
 
+    // Loop steps
+    Repeat,
+    Until,
+    Times,
+
     // Side effect steps
     As,
     SideEffect,
@@ -348,6 +356,7 @@ impl<'a> Lexer<'a> {
             "label" => TokenKind::Label,
             "properties" => TokenKind::Properties,
             "constant" => TokenKind::Constant,
+            "identity" => TokenKind::Identity,
             "count" => TokenKind::Count,
             "sum" => TokenKind::Sum,
             "mean" | "avg" => TokenKind::Mean,
@@ -358,6 +367,8 @@ impl<'a> Lexer<'a> {
             "group" => TokenKind::Group,
             "groupCount" => TokenKind::GroupCount,
             "path" => TokenKind::Path,
+            "simplePath" => TokenKind::SimplePath,
+            "cyclicPath" => TokenKind::CyclicPath,
             "select" => TokenKind::Select,
             "project" => TokenKind::Project,
             "by" => TokenKind::By,
@@ -366,6 +377,9 @@ impl<'a> Lexer<'a> {
             "optional" => TokenKind::Optional,
             "union" => TokenKind::Union,
             "choose" => TokenKind::Choose,
+            "repeat" => TokenKind::Repeat,
+            "until" => TokenKind::Until,
+            "times" => TokenKind::Times,
             "as" | "as_" => TokenKind::As,
             "sideEffect" => TokenKind::SideEffect,
             "aggregate" => TokenKind::Aggregate,
@@ -181,6 +181,50 @@ impl<'a> Parser<'a> {
                 self.expect(TokenKind::RParen)?;
                 Ok(Step::HasNot(key))
             }
+            TokenKind::Not => {
+                self.expect(TokenKind::LParen)?;
+                let steps = self.parse_anonymous_traversal()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Not(steps))
+            }
+            TokenKind::Where => {
+                self.expect(TokenKind::LParen)?;
+                let clause = if self.check(TokenKind::P) {
+                    WhereClause::Predicate(String::new(), self.parse_predicate()?)
+                } else if self.check_string() {
+                    let start = self.parse_string()?;
+                    self.expect(TokenKind::Comma)?;
+                    WhereClause::Predicate(start, self.parse_predicate()?)
+                } else {
+                    WhereClause::Traversal(self.parse_anonymous_traversal()?)
+                };
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Where(clause))
+            }
+            TokenKind::And => {
+                self.expect(TokenKind::LParen)?;
+                let traversals = self.parse_traversal_list()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::And(traversals))
+            }
+            TokenKind::Or => {
+                self.expect(TokenKind::LParen)?;
+                let traversals = self.parse_traversal_list()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Or(traversals))
+            }
+            TokenKind::Union => {
+                self.expect(TokenKind::LParen)?;
+                let traversals = self.parse_branch_list()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Union(traversals))
+            }
+            TokenKind::Coalesce => {
+                self.expect(TokenKind::LParen)?;
+                let traversals = self.parse_branch_list()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Coalesce(traversals))
+            }
             TokenKind::Dedup => {
                 self.expect(TokenKind::LParen)?;
                 let keys = self.parse_string_list()?;
@@ -217,9 +261,20 @@ impl<'a> Parser<'a> {
             }
             TokenKind::ValueMap => {
                 self.expect(TokenKind::LParen)?;
+                // The legacy valueMap(true) form also includes the id and label
+                let with_tokens = if self.check(TokenKind::True) || self.check(TokenKind::False) {
+                    let with_tokens = self.check(TokenKind::True);
+                    self.advance();
+                    if self.check(TokenKind::Comma) {
+                        self.advance();
+                    }
+                    with_tokens
+                } else {
+                    false
+                };
                 let keys = self.parse_string_list()?;
                 self.expect(TokenKind::RParen)?;
-                Ok(Step::ValueMap(keys))
+                Ok(Step::ValueMap(with_tokens, keys))
             }
             TokenKind::ElementMap => {
                 self.expect(TokenKind::LParen)?;
@@ -249,6 +304,11 @@ impl<'a> Parser<'a> {
                 self.expect(TokenKind::RParen)?;
                 Ok(Step::Constant(value))
             }
+            TokenKind::Identity => {
+                self.expect(TokenKind::LParen)?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Identity)
+            }
             TokenKind::Count => {
                 self.expect(TokenKind::LParen)?;
                 self.expect(TokenKind::RParen)?;
@@ -304,6 +364,16 @@ impl<'a> Parser<'a> {
                 self.expect(TokenKind::RParen)?;
                 Ok(Step::Path)
             }
+            TokenKind::SimplePath => {
+                self.expect(TokenKind::LParen)?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::SimplePath)
+            }
+            TokenKind::CyclicPath => {
+                self.expect(TokenKind::LParen)?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::CyclicPath)
+            }
             TokenKind::Select => {
                 self.expect(TokenKind::LParen)?;
                 let keys = self.parse_string_list()?;
@@ -328,6 +398,26 @@ impl<'a> Parser<'a> {
                 Ok(Step::Order(Vec::new()))
             }
 
+            // Loop steps
+            TokenKind::Repeat => {
+                self.expect(TokenKind::LParen)?;
+                let steps = self.parse_anonymous_traversal()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Repeat(steps))
+            }
+            TokenKind::Until => {
+                self.expect(TokenKind::LParen)?;
+                let steps = self.parse_anonymous_traversal()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Until(steps))
+            }
+            TokenKind::Times => {
+                self.expect(TokenKind::LParen)?;
+                let n = self.parse_integer()? as usize;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::Times(n))
+            }
+
             // Side effect steps
             TokenKind::As => {
                 self.expect(TokenKind::LParen)?;
@@ -335,6 +425,12 @@ impl<'a> Parser<'a> {
                 self.expect(TokenKind::RParen)?;
                 Ok(Step::As(label))
             }
+            TokenKind::SideEffect => {
+                self.expect(TokenKind::LParen)?;
+                let steps = self.parse_anonymous_traversal()?;
+                self.expect(TokenKind::RParen)?;
+                Ok(Step::SideEffect(steps))
+            }
             TokenKind::Aggregate => {
                 self.expect(TokenKind::LParen)?;
                 let label = self.parse_string()?;
@@ -752,8 +848,9 @@ impl<'a> Parser<'a> {
             return Ok(FromTo::Label(label));
         }
 
-        // Check for traversal starting with 'g'
-        if self.check(TokenKind::G) {
+        // Check for a traversal, either spawned from 'g' or anonymous
+        if self.check(TokenKind::G) || self.check(TokenKind::Underscore) || self.check(TokenKind::V)
+        {
             let steps = self.parse_sub_traversal()?;
             return Ok(FromTo::Traversal(steps));
         }
@@ -761,12 +858,19 @@ impl<'a> Parser<'a> {
         Err(self.error("Expected label or traversal for from/to"))
     }
 
-    /// Parse a sub-traversal (e.g., g.V().has('name', 'Bob'))
+    /// Parse a sub-traversal (e.g., g.V().has('name', 'Bob'), __.V() or V())
     /// Returns the steps as a Vec<Step>
     fn parse_sub_traversal(&mut self) -> Result<Vec<Step>> {
-        // Consume 'g'
-        self.expect(TokenKind::G)?;
-        self.expect(TokenKind::Dot)?;
+        // Consume the 'g' or '__' prefix, if any
+        if self.check(TokenKind::G) {
+            self.advance();
+            self.expect(TokenKind::Dot)?;
+        } else if self.check(TokenKind::Underscore) {
+            while self.check(TokenKind::Underscore) {
+                self.advance();
+            }
+            self.expect(TokenKind::Dot)?;
+        }
 
         // Parse source (V, E, etc.) and convert to a step
         let source = self.parse_source()?;
@@ -801,6 +905,44 @@ impl<'a> Parser<'a> {
         Ok(steps)
     }
 
+    /// Parse an anonymous traversal (e.g., out('knows') or __.out('knows'))
+    /// as used inside filter steps like not()
+    fn parse_anonymous_traversal(&mut self) -> Result<Vec<Step>> {
+        // The optional `__` prefix lexes as underscores followed by a dot
+        if self.check(TokenKind::Underscore) {
+            while self.check(TokenKind::Underscore) {
+                self.advance();
+            }
+            self.expect(TokenKind::Dot)?;
+        }
+
+        let mut steps = vec![self.parse_step()?];
+        while self.check(TokenKind::Dot) {
+            self.advance(); // consume '.'
+            steps.push(self.parse_step()?);
+        }
+
+        Ok(steps)
+    }
+
+    /// Parse comma-separated anonymous traversals, as used by and() and or()
+    fn parse_traversal_list(&mut self) -> Result<Vec<Vec<Step>>> {
+        let mut traversals = vec![self.parse_anonymous_traversal()?];
+        while self.check(TokenKind::Comma) {
+            self.advance(); // consume ','
+            traversals.push(self.parse_anonymous_traversal()?);
+        }
+        Ok(traversals)
+    }
+
+    /// Parse the branches of union() or coalesce(), which may be empty
+    fn parse_branch_list(&mut self) -> Result<Vec<Vec<Step>>> {
+        if self.check(TokenKind::RParen) {
+            return Ok(Vec::new());
+        }
+        self.parse_traversal_list()
+    }
+
     fn parse_string_list(&mut self) -> Result<Vec<String>> {
         let mut result = Vec::new();
         while self.check_string() {
@@ -974,6 +1116,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_value_map() {
+        let mut parser = Parser::new("g.V().valueMap('name', 'age')");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(&stmt.steps[0], Step::ValueMap(false, keys) if keys == &["name", "age"]));
+
+        let mut parser = Parser::new("g.V().valueMap(true, 'name')");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(&stmt.steps[0], Step::ValueMap(true, keys) if keys == &["name"]));
+
+        let mut parser = Parser::new("g.V().valueMap(true)");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(&stmt.steps[0], Step::ValueMap(true, keys) if keys.is_empty()));
+
+        let mut parser = Parser::new("g.E().elementMap()");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(&stmt.steps[0], Step::ElementMap(keys) if keys.is_empty()));
+    }
+
     #[test]
     fn test_parse_has_with_direct_predicate_gt() {
         let mut parser = Parser::new("g.V().has('age', gt(28))");
@@ -1021,4 +1182,138 @@ mod tests {
             panic!("Expected Has step with within predicate");
         }
     }
+
+    #[test]
+    fn test_parse_not() {
+        for query in ["g.V().not(out('knows'))", "g.V().not(__.out('knows'))"] {
+            let mut parser = Parser::new(query);
+            let result = parser.parse();
+            assert!(result.is_ok(), "Failed to parse {query}: {:?}", result);
+            let stmt = result.unwrap();
+            if let Step::Not(inner) = &stmt.steps[0] {
+                assert!(matches!(&inner[..], [Step::Out(labels)] if labels == &["knows"]));
+            } else {
+                panic!("Expected Not step, got: {:?}", stmt.steps[0]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_side_effect_and_identity() {
+        let mut parser = Parser::new("g.V().sideEffect(property('seen', true)).identity()");
+        let stmt = parser.parse().unwrap();
+        if let Step::SideEffect(inner) = &stmt.steps[0] {
+            assert!(matches!(&inner[..], [Step::Property(prop)] if prop.key == "seen"));
+        } else {
+            panic!("Expected SideEffect step, got: {:?}", stmt.steps[0]);
+        }
+        assert!(matches!(stmt.steps[1], Step::Identity));
+    }
+
+    #[test]
+    fn test_parse_where_and_or() {
+        let mut parser = Parser::new("g.V().as('a').out().where(P.neq('a'))");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(
+            &stmt.steps[2],
+            Step::Where(WhereClause::Predicate(start, Predicate::Neq(_))) if start.is_empty()
+        ));
+
+        let mut parser = Parser::new("g.V().where('a', P.gt('b'))");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(
+            &stmt.steps[0],
+            Step::Where(WhereClause::Predicate(start, Predicate::Gt(_))) if start == "a"
+        ));
+
+        let mut parser = Parser::new("g.V().where(out('knows'))");
+        let stmt = parser.parse().unwrap();
+        assert!(
+            matches!(&stmt.steps[0], Step::Where(WhereClause::Traversal(steps)) if steps.len() == 1)
+        );
+
+        let mut parser =
+            Parser::new("g.V().or(has('name', 'Alice'), hasLabel('Bot').hasNot('owner'))");
+        let stmt = parser.parse().unwrap();
+        if let Step::Or(branches) = &stmt.steps[0] {
+            assert_eq!(branches.len(), 2);
+            assert_eq!(branches[1].len(), 2);
+        } else {
+            panic!("Expected Or step, got: {:?}", stmt.steps[0]);
+        }
+        let mut parser = Parser::new("g.V().and(has('age'), __.has('name'))");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(&stmt.steps[0], Step::And(branches) if branches.len() == 2));
+    }
+
+    #[test]
+    fn test_parse_path_steps() {
+        let mut parser = Parser::new("g.V().out().simplePath().out().cyclicPath().path()");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(
+            &stmt.steps[..],
+            [
+                Step::Out(_),
+                Step::SimplePath,
+                Step::Out(_),
+                Step::CyclicPath,
+                Step::Path
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_parse_union_and_coalesce() {
+        let mut parser = Parser::new("g.V().union(out('knows'), __.out('likes').out())");
+        let stmt = parser.parse().unwrap();
+        if let Step::Union(branches) = &stmt.steps[0] {
+            assert_eq!(branches.len(), 2);
+            assert_eq!(branches[1].len(), 2);
+        } else {
+            panic!("Expected Union step, got: {:?}", stmt.steps[0]);
+        }
+
+        let mut parser = Parser::new("g.V().coalesce(out('a'), out('b')).values('name')");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(&stmt.steps[0], Step::Coalesce(branches) if branches.len() == 2));
+
+        let mut parser = Parser::new("g.V().union()");
+        let stmt = parser.parse().unwrap();
+        assert!(matches!(&stmt.steps[0], Step::Union(branches) if branches.is_empty()));
+    }
+
+    #[test]
+    fn test_parse_repeat_times_until() {
+        let mut parser = Parser::new("g.V().repeat(out('knows')).times(3)");
+        let stmt = parser.parse().unwrap();
+        if let Step::Repeat(inner) = &stmt.steps[0] {
+            assert!(matches!(&inner[..], [Step::Out(labels)] if labels == &["knows"]));
+        } else {
+            panic!("Expected Repeat step, got: {:?}", stmt.steps[0]);
+        }
+        assert!(matches!(stmt.steps[1], Step::Times(3)));
+
+        let mut parser = Parser::new("g.V().repeat(__.out()).until(has('name', 'X'))");
+        let stmt = parser.parse().unwrap();
+        assert!(
+            matches!(&stmt.steps[1], Step::Until(inner) if matches!(&inner[..], [Step::Has(_)]))
+        );
+    }
+
+    #[test]
+    fn test_parse_add_e_to_anonymous_traversal() {
+        for query in [
+            "g.V().addE('knows').to(V().has('name', 'b'))",
+            "g.V().addE('knows').to(__.V().has('name', 'b'))",
+            "g.V().addE('knows').to(g.V().has('name', 'b'))",
+        ] {
+            let mut parser = Parser::new(query);
+            let stmt = parser.parse().unwrap();
+            if let Step::To(FromTo::Traversal(steps)) = &stmt.steps[1] {
+                assert!(matches!(&steps[..], [Step::Has(_)]), "{query}");
+            } else {
+                panic!("Expected To step, got: {:?}", stmt.steps[1]);
+            }
+        }
+    }
 }
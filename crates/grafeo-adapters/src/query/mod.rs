@@ -11,6 +11,12 @@
 //! | Gremlin | Apache TinkerPop | `gremlin` | Graph traversal DSL |
 //! | GraphQL | June 2018 spec | `graphql` | API query language |
 
+/// Default limit on how deeply expressions may nest before a parser gives up.
+///
+/// Each level of nesting is a level of parser recursion, so without a limit a
+/// query like `((((...))))` can overflow the stack.
+pub const DEFAULT_MAX_NESTING_DEPTH: usize = 128;
+
 #[cfg(feature = "gql")]
 pub mod gql;
 
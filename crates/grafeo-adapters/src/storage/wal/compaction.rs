@@ -0,0 +1,238 @@
+//! WAL compaction - drops records that later records make redundant.
+//!
+//! Replaying the compacted records rebuilds the same graph as replaying the
+//! originals: only the last write to a property or label survives, and
+//! entities that were created and deleted again vanish entirely.
+
+use super::WalRecord;
+use grafeo_common::types::{EdgeId, NodeId};
+use grafeo_common::utils::hash::{FxHashMap, FxHashSet};
+use std::hash::Hash;
+
+/// Compacts committed records, keeping the survivors in log order.
+///
+/// Transaction and checkpoint markers are dropped, so the caller has to
+/// append a commit for the result.
+pub(super) fn compact_records(records: Vec<WalRecord>) -> Vec<WalRecord> {
+    // Edges replay against their endpoints, so those nodes keep their full
+    // create/delete history
+    let endpoints: FxHashSet<NodeId> = records
+        .iter()
+        .filter_map(|record| match record {
+            WalRecord::CreateEdge { src, dst, .. } => Some([*src, *dst]),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    let keep = survivors(&records, &endpoints);
+    records
+        .into_iter()
+        .zip(keep)
+        .filter_map(|(record, kept)| kept.then_some(record))
+        .collect()
+}
+
+/// Flags the records worth keeping.
+fn survivors(records: &[WalRecord], endpoints: &FxHashSet<NodeId>) -> Vec<bool> {
+    let mut keep = vec![true; records.len()];
+    let mut nodes = Lifetimes::<NodeId>::default();
+    let mut edges = Lifetimes::<EdgeId>::default();
+
+    // Walk backwards, so every record knows what happens to its entity later
+    for (i, record) in records.iter().enumerate().rev() {
+        let (kept, cancelled_delete) = match record {
+            WalRecord::CreateNode { id, .. } => nodes.create(*id, !endpoints.contains(id)),
+            WalRecord::DeleteNode { id } => (nodes.delete(*id, i), None),
+            WalRecord::SetNodeProperty { id, key, .. } => {
+                (nodes.write(*id, Slot::Property(key)), None)
+            }
+            WalRecord::AddNodeLabel { id, label } | WalRecord::RemoveNodeLabel { id, label } => {
+                (nodes.write(*id, Slot::Label(label)), None)
+            }
+            WalRecord::CreateEdge { id, .. } => edges.create(*id, true),
+            WalRecord::DeleteEdge { id } => (edges.delete(*id, i), None),
+            WalRecord::SetEdgeProperty { id, key, .. } => {
+                (edges.write(*id, Slot::Property(key)), None)
+            }
+            WalRecord::SetEdgeValidity { id, .. } => (edges.write(*id, Slot::Validity), None),
+            WalRecord::TxCommit { .. }
+            | WalRecord::TxAbort { .. }
+            | WalRecord::Checkpoint { .. }
+            | WalRecord::CompactionBase => (false, None),
+        };
+        keep[i] = kept;
+        if let Some(delete) = cancelled_delete {
+            keep[delete] = false;
+        }
+    }
+    keep
+}
+
+/// Something a record writes on an entity; only the last write counts.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Slot<'a> {
+    Property(&'a str),
+    Label(&'a str),
+    Validity,
+}
+
+/// What the rest of the log does to each entity, tracked during the
+/// backwards walk.
+struct Lifetimes<'a, Id> {
+    /// Entities deleted later on, with the index of the delete record.
+    deleted: FxHashMap<Id, usize>,
+    /// Slots written later on in the entity's current lifetime.
+    written: FxHashMap<Id, FxHashSet<Slot<'a>>>,
+}
+
+impl<Id> Default for Lifetimes<'_, Id> {
+    fn default() -> Self {
+        Self {
+            deleted: FxHashMap::default(),
+            written: FxHashMap::default(),
+        }
+    }
+}
+
+impl<'a, Id: Copy + Eq + Hash> Lifetimes<'a, Id> {
+    /// Returns whether the create is kept, and which delete it cancels.
+    fn create(&mut self, id: Id, removable: bool) -> (bool, Option<usize>) {
+        // Anything before the create belongs to an earlier lifetime
+        self.written.remove(&id);
+        match self.deleted.remove(&id) {
+            Some(delete) if removable => (false, Some(delete)),
+            _ => (true, None),
+        }
+    }
+
+    fn delete(&mut self, id: Id, index: usize) -> bool {
+        self.deleted.insert(id, index);
+        true
+    }
+
+    /// Returns whether the write is the last one to its slot.
+    fn write(&mut self, id: Id, slot: Slot<'a>) -> bool {
+        !self.deleted.contains_key(&id) && self.written.entry(id).or_default().insert(slot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grafeo_common::types::{TxId, Value};
+
+    fn set(id: u64, key: &str, value: i64) -> WalRecord {
+        WalRecord::SetNodeProperty {
+            id: NodeId::new(id),
+            key: key.to_string(),
+            value: Value::Int64(value),
+        }
+    }
+
+    fn create(id: u64) -> WalRecord {
+        WalRecord::CreateNode {
+            id: NodeId::new(id),
+            labels: vec!["Person".to_string()],
+        }
+    }
+
+    fn describe(records: &[WalRecord]) -> Vec<String> {
+        records.iter().map(|r| format!("{r:?}")).collect()
+    }
+
+    #[test]
+    fn test_last_property_write_wins() {
+        let compacted = compact_records(vec![
+            create(1),
+            set(1, "age", 30),
+            set(1, "name", 1),
+            set(1, "age", 31),
+            WalRecord::TxCommit {
+                tx_id: TxId::new(2),
+            },
+        ]);
+
+        assert_eq!(
+            describe(&compacted),
+            describe(&[create(1), set(1, "name", 1), set(1, "age", 31)])
+        );
+    }
+
+    #[test]
+    fn test_deleted_entities_vanish() {
+        let compacted = compact_records(vec![
+            create(1),
+            create(2),
+            set(2, "age", 30),
+            WalRecord::AddNodeLabel {
+                id: NodeId::new(2),
+                label: "Admin".to_string(),
+            },
+            WalRecord::DeleteNode { id: NodeId::new(2) },
+        ]);
+
+        assert_eq!(describe(&compacted), describe(&[create(1)]));
+    }
+
+    #[test]
+    fn test_recreated_entity_keeps_new_lifetime() {
+        let compacted = compact_records(vec![
+            create(1),
+            set(1, "age", 30),
+            WalRecord::DeleteNode { id: NodeId::new(1) },
+            create(1),
+            set(1, "age", 40),
+        ]);
+
+        assert_eq!(
+            describe(&compacted),
+            describe(&[create(1), set(1, "age", 40)])
+        );
+    }
+
+    #[test]
+    fn test_edge_endpoints_keep_history() {
+        let edge = WalRecord::CreateEdge {
+            id: EdgeId::new(7),
+            src: NodeId::new(1),
+            dst: NodeId::new(2),
+            edge_type: "KNOWS".to_string(),
+        };
+        let records = vec![
+            create(1),
+            create(2),
+            edge.clone(),
+            set(2, "age", 30),
+            WalRecord::DeleteNode { id: NodeId::new(2) },
+        ];
+
+        // The property write dies with the node, the node itself stays
+        let compacted = compact_records(records);
+        assert_eq!(
+            describe(&compacted),
+            describe(&[
+                create(1),
+                create(2),
+                edge,
+                WalRecord::DeleteNode { id: NodeId::new(2) },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_last_label_change_wins() {
+        let label = |add: bool| {
+            let id = NodeId::new(1);
+            let label = "Admin".to_string();
+            if add {
+                WalRecord::AddNodeLabel { id, label }
+            } else {
+                WalRecord::RemoveNodeLabel { id, label }
+            }
+        };
+        let compacted = compact_records(vec![create(1), label(true), label(false), label(true)]);
+
+        assert_eq!(describe(&compacted), describe(&[create(1), label(true)]));
+    }
+}
@@ -1,6 +1,7 @@
 //! WAL log file management.
 
-use super::WalRecord;
+use super::compaction::compact_records;
+use super::{WalRecord, WalRecovery};
 use grafeo_common::types::{EpochId, TxId};
 use grafeo_common::utils::error::{Error, Result};
 use parking_lot::Mutex;
@@ -27,6 +28,15 @@ pub struct CheckpointMetadata {
     pub tx_id: TxId,
 }
 
+/// Returned by [`WalManager::checkpoint()`].
+#[derive(Debug, Clone)]
+pub struct CheckpointHandle {
+    /// The metadata persisted for the checkpoint.
+    pub metadata: CheckpointMetadata,
+    /// Bytes freed by compacting the log up to the checkpoint.
+    pub bytes_reclaimed: u64,
+}
+
 /// Name of the checkpoint metadata file.
 const CHECKPOINT_METADATA_FILE: &str = "checkpoint.meta";
 
@@ -110,6 +120,8 @@ pub struct WalManager {
     current_sequence: AtomicU64,
     /// Latest checkpoint epoch.
     checkpoint_epoch: Mutex<Option<EpochId>>,
+    /// Held while segments are being compacted.
+    compaction: Mutex<()>,
 }
 
 impl WalManager {
@@ -157,6 +169,7 @@ impl WalManager {
             last_sync: Mutex::new(Instant::now()),
             current_sequence: AtomicU64::new(max_sequence),
             checkpoint_epoch: Mutex::new(None),
+            compaction: Mutex::new(()),
         };
 
         // Open or create the active log
@@ -178,24 +191,7 @@ impl WalManager {
             .as_mut()
             .ok_or_else(|| Error::Internal("WAL writer not available".to_string()))?;
 
-        // Serialize the record
-        let data = bincode::serde::encode_to_vec(record, bincode::config::standard())
-            .map_err(|e| Error::Serialization(e.to_string()))?;
-
-        // Write length prefix
-        let len = data.len() as u32;
-        log_file.writer.write_all(&len.to_le_bytes())?;
-
-        // Write data
-        log_file.writer.write_all(&data)?;
-
-        // Write checksum
-        let checksum = crc32fast::hash(&data);
-        log_file.writer.write_all(&checksum.to_le_bytes())?;
-
-        // Update size tracking
-        let record_size = 4 + data.len() as u64 + 4; // length + data + checksum
-        log_file.size += record_size;
+        log_file.size += write_record(&mut log_file.writer, record)?;
 
         self.total_record_count.fetch_add(1, Ordering::Relaxed);
         self.records_since_sync.fetch_add(1, Ordering::Relaxed);
@@ -244,23 +240,26 @@ impl WalManager {
         Ok(())
     }
 
-    /// Writes a checkpoint marker and persists checkpoint metadata.
+    /// Writes a checkpoint marker, compacts the log up to it, and persists
+    /// checkpoint metadata.
     ///
-    /// The checkpoint metadata is written atomically to a separate file,
-    /// allowing recovery to skip WAL files that precede the checkpoint.
+    /// Every segment up to the marker is folded into a single compacted
+    /// segment and removed, so the log only grows by what was written since
+    /// the last checkpoint. The checkpoint metadata is written atomically to a
+    /// separate file, allowing recovery to start at the compacted segment.
     ///
     /// # Errors
     ///
     /// Returns an error if the checkpoint cannot be written.
-    pub fn checkpoint(&self, current_tx: TxId, epoch: EpochId) -> Result<()> {
+    pub fn checkpoint(&self, current_tx: TxId, epoch: EpochId) -> Result<CheckpointHandle> {
+        let _compacting = self.compaction.lock();
+
         // Write checkpoint record to WAL
         self.log(&WalRecord::Checkpoint { tx_id: current_tx })?;
 
-        // Force sync on checkpoint
-        self.sync()?;
-
-        // Get current log sequence
-        let log_sequence = self.current_sequence.load(Ordering::SeqCst);
+        // Fold everything up to the marker into one segment
+        let log_sequence = self.seal_active_log()?;
+        let bytes_reclaimed = self.compact_into(log_sequence)?;
 
         // Get current timestamp
         let timestamp_ms = SystemTime::now()
@@ -282,10 +281,27 @@ impl WalManager {
         // Update in-memory checkpoint epoch
         *self.checkpoint_epoch.lock() = Some(epoch);
 
-        // Optionally truncate old logs
-        self.truncate_old_logs()?;
+        Ok(CheckpointHandle {
+            metadata,
+            bytes_reclaimed,
+        })
+    }
 
-        Ok(())
+    /// Rewrites the whole log without superseded records.
+    ///
+    /// Property writes that were overwritten later, entities that were
+    /// created and deleted again, and aborted transactions are dropped.
+    /// Recovering the compacted log rebuilds the same graph.
+    ///
+    /// Returns the number of bytes reclaimed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the log cannot be read or rewritten.
+    pub fn compact(&self) -> Result<u64> {
+        let _compacting = self.compaction.lock();
+        let base_sequence = self.seal_active_log()?;
+        self.compact_into(base_sequence)
     }
 
     /// Writes checkpoint metadata to disk atomically.
@@ -339,6 +355,9 @@ impl WalManager {
     ///
     /// Returns an error if rotation fails.
     pub fn rotate(&self) -> Result<()> {
+        // Hold the lock while picking the sequence, so a concurrent seal
+        // can't slip a log in between
+        let mut guard = self.active_log.lock();
         let new_sequence = self.current_sequence.fetch_add(1, Ordering::SeqCst) + 1;
         let new_path = self.log_path(new_sequence);
 
@@ -356,7 +375,6 @@ impl WalManager {
         };
 
         // Replace active log
-        let mut guard = self.active_log.lock();
         if let Some(old_log) = guard.take() {
             // Ensure old log is flushed
             drop(old_log);
@@ -461,6 +479,28 @@ impl WalManager {
         total
     }
 
+    /// Returns the last checkpoint if nothing has been logged since it was
+    /// taken, or `None` if there is no checkpoint or the log has moved on.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint metadata or log files can't be read.
+    pub fn unchanged_checkpoint(&self) -> Result<Option<CheckpointMetadata>> {
+        let Some(checkpoint) = self.read_checkpoint_metadata()? else {
+            return Ok(None);
+        };
+        self.flush()?;
+        // Records after the checkpoint go to the logs after its segment
+        for path in self.log_files()? {
+            if Self::sequence_from_path(&path).is_some_and(|seq| seq > checkpoint.log_sequence)
+                && fs::metadata(&path)?.len() > 0
+            {
+                return Ok(None);
+            }
+        }
+        Ok(Some(checkpoint))
+    }
+
     /// Returns the timestamp of the last checkpoint (Unix epoch seconds), if any.
     #[must_use]
     pub fn last_checkpoint_timestamp(&self) -> Option<u64> {
@@ -509,33 +549,112 @@ impl WalManager {
             .and_then(|s| s.parse().ok())
     }
 
-    fn truncate_old_logs(&self) -> Result<()> {
-        let checkpoint = match *self.checkpoint_epoch.lock() {
-            Some(e) => e,
-            None => return Ok(()),
-        };
+    /// Syncs and closes the active log, reserving the next sequence number
+    /// for a compacted segment. New records go to the log after it.
+    fn seal_active_log(&self) -> Result<u64> {
+        let mut guard = self.active_log.lock();
+        if let Some(log_file) = guard.as_mut() {
+            log_file.writer.flush()?;
+            log_file.writer.get_ref().sync_all()?;
+        }
 
-        // Keep logs that might still be needed
-        // For now, keep the two most recent logs after checkpoint
-        let files = self.log_files()?;
-        let current_seq = self.current_sequence.load(Ordering::Relaxed);
-
-        for file in files {
-            if let Some(seq) = Self::sequence_from_path(&file) {
-                // Keep the last 2 log files before current
-                if seq + 2 < current_seq {
-                    // Only delete if we have a checkpoint after this log
-                    if checkpoint.as_u64() > seq {
-                        let _ = fs::remove_file(&file);
-                    }
-                }
-            }
+        let base_sequence = self.current_sequence.fetch_add(2, Ordering::SeqCst) + 1;
+        let sequence = base_sequence + 1;
+        let path = self.log_path(sequence);
+        let file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(&path)?;
+
+        *guard = Some(LogFile {
+            writer: BufWriter::new(file),
+            size: 0,
+            path,
+            sequence,
+        });
+        self.records_since_sync.store(0, Ordering::Relaxed);
+        *self.last_sync.lock() = Instant::now();
+
+        Ok(base_sequence)
+    }
+
+    /// Compacts every log before `base_sequence` into a segment with that
+    /// sequence number, then removes them. Returns the bytes reclaimed.
+    fn compact_into(&self, base_sequence: u64) -> Result<u64> {
+        let segments: Vec<PathBuf> = self
+            .log_files()?
+            .into_iter()
+            .filter(|path| Self::sequence_from_path(path).is_some_and(|seq| seq < base_sequence))
+            .collect();
+        let old_size: u64 = segments
+            .iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+
+        let checkpoint = self.read_checkpoint_metadata()?;
+        let replay = WalRecovery::from_wal(self).replay(checkpoint.as_ref(), base_sequence)?;
+        let last_commit = replay
+            .committed
+            .iter()
+            .rev()
+            .find_map(|record| match record {
+                WalRecord::TxCommit { tx_id } => Some(*tx_id),
+                _ => None,
+            });
+
+        // The open transaction stays pending, to be committed by later records
+        let mut records = vec![WalRecord::CompactionBase];
+        records.extend(compact_records(replay.committed));
+        if let Some(tx_id) = last_commit {
+            records.push(WalRecord::TxCommit { tx_id });
+        }
+        records.extend(replay.pending);
+
+        // Write next to the log and rename, so the segment only shows up
+        // complete. Its leading marker makes recovery ignore older logs
+        // even if removing them below is cut short.
+        let path = self.log_path(base_sequence);
+        let temp_path = path.with_extension("log.tmp");
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        let mut new_size = 0;
+        for record in &records {
+            new_size += write_record(&mut writer, record)?;
         }
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        drop(writer);
+        fs::rename(&temp_path, &path)?;
 
-        Ok(())
+        for segment in &segments {
+            fs::remove_file(segment)?;
+        }
+
+        Ok(old_size.saturating_sub(new_size))
     }
 }
 
+/// Writes a length-prefixed, checksummed record. Returns the bytes written.
+fn write_record(writer: &mut impl Write, record: &WalRecord) -> Result<u64> {
+    // Serialize the record
+    let data = bincode::serde::encode_to_vec(record, bincode::config::standard())
+        .map_err(|e| Error::Serialization(e.to_string()))?;
+
+    // Write length prefix
+    let len = data.len() as u32;
+    writer.write_all(&len.to_le_bytes())?;
+
+    // Write data
+    writer.write_all(&data)?;
+
+    // Write checksum
+    let checksum = crc32fast::hash(&data);
+    writer.write_all(&checksum.to_le_bytes())?;
+
+    Ok(4 + data.len() as u64 + 4) // length + data + checksum
+}
+
 // Backward compatibility - single-file API
 impl WalManager {
     /// Opens a single WAL file (legacy API).
@@ -564,7 +683,7 @@ impl WalManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use grafeo_common::types::NodeId;
+    use grafeo_common::types::{NodeId, Value};
     use tempfile::tempdir;
 
     #[test]
@@ -683,4 +802,173 @@ mod tests {
 
         assert_eq!(wal.checkpoint_epoch(), Some(EpochId::new(10)));
     }
+
+    #[test]
+    fn test_unchanged_checkpoint() {
+        let dir = tempdir().unwrap();
+        let record = WalRecord::CreateNode {
+            id: NodeId::new(1),
+            labels: vec!["Test".to_string()],
+        };
+
+        {
+            let wal = WalManager::open(dir.path()).unwrap();
+            assert!(wal.unchanged_checkpoint().unwrap().is_none());
+            wal.log(&record).unwrap();
+            wal.log(&WalRecord::TxCommit {
+                tx_id: TxId::new(1),
+            })
+            .unwrap();
+            let handle = wal.checkpoint(TxId::new(1), EpochId::new(10)).unwrap();
+            let checkpoint = wal.unchanged_checkpoint().unwrap().unwrap();
+            assert_eq!(checkpoint.log_sequence, handle.metadata.log_sequence);
+        }
+
+        // Still at the checkpoint when reopened, until something is logged
+        let wal = WalManager::open(dir.path()).unwrap();
+        assert!(wal.unchanged_checkpoint().unwrap().is_some());
+        wal.log(&record).unwrap();
+        assert!(wal.unchanged_checkpoint().unwrap().is_none());
+    }
+
+    /// Logs a node whose `visits` property is overwritten many times.
+    fn log_churn(wal: &WalManager, id: u64, tx: u64) {
+        wal.log(&WalRecord::CreateNode {
+            id: NodeId::new(id),
+            labels: vec!["Person".to_string()],
+        })
+        .unwrap();
+        for visits in 0..50 {
+            wal.log(&WalRecord::SetNodeProperty {
+                id: NodeId::new(id),
+                key: "visits".to_string(),
+                value: Value::Int64(visits),
+            })
+            .unwrap();
+        }
+        wal.log(&WalRecord::TxCommit {
+            tx_id: TxId::new(tx),
+        })
+        .unwrap();
+    }
+
+    /// Replays recovered records into `(node, visits)` pairs.
+    fn recovered_visits(dir: &Path) -> Vec<(u64, Value)> {
+        let mut visits = std::collections::BTreeMap::new();
+        for record in WalRecovery::new(dir).recover().unwrap() {
+            match record {
+                WalRecord::CreateNode { id, .. } => {
+                    visits.insert(id.as_u64(), Value::Null);
+                }
+                WalRecord::SetNodeProperty { id, value, .. } => {
+                    visits.insert(id.as_u64(), value);
+                }
+                WalRecord::DeleteNode { id } => {
+                    visits.remove(&id.as_u64());
+                }
+                _ => {}
+            }
+        }
+        visits.into_iter().collect()
+    }
+
+    #[test]
+    fn test_checkpoint_compacts_log() {
+        let dir = tempdir().unwrap();
+
+        {
+            let wal = WalManager::open(dir.path()).unwrap();
+            log_churn(&wal, 1, 2);
+            wal.log(&WalRecord::CreateNode {
+                id: NodeId::new(2),
+                labels: vec![],
+            })
+            .unwrap();
+            wal.log(&WalRecord::DeleteNode { id: NodeId::new(2) })
+                .unwrap();
+            wal.log(&WalRecord::TxCommit {
+                tx_id: TxId::new(3),
+            })
+            .unwrap();
+            wal.sync().unwrap();
+            let before = wal.size_bytes();
+
+            let handle = wal.checkpoint(TxId::new(3), EpochId::new(7)).unwrap();
+            assert_eq!(handle.metadata.epoch, EpochId::new(7));
+            assert!(handle.bytes_reclaimed > 0);
+            assert!(wal.size_bytes() < before);
+
+            // Keep writing after the checkpoint, then crash without closing
+            log_churn(&wal, 3, 4);
+            wal.sync().unwrap();
+        }
+
+        assert_eq!(
+            recovered_visits(dir.path()),
+            vec![(1, Value::Int64(49)), (3, Value::Int64(49))]
+        );
+
+        // A reopened log continues after the compacted segment
+        {
+            let wal = WalManager::open(dir.path()).unwrap();
+            log_churn(&wal, 4, 5);
+            wal.checkpoint(TxId::new(5), EpochId::new(8)).unwrap();
+        }
+        assert_eq!(recovered_visits(dir.path()).len(), 3);
+        assert_eq!(
+            WalManager::open(dir.path())
+                .unwrap()
+                .log_files()
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_compact_rewrites_whole_log() {
+        let dir = tempdir().unwrap();
+        let config = WalConfig {
+            max_log_size: 256, // Spread the records over many logs
+            ..Default::default()
+        };
+
+        {
+            let wal = WalManager::with_config(dir.path(), config).unwrap();
+            log_churn(&wal, 1, 2);
+            log_churn(&wal, 2, 3);
+            // An open transaction survives compaction, uncommitted
+            wal.log(&WalRecord::CreateNode {
+                id: NodeId::new(9),
+                labels: vec![],
+            })
+            .unwrap();
+            wal.sync().unwrap();
+            assert!(wal.log_files().unwrap().len() > 2);
+            let before = wal.size_bytes() as u64;
+
+            let reclaimed = wal.compact().unwrap();
+            assert!(reclaimed > 0);
+            assert_eq!(wal.size_bytes() as u64, before - reclaimed);
+            assert_eq!(wal.log_files().unwrap().len(), 2);
+
+            // Nothing left to reclaim
+            assert_eq!(wal.compact().unwrap(), 0);
+
+            wal.log(&WalRecord::TxCommit {
+                tx_id: TxId::new(4),
+            })
+            .unwrap();
+            wal.sync().unwrap();
+        }
+
+        assert_eq!(
+            recovered_visits(dir.path()),
+            vec![
+                (1, Value::Int64(49)),
+                (2, Value::Int64(49)),
+                (9, Value::Null)
+            ]
+        );
+    }
 }
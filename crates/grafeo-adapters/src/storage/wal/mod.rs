@@ -13,11 +13,12 @@
 //! Choose [`WalManager`] for sync code, [`AsyncWalManager`] for async.
 
 mod async_log;
+mod compaction;
 mod log;
 mod record;
 mod recovery;
 
 pub use async_log::AsyncWalManager;
-pub use log::{CheckpointMetadata, DurabilityMode, WalConfig, WalManager};
+pub use log::{CheckpointHandle, CheckpointMetadata, DurabilityMode, WalConfig, WalManager};
 pub use record::WalRecord;
 pub use recovery::WalRecovery;
@@ -1,6 +1,6 @@
 //! WAL record types.
 
-use grafeo_common::types::{EdgeId, NodeId, TxId, Value};
+use grafeo_common::types::{EdgeId, NodeId, Timestamp, TxId, Value};
 use serde::{Deserialize, Serialize};
 
 /// A record in the Write-Ahead Log.
@@ -91,4 +91,18 @@ pub enum WalRecord {
         /// Transaction ID at checkpoint.
         tx_id: TxId,
     },
+
+    /// Set the interval during which an edge is valid.
+    SetEdgeValidity {
+        /// Edge ID.
+        id: EdgeId,
+        /// Start of the interval (inclusive), or `None` if open.
+        valid_from: Option<Timestamp>,
+        /// End of the interval (exclusive), or `None` if open.
+        valid_to: Option<Timestamp>,
+    },
+
+    /// Start of a compacted segment; every record logged before it is
+    /// superseded by the records that follow.
+    CompactionBase,
 }
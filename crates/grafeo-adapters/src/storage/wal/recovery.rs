@@ -9,6 +9,44 @@ use std::path::Path;
 /// Name of the checkpoint metadata file.
 const CHECKPOINT_METADATA_FILE: &str = "checkpoint.meta";
 
+/// Records read back from the log, sorted by transaction outcome.
+#[derive(Default)]
+pub(super) struct Replay {
+    /// Records of committed transactions, including their commit markers.
+    pub(super) committed: Vec<WalRecord>,
+    /// Records of the transaction still open at the end of the log.
+    pub(super) pending: Vec<WalRecord>,
+}
+
+impl Replay {
+    fn push(&mut self, record: WalRecord) {
+        match &record {
+            WalRecord::TxCommit { .. } => {
+                // Commit current transaction
+                self.committed.append(&mut self.pending);
+                self.committed.push(record);
+            }
+            WalRecord::TxAbort { .. } => {
+                // Discard current transaction
+                self.pending.clear();
+            }
+            WalRecord::Checkpoint { .. } => {
+                // Checkpoint - clear uncommitted, keep committed
+                self.pending.clear();
+                self.committed.push(record);
+            }
+            WalRecord::CompactionBase => {
+                // A compacted segment supersedes everything read so far
+                self.committed.clear();
+                self.pending.clear();
+            }
+            _ => {
+                self.pending.push(record);
+            }
+        }
+    }
+}
+
 /// Handles WAL recovery after a crash.
 pub struct WalRecovery {
     /// Directory containing WAL files.
@@ -93,19 +131,31 @@ impl WalRecovery {
     }
 
     fn recover_internal(&self, checkpoint: Option<CheckpointMetadata>) -> Result<Vec<WalRecord>> {
-        let mut current_tx_records = Vec::new();
-        let mut committed_records = Vec::new();
+        // Uncommitted records still pending at the end are discarded
+        Ok(self.replay(checkpoint.as_ref(), u64::MAX)?.committed)
+    }
+
+    /// Replays the log files with a sequence number below `end_sequence`.
+    ///
+    /// The log may start mid-stream: files before the checkpoint are
+    /// skipped, and a compacted segment discards whatever was read before it.
+    pub(super) fn replay(
+        &self,
+        checkpoint: Option<&CheckpointMetadata>,
+        end_sequence: u64,
+    ) -> Result<Replay> {
+        let mut replay = Replay::default();
 
         // Get all log files in order
         let log_files = self.get_log_files()?;
 
         // Determine the minimum sequence number to process
-        let min_sequence = checkpoint.as_ref().map(|cp| cp.log_sequence).unwrap_or(0);
+        let min_sequence = checkpoint.map(|cp| cp.log_sequence).unwrap_or(0);
 
         if checkpoint.is_some() {
             tracing::info!(
                 "Recovering from checkpoint at epoch {:?}, starting from log sequence {}",
-                checkpoint.as_ref().map(|c| c.epoch),
+                checkpoint.map(|c| c.epoch),
                 min_sequence
             );
         }
@@ -127,6 +177,9 @@ impl WalRecovery {
                 );
                 continue;
             }
+            if sequence >= end_sequence {
+                break;
+            }
 
             let file = match File::open(&log_file) {
                 Ok(f) => f,
@@ -138,27 +191,7 @@ impl WalRecovery {
             // Read all records from this file
             loop {
                 match self.read_record(&mut reader) {
-                    Ok(Some(record)) => {
-                        match &record {
-                            WalRecord::TxCommit { .. } => {
-                                // Commit current transaction
-                                committed_records.append(&mut current_tx_records);
-                                committed_records.push(record);
-                            }
-                            WalRecord::TxAbort { .. } => {
-                                // Discard current transaction
-                                current_tx_records.clear();
-                            }
-                            WalRecord::Checkpoint { .. } => {
-                                // Checkpoint - clear uncommitted, keep committed
-                                current_tx_records.clear();
-                                committed_records.push(record);
-                            }
-                            _ => {
-                                current_tx_records.push(record);
-                            }
-                        }
-                    }
+                    Ok(Some(record)) => replay.push(record),
                     Ok(None) => break, // EOF
                     Err(e) => {
                         // Log corruption - stop reading this file but continue
@@ -170,9 +203,7 @@ impl WalRecovery {
             }
         }
 
-        // Uncommitted records in current_tx_records are discarded
-
-        Ok(committed_records)
+        Ok(replay)
     }
 
     /// Extracts the sequence number from a WAL log file path.
@@ -490,4 +521,43 @@ mod tests {
         // The number depends on how many log files were skipped
         assert!(!records.is_empty(), "Should recover some records");
     }
+
+    #[test]
+    fn test_recovery_starts_at_compaction_base() {
+        let dir = tempdir().unwrap();
+
+        // Simulate a compaction cut short: the old log is still there, in
+        // front of the compacted segment that replaces it
+        {
+            let wal = WalManager::open(dir.path()).unwrap();
+            wal.log(&WalRecord::CreateNode {
+                id: NodeId::new(1),
+                labels: vec!["Old".to_string()],
+            })
+            .unwrap();
+            wal.log(&WalRecord::TxCommit {
+                tx_id: TxId::new(1),
+            })
+            .unwrap();
+
+            wal.log(&WalRecord::CompactionBase).unwrap();
+            wal.log(&WalRecord::CreateNode {
+                id: NodeId::new(2),
+                labels: vec!["New".to_string()],
+            })
+            .unwrap();
+            wal.log(&WalRecord::TxCommit {
+                tx_id: TxId::new(2),
+            })
+            .unwrap();
+            wal.sync().unwrap();
+        }
+
+        let records = WalRecovery::new(dir.path()).recover().unwrap();
+        assert_eq!(records.len(), 2);
+        assert!(matches!(
+            &records[0],
+            WalRecord::CreateNode { id, .. } if *id == NodeId::new(2)
+        ));
+    }
 }
@@ -113,6 +113,8 @@ pub struct BufferManager {
     allocated: AtomicUsize,
     /// Per-region allocated bytes.
     region_allocated: [AtomicUsize; 4],
+    /// Highest total allocation seen since creation or the last reset.
+    peak_allocated: AtomicUsize,
     /// Registered memory consumers.
     consumers: RwLock<Vec<Arc<dyn MemoryConsumer>>>,
     /// Computed soft limit in bytes.
@@ -142,6 +144,7 @@ impl BufferManager {
                 AtomicUsize::new(0),
                 AtomicUsize::new(0),
             ],
+            peak_allocated: AtomicUsize::new(0),
             consumers: RwLock::new(Vec::new()),
             soft_limit,
             evict_limit,
@@ -186,8 +189,7 @@ impl BufferManager {
         }
 
         // Perform allocation
-        self.allocated.fetch_add(size, Ordering::Relaxed);
-        self.region_allocated[region.index()].fetch_add(size, Ordering::Relaxed);
+        self.record_allocation(size, region);
 
         // Check pressure and potentially trigger background eviction
         self.check_pressure();
@@ -219,11 +221,25 @@ impl BufferManager {
                 self.region_allocated[2].load(Ordering::Relaxed),
                 self.region_allocated[3].load(Ordering::Relaxed),
             ],
+            peak_allocated: self.peak_allocated.load(Ordering::Relaxed),
             pressure_level: self.compute_pressure_level(total_allocated),
             consumer_count: self.consumers.read().len(),
         }
     }
 
+    /// Returns the highest total allocation seen so far.
+    #[must_use]
+    pub fn peak_allocated(&self) -> usize {
+        self.peak_allocated.load(Ordering::Relaxed)
+    }
+
+    /// Resets the peak to the current allocation, to start a new
+    /// measurement window.
+    pub fn reset_peak(&self) {
+        self.peak_allocated
+            .store(self.allocated.load(Ordering::Relaxed), Ordering::Relaxed);
+    }
+
     /// Registers a memory consumer for eviction callbacks.
     pub fn register_consumer(&self, consumer: Arc<dyn MemoryConsumer>) {
         self.consumers.write().push(consumer);
@@ -302,6 +318,12 @@ impl BufferManager {
         }
     }
 
+    fn record_allocation(&self, size: usize, region: MemoryRegion) {
+        let total = self.allocated.fetch_add(size, Ordering::Relaxed) + size;
+        self.region_allocated[region.index()].fetch_add(size, Ordering::Relaxed);
+        self.peak_allocated.fetch_max(total, Ordering::Relaxed);
+    }
+
     fn run_eviction_cycle(&self, aggressive: bool) -> usize {
         let target = if aggressive {
             self.soft_limit
@@ -367,8 +389,7 @@ impl GrantReleaser for BufferManager {
             }
         }
 
-        self.allocated.fetch_add(size, Ordering::Relaxed);
-        self.region_allocated[region.index()].fetch_add(size, Ordering::Relaxed);
+        self.record_allocation(size, region);
         true
     }
 }
@@ -461,6 +482,24 @@ mod tests {
         assert_eq!(manager.stats().total_allocated, 0);
     }
 
+    #[test]
+    fn test_peak_allocation_outlives_release() {
+        let manager = BufferManager::with_budget(10_000);
+
+        {
+            let _g1 = manager.try_allocate(300, MemoryRegion::ExecutionBuffers);
+            let _g2 = manager.try_allocate(200, MemoryRegion::IndexBuffers);
+        }
+        let _g3 = manager.try_allocate(100, MemoryRegion::ExecutionBuffers);
+
+        let stats = manager.stats();
+        assert_eq!(stats.total_allocated, 100);
+        assert_eq!(stats.peak_allocated, 500);
+
+        manager.reset_peak();
+        assert_eq!(manager.peak_allocated(), 100);
+    }
+
     #[test]
     fn test_pressure_levels() {
         let config = BufferManagerConfig {
@@ -69,6 +69,8 @@ pub struct BufferStats {
     pub total_allocated: usize,
     /// Per-region allocation in bytes.
     pub region_allocated: [usize; 4],
+    /// Highest total allocation seen, in bytes.
+    pub peak_allocated: usize,
     /// Current pressure level.
     pub pressure_level: PressureLevel,
     /// Number of registered consumers.
@@ -110,6 +112,7 @@ impl Default for BufferStats {
             budget: 0,
             total_allocated: 0,
             region_allocated: [0; 4],
+            peak_allocated: 0,
             pressure_level: PressureLevel::Normal,
             consumer_count: 0,
         }
@@ -131,6 +134,7 @@ impl std::fmt::Display for BufferStats {
             format_bytes(self.total_allocated),
             format_bytes(self.budget)
         )?;
+        writeln!(f, "  Peak: {}", format_bytes(self.peak_allocated))?;
         writeln!(f, "  Pressure: {}", self.pressure_level)?;
         writeln!(f, "  Consumers: {}", self.consumer_count)?;
         writeln!(f, "  Per-region:")?;
@@ -197,6 +201,7 @@ mod tests {
             budget: 1000,
             total_allocated: 750,
             region_allocated: [250, 250, 200, 50],
+            peak_allocated: 900,
             pressure_level: PressureLevel::Moderate,
             consumer_count: 3,
         };
@@ -212,6 +217,7 @@ mod tests {
             budget: 1000,
             total_allocated: 600,
             region_allocated: [100, 200, 250, 50],
+            peak_allocated: 600,
             pressure_level: PressureLevel::Normal,
             consumer_count: 2,
         };
@@ -18,6 +18,8 @@ pub struct VersionInfo {
     pub deleted_epoch: Option<EpochId>,
     /// The transaction that created this version.
     pub created_by: TxId,
+    /// The transaction deleting this version, until that delete commits.
+    pub deleted_by: Option<TxId>,
 }
 
 impl VersionInfo {
@@ -28,6 +30,7 @@ impl VersionInfo {
             created_epoch,
             deleted_epoch: None,
             created_by,
+            deleted_by: None,
         }
     }
 
@@ -59,8 +62,14 @@ impl VersionInfo {
     /// 1. It was created by the same transaction, OR
     /// 2. It was created in an epoch before the transaction's start epoch
     ///    and not deleted before that epoch
+    ///
+    /// A version the transaction itself is deleting is never visible to it.
     #[must_use]
     pub fn is_visible_to(&self, viewing_epoch: EpochId, viewing_tx: TxId) -> bool {
+        if self.deleted_by == Some(viewing_tx) {
+            return false;
+        }
+
         // Own modifications are always visible
         if self.created_by == viewing_tx {
             return self.deleted_epoch.is_none();
@@ -163,6 +172,37 @@ impl<T> VersionChain<T> {
         false
     }
 
+    /// Marks the current version as deleted by an uncommitted transaction.
+    ///
+    /// The transaction stops seeing the version straight away; everyone
+    /// else keeps seeing it until [`commit_versions_by`](Self::commit_versions_by)
+    /// stamps the delete with the commit epoch.
+    ///
+    /// Returns `true` if a version was marked, `false` if no undeleted
+    /// version exists.
+    pub fn mark_deleted_by(&mut self, tx: TxId) -> bool {
+        for version in &mut self.versions {
+            if version.info.deleted_epoch.is_none() {
+                version.info.deleted_epoch = Some(EpochId::PENDING);
+                version.info.deleted_by = Some(tx);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Withdraws the deletes an uncommitted transaction made.
+    ///
+    /// Used for rollback, alongside [`remove_versions_by`](Self::remove_versions_by).
+    pub fn restore_deleted_by(&mut self, tx: TxId) {
+        for version in &mut self.versions {
+            if version.info.deleted_by == Some(tx) {
+                version.info.deleted_epoch = None;
+                version.info.deleted_by = None;
+            }
+        }
+    }
+
     /// Checks if any version was modified by the given transaction.
     #[must_use]
     pub fn modified_by(&self, tx: TxId) -> bool {
@@ -176,6 +216,23 @@ impl<T> VersionChain<T> {
         self.versions.retain(|v| v.info.created_by != tx);
     }
 
+    /// Moves the versions created, and the deletes made, by a transaction
+    /// to its commit epoch.
+    ///
+    /// Until then they carry whatever epoch they were written with, usually
+    /// [`EpochId::PENDING`] so that no other transaction sees them.
+    pub fn commit_versions_by(&mut self, tx: TxId, commit_epoch: EpochId) {
+        for version in &mut self.versions {
+            if version.info.created_by == tx {
+                version.info.created_epoch = commit_epoch;
+            }
+            if version.info.deleted_by == Some(tx) {
+                version.info.deleted_epoch = Some(commit_epoch);
+                version.info.deleted_by = None;
+            }
+        }
+    }
+
     /// Checks if there's a concurrent modification conflict.
     ///
     /// A conflict exists if another transaction modified this entity
@@ -343,6 +400,18 @@ mod tests {
         assert_eq!(chain.visible_at(EpochId::new(10)), Some(&"v1"));
     }
 
+    #[test]
+    fn test_version_chain_commit_restamps_epoch() {
+        let mut chain = VersionChain::with_initial("v1", EpochId::new(1), TxId::new(1));
+        chain.add_version("v2", EpochId::new(1), TxId::new(2));
+
+        // Tx 2 commits at epoch 3; readers at epoch 1 or 2 keep seeing v1
+        chain.commit_versions_by(TxId::new(2), EpochId::new(3));
+
+        assert_eq!(chain.visible_at(EpochId::new(2)), Some(&"v1"));
+        assert_eq!(chain.visible_at(EpochId::new(3)), Some(&"v2"));
+    }
+
     #[test]
     fn test_version_chain_deletion() {
         let mut chain = VersionChain::with_initial("v1", EpochId::new(1), TxId::new(1));
@@ -355,4 +424,24 @@ mod tests {
         assert_eq!(chain.visible_at(EpochId::new(5)), None);
         assert_eq!(chain.visible_at(EpochId::new(10)), None);
     }
+
+    #[test]
+    fn test_pending_delete_hidden_only_from_deleter() {
+        let mut chain = VersionChain::with_initial("v1", EpochId::new(1), TxId::new(1));
+        assert!(chain.mark_deleted_by(TxId::new(2)));
+
+        assert_eq!(chain.visible_to(EpochId::new(1), TxId::new(2)), None);
+        assert_eq!(chain.visible_to(EpochId::new(1), TxId::new(3)), Some(&"v1"));
+        assert_eq!(chain.visible_at(EpochId::new(10)), Some(&"v1"));
+
+        // Rolled back, the deleter sees it again
+        chain.restore_deleted_by(TxId::new(2));
+        assert_eq!(chain.visible_to(EpochId::new(1), TxId::new(2)), Some(&"v1"));
+
+        // Committed at epoch 4, only older snapshots still see it
+        assert!(chain.mark_deleted_by(TxId::new(2)));
+        chain.commit_versions_by(TxId::new(2), EpochId::new(4));
+        assert_eq!(chain.visible_at(EpochId::new(3)), Some(&"v1"));
+        assert_eq!(chain.visible_at(EpochId::new(4)), None);
+    }
 }
@@ -226,6 +226,11 @@ impl EpochId {
     /// The initial epoch (epoch 0).
     pub const INITIAL: Self = Self(0);
 
+    /// Stands in for the commit epoch of a write that hasn't committed yet.
+    ///
+    /// It's later than every real epoch, so no snapshot sees such a write.
+    pub const PENDING: Self = Self(u64::MAX);
+
     /// Creates a new EpochId from a raw u64 value.
     #[inline]
     #[must_use]
@@ -6,10 +6,12 @@
 //! - **IDs**: [`NodeId`], [`EdgeId`] - handles to graph elements
 //! - **Values**: [`Value`] - the dynamic type for properties
 //! - **Keys**: [`PropertyKey`] - interned property names
-//! - **Time**: [`Timestamp`] - for temporal properties
+//! - **Time**: [`Timestamp`] - for temporal properties, with date and
+//!   duration helpers in [`temporal`]
 
 mod id;
 mod logical_type;
+pub mod temporal;
 mod timestamp;
 mod value;
 
@@ -0,0 +1,338 @@
+//! Calendar dates, durations, and ISO 8601 parsing for temporal values.
+//!
+//! Dates are stored as days since the Unix epoch and durations as
+//! microseconds, matching [`Value::Date`](super::Value::Date) and
+//! [`Value::Duration`](super::Value::Duration). Calendar math uses the
+//! proleptic Gregorian calendar in UTC.
+
+use std::fmt::Write;
+
+use super::Timestamp;
+
+/// Microseconds in one day.
+pub const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+const MICROS_PER_HOUR: i64 = 3_600_000_000;
+const MICROS_PER_MINUTE: i64 = 60_000_000;
+const MICROS_PER_SECOND: i64 = 1_000_000;
+
+/// Returns the days since 1970-01-01 of a calendar date.
+///
+/// Doesn't validate the date; out-of-range days roll over into the next month.
+#[must_use]
+pub const fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Returns the `(year, month, day)` of a day count since 1970-01-01.
+#[must_use]
+pub const fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Parses an ISO 8601 date (`2024-01-31`) into days since the Unix epoch.
+///
+/// Returns `None` if `text` isn't a valid calendar date.
+#[must_use]
+pub fn parse_date(text: &str) -> Option<i32> {
+    let (year, rest) = split_number(text.trim(), 4)?;
+    let (month, rest) = split_number(rest.strip_prefix('-')?, 2)?;
+    let (day, rest) = split_number(rest.strip_prefix('-')?, 2)?;
+    if !rest.is_empty() || !(1..=12).contains(&month) {
+        return None;
+    }
+    let days = days_from_civil(year, month as u32, day as u32);
+    // Reject days past the end of the month, which would roll over
+    if day < 1 || civil_from_days(days).2 != day as u32 {
+        return None;
+    }
+    i32::try_from(days).ok()
+}
+
+/// Formats days since the Unix epoch as an ISO 8601 date.
+#[must_use]
+pub fn format_date(days: i32) -> String {
+    let (year, month, day) = civil_from_days(i64::from(days));
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Parses an ISO 8601 date-time such as `2024-01-31T12:30:00Z`.
+///
+/// The time may have fractional seconds and a `Z` or `±hh:mm` offset; a
+/// missing offset means UTC. A plain date is midnight UTC.
+#[must_use]
+pub fn parse_timestamp(text: &str) -> Option<Timestamp> {
+    let text = text.trim();
+    let split = text.find(['T', 't', ' ']).unwrap_or(text.len());
+    let days = i64::from(parse_date(&text[..split])?);
+    let mut micros = days * MICROS_PER_DAY;
+    let time = text.get(split + 1..).unwrap_or("");
+    if time.is_empty() {
+        return (split == text.len()).then_some(Timestamp::from_micros(micros));
+    }
+
+    // Split off the offset, then read hh:mm[:ss[.ffffff]]
+    let (clock, offset) = if let Some(clock) = time.strip_suffix(['Z', 'z']) {
+        (clock, 0)
+    } else if let Some(at) = time.rfind(['+', '-']) {
+        let sign = if time.as_bytes()[at] == b'-' { -1 } else { 1 };
+        let (hours, rest) = split_number(&time[at + 1..], 2)?;
+        let (minutes, rest) = split_number(rest.strip_prefix(':')?, 2)?;
+        if !rest.is_empty() || hours > 23 || minutes > 59 {
+            return None;
+        }
+        (
+            &time[..at],
+            sign * (hours * MICROS_PER_HOUR + minutes * MICROS_PER_MINUTE),
+        )
+    } else {
+        (time, 0)
+    };
+    let (hours, rest) = split_number(clock, 2)?;
+    let (minutes, rest) = split_number(rest.strip_prefix(':')?, 2)?;
+    let (seconds, fraction) = match rest.strip_prefix(':') {
+        Some(rest) => split_number(rest, 2)?,
+        None if rest.is_empty() => (0, ""),
+        None => return None,
+    };
+    if hours > 23 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+    micros += hours * MICROS_PER_HOUR + minutes * MICROS_PER_MINUTE + seconds * MICROS_PER_SECOND;
+    if !fraction.is_empty() {
+        micros += parse_fraction(fraction.strip_prefix('.')?)?;
+    }
+    Some(Timestamp::from_micros(micros - offset))
+}
+
+/// Parses an ISO 8601 duration such as `P1DT2H30M` or `-PT0.5S` into
+/// microseconds.
+///
+/// Weeks, days, hours, minutes, and seconds are supported. Years and
+/// months have no fixed length, so durations using them are rejected.
+#[must_use]
+pub fn parse_duration(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, text),
+    };
+    let text = text.strip_prefix(['P', 'p'])?;
+    let (date, time) = match text.find(['T', 't']) {
+        Some(at) => (&text[..at], Some(&text[at + 1..])),
+        None => (text, None),
+    };
+
+    let mut total: i64 = 0;
+    let mut parts = 0;
+    for (part, units) in [
+        (
+            date,
+            &[('W', 7 * MICROS_PER_DAY), ('D', MICROS_PER_DAY)][..],
+        ),
+        (
+            time.unwrap_or(""),
+            &[
+                ('H', MICROS_PER_HOUR),
+                ('M', MICROS_PER_MINUTE),
+                ('S', MICROS_PER_SECOND),
+            ][..],
+        ),
+    ] {
+        let mut rest = part;
+        let mut next_unit = 0;
+        while !rest.is_empty() {
+            let end = rest.find(|c: char| c.is_ascii_alphabetic())?;
+            let (number, designator) = (&rest[..end], rest[end..].chars().next()?);
+            let position = units[next_unit..]
+                .iter()
+                .position(|(unit, _)| unit.eq_ignore_ascii_case(&designator))?;
+            let scale = units[next_unit + position].1;
+            next_unit += position + 1;
+
+            let value = match number.split_once('.') {
+                // Only seconds may have a fraction
+                Some((whole, fraction)) if scale == MICROS_PER_SECOND => whole
+                    .parse::<i64>()
+                    .ok()?
+                    .checked_mul(scale)?
+                    .checked_add(parse_fraction(fraction)?)?,
+                Some(_) => return None,
+                None => number.parse::<i64>().ok()?.checked_mul(scale)?,
+            };
+            total = total.checked_add(value)?;
+            parts += 1;
+            rest = &rest[end + 1..];
+        }
+    }
+    // `P` and `PT` alone aren't durations
+    if parts == 0 || time == Some("") {
+        return None;
+    }
+    Some(sign * total)
+}
+
+/// Formats microseconds as an ISO 8601 duration, such as `P1DT2H30M`.
+#[must_use]
+pub fn format_duration(micros: i64) -> String {
+    let mut out = String::new();
+    if micros < 0 {
+        out.push('-');
+    }
+    out.push('P');
+    let micros = micros.unsigned_abs();
+    let days = micros / MICROS_PER_DAY as u64;
+    let hours = micros % MICROS_PER_DAY as u64 / MICROS_PER_HOUR as u64;
+    let minutes = micros % MICROS_PER_HOUR as u64 / MICROS_PER_MINUTE as u64;
+    let seconds = micros % MICROS_PER_MINUTE as u64 / MICROS_PER_SECOND as u64;
+    let fraction = micros % MICROS_PER_SECOND as u64;
+
+    if days > 0 {
+        let _ = write!(out, "{days}D");
+    }
+    if hours > 0 || minutes > 0 || seconds > 0 || fraction > 0 || days == 0 {
+        out.push('T');
+        if hours > 0 {
+            let _ = write!(out, "{hours}H");
+        }
+        if minutes > 0 {
+            let _ = write!(out, "{minutes}M");
+        }
+        if fraction > 0 {
+            let fraction = format!("{fraction:06}");
+            let _ = write!(out, "{seconds}.{}S", fraction.trim_end_matches('0'));
+        } else if seconds > 0 || (days == 0 && hours == 0 && minutes == 0) {
+            let _ = write!(out, "{seconds}S");
+        }
+    }
+    out
+}
+
+/// Splits a fixed-width run of `width` digits (or more, for years) off the
+/// front of `text`.
+fn split_number(text: &str, width: usize) -> Option<(i64, &str)> {
+    let (sign, digits) = match text.strip_prefix('-') {
+        Some(rest) if width == 4 => (-1, rest),
+        _ => (1, text),
+    };
+    let end = digits
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(digits.len());
+    if end < width || (width < 4 && end != width) {
+        return None;
+    }
+    Some((sign * digits[..end].parse::<i64>().ok()?, &digits[end..]))
+}
+
+/// Parses the digits after a decimal point as microseconds, truncating
+/// anything finer.
+fn parse_fraction(digits: &str) -> Option<i64> {
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let padded = format!("{:0<6}", &digits[..digits.len().min(6)]);
+    padded.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_civil_roundtrip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 1, 1), 19_723);
+        assert_eq!(days_from_civil(1969, 12, 31), -1);
+        for days in [-800_000, -1, 0, 59, 19_723, 2_932_896] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("2024-01-01"), Some(19_723));
+        assert_eq!(parse_date("1969-12-31"), Some(-1));
+        assert_eq!(parse_date("2024-02-29"), Some(19_782));
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(parse_date("2024-1-01"), None);
+        assert_eq!(parse_date("2024-01-01T00:00"), None);
+        assert_eq!(parse_date("yesterday"), None);
+        assert_eq!(format_date(19_782), "2024-02-29");
+        assert_eq!(format_date(-1), "1969-12-31");
+    }
+
+    #[test]
+    fn test_parse_timestamp() {
+        let midnight = Timestamp::from_secs(19_723 * 86_400);
+        assert_eq!(parse_timestamp("2024-01-01"), Some(midnight));
+        assert_eq!(parse_timestamp("2024-01-01T00:00:00Z"), Some(midnight));
+        assert_eq!(
+            parse_timestamp("2024-01-01T12:30"),
+            Some(midnight.add_micros(12 * MICROS_PER_HOUR + 30 * MICROS_PER_MINUTE))
+        );
+        assert_eq!(
+            parse_timestamp("2024-01-01 00:00:01.25"),
+            Some(midnight.add_micros(1_250_000))
+        );
+        assert_eq!(parse_timestamp("2024-01-01T02:00:00+02:00"), Some(midnight));
+        assert_eq!(parse_timestamp("2023-12-31T22:00:00-02:00"), Some(midnight));
+        assert_eq!(parse_timestamp("2024-01-01T24:00:00"), None);
+        assert_eq!(parse_timestamp("2024-01-01T"), None);
+        assert_eq!(parse_timestamp("2024-01-01T12:30:00.Z"), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("P1D"), Some(MICROS_PER_DAY));
+        assert_eq!(parse_duration("P1W2D"), Some(9 * MICROS_PER_DAY));
+        assert_eq!(
+            parse_duration("PT1H30M"),
+            Some(MICROS_PER_HOUR + 30 * MICROS_PER_MINUTE)
+        );
+        assert_eq!(parse_duration("-PT0.5S"), Some(-500_000));
+        assert_eq!(parse_duration("P1Y"), None);
+        assert_eq!(parse_duration("P1M"), None);
+        assert_eq!(parse_duration("PT1M1H"), None);
+        assert_eq!(parse_duration("P"), None);
+        assert_eq!(parse_duration("P1DT"), None);
+        assert_eq!(parse_duration("P1.5D"), None);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "PT0S");
+        assert_eq!(format_duration(MICROS_PER_DAY), "P1D");
+        assert_eq!(
+            format_duration(MICROS_PER_DAY + 2 * MICROS_PER_HOUR + 500_000),
+            "P1DT2H0.5S"
+        );
+        assert_eq!(format_duration(-90 * MICROS_PER_MINUTE), "-PT1H30M");
+        for text in ["P3DT4H5M6.000007S", "-PT12S", "P14D"] {
+            assert_eq!(format_duration(parse_duration(text).unwrap()), text);
+        }
+    }
+}
@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+use super::temporal::{MICROS_PER_DAY, civil_from_days};
+
 /// A point in time, stored as microseconds since Unix epoch.
 ///
 /// Microsecond precision, covering roughly 290,000 years in each direction
@@ -115,30 +117,23 @@ impl fmt::Debug for Timestamp {
 
 impl fmt::Display for Timestamp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        // Simple ISO 8601-ish format
-        let secs = self.0 / 1_000_000;
-        let micros = (self.0 % 1_000_000).unsigned_abs();
-
-        // Calculate date/time components (simplified, doesn't handle all edge cases)
-        const SECS_PER_DAY: i64 = 86400;
-        const DAYS_PER_YEAR: i64 = 365;
-
-        let days = secs / SECS_PER_DAY;
-        let time_secs = (secs % SECS_PER_DAY + SECS_PER_DAY) % SECS_PER_DAY;
-
-        let hours = time_secs / 3600;
-        let minutes = (time_secs % 3600) / 60;
-        let seconds = time_secs % 60;
+        // ISO 8601 in UTC, with fractional seconds only when there are any
+        let days = self.0.div_euclid(MICROS_PER_DAY);
+        let time = self.0.rem_euclid(MICROS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
 
-        // Very rough year calculation (ignores leap years for display)
-        let year = 1970 + days / DAYS_PER_YEAR;
-        let day_of_year = days % DAYS_PER_YEAR;
+        let secs = time / 1_000_000;
+        let micros = time % 1_000_000;
+        let (hours, minutes, seconds) = (secs / 3600, secs % 3600 / 60, secs % 60);
 
         write!(
             f,
-            "{:04}-{:03}T{:02}:{:02}:{:02}.{:06}Z",
-            year, day_of_year, hours, minutes, seconds, micros
-        )
+            "{year:04}-{month:02}-{day:02}T{hours:02}:{minutes:02}:{seconds:02}"
+        )?;
+        if micros > 0 {
+            write!(f, ".{micros:06}")?;
+        }
+        write!(f, "Z")
     }
 }
 
@@ -228,6 +223,16 @@ mod tests {
         assert!(diff.as_micros() < 2);
     }
 
+    #[test]
+    fn test_timestamp_display() {
+        assert_eq!(Timestamp::EPOCH.to_string(), "1970-01-01T00:00:00Z");
+        assert_eq!(
+            Timestamp::from_micros(1_709_210_096_000_250).to_string(),
+            "2024-02-29T12:34:56.000250Z"
+        );
+        assert_eq!(Timestamp::from_secs(-1).to_string(), "1969-12-31T23:59:59Z");
+    }
+
     #[test]
     fn test_timestamp_epoch() {
         assert_eq!(Timestamp::EPOCH.as_micros(), 0);
@@ -5,11 +5,12 @@
 //! efficient property lookups.
 
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
 
-use super::Timestamp;
+use super::{Timestamp, temporal};
 
 /// An interned property name - cheap to clone and compare.
 ///
@@ -109,9 +110,42 @@ pub enum Value {
 
     /// Key-value map (uses BTreeMap for deterministic ordering)
     Map(Arc<BTreeMap<PropertyKey, Value>>),
+
+    /// A 2D point in the spatial reference system `srid`.
+    ///
+    /// For geographic points ([`Value::WGS84_SRID`]) `x` is the longitude and
+    /// `y` the latitude, in degrees.
+    Point {
+        /// X coordinate, or longitude.
+        x: f64,
+        /// Y coordinate, or latitude.
+        y: f64,
+        /// Spatial reference system identifier.
+        srid: u32,
+    },
+
+    /// Calendar date, as days since 1970-01-01
+    Date(i32),
+
+    /// Length of time, in microseconds
+    Duration(i64),
 }
 
 impl Value {
+    /// SRID of points on a flat plane, measured with Euclidean distance.
+    pub const CARTESIAN_SRID: u32 = 7203;
+
+    /// SRID of longitude/latitude points on the WGS 84 ellipsoid, measured
+    /// with haversine distance in meters.
+    pub const WGS84_SRID: u32 = 4326;
+
+    /// Creates a point value.
+    #[inline]
+    #[must_use]
+    pub const fn point(x: f64, y: f64, srid: u32) -> Self {
+        Value::Point { x, y, srid }
+    }
+
     /// Returns `true` if this value is null.
     #[inline]
     #[must_use]
@@ -199,6 +233,64 @@ impl Value {
         }
     }
 
+    /// Returns the days since the Unix epoch if this is a Date, otherwise None.
+    #[inline]
+    #[must_use]
+    pub const fn as_date(&self) -> Option<i32> {
+        match self {
+            Value::Date(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Returns the microseconds if this is a Duration, otherwise None.
+    #[inline]
+    #[must_use]
+    pub const fn as_duration(&self) -> Option<i64> {
+        match self {
+            Value::Duration(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Returns a Timestamp or Date as microseconds since the Unix epoch,
+    /// taking dates as midnight UTC, otherwise None.
+    ///
+    /// Comparisons go through this, so a date equals the timestamp at the
+    /// start of its day and orders before any later time that day.
+    #[inline]
+    #[must_use]
+    pub const fn as_epoch_micros(&self) -> Option<i64> {
+        match self {
+            Value::Timestamp(t) => Some(t.as_micros()),
+            Value::Date(d) => Some(*d as i64 * temporal::MICROS_PER_DAY),
+            _ => None,
+        }
+    }
+
+    /// Compares two temporal values: dates and timestamps by instant (see
+    /// [`as_epoch_micros`](Self::as_epoch_micros)), durations by length.
+    ///
+    /// Returns None if either value isn't temporal, or for a duration
+    /// against a date or timestamp.
+    #[must_use]
+    pub fn compare_temporal(&self, other: &Value) -> Option<Ordering> {
+        match (self, other) {
+            (Value::Duration(a), Value::Duration(b)) => Some(a.cmp(b)),
+            _ => Some(self.as_epoch_micros()?.cmp(&other.as_epoch_micros()?)),
+        }
+    }
+
+    /// Returns the coordinates and SRID if this is a Point, otherwise None.
+    #[inline]
+    #[must_use]
+    pub const fn as_point(&self) -> Option<(f64, f64, u32)> {
+        match self {
+            Value::Point { x, y, srid } => Some((*x, *y, *srid)),
+            _ => None,
+        }
+    }
+
     /// Returns the type name of this value.
     #[must_use]
     pub const fn type_name(&self) -> &'static str {
@@ -212,6 +304,9 @@ impl Value {
             Value::Timestamp(_) => "TIMESTAMP",
             Value::List(_) => "LIST",
             Value::Map(_) => "MAP",
+            Value::Point { .. } => "POINT",
+            Value::Date(_) => "DATE",
+            Value::Duration(_) => "DURATION",
         }
     }
 
@@ -245,6 +340,9 @@ impl fmt::Debug for Value {
             Value::Timestamp(t) => write!(f, "Timestamp({t:?})"),
             Value::List(l) => write!(f, "List({l:?})"),
             Value::Map(m) => write!(f, "Map({m:?})"),
+            Value::Point { x, y, srid } => write!(f, "Point({x}, {y}; {srid})"),
+            Value::Date(d) => write!(f, "Date({})", temporal::format_date(*d)),
+            Value::Duration(d) => write!(f, "Duration({})", temporal::format_duration(*d)),
         }
     }
 }
@@ -279,6 +377,9 @@ impl fmt::Display for Value {
                 }
                 write!(f, "}}")
             }
+            Value::Point { x, y, srid } => write!(f, "point({{x: {x}, y: {y}, srid: {srid}}})"),
+            Value::Date(d) => write!(f, "{}", temporal::format_date(*d)),
+            Value::Duration(d) => write!(f, "{}", temporal::format_duration(*d)),
         }
     }
 }
@@ -413,6 +514,9 @@ mod tests {
             Value::String("hello world".into()),
             Value::Bytes(vec![0, 1, 2, 255].into()),
             Value::List(vec![Value::Int64(1), Value::Int64(2)].into()),
+            Value::point(-0.1276, 51.5072, Value::WGS84_SRID),
+            Value::Date(19_723),
+            Value::Duration(-90_000_000),
         ];
 
         for v in values {
@@ -444,5 +548,33 @@ mod tests {
         assert_eq!(Value::Bytes(vec![].into()).type_name(), "BYTES");
         assert_eq!(Value::List(vec![].into()).type_name(), "LIST");
         assert_eq!(Value::Map(BTreeMap::new().into()).type_name(), "MAP");
+        assert_eq!(Value::point(1.0, 2.0, 7203).type_name(), "POINT");
+        assert_eq!(Value::Date(0).type_name(), "DATE");
+        assert_eq!(Value::Duration(0).type_name(), "DURATION");
+    }
+
+    #[test]
+    fn test_temporal_values() {
+        let date = Value::Date(19_723);
+        assert_eq!(date.to_string(), "2024-01-01");
+        assert_eq!(Value::Duration(5_400_000_000).to_string(), "PT1H30M");
+
+        let midnight = Value::Timestamp(Timestamp::from_secs(19_723 * 86_400));
+        assert_eq!(date.as_epoch_micros(), midnight.as_epoch_micros());
+        assert_eq!(Value::Duration(1).as_epoch_micros(), None);
+        assert_eq!(date.as_date(), Some(19_723));
+        assert_eq!(Value::Int64(1).as_duration(), None);
+
+        assert_eq!(date.compare_temporal(&midnight), Some(Ordering::Equal));
+        assert_eq!(
+            Value::Date(19_722).compare_temporal(&midnight),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            Value::Duration(2).compare_temporal(&Value::Duration(1)),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(date.compare_temporal(&Value::Duration(0)), None);
+        assert_eq!(date.compare_temporal(&Value::from("2024-01-01")), None);
     }
 }
@@ -112,6 +112,12 @@ pub enum TransactionError {
 
     /// Invalid transaction state.
     InvalidState(String),
+
+    /// The transaction would write more entities than its limit allows.
+    WriteSetTooLarge(usize),
+
+    /// No savepoint with this name is set in the transaction.
+    SavepointNotFound(String),
 }
 
 impl fmt::Display for TransactionError {
@@ -124,6 +130,13 @@ impl fmt::Display for TransactionError {
             TransactionError::Timeout => write!(f, "Transaction timeout"),
             TransactionError::ReadOnly => write!(f, "Cannot write in read-only transaction"),
             TransactionError::InvalidState(msg) => write!(f, "Invalid transaction state: {msg}"),
+            TransactionError::WriteSetTooLarge(limit) => write!(
+                f,
+                "Transaction writes more than {limit} entities; split it into smaller batches"
+            ),
+            TransactionError::SavepointNotFound(name) => {
+                write!(f, "No savepoint named '{name}' in this transaction")
+            }
         }
     }
 }
@@ -280,6 +293,12 @@ pub enum QueryErrorKind {
     Optimization,
     /// Execution error.
     Execution,
+    /// The query is nested too deeply or plans too many operators.
+    Complexity,
+    /// The query was cancelled while it ran.
+    Cancelled,
+    /// The query ran past its timeout.
+    Timeout,
 }
 
 impl fmt::Display for QueryErrorKind {
@@ -290,6 +309,9 @@ impl fmt::Display for QueryErrorKind {
             QueryErrorKind::Semantic => write!(f, "semantic error"),
             QueryErrorKind::Optimization => write!(f, "optimization error"),
             QueryErrorKind::Execution => write!(f, "execution error"),
+            QueryErrorKind::Complexity => write!(f, "complexity error"),
+            QueryErrorKind::Cancelled => write!(f, "cancellation"),
+            QueryErrorKind::Timeout => write!(f, "timeout"),
         }
     }
 }
@@ -0,0 +1,119 @@
+//! Benchmarks for hash table sizing in joins and aggregation.
+//!
+//! Compares building from a cold table against one pre-sized from a row
+//! estimate. That the estimate avoids resizes is checked by the operators'
+//! unit tests; this only times the difference.
+
+use std::hint::black_box;
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use grafeo_common::types::LogicalType;
+use grafeo_core::execution::DataChunk;
+use grafeo_core::execution::chunk::DataChunkBuilder;
+use grafeo_core::execution::operators::{
+    AggregateExpr, HashAggregateOperator, HashJoinOperator, JoinType, Operator, OperatorResult,
+};
+
+const BUILD_ROWS: i64 = 200_000;
+
+/// Replays a fixed set of chunks.
+struct ChunkSource {
+    chunks: Vec<DataChunk>,
+}
+
+impl Operator for ChunkSource {
+    fn next(&mut self) -> OperatorResult {
+        Ok(self.chunks.pop())
+    }
+
+    fn reset(&mut self) {}
+
+    fn name(&self) -> &'static str {
+        "ChunkSource"
+    }
+}
+
+fn int_chunks(rows: i64) -> Vec<DataChunk> {
+    (0..rows)
+        .step_by(2048)
+        .map(|start| {
+            let mut builder = DataChunkBuilder::new(&[LogicalType::Int64]);
+            for v in start..(start + 2048).min(rows) {
+                builder.column_mut(0).unwrap().push_int64(v);
+                builder.advance_row();
+            }
+            builder.finish()
+        })
+        .collect()
+}
+
+fn run_join(build: &[DataChunk], estimate: Option<f64>) -> usize {
+    let probe = ChunkSource {
+        chunks: int_chunks(16),
+    };
+    let join = HashJoinOperator::new(
+        Box::new(probe),
+        Box::new(ChunkSource {
+            chunks: build.to_vec(),
+        }),
+        vec![0],
+        vec![0],
+        JoinType::Inner,
+        vec![LogicalType::Int64, LogicalType::Int64],
+    );
+    let mut join = match estimate {
+        Some(rows) => join.with_estimated_build_rows(rows),
+        None => join,
+    };
+    while let Some(chunk) = join.next().unwrap() {
+        black_box(chunk);
+    }
+    join.hash_table_resizes()
+}
+
+fn run_aggregate(input: &[DataChunk], estimate: Option<f64>) -> usize {
+    let agg = HashAggregateOperator::new(
+        Box::new(ChunkSource {
+            chunks: input.to_vec(),
+        }),
+        vec![0],
+        vec![AggregateExpr::count_star()],
+        vec![LogicalType::Int64, LogicalType::Int64],
+    );
+    let mut agg = match estimate {
+        Some(groups) => agg.with_estimated_groups(groups),
+        None => agg,
+    };
+    while let Some(chunk) = agg.next().unwrap() {
+        black_box(chunk);
+    }
+    agg.group_table_resizes()
+}
+
+fn bench_hash_join_build(c: &mut Criterion) {
+    let build = int_chunks(BUILD_ROWS);
+    let estimate = Some(BUILD_ROWS as f64);
+    let mut group = c.benchmark_group("hash_join_build");
+    for (name, estimate) in [("unsized", None), ("estimated", estimate)] {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &estimate, |b, &est| {
+            b.iter(|| run_join(&build, est));
+        });
+    }
+    group.finish();
+}
+
+fn bench_hash_aggregate(c: &mut Criterion) {
+    let input = int_chunks(BUILD_ROWS);
+    let estimate = Some(BUILD_ROWS as f64);
+    let mut group = c.benchmark_group("hash_aggregate");
+    for (name, estimate) in [("unsized", None), ("estimated", estimate)] {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &estimate, |b, &est| {
+            b.iter(|| run_aggregate(&input, est));
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_hash_join_build, bench_hash_aggregate);
+criterion_main!(benches);
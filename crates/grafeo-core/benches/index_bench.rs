@@ -35,6 +35,46 @@ fn bench_adjacency_lookup(c: &mut Criterion) {
     });
 }
 
+fn bench_adjacency_expand_frontier(c: &mut Criterion) {
+    // 100k nodes with 8 edges each, expanded from a shuffled 10k-node frontier
+    let adj = ChunkedAdjacency::new();
+    let mut edge = 0u64;
+    for src in 0..100_000u64 {
+        for k in 1..=8u64 {
+            adj.add_edge(
+                NodeId(src),
+                NodeId((src * 31 + k * 7919) % 100_000),
+                EdgeId(edge),
+            );
+            edge += 1;
+        }
+    }
+    adj.compact();
+    let frontier: Vec<NodeId> = (0..10_000u64)
+        .map(|i| NodeId((i * 7_919) % 100_000))
+        .collect();
+
+    let mut group = c.benchmark_group("adjacency_expand_frontier_10k");
+    group.bench_function("per_node", |b| {
+        b.iter(|| {
+            let mut rows = Vec::new();
+            for &src in &frontier {
+                for (dst, edge) in adj.edges_from(src) {
+                    rows.push((src, edge, dst));
+                }
+            }
+            black_box(rows)
+        });
+    });
+    group.bench_function("batch", |b| {
+        b.iter(|| black_box(adj.expand_batch(&frontier, false)));
+    });
+    group.bench_function("batch_sorted", |b| {
+        b.iter(|| black_box(adj.expand_batch(&frontier, true)));
+    });
+    group.finish();
+}
+
 fn bench_hash_index_insert(c: &mut Criterion) {
     c.bench_function("hash_index_insert_1000", |b| {
         b.iter(|| {
@@ -66,6 +106,7 @@ criterion_group!(
     benches,
     bench_adjacency_insert,
     bench_adjacency_lookup,
+    bench_adjacency_expand_frontier,
     bench_hash_index_insert,
     bench_hash_index_lookup,
 );
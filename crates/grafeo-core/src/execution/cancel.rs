@@ -0,0 +1,198 @@
+//! Stopping queries early, on request or at a deadline.
+//!
+//! Execution checks an [`Interrupt`] at chunk boundaries: each chunk a
+//! [`Pipeline`](super::Pipeline) pulls from its source, and each chunk an
+//! [`InterruptibleOperator`] hands upward. A stopped query fails with
+//! [`OperatorError::Cancelled`] or [`OperatorError::TimedOut`]; dropping its
+//! operators then releases their memory and spill files as usual.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use super::operators::{Operator, OperatorError, OperatorResult};
+
+/// Lets another thread cancel a running query.
+///
+/// Clones share the same flag, so keep one and hand another to the query.
+/// Once cancelled, a token stays cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that isn't cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every query watching this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Release);
+    }
+
+    /// Returns true once [`cancel()`](Self::cancel) has been called.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Acquire)
+    }
+}
+
+/// What stops a query early: a cancellation token, a deadline, or both.
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+    token: Option<CancellationToken>,
+    deadline: Option<Instant>,
+}
+
+impl Interrupt {
+    /// Creates an interrupt that never fires.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops the query once `token` is cancelled.
+    #[must_use]
+    pub fn with_token(mut self, token: CancellationToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Stops the query at `deadline`.
+    #[must_use]
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Stops the query once `timeout` has passed from now.
+    ///
+    /// A timeout too long to represent means no deadline.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Instant::now().checked_add(timeout);
+        self
+    }
+
+    /// Returns true if there's anything to check.
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.token.is_some() || self.deadline.is_some()
+    }
+
+    /// Fails if the query was cancelled or its deadline has passed.
+    ///
+    /// Cancellation wins when both apply.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OperatorError::Cancelled`] or [`OperatorError::TimedOut`].
+    pub fn check(&self) -> Result<(), OperatorError> {
+        if self
+            .token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Err(OperatorError::Cancelled);
+        }
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(OperatorError::TimedOut);
+        }
+        Ok(())
+    }
+}
+
+/// Checks an [`Interrupt`] before pulling each chunk from its input.
+pub struct InterruptibleOperator {
+    input: Box<dyn Operator>,
+    interrupt: Interrupt,
+}
+
+impl InterruptibleOperator {
+    /// Wraps `input`, checking `interrupt` before each chunk.
+    #[must_use]
+    pub fn new(input: Box<dyn Operator>, interrupt: Interrupt) -> Self {
+        Self { input, interrupt }
+    }
+}
+
+impl Operator for InterruptibleOperator {
+    fn next(&mut self) -> OperatorResult {
+        self.interrupt.check()?;
+        self.input.next()
+    }
+
+    fn reset(&mut self) {
+        self.input.reset();
+    }
+
+    fn name(&self) -> &'static str {
+        self.input.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::DataChunk;
+    use crate::execution::vector::ValueVector;
+    use grafeo_common::types::Value;
+
+    /// Produces one-row chunks forever.
+    struct Endless;
+
+    impl Operator for Endless {
+        fn next(&mut self) -> OperatorResult {
+            Ok(Some(DataChunk::new(vec![ValueVector::from_values(&[
+                Value::Int64(1),
+            ])])))
+        }
+
+        fn reset(&mut self) {}
+
+        fn name(&self) -> &'static str {
+            "Endless"
+        }
+    }
+
+    #[test]
+    fn test_token_cancels_between_chunks() {
+        let token = CancellationToken::new();
+        let mut operator = InterruptibleOperator::new(
+            Box::new(Endless),
+            Interrupt::new().with_token(token.clone()),
+        );
+        assert!(operator.next().unwrap().is_some());
+        assert_eq!(operator.name(), "Endless");
+
+        token.cancel();
+        assert!(matches!(operator.next(), Err(OperatorError::Cancelled)));
+    }
+
+    #[test]
+    fn test_deadline_times_out() {
+        let interrupt = Interrupt::new().with_deadline(Instant::now());
+        assert!(matches!(interrupt.check(), Err(OperatorError::TimedOut)));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let both = interrupt.with_token(token);
+        assert!(matches!(both.check(), Err(OperatorError::Cancelled)));
+
+        let far = Interrupt::new().with_timeout(Duration::MAX);
+        assert!(!far.is_active());
+        assert!(far.check().is_ok());
+        assert!(
+            Interrupt::new()
+                .with_timeout(Duration::from_mins(1))
+                .check()
+                .is_ok()
+        );
+    }
+}
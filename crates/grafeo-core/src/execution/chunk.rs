@@ -141,6 +141,13 @@ impl DataChunk {
         self.count >= self.capacity
     }
 
+    /// Returns the estimated average bytes per row, summed over the
+    /// columns' [`average_width()`](ValueVector::average_width).
+    #[must_use]
+    pub fn average_row_width(&self) -> usize {
+        self.columns.iter().map(ValueVector::average_width).sum()
+    }
+
     /// Gets a column by index.
     #[must_use]
     pub fn column(&self, index: usize) -> Option<&ValueVector> {
@@ -565,4 +572,26 @@ mod tests {
         assert_eq!(chunk.row_count(), original_count);
         assert_eq!(chunk.column(0).unwrap().get_int64(0), Some(42));
     }
+
+    #[test]
+    fn test_average_row_width_sums_columns() {
+        let schema = [LogicalType::Int64, LogicalType::String];
+        let mut narrow = DataChunkBuilder::with_schema(&schema);
+        let mut wide = DataChunkBuilder::with_schema(&schema);
+        for i in 0..4 {
+            narrow.column_mut(0).unwrap().push_int64(i);
+            narrow.column_mut(1).unwrap().push_string("a");
+            narrow.advance_row();
+            wide.column_mut(0).unwrap().push_int64(i);
+            wide.column_mut(1).unwrap().push_string("a".repeat(64));
+            wide.advance_row();
+        }
+        let (narrow, wide) = (narrow.finish(), wide.finish());
+
+        assert_eq!(wide.average_row_width(), narrow.average_row_width() + 63);
+        assert_eq!(
+            narrow.average_row_width(),
+            narrow.column(0).unwrap().average_width() + narrow.column(1).unwrap().average_width()
+        );
+    }
 }
@@ -0,0 +1,232 @@
+//! String collation for sorting and comparisons.
+//!
+//! By default strings compare by their raw bytes - fast, deterministic, and
+//! what you want for identifiers. For user-facing text like names and titles,
+//! byte order puts `"Zoe"` before `"adam"` and `"Émile"` after everything
+//! ASCII. [`Collation::Unicode`] fixes that with a multi-level comparison in
+//! the spirit of the Unicode Collation Algorithm:
+//!
+//! 1. **Primary** - base letters, ignoring accents and case (`e` = `é` = `E`)
+//! 2. **Secondary** - accents (`e` < `é`)
+//! 3. **Tertiary** - case (`e` < `E`)
+//! 4. Byte order as a final tie-breaker, so distinct strings never compare equal
+//!
+//! # Example
+//!
+//! ```
+//! use grafeo_core::execution::Collation;
+//! use std::cmp::Ordering;
+//!
+//! assert_eq!(Collation::Binary.compare("Émile", "Zoe"), Ordering::Greater);
+//! assert_eq!(Collation::Unicode.compare("Émile", "Zoe"), Ordering::Less);
+//! ```
+
+use std::cmp::Ordering;
+
+/// How strings are ordered when sorting or evaluating range predicates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Collation {
+    /// Raw byte order. Fastest, and the default.
+    #[default]
+    Binary,
+    /// Case-insensitive ordering; accents still count as distinct letters.
+    CaseInsensitive,
+    /// Locale-aware ordering: accents and case only break ties.
+    Unicode,
+}
+
+impl Collation {
+    /// Compares two strings under this collation.
+    #[must_use]
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            Self::Binary => a.cmp(b),
+            Self::CaseInsensitive => compare_by(a, b, CharWeight::lower).then_with(|| a.cmp(b)),
+            Self::Unicode => compare_by(a, b, CharWeight::primary)
+                .then_with(|| compare_by(a, b, CharWeight::secondary))
+                .then_with(|| compare_by(a, b, CharWeight::tertiary))
+                .then_with(|| a.cmp(b)),
+        }
+    }
+
+    /// Returns true if this is the default byte-order collation.
+    #[inline]
+    #[must_use]
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Self::Binary)
+    }
+}
+
+/// Compares two strings by a per-character weight sequence.
+fn compare_by<K: Ord>(a: &str, b: &str, weight: impl Fn(char) -> K) -> Ordering {
+    let mut a_iter = a.chars().map(&weight);
+    let mut b_iter = b.chars().map(&weight);
+    loop {
+        match (a_iter.next(), b_iter.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) => match x.cmp(&y) {
+                Ordering::Equal => {}
+                other => return other,
+            },
+        }
+    }
+}
+
+/// Weight helpers for the individual collation levels.
+struct CharWeight;
+
+impl CharWeight {
+    /// Lowercased character (single-char mapping only).
+    fn lower(c: char) -> char {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+
+    /// Base letter with accents and case removed.
+    fn primary(c: char) -> char {
+        Self::lower(fold_diacritic(c).0)
+    }
+
+    /// Accent weight: 0 for unaccented letters, otherwise the accented char.
+    fn secondary(c: char) -> u32 {
+        let (base, accented) = fold_diacritic(Self::lower(c));
+        if accented {
+            // Keep distinct accents in a stable relative order.
+            u32::from(Self::lower(c)) - u32::from(base) + 1
+        } else {
+            0
+        }
+    }
+
+    /// Case weight: lowercase sorts before uppercase.
+    fn tertiary(c: char) -> u8 {
+        u8::from(c.is_uppercase())
+    }
+}
+
+/// Maps a Latin letter with a diacritic to its base letter.
+///
+/// Returns the base and whether the input carried a diacritic. Covers
+/// Latin-1 Supplement and Latin Extended-A, which handles the common
+/// Western and Central European languages.
+fn fold_diacritic(c: char) -> (char, bool) {
+    let base = match c {
+        'À'..='Å' => 'A',
+        'Ç' => 'C',
+        'È'..='Ë' => 'E',
+        'Ì'..='Ï' => 'I',
+        'Ñ' => 'N',
+        'Ò'..='Ö' | 'Ø' => 'O',
+        'Ù'..='Ü' => 'U',
+        'Ý' => 'Y',
+        'à'..='å' => 'a',
+        'ç' => 'c',
+        'è'..='ë' => 'e',
+        'ì'..='ï' => 'i',
+        'ñ' => 'n',
+        'ò'..='ö' | 'ø' => 'o',
+        'ù'..='ü' => 'u',
+        'ý' | 'ÿ' => 'y',
+        '\u{0100}'..='\u{017F}' => return fold_latin_extended_a(c),
+        _ => return (c, false),
+    };
+    (base, true)
+}
+
+/// Folds Latin Extended-A (U+0100..U+017F) to base letters.
+fn fold_latin_extended_a(c: char) -> (char, bool) {
+    let base = match c {
+        'Ā' | 'Ă' | 'Ą' => 'A',
+        'ā' | 'ă' | 'ą' => 'a',
+        'Ć' | 'Ĉ' | 'Ċ' | 'Č' => 'C',
+        'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+        'Ď' | 'Đ' => 'D',
+        'ď' | 'đ' => 'd',
+        'Ē' | 'Ĕ' | 'Ė' | 'Ę' | 'Ě' => 'E',
+        'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+        'Ĝ' | 'Ğ' | 'Ġ' | 'Ģ' => 'G',
+        'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+        'Ĥ' | 'Ħ' => 'H',
+        'ĥ' | 'ħ' => 'h',
+        'Ĩ' | 'Ī' | 'Ĭ' | 'Į' | 'İ' => 'I',
+        'ĩ' | 'ī' | 'ĭ' | 'į' | 'ı' => 'i',
+        'Ĵ' => 'J',
+        'ĵ' => 'j',
+        'Ķ' => 'K',
+        'ķ' => 'k',
+        'Ĺ' | 'Ļ' | 'Ľ' | 'Ŀ' | 'Ł' => 'L',
+        'ĺ' | 'ļ' | 'ľ' | 'ŀ' | 'ł' => 'l',
+        'Ń' | 'Ņ' | 'Ň' => 'N',
+        'ń' | 'ņ' | 'ň' => 'n',
+        'Ō' | 'Ŏ' | 'Ő' => 'O',
+        'ō' | 'ŏ' | 'ő' => 'o',
+        'Ŕ' | 'Ŗ' | 'Ř' => 'R',
+        'ŕ' | 'ŗ' | 'ř' => 'r',
+        'Ś' | 'Ŝ' | 'Ş' | 'Š' => 'S',
+        'ś' | 'ŝ' | 'ş' | 'š' => 's',
+        'Ţ' | 'Ť' | 'Ŧ' => 'T',
+        'ţ' | 'ť' | 'ŧ' => 't',
+        'Ũ' | 'Ū' | 'Ŭ' | 'Ů' | 'Ű' | 'Ų' => 'U',
+        'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+        'Ŵ' => 'W',
+        'ŵ' => 'w',
+        'Ŷ' | 'Ÿ' => 'Y',
+        'ŷ' => 'y',
+        'Ź' | 'Ż' | 'Ž' => 'Z',
+        'ź' | 'ż' | 'ž' => 'z',
+        _ => return (c, false),
+    };
+    (base, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(collation: Collation, words: &[&str]) -> Vec<String> {
+        let mut v: Vec<String> = words.iter().map(|s| (*s).to_string()).collect();
+        v.sort_by(|a, b| collation.compare(a, b));
+        v
+    }
+
+    #[test]
+    fn test_binary_is_byte_order() {
+        let out = sorted(Collation::Binary, &["b", "Émile", "a", "Zoe"]);
+        assert_eq!(out, vec!["Zoe", "a", "b", "Émile"]);
+    }
+
+    #[test]
+    fn test_unicode_orders_accents_with_base_letter() {
+        let out = sorted(Collation::Unicode, &["Zoe", "Émile", "eve", "Adam", "élan"]);
+        assert_eq!(out, vec!["Adam", "élan", "Émile", "eve", "Zoe"]);
+    }
+
+    #[test]
+    fn test_unicode_tie_breaks() {
+        // Unaccented before accented, lowercase before uppercase
+        assert_eq!(Collation::Unicode.compare("e", "é"), Ordering::Less);
+        assert_eq!(Collation::Unicode.compare("e", "E"), Ordering::Less);
+        assert_eq!(Collation::Unicode.compare("é", "E"), Ordering::Greater);
+        assert_eq!(Collation::Unicode.compare("same", "same"), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let out = sorted(Collation::CaseInsensitive, &["banana", "Apple", "cherry"]);
+        assert_eq!(out, vec!["Apple", "banana", "cherry"]);
+        // Accents remain distinct letters
+        assert_eq!(
+            Collation::CaseInsensitive.compare("é", "f"),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_fold_latin_extended() {
+        assert_eq!(
+            sorted(Collation::Unicode, &["Łukasz", "Luca", "Marta"]),
+            vec!["Luca", "Łukasz", "Marta"]
+        );
+    }
+}
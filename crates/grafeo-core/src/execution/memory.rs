@@ -1,6 +1,9 @@
 //! Execution memory context for memory-aware query execution.
 
+use super::spill::SpillManager;
 use grafeo_common::memory::buffer::{BufferManager, MemoryGrant, MemoryRegion, PressureLevel};
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Default chunk size for execution buffers.
@@ -15,6 +18,32 @@ pub const HIGH_PRESSURE_CHUNK_SIZE: usize = 512;
 /// Chunk size under critical memory pressure.
 pub const CRITICAL_PRESSURE_CHUNK_SIZE: usize = 256;
 
+/// Default bytes a chunk of rows should take, sized to stay in cache.
+pub const DEFAULT_TARGET_CHUNK_BYTES: usize = 256 * 1024;
+
+/// Default fewest rows per chunk, however wide the rows.
+pub const DEFAULT_MIN_CHUNK_SIZE: usize = 64;
+
+/// Default most rows per chunk, however narrow the rows.
+pub const DEFAULT_MAX_CHUNK_SIZE: usize = 8192;
+
+/// Default number of bytes operators may use before they're charged.
+pub const DEFAULT_ACCOUNTING_GRANULARITY: usize = 64 * 1024;
+
+/// Operator kinds that can spill to disk once they outgrow their budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpillableOperator {
+    /// Sorts, including `ORDER BY` and top-k.
+    Sort,
+    /// Grouped aggregation.
+    Aggregate,
+    /// Hash joins.
+    Join,
+}
+
+/// An execution memory context shared by the operators of one query.
+pub type SharedMemoryContext = Arc<Mutex<ExecutionMemoryContext>>;
+
 /// Execution context with memory awareness.
 ///
 /// This context provides memory allocation for query execution operators
@@ -26,6 +55,24 @@ pub struct ExecutionMemoryContext {
     allocated: usize,
     /// Grants held by this context.
     grants: Vec<MemoryGrant>,
+    /// Bytes to collect in `pending` before charging the buffer manager.
+    granularity: usize,
+    /// Bytes recorded through [`account()`](Self::account) but not yet charged.
+    pending: usize,
+    /// Directory operators may spill to when the budget runs out.
+    spill_dir: Option<PathBuf>,
+    /// Spill manager for `spill_dir`, created on first use.
+    spill_manager: Option<Arc<SpillManager>>,
+    /// Operator kinds that fail instead of spilling.
+    spill_disabled: Vec<SpillableOperator>,
+    /// Most bytes in use at once since the last [`reset_peak()`](Self::reset_peak).
+    peak: usize,
+    /// Bytes a chunk of rows should take.
+    target_chunk_bytes: usize,
+    /// Fewest rows a width-sized chunk holds.
+    min_chunk_size: usize,
+    /// Most rows a width-sized chunk holds.
+    max_chunk_size: usize,
 }
 
 impl ExecutionMemoryContext {
@@ -33,9 +80,154 @@ impl ExecutionMemoryContext {
     #[must_use]
     pub fn new(manager: Arc<BufferManager>) -> Self {
         Self {
+            spill_dir: manager.config().spill_path.clone(),
             manager,
             allocated: 0,
             grants: Vec::new(),
+            granularity: DEFAULT_ACCOUNTING_GRANULARITY,
+            pending: 0,
+            spill_manager: None,
+            spill_disabled: Vec::new(),
+            peak: 0,
+            target_chunk_bytes: DEFAULT_TARGET_CHUNK_BYTES,
+            min_chunk_size: DEFAULT_MIN_CHUNK_SIZE,
+            max_chunk_size: DEFAULT_MAX_CHUNK_SIZE,
+        }
+    }
+
+    /// Sets how many bytes [`account()`](Self::account) collects before
+    /// charging the buffer manager.
+    ///
+    /// A query can overshoot its limit by at most this much between checks.
+    /// Smaller values bound the overshoot more tightly at the cost of more
+    /// frequent accounting; zero charges every call.
+    #[must_use]
+    pub fn with_granularity(mut self, bytes: usize) -> Self {
+        self.granularity = bytes;
+        self
+    }
+
+    /// Returns the accounting granularity in bytes.
+    #[must_use]
+    pub fn granularity(&self) -> usize {
+        self.granularity
+    }
+
+    /// Sets how many bytes a chunk of rows should take; see
+    /// [`chunk_size_for_row_width()`](Self::chunk_size_for_row_width).
+    #[must_use]
+    pub fn with_target_chunk_bytes(mut self, bytes: usize) -> Self {
+        self.target_chunk_bytes = bytes;
+        self
+    }
+
+    /// Returns how many bytes a chunk of rows should take.
+    #[must_use]
+    pub fn target_chunk_bytes(&self) -> usize {
+        self.target_chunk_bytes
+    }
+
+    /// Sets the fewest and most rows a width-sized chunk holds.
+    ///
+    /// A `max` below `min` is raised to `min`.
+    #[must_use]
+    pub fn with_chunk_size_bounds(mut self, min: usize, max: usize) -> Self {
+        self.min_chunk_size = min.max(1);
+        self.max_chunk_size = max.max(self.min_chunk_size);
+        self
+    }
+
+    /// Returns the fewest and most rows a width-sized chunk holds.
+    #[must_use]
+    pub fn chunk_size_bounds(&self) -> (usize, usize) {
+        (self.min_chunk_size, self.max_chunk_size)
+    }
+
+    /// Sets the directory operators spill to, overriding the buffer
+    /// manager's `spill_path`.
+    pub fn set_spill_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.spill_dir = Some(dir.into());
+        self.spill_manager = None;
+    }
+
+    /// Returns the directory operators spill to, if spilling is possible.
+    #[must_use]
+    pub fn spill_dir(&self) -> Option<&Path> {
+        self.spill_dir.as_deref()
+    }
+
+    /// Stops `operator` from spilling, so it fails with a memory limit
+    /// error once it outgrows the budget.
+    pub fn disable_spill(&mut self, operator: SpillableOperator) {
+        if !self.spill_disabled.contains(&operator) {
+            self.spill_disabled.push(operator);
+        }
+    }
+
+    /// Returns whether `operator` may spill to disk.
+    ///
+    /// Spilling also needs a spill directory; see [`spill_dir()`](Self::spill_dir).
+    #[must_use]
+    pub fn spill_enabled(&self, operator: SpillableOperator) -> bool {
+        !self.spill_disabled.contains(&operator)
+    }
+
+    /// Returns the spill manager for this context's spill directory.
+    ///
+    /// The manager (and its directory) is created on first use and removes
+    /// its files once the context is dropped. Returns `None` when no spill
+    /// directory is configured.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the spill directory can't be created.
+    pub fn spill_manager(&mut self) -> std::io::Result<Option<Arc<SpillManager>>> {
+        if self.spill_manager.is_none() {
+            if let Some(dir) = &self.spill_dir {
+                self.spill_manager = Some(Arc::new(SpillManager::new(dir)?));
+            }
+        }
+        Ok(self.spill_manager.clone())
+    }
+
+    /// Returns bytes recorded but not yet charged to the buffer manager.
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.pending
+    }
+
+    /// Records memory an operator has used.
+    ///
+    /// Usage is batched: the buffer manager is only charged once the
+    /// uncharged total reaches the granularity. Returns `false` if that
+    /// charge would exceed the budget.
+    pub fn account(&mut self, bytes: usize) -> bool {
+        self.pending += bytes;
+        self.update_peak();
+        if self.pending < self.granularity {
+            return true;
+        }
+        self.charge(0)
+    }
+
+    /// Charges memory up front, before a large allocation is made.
+    ///
+    /// Unlike [`account()`](Self::account) this is never batched, so bulk
+    /// allocations like hash table builds and sort buffers can be refused
+    /// before they happen. Returns `false` if the budget can't cover it.
+    pub fn reserve(&mut self, bytes: usize) -> bool {
+        self.charge(bytes)
+    }
+
+    /// Charges the pending bytes plus `extra`, clearing `pending` on success.
+    fn charge(&mut self, extra: usize) -> bool {
+        // Cleared first so the peak doesn't count the pending bytes twice
+        let pending = std::mem::take(&mut self.pending);
+        if self.allocate_tracked(pending + extra) {
+            true
+        } else {
+            self.pending = pending;
+            false
         }
     }
 
@@ -47,6 +239,7 @@ impl ExecutionMemoryContext {
             .manager
             .try_allocate(size, MemoryRegion::ExecutionBuffers)?;
         self.allocated += size;
+        self.update_peak();
         Some(grant)
     }
 
@@ -60,6 +253,7 @@ impl ExecutionMemoryContext {
         {
             self.allocated += size;
             self.grants.push(grant);
+            self.update_peak();
             true
         } else {
             false
@@ -98,22 +292,102 @@ impl ExecutionMemoryContext {
         self.adjusted_chunk_size(DEFAULT_CHUNK_SIZE)
     }
 
+    /// Returns how many rows of `row_bytes` each fit the target chunk bytes.
+    ///
+    /// Wide rows get fewer rows per chunk and narrow rows more, further
+    /// reduced under memory pressure, but always within the
+    /// [bounds](Self::chunk_size_bounds). A width of zero, for rows not seen
+    /// yet, gets the default chunk size.
+    #[must_use]
+    pub fn chunk_size_for_row_width(&self, row_bytes: usize) -> usize {
+        let rows = self
+            .target_chunk_bytes
+            .checked_div(row_bytes)
+            .unwrap_or(DEFAULT_CHUNK_SIZE);
+        self.adjusted_chunk_size(rows)
+            .clamp(self.min_chunk_size, self.max_chunk_size)
+    }
+
     /// Returns total bytes allocated through this context.
     #[must_use]
     pub fn total_allocated(&self) -> usize {
         self.allocated
     }
 
+    /// Returns bytes in use: allocated plus recorded but not yet charged.
+    #[must_use]
+    pub fn in_use(&self) -> usize {
+        self.allocated + self.pending
+    }
+
+    /// Returns the most bytes in use at once since the context was created
+    /// or [`reset_peak()`](Self::reset_peak) was last called.
+    #[must_use]
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// Starts measuring the peak afresh from current usage, returning the
+    /// peak measured so far.
+    ///
+    /// Pass the returned value to [`restore_peak()`](Self::restore_peak)
+    /// afterwards to measure a nested stretch of work without losing the
+    /// outer peak.
+    pub fn reset_peak(&mut self) -> usize {
+        let in_use = self.in_use();
+        std::mem::replace(&mut self.peak, in_use)
+    }
+
+    /// Folds an earlier peak from [`reset_peak()`](Self::reset_peak) back in.
+    pub fn restore_peak(&mut self, earlier: usize) {
+        self.peak = self.peak.max(earlier);
+    }
+
+    fn update_peak(&mut self) {
+        self.peak = self.peak.max(self.in_use());
+    }
+
     /// Returns the buffer manager.
     #[must_use]
     pub fn manager(&self) -> &Arc<BufferManager> {
         &self.manager
     }
 
+    /// Gives back `bytes` of accounted memory, such as after an operator
+    /// has spilled the buffers it paid for.
+    ///
+    /// Uncharged pending bytes are dropped first, then tracked grants.
+    pub fn release(&mut self, bytes: usize) {
+        let from_pending = bytes.min(self.pending);
+        self.pending -= from_pending;
+        let mut remaining = (bytes - from_pending).min(self.allocated);
+        self.allocated -= remaining;
+        while remaining > 0 {
+            let Some(grant) = self.grants.last_mut() else {
+                break;
+            };
+            let size = grant.size();
+            if size <= remaining {
+                remaining -= size;
+                self.grants.pop();
+            } else {
+                grant.resize(size - remaining);
+                remaining = 0;
+            }
+        }
+    }
+
     /// Releases all tracked grants.
     pub fn release_all(&mut self) {
         self.grants.clear();
         self.allocated = 0;
+        self.pending = 0;
+    }
+
+    /// Wraps this context so several operators can share it.
+    #[must_use]
+    pub fn into_shared(self) -> SharedMemoryContext {
+        Arc::new(Mutex::new(self))
     }
 }
 
@@ -128,6 +402,7 @@ impl Drop for ExecutionMemoryContext {
 pub struct ExecutionMemoryContextBuilder {
     manager: Arc<BufferManager>,
     initial_allocation: usize,
+    granularity: usize,
 }
 
 impl ExecutionMemoryContextBuilder {
@@ -137,6 +412,7 @@ impl ExecutionMemoryContextBuilder {
         Self {
             manager,
             initial_allocation: 0,
+            granularity: DEFAULT_ACCOUNTING_GRANULARITY,
         }
     }
 
@@ -147,11 +423,18 @@ impl ExecutionMemoryContextBuilder {
         self
     }
 
+    /// Sets the accounting granularity in bytes.
+    #[must_use]
+    pub fn with_granularity(mut self, bytes: usize) -> Self {
+        self.granularity = bytes;
+        self
+    }
+
     /// Builds the execution memory context.
     ///
     /// Returns `None` if the initial allocation cannot be satisfied.
     pub fn build(self) -> Option<ExecutionMemoryContext> {
-        let mut ctx = ExecutionMemoryContext::new(self.manager);
+        let mut ctx = ExecutionMemoryContext::new(self.manager).with_granularity(self.granularity);
 
         if self.initial_allocation > 0 && !ctx.allocate_tracked(self.initial_allocation) {
             return None;
@@ -227,6 +510,49 @@ mod tests {
         assert!(ctx.should_reduce_chunk_size());
     }
 
+    #[test]
+    fn test_chunk_size_shrinks_as_rows_widen() {
+        let manager = BufferManager::with_budget(1024 * 1024);
+        let ctx = ExecutionMemoryContext::new(manager).with_target_chunk_bytes(64 * 1024);
+
+        assert_eq!(ctx.chunk_size_for_row_width(0), DEFAULT_CHUNK_SIZE);
+        let sizes: Vec<usize> = [16, 64, 256, 1024]
+            .iter()
+            .map(|&width| ctx.chunk_size_for_row_width(width))
+            .collect();
+        assert_eq!(sizes, vec![4096, 1024, 256, DEFAULT_MIN_CHUNK_SIZE]);
+
+        // Very narrow and very wide rows stop at the bounds
+        assert_eq!(ctx.chunk_size_for_row_width(1), DEFAULT_MAX_CHUNK_SIZE);
+        assert_eq!(
+            ctx.chunk_size_for_row_width(1 << 20),
+            DEFAULT_MIN_CHUNK_SIZE
+        );
+        let ctx = ctx.with_chunk_size_bounds(100, 2000);
+        assert_eq!(ctx.chunk_size_bounds(), (100, 2000));
+        assert_eq!(ctx.chunk_size_for_row_width(1), 2000);
+        assert_eq!(ctx.chunk_size_for_row_width(1 << 20), 100);
+    }
+
+    #[test]
+    fn test_chunk_size_for_row_width_under_pressure() {
+        let config = BufferManagerConfig {
+            budget: 1000,
+            soft_limit_fraction: 0.70,
+            evict_limit_fraction: 0.85,
+            hard_limit_fraction: 0.95,
+            background_eviction: false,
+            spill_path: None,
+        };
+        let manager = BufferManager::new(config);
+        let _g = manager.try_allocate(860, MemoryRegion::ExecutionBuffers);
+
+        let ctx = ExecutionMemoryContext::new(manager);
+        assert_eq!(ctx.chunk_size_for_row_width(8), HIGH_PRESSURE_CHUNK_SIZE);
+        let ctx = ctx.with_chunk_size_bounds(1024, 4096);
+        assert_eq!(ctx.chunk_size_for_row_width(8), 1024);
+    }
+
     #[test]
     fn test_builder() {
         let manager = BufferManager::with_budget(1024 * 1024);
@@ -240,6 +566,24 @@ mod tests {
         assert_eq!(ctx.total_allocated(), 4096);
     }
 
+    #[test]
+    fn test_peak_survives_release_and_nests() {
+        let manager = BufferManager::with_budget(1024 * 1024);
+        let mut ctx = ExecutionMemoryContext::new(manager).with_granularity(1000);
+
+        assert!(ctx.account(600));
+        assert!(ctx.reserve(400));
+        ctx.release(1000);
+        assert_eq!(ctx.in_use(), 0);
+        assert_eq!(ctx.peak(), 1000);
+
+        let outer = ctx.reset_peak();
+        assert!(ctx.account(300));
+        assert_eq!(ctx.peak(), 300);
+        ctx.restore_peak(outer);
+        assert_eq!(ctx.peak(), 1000);
+    }
+
     #[test]
     fn test_builder_insufficient_memory() {
         let manager = BufferManager::with_budget(1000);
@@ -251,4 +595,48 @@ mod tests {
 
         assert!(ctx.is_none());
     }
+
+    #[test]
+    fn test_account_batches_by_granularity() {
+        let manager = BufferManager::with_budget(1024 * 1024);
+        let mut ctx = ExecutionMemoryContext::new(Arc::clone(&manager)).with_granularity(1000);
+
+        assert!(ctx.account(400));
+        assert!(ctx.account(400));
+        assert_eq!(ctx.pending(), 800);
+        assert_eq!(manager.allocated(), 0);
+
+        // Crossing the granularity charges everything collected so far
+        assert!(ctx.account(400));
+        assert_eq!(ctx.pending(), 0);
+        assert_eq!(ctx.total_allocated(), 1200);
+        assert_eq!(manager.allocated(), 1200);
+    }
+
+    #[test]
+    fn test_reserve_is_not_batched() {
+        let manager = BufferManager::with_budget(10_000);
+        let mut ctx = ExecutionMemoryContext::new(Arc::clone(&manager)).with_granularity(1 << 20);
+
+        assert!(ctx.reserve(500));
+        assert_eq!(manager.allocated(), 500);
+
+        // More than the budget is refused without charging anything
+        assert!(!ctx.reserve(20_000));
+        assert_eq!(manager.allocated(), 500);
+    }
+
+    #[test]
+    fn test_release_returns_pending_then_grants() {
+        let manager = BufferManager::with_budget(1024 * 1024);
+        let mut ctx = ExecutionMemoryContext::new(Arc::clone(&manager)).with_granularity(1000);
+
+        assert!(ctx.reserve(1500));
+        assert!(ctx.account(300));
+
+        ctx.release(800);
+        assert_eq!(ctx.pending(), 0);
+        assert_eq!(ctx.total_allocated(), 1000);
+        assert_eq!(manager.allocated(), 1000);
+    }
 }
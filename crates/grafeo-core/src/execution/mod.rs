@@ -6,6 +6,7 @@
 //! | Module | Purpose |
 //! | ------ | ------- |
 //! | [`chunk`] | Batched rows (DataChunk = multiple columns) |
+//! | [`collation`] | String ordering (byte order or locale-aware) |
 //! | [`vector`] | Single column of values |
 //! | [`selection`] | Bitmap for filtering without copying |
 //! | [`operators`] | Physical operators (scan, filter, join, etc.) |
@@ -18,11 +19,14 @@
 //! operators until it reaches a sink.
 
 pub mod adaptive;
+pub mod cancel;
 pub mod chunk;
+pub mod collation;
 pub mod memory;
 pub mod operators;
 pub mod parallel;
 pub mod pipeline;
+pub mod profile;
 pub mod selection;
 pub mod sink;
 pub mod source;
@@ -36,15 +40,20 @@ pub use adaptive::{
     CardinalityTrackingOperator, CardinalityTrackingSink, CardinalityTrackingWrapper,
     ReoptimizationDecision, SharedAdaptiveContext, evaluate_reoptimization, execute_adaptive,
 };
+pub use cancel::{CancellationToken, Interrupt, InterruptibleOperator};
 pub use chunk::DataChunk;
-pub use memory::{ExecutionMemoryContext, ExecutionMemoryContextBuilder};
+pub use collation::Collation;
+pub use memory::{
+    ExecutionMemoryContext, ExecutionMemoryContextBuilder, SharedMemoryContext, SpillableOperator,
+};
 pub use parallel::{
     CloneableOperatorFactory, MorselScheduler, ParallelPipeline, ParallelPipelineConfig,
     ParallelSource, RangeSource,
 };
 pub use pipeline::{ChunkCollector, ChunkSizeHint, Pipeline, PushOperator, Sink, Source};
+pub use profile::{OperatorProfile, OperatorStats, ProfiledOperator};
 pub use selection::SelectionVector;
-pub use sink::{CollectorSink, CountingSink, LimitingSink, MaterializingSink, NullSink};
+pub use sink::{CollectorSink, CountingSink, LimitingSink, MaterializingSink, NullSink, TopNSink};
 pub use source::{ChunkSource, EmptySource, GeneratorSource, OperatorSource, VectorSource};
 pub use spill::{SpillFile, SpillFileReader, SpillManager};
 pub use vector::ValueVector;
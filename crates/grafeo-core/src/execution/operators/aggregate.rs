@@ -5,9 +5,9 @@
 //! - Various aggregation functions: COUNT, SUM, AVG, MIN, MAX, etc.
 
 use indexmap::IndexMap;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 
-use grafeo_common::types::{LogicalType, Value};
+use grafeo_common::types::{LogicalType, PropertyKey, Value};
 
 /// A wrapper for Value that can be hashed (for DISTINCT tracking).
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -39,9 +39,12 @@ impl From<Value> for HashableValue {
     }
 }
 
-use super::{Operator, OperatorError, OperatorResult};
-use crate::execution::DataChunk;
+use super::{
+    MIN_HASH_TABLE_CAPACITY, Operator, OperatorError, OperatorResult, account_memory,
+    hash_table_capacity, reserve_memory,
+};
 use crate::execution::chunk::DataChunkBuilder;
+use crate::execution::{DataChunk, SharedMemoryContext};
 
 /// Aggregation function types.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -64,6 +67,8 @@ pub enum AggregateFunction {
     Last,
     /// Collect values into a list.
     Collect,
+    /// Collect `[key, value]` pairs into a map.
+    CollectMap,
     /// Sample standard deviation (STDEV).
     StdDev,
     /// Population standard deviation (STDEVP).
@@ -189,6 +194,18 @@ impl AggregateExpr {
         }
     }
 
+    /// Creates a map-collecting expression over a column of `[key, value]`
+    /// pairs.
+    pub fn collect_map(column: usize) -> Self {
+        Self {
+            function: AggregateFunction::CollectMap,
+            column: Some(column),
+            distinct: false,
+            alias: None,
+            percentile: None,
+        }
+    }
+
     /// Creates a STDEV(column) expression (sample standard deviation).
     pub fn stdev(column: usize) -> Self {
         Self {
@@ -285,6 +302,8 @@ enum AggregateState {
     Collect(Vec<Value>),
     /// Collect distinct state (values, seen).
     CollectDistinct(Vec<Value>, HashSet<HashableValue>),
+    /// Collect map state (entries by key).
+    CollectMap(BTreeMap<PropertyKey, Value>),
     /// Sample standard deviation state using Welford's algorithm (count, mean, M2).
     StdDev { count: i64, mean: f64, m2: f64 },
     /// Population standard deviation state using Welford's algorithm (count, mean, M2).
@@ -317,6 +336,7 @@ impl AggregateState {
             (AggregateFunction::Collect, true) => {
                 AggregateState::CollectDistinct(Vec::new(), HashSet::new())
             }
+            (AggregateFunction::CollectMap, _) => AggregateState::CollectMap(BTreeMap::new()),
             // Statistical functions (Welford's algorithm for online computation)
             (AggregateFunction::StdDev, _) => AggregateState::StdDev {
                 count: 0,
@@ -468,6 +488,19 @@ impl AggregateState {
                     }
                 }
             }
+            // Map keys are strings, so other keys are stored by their display
+            // form; a later pair for the same key replaces the earlier one
+            AggregateState::CollectMap(map) => {
+                if let Some(Value::List(pair)) = value {
+                    if let [key, value] = &pair[..] {
+                        let key = match key {
+                            Value::String(s) => PropertyKey::new(s.clone()),
+                            other => PropertyKey::new(other.to_string()),
+                        };
+                        map.insert(key, value.clone());
+                    }
+                }
+            }
             // Statistical functions using Welford's online algorithm
             AggregateState::StdDev { count, mean, m2 }
             | AggregateState::StdDevPop { count, mean, m2 } => {
@@ -518,6 +551,7 @@ impl AggregateState {
             AggregateState::Collect(list) | AggregateState::CollectDistinct(list, _) => {
                 Value::List(list.clone().into())
             }
+            AggregateState::CollectMap(map) => Value::Map(map.clone().into()),
             // Sample standard deviation: sqrt(M2 / (n - 1))
             AggregateState::StdDev { count, m2, .. } => {
                 if *count < 2 {
@@ -605,7 +639,7 @@ fn compare_values(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
         (Value::String(s), Value::Float64(f)) => s.parse::<f64>().ok()?.partial_cmp(f),
         (Value::Int64(i), Value::String(s)) => (*i as f64).partial_cmp(&s.parse::<f64>().ok()?),
         (Value::Float64(f), Value::String(s)) => f.partial_cmp(&s.parse::<f64>().ok()?),
-        _ => None,
+        _ => a.compare_temporal(b),
     }
 }
 
@@ -672,6 +706,10 @@ pub struct HashAggregateOperator {
     output_schema: Vec<LogicalType>,
     /// Ordered map: group key -> aggregate states (IndexMap for deterministic iteration order).
     groups: IndexMap<GroupKey, Vec<AggregateState>>,
+    /// How many times the group table grew during aggregation.
+    group_table_resizes: usize,
+    /// Memory budget the group table is charged against.
+    memory: Option<SharedMemoryContext>,
     /// Whether aggregation is complete.
     aggregation_complete: bool,
     /// Results iterator.
@@ -698,24 +736,75 @@ impl HashAggregateOperator {
             aggregates,
             output_schema,
             groups: IndexMap::new(),
+            group_table_resizes: 0,
+            memory: None,
             aggregation_complete: false,
             results: None,
         }
     }
 
+    /// Sizes the group table for the expected number of groups.
+    ///
+    /// The capacity is clamped by [`hash_table_capacity`], so a bad estimate
+    /// can't over-allocate.
+    #[must_use]
+    pub fn with_estimated_groups(mut self, groups: f64) -> Self {
+        self.groups = IndexMap::with_capacity(hash_table_capacity(groups));
+        self
+    }
+
+    /// Charges the group table's memory to a query's memory context.
+    ///
+    /// Each new group is accounted and table growth is reserved before it
+    /// happens; running out fails with [`OperatorError::MemoryLimitExceeded`].
+    #[must_use]
+    pub fn with_memory_context(mut self, memory: SharedMemoryContext) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Returns how many times the group table had to grow while aggregating.
+    #[must_use]
+    pub fn group_table_resizes(&self) -> usize {
+        self.group_table_resizes
+    }
+
     /// Performs the aggregation.
     fn aggregate(&mut self) -> Result<(), OperatorError> {
+        let slot_size = std::mem::size_of::<(GroupKey, Vec<AggregateState>)>();
+        let states_size = self.aggregates.len() * std::mem::size_of::<AggregateState>();
+
+        // A pre-sized table is allocated up front
+        reserve_memory(self.memory.as_ref(), self.groups.capacity() * slot_size)?;
+
         while let Some(chunk) = self.child.next()? {
             for row in chunk.selected_indices() {
                 let key = GroupKey::from_row(&chunk, row, &self.group_columns);
 
                 // Get or create aggregate states for this group
-                let states = self.groups.entry(key).or_insert_with(|| {
+                let capacity = self.groups.capacity();
+                if self.memory.is_some() && !self.groups.contains_key(&key) {
+                    if self.groups.len() == capacity {
+                        // A new group in a full table doubles it
+                        reserve_memory(
+                            self.memory.as_ref(),
+                            capacity.max(MIN_HASH_TABLE_CAPACITY) * slot_size,
+                        )?;
+                    }
+                    account_memory(self.memory.as_ref(), states_size)?;
+                }
+                let entry = self.groups.entry(key);
+                let group_idx = entry.index();
+                entry.or_insert_with(|| {
                     self.aggregates
                         .iter()
                         .map(|agg| AggregateState::new(agg.function, agg.distinct, agg.percentile))
                         .collect()
                 });
+                if self.groups.capacity() != capacity {
+                    self.group_table_resizes += 1;
+                }
+                let states = &mut self.groups[group_idx];
 
                 // Update each aggregate
                 for (i, agg) in self.aggregates.iter().enumerate() {
@@ -1129,6 +1218,38 @@ mod tests {
         assert_eq!(results[1], (2, 120)); // Group 2: 30 + 40 + 50 = 120
     }
 
+    #[test]
+    fn test_grouped_aggregation_estimated_groups_avoid_resizes() {
+        let mut builder = DataChunkBuilder::new(&[LogicalType::Int64]);
+        for group in 0..5_000i64 {
+            builder.column_mut(0).unwrap().push_int64(group);
+            builder.advance_row();
+        }
+        let chunk = builder.finish();
+
+        let run = |estimate: Option<f64>| {
+            let agg = HashAggregateOperator::new(
+                Box::new(MockOperator::new(vec![chunk.clone()])),
+                vec![0],
+                vec![AggregateExpr::count_star()],
+                vec![LogicalType::Int64, LogicalType::Int64],
+            );
+            let mut agg = match estimate {
+                Some(groups) => agg.with_estimated_groups(groups),
+                None => agg,
+            };
+            let mut groups = 0;
+            while let Some(result) = agg.next().unwrap() {
+                groups += result.row_count();
+            }
+            assert_eq!(groups, 5_000);
+            agg.group_table_resizes()
+        };
+
+        assert!(run(None) > 0);
+        assert_eq!(run(Some(5_000.0)), 0);
+    }
+
     #[test]
     fn test_grouped_count() {
         let mock = MockOperator::new(vec![create_test_chunk()]);
@@ -1457,4 +1578,37 @@ mod tests {
         let stdev = result.column(0).unwrap().get_float64(0).unwrap();
         assert!((stdev - 0.0).abs() < 0.01);
     }
+
+    #[test]
+    fn test_collect_map() {
+        let mut builder = DataChunkBuilder::new(&[LogicalType::Any]);
+        for (key, value) in [
+            (Value::from("a"), Value::Int64(1)),
+            (Value::Int64(2), Value::from("two")),
+            (Value::from("a"), Value::Int64(3)),
+        ] {
+            builder
+                .column_mut(0)
+                .unwrap()
+                .push_value(Value::List(vec![key, value].into()));
+            builder.advance_row();
+        }
+        let mock = MockOperator::new(vec![builder.finish()]);
+
+        let mut agg = SimpleAggregateOperator::new(
+            Box::new(mock),
+            vec![AggregateExpr::collect_map(0)],
+            vec![LogicalType::Any],
+        );
+
+        let result = agg.next().unwrap().unwrap();
+        assert_eq!(result.row_count(), 1);
+        let Some(Value::Map(map)) = result.column(0).unwrap().get_value(0) else {
+            panic!("Expected a map");
+        };
+        // Non-string keys are stringified and the last pair for a key wins
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&PropertyKey::new("a")), Some(&Value::Int64(3)));
+        assert_eq!(map.get(&PropertyKey::new("2")), Some(&Value::from("two")));
+    }
 }
@@ -4,7 +4,7 @@ use super::{Operator, OperatorError, OperatorResult};
 use crate::execution::DataChunk;
 use crate::graph::Direction;
 use crate::graph::lpg::LpgStore;
-use grafeo_common::types::{EdgeId, EpochId, LogicalType, NodeId, TxId};
+use grafeo_common::types::{EdgeId, EpochId, LogicalType, NodeId, Timestamp, TxId};
 use std::sync::Arc;
 
 /// An expand operator that traverses edges from source nodes.
@@ -38,6 +38,8 @@ pub struct ExpandOperator {
     tx_id: Option<TxId>,
     /// Epoch for version visibility.
     viewing_epoch: Option<EpochId>,
+    /// Only traverse edges valid at this time, if set.
+    as_of: Option<Timestamp>,
 }
 
 impl ExpandOperator {
@@ -63,6 +65,7 @@ impl ExpandOperator {
             exhausted: false,
             tx_id: None,
             viewing_epoch: None,
+            as_of: None,
         }
     }
 
@@ -81,6 +84,14 @@ impl ExpandOperator {
         self
     }
 
+    /// Sets the time at which edges must be valid to be traversed.
+    ///
+    /// Edges whose validity interval doesn't contain `at` are skipped.
+    pub fn with_as_of(mut self, at: Timestamp) -> Self {
+        self.as_of = Some(at);
+        self
+    }
+
     /// Loads the next input chunk.
     fn load_next_input(&mut self) -> Result<bool, OperatorError> {
         match self.input.next() {
@@ -132,7 +143,7 @@ impl ExpandOperator {
                 // Filter by edge type if specified
                 let type_matches = if let Some(ref filter_type) = self.edge_type {
                     if let Some(edge_type) = self.store.edge_type(*edge_id) {
-                        edge_type.as_ref() == filter_type.as_str()
+                        self.store.label_matches(&edge_type, filter_type)
                     } else {
                         false
                     }
@@ -144,6 +155,13 @@ impl ExpandOperator {
                     return false;
                 }
 
+                // Skip edges that aren't valid at the as-of time
+                if let Some(at) = self.as_of {
+                    if !self.store.is_edge_valid_at(*edge_id, at) {
+                        return false;
+                    }
+                }
+
                 // Filter by visibility if we have tx context
                 if let Some(epoch) = epoch {
                     // Check if edge and target node are visible
@@ -407,4 +425,35 @@ mod tests {
         let result = expand.next().unwrap();
         assert!(result.is_none());
     }
+
+    #[test]
+    fn test_expand_as_of_skips_edges_not_valid_then() {
+        let store = Arc::new(LpgStore::new());
+
+        let alice = store.create_node(&["Person"]);
+        let bob = store.create_node(&["Employer"]);
+        let charlie = store.create_node(&["Employer"]);
+
+        let old_job = store.create_edge(alice, bob, "WORKS_AT");
+        let new_job = store.create_edge(alice, charlie, "WORKS_AT");
+        store.set_edge_validity(old_job, None, Some(Timestamp::from_secs(100)));
+        store.set_edge_validity(new_job, Some(Timestamp::from_secs(100)), None);
+
+        let targets_at = |secs: i64| {
+            let scan = Box::new(ScanOperator::with_label(Arc::clone(&store), "Person"));
+            let mut expand =
+                ExpandOperator::new(Arc::clone(&store), scan, 0, Direction::Outgoing, None)
+                    .with_as_of(Timestamp::from_secs(secs));
+            let mut targets = Vec::new();
+            while let Ok(Some(chunk)) = expand.next() {
+                for i in 0..chunk.row_count() {
+                    targets.push(chunk.column(2).unwrap().get_node_id(i).unwrap());
+                }
+            }
+            targets
+        };
+
+        assert_eq!(targets_at(50), vec![bob]);
+        assert_eq!(targets_at(150), vec![charlie]);
+    }
 }
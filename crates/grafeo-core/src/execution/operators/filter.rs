@@ -1,18 +1,30 @@
 //! Filter operator for applying predicates.
 
-use super::{Operator, OperatorResult};
-use crate::execution::{DataChunk, SelectionVector};
+use super::{Operator, OperatorError, OperatorResult};
+use crate::execution::{Collation, DataChunk, SelectionVector};
 use crate::graph::Direction;
-use crate::graph::lpg::LpgStore;
-use grafeo_common::types::{PropertyKey, Value};
+use crate::graph::lpg::{Edge, LpgStore, Node};
+use crate::index::spatial::{BoundingBox, DistanceMetric};
+use grafeo_common::types::{
+    EdgeId, EpochId, NodeId, PropertyKey, Timestamp, TxId, Value, temporal,
+};
+use grafeo_common::utils::error::Result;
 use regex::Regex;
 use std::collections::{BTreeMap, HashMap};
-use std::sync::Arc;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
 
 /// A predicate for filtering rows.
 pub trait Predicate: Send + Sync {
     /// Evaluates the predicate for a row.
     fn evaluate(&self, chunk: &DataChunk, row: usize) -> bool;
+
+    /// Returns the first error hit while evaluating, if any.
+    ///
+    /// The filter checks this after each chunk and fails the query with it.
+    fn error(&self) -> Option<&str> {
+        None
+    }
 }
 
 /// A comparison operator.
@@ -94,7 +106,43 @@ impl Predicate for ComparisonPredicate {
                 CompareOp::Ne => a != b,
                 _ => false, // Ordering on booleans doesn't make sense
             },
-            _ => false, // Type mismatch
+            (a, b) => match a.compare_temporal(b) {
+                Some(ordering) => match self.op {
+                    CompareOp::Eq => ordering.is_eq(),
+                    CompareOp::Ne => ordering.is_ne(),
+                    CompareOp::Lt => ordering.is_lt(),
+                    CompareOp::Le => ordering.is_le(),
+                    CompareOp::Gt => ordering.is_gt(),
+                    CompareOp::Ge => ordering.is_ge(),
+                },
+                None => false, // Type mismatch
+            },
+        }
+    }
+}
+
+/// The value a temporal constructor such as `date()` builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemporalKind {
+    Date,
+    Timestamp,
+    Duration,
+}
+
+impl TemporalKind {
+    fn function_name(self) -> &'static str {
+        match self {
+            Self::Date => "date",
+            Self::Timestamp => "datetime",
+            Self::Duration => "duration",
+        }
+    }
+
+    fn example(self) -> &'static str {
+        match self {
+            Self::Date => "date like '2024-01-31'",
+            Self::Timestamp => "date-time like '2024-01-31T12:00:00Z'",
+            Self::Duration => "duration like 'P1DT2H'",
         }
     }
 }
@@ -110,6 +158,14 @@ pub struct ExpressionPredicate {
     variable_columns: HashMap<String, usize>,
     /// The graph store for property lookups.
     store: Arc<LpgStore>,
+    /// Collation for string range comparisons (`<`, `<=`, `>`, `>=`).
+    collation: Collation,
+    /// The first error met while evaluating, such as indexing into a number.
+    error: OnceLock<String>,
+    /// Epoch to read nodes and edges at, the latest if unset.
+    viewing_epoch: Option<EpochId>,
+    /// Transaction whose own writes are visible.
+    tx_id: Option<TxId>,
 }
 
 /// A filter expression that can be evaluated.
@@ -184,6 +240,23 @@ pub enum FilterExpression {
     Labels(String),
     /// Edge type access.
     Type(String),
+    /// Total of a numeric edge property along a path, where the path
+    /// variable holds a list of edge ids.
+    PathCost {
+        /// The path variable.
+        variable: String,
+        /// The edge property to add up.
+        property: String,
+    },
+    /// The properties of a node or edge as a map.
+    PropertyMap {
+        /// The node or edge variable.
+        variable: String,
+        /// Properties to include, or all of them if empty.
+        keys: Vec<String>,
+        /// Whether to add `id` and `label` entries.
+        include_id_label: bool,
+    },
     /// List comprehension: [x IN list WHERE predicate | expression]
     ListComprehension {
         /// Variable name for each element.
@@ -210,6 +283,131 @@ pub enum FilterExpression {
         /// Maximum number of hops (for variable-length patterns).
         max_hops: Option<u32>,
     },
+    /// Per-row count of single-hop matches, e.g. `size((p)-->())`.
+    CountSubquery {
+        /// The start node variable from outer query.
+        start_var: String,
+        /// Direction of edge traversal.
+        direction: Direction,
+        /// Optional edge type filter.
+        edge_type: Option<String>,
+        /// Optional end node labels filter; the end node needs all of them.
+        end_labels: Option<Vec<String>>,
+    },
+    /// Call to a user-defined scalar function.
+    UserFunction {
+        /// The function to call.
+        function: ScalarUdf,
+        /// Arguments.
+        args: Vec<FilterExpression>,
+    },
+}
+
+/// Function names the engine implements itself.
+///
+/// A user-defined function can't take one of these names: queries would
+/// keep calling the built-in.
+pub const BUILTIN_FUNCTIONS: &[&str] = &[
+    "id",
+    "labels",
+    "type",
+    "degree",
+    "size",
+    "length",
+    "coalesce",
+    "exists",
+    "property_exists",
+    "tostring",
+    "tointeger",
+    "toint",
+    "tofloat",
+    "toboolean",
+    "tobool",
+    "haslabel",
+    "head",
+    "tail",
+    "last",
+    "reverse",
+    "count",
+    "sum",
+    "avg",
+    "min",
+    "max",
+    "collect",
+    "point",
+    "distance",
+    "point.withindistance",
+    "point.withinbbox",
+    "date",
+    "datetime",
+    "timestamp",
+    "duration",
+];
+
+/// Returns whether `name` is a built-in function, ignoring case.
+#[must_use]
+pub fn is_builtin_function(name: &str) -> bool {
+    BUILTIN_FUNCTIONS
+        .iter()
+        .any(|builtin| builtin.eq_ignore_ascii_case(name))
+}
+
+/// A user-defined scalar function.
+///
+/// UDFs must be pure: the same arguments always produce the same result, and
+/// a call has no side effects. The planner relies on this to evaluate calls
+/// with constant arguments once, while planning, instead of once per row.
+#[derive(Clone)]
+pub struct ScalarUdf {
+    name: String,
+    arity: usize,
+    function: Arc<dyn Fn(&[Value]) -> Result<Value> + Send + Sync>,
+}
+
+impl ScalarUdf {
+    /// Creates a function taking exactly `arity` arguments.
+    pub fn new(
+        name: impl Into<String>,
+        arity: usize,
+        function: Arc<dyn Fn(&[Value]) -> Result<Value> + Send + Sync>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            arity,
+            function,
+        }
+    }
+
+    /// Returns the function's name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns how many arguments the function takes.
+    #[must_use]
+    pub fn arity(&self) -> usize {
+        self.arity
+    }
+
+    /// Calls the function.
+    ///
+    /// # Errors
+    ///
+    /// Returns whatever error the function reports, typically for arguments
+    /// of the wrong type.
+    pub fn call(&self, args: &[Value]) -> Result<Value> {
+        (self.function)(args)
+    }
+}
+
+impl fmt::Debug for ScalarUdf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScalarUdf")
+            .field("name", &self.name)
+            .field("arity", &self.arity)
+            .finish_non_exhaustive()
+    }
 }
 
 /// Binary operators for filter expressions.
@@ -281,15 +479,77 @@ impl ExpressionPredicate {
             expression,
             variable_columns,
             store,
+            collation: Collation::Binary,
+            error: OnceLock::new(),
+            viewing_epoch: None,
+            tx_id: None,
         }
     }
 
+    /// Sets the collation used for string range comparisons.
+    #[must_use]
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Sets the transaction context property lookups read in.
+    #[must_use]
+    pub fn with_tx_context(mut self, epoch: EpochId, tx_id: Option<TxId>) -> Self {
+        self.viewing_epoch = Some(epoch);
+        self.tx_id = tx_id;
+        self
+    }
+
+    /// Reads a node as this predicate's reader sees it.
+    fn node(&self, id: NodeId) -> Option<Node> {
+        let epoch = self
+            .viewing_epoch
+            .unwrap_or_else(|| self.store.current_epoch());
+        self.store
+            .get_node_versioned(id, epoch, self.tx_id.unwrap_or(TxId::SYSTEM))
+    }
+
+    /// Reads an edge as this predicate's reader sees it.
+    fn edge(&self, id: EdgeId) -> Option<Edge> {
+        let epoch = self
+            .viewing_epoch
+            .unwrap_or_else(|| self.store.current_epoch());
+        self.store
+            .get_edge_versioned(id, epoch, self.tx_id.unwrap_or(TxId::SYSTEM))
+    }
+
+    /// Reads an edge's type as this predicate's reader sees it, or `None` if
+    /// the edge isn't visible to it.
+    fn edge_type(&self, id: EdgeId) -> Option<Arc<str>> {
+        let epoch = self
+            .viewing_epoch
+            .unwrap_or_else(|| self.store.current_epoch());
+        self.store
+            .edge_type_versioned(id, epoch, self.tx_id.unwrap_or(TxId::SYSTEM))
+    }
+
     /// Evaluates the expression for a specific row in a chunk, returning the result value.
     /// This is useful for evaluating expressions in contexts like RETURN clauses.
     pub fn eval_at(&self, chunk: &DataChunk, row: usize) -> Option<Value> {
         self.eval_expr(&self.expression, chunk, row)
     }
 
+    /// Returns the first error met while evaluating, if any.
+    ///
+    /// Evaluation itself never fails - an erroneous expression evaluates to
+    /// null - so callers that must reject bad input check here afterwards.
+    #[must_use]
+    pub fn error(&self) -> Option<&str> {
+        self.error.get().map(String::as_str)
+    }
+
+    /// Records an evaluation error, keeping the first one.
+    fn fail(&self, message: String) -> Option<Value> {
+        let _ = self.error.set(message);
+        None
+    }
+
     /// Evaluates the expression for a row, returning the result value.
     fn eval(&self, chunk: &DataChunk, row: usize) -> Option<Value> {
         self.eval_expr(&self.expression, chunk, row)
@@ -307,16 +567,20 @@ impl ExpressionPredicate {
                 let col = chunk.column(col_idx)?;
                 // Try as node first
                 if let Some(node_id) = col.get_node_id(row) {
-                    if let Some(node) = self.store.get_node(node_id) {
+                    if let Some(node) = self.node(node_id) {
                         return node.get_property(property).cloned();
                     }
                 }
                 // Try as edge if node lookup failed
                 if let Some(edge_id) = col.get_edge_id(row) {
-                    if let Some(edge) = self.store.get_edge(edge_id) {
+                    if let Some(edge) = self.edge(edge_id) {
                         return edge.get_property(property).cloned();
                     }
                 }
+                // A map value, as in `UNWIND $rows AS row RETURN row.name`
+                if let Some(Value::Map(map)) = col.get_value(row) {
+                    return map.get(&PropertyKey::new(property.as_str())).cloned();
+                }
                 None
             }
             FilterExpression::Binary { left, op, right } => {
@@ -336,6 +600,14 @@ impl ExpressionPredicate {
             FilterExpression::FunctionCall { name, args } => {
                 self.eval_function(name, args, chunk, row)
             }
+            FilterExpression::UserFunction { function, args } => {
+                // A failing call yields NULL, like a built-in given the wrong type
+                let values = args
+                    .iter()
+                    .map(|arg| self.eval_expr(arg, chunk, row).unwrap_or(Value::Null))
+                    .collect::<Vec<_>>();
+                function.call(&values).ok()
+            }
             FilterExpression::List(items) => {
                 let values: Vec<Value> = items
                     .iter()
@@ -357,32 +629,42 @@ impl ExpressionPredicate {
                 let base_val = self.eval_expr(base, chunk, row)?;
                 let index_val = self.eval_expr(index, chunk, row)?;
                 match (&base_val, &index_val) {
+                    (Value::Null, _) | (_, Value::Null) => None,
                     (Value::List(items), Value::Int64(i)) => {
+                        // Negative indexing from end; out of range is null
                         let idx = if *i < 0 {
-                            // Negative indexing from end
-                            let len = items.len() as i64;
-                            (len + i) as usize
+                            i.checked_add(items.len() as i64)?
                         } else {
-                            *i as usize
+                            *i
                         };
-                        items.get(idx).cloned()
+                        items.get(usize::try_from(idx).ok()?).cloned()
                     }
                     (Value::String(s), Value::Int64(i)) => {
                         let idx = if *i < 0 {
-                            let len = s.len() as i64;
-                            (len + i) as usize
+                            i.checked_add(s.chars().count() as i64)?
                         } else {
-                            *i as usize
+                            *i
                         };
                         s.chars()
-                            .nth(idx)
+                            .nth(usize::try_from(idx).ok()?)
                             .map(|c| Value::String(c.to_string().into()))
                     }
                     (Value::Map(m), Value::String(key)) => {
                         let prop_key = PropertyKey::new(key.as_ref());
                         m.get(&prop_key).cloned()
                     }
-                    _ => None,
+                    (Value::List(_) | Value::String(_), other) => self.fail(format!(
+                        "List index must be an integer, got {}",
+                        other.type_name()
+                    )),
+                    (Value::Map(_), other) => self.fail(format!(
+                        "Map key must be a string, got {}",
+                        other.type_name()
+                    )),
+                    (other, _) => self.fail(format!(
+                        "Cannot index into a value of type {}",
+                        other.type_name()
+                    )),
                 }
             }
             FilterExpression::SliceAccess { base, start, end } => {
@@ -464,11 +746,45 @@ impl ExpressionPredicate {
                     None
                 }
             }
+            FilterExpression::PropertyMap {
+                variable,
+                keys,
+                include_id_label,
+            } => {
+                let col_idx = *self.variable_columns.get(variable)?;
+                let col = chunk.column(col_idx)?;
+                let (id, label, properties) = if let Some(node_id) = col.get_node_id(row) {
+                    let node = self.node(node_id)?;
+                    let label = node.labels.first().map(|l| Value::String(l.clone()));
+                    (node_id.0, label, node.properties)
+                } else {
+                    let edge_id = col.get_edge_id(row)?;
+                    let edge = self.edge(edge_id)?;
+                    (
+                        edge_id.0,
+                        Some(Value::String(edge.edge_type)),
+                        edge.properties,
+                    )
+                };
+                let mut map: BTreeMap<PropertyKey, Value> = if keys.is_empty() {
+                    properties
+                } else {
+                    properties
+                        .into_iter()
+                        .filter(|(key, _)| keys.iter().any(|k| k == key.as_str()))
+                        .collect()
+                };
+                if *include_id_label {
+                    map.insert(PropertyKey::new("id"), Value::Int64(id as i64));
+                    map.insert(PropertyKey::new("label"), label.unwrap_or(Value::Null));
+                }
+                Some(Value::Map(Arc::new(map)))
+            }
             FilterExpression::Labels(variable) => {
                 let col_idx = *self.variable_columns.get(variable)?;
                 let col = chunk.column(col_idx)?;
                 let node_id = col.get_node_id(row)?;
-                let node = self.store.get_node(node_id)?;
+                let node = self.node(node_id)?;
                 let labels: Vec<Value> = node
                     .labels
                     .iter()
@@ -480,9 +796,35 @@ impl ExpressionPredicate {
                 let col_idx = *self.variable_columns.get(variable)?;
                 let col = chunk.column(col_idx)?;
                 let edge_id = col.get_edge_id(row)?;
-                let edge = self.store.get_edge(edge_id)?;
+                let edge = self.edge(edge_id)?;
                 Some(Value::String(edge.edge_type.clone()))
             }
+            FilterExpression::PathCost { variable, property } => {
+                let col_idx = *self.variable_columns.get(variable)?;
+                let Value::List(edges) = chunk.column(col_idx)?.get_value(row)? else {
+                    return None;
+                };
+                // Integer weights stay integers; any float weight makes the total a float
+                let mut int_total: i64 = 0;
+                let mut float_total: Option<f64> = None;
+                for edge in edges.iter() {
+                    let Value::Int64(raw) = edge else {
+                        return None;
+                    };
+                    let edge = self.edge(EdgeId::new(*raw as u64))?;
+                    match edge.get_property(property)? {
+                        Value::Int64(weight) => int_total = int_total.checked_add(*weight)?,
+                        Value::Float64(weight) => {
+                            *float_total.get_or_insert(0.0) += weight;
+                        }
+                        _ => return None,
+                    }
+                }
+                Some(match float_total {
+                    Some(total) => Value::Float64(total + int_total as f64),
+                    None => Value::Int64(int_total),
+                })
+            }
             FilterExpression::ListComprehension {
                 variable,
                 list_expr,
@@ -545,7 +887,7 @@ impl ExpressionPredicate {
                             // Check edge type if specified
                             if let Some(required_type) = edge_type {
                                 if let Some(actual_type) = self.store.edge_type(edge_id) {
-                                    actual_type.as_ref() == required_type.as_str()
+                                    self.store.label_matches(&actual_type, required_type)
                                 } else {
                                     false
                                 }
@@ -556,6 +898,39 @@ impl ExpressionPredicate {
 
                 Some(Value::Bool(exists))
             }
+            FilterExpression::CountSubquery {
+                start_var,
+                direction,
+                edge_type,
+                end_labels,
+            } => {
+                let col_idx = *self.variable_columns.get(start_var)?;
+                let col = chunk.column(col_idx)?;
+                let start_node_id = col.get_node_id(row)?;
+
+                let count = self
+                    .store
+                    .edges_from(start_node_id, *direction)
+                    .filter(|(target, edge_id)| {
+                        let Some(actual) = self.edge_type(*edge_id) else {
+                            return false;
+                        };
+                        let type_matches = edge_type
+                            .as_ref()
+                            .map_or(true, |required| self.store.label_matches(&actual, required));
+                        let labels_match = end_labels.as_ref().map_or(true, |labels| {
+                            self.node(*target).is_some_and(|node| {
+                                labels
+                                    .iter()
+                                    .all(|label| self.store.node_has_label(&node, label))
+                            })
+                        });
+                        type_matches && labels_match
+                    })
+                    .count();
+
+                Some(Value::Int64(count as i64))
+            }
         }
     }
 
@@ -762,7 +1137,7 @@ impl ExpressionPredicate {
                     let col_idx = *self.variable_columns.get(var)?;
                     let col = chunk.column(col_idx)?;
                     let node_id = col.get_node_id(row)?;
-                    let node = self.store.get_node(node_id)?;
+                    let node = self.node(node_id)?;
                     let labels: Vec<Value> = node
                         .labels
                         .iter()
@@ -780,11 +1155,44 @@ impl ExpressionPredicate {
                     let col_idx = *self.variable_columns.get(var)?;
                     let col = chunk.column(col_idx)?;
                     let edge_id = col.get_edge_id(row)?;
-                    let edge = self.store.get_edge(edge_id)?;
+                    let edge = self.edge(edge_id)?;
                     return Some(Value::String(edge.edge_type.clone()));
                 }
                 None
             }
+            "degree" => {
+                // degree(n [, direction [, edge type]]), read from adjacency
+                // counts rather than by expanding the node
+                if args.is_empty() || args.len() > 3 {
+                    return None;
+                }
+                let FilterExpression::Variable(var) = &args[0] else {
+                    return None;
+                };
+                let col_idx = *self.variable_columns.get(var)?;
+                let node_id = chunk.column(col_idx)?.get_node_id(row)?;
+                let direction = match args.get(1) {
+                    Some(arg) => match self.eval_expr(arg, chunk, row)? {
+                        Value::String(s) => match s.to_uppercase().as_str() {
+                            "OUT" | "OUTGOING" => Direction::Outgoing,
+                            "IN" | "INCOMING" => Direction::Incoming,
+                            "BOTH" => Direction::Both,
+                            _ => return None,
+                        },
+                        _ => return None,
+                    },
+                    None => Direction::Both,
+                };
+                let edge_type = match args.get(2) {
+                    Some(arg) => match self.eval_expr(arg, chunk, row)? {
+                        Value::String(s) => Some(s),
+                        _ => return None,
+                    },
+                    None => None,
+                };
+                let degree = self.store.degree(node_id, direction, edge_type.as_deref());
+                Some(Value::Int64(degree as i64))
+            }
             "size" | "length" => {
                 if args.len() != 1 {
                     return None;
@@ -815,6 +1223,26 @@ impl ExpressionPredicate {
                     val.is_some() && !matches!(val, Some(Value::Null)),
                 ))
             }
+            "property_exists" => {
+                // Unlike exists(), an explicitly null property still counts
+                if args.len() != 2 {
+                    return None;
+                }
+                let FilterExpression::Variable(var) = &args[0] else {
+                    return None;
+                };
+                let key = match self.eval_expr(&args[1], chunk, row)? {
+                    Value::String(s) => s,
+                    _ => return None,
+                };
+                let col = chunk.column(*self.variable_columns.get(var)?)?;
+                if let Some(node_id) = col.get_node_id(row) {
+                    let node = self.node(node_id)?;
+                    return Some(Value::Bool(node.has_property(&key)));
+                }
+                let edge = self.edge(col.get_edge_id(row)?)?;
+                Some(Value::Bool(edge.has_property(&key)))
+            }
             "tostring" => {
                 if args.len() != 1 {
                     return None;
@@ -880,8 +1308,8 @@ impl ExpressionPredicate {
                     _ => return None,
                 };
                 // Check if the node has this label
-                let node = self.store.get_node(node_id)?;
-                let has_label = node.labels.iter().any(|l| l.as_ref() == label.as_ref());
+                let node = self.node(node_id)?;
+                let has_label = self.store.node_has_label(&node, &label);
                 Some(Value::Bool(has_label))
             }
             "head" => {
@@ -941,10 +1369,295 @@ impl ExpressionPredicate {
                     _ => None,
                 }
             }
+            "point" => self.eval_point(args, chunk, row),
+            "date" => self.eval_temporal(TemporalKind::Date, args, chunk, row),
+            "datetime" | "timestamp" => {
+                self.eval_temporal(TemporalKind::Timestamp, args, chunk, row)
+            }
+            "duration" => self.eval_temporal(TemporalKind::Duration, args, chunk, row),
+            "distance" => {
+                // distance(a, b[, metric]) - the distance between two points
+                if !(2..=3).contains(&args.len()) {
+                    return self.fail(format!(
+                        "distance() takes 2 or 3 arguments, got {}",
+                        args.len()
+                    ));
+                }
+                let (points, srid) = self.eval_points("distance", &args[..2], chunk, row)?;
+                let metric = self.eval_metric("distance", args.get(2), srid, chunk, row)?;
+                Some(Value::Float64(metric.distance(points[0], points[1])))
+            }
+            "point.withindistance" => {
+                // point.withinDistance(p, center, radius[, metric])
+                if !(3..=4).contains(&args.len()) {
+                    return self.fail(format!(
+                        "point.withinDistance() takes 3 or 4 arguments, got {}",
+                        args.len()
+                    ));
+                }
+                let (points, srid) =
+                    self.eval_points("point.withinDistance", &args[..2], chunk, row)?;
+                let radius = match self.eval_expr(&args[2], chunk, row)? {
+                    Value::Null => return Some(Value::Null),
+                    Value::Int64(i) => i as f64,
+                    Value::Float64(f) => f,
+                    other => {
+                        return self.fail(format!(
+                            "point.withinDistance() radius must be a number, got {}",
+                            other.type_name()
+                        ));
+                    }
+                };
+                let metric =
+                    self.eval_metric("point.withinDistance", args.get(3), srid, chunk, row)?;
+                Some(Value::Bool(metric.distance(points[0], points[1]) <= radius))
+            }
+            "point.withinbbox" => {
+                // point.withinBBox(p, lowerLeft, upperRight)
+                if args.len() != 3 {
+                    return self.fail(format!(
+                        "point.withinBBox() takes 3 arguments, got {}",
+                        args.len()
+                    ));
+                }
+                let (points, _) = self.eval_points("point.withinBBox", args, chunk, row)?;
+                let (lower, upper) = (points[1], points[2]);
+                let area = BoundingBox::new(lower.0, lower.1, upper.0, upper.1);
+                Some(Value::Bool(area.contains(points[0].0, points[0].1)))
+            }
             _ => None, // Unknown function
         }
     }
 
+    /// Builds a point from `point({x, y[, srid]})`,
+    /// `point({longitude, latitude[, srid]})`, or `point(x, y[, srid])`.
+    ///
+    /// Map points without an SRID are Cartesian for x/y and WGS 84 for
+    /// longitude/latitude. Null coordinates give null.
+    fn eval_point(
+        &self,
+        args: &[FilterExpression],
+        chunk: &DataChunk,
+        row: usize,
+    ) -> Option<Value> {
+        let values = args
+            .iter()
+            .map(|arg| self.eval_expr(arg, chunk, row))
+            .collect::<Option<Vec<_>>>()?;
+        let (x, y, srid) = match values.as_slice() {
+            [Value::Map(map)] => {
+                let get = |key: &str| map.get(&PropertyKey::new(key));
+                let (x, y, default_srid) =
+                    match (get("x"), get("y"), get("longitude"), get("latitude")) {
+                        (Some(x), Some(y), None, None) => (x, y, Value::CARTESIAN_SRID),
+                        (None, None, Some(lon), Some(lat)) => (lon, lat, Value::WGS84_SRID),
+                        _ => {
+                            return self.fail(
+                                "point() needs either x and y, or longitude and latitude"
+                                    .to_string(),
+                            );
+                        }
+                    };
+                let srid = get("srid")
+                    .cloned()
+                    .unwrap_or(Value::Int64(i64::from(default_srid)));
+                (x.clone(), y.clone(), srid)
+            }
+            [x, y] => (
+                x.clone(),
+                y.clone(),
+                Value::Int64(i64::from(Value::CARTESIAN_SRID)),
+            ),
+            [x, y, srid] => (x.clone(), y.clone(), srid.clone()),
+            _ => {
+                return self.fail(format!(
+                    "point() takes a map or 2 to 3 arguments, got {}",
+                    args.len()
+                ));
+            }
+        };
+        if x.is_null() || y.is_null() || srid.is_null() {
+            return Some(Value::Null);
+        }
+        let coordinate = |value: &Value| match value {
+            Value::Int64(i) => Some(*i as f64),
+            Value::Float64(f) => Some(*f),
+            _ => None,
+        };
+        let srid = srid.as_int64().and_then(|srid| u32::try_from(srid).ok());
+        match (coordinate(&x), coordinate(&y), srid) {
+            (Some(x), Some(y), Some(srid)) => Some(Value::point(x, y, srid)),
+            _ => self.fail(
+                "point() coordinates must be numbers and its srid a non-negative integer"
+                    .to_string(),
+            ),
+        }
+    }
+
+    /// Builds a date, timestamp, or duration from an ISO 8601 string or
+    /// another temporal value.
+    ///
+    /// With no arguments, `date()` and `datetime()` return the current date
+    /// and time. `duration()` also takes a map of `weeks`, `days`, `hours`,
+    /// `minutes`, `seconds`, `milliseconds`, and `microseconds`.
+    fn eval_temporal(
+        &self,
+        kind: TemporalKind,
+        args: &[FilterExpression],
+        chunk: &DataChunk,
+        row: usize,
+    ) -> Option<Value> {
+        let name = kind.function_name();
+        let value = match args {
+            [] if kind != TemporalKind::Duration => Value::Timestamp(Timestamp::now()),
+            [arg] => self.eval_expr(arg, chunk, row)?,
+            _ => {
+                return self.fail(format!(
+                    "{name}() takes {} argument, got {}",
+                    if kind == TemporalKind::Duration {
+                        "1"
+                    } else {
+                        "at most 1"
+                    },
+                    args.len()
+                ));
+            }
+        };
+        let parsed = match (kind, &value) {
+            (_, Value::Null) => return Some(Value::Null),
+            (TemporalKind::Date, Value::String(text)) => {
+                temporal::parse_date(text).map(Value::Date)
+            }
+            (TemporalKind::Date, Value::Date(_)) => Some(value.clone()),
+            (TemporalKind::Date, Value::Timestamp(t)) => {
+                let days = t.as_micros().div_euclid(temporal::MICROS_PER_DAY);
+                i32::try_from(days).ok().map(Value::Date)
+            }
+            (TemporalKind::Timestamp, Value::String(text)) => {
+                temporal::parse_timestamp(text).map(Value::Timestamp)
+            }
+            (TemporalKind::Timestamp, Value::Timestamp(_) | Value::Date(_)) => value
+                .as_epoch_micros()
+                .map(|micros| Value::Timestamp(Timestamp::from_micros(micros))),
+            (TemporalKind::Duration, Value::String(text)) => {
+                temporal::parse_duration(text).map(Value::Duration)
+            }
+            (TemporalKind::Duration, Value::Duration(_)) => Some(value.clone()),
+            (TemporalKind::Duration, Value::Map(parts)) => {
+                return self.eval_duration_map(parts);
+            }
+            _ => {
+                return self.fail(format!(
+                    "{name}() can't convert a {} value",
+                    value.type_name()
+                ));
+            }
+        };
+        if parsed.is_none() {
+            return self.fail(format!(
+                "{name}() expects an ISO 8601 {}, got {value}",
+                kind.example()
+            ));
+        }
+        parsed
+    }
+
+    /// Sums a `duration({days: 1, hours: 2})` map into a duration.
+    fn eval_duration_map(&self, parts: &BTreeMap<PropertyKey, Value>) -> Option<Value> {
+        const UNITS: [(&str, f64); 7] = [
+            ("weeks", 7.0 * 86_400_000_000.0),
+            ("days", 86_400_000_000.0),
+            ("hours", 3_600_000_000.0),
+            ("minutes", 60_000_000.0),
+            ("seconds", 1_000_000.0),
+            ("milliseconds", 1_000.0),
+            ("microseconds", 1.0),
+        ];
+        let mut micros = 0.0;
+        for (key, value) in parts {
+            let Some(&(_, scale)) = UNITS.iter().find(|(unit, _)| *unit == key.as_str()) else {
+                return self.fail(format!("duration() has no unit '{key}'"));
+            };
+            let amount = match value {
+                Value::Int64(i) => *i as f64,
+                Value::Float64(f) => *f,
+                other => {
+                    return self.fail(format!(
+                        "duration() {key} must be a number, got {}",
+                        other.type_name()
+                    ));
+                }
+            };
+            micros += amount * scale;
+        }
+        Some(Value::Duration(micros.round() as i64))
+    }
+
+    /// Evaluates `args` as points for `function`, returning their
+    /// coordinates and shared SRID.
+    ///
+    /// Returns None if any is null, or on an error (a non-point, or points
+    /// in different SRIDs), which is recorded.
+    fn eval_points(
+        &self,
+        function: &str,
+        args: &[FilterExpression],
+        chunk: &DataChunk,
+        row: usize,
+    ) -> Option<(Vec<(f64, f64)>, u32)> {
+        let mut points = Vec::with_capacity(args.len());
+        let mut shared_srid = None;
+        for arg in args {
+            match self.eval_expr(arg, chunk, row)? {
+                Value::Null => return None,
+                Value::Point { x, y, srid } => {
+                    if shared_srid.is_some_and(|shared| shared != srid) {
+                        self.fail(format!(
+                            "{function}() can't mix points with SRID {} and {srid}",
+                            shared_srid.unwrap_or_default()
+                        ));
+                        return None;
+                    }
+                    shared_srid = Some(srid);
+                    points.push((x, y));
+                }
+                other => {
+                    self.fail(format!(
+                        "{function}() expects points, got {}",
+                        other.type_name()
+                    ));
+                    return None;
+                }
+            }
+        }
+        Some((points, shared_srid?))
+    }
+
+    /// Evaluates the optional metric argument of `function`, defaulting to
+    /// the natural metric for `srid`.
+    fn eval_metric(
+        &self,
+        function: &str,
+        arg: Option<&FilterExpression>,
+        srid: u32,
+        chunk: &DataChunk,
+        row: usize,
+    ) -> Option<DistanceMetric> {
+        let Some(arg) = arg else {
+            return Some(DistanceMetric::for_srid(srid));
+        };
+        let metric = match self.eval_expr(arg, chunk, row)? {
+            Value::String(name) => DistanceMetric::from_name(&name),
+            _ => None,
+        };
+        if metric.is_none() {
+            self.fail(format!(
+                "{function}() metric must be 'euclidean' or 'haversine'"
+            ));
+        }
+        metric
+    }
+
     fn eval_case(
         &self,
         operand: Option<&FilterExpression>,
@@ -1009,7 +1722,9 @@ impl ExpressionPredicate {
             (Value::Int64(a), Value::Float64(b)) | (Value::Float64(b), Value::Int64(a)) => {
                 (*a as f64 - b).abs() < f64::EPSILON
             }
-            _ => false,
+            _ => left
+                .compare_temporal(right)
+                .is_some_and(std::cmp::Ordering::is_eq),
         }
     }
 
@@ -1025,7 +1740,7 @@ impl ExpressionPredicate {
                     Some(0)
                 }
             }
-            (Value::String(a), Value::String(b)) => Some(a.cmp(b) as i32),
+            (Value::String(a), Value::String(b)) => Some(self.collation.compare(a, b) as i32),
             (Value::Int64(a), Value::Float64(b)) => {
                 let af = *a as f64;
                 if af < *b {
@@ -1046,7 +1761,7 @@ impl ExpressionPredicate {
                     Some(0)
                 }
             }
-            _ => None,
+            _ => left.compare_temporal(right).map(|ordering| ordering as i32),
         }
     }
 }
@@ -1058,6 +1773,10 @@ impl Predicate for ExpressionPredicate {
             _ => false,
         }
     }
+
+    fn error(&self) -> Option<&str> {
+        ExpressionPredicate::error(self)
+    }
 }
 
 /// A filter operator that applies a predicate to filter rows.
@@ -1087,6 +1806,9 @@ impl Operator for FilterOperator {
         let count = chunk.total_row_count();
         let selection =
             SelectionVector::from_predicate(count, |row| self.predicate.evaluate(&chunk, row));
+        if let Some(message) = self.predicate.error() {
+            return Err(OperatorError::Execution(message.to_string()));
+        }
 
         // If nothing passes, skip to next chunk
         if selection.is_empty() {
@@ -1204,6 +1926,27 @@ mod tests {
         assert!(!predicate_no_match.evaluate(&chunk, 0));
     }
 
+    #[test]
+    fn test_string_range_collation() {
+        let store = Arc::new(LpgStore::new());
+        let chunk = DataChunkBuilder::new(&[LogicalType::Int64]).finish();
+
+        // "émile" < "zoe"?
+        let expr = FilterExpression::Binary {
+            left: Box::new(FilterExpression::Literal(Value::String("émile".into()))),
+            op: BinaryFilterOp::Lt,
+            right: Box::new(FilterExpression::Literal(Value::String("zoe".into()))),
+        };
+
+        // Byte order puts the accented letter after every ASCII letter
+        let binary = ExpressionPredicate::new(expr.clone(), HashMap::new(), Arc::clone(&store));
+        assert!(!binary.evaluate(&chunk, 0));
+
+        let unicode = ExpressionPredicate::new(expr, HashMap::new(), store)
+            .with_collation(Collation::Unicode);
+        assert!(unicode.evaluate(&chunk, 0));
+    }
+
     #[test]
     fn test_pow_operator() {
         use crate::graph::lpg::LpgStore;
@@ -1345,6 +2088,223 @@ mod tests {
         assert!(predicate_neg.evaluate(&chunk, 0));
     }
 
+    #[test]
+    fn test_index_access_edge_cases() {
+        use crate::graph::lpg::LpgStore;
+
+        let store = Arc::new(LpgStore::new());
+        let chunk = DataChunkBuilder::new(&[LogicalType::Int64]).finish();
+        let list = || {
+            Box::new(FilterExpression::List(vec![
+                FilterExpression::Literal(Value::Int64(1)),
+                FilterExpression::Literal(Value::Int64(2)),
+            ]))
+        };
+        let index = |base: Box<FilterExpression>, index: Value| {
+            ExpressionPredicate::new(
+                FilterExpression::IndexAccess {
+                    base,
+                    index: Box::new(FilterExpression::Literal(index)),
+                },
+                HashMap::new(),
+                Arc::clone(&store),
+            )
+        };
+
+        // Out of range, in either direction, and null indices are null
+        for i in [Value::Int64(2), Value::Int64(-3), Value::Null] {
+            let predicate = index(list(), i);
+            assert_eq!(predicate.eval(&chunk, 0), None);
+            assert_eq!(predicate.error(), None);
+        }
+
+        // Maps are indexed by key
+        let map = Box::new(FilterExpression::Map(vec![(
+            "a".to_string(),
+            FilterExpression::Literal(Value::Int64(1)),
+        )]));
+        let predicate = index(map, Value::from("a"));
+        assert_eq!(predicate.eval(&chunk, 0), Some(Value::Int64(1)));
+
+        // Indexing a non-collection, or a list by string, is an error
+        let predicate = index(
+            Box::new(FilterExpression::Literal(Value::Int64(5))),
+            Value::Int64(0),
+        );
+        assert_eq!(predicate.eval(&chunk, 0), None);
+        assert!(predicate.error().is_some());
+
+        let predicate = index(list(), Value::from("a"));
+        assert_eq!(predicate.eval(&chunk, 0), None);
+        assert!(predicate.error().is_some());
+    }
+
+    #[test]
+    fn test_spatial_functions() {
+        use crate::graph::lpg::LpgStore;
+
+        let store = Arc::new(LpgStore::new());
+        let chunk = DataChunkBuilder::new(&[LogicalType::Int64]).finish();
+        let call = |name: &str, args: Vec<Value>| {
+            ExpressionPredicate::new(
+                FilterExpression::FunctionCall {
+                    name: name.to_string(),
+                    args: args.into_iter().map(FilterExpression::Literal).collect(),
+                },
+                HashMap::new(),
+                Arc::clone(&store),
+            )
+        };
+        let origin = Value::point(0.0, 0.0, Value::CARTESIAN_SRID);
+        let corner = Value::point(3.0, 4.0, Value::CARTESIAN_SRID);
+
+        // Points from maps, longitude/latitude maps, and coordinates
+        let map: BTreeMap<PropertyKey, Value> = [
+            (PropertyKey::new("longitude"), Value::Float64(2.35)),
+            (PropertyKey::new("latitude"), Value::Float64(48.85)),
+        ]
+        .into_iter()
+        .collect();
+        let predicate = call("point", vec![Value::Map(Arc::new(map))]);
+        assert_eq!(
+            predicate.eval(&chunk, 0),
+            Some(Value::point(2.35, 48.85, Value::WGS84_SRID))
+        );
+        let predicate = call("point", vec![Value::Int64(3), Value::Int64(4)]);
+        assert_eq!(predicate.eval(&chunk, 0), Some(corner.clone()));
+
+        // Euclidean by default for Cartesian points, haversine on request
+        let predicate = call("distance", vec![origin.clone(), corner.clone()]);
+        assert_eq!(predicate.eval(&chunk, 0), Some(Value::Float64(5.0)));
+        let predicate = call(
+            "distance",
+            vec![origin.clone(), corner.clone(), Value::from("haversine")],
+        );
+        let Some(Value::Float64(meters)) = predicate.eval(&chunk, 0) else {
+            panic!("expected a distance");
+        };
+        assert!((meters - 555_800.0).abs() < 1_000.0, "{meters}");
+
+        let within = |radius: f64| {
+            call(
+                "point.withinDistance",
+                vec![corner.clone(), origin.clone(), Value::Float64(radius)],
+            )
+            .eval(&chunk, 0)
+        };
+        assert_eq!(within(5.0), Some(Value::Bool(true)));
+        assert_eq!(within(4.9), Some(Value::Bool(false)));
+
+        let predicate = call(
+            "point.withinBBox",
+            vec![
+                Value::point(1.0, 1.0, Value::CARTESIAN_SRID),
+                origin.clone(),
+                corner.clone(),
+            ],
+        );
+        assert_eq!(predicate.eval(&chunk, 0), Some(Value::Bool(true)));
+
+        // Nulls propagate, but mixed SRIDs and non-points are errors
+        let predicate = call("distance", vec![origin.clone(), Value::Null]);
+        assert_eq!(predicate.eval(&chunk, 0), None);
+        assert_eq!(predicate.error(), None);
+
+        let predicate = call(
+            "distance",
+            vec![origin.clone(), Value::point(0.0, 0.0, Value::WGS84_SRID)],
+        );
+        assert_eq!(predicate.eval(&chunk, 0), None);
+        assert!(predicate.error().unwrap().contains("SRID"));
+
+        let predicate = call("distance", vec![origin, Value::Int64(1)]);
+        assert_eq!(predicate.eval(&chunk, 0), None);
+        assert!(predicate.error().is_some());
+    }
+
+    #[test]
+    fn test_temporal_functions() {
+        use crate::graph::lpg::LpgStore;
+
+        let store = Arc::new(LpgStore::new());
+        let chunk = DataChunkBuilder::new(&[LogicalType::Int64]).finish();
+        let predicate =
+            |expression| ExpressionPredicate::new(expression, HashMap::new(), Arc::clone(&store));
+        let call = |name: &str, arg: Value| FilterExpression::FunctionCall {
+            name: name.to_string(),
+            args: vec![FilterExpression::Literal(arg)],
+        };
+        let compare = |left, op, right| FilterExpression::Binary {
+            left: Box::new(left),
+            op,
+            right: Box::new(right),
+        };
+
+        let new_year = call("date", Value::from("2024-01-01"));
+        assert_eq!(
+            predicate(new_year.clone()).eval(&chunk, 0),
+            Some(Value::Date(19_723))
+        );
+        assert_eq!(
+            predicate(call("datetime", Value::from("2024-01-01T06:00:00+06:00"))).eval(&chunk, 0),
+            Some(Value::Timestamp(Timestamp::from_secs(19_723 * 86_400)))
+        );
+        assert_eq!(
+            predicate(call("duration", Value::from("PT1M30S"))).eval(&chunk, 0),
+            Some(Value::Duration(90_000_000))
+        );
+        let parts: BTreeMap<PropertyKey, Value> = [
+            (PropertyKey::new("days"), Value::Int64(1)),
+            (PropertyKey::new("hours"), Value::Float64(0.5)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(
+            predicate(call("duration", Value::Map(Arc::new(parts)))).eval(&chunk, 0),
+            Some(Value::Duration(86_400_000_000 + 1_800_000_000))
+        );
+
+        // Dates compare with timestamps as midnight UTC
+        let noon = call("datetime", Value::from("2024-01-01T12:00:00"));
+        let later = predicate(compare(noon.clone(), BinaryFilterOp::Gt, new_year.clone()));
+        assert_eq!(later.eval(&chunk, 0), Some(Value::Bool(true)));
+        let same_day = predicate(compare(
+            call(
+                "date",
+                Value::Timestamp(Timestamp::from_secs(19_723 * 86_400 + 43_200)),
+            ),
+            BinaryFilterOp::Eq,
+            new_year.clone(),
+        ));
+        assert_eq!(same_day.eval(&chunk, 0), Some(Value::Bool(true)));
+
+        // Durations don't compare with instants
+        let mismatch = predicate(compare(
+            call("duration", Value::from("P1D")),
+            BinaryFilterOp::Lt,
+            new_year,
+        ));
+        assert_eq!(mismatch.eval(&chunk, 0), None);
+
+        // Bad literals are errors rather than nulls
+        for (name, text) in [
+            ("date", "2024-02-30"),
+            ("datetime", "noon"),
+            ("duration", "P1M"),
+        ] {
+            let bad = predicate(call(name, Value::from(text)));
+            assert_eq!(bad.eval(&chunk, 0), None);
+            assert!(bad.error().unwrap().contains("ISO 8601"), "{name}");
+        }
+        let bad = predicate(call("date", Value::Int64(1)));
+        assert_eq!(bad.eval(&chunk, 0), None);
+        assert!(bad.error().is_some());
+        assert_eq!(
+            predicate(call("date", Value::Null)).eval(&chunk, 0),
+            Some(Value::Null)
+        );
+    }
+
     #[test]
     fn test_slice_access() {
         use crate::graph::lpg::LpgStore;
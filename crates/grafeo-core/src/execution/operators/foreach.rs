@@ -0,0 +1,144 @@
+//! ForEach operator for running update subplans once per list element.
+//!
+//! This backs Cypher's `FOREACH (x IN list | ...)`. The body is an ordinary
+//! operator tree whose leaf is a [`ForEachSourceOperator`]; the ForEach operator
+//! hands each input chunk to that leaf, drains the body, and passes the input
+//! through unchanged.
+
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use super::{Operator, OperatorResult};
+use crate::execution::DataChunk;
+
+/// Slot through which a [`ForEachOperator`] feeds its body.
+pub type ForEachFeed = Arc<Mutex<Option<DataChunk>>>;
+
+/// Runs an update subplan for every input chunk and passes the input through.
+///
+/// The body typically unwinds the list into one row per element and applies
+/// its mutations to those rows. Its output is discarded, so an empty list
+/// leaves the input untouched.
+pub struct ForEachOperator {
+    /// Operator producing the outer rows.
+    input: Box<dyn Operator>,
+    /// Update subplan, reading from a [`ForEachSourceOperator`] on `feed`.
+    body: Box<dyn Operator>,
+    /// Slot shared with the body's source.
+    feed: ForEachFeed,
+}
+
+impl ForEachOperator {
+    /// Creates a new ForEach operator.
+    ///
+    /// `feed` must be the slot the body's [`ForEachSourceOperator`] reads from.
+    pub fn new(input: Box<dyn Operator>, body: Box<dyn Operator>, feed: ForEachFeed) -> Self {
+        Self { input, body, feed }
+    }
+}
+
+impl Operator for ForEachOperator {
+    fn next(&mut self) -> OperatorResult {
+        let Some(chunk) = self.input.next()? else {
+            return Ok(None);
+        };
+
+        if !chunk.is_empty() {
+            *self.feed.lock() = Some(chunk.slice(0, chunk.row_count()));
+            self.body.reset();
+            while self.body.next()?.is_some() {}
+        }
+
+        Ok(Some(chunk))
+    }
+
+    fn reset(&mut self) {
+        self.input.reset();
+        self.body.reset();
+        *self.feed.lock() = None;
+    }
+
+    fn name(&self) -> &'static str {
+        "ForEach"
+    }
+}
+
+/// Leaf of a ForEach body: yields the chunk its [`ForEachOperator`] fed it, once.
+pub struct ForEachSourceOperator {
+    /// Slot shared with the owning ForEach operator.
+    feed: ForEachFeed,
+}
+
+impl ForEachSourceOperator {
+    /// Creates a new source reading from `feed`.
+    #[must_use]
+    pub fn new(feed: ForEachFeed) -> Self {
+        Self { feed }
+    }
+}
+
+impl Operator for ForEachSourceOperator {
+    fn next(&mut self) -> OperatorResult {
+        Ok(self.feed.lock().take())
+    }
+
+    fn reset(&mut self) {
+        // The feed is refilled by the ForEach operator before each reset
+    }
+
+    fn name(&self) -> &'static str {
+        "ForEachSource"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::operators::single_row::SingleRowOperator;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the rows it pulls from its child.
+    struct CountingOperator {
+        child: Box<dyn Operator>,
+        rows: Arc<AtomicUsize>,
+    }
+
+    impl Operator for CountingOperator {
+        fn next(&mut self) -> OperatorResult {
+            let chunk = self.child.next()?;
+            if let Some(chunk) = &chunk {
+                self.rows.fetch_add(chunk.row_count(), Ordering::Relaxed);
+            }
+            Ok(chunk)
+        }
+
+        fn reset(&mut self) {
+            self.child.reset();
+        }
+
+        fn name(&self) -> &'static str {
+            "Counting"
+        }
+    }
+
+    #[test]
+    fn test_foreach_runs_body_per_input_chunk() {
+        let feed = ForEachFeed::default();
+        let rows = Arc::new(AtomicUsize::new(0));
+        let body = Box::new(CountingOperator {
+            child: Box::new(ForEachSourceOperator::new(Arc::clone(&feed))),
+            rows: Arc::clone(&rows),
+        });
+        let mut op = ForEachOperator::new(Box::new(SingleRowOperator::new()), body, feed);
+
+        let chunk = op.next().unwrap().unwrap();
+        assert_eq!(chunk.row_count(), 1);
+        assert!(op.next().unwrap().is_none());
+        assert_eq!(rows.load(Ordering::Relaxed), 1);
+
+        op.reset();
+        assert!(op.next().unwrap().is_some());
+        assert_eq!(rows.load(Ordering::Relaxed), 2);
+    }
+}
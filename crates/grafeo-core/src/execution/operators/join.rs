@@ -8,9 +8,15 @@ use std::collections::HashMap;
 
 use grafeo_common::types::{LogicalType, Value};
 
-use super::{Operator, OperatorError, OperatorResult};
+use super::{
+    MIN_HASH_TABLE_CAPACITY, Operator, OperatorError, OperatorResult, account_memory,
+    chunk_memory_size, hash_table_capacity, reserve_memory,
+};
 use crate::execution::chunk::DataChunkBuilder;
-use crate::execution::{DataChunk, ValueVector};
+use crate::execution::{DataChunk, SharedMemoryContext, ValueVector};
+
+/// Approximate size of one hash table slot in a join build.
+const HASH_ENTRY_SIZE: usize = std::mem::size_of::<(HashKey, Vec<(usize, usize)>)>();
 
 /// The type of join to perform.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -63,6 +69,9 @@ impl HashKey {
                 HashKey::String(format!("{b:?}"))
             }
             Value::Timestamp(t) => HashKey::Int64(t.as_micros()),
+            // Dates join with the timestamp at the start of their day
+            Value::Date(_) => HashKey::Int64(value.as_epoch_micros().unwrap_or_default()),
+            Value::Duration(d) => HashKey::Int64(*d),
             Value::List(items) => {
                 HashKey::Composite(items.iter().map(HashKey::from_value).collect())
             }
@@ -79,6 +88,11 @@ impl HashKey {
                 keys.sort_by(|a, b| format!("{a:?}").cmp(&format!("{b:?}")));
                 HashKey::Composite(keys)
             }
+            Value::Point { x, y, srid } => HashKey::Composite(vec![
+                HashKey::Int64(x.to_bits() as i64),
+                HashKey::Int64(y.to_bits() as i64),
+                HashKey::Int64(i64::from(*srid)),
+            ]),
         }
     }
 
@@ -107,6 +121,10 @@ pub struct HashJoinOperator {
     output_schema: Vec<LogicalType>,
     /// Hash table: key -> list of (chunk_index, row_index).
     hash_table: HashMap<HashKey, Vec<(usize, usize)>>,
+    /// How many times the hash table grew during the build phase.
+    hash_table_resizes: usize,
+    /// Memory budget the build side is charged against.
+    memory: Option<SharedMemoryContext>,
     /// Materialized build side chunks.
     build_chunks: Vec<DataChunk>,
     /// Whether the build phase is complete.
@@ -157,6 +175,8 @@ impl HashJoinOperator {
             join_type,
             output_schema,
             hash_table: HashMap::new(),
+            hash_table_resizes: 0,
+            memory: None,
             build_chunks: Vec::new(),
             build_complete: false,
             current_probe_chunk: None,
@@ -171,10 +191,49 @@ impl HashJoinOperator {
         }
     }
 
+    /// Sizes the hash table for the expected number of build-side rows.
+    ///
+    /// A good estimate lets the build finish without rehashing; the capacity
+    /// is clamped by [`hash_table_capacity`], so a bad one stays bounded.
+    #[must_use]
+    pub fn with_estimated_build_rows(mut self, rows: f64) -> Self {
+        self.hash_table = HashMap::with_capacity(hash_table_capacity(rows));
+        self
+    }
+
+    /// Charges the build side's memory to a query's memory context.
+    ///
+    /// Buffered chunks are accounted as they arrive and hash table growth is
+    /// reserved before it happens, so the build fails with
+    /// [`OperatorError::MemoryLimitExceeded`] instead of overshooting the
+    /// budget by more than the context's granularity.
+    #[must_use]
+    pub fn with_memory_context(mut self, memory: SharedMemoryContext) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Returns how many times the hash table had to grow while building.
+    #[must_use]
+    pub fn hash_table_resizes(&self) -> usize {
+        self.hash_table_resizes
+    }
+
     /// Builds the hash table from the build side.
     fn build_hash_table(&mut self) -> Result<(), OperatorError> {
+        // A pre-sized table is allocated up front
+        reserve_memory(
+            self.memory.as_ref(),
+            self.hash_table.capacity() * HASH_ENTRY_SIZE,
+        )?;
+
         while let Some(chunk) = self.build_side.next()? {
             let chunk_idx = self.build_chunks.len();
+            account_memory(
+                self.memory.as_ref(),
+                chunk_memory_size(&chunk)
+                    + chunk.row_count() * std::mem::size_of::<(usize, usize)>(),
+            )?;
 
             // Initialize match tracking for outer joins
             if matches!(self.join_type, JoinType::Right | JoinType::Full) {
@@ -195,10 +254,21 @@ impl HashJoinOperator {
                     continue;
                 }
 
+                let capacity = self.hash_table.capacity();
+                if self.hash_table.len() == capacity && !self.hash_table.contains_key(&key) {
+                    // A new key in a full table doubles it
+                    reserve_memory(
+                        self.memory.as_ref(),
+                        capacity.max(MIN_HASH_TABLE_CAPACITY) * HASH_ENTRY_SIZE,
+                    )?;
+                }
                 self.hash_table
                     .entry(key)
                     .or_default()
                     .push((chunk_idx, row));
+                if self.hash_table.capacity() != capacity {
+                    self.hash_table_resizes += 1;
+                }
             }
 
             self.build_chunks.push(chunk);
@@ -982,6 +1052,78 @@ mod tests {
         assert_eq!(results, vec![(2, 2), (3, 3), (4, 4)]);
     }
 
+    #[test]
+    fn test_hash_join_estimated_build_avoids_resizes() {
+        let keys: Vec<i64> = (0..10_000).collect();
+        let build = |estimate: Option<f64>| {
+            let left = MockOperator::new(vec![create_int_chunk(&[1, 2, 3])]);
+            let right = MockOperator::new(vec![create_int_chunk(&keys)]);
+            let join = HashJoinOperator::new(
+                Box::new(left),
+                Box::new(right),
+                vec![0],
+                vec![0],
+                JoinType::Inner,
+                vec![LogicalType::Int64, LogicalType::Int64],
+            );
+            let mut join = match estimate {
+                Some(rows) => join.with_estimated_build_rows(rows),
+                None => join,
+            };
+            let mut rows = 0;
+            while let Some(chunk) = join.next().unwrap() {
+                rows += chunk.row_count();
+            }
+            assert_eq!(rows, 3);
+            join.hash_table_resizes()
+        };
+
+        assert!(build(None) > 0);
+        assert_eq!(build(Some(10_000.0)), 0);
+        // An underestimate still works, it just grows
+        assert!(build(Some(100.0)) > 0);
+    }
+
+    #[test]
+    fn test_hash_join_respects_memory_limit() {
+        use crate::execution::memory::ExecutionMemoryContext;
+        use grafeo_common::memory::buffer::BufferManager;
+        use std::sync::Arc;
+
+        let budget = 1024 * 1024;
+        let granularity = 16 * 1024;
+        let manager = BufferManager::with_budget(budget);
+        let memory = ExecutionMemoryContext::new(Arc::clone(&manager))
+            .with_granularity(granularity)
+            .into_shared();
+
+        // 100 build chunks of 1000 rows each need far more than the budget
+        let chunks: Vec<DataChunk> = (0..100)
+            .map(|c| create_int_chunk(&(c * 1000..(c + 1) * 1000).collect::<Vec<i64>>()))
+            .collect();
+        let chunk_bytes = chunk_memory_size(&chunks[0]);
+        let left = MockOperator::new(vec![create_int_chunk(&[1, 2, 3])]);
+        let right = MockOperator::new(chunks);
+        let mut join = HashJoinOperator::new(
+            Box::new(left),
+            Box::new(right),
+            vec![0],
+            vec![0],
+            JoinType::Inner,
+            vec![LogicalType::Int64, LogicalType::Int64],
+        )
+        .with_memory_context(Arc::clone(&memory));
+
+        assert!(matches!(
+            join.next(),
+            Err(OperatorError::MemoryLimitExceeded { .. })
+        ));
+        assert!(manager.allocated() <= budget);
+        // Usage recorded but not yet charged stays within one accounting step
+        let memory = memory.lock();
+        assert!(memory.total_allocated() + memory.pending() <= budget + granularity + chunk_bytes);
+    }
+
     #[test]
     fn test_hash_join_left_outer() {
         // Left: [1, 2, 3]
@@ -2,160 +2,452 @@
 //!
 //! The MERGE operator implements the Cypher MERGE semantics:
 //! 1. Try to match the pattern in the graph
-//! 2. If found, return existing element (optionally apply ON MATCH SET)
-//! 3. If not found, create the element (optionally apply ON CREATE SET)
+//! 2. If found, return the existing elements
+//! 3. If not found, create the pattern
+//!
+//! ON MATCH SET / ON CREATE SET run downstream as conditional updates, keyed
+//! on the "created" column this operator can emit (see
+//! [`MergeOperator::with_created_column`]).
 
-use super::{Operator, OperatorResult};
+use super::mutation::creation_epoch;
+use super::{Operator, OperatorError, OperatorResult, PropertySource};
+use crate::execution::DataChunk;
 use crate::execution::chunk::DataChunkBuilder;
-use crate::graph::lpg::LpgStore;
-use grafeo_common::types::{LogicalType, NodeId, PropertyKey, Value};
+use crate::graph::Direction;
+use crate::graph::lpg::{CompareOp, LpgStore};
+use grafeo_common::types::{EdgeId, EpochId, LogicalType, NodeId, PropertyKey, TxId, Value};
 use std::sync::Arc;
 
+/// A node in a MERGE pattern.
+///
+/// Either bound - already produced by the input, and reused as it is - or
+/// described by labels and properties to match or create.
+#[derive(Debug, Clone)]
+pub struct MergeNode {
+    /// Input column holding the node, if the variable is already bound.
+    bound_column: Option<usize>,
+    /// Labels to match/create.
+    labels: Vec<String>,
+    /// Properties that must match (also used for creation).
+    properties: Vec<(String, PropertySource)>,
+}
+
+impl MergeNode {
+    /// Creates a node pattern to match by labels and properties.
+    pub fn new(labels: Vec<String>, properties: Vec<(String, PropertySource)>) -> Self {
+        Self {
+            bound_column: None,
+            labels,
+            properties,
+        }
+    }
+
+    /// Creates a node pattern for a variable the input already binds.
+    pub fn bound(column: usize) -> Self {
+        Self {
+            bound_column: Some(column),
+            labels: Vec::new(),
+            properties: Vec::new(),
+        }
+    }
+}
+
+/// The relationship part of a MERGE pattern: `(a)-[:TYPE {props}]->(b)`.
+#[derive(Debug, Clone)]
+pub struct MergeEdge {
+    /// Relationship type to match/create.
+    edge_type: String,
+    /// Direction from the merged node to the target.
+    direction: Direction,
+    /// Relationship properties that must match (also used for creation).
+    properties: Vec<(String, PropertySource)>,
+    /// The node at the other end.
+    target: MergeNode,
+}
+
+impl MergeEdge {
+    /// Creates a relationship pattern.
+    ///
+    /// [`Direction::Both`] matches either way and creates an outgoing edge.
+    pub fn new(
+        edge_type: impl Into<String>,
+        direction: Direction,
+        properties: Vec<(String, PropertySource)>,
+        target: MergeNode,
+    ) -> Self {
+        Self {
+            edge_type: edge_type.into(),
+            direction,
+            properties,
+            target,
+        }
+    }
+}
+
+/// The elements a MERGE matched or created for one input row.
+struct Merged {
+    node: NodeId,
+    edge: Option<(EdgeId, NodeId)>,
+    created: bool,
+}
+
 /// Merge operator for MERGE clause.
 ///
-/// Tries to match a node with the given labels and properties.
-/// If found, returns the existing node. If not found, creates a new node.
+/// For every input row, tries to match the pattern; if it isn't there,
+/// creates it. Variables the input already binds are reused, so
+/// `MATCH (a), (b) MERGE (a)-[:KNOWS]->(b)` only ever creates the edge. The
+/// unbound parts of the pattern are matched as a whole or created as a whole.
+///
+/// Candidate nodes come from the label index, and a property the zone maps
+/// rule out skips the lookup altogether.
+///
+/// Output columns are the input columns, followed by the node (unless
+/// bound), the edge and the target node (unless bound) when merging a
+/// relationship, and the created flag when requested.
 pub struct MergeOperator {
     /// The graph store.
     store: Arc<LpgStore>,
-    /// Variable name for the merged node.
-    variable: String,
-    /// Labels to match/create.
-    labels: Vec<String>,
-    /// Properties that must match (also used for creation).
-    match_properties: Vec<(String, Value)>,
-    /// Properties to set on CREATE.
-    on_create_properties: Vec<(String, Value)>,
-    /// Properties to set on MATCH.
-    on_match_properties: Vec<(String, Value)>,
-    /// Whether we've already executed.
+    /// Input operator; without one, the pattern is merged once.
+    input: Option<Box<dyn Operator>>,
+    /// The node to merge.
+    node: MergeNode,
+    /// The relationship to merge from the node, if any.
+    edge: Option<MergeEdge>,
+    /// Output schema.
+    output_schema: Vec<LogicalType>,
+    /// Whether to emit a Bool column saying whether the row was created.
+    created_column: bool,
+    /// Whether we've already executed (for the no-input case).
     executed: bool,
+    /// Epoch for MVCC versioning.
+    viewing_epoch: Option<EpochId>,
+    /// Transaction ID for MVCC versioning.
+    tx_id: Option<TxId>,
 }
 
 impl MergeOperator {
     /// Creates a new merge operator.
     pub fn new(
         store: Arc<LpgStore>,
-        variable: String,
-        labels: Vec<String>,
-        match_properties: Vec<(String, Value)>,
-        on_create_properties: Vec<(String, Value)>,
-        on_match_properties: Vec<(String, Value)>,
+        input: Option<Box<dyn Operator>>,
+        node: MergeNode,
+        output_schema: Vec<LogicalType>,
     ) -> Self {
         Self {
             store,
-            variable,
-            labels,
-            match_properties,
-            on_create_properties,
-            on_match_properties,
+            input,
+            node,
+            edge: None,
+            output_schema,
+            created_column: false,
             executed: false,
+            viewing_epoch: None,
+            tx_id: None,
         }
     }
 
-    /// Returns the variable name for the merged node.
-    #[must_use]
-    pub fn variable(&self) -> &str {
-        &self.variable
+    /// Merges a relationship from the node as well.
+    pub fn with_edge(mut self, edge: MergeEdge) -> Self {
+        self.edge = Some(edge);
+        self
     }
 
-    /// Tries to find a matching node.
-    fn find_matching_node(&self) -> Option<NodeId> {
-        // Get all nodes with the first label (or all nodes if no labels)
-        let candidates: Vec<NodeId> = if let Some(first_label) = self.labels.first() {
-            self.store.nodes_by_label(first_label)
-        } else {
-            self.store.node_ids()
-        };
+    /// Appends a Bool column that is `true` for rows whose pattern was
+    /// created and `false` for rows that matched.
+    pub fn with_created_column(mut self) -> Self {
+        self.created_column = true;
+        self
+    }
 
-        // Filter by all labels and properties
-        for node_id in candidates {
-            if let Some(node) = self.store.get_node(node_id) {
-                // Check all labels
-                let has_all_labels = self.labels.iter().all(|label| node.has_label(label));
-                if !has_all_labels {
-                    continue;
-                }
+    /// Sets the transaction context for MVCC versioning.
+    pub fn with_tx_context(mut self, epoch: EpochId, tx_id: Option<TxId>) -> Self {
+        self.viewing_epoch = Some(epoch);
+        self.tx_id = tx_id;
+        self
+    }
 
-                // Check all match properties
-                let has_all_props = self.match_properties.iter().all(|(key, expected_value)| {
-                    node.properties
-                        .get(&PropertyKey::new(key.as_str()))
-                        .map(|v| v == expected_value)
-                        .unwrap_or(false)
-                });
+    fn epoch(&self) -> EpochId {
+        self.viewing_epoch
+            .unwrap_or_else(|| self.store.current_epoch())
+    }
 
-                if has_all_props {
-                    return Some(node_id);
-                }
-            }
+    fn tx(&self) -> TxId {
+        self.tx_id.unwrap_or(TxId::SYSTEM)
+    }
+
+    /// Reads the node a bound variable holds.
+    fn bound_node(
+        chunk: &DataChunk,
+        column: usize,
+        row: usize,
+    ) -> Result<Option<NodeId>, OperatorError> {
+        match chunk.column(column).and_then(|c| c.get_value(row)) {
+            Some(Value::Int64(id)) => Ok(Some(NodeId(id as u64))),
+            // An unmatched OPTIONAL MATCH leaves nothing to merge on
+            Some(Value::Null) => Ok(None),
+            Some(other) => Err(OperatorError::TypeMismatch {
+                expected: "Int64 (node ID)".to_string(),
+                found: format!("{other:?}"),
+            }),
+            None => Err(OperatorError::ColumnNotFound(format!(
+                "node column {column}"
+            ))),
         }
+    }
 
-        None
+    /// Resolves a pattern's properties for a row.
+    fn resolve(
+        properties: &[(String, PropertySource)],
+        chunk: &DataChunk,
+        row: usize,
+    ) -> Vec<(PropertyKey, Value)> {
+        properties
+            .iter()
+            .map(|(key, source)| (PropertyKey::new(key.as_str()), source.resolve(chunk, row)))
+            .collect()
     }
 
-    /// Creates a new node with the specified labels and properties.
-    fn create_node(&self) -> NodeId {
-        // Combine match properties with on_create properties
-        let mut all_props: Vec<(PropertyKey, Value)> = self
-            .match_properties
+    /// Finds the nodes matching an unbound node pattern.
+    fn matching_nodes(
+        &self,
+        labels: &[String],
+        properties: &[(PropertyKey, Value)],
+    ) -> Vec<NodeId> {
+        // Zone maps can rule out a property value without visiting any node
+        let ruled_out = properties.iter().any(|(key, value)| {
+            !self
+                .store
+                .node_property_might_match(key, CompareOp::Eq, value)
+        });
+        if ruled_out {
+            return Vec::new();
+        }
+
+        let label_refs: Vec<&str> = labels.iter().map(String::as_str).collect();
+        self.store
+            .nodes_with_all_labels(&label_refs)
+            .into_iter()
+            .filter(|&id| self.node_matches(id, labels, properties))
+            .collect()
+    }
+
+    /// Checks a node against a node pattern.
+    fn node_matches(
+        &self,
+        id: NodeId,
+        labels: &[String],
+        properties: &[(PropertyKey, Value)],
+    ) -> bool {
+        let Some(node) = self.store.get_node_versioned(id, self.epoch(), self.tx()) else {
+            return false;
+        };
+        labels
             .iter()
-            .map(|(k, v)| (PropertyKey::new(k.as_str()), v.clone()))
-            .collect();
-
-        // Add on_create properties (may override match properties)
-        for (k, v) in &self.on_create_properties {
-            // Check if property already exists, if so update it
-            if let Some(existing) = all_props.iter_mut().find(|(key, _)| key.as_str() == k) {
-                existing.1 = v.clone();
-            } else {
-                all_props.push((PropertyKey::new(k.as_str()), v.clone()));
-            }
+            .all(|label| self.store.node_has_label(&node, label))
+            && properties
+                .iter()
+                .all(|(key, value)| node.properties.get(key) == Some(value))
+    }
+
+    /// Creates a node from a node pattern.
+    fn create_node(
+        &self,
+        labels: &[String],
+        properties: Vec<(PropertyKey, Value)>,
+    ) -> Result<NodeId, OperatorError> {
+        let label_refs: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let id = self.store.create_node_versioned(
+            &label_refs,
+            creation_epoch(self.epoch(), self.tx()),
+            self.tx(),
+        );
+        for (key, value) in properties {
+            self.store
+                .set_node_property_versioned(id, key.as_str(), value, self.tx())?;
         }
+        Ok(id)
+    }
 
-        let labels: Vec<&str> = self.labels.iter().map(String::as_str).collect();
-        self.store.create_node_with_props(&labels, all_props)
+    /// Finds or creates a single node.
+    fn merge_node(&self, chunk: &DataChunk, row: usize) -> Result<Merged, OperatorError> {
+        let properties = Self::resolve(&self.node.properties, chunk, row);
+        if let Some(&id) = self.matching_nodes(&self.node.labels, &properties).first() {
+            return Ok(Merged {
+                node: id,
+                edge: None,
+                created: false,
+            });
+        }
+        Ok(Merged {
+            node: self.create_node(&self.node.labels, properties)?,
+            edge: None,
+            created: true,
+        })
     }
 
-    /// Applies ON MATCH properties to an existing node.
-    fn apply_on_match(&self, node_id: NodeId) {
-        for (key, value) in &self.on_match_properties {
+    /// Finds or creates a node with a relationship.
+    ///
+    /// Returns `None` when a bound endpoint is null.
+    fn merge_edge(
+        &self,
+        edge: &MergeEdge,
+        chunk: &DataChunk,
+        row: usize,
+    ) -> Result<Option<Merged>, OperatorError> {
+        let source_props = Self::resolve(&self.node.properties, chunk, row);
+        let target_props = Self::resolve(&edge.target.properties, chunk, row);
+        let edge_props = Self::resolve(&edge.properties, chunk, row);
+
+        let sources = match self.node.bound_column {
+            Some(column) => match Self::bound_node(chunk, column, row)? {
+                Some(id) => vec![id],
+                None => return Ok(None),
+            },
+            None => self.matching_nodes(&self.node.labels, &source_props),
+        };
+        let bound_target = match edge.target.bound_column {
+            Some(column) => match Self::bound_node(chunk, column, row)? {
+                Some(id) => Some(id),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
+        for &source in &sources {
+            for (neighbor, edge_id) in self.store.edges_from(source, edge.direction) {
+                let target_ok = match bound_target {
+                    Some(target) => neighbor == target,
+                    None => self.node_matches(neighbor, &edge.target.labels, &target_props),
+                };
+                if target_ok && self.edge_matches(edge_id, &edge.edge_type, &edge_props) {
+                    return Ok(Some(Merged {
+                        node: source,
+                        edge: Some((edge_id, neighbor)),
+                        created: false,
+                    }));
+                }
+            }
+        }
+
+        // No match: reuse the bound endpoints and create everything else
+        let source = match self.node.bound_column {
+            Some(_) => sources[0],
+            None => self.create_node(&self.node.labels, source_props)?,
+        };
+        let target = match bound_target {
+            Some(id) => id,
+            None => self.create_node(&edge.target.labels, target_props)?,
+        };
+        let (src, dst) = match edge.direction {
+            Direction::Incoming => (target, source),
+            Direction::Outgoing | Direction::Both => (source, target),
+        };
+        let edge_id = self.store.create_edge_versioned(
+            src,
+            dst,
+            &edge.edge_type,
+            creation_epoch(self.epoch(), self.tx()),
+            self.tx(),
+        );
+        for (key, value) in edge_props {
             self.store
-                .set_node_property(node_id, key.as_str(), value.clone());
+                .set_edge_property_versioned(edge_id, key.as_str(), value, self.tx())?;
         }
+
+        Ok(Some(Merged {
+            node: source,
+            edge: Some((edge_id, target)),
+            created: true,
+        }))
+    }
+
+    /// Checks an edge against the relationship pattern.
+    fn edge_matches(
+        &self,
+        id: EdgeId,
+        edge_type: &str,
+        properties: &[(PropertyKey, Value)],
+    ) -> bool {
+        let Some(edge) = self.store.get_edge_versioned(id, self.epoch(), self.tx()) else {
+            return false;
+        };
+        edge.edge_type.as_ref() == edge_type
+            && properties
+                .iter()
+                .all(|(key, value)| edge.properties.get(key) == Some(value))
+    }
+
+    /// Merges the pattern for every row of a chunk.
+    fn merge_chunk(&self, chunk: &DataChunk) -> Result<DataChunk, OperatorError> {
+        let mut builder = DataChunkBuilder::with_capacity(&self.output_schema, chunk.row_count());
+
+        for row in chunk.selected_indices() {
+            let merged = match &self.edge {
+                Some(edge) => match self.merge_edge(edge, chunk, row)? {
+                    Some(merged) => merged,
+                    // Nothing to merge on; the row is dropped
+                    None => continue,
+                },
+                None => self.merge_node(chunk, row)?,
+            };
+
+            let mut values: Vec<Value> = (0..chunk.column_count())
+                .map(|col| {
+                    chunk
+                        .column(col)
+                        .and_then(|c| c.get_value(row))
+                        .unwrap_or(Value::Null)
+                })
+                .collect();
+            if self.node.bound_column.is_none() {
+                values.push(Value::Int64(merged.node.0 as i64));
+            }
+            if let (Some(edge), Some((edge_id, target))) = (&self.edge, merged.edge) {
+                values.push(Value::Int64(edge_id.0 as i64));
+                if edge.target.bound_column.is_none() {
+                    values.push(Value::Int64(target.0 as i64));
+                }
+            }
+            if self.created_column {
+                values.push(Value::Bool(merged.created));
+            }
+
+            for (col, value) in values.into_iter().enumerate() {
+                if let Some(dst) = builder.column_mut(col) {
+                    dst.push_value(value);
+                }
+            }
+            builder.advance_row();
+        }
+
+        Ok(builder.finish())
     }
 }
 
 impl Operator for MergeOperator {
     fn next(&mut self) -> OperatorResult {
+        if let Some(input) = &mut self.input {
+            return match input.next()? {
+                Some(chunk) => self.merge_chunk(&chunk).map(Some),
+                None => Ok(None),
+            };
+        }
+
         if self.executed {
             return Ok(None);
         }
         self.executed = true;
 
-        // Try to find matching node
-        let (node_id, was_created) = if let Some(existing_id) = self.find_matching_node() {
-            // Node exists - apply ON MATCH properties
-            self.apply_on_match(existing_id);
-            (existing_id, false)
-        } else {
-            // Node doesn't exist - create it
-            let new_id = self.create_node();
-            (new_id, true)
-        };
-
-        // Build output chunk with the node ID
-        let mut builder = DataChunkBuilder::new(&[LogicalType::Node]);
-        builder.column_mut(0).unwrap().push_node_id(node_id);
-        builder.advance_row();
-
-        // Log for debugging (in real code, this would be removed)
-        let _ = was_created; // Suppress unused variable warning
-
-        Ok(Some(builder.finish()))
+        // Without input, merge once against a single empty row
+        let mut row = DataChunk::empty();
+        row.set_count(1);
+        self.merge_chunk(&row).map(Some)
     }
 
     fn reset(&mut self) {
+        if let Some(input) = &mut self.input {
+            input.reset();
+        }
         self.executed = false;
     }
 
@@ -168,6 +460,14 @@ impl Operator for MergeOperator {
 mod tests {
     use super::*;
 
+    fn constant(key: &str, value: impl Into<Value>) -> (String, PropertySource) {
+        (key.to_string(), PropertySource::Constant(value.into()))
+    }
+
+    fn person(name: &str) -> MergeNode {
+        MergeNode::new(vec!["Person".to_string()], vec![constant("name", name)])
+    }
+
     #[test]
     fn test_merge_creates_new_node() {
         let store = Arc::new(LpgStore::new());
@@ -175,11 +475,9 @@ mod tests {
         // MERGE should create a new node since none exists
         let mut merge = MergeOperator::new(
             Arc::clone(&store),
-            "n".to_string(),
-            vec!["Person".to_string()],
-            vec![("name".to_string(), Value::String("Alice".into()))],
-            vec![], // no on_create
-            vec![], // no on_match
+            None,
+            person("Alice"),
+            vec![LogicalType::Node],
         );
 
         let result = merge.next().unwrap();
@@ -202,7 +500,7 @@ mod tests {
         let store = Arc::new(LpgStore::new());
 
         // Create an existing node
-        store.create_node_with_props(
+        let existing = store.create_node_with_props(
             &["Person"],
             vec![(PropertyKey::new("name"), Value::String("Bob".into()))],
         );
@@ -210,15 +508,13 @@ mod tests {
         // MERGE should find the existing node
         let mut merge = MergeOperator::new(
             Arc::clone(&store),
-            "n".to_string(),
-            vec!["Person".to_string()],
-            vec![("name".to_string(), Value::String("Bob".into()))],
-            vec![], // no on_create
-            vec![], // no on_match
+            None,
+            person("Bob"),
+            vec![LogicalType::Node],
         );
 
-        let result = merge.next().unwrap();
-        assert!(result.is_some());
+        let chunk = merge.next().unwrap().unwrap();
+        assert_eq!(chunk.column(0).unwrap().get_node_id(0), Some(existing));
 
         // Verify only one node exists (no new node created)
         let nodes = store.nodes_by_label("Person");
@@ -226,61 +522,81 @@ mod tests {
     }
 
     #[test]
-    fn test_merge_with_on_create() {
+    fn test_merge_reports_created_rows() {
         let store = Arc::new(LpgStore::new());
+        let schema = vec![LogicalType::Node, LogicalType::Bool];
 
-        // MERGE with ON CREATE SET
-        let mut merge = MergeOperator::new(
-            Arc::clone(&store),
-            "n".to_string(),
-            vec!["Person".to_string()],
-            vec![("name".to_string(), Value::String("Charlie".into()))],
-            vec![("created".to_string(), Value::Bool(true))], // on_create
-            vec![],                                           // no on_match
-        );
-
-        let _ = merge.next().unwrap();
-
-        // Verify node has both match properties and on_create properties
-        let nodes = store.nodes_by_label("Person");
-        let node = store.get_node(nodes[0]).unwrap();
+        let mut first =
+            MergeOperator::new(Arc::clone(&store), None, person("Charlie"), schema.clone())
+                .with_created_column();
+        let chunk = first.next().unwrap().unwrap();
         assert_eq!(
-            node.properties.get(&PropertyKey::new("name")),
-            Some(&Value::String("Charlie".into()))
+            chunk.column(1).unwrap().get_value(0),
+            Some(Value::Bool(true))
         );
+
+        let mut second = MergeOperator::new(Arc::clone(&store), None, person("Charlie"), schema)
+            .with_created_column();
+        let chunk = second.next().unwrap().unwrap();
         assert_eq!(
-            node.properties.get(&PropertyKey::new("created")),
-            Some(&Value::Bool(true))
+            chunk.column(1).unwrap().get_value(0),
+            Some(Value::Bool(false))
         );
     }
 
     #[test]
-    fn test_merge_with_on_match() {
+    fn test_merge_edge_creates_only_missing_parts() {
         let store = Arc::new(LpgStore::new());
+        let alice = store.create_node(&["Person"]);
+        let bob = store.create_node(&["Person"]);
+
+        // Both endpoints are bound by the input; only the edge is missing
+        let input_chunk = || {
+            let mut builder = DataChunkBuilder::new(&[LogicalType::Node, LogicalType::Node]);
+            builder.column_mut(0).unwrap().push_node_id(alice);
+            builder.column_mut(1).unwrap().push_node_id(bob);
+            builder.advance_row();
+            builder.finish()
+        };
+        let merge = |chunk: DataChunk| {
+            let op = MergeOperator::new(
+                Arc::clone(&store),
+                None,
+                MergeNode::bound(0),
+                vec![
+                    LogicalType::Node,
+                    LogicalType::Node,
+                    LogicalType::Edge,
+                    LogicalType::Bool,
+                ],
+            )
+            .with_edge(MergeEdge::new(
+                "KNOWS",
+                Direction::Outgoing,
+                Vec::new(),
+                MergeNode::bound(1),
+            ))
+            .with_created_column();
+            op.merge_chunk(&chunk).unwrap()
+        };
 
-        // Create an existing node
-        let node_id = store.create_node_with_props(
-            &["Person"],
-            vec![(PropertyKey::new("name"), Value::String("Diana".into()))],
+        let created = merge(input_chunk());
+        assert_eq!(
+            created.column(3).unwrap().get_value(0),
+            Some(Value::Bool(true))
         );
+        assert_eq!(store.node_count(), 2);
+        assert_eq!(store.edge_count(), 1);
 
-        // MERGE with ON MATCH SET
-        let mut merge = MergeOperator::new(
-            Arc::clone(&store),
-            "n".to_string(),
-            vec!["Person".to_string()],
-            vec![("name".to_string(), Value::String("Diana".into()))],
-            vec![],                                           // no on_create
-            vec![("updated".to_string(), Value::Bool(true))], // on_match
+        let matched = merge(input_chunk());
+        assert_eq!(
+            matched.column(3).unwrap().get_value(0),
+            Some(Value::Bool(false))
         );
-
-        let _ = merge.next().unwrap();
-
-        // Verify node has the on_match property added
-        let node = store.get_node(node_id).unwrap();
         assert_eq!(
-            node.properties.get(&PropertyKey::new("updated")),
-            Some(&Value::Bool(true))
+            matched.column(2).unwrap().get_value(0),
+            created.column(2).unwrap().get_value(0)
         );
+        assert_eq!(store.edge_count(), 1);
     }
 }
@@ -23,6 +23,7 @@ mod aggregate;
 mod distinct;
 mod expand;
 mod filter;
+mod foreach;
 mod join;
 mod limit;
 mod merge;
@@ -43,16 +44,19 @@ pub use aggregate::{
 pub use distinct::DistinctOperator;
 pub use expand::ExpandOperator;
 pub use filter::{
-    BinaryFilterOp, ExpressionPredicate, FilterExpression, FilterOperator, Predicate, UnaryFilterOp,
+    BUILTIN_FUNCTIONS, BinaryFilterOp, ExpressionPredicate, FilterExpression, FilterOperator,
+    Predicate, ScalarUdf, UnaryFilterOp, is_builtin_function,
 };
+pub use foreach::{ForEachFeed, ForEachOperator, ForEachSourceOperator};
 pub use join::{
     EqualityCondition, HashJoinOperator, HashKey, JoinCondition, JoinType, NestedLoopJoinOperator,
 };
 pub use limit::{LimitOperator, LimitSkipOperator, SkipOperator};
-pub use merge::MergeOperator;
+pub use merge::{MergeEdge, MergeNode, MergeOperator};
 pub use mutation::{
     AddLabelOperator, CreateEdgeOperator, CreateNodeOperator, DeleteEdgeOperator,
-    DeleteNodeOperator, PropertySource, RemoveLabelOperator, SetPropertyOperator,
+    DeleteNodeOperator, EdgeMultiplicity, PropertyNormalizer, PropertySource, RemoveLabelOperator,
+    SetPropertyOperator,
 };
 pub use project::{ProjectExpr, ProjectOperator};
 pub use push::{
@@ -60,16 +64,17 @@ pub use push::{
     LimitPushOperator, ProjectPushOperator, SkipLimitPushOperator, SkipPushOperator,
     SortPushOperator, SpillableAggregatePushOperator, SpillableSortPushOperator,
 };
-pub use scan::ScanOperator;
-pub use shortest_path::ShortestPathOperator;
-pub use sort::{NullOrder, SortDirection, SortKey, SortOperator};
+pub use scan::{ScanOperator, ZoneFilter, ZoneMapCounters};
+pub use shortest_path::{FoundPath, PathError, PathFinder, ShortestPathOperator};
+pub(crate) use sort::compare_rows;
+pub use sort::{NullOrder, SortDirection, SortKey, SortOperator, TopNOperator};
 pub use union::UnionOperator;
 pub use unwind::UnwindOperator;
 pub use variable_length_expand::VariableLengthExpandOperator;
 
 use thiserror::Error;
 
-use super::DataChunk;
+use super::{DataChunk, SharedMemoryContext};
 
 /// Result of executing an operator.
 pub type OperatorResult = Result<Option<DataChunk>, OperatorError>;
@@ -91,6 +96,94 @@ pub enum OperatorError {
     /// Execution error.
     #[error("execution error: {0}")]
     Execution(String),
+    /// The query's memory budget can't cover what an operator needs.
+    #[error("memory limit exceeded: could not reserve {requested} more bytes")]
+    MemoryLimitExceeded {
+        /// Bytes the operator asked for.
+        requested: usize,
+    },
+    /// The query was cancelled through its cancellation token.
+    #[error("query cancelled")]
+    Cancelled,
+    /// The query ran past its deadline.
+    #[error("query timed out")]
+    TimedOut,
+    /// Another open transaction is writing the same node or edge.
+    #[error("write conflict: {0}")]
+    WriteConflict(String),
+}
+
+impl From<grafeo_common::utils::error::Error> for OperatorError {
+    fn from(err: grafeo_common::utils::error::Error) -> Self {
+        use grafeo_common::utils::error::{Error, TransactionError};
+
+        match err {
+            Error::Transaction(TransactionError::WriteConflict(msg)) => {
+                OperatorError::WriteConflict(msg)
+            }
+            err => OperatorError::Execution(err.to_string()),
+        }
+    }
+}
+
+/// Smallest initial capacity for join and aggregation hash tables.
+pub const MIN_HASH_TABLE_CAPACITY: usize = 16;
+
+/// Largest initial capacity for join and aggregation hash tables.
+///
+/// Caps what a badly overestimated input can reserve before any row arrives;
+/// past this the table simply grows as usual.
+pub const MAX_HASH_TABLE_CAPACITY: usize = 1 << 20;
+
+/// Picks an initial hash table capacity from an estimated row count.
+///
+/// The estimate is clamped to
+/// [`MIN_HASH_TABLE_CAPACITY`]..=[`MAX_HASH_TABLE_CAPACITY`]; a missing or
+/// nonsensical estimate (NaN, negative) gets the minimum.
+#[must_use]
+pub fn hash_table_capacity(estimated_rows: f64) -> usize {
+    if estimated_rows.is_nan() || estimated_rows < MIN_HASH_TABLE_CAPACITY as f64 {
+        return MIN_HASH_TABLE_CAPACITY;
+    }
+    if estimated_rows >= MAX_HASH_TABLE_CAPACITY as f64 {
+        return MAX_HASH_TABLE_CAPACITY;
+    }
+    estimated_rows.ceil() as usize
+}
+
+/// Rough in-memory size of a buffered chunk: one `Value` slot per cell.
+pub(crate) fn chunk_memory_size(chunk: &DataChunk) -> usize {
+    chunk.row_count() * chunk.column_count() * std::mem::size_of::<grafeo_common::types::Value>()
+}
+
+/// Records memory an operator has used against the query's budget.
+///
+/// Does nothing when the operator has no memory context.
+pub(crate) fn account_memory(
+    memory: Option<&SharedMemoryContext>,
+    bytes: usize,
+) -> Result<(), OperatorError> {
+    match memory {
+        Some(memory) if !memory.lock().account(bytes) => {
+            Err(OperatorError::MemoryLimitExceeded { requested: bytes })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Reserves memory before a bulk allocation, such as growing a hash table.
+///
+/// Does nothing when the operator has no memory context.
+pub(crate) fn reserve_memory(
+    memory: Option<&SharedMemoryContext>,
+    bytes: usize,
+) -> Result<(), OperatorError> {
+    match memory {
+        Some(memory) if !memory.lock().reserve(bytes) => {
+            Err(OperatorError::MemoryLimitExceeded { requested: bytes })
+        }
+        _ => Ok(()),
+    }
 }
 
 /// The core trait for pull-based operators.
@@ -107,3 +200,18 @@ pub trait Operator: Send + Sync {
     /// Returns a name for debugging/explain output.
     fn name(&self) -> &'static str;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_table_capacity_is_clamped() {
+        assert_eq!(hash_table_capacity(f64::NAN), MIN_HASH_TABLE_CAPACITY);
+        assert_eq!(hash_table_capacity(-5.0), MIN_HASH_TABLE_CAPACITY);
+        assert_eq!(hash_table_capacity(3.0), MIN_HASH_TABLE_CAPACITY);
+        assert_eq!(hash_table_capacity(1_000.4), 1_001);
+        assert_eq!(hash_table_capacity(1e12), MAX_HASH_TABLE_CAPACITY);
+        assert_eq!(hash_table_capacity(f64::INFINITY), MAX_HASH_TABLE_CAPACITY);
+    }
+}
@@ -6,12 +6,14 @@
 //! - `DeleteNodeOperator`: Deletes nodes
 //! - `DeleteEdgeOperator`: Deletes edges
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
-use grafeo_common::types::{EdgeId, EpochId, LogicalType, NodeId, TxId, Value};
+use grafeo_common::types::{EdgeId, EpochId, LogicalType, NodeId, PropertyKey, TxId, Value};
 
 use super::{Operator, OperatorError, OperatorResult};
-use crate::execution::chunk::DataChunkBuilder;
+use crate::execution::chunk::{DataChunk, DataChunkBuilder};
+use crate::graph::Direction;
 use crate::graph::lpg::LpgStore;
 
 /// Operator that creates new nodes.
@@ -46,6 +48,87 @@ pub enum PropertySource {
     Column(usize),
     /// Use a constant value.
     Constant(Value),
+    /// Get an entry of a map held in an input column, as in `row.name`.
+    ///
+    /// Missing keys and non-map values resolve to null.
+    MapEntry(usize, String),
+    /// Get a value from another source and normalize it before storing.
+    Normalized(Box<PropertySource>, PropertyNormalizer),
+}
+
+impl PropertySource {
+    /// Returns the value for a row of the input.
+    pub(crate) fn resolve(&self, chunk: &DataChunk, row: usize) -> Value {
+        match self {
+            PropertySource::Column(col_idx) => chunk
+                .column(*col_idx)
+                .and_then(|c| c.get_value(row))
+                .unwrap_or(Value::Null),
+            PropertySource::Constant(v) => v.clone(),
+            PropertySource::MapEntry(col_idx, key) => {
+                match chunk.column(*col_idx).and_then(|c| c.get_value(row)) {
+                    Some(Value::Map(map)) => map
+                        .get(&PropertyKey::new(key.as_str()))
+                        .cloned()
+                        .unwrap_or(Value::Null),
+                    _ => Value::Null,
+                }
+            }
+            PropertySource::Normalized(source, normalizer) => {
+                normalizer.apply(source.resolve(chunk, row))
+            }
+        }
+    }
+}
+
+/// Rewrites a property's values before they are stored, like lowercasing
+/// emails or trimming whitespace.
+#[derive(Clone)]
+pub struct PropertyNormalizer(Arc<dyn Fn(Value) -> Value + Send + Sync>);
+
+impl PropertyNormalizer {
+    /// Creates a normalizer from a function.
+    pub fn new(function: impl Fn(Value) -> Value + Send + Sync + 'static) -> Self {
+        Self(Arc::new(function))
+    }
+
+    /// Normalizes a value.
+    #[must_use]
+    pub fn apply(&self, value: Value) -> Value {
+        (self.0)(value)
+    }
+}
+
+impl std::fmt::Debug for PropertyNormalizer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PropertyNormalizer")
+    }
+}
+
+/// The epoch a node or edge created by `tx` at `epoch` is stamped with.
+///
+/// A transaction's creations stay [`EpochId::PENDING`] until it commits, so
+/// no other transaction sees them early; system writes are visible at once.
+pub(crate) fn creation_epoch(epoch: EpochId, tx: TxId) -> EpochId {
+    if tx == TxId::SYSTEM {
+        epoch
+    } else {
+        EpochId::PENDING
+    }
+}
+
+/// What to do when creating an edge that duplicates an existing one.
+///
+/// A duplicate has the same type and the same source and target nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EdgeMultiplicity {
+    /// Parallel edges are fine - always create a new edge.
+    #[default]
+    Allow,
+    /// At most one edge per type between two nodes - duplicates are an error.
+    Deny,
+    /// Reuse the existing edge and update its properties instead.
+    Upsert,
 }
 
 impl CreateNodeOperator {
@@ -104,18 +187,17 @@ impl Operator for CreateNodeOperator {
                 for row in chunk.selected_indices() {
                     // Create the node with MVCC versioning
                     let label_refs: Vec<&str> = self.labels.iter().map(String::as_str).collect();
-                    let node_id = self.store.create_node_versioned(&label_refs, epoch, tx);
+                    let node_id = self.store.create_node_versioned(
+                        &label_refs,
+                        creation_epoch(epoch, tx),
+                        tx,
+                    );
 
                     // Set properties
                     for (prop_name, source) in &self.properties {
-                        let value = match source {
-                            PropertySource::Column(col_idx) => chunk
-                                .column(*col_idx)
-                                .and_then(|c| c.get_value(row))
-                                .unwrap_or(Value::Null),
-                            PropertySource::Constant(v) => v.clone(),
-                        };
-                        self.store.set_node_property(node_id, prop_name, value);
+                        let value = source.resolve(&chunk, row);
+                        self.store
+                            .set_node_property_versioned(node_id, prop_name, value, tx)?;
                     }
 
                     // Copy input columns to output
@@ -153,13 +235,19 @@ impl Operator for CreateNodeOperator {
 
             // Create the node with MVCC versioning
             let label_refs: Vec<&str> = self.labels.iter().map(String::as_str).collect();
-            let node_id = self.store.create_node_versioned(&label_refs, epoch, tx);
+            let node_id =
+                self.store
+                    .create_node_versioned(&label_refs, creation_epoch(epoch, tx), tx);
 
             // Set properties from constants only
             for (prop_name, source) in &self.properties {
                 if let PropertySource::Constant(value) = source {
-                    self.store
-                        .set_node_property(node_id, prop_name, value.clone());
+                    self.store.set_node_property_versioned(
+                        node_id,
+                        prop_name,
+                        value.clone(),
+                        tx,
+                    )?;
                 }
             }
 
@@ -204,6 +292,8 @@ pub struct CreateEdgeOperator {
     output_schema: Vec<LogicalType>,
     /// Column index for the created edge variable (if any).
     output_column: Option<usize>,
+    /// How duplicates of an existing edge are handled.
+    multiplicity: EdgeMultiplicity,
     /// Epoch for MVCC versioning.
     viewing_epoch: Option<EpochId>,
     /// Transaction ID for MVCC versioning.
@@ -232,17 +322,49 @@ impl CreateEdgeOperator {
             properties,
             output_schema,
             output_column,
+            multiplicity: EdgeMultiplicity::Allow,
             viewing_epoch: None,
             tx_id: None,
         }
     }
 
+    /// Sets how duplicates of an existing edge are handled.
+    pub fn with_multiplicity(mut self, multiplicity: EdgeMultiplicity) -> Self {
+        self.multiplicity = multiplicity;
+        self
+    }
+
     /// Sets the transaction context for MVCC versioning.
     pub fn with_tx_context(mut self, epoch: EpochId, tx_id: Option<TxId>) -> Self {
         self.viewing_epoch = Some(epoch);
         self.tx_id = tx_id;
         self
     }
+
+    /// Finds an existing edge of this operator's type from `from` to `to`,
+    /// as transaction `tx` sees the graph at `epoch`.
+    ///
+    /// Takes the edge type's write lock first, so no other transaction can
+    /// create such an edge between this check and this transaction's end.
+    fn find_existing(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        epoch: EpochId,
+        tx: TxId,
+    ) -> Result<Option<EdgeId>, OperatorError> {
+        self.store.lock_edge_type(&self.edge_type, tx)?;
+        Ok(self
+            .store
+            .edges_from(from, Direction::Outgoing)
+            .filter(|&(target, _)| target == to)
+            .map(|(_, edge_id)| edge_id)
+            .find(|&edge_id| {
+                self.store
+                    .edge_type_versioned(edge_id, epoch, tx)
+                    .is_some_and(|t| *t == *self.edge_type)
+            }))
+    }
 }
 
 impl Operator for CreateEdgeOperator {
@@ -294,25 +416,36 @@ impl Operator for CreateEdgeOperator {
                     }
                 };
 
-                // Create the edge with MVCC versioning
-                let edge_id = self.store.create_edge_versioned(
-                    from_node_id,
-                    to_node_id,
-                    &self.edge_type,
-                    epoch,
-                    tx,
-                );
+                let existing = match self.multiplicity {
+                    EdgeMultiplicity::Allow => None,
+                    EdgeMultiplicity::Deny | EdgeMultiplicity::Upsert => {
+                        self.find_existing(from_node_id, to_node_id, epoch, tx)?
+                    }
+                };
+
+                let edge_id = match (existing, self.multiplicity) {
+                    (Some(_), EdgeMultiplicity::Deny) => {
+                        return Err(OperatorError::Execution(format!(
+                            "duplicate {} edge from {} to {}",
+                            self.edge_type, from_node_id.0, to_node_id.0
+                        )));
+                    }
+                    (Some(edge_id), _) => edge_id,
+                    // Create the edge with MVCC versioning
+                    (None, _) => self.store.create_edge_versioned(
+                        from_node_id,
+                        to_node_id,
+                        &self.edge_type,
+                        creation_epoch(epoch, tx),
+                        tx,
+                    ),
+                };
 
                 // Set properties
                 for (prop_name, source) in &self.properties {
-                    let value = match source {
-                        PropertySource::Column(col_idx) => chunk
-                            .column(*col_idx)
-                            .and_then(|c| c.get_value(row))
-                            .unwrap_or(Value::Null),
-                        PropertySource::Constant(v) => v.clone(),
-                    };
-                    self.store.set_edge_property(edge_id, prop_name, value);
+                    let value = source.resolve(&chunk, row);
+                    self.store
+                        .set_edge_property_versioned(edge_id, prop_name, value, tx)?;
                 }
 
                 // Copy input columns
@@ -366,8 +499,7 @@ pub struct DeleteNodeOperator {
     detach: bool,
     /// Epoch for MVCC versioning.
     viewing_epoch: Option<EpochId>,
-    /// Transaction ID for MVCC versioning (reserved for future use).
-    #[allow(dead_code)]
+    /// Transaction ID for MVCC versioning.
     tx_id: Option<TxId>,
 }
 
@@ -405,6 +537,7 @@ impl Operator for DeleteNodeOperator {
         let epoch = self
             .viewing_epoch
             .unwrap_or_else(|| self.store.current_epoch());
+        let tx = self.tx_id.unwrap_or(TxId::SYSTEM);
 
         if let Some(chunk) = self.input.next()? {
             let mut deleted_count = 0;
@@ -429,12 +562,11 @@ impl Operator for DeleteNodeOperator {
 
                 if self.detach {
                     // Delete all connected edges first
-                    // Note: Edge deletion will use epoch internally
-                    self.store.delete_node_edges(node_id);
+                    self.store.delete_node_edges_versioned(node_id, epoch, tx)?;
                 }
 
                 // Delete the node with MVCC versioning
-                if self.store.delete_node_at_epoch(node_id, epoch) {
+                if self.store.delete_node_versioned(node_id, epoch, tx)? {
                     deleted_count += 1;
                 }
             }
@@ -472,8 +604,7 @@ pub struct DeleteEdgeOperator {
     output_schema: Vec<LogicalType>,
     /// Epoch for MVCC versioning.
     viewing_epoch: Option<EpochId>,
-    /// Transaction ID for MVCC versioning (reserved for future use).
-    #[allow(dead_code)]
+    /// Transaction ID for MVCC versioning.
     tx_id: Option<TxId>,
 }
 
@@ -509,6 +640,7 @@ impl Operator for DeleteEdgeOperator {
         let epoch = self
             .viewing_epoch
             .unwrap_or_else(|| self.store.current_epoch());
+        let tx = self.tx_id.unwrap_or(TxId::SYSTEM);
 
         if let Some(chunk) = self.input.next()? {
             let mut deleted_count = 0;
@@ -532,7 +664,7 @@ impl Operator for DeleteEdgeOperator {
                 };
 
                 // Delete the edge with MVCC versioning
-                if self.store.delete_edge_at_epoch(edge_id, epoch) {
+                if self.store.delete_edge_versioned(edge_id, epoch, tx)? {
                     deleted_count += 1;
                 }
             }
@@ -570,6 +702,10 @@ pub struct AddLabelOperator {
     labels: Vec<String>,
     /// Output schema.
     output_schema: Vec<LogicalType>,
+    /// Epoch for MVCC versioning.
+    viewing_epoch: Option<EpochId>,
+    /// Transaction ID for MVCC versioning.
+    tx_id: Option<TxId>,
 }
 
 impl AddLabelOperator {
@@ -587,12 +723,26 @@ impl AddLabelOperator {
             node_column,
             labels,
             output_schema,
+            viewing_epoch: None,
+            tx_id: None,
         }
     }
+
+    /// Sets the transaction context for MVCC versioning.
+    pub fn with_tx_context(mut self, epoch: EpochId, tx_id: Option<TxId>) -> Self {
+        self.viewing_epoch = Some(epoch);
+        self.tx_id = tx_id;
+        self
+    }
 }
 
 impl Operator for AddLabelOperator {
     fn next(&mut self) -> OperatorResult {
+        let epoch = self
+            .viewing_epoch
+            .unwrap_or_else(|| self.store.current_epoch());
+        let tx = self.tx_id.unwrap_or(TxId::SYSTEM);
+
         if let Some(chunk) = self.input.next()? {
             let mut updated_count = 0;
 
@@ -616,7 +766,7 @@ impl Operator for AddLabelOperator {
 
                 // Add all labels
                 for label in &self.labels {
-                    if self.store.add_label(node_id, label) {
+                    if self.store.add_label_versioned(node_id, label, epoch, tx)? {
                         updated_count += 1;
                     }
                 }
@@ -655,6 +805,10 @@ pub struct RemoveLabelOperator {
     labels: Vec<String>,
     /// Output schema.
     output_schema: Vec<LogicalType>,
+    /// Epoch for MVCC versioning.
+    viewing_epoch: Option<EpochId>,
+    /// Transaction ID for MVCC versioning.
+    tx_id: Option<TxId>,
 }
 
 impl RemoveLabelOperator {
@@ -672,12 +826,26 @@ impl RemoveLabelOperator {
             node_column,
             labels,
             output_schema,
+            viewing_epoch: None,
+            tx_id: None,
         }
     }
+
+    /// Sets the transaction context for MVCC versioning.
+    pub fn with_tx_context(mut self, epoch: EpochId, tx_id: Option<TxId>) -> Self {
+        self.viewing_epoch = Some(epoch);
+        self.tx_id = tx_id;
+        self
+    }
 }
 
 impl Operator for RemoveLabelOperator {
     fn next(&mut self) -> OperatorResult {
+        let epoch = self
+            .viewing_epoch
+            .unwrap_or_else(|| self.store.current_epoch());
+        let tx = self.tx_id.unwrap_or(TxId::SYSTEM);
+
         if let Some(chunk) = self.input.next()? {
             let mut updated_count = 0;
 
@@ -701,7 +869,10 @@ impl Operator for RemoveLabelOperator {
 
                 // Remove all labels
                 for label in &self.labels {
-                    if self.store.remove_label(node_id, label) {
+                    if self
+                        .store
+                        .remove_label_versioned(node_id, label, epoch, tx)?
+                    {
                         updated_count += 1;
                     }
                 }
@@ -743,8 +914,18 @@ pub struct SetPropertyOperator {
     is_edge: bool,
     /// Properties to set (name -> source).
     properties: Vec<(String, PropertySource)>,
+    /// Whether to remove properties not being set.
+    replace: bool,
+    /// Whether to add the values to the current ones instead of overwriting.
+    increment: bool,
+    /// Only update rows whose column (index) holds this Bool value.
+    condition: Option<(usize, bool)>,
     /// Output schema.
     output_schema: Vec<LogicalType>,
+    /// Epoch for MVCC versioning.
+    viewing_epoch: Option<EpochId>,
+    /// Transaction ID for MVCC versioning.
+    tx_id: Option<TxId>,
 }
 
 impl SetPropertyOperator {
@@ -762,7 +943,12 @@ impl SetPropertyOperator {
             entity_column: node_column,
             is_edge: false,
             properties,
+            replace: false,
+            increment: false,
+            condition: None,
             output_schema,
+            viewing_epoch: None,
+            tx_id: None,
         }
     }
 
@@ -780,8 +966,142 @@ impl SetPropertyOperator {
             entity_column: edge_column,
             is_edge: true,
             properties,
+            replace: false,
+            increment: false,
+            condition: None,
             output_schema,
+            viewing_epoch: None,
+            tx_id: None,
+        }
+    }
+
+    /// Replaces all of the entity's properties instead of merging into them.
+    ///
+    /// Properties that aren't being set are removed, as in `SET n = {...}`.
+    pub fn with_replace(mut self) -> Self {
+        self.replace = true;
+        self
+    }
+
+    /// Adds the values to the entity's current ones instead of overwriting
+    /// them, as in `SET n.views += 1`.
+    ///
+    /// Each addition is atomic, so concurrent increments all count. A
+    /// missing property starts from the value being added.
+    pub fn with_increment(mut self) -> Self {
+        self.increment = true;
+        self
+    }
+
+    /// Only updates rows whose `column` holds `expected`; other rows pass
+    /// through untouched.
+    ///
+    /// MERGE uses this for ON CREATE SET and ON MATCH SET, keyed on the
+    /// merge's created column.
+    pub fn with_condition(mut self, column: usize, expected: bool) -> Self {
+        self.condition = Some((column, expected));
+        self
+    }
+
+    /// Sets the transaction context for MVCC versioning.
+    pub fn with_tx_context(mut self, epoch: EpochId, tx_id: Option<TxId>) -> Self {
+        self.viewing_epoch = Some(epoch);
+        self.tx_id = tx_id;
+        self
+    }
+
+    fn epoch(&self) -> EpochId {
+        self.viewing_epoch
+            .unwrap_or_else(|| self.store.current_epoch())
+    }
+
+    fn tx(&self) -> TxId {
+        self.tx_id.unwrap_or(TxId::SYSTEM)
+    }
+
+    /// Returns the entity's properties as this operator's reader sees them.
+    fn current_properties(&self, entity_id: u64) -> BTreeMap<PropertyKey, Value> {
+        if self.is_edge {
+            self.store
+                .get_edge_versioned(EdgeId(entity_id), self.epoch(), self.tx())
+                .map(|edge| edge.properties)
+                .unwrap_or_default()
+        } else {
+            self.store
+                .get_node_versioned(NodeId(entity_id), self.epoch(), self.tx())
+                .map(|node| node.properties)
+                .unwrap_or_default()
+        }
+    }
+
+    /// Sets one of the entity's properties.
+    fn set_property(&self, entity_id: u64, name: &str, value: Value) -> Result<(), OperatorError> {
+        if self.is_edge {
+            self.store
+                .set_edge_property_versioned(EdgeId(entity_id), name, value, self.tx())?;
+        } else {
+            self.store
+                .set_node_property_versioned(NodeId(entity_id), name, value, self.tx())?;
+        }
+        Ok(())
+    }
+
+    /// Adds `delta` to one of the entity's properties.
+    fn increment_property(
+        &self,
+        entity_id: u64,
+        name: &str,
+        delta: &Value,
+    ) -> Result<(), OperatorError> {
+        let sum = if self.is_edge {
+            self.store.increment_edge_property_versioned(
+                EdgeId(entity_id),
+                name,
+                delta,
+                self.tx(),
+            )?
+        } else {
+            self.store.increment_node_property_versioned(
+                NodeId(entity_id),
+                name,
+                delta,
+                self.tx(),
+            )?
+        };
+        if sum.is_some() {
+            return Ok(());
+        }
+
+        let current = self
+            .current_properties(entity_id)
+            .remove(&PropertyKey::new(name));
+        Err(OperatorError::TypeMismatch {
+            expected: format!("numbers to add for property '{name}'"),
+            found: format!("{current:?} += {delta:?}"),
+        })
+    }
+
+    /// Removes the entity's properties that aren't among those being set.
+    fn remove_unset_properties(&self, entity_id: u64) -> Result<(), OperatorError> {
+        for key in self.current_properties(entity_id).into_keys() {
+            if self.properties.iter().any(|(name, _)| name == key.as_str()) {
+                continue;
+            }
+            if self.is_edge {
+                self.store.remove_edge_property_versioned(
+                    EdgeId(entity_id),
+                    key.as_str(),
+                    self.tx(),
+                )?;
+            } else {
+                self.store.remove_node_property_versioned(
+                    NodeId(entity_id),
+                    key.as_str(),
+                    self.tx(),
+                )?;
+            }
         }
+        Ok(())
     }
 }
 
@@ -802,8 +1122,15 @@ impl Operator for SetPropertyOperator {
                         ))
                     })?;
 
+                let applies = self.condition.is_none_or(|(column, expected)| {
+                    chunk.column(column).and_then(|c| c.get_value(row))
+                        == Some(Value::Bool(expected))
+                });
+
                 let entity_id = match entity_val {
                     Value::Int64(id) => id as u64,
+                    // Rows the update skips may hold anything
+                    _ if !applies => 0,
                     _ => {
                         return Err(OperatorError::TypeMismatch {
                             expected: "Int64 (entity ID)".to_string(),
@@ -812,22 +1139,18 @@ impl Operator for SetPropertyOperator {
                     }
                 };
 
+                if applies && self.replace {
+                    self.remove_unset_properties(entity_id)?;
+                }
+
                 // Set all properties
-                for (prop_name, source) in &self.properties {
-                    let value = match source {
-                        PropertySource::Column(col_idx) => chunk
-                            .column(*col_idx)
-                            .and_then(|c| c.get_value(row))
-                            .unwrap_or(Value::Null),
-                        PropertySource::Constant(v) => v.clone(),
-                    };
-
-                    if self.is_edge {
-                        self.store
-                            .set_edge_property(EdgeId(entity_id), prop_name, value);
+                for (prop_name, source) in self.properties.iter().filter(|_| applies) {
+                    let value = source.resolve(&chunk, row);
+
+                    if self.increment {
+                        self.increment_property(entity_id, prop_name, &value)?;
                     } else {
-                        self.store
-                            .set_node_property(NodeId(entity_id), prop_name, value);
+                        self.set_property(entity_id, prop_name, value)?;
                     }
                 }
 
@@ -947,6 +1270,146 @@ mod tests {
         assert_eq!(store.edge_count(), 1);
     }
 
+    /// Input operator yielding one (from, to) row per call to `next`.
+    struct PairInput {
+        chunk: Option<DataChunk>,
+    }
+
+    impl PairInput {
+        fn new(from: NodeId, to: NodeId) -> Self {
+            let mut builder = DataChunkBuilder::new(&[LogicalType::Int64, LogicalType::Int64]);
+            builder.column_mut(0).unwrap().push_int64(from.0 as i64);
+            builder.column_mut(1).unwrap().push_int64(to.0 as i64);
+            builder.advance_row();
+            Self {
+                chunk: Some(builder.finish()),
+            }
+        }
+    }
+
+    impl Operator for PairInput {
+        fn next(&mut self) -> OperatorResult {
+            Ok(self.chunk.take())
+        }
+        fn reset(&mut self) {}
+        fn name(&self) -> &'static str {
+            "PairInput"
+        }
+    }
+
+    fn create_knows(
+        store: &Arc<LpgStore>,
+        from: NodeId,
+        to: NodeId,
+        since: i64,
+        multiplicity: EdgeMultiplicity,
+    ) -> Result<(), OperatorError> {
+        create_knows_in(store, from, to, since, multiplicity, None)
+    }
+
+    /// Like `create_knows`, as transaction `tx` if given.
+    fn create_knows_in(
+        store: &Arc<LpgStore>,
+        from: NodeId,
+        to: NodeId,
+        since: i64,
+        multiplicity: EdgeMultiplicity,
+        tx: Option<TxId>,
+    ) -> Result<(), OperatorError> {
+        let op = CreateEdgeOperator::new(
+            Arc::clone(store),
+            Box::new(PairInput::new(from, to)),
+            0,
+            1,
+            "KNOWS".to_string(),
+            vec![(
+                "since".to_string(),
+                PropertySource::Constant(Value::Int64(since)),
+            )],
+            vec![LogicalType::Int64, LogicalType::Int64],
+            None,
+        )
+        .with_multiplicity(multiplicity);
+        let mut op = match tx {
+            Some(tx) => op.with_tx_context(store.current_epoch(), Some(tx)),
+            None => op,
+        };
+        op.next().map(|_| ())
+    }
+
+    #[test]
+    fn test_create_edge_multiplicity_allow() {
+        let store = create_test_store();
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+
+        create_knows(&store, a, b, 2020, EdgeMultiplicity::Allow).unwrap();
+        create_knows(&store, a, b, 2021, EdgeMultiplicity::Allow).unwrap();
+
+        assert_eq!(store.edge_count(), 2);
+    }
+
+    #[test]
+    fn test_create_edge_multiplicity_deny() {
+        let store = create_test_store();
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+
+        create_knows(&store, a, b, 2020, EdgeMultiplicity::Deny).unwrap();
+        let err = create_knows(&store, a, b, 2021, EdgeMultiplicity::Deny).unwrap_err();
+        assert!(err.to_string().contains("duplicate KNOWS edge"));
+
+        // The reverse direction and other types are not duplicates
+        create_knows(&store, b, a, 2021, EdgeMultiplicity::Deny).unwrap();
+        store.create_edge(a, b, "WORKS_WITH");
+        assert_eq!(store.edge_count(), 3);
+    }
+
+    #[test]
+    fn test_create_edge_multiplicity_upsert() {
+        let store = create_test_store();
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+
+        create_knows(&store, a, b, 2020, EdgeMultiplicity::Upsert).unwrap();
+        create_knows(&store, a, b, 2021, EdgeMultiplicity::Upsert).unwrap();
+
+        assert_eq!(store.edge_count(), 1);
+        let (_, edge_id) = store.edges_from(a, Direction::Outgoing).next().unwrap();
+        let edge = store.get_edge(edge_id).unwrap();
+        assert_eq!(
+            edge.get_property("since").cloned(),
+            Some(Value::Int64(2021))
+        );
+    }
+
+    #[test]
+    fn test_create_edge_multiplicity_sees_only_own_transaction() {
+        let store = create_test_store();
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+        let (first, second) = (TxId::new(10), TxId::new(11));
+
+        // Another transaction's uncommitted edge isn't a duplicate, but
+        // checking while it's open is a write conflict
+        create_knows_in(&store, a, b, 2020, EdgeMultiplicity::Deny, Some(first)).unwrap();
+        let err =
+            create_knows_in(&store, a, b, 2021, EdgeMultiplicity::Deny, Some(second)).unwrap_err();
+        assert!(matches!(err, OperatorError::WriteConflict(_)), "{err}");
+
+        // An edge the transaction deleted itself isn't a duplicate either
+        let (_, edge_id) = store.edges_from(a, Direction::Outgoing).next().unwrap();
+        assert!(
+            store
+                .delete_edge_versioned(edge_id, store.current_epoch(), first)
+                .unwrap()
+        );
+        create_knows_in(&store, a, b, 2022, EdgeMultiplicity::Deny, Some(first)).unwrap();
+        let err =
+            create_knows_in(&store, a, b, 2023, EdgeMultiplicity::Deny, Some(first)).unwrap_err();
+        assert!(err.to_string().contains("duplicate KNOWS edge"));
+    }
+
     #[test]
     fn test_delete_node() {
         let store = create_test_store();
@@ -992,4 +1455,101 @@ mod tests {
         assert_eq!(deleted, 1);
         assert_eq!(store.node_count(), 0);
     }
+
+    #[test]
+    fn test_set_property_merge_vs_replace() {
+        struct MockInput {
+            chunk: Option<DataChunk>,
+        }
+        impl Operator for MockInput {
+            fn next(&mut self) -> OperatorResult {
+                Ok(self.chunk.take())
+            }
+            fn reset(&mut self) {}
+            fn name(&self) -> &'static str {
+                "MockInput"
+            }
+        }
+
+        let store = create_test_store();
+        let set_name = |node: NodeId, replace: bool| {
+            let mut builder = DataChunkBuilder::new(&[LogicalType::Int64]);
+            builder.column_mut(0).unwrap().push_int64(node.0 as i64);
+            builder.advance_row();
+            let op = SetPropertyOperator::new_for_node(
+                Arc::clone(&store),
+                Box::new(MockInput {
+                    chunk: Some(builder.finish()),
+                }),
+                0,
+                vec![(
+                    "name".to_string(),
+                    PropertySource::Constant(Value::from("Bob")),
+                )],
+                vec![LogicalType::Int64],
+            );
+            let mut op = if replace { op.with_replace() } else { op };
+            op.next().unwrap().unwrap();
+            store.get_node(node).unwrap()
+        };
+
+        let merged =
+            store.create_node_with_props(&["Person"], [("name", "Alice"), ("city", "Oslo")]);
+        let node = set_name(merged, false);
+        assert_eq!(node.get_property("name"), Some(&Value::from("Bob")));
+        assert_eq!(node.get_property("city"), Some(&Value::from("Oslo")));
+
+        let replaced =
+            store.create_node_with_props(&["Person"], [("name", "Alice"), ("city", "Oslo")]);
+        let node = set_name(replaced, true);
+        assert_eq!(node.get_property("name"), Some(&Value::from("Bob")));
+        assert_eq!(node.get_property("city"), None);
+    }
+
+    #[test]
+    fn test_set_property_increment() {
+        struct MockInput {
+            chunk: Option<DataChunk>,
+        }
+        impl Operator for MockInput {
+            fn next(&mut self) -> OperatorResult {
+                Ok(self.chunk.take())
+            }
+            fn reset(&mut self) {}
+            fn name(&self) -> &'static str {
+                "MockInput"
+            }
+        }
+
+        let store = create_test_store();
+        let page = store.create_node_with_props(
+            &["Page"],
+            [("views", Value::Int64(41)), ("title", Value::from("Home"))],
+        );
+        let increment = |name: &str, delta: Value| {
+            let mut builder = DataChunkBuilder::new(&[LogicalType::Int64]);
+            builder.column_mut(0).unwrap().push_int64(page.0 as i64);
+            builder.advance_row();
+            let mut op = SetPropertyOperator::new_for_node(
+                Arc::clone(&store),
+                Box::new(MockInput {
+                    chunk: Some(builder.finish()),
+                }),
+                0,
+                vec![(name.to_string(), PropertySource::Constant(delta))],
+                vec![LogicalType::Int64],
+            )
+            .with_increment();
+            op.next().map(|_| store.get_node(page).unwrap())
+        };
+
+        let node = increment("views", Value::Int64(1)).unwrap();
+        assert_eq!(node.get_property("views"), Some(&Value::Int64(42)));
+
+        let node = increment("likes", Value::Int64(1)).unwrap();
+        assert_eq!(node.get_property("likes"), Some(&Value::Int64(1)));
+
+        let result = increment("title", Value::Int64(1));
+        assert!(matches!(result, Err(OperatorError::TypeMismatch { .. })));
+    }
 }
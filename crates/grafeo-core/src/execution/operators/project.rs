@@ -4,7 +4,7 @@ use super::filter::{ExpressionPredicate, FilterExpression};
 use super::{Operator, OperatorError, OperatorResult};
 use crate::execution::DataChunk;
 use crate::graph::lpg::LpgStore;
-use grafeo_common::types::{LogicalType, Value};
+use grafeo_common::types::{EpochId, LogicalType, TxId, Value};
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -45,6 +45,10 @@ pub struct ProjectOperator {
     output_types: Vec<LogicalType>,
     /// Optional store for property access.
     store: Option<Arc<LpgStore>>,
+    /// Epoch to read properties at, the latest if unset.
+    viewing_epoch: Option<EpochId>,
+    /// Transaction whose own writes are visible.
+    tx_id: Option<TxId>,
 }
 
 impl ProjectOperator {
@@ -60,6 +64,8 @@ impl ProjectOperator {
             projections,
             output_types,
             store: None,
+            viewing_epoch: None,
+            tx_id: None,
         }
     }
 
@@ -76,9 +82,19 @@ impl ProjectOperator {
             projections,
             output_types,
             store: Some(store),
+            viewing_epoch: None,
+            tx_id: None,
         }
     }
 
+    /// Sets the transaction context property lookups read in.
+    #[must_use]
+    pub fn with_tx_context(mut self, epoch: EpochId, tx_id: Option<TxId>) -> Self {
+        self.viewing_epoch = Some(epoch);
+        self.tx_id = tx_id;
+        self
+    }
+
     /// Creates a project operator that selects specific columns.
     pub fn select_columns(
         child: Box<dyn Operator>,
@@ -138,17 +154,20 @@ impl Operator for ProjectOperator {
                         OperatorError::Execution("Store required for property access".to_string())
                     })?;
 
+                    let epoch = self.viewing_epoch.unwrap_or_else(|| store.current_epoch());
+                    let tx = self.tx_id.unwrap_or(TxId::SYSTEM);
+
                     // Extract property for each row
                     for row in input.selected_indices() {
                         // Try to get node ID first, then edge ID
                         let value = if let Some(node_id) = input_col.get_node_id(row) {
                             store
-                                .get_node(node_id)
+                                .get_node_versioned(node_id, epoch, tx)
                                 .and_then(|node| node.get_property(property).cloned())
                                 .unwrap_or(Value::Null)
                         } else if let Some(edge_id) = input_col.get_edge_id(row) {
                             store
-                                .get_edge(edge_id)
+                                .get_edge_versioned(edge_id, epoch, tx)
                                 .and_then(|edge| edge.get_property(property).cloned())
                                 .unwrap_or(Value::Null)
                         } else {
@@ -194,16 +213,22 @@ impl Operator for ProjectOperator {
                     })?;
 
                     // Use the ExpressionPredicate for expression evaluation
-                    let evaluator = ExpressionPredicate::new(
+                    let mut evaluator = ExpressionPredicate::new(
                         expr.clone(),
                         variable_columns.clone(),
                         Arc::clone(store),
                     );
+                    if let Some(epoch) = self.viewing_epoch {
+                        evaluator = evaluator.with_tx_context(epoch, self.tx_id);
+                    }
 
                     for row in input.selected_indices() {
                         let value = evaluator.eval_at(&input, row).unwrap_or(Value::Null);
                         output_col.push_value(value);
                     }
+                    if let Some(message) = evaluator.error() {
+                        return Err(OperatorError::Execution(message.to_string()));
+                    }
                 }
             }
         }
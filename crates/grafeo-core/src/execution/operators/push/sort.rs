@@ -313,6 +313,7 @@ impl SpillableSortPushOperator {
                         NullOrder::First => crate::execution::spill::NullOrder::First,
                         NullOrder::Last => crate::execution::spill::NullOrder::Last,
                     },
+                    collation: crate::execution::Collation::Binary,
                 })
                 .collect();
 
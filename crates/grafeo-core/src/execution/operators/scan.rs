@@ -2,16 +2,71 @@
 
 use super::{Operator, OperatorResult};
 use crate::execution::DataChunk;
-use crate::graph::lpg::LpgStore;
-use grafeo_common::types::{EpochId, LogicalType, NodeId, TxId};
+use crate::graph::lpg::{CompareOp, LpgStore};
+use grafeo_common::types::{EpochId, LogicalType, NodeId, PropertyKey, TxId, Value};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A property comparison a scan can check against zone maps, such as
+/// `n.age > 30`.
+///
+/// The scan skips a chunk only when zone maps prove no node in it matches;
+/// a filter above the scan still evaluates the predicate row by row.
+#[derive(Debug, Clone)]
+pub struct ZoneFilter {
+    /// The property compared.
+    pub property: PropertyKey,
+    /// The comparison.
+    pub op: CompareOp,
+    /// The value compared against.
+    pub value: Value,
+}
+
+/// Counts how many scan chunks zone maps let a plan skip.
+///
+/// Shared by every filtered scan in a plan. Only scans with at least one
+/// [`ZoneFilter`] record anything.
+#[derive(Debug, Default)]
+pub struct ZoneMapCounters {
+    skipped: AtomicU64,
+    scanned: AtomicU64,
+}
+
+impl ZoneMapCounters {
+    /// Creates zeroed counters.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of chunks skipped without reading them.
+    #[must_use]
+    pub fn skipped(&self) -> u64 {
+        self.skipped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of chunks zone maps couldn't rule out.
+    #[must_use]
+    pub fn scanned(&self) -> u64 {
+        self.scanned.load(Ordering::Relaxed)
+    }
+
+    fn record(&self, skipped: bool) {
+        let counter = if skipped {
+            &self.skipped
+        } else {
+            &self.scanned
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
 
 /// A scan operator that reads nodes from storage.
 pub struct ScanOperator {
     /// The store to scan from.
     store: Arc<LpgStore>,
-    /// Label filter (None = all nodes).
-    label: Option<String>,
+    /// Labels every scanned node must have (empty = all nodes).
+    labels: Vec<String>,
     /// Current position in the scan.
     position: usize,
     /// Batch of node IDs to scan.
@@ -24,6 +79,12 @@ pub struct ScanOperator {
     tx_id: Option<TxId>,
     /// Epoch for version visibility.
     viewing_epoch: Option<EpochId>,
+    /// Predicates checked against zone maps to skip whole chunks.
+    zone_filters: Vec<ZoneFilter>,
+    /// Where skipped and scanned chunks are counted.
+    zone_counters: Option<Arc<ZoneMapCounters>>,
+    /// Sorted node IDs an index narrowed the scan to, if any.
+    candidates: Option<Vec<NodeId>>,
 }
 
 impl ScanOperator {
@@ -31,27 +92,29 @@ impl ScanOperator {
     pub fn new(store: Arc<LpgStore>) -> Self {
         Self {
             store,
-            label: None,
+            labels: Vec::new(),
             position: 0,
             batch: Vec::new(),
             exhausted: false,
             chunk_capacity: 2048,
             tx_id: None,
             viewing_epoch: None,
+            zone_filters: Vec::new(),
+            zone_counters: None,
+            candidates: None,
         }
     }
 
     /// Creates a new scan operator for nodes with a specific label.
     pub fn with_label(store: Arc<LpgStore>, label: impl Into<String>) -> Self {
+        Self::with_labels(store, vec![label.into()])
+    }
+
+    /// Creates a new scan operator for nodes that have all of the given labels.
+    pub fn with_labels(store: Arc<LpgStore>, labels: Vec<String>) -> Self {
         Self {
-            store,
-            label: Some(label.into()),
-            position: 0,
-            batch: Vec::new(),
-            exhausted: false,
-            chunk_capacity: 2048,
-            tx_id: None,
-            viewing_epoch: None,
+            labels,
+            ..Self::new(store)
         }
     }
 
@@ -70,26 +133,72 @@ impl ScanOperator {
         self
     }
 
+    /// Skips chunks that zone maps prove can't satisfy every filter.
+    ///
+    /// Pass counters to see how many chunks were skipped.
+    pub fn with_zone_filters(
+        mut self,
+        filters: Vec<ZoneFilter>,
+        counters: Option<Arc<ZoneMapCounters>>,
+    ) -> Self {
+        self.zone_filters = filters;
+        self.zone_counters = counters;
+        self
+    }
+
+    /// Only scans nodes among `candidates`, such as the nodes an index
+    /// found for a predicate.
+    ///
+    /// The candidates only narrow the scan: nodes still need the scan's
+    /// labels, and a filter above the scan still checks the predicate.
+    pub fn with_candidates(mut self, mut candidates: Vec<NodeId>) -> Self {
+        candidates.sort_unstable();
+        self.candidates = Some(candidates);
+        self
+    }
+
+    /// Returns whether zone maps rule out every node in `ids`.
+    ///
+    /// `ids` is sorted, so checking the range from first to last covers it.
+    fn can_skip(&self, ids: &[NodeId]) -> bool {
+        let (Some(&first), Some(&last)) = (ids.first(), ids.last()) else {
+            return false;
+        };
+        self.zone_filters.iter().any(|filter| {
+            !self.store.node_property_might_match_in(
+                &filter.property,
+                first,
+                last,
+                filter.op,
+                &filter.value,
+            )
+        })
+    }
+
     fn load_batch(&mut self) {
         if !self.batch.is_empty() || self.exhausted {
             return;
         }
 
         // Get nodes, using versioned method if tx context is set
-        let all_ids = match &self.label {
-            Some(label) => self.store.nodes_by_label(label),
-            None => self.store.node_ids(),
+        let labels: Vec<&str> = self.labels.iter().map(String::as_str).collect();
+        let tx = self.tx_id.unwrap_or(TxId::SYSTEM);
+        let mut all_ids = match self.viewing_epoch {
+            // Includes the transaction's own uncommitted nodes
+            Some(epoch) if labels.is_empty() => self.store.node_ids_versioned(epoch, tx),
+            _ => self.store.nodes_with_all_labels(&labels),
         };
+        if let Some(candidates) = &self.candidates {
+            all_ids.retain(|id| candidates.binary_search(id).is_ok());
+        }
 
         // Filter by visibility if we have tx context
-        self.batch = if let Some(epoch) = self.viewing_epoch {
-            let tx = self.tx_id.unwrap_or(TxId::SYSTEM);
-            all_ids
+        self.batch = match self.viewing_epoch {
+            Some(epoch) if !labels.is_empty() => all_ids
                 .into_iter()
                 .filter(|id| self.store.get_node_versioned(*id, epoch, tx).is_some())
-                .collect()
-        } else {
-            all_ids
+                .collect(),
+            _ => all_ids,
         };
 
         if self.batch.is_empty() {
@@ -102,15 +211,32 @@ impl Operator for ScanOperator {
     fn next(&mut self) -> OperatorResult {
         self.load_batch();
 
-        if self.exhausted || self.position >= self.batch.len() {
+        if self.exhausted {
             return Ok(None);
         }
 
+        let mut end;
+        loop {
+            if self.position >= self.batch.len() {
+                return Ok(None);
+            }
+            end = (self.position + self.chunk_capacity).min(self.batch.len());
+            if self.zone_filters.is_empty() {
+                break;
+            }
+            let skip = self.can_skip(&self.batch[self.position..end]);
+            if let Some(counters) = &self.zone_counters {
+                counters.record(skip);
+            }
+            if !skip {
+                break;
+            }
+            self.position = end;
+        }
+
         // Create output chunk with node IDs
         let schema = [LogicalType::Node];
         let mut chunk = DataChunk::with_capacity(&schema, self.chunk_capacity);
-
-        let end = (self.position + self.chunk_capacity).min(self.batch.len());
         let count = end - self.position;
 
         {
@@ -162,6 +288,51 @@ mod tests {
         assert!(next.is_none());
     }
 
+    #[test]
+    fn test_scan_with_candidates() {
+        let store = Arc::new(LpgStore::new());
+
+        store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+        let animal = store.create_node(&["Animal"]);
+
+        let mut scan =
+            ScanOperator::with_label(Arc::clone(&store), "Person").with_candidates(vec![animal, b]);
+        let chunk = scan.next().unwrap().unwrap();
+        assert_eq!(chunk.row_count(), 1);
+        assert_eq!(chunk.column(0).unwrap().get_node_id(0), Some(b));
+        assert!(scan.next().unwrap().is_none());
+
+        let mut scan = ScanOperator::with_label(store, "Person").with_candidates(vec![]);
+        assert!(scan.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_scan_requires_all_labels() {
+        let store = Arc::new(LpgStore::new());
+
+        let both = store.create_node(&["Person", "Employee"]);
+        store.create_node(&["Person"]);
+        store.create_node(&["Employee"]);
+        store.create_node(&["Employee", "Contractor"]);
+
+        let mut scan = ScanOperator::with_labels(
+            Arc::clone(&store),
+            vec!["Person".to_string(), "Employee".to_string()],
+        );
+
+        let chunk = scan.next().unwrap().unwrap();
+        assert_eq!(chunk.row_count(), 1);
+        assert_eq!(chunk.column(0).unwrap().get_node_id(0), Some(both));
+        assert!(scan.next().unwrap().is_none());
+
+        let mut missing = ScanOperator::with_labels(
+            Arc::clone(&store),
+            vec!["Person".to_string(), "Robot".to_string()],
+        );
+        assert!(missing.next().unwrap().is_none());
+    }
+
     #[test]
     fn test_scan_reset() {
         let store = Arc::new(LpgStore::new());
@@ -202,6 +373,29 @@ mod tests {
         assert!(next.is_none());
     }
 
+    #[test]
+    fn test_scan_skips_chunks_with_zone_filters() {
+        let store = Arc::new(LpgStore::new());
+        for i in 0..100 {
+            let id = store.create_node(&["Item"]);
+            store.set_node_property(id, "rank", Value::Int64(i));
+        }
+
+        let counters = Arc::new(ZoneMapCounters::new());
+        let filter = ZoneFilter {
+            property: PropertyKey::new("rank"),
+            op: CompareOp::Ge,
+            value: Value::Int64(1000),
+        };
+        let mut scan = ScanOperator::new(Arc::clone(&store))
+            .with_chunk_capacity(10)
+            .with_zone_filters(vec![filter], Some(Arc::clone(&counters)));
+
+        assert!(scan.next().unwrap().is_none());
+        assert_eq!(counters.skipped(), 10);
+        assert_eq!(counters.scanned(), 0);
+    }
+
     #[test]
     fn test_scan_with_mvcc_context() {
         let store = Arc::new(LpgStore::new());
@@ -1,20 +1,350 @@
 //! Shortest path operator for finding paths between nodes.
 //!
 //! This operator computes shortest paths between source and target nodes
-//! using BFS for unweighted graphs.
+//! using BFS, or Dijkstra's algorithm when edges are weighted by a numeric
+//! property. The search itself is a [`PathFinder`], which callers outside
+//! query execution can use directly.
 
-use super::{Operator, OperatorResult};
+use super::{Operator, OperatorError, OperatorResult};
 use crate::execution::chunk::DataChunkBuilder;
 use crate::graph::Direction;
 use crate::graph::lpg::LpgStore;
-use grafeo_common::types::{LogicalType, NodeId, Value};
-use std::collections::{HashMap, VecDeque};
+use grafeo_common::types::{EdgeId, LogicalType, NodeId, Timestamp, Value};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
 use std::sync::Arc;
+use thiserror::Error;
+
+/// Why a weighted path search couldn't read an edge's weight.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum PathError {
+    /// An edge on the search frontier doesn't have the weight property.
+    #[error("edge {edge} has no '{property}' weight")]
+    MissingWeight {
+        /// The edge.
+        edge: EdgeId,
+        /// The weight property.
+        property: String,
+    },
+    /// The weight property isn't a number.
+    #[error("edge {edge} has a {found} '{property}' weight, expected a number")]
+    NonNumericWeight {
+        /// The edge.
+        edge: EdgeId,
+        /// The weight property.
+        property: String,
+        /// Type name of the value found.
+        found: &'static str,
+    },
+    /// The weight is negative or NaN, which Dijkstra's algorithm can't handle.
+    #[error("edge {edge} has an invalid '{property}' weight {weight}, expected zero or more")]
+    InvalidWeight {
+        /// The edge.
+        edge: EdgeId,
+        /// The weight property.
+        property: String,
+        /// The weight found.
+        weight: f64,
+    },
+}
+
+impl From<PathError> for OperatorError {
+    fn from(err: PathError) -> Self {
+        OperatorError::Execution(err.to_string())
+    }
+}
+
+/// A path found by a [`PathFinder`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundPath {
+    /// Nodes along the path, source first and target last.
+    pub nodes: Vec<NodeId>,
+    /// Edges along the path; `edges[i]` joins `nodes[i]` and `nodes[i + 1]`.
+    pub edges: Vec<EdgeId>,
+    /// Total weight, or the number of edges for an unweighted search.
+    pub cost: f64,
+}
+
+/// Finds a single shortest path between two nodes.
+///
+/// Unweighted searches run breadth-first. Naming a weight property switches
+/// to Dijkstra's algorithm, reading each edge's weight as it's reached; a
+/// missing, non-numeric or negative weight fails the search.
+pub struct PathFinder<'a> {
+    store: &'a LpgStore,
+    direction: Direction,
+    edge_type: Option<&'a str>,
+    weight: Option<&'a str>,
+    max_hops: Option<u32>,
+    as_of: Option<Timestamp>,
+}
+
+impl<'a> PathFinder<'a> {
+    /// Creates an unweighted, unbounded search following edges in `direction`.
+    #[must_use]
+    pub fn new(store: &'a LpgStore, direction: Direction) -> Self {
+        Self {
+            store,
+            direction,
+            edge_type: None,
+            weight: None,
+            max_hops: None,
+            as_of: None,
+        }
+    }
+
+    /// Follows only edges of `edge_type`.
+    #[must_use]
+    pub fn with_edge_type(mut self, edge_type: Option<&'a str>) -> Self {
+        self.edge_type = edge_type;
+        self
+    }
+
+    /// Weighs edges by the numeric property `weight`.
+    #[must_use]
+    pub fn with_weight(mut self, weight: Option<&'a str>) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Ignores paths longer than `max_hops` edges.
+    #[must_use]
+    pub fn with_max_hops(mut self, max_hops: Option<u32>) -> Self {
+        self.max_hops = max_hops;
+        self
+    }
+
+    /// Only traverses edges valid at `at`.
+    #[must_use]
+    pub fn with_as_of(mut self, at: Option<Timestamp>) -> Self {
+        self.as_of = at;
+        self
+    }
+
+    /// Returns the shortest path from `source` to `target`, or `None` if
+    /// there isn't one within the hop limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a weighted search reaches an edge whose weight
+    /// is missing, non-numeric or negative.
+    pub fn find(&self, source: NodeId, target: NodeId) -> Result<Option<FoundPath>, PathError> {
+        match self.weight {
+            Some(weight) => self.dijkstra(source, target, weight),
+            None => Ok(self.breadth_first(source, target)),
+        }
+    }
+
+    fn breadth_first(&self, source: NodeId, target: NodeId) -> Option<FoundPath> {
+        // Each reached node remembers the node and edge it was reached by
+        let mut parents: HashMap<NodeId, Option<(NodeId, EdgeId)>> = HashMap::new();
+        let mut queue: VecDeque<(NodeId, u32)> = VecDeque::new();
+        parents.insert(source, None);
+        queue.push_back((source, 0));
+
+        while let Some((current, depth)) = queue.pop_front() {
+            if current == target {
+                return Some(trace(&parents, target));
+            }
+            if self.max_hops.is_some_and(|max| depth >= max) {
+                continue;
+            }
+            for (neighbor, edge) in self.neighbors(current) {
+                parents.entry(neighbor).or_insert_with(|| {
+                    queue.push_back((neighbor, depth + 1));
+                    Some((current, edge))
+                });
+            }
+        }
+        None
+    }
+
+    fn dijkstra(
+        &self,
+        source: NodeId,
+        target: NodeId,
+        weight: &str,
+    ) -> Result<Option<FoundPath>, PathError> {
+        // With a hop limit, a costlier path may still be the only one that
+        // fits, so a node can be settled again when reached in fewer hops.
+        // States live in `reached` and point back at the state they came from.
+        struct State {
+            node: NodeId,
+            hops: u32,
+            cost: f64,
+            from: Option<(usize, EdgeId)>,
+        }
+        let mut reached = vec![State {
+            node: source,
+            hops: 0,
+            cost: 0.0,
+            from: None,
+        }];
+        let mut fewest_hops: HashMap<NodeId, u32> = HashMap::new();
+        let mut best_cost: HashMap<NodeId, f64> = HashMap::new();
+        let mut frontier = BinaryHeap::new();
+        frontier.push(Reverse(Frontier {
+            cost: 0.0,
+            state: 0,
+        }));
+
+        while let Some(Reverse(Frontier { state, .. })) = frontier.pop() {
+            let State {
+                node, hops, cost, ..
+            } = reached[state];
+            if fewest_hops
+                .get(&node)
+                .is_some_and(|&settled| settled <= hops)
+            {
+                continue;
+            }
+            fewest_hops.insert(node, hops);
+            if node == target {
+                let mut nodes = vec![node];
+                let mut edges = Vec::new();
+                let mut at = state;
+                while let Some((previous, edge)) = reached[at].from {
+                    edges.push(edge);
+                    nodes.push(reached[previous].node);
+                    at = previous;
+                }
+                nodes.reverse();
+                edges.reverse();
+                return Ok(Some(FoundPath { nodes, edges, cost }));
+            }
+            if self.max_hops.is_some_and(|max| hops >= max) {
+                continue;
+            }
+
+            for (neighbor, edge) in self.neighbors(node) {
+                let next_cost = cost + self.edge_weight(edge, weight)?;
+                // Without a hop limit, only a cheaper route is worth queueing
+                if self.max_hops.is_none() {
+                    if best_cost.get(&neighbor).is_some_and(|&c| c <= next_cost) {
+                        continue;
+                    }
+                    best_cost.insert(neighbor, next_cost);
+                }
+                reached.push(State {
+                    node: neighbor,
+                    hops: hops + 1,
+                    cost: next_cost,
+                    from: Some((state, edge)),
+                });
+                frontier.push(Reverse(Frontier {
+                    cost: next_cost,
+                    state: reached.len() - 1,
+                }));
+            }
+        }
+        Ok(None)
+    }
+
+    fn edge_weight(&self, edge: EdgeId, weight: &str) -> Result<f64, PathError> {
+        let value = self
+            .store
+            .get_edge(edge)
+            .and_then(|e| e.get_property(weight).cloned());
+        let number = match value {
+            Some(Value::Int64(n)) => n as f64,
+            Some(Value::Float64(n)) => n,
+            Some(other) => {
+                return Err(PathError::NonNumericWeight {
+                    edge,
+                    property: weight.to_string(),
+                    found: other.type_name(),
+                });
+            }
+            None => {
+                return Err(PathError::MissingWeight {
+                    edge,
+                    property: weight.to_string(),
+                });
+            }
+        };
+        if number.is_nan() || number < 0.0 {
+            return Err(PathError::InvalidWeight {
+                edge,
+                property: weight.to_string(),
+                weight: number,
+            });
+        }
+        Ok(number)
+    }
+
+    /// Neighbours of `node` and the edges leading to them, respecting the
+    /// edge type filter, direction and validity time.
+    fn neighbors(&self, node: NodeId) -> Vec<(NodeId, EdgeId)> {
+        self.store
+            .edges_from(node, self.direction)
+            .filter(|&(_, edge)| {
+                if let Some(at) = self.as_of {
+                    if !self.store.is_edge_valid_at(edge, at) {
+                        return false;
+                    }
+                }
+                match self.edge_type {
+                    Some(filter_type) => self
+                        .store
+                        .edge_type(edge)
+                        .is_some_and(|edge_type| self.store.label_matches(&edge_type, filter_type)),
+                    None => true,
+                }
+            })
+            .collect()
+    }
+}
+
+/// A queued Dijkstra state, ordered by cost and then by when it was reached
+/// so ties resolve the same way every time.
+struct Frontier {
+    cost: f64,
+    state: usize,
+}
+
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Frontier {}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cost
+            .total_cmp(&other.cost)
+            .then(self.state.cmp(&other.state))
+    }
+}
+
+/// Walks breadth-first parent links back from `target`.
+fn trace(parents: &HashMap<NodeId, Option<(NodeId, EdgeId)>>, target: NodeId) -> FoundPath {
+    let mut nodes = vec![target];
+    let mut edges = Vec::new();
+    let mut at = target;
+    while let Some(&Some((previous, edge))) = parents.get(&at) {
+        edges.push(edge);
+        nodes.push(previous);
+        at = previous;
+    }
+    nodes.reverse();
+    edges.reverse();
+    let cost = edges.len() as f64;
+    FoundPath { nodes, edges, cost }
+}
 
 /// Operator that finds shortest paths between source and target nodes.
 ///
 /// For each input row containing source and target nodes, this operator
-/// computes the shortest path and outputs the path as a value.
+/// computes the shortest path and outputs its length, and optionally the
+/// path itself as a list of edge ids.
 pub struct ShortestPathOperator {
     /// The graph store.
     store: Arc<LpgStore>,
@@ -30,6 +360,12 @@ pub struct ShortestPathOperator {
     direction: Direction,
     /// Whether to find all shortest paths (vs. just one).
     all_paths: bool,
+    /// Longest path to consider, in edges.
+    max_hops: Option<u32>,
+    /// Whether to output the path's edges after its length.
+    output_path: bool,
+    /// Only traverse edges valid at this time, if set.
+    as_of: Option<Timestamp>,
     /// Whether the operator has been exhausted.
     exhausted: bool,
 }
@@ -52,6 +388,9 @@ impl ShortestPathOperator {
             edge_type,
             direction,
             all_paths: false,
+            max_hops: None,
+            output_path: false,
+            as_of: None,
             exhausted: false,
         }
     }
@@ -62,36 +401,34 @@ impl ShortestPathOperator {
         self
     }
 
-    /// Finds the shortest path between source and target using BFS.
-    /// Returns the path length (number of edges).
-    fn find_shortest_path(&self, source: NodeId, target: NodeId) -> Option<i64> {
-        if source == target {
-            return Some(0);
-        }
-
-        let mut visited: HashMap<NodeId, i64> = HashMap::new();
-        let mut queue: VecDeque<(NodeId, i64)> = VecDeque::new();
-
-        visited.insert(source, 0);
-        queue.push_back((source, 0));
-
-        while let Some((current, depth)) = queue.pop_front() {
-            // Get neighbors based on direction
-            let neighbors = self.get_neighbors(current);
+    /// Ignores paths longer than `max_hops` edges.
+    pub fn with_max_hops(mut self, max_hops: Option<u32>) -> Self {
+        self.max_hops = max_hops;
+        self
+    }
 
-            for neighbor in neighbors {
-                if neighbor == target {
-                    return Some(depth + 1);
-                }
+    /// Enables the path's edges as an additional column after its length.
+    ///
+    /// The path is a list of edge ids, first hop first, and is null when
+    /// there's no path. Only single shortest paths are output this way.
+    pub fn with_path_output(mut self) -> Self {
+        self.output_path = true;
+        self
+    }
 
-                if !visited.contains_key(&neighbor) {
-                    visited.insert(neighbor, depth + 1);
-                    queue.push_back((neighbor, depth + 1));
-                }
-            }
-        }
+    /// Sets the time at which edges must be valid to be traversed.
+    ///
+    /// Edges whose validity interval doesn't contain `at` are skipped.
+    pub fn with_as_of(mut self, at: Timestamp) -> Self {
+        self.as_of = Some(at);
+        self
+    }
 
-        None // No path found
+    fn finder(&self) -> PathFinder<'_> {
+        PathFinder::new(&self.store, self.direction)
+            .with_edge_type(self.edge_type.as_deref())
+            .with_max_hops(self.max_hops)
+            .with_as_of(self.as_of)
     }
 
     /// Finds all shortest paths between source and target using BFS.
@@ -102,6 +439,9 @@ impl ShortestPathOperator {
             return vec![0];
         }
 
+        let finder = self.finder();
+        let max_depth = self.max_hops.map_or(i64::MAX, i64::from);
+
         // BFS that tracks number of paths to each node at each depth
         let mut distances: HashMap<NodeId, i64> = HashMap::new();
         let mut path_counts: HashMap<NodeId, usize> = HashMap::new();
@@ -124,8 +464,11 @@ impl ShortestPathOperator {
                     continue;
                 }
             }
+            if current_depth >= max_depth {
+                continue;
+            }
 
-            for neighbor in self.get_neighbors(current) {
+            for (neighbor, _) in finder.neighbors(current) {
                 let new_depth = current_depth + 1;
 
                 if neighbor == target {
@@ -162,26 +505,6 @@ impl ShortestPathOperator {
             vec![]
         }
     }
-
-    /// Gets neighbors of a node respecting edge type filter and direction.
-    fn get_neighbors(&self, node: NodeId) -> Vec<NodeId> {
-        self.store
-            .edges_from(node, self.direction)
-            .filter(|(_target, edge_id)| {
-                // Filter by edge type if specified
-                if let Some(ref filter_type) = self.edge_type {
-                    if let Some(edge_type) = self.store.edge_type(*edge_id) {
-                        edge_type.as_ref() == filter_type.as_str()
-                    } else {
-                        false
-                    }
-                } else {
-                    true
-                }
-            })
-            .map(|(target, _)| target)
-            .collect()
-    }
 }
 
 impl Operator for ShortestPathOperator {
@@ -199,7 +522,7 @@ impl Operator for ShortestPathOperator {
             }
         };
 
-        // Build output: input columns + path length
+        // Build output: input columns + path length (+ path)
         let num_input_cols = input_chunk.column_count();
         let mut output_schema: Vec<LogicalType> = (0..num_input_cols)
             .map(|i| {
@@ -209,7 +532,10 @@ impl Operator for ShortestPathOperator {
                     .unwrap_or(LogicalType::Any)
             })
             .collect();
-        output_schema.push(LogicalType::Any); // Path column (stores length as int)
+        output_schema.push(LogicalType::Any); // Path length column
+        if self.output_path {
+            output_schema.push(LogicalType::Any); // Path edges column
+        }
 
         // For allShortestPaths, we may need more rows than input
         let initial_capacity = if self.all_paths {
@@ -228,25 +554,29 @@ impl Operator for ShortestPathOperator {
                 .column(self.target_column)
                 .and_then(|c| c.get_node_id(row));
 
-            // Compute shortest path(s)
-            let path_lengths: Vec<Option<i64>> = match (source, target) {
+            // Compute shortest path(s), as (length, edges) per output row
+            let paths: Vec<Option<(i64, Vec<EdgeId>)>> = match (source, target) {
                 (Some(s), Some(t)) => {
                     if self.all_paths {
                         let paths = self.find_all_shortest_paths(s, t);
                         if paths.is_empty() {
                             vec![None] // No path found, still output one row with null
                         } else {
-                            paths.into_iter().map(Some).collect()
+                            paths
+                                .into_iter()
+                                .map(|len| Some((len, Vec::new())))
+                                .collect()
                         }
                     } else {
-                        vec![self.find_shortest_path(s, t)]
+                        let found = self.finder().find(s, t)?;
+                        vec![found.map(|path| (path.edges.len() as i64, path.edges))]
                     }
                 }
                 _ => vec![None],
             };
 
             // Output one row per path
-            for path_length in path_lengths {
+            for path in paths {
                 // Copy input columns
                 for col_idx in 0..num_input_cols {
                     if let Some(in_col) = input_chunk.column(col_idx) {
@@ -266,12 +596,28 @@ impl Operator for ShortestPathOperator {
 
                 // Add path length column
                 if let Some(out_col) = builder.column_mut(num_input_cols) {
-                    match path_length {
-                        Some(len) => out_col.push_value(Value::Int64(len)),
+                    match &path {
+                        Some((len, _)) => out_col.push_value(Value::Int64(*len)),
                         None => out_col.push_value(Value::Null),
                     }
                 }
 
+                // Add path column
+                if self.output_path {
+                    if let Some(out_col) = builder.column_mut(num_input_cols + 1) {
+                        match path {
+                            Some((_, edges)) => {
+                                let edges: Vec<Value> = edges
+                                    .iter()
+                                    .map(|edge| Value::Int64(edge.as_u64() as i64))
+                                    .collect();
+                                out_col.push_value(Value::List(edges.into()));
+                            }
+                            None => out_col.push_value(Value::Null),
+                        }
+                    }
+                }
+
                 builder.advance_row();
             }
         }
@@ -316,4 +662,86 @@ mod tests {
         // The ShortestPathOperator expects source and target columns
         // This is a simplified test that doesn't fully exercise the operator
     }
+
+    /// a -> b -> c -> d, plus a shortcut a -> detour -> d; weights make the
+    /// three-hop route the cheaper one.
+    fn diamond() -> (LpgStore, [NodeId; 5], [EdgeId; 5]) {
+        let store = LpgStore::new();
+        let [a, b, c, d, detour] = std::array::from_fn(|_| store.create_node(&["Node"]));
+        let edges = [
+            (a, b, 1),
+            (b, c, 1),
+            (c, d, 1),
+            (a, detour, 5),
+            (detour, d, 5),
+        ]
+        .map(|(src, dst, w)| {
+            let edge = store.create_edge(src, dst, "ROAD");
+            store.set_edge_property(edge, "km", Value::Int64(w));
+            edge
+        });
+        (store, [a, b, c, d, detour], edges)
+    }
+
+    #[test]
+    fn test_breadth_first_takes_fewest_hops() {
+        let (store, [a, _, _, d, detour], [.., ae, ed]) = diamond();
+        let path = PathFinder::new(&store, Direction::Outgoing)
+            .find(a, d)
+            .unwrap()
+            .unwrap();
+        assert_eq!(path.nodes, vec![a, detour, d]);
+        assert_eq!(path.edges, vec![ae, ed]);
+        assert_eq!(path.cost, 2.0);
+
+        // Against the edges' direction there's no way from a to d
+        let finder = PathFinder::new(&store, Direction::Incoming);
+        assert_eq!(finder.find(a, d).unwrap(), None);
+        assert_eq!(finder.find(a, a).unwrap().unwrap().nodes, vec![a]);
+    }
+
+    #[test]
+    fn test_dijkstra_respects_weights_and_hop_limit() {
+        let (store, [a, b, c, d, detour], _) = diamond();
+        let weighted = PathFinder::new(&store, Direction::Outgoing).with_weight(Some("km"));
+        let path = weighted.find(a, d).unwrap().unwrap();
+        assert_eq!(path.nodes, vec![a, b, c, d]);
+        assert_eq!(path.cost, 3.0);
+
+        // Two hops only fit the costlier route, and one hop fits none
+        let path = weighted.with_max_hops(Some(2)).find(a, d).unwrap().unwrap();
+        assert_eq!(path.nodes, vec![a, detour, d]);
+        assert_eq!(path.cost, 10.0);
+        let weighted = PathFinder::new(&store, Direction::Both)
+            .with_weight(Some("km"))
+            .with_max_hops(Some(1));
+        assert_eq!(weighted.find(a, d).unwrap(), None);
+        assert!(weighted.find(d, detour).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_bad_weights_fail_the_search() {
+        let (store, [a, b, _, d, _], [ab, ..]) = diamond();
+        let finder = PathFinder::new(&store, Direction::Outgoing).with_weight(Some("km"));
+
+        store.set_edge_property(ab, "km", Value::from("far"));
+        let err = finder.find(a, d).unwrap_err();
+        assert!(matches!(err, PathError::NonNumericWeight { edge, .. } if edge == ab));
+        assert!(err.to_string().contains("expected a number"), "{err}");
+
+        store.set_edge_property(ab, "km", Value::Float64(-1.0));
+        assert!(matches!(
+            finder.find(a, d),
+            Err(PathError::InvalidWeight { .. })
+        ));
+
+        store.remove_edge_property(ab, "km");
+        assert_eq!(
+            finder.find(a, b),
+            Err(PathError::MissingWeight {
+                edge: ab,
+                property: "km".to_string(),
+            })
+        );
+    }
 }
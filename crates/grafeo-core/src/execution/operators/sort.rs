@@ -2,14 +2,19 @@
 //!
 //! This module provides:
 //! - `SortOperator`: Orders results by one or more columns
+//! - `TopNOperator`: Returns the first rows in order, without a full sort
 
 use std::cmp::Ordering;
 
+use grafeo_common::memory::buffer::PressureLevel;
 use grafeo_common::types::{LogicalType, Value};
 
-use super::{Operator, OperatorError, OperatorResult};
-use crate::execution::DataChunk;
+use super::{Operator, OperatorError, OperatorResult, account_memory, chunk_memory_size};
 use crate::execution::chunk::DataChunkBuilder;
+use crate::execution::collation::Collation;
+use crate::execution::sink::TopNSink;
+use crate::execution::spill::{self, ExternalSort, MergedRuns};
+use crate::execution::{DataChunk, SharedMemoryContext, Sink, SpillableOperator};
 
 /// Sort direction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -38,6 +43,8 @@ pub struct SortKey {
     pub direction: SortDirection,
     /// Null ordering.
     pub null_order: NullOrder,
+    /// Collation used when comparing strings.
+    pub collation: Collation,
 }
 
 impl SortKey {
@@ -47,6 +54,7 @@ impl SortKey {
             column,
             direction: SortDirection::Ascending,
             null_order: NullOrder::NullsLast,
+            collation: Collation::Binary,
         }
     }
 
@@ -56,6 +64,7 @@ impl SortKey {
             column,
             direction: SortDirection::Descending,
             null_order: NullOrder::NullsLast,
+            collation: Collation::Binary,
         }
     }
 
@@ -64,8 +73,20 @@ impl SortKey {
         self.null_order = null_order;
         self
     }
+
+    /// Sets the string collation.
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
 }
 
+/// Smallest buffer a sort spills just because memory is under pressure.
+///
+/// Smaller inputs stay in memory unless they outgrow the budget outright;
+/// writing them out would cost more than it frees.
+const MIN_PRESSURE_SPILL_BYTES: usize = 1024 * 1024;
+
 /// A row reference for sorting.
 #[derive(Debug, Clone)]
 struct SortRow {
@@ -77,7 +98,12 @@ struct SortRow {
 
 /// Sort operator.
 ///
-/// Materializes all input and sorts by the specified keys.
+/// Materializes all input and sorts by the specified keys. With a memory
+/// context that has a spill directory, input that outgrows the budget, or
+/// that runs memory into high pressure, is written to disk as sorted runs.
+/// Output then merges the runs a row at a time, keeping equal rows in input
+/// order, and the runs are deleted once output is done or the operator is
+/// dropped.
 pub struct SortOperator {
     /// Child operator.
     child: Box<dyn Operator>,
@@ -93,6 +119,14 @@ pub struct SortOperator {
     sort_complete: bool,
     /// Current position in output.
     output_position: usize,
+    /// Memory budget the sort buffer is charged against.
+    memory: Option<SharedMemoryContext>,
+    /// Bytes charged for the chunks currently buffered.
+    buffered_bytes: usize,
+    /// Sorted runs spilled to disk, once the buffer outgrew the budget.
+    external: Option<ExternalSort>,
+    /// Output merged from the spilled runs and the final buffer.
+    merged: Option<MergedRuns>,
 }
 
 impl SortOperator {
@@ -110,13 +144,38 @@ impl SortOperator {
             sorted_rows: Vec::new(),
             sort_complete: false,
             output_position: 0,
+            memory: None,
+            buffered_bytes: 0,
+            external: None,
+            merged: None,
         }
     }
 
+    /// Charges the sort buffer to a query's memory context.
+    ///
+    /// Each buffered chunk is accounted as it arrives. Running out spills
+    /// the buffer when the context has a spill directory and allows sorts to
+    /// spill, and otherwise fails with [`OperatorError::MemoryLimitExceeded`].
+    /// Buffers of at least a megabyte also spill once memory pressure is
+    /// high.
+    #[must_use]
+    pub fn with_memory_context(mut self, memory: SharedMemoryContext) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
     /// Materializes and sorts the input.
     fn sort(&mut self) -> Result<(), OperatorError> {
         // Materialize all input
         while let Some(chunk) = self.child.next()? {
+            let bytes =
+                chunk_memory_size(&chunk) + chunk.row_count() * std::mem::size_of::<SortRow>();
+            if let Err(err) = account_memory(self.memory.as_ref(), bytes) {
+                if !self.spill_run()? {
+                    return Err(err);
+                }
+            }
+            self.buffered_bytes += bytes;
             let chunk_idx = self.chunks.len();
             for row_idx in chunk.selected_indices() {
                 self.sorted_rows.push(SortRow {
@@ -125,9 +184,66 @@ impl SortOperator {
                 });
             }
             self.chunks.push(chunk);
+            if self.under_pressure() {
+                self.spill_run()?;
+            }
         }
 
-        // Sort the row references
+        self.sort_buffered();
+        if let Some(external) = &mut self.external {
+            let rows = take_buffered_rows(&mut self.chunks, &mut self.sorted_rows);
+            self.merged = Some(external.merge(rows).map_err(spill_error)?);
+        }
+
+        self.sort_complete = true;
+        Ok(())
+    }
+
+    /// Returns true if the buffer is big enough to spill and memory is
+    /// under high pressure.
+    fn under_pressure(&self) -> bool {
+        self.buffered_bytes >= MIN_PRESSURE_SPILL_BYTES
+            && self.memory.as_ref().is_some_and(|memory| {
+                matches!(
+                    memory.lock().pressure_level(),
+                    PressureLevel::High | PressureLevel::Critical
+                )
+            })
+    }
+
+    /// Writes the buffered rows to disk as a sorted run and gives their
+    /// memory back.
+    ///
+    /// Returns `false` when there's nothing to spill, nowhere to spill to, or
+    /// spilling sorts is disabled.
+    fn spill_run(&mut self) -> Result<bool, OperatorError> {
+        let Some(memory) = self.memory.clone() else {
+            return Ok(false);
+        };
+        if self.sorted_rows.is_empty() || !memory.lock().spill_enabled(SpillableOperator::Sort) {
+            return Ok(false);
+        }
+        let Some(manager) = memory.lock().spill_manager().map_err(spill_error)? else {
+            return Ok(false);
+        };
+
+        self.sort_buffered();
+        let rows = take_buffered_rows(&mut self.chunks, &mut self.sorted_rows);
+        let num_columns = rows.first().map_or(0, Vec::len);
+        let keys = self.sort_keys.iter().map(spill_key).collect();
+        self.external
+            .get_or_insert_with(|| ExternalSort::new(manager, num_columns, keys))
+            .spill_sorted_run(rows)
+            .map_err(spill_error)?;
+
+        memory
+            .lock()
+            .release(std::mem::take(&mut self.buffered_bytes));
+        Ok(true)
+    }
+
+    /// Sorts the buffered row references by the sort keys.
+    fn sort_buffered(&mut self) {
         let chunks = &self.chunks;
         let sort_keys = &self.sort_keys;
 
@@ -143,30 +259,125 @@ impl SortOperator {
                     .column(key.column)
                     .and_then(|c| c.get_value(b.row_index));
 
-                let cmp = compare_values_with_nulls(&val_a, &val_b, key.null_order);
-
-                let cmp = match key.direction {
-                    SortDirection::Ascending => cmp,
-                    SortDirection::Descending => cmp.reverse(),
-                };
-
+                let cmp = compare_by_key(key, val_a.as_ref(), val_b.as_ref());
                 if cmp != Ordering::Equal {
                     return cmp;
                 }
             }
             Ordering::Equal
         });
+    }
 
-        self.sort_complete = true;
-        Ok(())
+    /// Builds the next output chunk from rows merged out of spilled runs.
+    fn next_merged(&mut self) -> OperatorResult {
+        let Some(merged) = &mut self.merged else {
+            return Ok(None);
+        };
+
+        let mut builder = DataChunkBuilder::with_capacity(&self.output_schema, 2048);
+        while !builder.is_full() {
+            let Some(row) = merged.next_row().map_err(spill_error)? else {
+                break;
+            };
+            for (col_idx, value) in row.into_iter().enumerate() {
+                if let Some(dst_col) = builder.column_mut(col_idx) {
+                    dst_col.push_value(value);
+                }
+            }
+            builder.advance_row();
+        }
+        if merged.remaining() == 0 {
+            self.finish_merge();
+        }
+
+        if builder.row_count() > 0 {
+            Ok(Some(builder.finish()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Deletes the spilled runs and gives back the final buffer's memory
+    /// once everything has been merged.
+    fn finish_merge(&mut self) {
+        self.merged = None;
+        self.external = None;
+        if let Some(memory) = &self.memory {
+            memory
+                .lock()
+                .release(std::mem::take(&mut self.buffered_bytes));
+        }
+    }
+}
+
+/// Copies the buffered rows out in sorted order, emptying the buffer.
+fn take_buffered_rows(
+    chunks: &mut Vec<DataChunk>,
+    sorted_rows: &mut Vec<SortRow>,
+) -> Vec<Vec<Value>> {
+    let rows = sorted_rows
+        .iter()
+        .map(|row| {
+            let chunk = &chunks[row.chunk_index];
+            (0..chunk.column_count())
+                .map(|col_idx| {
+                    chunk
+                        .column(col_idx)
+                        .and_then(|c| c.get_value(row.row_index))
+                        .unwrap_or(Value::Null)
+                })
+                .collect()
+        })
+        .collect();
+    chunks.clear();
+    sorted_rows.clear();
+    rows
+}
+
+/// Converts a sort key to its spill-side equivalent.
+fn spill_key(key: &SortKey) -> spill::SortKey {
+    spill::SortKey {
+        column: key.column,
+        direction: match key.direction {
+            SortDirection::Ascending => spill::SortDirection::Ascending,
+            SortDirection::Descending => spill::SortDirection::Descending,
+        },
+        null_order: match key.null_order {
+            NullOrder::NullsFirst => spill::NullOrder::First,
+            NullOrder::NullsLast => spill::NullOrder::Last,
+        },
+        collation: key.collation,
+    }
+}
+
+fn spill_error(err: std::io::Error) -> OperatorError {
+    OperatorError::Execution(format!("sort spill failed: {err}"))
+}
+
+/// Compares two rows by the sort keys, the way [`SortOperator`] orders
+/// them.
+pub(crate) fn compare_rows(keys: &[SortKey], a: &[Value], b: &[Value]) -> Ordering {
+    keys.iter()
+        .map(|key| compare_by_key(key, a.get(key.column), b.get(key.column)))
+        .find(|cmp| *cmp != Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Compares two values of one sort key, applying its direction.
+fn compare_by_key(key: &SortKey, a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    let cmp = compare_values_with_nulls(a, b, key.null_order, key.collation);
+    match key.direction {
+        SortDirection::Ascending => cmp,
+        SortDirection::Descending => cmp.reverse(),
     }
 }
 
 /// Compares two optional values with null handling.
 fn compare_values_with_nulls(
-    a: &Option<Value>,
-    b: &Option<Value>,
+    a: Option<&Value>,
+    b: Option<&Value>,
     null_order: NullOrder,
+    collation: Collation,
 ) -> Ordering {
     match (a, b) {
         (None, None) | (Some(Value::Null), Some(Value::Null)) => Ordering::Equal,
@@ -178,24 +389,24 @@ fn compare_values_with_nulls(
             NullOrder::NullsFirst => Ordering::Greater,
             NullOrder::NullsLast => Ordering::Less,
         },
-        (Some(a), Some(b)) => compare_values(a, b),
+        (Some(a), Some(b)) => compare_values(a, b, collation),
     }
 }
 
-/// Compares two values.
-fn compare_values(a: &Value, b: &Value) -> Ordering {
+/// Compares two values, ordering strings by the given collation.
+fn compare_values(a: &Value, b: &Value, collation: Collation) -> Ordering {
     match (a, b) {
         (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
         (Value::Int64(a), Value::Int64(b)) => a.cmp(b),
         (Value::Float64(a), Value::Float64(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
-        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::String(a), Value::String(b)) => collation.compare(a, b),
         (Value::Int64(a), Value::Float64(b)) => {
             (*a as f64).partial_cmp(b).unwrap_or(Ordering::Equal)
         }
         (Value::Float64(a), Value::Int64(b)) => {
             a.partial_cmp(&(*b as f64)).unwrap_or(Ordering::Equal)
         }
-        _ => Ordering::Equal,
+        _ => a.compare_temporal(b).unwrap_or(Ordering::Equal),
     }
 }
 
@@ -204,6 +415,9 @@ impl Operator for SortOperator {
         if !self.sort_complete {
             self.sort()?;
         }
+        if self.merged.is_some() {
+            return self.next_merged();
+        }
 
         if self.output_position >= self.sorted_rows.len() {
             return Ok(None);
@@ -245,6 +459,9 @@ impl Operator for SortOperator {
         self.sorted_rows.clear();
         self.sort_complete = false;
         self.output_position = 0;
+        self.buffered_bytes = 0;
+        self.merged = None;
+        self.external = None;
     }
 
     fn name(&self) -> &'static str {
@@ -252,10 +469,99 @@ impl Operator for SortOperator {
     }
 }
 
+/// Top-N operator.
+///
+/// Returns the first `limit` rows in sort order, the way a [`SortOperator`]
+/// followed by a limit would, but streams its input through a [`TopNSink`]
+/// so only `limit` rows are ever held. A limit of zero returns nothing
+/// without reading the input.
+pub struct TopNOperator {
+    /// Child operator.
+    child: Box<dyn Operator>,
+    /// Sort keys.
+    sort_keys: Vec<SortKey>,
+    /// Number of rows to return.
+    limit: usize,
+    /// Output schema.
+    output_schema: Vec<LogicalType>,
+    /// The kept rows in order, once the input is consumed.
+    rows: Option<Vec<Vec<Value>>>,
+    /// Current position in output.
+    output_position: usize,
+}
+
+impl TopNOperator {
+    /// Creates a new top-N operator.
+    pub fn new(
+        child: Box<dyn Operator>,
+        sort_keys: Vec<SortKey>,
+        limit: usize,
+        output_schema: Vec<LogicalType>,
+    ) -> Self {
+        Self {
+            child,
+            sort_keys,
+            limit,
+            output_schema,
+            rows: None,
+            output_position: 0,
+        }
+    }
+
+    /// Consumes the input, keeping the first `limit` rows.
+    fn collect(&mut self) -> Result<Vec<Vec<Value>>, OperatorError> {
+        let mut sink = TopNSink::new(self.sort_keys.clone(), self.limit);
+        while let Some(chunk) = self.child.next()? {
+            sink.consume(chunk)?;
+        }
+        Ok(sink.into_rows())
+    }
+}
+
+impl Operator for TopNOperator {
+    fn next(&mut self) -> OperatorResult {
+        if self.limit == 0 {
+            return Ok(None);
+        }
+        if self.rows.is_none() {
+            self.rows = Some(self.collect()?);
+        }
+        let rows = self.rows.as_mut().expect("rows collected");
+        if self.output_position >= rows.len() {
+            return Ok(None);
+        }
+
+        let mut builder = DataChunkBuilder::with_capacity(&self.output_schema, 2048);
+        while self.output_position < rows.len() && !builder.is_full() {
+            let row = std::mem::take(&mut rows[self.output_position]);
+            for (col_idx, value) in row.into_iter().enumerate() {
+                if let Some(dst_col) = builder.column_mut(col_idx) {
+                    dst_col.push_value(value);
+                }
+            }
+            builder.advance_row();
+            self.output_position += 1;
+        }
+
+        Ok(Some(builder.finish()))
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+        self.rows = None;
+        self.output_position = 0;
+    }
+
+    fn name(&self) -> &'static str {
+        "TopN"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::execution::chunk::DataChunkBuilder;
+    use std::sync::Arc;
 
     struct MockOperator {
         chunks: Vec<DataChunk>,
@@ -394,4 +700,387 @@ mod tests {
             ]
         );
     }
+
+    fn sort_names(names: &[&str], key: SortKey) -> Vec<String> {
+        let mut builder = DataChunkBuilder::new(&[LogicalType::String]);
+        for name in names {
+            builder.column_mut(0).unwrap().push_string(*name);
+            builder.advance_row();
+        }
+        let mock = MockOperator::new(vec![builder.finish()]);
+        let mut sort = SortOperator::new(Box::new(mock), vec![key], vec![LogicalType::String]);
+
+        let mut results = Vec::new();
+        while let Some(chunk) = sort.next().unwrap() {
+            for row in chunk.selected_indices() {
+                results.push(
+                    chunk
+                        .column(0)
+                        .unwrap()
+                        .get_string(row)
+                        .unwrap()
+                        .to_string(),
+                );
+            }
+        }
+        results
+    }
+
+    #[test]
+    fn test_sort_string_default_byte_order() {
+        let results = sort_names(&["émile", "Zoe", "adam"], SortKey::ascending(0));
+        assert_eq!(results, vec!["Zoe", "adam", "émile"]);
+    }
+
+    #[test]
+    fn test_sort_string_unicode_collation() {
+        let key = SortKey::ascending(0).with_collation(Collation::Unicode);
+        let results = sort_names(&["Zoe", "émile", "eve", "adam", "Ève"], key);
+        assert_eq!(results, vec!["adam", "émile", "eve", "Ève", "Zoe"]);
+
+        let key = SortKey::descending(0).with_collation(Collation::Unicode);
+        let results = sort_names(&["Zoe", "émile", "adam"], key);
+        assert_eq!(results, vec!["Zoe", "émile", "adam"]);
+    }
+
+    #[test]
+    fn test_sort_spills_past_memory_budget() {
+        use crate::execution::ExecutionMemoryContext;
+        use grafeo_common::memory::buffer::BufferManager;
+
+        let chunks = (0..8_i64)
+            .map(|c| {
+                let mut builder = DataChunkBuilder::new(&[LogicalType::Int64]);
+                for i in 0..500_i64 {
+                    builder
+                        .column_mut(0)
+                        .unwrap()
+                        .push_int64((i * 8 + c) * 7 % 4000);
+                    builder.advance_row();
+                }
+                builder.finish()
+            })
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let spill_dir = dir.path().join("spill");
+        let manager = BufferManager::with_budget(48 * 1024);
+        let mut memory = ExecutionMemoryContext::new(manager).with_granularity(0);
+        memory.set_spill_dir(&spill_dir);
+        let memory = memory.into_shared();
+
+        let mut sort = SortOperator::new(
+            Box::new(MockOperator::new(chunks)),
+            vec![SortKey::ascending(0)],
+            vec![LogicalType::Int64],
+        )
+        .with_memory_context(memory);
+
+        let mut results = Vec::new();
+        while let Some(chunk) = sort.next().unwrap() {
+            for row in chunk.selected_indices() {
+                results.push(chunk.column(0).unwrap().get_int64(row).unwrap());
+            }
+        }
+
+        assert!(spill_dir.exists());
+        assert_eq!(results, (0..4000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_sort_with_spill_disabled_fails_fast() {
+        use crate::execution::ExecutionMemoryContext;
+        use grafeo_common::memory::buffer::BufferManager;
+
+        let chunks = (0..8_i64)
+            .map(|c| {
+                let mut builder = DataChunkBuilder::new(&[LogicalType::Int64]);
+                for i in 0..500_i64 {
+                    builder.column_mut(0).unwrap().push_int64(i * 8 + c);
+                    builder.advance_row();
+                }
+                builder.finish()
+            })
+            .collect();
+
+        let dir = tempfile::tempdir().unwrap();
+        let spill_dir = dir.path().join("spill");
+        let manager = BufferManager::with_budget(48 * 1024);
+        let mut memory = ExecutionMemoryContext::new(manager).with_granularity(0);
+        memory.set_spill_dir(&spill_dir);
+        memory.disable_spill(SpillableOperator::Sort);
+        let memory = memory.into_shared();
+
+        let mut sort = SortOperator::new(
+            Box::new(MockOperator::new(chunks)),
+            vec![SortKey::ascending(0)],
+            vec![LogicalType::Int64],
+        )
+        .with_memory_context(memory);
+
+        assert!(matches!(
+            sort.next(),
+            Err(OperatorError::MemoryLimitExceeded { .. })
+        ));
+        assert!(!spill_dir.exists());
+    }
+
+    /// Pseudo-random keys in `0..range`, so large inputs have many ties.
+    fn random_keys(count: usize, range: i64, mut seed: u64) -> Vec<Option<i64>> {
+        (0..count)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                Some((seed % range as u64) as i64)
+            })
+            .collect()
+    }
+
+    /// Files left behind in `dir`, which may not exist.
+    fn spill_files(dir: &std::path::Path) -> usize {
+        std::fs::read_dir(dir).map_or(0, Iterator::count)
+    }
+
+    #[test]
+    fn test_sort_spilled_large_random_input_is_sorted_stable_and_complete() {
+        use crate::execution::ExecutionMemoryContext;
+        use grafeo_common::memory::buffer::BufferManager;
+
+        let keys = random_keys(50_000, 1000, 0x9E37_79B9_7F4A_7C15);
+        let dir = tempfile::tempdir().unwrap();
+        let spill_dir = dir.path().join("spill");
+        let manager = BufferManager::with_budget(256 * 1024);
+        let mut memory = ExecutionMemoryContext::new(manager).with_granularity(0);
+        memory.set_spill_dir(&spill_dir);
+        let memory = memory.into_shared();
+
+        let mut sort = SortOperator::new(
+            Box::new(MockOperator::new(keyed_chunks(&keys, 1000))),
+            vec![SortKey::ascending(0)],
+            vec![LogicalType::Int64, LogicalType::Int64],
+        )
+        .with_memory_context(Arc::clone(&memory));
+
+        let rows = drain(&mut sort);
+        assert!(spill_dir.exists());
+        assert_eq!(spill_files(&spill_dir), 0);
+        assert_eq!(memory.lock().in_use(), 0);
+
+        let mut expected: Vec<(i64, i64)> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.unwrap(), i as i64))
+            .collect();
+        expected.sort_unstable();
+        let actual: Vec<(i64, i64)> = rows
+            .iter()
+            .map(|row| match (&row[0], &row[1]) {
+                (Value::Int64(key), Value::Int64(position)) => (*key, *position),
+                other => panic!("unexpected row {other:?}"),
+            })
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_sort_spills_under_high_pressure() {
+        use crate::execution::ExecutionMemoryContext;
+        use grafeo_common::memory::buffer::{BufferManager, MemoryRegion, PressureLevel};
+
+        let dir = tempfile::tempdir().unwrap();
+        let spill_dir = dir.path().join("spill");
+        let manager = BufferManager::with_budget(64 * 1024 * 1024);
+        let _held = manager
+            .try_allocate(56 * 1024 * 1024, MemoryRegion::GraphStorage)
+            .unwrap();
+        assert_eq!(manager.pressure_level(), PressureLevel::High);
+        let mut memory = ExecutionMemoryContext::new(manager).with_granularity(0);
+        memory.set_spill_dir(&spill_dir);
+        let memory = memory.into_shared();
+
+        // A tiny input stays in memory however high the pressure.
+        let keys = random_keys(100, 10, 7);
+        let mut sort = SortOperator::new(
+            Box::new(MockOperator::new(keyed_chunks(&keys, 10))),
+            vec![SortKey::ascending(0)],
+            vec![LogicalType::Int64, LogicalType::Int64],
+        )
+        .with_memory_context(Arc::clone(&memory));
+        assert_eq!(drain(&mut sort).len(), 100);
+        assert!(!spill_dir.exists());
+
+        // A larger one spills well before it runs out of budget.
+        let keys = random_keys(100_000, 5000, 11);
+        let mut sort = SortOperator::new(
+            Box::new(MockOperator::new(keyed_chunks(&keys, 2048))),
+            vec![SortKey::ascending(0)],
+            vec![LogicalType::Int64, LogicalType::Int64],
+        )
+        .with_memory_context(memory);
+        let rows = drain(&mut sort);
+        assert!(spill_dir.exists());
+        assert_eq!(rows.len(), 100_000);
+        assert!(
+            rows.windows(2)
+                .all(|pair| pair[0][0].as_int64() <= pair[1][0].as_int64())
+        );
+        assert_eq!(spill_files(&spill_dir), 0);
+    }
+
+    #[test]
+    fn test_sort_removes_spill_files_when_input_fails() {
+        use crate::execution::ExecutionMemoryContext;
+        use grafeo_common::memory::buffer::BufferManager;
+
+        /// Hands up its chunks, then fails.
+        struct FailingOperator(MockOperator);
+
+        impl Operator for FailingOperator {
+            fn next(&mut self) -> OperatorResult {
+                match self.0.next()? {
+                    Some(chunk) => Ok(Some(chunk)),
+                    None => Err(OperatorError::Execution("input failed".into())),
+                }
+            }
+
+            fn reset(&mut self) {
+                self.0.reset();
+            }
+
+            fn name(&self) -> &'static str {
+                "Failing"
+            }
+        }
+
+        let keys = random_keys(20_000, 100, 3);
+        let dir = tempfile::tempdir().unwrap();
+        let spill_dir = dir.path().join("spill");
+        let manager = BufferManager::with_budget(128 * 1024);
+        let mut memory = ExecutionMemoryContext::new(manager).with_granularity(0);
+        memory.set_spill_dir(&spill_dir);
+        let memory = memory.into_shared();
+
+        let mut sort = SortOperator::new(
+            Box::new(FailingOperator(MockOperator::new(keyed_chunks(
+                &keys, 1000,
+            )))),
+            vec![SortKey::ascending(0)],
+            vec![LogicalType::Int64, LogicalType::Int64],
+        )
+        .with_memory_context(memory);
+
+        assert!(sort.next().is_err());
+        assert!(spill_files(&spill_dir) > 0);
+        drop(sort);
+        assert_eq!(spill_files(&spill_dir), 0);
+    }
+
+    fn drain(op: &mut dyn Operator) -> Vec<Vec<Value>> {
+        let mut rows = Vec::new();
+        while let Some(chunk) = op.next().unwrap() {
+            for row in chunk.selected_indices() {
+                rows.push(
+                    (0..chunk.column_count())
+                        .map(|col| chunk.column(col).unwrap().get_value(row).unwrap())
+                        .collect(),
+                );
+            }
+        }
+        rows
+    }
+
+    /// Chunks of `(key, position)` rows, so ties show up in the output.
+    fn keyed_chunks(keys: &[Option<i64>], chunk_size: usize) -> Vec<DataChunk> {
+        let rows: Vec<(Value, Value)> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                (
+                    key.map_or(Value::Null, Value::Int64),
+                    Value::Int64(i as i64),
+                )
+            })
+            .collect();
+        rows.chunks(chunk_size)
+            .map(|rows| {
+                let keys: Vec<Value> = rows.iter().map(|(k, _)| k.clone()).collect();
+                let positions: Vec<Value> = rows.iter().map(|(_, p)| p.clone()).collect();
+                DataChunk::new(vec![
+                    crate::execution::ValueVector::from_values(&keys),
+                    crate::execution::ValueVector::from_values(&positions),
+                ])
+            })
+            .collect()
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn prop_top_n_matches_sort_then_limit(
+            keys in proptest::collection::vec(proptest::option::weighted(0.9, 0i64..20), 0..300),
+            limit in 0usize..50,
+            chunk_size in 1usize..64,
+            descending in proptest::bool::ANY,
+        ) {
+            let key = if descending { SortKey::descending(0) } else { SortKey::ascending(0) };
+            let schema = vec![LogicalType::Int64, LogicalType::Int64];
+
+            let mut sort = SortOperator::new(
+                Box::new(MockOperator::new(keyed_chunks(&keys, chunk_size))),
+                vec![key.clone()],
+                schema.clone(),
+            );
+            let mut expected = drain(&mut sort);
+            expected.truncate(limit);
+
+            let mut top = TopNOperator::new(
+                Box::new(MockOperator::new(keyed_chunks(&keys, chunk_size))),
+                vec![key],
+                limit,
+                schema,
+            );
+            proptest::prop_assert_eq!(drain(&mut top), expected);
+        }
+    }
+
+    #[test]
+    fn test_top_n_sink_holds_at_most_n_rows() {
+        let keys: Vec<Option<i64>> = (0..10_000).map(|i| Some((i * 7919) % 1000)).collect();
+        let mut sink = TopNSink::new(vec![SortKey::ascending(0)], 10);
+        for chunk in keyed_chunks(&keys, 512) {
+            assert!(sink.consume(chunk).unwrap());
+        }
+
+        assert_eq!(sink.peak_rows(), 10);
+        let rows = sink.into_rows();
+        assert_eq!(rows.len(), 10);
+        assert_eq!(rows[0][0], Value::Int64(0));
+        assert_eq!(rows[9][0], Value::Int64(0));
+    }
+
+    #[test]
+    fn test_top_n_zero_reads_no_input() {
+        struct Unreachable;
+        impl Operator for Unreachable {
+            fn next(&mut self) -> OperatorResult {
+                panic!("top-0 must not read its input")
+            }
+            fn reset(&mut self) {}
+            fn name(&self) -> &'static str {
+                "Unreachable"
+            }
+        }
+
+        let mut top = TopNOperator::new(
+            Box::new(Unreachable),
+            vec![SortKey::ascending(0)],
+            0,
+            vec![LogicalType::Int64],
+        );
+        assert!(top.next().unwrap().is_none());
+
+        let mut sink = TopNSink::new(vec![SortKey::ascending(0)], 0);
+        assert!(!sink.consume(keyed_chunks(&[Some(1)], 1).remove(0)).unwrap());
+        assert_eq!(sink.row_count(), 0);
+    }
 }
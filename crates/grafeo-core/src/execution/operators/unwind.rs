@@ -1,13 +1,18 @@
 //! Unwind operator for expanding lists into individual rows.
 
-use super::{Operator, OperatorResult};
+use super::{Operator, OperatorError, OperatorResult};
 use crate::execution::chunk::{DataChunk, DataChunkBuilder};
 use grafeo_common::types::{LogicalType, Value};
 
 /// Unwind operator that expands a list column into individual rows.
 ///
 /// For each input row, if the list column contains N elements, this operator
-/// produces N output rows, each with one element from the list.
+/// produces N output rows, each with one element from the list. Each input
+/// chunk becomes one (usually larger) output chunk holding the input's other
+/// columns followed by the element.
+///
+/// As in Cypher, an empty or null list produces no rows, and any other
+/// non-list value is an error.
 pub struct UnwindOperator {
     /// Child operator to read from.
     child: Box<dyn Operator>,
@@ -15,16 +20,8 @@ pub struct UnwindOperator {
     list_col_idx: usize,
     /// Name of the new variable for the unwound elements.
     variable_name: String,
-    /// Schema of output columns (inherited from input plus the new column).
+    /// Schema of output columns (the input's other columns plus the new one).
     output_schema: Vec<LogicalType>,
-    /// Current input chunk being processed.
-    current_chunk: Option<DataChunk>,
-    /// Current row index within the chunk.
-    current_row: usize,
-    /// Current index within the list being unwound.
-    current_list_idx: usize,
-    /// Current list being unwound.
-    current_list: Option<Vec<Value>>,
 }
 
 impl UnwindOperator {
@@ -46,10 +43,6 @@ impl UnwindOperator {
             list_col_idx,
             variable_name,
             output_schema,
-            current_chunk: None,
-            current_row: 0,
-            current_list_idx: 0,
-            current_list: None,
         }
     }
 
@@ -59,106 +52,67 @@ impl UnwindOperator {
         &self.variable_name
     }
 
-    /// Advances to the next row or fetches the next chunk.
-    fn advance(&mut self) -> OperatorResult {
-        loop {
-            // If we have a current list, try to get the next element
-            if let Some(list) = &self.current_list {
-                if self.current_list_idx < list.len() {
-                    // Still have elements in the current list
-                    return Ok(Some(self.emit_row()?));
-                }
-            }
-
-            // Need to move to the next row
-            self.current_list_idx = 0;
-            self.current_list = None;
+    /// Expands every row of a chunk, one output row per list element.
+    fn unwind_chunk(&self, chunk: &DataChunk) -> Result<DataChunk, OperatorError> {
+        let mut builder = DataChunkBuilder::with_capacity(&self.output_schema, chunk.row_count());
 
-            // Get the next chunk if needed
-            if self.current_chunk.is_none() {
-                self.current_chunk = self.child.next()?;
-                self.current_row = 0;
-                if self.current_chunk.is_none() {
-                    return Ok(None); // No more data
+        for row in chunk.selected_indices() {
+            let list = match chunk.column(self.list_col_idx).map(|c| c.get_value(row)) {
+                Some(Some(Value::List(list))) => list,
+                // UNWIND null produces no rows
+                Some(Some(Value::Null) | None) => continue,
+                Some(Some(other)) => {
+                    return Err(OperatorError::TypeMismatch {
+                        expected: "List to UNWIND".to_string(),
+                        found: format!("{other:?}"),
+                    });
                 }
-            }
-
-            let chunk = self.current_chunk.as_ref().unwrap();
-
-            // Find the next row with a list value
-            while self.current_row < chunk.row_count() {
-                if let Some(col) = chunk.column(self.list_col_idx) {
-                    if let Some(value) = col.get_value(self.current_row) {
-                        if let Value::List(list_arc) = value {
-                            // Found a list - store it and return first element
-                            let list: Vec<Value> = list_arc.iter().cloned().collect();
-                            if !list.is_empty() {
-                                self.current_list = Some(list);
-                                return Ok(Some(self.emit_row()?));
-                            }
-                        }
-                    }
+                None => {
+                    return Err(OperatorError::ColumnNotFound(format!(
+                        "list column {}",
+                        self.list_col_idx
+                    )));
                 }
-                self.current_row += 1;
-            }
-
-            // Exhausted current chunk, get next one
-            self.current_chunk = None;
-        }
-    }
-
-    /// Emits a single row with the current list element.
-    fn emit_row(&mut self) -> Result<DataChunk, super::OperatorError> {
-        let chunk = self.current_chunk.as_ref().unwrap();
-        let list = self.current_list.as_ref().unwrap();
-        let element = list[self.current_list_idx].clone();
+            };
 
-        // Build output row: copy all columns from input + add the unwound element
-        let mut builder = DataChunkBuilder::new(&self.output_schema);
-
-        // Copy existing columns (except the list column which we're replacing)
-        for col_idx in 0..chunk.column_count() {
-            if col_idx == self.list_col_idx {
-                continue; // Skip the list column
-            }
-            if let Some(col) = chunk.column(col_idx) {
-                if let Some(value) = col.get_value(self.current_row) {
-                    if let Some(out_col) = builder.column_mut(col_idx) {
+            for element in list.iter() {
+                // Copy the other columns, then add the element as the last one
+                let values = (0..chunk.column_count())
+                    .filter(|&col_idx| col_idx != self.list_col_idx)
+                    .map(|col_idx| {
+                        chunk
+                            .column(col_idx)
+                            .and_then(|c| c.get_value(row))
+                            .unwrap_or(Value::Null)
+                    })
+                    .chain(std::iter::once(element.clone()));
+                for (out_idx, value) in values.enumerate() {
+                    if let Some(out_col) = builder.column_mut(out_idx) {
                         out_col.push_value(value);
                     }
                 }
+                builder.advance_row();
             }
         }
 
-        // Add the unwound element as the last column
-        let new_col_idx = self.output_schema.len() - 1;
-        if let Some(out_col) = builder.column_mut(new_col_idx) {
-            out_col.push_value(element);
-        }
-
-        builder.advance_row();
-        self.current_list_idx += 1;
-
-        // If we've exhausted this list, move to the next row
-        if self.current_list_idx >= list.len() {
-            self.current_row += 1;
-        }
-
         Ok(builder.finish())
     }
 }
 
 impl Operator for UnwindOperator {
     fn next(&mut self) -> OperatorResult {
-        self.advance()
+        // Skip chunks whose lists were all empty
+        while let Some(chunk) = self.child.next()? {
+            let output = self.unwind_chunk(&chunk)?;
+            if output.row_count() > 0 {
+                return Ok(Some(output));
+            }
+        }
+        Ok(None)
     }
 
     fn reset(&mut self) {
         self.child.reset();
-        self.current_chunk = None;
-        self.current_row = 0;
-        self.current_list_idx = 0;
-        self.current_list = None;
     }
 
     fn name(&self) -> &'static str {
@@ -223,12 +177,85 @@ mod tests {
             vec![LogicalType::Int64], // Output is just the unwound element
         );
 
-        // Should produce 3 rows
+        // Should produce 3 rows, in a single chunk
         let mut results = Vec::new();
         while let Ok(Some(chunk)) = unwind.next() {
             results.push(chunk);
         }
 
-        assert_eq!(results.len(), 3);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].row_count(), 3);
+        assert_eq!(
+            results[0].column(0).unwrap().get_value(2),
+            Some(Value::Int64(3))
+        );
+    }
+
+    #[test]
+    fn test_unwind_keeps_other_columns_and_skips_empty_lists() {
+        let mut builder = DataChunkBuilder::new(&[LogicalType::Int64, LogicalType::Any]);
+        let rows = [
+            (
+                1,
+                Value::List(Arc::new([Value::from("a"), Value::from("b")])),
+            ),
+            (2, Value::List(Arc::new([]))),
+            (3, Value::Null),
+            (4, Value::List(Arc::new([Value::from("c")]))),
+        ];
+        for (id, list) in rows {
+            builder.column_mut(0).unwrap().push_value(Value::Int64(id));
+            builder.column_mut(1).unwrap().push_value(list);
+            builder.advance_row();
+        }
+
+        let mock = MockOperator {
+            chunks: vec![builder.finish()],
+            position: 0,
+        };
+        let mut unwind = UnwindOperator::new(
+            Box::new(mock),
+            1,
+            "x".to_string(),
+            vec![LogicalType::Int64, LogicalType::Any],
+        );
+
+        let chunk = unwind.next().unwrap().unwrap();
+        let rows: Vec<(Option<Value>, Option<Value>)> = (0..chunk.row_count())
+            .map(|row| {
+                (
+                    chunk.column(0).unwrap().get_value(row),
+                    chunk.column(1).unwrap().get_value(row),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (Some(Value::Int64(1)), Some(Value::from("a"))),
+                (Some(Value::Int64(1)), Some(Value::from("b"))),
+                (Some(Value::Int64(4)), Some(Value::from("c"))),
+            ]
+        );
+        assert!(unwind.next().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_unwind_non_list_errors() {
+        let mut builder = DataChunkBuilder::new(&[LogicalType::Any]);
+        builder.column_mut(0).unwrap().push_value(Value::Int64(7));
+        builder.advance_row();
+
+        let mock = MockOperator {
+            chunks: vec![builder.finish()],
+            position: 0,
+        };
+        let mut unwind =
+            UnwindOperator::new(Box::new(mock), 0, "x".to_string(), vec![LogicalType::Any]);
+
+        assert!(matches!(
+            unwind.next(),
+            Err(OperatorError::TypeMismatch { .. })
+        ));
     }
 }
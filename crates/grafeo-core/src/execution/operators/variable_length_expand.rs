@@ -4,7 +4,7 @@ use super::{Operator, OperatorError, OperatorResult};
 use crate::execution::DataChunk;
 use crate::graph::Direction;
 use crate::graph::lpg::LpgStore;
-use grafeo_common::types::{EdgeId, EpochId, LogicalType, NodeId, TxId};
+use grafeo_common::types::{EdgeId, EpochId, LogicalType, NodeId, Timestamp, TxId};
 use std::collections::VecDeque;
 use std::sync::Arc;
 
@@ -33,6 +33,8 @@ pub struct VariableLengthExpandOperator {
     tx_id: Option<TxId>,
     /// Epoch for version visibility.
     viewing_epoch: Option<EpochId>,
+    /// Only traverse edges valid at this time, if set.
+    as_of: Option<Timestamp>,
     /// Materialized input rows.
     input_rows: Option<Vec<InputRow>>,
     /// Current input row index.
@@ -43,6 +45,8 @@ pub struct VariableLengthExpandOperator {
     exhausted: bool,
     /// Whether to output path length as an additional column.
     output_path_length: bool,
+    /// Whether to output the path's edges as an additional column.
+    output_path: bool,
 }
 
 /// A materialized input row.
@@ -71,6 +75,8 @@ struct OutputRow {
     target_id: NodeId,
     /// The path length (number of edges/hops).
     path_length: u32,
+    /// The path's edges in traversal order (empty unless path output is on).
+    path: Vec<EdgeId>,
 }
 
 impl VariableLengthExpandOperator {
@@ -95,11 +101,13 @@ impl VariableLengthExpandOperator {
             chunk_capacity: 2048,
             tx_id: None,
             viewing_epoch: None,
+            as_of: None,
             input_rows: None,
             current_input_idx: 0,
             output_buffer: Vec::new(),
             exhausted: false,
             output_path_length: false,
+            output_path: false,
         }
     }
 
@@ -109,6 +117,15 @@ impl VariableLengthExpandOperator {
         self
     }
 
+    /// Enables the path's edges as an additional column, after the path
+    /// length if that's enabled too.
+    ///
+    /// The path is a list of edge ids, first hop first.
+    pub fn with_path_output(mut self) -> Self {
+        self.output_path = true;
+        self
+    }
+
     /// Sets the chunk capacity.
     pub fn with_chunk_capacity(mut self, capacity: usize) -> Self {
         self.chunk_capacity = capacity;
@@ -122,6 +139,14 @@ impl VariableLengthExpandOperator {
         self
     }
 
+    /// Sets the time at which edges must be valid to be traversed.
+    ///
+    /// Edges whose validity interval doesn't contain `at` are skipped.
+    pub fn with_as_of(mut self, at: Timestamp) -> Self {
+        self.as_of = Some(at);
+        self
+    }
+
     /// Materializes all input rows.
     fn materialize_input(&mut self) -> Result<(), OperatorError> {
         let mut rows = Vec::new();
@@ -181,7 +206,7 @@ impl VariableLengthExpandOperator {
                 // Filter by edge type if specified
                 let type_matches = if let Some(ref filter_type) = self.edge_type {
                     if let Some(edge_type) = self.store.edge_type(*edge_id) {
-                        edge_type.as_ref() == filter_type.as_str()
+                        self.store.label_matches(&edge_type, filter_type)
                     } else {
                         false
                     }
@@ -193,6 +218,13 @@ impl VariableLengthExpandOperator {
                     return false;
                 }
 
+                // Skip edges that aren't valid at the as-of time
+                if let Some(at) = self.as_of {
+                    if !self.store.is_edge_valid_at(*edge_id, at) {
+                        return false;
+                    }
+                }
+
                 // Filter by visibility if we have tx context
                 if let Some(epoch) = epoch {
                     let edge_visible = self.store.get_edge_versioned(*edge_id, epoch, tx).is_some();
@@ -212,30 +244,41 @@ impl VariableLengthExpandOperator {
     fn process_input_row(&self, input_idx: usize, source_node: NodeId) -> Vec<OutputRow> {
         let mut results = Vec::new();
 
+        // Every hop taken, as (edge, index of the hop it extends), so each
+        // output's path can be rebuilt by walking back to the source
+        let mut hops: Vec<(EdgeId, Option<usize>)> = Vec::new();
+
         // BFS from source node
-        let mut frontier: VecDeque<(NodeId, u32, EdgeId)> = VecDeque::new();
+        let mut frontier: VecDeque<(NodeId, u32, usize)> = VecDeque::new();
 
         // Initialize frontier with immediate neighbors
         for (target, edge_id) in self.get_edges(source_node) {
-            frontier.push_back((target, 1, edge_id));
+            hops.push((edge_id, None));
+            frontier.push_back((target, 1, hops.len() - 1));
         }
 
         // Process frontier
-        while let Some((current_node, depth, edge_id)) = frontier.pop_front() {
+        while let Some((current_node, depth, hop)) = frontier.pop_front() {
             // If within the hop range, add to results
             if depth >= self.min_hops && depth <= self.max_hops {
                 results.push(OutputRow {
                     input_idx,
-                    edge_id,
+                    edge_id: hops[hop].0,
                     target_id: current_node,
                     path_length: depth,
+                    path: if self.output_path {
+                        Self::rebuild_path(&hops, hop)
+                    } else {
+                        Vec::new()
+                    },
                 });
             }
 
             // If we haven't reached max depth, continue expanding
             if depth < self.max_hops {
                 for (target, next_edge_id) in self.get_edges(current_node) {
-                    frontier.push_back((target, depth + 1, next_edge_id));
+                    hops.push((next_edge_id, Some(hop)));
+                    frontier.push_back((target, depth + 1, hops.len() - 1));
                 }
             }
         }
@@ -243,6 +286,19 @@ impl VariableLengthExpandOperator {
         results
     }
 
+    /// Rebuilds the edges leading to `last`, first hop first.
+    fn rebuild_path(hops: &[(EdgeId, Option<usize>)], last: usize) -> Vec<EdgeId> {
+        let mut path = Vec::new();
+        let mut current = Some(last);
+        while let Some(hop) = current {
+            let (edge_id, previous) = hops[hop];
+            path.push(edge_id);
+            current = previous;
+        }
+        path.reverse();
+        path
+    }
+
     /// Fill the output buffer with results from the next input row.
     fn fill_output_buffer(&mut self) {
         let input_rows = match &self.input_rows {
@@ -287,8 +343,8 @@ impl Operator for VariableLengthExpandOperator {
         // Build output chunk from buffer
         let num_input_cols = input_rows.first().map_or(0, |r| r.columns.len());
 
-        // Schema: [input_columns..., edge, target, (path_length)?]
-        let extra_cols = if self.output_path_length { 3 } else { 2 };
+        // Schema: [input_columns..., edge, target, (path_length)?, (path)?]
+        let extra_cols = 2 + usize::from(self.output_path_length) + usize::from(self.output_path);
         let mut schema: Vec<LogicalType> = Vec::with_capacity(num_input_cols + extra_cols);
         if let Some(first_row) = input_rows.first() {
             for col_val in &first_row.columns {
@@ -305,6 +361,9 @@ impl Operator for VariableLengthExpandOperator {
         if self.output_path_length {
             schema.push(LogicalType::Int64);
         }
+        if self.output_path {
+            schema.push(LogicalType::Path);
+        }
 
         let mut chunk = DataChunk::with_capacity(&schema, self.chunk_capacity);
 
@@ -344,6 +403,19 @@ impl Operator for VariableLengthExpandOperator {
                     )));
                 }
             }
+
+            // Add path column if requested
+            if self.output_path {
+                let path_col = num_input_cols + 2 + usize::from(self.output_path_length);
+                if let Some(col) = chunk.column_mut(path_col) {
+                    let edges: Vec<_> = out_row
+                        .path
+                        .iter()
+                        .map(|edge| grafeo_common::types::Value::Int64(edge.as_u64() as i64))
+                        .collect();
+                    col.push_value(grafeo_common::types::Value::List(edges.into()));
+                }
+            }
         }
 
         chunk.set_count(to_output.len());
@@ -367,6 +439,7 @@ impl Operator for VariableLengthExpandOperator {
 mod tests {
     use super::*;
     use crate::execution::operators::ScanOperator;
+    use grafeo_common::types::Value;
 
     #[test]
     fn test_variable_length_expand_chain() {
@@ -468,4 +541,95 @@ mod tests {
         );
         assert!(a_targets.contains(&c), "a should reach c");
     }
+
+    #[test]
+    fn test_variable_length_expand_self_loop_terminates() {
+        let store = Arc::new(LpgStore::new());
+
+        // a loops back to itself and points on to b
+        let a = store.create_node(&["Node"]);
+        let b = store.create_node(&["Node"]);
+        let lp = store.create_edge(a, a, "NEXT");
+        let b_edge = store.create_edge(a, b, "NEXT");
+
+        let scan = Box::new(ScanOperator::with_label(Arc::clone(&store), "Node"));
+        let mut expand = VariableLengthExpandOperator::new(
+            Arc::clone(&store),
+            scan,
+            0,
+            Direction::Outgoing,
+            Some("NEXT".to_string()),
+            1,
+            3,
+        )
+        .with_path_length_output();
+
+        let mut from_a = Vec::new();
+        while let Some(chunk) = expand.next().unwrap() {
+            for i in 0..chunk.row_count() {
+                if chunk.column(0).unwrap().get_node_id(i) != Some(a) {
+                    continue;
+                }
+                let edge = chunk.column(1).unwrap().get_edge_id(i).unwrap();
+                let dst = chunk.column(2).unwrap().get_node_id(i).unwrap();
+                let len = chunk.column(3).unwrap().get_int64(i).unwrap();
+                from_a.push((len, edge, dst));
+            }
+        }
+
+        // Each extra hop can go round the loop once more, up to max_hops:
+        // a (via the loop) and b at every length from 1 to 3
+        from_a.sort_unstable();
+        let mut expected = Vec::new();
+        for len in 1..=3 {
+            expected.push((len, lp, a));
+            expected.push((len, b_edge, b));
+        }
+        expected.sort_unstable();
+        assert_eq!(from_a, expected);
+    }
+
+    #[test]
+    fn test_variable_length_expand_outputs_path_edges() {
+        let store = Arc::new(LpgStore::new());
+
+        // Chain: a -> b -> c
+        let a = store.create_node(&["Start"]);
+        let b = store.create_node(&["Node"]);
+        let c = store.create_node(&["Node"]);
+        let ab = store.create_edge(a, b, "NEXT");
+        let bc = store.create_edge(b, c, "NEXT");
+
+        let scan = Box::new(ScanOperator::with_label(Arc::clone(&store), "Start"));
+        let mut expand = VariableLengthExpandOperator::new(
+            Arc::clone(&store),
+            scan,
+            0,
+            Direction::Outgoing,
+            None,
+            1,
+            2,
+        )
+        .with_path_length_output()
+        .with_path_output();
+
+        let edge_value = |edge: EdgeId| Value::Int64(edge.as_u64() as i64);
+        let mut paths = Vec::new();
+        while let Some(chunk) = expand.next().unwrap() {
+            for i in 0..chunk.row_count() {
+                let len = chunk.column(3).unwrap().get_int64(i).unwrap();
+                let path = chunk.column(4).unwrap().get_value(i).unwrap();
+                paths.push((len, path));
+            }
+        }
+        paths.sort_by_key(|(len, _)| *len);
+
+        assert_eq!(
+            paths,
+            vec![
+                (1, Value::List(vec![edge_value(ab)].into())),
+                (2, Value::List(vec![edge_value(ab), edge_value(bc)].into())),
+            ]
+        );
+    }
 }
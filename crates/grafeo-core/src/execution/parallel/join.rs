@@ -0,0 +1,885 @@
+//! Parallel hash join.
+//!
+//! The join runs as two parallel pipelines over a shared, partitioned hash
+//! table. During the build phase every worker inserts its morsels' rows
+//! into the table, locking only the partition each row hashes to. During
+//! the probe phase workers look up their rows concurrently and emit the
+//! matches.
+//!
+//! When the query's [`ExecutionMemoryContext`](crate::execution::ExecutionMemoryContext)
+//! runs out of budget, the largest in-memory partition is written to disk
+//! and later build rows for it follow it there. Probe rows that hash to a
+//! spilled partition are spilled too, and each spilled partition is joined
+//! on its own once the probe phase is done, so only one of them is held in
+//! memory at a time.
+//!
+//! [`ParallelHashJoin`] drives both phases. The [`HashJoinBuildOperator`]
+//! and [`HashJoinProbeOperator`] it uses can also be put into a
+//! [`CloneableOperatorFactory`] directly, and [`ParallelHashJoinOperator`]
+//! runs the whole join as one operator of a pull-based plan.
+
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::BufReader;
+use std::sync::Arc;
+
+use grafeo_common::types::{LogicalType, Value};
+use parking_lot::RwLock;
+
+use super::merge::rows_to_chunks;
+use super::pipeline::ParallelPipelineResult;
+use super::pipeline::{CloneableOperatorFactory, ParallelPipeline, ParallelPipelineConfig};
+use super::source::{ParallelChunkSource, ParallelSource};
+use crate::execution::chunk::DataChunk;
+use crate::execution::memory::{SharedMemoryContext, SpillableOperator};
+use crate::execution::operators::{HashKey, Operator, OperatorError, OperatorResult};
+use crate::execution::pipeline::{DEFAULT_CHUNK_SIZE, PushOperator, Sink};
+use crate::execution::spill::{SpillFile, SpillManager, deserialize_row, serialize_row};
+use crate::execution::vector::ValueVector;
+
+/// Default number of hash table partitions.
+pub const DEFAULT_JOIN_PARTITIONS: usize = 64;
+
+/// One partition of a [`JoinHashTable`].
+#[derive(Default)]
+struct JoinPartition {
+    /// Build rows by join key, while the partition is in memory.
+    rows: HashMap<HashKey, Vec<Vec<Value>>>,
+    /// Bytes charged for `rows`.
+    bytes: usize,
+    /// Build rows written to disk, once the partition has spilled.
+    build_file: Option<SpillFile>,
+    /// Rows in `build_file`.
+    build_rows: usize,
+    /// Probe rows waiting for the spilled build side.
+    probe_file: Option<SpillFile>,
+    /// Rows in `probe_file`.
+    probe_rows: usize,
+}
+
+impl JoinPartition {
+    fn is_spilled(&self) -> bool {
+        self.build_file.is_some()
+    }
+}
+
+/// A hash table shared by every worker of a parallel hash join.
+///
+/// Rows are partitioned by the hash of their join key, and each partition
+/// has its own lock, so workers inserting or probing different keys rarely
+/// wait on each other. Rows with a NULL in any key column never match and
+/// are dropped.
+pub struct JoinHashTable {
+    partitions: Vec<RwLock<JoinPartition>>,
+    /// Key columns of build rows.
+    build_keys: Vec<usize>,
+    /// Key columns of probe rows.
+    probe_keys: Vec<usize>,
+    /// Memory budget the build side is charged to.
+    memory: Option<SharedMemoryContext>,
+}
+
+impl JoinHashTable {
+    /// Creates an empty table with [`DEFAULT_JOIN_PARTITIONS`] partitions.
+    #[must_use]
+    pub fn new(build_keys: Vec<usize>, probe_keys: Vec<usize>) -> Self {
+        Self::with_partitions(build_keys, probe_keys, DEFAULT_JOIN_PARTITIONS)
+    }
+
+    /// Creates an empty table with `num_partitions` partitions.
+    #[must_use]
+    pub fn with_partitions(
+        build_keys: Vec<usize>,
+        probe_keys: Vec<usize>,
+        num_partitions: usize,
+    ) -> Self {
+        Self {
+            partitions: (0..num_partitions.max(1))
+                .map(|_| RwLock::new(JoinPartition::default()))
+                .collect(),
+            build_keys,
+            probe_keys,
+            memory: None,
+        }
+    }
+
+    /// Charges build rows to a query's memory context, spilling partitions
+    /// once it runs out.
+    #[must_use]
+    pub fn with_memory_context(mut self, memory: SharedMemoryContext) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Returns the number of partitions.
+    #[must_use]
+    pub fn num_partitions(&self) -> usize {
+        self.partitions.len()
+    }
+
+    /// Returns how many partitions have been written to disk.
+    #[must_use]
+    pub fn spilled_partitions(&self) -> usize {
+        self.partitions
+            .iter()
+            .filter(|p| p.read().is_spilled())
+            .count()
+    }
+
+    /// Inserts the rows of a build-side chunk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the memory budget runs out and spilling isn't
+    /// possible, or if writing a spilled partition fails.
+    pub fn insert(&self, chunk: &DataChunk) -> Result<(), OperatorError> {
+        for (partition_idx, rows) in self.bucket_rows(chunk, &self.build_keys) {
+            let mut partition = self.partitions[partition_idx].write();
+            if partition.is_spilled() {
+                let file = partition.build_file.as_mut().expect("spilled partition");
+                write_rows(file, rows.iter().map(|(_, row)| row))?;
+                partition.build_rows += rows.len();
+                continue;
+            }
+            let bytes: usize = rows.iter().map(|(_, row)| row_memory_size(row)).sum();
+            for (key, row) in rows {
+                partition.rows.entry(key).or_default().push(row);
+            }
+            partition.bytes += bytes;
+            drop(partition);
+            self.charge(bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Joins the rows of a probe-side chunk with the in-memory partitions.
+    ///
+    /// Each output row is the probe row followed by the matching build row.
+    /// Rows hashing to a spilled partition are set aside for
+    /// [`join_spilled()`](Self::join_spilled).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if setting rows aside fails.
+    pub fn probe(&self, chunk: &DataChunk) -> Result<Vec<Vec<Value>>, OperatorError> {
+        let mut output = Vec::new();
+        for (partition_idx, rows) in self.bucket_rows(chunk, &self.probe_keys) {
+            let partition = self.partitions[partition_idx].read();
+            if !partition.is_spilled() {
+                for (key, row) in &rows {
+                    if let Some(matches) = partition.rows.get(key) {
+                        output.extend(matches.iter().map(|build| joined_row(row, build)));
+                    }
+                }
+                continue;
+            }
+            drop(partition);
+
+            let mut partition = self.partitions[partition_idx].write();
+            if partition.probe_file.is_none() {
+                partition.probe_file = Some(
+                    self.spill_manager()?
+                        .create_file("join_probe")
+                        .map_err(spill_error)?,
+                );
+            }
+            let file = partition.probe_file.as_mut().expect("probe file");
+            write_rows(file, rows.iter().map(|(_, row)| row))?;
+            partition.probe_rows += rows.len();
+        }
+        Ok(output)
+    }
+
+    /// Joins the spilled partitions, one at a time, once every probe row
+    /// has been seen.
+    ///
+    /// The in-memory partitions are released first, so each spilled
+    /// partition is reloaded into the memory they held.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a spilled partition doesn't fit in the memory
+    /// budget on its own, or reading it back fails.
+    pub fn join_spilled(&self, chunk_size: usize) -> Result<Vec<DataChunk>, OperatorError> {
+        self.release_in_memory();
+
+        let mut chunks = Vec::new();
+        for partition in &self.partitions {
+            let mut partition = partition.write();
+            let Some(mut build_file) = partition.build_file.take() else {
+                continue;
+            };
+            let mut probe_file = partition.probe_file.take();
+            let build_rows = std::mem::take(&mut partition.build_rows);
+            let probe_rows = std::mem::take(&mut partition.probe_rows);
+            drop(partition);
+
+            let output = match &mut probe_file {
+                Some(probe_file) => build_file
+                    .finish_write()
+                    .and_then(|()| probe_file.finish_write())
+                    .map_err(spill_error)
+                    .and_then(|()| {
+                        self.join_spilled_partition(&build_file, build_rows, probe_file, probe_rows)
+                    }),
+                None => Ok(Vec::new()),
+            };
+            self.delete_file(build_file);
+            if let Some(probe_file) = probe_file {
+                self.delete_file(probe_file);
+            }
+            chunks.extend(rows_to_chunks(output?, chunk_size)?);
+        }
+        Ok(chunks)
+    }
+
+    /// Joins one spilled partition's probe rows with its build rows.
+    fn join_spilled_partition(
+        &self,
+        build_file: &SpillFile,
+        build_rows: usize,
+        probe_file: &SpillFile,
+        probe_rows: usize,
+    ) -> Result<Vec<Vec<Value>>, OperatorError> {
+        let mut table: HashMap<HashKey, Vec<Vec<Value>>> = HashMap::new();
+        let mut bytes = 0;
+        for row in read_rows(build_file, build_rows)? {
+            let row = row?;
+            bytes += row_memory_size(&row);
+            if let Some(key) = row_key(&row, &self.build_keys) {
+                table.entry(key).or_default().push(row);
+            }
+        }
+        if let Some(memory) = &self.memory
+            && !memory.lock().reserve(bytes)
+        {
+            return Err(OperatorError::MemoryLimitExceeded { requested: bytes });
+        }
+
+        let mut output = Vec::new();
+        let result = read_rows(probe_file, probe_rows).and_then(|rows| {
+            for row in rows {
+                let row = row?;
+                if let Some(matches) = row_key(&row, &self.probe_keys).and_then(|k| table.get(&k)) {
+                    output.extend(matches.iter().map(|build| joined_row(&row, build)));
+                }
+            }
+            Ok(())
+        });
+        if let Some(memory) = &self.memory {
+            memory.lock().release(bytes);
+        }
+        result.map(|()| output)
+    }
+
+    /// Groups a chunk's rows by partition, dropping rows with NULL keys.
+    fn bucket_rows(
+        &self,
+        chunk: &DataChunk,
+        keys: &[usize],
+    ) -> HashMap<usize, Vec<(HashKey, Vec<Value>)>> {
+        let mut buckets: HashMap<usize, Vec<(HashKey, Vec<Value>)>> = HashMap::new();
+        let col_count = chunk.column_count();
+        for row_idx in chunk.selected_indices() {
+            let row: Vec<Value> = (0..col_count)
+                .map(|col_idx| {
+                    chunk
+                        .column(col_idx)
+                        .and_then(|col| col.get_value(row_idx))
+                        .unwrap_or(Value::Null)
+                })
+                .collect();
+            let Some(key) = row_key(&row, keys) else {
+                continue;
+            };
+            let mut hasher = DefaultHasher::new();
+            key.hash(&mut hasher);
+            let partition_idx = (hasher.finish() % self.partitions.len() as u64) as usize;
+            buckets.entry(partition_idx).or_default().push((key, row));
+        }
+        buckets
+    }
+
+    /// Charges `bytes` of build rows, spilling the largest partitions until
+    /// the budget covers them.
+    fn charge(&self, bytes: usize) -> Result<(), OperatorError> {
+        let Some(memory) = &self.memory else {
+            return Ok(());
+        };
+        if memory.lock().account(bytes) {
+            return Ok(());
+        }
+        if !memory.lock().spill_enabled(SpillableOperator::Join) {
+            return Err(OperatorError::MemoryLimitExceeded { requested: bytes });
+        }
+        let manager = self.spill_manager()?;
+        loop {
+            let freed = self.spill_largest(&manager)?;
+            let mut memory = memory.lock();
+            memory.release(freed);
+            if memory.account(0) {
+                return Ok(());
+            }
+            if freed == 0 {
+                return Err(OperatorError::MemoryLimitExceeded { requested: bytes });
+            }
+        }
+    }
+
+    /// Writes the largest in-memory partition to disk, returning the bytes
+    /// it held.
+    fn spill_largest(&self, manager: &SpillManager) -> Result<usize, OperatorError> {
+        loop {
+            let Some(largest) = (0..self.partitions.len())
+                .filter(|&idx| self.partitions[idx].read().bytes > 0)
+                .max_by_key(|&idx| self.partitions[idx].read().bytes)
+            else {
+                return Ok(0);
+            };
+
+            let mut partition = self.partitions[largest].write();
+            // Another worker may have spilled it in the meantime
+            if partition.is_spilled() || partition.bytes == 0 {
+                continue;
+            }
+            let mut file = manager.create_file("join_build").map_err(spill_error)?;
+            let rows = std::mem::take(&mut partition.rows);
+            write_rows(&mut file, rows.values().flatten())?;
+            partition.build_rows = rows.values().map(Vec::len).sum();
+            partition.build_file = Some(file);
+            return Ok(std::mem::take(&mut partition.bytes));
+        }
+    }
+
+    /// Drops the in-memory partitions and gives their memory back.
+    fn release_in_memory(&self) {
+        let mut freed = 0;
+        for partition in &self.partitions {
+            let mut partition = partition.write();
+            partition.rows = HashMap::new();
+            freed += std::mem::take(&mut partition.bytes);
+        }
+        if let Some(memory) = &self.memory {
+            memory.lock().release(freed);
+        }
+    }
+
+    /// Returns the spill manager of the memory context.
+    fn spill_manager(&self) -> Result<Arc<SpillManager>, OperatorError> {
+        self.memory
+            .as_ref()
+            .and_then(|memory| memory.lock().spill_manager().transpose())
+            .transpose()
+            .map_err(spill_error)?
+            .ok_or_else(|| {
+                OperatorError::Execution("hash join spilled without a spill directory".into())
+            })
+    }
+
+    /// Removes a spill file once its rows have been joined.
+    fn delete_file(&self, file: SpillFile) {
+        if let Ok(manager) = self.spill_manager() {
+            manager.unregister_file(file.path());
+        }
+        let _ = file.delete();
+    }
+}
+
+impl Drop for JoinHashTable {
+    fn drop(&mut self) {
+        for partition in &self.partitions {
+            let mut partition = partition.write();
+            let files = [partition.build_file.take(), partition.probe_file.take()];
+            drop(partition);
+            for file in files.into_iter().flatten() {
+                self.delete_file(file);
+            }
+        }
+        self.release_in_memory();
+    }
+}
+
+/// Push operator that inserts a worker's build rows into a shared
+/// [`JoinHashTable`].
+///
+/// Emits nothing; the table is probed afterwards by
+/// [`HashJoinProbeOperator`].
+pub struct HashJoinBuildOperator {
+    table: Arc<JoinHashTable>,
+}
+
+impl HashJoinBuildOperator {
+    /// Creates a build operator over a shared table.
+    #[must_use]
+    pub fn new(table: Arc<JoinHashTable>) -> Self {
+        Self { table }
+    }
+}
+
+impl PushOperator for HashJoinBuildOperator {
+    fn push(&mut self, chunk: DataChunk, _sink: &mut dyn Sink) -> Result<bool, OperatorError> {
+        self.table.insert(&chunk)?;
+        Ok(true)
+    }
+
+    fn finalize(&mut self, _sink: &mut dyn Sink) -> Result<(), OperatorError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "HashJoinBuild"
+    }
+}
+
+/// Push operator that probes a built [`JoinHashTable`] with a worker's
+/// rows.
+///
+/// Emits each probe row followed by every build row it matches.
+pub struct HashJoinProbeOperator {
+    table: Arc<JoinHashTable>,
+}
+
+impl HashJoinProbeOperator {
+    /// Creates a probe operator over a shared table.
+    #[must_use]
+    pub fn new(table: Arc<JoinHashTable>) -> Self {
+        Self { table }
+    }
+}
+
+impl PushOperator for HashJoinProbeOperator {
+    fn push(&mut self, chunk: DataChunk, sink: &mut dyn Sink) -> Result<bool, OperatorError> {
+        let rows = self.table.probe(&chunk)?;
+        for chunk in rows_to_chunks(rows, DEFAULT_CHUNK_SIZE)? {
+            if !sink.consume(chunk)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn finalize(&mut self, _sink: &mut dyn Sink) -> Result<(), OperatorError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "HashJoinProbe"
+    }
+}
+
+/// Inner equi-join of two parallel sources.
+///
+/// Builds a [`JoinHashTable`] from the build source with one parallel
+/// pipeline, probes it from the probe source with another, then joins any
+/// partitions that spilled. Output rows are the probe columns followed by
+/// the build columns, in no particular order.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use grafeo_common::types::Value;
+/// use grafeo_core::execution::parallel::{
+///     ParallelHashJoin, ParallelPipelineConfig, ParallelVectorSource,
+/// };
+///
+/// let build = Arc::new(ParallelVectorSource::single_column(
+///     (0..100).map(Value::Int64).collect(),
+/// ));
+/// let probe = Arc::new(ParallelVectorSource::single_column(
+///     (50..150).map(Value::Int64).collect(),
+/// ));
+///
+/// let join = ParallelHashJoin::new(vec![0], vec![0], ParallelPipelineConfig::for_testing());
+/// let result = join.execute(build, probe)?;
+/// let rows: usize = result.chunks.iter().map(|c| c.len()).sum();
+/// assert_eq!(rows, 50);
+/// # Ok::<(), grafeo_core::execution::operators::OperatorError>(())
+/// ```
+pub struct ParallelHashJoin {
+    build_keys: Vec<usize>,
+    probe_keys: Vec<usize>,
+    config: ParallelPipelineConfig,
+    num_partitions: usize,
+    memory: Option<SharedMemoryContext>,
+}
+
+impl ParallelHashJoin {
+    /// Creates a join on `build_keys` of the build rows equal to
+    /// `probe_keys` of the probe rows.
+    #[must_use]
+    pub fn new(
+        build_keys: Vec<usize>,
+        probe_keys: Vec<usize>,
+        config: ParallelPipelineConfig,
+    ) -> Self {
+        Self {
+            build_keys,
+            probe_keys,
+            config,
+            num_partitions: DEFAULT_JOIN_PARTITIONS,
+            memory: None,
+        }
+    }
+
+    /// Sets how many partitions the hash table is split into.
+    #[must_use]
+    pub fn with_partitions(mut self, num_partitions: usize) -> Self {
+        self.num_partitions = num_partitions.max(1);
+        self
+    }
+
+    /// Charges the build side to a query's memory context, spilling
+    /// partitions once it runs out.
+    #[must_use]
+    pub fn with_memory_context(mut self, memory: SharedMemoryContext) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
+    /// Runs the join.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either pipeline fails, or the memory budget runs
+    /// out and spilling isn't possible.
+    pub fn execute(
+        &self,
+        build: Arc<dyn ParallelSource>,
+        probe: Arc<dyn ParallelSource>,
+    ) -> Result<ParallelPipelineResult, OperatorError> {
+        let mut table = JoinHashTable::with_partitions(
+            self.build_keys.clone(),
+            self.probe_keys.clone(),
+            self.num_partitions,
+        );
+        if let Some(memory) = &self.memory {
+            table = table.with_memory_context(Arc::clone(memory));
+        }
+        let table = Arc::new(table);
+
+        let build_table = Arc::clone(&table);
+        let build_factory = CloneableOperatorFactory::new()
+            .with_operator(move || Box::new(HashJoinBuildOperator::new(Arc::clone(&build_table))));
+        ParallelPipeline::new(build, Arc::new(build_factory), self.config.clone()).execute()?;
+
+        let probe_table = Arc::clone(&table);
+        let probe_factory = CloneableOperatorFactory::new()
+            .with_operator(move || Box::new(HashJoinProbeOperator::new(Arc::clone(&probe_table))));
+        let mut result =
+            ParallelPipeline::new(probe, Arc::new(probe_factory), self.config.clone()).execute()?;
+
+        result
+            .chunks
+            .extend(table.join_spilled(self.config.chunk_size)?);
+        Ok(result)
+    }
+}
+
+/// Pull operator that runs a [`ParallelHashJoin`] under a serial plan.
+///
+/// The first call to [`next()`](Operator::next) drains both inputs, joins
+/// them with the parallel pipelines, and then hands out the joined rows
+/// converted to the output schema. Rows are the probe columns followed by
+/// the build columns, in no particular order.
+pub struct ParallelHashJoinOperator {
+    probe: Box<dyn Operator>,
+    build: Box<dyn Operator>,
+    join: ParallelHashJoin,
+    output_schema: Vec<LogicalType>,
+    output: Option<std::vec::IntoIter<DataChunk>>,
+}
+
+impl ParallelHashJoinOperator {
+    /// Creates an operator joining the rows of `probe` with those of
+    /// `build`, as configured by `join`.
+    #[must_use]
+    pub fn new(
+        probe: Box<dyn Operator>,
+        build: Box<dyn Operator>,
+        join: ParallelHashJoin,
+        output_schema: Vec<LogicalType>,
+    ) -> Self {
+        Self {
+            probe,
+            build,
+            join,
+            output_schema,
+            output: None,
+        }
+    }
+
+    /// Drains both inputs and joins them.
+    fn run(&mut self) -> Result<Vec<DataChunk>, OperatorError> {
+        let build = drain(self.build.as_mut())?;
+        let probe = drain(self.probe.as_mut())?;
+        let result = self.join.execute(
+            Arc::new(ParallelChunkSource::new(build)),
+            Arc::new(ParallelChunkSource::new(probe)),
+        )?;
+        Ok(result
+            .chunks
+            .iter()
+            .filter(|chunk| !chunk.is_empty())
+            .map(|chunk| self.typed(chunk))
+            .collect())
+    }
+
+    /// Copies a joined chunk into columns of the output schema's types, so
+    /// node and edge columns come out as ids again.
+    fn typed(&self, chunk: &DataChunk) -> DataChunk {
+        let columns = self
+            .output_schema
+            .iter()
+            .enumerate()
+            .map(|(col_idx, data_type)| {
+                let mut column = ValueVector::with_type(data_type.clone());
+                for row_idx in chunk.selected_indices() {
+                    let value = chunk
+                        .column(col_idx)
+                        .and_then(|col| col.get_value(row_idx))
+                        .unwrap_or(Value::Null);
+                    column.push_value(value);
+                }
+                column
+            })
+            .collect();
+        DataChunk::new(columns)
+    }
+}
+
+impl Operator for ParallelHashJoinOperator {
+    fn next(&mut self) -> OperatorResult {
+        if self.output.is_none() {
+            self.output = Some(self.run()?.into_iter());
+        }
+        Ok(self.output.as_mut().and_then(Iterator::next))
+    }
+
+    fn reset(&mut self) {
+        self.probe.reset();
+        self.build.reset();
+        self.output = None;
+    }
+
+    fn name(&self) -> &'static str {
+        "ParallelHashJoin"
+    }
+}
+
+/// Collects an operator's non-empty chunks.
+fn drain(operator: &mut dyn Operator) -> Result<Vec<DataChunk>, OperatorError> {
+    let mut chunks = Vec::new();
+    while let Some(chunk) = operator.next()? {
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        }
+    }
+    Ok(chunks)
+}
+
+/// Reports a failed spill file read or write.
+fn spill_error(err: std::io::Error) -> OperatorError {
+    OperatorError::Execution(err.to_string())
+}
+
+/// Approximate memory held by a buffered row.
+fn row_memory_size(row: &[Value]) -> usize {
+    row.len() * std::mem::size_of::<Value>()
+}
+
+/// Builds the join key of a row, or `None` if any key column is NULL.
+fn row_key(row: &[Value], keys: &[usize]) -> Option<HashKey> {
+    let mut parts = Vec::with_capacity(keys.len());
+    for &col in keys {
+        match row.get(col) {
+            None | Some(Value::Null) => return None,
+            Some(value) => parts.push(HashKey::from_value(value)),
+        }
+    }
+    if parts.len() == 1 {
+        parts.pop()
+    } else {
+        Some(HashKey::Composite(parts))
+    }
+}
+
+/// Concatenates a probe row and a build row.
+fn joined_row(probe: &[Value], build: &[Value]) -> Vec<Value> {
+    probe.iter().chain(build).cloned().collect()
+}
+
+/// Appends rows to a spill file.
+fn write_rows<'a>(
+    file: &mut SpillFile,
+    rows: impl Iterator<Item = &'a Vec<Value>>,
+) -> Result<(), OperatorError> {
+    let mut buf = Vec::new();
+    for row in rows {
+        serialize_row(row, &mut buf).map_err(spill_error)?;
+    }
+    file.write_all(&buf).map_err(spill_error)?;
+    Ok(())
+}
+
+/// Reads `count` rows back from a spill file.
+fn read_rows(
+    file: &SpillFile,
+    count: usize,
+) -> Result<impl Iterator<Item = Result<Vec<Value>, OperatorError>>, OperatorError> {
+    let mut reader = BufReader::new(File::open(file.path()).map_err(spill_error)?);
+    Ok((0..count).map(move |_| deserialize_row(&mut reader, 0).map_err(spill_error)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::memory::ExecutionMemoryContext;
+    use crate::execution::parallel::ParallelVectorSource;
+    use grafeo_common::memory::buffer::{BufferManager, BufferManagerConfig};
+
+    /// Build rows `(key, id)` where a quarter of the rows share key 0.
+    fn skewed_build(rows: i64) -> Vec<Vec<Value>> {
+        let keys = (0..rows)
+            .map(|i| Value::Int64(if i % 4 == 0 { 0 } else { i % 500 }))
+            .collect();
+        let ids = (0..rows).map(Value::Int64).collect();
+        vec![keys, ids]
+    }
+
+    /// Probe rows `(key, id)` cycling over keys 0..700, with some NULL keys.
+    fn probe_columns(rows: i64) -> Vec<Vec<Value>> {
+        let keys = (0..rows)
+            .map(|i| {
+                if i % 97 == 0 {
+                    Value::Null
+                } else {
+                    Value::Int64(i % 700)
+                }
+            })
+            .collect();
+        let ids = (0..rows).map(|i| Value::Int64(-i)).collect();
+        vec![keys, ids]
+    }
+
+    /// Joins column-major inputs on column 0 with a nested loop.
+    fn reference_join(build: &[Vec<Value>], probe: &[Vec<Value>]) -> Vec<Vec<i64>> {
+        let mut rows = Vec::new();
+        for p in 0..probe[0].len() {
+            for b in 0..build[0].len() {
+                if probe[0][p] != Value::Null && probe[0][p] == build[0][b] {
+                    rows.push(vec![
+                        probe[0][p].as_int64().unwrap(),
+                        probe[1][p].as_int64().unwrap(),
+                        build[0][b].as_int64().unwrap(),
+                        build[1][b].as_int64().unwrap(),
+                    ]);
+                }
+            }
+        }
+        rows.sort();
+        rows
+    }
+
+    fn sorted_rows(result: &ParallelPipelineResult) -> Vec<Vec<i64>> {
+        let mut rows: Vec<Vec<i64>> = result
+            .chunks
+            .iter()
+            .flat_map(|chunk| {
+                chunk.selected_indices().map(move |row| {
+                    (0..chunk.column_count())
+                        .map(|col| {
+                            chunk
+                                .column(col)
+                                .and_then(|c| c.get_value(row))
+                                .and_then(|v| v.as_int64())
+                                .unwrap()
+                        })
+                        .collect()
+                })
+            })
+            .collect();
+        rows.sort();
+        rows
+    }
+
+    #[test]
+    fn test_parallel_join_matches_reference_on_skewed_keys() {
+        let build = skewed_build(4_000);
+        let probe = probe_columns(3_000);
+        let expected = reference_join(&build, &probe);
+
+        let join = ParallelHashJoin::new(
+            vec![0],
+            vec![0],
+            ParallelPipelineConfig::default().with_workers(4),
+        );
+        let result = join
+            .execute(
+                Arc::new(ParallelVectorSource::new(build)),
+                Arc::new(ParallelVectorSource::new(probe)),
+            )
+            .unwrap();
+
+        assert_eq!(sorted_rows(&result), expected);
+    }
+
+    #[test]
+    fn test_parallel_join_spills_under_low_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let spill_dir = dir.path().join("spill");
+        let manager = BufferManager::new(BufferManagerConfig {
+            budget: 256 * 1024,
+            spill_path: Some(spill_dir.clone()),
+            ..BufferManagerConfig::default()
+        });
+        let memory = ExecutionMemoryContext::new(Arc::clone(&manager))
+            .with_granularity(0)
+            .into_shared();
+
+        let build = skewed_build(20_000);
+        let probe = probe_columns(2_000);
+        let expected = reference_join(&build, &probe);
+
+        let join = ParallelHashJoin::new(
+            vec![0],
+            vec![0],
+            ParallelPipelineConfig::default().with_workers(4),
+        )
+        .with_memory_context(Arc::clone(&memory));
+        let result = join
+            .execute(
+                Arc::new(ParallelVectorSource::new(build)),
+                Arc::new(ParallelVectorSource::new(probe)),
+            )
+            .unwrap();
+
+        assert_eq!(sorted_rows(&result), expected);
+        assert!(spill_dir.exists());
+        assert_eq!(std::fs::read_dir(&spill_dir).unwrap().count(), 0);
+        assert_eq!(memory.lock().total_allocated(), 0);
+    }
+
+    #[test]
+    fn test_parallel_join_fails_when_spill_disabled() {
+        let manager = BufferManager::with_budget(64 * 1024);
+        let mut memory = ExecutionMemoryContext::new(Arc::clone(&manager)).with_granularity(0);
+        memory.disable_spill(SpillableOperator::Join);
+
+        let join = ParallelHashJoin::new(vec![0], vec![0], ParallelPipelineConfig::for_testing())
+            .with_memory_context(memory.into_shared());
+        let result = join.execute(
+            Arc::new(ParallelVectorSource::new(skewed_build(20_000))),
+            Arc::new(ParallelVectorSource::new(probe_columns(10))),
+        );
+        drop(join);
+
+        assert!(matches!(
+            result,
+            Err(OperatorError::MemoryLimitExceeded { .. })
+        ));
+        assert_eq!(manager.allocated(), 0);
+    }
+}
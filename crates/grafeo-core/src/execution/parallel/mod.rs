@@ -30,6 +30,8 @@
 //! - **Work-Stealing**: Workers steal morsels from others when their queue is empty
 //! - **Per-Worker Pipelines**: Each worker has its own operator chain instances
 //! - **Pipeline Breakers**: Operators like Sort/Aggregate that need merge phase
+//! - **Parallel Hash Join**: Workers build one shared, partitioned hash table,
+//!   then probe it concurrently; partitions spill when memory runs out
 //!
 //! # Example
 //!
@@ -56,6 +58,7 @@
 //! println!("Processed {} rows", result.rows_processed);
 //! ```
 
+mod join;
 mod merge;
 mod morsel;
 mod pipeline;
@@ -63,6 +66,10 @@ mod scheduler;
 mod source;
 
 // Re-export main types
+pub use join::{
+    DEFAULT_JOIN_PARTITIONS, HashJoinBuildOperator, HashJoinProbeOperator, JoinHashTable,
+    ParallelHashJoin, ParallelHashJoinOperator,
+};
 pub use merge::{
     MergeableAccumulator, MergeableOperator, SortKey, concat_parallel_results,
     merge_distinct_results, merge_sorted_chunks, merge_sorted_runs, rows_to_chunks,
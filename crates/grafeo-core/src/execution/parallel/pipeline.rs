@@ -97,6 +97,11 @@ pub struct ParallelPipelineResult {
     pub num_workers: usize,
     /// Total morsels processed.
     pub morsels_processed: usize,
+    /// Most workers that processed morsels at the same time.
+    ///
+    /// Lower than `num_workers` when there wasn't enough work to go around;
+    /// 1 means the pipeline effectively ran serially.
+    pub max_concurrent_workers: usize,
     /// Total rows processed.
     pub rows_processed: usize,
 }
@@ -205,6 +210,7 @@ impl ParallelPipeline {
                 chunks: Vec::new(),
                 num_workers: self.config.num_workers,
                 morsels_processed: 0,
+                max_concurrent_workers: 0,
                 rows_processed: 0,
             });
         }
@@ -264,6 +270,7 @@ impl ParallelPipeline {
             chunks,
             num_workers: self.config.num_workers,
             morsels_processed: total_morsels,
+            max_concurrent_workers: scheduler.peak_concurrency(),
             rows_processed: rows_processed.load(Ordering::Relaxed),
         })
     }
@@ -537,9 +544,60 @@ mod tests {
 
         assert!(result.chunks.is_empty());
         assert_eq!(result.morsels_processed, 0);
+        assert_eq!(result.max_concurrent_workers, 0);
         assert_eq!(result.rows_processed, 0);
     }
 
+    #[test]
+    fn test_parallel_pipeline_reports_concurrency() {
+        /// Pass-through operator that takes a moment per chunk, so every
+        /// worker gets a chance to pick up morsels.
+        struct SlowOp;
+
+        impl PushOperator for SlowOp {
+            fn push(
+                &mut self,
+                chunk: DataChunk,
+                sink: &mut dyn Sink,
+            ) -> Result<bool, OperatorError> {
+                thread::sleep(std::time::Duration::from_millis(1));
+                sink.consume(chunk)
+            }
+
+            fn finalize(&mut self, _sink: &mut dyn Sink) -> Result<(), OperatorError> {
+                Ok(())
+            }
+
+            fn name(&self) -> &'static str {
+                "Slow"
+            }
+        }
+
+        let run = |rows| {
+            let source = Arc::new(RangeSource::new(rows));
+            let factory =
+                Arc::new(CloneableOperatorFactory::new().with_operator(|| Box::new(SlowOp)));
+            let config = ParallelPipelineConfig::default().with_workers(4);
+            ParallelPipeline::new(source, factory, config)
+                .execute()
+                .unwrap()
+        };
+
+        let large = run(DEFAULT_MORSEL_SIZE * 8);
+        assert_eq!(large.morsels_processed, 8);
+        assert!(
+            large.max_concurrent_workers > 1,
+            "large scan ran on {} worker(s)",
+            large.max_concurrent_workers
+        );
+        assert!(large.max_concurrent_workers <= 4);
+
+        // A single morsel can only ever keep one worker busy
+        let tiny = run(10);
+        assert_eq!(tiny.morsels_processed, 1);
+        assert_eq!(tiny.max_concurrent_workers, 1);
+    }
+
     #[test]
     fn test_parallel_pipeline_passthrough() {
         let source = Arc::new(RangeSource::new(100));
@@ -29,6 +29,10 @@ pub struct MorselScheduler {
     submission_done: AtomicBool,
     /// Whether all work is done.
     done: AtomicBool,
+    /// Workers currently processing a morsel.
+    busy_workers: AtomicUsize,
+    /// Most workers that were processing morsels at the same time.
+    peak_busy_workers: AtomicUsize,
 }
 
 impl MorselScheduler {
@@ -43,6 +47,8 @@ impl MorselScheduler {
             total_submitted: AtomicUsize::new(0),
             submission_done: AtomicBool::new(false),
             done: AtomicBool::new(false),
+            busy_workers: AtomicUsize::new(0),
+            peak_busy_workers: AtomicUsize::new(0),
         }
     }
 
@@ -155,6 +161,25 @@ impl MorselScheduler {
     pub fn total_submitted(&self) -> usize {
         self.total_submitted.load(Ordering::Relaxed)
     }
+
+    /// Returns the most workers that processed morsels at the same time.
+    ///
+    /// Only work taken through a [`WorkerHandle`] counts. A value of 1 after
+    /// a run means it effectively ran serially, usually because the input
+    /// fit in a single morsel.
+    #[must_use]
+    pub fn peak_concurrency(&self) -> usize {
+        self.peak_busy_workers.load(Ordering::Relaxed)
+    }
+
+    fn worker_started(&self) {
+        let busy = self.busy_workers.fetch_add(1, Ordering::Relaxed) + 1;
+        self.peak_busy_workers.fetch_max(busy, Ordering::Relaxed);
+    }
+
+    fn worker_finished(&self) {
+        self.busy_workers.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 impl std::fmt::Debug for MorselScheduler {
@@ -204,6 +229,14 @@ impl WorkerHandle {
     ///
     /// Tries: local queue -> global queue -> steal from others
     pub fn get_work(&self) -> Option<Morsel> {
+        let morsel = self.find_work();
+        if morsel.is_some() {
+            self.scheduler.worker_started();
+        }
+        morsel
+    }
+
+    fn find_work(&self) -> Option<Morsel> {
         // Try local queue first
         if let Some(morsel) = self.local_queue.pop() {
             return Some(morsel);
@@ -237,6 +270,7 @@ impl WorkerHandle {
 
     /// Marks the current morsel as complete.
     pub fn complete_morsel(&self) {
+        self.scheduler.worker_finished();
         self.scheduler.complete_morsel();
     }
 
@@ -325,6 +359,29 @@ mod tests {
         assert!(handle.is_done());
     }
 
+    #[test]
+    fn test_peak_concurrency() {
+        let scheduler = Arc::new(MorselScheduler::new(2));
+        scheduler.submit_batch((0..3).map(|i| Morsel::new(i, 0, 0, 100)).collect());
+        scheduler.finish_submission();
+
+        let first = WorkerHandle::new(Arc::clone(&scheduler));
+        let second = WorkerHandle::new(Arc::clone(&scheduler));
+
+        // One worker at a time
+        first.get_work().unwrap();
+        first.complete_morsel();
+        assert_eq!(scheduler.peak_concurrency(), 1);
+
+        // Both workers busy at once
+        first.get_work().unwrap();
+        second.get_work().unwrap();
+        first.complete_morsel();
+        second.complete_morsel();
+        assert_eq!(scheduler.peak_concurrency(), 2);
+        assert!(scheduler.is_done());
+    }
+
     #[test]
     fn test_worker_local_queue() {
         let scheduler = Arc::new(MorselScheduler::new(2));
@@ -4,7 +4,9 @@
 //! forward through operators via `push()` calls, enabling better parallelism
 //! and cache utilization compared to pull-based execution.
 
+use super::cancel::Interrupt;
 use super::chunk::DataChunk;
+use super::memory::SharedMemoryContext;
 use super::operators::OperatorError;
 
 /// Hint for preferred chunk size.
@@ -101,6 +103,10 @@ pub struct Pipeline {
     source: Box<dyn Source>,
     operators: Vec<Box<dyn PushOperator>>,
     sink: Box<dyn Sink>,
+    /// Checked before each source chunk.
+    interrupt: Interrupt,
+    /// Sizes source chunks by row width, when set.
+    memory: Option<SharedMemoryContext>,
 }
 
 impl Pipeline {
@@ -114,6 +120,8 @@ impl Pipeline {
             source,
             operators,
             sink,
+            interrupt: Interrupt::default(),
+            memory: None,
         }
     }
 
@@ -123,6 +131,8 @@ impl Pipeline {
             source,
             operators: Vec::new(),
             sink,
+            interrupt: Interrupt::default(),
+            memory: None,
         }
     }
 
@@ -133,12 +143,47 @@ impl Pipeline {
         self
     }
 
+    /// Stops the pipeline at the next chunk boundary once `interrupt`
+    /// fires.
+    #[must_use]
+    pub fn with_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.interrupt = interrupt;
+        self
+    }
+
+    /// Sizes source chunks to `memory`'s target chunk bytes.
+    ///
+    /// Each chunk is sized from the average row width of the one before,
+    /// so wide rows flow in smaller chunks and narrow rows in larger ones.
+    /// Operators asking for an exact chunk size, or for fewer rows than
+    /// the default, still get what they ask for.
+    #[must_use]
+    pub fn with_memory_context(mut self, memory: SharedMemoryContext) -> Self {
+        self.memory = Some(memory);
+        self
+    }
+
     /// Execute the pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns an operator's error, or [`OperatorError::Cancelled`] or
+    /// [`OperatorError::TimedOut`] if the pipeline's interrupt fired before
+    /// it finished. Operators are left part-way through; drop the pipeline
+    /// to release what they hold.
     pub fn execute(&mut self) -> Result<(), OperatorError> {
-        let chunk_size = self.compute_chunk_size();
+        let hinted = self.compute_chunk_size();
+        let mut chunk_size = self.adapt_chunk_size(hinted, 0);
 
         // Process all chunks from source
-        while let Some(chunk) = self.source.next_chunk(chunk_size)? {
+        loop {
+            self.interrupt.check()?;
+            let Some(chunk) = self.source.next_chunk(chunk_size)? else {
+                break;
+            };
+            if !chunk.is_empty() {
+                chunk_size = self.adapt_chunk_size(hinted, chunk.average_row_width());
+            }
             if !self.push_through(chunk)? {
                 // Early termination requested
                 break;
@@ -146,6 +191,7 @@ impl Pipeline {
         }
 
         // Finalize all operators (important for pipeline breakers)
+        self.interrupt.check()?;
         self.finalize_all()
     }
 
@@ -166,6 +212,32 @@ impl Pipeline {
         size
     }
 
+    /// Sizes the next source chunk for rows of `row_width` bytes, zero if
+    /// none have been seen yet.
+    ///
+    /// Falls back to the `hinted` size without a memory context or when an
+    /// operator asks for an exact size.
+    fn adapt_chunk_size(&self, hinted: usize, row_width: usize) -> usize {
+        let Some(memory) = &self.memory else {
+            return hinted;
+        };
+        let exact = self
+            .operators
+            .iter()
+            .any(|op| matches!(op.preferred_chunk_size(), ChunkSizeHint::Exact(_)));
+        if exact {
+            return hinted;
+        }
+
+        let size = memory.lock().chunk_size_for_row_width(row_width);
+        // Hints that shrink chunks, such as a LIMIT's, still apply
+        if hinted < DEFAULT_CHUNK_SIZE {
+            size.min(hinted)
+        } else {
+            size
+        }
+    }
+
     /// Push a chunk through the operator chain.
     fn push_through(&mut self, chunk: DataChunk) -> Result<bool, OperatorError> {
         if self.operators.is_empty() {
@@ -443,6 +515,167 @@ mod tests {
         pipeline.execute().unwrap();
     }
 
+    /// Holds a memory grant for as long as it lives.
+    struct GrantHoldingSink {
+        memory: crate::execution::SharedMemoryContext,
+    }
+
+    impl Sink for GrantHoldingSink {
+        fn consume(&mut self, chunk: DataChunk) -> Result<bool, OperatorError> {
+            self.memory.lock().reserve(chunk.len() * 8);
+            Ok(true)
+        }
+
+        fn finalize(&mut self) -> Result<(), OperatorError> {
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "GrantHoldingSink"
+        }
+    }
+
+    #[test]
+    fn test_cancelling_generator_fed_pipeline() {
+        use crate::execution::cancel::CancellationToken;
+        use crate::execution::memory::ExecutionMemoryContext;
+        use crate::execution::source::GeneratorSource;
+        use grafeo_common::memory::buffer::BufferManager;
+        use std::sync::Arc;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::time::{Duration, Instant};
+
+        let manager = BufferManager::with_budget(1 << 30);
+        let memory = ExecutionMemoryContext::new(Arc::clone(&manager))
+            .with_granularity(0)
+            .into_shared();
+        let generated = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&generated);
+        // Never runs dry on its own
+        let source = GeneratorSource::new(move |i| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            Some(vec![Value::Int64(i as i64)])
+        });
+        let sink = GrantHoldingSink {
+            memory: Arc::clone(&memory),
+        };
+        let token = CancellationToken::new();
+        let mut pipeline = Pipeline::new(Box::new(source), Vec::new(), Box::new(sink))
+            .with_operator(Box::new(PassThroughOperator))
+            .with_interrupt(Interrupt::new().with_token(token.clone()));
+
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            token.cancel();
+            Instant::now()
+        });
+        let result = pipeline.execute();
+        let stopped = Instant::now();
+        let cancelled_at = canceller.join().unwrap();
+
+        assert!(matches!(result, Err(OperatorError::Cancelled)));
+        assert!(generated.load(Ordering::Relaxed) > 0);
+        // Stopped within a chunk of the cancel
+        assert!(stopped.duration_since(cancelled_at) < Duration::from_secs(1));
+        assert!(manager.allocated() > 0);
+
+        drop(pipeline);
+        drop(memory);
+        assert_eq!(manager.allocated(), 0);
+    }
+
+    #[test]
+    fn test_pipeline_times_out() {
+        use crate::execution::source::GeneratorSource;
+        use std::time::Duration;
+
+        let source = GeneratorSource::new(|i| Some(vec![Value::Int64(i as i64)]));
+        let mut pipeline = Pipeline::simple(Box::new(source), Box::new(TestSink::new()))
+            .with_interrupt(Interrupt::new().with_timeout(Duration::from_millis(10)));
+        assert!(matches!(pipeline.execute(), Err(OperatorError::TimedOut)));
+    }
+
+    /// Records the row count of each chunk it receives.
+    struct ChunkSizeSink {
+        sizes: std::sync::Arc<parking_lot::Mutex<Vec<usize>>>,
+    }
+
+    impl Sink for ChunkSizeSink {
+        fn consume(&mut self, chunk: DataChunk) -> Result<bool, OperatorError> {
+            self.sizes.lock().push(chunk.len());
+            Ok(true)
+        }
+
+        fn finalize(&mut self) -> Result<(), OperatorError> {
+            Ok(())
+        }
+
+        fn name(&self) -> &'static str {
+            "ChunkSizeSink"
+        }
+    }
+
+    /// Runs 20,000 rows of an integer and a `width`-byte string through a
+    /// width-sized pipeline, returning the row count of each chunk.
+    fn chunk_sizes_for_width(width: usize, limit: Option<usize>) -> Vec<usize> {
+        use crate::execution::memory::ExecutionMemoryContext;
+        use crate::execution::operators::push::LimitPushOperator;
+        use crate::execution::source::GeneratorSource;
+        use grafeo_common::memory::buffer::BufferManager;
+        use std::sync::Arc;
+
+        let text: Arc<str> = "x".repeat(width).into();
+        let source = GeneratorSource::new(move |i| {
+            (i < 20_000).then(|| vec![Value::Int64(i as i64), Value::String(Arc::clone(&text))])
+        });
+        let sizes: Arc<parking_lot::Mutex<Vec<usize>>> = Arc::default();
+        let sink = ChunkSizeSink {
+            sizes: Arc::clone(&sizes),
+        };
+        let memory = ExecutionMemoryContext::new(BufferManager::with_budget(1 << 30))
+            .with_target_chunk_bytes(512 * 1024)
+            .with_chunk_size_bounds(32, 4096)
+            .into_shared();
+        let mut pipeline =
+            Pipeline::simple(Box::new(source), Box::new(sink)).with_memory_context(memory);
+        if let Some(limit) = limit {
+            pipeline = pipeline.with_operator(Box::new(LimitPushOperator::new(limit)));
+        }
+        pipeline.execute().unwrap();
+
+        sizes.lock().clone()
+    }
+
+    #[test]
+    fn test_chunk_rows_shrink_as_rows_widen() {
+        let widths = [0, 100, 1_000, 10_000, 1_000_000];
+        let runs: Vec<Vec<usize>> = widths
+            .iter()
+            .map(|&width| chunk_sizes_for_width(width, None))
+            .collect();
+
+        for (width, sizes) in widths.iter().zip(&runs) {
+            assert_eq!(sizes.iter().sum::<usize>(), 20_000, "width {width}");
+            // The first chunk is sized before any row is seen
+            assert_eq!(sizes[0], DEFAULT_CHUNK_SIZE, "width {width}");
+        }
+        let steady: Vec<usize> = runs.iter().map(|sizes| sizes[1]).collect();
+        assert!(
+            steady.windows(2).all(|pair| pair[0] > pair[1]),
+            "{steady:?}"
+        );
+        // Narrow and very wide rows stop at the bounds
+        assert_eq!(steady[0], 4096);
+        assert_eq!(steady[4], 32);
+    }
+
+    #[test]
+    fn test_width_sizing_keeps_small_hints() {
+        let sizes = chunk_sizes_for_width(0, Some(100));
+        assert_eq!(sizes.iter().sum::<usize>(), 100);
+        assert!(sizes.iter().all(|&size| size <= SMALL_CHUNK_SIZE));
+    }
+
     #[test]
     fn test_chunk_collector() {
         let mut collector = ChunkCollector::new();
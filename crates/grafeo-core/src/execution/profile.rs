@@ -0,0 +1,177 @@
+//! Measuring what each operator of a query actually did.
+//!
+//! A [`ProfiledOperator`] sits above an operator and records, in a shared
+//! [`OperatorProfile`], how many rows it handed up, how long its `next()`
+//! calls took, and the most memory the query's
+//! [`ExecutionMemoryContext`](super::ExecutionMemoryContext) held while they
+//! ran. Both time and memory include the operator's inputs, since those run
+//! inside its `next()`.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::Serialize;
+
+use super::memory::SharedMemoryContext;
+use super::operators::{Operator, OperatorResult};
+
+/// What one operator did while a query ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct OperatorStats {
+    /// Rows the operator produced.
+    pub rows: u64,
+    /// Wall time spent in the operator and its inputs.
+    pub elapsed: Duration,
+    /// Most bytes the query's memory context held while the operator ran.
+    pub peak_memory: usize,
+}
+
+/// Statistics a [`ProfiledOperator`] fills in while the query runs.
+///
+/// Clones share the same statistics, so keep one to read them afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct OperatorProfile {
+    stats: Arc<Mutex<OperatorStats>>,
+}
+
+impl OperatorProfile {
+    /// Creates an empty profile.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the statistics recorded so far.
+    #[must_use]
+    pub fn stats(&self) -> OperatorStats {
+        *self.stats.lock()
+    }
+}
+
+/// Records rows, time, and peak memory for the operator it wraps.
+pub struct ProfiledOperator {
+    input: Box<dyn Operator>,
+    profile: OperatorProfile,
+    memory: Option<SharedMemoryContext>,
+}
+
+impl ProfiledOperator {
+    /// Wraps `input`, recording into `profile`.
+    ///
+    /// Peak memory is read from `memory`, and stays zero without one.
+    #[must_use]
+    pub fn new(
+        input: Box<dyn Operator>,
+        profile: OperatorProfile,
+        memory: Option<SharedMemoryContext>,
+    ) -> Self {
+        Self {
+            input,
+            profile,
+            memory,
+        }
+    }
+}
+
+impl Operator for ProfiledOperator {
+    fn next(&mut self) -> OperatorResult {
+        let outer_peak = self
+            .memory
+            .as_ref()
+            .map(|memory| memory.lock().reset_peak());
+        let start = Instant::now();
+        let result = self.input.next();
+        let elapsed = start.elapsed();
+
+        let mut stats = self.profile.stats.lock();
+        stats.elapsed += elapsed;
+        if let Ok(Some(chunk)) = &result {
+            stats.rows += chunk.row_count() as u64;
+        }
+        if let (Some(memory), Some(outer_peak)) = (&self.memory, outer_peak) {
+            let mut memory = memory.lock();
+            stats.peak_memory = stats.peak_memory.max(memory.peak());
+            memory.restore_peak(outer_peak);
+        }
+        result
+    }
+
+    fn reset(&mut self) {
+        self.input.reset();
+    }
+
+    fn name(&self) -> &'static str {
+        self.input.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::DataChunk;
+    use crate::execution::memory::ExecutionMemoryContext;
+    use crate::execution::vector::ValueVector;
+    use grafeo_common::memory::buffer::BufferManager;
+    use grafeo_common::types::Value;
+
+    /// Hands up `chunks` chunks of three rows, accounting and then releasing
+    /// 1000 bytes for each.
+    struct Chunks {
+        chunks: usize,
+        memory: SharedMemoryContext,
+    }
+
+    impl Operator for Chunks {
+        fn next(&mut self) -> OperatorResult {
+            if self.chunks == 0 {
+                return Ok(None);
+            }
+            self.chunks -= 1;
+            let mut memory = self.memory.lock();
+            memory.account(1000);
+            memory.release(1000);
+            let values = [Value::Int64(1), Value::Int64(2), Value::Int64(3)];
+            Ok(Some(DataChunk::new(vec![ValueVector::from_values(
+                &values,
+            )])))
+        }
+
+        fn reset(&mut self) {}
+
+        fn name(&self) -> &'static str {
+            "Chunks"
+        }
+    }
+
+    #[test]
+    fn test_counts_rows_and_nested_peaks() {
+        let memory = ExecutionMemoryContext::new(BufferManager::with_budget(1 << 20)).into_shared();
+        let inner = OperatorProfile::new();
+        let outer = OperatorProfile::new();
+        let chunks = Chunks {
+            chunks: 2,
+            memory: Arc::clone(&memory),
+        };
+        let mut operator = ProfiledOperator::new(
+            Box::new(ProfiledOperator::new(
+                Box::new(chunks),
+                inner.clone(),
+                Some(Arc::clone(&memory)),
+            )),
+            outer.clone(),
+            Some(Arc::clone(&memory)),
+        );
+        assert_eq!(operator.name(), "Chunks");
+
+        while operator.next().unwrap().is_some() {}
+
+        for profile in [&inner, &outer] {
+            let stats = profile.stats();
+            assert_eq!(stats.rows, 6);
+            assert_eq!(stats.peak_memory, 1000);
+        }
+        assert!(outer.stats().elapsed >= inner.stats().elapsed);
+        assert_eq!(memory.lock().peak(), 1000);
+    }
+}
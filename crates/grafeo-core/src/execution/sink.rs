@@ -2,8 +2,14 @@
 //!
 //! Sinks receive the output from pipelines and handle the final results.
 
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::Arc;
+
+use grafeo_common::types::Value;
+
 use super::chunk::DataChunk;
-use super::operators::OperatorError;
+use super::operators::{OperatorError, SortKey, compare_rows};
 use super::pipeline::Sink;
 
 /// Collects all chunks for final query result output.
@@ -217,6 +223,133 @@ impl Sink for LimitingSink {
     }
 }
 
+/// Top-N sink that keeps the first `n` rows in sort order.
+///
+/// Holds at most `n` rows in a bounded heap however much input arrives, so
+/// `ORDER BY ... LIMIT n` never buffers the whole input. Rows with equal
+/// keys keep their arrival order, matching a stable full sort.
+pub struct TopNSink {
+    keys: Arc<[SortKey]>,
+    limit: usize,
+    /// The kept rows, worst on top.
+    heap: BinaryHeap<TopNEntry>,
+    /// Rows consumed so far, used to break ties.
+    seen: u64,
+    /// Most rows held at once.
+    peak_rows: usize,
+}
+
+impl TopNSink {
+    /// Create a top-N sink ordering rows by `keys`.
+    pub fn new(keys: Vec<SortKey>, limit: usize) -> Self {
+        Self {
+            keys: keys.into(),
+            limit,
+            heap: BinaryHeap::with_capacity(limit.min(4096)),
+            seen: 0,
+            peak_rows: 0,
+        }
+    }
+
+    /// Get the limit.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Get the number of rows currently kept.
+    pub fn row_count(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Get the most rows held at once.
+    pub fn peak_rows(&self) -> usize {
+        self.peak_rows
+    }
+
+    /// Offers one row, keeping it if it's among the first `limit`.
+    pub fn push_row(&mut self, row: Vec<Value>) {
+        let entry = TopNEntry {
+            row,
+            seq: self.seen,
+            keys: Arc::clone(&self.keys),
+        };
+        self.seen += 1;
+        if self.heap.len() < self.limit {
+            self.heap.push(entry);
+            self.peak_rows = self.peak_rows.max(self.heap.len());
+        } else if let Some(mut worst) = self.heap.peek_mut()
+            && entry < *worst
+        {
+            *worst = entry;
+        }
+    }
+
+    /// Take the kept rows in sort order.
+    pub fn into_rows(self) -> Vec<Vec<Value>> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|entry| entry.row)
+            .collect()
+    }
+}
+
+impl Sink for TopNSink {
+    fn consume(&mut self, chunk: DataChunk) -> Result<bool, OperatorError> {
+        if self.limit == 0 {
+            return Ok(false);
+        }
+        let col_count = chunk.column_count();
+        for row_idx in chunk.selected_indices() {
+            let row = (0..col_count)
+                .map(|col_idx| {
+                    chunk
+                        .column(col_idx)
+                        .and_then(|col| col.get_value(row_idx))
+                        .unwrap_or(Value::Null)
+                })
+                .collect();
+            self.push_row(row);
+        }
+        Ok(true)
+    }
+
+    fn finalize(&mut self) -> Result<(), OperatorError> {
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "TopNSink"
+    }
+}
+
+/// A row kept by [`TopNSink`], ordered by its keys and then arrival.
+struct TopNEntry {
+    row: Vec<Value>,
+    seq: u64,
+    keys: Arc<[SortKey]>,
+}
+
+impl Ord for TopNEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_rows(&self.keys, &self.row, &other.row).then(self.seq.cmp(&other.seq))
+    }
+}
+
+impl PartialOrd for TopNEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for TopNEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.seq == other.seq
+    }
+}
+
+impl Eq for TopNEntry {}
+
 /// Counting sink that just counts rows without storing data.
 ///
 /// Useful for COUNT(*) queries where only the count matters.
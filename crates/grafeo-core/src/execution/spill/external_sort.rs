@@ -7,6 +7,7 @@
 use super::file::{SpillFile, SpillFileReader};
 use super::manager::SpillManager;
 use super::serializer::{deserialize_row, serialize_row};
+use crate::execution::collation::Collation;
 use grafeo_common::types::Value;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
@@ -39,6 +40,8 @@ pub struct SortKey {
     pub direction: SortDirection,
     /// Null handling.
     pub null_order: NullOrder,
+    /// Collation used when comparing strings.
+    pub collation: Collation,
 }
 
 impl SortKey {
@@ -49,6 +52,7 @@ impl SortKey {
             column,
             direction: SortDirection::Ascending,
             null_order: NullOrder::Last,
+            collation: Collation::Binary,
         }
     }
 
@@ -59,6 +63,7 @@ impl SortKey {
             column,
             direction: SortDirection::Descending,
             null_order: NullOrder::First,
+            collation: Collation::Binary,
         }
     }
 }
@@ -139,7 +144,8 @@ impl ExternalSort {
 
     /// Merges all runs and an optional in-memory buffer into sorted output.
     ///
-    /// Uses k-way merge with a min-heap.
+    /// Collects everything [`merge()`](Self::merge) yields, so the whole
+    /// output has to fit in memory.
     ///
     /// # Errors
     ///
@@ -148,135 +154,53 @@ impl ExternalSort {
         &mut self,
         in_memory_buffer: Vec<Vec<Value>>,
     ) -> std::io::Result<Vec<Vec<Value>>> {
-        let num_runs = self.sorted_runs.len();
-        let has_memory = !in_memory_buffer.is_empty();
-
-        // Special case: no runs and no memory buffer
-        if num_runs == 0 && !has_memory {
-            return Ok(Vec::new());
-        }
-
-        // Special case: only in-memory buffer, no disk runs
-        if num_runs == 0 {
-            let mut sorted_buffer = in_memory_buffer;
-            let keys = self.sort_keys.clone();
-            sorted_buffer.sort_by(|a, b| compare_rows(a, b, &keys));
-            return Ok(sorted_buffer);
-        }
-
-        // Special case: single run and no memory buffer
-        if num_runs == 1 && !has_memory {
-            return self.read_single_run(0);
-        }
-
-        // General case: k-way merge
-        self.k_way_merge(in_memory_buffer)
-    }
-
-    /// Reads a single run from disk.
-    fn read_single_run(&mut self, run_index: usize) -> std::io::Result<Vec<Vec<Value>>> {
-        let spill_file = &self.sorted_runs[run_index];
-        let mut reader = spill_file.reader()?;
-
-        let row_count = reader.read_u64_le()? as usize;
-        let mut rows = Vec::with_capacity(row_count);
-
-        for _ in 0..row_count {
-            let row = deserialize_row(&mut SpillFileReaderAdapter(&mut reader), self.num_columns)?;
+        let mut merged = self.merge(in_memory_buffer)?;
+        let mut rows = Vec::with_capacity(merged.remaining());
+        while let Some(row) = merged.next_row()? {
             rows.push(row);
         }
-
         Ok(rows)
     }
 
-    /// Performs k-way merge of all runs and an optional in-memory buffer.
-    fn k_way_merge(
-        &mut self,
-        mut in_memory_buffer: Vec<Vec<Value>>,
-    ) -> std::io::Result<Vec<Vec<Value>>> {
-        let total_rows = self.total_rows() + in_memory_buffer.len();
-        let mut result = Vec::with_capacity(total_rows);
-
-        // Sort the in-memory buffer first
-        if !in_memory_buffer.is_empty() {
-            let keys = self.sort_keys.clone();
-            in_memory_buffer.sort_by(|a, b| compare_rows(a, b, &keys));
-        }
+    /// Starts a k-way merge of all runs and an in-memory buffer, reading
+    /// each run a row at a time as output is pulled.
+    ///
+    /// The merge is stable: rows that compare equal come out in the order
+    /// their runs were spilled, with the buffer, which holds the newest
+    /// rows, last. The buffer is sorted stably first. The runs stay on disk
+    /// until this sort is dropped or [cleaned up](Self::cleanup), so keep it
+    /// alive while merging.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a run can't be opened or read.
+    pub fn merge(&mut self, mut in_memory_buffer: Vec<Vec<Value>>) -> std::io::Result<MergedRuns> {
+        let sort_keys: Arc<[SortKey]> = self.sort_keys.clone().into();
+        in_memory_buffer.sort_by(|a, b| compare_rows(a, b, &sort_keys));
 
-        // Create readers for all runs
-        let mut run_readers: Vec<RunReader> = Vec::with_capacity(self.sorted_runs.len());
-        for (idx, spill_file) in self.sorted_runs.iter().enumerate() {
+        let mut runs = Vec::with_capacity(self.sorted_runs.len() + 1);
+        for spill_file in &self.sorted_runs {
             let mut reader = spill_file.reader()?;
-            let row_count = reader.read_u64_le()? as usize;
-
-            if row_count > 0 {
-                // Read first row
-                let first_row =
-                    deserialize_row(&mut SpillFileReaderAdapter(&mut reader), self.num_columns)?;
-                run_readers.push(RunReader {
-                    reader,
-                    remaining: row_count - 1,
-                    current_row: Some(first_row),
-                    run_index: idx,
-                    num_columns: self.num_columns,
-                });
-            }
-        }
-
-        // Create in-memory iterator
-        let mut memory_iter = in_memory_buffer.into_iter().peekable();
-        let memory_run_index = self.sorted_runs.len();
-
-        // Build initial heap
-        let mut heap = BinaryHeap::new();
-
-        for run_reader in &run_readers {
-            if let Some(row) = &run_reader.current_row {
-                heap.push(HeapEntry {
-                    row: row.clone(),
-                    run_index: run_reader.run_index,
-                    sort_keys: self.sort_keys.clone(),
-                });
-            }
-        }
-
-        // Add first memory row if present
-        if let Some(row) = memory_iter.peek() {
-            heap.push(HeapEntry {
-                row: row.clone(),
-                run_index: memory_run_index,
-                sort_keys: self.sort_keys.clone(),
-            });
+            let remaining = reader.read_u64_le()? as usize;
+            runs.push(MergeSource::File(RunReader {
+                reader,
+                remaining,
+                num_columns: self.num_columns,
+            }));
         }
+        let buffered = in_memory_buffer.len();
+        runs.push(MergeSource::Memory(in_memory_buffer.into_iter()));
 
-        // Merge loop
-        while let Some(entry) = heap.pop() {
-            result.push(entry.row);
-
-            if entry.run_index == memory_run_index {
-                // Advance memory iterator
-                memory_iter.next();
-                if let Some(row) = memory_iter.peek() {
-                    heap.push(HeapEntry {
-                        row: row.clone(),
-                        run_index: memory_run_index,
-                        sort_keys: self.sort_keys.clone(),
-                    });
-                }
-            } else {
-                // Advance file run
-                let run_reader = &mut run_readers[entry.run_index];
-                if let Some(next_row) = run_reader.next_row()? {
-                    heap.push(HeapEntry {
-                        row: next_row,
-                        run_index: entry.run_index,
-                        sort_keys: self.sort_keys.clone(),
-                    });
-                }
-            }
+        let mut merged = MergedRuns {
+            runs,
+            heap: BinaryHeap::new(),
+            remaining: self.total_rows() + buffered,
+            sort_keys,
+        };
+        for run_index in 0..merged.runs.len() {
+            merged.refill(run_index)?;
         }
-
-        Ok(result)
+        Ok(merged)
     }
 
     /// Cleans up all spill files.
@@ -296,28 +220,82 @@ impl Drop for ExternalSort {
     }
 }
 
-/// Helper struct for reading from a run.
+/// Rows of an [`ExternalSort`] in sorted order, merged from its runs as
+/// they're pulled.
+///
+/// Holds one row per run in memory at a time.
+pub struct MergedRuns {
+    /// The spilled runs in spill order, then the in-memory buffer.
+    runs: Vec<MergeSource>,
+    /// The next row of each run that has one left.
+    heap: BinaryHeap<HeapEntry>,
+    /// Rows not yet returned.
+    remaining: usize,
+    sort_keys: Arc<[SortKey]>,
+}
+
+impl MergedRuns {
+    /// Returns the next row in sorted order, or `None` once every run is
+    /// exhausted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading a run fails.
+    pub fn next_row(&mut self) -> std::io::Result<Option<Vec<Value>>> {
+        let Some(entry) = self.heap.pop() else {
+            return Ok(None);
+        };
+        self.refill(entry.run_index)?;
+        self.remaining -= 1;
+        Ok(Some(entry.row))
+    }
+
+    /// Returns how many rows are left to merge.
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Moves the next row of a run, if any, onto the heap.
+    fn refill(&mut self, run_index: usize) -> std::io::Result<()> {
+        let next = match &mut self.runs[run_index] {
+            MergeSource::File(reader) => reader.next_row()?,
+            MergeSource::Memory(rows) => rows.next(),
+        };
+        if let Some(row) = next {
+            self.heap.push(HeapEntry {
+                row,
+                run_index,
+                sort_keys: Arc::clone(&self.sort_keys),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// One sorted input of a merge.
+enum MergeSource {
+    File(RunReader),
+    Memory(std::vec::IntoIter<Vec<Value>>),
+}
+
+/// Reads a spilled run a row at a time.
 struct RunReader {
     reader: SpillFileReader,
     remaining: usize,
-    current_row: Option<Vec<Value>>,
-    run_index: usize,
     num_columns: usize,
 }
 
 impl RunReader {
     fn next_row(&mut self) -> std::io::Result<Option<Vec<Value>>> {
         if self.remaining == 0 {
-            self.current_row = None;
             return Ok(None);
         }
-
         let row = deserialize_row(
             &mut SpillFileReaderAdapter(&mut self.reader),
             self.num_columns,
         )?;
         self.remaining -= 1;
-        self.current_row = Some(row.clone());
         Ok(Some(row))
     }
 }
@@ -326,21 +304,23 @@ impl RunReader {
 struct HeapEntry {
     row: Vec<Value>,
     run_index: usize,
-    sort_keys: Vec<SortKey>,
+    sort_keys: Arc<[SortKey]>,
 }
 
 impl Eq for HeapEntry {}
 
 impl PartialEq for HeapEntry {
     fn eq(&self, other: &Self) -> bool {
-        self.row == other.row
+        self.cmp(other) == Ordering::Equal
     }
 }
 
 impl Ord for HeapEntry {
     fn cmp(&self, other: &Self) -> Ordering {
-        // Reverse comparison because BinaryHeap is a max-heap but we want min
+        // Reversed because BinaryHeap is a max-heap but we want the smallest
+        // row, taking ties from the earliest run to keep the merge stable
         compare_rows(&other.row, &self.row, &self.sort_keys)
+            .then_with(|| other.run_index.cmp(&self.run_index))
     }
 }
 
@@ -350,7 +330,7 @@ impl PartialOrd for HeapEntry {
     }
 }
 
-/// Compares two rows by sort keys.
+/// Compares two rows by sort keys, the way the sort operator does.
 fn compare_rows(a: &[Value], b: &[Value], keys: &[SortKey]) -> Ordering {
     for key in keys {
         let a_val = a.get(key.column);
@@ -366,7 +346,7 @@ fn compare_rows(a: &[Value], b: &[Value], keys: &[SortKey]) -> Ordering {
                 NullOrder::First => Ordering::Greater,
                 NullOrder::Last => Ordering::Less,
             },
-            (Some(a), Some(b)) => compare_values(a, b),
+            (Some(a), Some(b)) => compare_values(a, b, key.collation),
             _ => Ordering::Equal,
         };
 
@@ -383,14 +363,20 @@ fn compare_rows(a: &[Value], b: &[Value], keys: &[SortKey]) -> Ordering {
     Ordering::Equal
 }
 
-/// Compares two values.
-fn compare_values(a: &Value, b: &Value) -> Ordering {
+/// Compares two values, ordering strings by the given collation.
+fn compare_values(a: &Value, b: &Value, collation: Collation) -> Ordering {
     match (a, b) {
         (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
         (Value::Int64(a), Value::Int64(b)) => a.cmp(b),
         (Value::Float64(a), Value::Float64(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
-        (Value::String(a), Value::String(b)) => a.cmp(b),
-        _ => Ordering::Equal,
+        (Value::String(a), Value::String(b)) => collation.compare(a, b),
+        (Value::Int64(a), Value::Float64(b)) => {
+            (*a as f64).partial_cmp(b).unwrap_or(Ordering::Equal)
+        }
+        (Value::Float64(a), Value::Int64(b)) => {
+            a.partial_cmp(&(*b as f64)).unwrap_or(Ordering::Equal)
+        }
+        _ => a.compare_temporal(b).unwrap_or(Ordering::Equal),
     }
 }
 
@@ -458,6 +444,31 @@ mod tests {
         assert_eq!(result[2], row(&[3]));
     }
 
+    #[test]
+    fn test_merge_keeps_equal_rows_in_input_order() {
+        let (temp_dir, manager) = create_manager();
+        let mut sort = ExternalSort::new(manager, 2, vec![SortKey::ascending(0)]);
+
+        // Column 1 is the input position; every key appears in every run.
+        sort.spill_sorted_run(vec![row(&[1, 0]), row(&[1, 1]), row(&[2, 2])])
+            .unwrap();
+        sort.spill_sorted_run(vec![row(&[1, 3]), row(&[2, 4]), row(&[2, 5])])
+            .unwrap();
+        let buffer = vec![row(&[2, 6]), row(&[1, 7]), row(&[2, 8])];
+
+        let mut merged = sort.merge(buffer).unwrap();
+        assert_eq!(merged.remaining(), 9);
+        let mut positions = Vec::new();
+        while let Some(row) = merged.next_row().unwrap() {
+            positions.push(row[1].as_int64().unwrap());
+        }
+        assert_eq!(positions, vec![0, 1, 3, 7, 2, 4, 5, 6, 8]);
+        assert_eq!(merged.remaining(), 0);
+
+        drop(sort);
+        assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+    }
+
     #[test]
     fn test_external_sort_single_run() {
         let (_temp_dir, manager) = create_manager();
@@ -574,6 +585,7 @@ mod tests {
             column: 0,
             direction: SortDirection::Ascending,
             null_order: NullOrder::Last,
+            collation: Collation::Binary,
         }];
         let mut sort = ExternalSort::new(manager, 1, sort_keys);
 
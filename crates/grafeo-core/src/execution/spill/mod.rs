@@ -24,7 +24,7 @@ mod serializer;
 
 pub use async_file::{AsyncSpillFile, AsyncSpillFileReader};
 pub use async_manager::AsyncSpillManager;
-pub use external_sort::{ExternalSort, NullOrder, SortDirection, SortKey};
+pub use external_sort::{ExternalSort, MergedRuns, NullOrder, SortDirection, SortKey};
 pub use file::{SpillFile, SpillFileReader};
 pub use manager::SpillManager;
 pub use partition::{DEFAULT_NUM_PARTITIONS, PartitionedState};
@@ -509,6 +509,20 @@ fn hash_key(key: &[Value]) -> u64 {
                 8u8.hash(&mut hasher);
                 m.len().hash(&mut hasher);
             }
+            Value::Point { x, y, srid } => {
+                9u8.hash(&mut hasher);
+                x.to_bits().hash(&mut hasher);
+                y.to_bits().hash(&mut hasher);
+                srid.hash(&mut hasher);
+            }
+            Value::Date(d) => {
+                10u8.hash(&mut hasher);
+                d.hash(&mut hasher);
+            }
+            Value::Duration(d) => {
+                11u8.hash(&mut hasher);
+                d.hash(&mut hasher);
+            }
         }
     }
 
@@ -21,6 +21,9 @@ const TAG_BYTES: u8 = 5;
 const TAG_TIMESTAMP: u8 = 6;
 const TAG_LIST: u8 = 7;
 const TAG_MAP: u8 = 8;
+const TAG_POINT: u8 = 9;
+const TAG_DATE: u8 = 10;
+const TAG_DURATION: u8 = 11;
 
 /// Serializes a Value to bytes.
 ///
@@ -93,6 +96,23 @@ pub fn serialize_value<W: Write + ?Sized>(value: &Value, w: &mut W) -> std::io::
             }
             Ok(total)
         }
+        Value::Point { x, y, srid } => {
+            w.write_all(&[TAG_POINT])?;
+            w.write_all(&x.to_le_bytes())?;
+            w.write_all(&y.to_le_bytes())?;
+            w.write_all(&srid.to_le_bytes())?;
+            Ok(21)
+        }
+        Value::Date(days) => {
+            w.write_all(&[TAG_DATE])?;
+            w.write_all(&days.to_le_bytes())?;
+            Ok(5)
+        }
+        Value::Duration(micros) => {
+            w.write_all(&[TAG_DURATION])?;
+            w.write_all(&micros.to_le_bytes())?;
+            Ok(9)
+        }
     }
 }
 
@@ -179,6 +199,24 @@ pub fn deserialize_value<R: Read + ?Sized>(r: &mut R) -> std::io::Result<Value>
             }
             Ok(Value::Map(Arc::new(map)))
         }
+        TAG_POINT => {
+            let mut buf = [0u8; 20];
+            r.read_exact(&mut buf)?;
+            let x = f64::from_le_bytes(buf[..8].try_into().expect("8 bytes"));
+            let y = f64::from_le_bytes(buf[8..16].try_into().expect("8 bytes"));
+            let srid = u32::from_le_bytes(buf[16..].try_into().expect("4 bytes"));
+            Ok(Value::Point { x, y, srid })
+        }
+        TAG_DATE => {
+            let mut buf = [0u8; 4];
+            r.read_exact(&mut buf)?;
+            Ok(Value::Date(i32::from_le_bytes(buf)))
+        }
+        TAG_DURATION => {
+            let mut buf = [0u8; 8];
+            r.read_exact(&mut buf)?;
+            Ok(Value::Duration(i64::from_le_bytes(buf)))
+        }
         _ => Err(std::io::Error::new(
             std::io::ErrorKind::InvalidData,
             format!("Unknown value tag: {}", tag[0]),
@@ -321,6 +359,15 @@ mod tests {
         assert_eq!(result.as_timestamp(), Some(ts));
     }
 
+    #[test]
+    fn test_serialize_date_and_duration() {
+        assert_eq!(roundtrip_value(Value::Date(-19_723)), Value::Date(-19_723));
+        assert_eq!(
+            roundtrip_value(Value::Duration(i64::MAX)),
+            Value::Duration(i64::MAX)
+        );
+    }
+
     #[test]
     fn test_serialize_list() {
         let list = Value::List(Arc::from(vec![
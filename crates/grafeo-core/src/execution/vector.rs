@@ -2,7 +2,7 @@
 
 use std::sync::Arc;
 
-use grafeo_common::types::{EdgeId, LogicalType, NodeId, Value};
+use grafeo_common::types::{EdgeId, LogicalType, NodeId, PropertyKey, Value};
 
 /// Default vector capacity (tuples per vector).
 pub const DEFAULT_VECTOR_CAPACITY: usize = 2048;
@@ -21,6 +21,8 @@ pub struct ValueVector {
     len: usize,
     /// Validity bitmap (true = valid, false = null).
     validity: Option<Vec<bool>>,
+    /// Heap bytes held by variable-width values, such as string contents.
+    heap_bytes: usize,
 }
 
 /// Internal storage for vector data.
@@ -86,6 +88,7 @@ impl ValueVector {
             data,
             len: 0,
             validity: None,
+            heap_bytes: 0,
         }
     }
 
@@ -117,13 +120,13 @@ impl ValueVector {
 
     /// Sets the value at index to null.
     pub fn set_null(&mut self, index: usize) {
-        if self.validity.is_none() {
-            self.validity = Some(vec![true; self.len]);
+        let validity = self.validity.get_or_insert_with(Vec::new);
+        // Values pushed since the last null aren't tracked yet
+        if validity.len() < self.len {
+            validity.resize(self.len, true);
         }
-        if let Some(validity) = &mut self.validity {
-            if index < validity.len() {
-                validity[index] = false;
-            }
+        if index < validity.len() {
+            validity[index] = false;
         }
     }
 
@@ -154,7 +157,9 @@ impl ValueVector {
     /// Pushes a string value.
     pub fn push_string(&mut self, value: impl Into<Arc<str>>) {
         if let VectorData::String(vec) = &mut self.data {
-            vec.push(value.into());
+            let value = value.into();
+            self.heap_bytes += value.len();
+            vec.push(value);
             self.len += 1;
         }
     }
@@ -197,12 +202,18 @@ impl ValueVector {
             (VectorData::Bool(vec), Value::Bool(b)) => vec.push(*b),
             (VectorData::Int64(vec), Value::Int64(i)) => vec.push(*i),
             (VectorData::Float64(vec), Value::Float64(f)) => vec.push(*f),
-            (VectorData::String(vec), Value::String(s)) => vec.push(s.clone()),
+            (VectorData::String(vec), Value::String(s)) => {
+                self.heap_bytes += s.len();
+                vec.push(s.clone());
+            }
             // Handle Int64 -> NodeId conversion (from get_value roundtrip)
             (VectorData::NodeId(vec), Value::Int64(i)) => vec.push(NodeId::new(*i as u64)),
             // Handle Int64 -> EdgeId conversion (from get_value roundtrip)
             (VectorData::EdgeId(vec), Value::Int64(i)) => vec.push(EdgeId::new(*i as u64)),
-            (VectorData::Generic(vec), _) => vec.push(value),
+            (VectorData::Generic(vec), _) => {
+                self.heap_bytes += heap_size(&value);
+                vec.push(value);
+            }
             _ => {
                 // Type mismatch - push a default value to maintain vector alignment
                 match &mut self.data {
@@ -212,7 +223,10 @@ impl ValueVector {
                     VectorData::String(vec) => vec.push("".into()),
                     VectorData::NodeId(vec) => vec.push(NodeId::new(0)),
                     VectorData::EdgeId(vec) => vec.push(EdgeId::new(0)),
-                    VectorData::Generic(vec) => vec.push(value),
+                    VectorData::Generic(vec) => {
+                        self.heap_bytes += heap_size(&value);
+                        vec.push(value);
+                    }
                 }
             }
         }
@@ -439,6 +453,26 @@ impl ValueVector {
         }
     }
 
+    /// Returns the estimated average bytes per entry.
+    ///
+    /// Fixed-width types report their slot size. Strings and generic values
+    /// add the average size of what they point to, tracked as values are
+    /// pushed, so a vector of long strings reports a larger width than one of
+    /// short strings.
+    #[must_use]
+    pub fn average_width(&self) -> usize {
+        let slot = match &self.data {
+            VectorData::Bool(_) => std::mem::size_of::<bool>(),
+            VectorData::Int64(_) => std::mem::size_of::<i64>(),
+            VectorData::Float64(_) => std::mem::size_of::<f64>(),
+            VectorData::String(_) => std::mem::size_of::<Arc<str>>(),
+            VectorData::NodeId(_) => std::mem::size_of::<NodeId>(),
+            VectorData::EdgeId(_) => std::mem::size_of::<EdgeId>(),
+            VectorData::Generic(_) => std::mem::size_of::<Value>(),
+        };
+        slot + self.heap_bytes.checked_div(self.len).unwrap_or(0)
+    }
+
     /// Clears all data from this vector.
     pub fn clear(&mut self) {
         match &mut self.data {
@@ -452,6 +486,29 @@ impl ValueVector {
         }
         self.len = 0;
         self.validity = None;
+        self.heap_bytes = 0;
+    }
+}
+
+/// Estimates the heap bytes a value points to, beyond its own slot.
+fn heap_size(value: &Value) -> usize {
+    match value {
+        Value::String(s) => s.len(),
+        Value::Bytes(b) => b.len(),
+        Value::List(items) => items
+            .iter()
+            .map(|item| std::mem::size_of::<Value>() + heap_size(item))
+            .sum(),
+        Value::Map(map) => map
+            .iter()
+            .map(|(key, item)| {
+                std::mem::size_of::<PropertyKey>()
+                    + key.as_str().len()
+                    + std::mem::size_of::<Value>()
+                    + heap_size(item)
+            })
+            .sum(),
+        _ => 0,
     }
 }
 
@@ -508,6 +565,22 @@ mod tests {
         assert_eq!(vec.get_int64(2), Some(3));
     }
 
+    #[test]
+    fn test_null_values_after_later_pushes() {
+        let mut vec = ValueVector::with_type(LogicalType::Node);
+
+        vec.push_value(Value::Null);
+        vec.push_value(Value::Int64(7));
+        vec.push_value(Value::Null);
+        vec.push_value(Value::Int64(8));
+
+        assert!(vec.is_null(0));
+        assert!(!vec.is_null(1));
+        assert!(vec.is_null(2));
+        assert_eq!(vec.get_value(2), Some(Value::Null));
+        assert!(!vec.is_null(3));
+    }
+
     #[test]
     fn test_get_value() {
         let mut vec = ValueVector::with_type(LogicalType::Int64);
@@ -539,4 +612,26 @@ mod tests {
         assert!(vec.is_empty());
         assert_eq!(vec.len(), 0);
     }
+
+    #[test]
+    fn test_average_width_tracks_variable_width_values() {
+        let mut ints = ValueVector::with_type(LogicalType::Int64);
+        ints.push_int64(1);
+        assert_eq!(ints.average_width(), 8);
+
+        let mut strings = ValueVector::with_type(LogicalType::String);
+        let empty_width = strings.average_width();
+        strings.push_string("ab");
+        strings.push_value(Value::String("abcdef".into()));
+        assert_eq!(strings.average_width(), empty_width + 4);
+
+        let mut generic = ValueVector::new();
+        generic.push_value(Value::String("x".repeat(100).into()));
+        let wide = generic.average_width();
+        generic.push_value(Value::Int64(1));
+        assert!(generic.average_width() < wide);
+
+        strings.clear();
+        assert_eq!(strings.average_width(), empty_width);
+    }
 }
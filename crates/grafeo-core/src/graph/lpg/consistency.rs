@@ -0,0 +1,209 @@
+//! Structural checks for an [`LpgStore`](super::LpgStore).
+//!
+//! The store keeps several redundant structures in step on every write:
+//! adjacency lists mirror the edge records, the label index mirrors each
+//! node's labels, and zone maps summarize each property column. If a bug or
+//! a bad recovery lets them drift apart, queries quietly return wrong
+//! answers. [`LpgStore::check_consistency`](super::LpgStore::check_consistency)
+//! walks all of them and reports each [`Inconsistency`] it finds, without
+//! repairing anything.
+
+use std::fmt;
+use std::sync::Arc;
+
+use grafeo_common::types::{EdgeId, NodeId, PropertyKey};
+
+use crate::graph::Direction;
+
+/// A structural invariant the store doesn't hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// A live edge's source node doesn't exist or is deleted.
+    DanglingSource {
+        /// The edge.
+        edge: EdgeId,
+        /// The missing source node.
+        src: NodeId,
+    },
+    /// A live edge's destination node doesn't exist or is deleted.
+    DanglingTarget {
+        /// The edge.
+        edge: EdgeId,
+        /// The missing destination node.
+        dst: NodeId,
+    },
+    /// A node has a label but the label index doesn't list it.
+    MissingLabelIndexEntry {
+        /// The node.
+        node: NodeId,
+        /// The label the index is missing the node under.
+        label: Arc<str>,
+    },
+    /// The label index lists a node that doesn't exist or doesn't have the
+    /// label.
+    StaleLabelIndexEntry {
+        /// The node.
+        node: NodeId,
+        /// The label the node is wrongly listed under.
+        label: Arc<str>,
+    },
+    /// A live edge is missing from an endpoint's adjacency list.
+    MissingAdjacencyEntry {
+        /// The edge.
+        edge: EdgeId,
+        /// The endpoint whose list should hold it.
+        node: NodeId,
+        /// [`Outgoing`](Direction::Outgoing) for the forward lists,
+        /// [`Incoming`](Direction::Incoming) for the backward ones.
+        direction: Direction,
+    },
+    /// An adjacency entry disagrees with the edge record about the edge's
+    /// endpoints.
+    WrongAdjacencyEntry {
+        /// The edge.
+        edge: EdgeId,
+        /// The node whose list holds the entry.
+        node: NodeId,
+        /// The neighbour the entry points at.
+        neighbor: NodeId,
+        /// Which adjacency lists hold the entry.
+        direction: Direction,
+    },
+    /// An adjacency structure's edge counters disagree with its entries.
+    AdjacencyCountMismatch {
+        /// Which adjacency lists are off.
+        direction: Direction,
+        /// Live edges according to the counters.
+        counted: usize,
+        /// Live entries actually in the lists.
+        actual: usize,
+    },
+    /// A node property value falls outside its column's zone map, so
+    /// filters could wrongly skip it.
+    NodeZoneMapMismatch {
+        /// The property.
+        key: PropertyKey,
+        /// The node holding the uncovered value.
+        node: NodeId,
+    },
+    /// An edge property value falls outside its column's zone map.
+    EdgeZoneMapMismatch {
+        /// The property.
+        key: PropertyKey,
+        /// The edge holding the uncovered value.
+        edge: EdgeId,
+    },
+}
+
+impl Inconsistency {
+    /// A short, stable code for the kind of problem, like `DANGLING_SRC`.
+    #[must_use]
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::DanglingSource { .. } => "DANGLING_SRC",
+            Self::DanglingTarget { .. } => "DANGLING_DST",
+            Self::MissingLabelIndexEntry { .. } => "LABEL_INDEX_MISSING",
+            Self::StaleLabelIndexEntry { .. } => "LABEL_INDEX_STALE",
+            Self::MissingAdjacencyEntry { .. } => "ADJACENCY_MISSING",
+            Self::WrongAdjacencyEntry { .. } => "ADJACENCY_MISMATCH",
+            Self::AdjacencyCountMismatch { .. } => "ADJACENCY_COUNT",
+            Self::NodeZoneMapMismatch { .. } | Self::EdgeZoneMapMismatch { .. } => {
+                "ZONE_MAP_MISMATCH"
+            }
+        }
+    }
+
+    /// The entity the problem is about, like `edge:4`, if there is one.
+    #[must_use]
+    pub fn context(&self) -> Option<String> {
+        match self {
+            Self::DanglingSource { edge, .. }
+            | Self::DanglingTarget { edge, .. }
+            | Self::MissingAdjacencyEntry { edge, .. }
+            | Self::WrongAdjacencyEntry { edge, .. }
+            | Self::EdgeZoneMapMismatch { edge, .. } => Some(format!("edge:{}", edge.0)),
+            Self::MissingLabelIndexEntry { node, .. }
+            | Self::StaleLabelIndexEntry { node, .. }
+            | Self::NodeZoneMapMismatch { node, .. } => Some(format!("node:{}", node.0)),
+            Self::AdjacencyCountMismatch { .. } => None,
+        }
+    }
+}
+
+/// Names the adjacency lists a direction refers to.
+fn lists(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Incoming => "backward",
+        Direction::Outgoing | Direction::Both => "forward",
+    }
+}
+
+impl fmt::Display for Inconsistency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DanglingSource { edge, src } => write!(
+                f,
+                "Edge {} references non-existent source node {}",
+                edge.0, src.0
+            ),
+            Self::DanglingTarget { edge, dst } => write!(
+                f,
+                "Edge {} references non-existent destination node {}",
+                edge.0, dst.0
+            ),
+            Self::MissingLabelIndexEntry { node, label } => write!(
+                f,
+                "Node {} has label '{label}' but the label index doesn't list it",
+                node.0
+            ),
+            Self::StaleLabelIndexEntry { node, label } => write!(
+                f,
+                "Label index lists node {} under '{label}' but the node doesn't have it",
+                node.0
+            ),
+            Self::MissingAdjacencyEntry {
+                edge,
+                node,
+                direction,
+            } => write!(
+                f,
+                "Edge {} is missing from the {} adjacency list of node {}",
+                edge.0,
+                lists(*direction),
+                node.0
+            ),
+            Self::WrongAdjacencyEntry {
+                edge,
+                node,
+                neighbor,
+                direction,
+            } => write!(
+                f,
+                "The {} adjacency list of node {} links edge {} to node {}, which isn't its other endpoint",
+                lists(*direction),
+                node.0,
+                edge.0,
+                neighbor.0
+            ),
+            Self::AdjacencyCountMismatch {
+                direction,
+                counted,
+                actual,
+            } => write!(
+                f,
+                "The {} adjacency counters report {counted} edges but the lists hold {actual}",
+                lists(*direction)
+            ),
+            Self::NodeZoneMapMismatch { key, node } => write!(
+                f,
+                "Zone map for node property '{key}' doesn't cover the value on node {}",
+                node.0
+            ),
+            Self::EdgeZoneMapMismatch { key, edge } => write!(
+                f,
+                "Zone map for edge property '{key}' doesn't cover the value on edge {}",
+                edge.0
+            ),
+        }
+    }
+}
@@ -3,7 +3,7 @@
 //! Like nodes, edges have two forms: [`Edge`] is the user-friendly version,
 //! [`EdgeRecord`] is the compact storage format.
 
-use grafeo_common::types::{EdgeId, EpochId, NodeId, PropertyKey, Value};
+use grafeo_common::types::{EdgeId, EpochId, NodeId, PropertyKey, Timestamp, Value};
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
@@ -65,6 +65,12 @@ impl Edge {
         self.properties.get(&PropertyKey::new(key))
     }
 
+    /// Returns true if this edge has the property, even if its value is null.
+    #[must_use]
+    pub fn has_property(&self, key: &str) -> bool {
+        self.properties.contains_key(&PropertyKey::new(key))
+    }
+
     /// Removes a property from this edge.
     pub fn remove_property(&mut self, key: &str) -> Option<Value> {
         self.properties.remove(&PropertyKey::new(key))
@@ -109,6 +115,10 @@ pub struct EdgeRecord {
     pub flags: EdgeFlags,
     /// Epoch this record was created in.
     pub epoch: EpochId,
+    /// Start of the edge's validity interval (inclusive).
+    pub valid_from: Timestamp,
+    /// End of the edge's validity interval (exclusive).
+    pub valid_to: Timestamp,
 }
 
 impl EdgeRecord {
@@ -129,6 +139,8 @@ impl EdgeRecord {
             props_count: 0,
             flags: EdgeFlags(0),
             epoch,
+            valid_from: Timestamp::MIN,
+            valid_to: Timestamp::MAX,
         }
     }
 
@@ -138,6 +150,14 @@ impl EdgeRecord {
         self.flags.contains(Self::FLAG_DELETED)
     }
 
+    /// Checks if this edge is valid at the given time.
+    ///
+    /// Edges without a validity interval are valid at all times.
+    #[must_use]
+    pub fn is_valid_at(&self, at: Timestamp) -> bool {
+        self.valid_from <= at && at < self.valid_to
+    }
+
     /// Marks this edge as deleted.
     pub fn set_deleted(&mut self, deleted: bool) {
         if deleted {
@@ -226,6 +246,25 @@ mod tests {
         assert!(record.is_deleted());
     }
 
+    #[test]
+    fn test_edge_record_validity() {
+        let mut record = EdgeRecord::new(
+            EdgeId::new(1),
+            NodeId::new(10),
+            NodeId::new(20),
+            0,
+            EpochId::INITIAL,
+        );
+        assert!(record.is_valid_at(Timestamp::EPOCH));
+
+        record.valid_from = Timestamp::from_secs(100);
+        record.valid_to = Timestamp::from_secs(200);
+        assert!(!record.is_valid_at(Timestamp::from_secs(99)));
+        assert!(record.is_valid_at(Timestamp::from_secs(100)));
+        assert!(record.is_valid_at(Timestamp::from_secs(199)));
+        assert!(!record.is_valid_at(Timestamp::from_secs(200)));
+    }
+
     #[test]
     fn test_edge_record_size() {
         // EdgeRecord should be a reasonable size for cache efficiency
@@ -12,12 +12,16 @@
 //!
 //! Start with [`LpgStore`] - that's where everything lives.
 
+mod consistency;
 mod edge;
 mod node;
 mod property;
 mod store;
+mod tx_write;
 
+pub use consistency::Inconsistency;
 pub use edge::{Edge, EdgeRecord};
 pub use node::{Node, NodeRecord};
-pub use property::{CompareOp, PropertyStorage};
-pub use store::LpgStore;
+pub use property::{ColumnLayout, CompareOp, PropertyColumnRef, PropertyStorage};
+pub use store::{LpgStore, LpgStoreConfig};
+pub use tx_write::TxWrite;
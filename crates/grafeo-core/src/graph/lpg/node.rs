@@ -92,6 +92,12 @@ impl Node {
         self.properties.get(&PropertyKey::new(key))
     }
 
+    /// Returns true if this node has the property, even if its value is null.
+    #[must_use]
+    pub fn has_property(&self, key: &str) -> bool {
+        self.properties.contains_key(&PropertyKey::new(key))
+    }
+
     /// Removes a property from this node.
     pub fn remove_property(&mut self, key: &str) -> Option<Value> {
         self.properties.remove(&PropertyKey::new(key))
@@ -247,6 +253,10 @@ mod tests {
         );
         assert!(node.get_property("missing").is_none());
 
+        node.set_property("nickname", Value::Null);
+        assert!(node.has_property("nickname"));
+        assert!(!node.has_property("missing"));
+
         let removed = node.remove_property("name");
         assert!(removed.is_some());
         assert!(node.get_property("name").is_none());
@@ -19,12 +19,20 @@
 //! | Int64 (repeated) | RunLength | 2-100x |
 //! | String (low cardinality) | Dictionary | 2-50x |
 //! | Bool | BitVector | 8x |
+//!
+//! ## Layout
+//!
+//! Uncompressed values live in a hash map keyed by entity id. When the ids
+//! holding a property are densely packed - the usual case for bulk-loaded
+//! nodes - the column switches to a flat array indexed by offset from the
+//! lowest id, and back again if the ids spread out. See [`ColumnLayout`].
 
+use crate::execution::ValueVector;
 use crate::index::zone_map::ZoneMapEntry;
 use crate::storage::{
     CompressedData, CompressionCodec, DictionaryBuilder, DictionaryEncoding, TypeSpecificCompressor,
 };
-use grafeo_common::types::{EdgeId, NodeId, PropertyKey, Value};
+use grafeo_common::types::{EdgeId, LogicalType, NodeId, PropertyKey, Value};
 use grafeo_common::utils::hash::FxHashMap;
 use parking_lot::RwLock;
 use std::cmp::Ordering;
@@ -50,6 +58,27 @@ const COMPRESSION_THRESHOLD: usize = 1000;
 /// Size of the hot buffer for recent writes (before compression).
 const HOT_BUFFER_SIZE: usize = 256;
 
+/// Minimum number of values before a column considers the dense layout.
+const DENSE_MIN_VALUES: usize = 64;
+
+/// A sparse column turns dense once its values fill at least
+/// 1/`DENSE_FILL` of the id range they span.
+const DENSE_FILL: u64 = 2;
+
+/// A dense column turns sparse again once its values fill less than
+/// 1/`SPARSE_FILL` of its slots. The gap to [`DENSE_FILL`] stops columns
+/// near the threshold from flipping back and forth.
+const SPARSE_FILL: u64 = 4;
+
+/// How a column stores its uncompressed values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnLayout {
+    /// Hash map from entity id to value, for ids scattered across a wide range.
+    Sparse,
+    /// Flat array indexed by offset from the lowest id, for densely packed ids.
+    Dense,
+}
+
 /// Comparison operators used for zone map predicate checks.
 ///
 /// These map directly to GQL comparison operators like `=`, `<`, `>=`.
@@ -69,13 +98,42 @@ pub enum CompareOp {
     Ge,
 }
 
+/// Number of consecutive entity ids covered by one block zone map.
+///
+/// Matches the default scan chunk size, so a scan over densely numbered
+/// entities checks about one block per chunk.
+pub const ZONE_BLOCK_SIZE: u64 = 2048;
+
 /// Marker trait for IDs that can key into property storage.
 ///
 /// Implemented for [`NodeId`] and [`EdgeId`] - you can store properties on both.
-pub trait EntityId: Copy + Eq + Hash + 'static {}
+pub trait EntityId: Copy + Eq + Hash + 'static {
+    /// Returns the raw numeric id.
+    fn raw(self) -> u64;
 
-impl EntityId for NodeId {}
-impl EntityId for EdgeId {}
+    /// Creates an id from its raw numeric form.
+    fn from_raw(raw: u64) -> Self;
+}
+
+impl EntityId for NodeId {
+    fn raw(self) -> u64 {
+        self.as_u64()
+    }
+
+    fn from_raw(raw: u64) -> Self {
+        NodeId::new(raw)
+    }
+}
+
+impl EntityId for EdgeId {
+    fn raw(self) -> u64 {
+        self.as_u64()
+    }
+
+    fn from_raw(raw: u64) -> Self {
+        EdgeId::new(raw)
+    }
+}
 
 /// Thread-safe columnar property storage.
 ///
@@ -145,6 +203,25 @@ impl<Id: EntityId> PropertyStorage<Id> {
             .set(id, value);
     }
 
+    /// Adds `delta` to a numeric property and returns the new value.
+    ///
+    /// The read and the write happen under the same lock, so concurrent
+    /// increments never lose an update. A missing or null property starts
+    /// from `delta`, and integers stay integers unless a float is involved.
+    /// Returns `None` and leaves the property alone if either side isn't
+    /// numeric or an integer sum would overflow.
+    pub fn increment(&self, id: Id, key: PropertyKey, delta: &Value) -> Option<Value> {
+        let mut columns = self.columns.write();
+        let current = columns.get(&key).and_then(|col| col.get(id));
+        let sum = add_numeric(current.as_ref().unwrap_or(&Value::Null), delta)?;
+        let mode = self.default_compression;
+        columns
+            .entry(key)
+            .or_insert_with(|| PropertyColumn::with_compression(mode))
+            .set(id, sum.clone());
+        Some(sum)
+    }
+
     /// Enables compression for a specific column.
     pub fn enable_compression(&self, key: &PropertyKey, mode: CompressionMode) {
         let mut columns = self.columns.write();
@@ -198,6 +275,17 @@ impl<Id: EntityId> PropertyStorage<Id> {
         columns.get(key).and_then(|col| col.get(id))
     }
 
+    /// Returns true if the entity has a value stored under `key`.
+    ///
+    /// Unlike [`get`](Self::get), this tells an explicit `Value::Null` apart
+    /// from a property that was never set: the former is present, the
+    /// latter is not.
+    #[must_use]
+    pub fn contains(&self, id: Id, key: &PropertyKey) -> bool {
+        let columns = self.columns.read();
+        columns.get(key).is_some_and(|col| col.get(id).is_some())
+    }
+
     /// Removes a property value for an entity.
     pub fn remove(&self, id: Id, key: &PropertyKey) -> Option<Value> {
         let mut columns = self.columns.write();
@@ -243,7 +331,7 @@ impl<Id: EntityId> PropertyStorage<Id> {
         let columns = self.columns.read();
         if columns.contains_key(key) {
             Some(PropertyColumnRef {
-                _guard: columns,
+                guard: columns,
                 key: key.clone(),
                 _marker: PhantomData,
             })
@@ -266,6 +354,27 @@ impl<Id: EntityId> PropertyStorage<Id> {
             .unwrap_or(true) // No column = assume might match (conservative)
     }
 
+    /// Checks if a predicate might match any entity with an id in
+    /// `first..=last`, using per-block zone maps.
+    ///
+    /// Returns `false` when no entity in the range has a value that could
+    /// satisfy the predicate - including when none has the property at all,
+    /// since comparisons against a missing property are never true.
+    #[must_use]
+    pub fn might_match_in(
+        &self,
+        key: &PropertyKey,
+        first: Id,
+        last: Id,
+        op: CompareOp,
+        value: &Value,
+    ) -> bool {
+        let columns = self.columns.read();
+        columns
+            .get(key)
+            .is_some_and(|col| col.might_match_in(first.raw(), last.raw(), op, value))
+    }
+
     /// Gets the zone map for a property column.
     #[must_use]
     pub fn zone_map(&self, key: &PropertyKey) -> Option<ZoneMapEntry> {
@@ -280,6 +389,27 @@ impl<Id: EntityId> PropertyStorage<Id> {
             col.rebuild_zone_map();
         }
     }
+
+    /// Returns every `(key, id)` whose value its column's zone maps don't
+    /// cover, so a filter could wrongly skip it.
+    ///
+    /// Columns already marked for a rebuild are skipped, since filters don't
+    /// trust their zone maps anyway.
+    #[must_use]
+    pub fn zone_map_violations(&self) -> Vec<(PropertyKey, Id)> {
+        let columns = self.columns.read();
+        let mut violations: Vec<(PropertyKey, Id)> = columns
+            .iter()
+            .flat_map(|(key, col)| {
+                col.zone_map_violations()
+                    .into_iter()
+                    .map(move |id| (key.clone(), id))
+            })
+            .collect();
+        violations
+            .sort_unstable_by(|(a_key, a), (b_key, b)| (a_key, a.raw()).cmp(&(b_key, b.raw())));
+        violations
+    }
 }
 
 impl<Id: EntityId> Default for PropertyStorage<Id> {
@@ -405,11 +535,14 @@ impl CompressionStats {
 /// is enabled, the column automatically selects the best codec based on the
 /// data type and characteristics.
 pub struct PropertyColumn<Id: EntityId = NodeId> {
-    /// Sparse storage: entity ID -> value (hot buffer + uncompressed).
+    /// Uncompressed values (hot buffer), sparse or dense.
     /// Used for recent writes and when compression is disabled.
-    values: FxHashMap<Id, Value>,
+    values: ColumnValues<Id>,
     /// Zone map tracking min/max/null_count for predicate pushdown.
     zone_map: ZoneMapEntry,
+    /// Zone maps per block of [`ZONE_BLOCK_SIZE`] ids, for skipping parts
+    /// of a scan.
+    block_zone_maps: FxHashMap<u64, ZoneMapEntry>,
     /// Whether zone map needs rebuild (after removes).
     zone_map_dirty: bool,
     /// Compression mode for this column.
@@ -425,8 +558,9 @@ impl<Id: EntityId> PropertyColumn<Id> {
     #[must_use]
     pub fn new() -> Self {
         Self {
-            values: FxHashMap::default(),
+            values: ColumnValues::default(),
             zone_map: ZoneMapEntry::new(),
+            block_zone_maps: FxHashMap::default(),
             zone_map_dirty: false,
             compression_mode: CompressionMode::None,
             compressed: None,
@@ -438,8 +572,9 @@ impl<Id: EntityId> PropertyColumn<Id> {
     #[must_use]
     pub fn with_compression(mode: CompressionMode) -> Self {
         Self {
-            values: FxHashMap::default(),
+            values: ColumnValues::default(),
             zone_map: ZoneMapEntry::new(),
+            block_zone_maps: FxHashMap::default(),
             zone_map_dirty: false,
             compression_mode: mode,
             compressed: None,
@@ -464,11 +599,25 @@ impl<Id: EntityId> PropertyColumn<Id> {
         self.compression_mode
     }
 
+    /// Returns how the column currently stores its uncompressed values.
+    #[must_use]
+    pub fn layout(&self) -> ColumnLayout {
+        self.values.layout()
+    }
+
     /// Sets a value for an entity.
     pub fn set(&mut self, id: Id, value: Value) {
-        // Update zone map incrementally
-        self.update_zone_map_on_insert(&value);
-        self.values.insert(id, value);
+        // Update zone maps incrementally
+        widen_zone_map(&mut self.zone_map, &value);
+        widen_zone_map(
+            self.block_zone_maps
+                .entry(id.raw() / ZONE_BLOCK_SIZE)
+                .or_default(),
+            &value,
+        );
+        if let Some(old) = self.values.insert(id, value) {
+            self.narrow_zone_maps(id, &old);
+        }
 
         // Check if we should compress (in Auto mode)
         if self.compression_mode == CompressionMode::Auto {
@@ -482,36 +631,6 @@ impl<Id: EntityId> PropertyColumn<Id> {
         }
     }
 
-    /// Updates zone map when inserting a value.
-    fn update_zone_map_on_insert(&mut self, value: &Value) {
-        self.zone_map.row_count += 1;
-
-        if matches!(value, Value::Null) {
-            self.zone_map.null_count += 1;
-            return;
-        }
-
-        // Update min
-        match &self.zone_map.min {
-            None => self.zone_map.min = Some(value.clone()),
-            Some(current) => {
-                if compare_values(value, current) == Some(Ordering::Less) {
-                    self.zone_map.min = Some(value.clone());
-                }
-            }
-        }
-
-        // Update max
-        match &self.zone_map.max {
-            None => self.zone_map.max = Some(value.clone()),
-            Some(current) => {
-                if compare_values(value, current) == Some(Ordering::Greater) {
-                    self.zone_map.max = Some(value.clone());
-                }
-            }
-        }
-    }
-
     /// Gets a value for an entity.
     ///
     /// First checks the hot buffer (uncompressed values), then falls back
@@ -519,7 +638,7 @@ impl<Id: EntityId> PropertyColumn<Id> {
     #[must_use]
     pub fn get(&self, id: Id) -> Option<Value> {
         // First check hot buffer
-        if let Some(value) = self.values.get(&id) {
+        if let Some(value) = self.values.get(id) {
             return Some(value.clone());
         }
 
@@ -531,25 +650,40 @@ impl<Id: EntityId> PropertyColumn<Id> {
     }
 
     /// Removes a value for an entity.
+    ///
+    /// The zone map counts are updated in place. The bounds only go stale
+    /// when the removed value was the column's min or max, and only then is
+    /// the zone map marked for a rebuild.
     pub fn remove(&mut self, id: Id) -> Option<Value> {
-        let removed = self.values.remove(&id);
-        if removed.is_some() {
-            // Mark zone map as dirty - would need full rebuild for accurate min/max
+        let removed = self.values.remove(id)?;
+        self.narrow_zone_maps(id, &removed);
+        Some(removed)
+    }
+
+    /// Takes a value that left the column out of the zone maps.
+    fn narrow_zone_maps(&mut self, id: Id, value: &Value) {
+        if narrow_zone_map(&mut self.zone_map, value) {
             self.zone_map_dirty = true;
         }
-        removed
+        // A block's bounds may end up wider than its values, which only
+        // costs a skipped skip; an emptied block can go
+        let block = id.raw() / ZONE_BLOCK_SIZE;
+        if let Some(zone_map) = self.block_zone_maps.get_mut(&block) {
+            narrow_zone_map(zone_map, value);
+            if zone_map.row_count == 0 {
+                self.block_zone_maps.remove(&block);
+            }
+        }
     }
 
     /// Returns the number of values in this column (hot + compressed).
     #[must_use]
-    #[allow(dead_code)]
     pub fn len(&self) -> usize {
         self.values.len() + self.compressed_count
     }
 
     /// Returns true if this column is empty.
     #[must_use]
-    #[allow(dead_code)]
     pub fn is_empty(&self) -> bool {
         self.values.is_empty() && self.compressed_count == 0
     }
@@ -558,9 +692,10 @@ impl<Id: EntityId> PropertyColumn<Id> {
     ///
     /// Note: This only iterates over uncompressed values. For full iteration
     /// including compressed values, use [`iter_all`].
-    #[allow(dead_code)]
+    /// Dense columns yield ids in ascending order; sparse ones in no
+    /// particular order.
     pub fn iter(&self) -> impl Iterator<Item = (Id, &Value)> {
-        self.values.iter().map(|(&id, v)| (id, v))
+        self.values.iter()
     }
 
     /// Returns compression statistics for this column.
@@ -629,7 +764,7 @@ impl<Id: EntityId> PropertyColumn<Id> {
         let mut str_count = 0;
         let mut bool_count = 0;
 
-        for value in self.values.values() {
+        for (_, value) in self.values.iter() {
             match value {
                 Value::Int64(_) => int_count += 1,
                 Value::String(_) => str_count += 1,
@@ -648,7 +783,7 @@ impl<Id: EntityId> PropertyColumn<Id> {
         let mut values: Vec<(u64, i64)> = Vec::new();
         let mut non_int_values: FxHashMap<Id, Value> = FxHashMap::default();
 
-        for (&id, value) in &self.values {
+        for (id, value) in self.values.iter() {
             match value {
                 Value::Int64(v) => {
                     // Convert Id to u64 for indexing (assumes Id can be converted)
@@ -684,7 +819,7 @@ impl<Id: EntityId> PropertyColumn<Id> {
                 index_to_id,
             });
             self.compressed_count = values.len();
-            self.values = non_int_values;
+            self.values = ColumnValues::from_map(non_int_values);
         }
     }
 
@@ -694,7 +829,7 @@ impl<Id: EntityId> PropertyColumn<Id> {
         let mut values: Vec<(u64, Arc<str>)> = Vec::new();
         let mut non_str_values: FxHashMap<Id, Value> = FxHashMap::default();
 
-        for (&id, value) in &self.values {
+        for (id, value) in self.values.iter() {
             match value {
                 Value::String(s) => {
                     let id_u64 = unsafe { std::mem::transmute_copy::<Id, u64>(&id) };
@@ -731,7 +866,7 @@ impl<Id: EntityId> PropertyColumn<Id> {
                 index_to_id,
             });
             self.compressed_count = values.len();
-            self.values = non_str_values;
+            self.values = ColumnValues::from_map(non_str_values);
         }
     }
 
@@ -741,7 +876,7 @@ impl<Id: EntityId> PropertyColumn<Id> {
         let mut values: Vec<(u64, bool)> = Vec::new();
         let mut non_bool_values: FxHashMap<Id, Value> = FxHashMap::default();
 
-        for (&id, value) in &self.values {
+        for (id, value) in self.values.iter() {
             match value {
                 Value::Bool(b) => {
                     let id_u64 = unsafe { std::mem::transmute_copy::<Id, u64>(&id) };
@@ -773,7 +908,7 @@ impl<Id: EntityId> PropertyColumn<Id> {
             index_to_id,
         });
         self.compressed_count = values.len();
-        self.values = non_bool_values;
+        self.values = ColumnValues::from_map(non_bool_values);
     }
 
     /// Decompresses all values back to the hot buffer.
@@ -854,62 +989,345 @@ impl<Id: EntityId> PropertyColumn<Id> {
             // Conservative: can't skip if zone map is stale
             return true;
         }
+        zone_might_match(&self.zone_map, op, value)
+    }
 
-        match op {
-            CompareOp::Eq => self.zone_map.might_contain_equal(value),
-            CompareOp::Ne => {
-                // Can only skip if all values are equal to the value
-                // (which means min == max == value)
-                match (&self.zone_map.min, &self.zone_map.max) {
-                    (Some(min), Some(max)) => {
-                        !(compare_values(min, value) == Some(Ordering::Equal)
-                            && compare_values(max, value) == Some(Ordering::Equal))
-                    }
-                    _ => true,
-                }
-            }
-            CompareOp::Lt => self.zone_map.might_contain_less_than(value, false),
-            CompareOp::Le => self.zone_map.might_contain_less_than(value, true),
-            CompareOp::Gt => self.zone_map.might_contain_greater_than(value, false),
-            CompareOp::Ge => self.zone_map.might_contain_greater_than(value, true),
+    /// Uses block zone maps to check if any entity with an id in
+    /// `first..=last` could satisfy the predicate.
+    ///
+    /// Blocks with no values for this column can't match.
+    #[must_use]
+    pub fn might_match_in(&self, first: u64, last: u64, op: CompareOp, value: &Value) -> bool {
+        if self.zone_map_dirty {
+            return true;
         }
+        (first / ZONE_BLOCK_SIZE..=last / ZONE_BLOCK_SIZE).any(|block| {
+            self.block_zone_maps
+                .get(&block)
+                .is_some_and(|zone_map| zone_might_match(zone_map, op, value))
+        })
     }
 
     /// Rebuilds zone map from current values.
     pub fn rebuild_zone_map(&mut self) {
         let mut zone_map = ZoneMapEntry::new();
+        let mut block_zone_maps: FxHashMap<u64, ZoneMapEntry> = FxHashMap::default();
+
+        for (id, value) in self.values.iter() {
+            widen_zone_map(&mut zone_map, value);
+            widen_zone_map(
+                block_zone_maps
+                    .entry(id.raw() / ZONE_BLOCK_SIZE)
+                    .or_default(),
+                value,
+            );
+        }
+
+        self.zone_map = zone_map;
+        self.block_zone_maps = block_zone_maps;
+        self.zone_map_dirty = false;
+    }
+
+    /// Returns the ids whose values the column or block zone maps don't
+    /// cover. Empty when the zone maps are marked dirty.
+    fn zone_map_violations(&self) -> Vec<Id> {
+        if self.zone_map_dirty {
+            return Vec::new();
+        }
+        self.values
+            .iter()
+            .filter(|(id, value)| {
+                !zone_map_covers(&self.zone_map, value)
+                    || !self
+                        .block_zone_maps
+                        .get(&(id.raw() / ZONE_BLOCK_SIZE))
+                        .is_some_and(|zone_map| zone_map_covers(zone_map, value))
+            })
+            .map(|(id, _)| id)
+            .collect()
+    }
+}
+
+/// A column's uncompressed values, in one of the two [`ColumnLayout`]s.
+///
+/// Each write checks whether the other layout now fits better, so the
+/// choice follows the ids as they're written and removed.
+enum ColumnValues<Id: EntityId> {
+    /// Hash map storage, with the lowest and highest id ever inserted. The
+    /// bounds aren't narrowed by removes, which only errs towards sparse.
+    Sparse {
+        map: FxHashMap<Id, Value>,
+        bounds: Option<(u64, u64)>,
+    },
+    /// Slot `i` holds the value of the entity with raw id `base + i`.
+    Dense {
+        base: u64,
+        slots: Vec<Option<Value>>,
+        len: usize,
+    },
+}
 
-        for value in self.values.values() {
-            zone_map.row_count += 1;
+impl<Id: EntityId> Default for ColumnValues<Id> {
+    fn default() -> Self {
+        ColumnValues::Sparse {
+            map: FxHashMap::default(),
+            bounds: None,
+        }
+    }
+}
+
+impl<Id: EntityId> ColumnValues<Id> {
+    /// Wraps a map of values, switching to the dense layout if it fits.
+    fn from_map(map: FxHashMap<Id, Value>) -> Self {
+        let bounds = map.keys().fold(None, |bounds, id| {
+            let raw = id.raw();
+            Some(bounds.map_or((raw, raw), |(lo, hi): (u64, u64)| {
+                (lo.min(raw), hi.max(raw))
+            }))
+        });
+        let mut values = ColumnValues::Sparse { map, bounds };
+        values.densify_if_packed();
+        values
+    }
+
+    fn layout(&self) -> ColumnLayout {
+        match self {
+            ColumnValues::Sparse { .. } => ColumnLayout::Sparse,
+            ColumnValues::Dense { .. } => ColumnLayout::Dense,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            ColumnValues::Sparse { map, .. } => map.len(),
+            ColumnValues::Dense { len, .. } => *len,
+        }
+    }
 
-            if matches!(value, Value::Null) {
-                zone_map.null_count += 1;
-                continue;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, id: Id) -> Option<&Value> {
+        match self {
+            ColumnValues::Sparse { map, .. } => map.get(&id),
+            ColumnValues::Dense { base, slots, .. } => {
+                let offset = usize::try_from(id.raw().checked_sub(*base)?).ok()?;
+                slots.get(offset)?.as_ref()
             }
+        }
+    }
 
-            // Update min
-            match &zone_map.min {
-                None => zone_map.min = Some(value.clone()),
-                Some(current) => {
-                    if compare_values(value, current) == Some(Ordering::Less) {
-                        zone_map.min = Some(value.clone());
+    /// Stores a value, returning the one it replaced.
+    fn insert(&mut self, id: Id, value: Value) -> Option<Value> {
+        let raw = id.raw();
+        match self {
+            ColumnValues::Sparse { map, bounds } => {
+                let old = map.insert(id, value);
+                *bounds = Some(bounds.map_or((raw, raw), |(lo, hi)| (lo.min(raw), hi.max(raw))));
+                self.densify_if_packed();
+                old
+            }
+            ColumnValues::Dense { base, slots, len } => {
+                let end = *base + slots.len() as u64;
+                let span = end.max(raw + 1) - (*base).min(raw);
+                if raw >= *base && raw < end {
+                    let old = slots[(raw - *base) as usize].replace(value);
+                    if old.is_none() {
+                        *len += 1;
+                    }
+                    old
+                } else if (*len as u64 + 1) * SPARSE_FILL >= span {
+                    // Still packed enough: widen the array to take the id
+                    if raw < *base {
+                        let shift = (*base - raw) as usize;
+                        slots.splice(0..0, std::iter::repeat_with(|| None).take(shift));
+                        *base = raw;
+                    } else {
+                        slots.resize_with((raw + 1 - *base) as usize, || None);
                     }
+                    slots[(raw - *base) as usize] = Some(value);
+                    *len += 1;
+                    None
+                } else {
+                    self.make_sparse();
+                    self.insert(id, value)
                 }
             }
+        }
+    }
 
-            // Update max
-            match &zone_map.max {
-                None => zone_map.max = Some(value.clone()),
-                Some(current) => {
-                    if compare_values(value, current) == Some(Ordering::Greater) {
-                        zone_map.max = Some(value.clone());
-                    }
+    fn remove(&mut self, id: Id) -> Option<Value> {
+        match self {
+            ColumnValues::Sparse { map, .. } => map.remove(&id),
+            ColumnValues::Dense { base, slots, len } => {
+                let offset = usize::try_from(id.raw().checked_sub(*base)?).ok()?;
+                let removed = slots.get_mut(offset)?.take()?;
+                *len -= 1;
+                if (*len as u64) * SPARSE_FILL < slots.len() as u64 {
+                    self.make_sparse();
                 }
+                Some(removed)
             }
         }
+    }
 
-        self.zone_map = zone_map;
-        self.zone_map_dirty = false;
+    /// Iterates over `(id, value)` pairs; in ascending id order when dense.
+    fn iter(&self) -> impl Iterator<Item = (Id, &Value)> {
+        let (sparse, dense) = match self {
+            ColumnValues::Sparse { map, .. } => (Some(map), None),
+            ColumnValues::Dense { base, slots, .. } => (None, Some((*base, slots))),
+        };
+        let sparse = sparse.into_iter().flatten().map(|(&id, v)| (id, v));
+        let dense = dense.into_iter().flat_map(|(base, slots)| {
+            slots.iter().enumerate().filter_map(move |(offset, slot)| {
+                slot.as_ref()
+                    .map(|v| (Id::from_raw(base + offset as u64), v))
+            })
+        });
+        sparse.chain(dense)
+    }
+
+    /// Moves a sparse column into an array once its ids are packed enough.
+    fn densify_if_packed(&mut self) {
+        let ColumnValues::Sparse {
+            map,
+            bounds: Some((lo, hi)),
+        } = self
+        else {
+            return;
+        };
+        let span = *hi - *lo + 1;
+        if map.len() < DENSE_MIN_VALUES || (map.len() as u64) * DENSE_FILL < span {
+            return;
+        }
+        let base = *lo;
+        let mut slots: Vec<Option<Value>> = Vec::new();
+        slots.resize_with(span as usize, || None);
+        let len = map.len();
+        for (id, value) in map.drain() {
+            slots[(id.raw() - base) as usize] = Some(value);
+        }
+        *self = ColumnValues::Dense { base, slots, len };
+    }
+
+    /// Moves a dense column back into a hash map.
+    fn make_sparse(&mut self) {
+        let ColumnValues::Dense { base, slots, len } = self else {
+            return;
+        };
+        let mut map = FxHashMap::default();
+        map.reserve(*len);
+        let mut bounds: Option<(u64, u64)> = None;
+        for (offset, slot) in slots.drain(..).enumerate() {
+            if let Some(value) = slot {
+                let raw = *base + offset as u64;
+                bounds = Some(bounds.map_or((raw, raw), |(lo, _)| (lo, raw)));
+                map.insert(Id::from_raw(raw), value);
+            }
+        }
+        *self = ColumnValues::Sparse { map, bounds };
+    }
+}
+
+/// Extends a zone map to cover a newly inserted value.
+fn widen_zone_map(zone_map: &mut ZoneMapEntry, value: &Value) {
+    zone_map.row_count += 1;
+
+    if matches!(value, Value::Null) {
+        zone_map.null_count += 1;
+        return;
+    }
+
+    // Update min
+    match &zone_map.min {
+        None => zone_map.min = Some(value.clone()),
+        Some(current) => {
+            if compare_values(value, current) == Some(Ordering::Less) {
+                zone_map.min = Some(value.clone());
+            }
+        }
+    }
+
+    // Update max
+    match &zone_map.max {
+        None => zone_map.max = Some(value.clone()),
+        Some(current) => {
+            if compare_values(value, current) == Some(Ordering::Greater) {
+                zone_map.max = Some(value.clone());
+            }
+        }
+    }
+}
+
+/// Takes a removed value out of a zone map's counts.
+///
+/// Returns true if the value was the min or max, so the bounds may now be
+/// wider than the remaining values.
+fn narrow_zone_map(zone_map: &mut ZoneMapEntry, value: &Value) -> bool {
+    zone_map.row_count = zone_map.row_count.saturating_sub(1);
+
+    if matches!(value, Value::Null) {
+        zone_map.null_count = zone_map.null_count.saturating_sub(1);
+        return false;
+    }
+
+    let is_bound = |bound: &Option<Value>| {
+        bound
+            .as_ref()
+            .is_some_and(|bound| compare_values(value, bound) == Some(Ordering::Equal))
+    };
+    is_bound(&zone_map.min) || is_bound(&zone_map.max)
+}
+
+/// Checks that a zone map accounts for a value stored under it.
+///
+/// Values that don't compare with the bounds (mixed types) count as covered,
+/// matching how [`widen_zone_map`] leaves the bounds alone for them.
+fn zone_map_covers(zone_map: &ZoneMapEntry, value: &Value) -> bool {
+    if matches!(value, Value::Null) {
+        return zone_map.null_count > 0;
+    }
+    match (&zone_map.min, &zone_map.max) {
+        (Some(min), Some(max)) => {
+            compare_values(value, min) != Some(Ordering::Less)
+                && compare_values(value, max) != Some(Ordering::Greater)
+        }
+        _ => false,
+    }
+}
+
+/// Checks a predicate against a zone map; `false` means nothing can match.
+fn zone_might_match(zone_map: &ZoneMapEntry, op: CompareOp, value: &Value) -> bool {
+    match op {
+        CompareOp::Eq => zone_map.might_contain_equal(value),
+        CompareOp::Ne => {
+            // Can only skip if all values are equal to the value
+            // (which means min == max == value)
+            match (&zone_map.min, &zone_map.max) {
+                (Some(min), Some(max)) => {
+                    !(compare_values(min, value) == Some(Ordering::Equal)
+                        && compare_values(max, value) == Some(Ordering::Equal))
+                }
+                _ => true,
+            }
+        }
+        CompareOp::Lt => zone_map.might_contain_less_than(value, false),
+        CompareOp::Le => zone_map.might_contain_less_than(value, true),
+        CompareOp::Gt => zone_map.might_contain_greater_than(value, false),
+        CompareOp::Ge => zone_map.might_contain_greater_than(value, true),
+    }
+}
+
+/// Adds two numbers, treating a null `current` as "nothing yet".
+fn add_numeric(current: &Value, delta: &Value) -> Option<Value> {
+    match (current, delta) {
+        (Value::Null, Value::Int64(_) | Value::Float64(_)) => Some(delta.clone()),
+        (Value::Int64(a), Value::Int64(b)) => a.checked_add(*b).map(Value::Int64),
+        (Value::Int64(a), Value::Float64(b)) => Some(Value::Float64(*a as f64 + b)),
+        (Value::Float64(a), Value::Int64(b)) => Some(Value::Float64(a + *b as f64)),
+        (Value::Float64(a), Value::Float64(b)) => Some(Value::Float64(a + b)),
+        _ => None,
     }
 }
 
@@ -922,7 +1340,7 @@ fn compare_values(a: &Value, b: &Value) -> Option<Ordering> {
         (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
         (Value::Int64(a), Value::Float64(b)) => (*a as f64).partial_cmp(b),
         (Value::Float64(a), Value::Int64(b)) => a.partial_cmp(&(*b as f64)),
-        _ => None,
+        _ => a.compare_temporal(b),
     }
 }
 
@@ -935,13 +1353,80 @@ impl<Id: EntityId> Default for PropertyColumn<Id> {
 /// A borrowed reference to a property column for bulk reads.
 ///
 /// Holds the read lock so the column can't change while you're iterating.
+///
+/// ```
+/// use grafeo_core::graph::lpg::PropertyStorage;
+/// use grafeo_common::types::{NodeId, PropertyKey, Value};
+///
+/// let storage = PropertyStorage::new();
+/// let age = PropertyKey::new("age");
+/// storage.set(NodeId::new(2), age.clone(), Value::Int64(41));
+/// storage.set(NodeId::new(1), age.clone(), Value::Int64(30));
+///
+/// let column = storage.column(&age).unwrap();
+/// let ages = column.collect_into_vector();
+/// assert_eq!(ages.get_value(0), Some(Value::Int64(30)));
+/// assert_eq!(ages.get_value(1), Some(Value::Int64(41)));
+/// ```
 pub struct PropertyColumnRef<'a, Id: EntityId = NodeId> {
-    _guard: parking_lot::RwLockReadGuard<'a, FxHashMap<PropertyKey, PropertyColumn<Id>>>,
-    #[allow(dead_code)]
+    guard: parking_lot::RwLockReadGuard<'a, FxHashMap<PropertyKey, PropertyColumn<Id>>>,
     key: PropertyKey,
     _marker: PhantomData<Id>,
 }
 
+impl<Id: EntityId> PropertyColumnRef<'_, Id> {
+    fn column(&self) -> &PropertyColumn<Id> {
+        // `PropertyStorage::column` only hands out refs for existing keys, and
+        // the guard stops the column from being dropped.
+        &self.guard[&self.key]
+    }
+
+    /// Returns the property key this column holds.
+    #[must_use]
+    pub fn key(&self) -> &PropertyKey {
+        &self.key
+    }
+
+    /// Returns the number of values in the column.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.column().len()
+    }
+
+    /// Returns true if the column holds no values.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.column().is_empty()
+    }
+
+    /// Returns how the column currently stores its values.
+    #[must_use]
+    pub fn layout(&self) -> ColumnLayout {
+        self.column().layout()
+    }
+
+    /// Iterates over `(id, value)` pairs in ascending id order.
+    ///
+    /// Only uncompressed values are visited, like [`PropertyColumn::get`].
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &Value)> {
+        let mut entries: Vec<(Id, &Value)> = self.column().iter().collect();
+        entries.sort_unstable_by_key(|(id, _)| id.raw());
+        entries.into_iter()
+    }
+
+    /// Copies the column's values into a vector, in ascending id order.
+    ///
+    /// Position `i` holds the value of the `i`th id yielded by [`iter`](Self::iter).
+    #[must_use]
+    pub fn collect_into_vector(&self) -> ValueVector {
+        let mut vector = ValueVector::with_capacity(LogicalType::Any, self.len());
+        for (_, value) in self.iter() {
+            vector.push_value(value.clone());
+        }
+        vector
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -986,6 +1471,24 @@ mod tests {
         assert!(storage.get(node, &key).is_none());
     }
 
+    #[test]
+    fn test_property_storage_null_vs_missing() {
+        let storage = PropertyStorage::new();
+
+        let node = NodeId::new(1);
+        let key = PropertyKey::new("nickname");
+
+        assert!(!storage.contains(node, &key));
+        assert!(storage.get(node, &key).is_none());
+
+        storage.set(node, key.clone(), Value::Null);
+        assert!(storage.contains(node, &key));
+        assert_eq!(storage.get(node, &key), Some(Value::Null));
+
+        storage.remove(node, &key);
+        assert!(!storage.contains(node, &key));
+    }
+
     #[test]
     fn test_property_storage_get_all() {
         let storage = PropertyStorage::new();
@@ -1030,6 +1533,45 @@ mod tests {
         assert_eq!(col.len(), 1);
     }
 
+    #[test]
+    fn test_block_zone_maps() {
+        let storage = PropertyStorage::new();
+        let age = PropertyKey::new("age");
+
+        // Two blocks: ages 0..10 in the first, 100..110 in the second
+        for i in 0..10 {
+            storage.set(NodeId::new(i), age.clone(), Value::Int64(i as i64));
+            storage.set(
+                NodeId::new(ZONE_BLOCK_SIZE + i),
+                age.clone(),
+                Value::Int64(100 + i as i64),
+            );
+        }
+        let first = (NodeId::new(0), NodeId::new(ZONE_BLOCK_SIZE - 1));
+        let second = (
+            NodeId::new(ZONE_BLOCK_SIZE),
+            NodeId::new(2 * ZONE_BLOCK_SIZE - 1),
+        );
+        let big = Value::Int64(50);
+
+        assert!(!storage.might_match_in(&age, first.0, first.1, CompareOp::Gt, &big));
+        assert!(storage.might_match_in(&age, second.0, second.1, CompareOp::Gt, &big));
+        assert!(storage.might_match_in(&age, first.0, second.1, CompareOp::Gt, &big));
+        assert!(!storage.might_match_in(&age, second.0, second.1, CompareOp::Eq, &big));
+
+        // No values in the block, or no column at all: nothing can match
+        let third = NodeId::new(2 * ZONE_BLOCK_SIZE);
+        assert!(!storage.might_match_in(&age, third, third, CompareOp::Ne, &big));
+        let missing = PropertyKey::new("missing");
+        assert!(!storage.might_match_in(&missing, first.0, first.1, CompareOp::Ne, &big));
+
+        // Removing the column's min leaves the zone maps stale until rebuilt
+        storage.remove(NodeId::new(0), &age);
+        assert!(storage.might_match_in(&age, first.0, first.1, CompareOp::Gt, &big));
+        storage.rebuild_zone_maps();
+        assert!(!storage.might_match_in(&age, first.0, first.1, CompareOp::Gt, &big));
+    }
+
     #[test]
     fn test_compression_mode() {
         let col: PropertyColumn<NodeId> = PropertyColumn::new();
@@ -1174,6 +1716,54 @@ mod tests {
         assert!(stats.contains_key(&PropertyKey::new("name")));
     }
 
+    #[test]
+    fn test_column_ref_iterates_in_id_order() {
+        let storage = PropertyStorage::new();
+        let age = PropertyKey::new("age");
+        storage.set(NodeId::new(3), age.clone(), Value::Int64(35));
+        storage.set(NodeId::new(1), age.clone(), Value::Int64(30));
+        storage.set(NodeId::new(2), age.clone(), Value::Int64(25));
+        storage.set(NodeId::new(1), PropertyKey::new("name"), "Alice".into());
+
+        let column = storage.column(&age).unwrap();
+        assert_eq!(column.key(), &age);
+        assert_eq!(column.len(), 3);
+
+        let entries: Vec<(NodeId, Value)> = column.iter().map(|(id, v)| (id, v.clone())).collect();
+        assert_eq!(
+            entries,
+            vec![
+                (NodeId::new(1), Value::Int64(30)),
+                (NodeId::new(2), Value::Int64(25)),
+                (NodeId::new(3), Value::Int64(35)),
+            ]
+        );
+
+        assert!(storage.column(&PropertyKey::new("missing")).is_none());
+    }
+
+    #[test]
+    fn test_column_ref_collects_into_vector() {
+        let storage: PropertyStorage<EdgeId> = PropertyStorage::new();
+        let weight = PropertyKey::new("weight");
+        storage.set(EdgeId::new(7), weight.clone(), Value::Float64(0.5));
+        storage.set(EdgeId::new(4), weight.clone(), Value::Null);
+        storage.set(
+            EdgeId::new(9),
+            weight.clone(),
+            Value::String("heavy".into()),
+        );
+
+        let column = storage.column(&weight).unwrap();
+        let vector = column.collect_into_vector();
+        assert_eq!(vector.len(), 3);
+        for (i, (id, value)) in column.iter().enumerate() {
+            assert_eq!(vector.get_value(i).as_ref(), Some(value));
+            assert_eq!(storage.get(id, &weight).as_ref(), Some(value));
+        }
+        assert!(vector.is_null(0));
+    }
+
     #[test]
     fn test_memory_usage() {
         let storage = PropertyStorage::new();
@@ -1189,4 +1779,237 @@ mod tests {
         let usage = storage.memory_usage();
         assert!(usage > 0);
     }
+
+    #[test]
+    fn test_dense_layout_for_contiguous_ids() {
+        let mut col = PropertyColumn::new();
+        for i in 0..10_000 {
+            col.set(NodeId::new(i), Value::Int64(i as i64));
+        }
+        assert_eq!(col.layout(), ColumnLayout::Dense);
+        assert_eq!(col.len(), 10_000);
+
+        // A dense scan visits every value once, in id order
+        let mut expected = 0;
+        for (id, value) in col.iter() {
+            assert_eq!(id, NodeId::new(expected));
+            assert_eq!(value, &Value::Int64(expected as i64));
+            expected += 1;
+        }
+        assert_eq!(expected, 10_000);
+        assert_eq!(col.get(NodeId::new(9_999)), Some(Value::Int64(9_999)));
+        assert_eq!(col.get(NodeId::new(10_000)), None);
+
+        // Small columns stay sparse however packed their ids are
+        let mut small = PropertyColumn::new();
+        for i in 0..10 {
+            small.set(NodeId::new(i), Value::Int64(i as i64));
+        }
+        assert_eq!(small.layout(), ColumnLayout::Sparse);
+
+        let storage = PropertyStorage::new();
+        let age = PropertyKey::new("age");
+        for i in 0..1_000 {
+            storage.set(NodeId::new(i), age.clone(), Value::Int64(i as i64));
+        }
+        assert_eq!(storage.column(&age).unwrap().layout(), ColumnLayout::Dense);
+    }
+
+    #[test]
+    fn test_layout_switches_preserve_values() {
+        let mut col = PropertyColumn::new();
+        // Written in reverse, starting well above zero
+        for i in (100..1_100).rev() {
+            col.set(NodeId::new(i), Value::Int64(i as i64));
+        }
+        assert_eq!(col.layout(), ColumnLayout::Dense);
+
+        // Growing below the lowest id keeps the column dense
+        col.set(NodeId::new(50), Value::Int64(50));
+        assert_eq!(col.layout(), ColumnLayout::Dense);
+
+        // A far-off id would leave the array mostly empty
+        col.set(NodeId::new(1_000_000), "far".into());
+        assert_eq!(col.layout(), ColumnLayout::Sparse);
+        assert_eq!(col.len(), 1_002);
+        assert_eq!(col.get(NodeId::new(50)), Some(Value::Int64(50)));
+        assert_eq!(col.get(NodeId::new(1_000_000)), Some("far".into()));
+        for i in 100..1_100 {
+            assert_eq!(col.get(NodeId::new(i)), Some(Value::Int64(i as i64)));
+        }
+
+        // Removing most values turns a dense column sparse again
+        let mut col = PropertyColumn::new();
+        for i in 0..1_000 {
+            col.set(NodeId::new(i), Value::Int64(i as i64));
+        }
+        for i in 0..800 {
+            assert_eq!(col.remove(NodeId::new(i)), Some(Value::Int64(i as i64)));
+        }
+        assert_eq!(col.layout(), ColumnLayout::Sparse);
+        assert_eq!(col.len(), 200);
+        let mut remaining: Vec<(NodeId, Value)> =
+            col.iter().map(|(id, v)| (id, v.clone())).collect();
+        remaining.sort_by_key(|(id, _)| id.as_u64());
+        let expected: Vec<(NodeId, Value)> = (800..1_000)
+            .map(|i| (NodeId::new(i), Value::Int64(i as i64)))
+            .collect();
+        assert_eq!(remaining, expected);
+    }
+
+    #[test]
+    fn test_zone_maps_match_across_layouts() {
+        let mut dense = PropertyColumn::new();
+        let mut sparse = PropertyColumn::new();
+        for i in 0..1_000 {
+            let value = Value::Int64((i % 97) as i64);
+            dense.set(NodeId::new(i), value.clone());
+            sparse.set(NodeId::new(i * ZONE_BLOCK_SIZE), value);
+        }
+        assert_eq!(dense.layout(), ColumnLayout::Dense);
+        assert_eq!(sparse.layout(), ColumnLayout::Sparse);
+
+        dense.remove(NodeId::new(0));
+        sparse.remove(NodeId::new(0));
+        dense.rebuild_zone_map();
+        sparse.rebuild_zone_map();
+        let (left, right) = (dense.zone_map(), sparse.zone_map());
+        assert_eq!(left.min, right.min);
+        assert_eq!(left.max, right.max);
+        assert_eq!(left.row_count, right.row_count);
+        assert_eq!(left.null_count, right.null_count);
+        assert!(!dense.might_match(CompareOp::Gt, &Value::Int64(96)));
+        assert!(!sparse.might_match(CompareOp::Gt, &Value::Int64(96)));
+        assert!(dense.might_match_in(0, ZONE_BLOCK_SIZE - 1, CompareOp::Eq, &Value::Int64(1)));
+        assert!(!sparse.might_match_in(0, ZONE_BLOCK_SIZE - 1, CompareOp::Ne, &Value::Int64(1)));
+    }
+
+    #[test]
+    fn test_increment() {
+        let storage: PropertyStorage<NodeId> = PropertyStorage::new();
+        let views = PropertyKey::new("views");
+        let node = NodeId::new(1);
+
+        // A missing property starts from the delta
+        assert_eq!(
+            storage.increment(node, views.clone(), &Value::Int64(2)),
+            Some(Value::Int64(2))
+        );
+        assert_eq!(
+            storage.increment(node, views.clone(), &Value::Int64(3)),
+            Some(Value::Int64(5))
+        );
+        assert_eq!(
+            storage.increment(node, views.clone(), &Value::Float64(0.5)),
+            Some(Value::Float64(5.5))
+        );
+        assert_eq!(storage.get(node, &views), Some(Value::Float64(5.5)));
+
+        // Overflow and non-numeric values leave the property untouched
+        storage.set(node, views.clone(), Value::Int64(i64::MAX));
+        assert_eq!(
+            storage.increment(node, views.clone(), &Value::Int64(1)),
+            None
+        );
+        assert_eq!(
+            storage.increment(node, views.clone(), &Value::from("1")),
+            None
+        );
+        assert_eq!(storage.get(node, &views), Some(Value::Int64(i64::MAX)));
+
+        let name = PropertyKey::new("name");
+        assert_eq!(
+            storage.increment(node, name.clone(), &Value::from("x")),
+            None
+        );
+        assert!(storage.column(&name).is_none());
+    }
+
+    #[test]
+    fn test_zone_map_violations() {
+        let storage: PropertyStorage<NodeId> = PropertyStorage::new();
+        let age = PropertyKey::new("age");
+        for i in 0..10 {
+            storage.set(NodeId::new(i), age.clone(), Value::Int64(i as i64 * 10));
+        }
+        storage.set(NodeId::new(10), age.clone(), Value::Null);
+        assert!(storage.zone_map_violations().is_empty());
+
+        // Narrow the column zone map so it misses the top value
+        storage.columns.write().get_mut(&age).unwrap().zone_map.max = Some(Value::Int64(80));
+        assert_eq!(
+            storage.zone_map_violations(),
+            vec![(age.clone(), NodeId::new(9))]
+        );
+
+        // A block zone map that forgot its nulls
+        storage.rebuild_zone_maps();
+        storage
+            .columns
+            .write()
+            .get_mut(&age)
+            .unwrap()
+            .block_zone_maps
+            .get_mut(&0)
+            .unwrap()
+            .null_count = 0;
+        assert_eq!(
+            storage.zone_map_violations(),
+            vec![(age.clone(), NodeId::new(10))]
+        );
+
+        // Stale zone maps are already distrusted, so they aren't reported
+        storage.remove(NodeId::new(0), &age);
+        assert!(storage.zone_map_violations().is_empty());
+    }
+
+    #[test]
+    fn test_remove_mid_range_value_keeps_zone_map() {
+        let mut col = PropertyColumn::new();
+        for (i, age) in [10i64, 20, 30, 40].into_iter().enumerate() {
+            col.set(NodeId::new(i as u64), Value::Int64(age));
+        }
+        col.set(NodeId::new(4), Value::Null);
+        // Overwrites replace the old value in the counts
+        col.set(NodeId::new(1), Value::Int64(25));
+
+        assert_eq!(col.remove(NodeId::new(1)), Some(Value::Int64(25)));
+        assert_eq!(col.remove(NodeId::new(4)), Some(Value::Null));
+        assert!(!col.zone_map_dirty);
+        let zone_map = col.zone_map();
+        assert_eq!(zone_map.row_count, 3);
+        assert_eq!(zone_map.null_count, 0);
+        assert_eq!(zone_map.min, Some(Value::Int64(10)));
+        assert_eq!(zone_map.max, Some(Value::Int64(40)));
+
+        // Still skips without a rebuild
+        assert!(!col.might_match(CompareOp::Gt, &Value::Int64(40)));
+        assert!(!col.might_match(CompareOp::Lt, &Value::Int64(10)));
+        assert!(!col.might_match(CompareOp::Eq, &Value::Null));
+        assert!(col.might_match(CompareOp::Eq, &Value::Int64(30)));
+        assert!(!col.might_match_in(0, 10, CompareOp::Gt, &Value::Int64(40)));
+        assert!(col.zone_map_violations().is_empty());
+    }
+
+    #[test]
+    fn test_remove_bound_value_dirties_zone_map() {
+        let mut col = PropertyColumn::new();
+        for (i, age) in [10i64, 20, 30].into_iter().enumerate() {
+            col.set(NodeId::new(i as u64), Value::Int64(age));
+        }
+
+        col.remove(NodeId::new(0));
+        assert!(col.zone_map_dirty);
+        assert_eq!(col.zone_map().row_count, 2);
+        assert!(col.might_match(CompareOp::Lt, &Value::Int64(15)));
+
+        col.rebuild_zone_map();
+        assert!(!col.zone_map_dirty);
+        assert_eq!(col.zone_map().min, Some(Value::Int64(20)));
+        assert!(!col.might_match(CompareOp::Lt, &Value::Int64(15)));
+
+        // Removing the max dirties it too
+        col.remove(NodeId::new(2));
+        assert!(col.zone_map_dirty);
+    }
 }
@@ -10,17 +10,24 @@
 //! - Forward and backward adjacency indexes
 
 use super::property::CompareOp;
-use super::{Edge, EdgeRecord, Node, NodeRecord, PropertyStorage};
+use super::tx_write::{Entity, PropertyVersion};
+use super::{Edge, EdgeRecord, Inconsistency, Node, NodeRecord, PropertyStorage, TxWrite};
 use crate::graph::Direction;
-use crate::index::adjacency::ChunkedAdjacency;
+use crate::index::adjacency::{BatchExpansion, ChunkedAdjacency};
+use crate::index::fulltext::{FullTextIndex, Tokenizer};
+use crate::index::property::PropertyIndex;
+use crate::index::spatial::SpatialIndex;
 use crate::index::zone_map::ZoneMapEntry;
 use crate::statistics::{EdgeTypeStatistics, LabelStatistics, Statistics};
 use grafeo_common::mvcc::VersionChain;
-use grafeo_common::types::{EdgeId, EpochId, NodeId, PropertyKey, TxId, Value};
+use grafeo_common::types::{EdgeId, EpochId, NodeId, PropertyKey, Timestamp, TxId, Value};
+use grafeo_common::utils::error::{Error, Result, TransactionError};
 use grafeo_common::utils::hash::{FxHashMap, FxHashSet};
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use std::collections::BTreeMap;
+use std::hash::Hash;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 /// Configuration for the LPG store.
 ///
@@ -36,6 +43,10 @@ pub struct LpgStoreConfig {
     pub initial_node_capacity: usize,
     /// Initial capacity for edges (avoids early reallocations).
     pub initial_edge_capacity: usize,
+    /// Match labels and edge types ignoring case, so a scan for `person`
+    /// finds nodes labelled `Person`. Stored names keep their casing. Off
+    /// by default, where differently cased names are different labels.
+    pub case_insensitive_labels: bool,
 }
 
 impl Default for LpgStoreConfig {
@@ -44,6 +55,7 @@ impl Default for LpgStoreConfig {
             backward_edges: true,
             initial_node_capacity: 1024,
             initial_edge_capacity: 4096,
+            case_insensitive_labels: false,
         }
     }
 }
@@ -77,7 +89,6 @@ impl Default for LpgStoreConfig {
 /// ```
 pub struct LpgStore {
     /// Configuration.
-    #[allow(dead_code)]
     config: LpgStoreConfig,
 
     /// Node records indexed by NodeId, with version chains for MVCC.
@@ -98,12 +109,20 @@ pub struct LpgStore {
     /// Label ID to name mapping.
     id_to_label: RwLock<Vec<Arc<str>>>,
 
+    /// Lowercased label name to the IDs of every label that folds to it.
+    /// Only consulted with case-insensitive labels.
+    folded_label_ids: RwLock<FxHashMap<Arc<str>, Vec<u32>>>,
+
     /// Edge type name to ID mapping.
     edge_type_to_id: RwLock<FxHashMap<Arc<str>, u32>>,
 
     /// Edge type ID to name mapping.
     id_to_edge_type: RwLock<Vec<Arc<str>>>,
 
+    /// Lowercased edge type name to the IDs of every type that folds to it.
+    /// Only consulted with case-insensitive labels.
+    folded_edge_type_ids: RwLock<FxHashMap<Arc<str>, Vec<u32>>>,
+
     /// Forward adjacency lists (outgoing edges).
     forward_adj: ChunkedAdjacency,
 
@@ -127,8 +146,49 @@ pub struct LpgStore {
     /// Current epoch.
     current_epoch: AtomicU64,
 
+    /// Number of changes made to the graph, for detecting stale derived data.
+    change_count: AtomicU64,
+
     /// Statistics for cost-based optimization.
     statistics: RwLock<Statistics>,
+
+    /// Nodes changed per label id since statistics were last computed.
+    label_changes: RwLock<FxHashMap<u32, u64>>,
+
+    /// Edges changed per edge type id since statistics were last computed.
+    edge_type_changes: RwLock<FxHashMap<u32, u64>>,
+
+    /// Full-text indexes, kept in step with node property and label changes.
+    fulltext_indexes: RwLock<Vec<PropertyIndexEntry<FullTextIndex>>>,
+
+    /// Spatial indexes, kept in step with node property and label changes.
+    spatial_indexes: RwLock<Vec<PropertyIndexEntry<SpatialIndex>>>,
+
+    /// Value indexes, kept in step with node property and label changes.
+    value_indexes: RwLock<Vec<PropertyIndexEntry<PropertyIndex>>>,
+
+    /// Whether an incoming degree has had to scan every edge yet, so the
+    /// warning about it is logged once.
+    warned_in_degree_scan: AtomicBool,
+
+    /// What each open transaction has written, in order.
+    tx_writes: Mutex<FxHashMap<TxId, Vec<TxWrite>>>,
+
+    /// The open transaction writing each locked node or edge.
+    write_locks: Mutex<FxHashMap<Entity, TxId>>,
+
+    /// Node property values overwritten by transactions, oldest first.
+    node_property_versions: RwLock<FxHashMap<NodeId, Vec<PropertyVersion>>>,
+
+    /// Edge property values overwritten by transactions, oldest first.
+    edge_property_versions: RwLock<FxHashMap<EdgeId, Vec<PropertyVersion>>>,
+}
+
+/// An index over one property of nodes with a label.
+struct PropertyIndexEntry<T> {
+    label: String,
+    property: PropertyKey,
+    index: Arc<T>,
 }
 
 impl LpgStore {
@@ -154,8 +214,10 @@ impl LpgStore {
             edge_properties: PropertyStorage::new(),
             label_to_id: RwLock::new(FxHashMap::default()),
             id_to_label: RwLock::new(Vec::new()),
+            folded_label_ids: RwLock::new(FxHashMap::default()),
             edge_type_to_id: RwLock::new(FxHashMap::default()),
             id_to_edge_type: RwLock::new(Vec::new()),
+            folded_edge_type_ids: RwLock::new(FxHashMap::default()),
             forward_adj: ChunkedAdjacency::new(),
             backward_adj,
             label_index: RwLock::new(Vec::new()),
@@ -163,7 +225,18 @@ impl LpgStore {
             next_node_id: AtomicU64::new(0),
             next_edge_id: AtomicU64::new(0),
             current_epoch: AtomicU64::new(0),
+            change_count: AtomicU64::new(0),
             statistics: RwLock::new(Statistics::new()),
+            label_changes: RwLock::new(FxHashMap::default()),
+            edge_type_changes: RwLock::new(FxHashMap::default()),
+            fulltext_indexes: RwLock::new(Vec::new()),
+            spatial_indexes: RwLock::new(Vec::new()),
+            value_indexes: RwLock::new(Vec::new()),
+            warned_in_degree_scan: AtomicBool::new(false),
+            tx_writes: Mutex::new(FxHashMap::default()),
+            write_locks: Mutex::new(FxHashMap::default()),
+            node_property_versions: RwLock::new(FxHashMap::default()),
+            edge_property_versions: RwLock::new(FxHashMap::default()),
             config,
         }
     }
@@ -180,6 +253,29 @@ impl LpgStore {
         EpochId::new(id)
     }
 
+    /// Returns how many changes have been made to the graph so far.
+    ///
+    /// Every node, edge, label, or property change bumps this, and so does
+    /// every commit and rollback, since those change what readers see.
+    /// Anything derived from the graph can tell whether it's out of date by
+    /// comparing counts.
+    #[must_use]
+    pub fn change_count(&self) -> u64 {
+        self.change_count.load(Ordering::Acquire)
+    }
+
+    fn record_change(&self) {
+        self.change_count.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn record_label_change(&self, label_id: u32) {
+        *self.label_changes.write().entry(label_id).or_default() += 1;
+    }
+
+    fn record_edge_type_change(&self, type_id: u32) {
+        *self.edge_type_changes.write().entry(type_id).or_default() += 1;
+    }
+
     // === Node Operations ===
 
     /// Creates a new node with the given labels.
@@ -201,6 +297,7 @@ impl LpgStore {
         for label in labels {
             let label_id = self.get_or_create_label_id(*label);
             node_label_set.insert(label_id);
+            self.record_label_change(label_id);
 
             // Update label index
             let mut index = self.label_index.write();
@@ -216,6 +313,11 @@ impl LpgStore {
         // Create version chain with initial version
         let chain = VersionChain::with_initial(record, epoch, tx_id);
         self.nodes.write().insert(id, chain);
+        self.record_change();
+        if tx_id != TxId::SYSTEM {
+            let labels = labels.iter().map(|label| Arc::from(*label)).collect();
+            self.log_write(tx_id, TxWrite::NodeCreated { id, labels });
+        }
         id
     }
 
@@ -244,8 +346,21 @@ impl LpgStore {
         let id = self.create_node_versioned(labels, epoch, tx_id);
 
         for (key, value) in properties {
-            self.node_properties.set(id, key.into(), value.into());
+            let (key, value) = (key.into(), value.into());
+            if tx_id != TxId::SYSTEM {
+                self.log_write(
+                    tx_id,
+                    TxWrite::NodePropertySet {
+                        id,
+                        key: key.clone(),
+                        old: None,
+                        value: Some(value.clone()),
+                    },
+                );
+            }
+            self.node_properties.set(id, key, value);
         }
+        self.update_property_indexes(id, None);
 
         // Update props_count in record
         let count = self.node_properties.get_all(id).len() as u16;
@@ -288,8 +403,15 @@ impl LpgStore {
             }
         }
 
-        // Get properties
+        // Get properties, as they were for this reader
         node.properties = self.node_properties.get_all(id).into_iter().collect();
+        Self::apply_property_versions(
+            &self.node_property_versions,
+            id,
+            &mut node.properties,
+            epoch,
+            None,
+        );
 
         Some(node)
     }
@@ -318,8 +440,15 @@ impl LpgStore {
             }
         }
 
-        // Get properties
+        // Get properties, as they were for this reader
         node.properties = self.node_properties.get_all(id).into_iter().collect();
+        Self::apply_property_versions(
+            &self.node_property_versions,
+            id,
+            &mut node.properties,
+            epoch,
+            Some(tx_id),
+        );
 
         Some(node)
     }
@@ -345,23 +474,10 @@ impl LpgStore {
 
             // Mark the version chain as deleted at this epoch
             chain.mark_deleted(epoch);
+            drop(nodes); // Release lock before removing labels and properties
 
-            // Remove from label index using node_labels map
-            let mut index = self.label_index.write();
-            let mut node_labels = self.node_labels.write();
-            if let Some(label_ids) = node_labels.remove(&id) {
-                for label_id in label_ids {
-                    if let Some(set) = index.get_mut(label_id as usize) {
-                        set.remove(&id);
-                    }
-                }
-            }
-
-            // Remove properties
-            drop(nodes); // Release lock before removing properties
-            drop(index);
-            drop(node_labels);
-            self.node_properties.remove_all(id);
+            self.remove_node_contents(id);
+            self.record_change();
 
             // Note: Caller should use delete_node_edges() first if detach is needed
 
@@ -371,11 +487,39 @@ impl LpgStore {
         }
     }
 
+    /// Removes a deleted node's labels and properties.
+    fn remove_node_contents(&self, id: NodeId) {
+        // Remove from label index using node_labels map
+        let mut index = self.label_index.write();
+        let mut node_labels = self.node_labels.write();
+        if let Some(label_ids) = node_labels.remove(&id) {
+            for label_id in label_ids {
+                if let Some(set) = index.get_mut(label_id as usize) {
+                    set.remove(&id);
+                }
+                self.record_label_change(label_id);
+            }
+        }
+        drop(index);
+        drop(node_labels);
+
+        self.node_properties.remove_all(id);
+        self.update_property_indexes(id, None);
+    }
+
     /// Deletes all edges connected to a node (implements DETACH DELETE).
     ///
     /// Call this before `delete_node()` if you want to remove a node that
     /// has edges. Grafeo doesn't auto-delete edges - you have to be explicit.
     pub fn delete_node_edges(&self, node_id: NodeId) {
+        for edge_id in self.node_edge_ids(node_id) {
+            self.delete_edge(edge_id);
+        }
+    }
+
+    /// Returns the edges into and out of a node, including ones already
+    /// deleted but not yet cleaned out of the adjacency.
+    fn node_edge_ids(&self, node_id: NodeId) -> Vec<EdgeId> {
         // Get outgoing edges
         let outgoing: Vec<EdgeId> = self
             .forward_adj
@@ -393,31 +537,26 @@ impl LpgStore {
                 .collect()
         } else {
             // No backward adjacency - scan all edges
-            let epoch = self.current_epoch();
             self.edges
                 .read()
                 .iter()
                 .filter_map(|(id, chain)| {
-                    chain.visible_at(epoch).and_then(|r| {
-                        if !r.is_deleted() && r.dst == node_id {
-                            Some(*id)
-                        } else {
-                            None
-                        }
-                    })
+                    chain
+                        .latest()
+                        .and_then(|r| if r.dst == node_id { Some(*id) } else { None })
                 })
                 .collect()
         };
 
-        // Delete all edges
-        for edge_id in outgoing.into_iter().chain(incoming) {
-            self.delete_edge(edge_id);
-        }
+        outgoing.into_iter().chain(incoming).collect()
     }
 
     /// Sets a property on a node.
     pub fn set_node_property(&self, id: NodeId, key: &str, value: Value) {
-        self.node_properties.set(id, key.into(), value);
+        let key = PropertyKey::from(key);
+        self.node_properties.set(id, key.clone(), value);
+        self.update_property_indexes(id, Some(&key));
+        self.record_change();
 
         // Update props_count in record
         let count = self.node_properties.get_all(id).len() as u16;
@@ -431,13 +570,46 @@ impl LpgStore {
     /// Sets a property on an edge.
     pub fn set_edge_property(&self, id: EdgeId, key: &str, value: Value) {
         self.edge_properties.set(id, key.into(), value);
+        self.record_change();
+    }
+
+    /// Atomically adds `delta` to a numeric node property.
+    ///
+    /// Returns the new value, or None if the property or `delta` isn't
+    /// numeric. See [`PropertyStorage::increment`].
+    pub fn increment_node_property(&self, id: NodeId, key: &str, delta: &Value) -> Option<Value> {
+        let sum = self.node_properties.increment(id, key.into(), delta)?;
+        self.record_change();
+
+        // Update props_count in record
+        let count = self.node_properties.get_all(id).len() as u16;
+        if let Some(chain) = self.nodes.write().get_mut(&id) {
+            if let Some(record) = chain.latest_mut() {
+                record.props_count = count;
+            }
+        }
+
+        Some(sum)
+    }
+
+    /// Atomically adds `delta` to a numeric edge property.
+    ///
+    /// Returns the new value, or None if the property or `delta` isn't
+    /// numeric.
+    pub fn increment_edge_property(&self, id: EdgeId, key: &str, delta: &Value) -> Option<Value> {
+        let sum = self.edge_properties.increment(id, key.into(), delta)?;
+        self.record_change();
+        Some(sum)
     }
 
     /// Removes a property from a node.
     ///
     /// Returns the previous value if it existed, or None if the property didn't exist.
     pub fn remove_node_property(&self, id: NodeId, key: &str) -> Option<Value> {
-        let result = self.node_properties.remove(id, &key.into());
+        let key = PropertyKey::from(key);
+        let result = self.node_properties.remove(id, &key);
+        self.update_property_indexes(id, Some(&key));
+        self.record_change();
 
         // Update props_count in record
         let count = self.node_properties.get_all(id).len() as u16;
@@ -454,7 +626,9 @@ impl LpgStore {
     ///
     /// Returns the previous value if it existed, or None if the property didn't exist.
     pub fn remove_edge_property(&self, id: EdgeId, key: &str) -> Option<Value> {
-        self.edge_properties.remove(id, &key.into())
+        let result = self.edge_properties.remove(id, &key.into());
+        self.record_change();
+        result
     }
 
     /// Adds a label to a node.
@@ -475,6 +649,11 @@ impl LpgStore {
         }
         drop(nodes);
 
+        self.attach_label(node_id, label)
+    }
+
+    /// Adds a label to a node without checking the node is visible.
+    fn attach_label(&self, node_id: NodeId, label: &str) -> bool {
         // Get or create label ID
         let label_id = self.get_or_create_label_id(label);
 
@@ -497,6 +676,9 @@ impl LpgStore {
             index.resize(label_id as usize + 1, FxHashMap::default());
         }
         index[label_id as usize].insert(node_id, ());
+        drop(index);
+        self.record_label_change(label_id);
+        self.update_property_indexes(node_id, None);
 
         // Update label count in node record
         if let Some(chain) = self.nodes.write().get_mut(&node_id) {
@@ -505,6 +687,7 @@ impl LpgStore {
                 record.set_label_count(count as u16);
             }
         }
+        self.record_change();
 
         true
     }
@@ -527,6 +710,11 @@ impl LpgStore {
         }
         drop(nodes);
 
+        self.detach_label(node_id, label)
+    }
+
+    /// Removes a label from a node without checking the node is visible.
+    fn detach_label(&self, node_id: NodeId, label: &str) -> bool {
         // Get label ID
         let label_id = {
             let label_ids = self.label_to_id.read();
@@ -552,6 +740,9 @@ impl LpgStore {
         if (label_id as usize) < index.len() {
             index[label_id as usize].remove(&node_id);
         }
+        drop(index);
+        self.record_label_change(label_id);
+        self.update_property_indexes(node_id, None);
 
         // Update label count in node record
         if let Some(chain) = self.nodes.write().get_mut(&node_id) {
@@ -560,6 +751,7 @@ impl LpgStore {
                 record.set_label_count(count as u16);
             }
         }
+        self.record_change();
 
         true
     }
@@ -598,6 +790,24 @@ impl LpgStore {
         ids
     }
 
+    /// Returns the IDs of all nodes visible to a specific transaction,
+    /// including the ones it created itself and hasn't committed yet.
+    #[must_use]
+    pub fn node_ids_versioned(&self, epoch: EpochId, tx_id: TxId) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = self
+            .nodes
+            .read()
+            .iter()
+            .filter_map(|(id, chain)| {
+                chain
+                    .visible_to(epoch, tx_id)
+                    .and_then(|r| if !r.is_deleted() { Some(*id) } else { None })
+            })
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
     // === Edge Operations ===
 
     /// Creates a new edge.
@@ -620,11 +830,25 @@ impl LpgStore {
         let record = EdgeRecord::new(id, src, dst, type_id, epoch);
         let chain = VersionChain::with_initial(record, epoch, tx_id);
         self.edges.write().insert(id, chain);
+        self.record_edge_type_change(type_id);
 
         // Update adjacency
-        self.forward_adj.add_edge(src, dst, id);
+        self.forward_adj.add_typed_edge(src, dst, id, type_id);
         if let Some(ref backward) = self.backward_adj {
-            backward.add_edge(dst, src, id);
+            backward.add_typed_edge(dst, src, id, type_id);
+        }
+        self.record_change();
+        if tx_id != TxId::SYSTEM {
+            let edge_type = Arc::from(edge_type);
+            self.log_write(
+                tx_id,
+                TxWrite::EdgeCreated {
+                    id,
+                    src,
+                    dst,
+                    edge_type,
+                },
+            );
         }
 
         id
@@ -671,8 +895,15 @@ impl LpgStore {
 
         let mut edge = Edge::new(id, record.src, record.dst, edge_type);
 
-        // Get properties
+        // Get properties, as they were for this reader
         edge.properties = self.edge_properties.get_all(id).into_iter().collect();
+        Self::apply_property_versions(
+            &self.edge_property_versions,
+            id,
+            &mut edge.properties,
+            epoch,
+            None,
+        );
 
         Some(edge)
     }
@@ -695,12 +926,65 @@ impl LpgStore {
 
         let mut edge = Edge::new(id, record.src, record.dst, edge_type);
 
-        // Get properties
+        // Get properties, as they were for this reader
         edge.properties = self.edge_properties.get_all(id).into_iter().collect();
+        Self::apply_property_versions(
+            &self.edge_property_versions,
+            id,
+            &mut edge.properties,
+            epoch,
+            Some(tx_id),
+        );
 
         Some(edge)
     }
 
+    /// Sets the interval during which an edge is valid, for as-of traversal.
+    ///
+    /// `None` leaves that end of the interval open. The interval includes
+    /// `valid_from` and excludes `valid_to`. Returns false if the edge
+    /// doesn't exist.
+    pub fn set_edge_validity(
+        &self,
+        id: EdgeId,
+        valid_from: Option<Timestamp>,
+        valid_to: Option<Timestamp>,
+    ) -> bool {
+        let mut edges = self.edges.write();
+        let Some(record) = edges.get_mut(&id).and_then(VersionChain::latest_mut) else {
+            return false;
+        };
+        record.valid_from = valid_from.unwrap_or(Timestamp::MIN);
+        record.valid_to = valid_to.unwrap_or(Timestamp::MAX);
+        drop(edges);
+        self.record_change();
+        true
+    }
+
+    /// Returns an edge's validity interval, with `None` for open ends.
+    #[must_use]
+    pub fn edge_validity(&self, id: EdgeId) -> Option<(Option<Timestamp>, Option<Timestamp>)> {
+        let edges = self.edges.read();
+        let record = edges.get(&id)?.latest()?;
+        Some((
+            Some(record.valid_from).filter(|&t| t != Timestamp::MIN),
+            Some(record.valid_to).filter(|&t| t != Timestamp::MAX),
+        ))
+    }
+
+    /// Checks whether an edge is valid at the given time.
+    ///
+    /// Edges without a validity interval are always valid; unknown edges
+    /// never are.
+    #[must_use]
+    pub fn is_edge_valid_at(&self, id: EdgeId, at: Timestamp) -> bool {
+        self.edges
+            .read()
+            .get(&id)
+            .and_then(VersionChain::latest)
+            .is_some_and(|record| record.is_valid_at(at))
+    }
+
     /// Deletes an edge (using latest epoch).
     pub fn delete_edge(&self, id: EdgeId) -> bool {
         self.delete_edge_at_epoch(id, self.current_epoch())
@@ -711,13 +995,13 @@ impl LpgStore {
         let mut edges = self.edges.write();
         if let Some(chain) = edges.get_mut(&id) {
             // Get the visible record to check if deleted and get src/dst
-            let (src, dst) = {
+            let (src, dst, type_id) = {
                 match chain.visible_at(epoch) {
                     Some(record) => {
                         if record.is_deleted() {
                             return false;
                         }
-                        (record.src, record.dst)
+                        (record.src, record.dst, record.type_id)
                     }
                     None => return false, // Not visible at this epoch (already deleted)
                 }
@@ -729,13 +1013,15 @@ impl LpgStore {
             drop(edges); // Release lock
 
             // Mark as deleted in adjacency (soft delete)
-            self.forward_adj.mark_deleted(src, id);
+            self.forward_adj.mark_typed_deleted(src, id, type_id);
             if let Some(ref backward) = self.backward_adj {
-                backward.mark_deleted(dst, id);
+                backward.mark_typed_deleted(dst, id, type_id);
             }
 
             // Remove properties
             self.edge_properties.remove_all(id);
+            self.record_edge_type_change(type_id);
+            self.record_change();
 
             true
         } else {
@@ -755,155 +1041,1174 @@ impl LpgStore {
             .count()
     }
 
-    /// Discards all uncommitted versions created by a transaction.
+    // === Transaction Writes ===
+    //
+    // Writes inside a transaction are applied in place, logged, and lock
+    // the node or edge they touch until the transaction ends. Property
+    // values they overwrite are kept as versions, and creations and deletes
+    // live in the version chains, so other readers keep their snapshot.
+    // Outside a transaction (`TxId::SYSTEM`) these fall through to the plain
+    // writes above.
+
+    /// Sets a node property within a transaction.
     ///
-    /// This is called during transaction rollback to clean up uncommitted changes.
-    /// The method removes version chain entries created by the specified transaction.
-    pub fn discard_uncommitted_versions(&self, tx_id: TxId) {
-        // Remove uncommitted node versions
-        {
-            let mut nodes = self.nodes.write();
-            for chain in nodes.values_mut() {
-                chain.remove_versions_by(tx_id);
-            }
-            // Remove completely empty chains (no versions left)
-            nodes.retain(|_, chain| !chain.is_empty());
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// node.
+    pub fn set_node_property_versioned(
+        &self,
+        id: NodeId,
+        key: &str,
+        value: Value,
+        tx_id: TxId,
+    ) -> Result<()> {
+        if tx_id == TxId::SYSTEM {
+            self.set_node_property(id, key, value);
+            return Ok(());
         }
+        self.lock_entity(Entity::Node(id), tx_id)?;
+        let key = PropertyKey::from(key);
+        let old = self.node_properties.get(id, &key);
+        self.set_node_property(id, key.as_str(), value.clone());
+        self.track_node_property(id, key, old, Some(value), tx_id);
+        Ok(())
+    }
 
-        // Remove uncommitted edge versions
-        {
-            let mut edges = self.edges.write();
-            for chain in edges.values_mut() {
-                chain.remove_versions_by(tx_id);
-            }
-            // Remove completely empty chains (no versions left)
-            edges.retain(|_, chain| !chain.is_empty());
+    /// Sets an edge property within a transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// edge.
+    pub fn set_edge_property_versioned(
+        &self,
+        id: EdgeId,
+        key: &str,
+        value: Value,
+        tx_id: TxId,
+    ) -> Result<()> {
+        if tx_id == TxId::SYSTEM {
+            self.set_edge_property(id, key, value);
+            return Ok(());
         }
+        self.lock_entity(Entity::Edge(id), tx_id)?;
+        let key = PropertyKey::from(key);
+        let old = self.edge_properties.get(id, &key);
+        self.set_edge_property(id, key.as_str(), value.clone());
+        self.track_edge_property(id, key, old, Some(value), tx_id);
+        Ok(())
     }
 
-    /// Returns the number of distinct labels in the store.
-    #[must_use]
-    pub fn label_count(&self) -> usize {
-        self.id_to_label.read().len()
+    /// Adds `delta` to a numeric node property within a transaction.
+    ///
+    /// Returns the new value, or None if the property or `delta` isn't
+    /// numeric.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// node.
+    pub fn increment_node_property_versioned(
+        &self,
+        id: NodeId,
+        key: &str,
+        delta: &Value,
+        tx_id: TxId,
+    ) -> Result<Option<Value>> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(self.increment_node_property(id, key, delta));
+        }
+        self.lock_entity(Entity::Node(id), tx_id)?;
+        let key = PropertyKey::from(key);
+        let old = self.node_properties.get(id, &key);
+        let Some(sum) = self.increment_node_property(id, key.as_str(), delta) else {
+            return Ok(None);
+        };
+        self.track_node_property(id, key, old, Some(sum.clone()), tx_id);
+        Ok(Some(sum))
     }
 
-    /// Returns the number of distinct property keys in the store.
+    /// Adds `delta` to a numeric edge property within a transaction.
     ///
-    /// This counts unique property keys across both nodes and edges.
-    #[must_use]
-    pub fn property_key_count(&self) -> usize {
-        let node_keys = self.node_properties.column_count();
-        let edge_keys = self.edge_properties.column_count();
-        // Note: This may count some keys twice if the same key is used
-        // for both nodes and edges. A more precise count would require
-        // tracking unique keys across both storages.
-        node_keys + edge_keys
+    /// Returns the new value, or None if the property or `delta` isn't
+    /// numeric.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// edge.
+    pub fn increment_edge_property_versioned(
+        &self,
+        id: EdgeId,
+        key: &str,
+        delta: &Value,
+        tx_id: TxId,
+    ) -> Result<Option<Value>> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(self.increment_edge_property(id, key, delta));
+        }
+        self.lock_entity(Entity::Edge(id), tx_id)?;
+        let key = PropertyKey::from(key);
+        let old = self.edge_properties.get(id, &key);
+        let Some(sum) = self.increment_edge_property(id, key.as_str(), delta) else {
+            return Ok(None);
+        };
+        self.track_edge_property(id, key, old, Some(sum.clone()), tx_id);
+        Ok(Some(sum))
     }
 
-    /// Returns the number of distinct edge types in the store.
-    #[must_use]
-    pub fn edge_type_count(&self) -> usize {
-        self.id_to_edge_type.read().len()
+    /// Removes a node property within a transaction.
+    ///
+    /// Returns the previous value if it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// node.
+    pub fn remove_node_property_versioned(
+        &self,
+        id: NodeId,
+        key: &str,
+        tx_id: TxId,
+    ) -> Result<Option<Value>> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(self.remove_node_property(id, key));
+        }
+        self.lock_entity(Entity::Node(id), tx_id)?;
+        let old = self.remove_node_property(id, key);
+        if old.is_some() {
+            self.track_node_property(id, key.into(), old.clone(), None, tx_id);
+        }
+        Ok(old)
     }
 
-    // === Traversal ===
-
-    /// Iterates over neighbors of a node in the specified direction.
+    /// Removes an edge property within a transaction.
     ///
-    /// This is the fast path for graph traversal - goes straight to the
-    /// adjacency index without loading full node data.
-    pub fn neighbors(
+    /// Returns the previous value if it existed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// edge.
+    pub fn remove_edge_property_versioned(
         &self,
-        node: NodeId,
-        direction: Direction,
-    ) -> impl Iterator<Item = NodeId> + '_ {
-        let forward: Box<dyn Iterator<Item = NodeId>> = match direction {
-            Direction::Outgoing | Direction::Both => {
-                Box::new(self.forward_adj.neighbors(node).into_iter())
-            }
-            Direction::Incoming => Box::new(std::iter::empty()),
-        };
-
-        let backward: Box<dyn Iterator<Item = NodeId>> = match direction {
-            Direction::Incoming | Direction::Both => {
-                if let Some(ref adj) = self.backward_adj {
-                    Box::new(adj.neighbors(node).into_iter())
-                } else {
-                    Box::new(std::iter::empty())
-                }
-            }
-            Direction::Outgoing => Box::new(std::iter::empty()),
-        };
-
-        forward.chain(backward)
+        id: EdgeId,
+        key: &str,
+        tx_id: TxId,
+    ) -> Result<Option<Value>> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(self.remove_edge_property(id, key));
+        }
+        self.lock_entity(Entity::Edge(id), tx_id)?;
+        let old = self.remove_edge_property(id, key);
+        if old.is_some() {
+            self.track_edge_property(id, key.into(), old.clone(), None, tx_id);
+        }
+        Ok(old)
     }
 
-    /// Returns edges from a node with their targets.
+    /// Adds a label to a node visible to a transaction.
     ///
-    /// Returns an iterator of (target_node, edge_id) pairs.
-    pub fn edges_from(
+    /// Labels aren't versioned: other readers see the change straight away,
+    /// though a rollback still takes it back.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// node.
+    pub fn add_label_versioned(
         &self,
-        node: NodeId,
-        direction: Direction,
-    ) -> impl Iterator<Item = (NodeId, EdgeId)> + '_ {
-        let forward: Box<dyn Iterator<Item = (NodeId, EdgeId)>> = match direction {
-            Direction::Outgoing | Direction::Both => {
-                Box::new(self.forward_adj.edges_from(node).into_iter())
-            }
-            Direction::Incoming => Box::new(std::iter::empty()),
-        };
-
-        let backward: Box<dyn Iterator<Item = (NodeId, EdgeId)>> = match direction {
-            Direction::Incoming | Direction::Both => {
-                if let Some(ref adj) = self.backward_adj {
-                    Box::new(adj.edges_from(node).into_iter())
-                } else {
-                    Box::new(std::iter::empty())
-                }
-            }
-            Direction::Outgoing => Box::new(std::iter::empty()),
-        };
-
-        forward.chain(backward)
+        node_id: NodeId,
+        label: &str,
+        epoch: EpochId,
+        tx_id: TxId,
+    ) -> Result<bool> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(self.add_label(node_id, label));
+        }
+        if !self.node_visible_to(node_id, epoch, tx_id) {
+            return Ok(false);
+        }
+        self.lock_entity(Entity::Node(node_id), tx_id)?;
+        let added = self.attach_label(node_id, label);
+        if added {
+            let label = Arc::from(label);
+            self.log_write(tx_id, TxWrite::LabelAdded { id: node_id, label });
+        }
+        Ok(added)
     }
 
-    /// Gets the type of an edge by ID.
-    #[must_use]
-    pub fn edge_type(&self, id: EdgeId) -> Option<Arc<str>> {
-        let edges = self.edges.read();
-        let chain = edges.get(&id)?;
-        let epoch = self.current_epoch();
-        let record = chain.visible_at(epoch)?;
-        let id_to_type = self.id_to_edge_type.read();
-        id_to_type.get(record.type_id as usize).cloned()
+    /// Removes a label from a node visible to a transaction.
+    ///
+    /// Like [`add_label_versioned`](Self::add_label_versioned), the change
+    /// isn't versioned.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// node.
+    pub fn remove_label_versioned(
+        &self,
+        node_id: NodeId,
+        label: &str,
+        epoch: EpochId,
+        tx_id: TxId,
+    ) -> Result<bool> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(self.remove_label(node_id, label));
+        }
+        if !self.node_visible_to(node_id, epoch, tx_id) {
+            return Ok(false);
+        }
+        self.lock_entity(Entity::Node(node_id), tx_id)?;
+        let removed = self.detach_label(node_id, label);
+        if removed {
+            let label = Arc::from(label);
+            self.log_write(tx_id, TxWrite::LabelRemoved { id: node_id, label });
+        }
+        Ok(removed)
     }
 
-    /// Returns all nodes with a specific label.
+    /// Deletes a node visible to a transaction.
     ///
-    /// Uses the label index for O(1) lookup per label. Returns a snapshot -
-    /// concurrent modifications won't affect the returned vector. Results are
-    /// sorted by NodeId for deterministic iteration order.
-    pub fn nodes_by_label(&self, label: &str) -> Vec<NodeId> {
-        let label_to_id = self.label_to_id.read();
-        if let Some(&label_id) = label_to_id.get(label) {
-            let index = self.label_index.read();
-            if let Some(set) = index.get(label_id as usize) {
-                let mut ids: Vec<NodeId> = set.keys().copied().collect();
-                ids.sort_unstable();
-                return ids;
-            }
+    /// The node disappears for the transaction straight away and for
+    /// everyone else once it commits; its labels and properties are only
+    /// cleared then.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// node.
+    pub fn delete_node_versioned(&self, id: NodeId, epoch: EpochId, tx_id: TxId) -> Result<bool> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(self.delete_node_at_epoch(id, epoch));
         }
-        Vec::new()
+        if !self.node_visible_to(id, epoch, tx_id) {
+            return Ok(false);
+        }
+        self.lock_entity(Entity::Node(id), tx_id)?;
+        if let Some(chain) = self.nodes.write().get_mut(&id) {
+            chain.mark_deleted_by(tx_id);
+        }
+        self.record_change();
+        self.log_write(tx_id, TxWrite::NodeDeleted { id });
+        Ok(true)
     }
 
-    // === Admin API: Iteration ===
+    /// Deletes an edge visible to a transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing the
+    /// edge.
+    pub fn delete_edge_versioned(&self, id: EdgeId, epoch: EpochId, tx_id: TxId) -> Result<bool> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(self.delete_edge_at_epoch(id, epoch));
+        }
+        let visible = self
+            .edges
+            .read()
+            .get(&id)
+            .and_then(|chain| chain.visible_to(epoch, tx_id))
+            .is_some_and(|record| !record.is_deleted());
+        if !visible {
+            return Ok(false);
+        }
+        self.lock_entity(Entity::Edge(id), tx_id)?;
+        if let Some(chain) = self.edges.write().get_mut(&id) {
+            chain.mark_deleted_by(tx_id);
+        }
+        self.record_change();
+        self.log_write(tx_id, TxWrite::EdgeDeleted { id });
+        Ok(true)
+    }
+
+    /// Deletes the edges of a node that a transaction sees.
+    ///
+    /// # Errors
+    ///
+    /// Returns a write conflict if another open transaction is writing one
+    /// of the edges.
+    pub fn delete_node_edges_versioned(
+        &self,
+        node_id: NodeId,
+        epoch: EpochId,
+        tx_id: TxId,
+    ) -> Result<()> {
+        if tx_id == TxId::SYSTEM {
+            self.delete_node_edges(node_id);
+            return Ok(());
+        }
+        for edge_id in self.node_edge_ids(node_id) {
+            self.delete_edge_versioned(edge_id, epoch, tx_id)?;
+        }
+        Ok(())
+    }
+
+    /// Takes the write lock on an edge type for a transaction, failing if
+    /// another open transaction holds it.
+    ///
+    /// Creating an edge only if no such edge exists yet takes this first, so
+    /// two transactions can't both see none and both create one. Like every
+    /// write lock it's held until the transaction ends.
+    pub fn lock_edge_type(&self, edge_type: &str, tx_id: TxId) -> Result<()> {
+        if tx_id == TxId::SYSTEM {
+            return Ok(());
+        }
+        let type_id = self.get_or_create_edge_type_id(edge_type);
+        self.lock_entity(Entity::EdgeType(type_id), tx_id)
+    }
+
+    /// Returns the writes a transaction has made so far, in order.
+    #[must_use]
+    pub fn tx_writes(&self, tx_id: TxId) -> Vec<TxWrite> {
+        self.tx_writes
+            .lock()
+            .get(&tx_id)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns how many writes a transaction has made so far.
+    ///
+    /// Pass it to [`undo_writes_since`](Self::undo_writes_since) to roll
+    /// back to this point later.
+    #[must_use]
+    pub fn tx_write_count(&self, tx_id: TxId) -> usize {
+        self.tx_writes.lock().get(&tx_id).map_or(0, Vec::len)
+    }
+
+    /// Undoes the writes a transaction made after the first `mark`, newest
+    /// first, keeping the ones before.
+    ///
+    /// The locks the transaction took stay held until it ends.
+    pub fn undo_writes_since(&self, tx_id: TxId, mark: usize) {
+        let undone = self
+            .tx_writes
+            .lock()
+            .get_mut(&tx_id)
+            .map(|writes| writes.split_off(mark.min(writes.len())))
+            .unwrap_or_default();
+        for write in undone.into_iter().rev() {
+            self.undo_write(tx_id, write);
+        }
+        self.record_change();
+    }
+
+    /// Undoes everything a transaction wrote.
+    ///
+    /// This is called during transaction rollback, and releases the
+    /// transaction's locks.
+    pub fn discard_uncommitted_versions(&self, tx_id: TxId) {
+        self.undo_writes_since(tx_id, 0);
+        self.tx_writes.lock().remove(&tx_id);
+        self.release_locks(tx_id);
+    }
+
+    /// Makes what a transaction wrote visible from its commit epoch on.
+    ///
+    /// Called once the transaction commits, so snapshots taken before the
+    /// commit keep not seeing its writes. Deleted nodes and edges are
+    /// cleared out, the transaction's locks are released, and the store's
+    /// epoch moves up to the commit epoch, so reads outside transactions do
+    /// see the writes.
+    pub fn finalize_committed_versions(&self, tx_id: TxId, commit_epoch: EpochId) {
+        let writes = self.tx_writes.lock().remove(&tx_id).unwrap_or_default();
+        for write in &writes {
+            match *write {
+                TxWrite::NodeCreated { id, .. } => {
+                    if let Some(chain) = self.nodes.write().get_mut(&id) {
+                        chain.commit_versions_by(tx_id, commit_epoch);
+                    }
+                }
+                TxWrite::EdgeCreated { id, .. } => {
+                    if let Some(chain) = self.edges.write().get_mut(&id) {
+                        chain.commit_versions_by(tx_id, commit_epoch);
+                    }
+                }
+                TxWrite::NodePropertySet { id, .. } => {
+                    Self::commit_property_versions(
+                        &self.node_property_versions,
+                        id,
+                        tx_id,
+                        commit_epoch,
+                    );
+                }
+                TxWrite::EdgePropertySet { id, .. } => {
+                    Self::commit_property_versions(
+                        &self.edge_property_versions,
+                        id,
+                        tx_id,
+                        commit_epoch,
+                    );
+                }
+                TxWrite::NodeDeleted { id } => self.purge_deleted_node(id, tx_id, commit_epoch),
+                TxWrite::EdgeDeleted { id } => self.purge_deleted_edge(id, tx_id, commit_epoch),
+                TxWrite::LabelAdded { .. } | TxWrite::LabelRemoved { .. } => {}
+            }
+        }
+        self.release_locks(tx_id);
+        self.current_epoch
+            .fetch_max(commit_epoch.as_u64(), Ordering::AcqRel);
+        // The writes were counted when made, but readers only see them now
+        self.record_change();
+    }
+
+    /// Drops the overwritten property values no reader at `oldest_epoch` or
+    /// later can see any more.
+    pub fn gc_property_versions(&self, oldest_epoch: EpochId) {
+        Self::prune_property_versions(&self.node_property_versions, oldest_epoch);
+        Self::prune_property_versions(&self.edge_property_versions, oldest_epoch);
+    }
+
+    fn log_write(&self, tx_id: TxId, write: TxWrite) {
+        self.tx_writes.lock().entry(tx_id).or_default().push(write);
+    }
+
+    /// Takes the write lock on a node or edge, failing if another open
+    /// transaction holds it.
+    fn lock_entity(&self, entity: Entity, tx_id: TxId) -> Result<()> {
+        let mut locks = self.write_locks.lock();
+        let owner = *locks.entry(entity).or_insert(tx_id);
+        if owner == tx_id {
+            Ok(())
+        } else {
+            Err(Error::Transaction(TransactionError::WriteConflict(
+                format!("{entity:?} is being written by transaction {owner:?}"),
+            )))
+        }
+    }
+
+    fn release_locks(&self, tx_id: TxId) {
+        self.write_locks.lock().retain(|_, owner| *owner != tx_id);
+    }
+
+    fn node_visible_to(&self, id: NodeId, epoch: EpochId, tx_id: TxId) -> bool {
+        self.nodes
+            .read()
+            .get(&id)
+            .and_then(|chain| chain.visible_to(epoch, tx_id))
+            .is_some_and(|record| !record.is_deleted())
+    }
+
+    /// Logs a node property write, keeping the old value for other readers
+    /// unless nobody else can see the node yet.
+    fn track_node_property(
+        &self,
+        id: NodeId,
+        key: PropertyKey,
+        old: Option<Value>,
+        value: Option<Value>,
+        tx_id: TxId,
+    ) {
+        let created_here = self
+            .nodes
+            .read()
+            .get(&id)
+            .is_some_and(|chain| chain.modified_by(tx_id));
+        if !created_here {
+            self.node_property_versions
+                .write()
+                .entry(id)
+                .or_default()
+                .push(PropertyVersion {
+                    key: key.clone(),
+                    writer: tx_id,
+                    committed: None,
+                    previous: old.clone(),
+                });
+        }
+        self.log_write(
+            tx_id,
+            TxWrite::NodePropertySet {
+                id,
+                key,
+                old,
+                value,
+            },
+        );
+    }
+
+    /// Logs an edge property write, like
+    /// [`track_node_property`](Self::track_node_property).
+    fn track_edge_property(
+        &self,
+        id: EdgeId,
+        key: PropertyKey,
+        old: Option<Value>,
+        value: Option<Value>,
+        tx_id: TxId,
+    ) {
+        let created_here = self
+            .edges
+            .read()
+            .get(&id)
+            .is_some_and(|chain| chain.modified_by(tx_id));
+        if !created_here {
+            self.edge_property_versions
+                .write()
+                .entry(id)
+                .or_default()
+                .push(PropertyVersion {
+                    key: key.clone(),
+                    writer: tx_id,
+                    committed: None,
+                    previous: old.clone(),
+                });
+        }
+        self.log_write(
+            tx_id,
+            TxWrite::EdgePropertySet {
+                id,
+                key,
+                old,
+                value,
+            },
+        );
+    }
+
+    /// Puts back what one write changed.
+    fn undo_write(&self, tx_id: TxId, write: TxWrite) {
+        match write {
+            TxWrite::NodeCreated { id, .. } => {
+                let mut nodes = self.nodes.write();
+                if let Some(chain) = nodes.get_mut(&id) {
+                    chain.remove_versions_by(tx_id);
+                    if chain.is_empty() {
+                        nodes.remove(&id);
+                    }
+                }
+                drop(nodes);
+                self.remove_node_contents(id);
+                self.node_property_versions.write().remove(&id);
+            }
+            TxWrite::EdgeCreated {
+                id,
+                src,
+                dst,
+                edge_type,
+            } => {
+                let mut edges = self.edges.write();
+                if let Some(chain) = edges.get_mut(&id) {
+                    chain.remove_versions_by(tx_id);
+                    if chain.is_empty() {
+                        edges.remove(&id);
+                    }
+                }
+                drop(edges);
+                let type_id = self.edge_type_to_id.read().get(&edge_type).copied();
+                if let Some(type_id) = type_id {
+                    self.forward_adj.mark_typed_deleted(src, id, type_id);
+                    if let Some(ref backward) = self.backward_adj {
+                        backward.mark_typed_deleted(dst, id, type_id);
+                    }
+                    self.record_edge_type_change(type_id);
+                }
+                self.edge_properties.remove_all(id);
+                self.edge_property_versions.write().remove(&id);
+            }
+            TxWrite::NodePropertySet { id, key, old, .. } => {
+                match old {
+                    Some(value) => self.node_properties.set(id, key.clone(), value),
+                    None => {
+                        self.node_properties.remove(id, &key);
+                    }
+                }
+                self.update_property_indexes(id, Some(&key));
+                let count = self.node_properties.get_all(id).len() as u16;
+                if let Some(record) = self
+                    .nodes
+                    .write()
+                    .get_mut(&id)
+                    .and_then(VersionChain::latest_mut)
+                {
+                    record.props_count = count;
+                }
+                Self::pop_property_version(&self.node_property_versions, id, &key, tx_id);
+            }
+            TxWrite::EdgePropertySet { id, key, old, .. } => {
+                match old {
+                    Some(value) => self.edge_properties.set(id, key.clone(), value),
+                    None => {
+                        self.edge_properties.remove(id, &key);
+                    }
+                }
+                Self::pop_property_version(&self.edge_property_versions, id, &key, tx_id);
+            }
+            TxWrite::LabelAdded { id, label } => {
+                self.detach_label(id, &label);
+            }
+            TxWrite::LabelRemoved { id, label } => {
+                self.attach_label(id, &label);
+            }
+            TxWrite::NodeDeleted { id } => {
+                if let Some(chain) = self.nodes.write().get_mut(&id) {
+                    chain.restore_deleted_by(tx_id);
+                }
+            }
+            TxWrite::EdgeDeleted { id } => {
+                if let Some(chain) = self.edges.write().get_mut(&id) {
+                    chain.restore_deleted_by(tx_id);
+                }
+            }
+        }
+    }
+
+    /// Clears out a node whose delete just committed, keeping its
+    /// properties as versions for the snapshots that still see it.
+    fn purge_deleted_node(&self, id: NodeId, tx_id: TxId, commit_epoch: EpochId) {
+        if let Some(chain) = self.nodes.write().get_mut(&id) {
+            chain.commit_versions_by(tx_id, commit_epoch);
+        }
+        Self::keep_removed_properties(
+            &self.node_property_versions,
+            id,
+            self.node_properties.get_all(id),
+            tx_id,
+            commit_epoch,
+        );
+        Self::commit_property_versions(&self.node_property_versions, id, tx_id, commit_epoch);
+        self.remove_node_contents(id);
+    }
+
+    /// Clears out an edge whose delete just committed, like
+    /// [`purge_deleted_node`](Self::purge_deleted_node).
+    fn purge_deleted_edge(&self, id: EdgeId, tx_id: TxId, commit_epoch: EpochId) {
+        let mut edges = self.edges.write();
+        let Some(chain) = edges.get_mut(&id) else {
+            return;
+        };
+        chain.commit_versions_by(tx_id, commit_epoch);
+        let Some((src, dst, type_id)) = chain.latest().map(|r| (r.src, r.dst, r.type_id)) else {
+            return;
+        };
+        drop(edges);
+
+        self.forward_adj.mark_typed_deleted(src, id, type_id);
+        if let Some(ref backward) = self.backward_adj {
+            backward.mark_typed_deleted(dst, id, type_id);
+        }
+        Self::keep_removed_properties(
+            &self.edge_property_versions,
+            id,
+            self.edge_properties.get_all(id),
+            tx_id,
+            commit_epoch,
+        );
+        Self::commit_property_versions(&self.edge_property_versions, id, tx_id, commit_epoch);
+        self.edge_properties.remove_all(id);
+        self.record_edge_type_change(type_id);
+    }
+
+    /// Records the properties of a deleted node or edge as overwritten by
+    /// the delete.
+    fn keep_removed_properties<Id: Eq + Hash>(
+        versions: &RwLock<FxHashMap<Id, Vec<PropertyVersion>>>,
+        id: Id,
+        properties: FxHashMap<PropertyKey, Value>,
+        tx_id: TxId,
+        commit_epoch: EpochId,
+    ) {
+        if properties.is_empty() {
+            return;
+        }
+        versions
+            .write()
+            .entry(id)
+            .or_default()
+            .extend(properties.into_iter().map(|(key, value)| PropertyVersion {
+                key,
+                writer: tx_id,
+                committed: Some(commit_epoch),
+                previous: Some(value),
+            }));
+    }
+
+    fn commit_property_versions<Id: Eq + Hash>(
+        versions: &RwLock<FxHashMap<Id, Vec<PropertyVersion>>>,
+        id: Id,
+        tx_id: TxId,
+        commit_epoch: EpochId,
+    ) {
+        if let Some(list) = versions.write().get_mut(&id) {
+            for version in list
+                .iter_mut()
+                .filter(|v| v.writer == tx_id && v.committed.is_none())
+            {
+                version.committed = Some(commit_epoch);
+            }
+        }
+    }
+
+    /// Drops the newest uncommitted version a transaction left for a
+    /// property, as its write is undone.
+    fn pop_property_version<Id: Eq + Hash>(
+        versions: &RwLock<FxHashMap<Id, Vec<PropertyVersion>>>,
+        id: Id,
+        key: &PropertyKey,
+        tx_id: TxId,
+    ) {
+        let mut versions = versions.write();
+        let Some(list) = versions.get_mut(&id) else {
+            return;
+        };
+        if let Some(pos) = list
+            .iter()
+            .rposition(|v| v.writer == tx_id && v.committed.is_none() && &v.key == key)
+        {
+            list.remove(pos);
+        }
+        if list.is_empty() {
+            versions.remove(&id);
+        }
+    }
+
+    fn prune_property_versions<Id: Eq + Hash>(
+        versions: &RwLock<FxHashMap<Id, Vec<PropertyVersion>>>,
+        oldest_epoch: EpochId,
+    ) {
+        let mut versions = versions.write();
+        if versions.is_empty() {
+            return;
+        }
+        versions.retain(|_, list| {
+            list.retain(|v| !v.committed.is_some_and(|c| c.is_visible_at(oldest_epoch)));
+            !list.is_empty()
+        });
+    }
+
+    /// Rolls properties back to what a reader at `epoch`, in transaction
+    /// `tx` if any, should see.
+    fn apply_property_versions<Id: Eq + Hash>(
+        versions: &RwLock<FxHashMap<Id, Vec<PropertyVersion>>>,
+        id: Id,
+        properties: &mut BTreeMap<PropertyKey, Value>,
+        epoch: EpochId,
+        tx: Option<TxId>,
+    ) {
+        let versions = versions.read();
+        let Some(list) = versions.get(&id) else {
+            return;
+        };
+        // Walk back from the newest write, stopping per key at the first
+        // write the reader sees
+        let mut settled = FxHashSet::default();
+        for version in list.iter().rev() {
+            if settled.contains(&version.key) {
+                continue;
+            }
+            if version.is_seen_by(epoch, tx) {
+                settled.insert(&version.key);
+                continue;
+            }
+            match &version.previous {
+                Some(value) => properties.insert(version.key.clone(), value.clone()),
+                None => properties.remove(&version.key),
+            };
+        }
+    }
+
+    /// Returns the number of distinct labels in the store.
+    #[must_use]
+    pub fn label_count(&self) -> usize {
+        self.id_to_label.read().len()
+    }
+
+    /// Returns the number of distinct property keys in the store.
+    ///
+    /// This counts unique property keys across both nodes and edges.
+    #[must_use]
+    pub fn property_key_count(&self) -> usize {
+        let node_keys = self.node_properties.column_count();
+        let edge_keys = self.edge_properties.column_count();
+        // Note: This may count some keys twice if the same key is used
+        // for both nodes and edges. A more precise count would require
+        // tracking unique keys across both storages.
+        node_keys + edge_keys
+    }
+
+    /// Returns the number of distinct edge types in the store.
+    #[must_use]
+    pub fn edge_type_count(&self) -> usize {
+        self.id_to_edge_type.read().len()
+    }
+
+    // === Traversal ===
+
+    /// Iterates over neighbors of a node in the specified direction.
+    ///
+    /// This is the fast path for graph traversal - goes straight to the
+    /// adjacency index without loading full node data. A self-loop makes the
+    /// node its own neighbor in every direction, and is reported once even
+    /// for [`Direction::Both`].
+    pub fn neighbors(
+        &self,
+        node: NodeId,
+        direction: Direction,
+    ) -> impl Iterator<Item = NodeId> + '_ {
+        let forward: Box<dyn Iterator<Item = NodeId>> = match direction {
+            Direction::Outgoing | Direction::Both => {
+                Box::new(self.forward_adj.neighbors(node).into_iter())
+            }
+            Direction::Incoming => Box::new(std::iter::empty()),
+        };
+
+        let backward: Box<dyn Iterator<Item = NodeId>> = match direction {
+            Direction::Incoming => {
+                if let Some(ref adj) = self.backward_adj {
+                    Box::new(adj.neighbors(node).into_iter())
+                } else {
+                    Box::new(std::iter::empty())
+                }
+            }
+            Direction::Both => {
+                // Self-loops were already seen on the forward side
+                if let Some(ref adj) = self.backward_adj {
+                    Box::new(
+                        adj.neighbors(node)
+                            .into_iter()
+                            .filter(move |&source| source != node),
+                    )
+                } else {
+                    Box::new(std::iter::empty())
+                }
+            }
+            Direction::Outgoing => Box::new(std::iter::empty()),
+        };
+
+        forward.chain(backward)
+    }
+
+    /// Returns edges from a node with their targets.
+    ///
+    /// Returns an iterator of (target_node, edge_id) pairs. Self-loops are
+    /// reported once in every direction, including [`Direction::Both`].
+    pub fn edges_from(
+        &self,
+        node: NodeId,
+        direction: Direction,
+    ) -> impl Iterator<Item = (NodeId, EdgeId)> + '_ {
+        let forward: Box<dyn Iterator<Item = (NodeId, EdgeId)>> = match direction {
+            Direction::Outgoing | Direction::Both => {
+                Box::new(self.forward_adj.edges_from(node).into_iter())
+            }
+            Direction::Incoming => Box::new(std::iter::empty()),
+        };
+
+        let backward: Box<dyn Iterator<Item = (NodeId, EdgeId)>> = match direction {
+            Direction::Incoming => {
+                if let Some(ref adj) = self.backward_adj {
+                    Box::new(adj.edges_from(node).into_iter())
+                } else {
+                    Box::new(std::iter::empty())
+                }
+            }
+            Direction::Both => {
+                // Self-loops were already seen on the forward side
+                if let Some(ref adj) = self.backward_adj {
+                    Box::new(
+                        adj.edges_from(node)
+                            .into_iter()
+                            .filter(move |&(source, _)| source != node),
+                    )
+                } else {
+                    Box::new(std::iter::empty())
+                }
+            }
+            Direction::Outgoing => Box::new(std::iter::empty()),
+        };
+
+        forward.chain(backward)
+    }
+
+    /// Expands a whole frontier of source nodes at once.
+    ///
+    /// Returns the same rows as calling [`edges_from`](Self::edges_from) on
+    /// each source and keeping edges of `edge_type`, but walks each adjacency
+    /// index in a single pass. For [`Direction::Both`] the outgoing rows come
+    /// first, then the incoming ones. With `sort_sources`, sources are
+    /// visited in ID order for better locality.
+    #[must_use]
+    pub fn expand_batch(
+        &self,
+        sources: &[NodeId],
+        direction: Direction,
+        edge_type: Option<&str>,
+        sort_sources: bool,
+    ) -> BatchExpansion {
+        let mut batch = match direction {
+            Direction::Outgoing | Direction::Both => {
+                self.forward_adj.expand_batch(sources, sort_sources)
+            }
+            Direction::Incoming => BatchExpansion::default(),
+        };
+        if matches!(direction, Direction::Incoming | Direction::Both)
+            && let Some(ref adj) = self.backward_adj
+        {
+            let mut incoming = adj.expand_batch(sources, sort_sources);
+            if direction == Direction::Both {
+                // Self-loops were already seen on the forward side
+                incoming.retain(|source, _, target| source != target);
+            }
+            batch.append(&mut incoming);
+        }
+
+        if let Some(edge_type) = edge_type {
+            let type_ids = self.matching_edge_type_ids(edge_type);
+            if type_ids.is_empty() {
+                return BatchExpansion::default();
+            }
+            let epoch = self.current_epoch();
+            let edges = self.edges.read();
+            batch.retain(|_, edge_id, _| {
+                edges
+                    .get(&edge_id)
+                    .and_then(|chain| chain.visible_at(epoch))
+                    .is_some_and(|record| type_ids.contains(&record.type_id))
+            });
+        }
+        batch
+    }
+
+    /// Returns how many edge endpoints attach to a node in a direction,
+    /// counting only edges of `edge_type` if given.
+    ///
+    /// A self-loop counts once for [`Direction::Outgoing`] and once for
+    /// [`Direction::Incoming`], so it adds two to [`Direction::Both`] - the
+    /// usual convention, where a loop touches the node at both ends. That
+    /// makes `Both` one more than [`edges_from`](Self::edges_from) yields for
+    /// each self-loop.
+    ///
+    /// Counts come from the adjacency lists' metadata, so no neighbours are
+    /// read. Without backward edges, incoming degree falls back to scanning
+    /// every edge, and the first such scan logs a warning.
+    #[must_use]
+    pub fn degree(&self, node: NodeId, direction: Direction, edge_type: Option<&str>) -> usize {
+        let type_ids = edge_type.map(|edge_type| self.matching_edge_type_ids(edge_type));
+        let type_ids = type_ids.as_deref();
+        if type_ids.is_some_and(<[u32]>::is_empty) {
+            return 0;
+        }
+
+        let outgoing = || self.forward_adj.degree(node, type_ids);
+        let incoming = || match self.backward_adj {
+            Some(ref backward) => backward.degree(node, type_ids),
+            None => self.scan_in_degree(node, type_ids),
+        };
+        match direction {
+            Direction::Outgoing => outgoing(),
+            Direction::Incoming => incoming(),
+            Direction::Both => outgoing() + incoming(),
+        }
+    }
+
+    /// Counts live edges into `node` by walking every edge, for stores
+    /// without backward adjacency.
+    fn scan_in_degree(&self, node: NodeId, type_ids: Option<&[u32]>) -> usize {
+        if !self.warned_in_degree_scan.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                "incoming degree scans every edge because backward edges are disabled; \
+                 enable LpgStoreConfig::backward_edges to count them from adjacency"
+            );
+        }
+        let epoch = self.current_epoch();
+        self.edges
+            .read()
+            .values()
+            .filter_map(|chain| chain.visible_at(epoch))
+            .filter(|record| {
+                !record.is_deleted()
+                    && record.dst == node
+                    && type_ids.is_none_or(|ids| ids.contains(&record.type_id))
+            })
+            .count()
+    }
+
+    /// Walks `length` random steps from `start` and returns the visited
+    /// nodes, starting with `start` itself.
+    ///
+    /// Each step follows one of the current node's edges in `direction`,
+    /// picked with probability proportional to its `weight_property`, or
+    /// uniformly without one. Edges missing the property or holding a
+    /// non-numeric value weigh 1; negative weights count as 0. The walk ends
+    /// early at a node with no edge to follow, and is empty if `start`
+    /// doesn't exist.
+    ///
+    /// The same `seed` over the same graph always produces the same walk,
+    /// which keeps node2vec-style sampling reproducible.
+    #[must_use]
+    pub fn random_walk(
+        &self,
+        start: NodeId,
+        length: usize,
+        direction: Direction,
+        weight_property: Option<&str>,
+        seed: u64,
+    ) -> Vec<NodeId> {
+        let epoch = self.current_epoch();
+        let start_exists = self
+            .nodes
+            .read()
+            .get(&start)
+            .and_then(|chain| chain.visible_at(epoch))
+            .is_some_and(|record| !record.is_deleted());
+        if !start_exists {
+            return Vec::new();
+        }
+
+        let weight_key = weight_property.map(PropertyKey::new);
+        let mut rng = SplitMix64(seed);
+        let mut walk = Vec::with_capacity(length + 1);
+        walk.push(start);
+        let mut current = start;
+        for _ in 0..length {
+            // Sort by edge id so the walk doesn't depend on adjacency order
+            let mut candidates: Vec<(NodeId, EdgeId)> =
+                self.edges_from(current, direction).collect();
+            candidates.sort_unstable_by_key(|&(_, edge)| edge);
+            let weights: Vec<f64> = candidates
+                .iter()
+                .map(|&(_, edge)| {
+                    let weight = weight_key.as_ref().map_or(1.0, |key| {
+                        match self.edge_properties.get(edge, key) {
+                            Some(Value::Int64(i)) => i as f64,
+                            Some(Value::Float64(f)) => f,
+                            _ => 1.0,
+                        }
+                    });
+                    if weight > 0.0 { weight } else { 0.0 }
+                })
+                .collect();
+            let total: f64 = weights.iter().sum();
+            if total <= 0.0 {
+                break;
+            }
+
+            let mut target = rng.next_f64() * total;
+            let mut next = candidates[candidates.len() - 1].0;
+            for (&(node, _), weight) in candidates.iter().zip(&weights) {
+                if target < *weight {
+                    next = node;
+                    break;
+                }
+                target -= weight;
+            }
+            walk.push(next);
+            current = next;
+        }
+        walk
+    }
+
+    /// Gets the type of an edge visible to a specific transaction.
+    #[must_use]
+    pub fn edge_type_versioned(&self, id: EdgeId, epoch: EpochId, tx_id: TxId) -> Option<Arc<str>> {
+        let edges = self.edges.read();
+        let record = edges.get(&id)?.visible_to(epoch, tx_id)?;
+        if record.is_deleted() {
+            return None;
+        }
+        let id_to_type = self.id_to_edge_type.read();
+        id_to_type.get(record.type_id as usize).cloned()
+    }
+
+    /// Gets the type of an edge by ID.
+    #[must_use]
+    pub fn edge_type(&self, id: EdgeId) -> Option<Arc<str>> {
+        let edges = self.edges.read();
+        let chain = edges.get(&id)?;
+        let epoch = self.current_epoch();
+        let record = chain.visible_at(epoch)?;
+        let id_to_type = self.id_to_edge_type.read();
+        id_to_type.get(record.type_id as usize).cloned()
+    }
+
+    /// Returns all nodes with a specific label.
+    ///
+    /// Uses the label index for O(1) lookup per label. Returns a snapshot -
+    /// concurrent modifications won't affect the returned vector. Results are
+    /// sorted by NodeId for deterministic iteration order.
+    ///
+    /// With [`case_insensitive_labels`](LpgStoreConfig::case_insensitive_labels)
+    /// this covers every label that differs from `label` only in case.
+    pub fn nodes_by_label(&self, label: &str) -> Vec<NodeId> {
+        let label_ids = self.matching_label_ids(label);
+        let index = self.label_index.read();
+        let mut ids: Vec<NodeId> = label_ids
+            .iter()
+            .filter_map(|&label_id| index.get(label_id as usize))
+            .flat_map(|set| set.keys().copied())
+            .collect();
+        ids.sort_unstable();
+        if label_ids.len() > 1 {
+            // A node can carry more than one casing of the label
+            ids.dedup();
+        }
+        ids
+    }
+
+    /// Returns all nodes that have every one of the given labels.
+    ///
+    /// Intersects the label index sets, starting from the smallest so the
+    /// work is bounded by the rarest label. An empty slice means no label
+    /// constraint and returns every node. Results are sorted by NodeId.
+    pub fn nodes_with_all_labels(&self, labels: &[&str]) -> Vec<NodeId> {
+        match labels {
+            [] => return self.node_ids(),
+            [label] => return self.nodes_by_label(label),
+            _ => {}
+        }
+
+        let label_ids: Vec<Vec<u32>> = labels
+            .iter()
+            .map(|label| self.matching_label_ids(label))
+            .collect();
+        let index = self.label_index.read();
+        // Each label's node sets; more than one when casings are folded
+        let mut groups = Vec::with_capacity(labels.len());
+        for ids in &label_ids {
+            let sets: Vec<_> = ids
+                .iter()
+                .filter_map(|&label_id| index.get(label_id as usize))
+                .collect();
+            if sets.is_empty() {
+                return Vec::new();
+            }
+            groups.push(sets);
+        }
+        groups.sort_by_key(|sets| sets.iter().map(|set| set.len()).sum::<usize>());
+
+        let (smallest, rest) = groups.split_first().expect("at least two labels");
+        let mut ids: Vec<NodeId> = smallest
+            .iter()
+            .flat_map(|set| set.keys())
+            .filter(|id| {
+                rest.iter()
+                    .all(|sets| sets.iter().any(|set| set.contains_key(*id)))
+            })
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    // === Admin API: Iteration ===
 
     /// Returns an iterator over all nodes in the database.
     ///
-    /// This creates a snapshot of all visible nodes at the current epoch.
+    /// This creates a snapshot of all visible nodes at the current epoch:
+    /// nodes created or deleted while iterating don't change what it yields.
+    /// Nodes are read one at a time, so no lock is held between items.
     /// Useful for dump/export operations.
     pub fn all_nodes(&self) -> impl Iterator<Item = Node> + '_ {
-        let epoch = self.current_epoch();
+        self.all_nodes_at(self.current_epoch())
+    }
+
+    /// Returns an iterator over the nodes visible at `epoch`.
+    ///
+    /// Lets several passes over the graph see the same snapshot.
+    pub fn all_nodes_at(&self, epoch: EpochId) -> impl Iterator<Item = Node> + '_ {
         let node_ids: Vec<NodeId> = self
             .nodes
             .read()
@@ -915,15 +2220,22 @@ impl LpgStore {
             })
             .collect();
 
-        node_ids.into_iter().filter_map(move |id| self.get_node(id))
+        node_ids
+            .into_iter()
+            .filter_map(move |id| self.get_node_at_epoch(id, epoch))
     }
 
     /// Returns an iterator over all edges in the database.
     ///
-    /// This creates a snapshot of all visible edges at the current epoch.
+    /// Like [`all_nodes()`](Self::all_nodes), this is a snapshot of the
+    /// edges visible at the current epoch, read one at a time.
     /// Useful for dump/export operations.
     pub fn all_edges(&self) -> impl Iterator<Item = Edge> + '_ {
-        let epoch = self.current_epoch();
+        self.all_edges_at(self.current_epoch())
+    }
+
+    /// Returns an iterator over the edges visible at `epoch`.
+    pub fn all_edges_at(&self, epoch: EpochId) -> impl Iterator<Item = Edge> + '_ {
         let edge_ids: Vec<EdgeId> = self
             .edges
             .read()
@@ -935,7 +2247,9 @@ impl LpgStore {
             })
             .collect();
 
-        edge_ids.into_iter().filter_map(move |id| self.get_edge(id))
+        edge_ids
+            .into_iter()
+            .filter_map(move |id| self.get_edge_at_epoch(id, epoch))
     }
 
     /// Returns all label names in the database.
@@ -969,24 +2283,31 @@ impl LpgStore {
     }
 
     /// Returns an iterator over nodes with a specific label.
+    ///
+    /// A snapshot at the current epoch, like [`all_nodes()`](Self::all_nodes).
     pub fn nodes_with_label<'a>(&'a self, label: &str) -> impl Iterator<Item = Node> + 'a {
+        let epoch = self.current_epoch();
         let node_ids = self.nodes_by_label(label);
-        node_ids.into_iter().filter_map(move |id| self.get_node(id))
+        node_ids
+            .into_iter()
+            .filter_map(move |id| self.get_node_at_epoch(id, epoch))
     }
 
     /// Returns an iterator over edges with a specific type.
+    ///
+    /// A snapshot at the current epoch, like [`all_edges()`](Self::all_edges).
     pub fn edges_with_type<'a>(&'a self, edge_type: &str) -> impl Iterator<Item = Edge> + 'a {
         let epoch = self.current_epoch();
-        let type_to_id = self.edge_type_to_id.read();
+        let type_ids = self.matching_edge_type_ids(edge_type);
 
-        if let Some(&type_id) = type_to_id.get(edge_type) {
+        if !type_ids.is_empty() {
             let edge_ids: Vec<EdgeId> = self
                 .edges
                 .read()
                 .iter()
                 .filter_map(|(id, chain)| {
                     chain.visible_at(epoch).and_then(|r| {
-                        if !r.is_deleted() && r.type_id == type_id {
+                        if !r.is_deleted() && type_ids.contains(&r.type_id) {
                             Some(*id)
                         } else {
                             None
@@ -996,8 +2317,11 @@ impl LpgStore {
                 .collect();
 
             // Return a boxed iterator for the found edges
-            Box::new(edge_ids.into_iter().filter_map(move |id| self.get_edge(id)))
-                as Box<dyn Iterator<Item = Edge> + 'a>
+            Box::new(
+                edge_ids
+                    .into_iter()
+                    .filter_map(move |id| self.get_edge_at_epoch(id, epoch)),
+            ) as Box<dyn Iterator<Item = Edge> + 'a>
         } else {
             // Return empty iterator
             Box::new(std::iter::empty()) as Box<dyn Iterator<Item = Edge> + 'a>
@@ -1020,6 +2344,24 @@ impl LpgStore {
         self.node_properties.might_match(property, op, value)
     }
 
+    /// Checks if a node property predicate might match any node with an id
+    /// in `first..=last`.
+    ///
+    /// Uses per-block zone maps, so scans can skip runs of nodes that
+    /// certainly don't match.
+    #[must_use]
+    pub fn node_property_might_match_in(
+        &self,
+        property: &PropertyKey,
+        first: NodeId,
+        last: NodeId,
+        op: CompareOp,
+        value: &Value,
+    ) -> bool {
+        self.node_properties
+            .might_match_in(property, first, last, op, value)
+    }
+
     /// Checks if an edge property predicate might match any edges.
     #[must_use]
     pub fn edge_property_might_match(
@@ -1049,6 +2391,231 @@ impl LpgStore {
         self.edge_properties.rebuild_zone_maps();
     }
 
+    // === Property Indexes ===
+
+    /// Creates a full-text index over the `property` of nodes labeled `label`.
+    ///
+    /// Existing nodes are indexed right away, and the index follows every
+    /// later property, label, and delete change. Only string values are
+    /// indexed. If the index already exists it's returned as is.
+    pub fn create_fulltext_index(
+        &self,
+        label: &str,
+        property: &str,
+        tokenizer: Tokenizer,
+    ) -> Arc<FullTextIndex> {
+        let mut indexes = self.fulltext_indexes.write();
+        if let Some(entry) = self.find_property_index(&indexes, label, property) {
+            return Arc::clone(&entry.index);
+        }
+
+        let key = PropertyKey::from(property);
+        let index = Arc::new(FullTextIndex::with_tokenizer(tokenizer));
+        for id in self.nodes_by_label(label) {
+            if let Some(Value::String(text)) = self.node_properties.get(id, &key) {
+                index.insert(id, &text);
+            }
+        }
+        indexes.push(PropertyIndexEntry {
+            label: label.to_string(),
+            property: key,
+            index: Arc::clone(&index),
+        });
+        index
+    }
+
+    /// Returns the full-text index over `label` and `property`, if there is one.
+    #[must_use]
+    pub fn fulltext_index(&self, label: &str, property: &str) -> Option<Arc<FullTextIndex>> {
+        self.find_property_index(&self.fulltext_indexes.read(), label, property)
+            .map(|e| Arc::clone(&e.index))
+    }
+
+    /// Drops the full-text index over `label` and `property`.
+    ///
+    /// Returns true if the index existed.
+    pub fn drop_fulltext_index(&self, label: &str, property: &str) -> bool {
+        self.drop_property_index(&mut self.fulltext_indexes.write(), label, property)
+    }
+
+    /// Creates a spatial index over the `property` of nodes labeled `label`.
+    ///
+    /// Existing nodes are indexed right away, and the index follows every
+    /// later property, label, and delete change. Only point values are
+    /// indexed. If the index already exists it's returned as is.
+    pub fn create_spatial_index(&self, label: &str, property: &str) -> Arc<SpatialIndex> {
+        let mut indexes = self.spatial_indexes.write();
+        if let Some(entry) = self.find_property_index(&indexes, label, property) {
+            return Arc::clone(&entry.index);
+        }
+
+        let key = PropertyKey::from(property);
+        let index = Arc::new(SpatialIndex::new());
+        for id in self.nodes_by_label(label) {
+            if let Some(Value::Point { x, y, srid }) = self.node_properties.get(id, &key) {
+                index.insert(id, x, y, srid);
+            }
+        }
+        indexes.push(PropertyIndexEntry {
+            label: label.to_string(),
+            property: key,
+            index: Arc::clone(&index),
+        });
+        index
+    }
+
+    /// Returns the spatial index over `label` and `property`, if there is one.
+    #[must_use]
+    pub fn spatial_index(&self, label: &str, property: &str) -> Option<Arc<SpatialIndex>> {
+        self.find_property_index(&self.spatial_indexes.read(), label, property)
+            .map(|e| Arc::clone(&e.index))
+    }
+
+    /// Drops the spatial index over `label` and `property`.
+    ///
+    /// Returns true if the index existed.
+    pub fn drop_spatial_index(&self, label: &str, property: &str) -> bool {
+        self.drop_property_index(&mut self.spatial_indexes.write(), label, property)
+    }
+
+    /// Creates a value index over the `property` of nodes labeled `label`,
+    /// for equality and range lookups.
+    ///
+    /// Existing nodes are indexed right away, and the index follows every
+    /// later property, label, and delete change. If the index already exists
+    /// it's returned as is.
+    pub fn create_value_index(&self, label: &str, property: &str) -> Arc<PropertyIndex> {
+        if let Some(index) = self.value_index(label, property) {
+            return index;
+        }
+        let index = PropertyIndex::new();
+        let key = PropertyKey::from(property);
+        for id in self.nodes_by_label(label) {
+            if let Some(value) = self.node_properties.get(id, &key) {
+                index.insert(id, value);
+            }
+        }
+        self.attach_value_index(label, property, index)
+    }
+
+    /// Installs a value index built elsewhere, such as one read back from
+    /// disk, replacing any index over `label` and `property`.
+    ///
+    /// The index must match the nodes currently in the store; from then on
+    /// it follows every change like one from
+    /// [`create_value_index`](Self::create_value_index).
+    pub fn attach_value_index(
+        &self,
+        label: &str,
+        property: &str,
+        index: PropertyIndex,
+    ) -> Arc<PropertyIndex> {
+        let mut indexes = self.value_indexes.write();
+        self.drop_property_index(&mut indexes, label, property);
+        let index = Arc::new(index);
+        indexes.push(PropertyIndexEntry {
+            label: label.to_string(),
+            property: PropertyKey::from(property),
+            index: Arc::clone(&index),
+        });
+        index
+    }
+
+    /// Returns the value index over `label` and `property`, if there is one.
+    #[must_use]
+    pub fn value_index(&self, label: &str, property: &str) -> Option<Arc<PropertyIndex>> {
+        self.find_property_index(&self.value_indexes.read(), label, property)
+            .map(|e| Arc::clone(&e.index))
+    }
+
+    /// Drops the value index over `label` and `property`.
+    ///
+    /// Returns true if the index existed.
+    pub fn drop_value_index(&self, label: &str, property: &str) -> bool {
+        self.drop_property_index(&mut self.value_indexes.write(), label, property)
+    }
+
+    fn find_property_index<'a, T>(
+        &self,
+        indexes: &'a [PropertyIndexEntry<T>],
+        label: &str,
+        property: &str,
+    ) -> Option<&'a PropertyIndexEntry<T>> {
+        indexes
+            .iter()
+            .find(|e| self.same_label(&e.label, label) && e.property.as_str() == property)
+    }
+
+    fn drop_property_index<T>(
+        &self,
+        indexes: &mut Vec<PropertyIndexEntry<T>>,
+        label: &str,
+        property: &str,
+    ) -> bool {
+        let before = indexes.len();
+        indexes.retain(|e| !(self.same_label(&e.label, label) && e.property.as_str() == property));
+        indexes.len() < before
+    }
+
+    /// Re-indexes a node after a change to `property`, or to the node as a
+    /// whole (labels, deletion) when `property` is `None`.
+    fn update_property_indexes(&self, id: NodeId, property: Option<&PropertyKey>) {
+        let fulltext = self.fulltext_indexes.read();
+        let spatial = self.spatial_indexes.read();
+        let values = self.value_indexes.read();
+        if fulltext.is_empty() && spatial.is_empty() && values.is_empty() {
+            return;
+        }
+        let labels = self
+            .node_labels
+            .read()
+            .get(&id)
+            .cloned()
+            .unwrap_or_default();
+        // The indexed value, or None if the node isn't covered by the index
+        let value = |label: &str, key: &PropertyKey| {
+            self.matching_label_ids(label)
+                .iter()
+                .any(|label_id| labels.contains(label_id))
+                .then(|| self.node_properties.get(id, key))
+                .flatten()
+        };
+        let affected = |key: &PropertyKey| property.map_or(true, |p| p == key);
+
+        for entry in fulltext.iter().filter(|e| affected(&e.property)) {
+            match value(&entry.label, &entry.property) {
+                Some(Value::String(text)) => entry.index.insert(id, &text),
+                _ => {
+                    entry.index.remove(id);
+                }
+            }
+        }
+        for entry in spatial.iter().filter(|e| affected(&e.property)) {
+            match value(&entry.label, &entry.property) {
+                Some(Value::Point { x, y, srid }) => entry.index.insert(id, x, y, srid),
+                _ => {
+                    entry.index.remove(id);
+                }
+            }
+        }
+        for entry in values.iter().filter(|e| affected(&e.property)) {
+            match value(&entry.label, &entry.property) {
+                Some(value) => entry.index.insert(id, value),
+                None => {
+                    entry.index.remove(id);
+                }
+            }
+        }
+    }
+
+    fn same_label(&self, a: &str, b: &str) -> bool {
+        if self.config.case_insensitive_labels {
+            a.to_lowercase() == b.to_lowercase()
+        } else {
+            a == b
+        }
+    }
+
     // === Statistics ===
 
     /// Returns the current statistics.
@@ -1057,11 +2624,26 @@ impl LpgStore {
         self.statistics.read().clone()
     }
 
+    /// Replaces the current statistics, for example with ones extended by
+    /// an `ANALYZE`.
+    ///
+    /// Doesn't reset change tracking: start from
+    /// [`compute_statistics()`](Self::compute_statistics) so changes made
+    /// while extending still count towards the next refresh.
+    pub fn set_statistics(&self, statistics: Statistics) {
+        *self.statistics.write() = statistics;
+    }
+
     /// Recomputes statistics from current data.
     ///
     /// Scans all labels and edge types to build cardinality estimates for the
     /// query optimizer. Call this periodically or after bulk data loads.
     pub fn compute_statistics(&self) {
+        // Clear first so changes made while computing count towards the next
+        // refresh rather than being lost.
+        self.label_changes.write().clear();
+        self.edge_type_changes.write().clear();
+
         let mut stats = Statistics::new();
 
         // Compute total counts
@@ -1123,6 +2705,57 @@ impl LpgStore {
         *self.statistics.write() = stats;
     }
 
+    /// Returns whether any label or edge type has changed by more than
+    /// `ratio` of its size since statistics were last computed.
+    ///
+    /// A ratio of 0.1 means a label with 1000 nodes is stale after more than
+    /// 100 of its nodes were created, deleted, or relabeled. Labels and edge
+    /// types that didn't exist at the last computation are stale as soon as
+    /// they change.
+    #[must_use]
+    pub fn statistics_are_stale(&self, ratio: f64) -> bool {
+        let label_changes = self.label_changes.read();
+        let edge_type_changes = self.edge_type_changes.read();
+        if label_changes.is_empty() && edge_type_changes.is_empty() {
+            return false;
+        }
+
+        let stats = self.statistics.read();
+        let exceeds = |changes: u64, count: u64| changes as f64 > ratio * count as f64;
+
+        let id_to_label = self.id_to_label.read();
+        let stale_label = label_changes.iter().any(|(&label_id, &changes)| {
+            let count = id_to_label
+                .get(label_id as usize)
+                .and_then(|label| stats.get_label(label))
+                .map_or(0, |label_stats| label_stats.node_count);
+            exceeds(changes, count)
+        });
+        if stale_label {
+            return true;
+        }
+
+        let id_to_edge_type = self.id_to_edge_type.read();
+        edge_type_changes.iter().any(|(&type_id, &changes)| {
+            let count = id_to_edge_type
+                .get(type_id as usize)
+                .and_then(|edge_type| stats.get_edge_type(edge_type))
+                .map_or(0, |type_stats| type_stats.edge_count);
+            exceeds(changes, count)
+        })
+    }
+
+    /// Recomputes statistics if [`statistics_are_stale`](Self::statistics_are_stale)
+    /// for `ratio`, returning whether they were refreshed.
+    pub fn refresh_statistics_if_stale(&self, ratio: f64) -> bool {
+        if self.statistics_are_stale(ratio) {
+            self.compute_statistics();
+            true
+        } else {
+            false
+        }
+    }
+
     /// Estimates cardinality for a label scan.
     #[must_use]
     pub fn estimate_label_cardinality(&self, label: &str) -> f64 {
@@ -1137,55 +2770,318 @@ impl LpgStore {
             .estimate_avg_degree(edge_type, outgoing)
     }
 
+    /// Returns true if labels and edge types are matched ignoring case.
+    #[must_use]
+    pub fn case_insensitive_labels(&self) -> bool {
+        self.config.case_insensitive_labels
+    }
+
+    /// Returns true if a stored label or edge type satisfies `name` from a
+    /// query: the same string, or with
+    /// [`case_insensitive_labels`](LpgStoreConfig::case_insensitive_labels),
+    /// the same up to case.
+    #[must_use]
+    pub fn label_matches(&self, stored: &str, name: &str) -> bool {
+        stored == name
+            || (self.config.case_insensitive_labels
+                && stored
+                    .chars()
+                    .flat_map(char::to_lowercase)
+                    .eq(name.chars().flat_map(char::to_lowercase)))
+    }
+
+    /// Returns true if the node carries `label`, honouring
+    /// [`case_insensitive_labels`](LpgStoreConfig::case_insensitive_labels).
+    #[must_use]
+    pub fn node_has_label(&self, node: &Node, label: &str) -> bool {
+        node.labels
+            .iter()
+            .any(|stored| self.label_matches(stored, label))
+    }
+
     // === Internal Helpers ===
 
-    fn get_or_create_label_id(&self, label: &str) -> u32 {
-        {
-            let label_to_id = self.label_to_id.read();
-            if let Some(&id) = label_to_id.get(label) {
-                return id;
+    /// Returns the IDs of the labels a query for `label` matches.
+    fn matching_label_ids(&self, label: &str) -> Vec<u32> {
+        if self.config.case_insensitive_labels {
+            let folded = label.to_lowercase();
+            self.folded_label_ids
+                .read()
+                .get(folded.as_str())
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            self.label_to_id
+                .read()
+                .get(label)
+                .copied()
+                .into_iter()
+                .collect()
+        }
+    }
+
+    /// Returns the IDs of the edge types a query for `edge_type` matches.
+    fn matching_edge_type_ids(&self, edge_type: &str) -> Vec<u32> {
+        if self.config.case_insensitive_labels {
+            let folded = edge_type.to_lowercase();
+            self.folded_edge_type_ids
+                .read()
+                .get(folded.as_str())
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            self.edge_type_to_id
+                .read()
+                .get(edge_type)
+                .copied()
+                .into_iter()
+                .collect()
+        }
+    }
+
+    fn get_or_create_label_id(&self, label: &str) -> u32 {
+        {
+            let label_to_id = self.label_to_id.read();
+            if let Some(&id) = label_to_id.get(label) {
+                return id;
+            }
+        }
+
+        let mut label_to_id = self.label_to_id.write();
+        let mut id_to_label = self.id_to_label.write();
+
+        // Double-check after acquiring write lock
+        if let Some(&id) = label_to_id.get(label) {
+            return id;
+        }
+
+        let id = id_to_label.len() as u32;
+
+        self.folded_label_ids
+            .write()
+            .entry(label.to_lowercase().into())
+            .or_default()
+            .push(id);
+        let label: Arc<str> = label.into();
+        label_to_id.insert(label.clone(), id);
+        id_to_label.push(label);
+
+        id
+    }
+
+    fn get_or_create_edge_type_id(&self, edge_type: &str) -> u32 {
+        {
+            let type_to_id = self.edge_type_to_id.read();
+            if let Some(&id) = type_to_id.get(edge_type) {
+                return id;
+            }
+        }
+
+        let mut type_to_id = self.edge_type_to_id.write();
+        let mut id_to_type = self.id_to_edge_type.write();
+
+        // Double-check
+        if let Some(&id) = type_to_id.get(edge_type) {
+            return id;
+        }
+
+        let id = id_to_type.len() as u32;
+        self.folded_edge_type_ids
+            .write()
+            .entry(edge_type.to_lowercase().into())
+            .or_default()
+            .push(id);
+        let edge_type: Arc<str> = edge_type.into();
+        type_to_id.insert(edge_type.clone(), id);
+        id_to_type.push(edge_type);
+
+        id
+    }
+
+    // === Consistency ===
+
+    /// Checks that the store's redundant structures agree with each other.
+    ///
+    /// Verifies that live edges point at live nodes, that the label index
+    /// matches each node's labels, that adjacency lists and their counters
+    /// match the edge records, and that zone maps cover their columns'
+    /// values. Returns every [`Inconsistency`] found - empty means the store
+    /// is sound. Nothing is modified.
+    ///
+    /// Edges not visible at the current epoch (deleted, or written by a
+    /// transaction that hasn't committed) are left out, as traversals skip
+    /// them too.
+    #[must_use]
+    pub fn check_consistency(&self) -> Vec<Inconsistency> {
+        let epoch = self.current_epoch();
+        let mut issues = Vec::new();
+
+        let live_nodes: FxHashSet<NodeId> = self
+            .nodes
+            .read()
+            .iter()
+            .filter(|(_, chain)| chain.visible_at(epoch).is_some_and(|r| !r.is_deleted()))
+            .map(|(&id, _)| id)
+            .collect();
+        let mut live_edges: Vec<(EdgeId, NodeId, NodeId)> = self
+            .edges
+            .read()
+            .iter()
+            .filter_map(|(&id, chain)| {
+                chain
+                    .visible_at(epoch)
+                    .filter(|r| !r.is_deleted())
+                    .map(|r| (id, r.src, r.dst))
+            })
+            .collect();
+        live_edges.sort_unstable();
+
+        for &(edge, src, dst) in &live_edges {
+            if !live_nodes.contains(&src) {
+                issues.push(Inconsistency::DanglingSource { edge, src });
+            }
+            if !live_nodes.contains(&dst) {
+                issues.push(Inconsistency::DanglingTarget { edge, dst });
             }
         }
 
-        let mut label_to_id = self.label_to_id.write();
-        let mut id_to_label = self.id_to_label.write();
+        self.check_label_index(&mut issues);
 
-        // Double-check after acquiring write lock
-        if let Some(&id) = label_to_id.get(label) {
-            return id;
+        Self::check_adjacency(
+            &self.forward_adj,
+            Direction::Outgoing,
+            &live_edges,
+            &mut issues,
+        );
+        if let Some(ref backward) = self.backward_adj {
+            Self::check_adjacency(backward, Direction::Incoming, &live_edges, &mut issues);
         }
 
-        let id = id_to_label.len() as u32;
-
-        let label: Arc<str> = label.into();
-        label_to_id.insert(label.clone(), id);
-        id_to_label.push(label);
+        issues.extend(
+            self.node_properties
+                .zone_map_violations()
+                .into_iter()
+                .map(|(key, node)| Inconsistency::NodeZoneMapMismatch { key, node }),
+        );
+        issues.extend(
+            self.edge_properties
+                .zone_map_violations()
+                .into_iter()
+                .map(|(key, edge)| Inconsistency::EdgeZoneMapMismatch { key, edge }),
+        );
 
-        id
+        issues
     }
 
-    fn get_or_create_edge_type_id(&self, edge_type: &str) -> u32 {
-        {
-            let type_to_id = self.edge_type_to_id.read();
-            if let Some(&id) = type_to_id.get(edge_type) {
-                return id;
+    /// Compares the label index with each node's own label set.
+    fn check_label_index(&self, issues: &mut Vec<Inconsistency>) {
+        // Snapshot each structure under its own lock, so the check never holds
+        // two locks that writers take in a different order
+        let existing: FxHashSet<NodeId> = self.nodes.read().keys().copied().collect();
+        let node_labels = self.node_labels.read().clone();
+        let index: Vec<FxHashSet<NodeId>> = self
+            .label_index
+            .read()
+            .iter()
+            .map(|set| set.keys().copied().collect())
+            .collect();
+
+        // (node, label id, whether the index is missing it)
+        let mut mismatches = Vec::new();
+        for (&node, label_ids) in &node_labels {
+            for &label_id in label_ids {
+                if !index
+                    .get(label_id as usize)
+                    .is_some_and(|set| set.contains(&node))
+                {
+                    mismatches.push((node, label_id, true));
+                }
             }
         }
+        for (label_id, set) in index.iter().enumerate() {
+            let label_id = label_id as u32;
+            for &node in set {
+                if !existing.contains(&node)
+                    || !node_labels
+                        .get(&node)
+                        .is_some_and(|ids| ids.contains(&label_id))
+                {
+                    mismatches.push((node, label_id, false));
+                }
+            }
+        }
+        mismatches.sort_unstable();
 
-        let mut type_to_id = self.edge_type_to_id.write();
-        let mut id_to_type = self.id_to_edge_type.write();
+        let id_to_label = self.id_to_label.read();
+        for (node, label_id, missing) in mismatches {
+            let label = id_to_label
+                .get(label_id as usize)
+                .cloned()
+                .unwrap_or_else(|| label_id.to_string().into());
+            issues.push(if missing {
+                Inconsistency::MissingLabelIndexEntry { node, label }
+            } else {
+                Inconsistency::StaleLabelIndexEntry { node, label }
+            });
+        }
+    }
 
-        // Double-check
-        if let Some(&id) = type_to_id.get(edge_type) {
-            return id;
+    /// Compares one adjacency structure with the live edges and with its own
+    /// counters. `direction` says whether it's keyed by source or target.
+    fn check_adjacency(
+        adjacency: &ChunkedAdjacency,
+        direction: Direction,
+        live_edges: &[(EdgeId, NodeId, NodeId)],
+        issues: &mut Vec<Inconsistency>,
+    ) {
+        let mut entries = adjacency.entries();
+        let counted = adjacency.active_edge_count();
+        if counted != entries.len() {
+            issues.push(Inconsistency::AdjacencyCountMismatch {
+                direction,
+                counted,
+                actual: entries.len(),
+            });
         }
 
-        let id = id_to_type.len() as u32;
-        let edge_type: Arc<str> = edge_type.into();
-        type_to_id.insert(edge_type.clone(), id);
-        id_to_type.push(edge_type);
+        // (node holding the entry, neighbour) for each live edge
+        let endpoints = |src: NodeId, dst: NodeId| match direction {
+            Direction::Incoming => (dst, src),
+            Direction::Outgoing | Direction::Both => (src, dst),
+        };
+        let expected: FxHashMap<EdgeId, (NodeId, NodeId)> = live_edges
+            .iter()
+            .map(|&(edge, src, dst)| (edge, endpoints(src, dst)))
+            .collect();
 
-        id
+        entries.sort_unstable_by_key(|&(node, neighbor, edge)| (edge, node, neighbor));
+        let mut found = FxHashSet::default();
+        for (node, neighbor, edge) in entries {
+            // Entries for dead edges are expected; reads filter them out
+            let Some(&endpoints) = expected.get(&edge) else {
+                continue;
+            };
+            if endpoints == (node, neighbor) {
+                found.insert(edge);
+            } else {
+                issues.push(Inconsistency::WrongAdjacencyEntry {
+                    edge,
+                    node,
+                    neighbor,
+                    direction,
+                });
+            }
+        }
+
+        for &(edge, src, dst) in live_edges {
+            if !found.contains(&edge) {
+                issues.push(Inconsistency::MissingAdjacencyEntry {
+                    edge,
+                    node: endpoints(src, dst).0,
+                    direction,
+                });
+            }
+        }
     }
 
     // === Recovery Support ===
@@ -1204,6 +3100,7 @@ impl LpgStore {
         for label in labels {
             let label_id = self.get_or_create_label_id(*label);
             node_label_set.insert(label_id);
+            self.record_label_change(label_id);
 
             // Update label index
             let mut index = self.label_index.write();
@@ -1231,6 +3128,7 @@ impl LpgStore {
                     None
                 }
             });
+        self.record_change();
     }
 
     /// Creates an edge with a specific ID during recovery.
@@ -1243,11 +3141,12 @@ impl LpgStore {
         let record = EdgeRecord::new(id, src, dst, type_id, epoch);
         let chain = VersionChain::with_initial(record, epoch, TxId::SYSTEM);
         self.edges.write().insert(id, chain);
+        self.record_edge_type_change(type_id);
 
         // Update adjacency
-        self.forward_adj.add_edge(src, dst, id);
+        self.forward_adj.add_typed_edge(src, dst, id, type_id);
         if let Some(ref backward) = self.backward_adj {
-            backward.add_edge(dst, src, id);
+            backward.add_typed_edge(dst, src, id, type_id);
         }
 
         // Update next_edge_id if necessary
@@ -1261,6 +3160,7 @@ impl LpgStore {
                     None
                 }
             });
+        self.record_change();
     }
 
     /// Sets the current epoch during recovery.
@@ -1275,6 +3175,26 @@ impl Default for LpgStore {
     }
 }
 
+/// A small, fast, seedable generator for random walks.
+///
+/// See <https://prng.di.unimi.it/splitmix64.c>.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniform value in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1326,6 +3246,30 @@ mod tests {
         assert!(!store.delete_node(id));
     }
 
+    #[test]
+    fn test_change_count() {
+        let store = LpgStore::new();
+        assert_eq!(store.change_count(), 0);
+
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+        let edge = store.create_edge(a, b, "KNOWS");
+        assert_eq!(store.change_count(), 3);
+
+        store.set_node_property(a, "name", Value::from("Alice"));
+        store.set_edge_property(edge, "since", Value::Int64(2020));
+        assert!(store.add_label(a, "Employee"));
+        assert_eq!(store.change_count(), 6);
+
+        // No-ops leave the count alone
+        assert!(!store.add_label(a, "Employee"));
+        assert!(!store.delete_node(NodeId::new(99)));
+        assert_eq!(store.change_count(), 6);
+
+        assert!(store.delete_edge(edge));
+        assert_eq!(store.change_count(), 7);
+    }
+
     #[test]
     fn test_create_edge() {
         let store = LpgStore::new();
@@ -1363,6 +3307,138 @@ mod tests {
         assert!(incoming.contains(&a));
     }
 
+    #[test]
+    fn test_expand_batch_matches_per_node_expansion() {
+        let store = LpgStore::new();
+
+        let nodes: Vec<_> = (0..5).map(|_| store.create_node(&["Person"])).collect();
+        store.create_edge(nodes[0], nodes[1], "KNOWS");
+        store.create_edge(nodes[0], nodes[2], "LIKES");
+        store.create_edge(nodes[1], nodes[2], "KNOWS");
+        store.create_edge(nodes[3], nodes[0], "KNOWS");
+        store.create_edge(nodes[2], nodes[2], "KNOWS");
+
+        let sources = [nodes[2], nodes[0], nodes[4], nodes[1]];
+        for direction in [Direction::Outgoing, Direction::Incoming, Direction::Both] {
+            for edge_type in [None, Some("KNOWS"), Some("MISSING")] {
+                let mut expected: Vec<_> = sources
+                    .iter()
+                    .flat_map(|&src| {
+                        store
+                            .edges_from(src, direction)
+                            .filter(|&(_, edge)| {
+                                edge_type
+                                    .is_none_or(|t| store.edge_type(edge).as_deref() == Some(t))
+                            })
+                            .map(move |(dst, edge)| (src, edge, dst))
+                    })
+                    .collect();
+                expected.sort_unstable();
+
+                for sort_sources in [false, true] {
+                    let batch = store.expand_batch(&sources, direction, edge_type, sort_sources);
+                    let mut rows: Vec<_> = batch.iter().collect();
+                    rows.sort_unstable();
+                    assert_eq!(rows, expected, "{direction:?} {edge_type:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_degree_matches_expand_count() {
+        for backward_edges in [true, false] {
+            let store = LpgStore::with_config(LpgStoreConfig {
+                backward_edges,
+                ..Default::default()
+            });
+            let nodes: Vec<NodeId> = (0..12).map(|_| store.create_node(&["Node"])).collect();
+            let mut edges = Vec::new();
+            for i in 0..60 {
+                let src = nodes[(i * 7) % nodes.len()];
+                let dst = nodes[(i * 5 + 3) % nodes.len()];
+                let edge_type = ["KNOWS", "LIKES", "OWNS"][i % 3];
+                edges.push(store.create_edge(src, dst, edge_type));
+            }
+            // Deletes drop out of every count
+            for edge in edges.iter().step_by(4) {
+                store.delete_edge(*edge);
+            }
+
+            // What a full expand finds: walk every live edge
+            let expanded = |node: NodeId, direction: Direction, edge_type: Option<&str>| {
+                store
+                    .all_edges()
+                    .filter(|edge| edge_type.is_none_or(|t| &*edge.edge_type == t))
+                    .map(|edge| {
+                        let out = usize::from(edge.src == node);
+                        let inc = usize::from(edge.dst == node);
+                        match direction {
+                            Direction::Outgoing => out,
+                            Direction::Incoming => inc,
+                            Direction::Both => out + inc,
+                        }
+                    })
+                    .sum::<usize>()
+            };
+            for &node in &nodes {
+                for direction in [Direction::Outgoing, Direction::Incoming, Direction::Both] {
+                    for edge_type in [None, Some("KNOWS"), Some("OWNS"), Some("MISSING")] {
+                        assert_eq!(
+                            store.degree(node, direction, edge_type),
+                            expanded(node, direction, edge_type),
+                            "{node:?} {direction:?} {edge_type:?}, backward edges {backward_edges}"
+                        );
+                    }
+                }
+                // There are no self-loops, so this agrees with the traversal
+                if backward_edges {
+                    assert_eq!(
+                        store.degree(node, Direction::Both, None),
+                        store.edges_from(node, Direction::Both).count()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_self_loop_traversal_and_degree() {
+        let store = LpgStore::new();
+
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+        let lp = store.create_edge(a, a, "LIKES");
+        let ab = store.create_edge(a, b, "KNOWS");
+
+        let edge = store.get_edge(lp).unwrap();
+        assert_eq!((edge.src, edge.dst), (a, a));
+
+        // The loop is reachable from both ends of itself
+        let outgoing: Vec<_> = store.edges_from(a, Direction::Outgoing).collect();
+        assert!(outgoing.contains(&(a, lp)));
+        let incoming: Vec<_> = store.edges_from(a, Direction::Incoming).collect();
+        assert_eq!(incoming, vec![(a, lp)]);
+
+        // ...but reported once when both directions are walked
+        let mut both: Vec<_> = store.edges_from(a, Direction::Both).collect();
+        both.sort_unstable();
+        assert_eq!(both, vec![(a, lp), (b, ab)]);
+        let mut neighbors: Vec<_> = store.neighbors(a, Direction::Both).collect();
+        neighbors.sort_unstable();
+        assert_eq!(neighbors, vec![a, b]);
+
+        // Degree counts the loop at both of its ends
+        assert_eq!(store.degree(a, Direction::Outgoing, None), 2);
+        assert_eq!(store.degree(a, Direction::Incoming, None), 1);
+        assert_eq!(store.degree(a, Direction::Both, None), 3);
+        assert_eq!(store.degree(b, Direction::Both, None), 1);
+
+        store.delete_node_edges(a);
+        assert_eq!(store.edge_count(), 0);
+        assert_eq!(store.degree(a, Direction::Both, None), 0);
+    }
+
     #[test]
     fn test_nodes_by_label() {
         let store = LpgStore::new();
@@ -1394,4 +3470,464 @@ mod tests {
         assert_eq!(store.edge_count(), 0);
         assert!(store.get_edge(edge_id).is_none());
     }
+
+    #[test]
+    fn test_edge_validity() {
+        let store = LpgStore::new();
+
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+        let edge_id = store.create_edge(a, b, "KNOWS");
+        assert_eq!(store.edge_validity(edge_id), Some((None, None)));
+        assert!(store.is_edge_valid_at(edge_id, Timestamp::EPOCH));
+
+        let from = Timestamp::from_secs(10);
+        assert!(store.set_edge_validity(edge_id, Some(from), None));
+        assert_eq!(store.edge_validity(edge_id), Some((Some(from), None)));
+        assert!(!store.is_edge_valid_at(edge_id, Timestamp::from_secs(9)));
+        assert!(store.is_edge_valid_at(edge_id, Timestamp::from_secs(1_000)));
+
+        assert!(!store.set_edge_validity(EdgeId::new(99), Some(from), None));
+    }
+
+    #[test]
+    fn test_statistics_go_stale_after_changes() {
+        let store = LpgStore::new();
+        for _ in 0..10 {
+            store.create_node(&["Person"]);
+        }
+        assert!(store.statistics_are_stale(0.5));
+        store.compute_statistics();
+        assert!(!store.statistics_are_stale(0.5));
+
+        // 5 of 10 is not more than half
+        for _ in 0..5 {
+            store.create_node(&["Person"]);
+        }
+        assert!(!store.refresh_statistics_if_stale(0.5));
+        assert_eq!(
+            store.statistics().get_label("Person").unwrap().node_count,
+            10
+        );
+
+        store.create_node(&["Person"]);
+        assert!(store.refresh_statistics_if_stale(0.5));
+        assert_eq!(
+            store.statistics().get_label("Person").unwrap().node_count,
+            16
+        );
+        assert!(!store.statistics_are_stale(0.5));
+    }
+
+    #[test]
+    fn test_new_edge_type_makes_statistics_stale() {
+        let store = LpgStore::new();
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+        store.compute_statistics();
+
+        let edge = store.create_edge(a, b, "KNOWS");
+        assert!(store.statistics_are_stale(10.0));
+        store.compute_statistics();
+
+        store.delete_edge(edge);
+        assert!(store.refresh_statistics_if_stale(0.5));
+        assert!(store.statistics().get_edge_type("KNOWS").is_none());
+    }
+
+    #[test]
+    fn test_random_walk_is_reproducible() {
+        let store = LpgStore::new();
+        let nodes: Vec<_> = (0..6).map(|_| store.create_node(&["Node"])).collect();
+        for &a in &nodes {
+            for &b in &nodes {
+                if a != b {
+                    store.create_edge(a, b, "LINK");
+                }
+            }
+        }
+
+        let walk = store.random_walk(nodes[0], 50, Direction::Outgoing, None, 42);
+        assert_eq!(walk.len(), 51);
+        assert_eq!(walk[0], nodes[0]);
+        assert_eq!(
+            walk,
+            store.random_walk(nodes[0], 50, Direction::Outgoing, None, 42)
+        );
+        assert_ne!(
+            walk,
+            store.random_walk(nodes[0], 50, Direction::Outgoing, None, 7)
+        );
+    }
+
+    #[test]
+    fn test_random_walk_follows_edge_weights() {
+        // The hub sends weight 1 to `light`, 9 to `heavy`, and 0 to `never`;
+        // each leaf leads straight back
+        let store = LpgStore::new();
+        let hub = store.create_node(&["Node"]);
+        let light = store.create_node(&["Node"]);
+        let heavy = store.create_node(&["Node"]);
+        let never = store.create_node(&["Node"]);
+        for (leaf, weight) in [(light, 1.0), (heavy, 9.0), (never, 0.0)] {
+            let edge = store.create_edge(hub, leaf, "LINK");
+            store.set_edge_property(edge, "weight", Value::Float64(weight));
+            store.create_edge(leaf, hub, "LINK");
+        }
+
+        let count = |walk: &[NodeId], node| walk.iter().filter(|&&n| n == node).count() as f64;
+
+        let walk = store.random_walk(hub, 20_000, Direction::Outgoing, Some("weight"), 1);
+        let heavy_share = count(&walk, heavy) / (count(&walk, light) + count(&walk, heavy));
+        assert!((heavy_share - 0.9).abs() < 0.02, "{heavy_share}");
+        assert_eq!(count(&walk, never), 0.0);
+
+        // Without a weight property every edge is equally likely
+        let walk = store.random_walk(hub, 30_000, Direction::Outgoing, None, 1);
+        let never_share = count(&walk, never) / 15_000.0;
+        assert!((never_share - 1.0 / 3.0).abs() < 0.02, "{never_share}");
+    }
+
+    #[test]
+    fn test_random_walk_stops_at_dead_end() {
+        let store = LpgStore::new();
+        let a = store.create_node(&["Node"]);
+        let b = store.create_node(&["Node"]);
+        store.create_edge(a, b, "LINK");
+
+        assert_eq!(
+            store.random_walk(a, 10, Direction::Outgoing, None, 0),
+            vec![a, b]
+        );
+        assert_eq!(
+            store.random_walk(b, 10, Direction::Incoming, None, 0),
+            vec![b, a]
+        );
+        assert!(
+            store
+                .random_walk(NodeId::new(999), 10, Direction::Outgoing, None, 0)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_labels() {
+        let setup = |store: &LpgStore| {
+            let alice = store.create_node(&["Person"]);
+            let bob = store.create_node(&["person", "Employee"]);
+            store.create_edge(alice, bob, "KNOWS");
+            store.create_edge(bob, alice, "knows");
+            (alice, bob)
+        };
+
+        let store = LpgStore::new();
+        let (alice, _) = setup(&store);
+        assert!(!store.case_insensitive_labels());
+        assert_eq!(store.nodes_by_label("Person"), vec![alice]);
+        assert!(store.nodes_by_label("PERSON").is_empty());
+        assert_eq!(store.edges_with_type("KNOWS").count(), 1);
+        assert!(!store.label_matches("Person", "person"));
+
+        let store = LpgStore::with_config(LpgStoreConfig {
+            case_insensitive_labels: true,
+            ..LpgStoreConfig::default()
+        });
+        let (alice, bob) = setup(&store);
+        assert_eq!(store.nodes_by_label("PERSON"), vec![alice, bob]);
+        assert_eq!(
+            store.nodes_with_all_labels(&["person", "EMPLOYEE"]),
+            vec![bob]
+        );
+        assert_eq!(store.edges_with_type("Knows").count(), 2);
+        assert!(store.label_matches("Person", "pErSoN"));
+
+        let node = store.get_node(bob).unwrap();
+        assert!(store.node_has_label(&node, "PERSON"));
+        assert!(!store.node_has_label(&node, "Company"));
+    }
+
+    #[test]
+    fn test_increment_node_property_concurrently() {
+        let store = LpgStore::new();
+        let page = store.create_node(&["Page"]);
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..250 {
+                        store.increment_node_property(page, "views", &Value::Int64(1));
+                    }
+                });
+            }
+        });
+
+        let node = store.get_node(page).unwrap();
+        assert_eq!(node.get_property("views"), Some(&Value::Int64(1000)));
+        assert_eq!(
+            store.increment_node_property(page, "views", &Value::from("one")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tx_writes_stay_private_until_commit() {
+        let store = LpgStore::new();
+        let alice = store.create_node_with_props(&["Person"], [("name", "Alice")]);
+        let (tx, other) = (TxId::new(2), TxId::new(3));
+        let epoch = store.current_epoch();
+        let name = |node: Option<Node>| node.and_then(|n| n.get_property("name").cloned());
+
+        store
+            .set_node_property_versioned(alice, "name", Value::from("Carol"), tx)
+            .unwrap();
+        assert!(store.delete_node_versioned(alice, epoch, tx).unwrap());
+        assert!(store.get_node_versioned(alice, epoch, tx).is_none());
+        assert_eq!(name(store.get_node(alice)), Some(Value::from("Alice")));
+
+        // The writer holds the node until it ends
+        let conflict = store.set_node_property_versioned(alice, "name", Value::from("Dave"), other);
+        assert!(matches!(
+            conflict,
+            Err(Error::Transaction(TransactionError::WriteConflict(_)))
+        ));
+
+        // Undoing the delete brings back the written value for the writer
+        store.undo_writes_since(tx, 1);
+        assert_eq!(
+            name(store.get_node_versioned(alice, epoch, tx)),
+            Some(Value::from("Carol"))
+        );
+
+        let commit_epoch = epoch.next();
+        store.finalize_committed_versions(tx, commit_epoch);
+        assert_eq!(name(store.get_node(alice)), Some(Value::from("Carol")));
+        assert_eq!(
+            name(store.get_node_at_epoch(alice, epoch)),
+            Some(Value::from("Alice"))
+        );
+        store
+            .set_node_property_versioned(alice, "name", Value::from("Dave"), other)
+            .unwrap();
+
+        // Once nobody reads at the old epoch its value can go
+        store.discard_uncommitted_versions(other);
+        store.gc_property_versions(commit_epoch);
+        assert_eq!(
+            name(store.get_node_at_epoch(alice, epoch)),
+            Some(Value::from("Carol"))
+        );
+    }
+
+    #[test]
+    fn test_rollback_undoes_tx_writes() {
+        let store = LpgStore::new();
+        let alice = store.create_node_with_props(&["Person"], [("age", 30i64)]);
+        let tx = TxId::new(2);
+        let epoch = store.current_epoch();
+
+        let bob = store.create_node_versioned(&["Person"], EpochId::PENDING, tx);
+        store.create_edge_versioned(alice, bob, "KNOWS", EpochId::PENDING, tx);
+        store
+            .increment_node_property_versioned(alice, "age", &Value::Int64(1), tx)
+            .unwrap();
+        store
+            .remove_node_property_versioned(alice, "age", tx)
+            .unwrap();
+        assert!(
+            store
+                .add_label_versioned(alice, "Admin", epoch, tx)
+                .unwrap()
+        );
+        assert_eq!(store.node_count(), 1);
+        assert_eq!(store.tx_writes(tx).len(), 5);
+
+        store.discard_uncommitted_versions(tx);
+        let node = store.get_node(alice).unwrap();
+        assert_eq!(node.get_property("age"), Some(&Value::Int64(30)));
+        assert!(!store.node_has_label(&node, "Admin"));
+        assert!(store.get_node_versioned(bob, epoch, tx).is_none());
+        assert_eq!(store.neighbors(alice, Direction::Outgoing).count(), 0);
+        assert!(store.tx_writes(tx).is_empty());
+        assert!(store.check_consistency().is_empty());
+    }
+
+    #[test]
+    fn test_check_consistency_clean_store() {
+        let store = LpgStore::new();
+        let alice = store.create_node_with_props(&["Person"], [("age", 30i64)]);
+        let bob = store.create_node(&["Person", "Employee"]);
+        let acme = store.create_node(&["Company"]);
+        store.create_edge_with_props(alice, bob, "KNOWS", [("since", 2020i64)]);
+        store.create_edge(bob, acme, "WORKS_AT");
+        let loop_edge = store.create_edge(alice, alice, "LIKES");
+
+        store.delete_edge(loop_edge);
+        store.remove_label(bob, "Employee");
+        store.delete_node_edges(acme);
+        store.delete_node(acme);
+        assert!(store.check_consistency().is_empty());
+    }
+
+    #[test]
+    fn test_check_consistency_reports_corruption() {
+        let store = LpgStore::new();
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+        let c = store.create_node(&["Person"]);
+        let ab = store.create_edge(a, b, "KNOWS");
+        let bc = store.create_edge(b, c, "KNOWS");
+        assert!(store.check_consistency().is_empty());
+
+        // Deleting a node without detaching it leaves its edges dangling
+        store.delete_node(c);
+        assert_eq!(
+            store.check_consistency(),
+            vec![Inconsistency::DanglingTarget { edge: bc, dst: c }]
+        );
+        store.delete_edge(bc);
+        assert!(store.check_consistency().is_empty());
+
+        // Label index entries dropped or left behind
+        store.label_index.write()[0].remove(&a);
+        store.label_index.write()[0].insert(c, ());
+        assert_eq!(
+            store.check_consistency(),
+            vec![
+                Inconsistency::MissingLabelIndexEntry {
+                    node: a,
+                    label: "Person".into(),
+                },
+                Inconsistency::StaleLabelIndexEntry {
+                    node: c,
+                    label: "Person".into(),
+                },
+            ]
+        );
+        store.label_index.write()[0].insert(a, ());
+        store.label_index.write()[0].remove(&c);
+
+        // A forward list that lost an edge, and a backward list with a
+        // wrong entry and counters that no longer add up
+        store.forward_adj.mark_deleted(a, ab);
+        let backward = store.backward_adj.as_ref().unwrap();
+        backward.add_edge(b, c, ab);
+        backward.mark_deleted(b, EdgeId::new(99));
+        assert_eq!(
+            store.check_consistency(),
+            vec![
+                Inconsistency::MissingAdjacencyEntry {
+                    edge: ab,
+                    node: a,
+                    direction: Direction::Outgoing,
+                },
+                Inconsistency::AdjacencyCountMismatch {
+                    direction: Direction::Incoming,
+                    counted: 1,
+                    actual: 2,
+                },
+                Inconsistency::WrongAdjacencyEntry {
+                    edge: ab,
+                    node: b,
+                    neighbor: c,
+                    direction: Direction::Incoming,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fulltext_index_follows_changes() {
+        let store = LpgStore::new();
+        let a = store.create_node_with_props(&["Article"], [("body", "graph databases")]);
+        let b = store.create_node_with_props(&["Note"], [("body", "graph notes")]);
+
+        let index = store.create_fulltext_index("Article", "body", Tokenizer::new());
+        let hits =
+            |q: &str| -> Vec<NodeId> { index.search(q).into_iter().map(|(id, _)| id).collect() };
+        assert_eq!(hits("graph"), vec![a]);
+
+        // New and updated articles are picked up
+        let c = store.create_node_with_props(&["Article"], [("body", "query engines")]);
+        store.set_node_property(a, "body", Value::from("relational databases"));
+        assert_eq!(hits("graph"), Vec::<NodeId>::new());
+        assert_eq!(hits("databases"), vec![a]);
+        assert_eq!(hits("query"), vec![c]);
+
+        // Label changes decide membership
+        store.add_label(b, "Article");
+        assert_eq!(hits("graph"), vec![b]);
+        store.remove_label(b, "Article");
+        assert!(hits("graph").is_empty());
+
+        // Non-string values, removed properties and deleted nodes drop out
+        store.set_node_property(c, "body", Value::Int64(1));
+        assert!(hits("query").is_empty());
+        store.remove_node_property(a, "body");
+        assert!(index.is_empty());
+        store.set_node_property(a, "body", Value::from("graph again"));
+        store.delete_node(a);
+        assert!(index.is_empty());
+
+        assert!(Arc::ptr_eq(
+            &index,
+            &store.fulltext_index("Article", "body").unwrap()
+        ));
+        assert!(store.drop_fulltext_index("Article", "body"));
+        assert!(store.fulltext_index("Article", "body").is_none());
+    }
+
+    #[test]
+    fn test_spatial_index_follows_changes() {
+        use crate::index::DistanceMetric;
+
+        let store = LpgStore::new();
+        let near = store.create_node_with_props(&["Place"], [("at", Value::point(1.0, 1.0, 7203))]);
+        store.create_node_with_props(&["Place"], [("at", Value::point(9.0, 9.0, 7203))]);
+        store.create_node_with_props(&["Other"], [("at", Value::point(0.0, 0.0, 7203))]);
+
+        let index = store.create_spatial_index("Place", "at");
+        let within = || index.within_distance((0.0, 0.0), 2.0, DistanceMetric::Euclidean);
+        assert_eq!(within(), vec![near]);
+
+        let moved =
+            store.create_node_with_props(&["Place"], [("at", Value::point(5.0, 5.0, 7203))]);
+        store.set_node_property(moved, "at", Value::point(0.5, 0.0, 7203));
+        assert_eq!(within(), vec![near, moved]);
+
+        store.set_node_property(near, "at", Value::from("not a point"));
+        store.delete_node(moved);
+        assert!(within().is_empty());
+        assert_eq!(index.len(), 1);
+
+        assert!(store.drop_spatial_index("Place", "at"));
+        assert!(store.spatial_index("Place", "at").is_none());
+    }
+
+    #[test]
+    fn test_value_index_follows_changes() {
+        let store = LpgStore::new();
+        let alice = store.create_node_with_props(&["Person"], [("age", Value::Int64(30))]);
+        let bob = store.create_node_with_props(&["Person"], [("age", Value::Int64(40))]);
+        store.create_node_with_props(&["Robot"], [("age", Value::Int64(30))]);
+
+        let index = store.create_value_index("Person", "age");
+        assert_eq!(index.nodes_with(&Value::Int64(30)), vec![alice]);
+
+        let carol = store.create_node(&["Person"]);
+        store.set_node_property(carol, "age", Value::Int64(35));
+        store.set_node_property(bob, "age", Value::Int64(30));
+        assert_eq!(index.nodes_with(&Value::Int64(30)), vec![alice, bob]);
+        assert_eq!(
+            index.nodes_between(&Value::Int64(31), &Value::Int64(99)),
+            vec![carol]
+        );
+
+        store.remove_label(alice, "Person");
+        store.delete_node(carol);
+        assert_eq!(index.nodes_with(&Value::Int64(30)), vec![bob]);
+        assert_eq!(index.len(), 1);
+
+        assert!(store.drop_value_index("Person", "age"));
+        assert!(store.value_index("Person", "age").is_none());
+    }
 }
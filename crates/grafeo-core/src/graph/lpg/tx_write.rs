@@ -0,0 +1,116 @@
+//! What transactions change in the store, kept until they end.
+//!
+//! Writes made inside a transaction are applied to the store straight
+//! away, but each is also logged as a [`TxWrite`] with what it replaced.
+//! The log lets a rollback - of the whole transaction, or back to a
+//! savepoint - put everything back, and tells the engine what to write
+//! ahead and publish once the transaction commits.
+//!
+//! Overwritten property values are also kept as [`PropertyVersion`]s, so
+//! readers that mustn't see a write yet still get the value from before
+//! it.
+
+use std::sync::Arc;
+
+use grafeo_common::types::{EdgeId, EpochId, NodeId, PropertyKey, TxId, Value};
+
+/// A change a transaction made to the store, in the order it was made.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxWrite {
+    /// A node was created.
+    NodeCreated {
+        /// The new node.
+        id: NodeId,
+        /// The labels it was created with.
+        labels: Vec<Arc<str>>,
+    },
+    /// An edge was created.
+    EdgeCreated {
+        /// The new edge.
+        id: EdgeId,
+        /// Its source node.
+        src: NodeId,
+        /// Its destination node.
+        dst: NodeId,
+        /// Its type.
+        edge_type: Arc<str>,
+    },
+    /// A node property was set, or removed if `value` is `None`.
+    NodePropertySet {
+        /// The node.
+        id: NodeId,
+        /// Property key.
+        key: PropertyKey,
+        /// The value it replaced, if the property was set.
+        old: Option<Value>,
+        /// The new value.
+        value: Option<Value>,
+    },
+    /// An edge property was set, or removed if `value` is `None`.
+    EdgePropertySet {
+        /// The edge.
+        id: EdgeId,
+        /// Property key.
+        key: PropertyKey,
+        /// The value it replaced, if the property was set.
+        old: Option<Value>,
+        /// The new value.
+        value: Option<Value>,
+    },
+    /// A label was added to a node.
+    LabelAdded {
+        /// The node.
+        id: NodeId,
+        /// The added label.
+        label: Arc<str>,
+    },
+    /// A label was removed from a node.
+    LabelRemoved {
+        /// The node.
+        id: NodeId,
+        /// The removed label.
+        label: Arc<str>,
+    },
+    /// A node was deleted.
+    NodeDeleted {
+        /// The deleted node.
+        id: NodeId,
+    },
+    /// An edge was deleted.
+    EdgeDeleted {
+        /// The deleted edge.
+        id: EdgeId,
+    },
+}
+
+/// A node, edge or edge type a transaction holds the write lock on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum Entity {
+    Node(NodeId),
+    Edge(EdgeId),
+    /// Held while checking for, then creating, an edge that must be unique
+    /// per type and endpoints.
+    EdgeType(u32),
+}
+
+/// A property value a transaction overwrote, kept for the readers that
+/// mustn't see the write yet.
+#[derive(Debug, Clone)]
+pub(super) struct PropertyVersion {
+    /// The overwritten property.
+    pub(super) key: PropertyKey,
+    /// The transaction that wrote over it.
+    pub(super) writer: TxId,
+    /// When the write committed, or `None` while it's uncommitted.
+    pub(super) committed: Option<EpochId>,
+    /// The value before the write, `None` if the property wasn't set.
+    pub(super) previous: Option<Value>,
+}
+
+impl PropertyVersion {
+    /// Whether a reader at `epoch`, in transaction `tx` if any, sees the
+    /// write that replaced this value.
+    pub(super) fn is_seen_by(&self, epoch: EpochId, tx: Option<TxId>) -> bool {
+        tx == Some(self.writer) || self.committed.is_some_and(|c| c.is_visible_at(epoch))
+    }
+}
@@ -447,6 +447,27 @@ impl RdfStore {
             .unwrap_or(false)
     }
 
+    /// Returns how many operations a transaction has buffered so far.
+    ///
+    /// Pass the count to [`discard_ops_since`](Self::discard_ops_since) to
+    /// undo what the transaction buffers after this point.
+    #[must_use]
+    pub fn pending_op_count(&self, tx_id: TxId) -> usize {
+        self.tx_buffer
+            .read()
+            .buffers
+            .get(&tx_id)
+            .map_or(0, Vec::len)
+    }
+
+    /// Discards the operations a transaction buffered after the first
+    /// `mark`, keeping the ones before.
+    pub fn discard_ops_since(&self, tx_id: TxId, mark: usize) {
+        if let Some(ops) = self.tx_buffer.write().buffers.get_mut(&tx_id) {
+            ops.truncate(mark);
+        }
+    }
+
     /// Returns triples matching the given pattern, including pending inserts
     /// from the specified transaction (for read-your-writes within a transaction).
     pub fn find_with_pending(
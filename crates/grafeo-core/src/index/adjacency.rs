@@ -6,6 +6,8 @@
 //! - **O(1) amortized inserts** - new edges go into a delta buffer
 //! - **Cache-friendly scans** - chunks are sized for L1/L2 cache
 //! - **Soft deletes** - deletions don't require recompaction
+//! - **Constant-time degrees** - live edge counts, overall and per edge type,
+//!   are kept alongside each list
 //! - **Concurrent reads** - RwLock allows many simultaneous traversals
 //! - **Compression** - cold chunks can be compressed using DeltaBitPacked
 
@@ -181,6 +183,11 @@ struct AdjacencyList {
     delta_inserts: SmallVec<[(NodeId, EdgeId); 8]>,
     /// Set of deleted edge IDs.
     deleted: FxHashSet<EdgeId>,
+    /// Number of entries that aren't deleted.
+    live: usize,
+    /// Live entries per edge type ID, sorted by type. Entries added without
+    /// a type only count towards `live`.
+    type_degrees: SmallVec<[(u32, usize); 2]>,
 }
 
 impl AdjacencyList {
@@ -190,10 +197,23 @@ impl AdjacencyList {
             cold_chunks: Vec::new(),
             delta_inserts: SmallVec::new(),
             deleted: FxHashSet::default(),
+            live: 0,
+            type_degrees: SmallVec::new(),
         }
     }
 
-    fn add_edge(&mut self, dst: NodeId, edge_id: EdgeId) {
+    fn add_edge(&mut self, dst: NodeId, edge_id: EdgeId, edge_type: Option<u32>) {
+        self.live += 1;
+        if let Some(edge_type) = edge_type {
+            match self
+                .type_degrees
+                .binary_search_by_key(&edge_type, |&(t, _)| t)
+            {
+                Ok(i) => self.type_degrees[i].1 += 1,
+                Err(i) => self.type_degrees.insert(i, (edge_type, 1)),
+            }
+        }
+
         // Try to add to the last hot chunk
         if let Some(last) = self.hot_chunks.last_mut() {
             if last.push(dst, edge_id) {
@@ -205,8 +225,19 @@ impl AdjacencyList {
         self.delta_inserts.push((dst, edge_id));
     }
 
-    fn mark_deleted(&mut self, edge_id: EdgeId) {
-        self.deleted.insert(edge_id);
+    fn mark_deleted(&mut self, edge_id: EdgeId, edge_type: Option<u32>) {
+        if !self.deleted.insert(edge_id) {
+            return;
+        }
+        self.live = self.live.saturating_sub(1);
+        if let Some(edge_type) = edge_type {
+            if let Ok(i) = self
+                .type_degrees
+                .binary_search_by_key(&edge_type, |&(t, _)| t)
+            {
+                self.type_degrees[i].1 = self.type_degrees[i].1.saturating_sub(1);
+            }
+        }
     }
 
     fn compact(&mut self, chunk_capacity: usize) {
@@ -295,7 +326,20 @@ impl AdjacencyList {
     }
 
     fn degree(&self) -> usize {
-        self.iter().count()
+        self.live
+    }
+
+    /// Live entries whose type is one of `edge_types`.
+    fn type_degree(&self, edge_types: &[u32]) -> usize {
+        edge_types
+            .iter()
+            .filter_map(|edge_type| {
+                self.type_degrees
+                    .binary_search_by_key(edge_type, |&(t, _)| t)
+                    .ok()
+            })
+            .map(|i| self.type_degrees[i].1)
+            .sum()
     }
 
     /// Returns the number of entries in hot storage.
@@ -404,20 +448,43 @@ impl ChunkedAdjacency {
     }
 
     /// Adds an edge from src to dst.
+    ///
+    /// The edge has no type as far as [`degree`](Self::degree) is concerned;
+    /// use [`add_typed_edge`](Self::add_typed_edge) to count it by type.
     pub fn add_edge(&self, src: NodeId, dst: NodeId, edge_id: EdgeId) {
+        self.insert(src, dst, edge_id, None);
+    }
+
+    /// Adds an edge from src to dst, counted under `edge_type` for
+    /// [`degree`](Self::degree).
+    pub fn add_typed_edge(&self, src: NodeId, dst: NodeId, edge_id: EdgeId, edge_type: u32) {
+        self.insert(src, dst, edge_id, Some(edge_type));
+    }
+
+    fn insert(&self, src: NodeId, dst: NodeId, edge_id: EdgeId, edge_type: Option<u32>) {
         let mut lists = self.lists.write();
         lists
             .entry(src)
             .or_insert_with(AdjacencyList::new)
-            .add_edge(dst, edge_id);
+            .add_edge(dst, edge_id, edge_type);
         self.edge_count.fetch_add(1, Ordering::Relaxed);
     }
 
     /// Marks an edge as deleted.
     pub fn mark_deleted(&self, src: NodeId, edge_id: EdgeId) {
+        self.remove(src, edge_id, None);
+    }
+
+    /// Marks an edge added with [`add_typed_edge`](Self::add_typed_edge) as
+    /// deleted; `edge_type` must be the type it was added with.
+    pub fn mark_typed_deleted(&self, src: NodeId, edge_id: EdgeId, edge_type: u32) {
+        self.remove(src, edge_id, Some(edge_type));
+    }
+
+    fn remove(&self, src: NodeId, edge_id: EdgeId, edge_type: Option<u32>) {
         let mut lists = self.lists.write();
         if let Some(list) = lists.get_mut(&src) {
-            list.mark_deleted(edge_id);
+            list.mark_deleted(edge_id, edge_type);
             self.deleted_count.fetch_add(1, Ordering::Relaxed);
         }
     }
@@ -450,10 +517,68 @@ impl ChunkedAdjacency {
             .unwrap_or_default()
     }
 
+    /// Expands many source nodes in one pass.
+    ///
+    /// Takes the lock once for the whole frontier instead of once per node,
+    /// and returns every `(source, edge, target)` found as parallel columns.
+    /// With `sort_sources`, sources are visited in ID order, which keeps
+    /// lookups for nearby nodes together; the rows then come out in that
+    /// order too. Repeated sources are expanded once per occurrence.
+    #[must_use]
+    pub fn expand_batch(&self, sources: &[NodeId], sort_sources: bool) -> BatchExpansion {
+        let mut sorted;
+        let sources = if sort_sources {
+            sorted = sources.to_vec();
+            sorted.sort_unstable();
+            &sorted
+        } else {
+            sources
+        };
+
+        let mut batch = BatchExpansion::default();
+        let lists = self.lists.read();
+        for &src in sources {
+            if let Some(list) = lists.get(&src) {
+                for (dst, edge_id) in list.iter() {
+                    batch.push(src, edge_id, dst);
+                }
+            }
+        }
+        batch
+    }
+
+    /// Returns every live `(source, destination, edge)` entry, in no
+    /// particular order.
+    ///
+    /// Holds the lock for the whole walk, so keep this for checks and
+    /// diagnostics rather than traversal.
+    #[must_use]
+    pub fn entries(&self) -> Vec<(NodeId, NodeId, EdgeId)> {
+        let lists = self.lists.read();
+        lists
+            .iter()
+            .flat_map(|(&src, list)| list.iter().map(move |(dst, edge_id)| (src, dst, edge_id)))
+            .collect()
+    }
+
     /// Returns the out-degree of a node.
     pub fn out_degree(&self, src: NodeId) -> usize {
+        self.degree(src, None)
+    }
+
+    /// Returns how many live edges leave `src`, counting only those added
+    /// with one of `edge_types` if given.
+    ///
+    /// Answered from counts kept as edges are added and deleted, without
+    /// walking the list: O(1) overall, O(log t) per type for a node with `t`
+    /// edge types.
+    #[must_use]
+    pub fn degree(&self, src: NodeId, edge_types: Option<&[u32]>) -> usize {
         let lists = self.lists.read();
-        lists.get(&src).map_or(0, |list| list.degree())
+        lists.get(&src).map_or(0, |list| match edge_types {
+            Some(edge_types) => list.type_degree(edge_types),
+            None => list.degree(),
+        })
     }
 
     /// Compacts all adjacency lists.
@@ -481,7 +606,9 @@ impl ChunkedAdjacency {
 
     /// Returns the number of active (non-deleted) edges.
     pub fn active_edge_count(&self) -> usize {
-        self.edge_count.load(Ordering::Relaxed) - self.deleted_count.load(Ordering::Relaxed)
+        self.edge_count
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.deleted_count.load(Ordering::Relaxed))
     }
 
     /// Returns the number of nodes with adjacency lists.
@@ -540,6 +667,72 @@ impl ChunkedAdjacency {
     }
 }
 
+/// The result of [`ChunkedAdjacency::expand_batch`], stored column-wise.
+///
+/// Row `i` is the edge `edges[i]` from `sources[i]` to `targets[i]`, so each
+/// column maps straight onto a vector of a [`DataChunk`](crate::execution::DataChunk).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchExpansion {
+    /// The node each row was expanded from.
+    pub sources: Vec<NodeId>,
+    /// The edge followed.
+    pub edges: Vec<EdgeId>,
+    /// The node at the other end of the edge.
+    pub targets: Vec<NodeId>,
+}
+
+impl BatchExpansion {
+    /// Returns the number of rows.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns true if nothing was found.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Iterates over the rows as `(source, edge, target)` tuples.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, EdgeId, NodeId)> + '_ {
+        self.sources
+            .iter()
+            .zip(&self.edges)
+            .zip(&self.targets)
+            .map(|((&src, &edge), &dst)| (src, edge, dst))
+    }
+
+    /// Keeps only the rows for which `keep` returns true.
+    pub fn retain(&mut self, mut keep: impl FnMut(NodeId, EdgeId, NodeId) -> bool) {
+        let mut kept = 0;
+        for row in 0..self.len() {
+            if keep(self.sources[row], self.edges[row], self.targets[row]) {
+                self.sources[kept] = self.sources[row];
+                self.edges[kept] = self.edges[row];
+                self.targets[kept] = self.targets[row];
+                kept += 1;
+            }
+        }
+        self.sources.truncate(kept);
+        self.edges.truncate(kept);
+        self.targets.truncate(kept);
+    }
+
+    /// Appends all rows of `other`.
+    pub fn append(&mut self, other: &mut Self) {
+        self.sources.append(&mut other.sources);
+        self.edges.append(&mut other.edges);
+        self.targets.append(&mut other.targets);
+    }
+
+    fn push(&mut self, src: NodeId, edge_id: EdgeId, dst: NodeId) {
+        self.sources.push(src);
+        self.edges.push(edge_id);
+        self.targets.push(dst);
+    }
+}
+
 /// Memory statistics for the adjacency structure.
 #[derive(Debug, Clone)]
 pub struct AdjacencyMemoryStats {
@@ -617,6 +810,43 @@ mod tests {
         assert!(neighbors.contains(&NodeId::new(3)));
     }
 
+    #[test]
+    fn test_expand_batch() {
+        let adj = ChunkedAdjacency::with_chunk_capacity(4);
+        for i in 0..10 {
+            adj.add_edge(NodeId::new(i % 3), NodeId::new(10 + i), EdgeId::new(i));
+        }
+        adj.compact();
+        adj.mark_deleted(NodeId::new(1), EdgeId::new(4));
+
+        let sources = [NodeId::new(2), NodeId::new(7), NodeId::new(1)];
+        let batch = adj.expand_batch(&sources, false);
+
+        // Same rows as expanding one node at a time
+        let expected: Vec<_> = sources
+            .iter()
+            .flat_map(|&src| {
+                adj.edges_from(src)
+                    .into_iter()
+                    .map(move |(dst, edge)| (src, edge, dst))
+            })
+            .collect();
+        assert_eq!(batch.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(batch.len(), 5);
+
+        // Sorting visits node 1 before node 2
+        let sorted = adj.expand_batch(&sources, true);
+        assert_eq!(sorted.len(), batch.len());
+        assert!(sorted.sources.windows(2).all(|w| w[0] <= w[1]));
+
+        let mut filtered = sorted;
+        filtered.retain(|_, edge, _| edge.as_u64() % 2 == 0);
+        assert!(filtered.iter().all(|(_, edge, _)| edge.as_u64() % 2 == 0));
+        assert_eq!(filtered.len(), 2);
+
+        assert!(adj.expand_batch(&[], true).is_empty());
+    }
+
     #[test]
     fn test_out_degree() {
         let adj = ChunkedAdjacency::new();
@@ -642,6 +872,33 @@ mod tests {
         assert!(neighbors.contains(&NodeId::new(2)));
     }
 
+    #[test]
+    fn test_degree_counts_by_type() {
+        let adj = ChunkedAdjacency::with_chunk_capacity(4);
+        let src = NodeId::new(0);
+        // Enough edges to spill into several chunks and the delta buffer
+        for i in 0..20 {
+            adj.add_typed_edge(src, NodeId::new(i + 1), EdgeId::new(i), (i % 3) as u32);
+        }
+        adj.add_edge(src, NodeId::new(99), EdgeId::new(99));
+        adj.compact();
+
+        assert_eq!(adj.degree(src, None), 21);
+        assert_eq!(adj.degree(src, Some(&[0])), 7);
+        assert_eq!(adj.degree(src, Some(&[1, 2])), 13);
+        assert_eq!(adj.degree(src, Some(&[7])), 0);
+        assert_eq!(adj.degree(NodeId::new(5), None), 0);
+
+        // Deleting twice only counts once
+        adj.mark_typed_deleted(src, EdgeId::new(0), 0);
+        adj.mark_typed_deleted(src, EdgeId::new(0), 0);
+        adj.mark_deleted(src, EdgeId::new(99));
+        assert_eq!(adj.degree(src, None), 19);
+        assert_eq!(adj.degree(src, Some(&[0])), 6);
+        assert_eq!(adj.degree(src, None), adj.edges_from(src).len());
+        assert_eq!(adj.out_degree(src), 19);
+    }
+
     #[test]
     fn test_edges_from() {
         let adj = ChunkedAdjacency::new();
@@ -886,7 +1143,7 @@ mod tests {
 
         // Add edges
         for i in 0..50 {
-            list.add_edge(NodeId::new(i + 1), EdgeId::new(i));
+            list.add_edge(NodeId::new(i + 1), EdgeId::new(i), None);
         }
 
         // Compact with small chunk capacity to get multiple chunks
@@ -2,11 +2,18 @@
 //!
 //! Use this when you need to answer range queries like `age > 30 AND age < 50`
 //! or when you need min/max values. O(log n) lookups but efficient range scans.
+//! For property values of any type, key the index with [`ValueKey`].
 
-use grafeo_common::types::NodeId;
+use super::persist::{self, IndexKind};
+use grafeo_common::types::{NodeId, Value};
+use grafeo_common::utils::error::Result;
 use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
-use std::ops::RangeBounds;
+use std::io::{Read, Write};
+use std::ops::{Bound, RangeBounds};
 
 /// A thread-safe BTree index for range queries.
 ///
@@ -66,11 +73,31 @@ impl<K: Ord + Clone, V: Copy> BTreeIndex<K, V> {
         self.map.read().contains_key(key)
     }
 
-    /// Returns all values in the given range.
-    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Vec<(K, V)> {
+    /// Returns the entries with keys in `range`, in ascending key order.
+    ///
+    /// Accepts any range expression, or a `(Bound<K>, Bound<K>)` pair to
+    /// mix inclusive and exclusive ends. A range that starts past its end
+    /// yields nothing. The entries are copied out, so the index isn't
+    /// locked while you iterate.
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl ExactSizeIterator<Item = (K, V)> {
+        self.collect_range(&range).into_iter()
+    }
+
+    /// Returns the entries with keys in `range`, in descending key order.
+    ///
+    /// Serves `ORDER BY ... DESC LIMIT n`: take the first `n` items.
+    pub fn range_rev<R: RangeBounds<K>>(&self, range: R) -> impl ExactSizeIterator<Item = (K, V)> {
+        self.collect_range(&range).into_iter().rev()
+    }
+
+    fn collect_range<R: RangeBounds<K>>(&self, range: &R) -> Vec<(K, V)> {
+        if is_inverted(range) {
+            // BTreeMap::range panics on these
+            return Vec::new();
+        }
         self.map
             .read()
-            .range(range)
+            .range::<K, _>((range.start_bound(), range.end_bound()))
             .map(|(k, v)| (k.clone(), *v))
             .collect()
     }
@@ -107,12 +134,55 @@ impl<K: Ord + Clone, V: Copy> BTreeIndex<K, V> {
     }
 }
 
+impl<K, V> BTreeIndex<K, V>
+where
+    K: Ord + Clone + Serialize + DeserializeOwned,
+    V: Copy + Serialize + DeserializeOwned,
+{
+    /// Writes the index to `writer` so it can be loaded without a rebuild.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encoding or writing fails.
+    pub fn serialize(&self, writer: &mut impl Write) -> Result<()> {
+        let map = self.map.read();
+        let entries: Vec<(&K, &V)> = map.iter().collect();
+        persist::write_entries(writer, IndexKind::BTree, &entries)
+    }
+
+    /// Reads an index written by [`serialize`](Self::serialize).
+    ///
+    /// # Errors
+    ///
+    /// Returns a corruption error if the data isn't a BTree index in the
+    /// current format or fails its checksum. Rebuild the index in that case.
+    pub fn deserialize(reader: &mut impl Read) -> Result<Self> {
+        let entries: Vec<(K, V)> = persist::read_entries(reader, IndexKind::BTree)?;
+        Ok(Self {
+            map: RwLock::new(entries.into_iter().collect()),
+        })
+    }
+}
+
 impl<K: Ord + Clone, V: Copy> Default for BTreeIndex<K, V> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Returns true if a range starts after it ends, or excludes both ends of a
+/// single key.
+fn is_inverted<K: Ord, R: RangeBounds<K>>(range: &R) -> bool {
+    match (range.start_bound(), range.end_bound()) {
+        (Bound::Excluded(start), Bound::Excluded(end)) => start >= end,
+        (
+            Bound::Included(start) | Bound::Excluded(start),
+            Bound::Included(end) | Bound::Excluded(end),
+        ) => start > end,
+        _ => false,
+    }
+}
+
 /// A BTree index from i64 keys to NodeIds.
 pub type Int64Index = BTreeIndex<i64, NodeId>;
 
@@ -124,7 +194,7 @@ pub type Float64Index = BTreeIndex<OrderedFloat, NodeId>;
 ///
 /// Since f64 doesn't implement Ord (due to NaN), we need this wrapper.
 /// NaN values are treated as equal to each other.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct OrderedFloat(pub f64);
 
 impl Eq for OrderedFloat {}
@@ -149,6 +219,105 @@ impl From<f64> for OrderedFloat {
     }
 }
 
+/// A BTree index from property values to NodeIds.
+pub type ValueIndex = BTreeIndex<ValueKey, NodeId>;
+
+/// A wrapper around [`Value`] that implements Ord for use in BTreeIndex.
+///
+/// Integers and floats compare numerically with each other, so `Int64(2)`
+/// sorts between `Float64(1.5)` and `Float64(2.5)`, and strings compare
+/// lexicographically - the same order filters and zone maps use. Values of
+/// different kinds sort by kind: nulls, booleans, numbers, strings, bytes,
+/// timestamps, lists, then maps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueKey(pub Value);
+
+impl PartialEq for ValueKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ValueKey {}
+
+impl PartialOrd for ValueKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ValueKey {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_keys(&self.0, &other.0)
+    }
+}
+
+impl From<Value> for ValueKey {
+    fn from(value: Value) -> Self {
+        Self(value)
+    }
+}
+
+/// Position of a value's kind in the [`ValueKey`] order.
+fn kind_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Int64(_) | Value::Float64(_) => 2,
+        Value::String(_) => 3,
+        Value::Bytes(_) => 4,
+        Value::Timestamp(_) | Value::Date(_) => 5,
+        Value::List(_) => 6,
+        Value::Map(_) => 7,
+        Value::Point { .. } => 8,
+        Value::Duration(_) => 9,
+    }
+}
+
+/// Total order over values; see [`ValueKey`].
+fn compare_keys(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (Value::Int64(a), Value::Int64(b)) => a.cmp(b),
+        (Value::Float64(a), Value::Float64(b)) => a.total_cmp(b),
+        (Value::Int64(a), Value::Float64(b)) => (*a as f64).total_cmp(b),
+        (Value::Float64(a), Value::Int64(b)) => a.total_cmp(&(*b as f64)),
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bytes(a), Value::Bytes(b)) => a.cmp(b),
+        (Value::Timestamp(a), Value::Timestamp(b)) => a.cmp(b),
+        (Value::Date(a), Value::Date(b)) => a.cmp(b),
+        // A date is the same key as the timestamp of its midnight
+        (Value::Date(_), Value::Timestamp(_)) | (Value::Timestamp(_), Value::Date(_)) => {
+            a.as_epoch_micros().cmp(&b.as_epoch_micros())
+        }
+        (Value::Duration(a), Value::Duration(b)) => a.cmp(b),
+        (Value::List(a), Value::List(b)) => a
+            .iter()
+            .zip(b.iter())
+            .map(|(a, b)| compare_keys(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Map(a), Value::Map(b)) => a
+            .iter()
+            .zip(b.iter())
+            .map(|((a_key, a), (b_key, b))| a_key.cmp(b_key).then_with(|| compare_keys(a, b)))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (
+            Value::Point { x, y, srid },
+            Value::Point {
+                x: b_x,
+                y: b_y,
+                srid: b_srid,
+            },
+        ) => srid
+            .cmp(b_srid)
+            .then_with(|| x.total_cmp(b_x))
+            .then_with(|| y.total_cmp(b_y)),
+        _ => kind_rank(a).cmp(&kind_rank(b)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -175,12 +344,132 @@ mod tests {
         index.insert(30, NodeId::new(300));
         index.insert(40, NodeId::new(400));
 
-        let range = index.range(15..=35);
+        let range: Vec<_> = index.range(15..=35).collect();
         assert_eq!(range.len(), 2);
         assert!(range.contains(&(20, NodeId::new(200))));
         assert!(range.contains(&(30, NodeId::new(300))));
     }
 
+    #[test]
+    fn test_btree_range_bounds() {
+        let index: Int64Index = BTreeIndex::new();
+        for key in [10, 20, 30, 40] {
+            index.insert(key, NodeId::new(key as u64));
+        }
+        let keys = |lower: Bound<i64>, upper: Bound<i64>| {
+            index
+                .range((lower, upper))
+                .map(|(k, _)| k)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(keys(Bound::Included(20), Bound::Included(40)), [20, 30, 40]);
+        assert_eq!(keys(Bound::Excluded(20), Bound::Excluded(40)), [30]);
+        assert_eq!(keys(Bound::Excluded(20), Bound::Unbounded), [30, 40]);
+        assert_eq!(keys(Bound::Unbounded, Bound::Excluded(20)), [10]);
+        assert_eq!(keys(Bound::Included(30), Bound::Included(30)), [30]);
+
+        // Empty ranges, including ones BTreeMap would panic on
+        assert!(keys(Bound::Included(21), Bound::Included(29)).is_empty());
+        assert!(keys(Bound::Included(40), Bound::Included(10)).is_empty());
+        assert!(keys(Bound::Excluded(30), Bound::Excluded(30)).is_empty());
+        assert!(keys(Bound::Included(30), Bound::Excluded(30)).is_empty());
+        assert_eq!(BTreeIndex::<i64, NodeId>::new().range(..).len(), 0);
+    }
+
+    #[test]
+    fn test_btree_range_rev() {
+        let index: Int64Index = BTreeIndex::new();
+        for key in [10, 20, 30, 40] {
+            index.insert(key, NodeId::new(key as u64));
+        }
+
+        let desc: Vec<_> = index.range_rev(..).map(|(k, _)| k).collect();
+        assert_eq!(desc, [40, 30, 20, 10]);
+
+        // ORDER BY key DESC LIMIT 2 WHERE key < 40
+        let top: Vec<_> = index.range_rev(..40).take(2).collect();
+        assert_eq!(top, [(30, NodeId::new(30)), (20, NodeId::new(20))]);
+        assert_eq!(index.range_rev(41..).len(), 0);
+    }
+
+    #[test]
+    fn test_value_index_ordering() {
+        let index: ValueIndex = BTreeIndex::new();
+        let entries = [
+            Value::from("b"),
+            Value::Int64(2),
+            Value::Float64(1.5),
+            Value::from("a"),
+            Value::Float64(2.5),
+            Value::Null,
+            Value::Bool(true),
+        ];
+        for (i, value) in entries.iter().enumerate() {
+            index.insert(ValueKey(value.clone()), NodeId::new(i as u64));
+        }
+
+        let ordered: Vec<Value> = index.range(..).map(|(k, _)| k.0).collect();
+        assert_eq!(
+            ordered,
+            [
+                Value::Null,
+                Value::Bool(true),
+                Value::Float64(1.5),
+                Value::Int64(2),
+                Value::Float64(2.5),
+                Value::from("a"),
+                Value::from("b"),
+            ]
+        );
+
+        // Numeric bounds match integers and floats alike
+        let lower = Bound::Excluded(ValueKey(Value::Int64(1)));
+        let upper = Bound::Included(ValueKey(Value::Float64(2.0)));
+        let ids: Vec<NodeId> = index.range((lower, upper)).map(|(_, id)| id).collect();
+        assert_eq!(ids, [NodeId::new(2), NodeId::new(1)]);
+
+        // Equal numbers are the same key
+        assert_eq!(ValueKey(Value::Int64(2)), ValueKey(Value::Float64(2.0)));
+        let strings: Vec<Value> = index
+            .range_rev(ValueKey(Value::from(""))..)
+            .map(|(k, _)| k.0)
+            .collect();
+        assert_eq!(strings, [Value::from("b"), Value::from("a")]);
+    }
+
+    #[test]
+    fn test_value_index_temporal_ranges() {
+        use grafeo_common::types::Timestamp;
+
+        let index: ValueIndex = BTreeIndex::new();
+        let day = |days: i64| Timestamp::from_secs(days * 86_400);
+        let entries = [
+            Value::Timestamp(day(19_722).add_micros(1)),
+            Value::Date(19_724),
+            Value::Timestamp(day(19_723).add_micros(1)),
+            Value::Duration(5),
+            Value::Date(19_722),
+        ];
+        for (i, value) in entries.iter().enumerate() {
+            index.insert(ValueKey(value.clone()), NodeId::new(i as u64));
+        }
+
+        // Dates and timestamps interleave by instant; durations sort apart
+        let ordered: Vec<NodeId> = index.range(..).map(|(_, id)| id).collect();
+        assert_eq!(ordered, [4, 0, 2, 1, 3].map(NodeId::new));
+
+        // Everything after 2024-01-01 (day 19723), whether date or timestamp
+        let lower = Bound::Excluded(ValueKey(Value::Date(19_723)));
+        let upper = Bound::Excluded(ValueKey(Value::Duration(i64::MIN)));
+        let ids: Vec<NodeId> = index.range((lower, upper)).map(|(_, id)| id).collect();
+        assert_eq!(ids, [NodeId::new(2), NodeId::new(1)]);
+        assert_eq!(
+            ValueKey(Value::Date(19_723)),
+            ValueKey(Value::Timestamp(day(19_723)))
+        );
+    }
+
     #[test]
     fn test_btree_min_max() {
         let index: Int64Index = BTreeIndex::new();
@@ -220,4 +509,55 @@ mod tests {
         let range = index.range(OrderedFloat(1.0)..OrderedFloat(3.0));
         assert_eq!(range.len(), 2);
     }
+
+    #[test]
+    fn test_value_index_round_trip() {
+        let index: ValueIndex = BTreeIndex::new();
+        index.insert(Value::Int64(30).into(), NodeId::new(1));
+        index.insert(Value::Float64(2.5).into(), NodeId::new(2));
+        index.insert(Value::from("alice").into(), NodeId::new(3));
+        index.insert(Value::Null.into(), NodeId::new(4));
+
+        let mut bytes = Vec::new();
+        index.serialize(&mut bytes).unwrap();
+        let loaded = ValueIndex::deserialize(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.len(), index.len());
+        assert_eq!(
+            loaded.get(&Value::from("alice").into()),
+            Some(NodeId::new(3))
+        );
+        let expected: Vec<_> = index.range(..).map(|(_, v)| v).collect();
+        let actual: Vec<_> = loaded.range(..).map(|(_, v)| v).collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_btree_rejects_corrupt_or_foreign_bytes() {
+        use crate::index::HashIndex;
+        use grafeo_common::utils::error::{Error, StorageError};
+
+        let index: Int64Index = BTreeIndex::new();
+        index.insert(10, NodeId::new(100));
+
+        let mut bytes = Vec::new();
+        index.serialize(&mut bytes).unwrap();
+        let mut corrupt = bytes.clone();
+        let last = corrupt.len() - 5;
+        corrupt[last] ^= 0xFF;
+        assert!(matches!(
+            Int64Index::deserialize(&mut corrupt.as_slice()),
+            Err(Error::Storage(StorageError::Corruption(_)))
+        ));
+
+        // Truncated files and files from the other index type are rejected too
+        assert!(Int64Index::deserialize(&mut &bytes[..bytes.len() - 2]).is_err());
+        let hash: HashIndex<i64, NodeId> = HashIndex::new();
+        let mut hash_bytes = Vec::new();
+        hash.serialize(&mut hash_bytes).unwrap();
+        assert!(matches!(
+            Int64Index::deserialize(&mut hash_bytes.as_slice()),
+            Err(Error::Storage(StorageError::Corruption(_)))
+        ));
+    }
 }
@@ -0,0 +1,404 @@
+//! Full-text index for searching inside string properties.
+//!
+//! Text is split into tokens by a [`Tokenizer`] and stored in an inverted
+//! index: each token maps to the nodes containing it and the positions where
+//! it occurs. That makes "which documents mention these words" and "which
+//! documents contain this exact phrase" cheap, no matter how many nodes
+//! there are.
+//!
+//! Results come back ranked by TF-IDF: documents that mention a term often
+//! score higher, and rare terms count for more than common ones.
+
+use grafeo_common::types::NodeId;
+use grafeo_common::utils::hash::FxHashMap;
+use parking_lot::RwLock;
+
+/// Splits text into the tokens a [`FullTextIndex`] stores.
+///
+/// Tokens are the whitespace-separated pieces of the text, lowercased unless
+/// you turn that off. Punctuation stays attached to its word.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tokenizer {
+    lowercase: bool,
+}
+
+impl Tokenizer {
+    /// Creates a tokenizer that lowercases and splits on whitespace.
+    #[must_use]
+    pub fn new() -> Self {
+        Self { lowercase: true }
+    }
+
+    /// Sets whether tokens are lowercased, making search case-insensitive.
+    #[must_use]
+    pub fn with_lowercase(mut self, lowercase: bool) -> Self {
+        self.lowercase = lowercase;
+        self
+    }
+
+    /// Returns true if tokens are lowercased.
+    #[must_use]
+    pub fn lowercase(&self) -> bool {
+        self.lowercase
+    }
+
+    /// Splits `text` into tokens.
+    #[must_use]
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        text.split_whitespace().map(|t| self.normalize(t)).collect()
+    }
+
+    /// Normalizes a single token.
+    ///
+    /// Lowercasing goes char by char, so a substring of the text stays a
+    /// substring of the normalized text.
+    fn normalize(&self, token: &str) -> String {
+        if self.lowercase {
+            token.chars().flat_map(char::to_lowercase).collect()
+        } else {
+            token.to_string()
+        }
+    }
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Default)]
+struct Postings {
+    /// Token -> node -> positions of the token in that node's text.
+    terms: FxHashMap<String, FxHashMap<NodeId, Vec<u32>>>,
+    /// Node -> distinct tokens in its text, so it can be removed later.
+    documents: FxHashMap<NodeId, Vec<String>>,
+}
+
+/// A thread-safe inverted index over text, keyed by node.
+///
+/// Each node has at most one document. Inserting a node again replaces its
+/// old text, so keep the index in step with the property by calling
+/// [`insert`](Self::insert) on every update and [`remove`](Self::remove)
+/// when the value goes away.
+///
+/// # Example
+///
+/// ```
+/// use grafeo_core::index::FullTextIndex;
+/// use grafeo_common::types::NodeId;
+///
+/// let index = FullTextIndex::new();
+/// index.insert(NodeId::new(1), "Graph databases store graphs");
+/// index.insert(NodeId::new(2), "Relational databases store tables");
+///
+/// let hits: Vec<NodeId> = index.search("graph").into_iter().map(|(id, _)| id).collect();
+/// assert_eq!(hits, vec![NodeId::new(1)]);
+///
+/// let hits = index.search_phrase("databases store");
+/// assert_eq!(hits.len(), 2);
+/// ```
+pub struct FullTextIndex {
+    tokenizer: Tokenizer,
+    postings: RwLock<Postings>,
+}
+
+impl FullTextIndex {
+    /// Creates an empty index with the default tokenizer.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_tokenizer(Tokenizer::default())
+    }
+
+    /// Creates an empty index that tokenizes with `tokenizer`.
+    #[must_use]
+    pub fn with_tokenizer(tokenizer: Tokenizer) -> Self {
+        Self {
+            tokenizer,
+            postings: RwLock::new(Postings::default()),
+        }
+    }
+
+    /// Returns the tokenizer used for documents and queries.
+    #[must_use]
+    pub fn tokenizer(&self) -> Tokenizer {
+        self.tokenizer
+    }
+
+    /// Indexes `text` as the document for `node`, replacing any previous one.
+    pub fn insert(&self, node: NodeId, text: &str) {
+        let tokens = self.tokenizer.tokenize(text);
+        let mut postings = self.postings.write();
+        Self::remove_locked(&mut postings, node);
+
+        let mut distinct = Vec::new();
+        for (position, token) in tokens.into_iter().enumerate() {
+            let positions = postings
+                .terms
+                .entry(token.clone())
+                .or_default()
+                .entry(node)
+                .or_default();
+            if positions.is_empty() {
+                distinct.push(token);
+            }
+            positions.push(position as u32);
+        }
+        postings.documents.insert(node, distinct);
+    }
+
+    /// Removes the document for `node`.
+    ///
+    /// Returns true if the node was indexed.
+    pub fn remove(&self, node: NodeId) -> bool {
+        Self::remove_locked(&mut self.postings.write(), node)
+    }
+
+    fn remove_locked(postings: &mut Postings, node: NodeId) -> bool {
+        let Some(tokens) = postings.documents.remove(&node) else {
+            return false;
+        };
+        for token in tokens {
+            if let Some(nodes) = postings.terms.get_mut(&token) {
+                nodes.remove(&node);
+                if nodes.is_empty() {
+                    postings.terms.remove(&token);
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the number of indexed documents.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.postings.read().documents.len()
+    }
+
+    /// Returns true if nothing is indexed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if `node` has a document in the index.
+    #[must_use]
+    pub fn contains_node(&self, node: NodeId) -> bool {
+        self.postings.read().documents.contains_key(&node)
+    }
+
+    /// Finds documents containing every token of `query`, in any order.
+    ///
+    /// Results are sorted by score, best first. An empty query matches
+    /// nothing.
+    #[must_use]
+    pub fn search(&self, query: &str) -> Vec<(NodeId, f64)> {
+        let tokens = self.tokenizer.tokenize(query);
+        let postings = self.postings.read();
+        let Some(lists) = Self::posting_lists(&postings, &tokens) else {
+            return Vec::new();
+        };
+
+        let total = postings.documents.len();
+        let mut hits: Vec<(NodeId, f64)> = Self::common_nodes(&lists)
+            .into_iter()
+            .map(|node| {
+                let score = lists
+                    .iter()
+                    .map(|nodes| nodes[&node].len() as f64 * idf(total, nodes.len()))
+                    .sum();
+                (node, score)
+            })
+            .collect();
+        rank(&mut hits);
+        hits
+    }
+
+    /// Finds documents containing the tokens of `phrase` next to each other,
+    /// in order.
+    ///
+    /// Documents with more occurrences of the phrase score higher. An empty
+    /// phrase matches nothing.
+    #[must_use]
+    pub fn search_phrase(&self, phrase: &str) -> Vec<(NodeId, f64)> {
+        let tokens = self.tokenizer.tokenize(phrase);
+        let postings = self.postings.read();
+        let Some(lists) = Self::posting_lists(&postings, &tokens) else {
+            return Vec::new();
+        };
+
+        let total = postings.documents.len();
+        let weight: f64 = lists.iter().map(|nodes| idf(total, nodes.len())).sum();
+        let mut hits: Vec<(NodeId, f64)> = Self::common_nodes(&lists)
+            .into_iter()
+            .filter_map(|node| {
+                let occurrences = lists[0][&node]
+                    .iter()
+                    .filter(|&&start| {
+                        lists.iter().enumerate().skip(1).all(|(offset, nodes)| {
+                            nodes[&node].binary_search(&(start + offset as u32)).is_ok()
+                        })
+                    })
+                    .count();
+                (occurrences > 0).then_some((node, occurrences as f64 * weight))
+            })
+            .collect();
+        rank(&mut hits);
+        hits
+    }
+
+    /// Returns the nodes with a token that contains `fragment`.
+    ///
+    /// Any document whose text contains `fragment` without crossing
+    /// whitespace is in the result, which makes this a candidate set for
+    /// substring predicates like `CONTAINS`: filter the candidates on the
+    /// real text to get the exact answer. Returns `None` if `fragment` is
+    /// empty, since every document would match. Sorted by node ID.
+    #[must_use]
+    pub fn nodes_with_token_containing(&self, fragment: &str) -> Option<Vec<NodeId>> {
+        if fragment.is_empty() {
+            return None;
+        }
+        let fragment = self.tokenizer.normalize(fragment);
+        let postings = self.postings.read();
+        let mut nodes: Vec<NodeId> = postings
+            .terms
+            .iter()
+            .filter(|(token, _)| token.contains(fragment.as_str()))
+            .flat_map(|(_, nodes)| nodes.keys().copied())
+            .collect();
+        nodes.sort_unstable();
+        nodes.dedup();
+        Some(nodes)
+    }
+
+    /// Looks up the posting list of every token, or `None` if there are no
+    /// tokens or one of them isn't indexed.
+    fn posting_lists<'a>(
+        postings: &'a Postings,
+        tokens: &[String],
+    ) -> Option<Vec<&'a FxHashMap<NodeId, Vec<u32>>>> {
+        if tokens.is_empty() {
+            return None;
+        }
+        tokens.iter().map(|t| postings.terms.get(t)).collect()
+    }
+
+    /// Returns the nodes present in every posting list.
+    fn common_nodes(lists: &[&FxHashMap<NodeId, Vec<u32>>]) -> Vec<NodeId> {
+        let smallest = lists
+            .iter()
+            .min_by_key(|nodes| nodes.len())
+            .expect("at least one posting list");
+        smallest
+            .keys()
+            .copied()
+            .filter(|node| lists.iter().all(|nodes| nodes.contains_key(node)))
+            .collect()
+    }
+}
+
+impl Default for FullTextIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Inverse document frequency: rare tokens weigh more than common ones.
+fn idf(total: usize, matching: usize) -> f64 {
+    (1.0 + total as f64 / matching as f64).ln()
+}
+
+/// Sorts by descending score, then by node ID so ties are deterministic.
+fn rank(hits: &mut [(NodeId, f64)]) {
+    hits.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(hits: &[(NodeId, f64)]) -> Vec<u64> {
+        hits.iter().map(|(id, _)| id.as_u64()).collect()
+    }
+
+    fn sample() -> FullTextIndex {
+        let index = FullTextIndex::new();
+        index.insert(NodeId::new(1), "Graph databases store nodes and edges");
+        index.insert(
+            NodeId::new(2),
+            "A graph is nodes plus edges plus graph metadata",
+        );
+        index.insert(NodeId::new(3), "Relational databases store rows");
+        index
+    }
+
+    #[test]
+    fn test_tokenizer() {
+        let tokenizer = Tokenizer::new();
+        assert_eq!(
+            tokenizer.tokenize("  Hello\tWorld \n"),
+            vec!["hello", "world"]
+        );
+
+        let tokenizer = tokenizer.with_lowercase(false);
+        assert!(!tokenizer.lowercase());
+        assert_eq!(tokenizer.tokenize("Hello World"), vec!["Hello", "World"]);
+    }
+
+    #[test]
+    fn test_search_single_term() {
+        let index = sample();
+        assert_eq!(index.len(), 3);
+
+        // Node 2 mentions "graph" twice, so it ranks first.
+        assert_eq!(ids(&index.search("GRAPH")), vec![2, 1]);
+        assert_eq!(ids(&index.search("rows")), vec![3]);
+        assert!(index.search("missing").is_empty());
+        assert!(index.search("   ").is_empty());
+    }
+
+    #[test]
+    fn test_search_all_terms() {
+        let index = sample();
+        assert_eq!(ids(&index.search("databases store")), vec![1, 3]);
+        assert_eq!(ids(&index.search("edges graph")), vec![2, 1]);
+        assert!(index.search("graph rows").is_empty());
+    }
+
+    #[test]
+    fn test_search_phrase() {
+        let index = sample();
+        assert_eq!(ids(&index.search_phrase("databases store")), vec![1, 3]);
+        assert_eq!(ids(&index.search_phrase("nodes and edges")), vec![1]);
+        // Both words appear in node 2, but not next to each other.
+        assert!(index.search_phrase("graph edges").is_empty());
+        assert!(index.search_phrase("store databases").is_empty());
+    }
+
+    #[test]
+    fn test_update_and_remove() {
+        let index = sample();
+
+        index.insert(NodeId::new(3), "Graph analytics");
+        assert!(index.search("rows").is_empty());
+        assert_eq!(ids(&index.search("graph")), vec![2, 1, 3]);
+        assert_eq!(index.len(), 3);
+
+        assert!(index.remove(NodeId::new(2)));
+        assert!(!index.remove(NodeId::new(2)));
+        assert!(!index.contains_node(NodeId::new(2)));
+        assert_eq!(ids(&index.search("graph")).len(), 2);
+        assert!(index.search("metadata").is_empty());
+    }
+
+    #[test]
+    fn test_nodes_with_token_containing() {
+        let index = sample();
+        let nodes = index.nodes_with_token_containing("DATA").unwrap();
+        assert_eq!(nodes, vec![NodeId::new(1), NodeId::new(2), NodeId::new(3)]);
+        let nodes = index.nodes_with_token_containing("ow").unwrap();
+        assert_eq!(nodes, vec![NodeId::new(3)]);
+        assert!(index.nodes_with_token_containing("").is_none());
+    }
+}
@@ -3,10 +3,15 @@
 //! Use this when you need to find entities by exact key - like looking up
 //! a user by their unique username or finding a node by a primary key.
 
+use super::persist::{self, IndexKind};
 use grafeo_common::types::NodeId;
+use grafeo_common::utils::error::Result;
 use grafeo_common::utils::hash::FxHashMap;
 use parking_lot::RwLock;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::hash::Hash;
+use std::io::{Read, Write};
 
 /// A thread-safe hash index for O(1) key lookups.
 ///
@@ -90,6 +95,36 @@ impl<K: Hash + Eq, V: Copy> HashIndex<K, V> {
     }
 }
 
+impl<K, V> HashIndex<K, V>
+where
+    K: Hash + Eq + Serialize + DeserializeOwned,
+    V: Copy + Serialize + DeserializeOwned,
+{
+    /// Writes the index to `writer` so it can be loaded without a rebuild.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encoding or writing fails.
+    pub fn serialize(&self, writer: &mut impl Write) -> Result<()> {
+        let map = self.map.read();
+        let entries: Vec<(&K, &V)> = map.iter().collect();
+        persist::write_entries(writer, IndexKind::Hash, &entries)
+    }
+
+    /// Reads an index written by [`serialize`](Self::serialize).
+    ///
+    /// # Errors
+    ///
+    /// Returns a corruption error if the data isn't a hash index in the
+    /// current format or fails its checksum. Rebuild the index in that case.
+    pub fn deserialize(reader: &mut impl Read) -> Result<Self> {
+        let entries: Vec<(K, V)> = persist::read_entries(reader, IndexKind::Hash)?;
+        let index = Self::with_capacity(entries.len());
+        index.map.write().extend(entries);
+        Ok(index)
+    }
+}
+
 impl<K: Hash + Eq, V: Copy> Default for HashIndex<K, V> {
     fn default() -> Self {
         Self::new()
@@ -178,4 +213,43 @@ mod tests {
         assert_eq!(index.get(&"alice".to_string()), Some(NodeId::new(1)));
         assert_eq!(index.get(&"bob".to_string()), Some(NodeId::new(2)));
     }
+
+    #[test]
+    fn test_hash_index_round_trip() {
+        let index: StringKeyIndex = HashIndex::new();
+        for i in 0..100 {
+            index.insert(format!("user{i}"), NodeId::new(i));
+        }
+
+        let mut bytes = Vec::new();
+        index.serialize(&mut bytes).unwrap();
+        let loaded = StringKeyIndex::deserialize(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.len(), 100);
+        for i in 0..100 {
+            let key = format!("user{i}");
+            assert_eq!(loaded.get(&key), index.get(&key));
+        }
+        assert_eq!(loaded.get(&"nobody".to_string()), None);
+    }
+
+    #[test]
+    fn test_hash_index_rejects_corrupt_bytes() {
+        use grafeo_common::utils::error::{Error, StorageError};
+
+        let index: HashIndex<u64, NodeId> = HashIndex::new();
+        index.insert(1, NodeId::new(100));
+        index.insert(2, NodeId::new(200));
+
+        let mut bytes = Vec::new();
+        index.serialize(&mut bytes).unwrap();
+        let last = bytes.len() - 5;
+        bytes[last] ^= 0xFF;
+
+        let result = HashIndex::<u64, NodeId>::deserialize(&mut bytes.as_slice());
+        assert!(matches!(
+            result,
+            Err(Error::Storage(StorageError::Corruption(_)))
+        ));
+    }
 }
@@ -6,19 +6,31 @@
 //! | ----- | -------- | ---------- |
 //! | [`adjacency`] | Traversing neighbors | O(degree) |
 //! | [`hash`] | Point lookups by exact value | O(1) average |
+//! | [`spatial`] | Points within a radius or bounding box | O(log n) |
 //! | [`btree`] | Range queries like `age > 30` | O(log n) |
+//! | [`property`] | Node property lookups, kept up to date by the store | O(log n) |
+//! | [`fulltext`] | Word and phrase search in text | O(matching documents) |
 //! | [`trie`] | Multi-way joins | Worst-case optimal |
 //! | [`zone_map`] | Skipping chunks during scans | O(1) per chunk |
 //!
 //! Most queries use `adjacency` for traversals and `hash` or `btree` for filtering.
+//! Hash and BTree indexes can be written to disk and read back, so they
+//! don't have to be rebuilt when a database reopens.
 
 pub mod adjacency;
 pub mod btree;
+pub mod fulltext;
 pub mod hash;
+mod persist;
+pub mod property;
+pub mod spatial;
 pub mod trie;
 pub mod zone_map;
 
-pub use adjacency::ChunkedAdjacency;
-pub use btree::BTreeIndex;
+pub use adjacency::{BatchExpansion, ChunkedAdjacency};
+pub use btree::{BTreeIndex, ValueIndex, ValueKey};
+pub use fulltext::{FullTextIndex, Tokenizer};
 pub use hash::HashIndex;
+pub use property::PropertyIndex;
+pub use spatial::{BoundingBox, DistanceMetric, SpatialIndex};
 pub use zone_map::{BloomFilter, ZoneMapBuilder, ZoneMapEntry, ZoneMapIndex};
@@ -0,0 +1,107 @@
+//! On-disk format shared by the persistent indexes.
+//!
+//! An index is written as a small header followed by its entries:
+//!
+//! | Field | Size |
+//! | ----- | ---- |
+//! | Magic `GRIX` | 4 bytes |
+//! | Format version | u16 |
+//! | Index kind | u8 |
+//! | Entries length | u64 |
+//! | Entries (bincode) | variable |
+//! | CRC32 of the entries | u32 |
+//!
+//! Integers are little-endian. Reading checks every field, so a file from
+//! another format version, of the wrong kind, or with damaged entries is
+//! rejected instead of loaded.
+
+use std::io::{Read, Write};
+
+use grafeo_common::utils::error::{Error, Result, StorageError};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Identifies an index file.
+const MAGIC: [u8; 4] = *b"GRIX";
+
+/// Bumped whenever the layout or the entry encoding changes.
+pub(crate) const FORMAT_VERSION: u16 = 1;
+
+/// Which index type wrote a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub(crate) enum IndexKind {
+    Hash = 1,
+    BTree = 2,
+}
+
+/// Writes an index's entries with a header and checksum.
+pub(crate) fn write_entries<T: Serialize>(
+    writer: &mut impl Write,
+    kind: IndexKind,
+    entries: &T,
+) -> Result<()> {
+    let data = bincode::serde::encode_to_vec(entries, bincode::config::standard())
+        .map_err(|e| Error::Serialization(e.to_string()))?;
+
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+    writer.write_all(&[kind as u8])?;
+    writer.write_all(&(data.len() as u64).to_le_bytes())?;
+    writer.write_all(&data)?;
+    writer.write_all(&crc32fast::hash(&data).to_le_bytes())?;
+    Ok(())
+}
+
+/// Reads entries written by [`write_entries`], checking the header and
+/// checksum.
+pub(crate) fn read_entries<T: DeserializeOwned>(
+    reader: &mut impl Read,
+    kind: IndexKind,
+) -> Result<T> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if magic != MAGIC {
+        return Err(corruption("not an index file"));
+    }
+
+    let mut version = [0u8; 2];
+    reader.read_exact(&mut version)?;
+    let version = u16::from_le_bytes(version);
+    if version != FORMAT_VERSION {
+        return Err(corruption(&format!(
+            "index format version {version}, expected {FORMAT_VERSION}"
+        )));
+    }
+
+    let mut found_kind = [0u8; 1];
+    reader.read_exact(&mut found_kind)?;
+    if found_kind[0] != kind as u8 {
+        return Err(corruption(&format!("expected a {kind:?} index")));
+    }
+
+    let mut len = [0u8; 8];
+    reader.read_exact(&mut len)?;
+    let len = u64::from_le_bytes(len);
+
+    // Read through `take` so a damaged length can't force a huge allocation
+    let mut data = Vec::new();
+    reader.take(len).read_to_end(&mut data)?;
+    if data.len() as u64 != len {
+        return Err(corruption("index entries are truncated"));
+    }
+
+    let mut checksum = [0u8; 4];
+    reader.read_exact(&mut checksum)?;
+    if u32::from_le_bytes(checksum) != crc32fast::hash(&data) {
+        return Err(corruption("index checksum mismatch"));
+    }
+
+    let (entries, _) = bincode::serde::decode_from_slice(&data, bincode::config::standard())
+        .map_err(|e| Error::Serialization(e.to_string()))?;
+    Ok(entries)
+}
+
+fn corruption(msg: &str) -> Error {
+    Error::Storage(StorageError::Corruption(msg.to_string()))
+}
@@ -0,0 +1,185 @@
+//! Equality and range lookups over one node property.
+//!
+//! Backs the catalog's hash and BTree indexes. Several nodes can share a
+//! value, so entries are keyed by value and node together, ordered the way
+//! [`ValueKey`] orders values.
+
+use std::io::{Read, Write};
+use std::ops::Bound;
+
+use grafeo_common::types::{NodeId, Value};
+use grafeo_common::utils::error::Result;
+use grafeo_common::utils::hash::FxHashMap;
+use parking_lot::RwLock;
+
+use super::btree::{BTreeIndex, ValueKey};
+
+/// An index from the values of a node property to the nodes holding them.
+///
+/// # Example
+///
+/// ```
+/// use grafeo_core::index::PropertyIndex;
+/// use grafeo_common::types::{NodeId, Value};
+///
+/// let index = PropertyIndex::new();
+/// index.insert(NodeId::new(1), Value::from(30i64));
+/// index.insert(NodeId::new(2), Value::from(30i64));
+/// index.insert(NodeId::new(3), Value::from(45i64));
+///
+/// assert_eq!(index.nodes_with(&Value::from(30i64)).len(), 2);
+/// let older = index.nodes_between(&Value::from(31i64), &Value::from(50i64));
+/// assert_eq!(older, vec![NodeId::new(3)]);
+/// ```
+pub struct PropertyIndex {
+    /// One entry per indexed node, ordered by value, then node.
+    entries: BTreeIndex<(ValueKey, NodeId), NodeId>,
+    /// Each indexed node's value, to find its entry when it changes.
+    values: RwLock<FxHashMap<NodeId, ValueKey>>,
+}
+
+impl PropertyIndex {
+    /// Creates an empty index.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            entries: BTreeIndex::new(),
+            values: RwLock::new(FxHashMap::default()),
+        }
+    }
+
+    /// Indexes `id` under `value`, replacing any value it was indexed under.
+    pub fn insert(&self, id: NodeId, value: Value) {
+        let key = ValueKey(value);
+        if let Some(old) = self.values.write().insert(id, key.clone()) {
+            self.entries.remove(&(old, id));
+        }
+        self.entries.insert((key, id), id);
+    }
+
+    /// Removes `id` from the index.
+    ///
+    /// Returns true if it was indexed.
+    pub fn remove(&self, id: NodeId) -> bool {
+        match self.values.write().remove(&id) {
+            Some(old) => {
+                self.entries.remove(&(old, id));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the nodes indexed under `value`, in id order.
+    #[must_use]
+    pub fn nodes_with(&self, value: &Value) -> Vec<NodeId> {
+        self.nodes_between(value, value)
+    }
+
+    /// Returns the nodes with values from `low` to `high`, both included,
+    /// in value order.
+    #[must_use]
+    pub fn nodes_between(&self, low: &Value, high: &Value) -> Vec<NodeId> {
+        let start = (ValueKey(low.clone()), NodeId::new(0));
+        let end = (ValueKey(high.clone()), NodeId::INVALID);
+        self.entries
+            .range((Bound::Included(start), Bound::Included(end)))
+            .map(|(_, id)| id)
+            .collect()
+    }
+
+    /// Returns the number of indexed nodes.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if no node is indexed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the index to `writer` so it can be loaded without a rebuild.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if encoding or writing fails.
+    pub fn serialize(&self, writer: &mut impl Write) -> Result<()> {
+        self.entries.serialize(writer)
+    }
+
+    /// Reads an index written by [`serialize`](Self::serialize).
+    ///
+    /// # Errors
+    ///
+    /// Returns a corruption error if the data isn't an index in the current
+    /// format or fails its checksum. Rebuild the index in that case.
+    pub fn deserialize(reader: &mut impl Read) -> Result<Self> {
+        let entries: BTreeIndex<(ValueKey, NodeId), NodeId> = BTreeIndex::deserialize(reader)?;
+        let values = entries.range(..).map(|((key, _), id)| (id, key)).collect();
+        Ok(Self {
+            entries,
+            values: RwLock::new(values),
+        })
+    }
+}
+
+impl Default for PropertyIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_property_index_follows_changes() {
+        let index = PropertyIndex::new();
+        index.insert(NodeId::new(1), Value::from("Alice"));
+        index.insert(NodeId::new(2), Value::from("Alice"));
+        index.insert(NodeId::new(3), Value::from("Bob"));
+
+        assert_eq!(
+            index.nodes_with(&Value::from("Alice")),
+            vec![NodeId::new(1), NodeId::new(2)]
+        );
+
+        index.insert(NodeId::new(2), Value::from("Bob"));
+        assert!(index.remove(NodeId::new(3)));
+        assert!(!index.remove(NodeId::new(3)));
+        assert_eq!(
+            index.nodes_with(&Value::from("Alice")),
+            vec![NodeId::new(1)]
+        );
+        assert_eq!(index.nodes_with(&Value::from("Bob")), vec![NodeId::new(2)]);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn test_property_index_round_trip() {
+        let index = PropertyIndex::new();
+        for i in 0..100 {
+            index.insert(NodeId::new(i), Value::Int64((i % 10) as i64));
+        }
+
+        let mut bytes = Vec::new();
+        index.serialize(&mut bytes).unwrap();
+        let loaded = PropertyIndex::deserialize(&mut bytes.as_slice()).unwrap();
+
+        assert_eq!(loaded.len(), 100);
+        assert_eq!(
+            loaded.nodes_between(&Value::Int64(2), &Value::Int64(3)),
+            index.nodes_between(&Value::Int64(2), &Value::Int64(3))
+        );
+        // The loaded index still knows each node's value
+        loaded.insert(NodeId::new(7), Value::Int64(42));
+        assert_eq!(loaded.nodes_with(&Value::Int64(7)).len(), 9);
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        assert!(PropertyIndex::deserialize(&mut bytes.as_slice()).is_err());
+    }
+}
@@ -0,0 +1,547 @@
+//! R-tree index for finding points near a location.
+//!
+//! Points are grouped into nested bounding boxes, so a radius or
+//! bounding-box query only visits the boxes that overlap the search area
+//! instead of every point.
+//!
+//! Distances are either Euclidean, for points on a flat plane, or haversine,
+//! for longitude/latitude points on the Earth. See [`DistanceMetric`].
+
+use grafeo_common::types::{NodeId, Value};
+use grafeo_common::utils::hash::FxHashMap;
+use parking_lot::RwLock;
+
+/// Mean Earth radius in meters, used for haversine distances.
+pub const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+/// Most entries an R-tree node holds before it splits.
+const MAX_ENTRIES: usize = 16;
+
+/// How the distance between two points is measured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DistanceMetric {
+    /// Straight-line distance on a plane, in coordinate units.
+    Euclidean,
+    /// Great-circle distance between longitude/latitude points, in meters.
+    Haversine,
+}
+
+impl DistanceMetric {
+    /// Returns the natural metric for points in `srid`: haversine for
+    /// WGS 84, Euclidean for everything else.
+    #[must_use]
+    pub const fn for_srid(srid: u32) -> Self {
+        if srid == Value::WGS84_SRID {
+            Self::Haversine
+        } else {
+            Self::Euclidean
+        }
+    }
+
+    /// Parses a metric name (`"euclidean"` or `"haversine"`), ignoring case.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.eq_ignore_ascii_case("euclidean") {
+            Some(Self::Euclidean)
+        } else if name.eq_ignore_ascii_case("haversine") {
+            Some(Self::Haversine)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the distance between `(x, y)` points `a` and `b`.
+    ///
+    /// For haversine, `x` is the longitude and `y` the latitude in degrees.
+    #[must_use]
+    pub fn distance(self, a: (f64, f64), b: (f64, f64)) -> f64 {
+        match self {
+            Self::Euclidean => (a.0 - b.0).hypot(a.1 - b.1),
+            Self::Haversine => {
+                let (lat_a, lat_b) = (a.1.to_radians(), b.1.to_radians());
+                let half_dlat = (lat_b - lat_a) / 2.0;
+                let half_dlon = (b.0 - a.0).to_radians() / 2.0;
+                let h =
+                    half_dlat.sin().powi(2) + lat_a.cos() * lat_b.cos() * half_dlon.sin().powi(2);
+                2.0 * EARTH_RADIUS_METERS * h.sqrt().min(1.0).asin()
+            }
+        }
+    }
+
+    /// Returns a box holding every point within `radius` of `center`.
+    fn bounds(self, center: (f64, f64), radius: f64) -> BoundingBox {
+        match self {
+            Self::Euclidean => BoundingBox::new(
+                center.0 - radius,
+                center.1 - radius,
+                center.0 + radius,
+                center.1 + radius,
+            ),
+            Self::Haversine => {
+                let angle = radius / EARTH_RADIUS_METERS;
+                let dlat = angle.to_degrees();
+                let (min_lat, max_lat) = (center.1 - dlat, center.1 + dlat);
+                // Near the poles or across the antimeridian, any longitude
+                // can be in range
+                let (min_lon, max_lon) = if min_lat <= -90.0 || max_lat >= 90.0 {
+                    (f64::NEG_INFINITY, f64::INFINITY)
+                } else {
+                    let ratio = angle.sin() / center.1.to_radians().cos();
+                    let dlon = if ratio >= 1.0 {
+                        f64::INFINITY
+                    } else {
+                        ratio.asin().to_degrees()
+                    };
+                    if center.0 - dlon < -180.0 || center.0 + dlon > 180.0 {
+                        (f64::NEG_INFINITY, f64::INFINITY)
+                    } else {
+                        (center.0 - dlon, center.0 + dlon)
+                    }
+                };
+                BoundingBox::new(min_lon, min_lat, max_lon, max_lat)
+            }
+        }
+    }
+}
+
+/// An axis-aligned rectangle, bounds included.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// Smallest x (or longitude).
+    pub min_x: f64,
+    /// Smallest y (or latitude).
+    pub min_y: f64,
+    /// Largest x (or longitude).
+    pub max_x: f64,
+    /// Largest y (or latitude).
+    pub max_y: f64,
+}
+
+impl BoundingBox {
+    /// Creates a box from its lower-left and upper-right corners.
+    #[must_use]
+    pub const fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    /// Creates an empty box at a single point.
+    #[must_use]
+    pub const fn point(x: f64, y: f64) -> Self {
+        Self::new(x, y, x, y)
+    }
+
+    /// Returns true if `(x, y)` lies inside the box or on its edge.
+    #[must_use]
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        self.min_x <= x && x <= self.max_x && self.min_y <= y && y <= self.max_y
+    }
+
+    /// Returns true if the boxes share at least one point.
+    #[must_use]
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+
+    /// Returns the smallest box covering both boxes.
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            self.min_x.min(other.min_x),
+            self.min_y.min(other.min_y),
+            self.max_x.max(other.max_x),
+            self.max_y.max(other.max_y),
+        )
+    }
+
+    fn area(&self) -> f64 {
+        (self.max_x - self.min_x) * (self.max_y - self.min_y)
+    }
+
+    fn center(&self) -> (f64, f64) {
+        (
+            f64::midpoint(self.min_x, self.max_x),
+            f64::midpoint(self.min_y, self.max_y),
+        )
+    }
+}
+
+enum RNode {
+    Leaf(Vec<(f64, f64, NodeId)>),
+    Branch(Vec<(BoundingBox, RNode)>),
+}
+
+impl RNode {
+    fn bounds(&self) -> Option<BoundingBox> {
+        match self {
+            Self::Leaf(points) => points
+                .iter()
+                .map(|&(x, y, _)| BoundingBox::point(x, y))
+                .reduce(|a, b| a.union(&b)),
+            Self::Branch(children) => children.iter().map(|(b, _)| *b).reduce(|a, b| a.union(&b)),
+        }
+    }
+
+    /// Inserts a point, returning the new sibling if this node split.
+    fn insert(&mut self, x: f64, y: f64, id: NodeId) -> Option<RNode> {
+        match self {
+            Self::Leaf(points) => {
+                points.push((x, y, id));
+                (points.len() > MAX_ENTRIES).then(|| Self::Leaf(split_half(points, |p| (p.0, p.1))))
+            }
+            Self::Branch(children) => {
+                let point = BoundingBox::point(x, y);
+                let best = children
+                    .iter()
+                    .enumerate()
+                    .min_by(|(_, (a, _)), (_, (b, _))| {
+                        let growth =
+                            |bounds: &BoundingBox| bounds.union(&point).area() - bounds.area();
+                        growth(a)
+                            .total_cmp(&growth(b))
+                            .then(a.area().total_cmp(&b.area()))
+                    })
+                    .map(|(i, _)| i)
+                    .expect("branches are never empty");
+
+                let (bounds, child) = &mut children[best];
+                *bounds = bounds.union(&point);
+                if let Some(sibling) = child.insert(x, y, id) {
+                    *bounds = child.bounds().expect("split nodes keep entries");
+                    let sibling_bounds = sibling.bounds().expect("split nodes keep entries");
+                    children.push((sibling_bounds, sibling));
+                }
+                (children.len() > MAX_ENTRIES)
+                    .then(|| Self::Branch(split_half(children, |(b, _)| b.center())))
+            }
+        }
+    }
+
+    /// Removes the point for `id` at `(x, y)`, returning true if found.
+    fn remove(&mut self, x: f64, y: f64, id: NodeId) -> bool {
+        match self {
+            Self::Leaf(points) => {
+                let before = points.len();
+                points.retain(|&(_, _, p)| p != id);
+                points.len() < before
+            }
+            Self::Branch(children) => {
+                let Some(i) = children
+                    .iter_mut()
+                    .position(|(bounds, child)| bounds.contains(x, y) && child.remove(x, y, id))
+                else {
+                    return false;
+                };
+                match children[i].1.bounds() {
+                    Some(bounds) => children[i].0 = bounds,
+                    None => {
+                        children.swap_remove(i);
+                    }
+                }
+                true
+            }
+        }
+    }
+
+    fn search(&self, area: &BoundingBox, out: &mut Vec<(f64, f64, NodeId)>) {
+        match self {
+            Self::Leaf(points) => out.extend(points.iter().filter(|p| area.contains(p.0, p.1))),
+            Self::Branch(children) => {
+                for (bounds, child) in children {
+                    if bounds.intersects(area) {
+                        child.search(area, out);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Sorts `entries` along their wider axis and moves the upper half out.
+fn split_half<T>(entries: &mut Vec<T>, center: impl Fn(&T) -> (f64, f64)) -> Vec<T> {
+    let spread = |axis: fn((f64, f64)) -> f64| {
+        let values = entries.iter().map(|e| axis(center(e)));
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), v| {
+            (lo.min(v), hi.max(v))
+        });
+        max - min
+    };
+    if spread(|c| c.0) >= spread(|c| c.1) {
+        entries.sort_by(|a, b| center(a).0.total_cmp(&center(b).0));
+    } else {
+        entries.sort_by(|a, b| center(a).1.total_cmp(&center(b).1));
+    }
+    let half = entries.len() / 2;
+    entries.split_off(half)
+}
+
+struct Tree {
+    root: RNode,
+    /// Node -> its point and SRID, so it can be found again for removal.
+    locations: FxHashMap<NodeId, (f64, f64, u32)>,
+    /// SRID -> number of indexed points in it.
+    srids: FxHashMap<u32, usize>,
+}
+
+/// A thread-safe R-tree over point values, keyed by node.
+///
+/// Each node has at most one point. Inserting a node again moves it. Points
+/// of different SRIDs share one tree, so check [`srids`](Self::srids)
+/// before comparing coordinates across them.
+///
+/// # Example
+///
+/// ```
+/// use grafeo_core::index::{DistanceMetric, SpatialIndex};
+/// use grafeo_common::types::NodeId;
+///
+/// let index = SpatialIndex::new();
+/// index.insert(NodeId::new(1), 0.0, 0.0, 7203);
+/// index.insert(NodeId::new(2), 3.0, 4.0, 7203);
+/// index.insert(NodeId::new(3), 10.0, 10.0, 7203);
+///
+/// let near = index.within_distance((0.0, 0.0), 5.0, DistanceMetric::Euclidean);
+/// assert_eq!(near, vec![NodeId::new(1), NodeId::new(2)]);
+/// ```
+pub struct SpatialIndex {
+    tree: RwLock<Tree>,
+}
+
+impl SpatialIndex {
+    /// Creates an empty index.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tree: RwLock::new(Tree {
+                root: RNode::Leaf(Vec::new()),
+                locations: FxHashMap::default(),
+                srids: FxHashMap::default(),
+            }),
+        }
+    }
+
+    /// Indexes `node` at `(x, y)` in `srid`, replacing its previous point.
+    pub fn insert(&self, node: NodeId, x: f64, y: f64, srid: u32) {
+        let mut tree = self.tree.write();
+        Self::remove_locked(&mut tree, node);
+
+        if let Some(sibling) = tree.root.insert(x, y, node) {
+            let old = std::mem::replace(&mut tree.root, RNode::Branch(Vec::new()));
+            let old_bounds = old.bounds().expect("split nodes keep entries");
+            let sibling_bounds = sibling.bounds().expect("split nodes keep entries");
+            tree.root = RNode::Branch(vec![(old_bounds, old), (sibling_bounds, sibling)]);
+        }
+        tree.locations.insert(node, (x, y, srid));
+        *tree.srids.entry(srid).or_default() += 1;
+    }
+
+    /// Removes the point for `node`.
+    ///
+    /// Returns true if the node was indexed.
+    pub fn remove(&self, node: NodeId) -> bool {
+        Self::remove_locked(&mut self.tree.write(), node)
+    }
+
+    fn remove_locked(tree: &mut Tree, node: NodeId) -> bool {
+        let Some((x, y, srid)) = tree.locations.remove(&node) else {
+            return false;
+        };
+        tree.root.remove(x, y, node);
+        // Collapse roots left with a single child
+        while let RNode::Branch(children) = &mut tree.root {
+            match children.len() {
+                0 => tree.root = RNode::Leaf(Vec::new()),
+                1 => tree.root = children.pop().expect("one child").1,
+                _ => break,
+            }
+        }
+        if let Some(count) = tree.srids.get_mut(&srid) {
+            *count -= 1;
+            if *count == 0 {
+                tree.srids.remove(&srid);
+            }
+        }
+        true
+    }
+
+    /// Returns the number of indexed points.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.tree.read().locations.len()
+    }
+
+    /// Returns true if nothing is indexed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the SRIDs of the indexed points, sorted.
+    #[must_use]
+    pub fn srids(&self) -> Vec<u32> {
+        let mut srids: Vec<u32> = self.tree.read().srids.keys().copied().collect();
+        srids.sort_unstable();
+        srids
+    }
+
+    /// Returns the nodes whose point lies inside `area`, sorted by node ID.
+    #[must_use]
+    pub fn within_bbox(&self, area: BoundingBox) -> Vec<NodeId> {
+        let mut found = Vec::new();
+        self.tree.read().root.search(&area, &mut found);
+        let mut nodes: Vec<NodeId> = found.into_iter().map(|(_, _, id)| id).collect();
+        nodes.sort_unstable();
+        nodes
+    }
+
+    /// Returns the nodes whose point is at most `radius` from `center`
+    /// under `metric`, sorted by node ID.
+    #[must_use]
+    pub fn within_distance(
+        &self,
+        center: (f64, f64),
+        radius: f64,
+        metric: DistanceMetric,
+    ) -> Vec<NodeId> {
+        let mut found = Vec::new();
+        self.tree
+            .read()
+            .root
+            .search(&metric.bounds(center, radius), &mut found);
+        let mut nodes: Vec<NodeId> = found
+            .into_iter()
+            .filter(|&(x, y, _)| metric.distance(center, (x, y)) <= radius)
+            .map(|(_, _, id)| id)
+            .collect();
+        nodes.sort_unstable();
+        nodes
+    }
+}
+
+impl Default for SpatialIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> SpatialIndex {
+        // A 20x20 grid is big enough to split nodes several times
+        let index = SpatialIndex::new();
+        for i in 0..400u64 {
+            index.insert(NodeId::new(i), (i % 20) as f64, (i / 20) as f64, 7203);
+        }
+        index
+    }
+
+    fn brute_force(center: (f64, f64), radius: f64) -> Vec<NodeId> {
+        (0..400u64)
+            .filter(|i| {
+                let point = ((i % 20) as f64, (i / 20) as f64);
+                DistanceMetric::Euclidean.distance(center, point) <= radius
+            })
+            .map(NodeId::new)
+            .collect()
+    }
+
+    #[test]
+    fn test_metrics() {
+        let euclidean = DistanceMetric::Euclidean.distance((0.0, 0.0), (3.0, 4.0));
+        assert!((euclidean - 5.0).abs() < 1e-9);
+
+        // London to Paris is about 344 km
+        let haversine = DistanceMetric::Haversine.distance((-0.1276, 51.5072), (2.3522, 48.8566));
+        assert!((haversine - 343_500.0).abs() < 1_500.0, "{haversine}");
+
+        assert_eq!(DistanceMetric::for_srid(4326), DistanceMetric::Haversine);
+        assert_eq!(DistanceMetric::for_srid(7203), DistanceMetric::Euclidean);
+        assert_eq!(
+            DistanceMetric::from_name("HAVERSINE"),
+            Some(DistanceMetric::Haversine)
+        );
+        assert_eq!(DistanceMetric::from_name("manhattan"), None);
+    }
+
+    #[test]
+    fn test_within_distance_matches_brute_force() {
+        let index = grid();
+        assert_eq!(index.len(), 400);
+        for (center, radius) in [((5.0, 5.0), 3.0), ((0.0, 0.0), 1.5), ((19.5, 10.0), 0.4)] {
+            assert_eq!(
+                index.within_distance(center, radius, DistanceMetric::Euclidean),
+                brute_force(center, radius)
+            );
+        }
+    }
+
+    #[test]
+    fn test_within_bbox() {
+        let index = grid();
+        let found = index.within_bbox(BoundingBox::new(2.0, 3.0, 3.0, 4.0));
+        let expected: Vec<NodeId> = [62, 63, 82, 83].into_iter().map(NodeId::new).collect();
+        assert_eq!(found, expected);
+        assert!(
+            index
+                .within_bbox(BoundingBox::new(30.0, 30.0, 40.0, 40.0))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_move_and_remove() {
+        let index = grid();
+        index.insert(NodeId::new(0), 100.0, 100.0, 7203);
+        assert_eq!(index.len(), 400);
+        assert!(
+            !index
+                .within_distance((0.0, 0.0), 0.5, DistanceMetric::Euclidean)
+                .contains(&NodeId::new(0))
+        );
+        assert_eq!(
+            index.within_distance((100.0, 100.0), 0.5, DistanceMetric::Euclidean),
+            vec![NodeId::new(0)]
+        );
+
+        for i in 0..400 {
+            assert!(index.remove(NodeId::new(i)));
+        }
+        assert!(!index.remove(NodeId::new(0)));
+        assert!(index.is_empty());
+        assert!(index.srids().is_empty());
+        assert!(
+            index
+                .within_bbox(BoundingBox::new(-1e9, -1e9, 1e9, 1e9))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_haversine_radius() {
+        let index = SpatialIndex::new();
+        index.insert(NodeId::new(1), -0.1276, 51.5072, 4326); // London
+        index.insert(NodeId::new(2), 2.3522, 48.8566, 4326); // Paris
+        index.insert(NodeId::new(3), 13.4050, 52.5200, 4326); // Berlin
+        index.insert(NodeId::new(4), 179.9, 0.0, 4326);
+        index.insert(NodeId::new(5), -179.9, 0.0, 4326);
+        assert_eq!(index.srids(), vec![4326]);
+
+        let near_london =
+            index.within_distance((-0.1276, 51.5072), 400_000.0, DistanceMetric::Haversine);
+        assert_eq!(near_london, vec![NodeId::new(1), NodeId::new(2)]);
+
+        // Across the antimeridian
+        let near_dateline =
+            index.within_distance((180.0, 0.0), 50_000.0, DistanceMetric::Haversine);
+        assert_eq!(near_dateline, vec![NodeId::new(4), NodeId::new(5)]);
+    }
+}
@@ -453,7 +453,7 @@ impl StatisticsCollector {
         // Find most common values
         let total_non_null = self.values.len() as f64;
         let mut freq_vec: Vec<_> = self.frequencies.into_iter().collect();
-        freq_vec.sort_by(|a, b| b.1.cmp(&a.1));
+        freq_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
 
         let most_common: Vec<(Value, f64)> = freq_vec
             .into_iter()
@@ -526,7 +526,7 @@ fn compare_values(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
         (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
         (Value::Int64(a), Value::Float64(b)) => (*a as f64).partial_cmp(b),
         (Value::Float64(a), Value::Int64(b)) => a.partial_cmp(&(*b as f64)),
-        _ => None,
+        _ => a.compare_temporal(b),
     }
 }
 
@@ -0,0 +1,190 @@
+//! HyperLogLog sketches for approximate distinct counts.
+//!
+//! Counting distinct values exactly needs memory proportional to the number
+//! of distinct values. A HyperLogLog sketch uses a fixed `2^precision` bytes
+//! and estimates the count with a standard error of about
+//! `1.04 / sqrt(2^precision)` - roughly 1.6% at the default precision of 12.
+//!
+//! Reference: Flajolet, P., Fusy, É., Gandouet, O., & Meunier, F. (2007).
+//! HyperLogLog: the analysis of a near-optimal cardinality estimation
+//! algorithm.
+
+use grafeo_common::types::Value;
+use grafeo_common::utils::hash::hash_one;
+
+use crate::execution::operators::HashKey;
+
+/// Default number of index bits, giving 4096 registers.
+pub const DEFAULT_HLL_PRECISION: u8 = 12;
+
+/// Approximate distinct-value counter with a fixed memory footprint.
+///
+/// # Examples
+///
+/// ```
+/// use grafeo_common::types::Value;
+/// use grafeo_core::statistics::HyperLogLog;
+///
+/// let mut hll = HyperLogLog::new();
+/// for i in 0..1000 {
+///     hll.add(&Value::Int64(i % 100));
+/// }
+/// let estimate = hll.estimate();
+/// assert!((95..=105).contains(&estimate));
+/// ```
+#[derive(Debug, Clone)]
+pub struct HyperLogLog {
+    /// Number of hash bits used to pick a register.
+    precision: u8,
+    /// Highest leading-zero rank seen per register.
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    /// Creates an empty sketch with the default precision.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_precision(DEFAULT_HLL_PRECISION)
+    }
+
+    /// Creates an empty sketch with `2^precision` registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `precision` is outside `4..=16`.
+    #[must_use]
+    pub fn with_precision(precision: u8) -> Self {
+        assert!(
+            (4..=16).contains(&precision),
+            "HyperLogLog precision must be between 4 and 16, got {precision}"
+        );
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+        }
+    }
+
+    /// Adds a value to the sketch.
+    ///
+    /// Values that compare equal as join keys (like `1.0` and its bit
+    /// pattern) hash alike, so they count once.
+    pub fn add(&mut self, value: &Value) {
+        self.add_hash(hash_one(&HashKey::from_value(value)));
+    }
+
+    /// Adds a precomputed 64-bit hash to the sketch.
+    pub fn add_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - self.precision)) as usize;
+        // Rank of the first set bit in the remaining bits, counting from 1
+        let rest = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Folds another sketch into this one, as if its values were added here.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sketches have different precisions.
+    pub fn merge(&mut self, other: &Self) {
+        assert_eq!(
+            self.precision, other.precision,
+            "can only merge HyperLogLog sketches of equal precision"
+        );
+        for (mine, theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *mine = (*mine).max(*theirs);
+        }
+    }
+
+    /// Returns the estimated number of distinct values added.
+    #[must_use]
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let (sum, zeros) = self
+            .registers
+            .iter()
+            .fold((0.0, 0usize), |(sum, zeros), &rank| {
+                (
+                    sum + 2f64.powi(-i32::from(rank)),
+                    zeros + usize::from(rank == 0),
+                )
+            });
+        let raw = alpha * m * m / sum;
+
+        // Small cardinalities: linear counting over the empty registers is
+        // far more accurate
+        let estimate = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as u64
+    }
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Adds `value` hashed with fixed seeds, so estimates don't vary between
+    /// runs the way they do with the per-process seed of [`HyperLogLog::add`].
+    fn add_seeded(hll: &mut HyperLogLog, value: &Value) {
+        let state = ahash::RandomState::with_seeds(1, 2, 3, 4);
+        hll.add_hash(state.hash_one(HashKey::from_value(value)));
+    }
+
+    /// Whether `estimate` is within `tolerance` of `exact`, or within 2 of
+    /// it for counts too small for a relative tolerance to allow any error.
+    fn within(estimate: u64, exact: u64, tolerance: f64) -> bool {
+        (estimate as f64 - exact as f64).abs() <= (exact as f64 * tolerance).max(2.0)
+    }
+
+    #[test]
+    fn test_empty_sketch_estimates_zero() {
+        assert_eq!(HyperLogLog::new().estimate(), 0);
+    }
+
+    #[test]
+    fn test_estimates_within_tolerance() {
+        for exact in [10u64, 1_000, 50_000] {
+            let mut hll = HyperLogLog::new();
+            for i in 0..exact {
+                add_seeded(&mut hll, &Value::Int64(i as i64));
+                // Duplicates don't count again
+                add_seeded(&mut hll, &Value::Int64(i as i64));
+            }
+            let estimate = hll.estimate();
+            assert!(within(estimate, exact, 0.08), "{estimate} vs {exact}");
+        }
+    }
+
+    #[test]
+    fn test_strings_and_merge() {
+        let mut left = HyperLogLog::new();
+        let mut right = HyperLogLog::new();
+        for i in 0..3000 {
+            add_seeded(&mut left, &Value::from(format!("user-{i}").as_str()));
+        }
+        for i in 2000..5000 {
+            add_seeded(&mut right, &Value::from(format!("user-{i}").as_str()));
+        }
+        left.merge(&right);
+
+        let estimate = left.estimate();
+        assert!(within(estimate, 5000, 0.08), "{estimate}");
+    }
+}
@@ -9,16 +9,18 @@
 //! | Label cardinality | How many nodes have this label |
 //! | Property histograms | Distribution of values for range predicates |
 //! | Degree stats | How many edges per node (affects traversal cost) |
-//! | Distinct counts | Selectivity of equality predicates |
+//! | Distinct counts | Selectivity of equality predicates (estimated with [`HyperLogLog`]) |
 
 mod collector;
 mod histogram;
+mod hyperloglog;
 mod rdf;
 
 pub use collector::{
     ColumnStatistics, EdgeTypeStatistics, LabelStatistics, PropertyKey, Statistics, TableStatistics,
 };
 pub use histogram::{Histogram, HistogramBucket};
+pub use hyperloglog::{DEFAULT_HLL_PRECISION, HyperLogLog};
 pub use rdf::{
     IndexStatistics, PredicateStatistics, RdfStatistics, RdfStatisticsCollector, TriplePosition,
 };
@@ -0,0 +1,288 @@
+//! Weakly and strongly connected components.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use grafeo_common::types::NodeId;
+use grafeo_core::graph::lpg::LpgStore;
+
+use super::{Subgraph, run_morsels};
+
+/// Settings for [`Session::connected_components_with()`](crate::Session::connected_components_with)
+/// and [`Session::strongly_connected_components_with()`](crate::Session::strongly_connected_components_with).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComponentsConfig {
+    /// Only include nodes with this label.
+    pub label: Option<String>,
+    /// Only follow edges of this type.
+    pub edge_type: Option<String>,
+}
+
+impl ComponentsConfig {
+    /// Creates the default settings, covering the whole graph.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Includes only the nodes with `label`, and the edges between them.
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Follows only edges of `edge_type`.
+    #[must_use]
+    pub fn with_edge_type(mut self, edge_type: impl Into<String>) -> Self {
+        self.edge_type = Some(edge_type.into());
+        self
+    }
+}
+
+/// Labels weakly connected components, ignoring edge direction.
+pub(crate) fn connected_components(
+    store: &LpgStore,
+    config: &ComponentsConfig,
+) -> HashMap<NodeId, u64> {
+    let graph = Subgraph::build(store, config.label.as_deref(), config.edge_type.as_deref());
+    let sets = DisjointSets::new(graph.len());
+    run_morsels(graph.len(), |range| {
+        for u in range {
+            for &v in graph.neighbors(u) {
+                sets.union(u, v);
+            }
+        }
+    });
+    let roots: Vec<usize> = (0..graph.len()).map(|i| sets.find(i)).collect();
+    number_components(&graph, &roots)
+}
+
+/// Labels strongly connected components with Tarjan's algorithm.
+///
+/// Tarjan's depth-first search is sequential; only reading the graph runs
+/// in parallel.
+pub(crate) fn strongly_connected_components(
+    store: &LpgStore,
+    config: &ComponentsConfig,
+) -> HashMap<NodeId, u64> {
+    let graph = Subgraph::build(store, config.label.as_deref(), config.edge_type.as_deref());
+    let roots = tarjan(&graph);
+    number_components(&graph, &roots)
+}
+
+/// Numbers components from 0 in order of their smallest node id, given any
+/// representative per node.
+fn number_components(graph: &Subgraph, representatives: &[usize]) -> HashMap<NodeId, u64> {
+    let mut numbers: HashMap<usize, u64> = HashMap::new();
+    (0..graph.len())
+        .map(|i| {
+            let next = numbers.len() as u64;
+            let component = *numbers.entry(representatives[i]).or_insert(next);
+            (graph.node_id(i), component)
+        })
+        .collect()
+}
+
+/// Union-find that many threads can merge into at once.
+///
+/// Each set is rooted at its smallest member, and a union only ever links a
+/// root to a smaller index, so concurrent unions settle on the same roots.
+struct DisjointSets {
+    parents: Vec<AtomicUsize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).map(AtomicUsize::new).collect(),
+        }
+    }
+
+    fn find(&self, mut x: usize) -> usize {
+        loop {
+            let parent = self.parents[x].load(Ordering::Acquire);
+            if parent == x {
+                return x;
+            }
+            // Path halving; losing the race only skips a shortcut
+            let grandparent = self.parents[parent].load(Ordering::Acquire);
+            let _ = self.parents[x].compare_exchange(
+                parent,
+                grandparent,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            );
+            x = grandparent;
+        }
+    }
+
+    fn union(&self, a: usize, b: usize) {
+        loop {
+            let (a_root, b_root) = (self.find(a), self.find(b));
+            if a_root == b_root {
+                return;
+            }
+            let (high, low) = if a_root > b_root {
+                (a_root, b_root)
+            } else {
+                (b_root, a_root)
+            };
+            if self.parents[high]
+                .compare_exchange(high, low, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return;
+            }
+        }
+    }
+}
+
+/// Returns, for each node, the root of its strongly connected component.
+///
+/// Iterative, so long paths don't overflow the stack.
+fn tarjan(graph: &Subgraph) -> Vec<usize> {
+    const UNVISITED: usize = usize::MAX;
+    let n = graph.len();
+    let mut index = vec![UNVISITED; n];
+    let mut low = vec![0; n];
+    let mut on_stack = vec![false; n];
+    let mut stack = Vec::new();
+    let mut roots = vec![0; n];
+    let mut next_index = 0;
+    // Depth-first frames: node and position in its neighbour list
+    let mut frames: Vec<(usize, usize)> = Vec::new();
+
+    for start in 0..n {
+        if index[start] != UNVISITED {
+            continue;
+        }
+        frames.push((start, 0));
+        while let Some(&mut (v, ref mut edge)) = frames.last_mut() {
+            if *edge == 0 && index[v] == UNVISITED {
+                index[v] = next_index;
+                low[v] = next_index;
+                next_index += 1;
+                stack.push(v);
+                on_stack[v] = true;
+            }
+            if let Some(&w) = graph.neighbors(v).get(*edge) {
+                *edge += 1;
+                if index[w] == UNVISITED {
+                    frames.push((w, 0));
+                } else if on_stack[w] {
+                    low[v] = low[v].min(index[w]);
+                }
+                continue;
+            }
+
+            frames.pop();
+            if let Some(&(parent, _)) = frames.last() {
+                low[parent] = low[parent].min(low[v]);
+            }
+            if low[v] == index[v] {
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    roots[w] = v;
+                    if w == v {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    roots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GrafeoDB;
+
+    /// Two clusters joined by KNOWS edges, plus one isolated node 6:
+    /// 0 -> 1 -> 2 -> 0 (a cycle), 2 -> 3, and 4 -> 5 -> 4 with a self-loop
+    /// on 5.
+    fn clusters(db: &GrafeoDB) -> [NodeId; 7] {
+        let ids: [NodeId; 7] = std::array::from_fn(|_| db.create_node(&["Item"]));
+        for (src, dst) in [(0, 1), (1, 2), (2, 0), (2, 3), (4, 5), (5, 4), (5, 5)] {
+            db.create_edge(ids[src], ids[dst], "KNOWS");
+        }
+        ids
+    }
+
+    fn assignment(components: &HashMap<NodeId, u64>, ids: &[NodeId]) -> Vec<u64> {
+        ids.iter().map(|id| components[id]).collect()
+    }
+
+    #[test]
+    fn test_weak_components_of_two_clusters_and_an_isolated_node() {
+        let db = GrafeoDB::new_in_memory();
+        let ids = clusters(&db);
+
+        let components = db.session().connected_components();
+        assert_eq!(components.len(), 7);
+        assert_eq!(assignment(&components, &ids), vec![0, 0, 0, 0, 1, 1, 2]);
+    }
+
+    #[test]
+    fn test_strong_components_follow_direction() {
+        let db = GrafeoDB::new_in_memory();
+        let ids = clusters(&db);
+
+        let components = db.session().strongly_connected_components();
+        // Node 3 is reachable from the cycle but can't get back
+        assert_eq!(assignment(&components, &ids), vec![0, 0, 0, 1, 2, 2, 3]);
+    }
+
+    #[test]
+    fn test_edge_type_filter() {
+        let db = GrafeoDB::new_in_memory();
+        let ids = clusters(&db);
+        db.create_edge(ids[3], ids[4], "OWES");
+        db.create_edge(ids[6], ids[0], "OWES");
+
+        let session = db.session();
+        let everything = session.connected_components();
+        assert!(everything.values().all(|&c| c == 0));
+
+        let config = ComponentsConfig::new().with_edge_type("KNOWS");
+        let knows = session.connected_components_with(&config);
+        assert_eq!(assignment(&knows, &ids), vec![0, 0, 0, 0, 1, 1, 2]);
+
+        let owes = session
+            .strongly_connected_components_with(&ComponentsConfig::new().with_edge_type("OWES"));
+        assert_eq!(assignment(&owes, &ids), vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_large_graph_runs_in_morsels() {
+        let db = GrafeoDB::new_in_memory();
+        let ids: Vec<NodeId> = (0..10_000).map(|_| db.create_node(&[])).collect();
+        // Two long chains, linked back into cycles
+        for half in [&ids[..5_000], &ids[5_000..]] {
+            for pair in half.windows(2) {
+                db.create_edge(pair[1], pair[0], "NEXT");
+            }
+            db.create_edge(half[0], half[half.len() - 1], "NEXT");
+        }
+
+        let session = db.session();
+        for components in [
+            session.connected_components(),
+            session.strongly_connected_components(),
+        ] {
+            assert_eq!(components[&ids[0]], 0);
+            assert_eq!(components[&ids[4_999]], 0);
+            assert_eq!(components[&ids[5_000]], 1);
+            assert_eq!(components[&ids[9_999]], 1);
+            assert_eq!(components.values().filter(|&&c| c == 0).count(), 5_000);
+        }
+    }
+
+    #[test]
+    fn test_empty_graph() {
+        let db = GrafeoDB::new_in_memory();
+        assert!(db.session().connected_components().is_empty());
+        assert!(db.session().strongly_connected_components().is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+//! Graph algorithms run natively from a [`Session`](crate::Session).
+//!
+//! Each algorithm reads the graph through the store's chunked adjacency
+//! lists, optionally narrowed to the nodes with one label and the edges of
+//! one type, and copies it into a compact [`Subgraph`]. Work is split into
+//! node morsels handed to worker threads by a
+//! [`MorselScheduler`]; small graphs run on the calling thread.
+//!
+//! Unlike the [plugin algorithms](grafeo_adapters::plugins::algorithms),
+//! these don't need a projection to run on part of the graph.
+
+mod components;
+mod page_rank;
+mod shortest_path;
+
+pub use components::ComponentsConfig;
+pub(crate) use components::{connected_components, strongly_connected_components};
+pub use page_rank::PageRankConfig;
+pub(crate) use page_rank::page_rank;
+pub(crate) use shortest_path::shortest_path;
+pub use shortest_path::{Path, PathOpts};
+
+use std::num::NonZeroUsize;
+use std::ops::Range;
+use std::sync::Arc;
+use std::thread;
+
+use grafeo_common::types::NodeId;
+use grafeo_common::utils::hash::FxHashMap;
+use grafeo_core::execution::parallel::{MorselScheduler, WorkerHandle, generate_morsels};
+use grafeo_core::graph::Direction;
+use grafeo_core::graph::lpg::LpgStore;
+
+/// Nodes per morsel when work is spread over threads.
+const NODES_PER_MORSEL: usize = 2048;
+
+/// Below this many nodes, work stays on the calling thread.
+const PARALLEL_THRESHOLD: usize = 4096;
+
+/// The part of the graph an algorithm runs on, with nodes numbered densely.
+///
+/// Edges are kept in compressed sparse row form: node `i`'s out-neighbours
+/// are `targets[offsets[i]..offsets[i + 1]]`, one entry per edge.
+pub(crate) struct Subgraph {
+    /// Node ids, sorted; a node's index is its position here.
+    nodes: Vec<NodeId>,
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+}
+
+impl Subgraph {
+    /// Collects the nodes with `label` (or all nodes) and the edges of
+    /// `edge_type` (or all edges) between them.
+    pub(crate) fn build(store: &LpgStore, label: Option<&str>, edge_type: Option<&str>) -> Self {
+        let nodes = match label {
+            Some(label) => store.nodes_by_label(label),
+            None => store.node_ids(),
+        };
+        let index: FxHashMap<NodeId, usize> =
+            nodes.iter().enumerate().map(|(i, &id)| (id, i)).collect();
+
+        let pairs: Vec<Vec<(usize, usize)>> = run_morsels(nodes.len(), |range| {
+            let batch = store.expand_batch(&nodes[range], Direction::Outgoing, edge_type, false);
+            batch
+                .iter()
+                .filter_map(|(src, _, dst)| Some((index[&src], *index.get(&dst)?)))
+                .collect()
+        });
+        Self::from_pairs(nodes, pairs.iter().flatten().copied())
+    }
+
+    /// Builds the rows from `(source, target)` index pairs in any order.
+    fn from_pairs(nodes: Vec<NodeId>, pairs: impl Iterator<Item = (usize, usize)> + Clone) -> Self {
+        let mut offsets = vec![0; nodes.len() + 1];
+        for (src, _) in pairs.clone() {
+            offsets[src + 1] += 1;
+        }
+        for i in 0..nodes.len() {
+            offsets[i + 1] += offsets[i];
+        }
+        let mut next = offsets.clone();
+        let mut targets = vec![0; offsets[nodes.len()]];
+        for (src, dst) in pairs {
+            targets[next[src]] = dst;
+            next[src] += 1;
+        }
+        Self {
+            nodes,
+            offsets,
+            targets,
+        }
+    }
+
+    /// The same nodes with every edge reversed.
+    pub(crate) fn transpose(&self) -> Self {
+        let pairs =
+            (0..self.len()).flat_map(|src| self.neighbors(src).iter().map(move |&dst| (dst, src)));
+        Self::from_pairs(self.nodes.clone(), pairs)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(crate) fn node_id(&self, index: usize) -> NodeId {
+        self.nodes[index]
+    }
+
+    pub(crate) fn neighbors(&self, index: usize) -> &[usize] {
+        &self.targets[self.offsets[index]..self.offsets[index + 1]]
+    }
+
+    pub(crate) fn degree(&self, index: usize) -> usize {
+        self.offsets[index + 1] - self.offsets[index]
+    }
+}
+
+/// Runs `work` over `0..n` in node morsels and returns the results in range
+/// order.
+pub(crate) fn run_morsels<T: Send>(n: usize, work: impl Fn(Range<usize>) -> T + Sync) -> Vec<T> {
+    let workers = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    if n < PARALLEL_THRESHOLD || workers == 1 {
+        return vec![work(0..n)];
+    }
+
+    let morsels = generate_morsels(n, NODES_PER_MORSEL, 0);
+    let workers = workers.min(morsels.len());
+    let scheduler = Arc::new(MorselScheduler::new(workers));
+    scheduler.submit_batch(morsels);
+    scheduler.finish_submission();
+
+    let mut done: Vec<(usize, T)> = thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let scheduler = Arc::clone(&scheduler);
+                let work = &work;
+                s.spawn(move || {
+                    let handle = WorkerHandle::new(scheduler);
+                    let mut done = Vec::new();
+                    while let Some(morsel) = handle.get_work() {
+                        done.push((morsel.start_row, work(morsel.start_row..morsel.end_row)));
+                        handle.complete_morsel();
+                    }
+                    done
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("algorithm worker panicked"))
+            .collect()
+    });
+    done.sort_by_key(|(start, _)| *start);
+    done.into_iter().map(|(_, result)| result).collect()
+}
@@ -0,0 +1,239 @@
+//! Shortest paths between two nodes, by breadth-first search or Dijkstra.
+
+use grafeo_common::types::{EdgeId, NodeId};
+use grafeo_common::utils::error::{Error, Result};
+use grafeo_core::execution::operators::{FoundPath, PathFinder};
+use grafeo_core::graph::Direction;
+use grafeo_core::graph::lpg::LpgStore;
+
+/// Settings for [`Session::shortest_path()`](crate::Session::shortest_path).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathOpts {
+    /// Numeric edge property to minimise the total of; unweighted paths
+    /// minimise the number of edges.
+    pub weight: Option<String>,
+    /// Longest path to consider, in edges.
+    pub max_hops: Option<u32>,
+    /// Only follow edges of this type.
+    pub edge_type: Option<String>,
+    /// Which way edges may be followed.
+    pub direction: Direction,
+}
+
+impl Default for PathOpts {
+    fn default() -> Self {
+        Self {
+            weight: None,
+            max_hops: None,
+            edge_type: None,
+            direction: Direction::Outgoing,
+        }
+    }
+}
+
+impl PathOpts {
+    /// Creates the default settings: unweighted, unlimited hops, following
+    /// outgoing edges of any type.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Weighs each edge by its numeric `property`, searching with Dijkstra's
+    /// algorithm.
+    #[must_use]
+    pub fn with_weight(mut self, property: impl Into<String>) -> Self {
+        self.weight = Some(property.into());
+        self
+    }
+
+    /// Ignores paths longer than `max_hops` edges.
+    #[must_use]
+    pub fn with_max_hops(mut self, max_hops: u32) -> Self {
+        self.max_hops = Some(max_hops);
+        self
+    }
+
+    /// Follows only edges of `edge_type`.
+    #[must_use]
+    pub fn with_edge_type(mut self, edge_type: impl Into<String>) -> Self {
+        self.edge_type = Some(edge_type.into());
+        self
+    }
+
+    /// Sets which way edges may be followed.
+    #[must_use]
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+}
+
+/// A path returned by [`Session::shortest_path()`](crate::Session::shortest_path).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    /// Nodes along the path, source first and target last.
+    pub nodes: Vec<NodeId>,
+    /// Edges along the path; `edges[i]` joins `nodes[i]` and `nodes[i + 1]`.
+    pub edges: Vec<EdgeId>,
+    /// Total weight, or the number of edges for an unweighted path.
+    pub cost: f64,
+}
+
+impl From<FoundPath> for Path {
+    fn from(path: FoundPath) -> Self {
+        Self {
+            nodes: path.nodes,
+            edges: path.edges,
+            cost: path.cost,
+        }
+    }
+}
+
+/// Finds the cheapest path from `from` to `to`, or `None` if there isn't one
+/// within the hop limit.
+pub(crate) fn shortest_path(
+    store: &LpgStore,
+    from: NodeId,
+    to: NodeId,
+    opts: &PathOpts,
+) -> Result<Option<Path>> {
+    for node in [from, to] {
+        if store.get_node(node).is_none() {
+            return Err(Error::NodeNotFound(node));
+        }
+    }
+    PathFinder::new(store, opts.direction)
+        .with_edge_type(opts.edge_type.as_deref())
+        .with_weight(opts.weight.as_deref())
+        .with_max_hops(opts.max_hops)
+        .find(from, to)
+        .map(|path| path.map(Path::from))
+        .map_err(|err| Error::InvalidValue(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GrafeoDB;
+    use grafeo_common::types::Value;
+
+    /// Cities on a ROAD network, with `km` on every road:
+    /// a -> b -> c -> d costs 3, a -> detour -> d costs 10, and d -> f is a
+    /// RAIL link.
+    fn roads(db: &GrafeoDB) -> [NodeId; 6] {
+        let ids: [NodeId; 6] = std::array::from_fn(|_| db.create_node(&["City"]));
+        for (src, dst, km) in [(0, 1, 1), (1, 2, 1), (2, 3, 1), (0, 4, 5), (4, 3, 5)] {
+            db.create_edge_with_props(ids[src], ids[dst], "ROAD", [("km", Value::Int64(km))]);
+        }
+        db.create_edge(ids[3], ids[5], "RAIL");
+        ids
+    }
+
+    fn find(db: &GrafeoDB, from: NodeId, to: NodeId, opts: PathOpts) -> Option<Path> {
+        db.session().shortest_path(from, to, opts).unwrap()
+    }
+
+    #[test]
+    fn test_unweighted_path_has_fewest_edges() {
+        let db = GrafeoDB::new_in_memory();
+        let [a, _, _, d, detour, f] = roads(&db);
+
+        let path = find(&db, a, d, PathOpts::new()).unwrap();
+        assert_eq!(path.nodes, vec![a, detour, d]);
+        assert_eq!(path.edges.len(), 2);
+        assert_eq!(path.cost, 2.0);
+
+        let path = find(&db, a, f, PathOpts::new()).unwrap();
+        assert_eq!(path.nodes, vec![a, detour, d, f]);
+        assert_eq!(
+            find(&db, a, f, PathOpts::new().with_edge_type("ROAD")),
+            None
+        );
+        assert_eq!(find(&db, f, a, PathOpts::new()), None);
+
+        let undirected = PathOpts::new().with_direction(Direction::Both);
+        assert_eq!(
+            find(&db, f, a, undirected).unwrap().nodes,
+            vec![f, d, detour, a]
+        );
+    }
+
+    #[test]
+    fn test_weighted_path_minimises_total() {
+        let db = GrafeoDB::new_in_memory();
+        let [a, b, c, d, detour, _] = roads(&db);
+
+        let path = find(&db, a, d, PathOpts::new().with_weight("km")).unwrap();
+        assert_eq!(path.nodes, vec![a, b, c, d]);
+        assert_eq!(path.edges.len(), 3);
+        assert_eq!(path.cost, 3.0);
+
+        // A hop limit rules out the cheap route but not the short one
+        let path = find(
+            &db,
+            a,
+            d,
+            PathOpts::new().with_weight("km").with_max_hops(2),
+        )
+        .unwrap();
+        assert_eq!(path.nodes, vec![a, detour, d]);
+        assert_eq!(path.cost, 10.0);
+        assert_eq!(find(&db, a, d, PathOpts::new().with_max_hops(1)), None);
+    }
+
+    #[test]
+    fn test_bad_weights_and_missing_nodes_are_errors() {
+        let db = GrafeoDB::new_in_memory();
+        let [a, b, _, d, _, f] = roads(&db);
+        let session = db.session();
+
+        // The RAIL edge has no km
+        let err = session
+            .shortest_path(a, f, PathOpts::new().with_weight("km"))
+            .unwrap_err();
+        assert!(err.to_string().contains("has no 'km' weight"), "{err}");
+
+        let edge = db.create_edge_with_props(a, b, "ROAD", [("km", Value::from("one"))]);
+        let err = session
+            .shortest_path(a, d, PathOpts::new().with_weight("km"))
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(&format!("edge {edge} has a STRING 'km' weight")),
+            "{err}"
+        );
+
+        let missing = NodeId::new(999);
+        assert!(matches!(
+            session.shortest_path(a, missing, PathOpts::new()),
+            Err(Error::NodeNotFound(id)) if id == missing
+        ));
+    }
+
+    #[test]
+    fn test_query_binding_matches_session() {
+        let db = GrafeoDB::new_in_memory();
+        let [a, _, _, d, detour, f] = roads(&db);
+        db.set_node_property(a, "name", Value::from("a"));
+        db.set_node_property(f, "name", Value::from("f"));
+
+        let result = db
+            .execute(
+                "MATCH p = shortestPath((x:City {name: 'a'})-[*]->(y:City {name: 'f'})) \
+                 RETURN length(p), p",
+            )
+            .unwrap();
+        let path = find(&db, a, f, PathOpts::new()).unwrap();
+        assert_eq!(path.nodes, vec![a, detour, d, f]);
+        let edges: Vec<Value> = path
+            .edges
+            .iter()
+            .map(|edge| Value::Int64(edge.as_u64() as i64))
+            .collect();
+        assert_eq!(
+            result.rows,
+            vec![vec![Value::Int64(3), Value::List(edges.into())]]
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Fluent construction of a [`GrafeoDB`].
+//!
+//! [`GrafeoDB::builder()`] gathers storage, threading, memory and feature
+//! options in one chain instead of assembling a [`Config`] by hand:
+//!
+//! ```no_run
+//! use grafeo_engine::GrafeoDB;
+//!
+//! let db = GrafeoDB::builder()
+//!     .with_path("./my_graph")
+//!     .with_threads(4)
+//!     .with_memory_limit(512 * 1024 * 1024)
+//!     .with_result_cache(128)
+//!     .build()?;
+//! # Ok::<(), grafeo_common::utils::error::Error>(())
+//! ```
+
+use std::path::PathBuf;
+
+use grafeo_common::utils::error::Result;
+use grafeo_core::execution::Collation;
+
+use crate::config::{AdaptiveConfig, Config, QueryLimits};
+use crate::database::GrafeoDB;
+
+/// Builder for a [`GrafeoDB`].
+///
+/// Starts from an in-memory [`Config`]; every option maps onto one of its
+/// fields, so anything not covered here can be set with
+/// [`with_config`](Self::with_config).
+#[derive(Debug, Clone)]
+pub struct GrafeoDBBuilder {
+    config: Config,
+}
+
+impl GrafeoDBBuilder {
+    /// Creates a builder for an in-memory database.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            config: Config::in_memory(),
+        }
+    }
+
+    /// Replaces the whole configuration.
+    ///
+    /// Options chained after this apply on top of `config`.
+    #[must_use]
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Keeps the database in memory, with no write-ahead log.
+    #[must_use]
+    pub fn in_memory(mut self) -> Self {
+        self.config.path = None;
+        self.config.wal_enabled = false;
+        self
+    }
+
+    /// Stores the database at `path`, recovering it from the write-ahead
+    /// log if it exists.
+    #[must_use]
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.path = Some(path.into());
+        self.config.wal_enabled = true;
+        self
+    }
+
+    /// Turns off the write-ahead log, trading durability for write speed.
+    #[must_use]
+    pub fn without_wal(mut self) -> Self {
+        self.config.wal_enabled = false;
+        self
+    }
+
+    /// Sets how many worker threads queries may use.
+    #[must_use]
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.config = self.config.with_threads(threads);
+        self
+    }
+
+    /// Caps the memory queries may use, in bytes.
+    #[must_use]
+    pub fn with_memory_limit(mut self, limit: usize) -> Self {
+        self.config = self.config.with_memory_limit(limit);
+        self
+    }
+
+    /// Sets where operators spill when they run out of memory.
+    #[must_use]
+    pub fn with_spill_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config = self.config.with_spill_path(path);
+        self
+    }
+
+    /// Caches the results of up to `size` read-only queries.
+    #[must_use]
+    pub fn with_result_cache(mut self, size: usize) -> Self {
+        self.config = self.config.with_result_cache(size);
+        self
+    }
+
+    /// Sets adaptive query execution options.
+    #[must_use]
+    pub fn with_adaptive(mut self, adaptive: AdaptiveConfig) -> Self {
+        self.config = self.config.with_adaptive(adaptive);
+        self
+    }
+
+    /// Sets the string collation for sorting and range predicates.
+    #[must_use]
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.config = self.config.with_collation(collation);
+        self
+    }
+
+    /// Sets limits that reject overly complex queries.
+    #[must_use]
+    pub fn with_query_limits(mut self, limits: QueryLimits) -> Self {
+        self.config = self.config.with_query_limits(limits);
+        self
+    }
+
+    /// Requires writes to use labels, edge types and keys declared in the
+    /// catalog.
+    #[must_use]
+    pub fn with_strict_schema(mut self) -> Self {
+        self.config = self.config.with_strict_schema();
+        self
+    }
+
+    /// Matches labels and edge types ignoring case.
+    #[must_use]
+    pub fn with_case_insensitive_labels(mut self) -> Self {
+        self.config = self.config.with_case_insensitive_labels();
+        self
+    }
+
+    /// Runs joins in the order the query wrote them.
+    #[must_use]
+    pub fn without_join_reorder(mut self) -> Self {
+        self.config = self.config.without_join_reorder();
+        self
+    }
+
+    /// Logs every query.
+    #[must_use]
+    pub fn with_query_logging(mut self) -> Self {
+        self.config = self.config.with_query_logging();
+        self
+    }
+
+    /// Returns the configuration built so far.
+    #[must_use]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Opens the database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database can't be created or recovery fails.
+    pub fn build(self) -> Result<GrafeoDB> {
+        GrafeoDB::with_config(self.config)
+    }
+}
+
+impl Default for GrafeoDBBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SchemaMode;
+
+    #[test]
+    fn test_builder_defaults_to_in_memory() {
+        let db = GrafeoDB::builder().build().unwrap();
+
+        assert!(db.path().is_none());
+        assert!(!db.config().wal_enabled);
+    }
+
+    #[test]
+    fn test_builder_applies_options() {
+        let db = GrafeoDB::builder()
+            .with_threads(3)
+            .with_memory_limit(64 * 1024 * 1024)
+            .with_result_cache(16)
+            .with_adaptive(AdaptiveConfig::disabled())
+            .with_collation(Collation::Unicode)
+            .with_query_limits(QueryLimits::default().with_max_depth(8))
+            .with_strict_schema()
+            .with_case_insensitive_labels()
+            .without_join_reorder()
+            .build()
+            .unwrap();
+
+        let config = db.config();
+        assert_eq!(config.threads, 3);
+        assert_eq!(config.memory_limit, Some(64 * 1024 * 1024));
+        assert_eq!(config.result_cache_size, 16);
+        assert!(!config.adaptive.enabled);
+        assert_eq!(config.collation, Collation::Unicode);
+        assert_eq!(config.query_limits.max_depth, 8);
+        assert_eq!(config.schema_mode, SchemaMode::Strict);
+        assert!(config.case_insensitive_labels);
+        assert!(!config.join_reorder);
+
+        db.declare_label("Person");
+        db.declare_property_key("name");
+        let session = db.session();
+        session.execute("INSERT (:Person {name: 'Alice'})").unwrap();
+        let result = session.execute("MATCH (p:PERSON) RETURN p.name").unwrap();
+        assert_eq!(result.row_count(), 1);
+    }
+
+    #[test]
+    fn test_builder_options_apply_over_config() {
+        let builder = GrafeoDB::builder()
+            .with_config(Config::in_memory().with_threads(2).with_query_logging())
+            .with_path("/tmp/grafeo-builder")
+            .without_wal()
+            .with_threads(6);
+
+        let config = builder.config();
+        assert_eq!(config.threads, 6);
+        assert!(config.query_logging);
+        assert_eq!(config.path, Some(PathBuf::from("/tmp/grafeo-builder")));
+        assert!(!config.wal_enabled);
+    }
+
+    #[test]
+    fn test_builder_persistent_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("built_db");
+
+        {
+            let db = GrafeoDB::builder().with_path(&path).build().unwrap();
+            db.create_node(&["Person"]);
+            db.close().unwrap();
+        }
+
+        let db = GrafeoDB::builder().with_path(&path).build().unwrap();
+        let result = db.session().execute("MATCH (p:Person) RETURN p").unwrap();
+        assert_eq!(result.row_count(), 1);
+    }
+}
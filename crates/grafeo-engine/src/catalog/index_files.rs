@@ -0,0 +1,222 @@
+//! Hash and BTree indexes saved in the database directory.
+//!
+//! A clean close writes each hash and BTree index to its own file under
+//! `indexes/`, named after its type, label and property, and stamped with
+//! the WAL checkpoint it was saved at. The next open declares those indexes
+//! again and, if the log is still at that checkpoint, reads them back
+//! instead of scanning the graph.
+//!
+//! Files are kept until the next close replaces them, so a crash doesn't
+//! lose the indexes. An index whose file is stale - the log has moved past
+//! its checkpoint - or can't be read - damaged, or from another format
+//! version - is rebuilt from the store instead.
+
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use grafeo_adapters::storage::wal::CheckpointMetadata;
+use grafeo_common::utils::error::Result;
+use grafeo_core::graph::lpg::LpgStore;
+use grafeo_core::index::PropertyIndex;
+
+use super::{Catalog, IndexType};
+
+/// Extension of index files.
+const EXTENSION: &str = "idx";
+
+/// Writes every hash and BTree index in `catalog` to `dir`, stamped with
+/// `checkpoint`, replacing the files already there.
+pub(crate) fn save_indexes(
+    catalog: &Catalog,
+    store: &LpgStore,
+    dir: &Path,
+    checkpoint: &CheckpointMetadata,
+) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    for path in index_files(dir)? {
+        fs::remove_file(path)?;
+    }
+
+    for definition in catalog.all_indexes() {
+        let (Some(label), Some(property)) = (
+            catalog.get_label_name(definition.label),
+            catalog.get_property_key_name(definition.property_key),
+        ) else {
+            continue;
+        };
+        let Some(name) = file_name(definition.index_type, &label, &property) else {
+            continue;
+        };
+        let index = store
+            .value_index(&label, &property)
+            .unwrap_or_else(|| store.create_value_index(&label, &property));
+
+        // Written aside and renamed, so a crash mid-write leaves no file
+        let path = dir.join(name);
+        let partial = path.with_extension("partial");
+        let mut writer = BufWriter::new(File::create(&partial)?);
+        for part in stamp(checkpoint) {
+            writer.write_all(&part.to_le_bytes())?;
+        }
+        index.serialize(&mut writer)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        fs::rename(&partial, &path)?;
+    }
+    Ok(())
+}
+
+/// Declares the indexes saved in `dir` in `catalog` and installs them in
+/// `store`, which must already hold the recovered graph.
+///
+/// `checkpoint` is the checkpoint the log is still at, if nothing has been
+/// logged since; files saved at any other point are stale.
+pub(crate) fn load_indexes(
+    catalog: &Catalog,
+    store: &LpgStore,
+    dir: &Path,
+    checkpoint: Option<&CheckpointMetadata>,
+) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    for path in index_files(dir)? {
+        let Some((index_type, label, property)) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(parse_file_name)
+        else {
+            tracing::warn!("Ignoring unrecognized index file {}", path.display());
+            continue;
+        };
+
+        match read_index(&path, checkpoint) {
+            Ok(Some(index)) => {
+                store.attach_value_index(&label, &property, index);
+            }
+            Ok(None) => {
+                tracing::info!(
+                    "Rebuilding index on :{label}({property}), the log has moved past {}",
+                    path.display()
+                );
+                store.create_value_index(&label, &property);
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Rebuilding index on :{label}({property}), {} can't be read: {e}",
+                    path.display()
+                );
+                store.create_value_index(&label, &property);
+            }
+        }
+        catalog.declare_index(&label, &property, index_type);
+    }
+    Ok(())
+}
+
+/// Reads the index saved at `path`, or `None` if it wasn't saved at
+/// `checkpoint`.
+fn read_index(
+    path: &Path,
+    checkpoint: Option<&CheckpointMetadata>,
+) -> Result<Option<PropertyIndex>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut saved_at = [0_u64; 2];
+    for part in &mut saved_at {
+        let mut bytes = [0; 8];
+        reader.read_exact(&mut bytes)?;
+        *part = u64::from_le_bytes(bytes);
+    }
+    if checkpoint.is_none_or(|checkpoint| stamp(checkpoint) != saved_at) {
+        return Ok(None);
+    }
+    PropertyIndex::deserialize(&mut reader).map(Some)
+}
+
+/// What identifies a checkpoint in an index file.
+fn stamp(checkpoint: &CheckpointMetadata) -> [u64; 2] {
+    [checkpoint.log_sequence, checkpoint.timestamp_ms]
+}
+
+/// The index files in `dir`.
+fn index_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == EXTENSION) {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// The file an index is saved to, or `None` if it isn't saved.
+///
+/// Names are hex-encoded, as labels and properties may hold characters
+/// file systems don't allow.
+fn file_name(index_type: IndexType, label: &str, property: &str) -> Option<String> {
+    let kind = match index_type {
+        IndexType::Hash => "hash",
+        IndexType::BTree => "btree",
+        IndexType::FullText | IndexType::Spatial => return None,
+    };
+    Some(format!(
+        "{kind}.{}.{}.{EXTENSION}",
+        to_hex(label),
+        to_hex(property)
+    ))
+}
+
+/// Reads the index type, label and property back out of a file name.
+fn parse_file_name(name: &str) -> Option<(IndexType, String, String)> {
+    let mut parts = name.strip_suffix(EXTENSION)?.strip_suffix('.')?.split('.');
+    let index_type = match parts.next()? {
+        "hash" => IndexType::Hash,
+        "btree" => IndexType::BTree,
+        _ => return None,
+    };
+    let label = from_hex(parts.next()?)?;
+    let property = from_hex(parts.next()?)?;
+    parts
+        .next()
+        .is_none()
+        .then_some((index_type, label, property))
+}
+
+fn to_hex(name: &str) -> String {
+    name.bytes().fold(String::new(), |mut hex, b| {
+        let _ = write!(hex, "{b:02x}");
+        hex
+    })
+}
+
+fn from_hex(hex: &str) -> Option<String> {
+    let bytes = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_file_names_round_trip() {
+        let name = file_name(IndexType::BTree, "Person", "first name.é").unwrap();
+        assert_eq!(
+            parse_file_name(&name),
+            Some((
+                IndexType::BTree,
+                "Person".to_string(),
+                "first name.é".to_string()
+            ))
+        );
+        assert!(file_name(IndexType::FullText, "Person", "bio").is_none());
+        assert!(parse_file_name("hash.zz.00.idx").is_none());
+        assert!(parse_file_name("notes.txt").is_none());
+    }
+}
@@ -8,16 +8,28 @@
 //! | -------------- | -------------- |
 //! | Labels | Maps "Person" → LabelId for efficient storage |
 //! | Property keys | Maps "name" → PropertyKeyId |
-//! | Edge types | Maps "KNOWS" → EdgeTypeId |
+//! | Edge types | Maps "KNOWS" → EdgeTypeId, plus whether parallel edges are allowed |
 //! | Indexes | Which properties are indexed for fast lookups |
+//!
+//! [`Catalog::analyze()`] collects the statistics the optimizer plans with.
+
+mod index_files;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 
 use parking_lot::RwLock;
 
-use grafeo_common::types::{EdgeTypeId, IndexId, LabelId, PropertyKeyId};
+use grafeo_common::types::{EdgeTypeId, IndexId, LabelId, PropertyKey, PropertyKeyId};
+use grafeo_common::utils::error::Error;
+pub use grafeo_core::execution::operators::{EdgeMultiplicity, PropertyNormalizer};
+use grafeo_core::graph::lpg::LpgStore;
+use grafeo_core::index::Tokenizer;
+use grafeo_core::index::zone_map::ZoneMapEntry;
+use grafeo_core::statistics::{ColumnStatistics, HyperLogLog, Statistics};
+
+pub(crate) use index_files::{load_indexes, save_indexes};
 
 /// The database's schema dictionary - maps names to compact internal IDs.
 ///
@@ -34,6 +46,10 @@ pub struct Catalog {
     indexes: IndexCatalog,
     /// Optional schema constraints.
     schema: Option<SchemaCatalog>,
+    /// Whether label and edge type lookups ignore case.
+    case_insensitive_labels: bool,
+    /// Bumped on every change, so cached plans can tell they're stale.
+    version: AtomicU64,
 }
 
 impl Catalog {
@@ -46,6 +62,8 @@ impl Catalog {
             edge_types: EdgeTypeCatalog::new(),
             indexes: IndexCatalog::new(),
             schema: None,
+            case_insensitive_labels: false,
+            version: AtomicU64::new(0),
         }
     }
 
@@ -58,20 +76,73 @@ impl Catalog {
             edge_types: EdgeTypeCatalog::new(),
             indexes: IndexCatalog::new(),
             schema: Some(SchemaCatalog::new()),
+            case_insensitive_labels: false,
+            version: AtomicU64::new(0),
         }
     }
 
+    /// Makes label and edge type lookups ignore case, so `person` resolves
+    /// to `Person`. Names are still stored as first written.
+    #[must_use]
+    pub fn with_case_insensitive_labels(mut self) -> Self {
+        self.case_insensitive_labels = true;
+        self
+    }
+
+    /// Returns true if label and edge type lookups ignore case.
+    #[must_use]
+    pub fn case_insensitive_labels(&self) -> bool {
+        self.case_insensitive_labels
+    }
+
+    /// Returns the catalog version, which changes whenever names, indexes,
+    /// constraints, or edge settings change.
+    ///
+    /// Prepared queries compare this against the version they were compiled
+    /// at to decide whether to re-plan.
+    #[must_use]
+    pub fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    /// Marks the catalog as changed.
+    fn bump_version(&self) {
+        self.version.fetch_add(1, Ordering::AcqRel);
+    }
+
     // === Label Operations ===
 
     /// Gets or creates a label ID for the given label name.
     pub fn get_or_create_label(&self, name: &str) -> LabelId {
+        if let Some(id) = self.labels.get_id(name) {
+            return id;
+        }
+        self.bump_version();
         self.labels.get_or_create(name)
     }
 
     /// Gets the label ID for a label name, if it exists.
+    ///
+    /// When lookups ignore case and there's no exact match, returns the
+    /// first label created that differs only in case.
     #[must_use]
     pub fn get_label_id(&self, name: &str) -> Option<LabelId> {
-        self.labels.get_id(name)
+        self.labels.get_id(name).or_else(|| {
+            self.case_insensitive_labels
+                .then(|| self.labels.get_folded_ids(name).first().copied())
+                .flatten()
+        })
+    }
+
+    /// Gets the IDs of every label a lookup for `name` matches: the exact
+    /// name, or when lookups ignore case, every casing of it.
+    #[must_use]
+    pub fn get_label_ids(&self, name: &str) -> Vec<LabelId> {
+        if self.case_insensitive_labels {
+            self.labels.get_folded_ids(name)
+        } else {
+            self.labels.get_id(name).into_iter().collect()
+        }
     }
 
     /// Gets the label name for a label ID, if it exists.
@@ -96,6 +167,10 @@ impl Catalog {
 
     /// Gets or creates a property key ID for the given property key name.
     pub fn get_or_create_property_key(&self, name: &str) -> PropertyKeyId {
+        if let Some(id) = self.property_keys.get_id(name) {
+            return id;
+        }
+        self.bump_version();
         self.property_keys.get_or_create(name)
     }
 
@@ -123,17 +198,59 @@ impl Catalog {
         self.property_keys.all_names()
     }
 
+    /// Normalizes every value written to this property by a query.
+    ///
+    /// Registers the property key if it doesn't exist yet, and replaces any
+    /// normalizer set before.
+    pub fn set_property_normalizer(&self, name: &str, normalizer: PropertyNormalizer) {
+        let id = self.get_or_create_property_key(name);
+        self.property_keys
+            .normalizers
+            .write()
+            .insert(id, normalizer);
+        self.bump_version();
+    }
+
+    /// Returns the normalizer for a property, if one is set.
+    #[must_use]
+    pub fn property_normalizer(&self, name: &str) -> Option<PropertyNormalizer> {
+        let id = self.property_keys.get_id(name)?;
+        self.property_keys.normalizers.read().get(&id).cloned()
+    }
+
     // === Edge Type Operations ===
 
     /// Gets or creates an edge type ID for the given edge type name.
     pub fn get_or_create_edge_type(&self, name: &str) -> EdgeTypeId {
+        if let Some(id) = self.edge_types.get_id(name) {
+            return id;
+        }
+        self.bump_version();
         self.edge_types.get_or_create(name)
     }
 
     /// Gets the edge type ID for an edge type name, if it exists.
+    ///
+    /// Falls back to a differently cased type like
+    /// [`get_label_id`](Self::get_label_id) does.
     #[must_use]
     pub fn get_edge_type_id(&self, name: &str) -> Option<EdgeTypeId> {
-        self.edge_types.get_id(name)
+        self.edge_types.get_id(name).or_else(|| {
+            self.case_insensitive_labels
+                .then(|| self.edge_types.get_folded_ids(name).first().copied())
+                .flatten()
+        })
+    }
+
+    /// Gets the IDs of every edge type a lookup for `name` matches, like
+    /// [`get_label_ids`](Self::get_label_ids).
+    #[must_use]
+    pub fn get_edge_type_ids(&self, name: &str) -> Vec<EdgeTypeId> {
+        if self.case_insensitive_labels {
+            self.edge_types.get_folded_ids(name)
+        } else {
+            self.edge_types.get_id(name).into_iter().collect()
+        }
     }
 
     /// Gets the edge type name for an edge type ID, if it exists.
@@ -148,6 +265,29 @@ impl Catalog {
         self.edge_types.count()
     }
 
+    /// Sets whether parallel edges of this type are allowed, rejected, or upserted.
+    ///
+    /// Registers the edge type if it doesn't exist yet.
+    pub fn set_edge_multiplicity(&self, name: &str, multiplicity: EdgeMultiplicity) {
+        let id = self.get_or_create_edge_type(name);
+        self.edge_types
+            .multiplicity
+            .write()
+            .insert(id, multiplicity);
+        self.bump_version();
+    }
+
+    /// Returns how duplicate edges of this type are handled.
+    ///
+    /// Defaults to [`EdgeMultiplicity::Allow`] for unknown or unconfigured types.
+    #[must_use]
+    pub fn edge_multiplicity(&self, name: &str) -> EdgeMultiplicity {
+        self.edge_types
+            .get_id(name)
+            .and_then(|id| self.edge_types.multiplicity.read().get(&id).copied())
+            .unwrap_or_default()
+    }
+
     /// Returns all edge type names.
     #[must_use]
     pub fn all_edge_types(&self) -> Vec<Arc<str>> {
@@ -163,12 +303,75 @@ impl Catalog {
         property_key: PropertyKeyId,
         index_type: IndexType,
     ) -> IndexId {
-        self.indexes.create(label, property_key, index_type)
+        let id = self.indexes.create(label, property_key, index_type);
+        self.bump_version();
+        id
+    }
+
+    /// Creates a full-text index over the `property` of nodes labeled
+    /// `label`, building it from the nodes already in `store`.
+    ///
+    /// The store keeps the index up to date from then on, and queries with
+    /// `CONTAINS`, `STARTS WITH`, or `ENDS WITH` on the property use it to
+    /// narrow their scans. Declaring an existing index again returns it.
+    pub fn create_fulltext_index(&self, store: &LpgStore, label: &str, property: &str) -> IndexId {
+        store.create_fulltext_index(label, property, Tokenizer::new());
+        self.declare_index(label, property, IndexType::FullText)
+    }
+
+    /// Creates a spatial index over the point-valued `property` of nodes
+    /// labeled `label`, building it from the nodes already in `store`.
+    ///
+    /// The store keeps the index up to date from then on, and queries with
+    /// `point.withinDistance` or `point.withinBBox` on the property use it to
+    /// narrow their scans. Declaring an existing index again returns it.
+    pub fn create_spatial_index(&self, store: &LpgStore, label: &str, property: &str) -> IndexId {
+        store.create_spatial_index(label, property);
+        self.declare_index(label, property, IndexType::Spatial)
+    }
+
+    /// Creates a hash index over the `property` of nodes labeled `label`,
+    /// building it from the nodes already in `store`.
+    ///
+    /// The store keeps the index up to date from then on. Declaring an
+    /// existing index again returns it.
+    pub fn create_hash_index(&self, store: &LpgStore, label: &str, property: &str) -> IndexId {
+        store.create_value_index(label, property);
+        self.declare_index(label, property, IndexType::Hash)
+    }
+
+    /// Creates a BTree index over the `property` of nodes labeled `label`,
+    /// building it from the nodes already in `store`.
+    ///
+    /// Like [`create_hash_index`](Self::create_hash_index), but meant for
+    /// range lookups. Both are served by the same ordered index in `store`.
+    pub fn create_btree_index(&self, store: &LpgStore, label: &str, property: &str) -> IndexId {
+        store.create_value_index(label, property);
+        self.declare_index(label, property, IndexType::BTree)
+    }
+
+    /// Returns the index of `index_type` on `label(property)`, creating its
+    /// definition if there is none yet.
+    fn declare_index(&self, label: &str, property: &str, index_type: IndexType) -> IndexId {
+        let label_id = self.get_or_create_label(label);
+        let property_key = self.get_or_create_property_key(property);
+        let existing = self
+            .indexes_for_label_property(label_id, property_key)
+            .into_iter()
+            .find(|&id| {
+                self.get_index(id)
+                    .is_some_and(|index| index.index_type == index_type)
+            });
+        existing.unwrap_or_else(|| self.create_index(label_id, property_key, index_type))
     }
 
     /// Drops an index by ID.
     pub fn drop_index(&self, id: IndexId) -> bool {
-        self.indexes.drop(id)
+        let dropped = self.indexes.drop(id);
+        if dropped {
+            self.bump_version();
+        }
+        dropped
     }
 
     /// Gets the index definition for an index ID.
@@ -199,6 +402,132 @@ impl Catalog {
         self.indexes.count()
     }
 
+    /// Returns every index definition, ordered by ID.
+    #[must_use]
+    pub fn all_indexes(&self) -> Vec<IndexDefinition> {
+        self.indexes.all()
+    }
+
+    // === Statistics ===
+
+    /// Scans `store` to collect statistics for the optimizer, and keeps
+    /// them in the store so later queries plan with them.
+    ///
+    /// Collects node counts per label, edge counts and average degrees per
+    /// edge type, and for each indexed property an approximate distinct
+    /// count (via [`HyperLogLog`]) along with null counts. Min and max come
+    /// from the property's zone map, so they cover every node with the
+    /// property, not only the indexed label.
+    ///
+    /// Statistics go stale as the graph changes. Automatic refreshes only
+    /// recompute counts, dropping property statistics until the next
+    /// `analyze`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an index refers to a label or property key the
+    /// catalog doesn't know.
+    pub fn analyze(&self, store: &LpgStore) -> Result<Statistics, Error> {
+        store.compute_statistics();
+        let mut statistics = store.statistics();
+
+        let mut analyzed = HashSet::new();
+        let mut sketches: HashMap<Arc<str>, (HyperLogLog, u64, u64)> = HashMap::new();
+        for index in self.all_indexes() {
+            if !analyzed.insert((index.label, index.property_key)) {
+                continue;
+            }
+            let label = self.get_label_name(index.label).ok_or_else(|| {
+                Error::Internal(format!("index {} has an unknown label", index.id))
+            })?;
+            let property = self
+                .get_property_key_name(index.property_key)
+                .ok_or_else(|| {
+                    Error::Internal(format!("index {} has an unknown property key", index.id))
+                })?;
+
+            let mut sketch = HyperLogLog::new();
+            let (mut total, mut nulls) = (0, 0);
+            for node in store.nodes_with_label(&label) {
+                total += 1;
+                match node.get_property(&property) {
+                    Some(value) if !value.is_null() => sketch.add(value),
+                    _ => nulls += 1,
+                }
+            }
+
+            let column = property_statistics(store, &property, &sketch, total, nulls);
+            if let Some(label_statistics) = statistics.labels.get_mut(label.as_ref()) {
+                label_statistics
+                    .properties
+                    .insert(property.to_string(), column);
+            }
+
+            let (merged, merged_total, merged_nulls) = sketches
+                .entry(property)
+                .or_insert_with(|| (HyperLogLog::new(), 0, 0));
+            merged.merge(&sketch);
+            *merged_total += total;
+            *merged_nulls += nulls;
+        }
+
+        for (property, (sketch, total, nulls)) in sketches {
+            let column = property_statistics(store, &property, &sketch, total, nulls);
+            statistics.update_property(&property, column);
+        }
+
+        store.set_statistics(statistics.clone());
+        Ok(statistics)
+    }
+
+    // === Strict Schema Validation ===
+
+    /// Checks that a label has been declared.
+    ///
+    /// Used in strict schema mode to reject writes with undeclared labels.
+    pub fn validate_label(&self, name: &str) -> Result<(), CatalogError> {
+        match self.labels.get_id(name) {
+            Some(_) => Ok(()),
+            None => Err(CatalogError::LabelNotFound(name.to_string())),
+        }
+    }
+
+    /// Checks that a property key has been declared.
+    pub fn validate_property_key(&self, name: &str) -> Result<(), CatalogError> {
+        match self.property_keys.get_id(name) {
+            Some(_) => Ok(()),
+            None => Err(CatalogError::PropertyKeyNotFound(name.to_string())),
+        }
+    }
+
+    /// Checks that an edge type has been declared.
+    pub fn validate_edge_type(&self, name: &str) -> Result<(), CatalogError> {
+        match self.edge_types.get_id(name) {
+            Some(_) => Ok(()),
+            None => Err(CatalogError::EdgeTypeNotFound(name.to_string())),
+        }
+    }
+
+    /// Checks that every label, the edge type if any, and every property key
+    /// of a write have been declared.
+    pub fn validate_write<'a, 'b>(
+        &self,
+        labels: impl IntoIterator<Item = &'a str>,
+        edge_type: Option<&str>,
+        property_keys: impl IntoIterator<Item = &'b str>,
+    ) -> Result<(), CatalogError> {
+        for label in labels {
+            self.validate_label(label)?;
+        }
+        if let Some(edge_type) = edge_type {
+            self.validate_edge_type(edge_type)?;
+        }
+        for key in property_keys {
+            self.validate_property_key(key)?;
+        }
+        Ok(())
+    }
+
     // === Schema Operations ===
 
     /// Returns whether schema constraints are enabled.
@@ -216,7 +545,9 @@ impl Catalog {
         property_key: PropertyKeyId,
     ) -> Result<(), CatalogError> {
         match &self.schema {
-            Some(schema) => schema.add_unique_constraint(label, property_key),
+            Some(schema) => schema
+                .add_unique_constraint(label, property_key)
+                .inspect(|()| self.bump_version()),
             None => Err(CatalogError::SchemaNotEnabled),
         }
     }
@@ -230,7 +561,9 @@ impl Catalog {
         property_key: PropertyKeyId,
     ) -> Result<(), CatalogError> {
         match &self.schema {
-            Some(schema) => schema.add_required_property(label, property_key),
+            Some(schema) => schema
+                .add_required_property(label, property_key)
+                .inspect(|()| self.bump_version()),
             None => Err(CatalogError::SchemaNotEnabled),
         }
     }
@@ -264,6 +597,9 @@ impl Default for Catalog {
 struct LabelCatalog {
     name_to_id: RwLock<HashMap<Arc<str>, LabelId>>,
     id_to_name: RwLock<Vec<Arc<str>>>,
+    /// Lowercased name to the IDs of every label that folds to it, in
+    /// creation order.
+    folded_to_ids: RwLock<HashMap<Arc<str>, Vec<LabelId>>>,
     next_id: AtomicU32,
 }
 
@@ -272,6 +608,7 @@ impl LabelCatalog {
         Self {
             name_to_id: RwLock::new(HashMap::new()),
             id_to_name: RwLock::new(Vec::new()),
+            folded_to_ids: RwLock::new(HashMap::new()),
             next_id: AtomicU32::new(0),
         }
     }
@@ -295,6 +632,11 @@ impl LabelCatalog {
         }
 
         let id = LabelId::new(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.folded_to_ids
+            .write()
+            .entry(name.to_lowercase().into())
+            .or_default()
+            .push(id);
         let name: Arc<str> = name.into();
         name_to_id.insert(Arc::clone(&name), id);
         id_to_name.push(name);
@@ -305,6 +647,14 @@ impl LabelCatalog {
         self.name_to_id.read().get(name).copied()
     }
 
+    fn get_folded_ids(&self, name: &str) -> Vec<LabelId> {
+        self.folded_to_ids
+            .read()
+            .get(name.to_lowercase().as_str())
+            .cloned()
+            .unwrap_or_default()
+    }
+
     fn get_name(&self, id: LabelId) -> Option<Arc<str>> {
         self.id_to_name.read().get(id.as_u32() as usize).cloned()
     }
@@ -324,6 +674,7 @@ impl LabelCatalog {
 struct PropertyCatalog {
     name_to_id: RwLock<HashMap<Arc<str>, PropertyKeyId>>,
     id_to_name: RwLock<Vec<Arc<str>>>,
+    normalizers: RwLock<HashMap<PropertyKeyId, PropertyNormalizer>>,
     next_id: AtomicU32,
 }
 
@@ -332,6 +683,7 @@ impl PropertyCatalog {
         Self {
             name_to_id: RwLock::new(HashMap::new()),
             id_to_name: RwLock::new(Vec::new()),
+            normalizers: RwLock::new(HashMap::new()),
             next_id: AtomicU32::new(0),
         }
     }
@@ -384,6 +736,10 @@ impl PropertyCatalog {
 struct EdgeTypeCatalog {
     name_to_id: RwLock<HashMap<Arc<str>, EdgeTypeId>>,
     id_to_name: RwLock<Vec<Arc<str>>>,
+    /// Lowercased name to the IDs of every type that folds to it, in
+    /// creation order.
+    folded_to_ids: RwLock<HashMap<Arc<str>, Vec<EdgeTypeId>>>,
+    multiplicity: RwLock<HashMap<EdgeTypeId, EdgeMultiplicity>>,
     next_id: AtomicU32,
 }
 
@@ -392,6 +748,8 @@ impl EdgeTypeCatalog {
         Self {
             name_to_id: RwLock::new(HashMap::new()),
             id_to_name: RwLock::new(Vec::new()),
+            folded_to_ids: RwLock::new(HashMap::new()),
+            multiplicity: RwLock::new(HashMap::new()),
             next_id: AtomicU32::new(0),
         }
     }
@@ -415,6 +773,11 @@ impl EdgeTypeCatalog {
         }
 
         let id = EdgeTypeId::new(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.folded_to_ids
+            .write()
+            .entry(name.to_lowercase().into())
+            .or_default()
+            .push(id);
         let name: Arc<str> = name.into();
         name_to_id.insert(Arc::clone(&name), id);
         id_to_name.push(name);
@@ -425,6 +788,14 @@ impl EdgeTypeCatalog {
         self.name_to_id.read().get(name).copied()
     }
 
+    fn get_folded_ids(&self, name: &str) -> Vec<EdgeTypeId> {
+        self.folded_to_ids
+            .read()
+            .get(name.to_lowercase().as_str())
+            .cloned()
+            .unwrap_or_default()
+    }
+
     fn get_name(&self, id: EdgeTypeId) -> Option<Arc<str>> {
         self.id_to_name.read().get(id.as_u32() as usize).cloned()
     }
@@ -449,6 +820,8 @@ pub enum IndexType {
     BTree,
     /// Full-text index for text search.
     FullText,
+    /// R-tree index for radius and bounding-box queries over points.
+    Spatial,
 }
 
 /// Index definition.
@@ -472,6 +845,25 @@ struct IndexCatalog {
     next_id: AtomicU32,
 }
 
+/// Column statistics for an indexed property, with its zone map's range.
+fn property_statistics(
+    store: &LpgStore,
+    property: &str,
+    sketch: &HyperLogLog,
+    total: u64,
+    nulls: u64,
+) -> ColumnStatistics {
+    let column = ColumnStatistics::new(sketch.estimate(), total, nulls);
+    match store.node_property_zone_map(&PropertyKey::new(property)) {
+        Some(ZoneMapEntry {
+            min: Some(min),
+            max: Some(max),
+            ..
+        }) => column.with_min_max(min, max),
+        _ => column,
+    }
+}
+
 impl IndexCatalog {
     fn new() -> Self {
         Self {
@@ -536,6 +928,12 @@ impl IndexCatalog {
         self.indexes.read().get(&id).cloned()
     }
 
+    fn all(&self) -> Vec<IndexDefinition> {
+        let mut all: Vec<_> = self.indexes.read().values().cloned().collect();
+        all.sort_by_key(|index| index.id);
+        all
+    }
+
     fn for_label(&self, label: LabelId) -> Vec<IndexId> {
         self.label_indexes
             .read()
@@ -650,6 +1048,16 @@ impl std::fmt::Display for CatalogError {
 
 impl std::error::Error for CatalogError {}
 
+impl From<CatalogError> for Error {
+    fn from(e: CatalogError) -> Self {
+        match e {
+            CatalogError::LabelNotFound(name) => Error::LabelNotFound(name),
+            CatalogError::PropertyKeyNotFound(name) => Error::PropertyNotFound(name),
+            other => Error::InvalidValue(other.to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1174,4 +1582,121 @@ mod tests {
             CatalogError::LabelNotFound("Y".to_string())
         );
     }
+
+    #[test]
+    fn test_catalog_validate_declared_names() {
+        let catalog = Catalog::new();
+        catalog.get_or_create_label("Person");
+        catalog.get_or_create_property_key("name");
+        catalog.get_or_create_edge_type("KNOWS");
+
+        assert!(catalog.validate_label("Person").is_ok());
+        assert!(catalog.validate_property_key("name").is_ok());
+        assert!(catalog.validate_edge_type("KNOWS").is_ok());
+
+        assert_eq!(
+            catalog.validate_label("Persn"),
+            Err(CatalogError::LabelNotFound("Persn".to_string()))
+        );
+        assert_eq!(
+            catalog.validate_property_key("nmae"),
+            Err(CatalogError::PropertyKeyNotFound("nmae".to_string()))
+        );
+        assert_eq!(
+            catalog.validate_edge_type("KNOWZ"),
+            Err(CatalogError::EdgeTypeNotFound("KNOWZ".to_string()))
+        );
+
+        assert!(
+            catalog
+                .validate_write(["Person"], Some("KNOWS"), ["name"])
+                .is_ok()
+        );
+        assert_eq!(
+            catalog.validate_write(["Person"], None, ["name", "nmae"]),
+            Err(CatalogError::PropertyKeyNotFound("nmae".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_catalog_version_tracks_changes() {
+        let catalog = Catalog::new();
+        let v0 = catalog.version();
+
+        let person = catalog.get_or_create_label("Person");
+        let v1 = catalog.version();
+        assert!(v1 > v0);
+
+        // Looking up an existing name is not a change
+        catalog.get_or_create_label("Person");
+        assert_eq!(catalog.version(), v1);
+
+        let name = catalog.get_or_create_property_key("name");
+        let index = catalog.create_index(person, name, IndexType::Hash);
+        let v2 = catalog.version();
+        assert!(v2 > v1);
+
+        assert!(catalog.drop_index(index));
+        let v3 = catalog.version();
+        assert!(v3 > v2);
+        assert!(!catalog.drop_index(index));
+        assert_eq!(catalog.version(), v3);
+
+        catalog.set_property_normalizer("name", PropertyNormalizer::new(|value| value));
+        assert!(catalog.version() > v3);
+    }
+
+    #[test]
+    fn test_catalog_property_normalizers() {
+        use grafeo_common::types::Value;
+
+        let catalog = Catalog::new();
+        assert!(catalog.property_normalizer("email").is_none());
+
+        catalog.set_property_normalizer(
+            "email",
+            PropertyNormalizer::new(|value| match value {
+                Value::String(s) => Value::String(s.to_lowercase().into()),
+                other => other,
+            }),
+        );
+        assert!(catalog.get_property_key_id("email").is_some());
+
+        let normalizer = catalog.property_normalizer("email").unwrap();
+        assert_eq!(
+            normalizer.apply(Value::from("Alice@Example.COM")),
+            Value::from("alice@example.com")
+        );
+        assert_eq!(normalizer.apply(Value::Int64(1)), Value::Int64(1));
+        assert!(catalog.property_normalizer("name").is_none());
+    }
+
+    #[test]
+    fn test_catalog_case_insensitive_labels() {
+        let catalog = Catalog::new();
+        catalog.get_or_create_label("Person");
+        catalog.get_or_create_edge_type("KNOWS");
+        assert!(!catalog.case_insensitive_labels());
+        assert_eq!(catalog.get_label_id("person"), None);
+        assert!(catalog.get_label_ids("PERSON").is_empty());
+        assert_eq!(catalog.get_edge_type_id("knows"), None);
+
+        let catalog = Catalog::new().with_case_insensitive_labels();
+        let person = catalog.get_or_create_label("Person");
+        let shouting = catalog.get_or_create_label("PERSON");
+        let knows = catalog.get_or_create_edge_type("KNOWS");
+        assert!(catalog.case_insensitive_labels());
+
+        // Exact names win; otherwise the first casing created
+        assert_eq!(catalog.get_label_id("PERSON"), Some(shouting));
+        assert_eq!(catalog.get_label_id("person"), Some(person));
+        assert_eq!(catalog.get_label_ids("pErSoN"), vec![person, shouting]);
+        assert_eq!(catalog.get_edge_type_id("knows"), Some(knows));
+        assert_eq!(catalog.get_edge_type_ids("Knows"), vec![knows]);
+        assert!(catalog.get_label_ids("Company").is_empty());
+
+        // Names keep the casing they were created with
+        assert_eq!(catalog.get_label_name(person).as_deref(), Some("Person"));
+        assert_eq!(catalog.label_count(), 2);
+    }
 }
@@ -0,0 +1,515 @@
+//! Change feeds over committed writes.
+//!
+//! Every write that goes through the write-ahead log path - direct API
+//! calls, batches, and imports - is published here once it's committed, in
+//! commit order, to each open [`ChangeStream`]. Changes are numbered by a
+//! position that keeps growing for the lifetime of the database, and the
+//! most recent ones are retained so a subscriber can resume from the
+//! position after the last change it saw.
+//!
+//! Publishing never waits for subscribers. Each stream buffers a bounded
+//! number of changes; once the buffer is full, further changes are dropped
+//! and the stream reports how many it missed with a
+//! [`ChangeEvent::Overflow`].
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::{Condvar, Mutex};
+
+use grafeo_adapters::storage::wal::WalRecord;
+use grafeo_common::types::{EdgeId, NodeId, Timestamp, Value};
+use grafeo_common::utils::error::{Error, Result};
+use grafeo_common::utils::hash::FxHashMap;
+
+/// Changes a stream buffers before it starts dropping them.
+pub(crate) const DEFAULT_BUFFER_SIZE: usize = 1024;
+
+/// Recent changes kept for subscribers that start from a position.
+pub(crate) const DEFAULT_HISTORY_SIZE: usize = 4096;
+
+/// One committed change to the graph.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeEvent {
+    /// A node was created.
+    NodeCreated {
+        /// The new node.
+        id: NodeId,
+        /// Its labels.
+        labels: Vec<String>,
+    },
+    /// A node was deleted.
+    NodeDeleted {
+        /// The deleted node.
+        id: NodeId,
+    },
+    /// An edge was created.
+    EdgeCreated {
+        /// The new edge.
+        id: EdgeId,
+        /// Its source node.
+        src: NodeId,
+        /// Its destination node.
+        dst: NodeId,
+        /// Its type.
+        edge_type: String,
+    },
+    /// An edge was deleted.
+    EdgeDeleted {
+        /// The deleted edge.
+        id: EdgeId,
+    },
+    /// A node property was set.
+    NodePropertySet {
+        /// The node.
+        id: NodeId,
+        /// Property key.
+        key: String,
+        /// The new value.
+        value: Value,
+    },
+    /// An edge property was set.
+    EdgePropertySet {
+        /// The edge.
+        id: EdgeId,
+        /// Property key.
+        key: String,
+        /// The new value.
+        value: Value,
+    },
+    /// A label was added to a node.
+    LabelAdded {
+        /// The node.
+        id: NodeId,
+        /// The added label.
+        label: String,
+    },
+    /// A label was removed from a node.
+    LabelRemoved {
+        /// The node.
+        id: NodeId,
+        /// The removed label.
+        label: String,
+    },
+    /// An edge's validity interval was set.
+    EdgeValiditySet {
+        /// The edge.
+        id: EdgeId,
+        /// Start of the interval (inclusive), or `None` if open.
+        valid_from: Option<Timestamp>,
+        /// End of the interval (exclusive), or `None` if open.
+        valid_to: Option<Timestamp>,
+    },
+    /// The stream's buffer was full, so changes were dropped.
+    ///
+    /// The change carrying this event has the position of the first dropped
+    /// change; resubscribing from there recovers them if they're still
+    /// retained.
+    Overflow {
+        /// How many consecutive changes were dropped.
+        missed: u64,
+    },
+}
+
+impl ChangeEvent {
+    /// The change a WAL record describes, if it describes one.
+    fn from_record(record: &WalRecord) -> Option<Self> {
+        let event = match record {
+            WalRecord::CreateNode { id, labels } => Self::NodeCreated {
+                id: *id,
+                labels: labels.clone(),
+            },
+            WalRecord::DeleteNode { id } => Self::NodeDeleted { id: *id },
+            WalRecord::CreateEdge {
+                id,
+                src,
+                dst,
+                edge_type,
+            } => Self::EdgeCreated {
+                id: *id,
+                src: *src,
+                dst: *dst,
+                edge_type: edge_type.clone(),
+            },
+            WalRecord::DeleteEdge { id } => Self::EdgeDeleted { id: *id },
+            WalRecord::SetNodeProperty { id, key, value } => Self::NodePropertySet {
+                id: *id,
+                key: key.clone(),
+                value: value.clone(),
+            },
+            WalRecord::SetEdgeProperty { id, key, value } => Self::EdgePropertySet {
+                id: *id,
+                key: key.clone(),
+                value: value.clone(),
+            },
+            WalRecord::AddNodeLabel { id, label } => Self::LabelAdded {
+                id: *id,
+                label: label.clone(),
+            },
+            WalRecord::RemoveNodeLabel { id, label } => Self::LabelRemoved {
+                id: *id,
+                label: label.clone(),
+            },
+            WalRecord::SetEdgeValidity {
+                id,
+                valid_from,
+                valid_to,
+            } => Self::EdgeValiditySet {
+                id: *id,
+                valid_from: *valid_from,
+                valid_to: *valid_to,
+            },
+            WalRecord::TxCommit { .. }
+            | WalRecord::TxAbort { .. }
+            | WalRecord::Checkpoint { .. }
+            | WalRecord::CompactionBase => return None,
+        };
+        Some(event)
+    }
+}
+
+/// A change and its position in the feed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Position of the change; each committed change gets the next one.
+    pub position: u64,
+    /// What changed.
+    pub event: ChangeEvent,
+}
+
+/// Publishes committed changes to the open streams of a database.
+pub(crate) struct ChangeFeed {
+    /// Changes each stream buffers before dropping.
+    buffer_size: usize,
+    /// Recent changes retained for resuming subscribers.
+    history_size: usize,
+    state: Mutex<FeedState>,
+}
+
+struct FeedState {
+    /// Position the next published change gets.
+    next_position: u64,
+    /// The most recent changes, oldest first.
+    history: VecDeque<Change>,
+    /// Open streams, by subscription id.
+    subscribers: FxHashMap<u64, Arc<Subscriber>>,
+    next_subscriber: u64,
+}
+
+impl Default for ChangeFeed {
+    fn default() -> Self {
+        Self::new(DEFAULT_BUFFER_SIZE, DEFAULT_HISTORY_SIZE)
+    }
+}
+
+impl ChangeFeed {
+    /// Creates a feed whose streams buffer `buffer_size` changes each, and
+    /// which retains the last `history_size` changes.
+    pub(crate) fn new(buffer_size: usize, history_size: usize) -> Self {
+        Self {
+            buffer_size: buffer_size.max(1),
+            history_size,
+            state: Mutex::new(FeedState {
+                next_position: 0,
+                history: VecDeque::new(),
+                subscribers: FxHashMap::default(),
+                next_subscriber: 0,
+            }),
+        }
+    }
+
+    /// Publishes the changes in committed WAL records, in order.
+    ///
+    /// Records that don't change the graph, like commit markers, are
+    /// skipped.
+    pub(crate) fn publish(&self, records: &[WalRecord]) {
+        let mut state = self.state.lock();
+        for event in records.iter().filter_map(ChangeEvent::from_record) {
+            let change = Change {
+                position: state.next_position,
+                event,
+            };
+            state.next_position += 1;
+            for subscriber in state.subscribers.values() {
+                subscriber.push(change.clone(), self.buffer_size);
+            }
+            if self.history_size > 0 {
+                if state.history.len() == self.history_size {
+                    state.history.pop_front();
+                }
+                state.history.push_back(change);
+            }
+        }
+    }
+
+    /// Opens a stream of the changes published from now on.
+    pub(crate) fn subscribe(self: &Arc<Self>) -> ChangeStream {
+        let mut state = self.state.lock();
+        self.register(&mut state)
+    }
+
+    /// Opens a stream starting at `position`, replaying the retained
+    /// changes from there before new ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `position` is ahead of the feed, or if changes
+    /// from it are no longer retained.
+    pub(crate) fn subscribe_from(self: &Arc<Self>, position: u64) -> Result<ChangeStream> {
+        let mut state = self.state.lock();
+        let oldest = state.next_position - state.history.len() as u64;
+        if position > state.next_position {
+            return Err(Error::InvalidValue(format!(
+                "change position {position} is ahead of the feed, which is at {}",
+                state.next_position
+            )));
+        }
+        if position < oldest {
+            return Err(Error::InvalidValue(format!(
+                "changes from position {position} are no longer retained; the oldest is {oldest}"
+            )));
+        }
+
+        let stream = self.register(&mut state);
+        let skip = (position - oldest) as usize;
+        for change in state.history.iter().skip(skip) {
+            stream.subscriber.push(change.clone(), self.buffer_size);
+        }
+        Ok(stream)
+    }
+
+    fn register(self: &Arc<Self>, state: &mut FeedState) -> ChangeStream {
+        let id = state.next_subscriber;
+        state.next_subscriber += 1;
+        let subscriber = Arc::new(Subscriber::default());
+        state.subscribers.insert(id, Arc::clone(&subscriber));
+        ChangeStream {
+            feed: Arc::clone(self),
+            id,
+            subscriber,
+        }
+    }
+
+    /// Number of open streams.
+    #[cfg(test)]
+    pub(crate) fn subscriber_count(&self) -> usize {
+        self.state.lock().subscribers.len()
+    }
+
+    /// Position the next published change will get.
+    pub(crate) fn position(&self) -> u64 {
+        self.state.lock().next_position
+    }
+}
+
+/// A stream's buffer, shared between the feed and the stream.
+#[derive(Default)]
+struct Subscriber {
+    buffer: Mutex<VecDeque<Change>>,
+    ready: Condvar,
+}
+
+impl Subscriber {
+    /// Buffers `change`, or counts it as missed if the buffer is full.
+    ///
+    /// An overflow marker may sit past capacity, so the changes buffered
+    /// after it are known to follow the gap.
+    fn push(&self, change: Change, capacity: usize) {
+        let mut buffer = self.buffer.lock();
+        let buffered = buffer.len()
+            - usize::from(matches!(
+                buffer.back(),
+                Some(Change {
+                    event: ChangeEvent::Overflow { .. },
+                    ..
+                })
+            ));
+        if buffered < capacity {
+            buffer.push_back(change);
+        } else if let Some(Change {
+            event: ChangeEvent::Overflow { missed },
+            ..
+        }) = buffer.back_mut()
+        {
+            *missed += 1;
+        } else {
+            buffer.push_back(Change {
+                position: change.position,
+                event: ChangeEvent::Overflow { missed: 1 },
+            });
+        }
+        self.ready.notify_one();
+    }
+}
+
+/// Committed changes, in commit order, from
+/// [`Session::subscribe()`](crate::Session::subscribe).
+///
+/// Dropping the stream unsubscribes it.
+pub struct ChangeStream {
+    feed: Arc<ChangeFeed>,
+    id: u64,
+    subscriber: Arc<Subscriber>,
+}
+
+impl ChangeStream {
+    /// Returns the next buffered change without waiting.
+    pub fn try_next(&mut self) -> Option<Change> {
+        self.subscriber.buffer.lock().pop_front()
+    }
+
+    /// Returns the next change, waiting up to `timeout` for one to be
+    /// committed.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<Change> {
+        let deadline = Instant::now() + timeout;
+        let mut buffer = self.subscriber.buffer.lock();
+        while buffer.is_empty() {
+            if self
+                .subscriber
+                .ready
+                .wait_until(&mut buffer, deadline)
+                .timed_out()
+            {
+                break;
+            }
+        }
+        buffer.pop_front()
+    }
+
+    /// Iterates over the changes buffered so far, without waiting for more.
+    pub fn try_iter(&mut self) -> impl Iterator<Item = Change> + '_ {
+        std::iter::from_fn(|| self.try_next())
+    }
+}
+
+impl Drop for ChangeStream {
+    fn drop(&mut self) {
+        self.feed.state.lock().subscribers.remove(&self.id);
+    }
+}
+
+impl std::fmt::Debug for ChangeStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChangeStream")
+            .field("id", &self.id)
+            .field("buffered", &self.subscriber.buffer.lock().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn created(id: u64) -> WalRecord {
+        WalRecord::CreateNode {
+            id: NodeId::new(id),
+            labels: vec!["Item".to_string()],
+        }
+    }
+
+    fn positions(stream: &mut ChangeStream) -> Vec<u64> {
+        stream.try_iter().map(|change| change.position).collect()
+    }
+
+    #[test]
+    fn test_streams_see_changes_published_after_subscribing() {
+        let feed = Arc::new(ChangeFeed::default());
+        feed.publish(&[created(0)]);
+        let mut stream = feed.subscribe();
+        feed.publish(&[
+            created(1),
+            WalRecord::TxCommit {
+                tx_id: grafeo_common::types::TxId::SYSTEM,
+            },
+            WalRecord::DeleteNode { id: NodeId::new(0) },
+        ]);
+
+        let changes: Vec<Change> = stream.try_iter().collect();
+        assert_eq!(
+            changes,
+            vec![
+                Change {
+                    position: 1,
+                    event: ChangeEvent::NodeCreated {
+                        id: NodeId::new(1),
+                        labels: vec!["Item".to_string()],
+                    },
+                },
+                Change {
+                    position: 2,
+                    event: ChangeEvent::NodeDeleted { id: NodeId::new(0) },
+                },
+            ]
+        );
+        assert!(stream.try_next().is_none());
+    }
+
+    #[test]
+    fn test_full_buffer_drops_with_overflow_marker() {
+        let feed = Arc::new(ChangeFeed::new(2, 0));
+        let mut stream = feed.subscribe();
+        for id in 0..5 {
+            feed.publish(&[created(id)]);
+        }
+        assert_eq!(stream.try_next().unwrap().position, 0);
+        feed.publish(&[created(5)]);
+
+        let changes: Vec<Change> = stream.try_iter().collect();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].position, 1);
+        assert_eq!(
+            changes[1],
+            Change {
+                position: 2,
+                event: ChangeEvent::Overflow { missed: 3 },
+            }
+        );
+        assert_eq!(changes[2].position, 5);
+    }
+
+    #[test]
+    fn test_subscribe_from_retained_position() {
+        let feed = Arc::new(ChangeFeed::new(16, 3));
+        for id in 0..5 {
+            feed.publish(&[created(id)]);
+        }
+        assert_eq!(feed.position(), 5);
+
+        let mut stream = feed.subscribe_from(3).unwrap();
+        feed.publish(&[created(5)]);
+        assert_eq!(positions(&mut stream), vec![3, 4, 5]);
+
+        // Position 2 fell out of the history when 5 was published
+        assert!(feed.subscribe_from(2).is_err());
+        assert!(feed.subscribe_from(7).is_err());
+        let mut caught_up = feed.subscribe_from(6).unwrap();
+        assert!(caught_up.try_next().is_none());
+    }
+
+    #[test]
+    fn test_dropping_stream_unsubscribes() {
+        let feed = Arc::new(ChangeFeed::default());
+        let first = feed.subscribe();
+        let second = feed.subscribe();
+        assert_eq!(feed.subscriber_count(), 2);
+        drop(first);
+        assert_eq!(feed.subscriber_count(), 1);
+        drop(second);
+        assert_eq!(feed.subscriber_count(), 0);
+    }
+
+    #[test]
+    fn test_next_timeout_wakes_on_publish() {
+        let feed = Arc::new(ChangeFeed::default());
+        let mut stream = feed.subscribe();
+        assert!(stream.next_timeout(Duration::from_millis(10)).is_none());
+
+        let publisher = Arc::clone(&feed);
+        let handle = std::thread::spawn(move || publisher.publish(&[created(0)]));
+        let change = stream.next_timeout(Duration::from_secs(10));
+        handle.join().unwrap();
+        assert_eq!(change.map(|c| c.position), Some(0));
+    }
+}
@@ -1,9 +1,18 @@
 //! Database configuration.
 
 use std::path::PathBuf;
+use std::time::Duration;
+
+use grafeo_adapters::query::DEFAULT_MAX_NESTING_DEPTH;
+use grafeo_common::utils::error::{Error, QueryError, QueryErrorKind, Result};
+use grafeo_core::execution::memory::DEFAULT_ACCOUNTING_GRANULARITY;
+use grafeo_core::execution::{Collation, SpillableOperator};
+
+use crate::query::plan::LogicalPlan;
 
 /// Database configuration.
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)] // independent feature switches
 pub struct Config {
     /// Path to the database directory (None for in-memory only).
     pub path: Option<PathBuf>,
@@ -11,10 +20,23 @@ pub struct Config {
     /// Memory limit in bytes (None for unlimited).
     pub memory_limit: Option<usize>,
 
+    /// How many bytes a query's operators buffer before charging them
+    /// against `memory_limit`.
+    ///
+    /// Smaller values bound the overshoot past the limit more tightly, at
+    /// the cost of more frequent accounting.
+    pub memory_accounting_granularity: usize,
+
     /// Path for spilling data to disk under memory pressure.
     pub spill_path: Option<PathBuf>,
 
+    /// Operator kinds that fail with a memory limit error instead of
+    /// spilling, for callers that prefer a fast failure over slow disk.
+    pub spill_disabled: Vec<SpillableOperator>,
+
     /// Number of worker threads for query execution.
+    ///
+    /// With more than one, inner equi-joins run as parallel hash joins.
     pub threads: usize,
 
     /// Whether to enable WAL for durability.
@@ -31,6 +53,181 @@ pub struct Config {
 
     /// Adaptive execution configuration.
     pub adaptive: AdaptiveConfig,
+
+    /// Default string collation for sorting and range predicates.
+    ///
+    /// Byte order by default. Sessions can override this per query.
+    pub collation: Collation,
+
+    /// How writes are validated against declared schema.
+    pub schema_mode: SchemaMode,
+
+    /// What happens when an insert or update gives the same property key
+    /// more than once.
+    pub duplicate_keys: DuplicateKeyPolicy,
+
+    /// How long idempotency keys are remembered.
+    ///
+    /// A retry with the same key inside this window returns the first
+    /// attempt's result instead of running again. See
+    /// [`Session::execute_idempotent`](crate::Session::execute_idempotent).
+    pub idempotency_window: Duration,
+
+    /// How many changes each [`ChangeStream`](crate::ChangeStream) buffers.
+    ///
+    /// A subscriber that falls this far behind misses the changes that
+    /// follow, and is told how many with an overflow event.
+    pub change_buffer_size: usize,
+
+    /// How many recent changes are kept for subscribers that start from a
+    /// position, or 0 to keep none.
+    pub change_history_size: usize,
+
+    /// Change ratio past which statistics are refreshed automatically.
+    ///
+    /// Once more than this fraction of a label's nodes (or an edge type's
+    /// edges) has changed since statistics were last computed, the next
+    /// query recomputes them before planning. `None` disables automatic
+    /// refreshes.
+    pub statistics_refresh_ratio: Option<f64>,
+
+    /// How many query results to cache, or 0 to disable result caching.
+    ///
+    /// Cached results are returned for repeated queries (with the same
+    /// parameters) until the graph changes. Queries inside explicit
+    /// transactions are never cached.
+    pub result_cache_size: usize,
+
+    /// Limits that reject overly complex queries before they run.
+    pub query_limits: QueryLimits,
+
+    /// How long a query may run before it's stopped, if limited.
+    ///
+    /// Sessions start with this timeout; see
+    /// [`Session::execute_with`](crate::Session::execute_with) for
+    /// overriding it per call. `None` lets queries run to completion.
+    pub query_timeout: Option<Duration>,
+
+    /// Most nodes and edges a single transaction may write, if limited.
+    ///
+    /// A transaction that goes past it fails and is rolled back, so callers
+    /// have to split very large changes into batches. `None` means no limit.
+    pub max_write_set_size: Option<usize>,
+
+    /// Whether labels and edge types match ignoring case.
+    ///
+    /// When set, `(:person)` matches nodes labelled `Person` in scans,
+    /// label filters, and catalog lookups. Off by default, so differently
+    /// cased names stay distinct.
+    pub case_insensitive_labels: bool,
+
+    /// Whether the optimizer reorders joins by estimated cost.
+    ///
+    /// On by default. Turning it off runs joins in the order the query
+    /// wrote them, which can help when comparing plans.
+    pub join_reorder: bool,
+}
+
+/// Controls whether writes must use declared labels, edge types, and properties.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SchemaMode {
+    /// Schema-on-read: any label, edge type, or property can be written.
+    #[default]
+    Flexible,
+    /// Only names declared in the [`Catalog`](crate::Catalog) may be written.
+    ///
+    /// Catches typos like `Persn` vs `Person` at write time.
+    Strict,
+}
+
+/// What happens when a write gives the same property key more than once,
+/// as in `INSERT (:Person {name: 'a', name: 'b'})`.
+///
+/// Duplicates usually come from generated queries or merged maps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeyPolicy {
+    /// The last value given for a key wins.
+    #[default]
+    Lenient,
+    /// The write fails to plan, so an accidental duplicate can't silently
+    /// drop a value.
+    Strict,
+}
+
+/// How [`Session::run`](crate::Session::run) hands back query results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultMode {
+    /// Runs the query to completion and returns every row at once, so rows
+    /// can be indexed and counted. Best for small results.
+    #[default]
+    Materialized,
+    /// Returns an iterator that runs the query as rows are pulled, so a
+    /// large result never has to fit in memory.
+    Streaming,
+}
+
+/// Limits that reject overly complex queries before they run.
+///
+/// Useful when queries come from untrusted clients: a pathologically nested
+/// expression or an enormous plan fails with a
+/// [`Complexity`](grafeo_common::utils::error::QueryErrorKind::Complexity)
+/// error instead of exhausting the stack or memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryLimits {
+    /// How deeply a query's expressions may nest.
+    ///
+    /// Checked while parsing GQL and Cypher, before recursion gets deep
+    /// enough to overflow the parser's stack.
+    pub max_depth: usize,
+
+    /// How many operators a query plan may contain.
+    ///
+    /// Checked after translation, before the plan is optimized.
+    pub max_operators: usize,
+}
+
+impl Default for QueryLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_NESTING_DEPTH,
+            max_operators: 10_000,
+        }
+    }
+}
+
+impl QueryLimits {
+    /// Sets how deeply expressions may nest.
+    #[must_use]
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets how many operators a plan may contain.
+    #[must_use]
+    pub fn with_max_operators(mut self, max_operators: usize) -> Self {
+        self.max_operators = max_operators;
+        self
+    }
+
+    /// Checks that `plan` has no more operators than allowed.
+    ///
+    /// # Errors
+    ///
+    /// Returns a complexity error if the plan is too large.
+    pub fn check_plan(&self, plan: &LogicalPlan) -> Result<()> {
+        let operators = plan.operator_count();
+        if operators > self.max_operators {
+            return Err(Error::Query(QueryError::new(
+                QueryErrorKind::Complexity,
+                format!(
+                    "Query plan has {operators} operators, more than the limit of {}",
+                    self.max_operators
+                ),
+            )));
+        }
+        Ok(())
+    }
 }
 
 /// Configuration for adaptive query execution.
@@ -105,13 +302,28 @@ impl Default for Config {
         Self {
             path: None,
             memory_limit: None,
+            memory_accounting_granularity: DEFAULT_ACCOUNTING_GRANULARITY,
             spill_path: None,
+            spill_disabled: Vec::new(),
             threads: num_cpus::get(),
             wal_enabled: true,
             wal_flush_interval_ms: 100,
             backward_edges: true,
             query_logging: false,
             adaptive: AdaptiveConfig::default(),
+            collation: Collation::Binary,
+            schema_mode: SchemaMode::Flexible,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            idempotency_window: Duration::from_mins(10),
+            change_buffer_size: crate::changefeed::DEFAULT_BUFFER_SIZE,
+            change_history_size: crate::changefeed::DEFAULT_HISTORY_SIZE,
+            statistics_refresh_ratio: Some(0.1),
+            result_cache_size: 0,
+            query_limits: QueryLimits::default(),
+            query_timeout: None,
+            max_write_set_size: None,
+            case_insensitive_labels: false,
+            join_reorder: true,
         }
     }
 }
@@ -144,6 +356,13 @@ impl Config {
         self
     }
 
+    /// Sets how many bytes operators buffer between memory limit checks.
+    #[must_use]
+    pub fn with_memory_accounting_granularity(mut self, bytes: usize) -> Self {
+        self.memory_accounting_granularity = bytes;
+        self
+    }
+
     /// Sets the number of worker threads.
     #[must_use]
     pub fn with_threads(mut self, threads: usize) -> Self {
@@ -181,6 +400,16 @@ impl Config {
         self
     }
 
+    /// Makes `operator` fail with a memory limit error instead of spilling
+    /// once it outgrows its share of the memory limit.
+    #[must_use]
+    pub fn with_spill_disabled(mut self, operator: SpillableOperator) -> Self {
+        if !self.spill_disabled.contains(&operator) {
+            self.spill_disabled.push(operator);
+        }
+        self
+    }
+
     /// Sets the adaptive execution configuration.
     #[must_use]
     pub fn with_adaptive(mut self, adaptive: AdaptiveConfig) -> Self {
@@ -194,6 +423,110 @@ impl Config {
         self.adaptive.enabled = false;
         self
     }
+
+    /// Sets the default string collation.
+    #[must_use]
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Enables strict schema mode.
+    ///
+    /// Labels, edge types, and property keys must be declared (see
+    /// [`GrafeoDB::declare_label`](crate::GrafeoDB::declare_label)) before
+    /// queries can write them.
+    #[must_use]
+    pub fn with_strict_schema(mut self) -> Self {
+        self.schema_mode = SchemaMode::Strict;
+        self
+    }
+
+    /// Sets what happens when a write gives the same property key more
+    /// than once.
+    #[must_use]
+    pub fn with_duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Sets how long idempotency keys are remembered.
+    #[must_use]
+    pub fn with_idempotency_window(mut self, window: Duration) -> Self {
+        self.idempotency_window = window;
+        self
+    }
+
+    /// Sets how many changes each change stream buffers.
+    #[must_use]
+    pub fn with_change_buffer_size(mut self, size: usize) -> Self {
+        self.change_buffer_size = size;
+        self
+    }
+
+    /// Sets how many recent changes are kept for resuming subscribers.
+    #[must_use]
+    pub fn with_change_history_size(mut self, size: usize) -> Self {
+        self.change_history_size = size;
+        self
+    }
+
+    /// Sets the change ratio past which statistics are refreshed automatically.
+    #[must_use]
+    pub fn with_statistics_refresh_ratio(mut self, ratio: f64) -> Self {
+        self.statistics_refresh_ratio = Some(ratio);
+        self
+    }
+
+    /// Disables automatic statistics refreshes.
+    #[must_use]
+    pub fn without_statistics_refresh(mut self) -> Self {
+        self.statistics_refresh_ratio = None;
+        self
+    }
+
+    /// Caches up to `size` query results until the graph changes.
+    #[must_use]
+    pub fn with_result_cache(mut self, size: usize) -> Self {
+        self.result_cache_size = size;
+        self
+    }
+
+    /// Sets the limits that reject overly complex queries.
+    #[must_use]
+    pub fn with_query_limits(mut self, limits: QueryLimits) -> Self {
+        self.query_limits = limits;
+        self
+    }
+
+    /// Stops queries that run longer than `timeout`.
+    #[must_use]
+    pub fn with_query_timeout(mut self, timeout: Duration) -> Self {
+        self.query_timeout = Some(timeout);
+        self
+    }
+
+    /// Limits how many nodes and edges a single transaction may write.
+    #[must_use]
+    pub fn with_max_write_set_size(mut self, size: usize) -> Self {
+        self.max_write_set_size = Some(size);
+        self
+    }
+
+    /// Matches labels and edge types ignoring case.
+    #[must_use]
+    pub fn with_case_insensitive_labels(mut self) -> Self {
+        self.case_insensitive_labels = true;
+        self
+    }
+
+    /// Runs joins in the order the query wrote them instead of reordering
+    /// them by estimated cost.
+    #[must_use]
+    pub fn without_join_reorder(mut self) -> Self {
+        self.join_reorder = false;
+        self
+    }
 }
 
 /// Helper function to get CPU count (fallback implementation).
@@ -8,16 +8,28 @@ use std::sync::Arc;
 use parking_lot::RwLock;
 
 use grafeo_adapters::storage::wal::{WalConfig, WalManager, WalRecord, WalRecovery};
-use grafeo_common::memory::buffer::{BufferManager, BufferManagerConfig};
+use grafeo_common::memory::buffer::{BufferManager, BufferManagerConfig, BufferStats};
+use grafeo_common::types::{NodeId, Value};
 use grafeo_common::utils::error::Result;
-use grafeo_core::graph::lpg::LpgStore;
+use grafeo_common::utils::hash::FxHashSet;
+use grafeo_core::graph::lpg::{Edge, LpgStore, LpgStoreConfig, Node};
 #[cfg(feature = "rdf")]
 use grafeo_core::graph::rdf::RdfStore;
-
-use crate::config::Config;
+use grafeo_core::index::HashIndex;
+
+use crate::builder::GrafeoDBBuilder;
+use crate::catalog::{self, Catalog, EdgeMultiplicity, PropertyNormalizer};
+use crate::changefeed::ChangeFeed;
+use crate::config::{Config, SchemaMode};
+use crate::idempotency::IdempotencyStore;
+use crate::projection::GraphProjection;
+use crate::query::ResultCache;
 use crate::session::Session;
 use crate::transaction::TransactionManager;
 
+/// Directory in the database path where indexes are saved on close.
+const INDEX_DIR: &str = "indexes";
+
 /// Your handle to a Grafeo database.
 ///
 /// Start here. Create one with [`new_in_memory()`](Self::new_in_memory) for
@@ -52,8 +64,18 @@ pub struct GrafeoDB {
     tx_manager: Arc<TransactionManager>,
     /// Unified buffer manager.
     buffer_manager: Arc<BufferManager>,
+    /// Declared labels, edge types and property keys.
+    catalog: Arc<Catalog>,
     /// Write-ahead log manager (if durability is enabled).
     wal: Option<Arc<WalManager>>,
+    /// Recently seen idempotency keys and their results.
+    idempotency: Arc<IdempotencyStore>,
+    /// Keys of nodes loaded by streaming imports.
+    import_keys: Arc<HashIndex<String, NodeId>>,
+    /// Committed changes, published to subscribers.
+    changes: Arc<ChangeFeed>,
+    /// Cached query results (if result caching is enabled).
+    result_cache: Option<Arc<ResultCache>>,
     /// Whether the database is open.
     is_open: RwLock<bool>,
 }
@@ -79,6 +101,24 @@ impl GrafeoDB {
         Self::with_config(Config::in_memory()).expect("In-memory database creation should not fail")
     }
 
+    /// Starts building a database with options chained in one place.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::builder()
+    ///     .with_threads(4)
+    ///     .with_result_cache(64)
+    ///     .build()?;
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    #[must_use]
+    pub fn builder() -> GrafeoDBBuilder {
+        GrafeoDBBuilder::new()
+    }
+
     /// Opens a database at the given path, creating it if it doesn't exist.
     ///
     /// If you've used this path before, Grafeo recovers your data from the
@@ -125,10 +165,17 @@ impl GrafeoDB {
     /// # Ok::<(), grafeo_common::utils::error::Error>(())
     /// ```
     pub fn with_config(config: Config) -> Result<Self> {
-        let store = Arc::new(LpgStore::new());
+        let store = Arc::new(LpgStore::with_config(LpgStoreConfig {
+            case_insensitive_labels: config.case_insensitive_labels,
+            ..LpgStoreConfig::default()
+        }));
         #[cfg(feature = "rdf")]
         let rdf_store = Arc::new(RdfStore::new());
-        let tx_manager = Arc::new(TransactionManager::new());
+        let mut tx_manager = TransactionManager::new();
+        if let Some(limit) = config.max_write_set_size {
+            tx_manager = tx_manager.with_write_set_limit(limit);
+        }
+        let tx_manager = Arc::new(tx_manager);
 
         // Create buffer manager with configured limits
         let buffer_config = BufferManagerConfig {
@@ -169,13 +216,38 @@ impl GrafeoDB {
             None
         };
 
+        let mut catalog = Catalog::new();
+        if config.case_insensitive_labels {
+            catalog = catalog.with_case_insensitive_labels();
+        }
+
+        // Indexes saved by the last close, now that the graph is recovered
+        if let (Some(db_path), Some(wal)) = (&config.path, &wal) {
+            let checkpoint = wal.unchanged_checkpoint()?;
+            catalog::load_indexes(
+                &catalog,
+                &store,
+                &db_path.join(INDEX_DIR),
+                checkpoint.as_ref(),
+            )?;
+        }
+
         Ok(Self {
+            idempotency: Arc::new(IdempotencyStore::new(config.idempotency_window)),
+            import_keys: Arc::new(HashIndex::new()),
+            changes: Arc::new(ChangeFeed::new(
+                config.change_buffer_size,
+                config.change_history_size,
+            )),
+            result_cache: (config.result_cache_size > 0)
+                .then(|| Arc::new(ResultCache::new(config.result_cache_size))),
             config,
             store,
             #[cfg(feature = "rdf")]
             rdf_store,
             tx_manager,
             buffer_manager,
+            catalog: Arc::new(catalog),
             wal,
             is_open: RwLock::new(true),
         })
@@ -209,6 +281,13 @@ impl GrafeoDB {
                 WalRecord::SetEdgeProperty { id, key, value } => {
                     store.set_edge_property(*id, key, value.clone());
                 }
+                WalRecord::SetEdgeValidity {
+                    id,
+                    valid_from,
+                    valid_to,
+                } => {
+                    store.set_edge_validity(*id, *valid_from, *valid_to);
+                }
                 WalRecord::AddNodeLabel { id, label } => {
                     store.add_label(*id, label);
                 }
@@ -217,7 +296,8 @@ impl GrafeoDB {
                 }
                 WalRecord::TxCommit { .. }
                 | WalRecord::TxAbort { .. }
-                | WalRecord::Checkpoint { .. } => {
+                | WalRecord::Checkpoint { .. }
+                | WalRecord::CompactionBase => {
                     // Transaction control records don't need replay action
                     // (recovery already filtered to only committed transactions)
                 }
@@ -247,22 +327,48 @@ impl GrafeoDB {
     #[must_use]
     pub fn session(&self) -> Session {
         #[cfg(feature = "rdf")]
-        {
-            Session::with_rdf_store_and_adaptive(
-                Arc::clone(&self.store),
-                Arc::clone(&self.rdf_store),
-                Arc::clone(&self.tx_manager),
-                self.config.adaptive.clone(),
-            )
-        }
+        let mut session = Session::with_rdf_store_and_adaptive(
+            Arc::clone(&self.store),
+            Arc::clone(&self.rdf_store),
+            Arc::clone(&self.tx_manager),
+            self.config.adaptive.clone(),
+        );
         #[cfg(not(feature = "rdf"))]
-        {
-            Session::with_adaptive(
-                Arc::clone(&self.store),
-                Arc::clone(&self.tx_manager),
-                self.config.adaptive.clone(),
-            )
+        let mut session = Session::with_adaptive(
+            Arc::clone(&self.store),
+            Arc::clone(&self.tx_manager),
+            self.config.adaptive.clone(),
+        );
+        session.set_collation(self.config.collation);
+        session.set_query_limits(self.config.query_limits);
+        session.set_query_timeout(self.config.query_timeout);
+        session.set_join_reorder(self.config.join_reorder);
+        session.set_threads(self.config.threads);
+        session.set_catalog(Arc::clone(&self.catalog));
+        session.set_idempotency_store(Arc::clone(&self.idempotency));
+        session.set_import_keys(Arc::clone(&self.import_keys));
+        session.set_change_feed(Arc::clone(&self.changes));
+        if let Some(cache) = &self.result_cache {
+            session.set_result_cache(Arc::clone(cache));
+        }
+        if let Some(ratio) = self.config.statistics_refresh_ratio {
+            session.set_statistics_refresh_ratio(ratio);
+        }
+        if let Some(wal) = &self.wal {
+            session.set_wal(Arc::clone(wal));
         }
+        if self.config.schema_mode == SchemaMode::Strict {
+            session.set_strict_schema();
+        }
+        session.set_duplicate_keys(self.config.duplicate_keys);
+        if self.config.memory_limit.is_some() {
+            session.set_memory_budget(
+                Arc::clone(&self.buffer_manager),
+                self.config.memory_accounting_granularity,
+            );
+            session.set_spill_disabled(&self.config.spill_disabled);
+        }
+        session
     }
 
     /// Returns the adaptive execution configuration.
@@ -299,6 +405,19 @@ impl GrafeoDB {
         session.execute_with_params(query, params)
     }
 
+    /// Executes a GQL mutation at most once per idempotency key.
+    ///
+    /// See [`Session::execute_idempotent`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    #[cfg(feature = "gql")]
+    pub fn execute_idempotent(&self, key: &str, query: &str) -> Result<QueryResult> {
+        let session = self.session();
+        session.execute_idempotent(key, query)
+    }
+
     /// Executes a Cypher query and returns the result.
     ///
     /// # Errors
@@ -321,11 +440,8 @@ impl GrafeoDB {
         query: &str,
         params: std::collections::HashMap<String, grafeo_common::types::Value>,
     ) -> Result<QueryResult> {
-        use crate::query::processor::{QueryLanguage, QueryProcessor};
-
-        // Create processor
-        let processor = QueryProcessor::for_lpg(Arc::clone(&self.store));
-        processor.process(query, QueryLanguage::Cypher, Some(&params))
+        let session = self.session();
+        session.execute_cypher_with_params(query, params)
     }
 
     /// Executes a Gremlin query and returns the result.
@@ -365,6 +481,17 @@ impl GrafeoDB {
         session.execute_graphql(query)
     }
 
+    /// Executes a GraphQL query and returns its response as nested JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails.
+    #[cfg(feature = "graphql")]
+    pub fn execute_graphql_nested(&self, query: &str) -> Result<String> {
+        let session = self.session();
+        session.execute_graphql_nested(query)
+    }
+
     /// Executes a GraphQL query with parameters and returns the result.
     ///
     /// # Errors
@@ -398,24 +525,8 @@ impl GrafeoDB {
     /// ```
     #[cfg(all(feature = "sparql", feature = "rdf"))]
     pub fn execute_sparql(&self, query: &str) -> Result<QueryResult> {
-        use crate::query::{
-            Executor, optimizer::Optimizer, planner_rdf::RdfPlanner, sparql_translator,
-        };
-
-        // Parse and translate the SPARQL query to a logical plan
-        let logical_plan = sparql_translator::translate(query)?;
-
-        // Optimize the plan
-        let optimizer = Optimizer::new();
-        let optimized_plan = optimizer.optimize(logical_plan)?;
-
-        // Convert to physical plan using RDF planner
-        let planner = RdfPlanner::new(Arc::clone(&self.rdf_store));
-        let mut physical_plan = planner.plan(&optimized_plan)?;
-
-        // Execute the plan
-        let executor = Executor::with_columns(physical_plan.columns.clone());
-        executor.execute(physical_plan.operator.as_mut())
+        let session = self.session();
+        session.execute_sparql(query)
     }
 
     /// Returns the RDF store.
@@ -451,21 +562,119 @@ impl GrafeoDB {
         &self.store
     }
 
+    /// Returns the query result cache, if
+    /// [`Config::result_cache_size`](crate::Config::result_cache_size) enables it.
+    #[must_use]
+    pub fn result_cache(&self) -> Option<&ResultCache> {
+        self.result_cache.as_deref()
+    }
+
     /// Returns the buffer manager for memory-aware operations.
     #[must_use]
     pub fn buffer_manager(&self) -> &Arc<BufferManager> {
         &self.buffer_manager
     }
 
+    /// Returns a snapshot of the database's memory usage.
+    ///
+    /// Covers the bytes allocated now, overall and per region, the peak
+    /// allocation, and the current pressure level, so embedders can watch
+    /// memory and shed load before queries start failing. Query operators
+    /// only charge memory here when
+    /// [`Config::memory_limit`](crate::Config::memory_limit) is set.
+    #[must_use]
+    pub fn memory_stats(&self) -> BufferStats {
+        self.buffer_manager.stats()
+    }
+
+    /// Returns the catalog of declared labels, edge types and property keys.
+    #[must_use]
+    pub fn catalog(&self) -> &Arc<Catalog> {
+        &self.catalog
+    }
+
+    /// Declares a node label.
+    ///
+    /// Only needed in strict schema mode (see
+    /// [`Config::with_strict_schema`]), where queries, direct writes, batches
+    /// and imports may only use declared labels. Declaring the same label twice is a no-op.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::{Config, GrafeoDB};
+    ///
+    /// let db = GrafeoDB::with_config(Config::in_memory().with_strict_schema())?;
+    /// db.declare_label("Person");
+    /// db.declare_property_key("name");
+    ///
+    /// assert!(db.execute("INSERT (:Person {name: 'Alice'})").is_ok());
+    /// assert!(db.execute("INSERT (:Persn {name: 'Bob'})").is_err());
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    pub fn declare_label(&self, name: &str) {
+        self.catalog.get_or_create_label(name);
+    }
+
+    /// Declares an edge type for strict schema mode.
+    pub fn declare_edge_type(&self, name: &str) {
+        self.catalog.get_or_create_edge_type(name);
+    }
+
+    /// Declares a property key for strict schema mode.
+    pub fn declare_property_key(&self, name: &str) {
+        self.catalog.get_or_create_property_key(name);
+    }
+
+    /// Sets how creating a duplicate edge of this type is handled.
+    ///
+    /// A duplicate has the same type, source and target as an existing edge.
+    /// By default duplicates are allowed and create a parallel edge;
+    /// [`EdgeMultiplicity::Deny`] makes them an error and
+    /// [`EdgeMultiplicity::Upsert`] updates the existing edge instead.
+    pub fn set_edge_multiplicity(&self, edge_type: &str, multiplicity: EdgeMultiplicity) {
+        self.catalog.set_edge_multiplicity(edge_type, multiplicity);
+    }
+
+    /// Normalizes every value a query writes to this property.
+    ///
+    /// The normalizer runs on `INSERT`/`CREATE` and `SET`, so stored values,
+    /// zone maps and indexes only ever see the normalized form. Values written
+    /// directly through [`store()`](Self::store) are not affected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_common::types::Value;
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// db.set_property_normalizer("email", |value| match value {
+    ///     Value::String(s) => Value::String(s.trim().to_lowercase().into()),
+    ///     other => other,
+    /// });
+    /// ```
+    pub fn set_property_normalizer(
+        &self,
+        property: &str,
+        normalizer: impl Fn(Value) -> Value + Send + Sync + 'static,
+    ) {
+        self.catalog
+            .set_property_normalizer(property, PropertyNormalizer::new(normalizer));
+    }
+
     /// Closes the database, flushing all pending writes.
     ///
-    /// For persistent databases, this ensures everything is safely on disk.
-    /// Called automatically when the database is dropped, but you can call
-    /// it explicitly if you need to guarantee durability at a specific point.
+    /// For persistent databases, this ensures everything is safely on disk,
+    /// and saves hash and BTree indexes so the next open doesn't have to
+    /// rebuild them. Called automatically when the database is dropped, but
+    /// you can call it explicitly if you need to guarantee durability at a
+    /// specific point.
     ///
     /// # Errors
     ///
-    /// Returns an error if the WAL can't be flushed (check disk space/permissions).
+    /// Returns an error if the WAL can't be flushed or the indexes can't be
+    /// saved (check disk space/permissions).
     pub fn close(&self) -> Result<()> {
         let mut is_open = self.is_open.write();
         if !*is_open {
@@ -488,8 +697,17 @@ impl GrafeoDB {
             })?;
 
             // Then checkpoint
-            wal.checkpoint(checkpoint_tx, epoch)?;
+            let checkpoint = wal.checkpoint(checkpoint_tx, epoch)?;
             wal.sync()?;
+
+            if let Some(db_path) = &self.config.path {
+                catalog::save_indexes(
+                    &self.catalog,
+                    &self.store,
+                    &db_path.join(INDEX_DIR),
+                    &checkpoint.metadata,
+                )?;
+            }
         }
 
         *is_open = false;
@@ -502,11 +720,26 @@ impl GrafeoDB {
         self.wal.as_ref()
     }
 
-    /// Logs a WAL record if WAL is enabled.
+    /// Checks a write's names against the catalog in strict schema mode.
+    fn check_schema<'a>(
+        &self,
+        labels: &[&str],
+        edge_type: Option<&str>,
+        property_keys: impl IntoIterator<Item = &'a str>,
+    ) -> Result<()> {
+        if self.config.schema_mode == SchemaMode::Strict {
+            self.catalog
+                .validate_write(labels.iter().copied(), edge_type, property_keys)?;
+        }
+        Ok(())
+    }
+
+    /// Publishes a change to subscribers, and logs it if WAL is enabled.
     fn log_wal(&self, record: &WalRecord) -> Result<()> {
         if let Some(ref wal) = self.wal {
             wal.log(record)?;
         }
+        self.changes.publish(std::slice::from_ref(record));
         Ok(())
     }
 
@@ -556,7 +789,25 @@ impl GrafeoDB {
     /// let alice = db.create_node(&["Person"]);
     /// let company = db.create_node(&["Company", "Startup"]);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics in strict schema mode if a label hasn't been declared; use
+    /// [`try_create_node`](Self::try_create_node) to get an error instead.
     pub fn create_node(&self, labels: &[&str]) -> grafeo_common::types::NodeId {
+        self.try_create_node(labels)
+            .unwrap_or_else(|e| panic!("cannot create node: {e}"))
+    }
+
+    /// Creates a node with the given labels, checking them against the
+    /// schema first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in strict schema mode if a label hasn't been
+    /// declared.
+    pub fn try_create_node(&self, labels: &[&str]) -> Result<grafeo_common::types::NodeId> {
+        self.check_schema(labels, None, std::iter::empty())?;
         let id = self.store.create_node(labels);
 
         // Log to WAL if enabled
@@ -567,12 +818,18 @@ impl GrafeoDB {
             tracing::warn!("Failed to log CreateNode to WAL: {}", e);
         }
 
-        id
+        Ok(id)
     }
 
     /// Creates a new node with labels and properties.
     ///
     /// If WAL is enabled, the operation is logged for durability.
+    ///
+    /// # Panics
+    ///
+    /// Panics in strict schema mode if a label or property key hasn't been
+    /// declared; use [`try_create_node_with_props`](Self::try_create_node_with_props)
+    /// to get an error instead.
     pub fn create_node_with_props(
         &self,
         labels: &[&str],
@@ -583,6 +840,27 @@ impl GrafeoDB {
             ),
         >,
     ) -> grafeo_common::types::NodeId {
+        self.try_create_node_with_props(labels, properties)
+            .unwrap_or_else(|e| panic!("cannot create node: {e}"))
+    }
+
+    /// Creates a new node with labels and properties, checking them against
+    /// the schema first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in strict schema mode if a label or property key
+    /// hasn't been declared.
+    pub fn try_create_node_with_props(
+        &self,
+        labels: &[&str],
+        properties: impl IntoIterator<
+            Item = (
+                impl Into<grafeo_common::types::PropertyKey>,
+                impl Into<grafeo_common::types::Value>,
+            ),
+        >,
+    ) -> Result<grafeo_common::types::NodeId> {
         // Collect properties first so we can log them to WAL
         let props: Vec<(
             grafeo_common::types::PropertyKey,
@@ -591,6 +869,7 @@ impl GrafeoDB {
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
             .collect();
+        self.check_schema(labels, None, props.iter().map(|(k, _)| k.as_str()))?;
 
         let id = self
             .store
@@ -615,7 +894,7 @@ impl GrafeoDB {
             }
         }
 
-        id
+        Ok(id)
     }
 
     /// Gets a node by ID.
@@ -771,12 +1050,35 @@ impl GrafeoDB {
     /// // Alice knows Bob (directed: Alice -> Bob)
     /// let edge = db.create_edge(alice, bob, "KNOWS");
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics in strict schema mode if the edge type hasn't been declared;
+    /// use [`try_create_edge`](Self::try_create_edge) to get an error instead.
     pub fn create_edge(
         &self,
         src: grafeo_common::types::NodeId,
         dst: grafeo_common::types::NodeId,
         edge_type: &str,
     ) -> grafeo_common::types::EdgeId {
+        self.try_create_edge(src, dst, edge_type)
+            .unwrap_or_else(|e| panic!("cannot create edge: {e}"))
+    }
+
+    /// Creates an edge between two nodes, checking its type against the
+    /// schema first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in strict schema mode if the edge type hasn't been
+    /// declared.
+    pub fn try_create_edge(
+        &self,
+        src: grafeo_common::types::NodeId,
+        dst: grafeo_common::types::NodeId,
+        edge_type: &str,
+    ) -> Result<grafeo_common::types::EdgeId> {
+        self.check_schema(&[], Some(edge_type), std::iter::empty())?;
         let id = self.store.create_edge(src, dst, edge_type);
 
         // Log to WAL if enabled
@@ -789,12 +1091,18 @@ impl GrafeoDB {
             tracing::warn!("Failed to log CreateEdge to WAL: {}", e);
         }
 
-        id
+        Ok(id)
     }
 
     /// Creates a new edge with properties.
     ///
     /// If WAL is enabled, the operation is logged for durability.
+    ///
+    /// # Panics
+    ///
+    /// Panics in strict schema mode if the edge type or a property key hasn't
+    /// been declared; use [`try_create_edge_with_props`](Self::try_create_edge_with_props)
+    /// to get an error instead.
     pub fn create_edge_with_props(
         &self,
         src: grafeo_common::types::NodeId,
@@ -807,6 +1115,29 @@ impl GrafeoDB {
             ),
         >,
     ) -> grafeo_common::types::EdgeId {
+        self.try_create_edge_with_props(src, dst, edge_type, properties)
+            .unwrap_or_else(|e| panic!("cannot create edge: {e}"))
+    }
+
+    /// Creates a new edge with properties, checking its type and property
+    /// keys against the schema first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in strict schema mode if the edge type or a property
+    /// key hasn't been declared.
+    pub fn try_create_edge_with_props(
+        &self,
+        src: grafeo_common::types::NodeId,
+        dst: grafeo_common::types::NodeId,
+        edge_type: &str,
+        properties: impl IntoIterator<
+            Item = (
+                impl Into<grafeo_common::types::PropertyKey>,
+                impl Into<grafeo_common::types::Value>,
+            ),
+        >,
+    ) -> Result<grafeo_common::types::EdgeId> {
         // Collect properties first so we can log them to WAL
         let props: Vec<(
             grafeo_common::types::PropertyKey,
@@ -815,6 +1146,7 @@ impl GrafeoDB {
             .into_iter()
             .map(|(k, v)| (k.into(), v.into()))
             .collect();
+        self.check_schema(&[], Some(edge_type), props.iter().map(|(k, _)| k.as_str()))?;
 
         let id = self.store.create_edge_with_props(
             src,
@@ -844,7 +1176,7 @@ impl GrafeoDB {
             }
         }
 
-        id
+        Ok(id)
     }
 
     /// Gets an edge by ID.
@@ -891,6 +1223,30 @@ impl GrafeoDB {
         self.store.set_edge_property(id, key, value);
     }
 
+    /// Sets the interval during which an edge is valid, for as-of queries.
+    ///
+    /// The interval includes `valid_from` and excludes `valid_to`; `None`
+    /// leaves that end open. Sessions with an as-of time set (see
+    /// [`Session::set_as_of`]) only traverse edges valid at that time.
+    /// Returns false if the edge doesn't exist.
+    ///
+    /// If WAL is enabled, the operation is logged for durability.
+    pub fn set_edge_validity(
+        &self,
+        id: grafeo_common::types::EdgeId,
+        valid_from: Option<grafeo_common::types::Timestamp>,
+        valid_to: Option<grafeo_common::types::Timestamp>,
+    ) -> bool {
+        if let Err(e) = self.log_wal(&WalRecord::SetEdgeValidity {
+            id,
+            valid_from,
+            valid_to,
+        }) {
+            tracing::warn!("Failed to log SetEdgeValidity to WAL: {}", e);
+        }
+        self.store.set_edge_validity(id, valid_from, valid_to)
+    }
+
     /// Removes a property from a node.
     ///
     /// Returns true if the property existed and was removed, false otherwise.
@@ -1096,6 +1452,32 @@ impl GrafeoDB {
         result
     }
 
+    /// Checks the store's internal structures for inconsistencies - an fsck
+    /// for the graph.
+    ///
+    /// Goes deeper than [`validate`](Self::validate): besides dangling edges,
+    /// it checks that the label index matches node labels, that adjacency
+    /// lists and their counters match the edges, and that zone maps cover
+    /// their column values. Each problem becomes an error with a stable code
+    /// such as `ADJACENCY_MISSING`. Nothing is repaired.
+    #[must_use]
+    pub fn check_consistency(&self) -> crate::admin::ValidationResult {
+        let errors = self
+            .store
+            .check_consistency()
+            .into_iter()
+            .map(|issue| crate::admin::ValidationError {
+                code: issue.code().to_string(),
+                message: issue.to_string(),
+                context: issue.context(),
+            })
+            .collect();
+        crate::admin::ValidationResult {
+            errors,
+            warnings: Vec::new(),
+        }
+    }
+
     /// Returns WAL (Write-Ahead Log) status.
     ///
     /// Returns None if WAL is not enabled.
@@ -1124,7 +1506,8 @@ impl GrafeoDB {
 
     /// Forces a WAL checkpoint.
     ///
-    /// Flushes all pending WAL records to the main storage.
+    /// Commits all pending WAL records and compacts the log, dropping
+    /// records that later writes superseded.
     ///
     /// # Errors
     ///
@@ -1136,6 +1519,7 @@ impl GrafeoDB {
                 .tx_manager
                 .last_assigned_tx_id()
                 .unwrap_or_else(|| self.tx_manager.begin());
+            wal.log(&WalRecord::TxCommit { tx_id })?;
             wal.checkpoint(tx_id, epoch)?;
             wal.sync()?;
         }
@@ -1212,6 +1596,22 @@ impl GrafeoDB {
                     value,
                 })?;
             }
+
+            // Copy the validity interval, if the edge has one
+            if let Some((valid_from, valid_to)) = self
+                .store
+                .edge_validity(edge.id)
+                .filter(|&(from, to)| from.is_some() || to.is_some())
+            {
+                target
+                    .store
+                    .set_edge_validity(edge.id, valid_from, valid_to);
+                target.log_wal(&WalRecord::SetEdgeValidity {
+                    id: edge.id,
+                    valid_from,
+                    valid_to,
+                })?;
+            }
         }
 
         // Checkpoint and close the target database
@@ -1255,6 +1655,12 @@ impl GrafeoDB {
             for (key, value) in edge.properties {
                 target.store.set_edge_property(edge.id, key.as_str(), value);
             }
+
+            if let Some((valid_from, valid_to)) = self.store.edge_validity(edge.id) {
+                target
+                    .store
+                    .set_edge_validity(edge.id, valid_from, valid_to);
+            }
         }
 
         Ok(target)
@@ -1285,18 +1691,50 @@ impl GrafeoDB {
     // ADMIN API: Iteration
     // =========================================================================
 
-    /// Returns an iterator over all nodes in the database.
+    /// Returns an iterator over all nodes in the database, or only those
+    /// with `label`.
     ///
-    /// Useful for dump/export operations.
-    pub fn iter_nodes(&self) -> impl Iterator<Item = grafeo_core::graph::lpg::Node> + '_ {
-        self.store.all_nodes()
+    /// This is a full scan without a query language, for migrations,
+    /// exports, and consistency checks. The iterator sees the nodes that
+    /// existed when it was created; nodes added or removed while iterating
+    /// don't show up or disappear. It holds no lock between items, so
+    /// writers aren't blocked while you iterate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// db.create_node(&["Person"]);
+    /// db.create_node(&["Company"]);
+    ///
+    /// assert_eq!(db.iter_nodes(Some("Person")).count(), 1);
+    /// assert_eq!(db.iter_nodes(None).count(), 2);
+    /// ```
+    pub fn iter_nodes<'a>(
+        &'a self,
+        label: Option<&str>,
+    ) -> Box<dyn Iterator<Item = grafeo_core::graph::lpg::Node> + 'a> {
+        match label {
+            Some(label) => Box::new(self.store.nodes_with_label(label)),
+            None => Box::new(self.store.all_nodes()),
+        }
     }
 
-    /// Returns an iterator over all edges in the database.
+    /// Returns an iterator over all edges in the database, or only those of
+    /// `edge_type`.
     ///
-    /// Useful for dump/export operations.
-    pub fn iter_edges(&self) -> impl Iterator<Item = grafeo_core::graph::lpg::Edge> + '_ {
-        self.store.all_edges()
+    /// Like [`iter_nodes()`](Self::iter_nodes), the iterator sees the edges
+    /// that existed when it was created and holds no lock between items.
+    pub fn iter_edges<'a>(
+        &'a self,
+        edge_type: Option<&str>,
+    ) -> Box<dyn Iterator<Item = grafeo_core::graph::lpg::Edge> + 'a> {
+        match edge_type {
+            Some(edge_type) => Box::new(self.store.edges_with_type(edge_type)),
+            None => Box::new(self.store.all_edges()),
+        }
     }
 }
 
@@ -1333,7 +1771,7 @@ impl Drop for GrafeoDB {
 /// }
 /// # Ok::<(), grafeo_common::utils::error::Error>(())
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct QueryResult {
     /// Column names from the RETURN clause.
     pub columns: Vec<String>,
@@ -1341,6 +1779,22 @@ pub struct QueryResult {
     pub column_types: Vec<grafeo_common::types::LogicalType>,
     /// The actual result rows.
     pub rows: Vec<Vec<grafeo_common::types::Value>>,
+    /// Statistics gathered while executing the query.
+    pub stats: QueryStats,
+}
+
+/// Statistics about how a query executed.
+///
+/// Scans under a filter like `n.age > 30` check per-block zone maps (the
+/// min and max of each property over runs of node ids) and skip chunks that
+/// can't match. Many skips mean the data is clustered well for the
+/// predicate; zero skips with many scanned chunks mean it isn't.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryStats {
+    /// Scan chunks skipped because zone maps proved no row could match.
+    pub chunks_skipped: u64,
+    /// Scan chunks zone maps were checked for but couldn't rule out.
+    pub chunks_scanned: u64,
 }
 
 impl QueryResult {
@@ -1352,6 +1806,7 @@ impl QueryResult {
             columns,
             column_types: vec![grafeo_common::types::LogicalType::Any; len],
             rows: Vec::new(),
+            stats: QueryStats::default(),
         }
     }
 
@@ -1365,6 +1820,7 @@ impl QueryResult {
             columns,
             column_types,
             rows: Vec::new(),
+            stats: QueryStats::default(),
         }
     }
 
@@ -1407,6 +1863,85 @@ impl QueryResult {
     pub fn iter(&self) -> impl Iterator<Item = &Vec<grafeo_common::types::Value>> {
         self.rows.iter()
     }
+
+    /// Projects the part of `db` this result walked through.
+    ///
+    /// Collects every node from node-typed columns, every edge from
+    /// edge-typed columns, and the edges of every path in path-typed columns
+    /// (e.g. `p` in `MATCH p = (a)-[*1..3]->(b) RETURN p`). Edge endpoints are
+    /// included as nodes. Only the collected edges are kept, so algorithms
+    /// run on [`GraphProjection::graph()`] see exactly the traversed subgraph.
+    #[must_use]
+    pub fn induced_subgraph(&self, db: &GrafeoDB) -> GraphProjection {
+        use grafeo_common::types::{EdgeId, LogicalType, NodeId, Value};
+
+        let mut nodes: FxHashSet<NodeId> = FxHashSet::default();
+        let mut edges: FxHashSet<EdgeId> = FxHashSet::default();
+        for (col, ty) in self.column_types.iter().enumerate() {
+            for row in &self.rows {
+                match (ty, row.get(col)) {
+                    (LogicalType::Node, Some(Value::Int64(id))) => {
+                        nodes.insert(NodeId::new(*id as u64));
+                    }
+                    (LogicalType::Edge, Some(Value::Int64(id))) => {
+                        edges.insert(EdgeId::new(*id as u64));
+                    }
+                    (LogicalType::Path, Some(Value::List(ids))) => {
+                        for id in ids.iter() {
+                            if let Value::Int64(id) = id {
+                                edges.insert(EdgeId::new(*id as u64));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for &id in &edges {
+            if let Some(edge) = db.store.get_edge(id) {
+                nodes.insert(edge.src);
+                nodes.insert(edge.dst);
+            }
+        }
+
+        GraphProjection::build(
+            "induced",
+            Arc::clone(&db.store),
+            Arc::new(move |node: &Node| nodes.contains(&node.id)),
+            Arc::new(move |edge: &Edge| edges.contains(&edge.id)),
+        )
+    }
+}
+
+/// A pre-execution estimate of how large a query's result will be.
+///
+/// Produced by [`Session::estimate_result_size()`](crate::Session::estimate_result_size)
+/// from graph statistics alone - the query is planned but never run, so
+/// treat the numbers as a rough guide rather than a bound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResultSizeEstimate {
+    /// Estimated number of result rows.
+    pub rows: u64,
+    /// Number of columns in the result.
+    pub columns: usize,
+    /// Approximate in-memory size of the materialized rows, in bytes.
+    ///
+    /// Counts one [`Value`](grafeo_common::types::Value) slot per cell;
+    /// heap data behind strings and lists is not included.
+    pub bytes: u64,
+}
+
+impl ResultSizeEstimate {
+    /// Builds an estimate from a row count and column count.
+    #[must_use]
+    pub fn new(rows: u64, columns: usize) -> Self {
+        let cell = std::mem::size_of::<grafeo_common::types::Value>() as u64;
+        Self {
+            rows,
+            columns,
+            bytes: rows.saturating_mul(columns as u64).saturating_mul(cell),
+        }
+    }
 }
 
 /// Converts a [`Value`](grafeo_common::types::Value) to a concrete Rust type.
@@ -1529,6 +2064,168 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_indexes_saved_on_close_and_reloaded() {
+        use grafeo_common::types::Value;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test_db");
+        let (alice, bob) = {
+            let db = GrafeoDB::open(&db_path).unwrap();
+            let alice = db.create_node_with_props(&["Person"], [("age", Value::Int64(30))]);
+            let bob = db.create_node_with_props(&["Person"], [("age", Value::Int64(40))]);
+            db.catalog().create_btree_index(db.store(), "Person", "age");
+            db.close().unwrap();
+            (alice, bob)
+        };
+        let files = || std::fs::read_dir(db_path.join(INDEX_DIR)).unwrap().count();
+        assert_eq!(files(), 1);
+
+        let db = GrafeoDB::open(&db_path).unwrap();
+        // Read back, and kept in case the database isn't closed cleanly
+        assert_eq!(files(), 1);
+        assert_eq!(db.catalog().index_count(), 1);
+        let index = db.store().value_index("Person", "age").unwrap();
+        assert_eq!(index.nodes_with(&Value::Int64(30)), vec![alice]);
+
+        // The reloaded index follows later writes
+        db.set_node_property(bob, "age", Value::Int64(30));
+        assert_eq!(index.nodes_with(&Value::Int64(30)), vec![alice, bob]);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_indexes_survive_reopen_without_close() {
+        use grafeo_common::types::Value;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test_db");
+        let (alice, bob) = {
+            let db = GrafeoDB::open(&db_path).unwrap();
+            let alice = db.create_node_with_props(&["Person"], [("age", Value::Int64(30))]);
+            let bob = db.create_node_with_props(&["Person"], [("age", Value::Int64(40))]);
+            db.catalog().create_btree_index(db.store(), "Person", "age");
+            db.close().unwrap();
+            (alice, bob)
+        };
+
+        // Reopened and left without a clean close, twice: first untouched,
+        // then after a write the saved index doesn't know about
+        for write in [false, true] {
+            let db = GrafeoDB::open(&db_path).unwrap();
+            assert_eq!(db.catalog().index_count(), 1);
+            if write {
+                db.session()
+                    .execute("MATCH (p:Person) WHERE p.age = 40 SET p.age = 30")
+                    .unwrap();
+            }
+            std::mem::forget(db);
+        }
+
+        // The stale file is rebuilt from the recovered graph
+        let db = GrafeoDB::open(&db_path).unwrap();
+        assert_eq!(db.catalog().index_count(), 1);
+        let index = db.store().value_index("Person", "age").unwrap();
+        assert_eq!(index.nodes_with(&Value::Int64(30)), vec![alice, bob]);
+        assert!(index.nodes_with(&Value::Int64(40)).is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_index_file_is_rebuilt() {
+        use grafeo_common::types::Value;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("test_db");
+        let alice = {
+            let db = GrafeoDB::open(&db_path).unwrap();
+            let alice = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+            db.create_node_with_props(&["Person"], [("name", "Bob")]);
+            db.catalog().create_hash_index(db.store(), "Person", "name");
+            db.close().unwrap();
+            alice
+        };
+
+        let file = std::fs::read_dir(db_path.join(INDEX_DIR))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let mut bytes = std::fs::read(&file).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 0xff;
+        std::fs::write(&file, bytes).unwrap();
+
+        let db = GrafeoDB::open(&db_path).unwrap();
+        assert_eq!(db.catalog().index_count(), 1);
+        let index = db.store().value_index("Person", "name").unwrap();
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.nodes_with(&Value::from("Alice")), vec![alice]);
+    }
+
+    #[test]
+    fn test_batch_recovered_from_wal() {
+        use grafeo_common::types::Value;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("batch_db");
+
+        {
+            let db = GrafeoDB::open(&db_path).unwrap();
+            let outcome = db
+                .session()
+                .batch(|batch| {
+                    let alice = batch.create_node(&["Person"])?;
+                    let bob = batch.create_node(&["Person"])?;
+                    batch.create_edge(alice, bob, "KNOWS")?;
+                    batch.set_node_property(alice, "name", Value::from("Alice"))
+                })
+                .unwrap();
+            assert_eq!(outcome.created_nodes.len(), 2);
+            db.close().unwrap();
+        }
+
+        let db = GrafeoDB::open(&db_path).unwrap();
+        assert_eq!(db.node_count(), 2);
+        assert_eq!(db.edge_count(), 1);
+        let alice = db.get_node(grafeo_common::types::NodeId::new(0)).unwrap();
+        assert_eq!(alice.get_property("name"), Some(&Value::from("Alice")));
+    }
+
+    #[test]
+    fn test_wal_checkpoint_compacts_log() {
+        use grafeo_common::types::Value;
+        use tempfile::tempdir;
+
+        let dir = tempdir().unwrap();
+        let db_path = dir.path().join("checkpoint_db");
+
+        {
+            let db = GrafeoDB::open(&db_path).unwrap();
+            let alice = db.create_node(&["Person"]);
+            for visits in 0..100 {
+                db.set_node_property(alice, "visits", Value::Int64(visits));
+            }
+            let temp = db.create_node(&["Temp"]);
+            db.delete_node(temp);
+
+            let wal = db.wal().unwrap();
+            wal.sync().unwrap();
+            let before = wal.size_bytes();
+            db.wal_checkpoint().unwrap();
+            assert!(wal.size_bytes() < before);
+        }
+
+        let db = GrafeoDB::open(&db_path).unwrap();
+        assert_eq!(db.node_count(), 1);
+        let alice = db.get_node(grafeo_common::types::NodeId::new(0)).unwrap();
+        assert_eq!(alice.get_property("visits"), Some(&Value::Int64(99)));
+    }
+
     #[test]
     fn test_wal_logging() {
         use tempfile::tempdir;
@@ -1549,4 +2246,763 @@ mod tests {
 
         db.close().unwrap();
     }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_strict_schema_rejects_undeclared_names() {
+        let db = GrafeoDB::with_config(Config::in_memory().with_strict_schema()).unwrap();
+        db.declare_label("Person");
+        db.declare_property_key("name");
+        db.declare_edge_type("KNOWS");
+
+        db.execute("INSERT (:Person {name: 'Alice'})").unwrap();
+        db.execute("INSERT (:Person {name: 'Bob'})").unwrap();
+
+        // Typo in the label
+        let err = db.execute("INSERT (:Persn {name: 'Carol'})").unwrap_err();
+        assert!(
+            matches!(err, grafeo_common::utils::error::Error::LabelNotFound(ref l) if l == "Persn")
+        );
+
+        // Undeclared property
+        let err = db.execute("INSERT (:Person {nmae: 'Carol'})").unwrap_err();
+        assert!(
+            matches!(err, grafeo_common::utils::error::Error::PropertyNotFound(ref p) if p == "nmae")
+        );
+        assert!(db.execute("MATCH (n:Person) SET n.age = 30").is_err());
+
+        assert_eq!(db.node_count(), 2);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_duplicate_property_keys_last_value_wins_by_default() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+
+        db.execute("INSERT (:Person {name: 'Alice', age: 30, name: 'Alicia'})")
+            .unwrap();
+        db.execute("MATCH (n:Person) SET n += {age: 31, age: 32}")
+            .unwrap();
+
+        let result = db.execute("MATCH (n:Person) RETURN n.name, n.age").unwrap();
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0][0], Value::from("Alicia"));
+        assert_eq!(result.rows[0][1], Value::Int64(32));
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_duplicate_property_keys_rejected_in_strict_mode() {
+        use crate::config::DuplicateKeyPolicy;
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::with_config(
+            Config::in_memory().with_duplicate_keys(DuplicateKeyPolicy::Strict),
+        )
+        .unwrap();
+
+        let err = db
+            .execute("INSERT (:Person {name: 'Alice', age: 30, name: 'Alicia'})")
+            .unwrap_err();
+        assert!(err.to_string().contains("'name'"), "{err}");
+        assert_eq!(db.node_count(), 0);
+
+        db.execute("INSERT (:Person {name: 'Alice', age: 30})")
+            .unwrap();
+        assert!(
+            db.execute("MATCH (n:Person) SET n += {age: 31, age: 32}")
+                .is_err()
+        );
+        let result = db.execute("MATCH (n:Person) RETURN n.age").unwrap();
+        assert_eq!(result.rows[0][0], Value::Int64(30));
+    }
+
+    #[cfg(feature = "cypher")]
+    #[test]
+    fn test_strict_schema_rejects_undeclared_edge_type() {
+        let db = GrafeoDB::with_config(Config::in_memory().with_strict_schema()).unwrap();
+        db.declare_label("Person");
+        db.declare_edge_type("KNOWS");
+
+        assert!(
+            db.execute_cypher("CREATE (a:Person)-[:LIKES]->(b:Person)")
+                .is_err()
+        );
+        db.execute_cypher("CREATE (a:Person)-[:KNOWS]->(b:Person)")
+            .unwrap();
+
+        assert_eq!(db.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_strict_schema_rejects_undeclared_direct_writes() {
+        let db = GrafeoDB::with_config(Config::in_memory().with_strict_schema()).unwrap();
+        db.declare_label("Person");
+        db.declare_property_key("name");
+        db.declare_edge_type("KNOWS");
+
+        let alice = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+        assert!(db.try_create_node(&["Persn"]).is_err());
+        assert!(
+            db.try_create_node_with_props(&["Person"], [("nmae", "Bob")])
+                .is_err()
+        );
+        assert!(db.try_create_edge(alice, alice, "KNOWZ").is_err());
+        db.create_edge(alice, alice, "KNOWS");
+
+        let outcome = db.session().batch(|batch| {
+            batch.create_node(&["Person"])?;
+            batch.create_node(&["Persn"])?;
+            Ok(())
+        });
+        assert!(matches!(
+            outcome,
+            Err(grafeo_common::utils::error::Error::LabelNotFound(label)) if label == "Persn"
+        ));
+
+        assert_eq!(db.node_count(), 1);
+        assert_eq!(db.edge_count(), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Persn")]
+    fn test_strict_schema_create_node_panics_on_undeclared_label() {
+        let db = GrafeoDB::with_config(Config::in_memory().with_strict_schema()).unwrap();
+        db.create_node(&["Persn"]);
+    }
+
+    #[cfg(feature = "cypher")]
+    #[test]
+    fn test_edge_multiplicity_modes() {
+        let link = "MATCH (a:Person {name: 'Alice'}), (b:Person {name: 'Bob'}) \
+                    CREATE (a)-[:KNOWS {since: 2021}]->(b)";
+        let setup = |multiplicity| {
+            let db = GrafeoDB::new_in_memory();
+            db.set_edge_multiplicity("KNOWS", multiplicity);
+            db.execute_cypher("CREATE (:Person {name: 'Alice'})")
+                .unwrap();
+            db.execute_cypher("CREATE (:Person {name: 'Bob'})").unwrap();
+            db.execute_cypher(link).unwrap();
+            db
+        };
+
+        // Allow: a second, parallel edge
+        let db = setup(EdgeMultiplicity::Allow);
+        db.execute_cypher(link).unwrap();
+        assert_eq!(db.edge_count(), 2);
+
+        // Deny: the duplicate is rejected
+        let db = setup(EdgeMultiplicity::Deny);
+        assert!(db.execute_cypher(link).is_err());
+        assert_eq!(db.edge_count(), 1);
+
+        // Upsert: the existing edge is reused and updated
+        let db = setup(EdgeMultiplicity::Upsert);
+        db.execute_cypher(
+            "MATCH (a:Person {name: 'Alice'}), (b:Person {name: 'Bob'}) \
+             CREATE (a)-[:KNOWS {since: 2024}]->(b)",
+        )
+        .unwrap();
+        assert_eq!(db.edge_count(), 1);
+        let result = db
+            .execute_cypher("MATCH (:Person)-[r:KNOWS]->(:Person) RETURN r.since")
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![grafeo_common::types::Value::Int64(2024)]]
+        );
+    }
+
+    #[test]
+    fn test_property_normalizer_applies_on_write() {
+        use grafeo_common::types::{PropertyKey, Value};
+
+        let db = GrafeoDB::new_in_memory();
+        db.set_property_normalizer("email", |value| match value {
+            Value::String(s) => Value::String(s.trim().to_lowercase().into()),
+            other => other,
+        });
+
+        db.execute("INSERT (:Person {name: 'Alice', email: '  Alice@Example.COM '})")
+            .unwrap();
+        let result = db
+            .execute("MATCH (p:Person {email: 'alice@example.com'}) RETURN p.name")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::from("Alice")]]);
+
+        let zone_map = db
+            .store()
+            .node_property_zone_map(&PropertyKey::new("email"))
+            .unwrap();
+        assert_eq!(zone_map.min, Some(Value::from("alice@example.com")));
+
+        db.execute("MATCH (p:Person) SET p.email = 'ALICE@grafeo.DEV'")
+            .unwrap();
+        let result = db.execute("MATCH (p:Person) RETURN p.email").unwrap();
+        assert_eq!(result.rows, vec![vec![Value::from("alice@grafeo.dev")]]);
+
+        // Other properties are stored as written
+        let result = db.execute("MATCH (p:Person) RETURN p.name").unwrap();
+        assert_eq!(result.rows, vec![vec![Value::from("Alice")]]);
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let db = GrafeoDB::new_in_memory();
+        let alice = db.create_node_with_props(&["Person"], [("age", 30i64)]);
+        let bob = db.create_node(&["Person"]);
+        let edge = db.create_edge(alice, bob, "KNOWS");
+        db.execute("MATCH (p:Person) SET p.age = 31").unwrap();
+        assert!(db.check_consistency().is_valid());
+
+        // Deleting straight from the store skips the detach
+        db.store().delete_node(bob);
+        let report = db.check_consistency();
+        assert_eq!(report.errors.len(), 1);
+        let error = &report.errors[0];
+        assert_eq!(error.code, "DANGLING_DST");
+        assert_eq!(error.context, Some(format!("edge:{}", edge.0)));
+        assert_eq!(
+            error.message,
+            format!(
+                "Edge {} references non-existent destination node {}",
+                edge.0, bob.0
+            )
+        );
+    }
+
+    #[test]
+    fn test_join_reorder_uses_statistics_and_follows_config() {
+        use crate::query::plan::{
+            JoinCondition, JoinOp, JoinType, LogicalExpression, LogicalOperator, LogicalPlan,
+            NodeScanOp,
+        };
+
+        let scan = |variable: &str, label: &str| {
+            LogicalOperator::NodeScan(NodeScanOp {
+                variable: variable.to_string(),
+                labels: vec![label.to_string()],
+                input: None,
+            })
+        };
+        let join = |left, right, leaf: &str| {
+            LogicalOperator::Join(JoinOp {
+                left: Box::new(left),
+                right: Box::new(right),
+                join_type: JoinType::Inner,
+                conditions: vec![JoinCondition {
+                    left: LogicalExpression::Variable("h".to_string()),
+                    right: LogicalExpression::Variable(leaf.to_string()),
+                }],
+            })
+        };
+        // A star around `h`, written with both large leaves joined first
+        let star = || {
+            let plan = join(scan("h", "Hub"), scan("a", "Big"), "a");
+            let plan = join(plan, scan("b", "Huge"), "b");
+            LogicalPlan::new(join(plan, scan("s", "Small"), "s"))
+        };
+        let first_join = |plan: LogicalPlan| {
+            let mut op = plan.root;
+            while let LogicalOperator::Join(join) = op {
+                match (*join.left, *join.right) {
+                    (LogicalOperator::NodeScan(l), LogicalOperator::NodeScan(r)) => {
+                        let mut pair = [l.variable, r.variable];
+                        pair.sort();
+                        return pair;
+                    }
+                    (inner @ LogicalOperator::Join(_), _)
+                    | (_, inner @ LogicalOperator::Join(_)) => {
+                        op = inner;
+                    }
+                    _ => break,
+                }
+            }
+            panic!("not a join tree");
+        };
+        let setup = |db: &GrafeoDB| {
+            db.create_node(&["Hub"]);
+            db.create_node(&["Small"]);
+            for _ in 0..50 {
+                db.create_node(&["Big"]);
+                db.create_node(&["Huge"]);
+            }
+        };
+
+        let db = GrafeoDB::new_in_memory();
+        setup(&db);
+        let plan = db.session().optimizer().optimize(star()).unwrap();
+        assert_eq!(first_join(plan), ["h", "s"]);
+
+        let db = GrafeoDB::with_config(Config::in_memory().without_join_reorder()).unwrap();
+        setup(&db);
+        let plan = db.session().optimizer().optimize(star()).unwrap();
+        assert_eq!(first_join(plan), ["a", "h"]);
+    }
+
+    #[test]
+    fn test_parallel_join_follows_threads_config() {
+        use crate::query::plan::{
+            ExpandDirection, ExpandOp, JoinCondition, JoinOp, JoinType, LogicalExpression,
+            LogicalOperator, LogicalPlan, NodeScanOp, ReturnItem, ReturnOp,
+        };
+        use crate::query::planner::Planner;
+
+        let expand = |from: &str, to: &str| {
+            LogicalOperator::Expand(ExpandOp {
+                from_variable: from.to_string(),
+                to_variable: to.to_string(),
+                edge_variable: None,
+                direction: ExpandDirection::Outgoing,
+                edge_type: Some("KNOWS".to_string()),
+                min_hops: 1,
+                max_hops: Some(1),
+                input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
+                    variable: from.to_string(),
+                    labels: vec!["Person".to_string()],
+                    input: None,
+                })),
+                path_alias: None,
+            })
+        };
+        let name = |variable: &str| ReturnItem {
+            expression: LogicalExpression::Property {
+                variable: variable.to_string(),
+                property: "name".to_string(),
+            },
+            alias: Some(variable.to_string()),
+        };
+        // Friends of friends, as two expansions joined on the middle person
+        let plan = LogicalPlan::new(LogicalOperator::Return(ReturnOp {
+            items: vec![name("a"), name("c")],
+            distinct: false,
+            input: Box::new(LogicalOperator::Join(JoinOp {
+                left: Box::new(expand("a", "b")),
+                right: Box::new(expand("b", "c")),
+                join_type: JoinType::Inner,
+                conditions: vec![JoinCondition {
+                    left: LogicalExpression::Variable("b".to_string()),
+                    right: LogicalExpression::Variable("b".to_string()),
+                }],
+            })),
+        }));
+        let friends_of_friends = |threads: usize| {
+            let db = GrafeoDB::with_config(Config::in_memory().with_threads(threads)).unwrap();
+            let people: Vec<_> = (0..30)
+                .map(|i| db.create_node_with_props(&["Person"], [("name", format!("p{i}"))]))
+                .collect();
+            for i in 0..30 {
+                db.create_edge(people[i], people[(i + 1) % 30], "KNOWS");
+                db.create_edge(people[i], people[(i + 7) % 30], "KNOWS");
+            }
+            let explanation = Planner::new(Arc::clone(&db.store))
+                .with_parallelism(threads)
+                .plan(&plan)
+                .unwrap()
+                .explain()
+                .unwrap()
+                .to_string();
+            let mut rows: Vec<(String, String)> = db
+                .session()
+                .execute_plan(&plan)
+                .unwrap()
+                .rows
+                .into_iter()
+                .map(|row| {
+                    let name = |value: &Value| value.as_str().unwrap().to_string();
+                    (name(&row[0]), name(&row[1]))
+                })
+                .collect();
+            rows.sort();
+            (explanation.contains("ParallelHashJoin"), rows)
+        };
+
+        let (parallel, parallel_rows) = friends_of_friends(4);
+        let (serial, serial_rows) = friends_of_friends(1);
+        assert!(parallel);
+        assert!(!serial);
+        assert_eq!(parallel_rows.len(), 120);
+        assert_eq!(parallel_rows, serial_rows);
+        assert!(parallel_rows.contains(&("p0".to_string(), "p14".to_string())));
+    }
+
+    #[test]
+    fn test_case_insensitive_labels() {
+        use grafeo_common::types::Value;
+
+        let setup = |db: &GrafeoDB| {
+            let alice = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+            let bob = db.create_node_with_props(&["person"], [("name", "Bob")]);
+            db.create_edge(alice, bob, "KNOWS");
+        };
+        let names = |db: &GrafeoDB, query: &str| {
+            let mut names: Vec<String> = db
+                .execute(query)
+                .unwrap()
+                .rows
+                .into_iter()
+                .map(|row| row[0].to_string())
+                .collect();
+            names.sort();
+            names
+        };
+
+        // Default: casing tells labels and edge types apart
+        let db = GrafeoDB::new_in_memory();
+        setup(&db);
+        assert_eq!(names(&db, "MATCH (n:Person) RETURN n.name"), ["\"Alice\""]);
+        assert!(names(&db, "MATCH (n:PERSON) RETURN n.name").is_empty());
+        assert!(names(&db, "MATCH (a)-[:knows]->(b) RETURN b.name").is_empty());
+        assert!(!db.catalog().case_insensitive_labels());
+
+        let db = GrafeoDB::with_config(Config::in_memory().with_case_insensitive_labels()).unwrap();
+        setup(&db);
+        assert_eq!(
+            names(&db, "MATCH (n:PERSON) RETURN n.name"),
+            ["\"Alice\"", "\"Bob\""]
+        );
+        assert_eq!(
+            names(&db, "MATCH (a:person)-[:Knows]->(b) RETURN b.name"),
+            ["\"Bob\""]
+        );
+        assert_eq!(db.store().nodes_with_all_labels(&["PERSON"]).len(), 2);
+        assert!(db.catalog().case_insensitive_labels());
+
+        // Labels are still stored as written
+        let result = db
+            .execute("MATCH (n:PERSON {name: 'Bob'}) RETURN labels(n)")
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![Value::List(vec![Value::from("person")].into())]]
+        );
+    }
+
+    #[cfg(feature = "cypher")]
+    #[test]
+    fn test_foreach_applies_updates_per_element() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        db.execute_cypher("CREATE (:Counter {name: 'c'})").unwrap();
+
+        db.execute_cypher(
+            "MATCH (c:Counter) FOREACH (x IN [1, 2, 3] | CREATE (:Item) SET c.last = x)",
+        )
+        .unwrap();
+        assert_eq!(db.node_count(), 4);
+        let result = db
+            .execute_cypher("MATCH (c:Counter) RETURN c.last")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Int64(3)]]);
+
+        // An empty list leaves the graph untouched
+        db.execute_cypher("MATCH (c:Counter) FOREACH (x IN [] | CREATE (:Item))")
+            .unwrap();
+        assert_eq!(db.node_count(), 4);
+
+        // Nested FOREACH sees both element variables
+        db.execute_cypher(
+            "MATCH (c:Counter) \
+             FOREACH (x IN [1, 2] | FOREACH (y IN [10, 20] | SET c.outer = x, c.inner = y))",
+        )
+        .unwrap();
+        let result = db
+            .execute_cypher("MATCH (c:Counter) RETURN c.outer, c.inner")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Int64(2), Value::Int64(20)]]);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_default_schema_accepts_undeclared_names() {
+        let db = GrafeoDB::new_in_memory();
+
+        db.execute("INSERT (:Persn {nmae: 'Carol'})").unwrap();
+        db.execute("MATCH (n:Persn) SET n.age = 30").unwrap();
+
+        assert_eq!(db.node_count(), 1);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_statistics_refresh_after_enough_changes() {
+        let db =
+            GrafeoDB::with_config(Config::in_memory().with_statistics_refresh_ratio(0.5)).unwrap();
+        let person_count = |db: &GrafeoDB| {
+            db.store()
+                .statistics()
+                .get_label("Person")
+                .map_or(0, |stats| stats.node_count)
+        };
+
+        for _ in 0..10 {
+            db.create_node(&["Person"]);
+        }
+        db.execute("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(person_count(&db), 10);
+
+        // Below the ratio, queries keep planning with the old statistics
+        for _ in 0..5 {
+            db.create_node(&["Person"]);
+        }
+        db.execute("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(person_count(&db), 10);
+
+        db.execute("INSERT (:Person)").unwrap();
+        db.execute("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(person_count(&db), 16);
+
+        let manual =
+            GrafeoDB::with_config(Config::in_memory().without_statistics_refresh()).unwrap();
+        manual.create_node(&["Person"]);
+        manual.execute("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(person_count(&manual), 0);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_result_cache_invalidated_by_writes() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::with_config(Config::in_memory().with_result_cache(16)).unwrap();
+        db.create_node(&["Person"]);
+        let cache = db.result_cache().unwrap();
+        let count = "MATCH (n:Person) RETURN count(n)";
+
+        assert_eq!(db.execute(count).unwrap().rows[0][0], Value::Int64(1));
+        assert_eq!(db.execute(count).unwrap().rows[0][0], Value::Int64(1));
+        assert_eq!((cache.hits(), cache.misses()), (1, 1));
+
+        // Writes are never cached, and invalidate what was
+        db.execute("INSERT (:Person)").unwrap();
+        assert_eq!(db.execute(count).unwrap().rows[0][0], Value::Int64(2));
+        assert_eq!(cache.hits(), 1);
+
+        // Explicit transactions bypass the cache
+        let mut session = db.session();
+        session.begin_tx().unwrap();
+        session.execute("INSERT (:Person)").unwrap();
+        assert_eq!(session.execute(count).unwrap().rows[0][0], Value::Int64(3));
+        session.rollback().unwrap();
+        assert_eq!(db.execute(count).unwrap().rows[0][0], Value::Int64(2));
+
+        assert!(GrafeoDB::new_in_memory().result_cache().is_none());
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_result_cache_invalidated_by_other_sessions_commits() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::with_config(Config::in_memory().with_result_cache(16)).unwrap();
+        let alice = db.create_node_with_props(&["Person"], [("age", 30i64)]);
+        let reader = db.session();
+        let count = "MATCH (n:Person) RETURN count(n)";
+        let age = "MATCH (n:Person) WHERE n.age > 0 RETURN n.age";
+
+        // Cached while another session's batch is still open...
+        db.session()
+            .batch(|batch| {
+                batch.create_node(&["Person"])?;
+                batch.set_node_property(alice, "age", Value::Int64(31))?;
+                assert_eq!(reader.execute(count)?.rows[0][0], Value::Int64(1));
+                assert_eq!(reader.execute(age)?.rows[0][0], Value::Int64(30));
+                Ok(())
+            })
+            .unwrap();
+
+        // ...and stale once it commits
+        assert_eq!(reader.execute(count).unwrap().rows[0][0], Value::Int64(2));
+        assert_eq!(reader.execute(age).unwrap().rows[0][0], Value::Int64(31));
+
+        // A rolled-back batch leaves the cached result correct
+        let failed = db.session().batch(|batch| {
+            batch.create_node(&["Person"])?;
+            assert_eq!(reader.execute(count)?.rows[0][0], Value::Int64(2));
+            Err(grafeo_common::utils::error::Error::Internal("abort".into()))
+        });
+        assert!(failed.is_err());
+        assert_eq!(reader.execute(count).unwrap().rows[0][0], Value::Int64(2));
+    }
+
+    #[test]
+    fn test_query_limits_reject_complex_queries() {
+        use crate::config::QueryLimits;
+        use grafeo_common::utils::error::{Error, QueryErrorKind};
+
+        let is_complexity_error = |result: Result<QueryResult>| matches!(result, Err(Error::Query(err)) if err.kind == QueryErrorKind::Complexity);
+
+        // A pathologically nested query fails cleanly instead of overflowing the stack
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        let nested = format!(
+            "MATCH (n) RETURN {}1{}",
+            "(".repeat(50_000),
+            ")".repeat(50_000)
+        );
+        assert!(is_complexity_error(session.execute(&nested)));
+
+        let limits = QueryLimits::default()
+            .with_max_depth(8)
+            .with_max_operators(3);
+        let db = GrafeoDB::with_config(Config::in_memory().with_query_limits(limits)).unwrap();
+        let session = db.session();
+        assert!(session.execute("MATCH (n) RETURN n").is_ok());
+        assert!(is_complexity_error(session.execute(
+            "MATCH (n) WHERE n.age > 1 RETURN n ORDER BY n.age LIMIT 1"
+        )));
+        assert!(is_complexity_error(
+            session.execute("MATCH (n) RETURN ((((((((((1))))))))))")
+        ));
+    }
+
+    #[test]
+    fn test_memory_limit_stops_hash_heavy_query() {
+        let populate = |db: &GrafeoDB| {
+            for i in 0..20_000_i64 {
+                db.create_node_with_props(&["Item"], [("id", i)]);
+            }
+        };
+        let query = "MATCH (n:Item) RETURN n.id, count(n)";
+
+        let limit = 512 * 1024;
+        let tight = GrafeoDB::with_config(
+            Config::in_memory()
+                .with_memory_limit(limit)
+                .with_memory_accounting_granularity(16 * 1024),
+        )
+        .unwrap();
+        populate(&tight);
+        let err = tight.execute(query).unwrap_err();
+        assert!(err.to_string().contains("memory limit exceeded"));
+        assert!(tight.buffer_manager().allocated() <= limit);
+
+        let roomy = GrafeoDB::with_config(Config::in_memory().with_memory_limit(1 << 30)).unwrap();
+        populate(&roomy);
+        assert_eq!(roomy.execute(query).unwrap().row_count(), 20_000);
+        // Charges are released once the query's operators are dropped
+        assert_eq!(roomy.buffer_manager().allocated(), 0);
+    }
+
+    #[test]
+    fn test_memory_stats_peak_reflects_query_activity() {
+        use grafeo_common::memory::buffer::{MemoryRegion, PressureLevel};
+
+        let db = GrafeoDB::with_config(Config::in_memory().with_memory_limit(1 << 30)).unwrap();
+        for i in 0..20_000_i64 {
+            db.create_node_with_props(&["Item"], [("id", i)]);
+        }
+        let before = db.memory_stats();
+
+        db.execute("MATCH (n:Item) RETURN n.id, count(n)").unwrap();
+        db.execute("MATCH (n:Item) RETURN n.id ORDER BY n.id DESC")
+            .unwrap();
+
+        let after = db.memory_stats();
+        assert!(after.peak_allocated > before.peak_allocated);
+        assert_eq!(after.total_allocated, before.total_allocated);
+        assert_eq!(after.region_usage(MemoryRegion::ExecutionBuffers), 0);
+        assert_eq!(after.pressure_level, PressureLevel::Normal);
+        assert_eq!(after.budget, 1 << 30);
+    }
+
+    #[test]
+    fn test_pagerank_on_induced_subgraph_sees_only_traversed_nodes() {
+        use grafeo_adapters::plugins::algorithms::pagerank;
+        use grafeo_common::types::LogicalType;
+
+        let db = GrafeoDB::new_in_memory();
+        let a = db.create_node(&["Start"]);
+        let b = db.create_node(&["Hop"]);
+        let c = db.create_node(&["Hop"]);
+        let detour = db.create_node(&["Hop"]);
+        let beyond = db.create_node(&["Hop"]);
+        db.create_edge(a, b, "R");
+        db.create_edge(b, c, "R");
+        // Reachable only over other edge types, or not at all from a
+        db.create_edge(c, detour, "OTHER");
+        db.create_edge(detour, beyond, "R");
+
+        let result = db
+            .execute("MATCH p = (x:Start)-[:R*1..3]->(y) RETURN p")
+            .unwrap();
+        assert_eq!(result.column_types, vec![LogicalType::Path]);
+
+        let subgraph = result.induced_subgraph(&db);
+        assert_eq!(subgraph.node_ids(), &[a, b, c]);
+        assert_eq!(subgraph.edge_ids().len(), 2);
+
+        let ranks = pagerank(subgraph.graph(), 0.85, 100, 1e-6);
+        assert_eq!(ranks.len(), 3);
+        assert!(!ranks.contains_key(&detour));
+        assert!(!ranks.contains_key(&beyond));
+        assert!(ranks[&c] > ranks[&a]);
+    }
+
+    #[test]
+    fn test_iter_nodes_and_edges_with_filters() {
+        let db = GrafeoDB::new_in_memory();
+        let people: Vec<_> = (0..5).map(|_| db.create_node(&["Person"])).collect();
+        db.create_node(&["Company"]);
+        for pair in people.windows(2) {
+            db.create_edge(pair[0], pair[1], "KNOWS");
+        }
+        db.create_edge(people[0], people[4], "LIKES");
+
+        assert_eq!(db.iter_nodes(None).count(), db.node_count());
+        assert_eq!(db.iter_nodes(Some("Person")).count(), 5);
+        assert!(
+            db.iter_nodes(Some("Person"))
+                .all(|node| node.has_label("Person"))
+        );
+        assert_eq!(db.iter_nodes(Some("Robot")).count(), 0);
+
+        assert_eq!(db.iter_edges(None).count(), db.edge_count());
+        assert_eq!(db.iter_edges(Some("KNOWS")).count(), 4);
+        assert_eq!(db.iter_edges(Some("LIKES")).count(), 1);
+
+        // Writes made mid-iteration don't change what the iterator yields
+        let mut seen = 0;
+        for _ in db.iter_nodes(Some("Person")) {
+            db.create_node(&["Person"]);
+            seen += 1;
+        }
+        assert_eq!(seen, 5);
+        assert_eq!(db.iter_nodes(Some("Person")).count(), 10);
+    }
+
+    #[test]
+    fn test_zone_map_skip_stats() {
+        let db = GrafeoDB::new_in_memory();
+        // Ranks rise with node ids, so each scan chunk covers a narrow range
+        for i in 0..10_000_i64 {
+            db.create_node_with_props(&["Item"], [("rank", i)]);
+        }
+
+        let selective = db
+            .execute("MATCH (n:Item) WHERE n.rank < 100 RETURN n.rank")
+            .unwrap();
+        assert_eq!(selective.row_count(), 100);
+        assert_eq!(selective.stats.chunks_scanned, 1);
+        assert_eq!(selective.stats.chunks_skipped, 4);
+
+        let everything = db
+            .execute("MATCH (n:Item) WHERE n.rank >= 0 RETURN n.rank")
+            .unwrap();
+        assert_eq!(everything.row_count(), 10_000);
+        assert_eq!(everything.stats.chunks_skipped, 0);
+        assert_eq!(everything.stats.chunks_scanned, 5);
+
+        // Literal on the left is flipped, and unfiltered scans record nothing
+        let flipped = db
+            .execute("MATCH (n:Item) WHERE 9000 <= n.rank RETURN n.rank")
+            .unwrap();
+        assert_eq!(flipped.row_count(), 1_000);
+        assert_eq!(flipped.stats.chunks_skipped, 4);
+        let plain = db.execute("MATCH (n:Item) RETURN n.rank").unwrap();
+        assert_eq!(plain.stats, QueryStats::default());
+    }
 }
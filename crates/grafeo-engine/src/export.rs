@@ -0,0 +1,574 @@
+//! Writing the whole graph out as GraphML or node-link JSON.
+//!
+//! [`Session::export()`](crate::Session::export) streams nodes, then edges,
+//! from a single snapshot, so the graph is never held in memory at once.
+//!
+//! **GraphML** declares one `<key>` per property name, separately for nodes
+//! and edges. Its `attr.type` follows the values found: `boolean`, `long`,
+//! `double`, or `string` when they're mixed or have no GraphML type. Integers
+//! and floats under one name become `double`. Labels are kept in the
+//! reserved `labels` key as `:A:B`, and edge types in the reserved `type`
+//! key. Property names go in `attr.name`, so they may hold any characters;
+//! the few that XML can't carry at all are replaced with `U+FFFD`.
+//!
+//! **JSON** follows the node-link layout used by NetworkX and D3:
+//!
+//! ```text
+//! {"directed":true,"multigraph":true,"graph":{},
+//!  "nodes":[{"id":1,"labels":["Person"],"properties":{"name":"Alice"}}],
+//!  "links":[{"id":1,"source":1,"target":2,"type":"KNOWS","properties":{}}]}
+//! ```
+//!
+//! Property values are written as in query results (see
+//! [`format`](crate::format)), so temporal values become ISO 8601 strings.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+
+use grafeo_common::types::{EpochId, PropertyKey, Value};
+use grafeo_core::graph::lpg::LpgStore;
+
+use crate::format::{FloatFormat, write_json_value};
+
+/// The layout of an export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// GraphML XML, with typed property keys.
+    GraphMl,
+    /// A node-link JSON document.
+    Json,
+}
+
+/// Writes every node and edge visible at `epoch`.
+pub(crate) fn export(
+    store: &LpgStore,
+    epoch: EpochId,
+    writer: &mut impl Write,
+    format: ExportFormat,
+) -> io::Result<()> {
+    match format {
+        ExportFormat::GraphMl => write_graphml(store, epoch, writer),
+        ExportFormat::Json => write_node_link(store, epoch, writer),
+    }
+}
+
+/// The GraphML type of a property key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyType {
+    Boolean,
+    Long,
+    Double,
+    String,
+}
+
+impl KeyType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Bool(_) => Self::Boolean,
+            Value::Int64(_) => Self::Long,
+            Value::Float64(_) => Self::Double,
+            _ => Self::String,
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (a, b) if a == b => a,
+            (Self::Long, Self::Double) | (Self::Double, Self::Long) => Self::Double,
+            _ => Self::String,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Boolean => "boolean",
+            Self::Long => "long",
+            Self::Double => "double",
+            Self::String => "string",
+        }
+    }
+}
+
+/// Property keys in use, with their GraphML type and key id.
+type Keys = BTreeMap<PropertyKey, (KeyType, String)>;
+
+fn collect_keys<'a>(
+    properties: impl Iterator<Item = (&'a PropertyKey, &'a Value)>,
+    keys: &mut BTreeMap<PropertyKey, KeyType>,
+) {
+    for (key, value) in properties {
+        let ty = KeyType::of(value);
+        keys.entry(key.clone())
+            .and_modify(|known| *known = known.merge(ty))
+            .or_insert(ty);
+    }
+}
+
+fn write_graphml(store: &LpgStore, epoch: EpochId, w: &mut impl Write) -> io::Result<()> {
+    // First pass: only the key schema, which GraphML wants up front
+    let mut node_types = BTreeMap::new();
+    for node in store.all_nodes_at(epoch) {
+        collect_keys(node.properties.iter(), &mut node_types);
+    }
+    let mut edge_types = BTreeMap::new();
+    for edge in store.all_edges_at(epoch) {
+        collect_keys(edge.properties.iter(), &mut edge_types);
+    }
+    let mut next_id = 0;
+    let mut number = |types: BTreeMap<PropertyKey, KeyType>| -> Keys {
+        types
+            .into_iter()
+            .map(|(key, ty)| {
+                next_id += 1;
+                (key, (ty, format!("k{}", next_id - 1)))
+            })
+            .collect()
+    };
+    let node_keys = number(node_types);
+    let edge_keys = number(edge_types);
+
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="labels" for="node" attr.name="labels" attr.type="string"/>"#
+    )?;
+    writeln!(
+        w,
+        r#"  <key id="type" for="edge" attr.name="type" attr.type="string"/>"#
+    )?;
+    for (domain, keys) in [("node", &node_keys), ("edge", &edge_keys)] {
+        for (key, (ty, id)) in keys {
+            writeln!(
+                w,
+                r#"  <key id="{id}" for="{domain}" attr.name="{}" attr.type="{}"/>"#,
+                escape_xml(key.as_str()),
+                ty.name()
+            )?;
+        }
+    }
+    writeln!(w, r#"  <graph id="G" edgedefault="directed">"#)?;
+
+    for node in store.all_nodes_at(epoch) {
+        writeln!(w, r#"    <node id="n{}">"#, node.id.as_u64())?;
+        if !node.labels.is_empty() {
+            let mut labels = String::new();
+            for label in &node.labels {
+                labels.push(':');
+                labels.push_str(label);
+            }
+            writeln!(
+                w,
+                r#"      <data key="labels">{}</data>"#,
+                escape_xml(&labels)
+            )?;
+        }
+        write_data(w, &node.properties, &node_keys)?;
+        writeln!(w, "    </node>")?;
+    }
+    for edge in store.all_edges_at(epoch) {
+        writeln!(
+            w,
+            r#"    <edge id="e{}" source="n{}" target="n{}">"#,
+            edge.id.as_u64(),
+            edge.src.as_u64(),
+            edge.dst.as_u64()
+        )?;
+        writeln!(
+            w,
+            r#"      <data key="type">{}</data>"#,
+            escape_xml(&edge.edge_type)
+        )?;
+        write_data(w, &edge.properties, &edge_keys)?;
+        writeln!(w, "    </edge>")?;
+    }
+
+    writeln!(w, "  </graph>")?;
+    writeln!(w, "</graphml>")
+}
+
+fn write_data(
+    w: &mut impl Write,
+    properties: &BTreeMap<PropertyKey, Value>,
+    keys: &Keys,
+) -> io::Result<()> {
+    for (key, value) in properties {
+        let Some((_, id)) = keys.get(key) else {
+            continue;
+        };
+        writeln!(
+            w,
+            r#"      <data key="{id}">{}</data>"#,
+            escape_xml(&graphml_text(value))
+        )?;
+    }
+    Ok(())
+}
+
+/// Spells a value as GraphML data text.
+fn graphml_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.to_string(),
+        Value::Float64(f) if f.is_nan() => "NaN".to_string(),
+        Value::Float64(f) if f.is_infinite() => if *f > 0.0 { "INF" } else { "-INF" }.to_string(),
+        Value::Bool(_) | Value::Int64(_) | Value::Float64(_) => value.to_string(),
+        Value::Timestamp(_) | Value::Date(_) | Value::Duration(_) => value.to_string(),
+        _ => {
+            let mut out = String::new();
+            write_json_value(&mut out, value, &FloatFormat::default());
+            out
+        }
+    }
+}
+
+/// Escapes text for XML content and attributes. Characters XML 1.0 can't
+/// carry even as references are replaced with `U+FFFD`.
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\t' => out.push_str("&#9;"),
+            '\n' => out.push_str("&#10;"),
+            '\r' => out.push_str("&#13;"),
+            c if u32::from(c) < 0x20 || c == '\u{FFFE}' || c == '\u{FFFF}' => {
+                out.push('\u{FFFD}');
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn write_node_link(store: &LpgStore, epoch: EpochId, w: &mut impl Write) -> io::Result<()> {
+    let floats = FloatFormat::default();
+    let mut out = String::new();
+    write!(
+        w,
+        r#"{{"directed":true,"multigraph":true,"graph":{{}},"nodes":["#
+    )?;
+    for (i, node) in store.all_nodes_at(epoch).enumerate() {
+        out.clear();
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        let _ = write!(out, r#"{{"id":{},"labels":["#, node.id.as_u64());
+        for (j, label) in node.labels.iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            write_json_value(&mut out, &Value::from(label.as_ref()), &floats);
+        }
+        out.push_str(r#"],"properties":"#);
+        write_properties(&mut out, &node.properties, &floats);
+        out.push('}');
+        w.write_all(out.as_bytes())?;
+    }
+    write!(w, r#"],"links":["#)?;
+    for (i, edge) in store.all_edges_at(epoch).enumerate() {
+        out.clear();
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        let _ = write!(
+            out,
+            r#"{{"id":{},"source":{},"target":{},"type":"#,
+            edge.id.as_u64(),
+            edge.src.as_u64(),
+            edge.dst.as_u64()
+        );
+        write_json_value(&mut out, &Value::from(edge.edge_type.as_ref()), &floats);
+        out.push_str(r#","properties":"#);
+        write_properties(&mut out, &edge.properties, &floats);
+        out.push('}');
+        w.write_all(out.as_bytes())?;
+    }
+    writeln!(w, "]}}")
+}
+
+fn write_properties(
+    out: &mut String,
+    properties: &BTreeMap<PropertyKey, Value>,
+    floats: &FloatFormat,
+) {
+    out.push('{');
+    for (i, (key, value)) in properties.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json_value(out, &Value::from(key.as_str()), floats);
+        out.push(':');
+        write_json_value(out, value, floats);
+    }
+    out.push('}');
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use grafeo_common::types::NodeId;
+
+    use super::*;
+    use crate::GrafeoDB;
+
+    const WEIRD_KEY: &str = "odd <key> & \"quotes\"";
+
+    fn sample() -> GrafeoDB {
+        let db = GrafeoDB::new_in_memory();
+        let mut ids = Vec::new();
+        for i in 0..20_i64 {
+            let labels: &[&str] = if i % 3 == 0 {
+                &["Person", "Admin"]
+            } else {
+                &["Person"]
+            };
+            ids.push(db.create_node_with_props(
+                labels,
+                [
+                    ("name", Value::from(format!("p{i} <&> 'x'"))),
+                    ("age", Value::Int64(20 + i)),
+                    ("score", Value::Float64(i as f64 / 2.0)),
+                    ("active", Value::Bool(i % 2 == 0)),
+                    (WEIRD_KEY, Value::from("line\nbreak")),
+                ],
+            ));
+        }
+        let bare = db.create_node(&[]);
+        for i in 0..20 {
+            db.create_edge_with_props(
+                ids[i],
+                ids[(i + 1) % 20],
+                "KNOWS",
+                [("since", 2000 + i as i64)],
+            );
+        }
+        // A parallel edge and a self loop
+        db.create_edge(ids[0], ids[1], "KNOWS");
+        db.create_edge_with_props(ids[5], ids[5], "LIKES", [("w", 0.5)]);
+        db.create_edge(bare, ids[2], "SEES");
+        db
+    }
+
+    fn export_text(db: &GrafeoDB, format: ExportFormat) -> String {
+        let mut out = Vec::new();
+        db.session().export(&mut out, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// Describes the graph without ids, so isomorphic graphs with unique node
+    /// descriptions compare equal.
+    fn canonical(db: &GrafeoDB) -> (Vec<String>, Vec<String>) {
+        let store = db.store();
+        let describe = |id: NodeId| {
+            let node = store.get_node(id).unwrap();
+            let mut labels: Vec<&str> = node.labels.iter().map(AsRef::as_ref).collect();
+            labels.sort_unstable();
+            format!("{labels:?} {:?}", node.properties)
+        };
+        let mut nodes: Vec<String> = store.node_ids().into_iter().map(describe).collect();
+        nodes.sort();
+        let mut edges: Vec<String> = store
+            .all_edges()
+            .map(|e| {
+                format!(
+                    "{} -[{} {:?}]-> {}",
+                    describe(e.src),
+                    e.edge_type,
+                    e.properties,
+                    describe(e.dst)
+                )
+            })
+            .collect();
+        edges.sort();
+        (nodes, edges)
+    }
+
+    fn attribute<'a>(line: &'a str, name: &str) -> &'a str {
+        let start = line.find(&format!(" {name}=\"")).unwrap() + name.len() + 3;
+        let len = line[start..].find('"').unwrap();
+        &line[start..start + len]
+    }
+
+    fn unescape(text: &str) -> String {
+        let mut out = String::new();
+        let mut rest = text;
+        while let Some(amp) = rest.find('&') {
+            out.push_str(&rest[..amp]);
+            let end = rest[amp..].find(';').unwrap() + amp;
+            let entity = &rest[amp + 1..end];
+            out.push(match entity {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => char::from_u32(entity[1..].parse().unwrap()).unwrap(),
+            });
+            rest = &rest[end + 1..];
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Reads back the one-element-per-line GraphML this module writes.
+    fn load_graphml(text: &str) -> GrafeoDB {
+        let db = GrafeoDB::new_in_memory();
+        let mut keys: HashMap<String, (String, String)> = HashMap::new();
+        let mut ids: HashMap<String, NodeId> = HashMap::new();
+        let mut current: Option<(String, Option<(String, String)>)> = None;
+        let mut labels: Vec<String> = Vec::new();
+        let mut edge_type = String::new();
+        let mut props: Vec<(String, Value)> = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.starts_with("<key ") {
+                keys.insert(
+                    attribute(line, "id").to_string(),
+                    (
+                        unescape(attribute(line, "attr.name")),
+                        attribute(line, "attr.type").to_string(),
+                    ),
+                );
+            } else if line.starts_with("<node ") {
+                current = Some((attribute(line, "id").to_string(), None));
+            } else if line.starts_with("<edge ") {
+                let ends = (
+                    attribute(line, "source").to_string(),
+                    attribute(line, "target").to_string(),
+                );
+                current = Some((attribute(line, "id").to_string(), Some(ends)));
+            } else if line.starts_with("<data ") {
+                let key = attribute(line, "key");
+                let start = line.find('>').unwrap() + 1;
+                let text = unescape(&line[start..line.rfind("</data>").unwrap()]);
+                match key {
+                    "labels" => {
+                        labels = text.split(':').skip(1).map(str::to_string).collect();
+                    }
+                    "type" => edge_type = text,
+                    _ => {
+                        let (name, ty) = &keys[key];
+                        let value = match ty.as_str() {
+                            "boolean" => Value::Bool(text == "true"),
+                            "long" => Value::Int64(text.parse().unwrap()),
+                            "double" => Value::Float64(text.parse().unwrap()),
+                            _ => Value::from(text),
+                        };
+                        props.push((name.clone(), value));
+                    }
+                }
+            } else if line == "</node>" {
+                let (id, _) = current.take().unwrap();
+                let owned = std::mem::take(&mut labels);
+                let labels: Vec<&str> = owned.iter().map(String::as_str).collect();
+                ids.insert(id, db.create_node_with_props(&labels, props.drain(..)));
+            } else if line == "</edge>" {
+                let (_, ends) = current.take().unwrap();
+                let (src, dst) = ends.unwrap();
+                db.create_edge_with_props(ids[&src], ids[&dst], &edge_type, props.drain(..));
+            }
+        }
+        db
+    }
+
+    fn load_node_link(text: &str) -> GrafeoDB {
+        let db = GrafeoDB::new_in_memory();
+        let doc: serde_json::Value = serde_json::from_str(text).unwrap();
+        let properties = |item: &serde_json::Value| -> Vec<(String, Value)> {
+            item["properties"]
+                .as_object()
+                .unwrap()
+                .iter()
+                .map(|(k, v)| (k.clone(), crate::import::json_to_value(v.clone())))
+                .collect()
+        };
+        let mut ids = HashMap::new();
+        for node in doc["nodes"].as_array().unwrap() {
+            let labels: Vec<&str> = node["labels"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|l| l.as_str().unwrap())
+                .collect();
+            let id = db.create_node_with_props(&labels, properties(node));
+            ids.insert(node["id"].as_u64().unwrap(), id);
+        }
+        for link in doc["links"].as_array().unwrap() {
+            let src = ids[&link["source"].as_u64().unwrap()];
+            let dst = ids[&link["target"].as_u64().unwrap()];
+            db.create_edge_with_props(src, dst, link["type"].as_str().unwrap(), properties(link));
+        }
+        db
+    }
+
+    #[test]
+    fn test_graphml_round_trip() {
+        let db = sample();
+        let text = export_text(&db, ExportFormat::GraphMl);
+        assert!(text.contains(r#"attr.name="odd &lt;key&gt; &amp; &quot;quotes&quot;""#));
+        assert!(text.contains(r#"attr.name="age" attr.type="long""#));
+        assert!(text.contains(r#"attr.name="score" attr.type="double""#));
+        assert!(text.contains(r#"attr.name="active" attr.type="boolean""#));
+
+        let copy = load_graphml(&text);
+        assert_eq!(copy.node_count(), 21);
+        assert_eq!(copy.edge_count(), 23);
+        assert_eq!(canonical(&copy), canonical(&db));
+    }
+
+    #[test]
+    fn test_node_link_json_round_trip() {
+        let db = sample();
+        let copy = load_node_link(&export_text(&db, ExportFormat::Json));
+        assert_eq!(copy.node_count(), 21);
+        assert_eq!(copy.edge_count(), 23);
+        assert_eq!(canonical(&copy), canonical(&db));
+    }
+
+    #[test]
+    fn test_empty_graph_exports_valid_documents() {
+        let db = GrafeoDB::new_in_memory();
+
+        let graphml = export_text(&db, ExportFormat::GraphMl);
+        assert!(graphml.starts_with("<?xml"));
+        assert!(graphml.contains(r#"<graph id="G" edgedefault="directed">"#));
+        assert!(graphml.trim_end().ends_with("</graphml>"));
+        assert_eq!(load_graphml(&graphml).node_count(), 0);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&export_text(&db, ExportFormat::Json)).unwrap();
+        assert_eq!(json["nodes"], serde_json::json!([]));
+        assert_eq!(json["links"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_mixed_property_types_widen_graphml_keys() {
+        let db = GrafeoDB::new_in_memory();
+        db.create_node_with_props(&["N"], [("n", Value::Int64(1)), ("m", Value::Int64(1))]);
+        db.create_node_with_props(
+            &["N"],
+            [("n", Value::Float64(2.5)), ("m", Value::from("x"))],
+        );
+        db.create_node_with_props(
+            &["N"],
+            [("list", Value::List(vec![Value::Int64(1)].into()))],
+        );
+
+        let text = export_text(&db, ExportFormat::GraphMl);
+        assert!(text.contains(r#"attr.name="n" attr.type="double""#));
+        assert!(text.contains(r#"attr.name="m" attr.type="string""#));
+        assert!(text.contains(r#"attr.name="list" attr.type="string""#));
+        assert!(text.contains(">[1]</data>"));
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(escape_xml("a<b>&\"c'"), "a&lt;b&gt;&amp;&quot;c&apos;");
+        assert_eq!(escape_xml("tab\there"), "tab&#9;here");
+        assert_eq!(escape_xml("bell\u{7}"), "bell\u{FFFD}");
+    }
+}
@@ -0,0 +1,853 @@
+//! Rendering query results as JSON, CSV, or plain text.
+//!
+//! All three renderers take a [`FloatFormat`], so a `Float64` comes out the
+//! same way whichever format you pick. Floats always keep a decimal point
+//! (`1.0`, never `1`) so they stay distinguishable from integers.
+//!
+//! Non-finite floats have no common spelling, so each format defines its own:
+//!
+//! | Value     | JSON   | CSV    | Text        |
+//! |-----------|--------|--------|-------------|
+//! | NaN       | `null` | `NaN`  | `NaN`       |
+//! | +Infinity | `null` | `inf`  | `Infinity`  |
+//! | -Infinity | `null` | `-inf` | `-Infinity` |
+//!
+//! JSON has no way to spell these at all, hence `null`.
+//!
+//! Node and edge ids are plain integers internally. An [`IdFormat`] controls
+//! how columns holding them are written: as the raw integer, with a prefix,
+//! or through a URI template. Pass either format on its own, or combine both
+//! in a [`ResultFormat`].
+//!
+//! ```
+//! use grafeo_engine::{FloatFormat, GrafeoDB};
+//!
+//! let db = GrafeoDB::new_in_memory();
+//! db.create_node_with_props(&["Item"], [("ratio", 2.0_f64 / 3.0)]);
+//! let result = db.execute("MATCH (n:Item) RETURN n.ratio AS ratio")?;
+//!
+//! let format = FloatFormat::default().with_precision(3);
+//! assert_eq!(result.to_csv(&format), "ratio\n0.667\n");
+//! # Ok::<(), grafeo_common::utils::error::Error>(())
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use grafeo_common::types::{LogicalType, Value};
+
+use crate::database::QueryResult;
+
+/// How `Float64` values are written out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloatFormat {
+    /// Digits after the decimal point, or `None` for the shortest form that
+    /// reads back as the same value.
+    pub precision: Option<usize>,
+    /// Magnitudes at or above this switch to scientific notation.
+    pub scientific_above: f64,
+    /// Non-zero magnitudes below this switch to scientific notation.
+    pub scientific_below: f64,
+}
+
+impl Default for FloatFormat {
+    fn default() -> Self {
+        Self {
+            precision: None,
+            scientific_above: 1e15,
+            scientific_below: 1e-6,
+        }
+    }
+}
+
+impl FloatFormat {
+    /// Rounds floats to a fixed number of decimal places.
+    #[must_use]
+    pub fn with_precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+
+    /// Sets the magnitudes outside which floats use scientific notation.
+    #[must_use]
+    pub fn with_scientific_bounds(mut self, below: f64, above: f64) -> Self {
+        self.scientific_below = below;
+        self.scientific_above = above;
+        self
+    }
+
+    /// Formats a finite float. Non-finite values are left to the renderers.
+    fn format_finite(&self, value: f64) -> String {
+        let magnitude = value.abs();
+        let scientific = magnitude >= self.scientific_above
+            || (magnitude != 0.0 && magnitude < self.scientific_below);
+
+        if scientific {
+            return match self.precision {
+                Some(digits) => format!("{value:.digits$e}"),
+                None => format!("{value:e}"),
+            };
+        }
+
+        let text = match self.precision {
+            Some(digits) => format!("{value:.digits$}"),
+            None => format!("{value}"),
+        };
+        if text.contains('.') {
+            text
+        } else {
+            format!("{text}.0")
+        }
+    }
+}
+
+/// How node and edge ids are written out.
+///
+/// Applies to result columns typed as nodes or edges; ids nested inside
+/// lists or maps are written as integers.
+///
+/// ```
+/// use grafeo_engine::{GrafeoDB, IdFormat};
+///
+/// let db = GrafeoDB::new_in_memory();
+/// let mut session = db.session();
+/// session.create_node(&["Person"]);
+/// session.set_id_format(IdFormat::uri(
+///     "http://example.org/node/{id}",
+///     "http://example.org/edge/{id}",
+/// ));
+///
+/// let result = session.execute("MATCH (p:Person) RETURN p")?;
+/// assert_eq!(result.to_csv(session.id_format()), "p\nhttp://example.org/node/0\n");
+/// # Ok::<(), grafeo_common::utils::error::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum IdFormat {
+    /// The raw integer id, e.g. `42`.
+    #[default]
+    Integer,
+    /// The id as a string after a prefix, e.g. `"n42"`.
+    Prefixed {
+        /// Prefix for node ids.
+        node: String,
+        /// Prefix for edge ids.
+        edge: String,
+    },
+    /// The id substituted for `{id}` in a URI template, e.g.
+    /// `"http://example.org/node/42"`.
+    Uri {
+        /// Template for node ids.
+        node: String,
+        /// Template for edge ids.
+        edge: String,
+    },
+}
+
+impl IdFormat {
+    /// Writes ids as strings after a node or edge prefix.
+    #[must_use]
+    pub fn prefixed(node: impl Into<String>, edge: impl Into<String>) -> Self {
+        Self::Prefixed {
+            node: node.into(),
+            edge: edge.into(),
+        }
+    }
+
+    /// Writes ids by substituting them for `{id}` in a node or edge template.
+    #[must_use]
+    pub fn uri(node: impl Into<String>, edge: impl Into<String>) -> Self {
+        Self::Uri {
+            node: node.into(),
+            edge: edge.into(),
+        }
+    }
+
+    /// Formats an id, or returns `None` if it stays an integer.
+    fn format_id(&self, id: i64, is_edge: bool) -> Option<String> {
+        match self {
+            IdFormat::Integer => None,
+            IdFormat::Prefixed { node, edge } => {
+                let prefix = if is_edge { edge } else { node };
+                Some(format!("{prefix}{id}"))
+            }
+            IdFormat::Uri { node, edge } => {
+                let template = if is_edge { edge } else { node };
+                Some(template.replace("{id}", &id.to_string()))
+            }
+        }
+    }
+}
+
+/// Float and id formatting for rendering a result.
+///
+/// The renderers accept a `ResultFormat`, or just a [`FloatFormat`] or
+/// [`IdFormat`] to leave the other at its default.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ResultFormat {
+    /// How floats are written.
+    pub floats: FloatFormat,
+    /// How node and edge ids are written.
+    pub ids: IdFormat,
+}
+
+impl ResultFormat {
+    /// Sets how floats are written.
+    #[must_use]
+    pub fn with_floats(mut self, floats: FloatFormat) -> Self {
+        self.floats = floats;
+        self
+    }
+
+    /// Sets how node and edge ids are written.
+    #[must_use]
+    pub fn with_ids(mut self, ids: IdFormat) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Returns the id as formatted text, if this column holds ids.
+    fn format_id(&self, value: &Value, column_type: Option<&LogicalType>) -> Option<String> {
+        let is_edge = match column_type {
+            Some(LogicalType::Node) => false,
+            Some(LogicalType::Edge) => true,
+            _ => return None,
+        };
+        match value {
+            Value::Int64(id) => self.ids.format_id(*id, is_edge),
+            _ => None,
+        }
+    }
+}
+
+impl From<&FloatFormat> for ResultFormat {
+    fn from(floats: &FloatFormat) -> Self {
+        Self::default().with_floats(*floats)
+    }
+}
+
+impl From<&IdFormat> for ResultFormat {
+    fn from(ids: &IdFormat) -> Self {
+        Self::default().with_ids(ids.clone())
+    }
+}
+
+impl From<&ResultFormat> for ResultFormat {
+    fn from(format: &ResultFormat) -> Self {
+        format.clone()
+    }
+}
+
+/// How flat result rows fold into nested JSON objects.
+///
+/// Joins repeat a parent's columns once per child, so a shape names the
+/// column identifying each level's objects. Rows sharing an id become one
+/// object, whose nested fields collect the child objects from those rows.
+///
+/// ```
+/// use grafeo_common::types::Value;
+/// use grafeo_engine::{GrafeoDB, IdFormat, ResultShape};
+///
+/// let db = GrafeoDB::new_in_memory();
+/// let session = db.session();
+/// let alice = session.create_node_with_props(&["Person"], [("name", Value::from("Alice"))]);
+/// let bob = session.create_node_with_props(&["Person"], [("name", Value::from("Bob"))]);
+/// session.create_edge(alice, bob, "KNOWS");
+///
+/// let result = session.execute(
+///     "MATCH (a:Person)-[:KNOWS]->(b) RETURN id(a) AS a, a.name AS name, id(b) AS b, b.name AS friend",
+/// )?;
+///
+/// let friends = ResultShape::list("b").with_column("name", "friend");
+/// let shape = ResultShape::list("a")
+///     .with_column("name", "name")
+///     .with_nested("knows", friends);
+/// assert_eq!(
+///     result.to_nested_json(&shape, &IdFormat::Integer),
+///     r#"[{"name":"Alice","knows":[{"name":"Bob"}]}]"#
+/// );
+/// # Ok::<(), grafeo_common::utils::error::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResultShape {
+    /// Column identifying this level's objects, or `None` for a single object.
+    id_column: Option<String>,
+    /// Whether the objects are written as a single value rather than a list.
+    single: bool,
+    /// Output fields, in order.
+    fields: Vec<(String, ShapeField)>,
+}
+
+/// A field of a [`ResultShape`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ShapeField {
+    /// A value read from a result column.
+    Column(String),
+    /// A list of child objects.
+    Nested(ResultShape),
+}
+
+impl ResultShape {
+    /// A list with one object per distinct value of `id_column`.
+    ///
+    /// Rows where the id is null, such as a parent without children in an
+    /// outer join, contribute no object.
+    #[must_use]
+    pub fn list(id_column: impl Into<String>) -> Self {
+        Self {
+            id_column: Some(id_column.into()),
+            single: false,
+            fields: Vec::new(),
+        }
+    }
+
+    /// The first object by `id_column`, or `null` if no row has an id.
+    ///
+    /// For to-one relationships, where a list would hold at most one object.
+    #[must_use]
+    pub fn one(id_column: impl Into<String>) -> Self {
+        Self {
+            single: true,
+            ..Self::list(id_column)
+        }
+    }
+
+    /// A single object built from all rows.
+    #[must_use]
+    pub fn object() -> Self {
+        Self {
+            id_column: None,
+            single: false,
+            fields: Vec::new(),
+        }
+    }
+
+    /// Adds a field holding the value of `column`.
+    #[must_use]
+    pub fn with_column(mut self, key: impl Into<String>, column: impl Into<String>) -> Self {
+        self.fields
+            .push((key.into(), ShapeField::Column(column.into())));
+        self
+    }
+
+    /// Adds a field holding the objects of a nested shape.
+    #[must_use]
+    pub fn with_nested(mut self, key: impl Into<String>, shape: ResultShape) -> Self {
+        self.fields.push((key.into(), ShapeField::Nested(shape)));
+        self
+    }
+}
+
+/// The output formats, which differ in how they write non-finite floats.
+#[derive(Clone, Copy)]
+enum Target {
+    Json,
+    Csv,
+    Text,
+}
+
+impl Target {
+    fn format_float(self, value: f64, format: &FloatFormat) -> String {
+        if value.is_finite() {
+            return format.format_finite(value);
+        }
+        let text = match (self, value.is_nan(), value > 0.0) {
+            (Target::Json, _, _) => "null",
+            (_, true, _) => "NaN",
+            (Target::Csv, false, true) => "inf",
+            (Target::Csv, false, false) => "-inf",
+            (Target::Text, false, true) => "Infinity",
+            (Target::Text, false, false) => "-Infinity",
+        };
+        text.to_string()
+    }
+}
+
+impl QueryResult {
+    /// Renders the result as a JSON array with one object per row.
+    ///
+    /// Ids formatted as prefixed strings or URIs become JSON strings.
+    #[must_use]
+    pub fn to_json(&self, format: impl Into<ResultFormat>) -> String {
+        let format = format.into();
+        let mut out = String::from("[");
+        for (i, row) in self.rows.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            out.push('{');
+            for (j, (column, value)) in self.columns.iter().zip(row).enumerate() {
+                if j > 0 {
+                    out.push(',');
+                }
+                write_json_string(&mut out, column);
+                out.push(':');
+                match format.format_id(value, self.column_types.get(j)) {
+                    Some(id) => write_json_string(&mut out, &id),
+                    None => write_json_value(&mut out, value, &format.floats),
+                }
+            }
+            out.push('}');
+        }
+        out.push(']');
+        out
+    }
+
+    /// Renders the result as nested JSON following `shape`.
+    ///
+    /// Objects keep the order in which their ids first appear. A field's
+    /// value comes from the first row of its object.
+    #[must_use]
+    pub fn to_nested_json(&self, shape: &ResultShape, format: impl Into<ResultFormat>) -> String {
+        let format = format.into();
+        let rows: Vec<usize> = (0..self.rows.len()).collect();
+        let mut out = String::new();
+        self.write_shape(&mut out, shape, &rows, &format);
+        out
+    }
+
+    fn write_shape(
+        &self,
+        out: &mut String,
+        shape: &ResultShape,
+        rows: &[usize],
+        format: &ResultFormat,
+    ) {
+        let Some(id_column) = &shape.id_column else {
+            self.write_object(out, shape, rows, format);
+            return;
+        };
+
+        // Group rows by id, in order of first appearance
+        let id_index = self.columns.iter().position(|c| c == id_column);
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        let mut group_of: HashMap<i64, usize> = HashMap::new();
+        for &row in rows {
+            let id = id_index.and_then(|i| self.rows[row][i].as_int64());
+            let Some(id) = id else {
+                continue;
+            };
+            let group = *group_of.entry(id).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(row);
+        }
+
+        if shape.single {
+            match groups.first() {
+                Some(group) => self.write_object(out, shape, group, format),
+                None => out.push_str("null"),
+            }
+            return;
+        }
+
+        out.push('[');
+        for (i, group) in groups.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            self.write_object(out, shape, group, format);
+        }
+        out.push(']');
+    }
+
+    fn write_object(
+        &self,
+        out: &mut String,
+        shape: &ResultShape,
+        rows: &[usize],
+        format: &ResultFormat,
+    ) {
+        out.push('{');
+        for (i, (key, field)) in shape.fields.iter().enumerate() {
+            if i > 0 {
+                out.push(',');
+            }
+            write_json_string(out, key);
+            out.push(':');
+            match field {
+                ShapeField::Column(column) => {
+                    let index = self.columns.iter().position(|c| c == column);
+                    let value = index
+                        .zip(rows.first())
+                        .map_or(&Value::Null, |(index, &row)| &self.rows[row][index]);
+                    match format.format_id(value, index.and_then(|i| self.column_types.get(i))) {
+                        Some(id) => write_json_string(out, &id),
+                        None => write_json_value(out, value, &format.floats),
+                    }
+                }
+                ShapeField::Nested(nested) => self.write_shape(out, nested, rows, format),
+            }
+        }
+        out.push('}');
+    }
+
+    /// Renders the result as CSV with a header row.
+    ///
+    /// Fields are quoted only when they contain a comma, quote, or line break.
+    #[must_use]
+    pub fn to_csv(&self, format: impl Into<ResultFormat>) -> String {
+        let format = format.into();
+        let mut out = String::new();
+        write_csv_row(&mut out, self.columns.iter().map(String::as_str));
+        for row in &self.rows {
+            let fields: Vec<String> = row
+                .iter()
+                .enumerate()
+                .map(|(i, value)| self.render_cell(i, value, &format, Target::Csv))
+                .collect();
+            write_csv_row(&mut out, fields.iter().map(String::as_str));
+        }
+        out
+    }
+
+    /// Renders the result as a plain-text table with aligned columns.
+    #[must_use]
+    pub fn to_text(&self, format: impl Into<ResultFormat>) -> String {
+        let format = format.into();
+        let cells: Vec<Vec<String>> = self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .map(|(i, value)| self.render_cell(i, value, &format, Target::Text))
+                    .collect()
+            })
+            .collect();
+
+        let mut widths: Vec<usize> = self.columns.iter().map(|c| c.chars().count()).collect();
+        for row in &cells {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let mut out = String::new();
+        write_text_row(&mut out, &self.columns, &widths);
+        let rule: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+        write_text_row(&mut out, &rule, &widths);
+        for row in &cells {
+            write_text_row(&mut out, row, &widths);
+        }
+        out
+    }
+
+    /// Renders the value in column `column` for CSV or text output.
+    fn render_cell(
+        &self,
+        column: usize,
+        value: &Value,
+        format: &ResultFormat,
+        target: Target,
+    ) -> String {
+        format
+            .format_id(value, self.column_types.get(column))
+            .unwrap_or_else(|| render_text(value, &format.floats, target))
+    }
+}
+
+pub(crate) fn write_json_value(out: &mut String, value: &Value, format: &FloatFormat) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Int64(i) => {
+            let _ = write!(out, "{i}");
+        }
+        Value::Float64(f) => out.push_str(&Target::Json.format_float(*f, format)),
+        Value::String(s) => write_json_string(out, s),
+        Value::Bytes(bytes) => {
+            out.push('[');
+            for (i, byte) in bytes.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                let _ = write!(out, "{byte}");
+            }
+            out.push(']');
+        }
+        Value::Timestamp(t) => write_json_string(out, &t.to_string()),
+        Value::Date(_) | Value::Duration(_) => write_json_string(out, &value.to_string()),
+        Value::List(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_json_value(out, item, format);
+            }
+            out.push(']');
+        }
+        Value::Map(map) => {
+            out.push('{');
+            for (i, (key, item)) in map.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_json_string(out, key.as_str());
+                out.push(':');
+                write_json_value(out, item, format);
+            }
+            out.push('}');
+        }
+        Value::Point { x, y, srid } => {
+            out.push_str("{\"x\":");
+            out.push_str(&Target::Json.format_float(*x, format));
+            out.push_str(",\"y\":");
+            out.push_str(&Target::Json.format_float(*y, format));
+            let _ = write!(out, ",\"srid\":{srid}}}");
+        }
+    }
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", u32::from(c));
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Renders a value for CSV or text output. Top-level strings are unquoted.
+fn render_text(value: &Value, format: &FloatFormat, target: Target) -> String {
+    match value {
+        Value::Null if matches!(target, Target::Csv) => String::new(),
+        Value::Float64(f) => target.format_float(*f, format),
+        Value::String(s) => s.to_string(),
+        Value::List(items) => {
+            let inner: Vec<String> = items
+                .iter()
+                .map(|item| render_nested(item, format, target))
+                .collect();
+            format!("[{}]", inner.join(", "))
+        }
+        Value::Map(map) => {
+            let inner: Vec<String> = map
+                .iter()
+                .map(|(key, item)| format!("{key}: {}", render_nested(item, format, target)))
+                .collect();
+            format!("{{{}}}", inner.join(", "))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Renders a value inside a list or map, where strings keep their quotes.
+fn render_nested(value: &Value, format: &FloatFormat, target: Target) -> String {
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::String(s) => format!("{s:?}"),
+        other => render_text(other, format, target),
+    }
+}
+
+fn write_csv_row<'a>(out: &mut String, fields: impl Iterator<Item = &'a str>) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push('\n');
+}
+
+fn write_text_row(out: &mut String, cells: &[String], widths: &[usize]) {
+    let padded: Vec<String> = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{cell:<width$}"))
+        .collect();
+    out.push_str(padded.join(" | ").trim_end());
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn floats(values: &[f64]) -> QueryResult {
+        let mut result = QueryResult::new(vec!["x".to_string()]);
+        for v in values {
+            result.rows.push(vec![Value::Float64(*v)]);
+        }
+        result
+    }
+
+    #[test]
+    fn test_integral_floats_keep_decimal_point() {
+        let result = floats(&[1.0, -3.0]);
+        let format = FloatFormat::default();
+
+        assert_eq!(result.to_json(&format), r#"[{"x":1.0},{"x":-3.0}]"#);
+        assert_eq!(result.to_csv(&format), "x\n1.0\n-3.0\n");
+        assert_eq!(result.to_text(&format), "x\n----\n1.0\n-3.0\n");
+    }
+
+    #[test]
+    fn test_precision_applies_to_every_format() {
+        let result = floats(&[1.0 / 3.0, 2.5]);
+        let format = FloatFormat::default().with_precision(2);
+
+        assert_eq!(result.to_json(&format), r#"[{"x":0.33},{"x":2.50}]"#);
+        assert_eq!(result.to_csv(&format), "x\n0.33\n2.50\n");
+        assert_eq!(result.to_text(&format), "x\n----\n0.33\n2.50\n");
+    }
+
+    #[test]
+    fn test_scientific_threshold() {
+        let result = floats(&[1.5e20, 2.5e-9, 0.0]);
+
+        let format = FloatFormat::default();
+        assert_eq!(result.to_csv(&format), "x\n1.5e20\n2.5e-9\n0.0\n");
+
+        let format = FloatFormat::default()
+            .with_precision(1)
+            .with_scientific_bounds(1e-3, 1e3);
+        let result = floats(&[1234.5, 0.0005, 12.25]);
+        assert_eq!(result.to_csv(&format), "x\n1.2e3\n5.0e-4\n12.2\n");
+    }
+
+    #[test]
+    fn test_non_finite_floats_per_format() {
+        let result = floats(&[f64::NAN, f64::INFINITY, f64::NEG_INFINITY]);
+        let format = FloatFormat::default();
+
+        assert_eq!(
+            result.to_json(&format),
+            r#"[{"x":null},{"x":null},{"x":null}]"#
+        );
+        assert_eq!(result.to_csv(&format), "x\nNaN\ninf\n-inf\n");
+        assert_eq!(
+            result.to_text(&format),
+            "x\n---------\nNaN\nInfinity\n-Infinity\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_floats_and_escaping() {
+        let mut result = QueryResult::new(vec!["name".to_string(), "scores".to_string()]);
+        result.rows.push(vec![
+            Value::from("Smith, \"Al\""),
+            Value::List(vec![Value::Float64(1.0), Value::Float64(0.125)].into()),
+        ]);
+        let format = FloatFormat::default().with_precision(1);
+
+        assert_eq!(
+            result.to_json(&format),
+            r#"[{"name":"Smith, \"Al\"","scores":[1.0,0.1]}]"#
+        );
+        assert_eq!(
+            result.to_csv(&format),
+            "name,scores\n\"Smith, \"\"Al\"\"\",\"[1.0, 0.1]\"\n"
+        );
+    }
+
+    #[test]
+    fn test_nested_one_writes_object_or_null() {
+        let mut result = QueryResult::new(vec![
+            "p".to_string(),
+            "name".to_string(),
+            "c".to_string(),
+            "company".to_string(),
+        ]);
+        result.rows.push(vec![
+            Value::Int64(1),
+            Value::from("Alice"),
+            Value::Int64(9),
+            Value::from("Acme"),
+        ]);
+        result.rows.push(vec![
+            Value::Int64(2),
+            Value::from("Bob"),
+            Value::Null,
+            Value::Null,
+        ]);
+        let shape = ResultShape::list("p")
+            .with_column("name", "name")
+            .with_nested(
+                "employer",
+                ResultShape::one("c").with_column("name", "company"),
+            );
+
+        assert_eq!(
+            result.to_nested_json(&shape, &IdFormat::Integer),
+            r#"[{"name":"Alice","employer":{"name":"Acme"}},{"name":"Bob","employer":null}]"#
+        );
+    }
+
+    fn ids() -> QueryResult {
+        let mut result = QueryResult::with_types(
+            vec!["n".to_string(), "e".to_string(), "age".to_string()],
+            vec![LogicalType::Node, LogicalType::Edge, LogicalType::Int64],
+        );
+        result
+            .rows
+            .push(vec![Value::Int64(7), Value::Int64(3), Value::Int64(30)]);
+        result
+    }
+
+    #[test]
+    fn test_integer_ids_by_default() {
+        let result = ids();
+        let format = IdFormat::default();
+
+        assert_eq!(result.to_json(&format), r#"[{"n":7,"e":3,"age":30}]"#);
+        assert_eq!(result.to_csv(&format), "n,e,age\n7,3,30\n");
+        assert_eq!(
+            result.to_text(&format),
+            "n | e | age\n- | - | ---\n7 | 3 | 30\n"
+        );
+    }
+
+    #[test]
+    fn test_prefixed_ids_per_format() {
+        let result = ids();
+        let format = IdFormat::prefixed("n", "e");
+
+        assert_eq!(result.to_json(&format), r#"[{"n":"n7","e":"e3","age":30}]"#);
+        assert_eq!(result.to_csv(&format), "n,e,age\nn7,e3,30\n");
+        assert_eq!(
+            result.to_text(&format),
+            "n  | e  | age\n-- | -- | ---\nn7 | e3 | 30\n"
+        );
+    }
+
+    #[test]
+    fn test_uri_ids_per_format() {
+        let result = ids();
+        let format = ResultFormat::default().with_ids(IdFormat::uri(
+            "http://ex.org/node/{id}",
+            "http://ex.org/edge/{id}",
+        ));
+
+        assert_eq!(
+            result.to_json(&format),
+            r#"[{"n":"http://ex.org/node/7","e":"http://ex.org/edge/3","age":30}]"#
+        );
+        assert_eq!(
+            result.to_csv(&format),
+            "n,e,age\nhttp://ex.org/node/7,http://ex.org/edge/3,30\n"
+        );
+        assert!(
+            result
+                .to_text(&format)
+                .ends_with("http://ex.org/node/7 | http://ex.org/edge/3 | 30\n")
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Idempotency keys for retry-safe mutations.
+//!
+//! A client that retries a write after a dropped connection can't tell
+//! whether the first attempt went through. Tagging the write with a key lets
+//! the database recognize the retry: the first run with a key executes and
+//! remembers its result, and later runs with the same key return that result
+//! without executing again.
+//!
+//! This deduplicates operations, not data - two different keys inserting the
+//! same node still create two nodes. Keys are only remembered for
+//! [`Config::idempotency_window`](crate::Config::idempotency_window) and are
+//! not persisted.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use grafeo_common::utils::error::Result;
+
+use crate::database::QueryResult;
+
+/// Results of recent keyed operations, forgotten after a window.
+pub(crate) struct IdempotencyStore {
+    /// How long a key is remembered after its operation ran.
+    window: Duration,
+    /// Result of each remembered key, and when its operation ran.
+    entries: Mutex<HashMap<String, (Instant, QueryResult)>>,
+}
+
+impl IdempotencyStore {
+    /// Creates a store that remembers keys for `window`.
+    pub(crate) fn new(window: Duration) -> Self {
+        Self {
+            window,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Runs `operation` unless `key` was seen within the window, in which
+    /// case the first run's result is returned instead.
+    ///
+    /// Keyed operations run one at a time, so concurrent retries can't both
+    /// apply. Failed operations aren't remembered and can be retried.
+    pub(crate) fn run(
+        &self,
+        key: &str,
+        operation: impl FnOnce() -> Result<QueryResult>,
+    ) -> Result<QueryResult> {
+        let mut entries = self.entries.lock();
+        let now = Instant::now();
+        entries.retain(|_, (ran_at, _)| now.duration_since(*ran_at) < self.window);
+
+        if let Some((_, result)) = entries.get(key) {
+            return Ok(result.clone());
+        }
+
+        let result = operation()?;
+        entries.insert(key.to_string(), (now, result.clone()));
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn empty_result() -> QueryResult {
+        QueryResult::new(Vec::new())
+    }
+
+    #[test]
+    fn test_replay_returns_first_result() {
+        let store = IdempotencyStore::new(Duration::from_mins(1));
+        let runs = Cell::new(0);
+        let operation = || {
+            runs.set(runs.get() + 1);
+            Ok(empty_result())
+        };
+
+        store.run("a", operation).unwrap();
+        store.run("a", operation).unwrap();
+        assert_eq!(runs.get(), 1);
+
+        store.run("b", operation).unwrap();
+        assert_eq!(runs.get(), 2);
+        assert_eq!(store.entries.lock().len(), 2);
+    }
+
+    #[test]
+    fn test_failed_operation_is_not_remembered() {
+        let store = IdempotencyStore::new(Duration::from_mins(1));
+
+        let failed = store.run("a", || {
+            Err(grafeo_common::utils::error::Error::Internal(
+                "connection reset".into(),
+            ))
+        });
+        assert!(failed.is_err());
+        assert_eq!(store.entries.lock().len(), 0);
+        assert!(store.run("a", || Ok(empty_result())).is_ok());
+    }
+
+    #[test]
+    fn test_keys_expire_after_window() {
+        let store = IdempotencyStore::new(Duration::ZERO);
+        let runs = Cell::new(0);
+        let operation = || {
+            runs.set(runs.get() + 1);
+            Ok(empty_result())
+        };
+
+        store.run("a", operation).unwrap();
+        store.run("a", operation).unwrap();
+        assert_eq!(runs.get(), 2);
+    }
+}
@@ -0,0 +1,1763 @@
+//! Loading nodes and edges from CSV and JSON Lines files.
+//!
+//! [`Session::import_parallel()`](crate::Session::import_parallel) reads many
+//! files at once, spreading them across threads. Loading runs in two phases:
+//! every node file first, building an index from each node's key to its id,
+//! then every edge file, resolving endpoints through that index.
+//!
+//! The format is picked from the file extension: `.csv`, or `.json`,
+//! `.jsonl`, and `.ndjson` for JSON Lines (one object per line).
+//!
+//! | File  | Reserved fields | Everything else |
+//! |-------|-----------------|-----------------|
+//! | Nodes | `id` (key), `labels` | properties |
+//! | Edges | `source`, `target` (node keys), `type` | properties |
+//!
+//! In CSV, `labels` separates multiple labels with `;`, and values are typed
+//! by how they read: integers, floats, `true`/`false`, or else strings. Empty
+//! fields are left unset. In JSON, `labels` can be a string or an array.
+//!
+//! A property's type can be fixed instead, either in the CSV header
+//! (`zip:string`, `born:date`) or with
+//! [`ImportOptions::with_property_type()`], which also applies to JSON. A
+//! value that doesn't convert to its declared type fails its row.
+//!
+//! Node keys only link edges to nodes and are not stored; repeat the key in
+//! another column to keep it as a property.
+//!
+//! [`Session::import_nodes()`](crate::Session::import_nodes) and
+//! [`Session::import_edges()`](crate::Session::import_edges) load a single
+//! stream instead, in the same layouts. Rows are batched into
+//! [`DataChunk`]s that worker threads create in parallel. Keys of streamed
+//! nodes are kept in an index for the life of the database handle, so edges
+//! streamed in a later call can find them.
+//!
+//! Ids are handed out as rows are created, so by default they vary from run
+//! to run. [`IdAssignment::SortedKey`] numbers rows by key instead, making
+//! repeated loads reproducible.
+//!
+//! A bad row doesn't stop the load. It is skipped and reported in the
+//! [`FileReport`] of the file it came from.
+//!
+//! Each import runs as a transaction of its own: nothing it creates is seen
+//! until all of it is in the WAL, and a failed import leaves nothing
+//! behind. Input is read a line at a time, and streamed rows are created in
+//! batches as they arrive.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+
+use rayon::prelude::*;
+
+use grafeo_common::types::temporal;
+use grafeo_common::types::{EdgeId, EpochId, LogicalType, NodeId, PropertyKey, TxId, Value};
+use grafeo_common::utils::error::Result as DbResult;
+use grafeo_core::execution::parallel::{MorselScheduler, WorkerHandle, generate_morsels};
+use grafeo_core::execution::{DataChunk, ValueVector};
+use grafeo_core::index::HashIndex;
+
+use crate::catalog::Catalog;
+
+/// Node field holding the key that edges refer to.
+pub const NODE_KEY_FIELD: &str = "id";
+/// Node field holding labels.
+pub const NODE_LABELS_FIELD: &str = "labels";
+/// Edge field holding the source node's key.
+pub const EDGE_SOURCE_FIELD: &str = "source";
+/// Edge field holding the target node's key.
+pub const EDGE_TARGET_FIELD: &str = "target";
+/// Edge field holding the edge type.
+pub const EDGE_TYPE_FIELD: &str = "type";
+
+/// A row that couldn't be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Line the row starts on (1-based), or 0 for problems with the whole file.
+    pub line: usize,
+    /// What went wrong.
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.line == 0 {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "line {}: {}", self.line, self.message)
+        }
+    }
+}
+
+/// What happened to one input file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileReport {
+    /// The file that was read.
+    pub path: PathBuf,
+    /// Rows loaded as nodes or edges.
+    pub loaded: usize,
+    /// Rows that were skipped, and why.
+    pub errors: Vec<RowError>,
+}
+
+/// The outcome of a multi-file import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    /// One report per node file, in the order given.
+    pub node_files: Vec<FileReport>,
+    /// One report per edge file, in the order given.
+    pub edge_files: Vec<FileReport>,
+}
+
+impl ImportReport {
+    /// Returns the total number of nodes created.
+    #[must_use]
+    pub fn nodes_created(&self) -> usize {
+        self.node_files.iter().map(|f| f.loaded).sum()
+    }
+
+    /// Returns the total number of edges created.
+    #[must_use]
+    pub fn edges_created(&self) -> usize {
+        self.edge_files.iter().map(|f| f.loaded).sum()
+    }
+
+    /// Returns the number of rows skipped across all files.
+    #[must_use]
+    pub fn error_count(&self) -> usize {
+        self.node_files
+            .iter()
+            .chain(&self.edge_files)
+            .map(|f| f.errors.len())
+            .sum()
+    }
+
+    /// Returns whether every row of every file loaded.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.error_count() == 0
+    }
+}
+
+/// How an import numbers the nodes and edges it creates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdAssignment {
+    /// Ids follow the order rows happen to be created in, which varies with
+    /// thread scheduling. The fastest option.
+    #[default]
+    Arrival,
+    /// Nodes are numbered in order of their key, and edges in order of
+    /// `(source, target, type)`, so loading the same data into a fresh
+    /// database always yields the same ids. Rows are created on one thread.
+    SortedKey,
+}
+
+/// The layout of an import stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// Comma-separated values with a header row.
+    Csv,
+    /// One JSON object per line.
+    JsonLines,
+}
+
+impl ImportFormat {
+    /// Picks the format from a file extension: `.csv`, or `.json`, `.jsonl`,
+    /// and `.ndjson` for JSON Lines.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "json" | "jsonl" | "ndjson" => Some(Self::JsonLines),
+            _ => None,
+        }
+    }
+
+    /// Reads rows from `reader` one line at a time.
+    fn rows<R: BufRead>(self, reader: R, types: &HashMap<String, LogicalType>) -> Rows<'_, R> {
+        Rows {
+            reader,
+            format: self,
+            types,
+            text: String::new(),
+            line: 0,
+            csv: CsvRecords::default(),
+            header: None,
+            done: false,
+        }
+    }
+}
+
+/// What happened to one streamed import.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadReport {
+    /// Rows loaded as nodes or edges.
+    pub loaded: usize,
+    /// Rows that were skipped, and why.
+    pub errors: Vec<RowError>,
+}
+
+impl LoadReport {
+    /// Returns whether every row loaded.
+    #[must_use]
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+/// Options for [`Session::import_parallel_with()`](crate::Session::import_parallel_with)
+/// and the streaming imports.
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    /// How created nodes and edges get their ids.
+    pub ids: IdAssignment,
+    /// Declared property types, by property name.
+    pub types: HashMap<String, LogicalType>,
+}
+
+impl ImportOptions {
+    /// Creates the default options.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets how created nodes and edges get their ids.
+    #[must_use]
+    pub fn with_ids(mut self, ids: IdAssignment) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Reads the named property as the given type instead of inferring it.
+    ///
+    /// Supports `BOOL`, `INT64`, `FLOAT64`, `STRING`, `DATE`, `TIMESTAMP`
+    /// and `DURATION`; temporal values are read from ISO 8601 strings. A
+    /// type in a CSV header takes precedence.
+    #[must_use]
+    pub fn with_property_type(mut self, name: impl Into<String>, ty: LogicalType) -> Self {
+        self.types.insert(name.into(), ty);
+        self
+    }
+}
+
+/// A parsed row: its starting line and its fields in file order.
+type Row = (usize, Vec<(String, Value)>);
+
+struct NodeRow {
+    line: usize,
+    key: String,
+    labels: Vec<String>,
+    properties: Vec<(String, Value)>,
+}
+
+impl NodeRow {
+    /// Checks the row's labels and property keys against `schema`, if any.
+    fn check_schema(&self, schema: Option<&Catalog>) -> Result<(), String> {
+        let Some(catalog) = schema else {
+            return Ok(());
+        };
+        catalog
+            .validate_write(
+                self.labels.iter().map(String::as_str),
+                None,
+                self.properties.iter().map(|(k, _)| k.as_str()),
+            )
+            .map_err(|e| e.to_string())
+    }
+}
+
+struct EdgeRow {
+    line: usize,
+    source: String,
+    target: String,
+    edge_type: String,
+    properties: Vec<(String, Value)>,
+}
+
+impl EdgeRow {
+    /// Checks the row's type and property keys against `schema`, if any.
+    fn check_schema(&self, schema: Option<&Catalog>) -> Result<(), String> {
+        let Some(catalog) = schema else {
+            return Ok(());
+        };
+        catalog
+            .validate_write(
+                [],
+                Some(&self.edge_type),
+                self.properties.iter().map(|(k, _)| k.as_str()),
+            )
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// A node file after parsing, before any node is created.
+struct ParsedNodes {
+    report: FileReport,
+    rows: Vec<NodeRow>,
+}
+
+/// Where imported nodes and edges are written.
+pub(crate) struct ImportTarget<'a> {
+    pub(crate) store: &'a grafeo_core::graph::lpg::LpgStore,
+    /// The import's own transaction.
+    pub(crate) tx_id: TxId,
+    /// The catalog rows are checked against, in strict schema mode.
+    pub(crate) schema: Option<&'a Catalog>,
+}
+
+/// Loads node files, then edge files, each phase in parallel across files.
+pub(crate) fn import_files<P: AsRef<Path> + Sync>(
+    target: &ImportTarget<'_>,
+    node_files: &[P],
+    edge_files: &[P],
+    options: &ImportOptions,
+) -> DbResult<ImportReport> {
+    // Phase 1a: parse every node file in parallel
+    let mut parsed: Vec<ParsedNodes> = node_files
+        .par_iter()
+        .map(|path| parse_node_file(path.as_ref(), &options.types, target.schema))
+        .collect();
+
+    // Phase 1b: keys must be unique across all files; the first one wins
+    let mut seen = HashSet::new();
+    for file in &mut parsed {
+        let errors = &mut file.report.errors;
+        file.rows.retain(|row| {
+            let fresh = seen.insert(row.key.clone());
+            if !fresh {
+                errors.push(RowError {
+                    line: row.line,
+                    message: format!("duplicate node key '{}'", row.key),
+                });
+            }
+            fresh
+        });
+        errors.sort_by_key(|e| e.line);
+    }
+
+    // Phase 1c: create the nodes, tagged with the file they came from
+    let rows: Vec<Vec<NodeRow>> = parsed
+        .iter_mut()
+        .map(|file| std::mem::take(&mut file.rows))
+        .collect();
+    let created_nodes: Vec<(usize, NodeId, NodeRow)> = match options.ids {
+        IdAssignment::Arrival => rows
+            .into_par_iter()
+            .enumerate()
+            .flat_map_iter(|(file, rows)| {
+                rows.into_iter()
+                    .map(move |row| (file, create_node(target, &row), row))
+            })
+            .collect(),
+        IdAssignment::SortedKey => {
+            let mut rows: Vec<(usize, NodeRow)> = rows
+                .into_iter()
+                .enumerate()
+                .flat_map(|(file, rows)| rows.into_iter().map(move |row| (file, row)))
+                .collect();
+            rows.sort_by(|(_, a), (_, b)| a.key.cmp(&b.key));
+            rows.into_iter()
+                .map(|(file, row)| (file, create_node(target, &row), row))
+                .collect()
+        }
+    };
+
+    let mut keys: HashMap<String, NodeId> = HashMap::with_capacity(created_nodes.len());
+    for (file, id, row) in created_nodes {
+        parsed[file].report.loaded += 1;
+        keys.insert(row.key, id);
+    }
+    let mut report = ImportReport {
+        node_files: parsed.into_iter().map(|file| file.report).collect(),
+        edge_files: Vec::new(),
+    };
+
+    // Phase 2a: parse edge files in parallel, resolving endpoint keys
+    let (edge_reports, resolved): (Vec<FileReport>, Vec<Vec<ResolvedEdge>>) = edge_files
+        .par_iter()
+        .map(|path| parse_edge_file(path.as_ref(), &options.types, &keys, target.schema))
+        .unzip();
+    report.edge_files = edge_reports;
+
+    // Phase 2b: create the edges
+    let created_edges: Vec<(usize, DbResult<EdgeId>)> = match options.ids {
+        IdAssignment::Arrival => resolved
+            .into_par_iter()
+            .enumerate()
+            .flat_map_iter(|(file, edges)| {
+                edges
+                    .into_iter()
+                    .map(move |edge| (file, create_edge(target, edge)))
+            })
+            .collect(),
+        IdAssignment::SortedKey => {
+            let mut edges: Vec<(usize, ResolvedEdge)> = resolved
+                .into_iter()
+                .enumerate()
+                .flat_map(|(file, edges)| edges.into_iter().map(move |edge| (file, edge)))
+                .collect();
+            // Ties keep file order, since the sort is stable
+            edges.sort_by(|(_, a), (_, b)| {
+                (&a.row.source, &a.row.target, &a.row.edge_type).cmp(&(
+                    &b.row.source,
+                    &b.row.target,
+                    &b.row.edge_type,
+                ))
+            });
+            edges
+                .into_iter()
+                .map(|(file, edge)| (file, create_edge(target, edge)))
+                .collect()
+        }
+    };
+    for (file, edge) in created_edges {
+        edge?;
+        report.edge_files[file].loaded += 1;
+    }
+    Ok(report)
+}
+
+/// Rows per chunk handed to a worker by the streaming imports.
+const ROWS_PER_CHUNK: usize = 1024;
+
+/// Rows the streaming imports read before creating them, unless ids are
+/// assigned by key, which needs every row first.
+const ROWS_PER_BATCH: usize = 64 * ROWS_PER_CHUNK;
+
+/// Loads one stream of nodes, each given `label` on top of any in its
+/// `labels` field. Returns the keys of the nodes created, for `keys` once
+/// the import commits.
+///
+/// Keys already in `keys` fail their rows as duplicates.
+pub(crate) fn import_node_stream(
+    target: &ImportTarget<'_>,
+    keys: &HashIndex<String, NodeId>,
+    label: &str,
+    reader: impl BufRead,
+    format: ImportFormat,
+    options: &ImportOptions,
+) -> DbResult<(LoadReport, Vec<(String, NodeId)>)> {
+    let mut report = LoadReport::default();
+    let mut seen = HashSet::new();
+    let mut rows = Vec::new();
+    let mut created = Vec::new();
+    for row in format.rows(reader, &options.types) {
+        let (line, fields) = match row? {
+            Ok(row) => row,
+            Err(e) => {
+                report.errors.push(e);
+                continue;
+            }
+        };
+        let mut row = match node_row(line, fields) {
+            Ok(row) => row,
+            Err(message) => {
+                report.errors.push(RowError { line, message });
+                continue;
+            }
+        };
+        if keys.contains(&row.key) || !seen.insert(row.key.clone()) {
+            report.errors.push(RowError {
+                line,
+                message: format!("duplicate node key '{}'", row.key),
+            });
+            continue;
+        }
+        row.labels.retain(|l| l != label);
+        row.labels.insert(0, label.to_string());
+        if let Err(message) = row.check_schema(target.schema) {
+            report.errors.push(RowError { line, message });
+            continue;
+        }
+        rows.push(row);
+        if rows.len() == ROWS_PER_BATCH && options.ids == IdAssignment::Arrival {
+            created.extend(create_nodes(target, &rows, options));
+            rows.clear();
+        }
+    }
+    report.errors.sort_by_key(|e| e.line);
+    if options.ids == IdAssignment::SortedKey {
+        rows.sort_by(|a, b| a.key.cmp(&b.key));
+    }
+    created.extend(create_nodes(target, &rows, options));
+    report.loaded = created.len();
+    Ok((report, created))
+}
+
+/// Creates streamed nodes, returning each one's key and id in row order.
+fn create_nodes(
+    target: &ImportTarget<'_>,
+    rows: &[NodeRow],
+    options: &ImportOptions,
+) -> Vec<(String, NodeId)> {
+    let (names, chunks) = to_chunks(
+        &rows,
+        &[LogicalType::String, LogicalType::Any],
+        |row| {
+            let labels: Vec<Value> = row.labels.iter().map(|l| Value::from(l.as_str())).collect();
+            vec![Value::from(row.key.as_str()), Value::List(labels.into())]
+        },
+        |row| &row.properties,
+    );
+    ingest(&chunks, worker_count(options), |chunk, row| {
+        let key = chunk
+            .column(0)
+            .and_then(|c| c.get_string(row))
+            .unwrap_or_default();
+        let labels = match chunk.column(1).and_then(|c| c.get_value(row)) {
+            Some(Value::List(labels)) => labels
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect(),
+            _ => Vec::new(),
+        };
+        let row = NodeRow {
+            line: 0,
+            key: key.to_string(),
+            labels,
+            properties: chunk_properties(chunk, row, 2, &names),
+        };
+        let id = create_node(target, &row);
+        (row.key, id)
+    })
+}
+
+/// Loads one stream of edges of type `edge_type`, unless a row has its own
+/// `type` field, resolving endpoints through `keys`.
+pub(crate) fn import_edge_stream(
+    target: &ImportTarget<'_>,
+    keys: &HashIndex<String, NodeId>,
+    edge_type: &str,
+    reader: impl BufRead,
+    format: ImportFormat,
+    options: &ImportOptions,
+) -> DbResult<LoadReport> {
+    let mut report = LoadReport::default();
+    let mut edges = Vec::new();
+    for row in format.rows(reader, &options.types) {
+        let (line, fields) = match row? {
+            Ok(row) => row,
+            Err(e) => {
+                report.errors.push(e);
+                continue;
+            }
+        };
+        let row = match edge_row(line, fields, Some(edge_type))
+            .and_then(|row| row.check_schema(target.schema).map(|()| row))
+        {
+            Ok(row) => row,
+            Err(message) => {
+                report.errors.push(RowError { line, message });
+                continue;
+            }
+        };
+        // Keys outlive the nodes they name, so check the node is still there
+        let resolve = |key: &String| {
+            keys.get(key)
+                .filter(|&id| target.store.get_node(id).is_some())
+        };
+        let (Some(src), Some(dst)) = (resolve(&row.source), resolve(&row.target)) else {
+            let missing = if resolve(&row.source).is_some() {
+                &row.target
+            } else {
+                &row.source
+            };
+            report.errors.push(RowError {
+                line,
+                message: format!("no node with key '{missing}'"),
+            });
+            continue;
+        };
+        edges.push(ResolvedEdge { src, dst, row });
+        if edges.len() == ROWS_PER_BATCH && options.ids == IdAssignment::Arrival {
+            report.loaded += create_edges(target, &edges, options)?;
+            edges.clear();
+        }
+    }
+    report.errors.sort_by_key(|e| e.line);
+    if options.ids == IdAssignment::SortedKey {
+        edges.sort_by(|a, b| {
+            (&a.row.source, &a.row.target, &a.row.edge_type).cmp(&(
+                &b.row.source,
+                &b.row.target,
+                &b.row.edge_type,
+            ))
+        });
+    }
+    report.loaded += create_edges(target, &edges, options)?;
+    Ok(report)
+}
+
+/// Creates streamed edges, returning how many were created.
+fn create_edges(
+    target: &ImportTarget<'_>,
+    edges: &[ResolvedEdge],
+    options: &ImportOptions,
+) -> DbResult<usize> {
+    let (names, chunks) = to_chunks(
+        &edges,
+        &[LogicalType::Node, LogicalType::Node, LogicalType::String],
+        |edge| {
+            vec![
+                Value::Int64(edge.src.as_u64() as i64),
+                Value::Int64(edge.dst.as_u64() as i64),
+                Value::from(edge.row.edge_type.as_str()),
+            ]
+        },
+        |edge| &edge.row.properties,
+    );
+    let created_edges = ingest(&chunks, worker_count(options), |chunk, row| {
+        let column = |i: usize| chunk.column(i).expect("edge chunks have fixed columns");
+        let edge = ResolvedEdge {
+            src: column(0).get_node_id(row).unwrap_or_default(),
+            dst: column(1).get_node_id(row).unwrap_or_default(),
+            row: EdgeRow {
+                line: 0,
+                source: String::new(),
+                target: String::new(),
+                edge_type: column(2).get_string(row).unwrap_or_default().to_string(),
+                properties: chunk_properties(chunk, row, 3, &names),
+            },
+        };
+        create_edge(target, edge)
+    });
+    created_edges
+        .into_iter()
+        .try_fold(0, |count, edge| edge.map(|_| count + 1))
+}
+
+/// Lays rows out as chunks: the `fixed` columns first, then one column per
+/// property name, in the order names first appear. Returns the names too.
+fn to_chunks<R>(
+    rows: &[R],
+    fixed: &[LogicalType],
+    fixed_values: impl Fn(&R) -> Vec<Value>,
+    properties: impl Fn(&R) -> &[(String, Value)],
+) -> (Vec<String>, Vec<DataChunk>) {
+    let mut names: Vec<String> = Vec::new();
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for row in rows {
+        for (name, _) in properties(row) {
+            if !positions.contains_key(name.as_str()) {
+                positions.insert(name, names.len());
+                names.push(name.clone());
+            }
+        }
+    }
+
+    let chunks = rows
+        .chunks(ROWS_PER_CHUNK)
+        .map(|batch| {
+            let mut columns: Vec<ValueVector> = fixed
+                .iter()
+                .cloned()
+                .chain(names.iter().map(|_| LogicalType::Any))
+                .map(|ty| ValueVector::with_capacity(ty, batch.len()))
+                .collect();
+            for row in batch {
+                for (column, value) in columns.iter_mut().zip(fixed_values(row)) {
+                    column.push_value(value);
+                }
+                let mut values = vec![Value::Null; names.len()];
+                for (name, value) in properties(row) {
+                    values[positions[name.as_str()]] = value.clone();
+                }
+                for (column, value) in columns[fixed.len()..].iter_mut().zip(values) {
+                    column.push_value(value);
+                }
+            }
+            DataChunk::new(columns)
+        })
+        .collect();
+    (names, chunks)
+}
+
+/// Reads the set properties of a chunk row, whose columns from `first` on
+/// hold the properties in `names`.
+fn chunk_properties(
+    chunk: &DataChunk,
+    row: usize,
+    first: usize,
+    names: &[String],
+) -> Vec<(String, Value)> {
+    names
+        .iter()
+        .zip(&chunk.columns()[first..])
+        .filter_map(|(name, column)| match column.get_value(row) {
+            None | Some(Value::Null) => None,
+            Some(value) => Some((name.clone(), value)),
+        })
+        .collect()
+}
+
+/// Streams on one worker when ids must follow the input order.
+fn worker_count(options: &ImportOptions) -> usize {
+    match options.ids {
+        IdAssignment::Arrival => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        IdAssignment::SortedKey => 1,
+    }
+}
+
+/// Runs `create` over every row of every chunk, one chunk per morsel,
+/// returning the results in input order.
+fn ingest<T: Send>(
+    chunks: &[DataChunk],
+    workers: usize,
+    create: impl Fn(&DataChunk, usize) -> T + Sync,
+) -> Vec<T> {
+    let workers = workers.min(chunks.len());
+    if workers == 0 {
+        return Vec::new();
+    }
+
+    let scheduler = Arc::new(MorselScheduler::new(workers));
+    scheduler.submit_batch(generate_morsels(chunks.len(), 1, 0));
+    scheduler.finish_submission();
+
+    let mut done: Vec<(usize, Vec<T>)> = thread::scope(|s| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                let scheduler = Arc::clone(&scheduler);
+                let create = &create;
+                s.spawn(move || {
+                    let handle = WorkerHandle::new(scheduler);
+                    let mut done = Vec::new();
+                    while let Some(morsel) = handle.get_work() {
+                        let chunk = &chunks[morsel.start_row];
+                        let results = (0..chunk.row_count())
+                            .map(|row| create(chunk, row))
+                            .collect();
+                        done.push((morsel.start_row, results));
+                        handle.complete_morsel();
+                    }
+                    done
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("import worker panicked"))
+            .collect()
+    });
+    done.sort_by_key(|(chunk, _)| *chunk);
+    done.into_iter().flat_map(|(_, results)| results).collect()
+}
+
+/// An edge row whose endpoints were found.
+struct ResolvedEdge {
+    src: NodeId,
+    dst: NodeId,
+    row: EdgeRow,
+}
+
+// Created as of an epoch that never comes, until the commit stamps the
+// real one
+fn create_node(target: &ImportTarget<'_>, row: &NodeRow) -> NodeId {
+    let labels: Vec<&str> = row.labels.iter().map(String::as_str).collect();
+    target.store.create_node_with_props_versioned(
+        &labels,
+        row.properties
+            .iter()
+            .map(|(k, v)| (PropertyKey::from(k.as_str()), v.clone())),
+        EpochId::PENDING,
+        target.tx_id,
+    )
+}
+
+fn create_edge(target: &ImportTarget<'_>, edge: ResolvedEdge) -> DbResult<EdgeId> {
+    let ResolvedEdge { src, dst, row } = edge;
+    let id = target.store.create_edge_versioned(
+        src,
+        dst,
+        &row.edge_type,
+        EpochId::PENDING,
+        target.tx_id,
+    );
+    for (key, value) in row.properties {
+        target
+            .store
+            .set_edge_property_versioned(id, &key, value, target.tx_id)?;
+    }
+    Ok(id)
+}
+
+fn parse_node_file(
+    path: &Path,
+    types: &HashMap<String, LogicalType>,
+    schema: Option<&Catalog>,
+) -> ParsedNodes {
+    let mut report = FileReport {
+        path: path.to_path_buf(),
+        loaded: 0,
+        errors: Vec::new(),
+    };
+    let mut rows = Vec::new();
+    for (line, fields) in read_rows(path, types, &mut report.errors) {
+        match node_row(line, fields).and_then(|row| row.check_schema(schema).map(|()| row)) {
+            Ok(row) => rows.push(row),
+            Err(message) => report.errors.push(RowError { line, message }),
+        }
+    }
+    ParsedNodes { report, rows }
+}
+
+fn parse_edge_file(
+    path: &Path,
+    types: &HashMap<String, LogicalType>,
+    keys: &HashMap<String, NodeId>,
+    schema: Option<&Catalog>,
+) -> (FileReport, Vec<ResolvedEdge>) {
+    let mut report = FileReport {
+        path: path.to_path_buf(),
+        loaded: 0,
+        errors: Vec::new(),
+    };
+    let mut edges = Vec::new();
+    for (line, fields) in read_rows(path, types, &mut report.errors) {
+        let row = match edge_row(line, fields, None)
+            .and_then(|row| row.check_schema(schema).map(|()| row))
+        {
+            Ok(row) => row,
+            Err(message) => {
+                report.errors.push(RowError { line, message });
+                continue;
+            }
+        };
+        let (Some(&src), Some(&dst)) = (keys.get(&row.source), keys.get(&row.target)) else {
+            let missing = if keys.contains_key(&row.source) {
+                &row.target
+            } else {
+                &row.source
+            };
+            report.errors.push(RowError {
+                line: row.line,
+                message: format!("no node with key '{missing}'"),
+            });
+            continue;
+        };
+        edges.push(ResolvedEdge { src, dst, row });
+    }
+    (report, edges)
+}
+
+fn node_row(line: usize, fields: Vec<(String, Value)>) -> Result<NodeRow, String> {
+    let mut key = None;
+    let mut labels = Vec::new();
+    let mut properties = Vec::new();
+    for (name, value) in fields {
+        match name.as_str() {
+            NODE_KEY_FIELD => key = Some(key_text(value)?),
+            NODE_LABELS_FIELD => labels = label_list(value)?,
+            _ => properties.push((name, value)),
+        }
+    }
+    Ok(NodeRow {
+        line,
+        key: key.ok_or_else(|| format!("missing '{NODE_KEY_FIELD}'"))?,
+        labels,
+        properties,
+    })
+}
+
+/// Reads an edge row, falling back to `default_type` when it has no type field.
+fn edge_row(
+    line: usize,
+    fields: Vec<(String, Value)>,
+    default_type: Option<&str>,
+) -> Result<EdgeRow, String> {
+    let mut source = None;
+    let mut target = None;
+    let mut edge_type = None;
+    let mut properties = Vec::new();
+    for (name, value) in fields {
+        match name.as_str() {
+            EDGE_SOURCE_FIELD => source = Some(key_text(value)?),
+            EDGE_TARGET_FIELD => target = Some(key_text(value)?),
+            EDGE_TYPE_FIELD => edge_type = Some(key_text(value)?),
+            _ => properties.push((name, value)),
+        }
+    }
+    let missing = |field: &str| format!("missing '{field}'");
+    Ok(EdgeRow {
+        line,
+        source: source.ok_or_else(|| missing(EDGE_SOURCE_FIELD))?,
+        target: target.ok_or_else(|| missing(EDGE_TARGET_FIELD))?,
+        edge_type: edge_type
+            .or_else(|| default_type.map(str::to_string))
+            .ok_or_else(|| missing(EDGE_TYPE_FIELD))?,
+        properties,
+    })
+}
+
+/// Reads a key or type field, which may be written as a string or integer.
+fn key_text(value: Value) -> Result<String, String> {
+    match value {
+        Value::String(s) => Ok(s.to_string()),
+        Value::Int64(i) => Ok(i.to_string()),
+        other => Err(format!("expected a string or integer, got {other:?}")),
+    }
+}
+
+fn label_list(value: Value) -> Result<Vec<String>, String> {
+    match value {
+        Value::String(s) => Ok(s
+            .split(';')
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_string)
+            .collect()),
+        Value::List(items) => items
+            .iter()
+            .map(|item| match item {
+                Value::String(s) => Ok(s.to_string()),
+                other => Err(format!("expected a label string, got {other:?}")),
+            })
+            .collect(),
+        other => Err(format!("expected labels, got {other:?}")),
+    }
+}
+
+/// Reads a file's rows, recording problems with them in `errors`.
+fn read_rows(
+    path: &Path,
+    types: &HashMap<String, LogicalType>,
+    errors: &mut Vec<RowError>,
+) -> Vec<Row> {
+    let Some(format) = ImportFormat::from_path(path) else {
+        errors.push(RowError {
+            line: 0,
+            message: "unknown file type, expected .csv or .jsonl".to_string(),
+        });
+        return Vec::new();
+    };
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) => {
+            errors.push(RowError {
+                line: 0,
+                message: format!("cannot read file: {e}"),
+            });
+            return Vec::new();
+        }
+    };
+    let mut rows = Vec::new();
+    for row in format.rows(BufReader::new(file), types) {
+        match row {
+            Ok(Ok(row)) => rows.push(row),
+            Ok(Err(e)) => errors.push(e),
+            Err(e) => {
+                errors.push(RowError {
+                    line: 0,
+                    message: format!("cannot read file: {e}"),
+                });
+                break;
+            }
+        }
+    }
+    rows
+}
+
+/// Rows read from a stream one line at a time.
+///
+/// Yields an I/O error if the stream can't be read, which ends it, and a
+/// [`RowError`] for each row that can't be parsed.
+struct Rows<'a, R> {
+    reader: R,
+    format: ImportFormat,
+    types: &'a HashMap<String, LogicalType>,
+    /// The line being read.
+    text: String,
+    /// Number of the line being read (1-based).
+    line: usize,
+    csv: CsvRecords,
+    /// CSV column names and their declared types, once the header is read.
+    header: Option<Vec<(String, Option<LogicalType>)>>,
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for Rows<'_, R> {
+    type Item = io::Result<Result<Row, RowError>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.text.clear();
+            match self.reader.read_line(&mut self.text) {
+                Ok(0) => {
+                    self.done = true;
+                    let record = std::mem::take(&mut self.csv).finish()?;
+                    return self.csv_row(record).map(Ok);
+                }
+                Ok(_) => self.line += 1,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+            let row = match self.format {
+                ImportFormat::Csv => match self.csv.push_line(self.line, &self.text) {
+                    Some(record) => self.csv_row(Ok(record)),
+                    None => None,
+                },
+                ImportFormat::JsonLines => json_row(self.line, &self.text, self.types),
+            };
+            if let Some(row) = row {
+                return Some(Ok(row));
+            }
+        }
+        None
+    }
+}
+
+impl<R> Rows<'_, R> {
+    /// Reads a CSV record as a row, or as the header if it's the first.
+    fn csv_row(
+        &mut self,
+        record: Result<(usize, Vec<String>), RowError>,
+    ) -> Option<Result<Row, RowError>> {
+        let (line, fields) = match record {
+            Ok(record) => record,
+            Err(e) => return Some(Err(e)),
+        };
+        let Some(header) = &self.header else {
+            self.header = Some(
+                fields
+                    .iter()
+                    .map(|column| {
+                        let (name, ty) = typed_column(column);
+                        let ty = ty.or_else(|| self.types.get(&name).cloned());
+                        (name, ty)
+                    })
+                    .collect(),
+            );
+            return None;
+        };
+        if fields.len() != header.len() {
+            return Some(Err(RowError {
+                line,
+                message: format!("expected {} fields, found {}", header.len(), fields.len()),
+            }));
+        }
+        let fields = header
+            .iter()
+            .zip(fields)
+            .filter(|(_, field)| !field.is_empty())
+            .map(|((name, ty), field)| {
+                let value = match ty {
+                    Some(ty) => coerce(name, Value::from(field), ty)?,
+                    None => infer_csv_value(&field),
+                };
+                Ok((name.clone(), value))
+            })
+            .collect::<Result<_, String>>();
+        Some(
+            fields
+                .map(|fields| (line, fields))
+                .map_err(|message| RowError { line, message }),
+        )
+    }
+}
+
+/// Splits a CSV header like `born:date` into the property name and its
+/// type. Suffixes that aren't type names stay part of the name.
+fn typed_column(column: &str) -> (String, Option<LogicalType>) {
+    if let Some((name, suffix)) = column.rsplit_once(':') {
+        let ty = match suffix.to_ascii_lowercase().as_str() {
+            "bool" | "boolean" => Some(LogicalType::Bool),
+            "int" | "integer" | "long" | "int64" => Some(LogicalType::Int64),
+            "float" | "double" | "float64" => Some(LogicalType::Float64),
+            "string" | "str" => Some(LogicalType::String),
+            "date" => Some(LogicalType::Date),
+            "datetime" | "timestamp" => Some(LogicalType::Timestamp),
+            "duration" => Some(LogicalType::Duration),
+            _ => None,
+        };
+        if ty.is_some() {
+            return (name.to_string(), ty);
+        }
+    }
+    (column.to_string(), None)
+}
+
+/// Converts the value of field `name` to its declared type.
+fn coerce(name: &str, value: Value, ty: &LogicalType) -> Result<Value, String> {
+    let converted = match (ty, &value) {
+        (LogicalType::Bool, Value::Bool(_))
+        | (LogicalType::Int64, Value::Int64(_))
+        | (LogicalType::Float64, Value::Float64(_))
+        | (LogicalType::String, Value::String(_))
+        | (LogicalType::Date, Value::Date(_))
+        | (LogicalType::Timestamp, Value::Timestamp(_))
+        | (LogicalType::Duration, Value::Duration(_)) => return Ok(value),
+        (LogicalType::Float64, Value::Int64(i)) => Some(Value::Float64(*i as f64)),
+        (LogicalType::String, Value::Int64(i)) => Some(Value::from(i.to_string())),
+        (LogicalType::String, Value::Float64(f)) => Some(Value::from(f.to_string())),
+        (LogicalType::String, Value::Bool(b)) => Some(Value::from(b.to_string())),
+        (_, Value::String(s)) => {
+            let text = s.trim();
+            match ty {
+                LogicalType::Bool if text.eq_ignore_ascii_case("true") => Some(Value::Bool(true)),
+                LogicalType::Bool if text.eq_ignore_ascii_case("false") => Some(Value::Bool(false)),
+                LogicalType::Int64 => text.parse().ok().map(Value::Int64),
+                LogicalType::Float64 => text.parse().ok().map(Value::Float64),
+                LogicalType::Date => temporal::parse_date(text).map(Value::Date),
+                LogicalType::Timestamp => temporal::parse_timestamp(text).map(Value::Timestamp),
+                LogicalType::Duration => temporal::parse_duration(text).map(Value::Duration),
+                _ => None,
+            }
+        }
+        _ => None,
+    };
+    converted.ok_or_else(|| format!("field '{name}': cannot read {value:?} as {ty}"))
+}
+
+/// Splits CSV input into records of raw fields, honouring quoted fields
+/// that contain commas, doubled quotes, or line breaks. Input is fed in one
+/// line at a time.
+#[derive(Default)]
+struct CsvRecords {
+    fields: Vec<String>,
+    field: String,
+    in_quotes: bool,
+    /// Line the record being read starts on.
+    record_line: usize,
+}
+
+impl CsvRecords {
+    /// Reads line number `line`, with its line break, returning the record
+    /// it completes, if any.
+    fn push_line(&mut self, line: usize, text: &str) -> Option<(usize, Vec<String>)> {
+        if !self.in_quotes && self.fields.is_empty() && self.field.is_empty() {
+            self.record_line = line;
+        }
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if self.in_quotes => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        self.field.push('"');
+                    } else {
+                        self.in_quotes = false;
+                    }
+                }
+                '"' if self.field.is_empty() => self.in_quotes = true,
+                ',' if !self.in_quotes => self.fields.push(std::mem::take(&mut self.field)),
+                '\r' if !self.in_quotes => {}
+                '\n' if !self.in_quotes => {
+                    self.fields.push(std::mem::take(&mut self.field));
+                    let fields = std::mem::take(&mut self.fields);
+                    // Blank lines hold no record
+                    if !(fields.len() == 1 && fields[0].is_empty()) {
+                        return Some((self.record_line, fields));
+                    }
+                }
+                c => self.field.push(c),
+            }
+        }
+        None
+    }
+
+    /// Ends the input, returning the last record if it has no line break.
+    fn finish(mut self) -> Option<Result<(usize, Vec<String>), RowError>> {
+        if self.in_quotes {
+            Some(Err(RowError {
+                line: self.record_line,
+                message: "unterminated quoted field".to_string(),
+            }))
+        } else if !self.field.is_empty() || !self.fields.is_empty() {
+            self.fields.push(self.field);
+            Some(Ok((self.record_line, self.fields)))
+        } else {
+            None
+        }
+    }
+}
+
+/// Types a CSV field by how it reads.
+fn infer_csv_value(field: &str) -> Value {
+    if let Ok(i) = field.parse::<i64>() {
+        return Value::Int64(i);
+    }
+    let numeric = field
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'));
+    if numeric {
+        if let Ok(f) = field.parse::<f64>() {
+            return Value::Float64(f);
+        }
+    }
+    match field {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => Value::from(field),
+    }
+}
+
+/// Reads line number `line` of JSON Lines input as a row, or `None` if
+/// it's blank.
+fn json_row(
+    line: usize,
+    text: &str,
+    types: &HashMap<String, LogicalType>,
+) -> Option<Result<Row, RowError>> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    let error = |message| Some(Err(RowError { line, message }));
+    let object = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(serde_json::Value::Object(object)) => object,
+        Ok(_) => return error("expected a JSON object".to_string()),
+        Err(e) => return error(format!("invalid JSON: {e}")),
+    };
+    let fields = object
+        .into_iter()
+        .filter(|(_, v)| !v.is_null())
+        .map(|(k, v)| {
+            let value = json_to_value(v);
+            let value = match types.get(&k) {
+                Some(ty) => coerce(&k, value, ty)?,
+                None => value,
+            };
+            Ok((k, value))
+        })
+        .collect::<Result<_, String>>();
+    match fields {
+        Ok(fields) => Some(Ok((line, fields))),
+        Err(message) => error(message),
+    }
+}
+
+pub(crate) fn json_to_value(json: serde_json::Value) -> Value {
+    match json {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Bool(b),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Int64(i),
+            None => Value::Float64(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        serde_json::Value::String(s) => Value::from(s),
+        serde_json::Value::Array(items) => Value::List(
+            items
+                .into_iter()
+                .map(json_to_value)
+                .collect::<Vec<_>>()
+                .into(),
+        ),
+        serde_json::Value::Object(object) => Value::Map(
+            object
+                .into_iter()
+                .map(|(k, v)| (PropertyKey::from(k), json_to_value(v)))
+                .collect::<BTreeMap<_, _>>()
+                .into(),
+        ),
+    }
+}
+
+#[cfg(all(test, feature = "gql"))]
+mod tests {
+    use std::fmt::Write as _;
+    use std::path::PathBuf;
+
+    use grafeo_common::types::Value;
+
+    use super::*;
+    use crate::GrafeoDB;
+
+    fn write(dir: &tempfile::TempDir, name: &str, contents: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Reads the whole graph back in a canonical order.
+    fn snapshot(db: &GrafeoDB) -> (Vec<Vec<Value>>, Vec<Vec<Value>>) {
+        let session = db.session();
+        let mut nodes = session
+            .execute("MATCH (n) RETURN labels(n), n.name, n.age, n.score")
+            .unwrap()
+            .rows;
+        // Label order follows interning order, which parallel loads don't fix
+        for row in &mut nodes {
+            if let Value::List(labels) = &row[0] {
+                let mut labels = labels.to_vec();
+                labels.sort_by_key(|l| format!("{l:?}"));
+                row[0] = Value::List(labels.into());
+            }
+        }
+        nodes.sort_by_key(|row| format!("{row:?}"));
+        let mut edges = session
+            .execute("MATCH (a)-[e]->(b) RETURN a.name, type(e), b.name, e.since")
+            .unwrap()
+            .rows;
+        edges.sort_by_key(|row| format!("{row:?}"));
+        (nodes, edges)
+    }
+
+    #[test]
+    fn test_parallel_load_matches_serial_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let people = [
+            ("p1", "Alice", 30),
+            ("p2", "Bob", 25),
+            ("p3", "Carol", 35),
+            ("p4", "Dave", 41),
+        ];
+        let knows = [("p1", "p2", 2010), ("p2", "p3", 2015), ("p3", "p4", 2020)];
+
+        // Serial: one CSV of nodes, one CSV of edges
+        let mut all_nodes = String::from("id,labels,name,age,score\n");
+        for (key, name, age) in people {
+            writeln!(all_nodes, "{key},Person,{name},{age},1.5").unwrap();
+        }
+        all_nodes.push_str("c1,Company;Org,Acme,,\n");
+        let mut all_edges = String::from("source,target,type,since\n");
+        for (src, dst, since) in knows {
+            writeln!(all_edges, "{src},{dst},KNOWS,{since}").unwrap();
+        }
+        all_edges.push_str("p4,c1,WORKS_AT,\n");
+
+        let serial = GrafeoDB::new_in_memory();
+        let report = serial
+            .session()
+            .import_parallel(
+                &[write(&dir, "nodes.csv", &all_nodes)],
+                &[write(&dir, "edges.csv", &all_edges)],
+            )
+            .unwrap();
+        assert!(report.is_clean(), "{report:?}");
+        assert_eq!(report.nodes_created(), 5);
+        assert_eq!(report.edges_created(), 4);
+
+        // Parallel: the same graph split across CSV and JSON Lines files
+        let node_files = [
+            write(
+                &dir,
+                "people_a.csv",
+                "id,labels,name,age,score\np1,Person,Alice,30,1.5\np2,Person,Bob,25,1.5\n",
+            ),
+            write(
+                &dir,
+                "people_b.jsonl",
+                concat!(
+                    r#"{"id": "p3", "labels": ["Person"], "name": "Carol", "age": 35, "score": 1.5}"#,
+                    "\n",
+                    r#"{"id": "p4", "labels": "Person", "name": "Dave", "age": 41, "score": 1.5}"#,
+                    "\n",
+                ),
+            ),
+            write(
+                &dir,
+                "companies.jsonl",
+                r#"{"id": "c1", "labels": ["Company", "Org"], "name": "Acme", "age": null}"#,
+            ),
+        ];
+        let edge_files = [
+            write(
+                &dir,
+                "knows.csv",
+                "source,target,type,since\np1,p2,KNOWS,2010\np2,p3,KNOWS,2015\n",
+            ),
+            write(
+                &dir,
+                "more.jsonl",
+                concat!(
+                    r#"{"source": "p3", "target": "p4", "type": "KNOWS", "since": 2020}"#,
+                    "\n",
+                    r#"{"source": "p4", "target": "c1", "type": "WORKS_AT"}"#,
+                ),
+            ),
+        ];
+
+        let parallel = GrafeoDB::new_in_memory();
+        let report = parallel
+            .session()
+            .import_parallel(&node_files, &edge_files)
+            .unwrap();
+        assert!(report.is_clean(), "{report:?}");
+        assert_eq!(report.nodes_created(), 5);
+        assert_eq!(report.edges_created(), 4);
+
+        assert_eq!(snapshot(&parallel), snapshot(&serial));
+    }
+
+    #[test]
+    fn test_import_reports_errors_per_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let nodes_a = write(&dir, "a.csv", "id,name\n1,Alice\n2,\"Bob, Jr.\"\n3\n");
+        let nodes_b = write(
+            &dir,
+            "b.jsonl",
+            "{\"id\": 2, \"name\": \"Dup\"}\nnot json\n",
+        );
+        let edges = write(
+            &dir,
+            "edges.csv",
+            "source,target,type\n1,2,KNOWS\n1,9,KNOWS\n2,1,\n",
+        );
+        let unknown = write(&dir, "edges.txt", "");
+
+        let db = GrafeoDB::new_in_memory();
+        let report = db
+            .session()
+            .import_parallel(&[nodes_a, nodes_b], &[edges, unknown])
+            .unwrap();
+
+        assert_eq!(report.nodes_created(), 2);
+        assert_eq!(report.edges_created(), 1);
+        assert_eq!(report.error_count(), 6);
+
+        let lines = |file: &FileReport| file.errors.iter().map(|e| e.line).collect::<Vec<_>>();
+        assert_eq!(lines(&report.node_files[0]), vec![4]);
+        assert_eq!(lines(&report.node_files[1]), vec![1, 2]);
+        assert!(report.node_files[1].errors[0].message.contains("duplicate"));
+        assert_eq!(lines(&report.edge_files[0]), vec![3, 4]);
+        assert!(report.edge_files[0].errors[0].message.contains("'9'"));
+        assert_eq!(lines(&report.edge_files[1]), vec![0]);
+
+        let result = db
+            .session()
+            .execute("MATCH (a)-[:KNOWS]->(b) RETURN b.name")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::from("Bob, Jr.")]]);
+    }
+
+    #[test]
+    fn test_sorted_key_ids_are_reproducible() {
+        let dir = tempfile::tempdir().unwrap();
+        let node_files: Vec<PathBuf> = (0..4)
+            .map(|f| {
+                let mut csv = String::from("id,name\n");
+                for i in 0..50 {
+                    let key = f * 50 + i;
+                    writeln!(csv, "n{key:03},node{key}").unwrap();
+                }
+                write(&dir, &format!("nodes{f}.csv"), &csv)
+            })
+            .collect();
+        let edge_files: Vec<PathBuf> = (0..4)
+            .map(|f| {
+                let mut csv = String::from("source,target,type\n");
+                for i in 0..50 {
+                    let key = f * 50 + i;
+                    writeln!(csv, "n{key:03},n{:03},NEXT", (key + 1) % 200).unwrap();
+                }
+                write(&dir, &format!("edges{f}.csv"), &csv)
+            })
+            .collect();
+
+        let options = ImportOptions::new().with_ids(IdAssignment::SortedKey);
+        let load = || {
+            let db = GrafeoDB::new_in_memory();
+            let report = db
+                .session()
+                .import_parallel_with(&node_files, &edge_files, &options)
+                .unwrap();
+            assert!(report.is_clean(), "{report:?}");
+            let mut nodes: Vec<(NodeId, Value)> = db
+                .store()
+                .all_nodes()
+                .map(|n| (n.id, n.get_property("name").cloned().unwrap()))
+                .collect();
+            nodes.sort_by_key(|(id, _)| *id);
+            let mut edges: Vec<(EdgeId, NodeId, NodeId)> = db
+                .store()
+                .all_edges()
+                .map(|e| (e.id, e.src, e.dst))
+                .collect();
+            edges.sort_by_key(|(id, _, _)| *id);
+            (nodes, edges)
+        };
+
+        let first = load();
+        assert_eq!(first.0.len(), 200);
+        assert_eq!(first.1.len(), 200);
+        // Keys sort in the same order as the names they were generated with
+        assert_eq!(first.0[0].1, Value::from("node0"));
+        assert_eq!(first.0[199].1, Value::from("node199"));
+        for _ in 0..3 {
+            assert_eq!(load(), first);
+        }
+    }
+
+    #[test]
+    fn test_stream_import_counts_and_values() {
+        let mut nodes = String::from("id,name,age:int,zip:string,score\n");
+        for i in 0..300 {
+            writeln!(nodes, "u{i},user{i},{},{:05},{}.5", 20 + i % 50, i, i % 10).unwrap();
+        }
+        nodes.push_str("u300,user300,forty,00300,1.0\n");
+        nodes.push_str("u301,user301\n");
+        nodes.push_str("u0,again,1,00000,0.0\n");
+
+        let mut edges = String::new();
+        for i in 0..300 {
+            writeln!(
+                edges,
+                r#"{{"source": "u{i}", "target": "u{}", "weight": {i}}}"#,
+                (i + 1) % 300
+            )
+            .unwrap();
+            writeln!(
+                edges,
+                r#"{{"source": "u{i}", "target": "u{}", "type": "LIKES"}}"#,
+                (i + 7) % 300
+            )
+            .unwrap();
+        }
+        edges.push_str("{\"source\": \"u1\", \"target\": \"nobody\"}\n");
+        edges.push_str("{broken\n");
+
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        let report = session
+            .import_nodes("User", nodes.as_bytes(), ImportFormat::Csv)
+            .unwrap();
+        assert_eq!(report.loaded, 300);
+        let lines: Vec<usize> = report.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![302, 303, 304]);
+        assert!(report.errors[0].message.contains("'age'"), "{report:?}");
+        assert!(report.errors[2].message.contains("duplicate"), "{report:?}");
+
+        let report = session
+            .import_edges("FOLLOWS", edges.as_bytes(), ImportFormat::JsonLines)
+            .unwrap();
+        assert_eq!(report.loaded, 600);
+        let lines: Vec<usize> = report.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![601, 602]);
+        assert!(report.errors[0].message.contains("'nobody'"));
+
+        assert_eq!(db.node_count(), 300);
+        assert_eq!(db.edge_count(), 600);
+        let count = |query: &str| session.execute(query).unwrap().rows[0][0].clone();
+        assert_eq!(count("MATCH (u:User) RETURN count(u)"), Value::Int64(300));
+        assert_eq!(
+            count("MATCH ()-[e:FOLLOWS]->() RETURN count(e)"),
+            Value::Int64(300)
+        );
+        assert_eq!(
+            count("MATCH ()-[e:LIKES]->() RETURN count(e)"),
+            Value::Int64(300)
+        );
+
+        let result = session
+            .execute(
+                "MATCH (a:User {name: 'user42'})-[e:FOLLOWS]->(b) \
+                 RETURN a.age, a.zip, a.score, e.weight, b.name",
+            )
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                Value::Int64(62),
+                Value::from("00042"),
+                Value::Float64(2.5),
+                Value::Int64(42),
+                Value::from("user43"),
+            ]]
+        );
+        let result = session
+            .execute("MATCH (a:User {name: 'user299'})-[:LIKES]->(b) RETURN b.name")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::from("user6")]]);
+    }
+
+    #[test]
+    fn test_stream_import_declared_types_and_later_keys() {
+        let options = ImportOptions::new()
+            .with_property_type("born", LogicalType::Date)
+            .with_property_type("rank", LogicalType::Float64);
+        let people = concat!(
+            r#"{"id": 1, "labels": ["Admin"], "born": "1990-05-17", "rank": 3}"#,
+            "\n",
+            r#"{"id": 2, "born": "yesterday"}"#,
+            "\n",
+        );
+
+        let db = GrafeoDB::new_in_memory();
+        let report = db
+            .session()
+            .import_nodes_with(
+                "Person",
+                people.as_bytes(),
+                ImportFormat::JsonLines,
+                &options,
+            )
+            .unwrap();
+        assert_eq!(report.loaded, 1);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].message.contains("DATE"), "{report:?}");
+
+        let node = db.store().all_nodes().next().unwrap();
+        assert_eq!(
+            node.get_property("born"),
+            Some(&Value::Date(temporal::parse_date("1990-05-17").unwrap()))
+        );
+        assert_eq!(node.get_property("rank"), Some(&Value::Float64(3.0)));
+        assert_eq!(node.labels.len(), 2);
+
+        // Keys are shared by every session of the database
+        let report = db
+            .session()
+            .import_nodes("Person", "id\n1\n3\n".as_bytes(), ImportFormat::Csv)
+            .unwrap();
+        assert_eq!(report.loaded, 1);
+        assert!(report.errors[0].message.contains("duplicate"));
+        let report = db
+            .session()
+            .import_edges(
+                "KNOWS",
+                "source,target\n1,3\n".as_bytes(),
+                ImportFormat::Csv,
+            )
+            .unwrap();
+        assert!(report.is_clean(), "{report:?}");
+        assert_eq!(db.edge_count(), 1);
+    }
+
+    /// A stream that fails once its data runs out.
+    struct Broken;
+
+    impl io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("connection reset"))
+        }
+    }
+
+    #[test]
+    fn test_failed_stream_import_loads_nothing() {
+        use std::io::Read as _;
+
+        let mut nodes = String::from("id,name\n");
+        for i in 0..ROWS_PER_BATCH + 10 {
+            writeln!(nodes, "u{i},user{i}").unwrap();
+        }
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        let mut changes = session.subscribe();
+
+        // A full batch is created before the stream fails, and then undone
+        let result =
+            session.import_nodes("User", nodes.as_bytes().chain(Broken), ImportFormat::Csv);
+        assert!(result.is_err());
+        assert_eq!(db.node_count(), 0);
+        assert!(changes.try_next().is_none());
+
+        // Keys of the failed import aren't taken
+        let report = session
+            .import_nodes("User", "id\nu0\n".as_bytes(), ImportFormat::Csv)
+            .unwrap();
+        assert!(report.is_clean(), "{report:?}");
+        assert_eq!(db.node_count(), 1);
+    }
+
+    #[test]
+    fn test_import_recovered_without_close() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        {
+            let db = GrafeoDB::open(&path).unwrap();
+            let session = db.session();
+            session
+                .import_nodes(
+                    "Person",
+                    "id,name\na,Alice\nb,Bob\n".as_bytes(),
+                    ImportFormat::Csv,
+                )
+                .unwrap();
+            session
+                .import_edges(
+                    "KNOWS",
+                    "source,target,since\na,b,2020\n".as_bytes(),
+                    ImportFormat::Csv,
+                )
+                .unwrap();
+            std::mem::forget(db);
+        }
+
+        let db = GrafeoDB::open(&path).unwrap();
+        let result = db
+            .session()
+            .execute("MATCH (a:Person)-[e:KNOWS]->(b) RETURN a.name, e.since, b.name")
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                Value::from("Alice"),
+                Value::Int64(2020),
+                Value::from("Bob")
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_strict_schema_rejects_undeclared_rows() {
+        let db = GrafeoDB::with_config(crate::Config::in_memory().with_strict_schema()).unwrap();
+        db.declare_label("Person");
+        db.declare_property_key("name");
+        db.declare_edge_type("KNOWS");
+        let session = db.session();
+
+        let dir = tempfile::tempdir().unwrap();
+        let nodes = write(&dir, "nodes.csv", "id,labels\na,Person\nb,Persn\n");
+        let edges = write(
+            &dir,
+            "edges.csv",
+            "source,target,type\na,a,KNOWS\na,a,LIKES\n",
+        );
+        let report = session.import_parallel(&[nodes], &[edges]).unwrap();
+        assert_eq!(report.node_files[0].loaded, 1);
+        assert_eq!(report.node_files[0].errors[0].line, 3);
+        assert!(report.node_files[0].errors[0].message.contains("Persn"));
+        assert_eq!(report.edge_files[0].loaded, 1);
+        assert!(report.edge_files[0].errors[0].message.contains("LIKES"));
+
+        let report = session
+            .import_nodes(
+                "Person",
+                "id,name,labels\nc,Carol,Person\nd,Dave,Persn\n".as_bytes(),
+                ImportFormat::Csv,
+            )
+            .unwrap();
+        assert_eq!(report.loaded, 1);
+        assert!(report.errors[0].message.contains("Persn"), "{report:?}");
+
+        assert_eq!(db.node_count(), 2);
+        assert_eq!(db.edge_count(), 1);
+    }
+
+    #[test]
+    fn test_typed_csv_header() {
+        assert_eq!(
+            typed_column("age:int"),
+            ("age".to_string(), Some(LogicalType::Int64))
+        );
+        assert_eq!(typed_column("at:DateTime").1, Some(LogicalType::Timestamp));
+        assert_eq!(
+            typed_column("time:of:day"),
+            ("time:of:day".to_string(), None)
+        );
+        assert_eq!(
+            coerce("n", Value::Int64(7), &LogicalType::String),
+            Ok(Value::from("7"))
+        );
+        assert!(coerce("n", Value::Float64(1.5), &LogicalType::Int64).is_err());
+    }
+
+    #[test]
+    fn test_csv_value_inference() {
+        assert_eq!(infer_csv_value("42"), Value::Int64(42));
+        assert_eq!(infer_csv_value("-1.5"), Value::Float64(-1.5));
+        assert_eq!(infer_csv_value("true"), Value::Bool(true));
+        assert_eq!(infer_csv_value("inf"), Value::from("inf"));
+        assert_eq!(infer_csv_value("NaN"), Value::from("NaN"));
+    }
+}
@@ -17,9 +17,18 @@
 //! - [`admin`] - Admin API types for inspection, backup, and maintenance
 
 pub mod admin;
+pub mod algorithms;
+pub mod builder;
 pub mod catalog;
+mod changefeed;
 pub mod config;
 pub mod database;
+pub mod export;
+pub mod format;
+mod idempotency;
+pub mod import;
+pub mod prepared;
+pub mod projection;
 pub mod query;
 pub mod session;
 pub mod transaction;
@@ -29,7 +38,22 @@ pub use admin::{
     IndexInfo, LpgSchemaInfo, RdfSchemaInfo, SchemaInfo, ValidationError, ValidationResult,
     ValidationWarning, WalStatus,
 };
-pub use catalog::{Catalog, CatalogError, IndexDefinition, IndexType};
-pub use config::Config;
-pub use database::GrafeoDB;
-pub use session::Session;
+pub use algorithms::{ComponentsConfig, PageRankConfig, Path, PathOpts};
+pub use builder::GrafeoDBBuilder;
+pub use catalog::{
+    Catalog, CatalogError, EdgeMultiplicity, IndexDefinition, IndexType, PropertyNormalizer,
+};
+pub use changefeed::{Change, ChangeEvent, ChangeStream};
+pub use config::{Config, DuplicateKeyPolicy, QueryLimits, ResultMode, SchemaMode};
+pub use database::{GrafeoDB, QueryStats, ResultSizeEstimate};
+pub use export::ExportFormat;
+pub use format::{FloatFormat, IdFormat, ResultFormat, ResultShape};
+pub use grafeo_core::execution::{CancellationToken, OperatorStats};
+pub use import::{
+    FileReport, IdAssignment, ImportFormat, ImportOptions, ImportReport, LoadReport, RowError,
+};
+pub use prepared::PreparedQuery;
+pub use projection::GraphProjection;
+pub use query::{ExplainNode, PlanExplanation, QueryOutput, ResultStream};
+pub use session::{QueryOptions, Session};
+pub use transaction::IsolationLevel;
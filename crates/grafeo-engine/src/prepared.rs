@@ -0,0 +1,224 @@
+//! Queries compiled once and executed many times.
+//!
+//! [`Session::prepare()`] parses, validates, and optimizes a query up front.
+//! Each [`PreparedQuery::execute()`] then only substitutes parameters and
+//! runs the plan, skipping the parser and optimizer.
+
+use parking_lot::Mutex;
+
+use grafeo_common::utils::error::Result;
+
+use crate::database::QueryResult;
+use crate::query::binder::Binder;
+use crate::query::plan::LogicalPlan;
+use crate::query::processor::{QueryLanguage, QueryParams, substitute_params, translate_lpg};
+use crate::session::Session;
+
+/// A query that has been parsed and optimized, ready to run with different
+/// parameters.
+///
+/// The optimized plan keeps `$name` parameters unresolved, so one prepared
+/// query serves every binding. If the catalog changes after preparing (a new
+/// index, constraint, or declared name), the query is compiled again on its
+/// next execution.
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use grafeo_common::types::Value;
+/// use grafeo_engine::GrafeoDB;
+///
+/// let db = GrafeoDB::new_in_memory();
+/// let session = db.session();
+/// session.execute("INSERT (:Person {name: 'Alice', age: 30})")?;
+/// session.execute("INSERT (:Person {name: 'Bob', age: 25})")?;
+///
+/// let older = session.prepare("MATCH (p:Person) WHERE p.age > $min RETURN p.name")?;
+/// let params = HashMap::from([("min".to_string(), Value::Int64(28))]);
+/// assert_eq!(older.execute(params)?.row_count(), 1);
+/// # Ok::<(), grafeo_common::utils::error::Error>(())
+/// ```
+pub struct PreparedQuery<'a> {
+    /// The session the query runs in.
+    session: &'a Session,
+    /// The original query text, kept for re-compiling.
+    query: String,
+    /// The language the query is written in.
+    language: QueryLanguage,
+    /// The optimized plan and the catalog version it was compiled against.
+    compiled: Mutex<(LogicalPlan, u64)>,
+}
+
+impl<'a> PreparedQuery<'a> {
+    /// Compiles a query for later execution in `session`.
+    pub(crate) fn new(session: &'a Session, query: &str, language: QueryLanguage) -> Result<Self> {
+        let version = session.catalog_version();
+        let plan = compile(query, language, session)?;
+        Ok(Self {
+            session,
+            query: query.to_string(),
+            language,
+            compiled: Mutex::new((plan, version)),
+        })
+    }
+
+    /// Returns the query text this was prepared from.
+    #[must_use]
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    /// Returns the language the query is written in.
+    #[must_use]
+    pub fn language(&self) -> QueryLanguage {
+        self.language
+    }
+
+    /// Runs the query with the given parameter bindings.
+    ///
+    /// Every `$name` the query references must be bound.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a parameter is missing, if re-compiling after a
+    /// catalog change fails, or if execution fails.
+    pub fn execute(&self, params: QueryParams) -> Result<QueryResult> {
+        let mut plan = {
+            let mut compiled = self.compiled.lock();
+            let version = self.session.catalog_version();
+            if compiled.1 != version {
+                *compiled = (compile(&self.query, self.language, self.session)?, version);
+            }
+            compiled.0.clone()
+        };
+        substitute_params(&mut plan, &params)?;
+        self.session.execute_plan(&plan)
+    }
+}
+
+/// Translates, validates, and optimizes a query, leaving parameters unbound.
+fn compile(query: &str, language: QueryLanguage, session: &Session) -> Result<LogicalPlan> {
+    let logical_plan = translate_lpg(query, language, session.query_limits())?;
+
+    let mut binder = Binder::new();
+    let _binding_context = binder.bind(&logical_plan)?;
+
+    session.optimizer().optimize(logical_plan)
+}
+
+#[cfg(all(test, feature = "gql"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use grafeo_common::types::Value;
+
+    use crate::GrafeoDB;
+
+    fn params(pairs: &[(&str, Value)]) -> HashMap<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_prepared_query_runs_with_many_bindings() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        for (name, age) in [("Alice", 30), ("Bob", 25), ("Carol", 35)] {
+            session
+                .execute(&format!("INSERT (:Person {{name: '{name}', age: {age}}})"))
+                .unwrap();
+        }
+
+        let prepared = session
+            .prepare("MATCH (p:Person) WHERE p.age > $min RETURN p.name")
+            .unwrap();
+        for (min, expected) in [(20, 3), (26, 2), (31, 1), (40, 0)] {
+            let result = prepared
+                .execute(params(&[("min", Value::Int64(min))]))
+                .unwrap();
+            assert_eq!(result.row_count(), expected, "min = {min}");
+        }
+    }
+
+    #[test]
+    fn test_prepared_insert_reused() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        let insert = session
+            .prepare("INSERT (:Person {name: $name, age: $age})")
+            .unwrap();
+        for (name, age) in [("Alice", 30), ("Bob", 25)] {
+            insert
+                .execute(params(&[
+                    ("name", Value::from(name)),
+                    ("age", Value::Int64(age)),
+                ]))
+                .unwrap();
+        }
+
+        let result = session
+            .execute("MATCH (p:Person) WHERE p.age = 25 RETURN p.name")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::from("Bob")]]);
+    }
+
+    #[test]
+    fn test_prepared_query_missing_parameter() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        let prepared = session
+            .prepare("MATCH (p:Person) WHERE p.age > $min RETURN p.name")
+            .unwrap();
+        assert!(prepared.execute(HashMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_prepared_query_recompiles_after_catalog_change() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        session
+            .execute("INSERT (:Person {name: 'Alice', age: 30})")
+            .unwrap();
+
+        let prepared = session
+            .prepare("MATCH (p:Person) WHERE p.age > $min RETURN p.name")
+            .unwrap();
+        let compiled_at = prepared.compiled.lock().1;
+
+        db.declare_label("Employee");
+        assert_ne!(db.catalog().version(), compiled_at);
+
+        let result = prepared
+            .execute(params(&[("min", Value::Int64(20))]))
+            .unwrap();
+        assert_eq!(result.row_count(), 1);
+        assert_eq!(prepared.compiled.lock().1, db.catalog().version());
+    }
+
+    #[cfg(feature = "cypher")]
+    #[test]
+    fn test_prepared_cypher_query() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        session
+            .execute_cypher("CREATE (:Person {name: 'Alice', age: 30})")
+            .unwrap();
+
+        let prepared = session
+            .prepare_cypher("MATCH (p:Person) WHERE p.name = $name RETURN p.age")
+            .unwrap();
+        let result = prepared
+            .execute(params(&[("name", Value::from("Alice"))]))
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Int64(30)]]);
+        let result = prepared
+            .execute(params(&[("name", Value::from("Bob"))]))
+            .unwrap();
+        assert!(result.rows.is_empty());
+    }
+}
@@ -0,0 +1,223 @@
+//! Named graph projections for running algorithms on a subgraph.
+//!
+//! A projection picks out the nodes and edges that pass a pair of filters and
+//! keeps them as a small graph of its own, with the original ids. Algorithms
+//! from [`grafeo_adapters::plugins::algorithms`] run against
+//! [`GraphProjection::graph()`] like any other store, so repeated runs don't
+//! have to filter the full graph again.
+//!
+//! Projections remember the graph's change count when they were built. Once
+//! the underlying graph changes they report themselves stale, and sessions
+//! re-project them on the next lookup.
+
+use std::sync::Arc;
+
+use grafeo_common::types::{EdgeId, NodeId};
+use grafeo_core::graph::lpg::{Edge, LpgStore, Node};
+
+/// Decides whether a node belongs to a projection.
+pub type NodeFilter = Arc<dyn Fn(&Node) -> bool + Send + Sync>;
+
+/// Decides whether an edge between two projected nodes belongs to a projection.
+pub type EdgeFilter = Arc<dyn Fn(&Edge) -> bool + Send + Sync>;
+
+/// A filtered, cached view of the graph.
+///
+/// Get one from [`Session::project_graph()`](crate::Session::project_graph).
+pub struct GraphProjection {
+    name: String,
+    source: Arc<LpgStore>,
+    node_filter: NodeFilter,
+    edge_filter: EdgeFilter,
+    /// The source's change count when this projection was built.
+    change_count: u64,
+    /// Projected node ids, sorted.
+    node_ids: Vec<NodeId>,
+    /// Projected edge ids, sorted.
+    edge_ids: Vec<EdgeId>,
+    /// The projected subgraph, keeping the source's ids.
+    graph: LpgStore,
+}
+
+impl GraphProjection {
+    /// Projects `source` through the given filters.
+    ///
+    /// Edges are only considered when both endpoints pass the node filter.
+    pub(crate) fn build(
+        name: &str,
+        source: Arc<LpgStore>,
+        node_filter: NodeFilter,
+        edge_filter: EdgeFilter,
+    ) -> Self {
+        let change_count = source.change_count();
+        let graph = LpgStore::new();
+
+        let mut node_ids = Vec::new();
+        for node in source.all_nodes().filter(|node| node_filter(node)) {
+            let labels: Vec<&str> = node.labels.iter().map(AsRef::as_ref).collect();
+            graph.create_node_with_id(node.id, &labels);
+            for (key, value) in node.properties {
+                graph.set_node_property(node.id, key.as_str(), value);
+            }
+            node_ids.push(node.id);
+        }
+        node_ids.sort_unstable();
+
+        let mut edge_ids = Vec::new();
+        for edge in source.all_edges() {
+            if node_ids.binary_search(&edge.src).is_err()
+                || node_ids.binary_search(&edge.dst).is_err()
+                || !edge_filter(&edge)
+            {
+                continue;
+            }
+            graph.create_edge_with_id(edge.id, edge.src, edge.dst, &edge.edge_type);
+            for (key, value) in edge.properties {
+                graph.set_edge_property(edge.id, key.as_str(), value);
+            }
+            edge_ids.push(edge.id);
+        }
+        edge_ids.sort_unstable();
+
+        Self {
+            name: name.to_string(),
+            source,
+            node_filter,
+            edge_filter,
+            change_count,
+            node_ids,
+            edge_ids,
+            graph,
+        }
+    }
+
+    /// Projects the current graph through this projection's filters again.
+    pub(crate) fn rebuild(&self) -> Self {
+        Self::build(
+            &self.name,
+            Arc::clone(&self.source),
+            Arc::clone(&self.node_filter),
+            Arc::clone(&self.edge_filter),
+        )
+    }
+
+    /// Returns the projection's name.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the projected subgraph, for running algorithms against.
+    #[must_use]
+    pub fn graph(&self) -> &LpgStore {
+        &self.graph
+    }
+
+    /// Returns the ids of the projected nodes, sorted.
+    #[must_use]
+    pub fn node_ids(&self) -> &[NodeId] {
+        &self.node_ids
+    }
+
+    /// Returns the ids of the projected edges, sorted.
+    #[must_use]
+    pub fn edge_ids(&self) -> &[EdgeId] {
+        &self.edge_ids
+    }
+
+    /// Returns whether the node is part of this projection.
+    #[must_use]
+    pub fn contains_node(&self, id: NodeId) -> bool {
+        self.node_ids.binary_search(&id).is_ok()
+    }
+
+    /// Returns whether the edge is part of this projection.
+    #[must_use]
+    pub fn contains_edge(&self, id: EdgeId) -> bool {
+        self.edge_ids.binary_search(&id).is_ok()
+    }
+
+    /// Returns whether the graph has changed since this projection was built.
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.source.change_count() != self.change_count
+    }
+}
+
+impl std::fmt::Debug for GraphProjection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GraphProjection")
+            .field("name", &self.name)
+            .field("nodes", &self.node_ids.len())
+            .field("edges", &self.edge_ids.len())
+            .field("stale", &self.is_stale())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use grafeo_adapters::plugins::algorithms::{connected_components, pagerank};
+    use grafeo_common::types::Value;
+
+    #[test]
+    fn test_algorithms_see_only_projected_graph() {
+        let store = Arc::new(LpgStore::new());
+        let a = store.create_node(&["Person"]);
+        let b = store.create_node(&["Person"]);
+        let c = store.create_node(&["Person"]);
+        let bot = store.create_node(&["Bot"]);
+        store.create_edge(a, b, "KNOWS");
+        store.create_edge(b, c, "SPAMS");
+        store.create_edge(bot, a, "KNOWS");
+        store.set_node_property(a, "name", Value::from("Alice"));
+
+        let projection = GraphProjection::build(
+            "people",
+            Arc::clone(&store),
+            Arc::new(|node: &Node| node.labels.iter().any(|l| l.as_ref() == "Person")),
+            Arc::new(|edge: &Edge| edge.edge_type.as_ref() == "KNOWS"),
+        );
+
+        assert_eq!(projection.node_ids(), &[a, b, c]);
+        assert_eq!(projection.edge_ids().len(), 1);
+        assert!(!projection.contains_node(bot));
+        assert_eq!(
+            projection.graph().get_node(a).unwrap().get_property("name"),
+            Some(&Value::from("Alice"))
+        );
+
+        // Without the SPAMS edge, c is a component of its own
+        let components = connected_components(projection.graph());
+        assert_eq!(components.len(), 3);
+        assert_eq!(components[&a], components[&b]);
+        assert_ne!(components[&a], components[&c]);
+
+        let ranks = pagerank(projection.graph(), 0.85, 100, 1e-6);
+        assert_eq!(ranks.len(), 3);
+        assert!(!ranks.contains_key(&bot));
+        assert!(ranks[&b] > ranks[&a]);
+    }
+
+    #[test]
+    fn test_projection_goes_stale_on_change() {
+        let store = Arc::new(LpgStore::new());
+        store.create_node(&["Person"]);
+
+        let projection = GraphProjection::build(
+            "all",
+            Arc::clone(&store),
+            Arc::new(|_: &Node| true),
+            Arc::new(|_: &Edge| true),
+        );
+        assert!(!projection.is_stale());
+
+        store.create_node(&["Person"]);
+        assert!(projection.is_stale());
+
+        let rebuilt = projection.rebuild();
+        assert!(!rebuilt.is_stale());
+        assert_eq!(rebuilt.node_ids().len(), 2);
+    }
+}
@@ -0,0 +1,342 @@
+//! Suggests indexes for a representative query workload.
+//!
+//! The advisor looks at logical plans rather than running anything. Every
+//! label scan followed by a filter on one of that label's properties is a
+//! scan that an index could turn into a lookup:
+//!
+//! | Predicate | Suggested index |
+//! | --------- | --------------- |
+//! | `n.prop = value`, `n.prop IN [...]` | Hash |
+//! | `a.prop = b.prop` (join key) | Hash on both sides |
+//! | `n.prop < value`, `STARTS WITH`, ... | BTree |
+//!
+//! Properties that already have an index in the [`Catalog`] are skipped.
+//! Nothing is created - turning a recommendation into an index is up to you.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::catalog::{Catalog, IndexType};
+use crate::query::plan::{BinaryOp, LogicalExpression, LogicalOperator, LogicalPlan};
+
+/// An index the advisor thinks the workload would benefit from.
+///
+/// Names rather than catalog IDs, since the index doesn't exist yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexRecommendation {
+    /// The label of the scanned nodes.
+    pub label: String,
+    /// The filtered property.
+    pub property: String,
+    /// Hash for equality-only use, BTree if any predicate is a range.
+    pub index_type: IndexType,
+    /// How many predicates in the workload would use this index.
+    pub uses: usize,
+}
+
+/// Collects index candidates across a set of logical plans.
+#[derive(Default)]
+pub struct IndexAdvisor {
+    /// Existing indexes to leave out of the recommendations.
+    catalog: Option<Arc<Catalog>>,
+    /// (label, property) -> (needs range support, use count)
+    candidates: HashMap<(String, String), (bool, usize)>,
+}
+
+impl IndexAdvisor {
+    /// Creates an advisor with no knowledge of existing indexes.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips properties that already have an index in this catalog.
+    #[must_use]
+    pub fn with_catalog(mut self, catalog: Arc<Catalog>) -> Self {
+        self.catalog = Some(catalog);
+        self
+    }
+
+    /// Records the index candidates found in one query plan.
+    pub fn add_plan(&mut self, plan: &LogicalPlan) {
+        let mut labels = HashMap::new();
+        collect_labels(&plan.root, &mut labels);
+        self.visit(&plan.root, &labels);
+    }
+
+    /// Returns the recommendations, most used first.
+    #[must_use]
+    pub fn recommendations(&self) -> Vec<IndexRecommendation> {
+        let mut result: Vec<IndexRecommendation> = self
+            .candidates
+            .iter()
+            .filter(|((label, property), _)| !self.is_indexed(label, property))
+            .map(|((label, property), &(range, uses))| IndexRecommendation {
+                label: label.clone(),
+                property: property.clone(),
+                index_type: if range {
+                    IndexType::BTree
+                } else {
+                    IndexType::Hash
+                },
+                uses,
+            })
+            .collect();
+
+        result.sort_by(|a, b| {
+            b.uses
+                .cmp(&a.uses)
+                .then_with(|| a.label.cmp(&b.label))
+                .then_with(|| a.property.cmp(&b.property))
+        });
+        result
+    }
+
+    fn is_indexed(&self, label: &str, property: &str) -> bool {
+        let Some(catalog) = &self.catalog else {
+            return false;
+        };
+        let Some(key_id) = catalog.get_property_key_id(property) else {
+            return false;
+        };
+        catalog.get_label_ids(label).into_iter().any(|label_id| {
+            !catalog
+                .indexes_for_label_property(label_id, key_id)
+                .is_empty()
+        })
+    }
+
+    fn visit(&mut self, op: &LogicalOperator, labels: &HashMap<String, String>) {
+        match op {
+            LogicalOperator::Filter(filter) => self.add_predicate(&filter.predicate, labels),
+            LogicalOperator::Join(join) => {
+                for condition in &join.conditions {
+                    self.add_join_key(&condition.left, &condition.right, labels);
+                }
+            }
+            LogicalOperator::LeftJoin(join) => {
+                if let Some(condition) = &join.condition {
+                    self.add_predicate(condition, labels);
+                }
+            }
+            _ => {}
+        }
+        for child in op.children() {
+            self.visit(child, labels);
+        }
+    }
+
+    /// Records candidates from the AND-ed conjuncts of a predicate.
+    ///
+    /// Disjunctions are left alone: a single index can't serve `a OR b`.
+    fn add_predicate(&mut self, expr: &LogicalExpression, labels: &HashMap<String, String>) {
+        let LogicalExpression::Binary { left, op, right } = expr else {
+            return;
+        };
+        match op {
+            BinaryOp::And => {
+                self.add_predicate(left, labels);
+                self.add_predicate(right, labels);
+            }
+            BinaryOp::Eq | BinaryOp::In => {
+                if is_constant(right) {
+                    self.add_candidate(left, false, labels);
+                } else if is_constant(left) && *op == BinaryOp::Eq {
+                    self.add_candidate(right, false, labels);
+                } else if *op == BinaryOp::Eq {
+                    self.add_join_key(left, right, labels);
+                }
+            }
+            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+                if is_constant(right) {
+                    self.add_candidate(left, true, labels);
+                } else if is_constant(left) {
+                    self.add_candidate(right, true, labels);
+                }
+            }
+            BinaryOp::StartsWith => {
+                if is_constant(right) {
+                    self.add_candidate(left, true, labels);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_join_key(
+        &mut self,
+        left: &LogicalExpression,
+        right: &LogicalExpression,
+        labels: &HashMap<String, String>,
+    ) {
+        self.add_candidate(left, false, labels);
+        self.add_candidate(right, false, labels);
+    }
+
+    /// Records a candidate if `expr` is a property of a labeled node.
+    fn add_candidate(
+        &mut self,
+        expr: &LogicalExpression,
+        range: bool,
+        labels: &HashMap<String, String>,
+    ) {
+        let LogicalExpression::Property { variable, property } = expr else {
+            return;
+        };
+        let Some(label) = labels.get(variable) else {
+            return;
+        };
+        let entry = self
+            .candidates
+            .entry((label.clone(), property.clone()))
+            .or_insert((false, 0));
+        entry.0 |= range;
+        entry.1 += 1;
+    }
+}
+
+/// Returns true for values known before execution: literals and parameters.
+fn is_constant(expr: &LogicalExpression) -> bool {
+    match expr {
+        LogicalExpression::Literal(_) | LogicalExpression::Parameter(_) => true,
+        LogicalExpression::List(items) => items.iter().all(is_constant),
+        _ => false,
+    }
+}
+
+/// Maps each variable bound by a labeled node scan to its first label.
+fn collect_labels(op: &LogicalOperator, labels: &mut HashMap<String, String>) {
+    if let LogicalOperator::NodeScan(scan) = op {
+        if let Some(label) = scan.labels.first() {
+            labels.insert(scan.variable.clone(), label.clone());
+        }
+    }
+    for child in op.children() {
+        collect_labels(child, labels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::plan::{FilterOp, JoinCondition, JoinOp, JoinType, NodeScanOp};
+    use grafeo_common::types::Value;
+
+    fn scan(variable: &str, label: &str) -> LogicalOperator {
+        LogicalOperator::NodeScan(NodeScanOp {
+            variable: variable.to_string(),
+            labels: vec![label.to_string()],
+            input: None,
+        })
+    }
+
+    fn prop(variable: &str, property: &str) -> LogicalExpression {
+        LogicalExpression::Property {
+            variable: variable.to_string(),
+            property: property.to_string(),
+        }
+    }
+
+    fn filter(input: LogicalOperator, predicate: LogicalExpression) -> LogicalPlan {
+        LogicalPlan::new(LogicalOperator::Filter(FilterOp {
+            predicate,
+            input: Box::new(input),
+        }))
+    }
+
+    #[test]
+    fn test_range_predicate_suggests_btree() {
+        let mut advisor = IndexAdvisor::new();
+        // Equality and range on the same property: BTree serves both
+        for op in [BinaryOp::Eq, BinaryOp::Gt] {
+            advisor.add_plan(&filter(
+                scan("n", "Person"),
+                LogicalExpression::Binary {
+                    left: Box::new(prop("n", "age")),
+                    op,
+                    right: Box::new(LogicalExpression::Literal(Value::Int64(30))),
+                },
+            ));
+        }
+
+        let recs = advisor.recommendations();
+        assert_eq!(recs.len(), 1);
+        assert_eq!(recs[0].index_type, IndexType::BTree);
+        assert_eq!(recs[0].uses, 2);
+    }
+
+    #[test]
+    fn test_join_keys_suggest_both_sides() {
+        let plan = LogicalPlan::new(LogicalOperator::Join(JoinOp {
+            left: Box::new(scan("o", "Order")),
+            right: Box::new(scan("c", "Customer")),
+            join_type: JoinType::Inner,
+            conditions: vec![JoinCondition {
+                left: prop("o", "customer_id"),
+                right: prop("c", "id"),
+            }],
+        }));
+
+        let mut advisor = IndexAdvisor::new();
+        advisor.add_plan(&plan);
+        let recs = advisor.recommendations();
+
+        assert_eq!(recs.len(), 2);
+        assert!(recs.iter().all(|r| r.index_type == IndexType::Hash));
+        assert!(
+            recs.iter()
+                .any(|r| r.label == "Customer" && r.property == "id")
+        );
+        assert!(
+            recs.iter()
+                .any(|r| r.label == "Order" && r.property == "customer_id")
+        );
+    }
+
+    #[test]
+    fn test_disjunction_and_unlabeled_scan_are_ignored() {
+        let unlabeled = LogicalOperator::NodeScan(NodeScanOp {
+            variable: "n".to_string(),
+            labels: Vec::new(),
+            input: None,
+        });
+        let eq = |v: &str| LogicalExpression::Binary {
+            left: Box::new(prop(v, "name")),
+            op: BinaryOp::Eq,
+            right: Box::new(LogicalExpression::Literal(Value::from("Alice"))),
+        };
+
+        let mut advisor = IndexAdvisor::new();
+        advisor.add_plan(&filter(unlabeled, eq("n")));
+        advisor.add_plan(&filter(
+            scan("m", "Person"),
+            LogicalExpression::Binary {
+                left: Box::new(eq("m")),
+                op: BinaryOp::Or,
+                right: Box::new(eq("m")),
+            },
+        ));
+
+        assert!(advisor.recommendations().is_empty());
+    }
+
+    #[test]
+    fn test_existing_index_is_skipped() {
+        let catalog = Arc::new(Catalog::new());
+        let label = catalog.get_or_create_label("Person");
+        let key = catalog.get_or_create_property_key("email");
+        catalog.create_index(label, key, IndexType::Hash);
+
+        let mut advisor = IndexAdvisor::new().with_catalog(catalog);
+        advisor.add_plan(&filter(
+            scan("n", "Person"),
+            LogicalExpression::Binary {
+                left: Box::new(prop("n", "email")),
+                op: BinaryOp::Eq,
+                right: Box::new(LogicalExpression::Parameter("email".to_string())),
+            },
+        ));
+
+        assert!(advisor.recommendations().is_empty());
+    }
+}
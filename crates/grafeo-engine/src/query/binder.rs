@@ -11,6 +11,7 @@ use crate::query::plan::{
     ExpandOp, FilterOp, LogicalExpression, LogicalOperator, LogicalPlan, NodeScanOp, ReturnItem,
     ReturnOp, TripleScanOp,
 };
+use crate::query::planner::expression_to_string;
 use grafeo_common::types::LogicalType;
 use grafeo_common::utils::error::{Error, QueryError, QueryErrorKind, Result};
 use std::collections::HashMap;
@@ -159,6 +160,13 @@ impl Binder {
                 }
                 Ok(())
             }
+            LogicalOperator::TopN(top) => {
+                self.bind_operator(&top.input)?;
+                for key in &top.keys {
+                    self.validate_expression(&key.expression)?;
+                }
+                Ok(())
+            }
             LogicalOperator::CreateNode(create) => {
                 // CreateNode introduces a new variable
                 if let Some(ref input) = create.input {
@@ -287,6 +295,22 @@ impl Binder {
                 Ok(())
             }
 
+            LogicalOperator::ForEach(foreach) => {
+                self.bind_operator(&foreach.input)?;
+                self.validate_expression(&foreach.list)?;
+                self.context.add_variable(
+                    foreach.variable.clone(),
+                    VariableInfo {
+                        name: foreach.variable.clone(),
+                        data_type: LogicalType::Any,
+                        is_node: false,
+                        is_edge: false,
+                    },
+                );
+                // The body's Empty leaf sees the input row plus the element
+                self.bind_operator(&foreach.body)
+            }
+
             // RDF/SPARQL operators
             LogicalOperator::TripleScan(scan) => self.bind_triple_scan(scan),
             LogicalOperator::Union(union) => {
@@ -329,14 +353,6 @@ impl Binder {
                 for (_, expr) in &merge.match_properties {
                     self.validate_expression(expr)?;
                 }
-                // Validate the ON CREATE property expressions
-                for (_, expr) in &merge.on_create {
-                    self.validate_expression(expr)?;
-                }
-                // Validate the ON MATCH property expressions
-                for (_, expr) in &merge.on_match {
-                    self.validate_expression(expr)?;
-                }
                 // MERGE introduces a new variable
                 self.context.add_variable(
                     merge.variable.clone(),
@@ -347,6 +363,35 @@ impl Binder {
                         is_edge: false,
                     },
                 );
+                if let Some(rel) = &merge.relationship {
+                    for (_, expr) in rel.properties.iter().chain(&rel.target_properties) {
+                        self.validate_expression(expr)?;
+                    }
+                    self.context.add_variable(
+                        rel.target_variable.clone(),
+                        VariableInfo {
+                            name: rel.target_variable.clone(),
+                            data_type: LogicalType::Node,
+                            is_node: true,
+                            is_edge: false,
+                        },
+                    );
+                    if let Some(edge_var) = &rel.variable {
+                        self.context.add_variable(
+                            edge_var.clone(),
+                            VariableInfo {
+                                name: edge_var.clone(),
+                                data_type: LogicalType::Edge,
+                                is_node: false,
+                                is_edge: true,
+                            },
+                        );
+                    }
+                }
+                // ON CREATE / ON MATCH updates see the merged variables
+                for set in merge.on_create.iter().chain(&merge.on_match) {
+                    self.bind_operator(&LogicalOperator::SetProperty(set.clone()))?;
+                }
                 Ok(())
             }
             LogicalOperator::AddLabel(add_label) => {
@@ -427,6 +472,8 @@ impl Binder {
                 self.bind_operator(&modify.where_clause)?;
                 Ok(())
             }
+            // Unbound template variables skip the triple rather than fail
+            LogicalOperator::Construct(construct) => self.bind_operator(&construct.input),
             LogicalOperator::ClearGraph(_)
             | LogicalOperator::CreateGraph(_)
             | LogicalOperator::DropGraph(_)
@@ -574,8 +621,17 @@ impl Binder {
             },
         );
 
-        // Add path length variable for variable-length paths (for length(p) calls)
+        // Add the path and its length for variable-length paths
         if let Some(ref path_alias) = expand.path_alias {
+            self.context.add_variable(
+                path_alias.clone(),
+                VariableInfo {
+                    name: path_alias.clone(),
+                    data_type: LogicalType::Any, // Path is a list of edges
+                    is_node: false,
+                    is_edge: false,
+                },
+            );
             let path_length_var = format!("_path_length_{}", path_alias);
             self.context.add_variable(
                 path_length_var.clone(),
@@ -705,6 +761,19 @@ impl Binder {
                 }
                 Ok(())
             }
+            LogicalExpression::PathLength(var)
+            | LogicalExpression::PathCost { variable: var, .. } => {
+                if !self.context.contains(var) {
+                    return Err(binding_error(format!("Undefined path variable '{var}'")));
+                }
+                Ok(())
+            }
+            LogicalExpression::PropertyMap { variable, .. } => {
+                if !self.context.contains(variable) {
+                    return Err(binding_error(format!("Undefined variable '{variable}'")));
+                }
+                Ok(())
+            }
             LogicalExpression::ListComprehension {
                 list_expr,
                 filter_expr,
@@ -796,9 +865,23 @@ impl Binder {
         // Bind the input first
         self.bind_operator(&agg.input)?;
 
-        // Validate group by expressions
+        // Validate group by expressions. Keys other than plain variables come
+        // out as columns named after the expression, which operators above
+        // the aggregate can refer to by that name
         for expr in &agg.group_by {
             self.validate_expression(expr)?;
+            if !matches!(expr, LogicalExpression::Variable(_)) {
+                let name = expression_to_string(expr);
+                self.context.add_variable(
+                    name.clone(),
+                    VariableInfo {
+                        name,
+                        data_type: LogicalType::Any,
+                        is_node: false,
+                        is_edge: false,
+                    },
+                );
+            }
         }
 
         // Validate aggregate expressions
@@ -845,7 +928,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         }));
@@ -869,7 +952,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
         }));
@@ -895,7 +978,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         }));
@@ -927,7 +1010,7 @@ mod tests {
                 },
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -967,7 +1050,7 @@ mod tests {
                 max_hops: Some(1),
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "a".to_string(),
-                    label: Some("Person".to_string()),
+                    labels: vec!["Person".to_string()],
                     input: None,
                 })),
                 path_alias: None,
@@ -8,6 +8,9 @@
 //! - **Parsed cache**: Caches logical plans after translation (language-specific parsing)
 //! - **Optimized cache**: Caches logical plans after optimization
 //!
+//! [`ResultCache`] sits on top of these and caches query *results*, which
+//! stay valid only until the graph changes.
+//!
 //! ## Usage
 //!
 //! ```ignore
@@ -30,6 +33,9 @@ use std::hash::Hash;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Instant;
 
+use grafeo_common::types::Value;
+
+use crate::database::QueryResult;
 use crate::query::plan::LogicalPlan;
 use crate::query::processor::QueryLanguage;
 
@@ -380,6 +386,110 @@ impl CacheStats {
     }
 }
 
+/// Cache key for a query result: the query plus its parameter values.
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct ResultCacheKey {
+    /// The query and its language.
+    query: CacheKey,
+    /// Parameters sorted by name, with values rendered for hashing.
+    params: Vec<(String, String)>,
+}
+
+impl ResultCacheKey {
+    /// Creates a key for a query run with `params`.
+    #[must_use]
+    pub fn new(query: CacheKey, params: Option<&HashMap<String, Value>>) -> Self {
+        let mut params: Vec<(String, String)> = params
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| (name.clone(), format!("{value:?}")))
+            .collect();
+        params.sort_unstable();
+        Self { query, params }
+    }
+}
+
+/// Cache for query results, keyed on the query, its parameters, and the
+/// graph version it ran against.
+///
+/// The graph version is [`LpgStore::change_count`](grafeo_core::graph::lpg::LpgStore::change_count),
+/// so any write invalidates every cached result - coarse, but never stale.
+/// Entries from older versions are dropped when next looked up.
+pub struct ResultCache {
+    /// Cached results with the graph version they were computed at.
+    entries: Mutex<LruCache<ResultCacheKey, (u64, QueryResult)>>,
+    /// Lookups answered from the cache.
+    hits: AtomicU64,
+    /// Lookups that had to run the query.
+    misses: AtomicU64,
+}
+
+impl ResultCache {
+    /// Creates a result cache holding up to `capacity` results.
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity.max(1))),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the cached result for `key` if it was computed at `version`.
+    pub fn get(&self, key: &ResultCacheKey, version: u64) -> Option<QueryResult> {
+        let mut entries = self.entries.lock();
+        let result = match entries.get(key) {
+            Some((cached_version, result)) if cached_version == version => Some(result),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        };
+        if result.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.misses.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Caches a result computed at graph `version`.
+    pub fn put(&self, key: ResultCacheKey, version: u64, result: QueryResult) {
+        self.entries.lock().put(key, (version, result));
+    }
+
+    /// Drops every cached result.
+    pub fn clear(&self) {
+        self.entries.lock().clear();
+    }
+
+    /// Returns the number of cached results, including ones that have gone
+    /// stale but haven't been looked up since.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// Returns true if no results are cached.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of lookups answered from the cache.
+    #[must_use]
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of lookups that missed.
+    #[must_use]
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
 /// A caching wrapper for the query processor.
 ///
 /// This type wraps a query processor and adds caching capabilities.
@@ -566,4 +676,25 @@ mod tests {
         assert_eq!(stats.optimized_misses, 2);
         assert!((stats.optimized_hit_rate() - 0.5).abs() < 0.01);
     }
+
+    #[test]
+    fn test_result_cache_versions_and_params() {
+        let cache = ResultCache::new(10);
+        let params = |n: i64| HashMap::from([("n".to_string(), Value::Int64(n))]);
+        let key = |n: i64| {
+            ResultCacheKey::new(
+                CacheKey::new("RETURN $n", test_language()),
+                Some(&params(n)),
+            )
+        };
+
+        cache.put(key(1), 7, QueryResult::new(vec!["n".to_string()]));
+        assert!(cache.get(&key(1), 7).is_some());
+        assert!(cache.get(&key(2), 7).is_none(), "different parameters");
+
+        // A newer graph version drops the entry
+        assert!(cache.get(&key(1), 8).is_none());
+        assert!(cache.is_empty());
+        assert_eq!((cache.hits(), cache.misses()), (1, 2));
+    }
 }
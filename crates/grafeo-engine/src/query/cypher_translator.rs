@@ -5,18 +5,25 @@
 
 use crate::query::plan::{
     AggregateExpr, AggregateFunction, AggregateOp, BinaryOp, CreateEdgeOp, CreateNodeOp,
-    DeleteNodeOp, DistinctOp, ExpandDirection, ExpandOp, FilterOp, LeftJoinOp, LimitOp,
-    LogicalExpression, LogicalOperator, LogicalPlan, MergeOp, NodeScanOp, ProjectOp, Projection,
-    RemoveLabelOp, ReturnItem, ReturnOp, SetPropertyOp, ShortestPathOp, SkipOp, SortKey, SortOp,
-    SortOrder, UnaryOp, UnwindOp,
+    DeleteNodeOp, DistinctOp, ExpandDirection, ExpandOp, FilterOp, ForEachOp, LeftJoinOp, LimitOp,
+    LogicalExpression, LogicalOperator, LogicalPlan, MergeOp, MergeRelationship, NodeScanOp,
+    ProjectOp, Projection, RemoveLabelOp, ReturnItem, ReturnOp, SetPropertyOp, ShortestPathOp,
+    SkipOp, SortKey, SortOp, SortOrder, UnaryOp, UnwindOp,
 };
+use grafeo_adapters::query::DEFAULT_MAX_NESTING_DEPTH;
 use grafeo_adapters::query::cypher::{self, ast};
 use grafeo_common::types::Value;
 use grafeo_common::utils::error::{Error, Result};
 
 /// Translates a Cypher query string to a logical plan.
 pub fn translate(query: &str) -> Result<LogicalPlan> {
-    let statement = cypher::parse(query)?;
+    translate_with_max_depth(query, DEFAULT_MAX_NESTING_DEPTH)
+}
+
+/// Translates a Cypher query string to a logical plan, rejecting expressions
+/// nested deeper than `max_depth`.
+pub fn translate_with_max_depth(query: &str, max_depth: usize) -> Result<LogicalPlan> {
+    let statement = cypher::parse_with_max_depth(query, max_depth)?;
     let translator = CypherTranslator::new();
     translator.translate_statement(&statement)
 }
@@ -24,13 +31,21 @@ pub fn translate(query: &str) -> Result<LogicalPlan> {
 /// Cypher AST to logical plan translator.
 struct CypherTranslator {
     /// Variable counter for generating unique variable names.
-    #[allow(dead_code)]
-    var_counter: u32,
+    var_counter: std::cell::Cell<u32>,
 }
 
 impl CypherTranslator {
     fn new() -> Self {
-        Self { var_counter: 0 }
+        Self {
+            var_counter: std::cell::Cell::new(0),
+        }
+    }
+
+    /// Generates a variable name for an unnamed relationship that must be bound.
+    fn next_anon_edge_var(&self) -> String {
+        let n = self.var_counter.get();
+        self.var_counter.set(n + 1);
+        format!("_anon_edge_{n}")
     }
 
     fn translate_statement(&self, stmt: &ast::Statement) -> Result<LogicalPlan> {
@@ -79,6 +94,7 @@ impl CypherTranslator {
             ast::Clause::Delete(delete_clause) => self.translate_delete(delete_clause, input),
             ast::Clause::Set(set_clause) => self.translate_set(set_clause, input),
             ast::Clause::Remove(remove_clause) => self.translate_remove(remove_clause, input),
+            ast::Clause::Foreach(foreach_clause) => self.translate_foreach(foreach_clause, input),
         }
     }
 
@@ -149,11 +165,11 @@ impl CypherTranslator {
         input: Option<LogicalOperator>,
     ) -> Result<LogicalOperator> {
         let variable = node.variable.clone().unwrap_or_else(|| "_anon".to_string());
-        let label = node.labels.first().cloned();
+        let labels = node.labels.clone();
 
         let mut plan = LogicalOperator::NodeScan(NodeScanOp {
             variable: variable.clone(),
-            label,
+            labels,
             input: input.map(Box::new),
         });
 
@@ -286,11 +302,11 @@ impl CypherTranslator {
             .variable
             .clone()
             .unwrap_or_else(|| "_src".to_string());
-        let source_label = path.start.labels.first().cloned();
+        let source_labels = path.start.labels.clone();
 
         let mut plan = LogicalOperator::NodeScan(NodeScanOp {
             variable: source_var.clone(),
-            label: source_label,
+            labels: source_labels,
             input: input.map(Box::new),
         });
 
@@ -318,12 +334,12 @@ impl CypherTranslator {
                 .variable
                 .clone()
                 .unwrap_or_else(|| "_tgt".to_string());
-            let target_label = rel.target.labels.first().cloned();
+            let target_labels = rel.target.labels.clone();
 
             // Scan for target node
             plan = LogicalOperator::NodeScan(NodeScanOp {
                 variable: target_var.clone(),
-                label: target_label,
+                labels: target_labels,
                 input: Some(Box::new(plan)),
             });
 
@@ -350,6 +366,7 @@ impl CypherTranslator {
             };
 
             let edge_type = rel.types.first().cloned();
+            let max_hops = rel.length.as_ref().and_then(|length| length.max);
             let all_paths = matches!(path_function, ast::PathFunction::AllShortestPaths);
 
             plan = LogicalOperator::ShortestPath(ShortestPathOp {
@@ -358,6 +375,7 @@ impl CypherTranslator {
                 target_var,
                 edge_type,
                 direction,
+                max_hops,
                 path_alias: path_alias.to_string(),
                 all_paths,
             });
@@ -382,7 +400,13 @@ impl CypherTranslator {
         path_alias: Option<String>,
     ) -> Result<LogicalOperator> {
         let from_variable = Self::get_last_variable(&input)?;
-        let edge_variable = rel.variable.clone();
+        // Inline properties are filtered on the edge, so an unnamed
+        // relationship still needs a binding the filter can reference.
+        let edge_variable = match &rel.variable {
+            Some(var) => Some(var.clone()),
+            None if !rel.properties.is_empty() => Some(self.next_anon_edge_var()),
+            None => None,
+        };
         let edge_type = rel.types.first().cloned();
         let to_variable = rel
             .target
@@ -403,10 +427,10 @@ impl CypherTranslator {
             (1, Some(1))
         };
 
-        let expand = LogicalOperator::Expand(ExpandOp {
+        let mut expand = LogicalOperator::Expand(ExpandOp {
             from_variable,
             to_variable: to_variable.clone(),
-            edge_variable,
+            edge_variable: edge_variable.clone(),
             direction,
             edge_type,
             min_hops,
@@ -415,6 +439,26 @@ impl CypherTranslator {
             path_alias,
         });
 
+        // Add filter for inline relationship properties (e.g., -[:KNOWS {since: 2020}]->)
+        if !rel.properties.is_empty() {
+            if let Some(edge_var) = &edge_variable {
+                let predicate = self.build_property_predicate(edge_var, &rel.properties)?;
+                expand = LogicalOperator::Filter(FilterOp {
+                    predicate,
+                    input: Box::new(expand),
+                });
+            }
+        }
+
+        // And for inline target node properties (e.g., ->(b {name: 'Bob'}))
+        if !rel.target.properties.is_empty() {
+            let predicate = self.build_property_predicate(&to_variable, &rel.target.properties)?;
+            expand = LogicalOperator::Filter(FilterOp {
+                predicate,
+                input: Box::new(expand),
+            });
+        }
+
         if let Some(label) = target_label {
             Ok(LogicalOperator::Filter(FilterOp {
                 predicate: LogicalExpression::FunctionCall {
@@ -507,6 +551,35 @@ impl CypherTranslator {
         }))
     }
 
+    fn translate_foreach(
+        &self,
+        foreach_clause: &ast::ForeachClause,
+        input: Option<LogicalOperator>,
+    ) -> Result<LogicalOperator> {
+        // Like UNWIND, FOREACH can start a query on its own
+        let input = input.unwrap_or(LogicalOperator::Empty);
+        let list = self.translate_expression(&foreach_clause.list)?;
+
+        // The body starts from Empty, which the planner feeds with the current
+        // row extended by the element variable
+        let mut body = LogicalOperator::Empty;
+        for clause in &foreach_clause.clauses {
+            if matches!(clause, ast::Clause::Merge(_)) {
+                return Err(Error::Internal(
+                    "MERGE is not supported inside FOREACH".into(),
+                ));
+            }
+            body = self.translate_clause(clause, Some(body))?;
+        }
+
+        Ok(LogicalOperator::ForEach(ForEachOp {
+            variable: foreach_clause.variable.clone(),
+            list,
+            body: Box::new(body),
+            input: Box::new(input),
+        }))
+    }
+
     fn translate_merge_statement(&self, merge: &ast::MergeClause) -> Result<LogicalPlan> {
         let op = self.translate_merge(merge, None)?;
         Ok(LogicalPlan { root: op })
@@ -519,24 +592,26 @@ impl CypherTranslator {
     ) -> Result<LogicalOperator> {
         let input = input.unwrap_or(LogicalOperator::Empty);
 
-        // Extract node information from the pattern
-        // For now, we only support simple single-node patterns: (n:Label {props})
-        let pattern = &merge_clause.pattern;
-
-        // Extract node from the pattern
-        let node = match pattern {
-            ast::Pattern::Node(n) => n,
-            ast::Pattern::Path(path) => &path.start,
-            ast::Pattern::NamedPath { pattern: inner, .. } => match inner.as_ref() {
-                ast::Pattern::Node(n) => n,
-                ast::Pattern::Path(path) => &path.start,
-                _ => {
-                    return Err(Error::Internal(
-                        "MERGE NamedPath must contain a node".into(),
-                    ));
-                }
-            },
+        // MERGE takes a single node, or a node with one relationship:
+        // (n:Label {props}) or (a)-[r:TYPE {props}]->(b)
+        let pattern = match &merge_clause.pattern {
+            ast::Pattern::NamedPath { pattern: inner, .. } => inner.as_ref(),
+            pattern => pattern,
+        };
+        let (node, chain) = match pattern {
+            ast::Pattern::Node(n) => (n, &[][..]),
+            ast::Pattern::Path(path) => (&path.start, path.chain.as_slice()),
+            ast::Pattern::NamedPath { .. } => {
+                return Err(Error::Internal(
+                    "MERGE NamedPath must contain a node".into(),
+                ));
+            }
         };
+        if chain.len() > 1 {
+            return Err(Error::Internal(
+                "MERGE supports at most one relationship per pattern".into(),
+            ));
+        }
 
         let variable = node
             .variable
@@ -545,38 +620,84 @@ impl CypherTranslator {
         let labels: Vec<String> = node.labels.clone();
 
         // Extract properties from the node pattern
-        let match_properties: Vec<(String, LogicalExpression)> = node
-            .properties
-            .iter()
-            .map(|(k, v)| Ok((k.clone(), self.translate_expression(v)?)))
-            .collect::<Result<Vec<_>>>()?;
+        let match_properties = self.translate_property_map(&node.properties)?;
 
-        // Extract ON CREATE properties
-        let on_create: Vec<(String, LogicalExpression)> =
-            if let Some(set_clause) = &merge_clause.on_create {
-                self.extract_set_properties(set_clause)?
-            } else {
-                Vec::new()
-            };
+        let relationship = match chain.first() {
+            Some(rel) => Some(self.translate_merge_relationship(rel)?),
+            None => None,
+        };
 
-        // Extract ON MATCH properties
-        let on_match: Vec<(String, LogicalExpression)> =
-            if let Some(set_clause) = &merge_clause.on_match {
-                self.extract_set_properties(set_clause)?
-            } else {
-                Vec::new()
-            };
+        // ON CREATE / ON MATCH become updates that only run on their branch
+        let on_create = match &merge_clause.on_create {
+            Some(set_clause) => self.translate_set_items(set_clause)?,
+            None => Vec::new(),
+        };
+        let on_match = match &merge_clause.on_match {
+            Some(set_clause) => self.translate_set_items(set_clause)?,
+            None => Vec::new(),
+        };
 
         Ok(LogicalOperator::Merge(MergeOp {
             variable,
             labels,
             match_properties,
+            relationship,
             on_create,
             on_match,
             input: Box::new(input),
         }))
     }
 
+    /// Translates the relationship of a `MERGE (a)-[r:TYPE]->(b)` pattern.
+    fn translate_merge_relationship(
+        &self,
+        rel: &ast::RelationshipPattern,
+    ) -> Result<MergeRelationship> {
+        if rel.length.is_some() {
+            return Err(Error::Internal(
+                "MERGE does not support variable-length relationships".into(),
+            ));
+        }
+        let edge_type = match rel.types.as_slice() {
+            [edge_type] => edge_type.clone(),
+            _ => {
+                return Err(Error::Internal(
+                    "MERGE requires exactly one relationship type".into(),
+                ));
+            }
+        };
+        let direction = match rel.direction {
+            ast::Direction::Outgoing => ExpandDirection::Outgoing,
+            ast::Direction::Incoming => ExpandDirection::Incoming,
+            ast::Direction::Undirected => ExpandDirection::Both,
+        };
+
+        Ok(MergeRelationship {
+            variable: rel.variable.clone(),
+            edge_type,
+            direction,
+            properties: self.translate_property_map(&rel.properties)?,
+            target_variable: rel
+                .target
+                .variable
+                .clone()
+                .unwrap_or_else(|| format!("_merge_{}", 1)),
+            target_labels: rel.target.labels.clone(),
+            target_properties: self.translate_property_map(&rel.target.properties)?,
+        })
+    }
+
+    /// Translates the property map of a node or relationship pattern.
+    fn translate_property_map(
+        &self,
+        properties: &[(String, ast::Expression)],
+    ) -> Result<Vec<(String, LogicalExpression)>> {
+        properties
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), self.translate_expression(v)?)))
+            .collect()
+    }
+
     /// Extracts properties from a map expression.
     #[allow(dead_code)]
     fn extract_map_properties(
@@ -594,51 +715,6 @@ impl CypherTranslator {
         }
     }
 
-    /// Extracts properties from a SET clause.
-    fn extract_set_properties(
-        &self,
-        set_clause: &ast::SetClause,
-    ) -> Result<Vec<(String, LogicalExpression)>> {
-        let mut properties = Vec::new();
-        for item in &set_clause.items {
-            match item {
-                ast::SetItem::Property {
-                    variable: _,
-                    property,
-                    value,
-                } => {
-                    properties.push((property.clone(), self.translate_expression(value)?));
-                }
-                ast::SetItem::AllProperties {
-                    variable: _,
-                    properties: prop_expr,
-                } => {
-                    // n = {props} - extract all properties from the map
-                    if let ast::Expression::Map(pairs) = prop_expr {
-                        for (k, v) in pairs {
-                            properties.push((k.clone(), self.translate_expression(v)?));
-                        }
-                    }
-                }
-                ast::SetItem::MergeProperties {
-                    variable: _,
-                    properties: prop_expr,
-                } => {
-                    // n += {props} - merge properties
-                    if let ast::Expression::Map(pairs) = prop_expr {
-                        for (k, v) in pairs {
-                            properties.push((k.clone(), self.translate_expression(v)?));
-                        }
-                    }
-                }
-                ast::SetItem::Labels { .. } => {
-                    // Labels are handled separately
-                }
-            }
-        }
-        Ok(properties)
-    }
-
     fn translate_return(
         &self,
         return_clause: &ast::ReturnClause,
@@ -971,10 +1047,23 @@ impl CypherTranslator {
         let input = input.ok_or_else(|| Error::Internal("SET requires input".into()))?;
 
         let mut plan = input;
+        for mut set in self.translate_set_items(set_clause)? {
+            set.input = Box::new(plan);
+            plan = LogicalOperator::SetProperty(set);
+        }
+
+        Ok(plan)
+    }
+
+    /// Translates the items of a SET clause into one update per item.
+    ///
+    /// Each update's input is left as [`LogicalOperator::Empty`] for the
+    /// caller to fill in.
+    fn translate_set_items(&self, set_clause: &ast::SetClause) -> Result<Vec<SetPropertyOp>> {
+        let mut updates = Vec::new();
 
-        // Group items by variable
         for item in &set_clause.items {
-            match item {
+            let (variable, properties, replace) = match item {
                 ast::SetItem::Property {
                     variable,
                     property,
@@ -982,12 +1071,7 @@ impl CypherTranslator {
                 } => {
                     // SET n.prop = value
                     let value_expr = self.translate_expression(value)?;
-                    plan = LogicalOperator::SetProperty(SetPropertyOp {
-                        variable: variable.clone(),
-                        properties: vec![(property.clone(), value_expr)],
-                        replace: false,
-                        input: Box::new(plan),
-                    });
+                    (variable, vec![(property.clone(), value_expr)], false)
                 }
                 ast::SetItem::AllProperties {
                     variable,
@@ -995,12 +1079,7 @@ impl CypherTranslator {
                 } => {
                     // SET n = {...} or SET n = m
                     let value_expr = self.translate_expression(properties)?;
-                    plan = LogicalOperator::SetProperty(SetPropertyOp {
-                        variable: variable.clone(),
-                        properties: vec![("*".to_string(), value_expr)],
-                        replace: true,
-                        input: Box::new(plan),
-                    });
+                    (variable, vec![("*".to_string(), value_expr)], true)
                 }
                 ast::SetItem::MergeProperties {
                     variable,
@@ -1008,20 +1087,22 @@ impl CypherTranslator {
                 } => {
                     // SET n += {...}
                     let value_expr = self.translate_expression(properties)?;
-                    plan = LogicalOperator::SetProperty(SetPropertyOp {
-                        variable: variable.clone(),
-                        properties: vec![("*".to_string(), value_expr)],
-                        replace: false,
-                        input: Box::new(plan),
-                    });
+                    (variable, vec![("*".to_string(), value_expr)], false)
                 }
                 ast::SetItem::Labels { .. } => {
                     return Err(Error::Internal("SET labels not yet supported".into()));
                 }
-            }
+            };
+            updates.push(SetPropertyOp {
+                variable: variable.clone(),
+                properties,
+                replace,
+                increment: false,
+                input: Box::new(LogicalOperator::Empty),
+            });
         }
 
-        Ok(plan)
+        Ok(updates)
     }
 
     fn translate_remove(
@@ -1044,6 +1125,7 @@ impl CypherTranslator {
                             LogicalExpression::Literal(Value::Null),
                         )],
                         replace: false,
+                        increment: false,
                         input: Box::new(plan),
                     });
                 }
@@ -1073,9 +1155,13 @@ impl CypherTranslator {
                         property: property.clone(),
                     })
                 } else {
-                    Err(Error::Internal(
-                        "Nested property access not supported".into(),
-                    ))
+                    // `{a: 1}.a` or `m.inner.key`: look the key up in the map
+                    Ok(LogicalExpression::IndexAccess {
+                        base: Box::new(self.translate_expression(base)?),
+                        index: Box::new(LogicalExpression::Literal(Value::String(
+                            property.clone().into(),
+                        ))),
+                    })
                 }
             }
             ast::Expression::IndexAccess { base, index } => {
@@ -1125,17 +1211,33 @@ impl CypherTranslator {
                 })
             }
             ast::Expression::FunctionCall { name, args, .. } => {
-                // Special handling for length() on path variables
-                // When length(p) is called where p is a path alias, we convert it
-                // to a variable reference to the path length column
+                // length(p) and cost(p, 'prop') are evaluated from the path value
                 if name.to_lowercase() == "length" && args.len() == 1 {
                     if let ast::Expression::Variable(var_name) = &args[0] {
-                        // Check if this looks like a path variable
-                        // Path lengths are stored in columns named _path_length_{alias}
-                        return Ok(LogicalExpression::Variable(format!(
-                            "_path_length_{}",
-                            var_name
-                        )));
+                        return Ok(LogicalExpression::PathLength(var_name.clone()));
+                    }
+                }
+                if name.to_lowercase() == "cost" && args.len() == 2 {
+                    if let ast::Expression::Variable(var_name) = &args[0] {
+                        let ast::Expression::Literal(ast::Literal::String(property)) = &args[1]
+                        else {
+                            return Err(Error::Internal(
+                                "cost() expects a property name string as its second argument"
+                                    .into(),
+                            ));
+                        };
+                        return Ok(LogicalExpression::PathCost {
+                            variable: var_name.clone(),
+                            property: property.clone(),
+                        });
+                    }
+                }
+
+                // size((p)-->()) counts the pattern's matches for each row
+                if name.to_lowercase() == "size" && args.len() == 1 {
+                    if let ast::Expression::PatternExpression(pattern) = &args[0] {
+                        let subplan = self.translate_pattern(pattern, None)?;
+                        return Ok(LogicalExpression::CountSubquery(Box::new(subplan)));
                     }
                 }
 
@@ -1227,6 +1329,11 @@ impl CypherTranslator {
             ast::Expression::PatternComprehension { .. } => Err(Error::Internal(
                 "Pattern comprehension not yet supported".into(),
             )),
+            ast::Expression::PatternExpression(pattern) => {
+                // A bare pattern is true when it has at least one match
+                let subplan = self.translate_pattern(pattern, None)?;
+                Ok(LogicalExpression::ExistsSubquery(Box::new(subplan)))
+            }
             ast::Expression::Exists(_) => Err(Error::Internal("EXISTS not yet supported".into())),
             ast::Expression::CountSubquery(_) => {
                 Err(Error::Internal("COUNT subquery not yet supported".into()))
@@ -1390,7 +1497,7 @@ mod tests {
             assert_eq!(ret.items.len(), 1);
             if let LogicalOperator::NodeScan(scan) = ret.input.as_ref() {
                 assert_eq!(scan.variable, "n");
-                assert_eq!(scan.label, Some("Person".into()));
+                assert_eq!(scan.labels, vec!["Person".to_string()]);
             } else {
                 panic!("Expected NodeScan");
             }
@@ -1462,6 +1569,56 @@ mod tests {
         assert_eq!(expand.edge_type.as_deref(), Some("KNOWS"));
     }
 
+    #[test]
+    fn test_translate_relationship_variable_binding() {
+        let plan = translate("MATCH (a:Person)-[r:KNOWS]->(b:Person) RETURN r.since").unwrap();
+
+        fn find_expand(op: &LogicalOperator) -> Option<&ExpandOp> {
+            match op {
+                LogicalOperator::Expand(e) => Some(e),
+                LogicalOperator::Return(r) => find_expand(&r.input),
+                LogicalOperator::Filter(f) => find_expand(&f.input),
+                _ => None,
+            }
+        }
+
+        let expand = find_expand(&plan.root).expect("Expected Expand");
+        assert_eq!(expand.edge_variable.as_deref(), Some("r"));
+    }
+
+    #[test]
+    fn test_translate_anonymous_relationship_with_properties() {
+        let plan = translate("MATCH (a)-[:KNOWS {since: 2020}]->(b) RETURN b").unwrap();
+
+        if let LogicalOperator::Return(ret) = &plan.root {
+            if let LogicalOperator::Filter(filter) = ret.input.as_ref() {
+                let expand = match filter.input.as_ref() {
+                    LogicalOperator::Expand(e) => e,
+                    _ => panic!("Expected Expand"),
+                };
+                // The unnamed edge gets a binding so the filter can reference it
+                let edge_var = expand
+                    .edge_variable
+                    .as_deref()
+                    .expect("Expected edge binding");
+                if let LogicalExpression::Binary { left, .. } = &filter.predicate {
+                    if let LogicalExpression::Property { variable, property } = left.as_ref() {
+                        assert_eq!(variable, edge_var);
+                        assert_eq!(property, "since");
+                    } else {
+                        panic!("Expected Property");
+                    }
+                } else {
+                    panic!("Expected Binary");
+                }
+            } else {
+                panic!("Expected Filter");
+            }
+        } else {
+            panic!("Expected Return");
+        }
+    }
+
     #[test]
     fn test_translate_incoming_relationship() {
         let plan = translate("MATCH (a:Person)<-[:KNOWS]-(b:Person) RETURN a, b").unwrap();
@@ -1537,7 +1694,7 @@ mod tests {
             assert_eq!(delete.variable, "n");
             if let LogicalOperator::NodeScan(scan) = delete.input.as_ref() {
                 assert_eq!(scan.variable, "n");
-                assert_eq!(scan.label, Some("Person".into()));
+                assert_eq!(scan.labels, vec!["Person".to_string()]);
             } else {
                 panic!("Expected NodeScan input");
             }
@@ -1771,10 +1928,36 @@ mod tests {
 
         if let LogicalOperator::Merge(merge) = &plan.root {
             assert_eq!(merge.on_create.len(), 1);
-            assert_eq!(merge.on_create[0].0, "created");
+            assert_eq!(merge.on_create[0].variable, "n");
+            assert_eq!(merge.on_create[0].properties[0].0, "created");
+        } else {
+            panic!("Expected Merge, got {:?}", plan.root);
+        }
+    }
+
+    #[test]
+    fn test_translate_merge_relationship() {
+        let plan = translate(
+            "MATCH (a:Person), (b:Person) MERGE (a)-[r:KNOWS {since: 2020}]->(b) \
+             ON MATCH SET r.seen = true",
+        )
+        .unwrap();
+
+        if let LogicalOperator::Merge(merge) = &plan.root {
+            assert_eq!(merge.variable, "a");
+            let rel = merge.relationship.as_ref().expect("relationship");
+            assert_eq!(rel.variable.as_deref(), Some("r"));
+            assert_eq!(rel.edge_type, "KNOWS");
+            assert_eq!(rel.direction, ExpandDirection::Outgoing);
+            assert_eq!(rel.properties[0].0, "since");
+            assert_eq!(rel.target_variable, "b");
+            assert_eq!(merge.on_match[0].variable, "r");
         } else {
             panic!("Expected Merge, got {:?}", plan.root);
         }
+
+        assert!(translate("MERGE (a)-[:R]->(b)-[:R]->(c)").is_err());
+        assert!(translate("MERGE (a)-[:R*1..2]->(b)").is_err());
     }
 
     // === Expression Tests ===
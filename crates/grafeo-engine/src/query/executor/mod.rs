@@ -3,12 +3,14 @@
 //! Executes physical plans and produces results.
 
 use crate::config::AdaptiveConfig;
-use crate::database::QueryResult;
+use crate::database::{QueryResult, QueryStats};
+use crate::query::planner::PhysicalPlan;
 use grafeo_common::types::{LogicalType, Value};
-use grafeo_common::utils::error::{Error, Result};
+use grafeo_common::utils::error::{Error, QueryError, QueryErrorKind, Result, TransactionError};
 use grafeo_core::execution::operators::{Operator, OperatorError};
 use grafeo_core::execution::{
-    AdaptiveContext, AdaptiveSummary, CardinalityTrackingWrapper, DataChunk, SharedAdaptiveContext,
+    AdaptiveContext, AdaptiveSummary, CardinalityTrackingWrapper, DataChunk, MaterializingSink,
+    SharedAdaptiveContext, Sink,
 };
 
 /// Executes a physical operator tree and collects results.
@@ -75,6 +77,17 @@ impl Executor {
         Ok(result)
     }
 
+    /// Executes a physical plan, recording its statistics in the result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if operator execution fails.
+    pub fn execute_plan(&self, plan: &mut PhysicalPlan) -> Result<QueryResult> {
+        let mut result = self.execute(plan.operator.as_mut())?;
+        result.stats = plan.stats();
+        Ok(result)
+    }
+
     /// Executes and returns at most `limit` rows.
     ///
     /// # Errors
@@ -280,6 +293,221 @@ impl Default for Executor {
     }
 }
 
+/// The rows of a query, all at once or as a stream.
+///
+/// Returned by [`Session::run`](crate::Session::run), which picks the
+/// variant from the session's [`ResultMode`](crate::ResultMode).
+pub enum QueryOutput {
+    /// Every row, already collected.
+    Materialized(QueryResult),
+    /// Rows produced as they're pulled.
+    Streaming(ResultStream),
+}
+
+impl QueryOutput {
+    /// Returns true if rows are produced as they're pulled.
+    #[must_use]
+    pub fn is_streaming(&self) -> bool {
+        matches!(self, Self::Streaming(_))
+    }
+
+    /// Returns the column names.
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        match self {
+            Self::Materialized(result) => &result.columns,
+            Self::Streaming(stream) => stream.columns(),
+        }
+    }
+
+    /// Returns every row, draining a stream if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rest of a streaming query fails.
+    pub fn into_result(self) -> Result<QueryResult> {
+        match self {
+            Self::Materialized(result) => Ok(result),
+            Self::Streaming(stream) => stream.materialize(),
+        }
+    }
+}
+
+/// Rows of a query, produced as they're pulled.
+///
+/// The query runs one chunk at a time as the iterator advances, so only
+/// the current chunk is held in memory. Dropping the stream stops the
+/// query; any writes it made are undone unless every row has been read.
+pub struct ResultStream {
+    plan: PhysicalPlan,
+    /// Column types, known once the first chunk arrives.
+    column_types: Vec<LogicalType>,
+    /// Rows of the current chunk not yet returned.
+    pending: std::vec::IntoIter<Vec<Value>>,
+    done: bool,
+    /// Ends the statement the stream runs as, if not ended yet.
+    finish: Option<StreamFinish>,
+}
+
+/// Ends a streamed statement: `true` once every row has been produced,
+/// `false` if the query failed or the stream was dropped first.
+type StreamFinish = Box<dyn FnOnce(bool) -> Result<()> + Send>;
+
+impl ResultStream {
+    /// Creates a stream over a physical plan's rows.
+    #[must_use]
+    pub fn new(plan: PhysicalPlan) -> Self {
+        let column_types = vec![LogicalType::Any; plan.columns.len()];
+        Self {
+            plan,
+            column_types,
+            pending: Vec::new().into_iter(),
+            done: false,
+            finish: None,
+        }
+    }
+
+    /// Runs `finish` when the stream ends, with `true` once every row has
+    /// been produced and `false` if the query fails or the stream is
+    /// dropped first. An error from it is returned in place of the end of
+    /// the rows.
+    #[must_use]
+    pub(crate) fn with_finish(
+        mut self,
+        finish: impl FnOnce(bool) -> Result<()> + Send + 'static,
+    ) -> Self {
+        self.finish = Some(Box::new(finish));
+        self
+    }
+
+    /// Returns the column names.
+    #[must_use]
+    pub fn columns(&self) -> &[String] {
+        &self.plan.columns
+    }
+
+    /// Returns the statistics gathered over the rows pulled so far.
+    #[must_use]
+    pub fn stats(&self) -> QueryStats {
+        self.plan.stats()
+    }
+
+    /// Collects the remaining rows into a [`QueryResult`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if operator execution fails.
+    pub fn materialize(mut self) -> Result<QueryResult> {
+        let mut sink = MaterializingSink::new();
+        while !self.done {
+            match self.plan.operator.next() {
+                Ok(Some(chunk)) => {
+                    self.capture_column_types(&chunk);
+                    if let Err(err) = sink.consume(chunk) {
+                        return Err(self.fail(err));
+                    }
+                }
+                Ok(None) => {
+                    self.done = true;
+                    self.end(true)?;
+                }
+                Err(err) => return Err(self.fail(err)),
+            }
+        }
+
+        let mut result =
+            QueryResult::with_types(self.plan.columns.clone(), self.column_types.clone());
+        result.rows.extend(self.pending.by_ref());
+        for chunk in sink.chunks() {
+            result.rows.extend(chunk_rows(chunk));
+        }
+        result.stats = self.plan.stats();
+        Ok(result)
+    }
+
+    /// Ends the stream's statement, if it hasn't ended yet.
+    fn end(&mut self, completed: bool) -> Result<()> {
+        self.finish
+            .take()
+            .map_or(Ok(()), |finish| finish(completed))
+    }
+
+    /// Ends the stream after its query failed with `err`.
+    fn fail(&mut self, err: OperatorError) -> Error {
+        self.done = true;
+        // The query's own error says more than one from undoing it
+        let _ = self.end(false);
+        convert_operator_error(err)
+    }
+
+    /// Records column types from the first chunk with columns.
+    fn capture_column_types(&mut self, chunk: &DataChunk) {
+        if chunk.column_count() == 0 || self.column_types.iter().any(|t| *t != LogicalType::Any) {
+            return;
+        }
+        self.column_types = (0..chunk.column_count())
+            .map(|col_idx| {
+                chunk
+                    .column(col_idx)
+                    .map(|col| col.data_type().clone())
+                    .unwrap_or(LogicalType::Any)
+            })
+            .collect();
+    }
+}
+
+impl Iterator for ResultStream {
+    type Item = Result<Vec<Value>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.pending.next() {
+                return Some(Ok(row));
+            }
+            if self.done {
+                return None;
+            }
+            match self.plan.operator.next() {
+                Ok(Some(chunk)) => {
+                    self.capture_column_types(&chunk);
+                    self.pending = chunk_rows(&chunk).into_iter();
+                }
+                Ok(None) => {
+                    self.done = true;
+                    if let Err(err) = self.end(true) {
+                        return Some(Err(err));
+                    }
+                }
+                Err(err) => return Some(Err(self.fail(err))),
+            }
+        }
+    }
+}
+
+impl Drop for ResultStream {
+    fn drop(&mut self) {
+        let _ = self.end(false);
+    }
+}
+
+/// Reads the selected rows of a chunk as values.
+fn chunk_rows(chunk: &DataChunk) -> Vec<Vec<Value>> {
+    let col_count = chunk.column_count();
+    chunk
+        .selected_indices()
+        .map(|row_idx| {
+            (0..col_count)
+                .map(|col_idx| {
+                    chunk
+                        .column(col_idx)
+                        .and_then(|col| col.get_value(row_idx))
+                        .unwrap_or(Value::Null)
+                })
+                .collect()
+        })
+        .collect()
+}
+
 /// Converts an operator error to a common error.
 fn convert_operator_error(err: OperatorError) -> Error {
     match err {
@@ -288,6 +516,18 @@ fn convert_operator_error(err: OperatorError) -> Error {
             Error::InvalidValue(format!("Column not found: {name}"))
         }
         OperatorError::Execution(msg) => Error::Internal(msg),
+        err @ OperatorError::MemoryLimitExceeded { .. } => {
+            Error::Query(QueryError::new(QueryErrorKind::Execution, err.to_string()))
+        }
+        err @ OperatorError::Cancelled => {
+            Error::Query(QueryError::new(QueryErrorKind::Cancelled, err.to_string()))
+        }
+        err @ OperatorError::TimedOut => {
+            Error::Query(QueryError::new(QueryErrorKind::Timeout, err.to_string()))
+        }
+        OperatorError::WriteConflict(msg) => {
+            Error::Transaction(TransactionError::WriteConflict(msg))
+        }
     }
 }
 
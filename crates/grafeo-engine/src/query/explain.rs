@@ -0,0 +1,277 @@
+//! Query plans annotated with the optimizer's estimates.
+//!
+//! [`Optimizer::explain()`](crate::query::Optimizer::explain) walks an
+//! optimized plan and records, for every operator, how many rows the
+//! [`CardinalityEstimator`](crate::query::CardinalityEstimator) expects it to
+//! produce and what the [`CostModel`](crate::query::optimizer::CostModel)
+//! charges for it. Comparing these against what a query actually does is the
+//! quickest way to spot a bad estimate behind a poor join order or a missed
+//! index.
+//!
+//! [`PhysicalPlan::explain()`](crate::query::PhysicalPlan::explain) does the
+//! same for the operators a plan will run, and after `EXPLAIN ANALYZE` also
+//! shows what each of them actually did.
+//!
+//! The text form indents children under their parent:
+//!
+//! ```text
+//! Return (rows: 3, cost: 3.0, total: 58.5)
+//!   Filter (rows: 3, cost: 10.0, total: 55.5)
+//!     NodeScan n:Person (rows: 10, cost: 45.5, total: 45.5)
+//! ```
+
+use std::fmt;
+use std::sync::Arc;
+
+use grafeo_common::types::Value;
+use grafeo_core::execution::{OperatorProfile, OperatorStats};
+use serde::Serialize;
+
+use crate::database::QueryResult;
+use crate::query::plan::{ExpandDirection, LogicalOperator};
+
+/// One operator of an explained plan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExplainNode {
+    /// The operator kind, like `NodeScan` or `Expand`.
+    pub operator: String,
+    /// A short description of what the operator works on, if any.
+    pub details: String,
+    /// Rows the optimizer expects this operator to produce.
+    pub estimated_rows: f64,
+    /// Abstract cost of this operator alone.
+    pub cost: f64,
+    /// Abstract cost of this operator and everything below it.
+    pub total_cost: f64,
+    /// What the operator actually did, once the plan has run profiled.
+    pub actual: Option<OperatorStats>,
+    /// The operator's inputs.
+    pub children: Vec<ExplainNode>,
+}
+
+/// A plan annotated with per-operator estimates.
+///
+/// Get one from [`Session::explain()`](crate::Session::explain) or
+/// [`Session::explain_analyze()`](crate::Session::explain_analyze). Display
+/// it for a readable tree, or use [`to_json()`](Self::to_json) for tooling.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanExplanation {
+    /// The plan's root operator.
+    pub root: ExplainNode,
+}
+
+impl PlanExplanation {
+    /// Returns the explanation as a JSON document.
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Returns the operators in pre-order: each operator before its inputs.
+    #[must_use]
+    pub fn operators(&self) -> Vec<&ExplainNode> {
+        let mut operators = Vec::new();
+        let mut pending = vec![&self.root];
+        while let Some(node) = pending.pop() {
+            operators.push(node);
+            pending.extend(node.children.iter().rev());
+        }
+        operators
+    }
+
+    /// Returns the explanation the way an `EXPLAIN` statement does: one row
+    /// per operator in pre-order, numbered from 0, each listing its
+    /// children's numbers.
+    ///
+    /// The columns are `id`, `operator`, `details`, `estimated_rows`, and
+    /// `children`; analyzed plans add `rows`, `elapsed`, and `peak_memory`.
+    pub(crate) fn to_result(&self) -> QueryResult {
+        fn add_rows(node: &ExplainNode, rows: &mut Vec<Vec<Value>>) -> i64 {
+            let id = rows.len();
+            let mut row = vec![
+                Value::Int64(id as i64),
+                Value::from(node.operator.as_str()),
+                Value::from(node.details.as_str()),
+                Value::Float64(node.estimated_rows),
+                Value::Null,
+            ];
+            if let Some(actual) = node.actual {
+                row.extend([
+                    Value::Int64(actual.rows as i64),
+                    Value::Duration(actual.elapsed.as_micros() as i64),
+                    Value::Int64(actual.peak_memory as i64),
+                ]);
+            }
+            rows.push(row);
+            let children: Vec<Value> = node
+                .children
+                .iter()
+                .map(|child| Value::Int64(add_rows(child, rows)))
+                .collect();
+            rows[id][4] = Value::List(Arc::from(children));
+            id as i64
+        }
+
+        let mut columns = vec!["id", "operator", "details", "estimated_rows", "children"];
+        if self.root.actual.is_some() {
+            columns.extend(["rows", "elapsed", "peak_memory"]);
+        }
+        let mut result = QueryResult::new(columns.into_iter().map(String::from).collect());
+        add_rows(&self.root, &mut result.rows);
+        result
+    }
+}
+
+/// An operator of a physical plan, as the planner recorded it.
+pub(crate) struct PlannedOperator {
+    pub(crate) operator: &'static str,
+    pub(crate) details: String,
+    pub(crate) estimated_rows: f64,
+    pub(crate) cost: f64,
+    /// Filled in as the plan runs, if it was planned for profiling.
+    pub(crate) profile: Option<OperatorProfile>,
+    /// Where the planned operator lives, to spot an operator planned to
+    /// its input unchanged.
+    pub(crate) address: Option<usize>,
+    pub(crate) children: Vec<PlannedOperator>,
+}
+
+impl PlannedOperator {
+    /// Explains this operator and its inputs with what they've done so far.
+    pub(crate) fn explain(&self) -> ExplainNode {
+        let children: Vec<ExplainNode> = self.children.iter().map(Self::explain).collect();
+        ExplainNode {
+            operator: self.operator.to_string(),
+            details: self.details.clone(),
+            estimated_rows: self.estimated_rows,
+            cost: self.cost,
+            total_cost: self.cost + children.iter().map(|c| c.total_cost).sum::<f64>(),
+            actual: self.profile.as_ref().map(OperatorProfile::stats),
+            children,
+        }
+    }
+}
+
+impl fmt::Display for PlanExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn write_node(f: &mut fmt::Formatter<'_>, node: &ExplainNode, depth: usize) -> fmt::Result {
+            write!(f, "{:indent$}{}", "", node.operator, indent = depth * 2)?;
+            if !node.details.is_empty() {
+                write!(f, " {}", node.details)?;
+            }
+            write!(
+                f,
+                " (rows: {:.0}, cost: {:.1}, total: {:.1}",
+                node.estimated_rows, node.cost, node.total_cost
+            )?;
+            match node.actual {
+                Some(actual) => writeln!(
+                    f,
+                    ") (actual rows: {}, time: {:.3?}, peak memory: {} bytes)",
+                    actual.rows, actual.elapsed, actual.peak_memory
+                )?,
+                None => writeln!(f, ")")?,
+            }
+            for child in &node.children {
+                write_node(f, child, depth + 1)?;
+            }
+            Ok(())
+        }
+        write_node(f, &self.root, 0)
+    }
+}
+
+/// Names an operator and summarizes what it works on.
+pub(crate) fn describe(op: &LogicalOperator) -> (&'static str, String) {
+    match op {
+        LogicalOperator::NodeScan(scan) => {
+            let mut details = scan.variable.clone();
+            for label in &scan.labels {
+                details.push(':');
+                details.push_str(label);
+            }
+            ("NodeScan", details)
+        }
+        LogicalOperator::EdgeScan(scan) => {
+            let edge_type = scan
+                .edge_type
+                .as_ref()
+                .map_or(String::new(), |t| format!(":{t}"));
+            ("EdgeScan", format!("{}{edge_type}", scan.variable))
+        }
+        LogicalOperator::Expand(expand) => {
+            let edge_type = expand
+                .edge_type
+                .as_ref()
+                .map_or(String::new(), |t| format!(":{t}"));
+            let hops = match (expand.min_hops, expand.max_hops) {
+                (1, Some(1)) => String::new(),
+                (min, Some(max)) => format!("*{min}..{max}"),
+                (min, None) => format!("*{min}.."),
+            };
+            let (left, right) = match expand.direction {
+                ExpandDirection::Outgoing => ("-", "->"),
+                ExpandDirection::Incoming => ("<-", "-"),
+                ExpandDirection::Both => ("-", "-"),
+            };
+            (
+                "Expand",
+                format!(
+                    "({}){left}[{edge_type}{hops}]{right}({})",
+                    expand.from_variable, expand.to_variable
+                ),
+            )
+        }
+        LogicalOperator::Filter(_) => ("Filter", String::new()),
+        LogicalOperator::Project(_) => ("Project", String::new()),
+        LogicalOperator::Join(join) => ("Join", format!("{:?}", join.join_type)),
+        LogicalOperator::Aggregate(agg) => (
+            "Aggregate",
+            format!(
+                "{} group keys, {} aggregates",
+                agg.group_by.len(),
+                agg.aggregates.len()
+            ),
+        ),
+        LogicalOperator::Limit(limit) => ("Limit", limit.count.to_string()),
+        LogicalOperator::Skip(skip) => ("Skip", skip.count.to_string()),
+        LogicalOperator::Sort(_) => ("Sort", String::new()),
+        LogicalOperator::TopN(top) => ("TopN", top.count.to_string()),
+        LogicalOperator::Distinct(_) => ("Distinct", String::new()),
+        LogicalOperator::CreateNode(_) => ("CreateNode", String::new()),
+        LogicalOperator::CreateEdge(_) => ("CreateEdge", String::new()),
+        LogicalOperator::DeleteNode(_) => ("DeleteNode", String::new()),
+        LogicalOperator::DeleteEdge(_) => ("DeleteEdge", String::new()),
+        LogicalOperator::SetProperty(_) => ("SetProperty", String::new()),
+        LogicalOperator::AddLabel(_) => ("AddLabel", String::new()),
+        LogicalOperator::RemoveLabel(_) => ("RemoveLabel", String::new()),
+        LogicalOperator::Return(_) => ("Return", String::new()),
+        LogicalOperator::Empty => ("Empty", String::new()),
+        LogicalOperator::TripleScan(_) => ("TripleScan", String::new()),
+        LogicalOperator::Union(_) => ("Union", String::new()),
+        LogicalOperator::LeftJoin(_) => ("LeftJoin", String::new()),
+        LogicalOperator::AntiJoin(_) => ("AntiJoin", String::new()),
+        LogicalOperator::Bind(_) => ("Bind", String::new()),
+        LogicalOperator::Unwind(_) => ("Unwind", String::new()),
+        LogicalOperator::ForEach(_) => ("ForEach", String::new()),
+        LogicalOperator::Merge(_) => ("Merge", String::new()),
+        LogicalOperator::ShortestPath(path) => (
+            "ShortestPath",
+            format!("({})..({})", path.source_var, path.target_var),
+        ),
+        LogicalOperator::Construct(construct) => (
+            "Construct",
+            format!("{} templates", construct.templates.len()),
+        ),
+        LogicalOperator::InsertTriple(_) => ("InsertTriple", String::new()),
+        LogicalOperator::DeleteTriple(_) => ("DeleteTriple", String::new()),
+        LogicalOperator::Modify(_) => ("Modify", String::new()),
+        LogicalOperator::ClearGraph(_) => ("ClearGraph", String::new()),
+        LogicalOperator::CreateGraph(_) => ("CreateGraph", String::new()),
+        LogicalOperator::DropGraph(_) => ("DropGraph", String::new()),
+        LogicalOperator::LoadGraph(_) => ("LoadGraph", String::new()),
+        LogicalOperator::CopyGraph(_) => ("CopyGraph", String::new()),
+        LogicalOperator::MoveGraph(_) => ("MoveGraph", String::new()),
+        LogicalOperator::AddGraph(_) => ("AddGraph", String::new()),
+    }
+}
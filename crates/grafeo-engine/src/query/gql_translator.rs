@@ -9,6 +9,7 @@ use crate::query::plan::{
     ProjectOp, Projection, RemoveLabelOp, ReturnItem, ReturnOp, SetPropertyOp, ShortestPathOp,
     SkipOp, SortKey, SortOp, SortOrder, UnaryOp, UnwindOp,
 };
+use grafeo_adapters::query::DEFAULT_MAX_NESTING_DEPTH;
 use grafeo_adapters::query::gql::{self, ast};
 use grafeo_common::types::Value;
 use grafeo_common::utils::error::{Error, Result};
@@ -19,7 +20,18 @@ use grafeo_common::utils::error::{Error, Result};
 ///
 /// Returns an error if the query cannot be parsed or translated.
 pub fn translate(query: &str) -> Result<LogicalPlan> {
-    let statement = gql::parse(query)?;
+    translate_with_max_depth(query, DEFAULT_MAX_NESTING_DEPTH)
+}
+
+/// Translates a GQL query string to a logical plan, rejecting expressions
+/// nested deeper than `max_depth`.
+///
+/// # Errors
+///
+/// Returns an error if the query cannot be parsed or translated, or is
+/// nested too deeply.
+pub fn translate_with_max_depth(query: &str, max_depth: usize) -> Result<LogicalPlan> {
+    let statement = gql::parse_with_max_depth(query, max_depth)?;
     let translator = GqlTranslator::new();
     translator.translate_statement(&statement)
 }
@@ -102,36 +114,21 @@ impl GqlTranslator {
                 }
             };
 
-            // Translate ON CREATE properties
-            let on_create: Vec<(String, LogicalExpression)> = merge_clause
-                .on_create
-                .as_ref()
-                .map(|assignments| {
-                    assignments
-                        .iter()
-                        .map(|a| Ok((a.property.clone(), self.translate_expression(&a.value)?)))
-                        .collect::<Result<Vec<_>>>()
-                })
-                .transpose()?
-                .unwrap_or_default();
-
-            // Translate ON MATCH properties
-            let on_match: Vec<(String, LogicalExpression)> = merge_clause
-                .on_match
-                .as_ref()
-                .map(|assignments| {
-                    assignments
-                        .iter()
-                        .map(|a| Ok((a.property.clone(), self.translate_expression(&a.value)?)))
-                        .collect::<Result<Vec<_>>>()
-                })
-                .transpose()?
-                .unwrap_or_default();
+            // ON CREATE / ON MATCH become updates that only run on their branch
+            let on_create = match &merge_clause.on_create {
+                Some(assignments) => self.translate_merge_assignments(assignments)?,
+                None => Vec::new(),
+            };
+            let on_match = match &merge_clause.on_match {
+                Some(assignments) => self.translate_merge_assignments(assignments)?,
+                None => Vec::new(),
+            };
 
             plan = LogicalOperator::Merge(MergeOp {
                 variable,
                 labels,
                 match_properties,
+                relationship: None,
                 on_create,
                 on_match,
                 input: Box::new(plan),
@@ -149,6 +146,22 @@ impl GqlTranslator {
 
         // Handle SET clauses
         for set_clause in &query.set_clauses {
+            // Handle map assignments (SET n = {...} and SET n += {...}) first,
+            // so a replace doesn't drop properties set alongside it
+            for assignment in &set_clause.map_assignments {
+                let entries = assignment
+                    .properties
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), self.translate_expression(value)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                plan = LogicalOperator::SetProperty(SetPropertyOp {
+                    variable: assignment.variable.clone(),
+                    properties: vec![("*".to_string(), LogicalExpression::Map(entries))],
+                    replace: assignment.replace,
+                    increment: false,
+                    input: Box::new(plan),
+                });
+            }
             // Handle property assignments
             for assignment in &set_clause.assignments {
                 let value = self.translate_expression(&assignment.value)?;
@@ -156,6 +169,7 @@ impl GqlTranslator {
                     variable: assignment.variable.clone(),
                     properties: vec![(assignment.property.clone(), value)],
                     replace: false,
+                    increment: assignment.increment,
                     input: Box::new(plan),
                 });
             }
@@ -185,6 +199,7 @@ impl GqlTranslator {
                     variable: variable.clone(),
                     properties: vec![(property.clone(), LogicalExpression::Literal(Value::Null))],
                     replace: false,
+                    increment: false,
                     input: Box::new(plan),
                 });
             }
@@ -242,26 +257,6 @@ impl GqlTranslator {
             }
         }
 
-        // Apply SKIP
-        if let Some(skip_expr) = &query.return_clause.skip {
-            if let ast::Expression::Literal(ast::Literal::Integer(n)) = skip_expr {
-                plan = LogicalOperator::Skip(SkipOp {
-                    count: *n as usize,
-                    input: Box::new(plan),
-                });
-            }
-        }
-
-        // Apply LIMIT
-        if let Some(limit_expr) = &query.return_clause.limit {
-            if let ast::Expression::Literal(ast::Literal::Integer(n)) = limit_expr {
-                plan = LogicalOperator::Limit(LimitOp {
-                    count: *n as usize,
-                    input: Box::new(plan),
-                });
-            }
-        }
-
         // Check if RETURN contains aggregate functions
         let has_aggregates = query
             .return_clause
@@ -313,6 +308,8 @@ impl GqlTranslator {
                 });
             }
 
+            plan = Self::apply_skip_limit(plan, &query.return_clause);
+
             // Note: For aggregate queries, we don't add a Return operator
             // because Aggregate already produces the final output
         } else {
@@ -338,6 +335,8 @@ impl GqlTranslator {
                 });
             }
 
+            plan = Self::apply_skip_limit(plan, &query.return_clause);
+
             // Apply RETURN
             let return_items = query
                 .return_clause
@@ -361,6 +360,26 @@ impl GqlTranslator {
         Ok(LogicalPlan::new(plan))
     }
 
+    /// Wraps the plan in SKIP and LIMIT, applied after ORDER BY.
+    fn apply_skip_limit(
+        mut plan: LogicalOperator,
+        return_clause: &ast::ReturnClause,
+    ) -> LogicalOperator {
+        if let Some(ast::Expression::Literal(ast::Literal::Integer(n))) = &return_clause.skip {
+            plan = LogicalOperator::Skip(SkipOp {
+                count: *n as usize,
+                input: Box::new(plan),
+            });
+        }
+        if let Some(ast::Expression::Literal(ast::Literal::Integer(n))) = &return_clause.limit {
+            plan = LogicalOperator::Limit(LimitOp {
+                count: *n as usize,
+                input: Box::new(plan),
+            });
+        }
+        plan
+    }
+
     /// Builds return items for an aggregate query.
     #[allow(dead_code)]
     fn build_aggregate_return_items(&self, items: &[ast::ReturnItem]) -> Result<Vec<ReturnItem>> {
@@ -428,7 +447,7 @@ impl GqlTranslator {
         input: Option<LogicalOperator>,
     ) -> Result<LogicalOperator> {
         // Extract source and target from the pattern
-        let (source_node, target_node, edge_type, direction) = match pattern {
+        let (source_node, target_node, edge_type, direction, max_hops) = match pattern {
             ast::Pattern::Path(path) => {
                 let target_node = if let Some(edge) = path.edges.last() {
                     &edge.target
@@ -447,7 +466,8 @@ impl GqlTranslator {
                         ast::EdgeDirection::Undirected => ExpandDirection::Both,
                     })
                     .unwrap_or(ExpandDirection::Both);
-                (&path.source, target_node, edge_type, direction)
+                let max_hops = path.edges.first().and_then(|e| e.max_hops);
+                (&path.source, target_node, edge_type, direction, max_hops)
             }
             ast::Pattern::Node(_) => {
                 return Err(Error::Internal(
@@ -482,6 +502,7 @@ impl GqlTranslator {
             target_var,
             edge_type,
             direction,
+            max_hops,
             path_alias: alias.unwrap_or("_path").to_string(),
             all_paths: matches!(path_function, ast::PathFunction::AllShortestPaths),
         }))
@@ -634,11 +655,11 @@ impl GqlTranslator {
             .clone()
             .unwrap_or_else(|| format!("_anon_{}", rand_id()));
 
-        let label = node.labels.first().cloned();
+        let labels = node.labels.clone();
 
         let mut plan = LogicalOperator::NodeScan(NodeScanOp {
             variable: variable.clone(),
-            label,
+            labels,
             input: input.map(Box::new),
         });
 
@@ -711,11 +732,11 @@ impl GqlTranslator {
             .clone()
             .unwrap_or_else(|| format!("_anon_{}", rand_id()));
 
-        let source_label = path.source.labels.first().cloned();
+        let source_labels = path.source.labels.clone();
 
         let mut plan = LogicalOperator::NodeScan(NodeScanOp {
             variable: source_var.clone(),
-            label: source_label,
+            labels: source_labels,
             input: input.map(Box::new),
         });
 
@@ -828,7 +849,7 @@ impl GqlTranslator {
         // Create a scan to find the entities to delete
         let scan = LogicalOperator::NodeScan(NodeScanOp {
             variable: first_var.clone(),
-            label: None,
+            labels: Vec::new(),
             input: None,
         });
 
@@ -868,28 +889,57 @@ impl GqlTranslator {
         // Create a scan to find the entity to update
         let scan = LogicalOperator::NodeScan(NodeScanOp {
             variable: var.clone(),
-            label: None,
+            labels: Vec::new(),
             input: None,
         });
 
-        // Build property assignments for this variable
-        let properties: Vec<(String, LogicalExpression)> = set
-            .assignments
-            .iter()
-            .filter(|a| &a.variable == var)
-            .map(|a| Ok((a.property.clone(), self.translate_expression(&a.value)?)))
-            .collect::<Result<_>>()?;
-
-        let plan = LogicalOperator::SetProperty(SetPropertyOp {
-            variable: var.clone(),
-            properties,
-            replace: false,
-            input: Box::new(scan),
-        });
+        // Build property assignments for this variable; increments get an
+        // operator of their own
+        let mut plan = scan;
+        for increment in [false, true] {
+            let properties: Vec<(String, LogicalExpression)> = set
+                .assignments
+                .iter()
+                .filter(|a| &a.variable == var && a.increment == increment)
+                .map(|a| Ok((a.property.clone(), self.translate_expression(&a.value)?)))
+                .collect::<Result<_>>()?;
+            if properties.is_empty() {
+                continue;
+            }
+            plan = LogicalOperator::SetProperty(SetPropertyOp {
+                variable: var.clone(),
+                properties,
+                replace: false,
+                increment,
+                input: Box::new(plan),
+            });
+        }
 
         Ok(LogicalPlan::new(plan))
     }
 
+    /// Translates the ON CREATE / ON MATCH assignments of a MERGE.
+    ///
+    /// Each update's input is left as [`LogicalOperator::Empty`]; the merge
+    /// runs them on its own output.
+    fn translate_merge_assignments(
+        &self,
+        assignments: &[ast::PropertyAssignment],
+    ) -> Result<Vec<SetPropertyOp>> {
+        assignments
+            .iter()
+            .map(|a| {
+                Ok(SetPropertyOp {
+                    variable: a.variable.clone(),
+                    properties: vec![(a.property.clone(), self.translate_expression(&a.value)?)],
+                    replace: false,
+                    increment: a.increment,
+                    input: Box::new(LogicalOperator::Empty),
+                })
+            })
+            .collect()
+    }
+
     fn translate_insert(&self, insert: &ast::InsertStatement) -> Result<LogicalPlan> {
         // For now, just translate insert patterns as creates
         // A full implementation would handle multiple patterns
@@ -972,17 +1022,25 @@ impl GqlTranslator {
                 args,
                 distinct,
             } => {
-                // Special handling for length() on path variables
-                // When length(p) is called where p is a path alias, we convert it
-                // to a variable reference to the path length column
+                // length(p) and cost(p, 'prop') are evaluated from the path value
                 if name.to_lowercase() == "length" && args.len() == 1 {
                     if let ast::Expression::Variable(var_name) = &args[0] {
-                        // Check if this looks like a path variable
-                        // Path lengths are stored in columns named _path_length_{alias}
-                        return Ok(LogicalExpression::Variable(format!(
-                            "_path_length_{}",
-                            var_name
-                        )));
+                        return Ok(LogicalExpression::PathLength(var_name.clone()));
+                    }
+                }
+                if name.to_lowercase() == "cost" && args.len() == 2 {
+                    if let ast::Expression::Variable(var_name) = &args[0] {
+                        let ast::Expression::Literal(ast::Literal::String(property)) = &args[1]
+                        else {
+                            return Err(Error::Internal(
+                                "cost() expects a property name string as its second argument"
+                                    .into(),
+                            ));
+                        };
+                        return Ok(LogicalExpression::PathCost {
+                            variable: var_name.clone(),
+                            property: property.clone(),
+                        });
                     }
                 }
 
@@ -1003,6 +1061,17 @@ impl GqlTranslator {
                     .collect::<Result<Vec<_>>>()?;
                 Ok(LogicalExpression::List(items))
             }
+            ast::Expression::Map(entries) => {
+                let entries = entries
+                    .iter()
+                    .map(|(key, value)| Ok((key.clone(), self.translate_expression(value)?)))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(LogicalExpression::Map(entries))
+            }
+            ast::Expression::IndexAccess { base, index } => Ok(LogicalExpression::IndexAccess {
+                base: Box::new(self.translate_expression(base)?),
+                index: Box::new(self.translate_expression(index)?),
+            }),
             ast::Expression::Case {
                 input,
                 whens,
@@ -1318,7 +1387,7 @@ mod tests {
         let plan = result.unwrap();
         if let LogicalOperator::Return(ret) = &plan.root {
             if let LogicalOperator::NodeScan(scan) = ret.input.as_ref() {
-                assert!(scan.label.is_none());
+                assert!(scan.labels.is_empty());
             } else {
                 panic!("Expected NodeScan operator");
             }
@@ -1644,6 +1713,7 @@ mod tests {
                 variable: "n".to_string(),
                 property: "name".to_string(),
                 value: ast::Expression::Literal(ast::Literal::String("Bob".to_string())),
+                increment: false,
             }],
             span: None,
         };
@@ -0,0 +1,630 @@
+//! Typed GraphQL schemas over the property graph.
+//!
+//! [`translate_nested`](super::graphql_translator::translate_nested) guesses
+//! the graph layout from field names. A [`GraphQLSchema`] states it instead:
+//! each object type maps to a node label, each relation field to an edge
+//! type and direction, and each relation says whether it holds one object
+//! or a list. Resolving a query against the schema checks every selected
+//! field and argument, and builds the plan from the declared mapping.
+//!
+//! ```
+//! use grafeo_engine::query::graphql_schema::{GraphQLSchema, ObjectType, RelationField};
+//!
+//! let schema = GraphQLSchema::new()
+//!     .with_type(
+//!         ObjectType::new("Person")
+//!             .with_property("name")
+//!             .with_relation("friends", RelationField::many("KNOWS", "Person"))
+//!             .with_relation("employer", RelationField::one("WORKS_AT", "Company")),
+//!     )
+//!     .with_type(ObjectType::new("Company").with_property("name"))
+//!     .with_query_list("people", "Person")
+//!     .with_query_one("person", "Person");
+//!
+//! let (plan, _shape) = schema.resolve("{ person(id: 1) { name employer { name } } }")?;
+//! assert!(schema.resolve("{ people { salary } }").is_err());
+//! # Ok::<(), grafeo_common::utils::error::Error>(())
+//! ```
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use grafeo_adapters::query::graphql::{self, ast};
+use grafeo_common::types::Value;
+use grafeo_common::utils::error::{Error, QueryError, QueryErrorKind, Result};
+
+use crate::format::ResultShape;
+use crate::query::graphql_translator::ID_COLUMN;
+use crate::query::plan::{
+    BinaryOp, ExpandDirection, ExpandOp, FilterOp, LeftJoinOp, LogicalExpression, LogicalOperator,
+    LogicalPlan, NodeScanOp, ReturnItem, ReturnOp,
+};
+
+/// Field that every object type has, resolving to the node id.
+const ID_FIELD: &str = "id";
+
+/// How many objects a field holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cardinality {
+    /// At most one object, or `null`.
+    One,
+    /// A list of objects.
+    Many,
+}
+
+/// A field that follows an edge to another object type.
+#[derive(Debug, Clone)]
+pub struct RelationField {
+    /// Edge type to follow.
+    pub edge_type: String,
+    /// Object type at the other end.
+    pub target: String,
+    /// Direction to follow the edge in.
+    pub direction: ExpandDirection,
+    /// Whether the field holds one object or a list.
+    pub cardinality: Cardinality,
+}
+
+impl RelationField {
+    /// A to-one relation following outgoing `edge_type` edges.
+    #[must_use]
+    pub fn one(edge_type: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            edge_type: edge_type.into(),
+            target: target.into(),
+            direction: ExpandDirection::Outgoing,
+            cardinality: Cardinality::One,
+        }
+    }
+
+    /// A to-many relation following outgoing `edge_type` edges.
+    #[must_use]
+    pub fn many(edge_type: impl Into<String>, target: impl Into<String>) -> Self {
+        Self {
+            cardinality: Cardinality::Many,
+            ..Self::one(edge_type, target)
+        }
+    }
+
+    /// Follows the edges from their target back to their source instead.
+    #[must_use]
+    pub fn incoming(mut self) -> Self {
+        self.direction = ExpandDirection::Incoming;
+        self
+    }
+}
+
+/// A GraphQL object type backed by nodes with one label.
+#[derive(Debug, Clone)]
+pub struct ObjectType {
+    /// The type's name in the schema.
+    pub name: String,
+    /// Label of the nodes this type resolves to.
+    pub label: String,
+    /// Scalar fields, read from node properties of the same name.
+    pub properties: Vec<String>,
+    /// Fields that follow edges, by field name.
+    pub relations: HashMap<String, RelationField>,
+}
+
+impl ObjectType {
+    /// Creates a type whose nodes carry a label of the same name.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            label: name.clone(),
+            name,
+            properties: Vec::new(),
+            relations: HashMap::new(),
+        }
+    }
+
+    /// Resolves the type to nodes with a different label.
+    #[must_use]
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = label.into();
+        self
+    }
+
+    /// Adds a scalar field read from the node property `name`.
+    #[must_use]
+    pub fn with_property(mut self, name: impl Into<String>) -> Self {
+        self.properties.push(name.into());
+        self
+    }
+
+    /// Adds a field that follows edges to another type.
+    #[must_use]
+    pub fn with_relation(mut self, name: impl Into<String>, relation: RelationField) -> Self {
+        self.relations.insert(name.into(), relation);
+        self
+    }
+
+    fn has_property(&self, name: &str) -> bool {
+        name == ID_FIELD || self.properties.iter().any(|p| p == name)
+    }
+}
+
+/// A root field of the `Query` type.
+#[derive(Debug, Clone)]
+struct QueryField {
+    /// Object type the field returns.
+    target: String,
+    cardinality: Cardinality,
+}
+
+/// A typed GraphQL schema mapping object types onto the graph.
+///
+/// See the [module docs](self) for an example.
+#[derive(Debug, Clone, Default)]
+pub struct GraphQLSchema {
+    /// Object types by name.
+    types: HashMap<String, ObjectType>,
+    /// Root query fields by name.
+    queries: HashMap<String, QueryField>,
+}
+
+impl GraphQLSchema {
+    /// Creates an empty schema.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an object type, replacing any type of the same name.
+    #[must_use]
+    pub fn with_type(mut self, object_type: ObjectType) -> Self {
+        self.types.insert(object_type.name.clone(), object_type);
+        self
+    }
+
+    /// Adds a root query field returning every node of `target`.
+    #[must_use]
+    pub fn with_query_list(mut self, name: impl Into<String>, target: impl Into<String>) -> Self {
+        self.queries.insert(
+            name.into(),
+            QueryField {
+                target: target.into(),
+                cardinality: Cardinality::Many,
+            },
+        );
+        self
+    }
+
+    /// Adds a root query field returning a single node of `target`, usually
+    /// picked with an `id` argument.
+    #[must_use]
+    pub fn with_query_one(mut self, name: impl Into<String>, target: impl Into<String>) -> Self {
+        self.queries.insert(
+            name.into(),
+            QueryField {
+                target: target.into(),
+                cardinality: Cardinality::One,
+            },
+        );
+        self
+    }
+
+    /// Returns the object type with the given name.
+    #[must_use]
+    pub fn object_type(&self, name: &str) -> Option<&ObjectType> {
+        self.types.get(name)
+    }
+
+    /// Resolves a GraphQL query against the schema.
+    ///
+    /// Returns a plan with one row per combination of nested objects, and
+    /// the shape that folds those rows into the response's `data` object.
+    /// Nested fields are outer-joined, so objects without related objects
+    /// come back with an empty list or `null`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query doesn't parse, isn't a query, or
+    /// selects a field or passes an argument the schema doesn't define.
+    pub fn resolve(&self, query: &str) -> Result<(LogicalPlan, ResultShape)> {
+        let doc = graphql::parse(query)?;
+        let operation = doc
+            .definitions
+            .iter()
+            .find_map(|def| match def {
+                ast::Definition::Operation(op) => Some(op),
+                _ => None,
+            })
+            .ok_or_else(|| schema_error("No operation found in document"))?;
+        if operation.operation != ast::OperationType::Query {
+            return Err(schema_error(
+                "Only queries can be resolved against a schema",
+            ));
+        }
+
+        let field = match operation.selection_set.selections.as_slice() {
+            [ast::Selection::Field(field)] => field,
+            [] => return Err(schema_error("Empty selection set")),
+            _ => {
+                return Err(schema_error("Queries must select exactly one root field"));
+            }
+        };
+        let root = self.queries.get(&field.name).ok_or_else(|| {
+            schema_error(format!(
+                "Cannot query field \"{}\" on type \"Query\".",
+                field.name
+            ))
+        })?;
+        let object_type = self.type_named(&root.target)?;
+
+        let resolver = Resolver {
+            schema: self,
+            var_counter: AtomicU32::new(0),
+        };
+        let var = resolver.next_var();
+        let mut plan = LogicalOperator::NodeScan(NodeScanOp {
+            variable: var.clone(),
+            labels: vec![object_type.label.clone()],
+            input: None,
+        });
+        plan = resolver.apply_arguments(plan, field, "Query", object_type, &var)?;
+        let (plan, items, shape) =
+            resolver.resolve_object(field, object_type, plan, &var, "", root.cardinality)?;
+
+        let key = field.alias.clone().unwrap_or(field.name.clone());
+        let plan = LogicalOperator::Return(ReturnOp {
+            items,
+            distinct: false,
+            input: Box::new(plan),
+        });
+        Ok((
+            LogicalPlan::new(plan),
+            ResultShape::object().with_nested(key, shape),
+        ))
+    }
+
+    fn type_named(&self, name: &str) -> Result<&ObjectType> {
+        self.types
+            .get(name)
+            .ok_or_else(|| schema_error(format!("Unknown type \"{name}\"")))
+    }
+}
+
+/// Builds a plan for one query against a schema.
+struct Resolver<'a> {
+    schema: &'a GraphQLSchema,
+    /// Counter for generating node variables.
+    var_counter: AtomicU32,
+}
+
+impl Resolver<'_> {
+    /// Resolves the selection set of `field`, whose objects are bound to
+    /// `var` in `plan`.
+    ///
+    /// Columns are prefixed with `prefix`, so nested objects don't clash
+    /// with their parents.
+    fn resolve_object(
+        &self,
+        field: &ast::Field,
+        object_type: &ObjectType,
+        mut plan: LogicalOperator,
+        var: &str,
+        prefix: &str,
+        cardinality: Cardinality,
+    ) -> Result<(LogicalOperator, Vec<ReturnItem>, ResultShape)> {
+        let selection_set = field.selection_set.as_ref().ok_or_else(|| {
+            schema_error(format!(
+                "Field \"{}\" of type \"{}\" must have a selection of subfields.",
+                field.name, object_type.name
+            ))
+        })?;
+
+        let id_column = format!("{prefix}{ID_COLUMN}");
+        let mut shape = match cardinality {
+            Cardinality::One => ResultShape::one(&id_column),
+            Cardinality::Many => ResultShape::list(&id_column),
+        };
+        let mut items = vec![ReturnItem {
+            expression: LogicalExpression::Id(var.to_string()),
+            alias: Some(id_column),
+        }];
+
+        for selection in &selection_set.selections {
+            let ast::Selection::Field(child) = selection else {
+                return Err(schema_error(
+                    "Fragments are not supported when resolving against a schema",
+                ));
+            };
+            let key = child.alias.clone().unwrap_or(child.name.clone());
+            let column = format!("{prefix}{key}");
+
+            if let Some(relation) = object_type.relations.get(&child.name) {
+                let target = self.schema.type_named(&relation.target)?;
+                let to_var = self.next_var();
+
+                // Expand from a fresh scan and outer-join back onto the parent
+                let mut right = LogicalOperator::Expand(ExpandOp {
+                    from_variable: var.to_string(),
+                    to_variable: to_var.clone(),
+                    edge_variable: None,
+                    direction: relation.direction,
+                    edge_type: Some(relation.edge_type.clone()),
+                    min_hops: 1,
+                    max_hops: Some(1),
+                    input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
+                        variable: var.to_string(),
+                        labels: vec![object_type.label.clone()],
+                        input: None,
+                    })),
+                    path_alias: None,
+                });
+                right = self.apply_arguments(right, child, &object_type.name, target, &to_var)?;
+                let (right, nested_items, nested_shape) = self.resolve_object(
+                    child,
+                    target,
+                    right,
+                    &to_var,
+                    &format!("{column}_"),
+                    relation.cardinality,
+                )?;
+
+                plan = LogicalOperator::LeftJoin(LeftJoinOp {
+                    left: Box::new(plan),
+                    right: Box::new(right),
+                    condition: None,
+                });
+                items.extend(nested_items);
+                shape = shape.with_nested(key, nested_shape);
+            } else if object_type.has_property(&child.name) {
+                if child.selection_set.is_some() {
+                    return Err(schema_error(format!(
+                        "Field \"{}\" must not have a selection since type \"{}\" has no subfields.",
+                        child.name, object_type.name
+                    )));
+                }
+                if !child.arguments.is_empty() {
+                    return Err(schema_error(format!(
+                        "Field \"{}\" on type \"{}\" takes no arguments.",
+                        child.name, object_type.name
+                    )));
+                }
+                let expression = if child.name == ID_FIELD {
+                    LogicalExpression::Id(var.to_string())
+                } else {
+                    LogicalExpression::Property {
+                        variable: var.to_string(),
+                        property: child.name.clone(),
+                    }
+                };
+                items.push(ReturnItem {
+                    expression,
+                    alias: Some(column.clone()),
+                });
+                shape = shape.with_column(key, column);
+            } else {
+                return Err(schema_error(format!(
+                    "Cannot query field \"{}\" on type \"{}\".",
+                    child.name, object_type.name
+                )));
+            }
+        }
+
+        Ok((plan, items, shape))
+    }
+
+    /// Filters `plan` by the arguments of `field`, which select objects of
+    /// `object_type` bound to `var`.
+    ///
+    /// `id` matches the node id; any other argument must name a property
+    /// and matches it by equality.
+    fn apply_arguments(
+        &self,
+        plan: LogicalOperator,
+        field: &ast::Field,
+        parent_type: &str,
+        object_type: &ObjectType,
+        var: &str,
+    ) -> Result<LogicalOperator> {
+        let predicates = field
+            .arguments
+            .iter()
+            .map(|arg| {
+                let left = if arg.name == ID_FIELD {
+                    LogicalExpression::Id(var.to_string())
+                } else if object_type.has_property(&arg.name) {
+                    LogicalExpression::Property {
+                        variable: var.to_string(),
+                        property: arg.name.clone(),
+                    }
+                } else {
+                    return Err(schema_error(format!(
+                        "Unknown argument \"{}\" on field \"{parent_type}.{}\".",
+                        arg.name, field.name
+                    )));
+                };
+                let value = match arg.value.to_value() {
+                    // Ids are often passed as strings, as GraphQL's ID type suggests
+                    Value::String(s) if arg.name == ID_FIELD => s
+                        .parse::<i64>()
+                        .map(Value::Int64)
+                        .unwrap_or(Value::String(s)),
+                    value => value,
+                };
+                Ok(LogicalExpression::Binary {
+                    left: Box::new(left),
+                    op: BinaryOp::Eq,
+                    right: Box::new(LogicalExpression::Literal(value)),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(
+            match predicates
+                .into_iter()
+                .reduce(|acc, pred| LogicalExpression::Binary {
+                    left: Box::new(acc),
+                    op: BinaryOp::And,
+                    right: Box::new(pred),
+                }) {
+                Some(predicate) => LogicalOperator::Filter(FilterOp {
+                    predicate,
+                    input: Box::new(plan),
+                }),
+                None => plan,
+            },
+        )
+    }
+
+    fn next_var(&self) -> String {
+        let n = self.var_counter.fetch_add(1, Ordering::Relaxed);
+        format!("_v{}", n)
+    }
+}
+
+fn schema_error(message: impl Into<String>) -> Error {
+    Error::Query(QueryError::new(QueryErrorKind::Semantic, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> GraphQLSchema {
+        GraphQLSchema::new()
+            .with_type(
+                ObjectType::new("Person")
+                    .with_property("name")
+                    .with_property("age")
+                    .with_relation("friends", RelationField::many("KNOWS", "Person"))
+                    .with_relation("employer", RelationField::one("WORKS_AT", "Company")),
+            )
+            .with_type(
+                ObjectType::new("Company")
+                    .with_property("name")
+                    .with_relation(
+                        "staff",
+                        RelationField::many("WORKS_AT", "Person").incoming(),
+                    ),
+            )
+            .with_query_list("people", "Person")
+            .with_query_one("person", "Person")
+    }
+
+    fn error_message(query: &str) -> String {
+        match schema().resolve(query) {
+            Err(Error::Query(err)) => err.to_string(),
+            other => panic!("expected a query error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_resolve_two_level_query() {
+        let (plan, shape) = schema()
+            .resolve(r#"{ person(id: "7") { name friends(name: "Bob") { name } } }"#)
+            .unwrap();
+
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("expected a return, got {:?}", plan.root);
+        };
+        let aliases: Vec<_> = ret
+            .items
+            .iter()
+            .filter_map(|i| i.alias.as_deref())
+            .collect();
+        assert_eq!(aliases, ["__id", "name", "friends___id", "friends_name"]);
+
+        // The parent is scanned by label and filtered by id
+        let LogicalOperator::LeftJoin(join) = ret.input.as_ref() else {
+            panic!("expected an outer join, got {:?}", ret.input);
+        };
+        let LogicalOperator::Filter(filter) = join.left.as_ref() else {
+            panic!("expected an id filter, got {:?}", join.left);
+        };
+        assert!(matches!(
+            &filter.predicate,
+            LogicalExpression::Binary { left, right, .. }
+                if matches!(left.as_ref(), LogicalExpression::Id(_))
+                    && matches!(right.as_ref(), LogicalExpression::Literal(Value::Int64(7)))
+        ));
+        let LogicalOperator::NodeScan(scan) = filter.input.as_ref() else {
+            panic!("expected a scan, got {:?}", filter.input);
+        };
+        assert_eq!(scan.labels, ["Person"]);
+
+        // The nested field expands along its edge type, then filters
+        let LogicalOperator::Filter(nested) = join.right.as_ref() else {
+            panic!("expected an argument filter, got {:?}", join.right);
+        };
+        let LogicalOperator::Expand(expand) = nested.input.as_ref() else {
+            panic!("expected an expand, got {:?}", nested.input);
+        };
+        assert_eq!(expand.from_variable, scan.variable);
+        assert_eq!(expand.edge_type.as_deref(), Some("KNOWS"));
+        assert_eq!(expand.direction, ExpandDirection::Outgoing);
+
+        assert_eq!(
+            shape,
+            ResultShape::object().with_nested(
+                "person",
+                ResultShape::one("__id")
+                    .with_column("name", "name")
+                    .with_nested(
+                        "friends",
+                        ResultShape::list("friends___id").with_column("name", "friends_name"),
+                    ),
+            )
+        );
+    }
+
+    #[test]
+    fn test_cardinality_and_direction_follow_schema() {
+        let (plan, shape) = schema()
+            .resolve("{ people { employer { staff { name } } } }")
+            .unwrap();
+
+        let expected = ResultShape::object().with_nested(
+            "people",
+            ResultShape::list("__id").with_nested(
+                "employer",
+                ResultShape::one("employer___id").with_nested(
+                    "staff",
+                    ResultShape::list("employer_staff___id")
+                        .with_column("name", "employer_staff_name"),
+                ),
+            ),
+        );
+        assert_eq!(shape, expected);
+
+        fn directions(op: &LogicalOperator, out: &mut Vec<ExpandDirection>) {
+            if let LogicalOperator::Expand(expand) = op {
+                out.push(expand.direction);
+            }
+            for child in op.children() {
+                directions(child, out);
+            }
+        }
+        let mut found = Vec::new();
+        directions(&plan.root, &mut found);
+        assert_eq!(
+            found,
+            [ExpandDirection::Outgoing, ExpandDirection::Incoming]
+        );
+    }
+
+    #[test]
+    fn test_unknown_fields_and_arguments_are_errors() {
+        assert!(
+            error_message("{ people { salary } }")
+                .contains("Cannot query field \"salary\" on type \"Person\".")
+        );
+        assert!(
+            error_message("{ companies { name } }")
+                .contains("Cannot query field \"companies\" on type \"Query\".")
+        );
+        assert!(
+            error_message("{ people(salary: 3) { name } }")
+                .contains("Unknown argument \"salary\" on field \"Query.people\".")
+        );
+        assert!(error_message("{ people { employer } }").contains("must have a selection"));
+        assert!(
+            error_message("{ people { name { first } } }").contains("must not have a selection")
+        );
+    }
+}
@@ -9,9 +9,15 @@
 //! - Field arguments → Filter predicates
 //! - Nested selections → Expand (field name is relationship type)
 //! - Scalar fields → Return projections
+//!
+//! [`translate_nested`] additionally returns each level's node id and a
+//! [`ResultShape`] that folds the flat rows back into the nested objects
+//! GraphQL clients expect. Nested fields become outer joins there, so
+//! parents without children still come back, with an empty list.
 
+use crate::format::ResultShape;
 use crate::query::plan::{
-    BinaryOp, CreateNodeOp, DeleteNodeOp, ExpandDirection, ExpandOp, FilterOp, LimitOp,
+    BinaryOp, CreateNodeOp, DeleteNodeOp, ExpandDirection, ExpandOp, FilterOp, LeftJoinOp, LimitOp,
     LogicalExpression, LogicalOperator, LogicalPlan, NodeScanOp, ReturnItem, ReturnOp,
     SetPropertyOp, SkipOp, SortKey, SortOp, SortOrder,
 };
@@ -31,6 +37,33 @@ pub fn translate(query: &str) -> Result<LogicalPlan> {
     translator.translate_document(&doc)
 }
 
+/// Column holding the node id of each selection level in nested plans.
+///
+/// The `__` prefix is reserved for introspection in GraphQL, so it can't
+/// clash with a selected field.
+pub(crate) const ID_COLUMN: &str = "__id";
+
+/// Translates a GraphQL query to a plan whose rows fold into nested objects.
+///
+/// Render the plan's result with
+/// [`QueryResult::to_nested_json()`](crate::database::QueryResult::to_nested_json)
+/// and the returned shape to get the response's `data` object.
+///
+/// # Errors
+///
+/// Returns an error if the query cannot be parsed or translated, or is not
+/// a query with a selection set on its root field.
+pub fn translate_nested(query: &str) -> Result<(LogicalPlan, ResultShape)> {
+    let doc = graphql::parse(query)?;
+    let translator = GraphQLTranslator {
+        nested: true,
+        ..GraphQLTranslator::new()
+    };
+    let plan = translator.translate_document(&doc)?;
+    let shape = translator.result_shape(&doc)?;
+    Ok((plan, shape))
+}
+
 /// Mutation type for GraphQL mutations.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum MutationType {
@@ -57,6 +90,9 @@ struct GraphQLTranslator {
     var_counter: AtomicU32,
     /// Fragment definitions for resolution.
     fragments: HashMap<String, ast::FragmentDefinition>,
+    /// Whether to return node ids and outer-join nested fields, for
+    /// assembling nested responses.
+    nested: bool,
 }
 
 impl GraphQLTranslator {
@@ -64,21 +100,42 @@ impl GraphQLTranslator {
         Self {
             var_counter: AtomicU32::new(0),
             fragments: HashMap::new(),
+            nested: false,
         }
     }
 
     fn translate_document(&self, doc: &ast::Document) -> Result<LogicalPlan> {
-        // First, collect all fragment definitions
+        // Create translator with fragments
+        let translator = GraphQLTranslator {
+            var_counter: AtomicU32::new(0),
+            fragments: Self::collect_fragments(doc),
+            nested: self.nested,
+        };
+
+        let operation = Self::first_operation(doc)?;
+        if translator.nested && operation.operation != ast::OperationType::Query {
+            return Err(Error::Query(QueryError::new(
+                QueryErrorKind::Semantic,
+                "Nested results are only available for queries",
+            )));
+        }
+        translator.translate_operation(operation)
+    }
+
+    /// Collects all fragment definitions in a document by name.
+    fn collect_fragments(doc: &ast::Document) -> HashMap<String, ast::FragmentDefinition> {
         let mut fragments = HashMap::new();
         for def in &doc.definitions {
             if let ast::Definition::Fragment(frag) = def {
                 fragments.insert(frag.name.clone(), frag.clone());
             }
         }
+        fragments
+    }
 
-        // Find the first operation
-        let operation = doc
-            .definitions
+    /// Finds the first operation in a document.
+    fn first_operation(doc: &ast::Document) -> Result<&ast::OperationDefinition> {
+        doc.definitions
             .iter()
             .find_map(|def| match def {
                 ast::Definition::Operation(op) => Some(op),
@@ -89,15 +146,70 @@ impl GraphQLTranslator {
                     QueryErrorKind::Syntax,
                     "No operation found in document",
                 ))
-            })?;
+            })
+    }
 
-        // Create translator with fragments
-        let translator = GraphQLTranslator {
-            var_counter: AtomicU32::new(0),
-            fragments,
+    /// Builds the shape that folds a nested plan's rows into the response's
+    /// `data` object.
+    fn result_shape(&self, doc: &ast::Document) -> Result<ResultShape> {
+        let fragments = Self::collect_fragments(doc);
+        let field = self.get_first_field(&Self::first_operation(doc)?.selection_set)?;
+        let selection_set = field.selection_set.as_ref().ok_or_else(|| {
+            Error::Query(QueryError::new(
+                QueryErrorKind::Semantic,
+                "Nested results need a selection set on the root field",
+            ))
+        })?;
+
+        let key = field.alias.clone().unwrap_or(field.name.clone());
+        Ok(ResultShape::object()
+            .with_nested(key, Self::selection_shape(selection_set, "", &fragments)))
+    }
+
+    /// Builds the shape of one selection level.
+    ///
+    /// Mirrors the aliasing of [`collect_selection_items`](Self::collect_selection_items):
+    /// columns of nested fields are prefixed with the field's name and `_`.
+    fn selection_shape(
+        selection_set: &ast::SelectionSet,
+        prefix: &str,
+        fragments: &HashMap<String, ast::FragmentDefinition>,
+    ) -> ResultShape {
+        let scalars = |shape: ResultShape, selection_set: &ast::SelectionSet| {
+            selection_set
+                .selections
+                .iter()
+                .fold(shape, |shape, selection| match selection {
+                    ast::Selection::Field(field) if field.selection_set.is_none() => {
+                        let key = field.alias.clone().unwrap_or(field.name.clone());
+                        shape.with_column(key.clone(), format!("{prefix}{key}"))
+                    }
+                    _ => shape,
+                })
         };
 
-        translator.translate_operation(operation)
+        let mut shape = ResultShape::list(format!("{prefix}{ID_COLUMN}"));
+        for selection in &selection_set.selections {
+            shape = match selection {
+                ast::Selection::Field(field) => {
+                    let key = field.alias.clone().unwrap_or(field.name.clone());
+                    match &field.selection_set {
+                        Some(nested) => {
+                            let nested_prefix = format!("{prefix}{key}_");
+                            let nested = Self::selection_shape(nested, &nested_prefix, fragments);
+                            shape.with_nested(key, nested)
+                        }
+                        None => shape.with_column(key.clone(), format!("{prefix}{key}")),
+                    }
+                }
+                ast::Selection::FragmentSpread(spread) => match fragments.get(&spread.name) {
+                    Some(frag) => scalars(shape, &frag.selection_set),
+                    None => shape,
+                },
+                ast::Selection::InlineFragment(inline) => scalars(shape, &inline.selection_set),
+            };
+        }
+        shape
     }
 
     fn translate_operation(&self, op: &ast::OperationDefinition) -> Result<LogicalPlan> {
@@ -267,7 +379,7 @@ impl GraphQLTranslator {
         // Start with a node scan for the type
         let mut plan = LogicalOperator::NodeScan(NodeScanOp {
             variable: var.clone(),
-            label: Some(self.capitalize_first(type_name)),
+            labels: vec![self.capitalize_first(type_name)],
             input: None,
         });
 
@@ -282,6 +394,7 @@ impl GraphQLTranslator {
             variable: var.clone(),
             properties,
             replace: false, // Merge properties, don't replace all
+            increment: false,
             input: Box::new(plan),
         });
 
@@ -342,7 +455,7 @@ impl GraphQLTranslator {
         // First scan for the node
         let mut plan = LogicalOperator::NodeScan(NodeScanOp {
             variable: var.clone(),
-            label: Some(self.capitalize_first(type_name)),
+            labels: vec![self.capitalize_first(type_name)],
             input: None,
         });
 
@@ -369,7 +482,7 @@ impl GraphQLTranslator {
         // Start with a node scan using the field name as the label
         let mut plan = LogicalOperator::NodeScan(NodeScanOp {
             variable: var.clone(),
-            label: Some(self.capitalize_first(&field.name)),
+            labels: vec![self.capitalize_first(&field.name)],
             input: None,
         });
 
@@ -514,6 +627,13 @@ impl GraphQLTranslator {
         let mut return_items = Vec::new();
         let mut plan = input;
 
+        if self.nested {
+            return_items.push(ReturnItem {
+                expression: LogicalExpression::Id(current_var.to_string()),
+                alias: Some(ID_COLUMN.to_string()),
+            });
+        }
+
         for selection in &selection_set.selections {
             match selection {
                 ast::Selection::Field(field) => {
@@ -583,6 +703,9 @@ impl GraphQLTranslator {
     }
 
     /// Translates a nested field and returns the plan + return items (not wrapped in Return).
+    ///
+    /// In nested mode the traversal is outer-joined onto `input`, so parents
+    /// without matching children are kept.
     fn translate_nested_field_items(
         &self,
         field: &ast::Field,
@@ -591,6 +714,18 @@ impl GraphQLTranslator {
     ) -> Result<(LogicalOperator, Vec<ReturnItem>)> {
         let to_var = self.next_var();
 
+        // Nested mode expands from a fresh scan and joins back onto `input`
+        let (expand_input, join_input) = if self.nested {
+            let scan = LogicalOperator::NodeScan(NodeScanOp {
+                variable: from_var.to_string(),
+                labels: Vec::new(),
+                input: None,
+            });
+            (scan, Some(input))
+        } else {
+            (input, None)
+        };
+
         // The field name is the edge type
         let mut plan = LogicalOperator::Expand(ExpandOp {
             from_variable: from_var.to_string(),
@@ -600,7 +735,7 @@ impl GraphQLTranslator {
             edge_type: Some(field.name.clone()),
             min_hops: 1,
             max_hops: Some(1),
-            input: Box::new(input),
+            input: Box::new(expand_input),
             path_alias: None,
         });
 
@@ -626,6 +761,14 @@ impl GraphQLTranslator {
             }]
         };
 
+        if let Some(input) = join_input {
+            plan = LogicalOperator::LeftJoin(LeftJoinOp {
+                left: Box::new(input),
+                right: Box::new(plan),
+                condition: None,
+            });
+        }
+
         Ok((plan, return_items))
     }
 
@@ -1196,4 +1339,41 @@ mod tests {
         let result = translate(query);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_translate_nested_shape() {
+        let query = r#"
+            query {
+                user {
+                    name
+                    pals: friends {
+                        name
+                    }
+                }
+            }
+        "#;
+        let (plan, shape) = translate_nested(query).unwrap();
+
+        let friends = ResultShape::list("pals___id").with_column("name", "pals_name");
+        let user = ResultShape::list("__id")
+            .with_column("name", "name")
+            .with_nested("pals", friends);
+        assert_eq!(shape, ResultShape::object().with_nested("user", user));
+
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return operator");
+        };
+        let aliases: Vec<_> = ret
+            .items
+            .iter()
+            .filter_map(|i| i.alias.as_deref())
+            .collect();
+        assert_eq!(aliases, ["__id", "name", "pals___id", "pals_name"]);
+        assert!(matches!(ret.input.as_ref(), LogicalOperator::LeftJoin(_)));
+    }
+
+    #[test]
+    fn test_translate_nested_rejects_mutations() {
+        assert!(translate_nested("mutation { createUser(name: \"Alice\") { name } }").is_err());
+    }
 }
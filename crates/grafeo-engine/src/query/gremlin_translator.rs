@@ -3,14 +3,18 @@
 //! Translates Gremlin AST to the common logical plan representation.
 
 use crate::query::plan::{
-    AggregateExpr, AggregateFunction, AggregateOp, BinaryOp, CreateEdgeOp, CreateNodeOp,
-    DeleteNodeOp, DistinctOp, ExpandDirection, ExpandOp, FilterOp, JoinOp, JoinType, LimitOp,
-    LogicalExpression, LogicalOperator, LogicalPlan, NodeScanOp, ProjectOp, Projection, ReturnItem,
-    ReturnOp, SetPropertyOp, SkipOp, SortKey, SortOp, SortOrder, UnaryOp,
+    AggregateExpr, AggregateFunction, AggregateOp, AntiJoinOp, BinaryOp, CreateEdgeOp,
+    CreateNodeOp, DeleteNodeOp, DistinctOp, ExpandDirection, ExpandOp, FilterOp, ForEachOp, JoinOp,
+    JoinType, LimitOp, LogicalExpression, LogicalOperator, LogicalPlan, NodeScanOp, ProjectOp,
+    Projection, ReturnItem, ReturnOp, SetPropertyOp, SkipOp, SortKey, SortOp, SortOrder, UnaryOp,
+    UnionOp, UnwindOp,
 };
+use crate::query::planner::expression_to_string;
 use grafeo_adapters::query::gremlin::{self, ast};
 use grafeo_common::types::Value;
 use grafeo_common::utils::error::{Error, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicU32, Ordering};
 
 /// Translates a Gremlin query string to a logical plan.
@@ -28,6 +32,8 @@ pub fn translate(query: &str) -> Result<LogicalPlan> {
 struct GremlinTranslator {
     /// Counter for generating anonymous variables.
     var_counter: AtomicU32,
+    /// Variables labelled with as(), for select() and where().
+    labels: RefCell<HashMap<String, String>>,
 }
 
 /// Context for building an edge during traversal processing.
@@ -42,6 +48,7 @@ impl GremlinTranslator {
     fn new() -> Self {
         Self {
             var_counter: AtomicU32::new(0),
+            labels: RefCell::new(HashMap::new()),
         }
     }
 
@@ -60,8 +67,12 @@ impl GremlinTranslator {
         // Track edge context for step-level addE
         let mut pending_edge: Option<PendingEdge> = None;
 
+        // Track the elements visited so far, for path() and friends
+        let mut path = vec![current_var.clone()];
+
         // Process each step
-        for step in &stmt.steps {
+        let mut steps = stmt.steps.iter().peekable();
+        while let Some(step) = steps.next() {
             // Handle edge creation steps specially
             if let Some(ref mut edge) = pending_edge {
                 match step {
@@ -119,6 +130,36 @@ impl GremlinTranslator {
                 }
             }
 
+            if let ast::Step::Path | ast::Step::SimplePath | ast::Step::CyclicPath = step {
+                plan = self.translate_path_step(step, &path, plan);
+                continue;
+            }
+
+            // project() takes the by() modulators that follow it
+            if let ast::Step::Project(keys) = step {
+                let mut modifiers = Vec::new();
+                while let Some(ast::Step::By(by)) = steps.peek() {
+                    modifiers.push(by);
+                    steps.next();
+                }
+                plan = self.translate_project(keys, &modifiers, plan, &current_var)?;
+                continue;
+            }
+
+            // So do group() and groupCount()
+            if let ast::Step::Group(_) | ast::Step::GroupCount(_) = step {
+                let mut modifiers = Vec::new();
+                while let Some(ast::Step::By(by)) = steps.peek() {
+                    modifiers.push(by);
+                    steps.next();
+                }
+                let (new_plan, map_var) =
+                    self.translate_group(step, &modifiers, plan, &current_var)?;
+                plan = new_plan;
+                current_var = map_var;
+                continue;
+            }
+
             // Check if this is a step-level addE
             if let ast::Step::AddE(edge_type) = step {
                 // For step-level addE, the current context is the source by default
@@ -131,9 +172,25 @@ impl GremlinTranslator {
                 continue;
             }
 
+            // A later path() lists the edges hops cross, so bind them
+            if let ast::Step::Out(labels) | ast::Step::In(labels) | ast::Step::Both(labels) = step {
+                if steps.clone().any(|later| matches!(later, ast::Step::Path)) {
+                    let (new_plan, target_var, edge_var) =
+                        self.translate_hop(step, labels, plan, &current_var, true);
+                    plan = new_plan;
+                    path.extend(edge_var);
+                    path.push(target_var.clone());
+                    current_var = target_var;
+                    continue;
+                }
+            }
+
             let (new_plan, new_var) = self.translate_step(step, plan, &current_var)?;
             plan = new_plan;
             if let Some(v) = new_var {
+                if v != current_var {
+                    path.push(v.clone());
+                }
                 current_var = v;
             }
         }
@@ -173,6 +230,174 @@ impl GremlinTranslator {
         Ok(LogicalPlan::new(plan))
     }
 
+    /// Returns the elements labelled with as() under their labels, in the
+    /// order `keys` names them.
+    fn translate_select(&self, keys: &[String], input: LogicalOperator) -> Result<LogicalOperator> {
+        let items = keys
+            .iter()
+            .map(|key| {
+                Ok(ReturnItem {
+                    expression: LogicalExpression::Variable(self.labelled_var("select", key)?),
+                    alias: Some(key.clone()),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(LogicalOperator::Return(ReturnOp {
+            items,
+            distinct: false,
+            input: Box::new(input),
+        }))
+    }
+
+    /// Returns one column per project() key, computed by the by() at the
+    /// same position. As in TinkerPop, fewer by()s than keys are cycled
+    /// through, and without any each key gets the element itself.
+    fn translate_project(
+        &self,
+        keys: &[String],
+        modifiers: &[&ast::ByModifier],
+        input: LogicalOperator,
+        current_var: &str,
+    ) -> Result<LogicalOperator> {
+        if modifiers.len() > keys.len() {
+            return Err(Error::Internal(format!(
+                "project() has {} keys but {} by() modulators",
+                keys.len(),
+                modifiers.len()
+            )));
+        }
+        let items = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| ReturnItem {
+                expression: if modifiers.is_empty() {
+                    LogicalExpression::Variable(current_var.to_string())
+                } else {
+                    self.translate_by_modifier(modifiers[i % modifiers.len()], current_var)
+                },
+                alias: Some(key.clone()),
+            })
+            .collect();
+        Ok(LogicalOperator::Return(ReturnOp {
+            items,
+            distinct: false,
+            input: Box::new(input),
+        }))
+    }
+
+    /// Groups the current elements for group() or groupCount().
+    ///
+    /// The first by() picks the group key, defaulting to the element itself.
+    /// groupCount() counts each group; group() aggregates it by its second
+    /// by(): count(), sum() or fold(), or a property to collect. Without one,
+    /// group() collects the elements.
+    ///
+    /// As in TinkerPop, the groups come back as a single map from key to
+    /// aggregate, which becomes the current value under the returned name.
+    fn translate_group(
+        &self,
+        step: &ast::Step,
+        modifiers: &[&ast::ByModifier],
+        input: LogicalOperator,
+        current_var: &str,
+    ) -> Result<(LogicalOperator, String)> {
+        let (max_modifiers, name) = match step {
+            ast::Step::GroupCount(Some(key)) => {
+                return Err(Error::Internal(format!(
+                    "groupCount('{key}') side effects are not supported"
+                )));
+            }
+            ast::Step::GroupCount(None) => (1, "groupCount"),
+            _ => (2, "group"),
+        };
+        if modifiers.len() > max_modifiers {
+            return Err(Error::Internal(format!(
+                "{name}() takes at most {max_modifiers} by() modulators, got {}",
+                modifiers.len()
+            )));
+        }
+
+        let key = modifiers.first().map_or_else(
+            || LogicalExpression::Variable(current_var.to_string()),
+            |by| self.translate_by_modifier(by, current_var),
+        );
+        let element = || LogicalExpression::Variable(current_var.to_string());
+        let (function, expression, alias) = if name == "groupCount" {
+            (AggregateFunction::Count, None, "count")
+        } else {
+            match modifiers.get(1) {
+                None | Some(ast::ByModifier::Identity) => {
+                    (AggregateFunction::Collect, Some(element()), "fold")
+                }
+                Some(ast::ByModifier::Key(key)) => (
+                    AggregateFunction::Collect,
+                    Some(LogicalExpression::Property {
+                        variable: current_var.to_string(),
+                        property: key.clone(),
+                    }),
+                    "fold",
+                ),
+                Some(ast::ByModifier::Traversal(steps)) => match &steps[..] {
+                    [ast::Step::Count] => (AggregateFunction::Count, None, "count"),
+                    [ast::Step::Sum] => (AggregateFunction::Sum, Some(element()), "sum"),
+                    [ast::Step::Fold] => (AggregateFunction::Collect, Some(element()), "fold"),
+                    _ => {
+                        return Err(Error::Internal(format!(
+                            "group() can't aggregate by {steps:?}; use count(), sum() or fold()"
+                        )));
+                    }
+                },
+                Some(by) => {
+                    return Err(Error::Internal(format!(
+                        "group() can't aggregate by {by:?}; use count(), sum() or fold()"
+                    )));
+                }
+            }
+        };
+
+        // One row per group, then folded into a map keyed by the column the
+        // planner names the group key after
+        let key_column = expression_to_string(&key);
+        let groups = LogicalOperator::Aggregate(AggregateOp {
+            group_by: vec![key],
+            aggregates: vec![AggregateExpr {
+                function,
+                expression,
+                distinct: false,
+                alias: Some(alias.to_string()),
+                percentile: None,
+            }],
+            input: Box::new(input),
+            having: None,
+        });
+        let plan = LogicalOperator::Aggregate(AggregateOp {
+            group_by: Vec::new(),
+            aggregates: vec![AggregateExpr {
+                function: AggregateFunction::CollectMap,
+                expression: Some(LogicalExpression::List(vec![
+                    LogicalExpression::Variable(key_column),
+                    LogicalExpression::Variable(alias.to_string()),
+                ])),
+                distinct: false,
+                alias: Some(name.to_string()),
+                percentile: None,
+            }],
+            input: Box::new(groups),
+            having: None,
+        });
+        Ok((plan, name.to_string()))
+    }
+
+    /// Returns the variable labelled `label` with as(), naming the step that
+    /// asked for it when there is none.
+    fn labelled_var(&self, step: &str, label: &str) -> Result<String> {
+        self.labels
+            .borrow()
+            .get(label)
+            .cloned()
+            .ok_or_else(|| Error::Internal(format!("{step}() refers to undefined label '{label}'")))
+    }
+
     /// Handle g.addE('type').from(...).to(...) pattern
     fn translate_add_edge_traversal(
         &self,
@@ -220,7 +445,7 @@ impl GremlinTranslator {
             let scan_var = self.next_var();
             plan = LogicalOperator::NodeScan(NodeScanOp {
                 variable: scan_var,
-                label: None,
+                labels: Vec::new(),
                 input: None,
             });
         }
@@ -249,6 +474,9 @@ impl GremlinTranslator {
 
     /// Extract variable name from FromTo specification and optionally modify the plan.
     /// Returns (variable_name, modified_plan).
+    ///
+    /// A sub-traversal becomes its own scan and filters, joined to `plan` so
+    /// that every row pairs up with each endpoint it finds.
     fn extract_from_to_with_plan(
         &self,
         from_to: &ast::FromTo,
@@ -256,13 +484,23 @@ impl GremlinTranslator {
         _current_var: &str,
     ) -> Result<(String, LogicalOperator)> {
         match from_to {
-            ast::FromTo::Label(label) => Ok((label.clone(), plan)),
+            ast::FromTo::Label(label) => {
+                // A label set with as() stands for the element it labelled;
+                // any other label names a variable directly
+                let var = self
+                    .labels
+                    .borrow()
+                    .get(label)
+                    .cloned()
+                    .unwrap_or_else(|| label.clone());
+                Ok((var, plan))
+            }
             ast::FromTo::Traversal(steps) => {
                 // Create a fresh NodeScan for the sub-traversal
                 let target_var = self.next_var();
                 let mut sub_plan = LogicalOperator::NodeScan(NodeScanOp {
                     variable: target_var.clone(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 });
 
@@ -301,7 +539,7 @@ impl GremlinTranslator {
                 let var = self.next_var();
                 let mut plan = LogicalOperator::NodeScan(NodeScanOp {
                     variable: var.clone(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 });
 
@@ -324,7 +562,7 @@ impl GremlinTranslator {
                 let var = self.next_var();
                 let mut plan = LogicalOperator::NodeScan(NodeScanOp {
                     variable: var.clone(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 });
 
@@ -382,52 +620,9 @@ impl GremlinTranslator {
     ) -> Result<(LogicalOperator, Option<String>)> {
         match step {
             // Navigation steps
-            ast::Step::Out(labels) => {
-                let target_var = self.next_var();
-                let edge_type = labels.first().cloned();
-                let plan = LogicalOperator::Expand(ExpandOp {
-                    from_variable: current_var.to_string(),
-                    to_variable: target_var.clone(),
-                    edge_variable: None,
-                    direction: ExpandDirection::Outgoing,
-                    edge_type,
-                    min_hops: 1,
-                    max_hops: Some(1),
-                    input: Box::new(input),
-                    path_alias: None,
-                });
-                Ok((plan, Some(target_var)))
-            }
-            ast::Step::In(labels) => {
-                let target_var = self.next_var();
-                let edge_type = labels.first().cloned();
-                let plan = LogicalOperator::Expand(ExpandOp {
-                    from_variable: current_var.to_string(),
-                    to_variable: target_var.clone(),
-                    edge_variable: None,
-                    direction: ExpandDirection::Incoming,
-                    edge_type,
-                    min_hops: 1,
-                    max_hops: Some(1),
-                    input: Box::new(input),
-                    path_alias: None,
-                });
-                Ok((plan, Some(target_var)))
-            }
-            ast::Step::Both(labels) => {
-                let target_var = self.next_var();
-                let edge_type = labels.first().cloned();
-                let plan = LogicalOperator::Expand(ExpandOp {
-                    from_variable: current_var.to_string(),
-                    to_variable: target_var.clone(),
-                    edge_variable: None,
-                    direction: ExpandDirection::Both,
-                    edge_type,
-                    min_hops: 1,
-                    max_hops: Some(1),
-                    input: Box::new(input),
-                    path_alias: None,
-                });
+            ast::Step::Out(labels) | ast::Step::In(labels) | ast::Step::Both(labels) => {
+                let (plan, target_var, _) =
+                    self.translate_hop(step, labels, input, current_var, false);
                 Ok((plan, Some(target_var)))
             }
             ast::Step::OutE(labels) => {
@@ -483,73 +678,33 @@ impl GremlinTranslator {
             }
 
             // Filter steps
-            ast::Step::Has(has_step) => {
-                let predicate = self.translate_has_step(has_step, current_var)?;
+            step if is_filter_step(step) => {
+                let predicate = self.translate_filter(step, current_var)?;
                 let plan = LogicalOperator::Filter(FilterOp {
                     predicate,
                     input: Box::new(input),
                 });
                 Ok((plan, None))
             }
-            ast::Step::HasLabel(labels) => {
-                // Labels(var) returns a list of labels, so we need to check if the
-                // target label is IN that list, not if the list equals the label
-                let predicate = if labels.len() == 1 {
-                    LogicalExpression::Binary {
-                        left: Box::new(LogicalExpression::Literal(Value::String(
-                            labels[0].clone().into(),
-                        ))),
-                        op: BinaryOp::In,
-                        right: Box::new(LogicalExpression::Labels(current_var.to_string())),
-                    }
-                } else {
-                    // For multiple labels, check if ANY of them are in the node's labels
-                    let mut conditions: Vec<LogicalExpression> = labels
-                        .iter()
-                        .map(|l| LogicalExpression::Binary {
-                            left: Box::new(LogicalExpression::Literal(Value::String(
-                                l.clone().into(),
-                            ))),
-                            op: BinaryOp::In,
-                            right: Box::new(LogicalExpression::Labels(current_var.to_string())),
-                        })
-                        .collect();
-                    // OR all conditions together
-                    let mut result = conditions.pop().unwrap();
-                    for cond in conditions {
-                        result = LogicalExpression::Binary {
-                            left: Box::new(cond),
-                            op: BinaryOp::Or,
-                            right: Box::new(result),
-                        };
-                    }
-                    result
-                };
-                let plan = LogicalOperator::Filter(FilterOp {
-                    predicate,
-                    input: Box::new(input),
-                });
-                Ok((plan, None))
-            }
-            ast::Step::HasId(ids) => {
-                let predicate = self.build_id_filter(current_var, ids);
-                let plan = LogicalOperator::Filter(FilterOp {
-                    predicate,
-                    input: Box::new(input),
+            ast::Step::Not(steps) => {
+                // Re-scan the current element on the inner side so the anti-join
+                // keys on it, then keep only outer rows with no inner result
+                let mut inner = LogicalOperator::NodeScan(NodeScanOp {
+                    variable: current_var.to_string(),
+                    labels: Vec::new(),
+                    input: None,
                 });
-                Ok((plan, None))
-            }
-            ast::Step::HasNot(key) => {
-                let predicate = LogicalExpression::Unary {
-                    op: UnaryOp::IsNull,
-                    operand: Box::new(LogicalExpression::Property {
-                        variable: current_var.to_string(),
-                        property: key.clone(),
-                    }),
-                };
-                let plan = LogicalOperator::Filter(FilterOp {
-                    predicate,
-                    input: Box::new(input),
+                let mut inner_var = current_var.to_string();
+                for step in steps {
+                    let (plan, new_var) = self.translate_step(step, inner, &inner_var)?;
+                    inner = plan;
+                    if let Some(v) = new_var {
+                        inner_var = v;
+                    }
+                }
+                let plan = LogicalOperator::AntiJoin(AntiJoinOp {
+                    left: Box::new(input),
+                    right: Box::new(inner),
                 });
                 Ok((plan, None))
             }
@@ -626,6 +781,26 @@ impl GremlinTranslator {
                 });
                 Ok((plan, None))
             }
+            ast::Step::ValueMap(_, keys) | ast::Step::ElementMap(keys) => {
+                // elementMap() and valueMap(true) also carry the id and label
+                let (include_id_label, alias) = match step {
+                    ast::Step::ValueMap(with_tokens, _) => (*with_tokens, "valueMap"),
+                    _ => (true, "elementMap"),
+                };
+                let plan = LogicalOperator::Return(ReturnOp {
+                    items: vec![ReturnItem {
+                        expression: LogicalExpression::PropertyMap {
+                            variable: current_var.to_string(),
+                            keys: keys.clone(),
+                            include_id_label,
+                        },
+                        alias: Some(alias.to_string()),
+                    }],
+                    distinct: false,
+                    input: Box::new(input),
+                });
+                Ok((plan, None))
+            }
             ast::Step::Label => {
                 let plan = LogicalOperator::Return(ReturnOp {
                     items: vec![ReturnItem {
@@ -731,8 +906,21 @@ impl GremlinTranslator {
                     input: Box::new(input),
                     having: None,
                 });
-                Ok((plan, None))
+                // The folded list becomes the current value so unfold() can find it
+                Ok((plan, Some("fold".to_string())))
+            }
+            ast::Step::Unfold => {
+                // Inverse of fold(): one row per list element, like UNWIND.
+                // Empty lists produce no rows.
+                let element_var = self.next_var();
+                let plan = LogicalOperator::Unwind(UnwindOp {
+                    expression: LogicalExpression::Variable(current_var.to_string()),
+                    variable: element_var.clone(),
+                    input: Box::new(input),
+                });
+                Ok((plan, Some(element_var)))
             }
+            ast::Step::Identity => Ok((input, None)),
             ast::Step::Order(modifiers) => {
                 let keys = if modifiers.is_empty() {
                     vec![SortKey {
@@ -761,9 +949,43 @@ impl GremlinTranslator {
 
             // Side effect steps
             ast::Step::As(label) => {
-                // 'as' just adds a label, which we track via variables
-                // In LogicalPlan, we use the label as an alias
-                Ok((input, Some(label.clone())))
+                // Remember the labelled element for select() and where(); it
+                // stays the current one
+                self.labels
+                    .borrow_mut()
+                    .insert(label.clone(), current_var.to_string());
+                Ok((input, None))
+            }
+            ast::Step::Select(keys) => Ok((self.translate_select(keys, input)?, None)),
+            ast::Step::Project(keys) => {
+                Ok((self.translate_project(keys, &[], input, current_var)?, None))
+            }
+            ast::Step::Group(_) | ast::Step::GroupCount(_) => {
+                let (plan, map_var) = self.translate_group(step, &[], input, current_var)?;
+                Ok((plan, Some(map_var)))
+            }
+            ast::Step::SideEffect(steps) => {
+                // Run the inner traversal as a FOREACH body over a one-element
+                // list: its writes apply per row, while the rows themselves
+                // pass through unchanged
+                let mut body = LogicalOperator::Empty;
+                let mut body_var = current_var.to_string();
+                for step in steps {
+                    let (plan, new_var) = self.translate_step(step, body, &body_var)?;
+                    body = plan;
+                    if let Some(v) = new_var {
+                        body_var = v;
+                    }
+                }
+                let plan = LogicalOperator::ForEach(ForEachOp {
+                    variable: self.next_var(),
+                    list: LogicalExpression::List(vec![LogicalExpression::Literal(Value::Bool(
+                        true,
+                    ))]),
+                    body: Box::new(body),
+                    input: Box::new(input),
+                });
+                Ok((plan, None))
             }
             ast::Step::Property(prop_step) => {
                 // If setting property on a node being created, add to CreateNodeOp
@@ -786,6 +1008,7 @@ impl GremlinTranslator {
                                 LogicalExpression::Literal(prop_step.value.clone()),
                             )],
                             replace: false,
+                            increment: false,
                             input: Box::new(input),
                         });
                         Ok((plan, None))
@@ -879,18 +1102,434 @@ impl GremlinTranslator {
                         }];
                         Ok((LogicalOperator::Sort(sort_op), None))
                     }
+                    LogicalOperator::Return(mut ret) => {
+                        // 'by' after select() projects a property of each
+                        // selected element
+                        if let ast::ByModifier::Key(key) = by_modifier {
+                            for item in &mut ret.items {
+                                if let LogicalExpression::Variable(var) = &item.expression {
+                                    item.expression = LogicalExpression::Property {
+                                        variable: var.clone(),
+                                        property: key.clone(),
+                                    };
+                                }
+                            }
+                        }
+                        Ok((LogicalOperator::Return(ret), None))
+                    }
                     _ => {
-                        // by() without a preceding order() - ignore
+                        // by() without a preceding order() or select() - ignore
                         Ok((input, None))
                     }
                 }
             }
 
+            // Branch steps
+            ast::Step::Union(branches) => self.translate_union(branches, input, current_var),
+            ast::Step::Coalesce(branches) => self.translate_coalesce(branches, input, current_var),
+
+            // Loop steps
+            ast::Step::Repeat(steps) => {
+                // A repeated single hop becomes an unbounded variable-length
+                // expand; a following times() or until() bounds it
+                let [step @ (ast::Step::Out(_) | ast::Step::In(_) | ast::Step::Both(_))] =
+                    &steps[..]
+                else {
+                    return Err(Error::Internal(format!(
+                        "repeat() only supports a single out(), in() or both() step, got {steps:?}"
+                    )));
+                };
+                match self.translate_step(step, input, current_var)? {
+                    (LogicalOperator::Expand(mut expand), target_var) => {
+                        expand.max_hops = None;
+                        Ok((LogicalOperator::Expand(expand), target_var))
+                    }
+                    _ => Err(Error::Internal(
+                        "repeat() body did not translate to an expand".to_string(),
+                    )),
+                }
+            }
+            ast::Step::Times(n) => match input {
+                LogicalOperator::Expand(mut expand) if expand.max_hops.is_none() => {
+                    let hops = u32::try_from(*n).map_err(|_| {
+                        Error::Internal(format!("times({n}) exceeds the maximum hop count"))
+                    })?;
+                    expand.min_hops = hops;
+                    expand.max_hops = Some(hops);
+                    Ok((LogicalOperator::Expand(expand), None))
+                }
+                _ => Err(Error::Internal(
+                    "times() must directly follow repeat()".to_string(),
+                )),
+            },
+            ast::Step::Until(steps) => {
+                if !matches!(&input, LogicalOperator::Expand(expand) if expand.max_hops.is_none()) {
+                    return Err(Error::Internal(
+                        "until() must directly follow repeat()".to_string(),
+                    ));
+                }
+                // Keep only the loop's end points that satisfy the condition
+                let mut plan = input;
+                for step in steps {
+                    if !matches!(
+                        step,
+                        ast::Step::Has(_)
+                            | ast::Step::HasLabel(_)
+                            | ast::Step::HasId(_)
+                            | ast::Step::HasNot(_)
+                    ) {
+                        return Err(Error::Internal(format!(
+                            "until() only supports has(), hasLabel(), hasId() and hasNot() steps, got {step:?}"
+                        )));
+                    }
+                    let (filtered, _) = self.translate_step(step, plan, current_var)?;
+                    plan = filtered;
+                }
+                Ok((plan, None))
+            }
+
             // Steps not fully supported
             _ => Ok((input, None)),
         }
     }
 
+    /// Translates an out(), in() or both() hop to the adjacent vertices.
+    ///
+    /// Gremlin drops the edge such a hop crosses, but with `bind_edge` it
+    /// is bound too, for later steps that refer to it, and returned
+    /// alongside the vertex variable.
+    fn translate_hop(
+        &self,
+        step: &ast::Step,
+        labels: &[String],
+        input: LogicalOperator,
+        current_var: &str,
+        bind_edge: bool,
+    ) -> (LogicalOperator, String, Option<String>) {
+        let direction = match step {
+            ast::Step::In(_) => ExpandDirection::Incoming,
+            ast::Step::Both(_) => ExpandDirection::Both,
+            _ => ExpandDirection::Outgoing,
+        };
+        let edge_var = bind_edge.then(|| self.next_var());
+        let target_var = self.next_var();
+        let plan = LogicalOperator::Expand(ExpandOp {
+            from_variable: current_var.to_string(),
+            to_variable: target_var.clone(),
+            edge_variable: edge_var.clone(),
+            direction,
+            edge_type: labels.first().cloned(),
+            min_hops: 1,
+            max_hops: Some(1),
+            input: Box::new(input),
+            path_alias: None,
+        });
+        (plan, target_var, edge_var)
+    }
+
+    /// Translates path(), simplePath() or cyclicPath() over the variables the
+    /// traversal has bound so far, in order.
+    ///
+    /// Unlike in TinkerPop, a path also lists the edges that out(), in() and
+    /// both() crossed, as a Cypher path would.
+    ///
+    /// This relies on the executor materializing the path history: every
+    /// element a step visits stays bound in its own column for the rest of
+    /// the query, so rows grow with the length of the traversal.
+    fn translate_path_step(
+        &self,
+        step: &ast::Step,
+        path: &[String],
+        input: LogicalOperator,
+    ) -> LogicalOperator {
+        if let ast::Step::Path = step {
+            return LogicalOperator::Return(ReturnOp {
+                items: vec![ReturnItem {
+                    expression: LogicalExpression::List(
+                        path.iter()
+                            .map(|var| LogicalExpression::Variable(var.clone()))
+                            .collect(),
+                    ),
+                    alias: Some("path".to_string()),
+                }],
+                distinct: false,
+                input: Box::new(input),
+            });
+        }
+
+        // The path is simple when no two of its elements are the same
+        let distinct = path
+            .iter()
+            .enumerate()
+            .flat_map(|(i, earlier)| {
+                path[i + 1..]
+                    .iter()
+                    .map(move |later| LogicalExpression::Binary {
+                        left: Box::new(LogicalExpression::Variable(earlier.clone())),
+                        op: BinaryOp::Ne,
+                        right: Box::new(LogicalExpression::Variable(later.clone())),
+                    })
+            })
+            .reduce(|result, differ| LogicalExpression::Binary {
+                left: Box::new(result),
+                op: BinaryOp::And,
+                right: Box::new(differ),
+            })
+            .unwrap_or(LogicalExpression::Literal(Value::Bool(true)));
+        let predicate = if let ast::Step::CyclicPath = step {
+            LogicalExpression::Unary {
+                op: UnaryOp::Not,
+                operand: Box::new(distinct),
+            }
+        } else {
+            distinct
+        };
+        LogicalOperator::Filter(FilterOp {
+            predicate,
+            input: Box::new(input),
+        })
+    }
+
+    /// Translates union(): every branch runs over the same input and their
+    /// results are concatenated.
+    fn translate_union(
+        &self,
+        branches: &[Vec<ast::Step>],
+        input: LogicalOperator,
+        current_var: &str,
+    ) -> Result<(LogicalOperator, Option<String>)> {
+        if branches.is_empty() {
+            return Err(Error::Internal(
+                "union() needs at least one traversal".to_string(),
+            ));
+        }
+        let output_var = self.next_var();
+        let inputs = branches
+            .iter()
+            .map(|steps| self.translate_branch(steps, input.clone(), current_var, &output_var))
+            .collect::<Result<Vec<_>>>()?;
+        let plan = LogicalOperator::Union(UnionOp { inputs });
+        Ok((plan, Some(output_var)))
+    }
+
+    /// Translates coalesce(): each input row takes the results of the first
+    /// branch that produces any.
+    ///
+    /// A branch only sees the rows for which every earlier branch came up
+    /// empty, so concatenating the branches keeps just the first match.
+    fn translate_coalesce(
+        &self,
+        branches: &[Vec<ast::Step>],
+        input: LogicalOperator,
+        current_var: &str,
+    ) -> Result<(LogicalOperator, Option<String>)> {
+        if branches.is_empty() {
+            return Err(Error::Internal(
+                "coalesce() needs at least one traversal".to_string(),
+            ));
+        }
+        let output_var = self.next_var();
+        let mut remaining = input;
+        let mut inputs = Vec::with_capacity(branches.len());
+        for (i, steps) in branches.iter().enumerate() {
+            inputs.push(self.translate_branch(
+                steps,
+                remaining.clone(),
+                current_var,
+                &output_var,
+            )?);
+            if i + 1 < branches.len() {
+                // Like not(), re-scan the current element on the inner side
+                // so the anti-join keys on it
+                let mut inner = LogicalOperator::NodeScan(NodeScanOp {
+                    variable: current_var.to_string(),
+                    labels: Vec::new(),
+                    input: None,
+                });
+                let mut inner_var = current_var.to_string();
+                for step in steps {
+                    let (plan, new_var) = self.translate_step(step, inner, &inner_var)?;
+                    inner = plan;
+                    if let Some(v) = new_var {
+                        inner_var = v;
+                    }
+                }
+                remaining = LogicalOperator::AntiJoin(AntiJoinOp {
+                    left: Box::new(remaining),
+                    right: Box::new(inner),
+                });
+            }
+        }
+        let plan = LogicalOperator::Union(UnionOp { inputs });
+        Ok((plan, Some(output_var)))
+    }
+
+    /// Translates one branch of union() or coalesce().
+    ///
+    /// Branches bind their own variables and may be of different lengths, so
+    /// each one is projected to the same columns: the element the branch
+    /// started from, the as() labels, and its result under `output_var`.
+    fn translate_branch(
+        &self,
+        steps: &[ast::Step],
+        input: LogicalOperator,
+        current_var: &str,
+        output_var: &str,
+    ) -> Result<LogicalOperator> {
+        let mut plan = input;
+        let mut branch_var = current_var.to_string();
+        for step in steps {
+            let (new_plan, new_var) = self.translate_step(step, plan, &branch_var)?;
+            plan = new_plan;
+            if let Some(v) = new_var {
+                branch_var = v;
+            }
+        }
+
+        let mut kept: Vec<String> = self.labels.borrow().values().cloned().collect();
+        kept.push(current_var.to_string());
+        kept.sort();
+        kept.dedup();
+        let mut projections: Vec<Projection> = kept
+            .into_iter()
+            .map(|var| Projection {
+                expression: LogicalExpression::Variable(var.clone()),
+                alias: Some(var),
+            })
+            .collect();
+        projections.push(Projection {
+            expression: LogicalExpression::Variable(branch_var),
+            alias: Some(output_var.to_string()),
+        });
+        Ok(LogicalOperator::Project(ProjectOp {
+            projections,
+            input: Box::new(plan),
+        }))
+    }
+
+    /// Translates a filter step to the predicate it keeps elements by.
+    ///
+    /// Traversals inside where(), and(), and or() may only chain filter
+    /// steps; a chain keeps elements that pass every step.
+    fn translate_filter(&self, step: &ast::Step, var: &str) -> Result<LogicalExpression> {
+        match step {
+            ast::Step::Has(has_step) => self.translate_has_step(has_step, var),
+            ast::Step::HasLabel(labels) => {
+                // Labels(var) returns a list of labels, so check whether any
+                // of the wanted labels is IN that list
+                let mut conditions = labels.iter().map(|l| LogicalExpression::Binary {
+                    left: Box::new(LogicalExpression::Literal(Value::String(l.clone().into()))),
+                    op: BinaryOp::In,
+                    right: Box::new(LogicalExpression::Labels(var.to_string())),
+                });
+                let first = conditions
+                    .next()
+                    .ok_or_else(|| Error::Internal("hasLabel() needs a label".to_string()))?;
+                Ok(
+                    conditions.fold(first, |result, cond| LogicalExpression::Binary {
+                        left: Box::new(cond),
+                        op: BinaryOp::Or,
+                        right: Box::new(result),
+                    }),
+                )
+            }
+            ast::Step::HasId(ids) => Ok(self.build_id_filter(var, ids)),
+            ast::Step::HasNot(key) => Ok(LogicalExpression::Unary {
+                op: UnaryOp::IsNull,
+                operand: Box::new(LogicalExpression::Property {
+                    variable: var.to_string(),
+                    property: key.clone(),
+                }),
+            }),
+            ast::Step::Where(ast::WhereClause::Predicate(start, pred)) => {
+                // where(P.gt('a')) compares the current element (or the one
+                // labelled `start`) against the element labelled 'a'
+                let left = if start.is_empty() {
+                    var.to_string()
+                } else {
+                    self.labelled_var("where", start)?
+                };
+                let (op, label) = match pred {
+                    ast::Predicate::Eq(label) => (BinaryOp::Eq, label),
+                    ast::Predicate::Neq(label) => (BinaryOp::Ne, label),
+                    ast::Predicate::Lt(label) => (BinaryOp::Lt, label),
+                    ast::Predicate::Lte(label) => (BinaryOp::Le, label),
+                    ast::Predicate::Gt(label) => (BinaryOp::Gt, label),
+                    ast::Predicate::Gte(label) => (BinaryOp::Ge, label),
+                    other => {
+                        return Err(Error::Internal(format!(
+                            "where() only supports eq, neq, lt, lte, gt and gte against a label, got {other:?}"
+                        )));
+                    }
+                };
+                let Value::String(label) = label else {
+                    return Err(Error::Internal(format!(
+                        "where() compares against an as() label, got {label:?}"
+                    )));
+                };
+                Ok(LogicalExpression::Binary {
+                    left: Box::new(LogicalExpression::Variable(left)),
+                    op,
+                    right: Box::new(LogicalExpression::Variable(
+                        self.labelled_var("where", label)?,
+                    )),
+                })
+            }
+            ast::Step::Where(ast::WhereClause::Traversal(steps)) => {
+                self.translate_filter_chain("where", steps, var)
+            }
+            ast::Step::And(traversals) | ast::Step::Or(traversals) => {
+                let (name, op) = if matches!(step, ast::Step::And(_)) {
+                    ("and", BinaryOp::And)
+                } else {
+                    ("or", BinaryOp::Or)
+                };
+                let mut branches = traversals
+                    .iter()
+                    .map(|steps| self.translate_filter_chain(name, steps, var));
+                let first = branches.next().ok_or_else(|| {
+                    Error::Internal(format!("{name}() needs at least one traversal"))
+                })??;
+                branches.try_fold(first, |result, branch| {
+                    Ok(LogicalExpression::Binary {
+                        left: Box::new(result),
+                        op,
+                        right: Box::new(branch?),
+                    })
+                })
+            }
+            other => Err(Error::Internal(format!("{other:?} is not a filter step"))),
+        }
+    }
+
+    /// Translates a traversal of filter steps inside `step_name`() to the
+    /// conjunction of their predicates.
+    fn translate_filter_chain(
+        &self,
+        step_name: &str,
+        steps: &[ast::Step],
+        var: &str,
+    ) -> Result<LogicalExpression> {
+        let mut predicates = steps.iter().map(|step| {
+            if !is_filter_step(step) {
+                return Err(Error::Internal(format!(
+                    "{step_name}() over a traversal with {step:?} needs subquery support, which is not available yet"
+                )));
+            }
+            self.translate_filter(step, var)
+        });
+        let first = predicates
+            .next()
+            .ok_or_else(|| Error::Internal(format!("{step_name}() needs a traversal")))??;
+        predicates.try_fold(first, |result, predicate| {
+            Ok(LogicalExpression::Binary {
+                left: Box::new(result),
+                op: BinaryOp::And,
+                right: Box::new(predicate?),
+            })
+        })
+    }
+
     fn translate_has_step(&self, has: &ast::HasStep, var: &str) -> Result<LogicalExpression> {
         match has {
             ast::HasStep::Key(key) => {
@@ -1127,6 +1766,20 @@ impl GremlinTranslator {
     }
 }
 
+/// Returns whether a step only filters, so it can become a predicate.
+fn is_filter_step(step: &ast::Step) -> bool {
+    matches!(
+        step,
+        ast::Step::Has(_)
+            | ast::Step::HasLabel(_)
+            | ast::Step::HasId(_)
+            | ast::Step::HasNot(_)
+            | ast::Step::Where(_)
+            | ast::Step::And(_)
+            | ast::Step::Or(_)
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1221,6 +1874,370 @@ mod tests {
 
     // === Filter Tests ===
 
+    #[test]
+    fn test_translate_repeat_times() {
+        let plan = translate("g.V().repeat(out('knows')).times(3)").unwrap();
+
+        fn find_expand(op: &LogicalOperator) -> Option<&ExpandOp> {
+            match op {
+                LogicalOperator::Expand(e) => Some(e),
+                LogicalOperator::Return(r) => find_expand(&r.input),
+                _ => None,
+            }
+        }
+
+        let expand = find_expand(&plan.root).expect("Expected Expand");
+        assert_eq!(expand.direction, ExpandDirection::Outgoing);
+        assert_eq!(expand.edge_type.as_deref(), Some("knows"));
+        assert_eq!(expand.min_hops, 3);
+        assert_eq!(expand.max_hops, Some(3));
+    }
+
+    #[test]
+    fn test_translate_repeat_until() {
+        let plan = translate("g.V().repeat(out()).until(has('name', 'X'))").unwrap();
+
+        fn find_filter(op: &LogicalOperator) -> Option<&FilterOp> {
+            match op {
+                LogicalOperator::Filter(f) => Some(f),
+                LogicalOperator::Return(r) => find_filter(&r.input),
+                _ => None,
+            }
+        }
+
+        let filter = find_filter(&plan.root).expect("Expected termination Filter");
+        let LogicalOperator::Expand(expand) = filter.input.as_ref() else {
+            panic!("Expected Expand under the termination filter");
+        };
+        assert_eq!(expand.min_hops, 1);
+        assert_eq!(expand.max_hops, None);
+    }
+
+    #[test]
+    fn test_translate_repeat_rejects_unsupported_body() {
+        let err = translate("g.V().repeat(out().has('name', 'X')).times(2)").unwrap_err();
+        assert!(err.to_string().contains("repeat()"), "{err}");
+
+        let err = translate("g.V().out().times(2)").unwrap_err();
+        assert!(err.to_string().contains("times()"), "{err}");
+    }
+
+    #[test]
+    fn test_translate_select_labels() {
+        let plan = translate("g.V().as('a').out('knows').as('b').select('a', 'b')").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let aliases: Vec<_> = ret.items.iter().map(|i| i.alias.as_deref()).collect();
+        assert_eq!(aliases, vec![Some("a"), Some("b")]);
+
+        let LogicalOperator::Expand(expand) = ret.input.as_ref() else {
+            panic!("Expected Expand, got {:?}", ret.input);
+        };
+        assert!(matches!(
+            &ret.items[0].expression,
+            LogicalExpression::Variable(v) if *v == expand.from_variable
+        ));
+        assert!(matches!(
+            &ret.items[1].expression,
+            LogicalExpression::Variable(v) if *v == expand.to_variable
+        ));
+
+        let plan = translate("g.V().as('a').select('a')").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        assert_eq!(ret.items.len(), 1);
+        assert_eq!(ret.items[0].alias.as_deref(), Some("a"));
+    }
+
+    #[test]
+    fn test_translate_select_by_property() {
+        let plan = translate("g.V().as('a').out().as('b').select('a', 'b').by('name')").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        for (item, alias) in ret.items.iter().zip(["a", "b"]) {
+            assert_eq!(item.alias.as_deref(), Some(alias));
+            assert!(matches!(
+                &item.expression,
+                LogicalExpression::Property { property, .. } if property == "name"
+            ));
+        }
+    }
+
+    #[test]
+    fn test_translate_project_by() {
+        let plan = translate("g.V().project('name', 'age').by('name').by('age')").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        assert_eq!(ret.items.len(), 2);
+        for (item, key) in ret.items.iter().zip(["name", "age"]) {
+            assert_eq!(item.alias.as_deref(), Some(key));
+            assert!(matches!(
+                &item.expression,
+                LogicalExpression::Property { property, .. } if property == key
+            ));
+        }
+    }
+
+    #[test]
+    fn test_translate_project_cycles_by_modulators() {
+        let plan = translate("g.V().project('a', 'b', 'c').by('name').by(T.id)").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let aliases: Vec<_> = ret.items.iter().map(|i| i.alias.as_deref()).collect();
+        assert_eq!(aliases, vec![Some("a"), Some("b"), Some("c")]);
+        assert!(
+            matches!(&ret.items[0].expression, LogicalExpression::Property { property, .. } if property == "name")
+        );
+        assert!(matches!(&ret.items[1].expression, LogicalExpression::Id(_)));
+        assert!(
+            matches!(&ret.items[2].expression, LogicalExpression::Property { property, .. } if property == "name")
+        );
+
+        // Without by() every key is the element itself
+        let plan = translate("g.V().project('x', 'y')").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        assert_eq!(ret.items.len(), 2);
+        assert!(
+            ret.items
+                .iter()
+                .all(|item| matches!(item.expression, LogicalExpression::Variable(_)))
+        );
+
+        assert!(translate("g.V().project('a').by('name').by('age')").is_err());
+    }
+
+    /// Returns the per-group aggregate under the map that group() and
+    /// groupCount() return.
+    fn group_aggregate(plan: &LogicalPlan) -> &AggregateOp {
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::Aggregate(map) = ret.input.as_ref() else {
+            panic!("Expected Aggregate, got {:?}", ret.input);
+        };
+        assert!(map.group_by.is_empty());
+        assert_eq!(map.aggregates[0].function, AggregateFunction::CollectMap);
+        let LogicalOperator::Aggregate(agg) = map.input.as_ref() else {
+            panic!("Expected Aggregate, got {:?}", map.input);
+        };
+        agg
+    }
+
+    #[test]
+    fn test_translate_group_count() {
+        let plan = translate("g.V().groupCount().by('age')").unwrap();
+        let agg = group_aggregate(&plan);
+        assert!(matches!(
+            &agg.group_by[..],
+            [LogicalExpression::Property { property, .. }] if property == "age"
+        ));
+        assert_eq!(agg.aggregates.len(), 1);
+        assert_eq!(agg.aggregates[0].function, AggregateFunction::Count);
+        assert!(agg.aggregates[0].expression.is_none());
+
+        // Without by(), elements are grouped by identity
+        let plan = translate("g.V().groupCount()").unwrap();
+        let agg = group_aggregate(&plan);
+        assert!(matches!(
+            &agg.group_by[..],
+            [LogicalExpression::Variable(_)]
+        ));
+    }
+
+    #[test]
+    fn test_translate_group_by_aggregate() {
+        for (query, function) in [
+            (
+                "g.V().group().by('age').by(count())",
+                AggregateFunction::Count,
+            ),
+            ("g.V().group().by('age').by(sum())", AggregateFunction::Sum),
+            (
+                "g.V().group().by('age').by(fold())",
+                AggregateFunction::Collect,
+            ),
+            ("g.V().group().by('age')", AggregateFunction::Collect),
+        ] {
+            let plan = translate(query).unwrap();
+            let agg = group_aggregate(&plan);
+            assert!(
+                matches!(
+                    &agg.group_by[..],
+                    [LogicalExpression::Property { property, .. }] if property == "age"
+                ),
+                "{query}"
+            );
+            assert_eq!(agg.aggregates.len(), 1, "{query}");
+            assert_eq!(agg.aggregates[0].function, function, "{query}");
+        }
+
+        let err = translate("g.V().group().by('age').by(mean())").unwrap_err();
+        assert!(err.to_string().contains("group()"), "{err}");
+        assert!(translate("g.V().groupCount().by('age').by('name')").is_err());
+    }
+
+    #[test]
+    fn test_translate_path() {
+        let plan = translate("g.V().out('knows').out('knows').path()").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalExpression::List(elements) = &ret.items[0].expression else {
+            panic!("Expected a list of path elements");
+        };
+        // The start vertex plus an edge and a vertex per hop
+        assert_eq!(elements.len(), 5);
+        assert!(matches!(&elements[0], LogicalExpression::Variable(v) if v == "_v0"));
+        assert_eq!(ret.items[0].alias.as_deref(), Some("path"));
+        let LogicalOperator::Expand(expand) = ret.input.as_ref() else {
+            panic!("Expected Expand, got {:?}", ret.input);
+        };
+        let edge = expand.edge_variable.as_deref().expect("hop edge bound");
+        assert!(matches!(&elements[3], LogicalExpression::Variable(v) if v == edge));
+
+        // Without a path() nothing refers to the edge, so it isn't bound
+        let plan = translate("g.V().out('knows').values('name')").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::Project(project) = ret.input.as_ref() else {
+            panic!("Expected Project, got {:?}", ret.input);
+        };
+        let LogicalOperator::Expand(expand) = project.input.as_ref() else {
+            panic!("Expected Expand, got {:?}", project.input);
+        };
+        assert!(expand.edge_variable.is_none());
+    }
+
+    #[test]
+    fn test_translate_simple_and_cyclic_path() {
+        fn count_ne(expr: &LogicalExpression) -> usize {
+            match expr {
+                LogicalExpression::Binary {
+                    op: BinaryOp::Ne, ..
+                } => 1,
+                LogicalExpression::Binary { left, right, .. } => count_ne(left) + count_ne(right),
+                LogicalExpression::Unary { operand, .. } => count_ne(operand),
+                _ => 0,
+            }
+        }
+
+        let plan = translate("g.V().out().out().simplePath()").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::Filter(filter) = ret.input.as_ref() else {
+            panic!("Expected Filter, got {:?}", ret.input);
+        };
+        // Three elements, so three pairs that must differ
+        assert_eq!(count_ne(&filter.predicate), 3);
+        assert!(matches!(filter.input.as_ref(), LogicalOperator::Expand(_)));
+
+        let plan = translate("g.V().out().cyclicPath()").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::Filter(filter) = ret.input.as_ref() else {
+            panic!("Expected Filter, got {:?}", ret.input);
+        };
+        assert!(matches!(
+            &filter.predicate,
+            LogicalExpression::Unary {
+                op: UnaryOp::Not,
+                ..
+            }
+        ));
+        assert_eq!(count_ne(&filter.predicate), 1);
+    }
+
+    #[test]
+    fn test_translate_union() {
+        let plan = translate("g.V().union(out('knows'), in('likes'))").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::Union(union) = ret.input.as_ref() else {
+            panic!("Expected Union, got {:?}", ret.input);
+        };
+        assert_eq!(union.inputs.len(), 2);
+
+        // Both branches expand from the same element and end up in the
+        // variable the traversal continues with
+        let LogicalExpression::Variable(output) = &ret.items[0].expression else {
+            panic!("Expected the union's output variable");
+        };
+        for (branch, direction) in union
+            .inputs
+            .iter()
+            .zip([ExpandDirection::Outgoing, ExpandDirection::Incoming])
+        {
+            let LogicalOperator::Project(project) = branch else {
+                panic!("Expected a projected branch, got {branch:?}");
+            };
+            let LogicalOperator::Expand(expand) = project.input.as_ref() else {
+                panic!("Expected Expand in branch, got {:?}", project.input);
+            };
+            assert_eq!(expand.from_variable, "_v0");
+            assert_eq!(expand.direction, direction);
+            let last = project.projections.last().unwrap();
+            assert_eq!(last.alias.as_deref(), Some(output.as_str()));
+            assert!(
+                matches!(&last.expression, LogicalExpression::Variable(v) if *v == expand.to_variable)
+            );
+        }
+    }
+
+    #[test]
+    fn test_translate_coalesce() {
+        let plan = translate("g.V().coalesce(out('a'), out('b'), out('c'))").unwrap();
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::Union(union) = ret.input.as_ref() else {
+            panic!("Expected Union, got {:?}", ret.input);
+        };
+        assert_eq!(union.inputs.len(), 3);
+
+        // Each later branch only sees rows the earlier ones left empty
+        for (i, branch) in union.inputs.iter().enumerate() {
+            let LogicalOperator::Project(project) = branch else {
+                panic!("Expected a projected branch, got {branch:?}");
+            };
+            let LogicalOperator::Expand(expand) = project.input.as_ref() else {
+                panic!("Expected Expand in branch, got {:?}", project.input);
+            };
+            let mut anti_joins = 0;
+            let mut op = expand.input.as_ref();
+            while let LogicalOperator::AntiJoin(anti_join) = op {
+                anti_joins += 1;
+                op = &anti_join.left;
+            }
+            assert_eq!(anti_joins, i);
+        }
+    }
+
+    #[test]
+    fn test_translate_union_needs_branches() {
+        let err = translate("g.V().union()").unwrap_err();
+        assert!(err.to_string().contains("union()"), "{err}");
+
+        let err = translate("g.V().coalesce()").unwrap_err();
+        assert!(err.to_string().contains("coalesce()"), "{err}");
+    }
+
+    #[test]
+    fn test_translate_select_undefined_label() {
+        let err = translate("g.V().as('a').select('a', 'missing')").unwrap_err();
+        assert!(err.to_string().contains("'missing'"), "{err}");
+    }
+
     #[test]
     fn test_translate_has_key_value() {
         let result = translate("g.V().has('name', 'Alice')");
@@ -1261,6 +2278,124 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_translate_where_against_label() {
+        let plan = translate("g.V().as('a').out('knows').where(P.gt('a'))").unwrap();
+
+        fn find_filter(op: &LogicalOperator) -> Option<&FilterOp> {
+            match op {
+                LogicalOperator::Filter(f) => Some(f),
+                LogicalOperator::Return(r) => find_filter(&r.input),
+                _ => None,
+            }
+        }
+
+        let filter = find_filter(&plan.root).expect("Expected Filter");
+        let LogicalOperator::Expand(expand) = filter.input.as_ref() else {
+            panic!("Expected Expand under the filter");
+        };
+        let LogicalExpression::Binary { left, op, right } = &filter.predicate else {
+            panic!("Expected Binary predicate, got {:?}", filter.predicate);
+        };
+        assert_eq!(*op, BinaryOp::Gt);
+        assert!(
+            matches!(left.as_ref(), LogicalExpression::Variable(v) if *v == expand.to_variable)
+        );
+        assert!(
+            matches!(right.as_ref(), LogicalExpression::Variable(v) if *v == expand.from_variable)
+        );
+
+        let err = translate("g.V().where(P.eq('nope'))").unwrap_err();
+        assert!(err.to_string().contains("'nope'"), "{err}");
+    }
+
+    #[test]
+    fn test_translate_and_or() {
+        fn find_filter(op: &LogicalOperator) -> Option<&FilterOp> {
+            match op {
+                LogicalOperator::Filter(f) => Some(f),
+                LogicalOperator::Return(r) => find_filter(&r.input),
+                _ => None,
+            }
+        }
+
+        let plan = translate("g.V().and(has('age'), hasNot('deleted'))").unwrap();
+        let filter = find_filter(&plan.root).expect("Expected Filter");
+        let LogicalExpression::Binary { left, op, right } = &filter.predicate else {
+            panic!("Expected Binary predicate, got {:?}", filter.predicate);
+        };
+        assert_eq!(*op, BinaryOp::And);
+        assert!(matches!(
+            left.as_ref(),
+            LogicalExpression::Unary {
+                op: UnaryOp::IsNotNull,
+                ..
+            }
+        ));
+        assert!(matches!(
+            right.as_ref(),
+            LogicalExpression::Unary {
+                op: UnaryOp::IsNull,
+                ..
+            }
+        ));
+
+        // A branch with several steps keeps elements passing all of them
+        let plan = translate("g.V().or(has('name', 'Alice'), hasLabel('Bot').has('age'))").unwrap();
+        let filter = find_filter(&plan.root).expect("Expected Filter");
+        let LogicalExpression::Binary { left, op, right } = &filter.predicate else {
+            panic!("Expected Binary predicate, got {:?}", filter.predicate);
+        };
+        assert_eq!(*op, BinaryOp::Or);
+        assert!(matches!(
+            left.as_ref(),
+            LogicalExpression::Binary {
+                op: BinaryOp::Eq,
+                ..
+            }
+        ));
+        assert!(matches!(
+            right.as_ref(),
+            LogicalExpression::Binary {
+                op: BinaryOp::And,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_translate_where_traversal_needs_subqueries() {
+        let err = translate("g.V().where(out('knows'))").unwrap_err();
+        assert!(err.to_string().contains("subquery"), "{err}");
+
+        let err = translate("g.V().or(has('age'), out())").unwrap_err();
+        assert!(err.to_string().contains("or()"), "{err}");
+
+        // Filter-only traversals don't need a subquery
+        assert!(translate("g.V().where(has('age'))").is_ok());
+    }
+
+    #[test]
+    fn test_translate_not() {
+        let result = translate("g.V().not(out('knows'))");
+        assert!(result.is_ok());
+        let plan = result.unwrap();
+
+        fn find_anti_join(op: &LogicalOperator) -> Option<&AntiJoinOp> {
+            match op {
+                LogicalOperator::AntiJoin(j) => Some(j),
+                LogicalOperator::Return(r) => find_anti_join(&r.input),
+                _ => None,
+            }
+        }
+
+        let anti_join = find_anti_join(&plan.root).expect("Expected AntiJoin");
+        assert!(matches!(
+            anti_join.right.as_ref(),
+            LogicalOperator::Expand(e) if e.edge_type.as_deref() == Some("knows")
+        ));
+    }
+
     #[test]
     fn test_translate_dedup() {
         let result = translate("g.V().dedup()");
@@ -1384,6 +2519,39 @@ mod tests {
         assert_eq!(agg.aggregates[0].function, AggregateFunction::Collect);
     }
 
+    #[test]
+    fn test_translate_fold_unfold_round_trip() {
+        let result = translate("g.V().fold().unfold()");
+        assert!(result.is_ok());
+        let plan = result.unwrap();
+
+        // Return(Unwind(Aggregate(collect)))
+        if let LogicalOperator::Return(ret) = &plan.root {
+            if let LogicalOperator::Unwind(unwind) = ret.input.as_ref() {
+                if let LogicalExpression::Variable(list_var) = &unwind.expression {
+                    assert_eq!(list_var, "fold");
+                } else {
+                    panic!("Expected unwind over the folded column");
+                }
+                if let LogicalOperator::Aggregate(agg) = unwind.input.as_ref() {
+                    assert_eq!(agg.aggregates[0].function, AggregateFunction::Collect);
+                } else {
+                    panic!("Expected Aggregate operator inside Unwind");
+                }
+                // The unwound element is what gets returned
+                if let LogicalExpression::Variable(var) = &ret.items[0].expression {
+                    assert_eq!(var, &unwind.variable);
+                } else {
+                    panic!("Expected Return of the unwound element");
+                }
+            } else {
+                panic!("Expected Unwind operator inside Return");
+            }
+        } else {
+            panic!("Expected Return operator");
+        }
+    }
+
     // === Map Steps ===
 
     #[test]
@@ -1400,6 +2568,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_translate_value_map() {
+        fn property_map(query: &str) -> (Vec<String>, bool) {
+            let plan = translate(query).unwrap();
+            let LogicalOperator::Return(ret) = &plan.root else {
+                panic!("Expected Return, got {:?}", plan.root);
+            };
+            assert_eq!(ret.items.len(), 1);
+            match &ret.items[0].expression {
+                LogicalExpression::PropertyMap {
+                    keys,
+                    include_id_label,
+                    ..
+                } => (keys.clone(), *include_id_label),
+                other => panic!("Expected PropertyMap, got {other:?}"),
+            }
+        }
+
+        assert_eq!(property_map("g.V().valueMap()"), (vec![], false));
+        assert_eq!(
+            property_map("g.V().valueMap('name', 'age')"),
+            (vec!["name".to_string(), "age".to_string()], false)
+        );
+        assert_eq!(property_map("g.V().valueMap(true)"), (vec![], true));
+        assert_eq!(
+            property_map("g.V().elementMap('name')"),
+            (vec!["name".to_string()], true)
+        );
+    }
+
     #[test]
     fn test_translate_id() {
         let result = translate("g.V().id()");
@@ -1565,6 +2763,77 @@ mod tests {
         assert_eq!(edge.properties[0].0, "since");
     }
 
+    #[test]
+    fn test_translate_add_e_to_traversal() {
+        let plan =
+            translate("g.V().has('name', 'a').addE('knows').to(V().has('name', 'b'))").unwrap();
+
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::CreateEdge(edge) = ret.input.as_ref() else {
+            panic!("Expected CreateEdge, got {:?}", ret.input);
+        };
+        let LogicalOperator::Join(join) = edge.input.as_ref() else {
+            panic!("Expected Join, got {:?}", edge.input);
+        };
+        // Each endpoint comes from its own filtered scan
+        let LogicalOperator::Filter(from) = join.left.as_ref() else {
+            panic!("Expected Filter, got {:?}", join.left);
+        };
+        let LogicalOperator::Filter(to) = join.right.as_ref() else {
+            panic!("Expected Filter, got {:?}", join.right);
+        };
+        let LogicalOperator::NodeScan(from_scan) = from.input.as_ref() else {
+            panic!("Expected NodeScan, got {:?}", from.input);
+        };
+        let LogicalOperator::NodeScan(to_scan) = to.input.as_ref() else {
+            panic!("Expected NodeScan, got {:?}", to.input);
+        };
+        assert_eq!(edge.from_variable, from_scan.variable);
+        assert_eq!(edge.to_variable, to_scan.variable);
+    }
+
+    #[test]
+    fn test_translate_add_e_from_and_to_traversals() {
+        let plan = translate(
+            "g.addE('knows').from(__.V().has('name', 'a')).to(V().has('name', 'b')).property('since', 2020)",
+        )
+        .unwrap();
+
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::CreateEdge(edge) = ret.input.as_ref() else {
+            panic!("Expected CreateEdge, got {:?}", ret.input);
+        };
+        let LogicalOperator::Join(join) = edge.input.as_ref() else {
+            panic!("Expected Join, got {:?}", edge.input);
+        };
+        assert!(matches!(join.left.as_ref(), LogicalOperator::Filter(_)));
+        assert!(matches!(join.right.as_ref(), LogicalOperator::Filter(_)));
+        assert_ne!(edge.from_variable, edge.to_variable);
+        assert_eq!(edge.properties.len(), 1);
+    }
+
+    #[test]
+    fn test_translate_add_e_to_labelled_vertex() {
+        let plan = translate("g.V().as('x').out('knows').addE('likes').to('x')").unwrap();
+
+        let LogicalOperator::Return(ret) = &plan.root else {
+            panic!("Expected Return, got {:?}", plan.root);
+        };
+        let LogicalOperator::CreateEdge(edge) = ret.input.as_ref() else {
+            panic!("Expected CreateEdge, got {:?}", ret.input);
+        };
+        let LogicalOperator::Expand(expand) = edge.input.as_ref() else {
+            panic!("Expected Expand, got {:?}", edge.input);
+        };
+        // to('x') refers to the vertex labelled with as('x')
+        assert_eq!(edge.from_variable, expand.to_variable);
+        assert_eq!(edge.to_variable, expand.from_variable);
+    }
+
     // === Order Tests ===
 
     #[test]
@@ -12,9 +12,11 @@
 //! [`Session::execute()`](crate::Session::execute). But if you're building
 //! custom query processing, [`QueryProcessor`] is the unified interface.
 
+pub mod advisor;
 pub mod binder;
 pub mod cache;
 pub mod executor;
+pub mod explain;
 pub mod optimizer;
 pub mod plan;
 pub mod planner;
@@ -38,12 +40,19 @@ pub mod gremlin_translator;
 #[cfg(feature = "graphql")]
 pub mod graphql_translator;
 
+#[cfg(feature = "graphql")]
+pub mod graphql_schema;
+
 #[cfg(all(feature = "graphql", feature = "rdf"))]
 pub mod graphql_rdf_translator;
 
 // Core exports
-pub use cache::{CacheKey, CacheStats, CachingQueryProcessor, QueryCache};
-pub use executor::Executor;
+pub use advisor::{IndexAdvisor, IndexRecommendation};
+pub use cache::{
+    CacheKey, CacheStats, CachingQueryProcessor, QueryCache, ResultCache, ResultCacheKey,
+};
+pub use executor::{Executor, QueryOutput, ResultStream};
+pub use explain::{ExplainNode, PlanExplanation};
 pub use optimizer::{CardinalityEstimator, Optimizer};
 pub use plan::{LogicalExpression, LogicalOperator, LogicalPlan};
 pub use planner::{
@@ -15,9 +15,11 @@
 //! - Adaptive to actual data characteristics
 
 use crate::query::plan::{
-    AggregateOp, BinaryOp, DistinctOp, ExpandOp, FilterOp, JoinOp, JoinType, LimitOp,
-    LogicalExpression, LogicalOperator, NodeScanOp, ProjectOp, SkipOp, SortOp, UnaryOp,
+    AggregateOp, BinaryOp, DistinctOp, ExpandDirection, ExpandOp, FilterOp, JoinOp, JoinType,
+    LimitOp, LogicalExpression, LogicalOperator, NodeScanOp, ProjectOp, SkipOp, SortOp, UnaryOp,
 };
+use grafeo_common::types::Value;
+use grafeo_core::statistics::Statistics;
 use std::collections::HashMap;
 
 /// A bucket in an equi-depth histogram.
@@ -402,6 +404,8 @@ pub struct CardinalityEstimator {
     default_selectivity: f64,
     /// Average edge fanout (outgoing edges per node).
     avg_fanout: f64,
+    /// Average outgoing and incoming degree per edge type.
+    edge_type_degrees: HashMap<String, (f64, f64)>,
 }
 
 impl CardinalityEstimator {
@@ -413,14 +417,66 @@ impl CardinalityEstimator {
             default_row_count: 1000,
             default_selectivity: 0.1,
             avg_fanout: 10.0,
+            edge_type_degrees: HashMap::new(),
         }
     }
 
+    /// Creates an estimator seeded from collected graph statistics.
+    ///
+    /// Label row counts come from the per-label node counts, unlabeled scans
+    /// assume every node, and the fanout is the graph-wide edges per node,
+    /// or the edge type's average degree when an expand names one. Property
+    /// statistics collected by `ANALYZE` give equality and range
+    /// selectivities for their label.
+    #[must_use]
+    pub fn from_statistics(stats: &Statistics) -> Self {
+        let mut estimator = Self::new();
+        for (label, label_stats) in &stats.labels {
+            let table = label_stats.properties.iter().fold(
+                TableStats::new(label_stats.node_count),
+                |table, (property, column)| {
+                    let mut stats =
+                        ColumnStats::new(column.distinct_count).with_nulls(column.null_count);
+                    let bound = |value: &Option<Value>| match value {
+                        Some(Value::Int64(i)) => Some(*i as f64),
+                        Some(Value::Float64(f)) => Some(*f),
+                        _ => None,
+                    };
+                    if let (Some(min), Some(max)) =
+                        (bound(&column.min_value), bound(&column.max_value))
+                    {
+                        stats = stats.with_range(min, max);
+                    }
+                    table.with_column(property, stats)
+                },
+            );
+            estimator.add_table_stats(label, table);
+        }
+        for (edge_type, edge_stats) in &stats.edge_types {
+            estimator.add_edge_type_degrees(
+                edge_type,
+                edge_stats.avg_out_degree,
+                edge_stats.avg_in_degree,
+            );
+        }
+        estimator.default_row_count = stats.total_nodes;
+        if stats.total_nodes > 0 {
+            estimator.avg_fanout = stats.total_edges as f64 / stats.total_nodes as f64;
+        }
+        estimator
+    }
+
     /// Adds statistics for a table/label.
     pub fn add_table_stats(&mut self, name: &str, stats: TableStats) {
         self.table_stats.insert(name.to_string(), stats);
     }
 
+    /// Sets the average outgoing and incoming degree for an edge type.
+    pub fn add_edge_type_degrees(&mut self, edge_type: &str, out_degree: f64, in_degree: f64) {
+        self.edge_type_degrees
+            .insert(edge_type.to_string(), (out_degree, in_degree));
+    }
+
     /// Sets the average edge fanout.
     pub fn set_avg_fanout(&mut self, fanout: f64) {
         self.avg_fanout = fanout;
@@ -437,6 +493,7 @@ impl CardinalityEstimator {
             LogicalOperator::Join(join) => self.estimate_join(join),
             LogicalOperator::Aggregate(agg) => self.estimate_aggregate(agg),
             LogicalOperator::Sort(sort) => self.estimate_sort(sort),
+            LogicalOperator::TopN(top) => self.estimate(&top.input).min(top.count as f64),
             LogicalOperator::Distinct(distinct) => self.estimate_distinct(distinct),
             LogicalOperator::Limit(limit) => self.estimate_limit(limit),
             LogicalOperator::Skip(skip) => self.estimate_skip(skip),
@@ -447,11 +504,18 @@ impl CardinalityEstimator {
     }
 
     /// Estimates node scan cardinality.
+    ///
+    /// A node with several labels is in every one of their tables, so the
+    /// smallest table bounds the scan.
     fn estimate_node_scan(&self, scan: &NodeScanOp) -> f64 {
-        if let Some(label) = &scan.label {
-            if let Some(stats) = self.table_stats.get(label) {
-                return stats.row_count as f64;
-            }
+        let smallest = scan
+            .labels
+            .iter()
+            .filter_map(|label| self.table_stats.get(label))
+            .map(|stats| stats.row_count)
+            .min();
+        if let Some(row_count) = smallest {
+            return row_count as f64;
         }
         // No label filter - scan all nodes
         self.default_row_count as f64
@@ -474,11 +538,19 @@ impl CardinalityEstimator {
         let input_cardinality = self.estimate(&expand.input);
 
         // Apply fanout based on edge type
-        let fanout = if expand.edge_type.is_some() {
+        let known_degrees = expand
+            .edge_type
+            .as_ref()
+            .and_then(|edge_type| self.edge_type_degrees.get(edge_type));
+        let fanout = match (known_degrees, expand.edge_type.is_some()) {
+            (Some(&(out_degree, in_degree)), _) => match expand.direction {
+                ExpandDirection::Outgoing => out_degree,
+                ExpandDirection::Incoming => in_degree,
+                ExpandDirection::Both => out_degree + in_degree,
+            },
             // Specific edge type typically has lower fanout
-            self.avg_fanout * 0.5
-        } else {
-            self.avg_fanout
+            (None, true) => self.avg_fanout * 0.5,
+            (None, false) => self.avg_fanout,
         };
 
         // Handle variable-length paths
@@ -552,8 +624,9 @@ impl CardinalityEstimator {
         }
     }
 
-    /// Estimates aggregation cardinality.
-    fn estimate_aggregate(&self, agg: &AggregateOp) -> f64 {
+    /// Estimates aggregation cardinality (the number of groups).
+    #[must_use]
+    pub fn estimate_aggregate(&self, agg: &AggregateOp) -> f64 {
         let input_cardinality = self.estimate(&agg.input);
 
         if agg.group_by.is_empty() {
@@ -859,7 +932,7 @@ mod tests {
 
         let scan = LogicalOperator::NodeScan(NodeScanOp {
             variable: "n".to_string(),
-            label: Some("Person".to_string()),
+            labels: vec!["Person".to_string()],
             input: None,
         });
 
@@ -883,7 +956,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -903,12 +976,12 @@ mod tests {
         let join = LogicalOperator::Join(JoinOp {
             left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "p".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "c".to_string(),
-                label: Some("Company".to_string()),
+                labels: vec!["Company".to_string()],
                 input: None,
             })),
             join_type: JoinType::Inner,
@@ -938,7 +1011,7 @@ mod tests {
             count: 10,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -958,7 +1031,7 @@ mod tests {
             aggregates: vec![],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             having: None,
@@ -976,7 +1049,7 @@ mod tests {
             aggregates: vec![],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             having: None,
@@ -993,7 +1066,7 @@ mod tests {
 
         let scan = LogicalOperator::NodeScan(NodeScanOp {
             variable: "n".to_string(),
-            label: Some("Unknown".to_string()),
+            labels: vec!["Unknown".to_string()],
             input: None,
         });
 
@@ -1008,7 +1081,7 @@ mod tests {
 
         let scan = LogicalOperator::NodeScan(NodeScanOp {
             variable: "n".to_string(),
-            label: None,
+            labels: Vec::new(),
             input: None,
         });
 
@@ -1033,7 +1106,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1059,7 +1132,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1099,7 +1172,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1141,7 +1214,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1161,7 +1234,7 @@ mod tests {
             predicate: LogicalExpression::Literal(Value::Bool(true)),
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1180,7 +1253,7 @@ mod tests {
             predicate: LogicalExpression::Literal(Value::Bool(false)),
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1202,7 +1275,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1224,7 +1297,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1249,7 +1322,7 @@ mod tests {
             max_hops: Some(1),
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "a".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             path_alias: None,
@@ -1275,7 +1348,7 @@ mod tests {
             max_hops: Some(1),
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "a".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             path_alias: None,
@@ -1286,6 +1359,35 @@ mod tests {
         assert!(cardinality > 100.0);
     }
 
+    #[test]
+    fn test_expand_uses_edge_type_degrees() {
+        let mut estimator = CardinalityEstimator::new();
+        estimator.add_table_stats("Person", TableStats::new(100));
+        estimator.add_edge_type_degrees("MANAGES", 0.2, 3.0);
+
+        let expand = |direction| {
+            LogicalOperator::Expand(ExpandOp {
+                from_variable: "a".to_string(),
+                to_variable: "b".to_string(),
+                edge_variable: None,
+                direction,
+                edge_type: Some("MANAGES".to_string()),
+                min_hops: 1,
+                max_hops: Some(1),
+                input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
+                    variable: "a".to_string(),
+                    labels: vec!["Person".to_string()],
+                    input: None,
+                })),
+                path_alias: None,
+            })
+        };
+
+        assert!((estimator.estimate(&expand(ExpandDirection::Outgoing)) - 20.0).abs() < 1e-9);
+        assert!((estimator.estimate(&expand(ExpandDirection::Incoming)) - 300.0).abs() < 1e-9);
+        assert!((estimator.estimate(&expand(ExpandDirection::Both)) - 320.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_expand_variable_length() {
         let mut estimator = CardinalityEstimator::new();
@@ -1301,7 +1403,7 @@ mod tests {
             max_hops: Some(3),
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "a".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             path_alias: None,
@@ -1321,12 +1423,12 @@ mod tests {
         let join = LogicalOperator::Join(JoinOp {
             left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "p".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "c".to_string(),
-                label: Some("Company".to_string()),
+                labels: vec!["Company".to_string()],
                 input: None,
             })),
             join_type: JoinType::Cross,
@@ -1347,12 +1449,12 @@ mod tests {
         let join = LogicalOperator::Join(JoinOp {
             left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "p".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "c".to_string(),
-                label: Some("Company".to_string()),
+                labels: vec!["Company".to_string()],
                 input: None,
             })),
             join_type: JoinType::Left,
@@ -1376,12 +1478,12 @@ mod tests {
         let join = LogicalOperator::Join(JoinOp {
             left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "p".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "c".to_string(),
-                label: Some("Company".to_string()),
+                labels: vec!["Company".to_string()],
                 input: None,
             })),
             join_type: JoinType::Semi,
@@ -1402,12 +1504,12 @@ mod tests {
         let join = LogicalOperator::Join(JoinOp {
             left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "p".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "c".to_string(),
-                label: Some("Company".to_string()),
+                labels: vec!["Company".to_string()],
                 input: None,
             })),
             join_type: JoinType::Anti,
@@ -1432,7 +1534,7 @@ mod tests {
             }],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1453,7 +1555,7 @@ mod tests {
             }],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1470,7 +1572,7 @@ mod tests {
         let distinct = LogicalOperator::Distinct(DistinctOp {
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             columns: None,
@@ -1490,7 +1592,7 @@ mod tests {
             count: 100,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1512,7 +1614,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1548,13 +1650,59 @@ mod tests {
         let estimator = CardinalityEstimator::default();
         let scan = LogicalOperator::NodeScan(NodeScanOp {
             variable: "n".to_string(),
-            label: None,
+            labels: Vec::new(),
             input: None,
         });
         let cardinality = estimator.estimate(&scan);
         assert!((cardinality - 1000.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_from_statistics_keeps_analyzed_properties() {
+        use grafeo_core::statistics::{ColumnStatistics, LabelStatistics};
+
+        let mut stats = Statistics::new();
+        stats.update_label(
+            "Person",
+            LabelStatistics::new(100).with_property(
+                "age",
+                ColumnStatistics::new(40, 100, 5).with_min_max(Value::Int64(18), Value::Int64(90)),
+            ),
+        );
+
+        let estimator = CardinalityEstimator::from_statistics(&stats);
+        let age = &estimator.table_stats["Person"].columns["age"];
+        assert_eq!(age.distinct_count, 40);
+        assert_eq!(age.null_count, 5);
+        assert_eq!((age.min_value, age.max_value), (Some(18.0), Some(90.0)));
+    }
+
+    #[test]
+    fn test_from_statistics() {
+        use grafeo_core::statistics::LabelStatistics;
+
+        let mut stats = Statistics::new();
+        stats.total_nodes = 200;
+        stats.total_edges = 400;
+        stats.update_label("Person", LabelStatistics::new(150));
+
+        let estimator = CardinalityEstimator::from_statistics(&stats);
+        let person = LogicalOperator::NodeScan(NodeScanOp {
+            variable: "n".to_string(),
+            labels: vec!["Person".to_string()],
+            input: None,
+        });
+        let all = LogicalOperator::NodeScan(NodeScanOp {
+            variable: "n".to_string(),
+            labels: Vec::new(),
+            input: None,
+        });
+
+        assert!((estimator.estimate(&person) - 150.0).abs() < 0.001);
+        assert!((estimator.estimate(&all) - 200.0).abs() < 0.001);
+        assert!((estimator.avg_fanout - 2.0).abs() < 0.001);
+    }
+
     #[test]
     fn test_set_avg_fanout() {
         let mut estimator = CardinalityEstimator::new();
@@ -1571,7 +1719,7 @@ mod tests {
             max_hops: Some(1),
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "a".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             path_alias: None,
@@ -1598,7 +1746,7 @@ mod tests {
             aggregates: vec![],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             having: None,
@@ -1618,7 +1766,7 @@ mod tests {
             aggregates: vec![],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             having: None,
@@ -1795,7 +1943,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         });
@@ -1832,7 +1980,7 @@ mod tests {
             },
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "d".to_string(),
-                label: Some("Data".to_string()),
+                labels: vec!["Data".to_string()],
                 input: None,
             })),
         });
@@ -4,7 +4,7 @@
 
 use crate::query::plan::{
     AggregateOp, DistinctOp, ExpandOp, FilterOp, JoinOp, JoinType, LimitOp, LogicalOperator,
-    NodeScanOp, ProjectOp, ReturnOp, SkipOp, SortOp,
+    NodeScanOp, ProjectOp, ReturnOp, SkipOp, SortOp, TopNOp,
 };
 
 /// Cost of an operation.
@@ -138,6 +138,7 @@ impl CostModel {
             LogicalOperator::Join(join) => self.join_cost(join, cardinality),
             LogicalOperator::Aggregate(agg) => self.aggregate_cost(agg, cardinality),
             LogicalOperator::Sort(sort) => self.sort_cost(sort, cardinality),
+            LogicalOperator::TopN(top) => self.top_n_cost(top, cardinality),
             LogicalOperator::Distinct(distinct) => self.distinct_cost(distinct, cardinality),
             LogicalOperator::Limit(limit) => self.limit_cost(limit, cardinality),
             LogicalOperator::Skip(skip) => self.skip_cost(skip, cardinality),
@@ -248,6 +249,22 @@ impl CostModel {
         Cost::cpu(comparisons * self.sort_comparison_cost * key_count).with_memory(memory_cost)
     }
 
+    /// Estimates the cost of a top-N, given its output cardinality.
+    ///
+    /// Each input row is compared against a heap of at most `count` rows, and
+    /// only those rows are held in memory.
+    fn top_n_cost(&self, top: &TopNOp, cardinality: f64) -> Cost {
+        if cardinality <= 1.0 {
+            return Cost::zero();
+        }
+
+        let comparisons = cardinality * cardinality.log2();
+        let key_count = top.keys.len() as f64;
+        let memory_cost = cardinality * self.avg_tuple_size;
+
+        Cost::cpu(comparisons * self.sort_comparison_cost * key_count).with_memory(memory_cost)
+    }
+
     /// Estimates the cost of a distinct operation.
     fn distinct_cost(&self, _distinct: &DistinctOp, cardinality: f64) -> Cost {
         // Hash-based distinct
@@ -320,7 +337,7 @@ mod tests {
         let model = CostModel::new();
         let scan = NodeScanOp {
             variable: "n".to_string(),
-            label: Some("Person".to_string()),
+            labels: vec!["Person".to_string()],
             input: None,
         };
         let cost = model.node_scan_cost(&scan, 1000.0);
@@ -648,7 +665,7 @@ mod tests {
         let model = CostModel::default();
         let scan = NodeScanOp {
             variable: "n".to_string(),
-            label: None,
+            labels: Vec::new(),
             input: None,
         };
         let cost = model.estimate(&LogicalOperator::NodeScan(scan), 100.0);
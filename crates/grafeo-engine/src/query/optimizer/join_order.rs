@@ -6,6 +6,9 @@
 //! 2. Enumerating all connected subgraphs
 //! 3. Finding optimal plans for each subgraph using dynamic programming
 //!
+//! Graphs with more relations than DPccp can enumerate in reasonable time
+//! fall back to [`GreedyJoinOrder`].
+//!
 //! Reference: Moerkotte, G., & Neumann, T. (2006). Analysis of Two Existing and
 //! One New Dynamic Programming Algorithm for the Generation of Optimal Bushy
 //! Join Trees without Cross Products.
@@ -467,6 +470,109 @@ impl<'a> DPccp<'a> {
     }
 }
 
+/// Greedy join ordering for graphs too large for [`DPccp`].
+///
+/// Starts with every relation as its own plan and repeatedly joins the pair
+/// of connected plans with the smallest estimated result, until one plan
+/// covers the whole graph. Runs in polynomial time, but may miss the
+/// optimal order.
+pub struct GreedyJoinOrder<'a> {
+    /// The join graph.
+    graph: &'a JoinGraph,
+    /// Cost model for estimating operator costs.
+    cost_model: &'a CostModel,
+    /// Cardinality estimator.
+    card_estimator: &'a CardinalityEstimator,
+}
+
+impl<'a> GreedyJoinOrder<'a> {
+    /// Creates a new greedy join optimizer.
+    pub fn new(
+        graph: &'a JoinGraph,
+        cost_model: &'a CostModel,
+        card_estimator: &'a CardinalityEstimator,
+    ) -> Self {
+        Self {
+            graph,
+            cost_model,
+            card_estimator,
+        }
+    }
+
+    /// Finds a join order for the graph.
+    ///
+    /// Returns `None` for an empty graph, or one whose relations aren't all
+    /// connected by join conditions.
+    pub fn optimize(&self) -> Option<JoinPlan> {
+        let mut plans: Vec<JoinPlan> = self
+            .graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let cardinality = self.card_estimator.estimate(&node.relation);
+                JoinPlan {
+                    nodes: BitSet::singleton(i),
+                    operator: node.relation.clone(),
+                    cost: self.cost_model.estimate(&node.relation, cardinality),
+                    cardinality,
+                }
+            })
+            .collect();
+
+        while plans.len() > 1 {
+            let mut best: Option<(usize, usize, JoinPlan)> = None;
+            for i in 0..plans.len() {
+                for j in (i + 1)..plans.len() {
+                    if !self.graph.are_connected(&plans[i].nodes, &plans[j].nodes) {
+                        continue;
+                    }
+                    let candidate = self.join(&plans[i], &plans[j]);
+                    let better = best.as_ref().is_none_or(|(_, _, current)| {
+                        (candidate.cardinality, candidate.cost.total())
+                            < (current.cardinality, current.cost.total())
+                    });
+                    if better {
+                        best = Some((i, j, candidate));
+                    }
+                }
+            }
+
+            // No connected pair left: the graph needs a cross product
+            let (i, j, joined) = best?;
+            plans.swap_remove(j);
+            plans[i] = joined;
+        }
+
+        plans.pop()
+    }
+
+    /// Joins two plans, putting the smaller input on the right, which the
+    /// hash join builds its table from.
+    fn join(&self, a: &JoinPlan, b: &JoinPlan) -> JoinPlan {
+        let (left, right) = if a.cardinality >= b.cardinality {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        let join_op = LogicalOperator::Join(JoinOp {
+            left: Box::new(left.operator.clone()),
+            right: Box::new(right.operator.clone()),
+            join_type: JoinType::Inner,
+            conditions: self.graph.get_conditions(&left.nodes, &right.nodes),
+        });
+        let cardinality = self.card_estimator.estimate(&join_op);
+        let cost = left.cost + right.cost + self.cost_model.estimate(&join_op, cardinality);
+
+        JoinPlan {
+            nodes: left.nodes.union(right.nodes),
+            operator: join_op,
+            cost,
+            cardinality,
+        }
+    }
+}
+
 /// Extracts a join graph from a query pattern.
 pub struct JoinGraphBuilder {
     graph: JoinGraph,
@@ -532,7 +638,7 @@ mod tests {
     fn create_node_scan(var: &str, label: &str) -> LogicalOperator {
         LogicalOperator::NodeScan(NodeScanOp {
             variable: var.to_string(),
-            label: Some(label.to_string()),
+            labels: vec![label.to_string()],
             input: None,
         })
     }
@@ -1004,6 +1110,48 @@ mod tests {
         assert!(plan.cost.total() > 0.0);
     }
 
+    #[test]
+    fn test_greedy_covers_connected_graph_and_rejects_cross_products() {
+        let mut builder = JoinGraphBuilder::new();
+        for (var, label) in [("a", "A"), ("b", "B"), ("c", "C")] {
+            builder.add_relation(var, create_node_scan(var, label));
+        }
+        builder.add_join_condition(
+            "a",
+            "b",
+            LogicalExpression::Variable("a".to_string()),
+            LogicalExpression::Variable("b".to_string()),
+        );
+        let cost_model = CostModel::new();
+        let card_estimator = CardinalityEstimator::new();
+
+        // `c` has no join condition, so covering it needs a cross product
+        let disconnected = builder.build();
+        assert!(
+            GreedyJoinOrder::new(&disconnected, &cost_model, &card_estimator)
+                .optimize()
+                .is_none()
+        );
+
+        let mut builder = JoinGraphBuilder::new();
+        for (var, label) in [("a", "A"), ("b", "B"), ("c", "C")] {
+            builder.add_relation(var, create_node_scan(var, label));
+        }
+        for (left, right) in [("a", "b"), ("b", "c")] {
+            builder.add_join_condition(
+                left,
+                right,
+                LogicalExpression::Variable(left.to_string()),
+                LogicalExpression::Variable(right.to_string()),
+            );
+        }
+        let chain = builder.build();
+        let plan = GreedyJoinOrder::new(&chain, &cost_model, &card_estimator)
+            .optimize()
+            .unwrap();
+        assert_eq!(plan.nodes, BitSet::full(3));
+    }
+
     #[test]
     fn test_dpccp_cycle_query() {
         // Cycle: a -> b -> c -> a
@@ -5,8 +5,9 @@
 //! | Optimization | What it does |
 //! | ------------ | ------------ |
 //! | Filter Pushdown | Moves `WHERE` clauses closer to scans - filter early, process less |
-//! | Join Reordering | Picks the best order to join tables using the DPccp algorithm |
+//! | Join Reordering | Picks the best order to join tables using DPccp, or greedily for large joins |
 //! | Predicate Simplification | Folds constants like `1 + 1` into `2` |
+//! | Top-N Fusion | Turns `ORDER BY ... LIMIT n` into a top-N that only keeps `n` rows |
 //!
 //! The optimizer uses [`CostModel`] and [`CardinalityEstimator`] to predict
 //! how expensive different plans are, then picks the cheapest.
@@ -17,12 +18,22 @@ pub mod join_order;
 
 pub use cardinality::{CardinalityEstimator, ColumnStats, TableStats};
 pub use cost::{Cost, CostModel};
-pub use join_order::{BitSet, DPccp, JoinGraph, JoinGraphBuilder, JoinPlan};
+pub use join_order::{BitSet, DPccp, GreedyJoinOrder, JoinGraph, JoinGraphBuilder, JoinPlan};
 
-use crate::query::plan::{FilterOp, LogicalExpression, LogicalOperator, LogicalPlan};
+use crate::query::explain::{self, ExplainNode, PlanExplanation};
+use crate::query::plan::{FilterOp, LogicalExpression, LogicalOperator, LogicalPlan, TopNOp};
 use grafeo_common::utils::error::Result;
 use std::collections::HashSet;
 
+/// Most relations a join is reordered exhaustively with DPccp by default.
+///
+/// DPccp's work grows exponentially with the number of relations, so larger
+/// joins are ordered greedily instead.
+pub const DEFAULT_DP_JOIN_LIMIT: usize = 10;
+
+/// Most relations a join can have and still be reordered at all.
+const MAX_REORDERED_RELATIONS: usize = 64;
+
 /// Information about a join condition for join reordering.
 #[derive(Debug, Clone)]
 struct JoinInfo {
@@ -45,6 +56,7 @@ enum RequiredColumn {
 ///
 /// Create with [`new()`](Self::new), then call [`optimize()`](Self::optimize).
 /// Use the builder methods to enable/disable specific optimizations.
+#[allow(clippy::struct_excessive_bools)] // independent pass switches
 pub struct Optimizer {
     /// Whether to enable filter pushdown.
     enable_filter_pushdown: bool,
@@ -52,6 +64,10 @@ pub struct Optimizer {
     enable_join_reorder: bool,
     /// Whether to enable projection pushdown.
     enable_projection_pushdown: bool,
+    /// Whether to fuse a sort and limit into a top-N.
+    enable_top_n: bool,
+    /// Most relations reordered with DPccp before falling back to greedy.
+    dp_join_limit: usize,
     /// Cost model for estimation.
     cost_model: CostModel,
     /// Cardinality estimator.
@@ -66,6 +82,8 @@ impl Optimizer {
             enable_filter_pushdown: true,
             enable_join_reorder: true,
             enable_projection_pushdown: true,
+            enable_top_n: true,
+            dp_join_limit: DEFAULT_DP_JOIN_LIMIT,
             cost_model: CostModel::new(),
             card_estimator: CardinalityEstimator::new(),
         }
@@ -89,6 +107,19 @@ impl Optimizer {
         self
     }
 
+    /// Enables or disables fusing `ORDER BY ... LIMIT` into a top-N.
+    pub fn with_top_n(mut self, enabled: bool) -> Self {
+        self.enable_top_n = enabled;
+        self
+    }
+
+    /// Sets how many relations a join can have and still be ordered
+    /// exhaustively; larger joins are ordered greedily.
+    pub fn with_dp_join_limit(mut self, limit: usize) -> Self {
+        self.dp_join_limit = limit;
+        self
+    }
+
     /// Sets the cost model.
     pub fn with_cost_model(mut self, cost_model: CostModel) -> Self {
         self.cost_model = cost_model;
@@ -122,6 +153,46 @@ impl Optimizer {
         self.card_estimator.estimate(&plan.root)
     }
 
+    /// Annotates every operator of a plan with its estimated output rows
+    /// and cost.
+    ///
+    /// These are the same numbers [`estimate_cardinality()`](Self::estimate_cardinality)
+    /// and [`estimate_cost()`](Self::estimate_cost) give for each subtree.
+    #[must_use]
+    pub fn explain(&self, plan: &LogicalPlan) -> PlanExplanation {
+        PlanExplanation {
+            root: self.explain_operator(&plan.root),
+        }
+    }
+
+    fn explain_operator(&self, op: &LogicalOperator) -> ExplainNode {
+        let children: Vec<ExplainNode> = op
+            .children()
+            .into_iter()
+            .map(|child| self.explain_operator(child))
+            .collect();
+        let (estimated_rows, cost) = self.estimate_operator(op);
+        let total_cost = cost + children.iter().map(|c| c.total_cost).sum::<f64>();
+        let (operator, details) = explain::describe(op);
+        ExplainNode {
+            operator: operator.to_string(),
+            details,
+            estimated_rows,
+            cost,
+            total_cost,
+            actual: None,
+            children,
+        }
+    }
+
+    /// Returns the rows an operator is expected to produce and its cost
+    /// alone, without its inputs.
+    pub(crate) fn estimate_operator(&self, op: &LogicalOperator) -> (f64, f64) {
+        let estimated_rows = self.card_estimator.estimate(op);
+        let cost = self.cost_model.estimate(op, estimated_rows).total();
+        (estimated_rows, cost)
+    }
+
     /// Optimizes a logical plan.
     ///
     /// # Errors
@@ -143,9 +214,58 @@ impl Optimizer {
             root = self.push_projections_down(root);
         }
 
+        // Last, since the other rules don't look inside a top-N
+        if self.enable_top_n {
+            Self::fuse_top_n(&mut root);
+        }
+
         Ok(LogicalPlan::new(root))
     }
 
+    /// Replaces a limit directly over a sort with a top-N.
+    ///
+    /// A skip between them stays, with the top-N keeping the skipped rows
+    /// too: `Limit(n, Skip(s, Sort))` becomes `Limit(n, Skip(s, TopN(s + n)))`.
+    fn fuse_top_n(op: &mut LogicalOperator) {
+        for child in op.children_mut() {
+            Self::fuse_top_n(child);
+        }
+
+        let LogicalOperator::Limit(limit) = op else {
+            return;
+        };
+        match limit.input.as_mut() {
+            LogicalOperator::Sort(_) => {
+                let LogicalOperator::Sort(sort) =
+                    std::mem::replace(limit.input.as_mut(), LogicalOperator::Empty)
+                else {
+                    unreachable!("matched a sort above");
+                };
+                *op = LogicalOperator::TopN(TopNOp {
+                    keys: sort.keys,
+                    count: limit.count,
+                    input: sort.input,
+                });
+            }
+            LogicalOperator::Skip(skip)
+                if matches!(skip.input.as_ref(), LogicalOperator::Sort(_)) =>
+            {
+                let LogicalOperator::Sort(sort) =
+                    std::mem::replace(skip.input.as_mut(), LogicalOperator::Empty)
+                else {
+                    unreachable!("matched a sort above");
+                };
+                let skip_count = skip.count;
+                *skip.input = LogicalOperator::TopN(TopNOp {
+                    keys: sort.keys,
+                    count: skip_count.saturating_add(limit.count),
+                    input: sort.input,
+                });
+            }
+            _ => {}
+        }
+    }
+
     /// Pushes projections down the operator tree to eliminate unused columns early.
     ///
     /// This optimization:
@@ -409,14 +529,15 @@ impl Optimizer {
     /// This optimization finds the optimal join order by:
     /// 1. Extracting all base relations (scans) and join conditions
     /// 2. Building a join graph
-    /// 3. Using dynamic programming to find the cheapest join order
+    /// 3. Using dynamic programming to find the cheapest join order, or a
+    ///    greedy search when there are more relations than the DP limit
     fn reorder_joins(&self, op: LogicalOperator) -> LogicalOperator {
         // First, recursively optimize children
         let op = self.reorder_joins_recursive(op);
 
         // Then, if this is a join tree, try to optimize it
         if let Some((relations, conditions)) = self.extract_join_tree(&op) {
-            if relations.len() >= 2 {
+            if (2..=MAX_REORDERED_RELATIONS).contains(&relations.len()) {
                 if let Some(optimized) = self.optimize_join_order(&relations, &conditions) {
                     return optimized;
                 }
@@ -532,8 +653,16 @@ impl Optimizer {
                 true
             }
             LogicalOperator::Filter(filter) => {
-                // A filter on a base relation is still part of the join tree
-                self.collect_join_tree(&filter.input, relations, conditions)
+                // A filter on a base relation moves with it, so the pair is
+                // a single relation
+                let variable = match filter.input.as_ref() {
+                    LogicalOperator::NodeScan(scan) => scan.variable.clone(),
+                    LogicalOperator::EdgeScan(scan) => scan.variable.clone(),
+                    LogicalOperator::Expand(expand) => expand.to_variable.clone(),
+                    _ => return false,
+                };
+                relations.push((variable, op.clone()));
+                true
             }
             LogicalOperator::Expand(expand) => {
                 // Expand is a special case - it's like a join with the adjacency
@@ -554,13 +683,13 @@ impl Optimizer {
         }
     }
 
-    /// Optimizes the join order using DPccp.
+    /// Optimizes the join order using DPccp, or greedily past the DP limit.
     fn optimize_join_order(
         &self,
         relations: &[(String, LogicalOperator)],
         conditions: &[JoinInfo],
     ) -> Option<LogicalOperator> {
-        use join_order::{DPccp, JoinGraphBuilder};
+        use join_order::{DPccp, GreedyJoinOrder, JoinGraphBuilder};
 
         // Build the join graph
         let mut builder = JoinGraphBuilder::new();
@@ -580,9 +709,11 @@ impl Optimizer {
 
         let graph = builder.build();
 
-        // Run DPccp
-        let mut dpccp = DPccp::new(&graph, &self.cost_model, &self.card_estimator);
-        let plan = dpccp.optimize()?;
+        let plan = if relations.len() <= self.dp_join_limit {
+            DPccp::new(&graph, &self.cost_model, &self.card_estimator).optimize()?
+        } else {
+            GreedyJoinOrder::new(&graph, &self.cost_model, &self.card_estimator).optimize()?
+        };
 
         Some(plan.operator)
     }
@@ -877,7 +1008,10 @@ impl Optimizer {
             }
             LogicalExpression::Labels(var)
             | LogicalExpression::Type(var)
-            | LogicalExpression::Id(var) => {
+            | LogicalExpression::Id(var)
+            | LogicalExpression::PathLength(var)
+            | LogicalExpression::PathCost { variable: var, .. }
+            | LogicalExpression::PropertyMap { variable: var, .. } => {
                 vars.insert(var.clone());
             }
             LogicalExpression::Literal(_) | LogicalExpression::Parameter(_) => {}
@@ -919,8 +1053,8 @@ mod tests {
     use super::*;
     use crate::query::plan::{
         AggregateExpr, AggregateFunction, AggregateOp, BinaryOp, DistinctOp, ExpandDirection,
-        ExpandOp, JoinOp, JoinType, LimitOp, NodeScanOp, ProjectOp, Projection, ReturnItem,
-        ReturnOp, SkipOp, SortKey, SortOp, SortOrder, UnaryOp,
+        ExpandOp, JoinCondition, JoinOp, JoinType, LimitOp, NodeScanOp, ProjectOp, Projection,
+        ReturnItem, ReturnOp, SkipOp, SortKey, SortOp, SortOrder, UnaryOp,
     };
     use grafeo_common::types::Value;
 
@@ -947,7 +1081,7 @@ mod tests {
                 },
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: Some("Person".to_string()),
+                    labels: vec!["Person".to_string()],
                     input: None,
                 })),
             })),
@@ -998,7 +1132,7 @@ mod tests {
                     max_hops: Some(1),
                     input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                         variable: "a".to_string(),
-                        label: Some("Person".to_string()),
+                        labels: vec!["Person".to_string()],
                         input: None,
                     })),
                     path_alias: None,
@@ -1056,7 +1190,7 @@ mod tests {
                     max_hops: Some(1),
                     input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                         variable: "a".to_string(),
-                        label: Some("Person".to_string()),
+                        labels: vec!["Person".to_string()],
                         input: None,
                     })),
                     path_alias: None,
@@ -1108,6 +1242,63 @@ mod tests {
 
     // Additional tests for optimizer configuration
 
+    #[test]
+    fn test_optimizer_fuses_sort_and_limit_into_top_n() {
+        let sorted = || {
+            Box::new(LogicalOperator::Sort(SortOp {
+                keys: vec![SortKey {
+                    expression: LogicalExpression::Property {
+                        variable: "n".to_string(),
+                        property: "age".to_string(),
+                    },
+                    order: SortOrder::Descending,
+                }],
+                input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
+                    variable: "n".to_string(),
+                    labels: vec!["Person".to_string()],
+                    input: None,
+                })),
+            }))
+        };
+
+        let plan = LogicalPlan::new(LogicalOperator::Limit(LimitOp {
+            count: 10,
+            input: sorted(),
+        }));
+        let optimized = Optimizer::new().optimize(plan).unwrap();
+        let LogicalOperator::TopN(top) = &optimized.root else {
+            panic!("expected TopN, got {:?}", optimized.root);
+        };
+        assert_eq!(top.count, 10);
+        assert_eq!(top.keys[0].order, SortOrder::Descending);
+        assert!(matches!(top.input.as_ref(), LogicalOperator::NodeScan(_)));
+
+        // With SKIP the top-N also keeps the skipped rows
+        let plan = LogicalPlan::new(LogicalOperator::Limit(LimitOp {
+            count: 10,
+            input: Box::new(LogicalOperator::Skip(SkipOp {
+                count: 5,
+                input: sorted(),
+            })),
+        }));
+        let optimized = Optimizer::new().optimize(plan).unwrap();
+        let LogicalOperator::Limit(limit) = &optimized.root else {
+            panic!("expected Limit, got {:?}", optimized.root);
+        };
+        let LogicalOperator::Skip(skip) = limit.input.as_ref() else {
+            panic!("expected Skip, got {:?}", limit.input);
+        };
+        assert!(matches!(skip.input.as_ref(), LogicalOperator::TopN(top) if top.count == 15));
+
+        // Disabled, the sort stays
+        let plan = LogicalPlan::new(LogicalOperator::Limit(LimitOp {
+            count: 10,
+            input: sorted(),
+        }));
+        let optimized = Optimizer::new().with_top_n(false).optimize(plan).unwrap();
+        assert!(matches!(optimized.root, LogicalOperator::Limit(_)));
+    }
+
     #[test]
     fn test_optimizer_default() {
         let optimizer = Optimizer::default();
@@ -1131,7 +1322,7 @@ mod tests {
                 predicate: LogicalExpression::Literal(Value::Bool(true)),
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -1157,6 +1348,114 @@ mod tests {
         );
     }
 
+    /// A star join around a small hub, written in the worst order: both
+    /// large leaves join the hub before the selective one does.
+    fn naive_star_join() -> LogicalPlan {
+        let scan = |variable: &str, label: &str| {
+            LogicalOperator::NodeScan(NodeScanOp {
+                variable: variable.to_string(),
+                labels: vec![label.to_string()],
+                input: None,
+            })
+        };
+        let join = |left, right, leaf: &str| {
+            LogicalOperator::Join(JoinOp {
+                left: Box::new(left),
+                right: Box::new(right),
+                join_type: JoinType::Inner,
+                conditions: vec![JoinCondition {
+                    left: LogicalExpression::Variable("h".to_string()),
+                    right: LogicalExpression::Variable(leaf.to_string()),
+                }],
+            })
+        };
+        let plan = join(scan("h", "Hub"), scan("a", "Big"), "a");
+        let plan = join(plan, scan("b", "Huge"), "b");
+        LogicalPlan::new(join(plan, scan("s", "Small"), "s"))
+    }
+
+    fn star_statistics_estimator() -> CardinalityEstimator {
+        use grafeo_core::statistics::{LabelStatistics, Statistics};
+
+        let mut stats = Statistics::new();
+        stats.total_nodes = 200_015;
+        stats.update_label("Hub", LabelStatistics::new(10));
+        stats.update_label("Big", LabelStatistics::new(100_000));
+        stats.update_label("Huge", LabelStatistics::new(100_000));
+        stats.update_label("Small", LabelStatistics::new(5));
+        CardinalityEstimator::from_statistics(&stats)
+    }
+
+    /// Variables of the scans under the deepest join in a join tree.
+    fn first_joined_variables(op: &LogicalOperator) -> Vec<String> {
+        let LogicalOperator::Join(join) = op else {
+            panic!("expected a join, got {op:?}");
+        };
+        for child in [&join.left, &join.right] {
+            if matches!(child.as_ref(), LogicalOperator::Join(_)) {
+                return first_joined_variables(child);
+            }
+        }
+        let mut variables: Vec<String> = [&join.left, &join.right]
+            .iter()
+            .map(|child| match child.as_ref() {
+                LogicalOperator::NodeScan(scan) => scan.variable.clone(),
+                other => panic!("expected a scan, got {other:?}"),
+            })
+            .collect();
+        variables.sort();
+        variables
+    }
+
+    /// Total estimated rows produced by the joins in a join tree.
+    fn join_rows(optimizer: &Optimizer, op: &LogicalOperator) -> f64 {
+        match op {
+            LogicalOperator::Join(join) => {
+                optimizer.cardinality_estimator().estimate(op)
+                    + join_rows(optimizer, &join.left)
+                    + join_rows(optimizer, &join.right)
+            }
+            _ => 0.0,
+        }
+    }
+
+    #[test]
+    fn test_join_reorder_joins_selective_star_leaf_first() {
+        let naive = naive_star_join();
+        let optimizer = Optimizer::new().with_cardinality_estimator(star_statistics_estimator());
+        let naive_rows = join_rows(&optimizer, &naive.root);
+
+        let optimized = optimizer.optimize(naive).unwrap();
+
+        assert_eq!(first_joined_variables(&optimized.root), ["h", "s"]);
+        assert!(join_rows(&optimizer, &optimized.root) < naive_rows);
+    }
+
+    #[test]
+    fn test_greedy_join_reorder_past_dp_limit() {
+        let naive = naive_star_join();
+        let optimizer = Optimizer::new()
+            .with_cardinality_estimator(star_statistics_estimator())
+            .with_dp_join_limit(2);
+        let naive_rows = join_rows(&optimizer, &naive.root);
+
+        let optimized = optimizer.optimize(naive).unwrap();
+
+        assert_eq!(first_joined_variables(&optimized.root), ["h", "s"]);
+        assert!(join_rows(&optimizer, &optimized.root) < naive_rows);
+    }
+
+    #[test]
+    fn test_join_reorder_disabled_keeps_written_order() {
+        let optimizer = Optimizer::new()
+            .with_cardinality_estimator(star_statistics_estimator())
+            .with_join_reorder(false);
+
+        let optimized = optimizer.optimize(naive_star_join()).unwrap();
+
+        assert_eq!(first_joined_variables(&optimized.root), ["a", "h"]);
+    }
+
     #[test]
     fn test_optimizer_with_cost_model() {
         let cost_model = CostModel::new();
@@ -1178,7 +1477,7 @@ mod tests {
 
         let scan = LogicalOperator::NodeScan(NodeScanOp {
             variable: "n".to_string(),
-            label: Some("Test".to_string()),
+            labels: vec!["Test".to_string()],
             input: None,
         });
         let plan = LogicalPlan::new(scan);
@@ -1192,7 +1491,7 @@ mod tests {
         let optimizer = Optimizer::new();
         let plan = LogicalPlan::new(LogicalOperator::NodeScan(NodeScanOp {
             variable: "n".to_string(),
-            label: None,
+            labels: Vec::new(),
             input: None,
         }));
 
@@ -1200,6 +1499,74 @@ mod tests {
         assert!(cost.total() > 0.0);
     }
 
+    #[test]
+    fn test_explain_matches_internal_estimates() {
+        let mut estimator = CardinalityEstimator::new();
+        estimator.add_table_stats("Person", TableStats::new(200));
+        let optimizer = Optimizer::new().with_cardinality_estimator(estimator);
+
+        let filter = LogicalOperator::Filter(FilterOp {
+            predicate: LogicalExpression::Binary {
+                left: Box::new(LogicalExpression::Property {
+                    variable: "b".to_string(),
+                    property: "age".to_string(),
+                }),
+                op: BinaryOp::Gt,
+                right: Box::new(LogicalExpression::Literal(Value::Int64(30))),
+            },
+            input: Box::new(LogicalOperator::Expand(ExpandOp {
+                from_variable: "a".to_string(),
+                to_variable: "b".to_string(),
+                edge_variable: None,
+                direction: ExpandDirection::Outgoing,
+                edge_type: Some("KNOWS".to_string()),
+                min_hops: 1,
+                max_hops: Some(1),
+                input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
+                    variable: "a".to_string(),
+                    labels: vec!["Person".to_string()],
+                    input: None,
+                })),
+                path_alias: None,
+            })),
+        });
+        let plan = LogicalPlan::new(LogicalOperator::Return(ReturnOp {
+            items: vec![ReturnItem {
+                expression: LogicalExpression::Variable("b".to_string()),
+                alias: None,
+            }],
+            distinct: false,
+            input: Box::new(filter),
+        }));
+
+        let explanation = optimizer.explain(&plan);
+        let names: Vec<_> = explanation
+            .operators()
+            .iter()
+            .map(|node| node.operator.as_str())
+            .collect();
+        assert_eq!(names, vec!["Return", "Filter", "Expand", "NodeScan"]);
+
+        // Each node carries the estimator's number for its own subtree
+        let mut op = &plan.root;
+        for node in explanation.operators() {
+            let rows = optimizer.cardinality_estimator().estimate(op);
+            assert!((node.estimated_rows - rows).abs() < f64::EPSILON);
+            let cost = optimizer.cost_model().estimate(op, rows).total();
+            assert!((node.cost - cost).abs() < f64::EPSILON);
+            op = op.children().first().copied().unwrap_or(op);
+        }
+        assert!(
+            (explanation.root.estimated_rows - optimizer.estimate_cardinality(&plan)).abs()
+                < f64::EPSILON
+        );
+
+        let scan = explanation.operators()[3];
+        assert!((scan.estimated_rows - 200.0).abs() < f64::EPSILON);
+        assert_eq!(scan.details, "a:Person");
+        assert!(explanation.root.total_cost > scan.total_cost);
+    }
+
     // Filter pushdown through various operators
 
     #[test]
@@ -1222,7 +1589,7 @@ mod tests {
                 }],
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -1260,7 +1627,7 @@ mod tests {
                 }],
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -1287,7 +1654,7 @@ mod tests {
                 count: 10,
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -1317,7 +1684,7 @@ mod tests {
                 }],
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -1343,7 +1710,7 @@ mod tests {
             input: Box::new(LogicalOperator::Distinct(DistinctOp {
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 columns: None,
@@ -1382,7 +1749,7 @@ mod tests {
                 }],
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 having: None,
@@ -1417,12 +1784,12 @@ mod tests {
             input: Box::new(LogicalOperator::Join(JoinOp {
                 left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "a".to_string(),
-                    label: Some("Person".to_string()),
+                    labels: vec!["Person".to_string()],
                     input: None,
                 })),
                 right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "b".to_string(),
-                    label: Some("Company".to_string()),
+                    labels: vec!["Company".to_string()],
                     input: None,
                 })),
                 join_type: JoinType::Inner,
@@ -1458,12 +1825,12 @@ mod tests {
             input: Box::new(LogicalOperator::Join(JoinOp {
                 left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "a".to_string(),
-                    label: Some("Person".to_string()),
+                    labels: vec!["Person".to_string()],
                     input: None,
                 })),
                 right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "b".to_string(),
-                    label: Some("Company".to_string()),
+                    labels: vec!["Company".to_string()],
                     input: None,
                 })),
                 join_type: JoinType::Inner,
@@ -1502,12 +1869,12 @@ mod tests {
             input: Box::new(LogicalOperator::Join(JoinOp {
                 left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "a".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "b".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 join_type: JoinType::Inner,
@@ -1716,7 +2083,7 @@ mod tests {
                     count: 5,
                     input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                         variable: "n".to_string(),
-                        label: None,
+                        labels: Vec::new(),
                         input: None,
                     })),
                 })),
@@ -1760,7 +2127,7 @@ mod tests {
                     },
                     input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                         variable: "n".to_string(),
-                        label: None,
+                        labels: Vec::new(),
                         input: None,
                     })),
                 })),
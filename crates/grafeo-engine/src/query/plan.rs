@@ -6,6 +6,8 @@
 
 use grafeo_common::types::Value;
 
+use crate::query::{Optimizer, PlanExplanation};
+
 /// A logical query plan.
 #[derive(Debug, Clone)]
 pub struct LogicalPlan {
@@ -18,6 +20,29 @@ impl LogicalPlan {
     pub fn new(root: LogicalOperator) -> Self {
         Self { root }
     }
+
+    /// Counts the operators in the plan.
+    #[must_use]
+    pub fn operator_count(&self) -> usize {
+        let mut count = 0;
+        let mut pending = vec![&self.root];
+        while let Some(op) = pending.pop() {
+            count += 1;
+            pending.extend(op.children());
+        }
+        count
+    }
+
+    /// Shows the plan's operators with the rows and cost the optimizer's
+    /// default estimates give each.
+    ///
+    /// These estimates don't know the graph; use
+    /// [`Optimizer::explain()`](crate::query::Optimizer::explain) with an
+    /// optimizer built from statistics for realistic numbers.
+    #[must_use]
+    pub fn explain(&self) -> PlanExplanation {
+        Optimizer::new().explain(self)
+    }
 }
 
 /// A logical operator in the query plan.
@@ -53,6 +78,9 @@ pub enum LogicalOperator {
     /// Sort results.
     Sort(SortOp),
 
+    /// Keep the first rows of a sort, without sorting the rest.
+    TopN(TopNOp),
+
     /// Remove duplicate results.
     Distinct(DistinctOp),
 
@@ -102,12 +130,18 @@ pub enum LogicalOperator {
     /// Unwind a list into individual rows.
     Unwind(UnwindOp),
 
+    /// Apply an update subplan once per element of a list.
+    ForEach(ForEachOp),
+
     /// Merge a pattern (match or create).
     Merge(MergeOp),
 
     /// Find shortest path between nodes.
     ShortestPath(ShortestPathOp),
 
+    /// Build RDF triples from templates (SPARQL CONSTRUCT).
+    Construct(ConstructOp),
+
     // ==================== SPARQL Update Operators ====================
     /// Insert RDF triples.
     InsertTriple(InsertTripleOp),
@@ -141,13 +175,91 @@ pub enum LogicalOperator {
     AddGraph(AddGraphOp),
 }
 
+impl LogicalOperator {
+    /// Returns the read-side inputs of this operator.
+    #[must_use]
+    pub fn children(&self) -> Vec<&LogicalOperator> {
+        match self {
+            LogicalOperator::NodeScan(scan) => scan.input.as_deref().into_iter().collect(),
+            LogicalOperator::EdgeScan(scan) => scan.input.as_deref().into_iter().collect(),
+            LogicalOperator::CreateNode(create) => create.input.as_deref().into_iter().collect(),
+            LogicalOperator::Expand(expand) => vec![&expand.input],
+            LogicalOperator::Filter(filter) => vec![&filter.input],
+            LogicalOperator::Project(project) => vec![&project.input],
+            LogicalOperator::Aggregate(agg) => vec![&agg.input],
+            LogicalOperator::Limit(limit) => vec![&limit.input],
+            LogicalOperator::Skip(skip) => vec![&skip.input],
+            LogicalOperator::Sort(sort) => vec![&sort.input],
+            LogicalOperator::TopN(top) => vec![&top.input],
+            LogicalOperator::Distinct(distinct) => vec![&distinct.input],
+            LogicalOperator::CreateEdge(create) => vec![&create.input],
+            LogicalOperator::DeleteNode(delete) => vec![&delete.input],
+            LogicalOperator::DeleteEdge(delete) => vec![&delete.input],
+            LogicalOperator::SetProperty(set) => vec![&set.input],
+            LogicalOperator::AddLabel(add) => vec![&add.input],
+            LogicalOperator::RemoveLabel(remove) => vec![&remove.input],
+            LogicalOperator::Return(ret) => vec![&ret.input],
+            LogicalOperator::Bind(bind) => vec![&bind.input],
+            LogicalOperator::Unwind(unwind) => vec![&unwind.input],
+            LogicalOperator::ForEach(foreach) => vec![&foreach.input, &foreach.body],
+            LogicalOperator::Merge(merge) => vec![&merge.input],
+            LogicalOperator::ShortestPath(path) => vec![&path.input],
+            LogicalOperator::Construct(construct) => vec![&construct.input],
+            LogicalOperator::Join(join) => vec![&join.left, &join.right],
+            LogicalOperator::LeftJoin(join) => vec![&join.left, &join.right],
+            LogicalOperator::AntiJoin(join) => vec![&join.left, &join.right],
+            LogicalOperator::Union(union) => union.inputs.iter().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the read-side inputs of this operator, mutably.
+    #[must_use]
+    pub fn children_mut(&mut self) -> Vec<&mut LogicalOperator> {
+        match self {
+            LogicalOperator::NodeScan(scan) => scan.input.as_deref_mut().into_iter().collect(),
+            LogicalOperator::EdgeScan(scan) => scan.input.as_deref_mut().into_iter().collect(),
+            LogicalOperator::CreateNode(create) => {
+                create.input.as_deref_mut().into_iter().collect()
+            }
+            LogicalOperator::Expand(expand) => vec![&mut expand.input],
+            LogicalOperator::Filter(filter) => vec![&mut filter.input],
+            LogicalOperator::Project(project) => vec![&mut project.input],
+            LogicalOperator::Aggregate(agg) => vec![&mut agg.input],
+            LogicalOperator::Limit(limit) => vec![&mut limit.input],
+            LogicalOperator::Skip(skip) => vec![&mut skip.input],
+            LogicalOperator::Sort(sort) => vec![&mut sort.input],
+            LogicalOperator::TopN(top) => vec![&mut top.input],
+            LogicalOperator::Distinct(distinct) => vec![&mut distinct.input],
+            LogicalOperator::CreateEdge(create) => vec![&mut create.input],
+            LogicalOperator::DeleteNode(delete) => vec![&mut delete.input],
+            LogicalOperator::DeleteEdge(delete) => vec![&mut delete.input],
+            LogicalOperator::SetProperty(set) => vec![&mut set.input],
+            LogicalOperator::AddLabel(add) => vec![&mut add.input],
+            LogicalOperator::RemoveLabel(remove) => vec![&mut remove.input],
+            LogicalOperator::Return(ret) => vec![&mut ret.input],
+            LogicalOperator::Bind(bind) => vec![&mut bind.input],
+            LogicalOperator::Unwind(unwind) => vec![&mut unwind.input],
+            LogicalOperator::ForEach(foreach) => vec![&mut foreach.input, &mut foreach.body],
+            LogicalOperator::Merge(merge) => vec![&mut merge.input],
+            LogicalOperator::ShortestPath(path) => vec![&mut path.input],
+            LogicalOperator::Construct(construct) => vec![&mut construct.input],
+            LogicalOperator::Join(join) => vec![&mut join.left, &mut join.right],
+            LogicalOperator::LeftJoin(join) => vec![&mut join.left, &mut join.right],
+            LogicalOperator::AntiJoin(join) => vec![&mut join.left, &mut join.right],
+            LogicalOperator::Union(union) => union.inputs.iter_mut().collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// Scan nodes from the graph.
 #[derive(Debug, Clone)]
 pub struct NodeScanOp {
     /// Variable name to bind the node to.
     pub variable: String,
-    /// Optional label filter.
-    pub label: Option<String>,
+    /// Labels the node must have, all of them (empty = any node).
+    pub labels: Vec<String>,
     /// Child operator (if any, for chained patterns).
     pub input: Option<Box<LogicalOperator>>,
 }
@@ -284,6 +396,8 @@ pub enum AggregateFunction {
     Max,
     /// Collect into list.
     Collect,
+    /// Collect `[key, value]` pairs into a map.
+    CollectMap,
     /// Sample standard deviation (STDEV).
     StdDev,
     /// Population standard deviation (STDEVP).
@@ -348,6 +462,21 @@ pub struct SortOp {
     pub input: Box<LogicalOperator>,
 }
 
+/// Keep the first `count` rows in sort order.
+///
+/// The optimizer's rewrite of a [`LimitOp`] directly over a [`SortOp`]. Only
+/// `count` rows are held at a time, and rows with equal keys keep their
+/// input order, as with a full sort.
+#[derive(Debug, Clone)]
+pub struct TopNOp {
+    /// Sort keys.
+    pub keys: Vec<SortKey>,
+    /// Number of rows to keep.
+    pub count: usize,
+    /// Input operator.
+    pub input: Box<LogicalOperator>,
+}
+
 /// A sort key.
 #[derive(Debug, Clone)]
 pub struct SortKey {
@@ -435,6 +564,8 @@ pub struct SetPropertyOp {
     pub properties: Vec<(String, LogicalExpression)>,
     /// Whether to replace all properties (vs. merge).
     pub replace: bool,
+    /// Whether to add the values to the current ones (`SET n.x += 1`).
+    pub increment: bool,
     /// Input operator.
     pub input: Box<LogicalOperator>,
 }
@@ -541,6 +672,24 @@ pub struct UnwindOp {
     pub input: Box<LogicalOperator>,
 }
 
+/// Apply an update subplan once per element of a list.
+///
+/// For each input row, evaluates the list expression and runs `body` once per
+/// element with `variable` bound to it. The body's leaf is [`LogicalOperator::Empty`],
+/// which stands for the current input row extended with the element. Input rows
+/// pass through unchanged, so an empty list is a no-op.
+#[derive(Debug, Clone)]
+pub struct ForEachOp {
+    /// The variable name for each element.
+    pub variable: String,
+    /// The list expression to iterate.
+    pub list: LogicalExpression,
+    /// Update subplan applied per element.
+    pub body: Box<LogicalOperator>,
+    /// Input operator.
+    pub input: Box<LogicalOperator>,
+}
+
 /// Merge a pattern (match or create).
 ///
 /// MERGE tries to match a pattern in the graph. If found, returns the existing
@@ -554,18 +703,46 @@ pub struct MergeOp {
     pub labels: Vec<String>,
     /// Properties that must match (used for both matching and creation).
     pub match_properties: Vec<(String, LogicalExpression)>,
-    /// Properties to set on CREATE.
-    pub on_create: Vec<(String, LogicalExpression)>,
-    /// Properties to set on MATCH.
-    pub on_match: Vec<(String, LogicalExpression)>,
+    /// Relationship leaving the node, for `MERGE (a)-[r:T]->(b)`.
+    pub relationship: Option<MergeRelationship>,
+    /// Updates applied when the pattern was created (ON CREATE SET).
+    ///
+    /// Each update's input is [`LogicalOperator::Empty`]; it runs on the
+    /// merge's output rows.
+    pub on_create: Vec<SetPropertyOp>,
+    /// Updates applied when the pattern was matched (ON MATCH SET).
+    pub on_match: Vec<SetPropertyOp>,
     /// Input operator.
     pub input: Box<LogicalOperator>,
 }
 
+/// The relationship part of a MERGE pattern.
+///
+/// Variables already bound by the input are reused as they are; everything
+/// else in the pattern is matched as a whole, or created as a whole.
+#[derive(Debug, Clone)]
+pub struct MergeRelationship {
+    /// Variable for the relationship, if named.
+    pub variable: Option<String>,
+    /// Relationship type to match/create.
+    pub edge_type: String,
+    /// Direction from the merged node to the target.
+    pub direction: ExpandDirection,
+    /// Relationship properties that must match (also used for creation).
+    pub properties: Vec<(String, LogicalExpression)>,
+    /// Variable for the target node.
+    pub target_variable: String,
+    /// Labels of the target node.
+    pub target_labels: Vec<String>,
+    /// Properties of the target node.
+    pub target_properties: Vec<(String, LogicalExpression)>,
+}
+
 /// Find shortest path between two nodes.
 ///
-/// This operator uses Dijkstra's algorithm to find the shortest path(s)
-/// between a source node and a target node, optionally filtered by edge type.
+/// This operator uses breadth-first search to find the shortest path(s)
+/// between a source node and a target node, optionally filtered by edge type
+/// and limited to a number of hops.
 #[derive(Debug, Clone)]
 pub struct ShortestPathOp {
     /// Input operator providing source/target nodes.
@@ -578,6 +755,8 @@ pub struct ShortestPathOp {
     pub edge_type: Option<String>,
     /// Direction of edge traversal.
     pub direction: ExpandDirection,
+    /// Longest path to consider, in edges (`None` for unlimited).
+    pub max_hops: Option<u32>,
     /// Variable name to bind the path result.
     pub path_alias: String,
     /// Whether to find all shortest paths (vs. just one).
@@ -649,6 +828,20 @@ pub struct TripleTemplate {
     pub graph: Option<String>,
 }
 
+/// SPARQL CONSTRUCT: instantiates triple templates once per input row.
+///
+/// Produces `subject`, `predicate` and `object` columns. A template whose
+/// variables aren't all bound in a row is skipped for that row, and blank
+/// nodes (variables named `_:label`) get fresh identifiers per row. The
+/// output is a set, so duplicate triples appear once.
+#[derive(Debug, Clone)]
+pub struct ConstructOp {
+    /// Templates to instantiate.
+    pub templates: Vec<TripleTemplate>,
+    /// The WHERE clause that provides variable bindings.
+    pub input: Box<LogicalOperator>,
+}
+
 /// Clear all triples from a graph.
 #[derive(Debug, Clone)]
 pub struct ClearGraphOp {
@@ -831,6 +1024,28 @@ pub enum LogicalExpression {
     /// ID of a node or edge.
     Id(String),
 
+    /// Length of a path in edges (`length(p)`).
+    PathLength(String),
+
+    /// Total of a numeric edge property along a path (`cost(p, 'weight')`).
+    PathCost {
+        /// The path variable.
+        variable: String,
+        /// The edge property to add up.
+        property: String,
+    },
+
+    /// The properties of a node or edge as a map (Gremlin `valueMap()` and
+    /// `elementMap()`).
+    PropertyMap {
+        /// The node or edge variable.
+        variable: String,
+        /// Properties to include, or all of them if empty.
+        keys: Vec<String>,
+        /// Whether to add `id` and `label` entries.
+        include_id_label: bool,
+    },
+
     /// List comprehension: [x IN list WHERE predicate | expression]
     ListComprehension {
         /// Variable name for each element.
@@ -930,7 +1145,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".into(),
-                label: Some("Person".into()),
+                labels: vec!["Person".into()],
                 input: None,
             })),
         }));
@@ -941,7 +1156,7 @@ mod tests {
             assert!(!ret.distinct);
             if let LogicalOperator::NodeScan(scan) = ret.input.as_ref() {
                 assert_eq!(scan.variable, "n");
-                assert_eq!(scan.label, Some("Person".into()));
+                assert_eq!(scan.labels, vec!["Person".to_string()]);
             } else {
                 panic!("Expected NodeScan");
             }
@@ -950,6 +1165,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_explain_uses_default_estimates() {
+        let plan = LogicalPlan::new(LogicalOperator::Limit(LimitOp {
+            count: 5,
+            input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
+                variable: "n".into(),
+                labels: vec!["Person".into()],
+                input: None,
+            })),
+        }));
+
+        let explanation = plan.explain();
+        assert_eq!(explanation.root.operator, "Limit");
+        assert_eq!(explanation.root.children[0].details, "n:Person");
+        assert!(explanation.root.estimated_rows <= 5.0);
+        assert!(explanation.root.actual.is_none());
+    }
+
     #[test]
     fn test_filter_plan() {
         let plan = LogicalPlan::new(LogicalOperator::Return(ReturnOp {
@@ -972,7 +1205,7 @@ mod tests {
                 },
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".into(),
-                    label: Some("Person".into()),
+                    labels: vec!["Person".into()],
                     input: None,
                 })),
             })),
@@ -7,29 +7,48 @@
 use crate::query::plan::{
     AddLabelOp, AggregateFunction as LogicalAggregateFunction, AggregateOp, AntiJoinOp, BinaryOp,
     CreateEdgeOp, CreateNodeOp, DeleteEdgeOp, DeleteNodeOp, DistinctOp, ExpandDirection, ExpandOp,
-    FilterOp, JoinOp, JoinType, LeftJoinOp, LimitOp, LogicalExpression, LogicalOperator,
+    FilterOp, ForEachOp, JoinOp, JoinType, LeftJoinOp, LimitOp, LogicalExpression, LogicalOperator,
     LogicalPlan, MergeOp, NodeScanOp, RemoveLabelOp, ReturnOp, SetPropertyOp, ShortestPathOp,
-    SkipOp, SortOp, SortOrder, UnaryOp, UnionOp, UnwindOp,
+    SkipOp, SortKey, SortOp, SortOrder, TopNOp, UnaryOp, UnionOp, UnwindOp,
 };
+use grafeo_common::memory::buffer::BufferManager;
 use grafeo_common::types::LogicalType;
-use grafeo_common::types::{EpochId, TxId};
-use grafeo_common::utils::error::{Error, Result};
-use grafeo_core::execution::AdaptiveContext;
+use grafeo_common::types::{EpochId, NodeId, PropertyKey, Timestamp, TxId, Value};
+use grafeo_common::utils::error::{Error, QueryError, QueryErrorKind, Result};
 use grafeo_core::execution::operators::{
     AddLabelOperator, AggregateExpr as PhysicalAggregateExpr,
     AggregateFunction as PhysicalAggregateFunction, BinaryFilterOp, CreateEdgeOperator,
-    CreateNodeOperator, DeleteEdgeOperator, DeleteNodeOperator, DistinctOperator, ExpandOperator,
-    ExpressionPredicate, FilterExpression, FilterOperator, HashAggregateOperator, HashJoinOperator,
-    JoinType as PhysicalJoinType, LimitOperator, MergeOperator, NestedLoopJoinOperator, NullOrder,
-    Operator, ProjectExpr, ProjectOperator, PropertySource, RemoveLabelOperator, ScanOperator,
-    SetPropertyOperator, ShortestPathOperator, SimpleAggregateOperator, SkipOperator,
-    SortDirection, SortKey as PhysicalSortKey, SortOperator, UnaryFilterOp, UnionOperator,
-    UnwindOperator, VariableLengthExpandOperator,
+    CreateNodeOperator, DeleteEdgeOperator, DeleteNodeOperator, DistinctOperator, EdgeMultiplicity,
+    ExpandOperator, ExpressionPredicate, FilterExpression, FilterOperator, ForEachFeed,
+    ForEachOperator, ForEachSourceOperator, HashAggregateOperator, HashJoinOperator,
+    JoinType as PhysicalJoinType, LimitOperator, MergeEdge, MergeNode, MergeOperator,
+    NestedLoopJoinOperator, NullOrder, Operator, ProjectExpr, ProjectOperator, PropertySource,
+    RemoveLabelOperator, ScalarUdf, ScanOperator, SetPropertyOperator, ShortestPathOperator,
+    SimpleAggregateOperator, SkipOperator, SortDirection, SortKey as PhysicalSortKey, SortOperator,
+    TopNOperator, UnaryFilterOp, UnionOperator, UnwindOperator, VariableLengthExpandOperator,
+    ZoneFilter, ZoneMapCounters, is_builtin_function,
 };
-use grafeo_core::graph::{Direction, lpg::LpgStore};
+use grafeo_core::execution::parallel::{ParallelHashJoin, ParallelHashJoinOperator};
+use grafeo_core::execution::{
+    AdaptiveContext, Collation, DataChunk, ExecutionMemoryContext, Interrupt,
+    InterruptibleOperator, OperatorProfile, ParallelPipelineConfig, ProfiledOperator,
+    SharedMemoryContext, SpillableOperator,
+};
+use grafeo_core::graph::{
+    Direction,
+    lpg::{CompareOp, LpgStore},
+};
+use grafeo_core::index::{BoundingBox, DistanceMetric, SpatialIndex};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use crate::catalog::Catalog;
+use crate::config::DuplicateKeyPolicy;
+use crate::database::QueryStats;
+use crate::query::Optimizer;
+use crate::query::explain::{self, PlanExplanation, PlannedOperator};
+use crate::query::optimizer::CardinalityEstimator;
 use crate::transaction::TransactionManager;
 
 /// Converts a logical plan to a physical operator tree.
@@ -42,8 +61,46 @@ pub struct Planner {
     tx_id: Option<TxId>,
     /// Epoch to use for visibility checks.
     viewing_epoch: EpochId,
+    /// Only traverse edges valid at this time, if set.
+    as_of: Option<Timestamp>,
     /// Counter for generating unique anonymous edge column names.
     anon_edge_counter: std::cell::Cell<u32>,
+    /// Collation for string ordering in sorts and range predicates.
+    collation: Collation,
+    /// Catalog for schema validation and per-edge-type settings.
+    catalog: Option<Arc<Catalog>>,
+    /// Whether writes must use names declared in the catalog.
+    strict_schema: bool,
+    /// What a write that repeats a property key does.
+    duplicate_keys: DuplicateKeyPolicy,
+    /// Memory budget shared by the plan's joins, aggregates, and sorts.
+    memory_context: Option<SharedMemoryContext>,
+    /// Workers for inner equi-joins, which run in parallel when set.
+    parallel: Option<ParallelPipelineConfig>,
+    /// Stops the planned operators early when it fires.
+    interrupt: Interrupt,
+    /// Whether planned operators record what they do as they run.
+    profile: bool,
+    /// Estimates the rows and cost of each planned operator for `explain()`.
+    estimates: Optimizer,
+    /// Operators planned so far, one list per operator still being planned,
+    /// holding its finished inputs.
+    planned: std::cell::RefCell<Vec<Vec<PlannedOperator>>>,
+    /// Chunks skipped and scanned by the plan's zone-map filtered scans.
+    zone_counters: Arc<ZoneMapCounters>,
+    /// Enclosing FOREACH bodies, innermost last; their `Empty` leaf reads from here.
+    foreach_scopes: std::cell::RefCell<Vec<ForEachScope>>,
+    /// User-defined scalar functions, keyed by lowercase name.
+    udfs: Arc<HashMap<String, ScalarUdf>>,
+}
+
+/// What a FOREACH body's `Empty` leaf stands for: the fed input row, extended
+/// with one row per list element.
+struct ForEachScope {
+    feed: ForEachFeed,
+    columns: Vec<String>,
+    list: LogicalExpression,
+    variable: String,
 }
 
 impl Planner {
@@ -59,7 +116,21 @@ impl Planner {
             tx_manager: None,
             tx_id: None,
             viewing_epoch: epoch,
+            as_of: None,
             anon_edge_counter: std::cell::Cell::new(0),
+            collation: Collation::Binary,
+            catalog: None,
+            strict_schema: false,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            memory_context: None,
+            parallel: None,
+            interrupt: Interrupt::default(),
+            profile: false,
+            estimates: Optimizer::new(),
+            planned: std::cell::RefCell::new(vec![Vec::new()]),
+            zone_counters: Arc::new(ZoneMapCounters::new()),
+            foreach_scopes: std::cell::RefCell::new(Vec::new()),
+            udfs: Arc::new(HashMap::new()),
         }
     }
 
@@ -83,8 +154,147 @@ impl Planner {
             tx_manager: Some(tx_manager),
             tx_id,
             viewing_epoch,
+            as_of: None,
             anon_edge_counter: std::cell::Cell::new(0),
+            collation: Collation::Binary,
+            catalog: None,
+            strict_schema: false,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            memory_context: None,
+            parallel: None,
+            interrupt: Interrupt::default(),
+            profile: false,
+            estimates: Optimizer::new(),
+            planned: std::cell::RefCell::new(vec![Vec::new()]),
+            zone_counters: Arc::new(ZoneMapCounters::new()),
+            foreach_scopes: std::cell::RefCell::new(Vec::new()),
+            udfs: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the collation used for string ordering in sorts and range predicates.
+    #[must_use]
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Sets the catalog consulted for schema validation and edge multiplicity.
+    #[must_use]
+    pub fn with_catalog(mut self, catalog: Arc<Catalog>) -> Self {
+        self.catalog = Some(catalog);
+        self
+    }
+
+    /// Enables strict schema validation against the planner's catalog.
+    ///
+    /// Write operators that use undeclared labels, edge types, or property
+    /// keys fail to plan.
+    #[must_use]
+    pub fn with_strict_schema(mut self) -> Self {
+        self.strict_schema = true;
+        self
+    }
+
+    /// Sets what a write that repeats a property key does.
+    #[must_use]
+    pub fn with_duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Makes user-defined scalar functions, keyed by lowercase name,
+    /// callable from expressions.
+    #[must_use]
+    pub fn with_udfs(mut self, udfs: Arc<HashMap<String, ScalarUdf>>) -> Self {
+        self.udfs = udfs;
+        self
+    }
+
+    /// Charges the plan's hash tables and sort buffers to a memory budget.
+    ///
+    /// The planned operators share one [`ExecutionMemoryContext`] that
+    /// accounts usage every `granularity` bytes, so a query overshoots the
+    /// budget by at most about that much before failing.
+    #[must_use]
+    pub fn with_memory_budget(mut self, manager: Arc<BufferManager>, granularity: usize) -> Self {
+        self.memory_context = Some(
+            ExecutionMemoryContext::new(manager)
+                .with_granularity(granularity)
+                .into_shared(),
+        );
+        self
+    }
+
+    /// Spills operators that outgrow the memory budget to `path`, instead
+    /// of the buffer manager's spill path.
+    ///
+    /// Has no effect unless [`with_memory_budget()`](Self::with_memory_budget)
+    /// was called first.
+    #[must_use]
+    pub fn with_spill_path(self, path: impl Into<PathBuf>) -> Self {
+        if let Some(memory) = &self.memory_context {
+            memory.lock().set_spill_dir(path);
+        }
+        self
+    }
+
+    /// Makes `operator` fail with a memory limit error instead of spilling
+    /// once it outgrows the memory budget.
+    ///
+    /// Has no effect unless [`with_memory_budget()`](Self::with_memory_budget)
+    /// was called first.
+    #[must_use]
+    pub fn with_spill_disabled(self, operator: SpillableOperator) -> Self {
+        if let Some(memory) = &self.memory_context {
+            memory.lock().disable_spill(operator);
         }
+        self
+    }
+
+    /// Runs inner equi-joins as parallel hash joins over `threads` workers.
+    ///
+    /// Has no effect with fewer than two threads. Parallel joins emit their
+    /// rows in no particular order.
+    #[must_use]
+    pub fn with_parallelism(mut self, threads: usize) -> Self {
+        self.parallel =
+            (threads > 1).then(|| ParallelPipelineConfig::default().with_workers(threads));
+        self
+    }
+
+    /// Makes every planned operator check `interrupt` before handing up
+    /// each chunk, so the query stops soon after it fires.
+    #[must_use]
+    pub fn with_interrupt(mut self, interrupt: Interrupt) -> Self {
+        self.interrupt = interrupt;
+        self
+    }
+
+    /// Makes every planned operator record the rows it produces, the time
+    /// it takes, and the peak memory of the plan's memory context, for
+    /// [`PhysicalPlan::explain()`] to show once the plan has run.
+    #[must_use]
+    pub fn with_profiling(mut self) -> Self {
+        self.profile = true;
+        self
+    }
+
+    /// Takes the rows and cost [`PhysicalPlan::explain()`] shows for each
+    /// operator from `optimizer`, rather than from default estimates.
+    #[must_use]
+    pub fn with_estimates(mut self, optimizer: Optimizer) -> Self {
+        self.estimates = optimizer;
+        self
+    }
+
+    /// Makes traversals only follow edges valid at the given time.
+    ///
+    /// Edges without a validity interval are always followed.
+    #[must_use]
+    pub fn with_as_of(mut self, at: Timestamp) -> Self {
+        self.as_of = Some(at);
+        self
     }
 
     /// Returns the viewing epoch for this planner.
@@ -116,6 +326,8 @@ impl Planner {
             operator,
             columns,
             adaptive_context: None,
+            zone_counters: Some(Arc::clone(&self.zone_counters)),
+            planned: self.take_planned(),
         })
     }
 
@@ -138,6 +350,8 @@ impl Planner {
             operator,
             columns,
             adaptive_context: Some(adaptive_context),
+            zone_counters: Some(Arc::clone(&self.zone_counters)),
+            planned: self.take_planned(),
         })
     }
 
@@ -151,11 +365,7 @@ impl Planner {
         match op {
             LogicalOperator::NodeScan(scan) => {
                 // Estimate based on label statistics
-                let estimate = if let Some(label) = &scan.label {
-                    self.store.nodes_by_label(label).len() as f64
-                } else {
-                    self.store.node_count() as f64
-                };
+                let estimate = self.estimate_node_scan(scan);
                 let id = format!("scan_{}", scan.variable);
                 ctx.set_estimate(&id, estimate);
 
@@ -238,6 +448,13 @@ impl Planner {
                 // Sort doesn't change cardinality
                 self.collect_cardinality_estimates(&sort.input, ctx, depth + 1);
             }
+            LogicalOperator::TopN(top) => {
+                let estimate = self.estimate_cardinality(&top.input).min(top.count as f64);
+                let id = format!("topn_{depth}");
+                ctx.set_estimate(&id, estimate);
+
+                self.collect_cardinality_estimates(&top.input, ctx, depth + 1);
+            }
             LogicalOperator::Union(union) => {
                 let estimate: f64 = union
                     .inputs
@@ -257,16 +474,20 @@ impl Planner {
         }
     }
 
+    /// Estimates how many nodes a scan returns from the label index.
+    fn estimate_node_scan(&self, scan: &NodeScanOp) -> f64 {
+        if scan.labels.is_empty() {
+            self.store.node_count() as f64
+        } else {
+            let labels: Vec<&str> = scan.labels.iter().map(String::as_str).collect();
+            self.store.nodes_with_all_labels(&labels).len() as f64
+        }
+    }
+
     /// Estimates cardinality for a logical operator subtree.
     fn estimate_cardinality(&self, op: &LogicalOperator) -> f64 {
         match op {
-            LogicalOperator::NodeScan(scan) => {
-                if let Some(label) = &scan.label {
-                    self.store.nodes_by_label(label).len() as f64
-                } else {
-                    self.store.node_count() as f64
-                }
-            }
+            LogicalOperator::NodeScan(scan) => self.estimate_node_scan(scan),
             LogicalOperator::Filter(filter) => self.estimate_cardinality(&filter.input) * 0.3,
             LogicalOperator::Expand(expand) => self.estimate_cardinality(&expand.input) * 10.0,
             LogicalOperator::Join(join) => {
@@ -292,6 +513,9 @@ impl Planner {
                 (self.estimate_cardinality(&skip.input) - skip.count as f64).max(0.0)
             }
             LogicalOperator::Sort(sort) => self.estimate_cardinality(&sort.input),
+            LogicalOperator::TopN(top) => {
+                self.estimate_cardinality(&top.input).min(top.count as f64)
+            }
             LogicalOperator::Union(union) => union
                 .inputs
                 .iter()
@@ -301,8 +525,63 @@ impl Planner {
         }
     }
 
-    /// Plans a single logical operator.
+    /// Plans a single logical operator, recording it for `explain()` and
+    /// wrapping it to profile it and check the planner's interrupt as asked.
     fn plan_operator(&self, op: &LogicalOperator) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        self.planned.borrow_mut().push(Vec::new());
+        let planned = self.plan_operator_unchecked(op);
+        let children = self.planned.borrow_mut().pop().unwrap_or_default();
+        let (mut operator, columns) = planned?;
+
+        // Some operators plan to their input unchanged, like a RETURN of
+        // bare variables; they add nothing to explain or profile
+        let address = operator_address(operator.as_ref());
+        if address.is_some() && children.iter().any(|child| child.address == address) {
+            if let Some(parent) = self.planned.borrow_mut().last_mut() {
+                parent.extend(children);
+            }
+            return Ok((operator, columns));
+        }
+
+        let (estimated_rows, cost) = self.estimates.estimate_operator(op);
+        let mut node = PlannedOperator {
+            operator: operator.name(),
+            details: explain::describe(op).1,
+            estimated_rows,
+            cost,
+            profile: None,
+            address: None,
+            children,
+        };
+        if self.profile {
+            let profile = OperatorProfile::new();
+            node.profile = Some(profile.clone());
+            operator = Box::new(ProfiledOperator::new(
+                operator,
+                profile,
+                self.memory_context.clone(),
+            ));
+        }
+        if self.interrupt.is_active() {
+            operator = Box::new(InterruptibleOperator::new(operator, self.interrupt.clone()));
+        }
+        node.address = operator_address(operator.as_ref());
+        if let Some(parent) = self.planned.borrow_mut().last_mut() {
+            parent.push(node);
+        }
+        Ok((operator, columns))
+    }
+
+    /// Takes the root operator recorded by the last plan.
+    fn take_planned(&self) -> Option<PlannedOperator> {
+        std::mem::take(self.planned.borrow_mut().first_mut()?).pop()
+    }
+
+    /// Plans a single logical operator.
+    fn plan_operator_unchecked(
+        &self,
+        op: &LogicalOperator,
+    ) -> Result<(Box<dyn Operator>, Vec<String>)> {
         match op {
             LogicalOperator::NodeScan(scan) => self.plan_node_scan(scan),
             LogicalOperator::Expand(expand) => self.plan_expand(expand),
@@ -312,6 +591,7 @@ impl Planner {
             LogicalOperator::Limit(limit) => self.plan_limit(limit),
             LogicalOperator::Skip(skip) => self.plan_skip(skip),
             LogicalOperator::Sort(sort) => self.plan_sort(sort),
+            LogicalOperator::TopN(top) => self.plan_top_n(top),
             LogicalOperator::Aggregate(agg) => self.plan_aggregate(agg),
             LogicalOperator::Join(join) => self.plan_join(join),
             LogicalOperator::Union(union) => self.plan_union(union),
@@ -328,7 +608,8 @@ impl Planner {
             LogicalOperator::RemoveLabel(remove_label) => self.plan_remove_label(remove_label),
             LogicalOperator::SetProperty(set_prop) => self.plan_set_property(set_prop),
             LogicalOperator::ShortestPath(sp) => self.plan_shortest_path(sp),
-            LogicalOperator::Empty => Err(Error::Internal("Empty plan".to_string())),
+            LogicalOperator::ForEach(foreach) => self.plan_for_each(foreach),
+            LogicalOperator::Empty => self.plan_foreach_leaf(),
             _ => Err(Error::Internal(format!(
                 "Unsupported operator: {:?}",
                 std::mem::discriminant(op)
@@ -338,10 +619,27 @@ impl Planner {
 
     /// Plans a node scan operator.
     fn plan_node_scan(&self, scan: &NodeScanOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
-        let scan_op = if let Some(label) = &scan.label {
-            ScanOperator::with_label(Arc::clone(&self.store), label)
+        self.plan_filtered_node_scan(scan, Vec::new(), None)
+    }
+
+    /// Plans a node scan that skips chunks zone maps rule out for
+    /// `zone_filters`, and only reads `candidates` when an index narrowed
+    /// them down.
+    fn plan_filtered_node_scan(
+        &self,
+        scan: &NodeScanOp,
+        zone_filters: Vec<ZoneFilter>,
+        candidates: Option<Vec<NodeId>>,
+    ) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        let scan_op = ScanOperator::with_labels(Arc::clone(&self.store), scan.labels.clone());
+        let scan_op = if zone_filters.is_empty() {
+            scan_op
         } else {
-            ScanOperator::new(Arc::clone(&self.store))
+            scan_op.with_zone_filters(zone_filters, Some(Arc::clone(&self.zone_counters)))
+        };
+        let scan_op = match candidates {
+            Some(candidates) => scan_op.with_candidates(candidates),
+            None => scan_op,
         };
 
         // Apply MVCC context if available
@@ -416,16 +714,19 @@ impl Planner {
                 max_hops,
             )
             .with_tx_context(self.viewing_epoch, self.tx_id);
+            if let Some(at) = self.as_of {
+                expand_op = expand_op.with_as_of(at);
+            }
 
-            // If a path alias is set, enable path length output
+            // If a path alias is set, output the path and its length
             if expand.path_alias.is_some() {
-                expand_op = expand_op.with_path_length_output();
+                expand_op = expand_op.with_path_length_output().with_path_output();
             }
 
             Box::new(expand_op)
         } else {
             // Use simple ExpandOperator for single-hop paths
-            let expand_op = ExpandOperator::new(
+            let mut expand_op = ExpandOperator::new(
                 Arc::clone(&self.store),
                 input_op,
                 source_column,
@@ -433,10 +734,13 @@ impl Planner {
                 expand.edge_type.clone(),
             )
             .with_tx_context(self.viewing_epoch, self.tx_id);
+            if let Some(at) = self.as_of {
+                expand_op = expand_op.with_as_of(at);
+            }
             Box::new(expand_op)
         };
 
-        // Build output columns: [input_columns..., edge, target, (path_length)?]
+        // Build output columns: [input_columns..., edge, target, (path_length, path)?]
         // Preserve all input columns and add edge + target to match ExpandOperator output
         let mut columns = input_columns;
 
@@ -450,9 +754,10 @@ impl Planner {
 
         columns.push(expand.to_variable.clone());
 
-        // If a path alias is set, add a column for the path length
-        if let Some(ref path_alias) = expand.path_alias {
+        // If a path alias is set, add columns for the path length and the path
+        if let Some(path_alias) = expand.path_alias.as_ref().filter(|_| is_variable_length) {
             columns.push(format!("_path_length_{}", path_alias));
+            columns.push(path_alias.clone());
         }
 
         Ok((operator, columns))
@@ -501,7 +806,7 @@ impl Planner {
                         })?;
                         projections.push(ProjectExpr::Column(col_idx));
                         // Use Node type for variables (they could be nodes, edges, or values)
-                        output_types.push(LogicalType::Node);
+                        output_types.push(variable_output_type(name, &variable_columns));
                     }
                     LogicalExpression::Property { variable, property } => {
                         let col_idx = *variable_columns.get(variable).ok_or_else(|| {
@@ -514,10 +819,38 @@ impl Planner {
                         // Property could be any type - use Any/Generic to preserve type
                         output_types.push(LogicalType::Any);
                     }
+                    LogicalExpression::Id(name) => {
+                        let col_idx = *variable_columns.get(name).ok_or_else(|| {
+                            Error::Internal(format!("Variable '{}' not found in input", name))
+                        })?;
+                        // Node and edge columns already hold the raw id
+                        projections.push(ProjectExpr::Column(col_idx));
+                        output_types.push(LogicalType::Int64);
+                    }
                     LogicalExpression::Literal(value) => {
                         projections.push(ProjectExpr::Constant(value.clone()));
                         output_types.push(value_to_logical_type(value));
                     }
+                    LogicalExpression::PathLength(path) => {
+                        let col_idx = *variable_columns
+                            .get(&format!("_path_length_{path}"))
+                            .ok_or_else(|| {
+                                Error::Internal(format!("Path '{}' not found in input", path))
+                            })?;
+                        projections.push(ProjectExpr::Column(col_idx));
+                        output_types.push(LogicalType::Int64);
+                    }
+                    LogicalExpression::PathCost { .. }
+                    | LogicalExpression::PropertyMap { .. }
+                    | LogicalExpression::List(_)
+                    | LogicalExpression::Map(_)
+                    | LogicalExpression::IndexAccess { .. } => {
+                        projections.push(ProjectExpr::Expression {
+                            expr: self.convert_expression(&item.expression)?,
+                            variable_columns: variable_columns.clone(),
+                        });
+                        output_types.push(LogicalType::Any);
+                    }
                     LogicalExpression::FunctionCall { name, args, .. } => {
                         // Handle built-in functions
                         match name.to_lowercase().as_str() {
@@ -599,12 +932,15 @@ impl Planner {
                 }
             }
 
-            let operator = Box::new(ProjectOperator::with_store(
-                input_op,
-                projections,
-                output_types,
-                Arc::clone(&self.store),
-            ));
+            let operator = Box::new(
+                ProjectOperator::with_store(
+                    input_op,
+                    projections,
+                    output_types,
+                    Arc::clone(&self.store),
+                )
+                .with_tx_context(self.viewing_epoch, self.tx_id),
+            );
 
             Ok((operator, columns))
         } else {
@@ -619,7 +955,7 @@ impl Planner {
                         Error::Internal(format!("Variable '{}' not found in input", name))
                     })?;
                     projections.push(ProjectExpr::Column(col_idx));
-                    output_types.push(LogicalType::Node);
+                    output_types.push(variable_output_type(name, &variable_columns));
                 }
             }
 
@@ -710,20 +1046,32 @@ impl Planner {
             }
         }
 
-        let operator = Box::new(ProjectOperator::with_store(
-            input_op,
-            projections,
-            output_types,
-            Arc::clone(&self.store),
-        ));
+        let operator = Box::new(
+            ProjectOperator::with_store(
+                input_op,
+                projections,
+                output_types,
+                Arc::clone(&self.store),
+            )
+            .with_tx_context(self.viewing_epoch, self.tx_id),
+        );
 
         Ok((operator, output_columns))
     }
 
     /// Plans a filter operator.
     fn plan_filter(&self, filter: &FilterOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
-        // Plan the input operator first
-        let (input_op, columns) = self.plan_operator(&filter.input)?;
+        // Plan the input operator first, letting a scan skip chunks that
+        // zone maps rule out and nodes a full-text or spatial index rules out
+        let (input_op, columns) = match filter.input.as_ref() {
+            LogicalOperator::NodeScan(scan) => {
+                let mut zone_filters = Vec::new();
+                self.collect_zone_filters(&filter.predicate, &scan.variable, &mut zone_filters);
+                let candidates = self.index_candidates(&filter.predicate, scan);
+                self.plan_filtered_node_scan(scan, zone_filters, candidates)?
+            }
+            input => self.plan_operator(input)?,
+        };
 
         // Build variable to column index mapping
         let variable_columns: HashMap<String, usize> = columns
@@ -737,7 +1085,9 @@ impl Planner {
 
         // Create the predicate
         let predicate =
-            ExpressionPredicate::new(filter_expr, variable_columns, Arc::clone(&self.store));
+            ExpressionPredicate::new(filter_expr, variable_columns, Arc::clone(&self.store))
+                .with_tx_context(self.viewing_epoch, self.tx_id)
+                .with_collation(self.collation);
 
         // Create the filter operator
         let operator = Box::new(FilterOperator::new(input_op, Box::new(predicate)));
@@ -745,6 +1095,250 @@ impl Planner {
         Ok((operator, columns))
     }
 
+    /// Collects the conjuncts of `predicate` that compare a property of
+    /// `variable` with a constant, in a form zone maps can check.
+    fn collect_zone_filters(
+        &self,
+        predicate: &LogicalExpression,
+        variable: &str,
+        filters: &mut Vec<ZoneFilter>,
+    ) {
+        let LogicalExpression::Binary { left, op, right } = predicate else {
+            return;
+        };
+        if *op == BinaryOp::And {
+            self.collect_zone_filters(left, variable, filters);
+            self.collect_zone_filters(right, variable, filters);
+            return;
+        }
+
+        let compare = match op {
+            BinaryOp::Eq => CompareOp::Eq,
+            BinaryOp::Ne => CompareOp::Ne,
+            BinaryOp::Lt => CompareOp::Lt,
+            BinaryOp::Le => CompareOp::Le,
+            BinaryOp::Gt => CompareOp::Gt,
+            BinaryOp::Ge => CompareOp::Ge,
+            _ => return,
+        };
+        // Normalize to `property op constant`, flipping `constant op property`
+        let (property, compare, constant) = match (left.as_ref(), right.as_ref()) {
+            (
+                LogicalExpression::Property {
+                    variable: v,
+                    property,
+                },
+                constant,
+            ) if v == variable => (property, compare, constant),
+            (
+                constant,
+                LogicalExpression::Property {
+                    variable: v,
+                    property,
+                },
+            ) if v == variable => {
+                let flipped = match compare {
+                    CompareOp::Lt => CompareOp::Gt,
+                    CompareOp::Le => CompareOp::Ge,
+                    CompareOp::Gt => CompareOp::Lt,
+                    CompareOp::Ge => CompareOp::Le,
+                    other => other,
+                };
+                (property, flipped, constant)
+            }
+            _ => return,
+        };
+        let Some(value) = self.constant_value(constant) else {
+            return;
+        };
+        // Zone maps order strings by bytes, which only matches binary collation
+        if matches!(value, Value::String(_)) && self.collation != Collation::Binary {
+            return;
+        }
+        filters.push(ZoneFilter {
+            property: PropertyKey::new(property.as_str()),
+            op: compare,
+            value,
+        });
+    }
+
+    /// Looks up the conjuncts of `predicate` on the scanned variable in
+    /// full-text and spatial indexes, returning the sorted nodes that can
+    /// match all of them. Returns `None` when no index applies.
+    fn index_candidates(
+        &self,
+        predicate: &LogicalExpression,
+        scan: &NodeScanOp,
+    ) -> Option<Vec<NodeId>> {
+        match predicate {
+            LogicalExpression::Binary {
+                left,
+                op: BinaryOp::And,
+                right,
+            } => match (
+                self.index_candidates(left, scan),
+                self.index_candidates(right, scan),
+            ) {
+                (Some(mut a), Some(b)) => {
+                    a.retain(|id| b.binary_search(id).is_ok());
+                    Some(a)
+                }
+                (a, b) => a.or(b),
+            },
+            LogicalExpression::Binary { left, op, right } => {
+                self.fulltext_candidates(left, *op, right, scan)
+            }
+            LogicalExpression::FunctionCall { name, args, .. } => {
+                self.spatial_candidates(name, args, scan)
+            }
+            _ => None,
+        }
+    }
+
+    /// Looks up a string search on the scanned variable (`CONTAINS`,
+    /// `STARTS WITH`, `ENDS WITH` against a literal) in full-text indexes.
+    ///
+    /// Any text containing the literal has a token containing its longest
+    /// whitespace-free piece, so the result is a superset of the matches
+    /// and the filter still decides.
+    fn fulltext_candidates(
+        &self,
+        left: &LogicalExpression,
+        op: BinaryOp,
+        right: &LogicalExpression,
+        scan: &NodeScanOp,
+    ) -> Option<Vec<NodeId>> {
+        if !matches!(
+            op,
+            BinaryOp::Contains | BinaryOp::StartsWith | BinaryOp::EndsWith
+        ) {
+            return None;
+        }
+        let (
+            LogicalExpression::Property { variable, property },
+            LogicalExpression::Literal(Value::String(text)),
+        ) = (left, right)
+        else {
+            return None;
+        };
+        if *variable != scan.variable {
+            return None;
+        }
+        let fragment = text.split_whitespace().max_by_key(|piece| piece.len())?;
+        scan.labels.iter().find_map(|label| {
+            self.store
+                .fulltext_index(label, property)?
+                .nodes_with_token_containing(fragment)
+        })
+    }
+
+    /// Looks up `point.withinDistance` or `point.withinBBox` on a property of
+    /// the scanned variable, with constant bounds, in spatial indexes.
+    ///
+    /// An index is only used when all its points share the SRID of the
+    /// bounds, so comparisons across SRIDs still reach the filter and fail
+    /// there.
+    fn spatial_candidates(
+        &self,
+        name: &str,
+        args: &[LogicalExpression],
+        scan: &NodeScanOp,
+    ) -> Option<Vec<NodeId>> {
+        let within_distance = name.eq_ignore_ascii_case("point.withinDistance");
+        if !within_distance && !name.eq_ignore_ascii_case("point.withinBBox") {
+            return None;
+        }
+        let (LogicalExpression::Property { variable, property }, bounds) = args.split_first()?
+        else {
+            return None;
+        };
+        if *variable != scan.variable {
+            return None;
+        }
+        let bounds = bounds
+            .iter()
+            .map(|arg| self.constant_value(arg))
+            .collect::<Option<Vec<_>>>()?;
+
+        let (srid, lookup): (u32, Box<dyn Fn(&SpatialIndex) -> Vec<NodeId>>) = if within_distance {
+            let (x, y, srid) = bounds.first()?.as_point()?;
+            let radius = match bounds.get(1)? {
+                Value::Int64(i) => *i as f64,
+                Value::Float64(f) => *f,
+                _ => return None,
+            };
+            let metric = match bounds.get(2) {
+                None => DistanceMetric::for_srid(srid),
+                Some(Value::String(name)) => DistanceMetric::from_name(name)?,
+                Some(_) => return None,
+            };
+            if bounds.len() > 3 {
+                return None;
+            }
+            let lookup = move |index: &SpatialIndex| index.within_distance((x, y), radius, metric);
+            (srid, Box::new(lookup))
+        } else {
+            let [lower, upper] = bounds.as_slice() else {
+                return None;
+            };
+            let (min_x, min_y, srid) = lower.as_point()?;
+            let (max_x, max_y, upper_srid) = upper.as_point()?;
+            if srid != upper_srid {
+                return None;
+            }
+            let area = BoundingBox::new(min_x, min_y, max_x, max_y);
+            (
+                srid,
+                Box::new(move |index: &SpatialIndex| index.within_bbox(area)),
+            )
+        };
+
+        scan.labels.iter().find_map(|label| {
+            let index = self.store.spatial_index(label, property)?;
+            (index.srids() == [srid]).then(|| lookup(&index))
+        })
+    }
+
+    /// Evaluates `expr` if it depends on nothing but literals, such as the
+    /// `point({x: 0, y: 0})` bounds of a spatial predicate or a
+    /// `date('2024-01-01')` to compare with.
+    fn constant_value(&self, expr: &LogicalExpression) -> Option<Value> {
+        fn is_constant(expr: &LogicalExpression) -> bool {
+            match expr {
+                LogicalExpression::Literal(_) => true,
+                LogicalExpression::List(items) => items.iter().all(is_constant),
+                LogicalExpression::Map(entries) => entries.iter().all(|(_, v)| is_constant(v)),
+                LogicalExpression::Unary { operand, .. } => is_constant(operand),
+                LogicalExpression::Binary { left, right, .. } => {
+                    is_constant(left) && is_constant(right)
+                }
+                // Only pure constructors; `date()` without arguments is today
+                LogicalExpression::FunctionCall { name, args, .. } => {
+                    ["point", "date", "datetime", "timestamp", "duration"]
+                        .iter()
+                        .any(|constructor| constructor.eq_ignore_ascii_case(name))
+                        && !args.is_empty()
+                        && args.iter().all(is_constant)
+                }
+                _ => false,
+            }
+        }
+
+        if let LogicalExpression::Literal(value) = expr {
+            return Some(value.clone());
+        }
+        if !is_constant(expr) {
+            return None;
+        }
+        let predicate = ExpressionPredicate::new(
+            self.convert_expression(expr).ok()?,
+            HashMap::new(),
+            Arc::clone(&self.store),
+        );
+        let value = predicate.eval_at(&DataChunk::empty(), 0)?;
+        predicate.error().is_none().then_some(value)
+    }
+
     /// Plans a LIMIT operator.
     fn plan_limit(&self, limit: &LimitOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
         let (input_op, columns) = self.plan_operator(&limit.input)?;
@@ -763,7 +1357,41 @@ impl Planner {
 
     /// Plans a SORT (ORDER BY) operator.
     fn plan_sort(&self, sort: &SortOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
-        let (mut input_op, input_columns) = self.plan_operator(&sort.input)?;
+        let (input_op, physical_keys, output_columns) =
+            self.plan_sort_input(&sort.input, &sort.keys)?;
+
+        let output_schema = self.derive_schema_from_columns(&output_columns);
+        let mut sort = SortOperator::new(input_op, physical_keys, output_schema);
+        if let Some(memory) = &self.memory_context {
+            sort = sort.with_memory_context(Arc::clone(memory));
+        }
+        let operator = Box::new(sort);
+        Ok((operator, output_columns))
+    }
+
+    /// Plans a top-N operator.
+    fn plan_top_n(&self, top: &TopNOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        let (input_op, physical_keys, output_columns) =
+            self.plan_sort_input(&top.input, &top.keys)?;
+
+        let output_schema = self.derive_schema_from_columns(&output_columns);
+        let operator = Box::new(TopNOperator::new(
+            input_op,
+            physical_keys,
+            top.count,
+            output_schema,
+        ));
+        Ok((operator, output_columns))
+    }
+
+    /// Plans the input of a sort, projecting the property and path cost
+    /// columns its keys need, and converts the keys to physical ones.
+    fn plan_sort_input(
+        &self,
+        input: &LogicalOperator,
+        keys: &[SortKey],
+    ) -> Result<(Box<dyn Operator>, Vec<PhysicalSortKey>, Vec<String>)> {
+        let (mut input_op, input_columns) = self.plan_operator(input)?;
 
         // Build variable to column index mapping
         let mut variable_columns: HashMap<String, usize> = input_columns
@@ -774,20 +1402,33 @@ impl Planner {
 
         // Collect property expressions that need to be projected before sorting
         let mut property_projections: Vec<(String, String, String)> = Vec::new();
+        // Path costs are computed from the path's edges before sorting too
+        let mut cost_projections: Vec<(&LogicalExpression, String)> = Vec::new();
         let mut next_col_idx = input_columns.len();
 
-        for key in &sort.keys {
-            if let LogicalExpression::Property { variable, property } = &key.expression {
-                let col_name = format!("{}_{}", variable, property);
-                if !variable_columns.contains_key(&col_name) {
-                    property_projections.push((
-                        variable.clone(),
-                        property.clone(),
-                        col_name.clone(),
-                    ));
-                    variable_columns.insert(col_name, next_col_idx);
-                    next_col_idx += 1;
+        for key in keys {
+            match &key.expression {
+                LogicalExpression::Property { variable, property } => {
+                    let col_name = format!("{}_{}", variable, property);
+                    if !variable_columns.contains_key(&col_name) {
+                        property_projections.push((
+                            variable.clone(),
+                            property.clone(),
+                            col_name.clone(),
+                        ));
+                        variable_columns.insert(col_name, next_col_idx);
+                        next_col_idx += 1;
+                    }
+                }
+                LogicalExpression::PathCost { variable, property } => {
+                    let col_name = format!("_path_cost_{}_{}", variable, property);
+                    if !variable_columns.contains_key(&col_name) {
+                        cost_projections.push((&key.expression, col_name.clone()));
+                        variable_columns.insert(col_name, next_col_idx);
+                        next_col_idx += 1;
+                    }
                 }
+                _ => {}
             }
         }
 
@@ -795,7 +1436,7 @@ impl Planner {
         let mut output_columns = input_columns.clone();
 
         // If we have property expressions, add a projection to materialize them
-        if !property_projections.is_empty() {
+        if !property_projections.is_empty() || !cost_projections.is_empty() {
             let mut projections = Vec::new();
             let mut output_types = Vec::new();
 
@@ -822,17 +1463,28 @@ impl Planner {
                 output_columns.push(col_name.clone());
             }
 
-            input_op = Box::new(ProjectOperator::with_store(
-                input_op,
-                projections,
-                output_types,
-                Arc::clone(&self.store),
-            ));
+            for (expr, col_name) in &cost_projections {
+                projections.push(ProjectExpr::Expression {
+                    expr: self.convert_expression(expr)?,
+                    variable_columns: variable_columns.clone(),
+                });
+                output_types.push(LogicalType::Any);
+                output_columns.push(col_name.clone());
+            }
+
+            input_op = Box::new(
+                ProjectOperator::with_store(
+                    input_op,
+                    projections,
+                    output_types,
+                    Arc::clone(&self.store),
+                )
+                .with_tx_context(self.viewing_epoch, self.tx_id),
+            );
         }
 
         // Convert logical sort keys to physical sort keys
-        let physical_keys: Vec<PhysicalSortKey> = sort
-            .keys
+        let physical_keys: Vec<PhysicalSortKey> = keys
             .iter()
             .map(|key| {
                 let col_idx = self
@@ -844,13 +1496,12 @@ impl Planner {
                         SortOrder::Descending => SortDirection::Descending,
                     },
                     null_order: NullOrder::NullsLast,
+                    collation: self.collation,
                 })
             })
             .collect::<Result<Vec<_>>>()?;
 
-        let output_schema = self.derive_schema_from_columns(&output_columns);
-        let operator = Box::new(SortOperator::new(input_op, physical_keys, output_schema));
-        Ok((operator, output_columns))
+        Ok((input_op, physical_keys, output_columns))
     }
 
     /// Resolves a sort expression to a column index, using projected property columns.
@@ -875,6 +1526,16 @@ impl Planner {
                     ))
                 })
             }
+            LogicalExpression::PathLength(path) => variable_columns
+                .get(&format!("_path_length_{path}"))
+                .copied()
+                .ok_or_else(|| Error::Internal(format!("Path '{}' not found for ORDER BY", path))),
+            LogicalExpression::PathCost { variable, property } => {
+                let col_name = format!("_path_cost_{}_{}", variable, property);
+                variable_columns.get(&col_name).copied().ok_or_else(|| {
+                    Error::Internal(format!("Path '{}' not found for ORDER BY", variable))
+                })
+            }
             _ => Err(Error::Internal(format!(
                 "Unsupported ORDER BY expression: {:?}",
                 expr
@@ -934,8 +1595,24 @@ impl Planner {
             }
         }
 
-        // If we have property expressions, add a projection to materialize them
-        if !property_projections.is_empty() {
+        // Aggregate arguments that are neither variables nor properties, such as
+        // size((p)-->()), are evaluated per row into their own columns
+        let mut expression_projections: Vec<FilterExpression> = Vec::new();
+        for (i, agg_expr) in agg.aggregates.iter().enumerate() {
+            if let Some(expr) = &agg_expr.expression {
+                if !matches!(
+                    expr,
+                    LogicalExpression::Variable(_) | LogicalExpression::Property { .. }
+                ) {
+                    expression_projections.push(self.convert_expression(expr)?);
+                    variable_columns.insert(format!("_agg_expr_{i}"), next_col_idx);
+                    next_col_idx += 1;
+                }
+            }
+        }
+
+        // If we have property or computed expressions, add a projection to materialize them
+        if !property_projections.is_empty() || !expression_projections.is_empty() {
             let mut projections = Vec::new();
             let mut output_types = Vec::new();
 
@@ -961,12 +1638,29 @@ impl Planner {
                 output_types.push(LogicalType::Any); // Properties can be any type (string, int, etc.)
             }
 
-            input_op = Box::new(ProjectOperator::with_store(
-                input_op,
-                projections,
-                output_types,
-                Arc::clone(&self.store),
-            ));
+            // Then the computed aggregate arguments, evaluated against the input columns
+            let input_variable_columns: HashMap<String, usize> = input_columns
+                .iter()
+                .enumerate()
+                .map(|(i, name)| (name.clone(), i))
+                .collect();
+            for expr in expression_projections {
+                projections.push(ProjectExpr::Expression {
+                    expr,
+                    variable_columns: input_variable_columns.clone(),
+                });
+                output_types.push(LogicalType::Any);
+            }
+
+            input_op = Box::new(
+                ProjectOperator::with_store(
+                    input_op,
+                    projections,
+                    output_types,
+                    Arc::clone(&self.store),
+                )
+                .with_tx_context(self.viewing_epoch, self.tx_id),
+            );
         }
 
         // Convert group-by expressions to column indices
@@ -980,12 +1674,21 @@ impl Planner {
         let physical_aggregates: Vec<PhysicalAggregateExpr> = agg
             .aggregates
             .iter()
-            .map(|agg_expr| {
+            .enumerate()
+            .map(|(i, agg_expr)| {
                 let column = agg_expr
                     .expression
                     .as_ref()
-                    .map(|e| {
-                        self.resolve_expression_to_column_with_properties(e, &variable_columns)
+                    .map(|e| match e {
+                        LogicalExpression::Variable(_) | LogicalExpression::Property { .. } => {
+                            self.resolve_expression_to_column_with_properties(e, &variable_columns)
+                        }
+                        _ => variable_columns
+                            .get(&format!("_agg_expr_{i}"))
+                            .copied()
+                            .ok_or_else(|| {
+                                Error::Internal(format!("Computed aggregate column {i} not found"))
+                            }),
                     })
                     .transpose()?;
 
@@ -1024,6 +1727,7 @@ impl Planner {
                     LogicalType::Int64
                 }
                 LogicalAggregateFunction::Collect => LogicalType::Any, // List type (using Any since List is a complex type)
+                LogicalAggregateFunction::CollectMap => LogicalType::Any,
                 // Statistical functions return Float64
                 LogicalAggregateFunction::StdDev
                 | LogicalAggregateFunction::StdDevPop
@@ -1047,12 +1751,17 @@ impl Planner {
                 output_schema,
             ))
         } else {
-            Box::new(HashAggregateOperator::new(
+            let mut aggregate = HashAggregateOperator::new(
                 input_op,
                 group_columns,
                 physical_aggregates,
                 output_schema,
-            ))
+            )
+            .with_estimated_groups(self.cardinality_estimator().estimate_aggregate(agg));
+            if let Some(memory) = &self.memory_context {
+                aggregate = aggregate.with_memory_context(Arc::clone(memory));
+            }
+            Box::new(aggregate)
         };
 
         // Apply HAVING clause filter if present
@@ -1066,7 +1775,9 @@ impl Planner {
 
             let filter_expr = self.convert_expression(having_expr)?;
             let predicate =
-                ExpressionPredicate::new(filter_expr, having_var_columns, Arc::clone(&self.store));
+                ExpressionPredicate::new(filter_expr, having_var_columns, Arc::clone(&self.store))
+                    .with_tx_context(self.viewing_epoch, self.tx_id)
+                    .with_collation(self.collation);
             operator = Box::new(FilterOperator::new(operator, Box::new(predicate)));
         }
 
@@ -1126,6 +1837,31 @@ impl Planner {
         }
     }
 
+    /// Looks up the user-defined function a call refers to, checking that
+    /// it's given the right number of arguments.
+    ///
+    /// Built-in functions take precedence, so a call to one of those never
+    /// resolves to a UDF.
+    fn user_function(&self, name: &str, arg_count: usize) -> Result<Option<&ScalarUdf>> {
+        if is_builtin_function(name) {
+            return Ok(None);
+        }
+        let Some(function) = self.udfs.get(&name.to_lowercase()) else {
+            return Ok(None);
+        };
+        if function.arity() != arg_count {
+            return Err(Error::Query(QueryError::new(
+                QueryErrorKind::Semantic,
+                format!(
+                    "{}() takes {} argument(s) but was given {arg_count}",
+                    function.name(),
+                    function.arity()
+                ),
+            )));
+        }
+        Ok(Some(function))
+    }
+
     /// Converts a logical expression to a filter expression.
     fn convert_expression(&self, expr: &LogicalExpression) -> Result<FilterExpression> {
         match expr {
@@ -1158,6 +1894,9 @@ impl Planner {
                     .iter()
                     .map(|a| self.convert_expression(a))
                     .collect::<Result<Vec<_>>>()?;
+                if let Some(function) = self.user_function(name, filter_args.len())? {
+                    return fold_user_function(function, filter_args);
+                }
                 Ok(FilterExpression::FunctionCall {
                     name: name.clone(),
                     args: filter_args,
@@ -1239,6 +1978,22 @@ impl Planner {
             LogicalExpression::Labels(var) => Ok(FilterExpression::Labels(var.clone())),
             LogicalExpression::Type(var) => Ok(FilterExpression::Type(var.clone())),
             LogicalExpression::Id(var) => Ok(FilterExpression::Id(var.clone())),
+            LogicalExpression::PathLength(var) => {
+                Ok(FilterExpression::Variable(format!("_path_length_{var}")))
+            }
+            LogicalExpression::PathCost { variable, property } => Ok(FilterExpression::PathCost {
+                variable: variable.clone(),
+                property: property.clone(),
+            }),
+            LogicalExpression::PropertyMap {
+                variable,
+                keys,
+                include_id_label,
+            } => Ok(FilterExpression::PropertyMap {
+                variable: variable.clone(),
+                keys: keys.clone(),
+                include_id_label: *include_id_label,
+            }),
             LogicalExpression::ListComprehension {
                 variable,
                 list_expr,
@@ -1274,8 +2029,78 @@ impl Planner {
                     max_hops: None,
                 })
             }
-            LogicalExpression::CountSubquery(_) => Err(Error::Internal(
-                "COUNT subqueries not yet supported".to_string(),
+            LogicalExpression::CountSubquery(subplan) => {
+                let (start_var, direction, edge_type, end_labels) =
+                    Self::extract_count_pattern(subplan)?;
+
+                Ok(FilterExpression::CountSubquery {
+                    start_var,
+                    direction,
+                    edge_type,
+                    end_labels,
+                })
+            }
+        }
+    }
+
+    /// Extracts the pattern from a COUNT subplan.
+    ///
+    /// Only single-hop patterns are supported. Unlike EXISTS, end labels are
+    /// taken from the `hasLabel` filter on the expanded node, since they
+    /// change the count. Any other filter, such as inline properties, is
+    /// rejected rather than ignored, which would count too many.
+    fn extract_count_pattern(
+        subplan: &LogicalOperator,
+    ) -> Result<(String, Direction, Option<String>, Option<Vec<String>>)> {
+        match subplan {
+            LogicalOperator::Expand(expand) => {
+                if expand.min_hops != 1 || expand.max_hops != Some(1) {
+                    return Err(Error::Internal(
+                        "COUNT subqueries over variable-length patterns not yet supported"
+                            .to_string(),
+                    ));
+                }
+                let direction = match expand.direction {
+                    ExpandDirection::Outgoing => Direction::Outgoing,
+                    ExpandDirection::Incoming => Direction::Incoming,
+                    ExpandDirection::Both => Direction::Both,
+                };
+                Ok((
+                    expand.from_variable.clone(),
+                    direction,
+                    expand.edge_type.clone(),
+                    None,
+                ))
+            }
+            LogicalOperator::Filter(filter) => {
+                let (start_var, direction, edge_type, mut end_labels) =
+                    Self::extract_count_pattern(&filter.input)?;
+                let LogicalExpression::FunctionCall { name, args, .. } = &filter.predicate else {
+                    return Err(Error::Internal(
+                        "COUNT subqueries with property filters not yet supported".to_string(),
+                    ));
+                };
+                let [
+                    _,
+                    LogicalExpression::Literal(grafeo_common::types::Value::String(label)),
+                ] = args.as_slice()
+                else {
+                    return Err(Error::Internal(format!(
+                        "COUNT subqueries filtered by {name}() not yet supported"
+                    )));
+                };
+                if name != "hasLabel" {
+                    return Err(Error::Internal(format!(
+                        "COUNT subqueries filtered by {name}() not yet supported"
+                    )));
+                }
+                end_labels
+                    .get_or_insert_with(Vec::new)
+                    .push(label.to_string());
+                Ok((start_var, direction, edge_type, end_labels))
+            }
+            _ => Err(Error::Internal(
+                "Unsupported COUNT subquery pattern".to_string(),
             )),
         }
     }
@@ -1322,7 +2147,7 @@ impl Planner {
     fn extract_end_labels_from_expand(&self, expand: &ExpandOp) -> Option<Vec<String>> {
         // Check if the expand has a NodeScan input with a label filter
         match expand.input.as_ref() {
-            LogicalOperator::NodeScan(scan) => scan.label.clone().map(|l| vec![l]),
+            LogicalOperator::NodeScan(scan) if !scan.labels.is_empty() => Some(scan.labels.clone()),
             _ => None,
         }
     }
@@ -1367,14 +2192,40 @@ impl Planner {
 
         let output_schema = self.derive_schema_from_columns(&columns);
 
-        let operator: Box<dyn Operator> = Box::new(HashJoinOperator::new(
-            left_op,
-            right_op,
-            probe_keys,
-            build_keys,
-            physical_join_type,
-            output_schema,
-        ));
+        // Equi-joins on every condition can use the parallel hash join
+        if let Some(config) = &self.parallel {
+            if physical_join_type == PhysicalJoinType::Inner
+                && !probe_keys.is_empty()
+                && probe_keys.len() == join.conditions.len()
+            {
+                let mut parallel = ParallelHashJoin::new(build_keys, probe_keys, config.clone());
+                if let Some(memory) = &self.memory_context {
+                    parallel = parallel.with_memory_context(Arc::clone(memory));
+                }
+                let operator: Box<dyn Operator> = Box::new(ParallelHashJoinOperator::new(
+                    left_op,
+                    right_op,
+                    parallel,
+                    output_schema,
+                ));
+                return Ok((operator, columns));
+            }
+        }
+
+        let build_rows = self.cardinality_estimator().estimate(&join.right);
+        let operator: Box<dyn Operator> = Box::new(
+            self.budget_hash_join(
+                HashJoinOperator::new(
+                    left_op,
+                    right_op,
+                    probe_keys,
+                    build_keys,
+                    physical_join_type,
+                    output_schema,
+                )
+                .with_estimated_build_rows(build_rows),
+            ),
+        );
 
         Ok((operator, columns))
     }
@@ -1425,8 +2276,52 @@ impl Planner {
         Ok((operator, columns))
     }
 
+    /// Checks labels, edge type and property keys against the strict schema, if any.
+    fn validate_schema<'a>(
+        &self,
+        labels: &[String],
+        edge_type: Option<&str>,
+        property_keys: impl IntoIterator<Item = &'a String>,
+    ) -> Result<()> {
+        let Some(catalog) = self.catalog.as_ref().filter(|_| self.strict_schema) else {
+            return Ok(());
+        };
+        catalog.validate_write(
+            labels.iter().map(String::as_str),
+            edge_type,
+            property_keys.into_iter().map(String::as_str),
+        )?;
+        Ok(())
+    }
+
+    /// Keeps one value per property key of a write: the last one given, or
+    /// none at all under [`DuplicateKeyPolicy::Strict`], which rejects the
+    /// write instead.
+    fn resolve_duplicate_keys<T>(&self, properties: Vec<(String, T)>) -> Result<Vec<(String, T)>> {
+        let mut resolved: Vec<(String, T)> = Vec::with_capacity(properties.len());
+        for (key, value) in properties {
+            match resolved.iter_mut().find(|(existing, _)| *existing == key) {
+                Some(_) if self.duplicate_keys == DuplicateKeyPolicy::Strict => {
+                    return Err(Error::Query(QueryError::new(
+                        QueryErrorKind::Semantic,
+                        format!("Property '{key}' is given more than once"),
+                    )));
+                }
+                Some(existing) => existing.1 = value,
+                None => resolved.push((key, value)),
+            }
+        }
+        Ok(resolved)
+    }
+
     /// Plans a CREATE NODE operator.
     fn plan_create_node(&self, create: &CreateNodeOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        self.validate_schema(
+            &create.labels,
+            None,
+            create.properties.iter().map(|(name, _)| name),
+        )?;
+
         // Plan input if present
         let (input_op, mut columns) = if let Some(ref input) = create.input {
             let (op, cols) = self.plan_operator(input)?;
@@ -1435,22 +2330,33 @@ impl Planner {
             (None, vec![])
         };
 
-        // Output column for the created node
-        let output_column = columns.len();
-        columns.push(create.variable.clone());
-
-        // Convert properties
+        // Convert properties; values may come from the input's columns, as
+        // in `UNWIND $rows AS row CREATE (:Person {name: row.name})`
         let properties: Vec<(String, PropertySource)> = create
             .properties
             .iter()
             .map(|(name, expr)| {
+                let column = |variable: &str| columns.iter().position(|c| c == variable);
                 let source = match expr {
                     LogicalExpression::Literal(v) => PropertySource::Constant(v.clone()),
+                    LogicalExpression::Variable(variable) => match column(variable) {
+                        Some(col_idx) => PropertySource::Column(col_idx),
+                        None => PropertySource::Constant(grafeo_common::types::Value::Null),
+                    },
+                    LogicalExpression::Property { variable, property } => match column(variable) {
+                        Some(col_idx) => PropertySource::MapEntry(col_idx, property.clone()),
+                        None => PropertySource::Constant(grafeo_common::types::Value::Null),
+                    },
                     _ => PropertySource::Constant(grafeo_common::types::Value::Null),
                 };
                 (name.clone(), source)
             })
             .collect();
+        let properties = self.normalize_properties(self.resolve_duplicate_keys(properties)?);
+
+        // Output column for the created node
+        let output_column = columns.len();
+        columns.push(create.variable.clone());
 
         let output_schema = self.derive_schema_from_columns(&columns);
 
@@ -1471,6 +2377,12 @@ impl Planner {
 
     /// Plans a CREATE EDGE operator.
     fn plan_create_edge(&self, create: &CreateEdgeOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        self.validate_schema(
+            &[],
+            Some(&create.edge_type),
+            create.properties.iter().map(|(name, _)| name),
+        )?;
+
         let (input_op, mut columns) = self.plan_operator(&create.input)?;
 
         // Find source and target columns
@@ -1513,6 +2425,7 @@ impl Planner {
                 (name.clone(), source)
             })
             .collect();
+        let properties = self.normalize_properties(self.resolve_duplicate_keys(properties)?);
 
         let output_schema = self.derive_schema_from_columns(&columns);
 
@@ -1527,12 +2440,54 @@ impl Planner {
                 output_schema,
                 output_column,
             )
+            .with_multiplicity(self.edge_multiplicity(&create.edge_type))
             .with_tx_context(self.viewing_epoch, self.tx_id),
         );
 
         Ok((operator, columns))
     }
 
+    /// Applies the catalog's property normalizers to the values a write
+    /// stores. Constants are normalized once, here.
+    fn normalize_properties(
+        &self,
+        properties: Vec<(String, PropertySource)>,
+    ) -> Vec<(String, PropertySource)> {
+        let Some(catalog) = self.catalog.as_ref() else {
+            return properties;
+        };
+        properties
+            .into_iter()
+            .map(|(name, source)| {
+                let source = match (catalog.property_normalizer(&name), source) {
+                    (None, source) => source,
+                    (Some(normalizer), PropertySource::Constant(value)) => {
+                        PropertySource::Constant(normalizer.apply(value))
+                    }
+                    (Some(normalizer), source) => {
+                        PropertySource::Normalized(Box::new(source), normalizer)
+                    }
+                };
+                (name, source)
+            })
+            .collect()
+    }
+
+    /// Returns the catalog's duplicate-edge policy for an edge type.
+    fn edge_multiplicity(&self, edge_type: &str) -> EdgeMultiplicity {
+        self.catalog
+            .as_ref()
+            .map_or(EdgeMultiplicity::Allow, |c| c.edge_multiplicity(edge_type))
+    }
+
+    /// Builds a cardinality estimator from the store's current statistics.
+    ///
+    /// Used to pre-size hash tables; with no statistics collected the
+    /// estimates fall back to the smallest table size.
+    fn cardinality_estimator(&self) -> CardinalityEstimator {
+        CardinalityEstimator::from_statistics(&self.store.statistics())
+    }
+
     /// Plans a DELETE NODE operator.
     fn plan_delete_node(&self, delete: &DeleteNodeOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
         let (input_op, columns) = self.plan_operator(&delete.input)?;
@@ -1603,7 +2558,6 @@ impl Planner {
 
         // Build combined output columns (left + right)
         let mut columns = left_columns.clone();
-        columns.extend(right_columns.clone());
 
         // Find common variables between left and right for join keys
         let mut probe_keys = Vec::new();
@@ -1613,23 +2567,36 @@ impl Planner {
             if let Some(left_idx) = left_columns.iter().position(|c| c == right_col) {
                 probe_keys.push(left_idx);
                 build_keys.push(right_idx);
+                // The right copy is null for unmatched rows, so keep it out
+                // of reach and let the variable resolve to the left one
+                columns.push(format!("{right_col}#optional"));
+            } else {
+                columns.push(right_col.clone());
             }
         }
 
         let output_schema = self.derive_schema_from_columns(&columns);
 
-        let operator: Box<dyn Operator> = Box::new(HashJoinOperator::new(
+        let operator: Box<dyn Operator> = Box::new(self.budget_hash_join(HashJoinOperator::new(
             left_op,
             right_op,
             probe_keys,
             build_keys,
             PhysicalJoinType::Left,
             output_schema,
-        ));
+        )));
 
         Ok((operator, columns))
     }
 
+    /// Attaches the query's memory budget, if any, to a hash join.
+    fn budget_hash_join(&self, join: HashJoinOperator) -> HashJoinOperator {
+        match &self.memory_context {
+            Some(memory) => join.with_memory_context(Arc::clone(memory)),
+            None => join,
+        }
+    }
+
     /// Plans an ANTI JOIN operator (for WHERE NOT EXISTS patterns).
     fn plan_anti_join(&self, anti_join: &AntiJoinOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
         let (left_op, left_columns) = self.plan_operator(&anti_join.left)?;
@@ -1651,154 +2618,319 @@ impl Planner {
 
         let output_schema = self.derive_schema_from_columns(&columns);
 
-        let operator: Box<dyn Operator> = Box::new(HashJoinOperator::new(
+        let operator: Box<dyn Operator> = Box::new(self.budget_hash_join(HashJoinOperator::new(
             left_op,
             right_op,
             probe_keys,
             build_keys,
             PhysicalJoinType::Anti,
             output_schema,
-        ));
+        )));
 
         Ok((operator, columns))
     }
 
     /// Plans an unwind operator.
     fn plan_unwind(&self, unwind: &UnwindOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
-        // Plan the input operator first
-        // Handle Empty specially - use a single-row operator
+        // UNWIND without a prior clause runs over a single empty row
         let (input_op, input_columns): (Box<dyn Operator>, Vec<String>) =
             if matches!(&*unwind.input, LogicalOperator::Empty) {
-                // For UNWIND without prior MATCH, create a single-row input
-                // We need an operator that produces one row with the list to unwind
-                // For now, use EmptyScan which produces no rows - we'll handle the literal
-                // list in the unwind operator itself
-                let literal_list = self.convert_expression(&unwind.expression)?;
-
-                // Create a project operator that produces a single row with the list
-                let single_row_op: Box<dyn Operator> = Box::new(
-                    grafeo_core::execution::operators::single_row::SingleRowOperator::new(),
-                );
-                let project_op: Box<dyn Operator> = Box::new(ProjectOperator::with_store(
-                    single_row_op,
-                    vec![ProjectExpr::Expression {
-                        expr: literal_list,
-                        variable_columns: HashMap::new(),
-                    }],
-                    vec![LogicalType::Any],
-                    Arc::clone(&self.store),
-                ));
-
-                (project_op, vec!["__list__".to_string()])
+                (
+                    Box::new(
+                        grafeo_core::execution::operators::single_row::SingleRowOperator::new(),
+                    ),
+                    Vec::new(),
+                )
             } else {
                 self.plan_operator(&unwind.input)?
             };
 
-        // The UNWIND expression should be a list - we need to find/evaluate it
-        // For now, we handle the case where the expression references an existing column
-        // or is a literal list
+        let variable_columns: HashMap<String, usize> = input_columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect();
 
-        // Find if the expression references an existing column (like a list property)
-        let list_col_idx = match &unwind.expression {
-            LogicalExpression::Variable(var) => input_columns.iter().position(|c| c == var),
-            LogicalExpression::Property { variable, .. } => {
-                // Property access needs to be evaluated - for now we'll need the filter predicate
-                // to evaluate this. For simple cases, we treat it as a list column.
-                input_columns.iter().position(|c| c == variable)
-            }
-            LogicalExpression::List(_) | LogicalExpression::Literal(_) => {
-                // Literal list expression - we'll add it as a virtual column
-                None
-            }
-            _ => None,
-        };
+        // Keep the input columns and append the evaluated list
+        let mut projections: Vec<ProjectExpr> =
+            (0..input_columns.len()).map(ProjectExpr::Column).collect();
+        projections.push(ProjectExpr::Expression {
+            expr: self.convert_expression(&unwind.expression)?,
+            variable_columns,
+        });
+        let project_op: Box<dyn Operator> = Box::new(
+            ProjectOperator::with_store(
+                input_op,
+                projections,
+                self.derive_schema_from_columns(&input_columns)
+                    .into_iter()
+                    .chain([LogicalType::Any])
+                    .collect(),
+                Arc::clone(&self.store),
+            )
+            .with_tx_context(self.viewing_epoch, self.tx_id),
+        );
 
-        // Build output columns: all input columns plus the new variable
+        // Unwinding the list column replaces it with the element
         let mut columns = input_columns.clone();
         columns.push(unwind.variable.clone());
+        let operator: Box<dyn Operator> = Box::new(UnwindOperator::new(
+            project_op,
+            input_columns.len(),
+            unwind.variable.clone(),
+            self.derive_schema_from_columns(&columns),
+        ));
 
-        // Build output schema
-        let mut output_schema = self.derive_schema_from_columns(&input_columns);
-        output_schema.push(LogicalType::Any); // The unwound element type is dynamic
+        Ok((operator, columns))
+    }
 
-        // Use the list column index if found, otherwise default to 0
-        // (in which case the first column should contain the list)
-        let col_idx = list_col_idx.unwrap_or(0);
+    /// Plans a FOREACH operator.
+    ///
+    /// The body is planned with a scope whose `Empty` leaf unwinds the list
+    /// over the row fed to it, so the body's mutations run once per element.
+    fn plan_for_each(&self, foreach: &ForEachOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        let (input_op, columns): (Box<dyn Operator>, Vec<String>) =
+            if matches!(foreach.input.as_ref(), LogicalOperator::Empty)
+                && self.foreach_scopes.borrow().is_empty()
+            {
+                (
+                    Box::new(
+                        grafeo_core::execution::operators::single_row::SingleRowOperator::new(),
+                    ),
+                    Vec::new(),
+                )
+            } else {
+                self.plan_operator(&foreach.input)?
+            };
 
-        let operator: Box<dyn Operator> = Box::new(UnwindOperator::new(
-            input_op,
-            col_idx,
-            unwind.variable.clone(),
-            output_schema,
+        let feed = ForEachFeed::default();
+        self.foreach_scopes.borrow_mut().push(ForEachScope {
+            feed: Arc::clone(&feed),
+            columns: columns.clone(),
+            list: foreach.list.clone(),
+            variable: foreach.variable.clone(),
+        });
+        let body = self.plan_operator(&foreach.body);
+        self.foreach_scopes.borrow_mut().pop();
+        let (body_op, _) = body?;
+
+        let operator = Box::new(ForEachOperator::new(input_op, body_op, feed));
+        Ok((operator, columns))
+    }
+
+    /// Plans the `Empty` leaf of the innermost FOREACH body.
+    fn plan_foreach_leaf(&self) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        let scopes = self.foreach_scopes.borrow();
+        let scope = scopes
+            .last()
+            .ok_or_else(|| Error::Internal("Empty plan".to_string()))?;
+
+        let variable_columns: HashMap<String, usize> = scope
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect();
+
+        // Keep the fed row's columns and append the evaluated list
+        let mut projections: Vec<ProjectExpr> =
+            (0..scope.columns.len()).map(ProjectExpr::Column).collect();
+        projections.push(ProjectExpr::Expression {
+            expr: self.convert_expression(&scope.list)?,
+            variable_columns,
+        });
+        let project_op: Box<dyn Operator> = Box::new(
+            ProjectOperator::with_store(
+                Box::new(ForEachSourceOperator::new(Arc::clone(&scope.feed))),
+                projections,
+                self.derive_schema_from_columns(&scope.columns)
+                    .into_iter()
+                    .chain([LogicalType::Any])
+                    .collect(),
+                Arc::clone(&self.store),
+            )
+            .with_tx_context(self.viewing_epoch, self.tx_id),
+        );
+
+        // Unwinding the list column replaces it with the element
+        let mut columns = scope.columns.clone();
+        columns.push(scope.variable.clone());
+        let operator = Box::new(UnwindOperator::new(
+            project_op,
+            scope.columns.len(),
+            scope.variable.clone(),
+            self.derive_schema_from_columns(&columns),
         ));
 
         Ok((operator, columns))
     }
 
     /// Plans a MERGE operator.
+    ///
+    /// ON CREATE / ON MATCH updates run on the merge's output, each keyed on
+    /// the created column the merge emits; that column is dropped again
+    /// once they've run.
     fn plan_merge(&self, merge: &MergeOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        self.validate_schema(
+            &merge.labels,
+            None,
+            merge.match_properties.iter().map(|(name, _)| name),
+        )?;
+        if let Some(rel) = &merge.relationship {
+            self.validate_schema(
+                &rel.target_labels,
+                Some(&rel.edge_type),
+                rel.properties
+                    .iter()
+                    .chain(&rel.target_properties)
+                    .map(|(name, _)| name),
+            )?;
+        }
+
         // Plan the input operator if present (skip if Empty)
-        let mut columns = if matches!(merge.input.as_ref(), LogicalOperator::Empty) {
-            Vec::new()
+        let (input_op, mut columns) = if matches!(merge.input.as_ref(), LogicalOperator::Empty) {
+            (None, Vec::new())
         } else {
-            let (_input_op, cols) = self.plan_operator(&merge.input)?;
-            cols
+            let (op, cols) = self.plan_operator(&merge.input)?;
+            (Some(op), cols)
+        };
+        let input_columns = columns.clone();
+
+        // Variables the input already binds are reused rather than merged
+        let node = self.merge_node(
+            &merge.variable,
+            &merge.labels,
+            &merge.match_properties,
+            &input_columns,
+            &mut columns,
+        )?;
+        let edge = match &merge.relationship {
+            Some(rel) => {
+                columns.push(
+                    rel.variable
+                        .clone()
+                        .unwrap_or_else(|| "_merge_edge".to_string()),
+                );
+                let target = self.merge_node(
+                    &rel.target_variable,
+                    &rel.target_labels,
+                    &rel.target_properties,
+                    &input_columns,
+                    &mut columns,
+                )?;
+                let properties = rel
+                    .properties
+                    .iter()
+                    .map(|(name, expr)| {
+                        Ok((
+                            name.clone(),
+                            self.expression_to_property_source(expr, &input_columns)?,
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let direction = match rel.direction {
+                    ExpandDirection::Outgoing => Direction::Outgoing,
+                    ExpandDirection::Incoming => Direction::Incoming,
+                    ExpandDirection::Both => Direction::Both,
+                };
+                Some(MergeEdge::new(
+                    rel.edge_type.clone(),
+                    direction,
+                    self.normalize_properties(self.resolve_duplicate_keys(properties)?),
+                    target,
+                ))
+            }
+            None => None,
         };
 
-        // Convert match properties from LogicalExpression to Value
-        let match_properties: Vec<(String, grafeo_common::types::Value)> = merge
-            .match_properties
-            .iter()
-            .filter_map(|(name, expr)| {
-                if let LogicalExpression::Literal(v) = expr {
-                    Some((name.clone(), v.clone()))
-                } else {
-                    None // Skip non-literal expressions for now
-                }
-            })
-            .collect();
+        let has_updates = !merge.on_create.is_empty() || !merge.on_match.is_empty();
+        let created_column = columns.len();
+        if has_updates {
+            columns.push("_merge_created".to_string());
+        }
 
-        // Convert ON CREATE properties
-        let on_create_properties: Vec<(String, grafeo_common::types::Value)> = merge
-            .on_create
-            .iter()
-            .filter_map(|(name, expr)| {
-                if let LogicalExpression::Literal(v) = expr {
-                    Some((name.clone(), v.clone()))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        let mut operator = MergeOperator::new(
+            Arc::clone(&self.store),
+            input_op,
+            node,
+            self.derive_schema_from_columns(&columns),
+        )
+        .with_tx_context(self.viewing_epoch, self.tx_id);
+        if let Some(edge) = edge {
+            operator = operator.with_edge(edge);
+        }
+        if has_updates {
+            operator = operator.with_created_column();
+        }
+        let mut operator: Box<dyn Operator> = Box::new(operator);
 
-        // Convert ON MATCH properties
-        let on_match_properties: Vec<(String, grafeo_common::types::Value)> = merge
-            .on_match
-            .iter()
-            .filter_map(|(name, expr)| {
-                if let LogicalExpression::Literal(v) = expr {
-                    Some((name.clone(), v.clone()))
-                } else {
-                    None
-                }
-            })
-            .collect();
+        if !has_updates {
+            return Ok((operator, columns));
+        }
 
-        // Add the merged node variable to output columns
-        columns.push(merge.variable.clone());
+        for (updates, created) in [(&merge.on_create, true), (&merge.on_match, false)] {
+            for set_prop in updates {
+                let is_edge = merge
+                    .relationship
+                    .as_ref()
+                    .and_then(|rel| rel.variable.as_ref())
+                    .is_some_and(|edge_var| edge_var == &set_prop.variable);
+                operator = Box::new(
+                    self.build_set_property(set_prop, operator, &columns, is_edge)?
+                        .with_condition(created_column, created),
+                );
+            }
+        }
 
-        let operator: Box<dyn Operator> = Box::new(MergeOperator::new(
-            Arc::clone(&self.store),
-            merge.variable.clone(),
-            merge.labels.clone(),
-            match_properties,
-            on_create_properties,
-            on_match_properties,
+        // Drop the created column again
+        columns.pop();
+        let operator = Box::new(ProjectOperator::select_columns(
+            operator,
+            (0..columns.len()).collect(),
+            self.derive_schema_from_columns(&columns),
         ));
 
         Ok((operator, columns))
     }
 
+    /// Builds one node of a MERGE pattern.
+    ///
+    /// A variable bound by the input is reused; otherwise the node is merged
+    /// by labels and properties and gets an output column of its own.
+    fn merge_node(
+        &self,
+        variable: &str,
+        labels: &[String],
+        properties: &[(String, LogicalExpression)],
+        input_columns: &[String],
+        columns: &mut Vec<String>,
+    ) -> Result<MergeNode> {
+        if let Some(column) = input_columns.iter().position(|c| c == variable) {
+            if !labels.is_empty() || !properties.is_empty() {
+                return Err(Error::Internal(format!(
+                    "Variable '{}' is already bound and can't be redeclared in MERGE",
+                    variable
+                )));
+            }
+            return Ok(MergeNode::bound(column));
+        }
+
+        let properties = properties
+            .iter()
+            .map(|(name, expr)| {
+                Ok((
+                    name.clone(),
+                    self.expression_to_property_source(expr, input_columns)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        columns.push(variable.to_string());
+        Ok(MergeNode::new(
+            labels.to_vec(),
+            self.normalize_properties(self.resolve_duplicate_keys(properties)?),
+        ))
+    }
+
     /// Plans a SHORTEST PATH operator.
     fn plan_shortest_path(&self, sp: &ShortestPathOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
         // Plan the input operator
@@ -1833,27 +2965,40 @@ impl Planner {
         };
 
         // Create the shortest path operator
-        let operator: Box<dyn Operator> = Box::new(
-            ShortestPathOperator::new(
-                Arc::clone(&self.store),
-                input_op,
-                source_column,
-                target_column,
-                sp.edge_type.clone(),
-                direction,
-            )
-            .with_all_paths(sp.all_paths),
-        );
+        let mut shortest_path = ShortestPathOperator::new(
+            Arc::clone(&self.store),
+            input_op,
+            source_column,
+            target_column,
+            sp.edge_type.clone(),
+            direction,
+        )
+        .with_all_paths(sp.all_paths)
+        .with_max_hops(sp.max_hops);
+        if let Some(at) = self.as_of {
+            shortest_path = shortest_path.with_as_of(at);
+        }
+        if !sp.all_paths {
+            shortest_path = shortest_path.with_path_output();
+        }
+        let operator: Box<dyn Operator> = Box::new(shortest_path);
 
         // Add path length column with the expected naming convention
         // The translator expects _path_length_{alias} format for length(p) calls
         columns.push(format!("_path_length_{}", sp.path_alias));
+        // A single shortest path is also bound as its list of edges, like a
+        // variable-length expand's path
+        if !sp.all_paths {
+            columns.push(sp.path_alias.clone());
+        }
 
         Ok((operator, columns))
     }
 
     /// Plans an ADD LABEL operator.
     fn plan_add_label(&self, add_label: &AddLabelOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        self.validate_schema(&add_label.labels, None, [])?;
+
         let (input_op, columns) = self.plan_operator(&add_label.input)?;
 
         // Find the node column
@@ -1871,13 +3016,16 @@ impl Planner {
         let output_schema = vec![LogicalType::Int64];
         let output_columns = vec!["labels_added".to_string()];
 
-        let operator = Box::new(AddLabelOperator::new(
-            Arc::clone(&self.store),
-            input_op,
-            node_column,
-            add_label.labels.clone(),
-            output_schema,
-        ));
+        let operator = Box::new(
+            AddLabelOperator::new(
+                Arc::clone(&self.store),
+                input_op,
+                node_column,
+                add_label.labels.clone(),
+                output_schema,
+            )
+            .with_tx_context(self.viewing_epoch, self.tx_id),
+        );
 
         Ok((operator, output_columns))
     }
@@ -1904,13 +3052,16 @@ impl Planner {
         let output_schema = vec![LogicalType::Int64];
         let output_columns = vec!["labels_removed".to_string()];
 
-        let operator = Box::new(RemoveLabelOperator::new(
-            Arc::clone(&self.store),
-            input_op,
-            node_column,
-            remove_label.labels.clone(),
-            output_schema,
-        ));
+        let operator = Box::new(
+            RemoveLabelOperator::new(
+                Arc::clone(&self.store),
+                input_op,
+                node_column,
+                remove_label.labels.clone(),
+                output_schema,
+            )
+            .with_tx_context(self.viewing_epoch, self.tx_id),
+        );
 
         Ok((operator, output_columns))
     }
@@ -1921,6 +3072,50 @@ impl Planner {
         set_prop: &SetPropertyOp,
     ) -> Result<(Box<dyn Operator>, Vec<String>)> {
         let (input_op, columns) = self.plan_operator(&set_prop.input)?;
+        let operator = self.build_set_property(set_prop, input_op, &columns, false)?;
+
+        // Output preserves the input columns (passes through)
+        Ok((Box::new(operator), columns))
+    }
+
+    /// Builds the operator for a SET PROPERTY on top of an already planned
+    /// input producing `columns`.
+    fn build_set_property(
+        &self,
+        set_prop: &SetPropertyOp,
+        input_op: Box<dyn Operator>,
+        columns: &[String],
+        is_edge: bool,
+    ) -> Result<SetPropertyOperator> {
+        // `SET n = {...}` and `SET n += {...}` arrive as one "*" entry
+        // holding the map; set each of its keys
+        let mut assignments: Vec<(String, LogicalExpression)> = Vec::new();
+        for (name, expr) in &set_prop.properties {
+            if name != "*" {
+                assignments.push((name.clone(), expr.clone()));
+                continue;
+            }
+            match expr {
+                LogicalExpression::Map(entries) => assignments.extend(entries.iter().cloned()),
+                LogicalExpression::Literal(Value::Map(map)) => {
+                    assignments.extend(map.iter().map(|(key, value)| {
+                        (
+                            key.as_str().to_string(),
+                            LogicalExpression::Literal(value.clone()),
+                        )
+                    }));
+                }
+                _ => {
+                    return Err(Error::Internal(format!(
+                        "SET {} = ... expects a map of properties",
+                        set_prop.variable
+                    )));
+                }
+            }
+        }
+
+        let assignments = self.resolve_duplicate_keys(assignments)?;
+        self.validate_schema(&[], None, assignments.iter().map(|(name, _)| name))?;
 
         // Find the entity column (node or edge variable)
         let entity_column = columns
@@ -1934,29 +3129,48 @@ impl Planner {
             })?;
 
         // Convert properties to PropertySource
-        let properties: Vec<(String, PropertySource)> = set_prop
-            .properties
+        let properties: Vec<(String, PropertySource)> = assignments
             .iter()
             .map(|(name, expr)| {
-                let source = self.expression_to_property_source(expr, &columns)?;
+                let source = self.expression_to_property_source(expr, columns)?;
                 Ok((name.clone(), source))
             })
             .collect::<Result<Vec<_>>>()?;
+        let properties = self.normalize_properties(properties);
 
         // Output schema preserves input schema (passes through)
-        let output_schema: Vec<LogicalType> = columns.iter().map(|_| LogicalType::Node).collect();
-        let output_columns = columns.clone();
-
-        // Determine if this is a node or edge (for now assume node, edge detection can be added later)
-        let operator = Box::new(SetPropertyOperator::new_for_node(
-            Arc::clone(&self.store),
-            input_op,
-            entity_column,
-            properties,
-            output_schema,
-        ));
+        let output_schema = self.derive_schema_from_columns(columns);
 
-        Ok((operator, output_columns))
+        // Only callers that know the variable's kind ask for an edge
+        let operator = if is_edge {
+            SetPropertyOperator::new_for_edge(
+                Arc::clone(&self.store),
+                input_op,
+                entity_column,
+                properties,
+                output_schema,
+            )
+            .with_tx_context(self.viewing_epoch, self.tx_id)
+        } else {
+            SetPropertyOperator::new_for_node(
+                Arc::clone(&self.store),
+                input_op,
+                entity_column,
+                properties,
+                output_schema,
+            )
+            .with_tx_context(self.viewing_epoch, self.tx_id)
+        };
+        let operator = if set_prop.replace {
+            operator.with_replace()
+        } else {
+            operator
+        };
+        Ok(if set_prop.increment {
+            operator.with_increment()
+        } else {
+            operator
+        })
     }
 
     /// Converts a logical expression to a PropertySource.
@@ -2038,6 +3252,7 @@ pub fn convert_aggregate_function(func: LogicalAggregateFunction) -> PhysicalAgg
         LogicalAggregateFunction::Min => PhysicalAggregateFunction::Min,
         LogicalAggregateFunction::Max => PhysicalAggregateFunction::Max,
         LogicalAggregateFunction::Collect => PhysicalAggregateFunction::Collect,
+        LogicalAggregateFunction::CollectMap => PhysicalAggregateFunction::CollectMap,
         LogicalAggregateFunction::StdDev => PhysicalAggregateFunction::StdDev,
         LogicalAggregateFunction::StdDevPop => PhysicalAggregateFunction::StdDevPop,
         LogicalAggregateFunction::PercentileDisc => PhysicalAggregateFunction::PercentileDisc,
@@ -2045,6 +3260,39 @@ pub fn convert_aggregate_function(func: LogicalAggregateFunction) -> PhysicalAgg
     }
 }
 
+/// Builds a call to a user-defined function.
+///
+/// UDFs are pure, so a call whose arguments are all constants is evaluated
+/// here, once, and replaced by its result. That also surfaces argument type
+/// errors while planning rather than as NULLs at run time.
+fn fold_user_function(
+    function: &ScalarUdf,
+    args: Vec<FilterExpression>,
+) -> Result<FilterExpression> {
+    let constants = args
+        .iter()
+        .map(|arg| match arg {
+            FilterExpression::Literal(value) => Some(value.clone()),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>();
+    let Some(constants) = constants else {
+        return Ok(FilterExpression::UserFunction {
+            function: function.clone(),
+            args,
+        });
+    };
+    function
+        .call(&constants)
+        .map(FilterExpression::Literal)
+        .map_err(|err| {
+            Error::Query(QueryError::new(
+                QueryErrorKind::Semantic,
+                format!("{}(): {err}", function.name()),
+            ))
+        })
+}
+
 /// Converts a logical expression to a filter expression.
 ///
 /// This is a standalone function that can be used by both LPG and RDF planners.
@@ -2160,6 +3408,22 @@ pub fn convert_filter_expression(expr: &LogicalExpression) -> Result<FilterExpre
         LogicalExpression::Labels(var) => Ok(FilterExpression::Labels(var.clone())),
         LogicalExpression::Type(var) => Ok(FilterExpression::Type(var.clone())),
         LogicalExpression::Id(var) => Ok(FilterExpression::Id(var.clone())),
+        LogicalExpression::PathLength(var) => {
+            Ok(FilterExpression::Variable(format!("_path_length_{var}")))
+        }
+        LogicalExpression::PathCost { variable, property } => Ok(FilterExpression::PathCost {
+            variable: variable.clone(),
+            property: property.clone(),
+        }),
+        LogicalExpression::PropertyMap {
+            variable,
+            keys,
+            include_id_label,
+        } => Ok(FilterExpression::PropertyMap {
+            variable: variable.clone(),
+            keys: keys.clone(),
+            include_id_label: *include_id_label,
+        }),
         LogicalExpression::ListComprehension {
             variable,
             list_expr,
@@ -2197,13 +3461,28 @@ fn value_to_logical_type(value: &grafeo_common::types::Value) -> LogicalType {
         Value::String(_) => LogicalType::String,
         Value::Bytes(_) => LogicalType::String, // No Bytes logical type, use String
         Value::Timestamp(_) => LogicalType::Timestamp,
-        Value::List(_) => LogicalType::String, // Lists not yet supported as logical type
-        Value::Map(_) => LogicalType::String,  // Maps not yet supported as logical type
+        Value::List(_) => LogicalType::Any,
+        Value::Map(_) => LogicalType::Any,
+        Value::Point { .. } => LogicalType::Any,
+        Value::Date(_) => LogicalType::Date,
+        Value::Duration(_) => LogicalType::Duration,
+    }
+}
+
+/// Picks the output type for a returned variable.
+///
+/// Path variables bound by a variable-length expand sit next to their
+/// `_path_length_` column; everything else is reported as a node.
+fn variable_output_type(name: &str, variable_columns: &HashMap<String, usize>) -> LogicalType {
+    if variable_columns.contains_key(&format!("_path_length_{name}")) {
+        LogicalType::Path
+    } else {
+        LogicalType::Node
     }
 }
 
 /// Converts an expression to a string for column naming.
-fn expression_to_string(expr: &LogicalExpression) -> String {
+pub(crate) fn expression_to_string(expr: &LogicalExpression) -> String {
     match expr {
         LogicalExpression::Variable(name) => name.clone(),
         LogicalExpression::Property { variable, property } => {
@@ -2215,6 +3494,13 @@ fn expression_to_string(expr: &LogicalExpression) -> String {
     }
 }
 
+/// Returns where a boxed operator lives, or `None` for zero-sized operators,
+/// which don't have an address of their own.
+fn operator_address(operator: &dyn Operator) -> Option<usize> {
+    (std::mem::size_of_val(operator) > 0)
+        .then(|| std::ptr::from_ref(operator).cast::<()>() as usize)
+}
+
 /// A physical plan ready for execution.
 pub struct PhysicalPlan {
     /// The root physical operator.
@@ -2227,15 +3513,43 @@ pub struct PhysicalPlan {
     /// cardinalities at various checkpoints in the plan. During execution,
     /// actual row counts are recorded and compared against estimates.
     pub adaptive_context: Option<AdaptiveContext>,
+    /// Chunks the plan's scans skipped or scanned using zone maps.
+    pub zone_counters: Option<Arc<ZoneMapCounters>>,
+    /// The operators as the planner recorded them, for `explain()`.
+    pub(crate) planned: Option<PlannedOperator>,
 }
 
 impl PhysicalPlan {
+    /// Returns the statistics gathered so far while executing the plan.
+    #[must_use]
+    pub fn stats(&self) -> QueryStats {
+        self.zone_counters
+            .as_ref()
+            .map_or_else(QueryStats::default, |counters| QueryStats {
+                chunks_skipped: counters.skipped(),
+                chunks_scanned: counters.scanned(),
+            })
+    }
+
     /// Returns the column names.
     #[must_use]
     pub fn columns(&self) -> &[String] {
         &self.columns
     }
 
+    /// Shows the operators the plan runs, with the optimizer's estimates
+    /// for each.
+    ///
+    /// If the plan was built with [`Planner::with_profiling()`] and has
+    /// run, each operator also shows what it actually did. Returns `None`
+    /// for plans whose planner doesn't record its operators, like RDF plans.
+    #[must_use]
+    pub fn explain(&self) -> Option<PlanExplanation> {
+        self.planned.as_ref().map(|root| PlanExplanation {
+            root: root.explain(),
+        })
+    }
+
     /// Consumes the plan and returns the operator.
     pub fn into_operator(self) -> Box<dyn Operator> {
         self.operator
@@ -2288,7 +3602,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         }));
@@ -2297,6 +3611,42 @@ mod tests {
         assert_eq!(physical.columns(), &["n"]);
     }
 
+    #[test]
+    fn test_profiled_plan_explains_what_ran() {
+        let store = create_test_store();
+        let planner = Planner::new(store).with_profiling();
+
+        // MATCH (n:Person) RETURN n
+        let logical = LogicalPlan::new(LogicalOperator::Return(ReturnOp {
+            items: vec![ReturnItem {
+                expression: LogicalExpression::Variable("n".to_string()),
+                alias: None,
+            }],
+            distinct: false,
+            input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
+                variable: "n".to_string(),
+                labels: vec!["Person".to_string()],
+                input: None,
+            })),
+        }));
+
+        let mut physical = planner.plan(&logical).unwrap();
+        // Returning bare variables passes the scan's rows through as they are
+        let before = physical.explain().unwrap();
+        assert_eq!(before.operators().len(), 1);
+        assert_eq!(before.root.operator, "Scan");
+        assert_eq!(before.root.details, "n:Person");
+        assert_eq!(before.root.actual.map(|stats| stats.rows), Some(0));
+
+        while physical.operator.next().unwrap().is_some() {}
+        let after = physical.explain().unwrap();
+        assert_eq!(after.root.actual.map(|stats| stats.rows), Some(2));
+
+        // Unprofiled plans still explain, without actuals
+        let plain = Planner::new(create_test_store()).plan(&logical).unwrap();
+        assert!(plain.explain().unwrap().root.actual.is_none());
+    }
+
     #[test]
     fn test_plan_scan_without_label() {
         let store = create_test_store();
@@ -2311,7 +3661,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
         }));
@@ -2334,7 +3684,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         }));
@@ -2360,7 +3710,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
         }));
@@ -2383,7 +3733,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
         }));
@@ -2417,7 +3767,7 @@ mod tests {
                 },
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: Some("Person".to_string()),
+                    labels: vec!["Person".to_string()],
                     input: None,
                 })),
             })),
@@ -2427,6 +3777,124 @@ mod tests {
         assert_eq!(physical.columns(), &["n"]);
     }
 
+    #[test]
+    fn test_fulltext_candidates() {
+        use grafeo_core::index::Tokenizer;
+
+        let store = Arc::new(LpgStore::new());
+        let graph = store.create_node_with_props(&["Doc"], [("body", "graph databases")]);
+        store.create_node_with_props(&["Doc"], [("body", "relational tables")]);
+        let planner = Planner::new(Arc::clone(&store));
+
+        let scan = NodeScanOp {
+            variable: "d".to_string(),
+            labels: vec!["Doc".to_string()],
+            input: None,
+        };
+        let contains = |text: &str| LogicalExpression::Binary {
+            left: Box::new(LogicalExpression::Property {
+                variable: "d".to_string(),
+                property: "body".to_string(),
+            }),
+            op: BinaryOp::Contains,
+            right: Box::new(LogicalExpression::Literal(Value::from(text))),
+        };
+
+        // Without an index the scan isn't narrowed
+        assert_eq!(planner.index_candidates(&contains("graph"), &scan), None);
+
+        store.create_fulltext_index("Doc", "body", Tokenizer::new());
+        assert_eq!(
+            planner.index_candidates(&contains("ph data"), &scan),
+            Some(vec![graph])
+        );
+        let both = LogicalExpression::Binary {
+            left: Box::new(contains("graph")),
+            op: BinaryOp::And,
+            right: Box::new(contains("tables")),
+        };
+        assert_eq!(planner.index_candidates(&both, &scan), Some(vec![]));
+        assert_eq!(planner.index_candidates(&contains(" "), &scan), None);
+    }
+
+    #[test]
+    fn test_spatial_candidates() {
+        let store = Arc::new(LpgStore::new());
+        let near = store.create_node_with_props(
+            &["Place"],
+            [("loc", Value::point(1.0, 1.0, Value::CARTESIAN_SRID))],
+        );
+        store.create_node_with_props(
+            &["Place"],
+            [("loc", Value::point(10.0, 10.0, Value::CARTESIAN_SRID))],
+        );
+        let planner = Planner::new(Arc::clone(&store));
+
+        let scan = NodeScanOp {
+            variable: "p".to_string(),
+            labels: vec!["Place".to_string()],
+            input: None,
+        };
+        let point = |x: i64, y: i64| LogicalExpression::FunctionCall {
+            name: "point".to_string(),
+            args: vec![LogicalExpression::Map(vec![
+                ("x".to_string(), LogicalExpression::Literal(Value::Int64(x))),
+                ("y".to_string(), LogicalExpression::Literal(Value::Int64(y))),
+            ])],
+            distinct: false,
+        };
+        let call = |name: &str, bounds: Vec<LogicalExpression>| {
+            let mut args = vec![LogicalExpression::Property {
+                variable: "p".to_string(),
+                property: "loc".to_string(),
+            }];
+            args.extend(bounds);
+            LogicalExpression::FunctionCall {
+                name: name.to_string(),
+                args,
+                distinct: false,
+            }
+        };
+        let within = call(
+            "point.withinDistance",
+            vec![point(0, 0), LogicalExpression::Literal(Value::Int64(2))],
+        );
+
+        // Without an index the scan isn't narrowed
+        assert_eq!(planner.index_candidates(&within, &scan), None);
+
+        store.create_spatial_index("Place", "loc");
+        assert_eq!(planner.index_candidates(&within, &scan), Some(vec![near]));
+        let bbox = call("point.withinBBox", vec![point(5, 5), point(20, 20)]);
+        assert_eq!(
+            planner.index_candidates(&bbox, &scan).map(|c| c.len()),
+            Some(1)
+        );
+
+        // Bounds that depend on the row can't use the index
+        let dynamic = call(
+            "point.withinDistance",
+            vec![
+                LogicalExpression::Property {
+                    variable: "p".to_string(),
+                    property: "loc".to_string(),
+                },
+                LogicalExpression::Literal(Value::Int64(2)),
+            ],
+        );
+        assert_eq!(planner.index_candidates(&dynamic, &scan), None);
+
+        // Neither can bounds in another SRID, so the filter reports the error
+        let wgs84 = call(
+            "point.withinDistance",
+            vec![
+                LogicalExpression::Literal(Value::point(0.0, 0.0, Value::WGS84_SRID)),
+                LogicalExpression::Literal(Value::Int64(2)),
+            ],
+        );
+        assert_eq!(planner.index_candidates(&wgs84, &scan), None);
+    }
+
     #[test]
     fn test_plan_filter_compound_and() {
         let store = create_test_store();
@@ -2461,7 +3929,7 @@ mod tests {
                 },
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -2493,7 +3961,7 @@ mod tests {
                 },
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -2525,7 +3993,7 @@ mod tests {
                 },
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -2562,7 +4030,7 @@ mod tests {
                 },
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -2602,7 +4070,7 @@ mod tests {
                 max_hops: Some(1),
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "a".to_string(),
-                    label: Some("Person".to_string()),
+                    labels: vec!["Person".to_string()],
                     input: None,
                 })),
                 path_alias: None,
@@ -2647,7 +4115,7 @@ mod tests {
                 max_hops: Some(1),
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "a".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 path_alias: None,
@@ -2678,7 +4146,7 @@ mod tests {
                 count: 10,
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -2704,7 +4172,7 @@ mod tests {
                 count: 5,
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -2733,7 +4201,7 @@ mod tests {
                 }],
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -2762,7 +4230,7 @@ mod tests {
                 }],
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
             })),
@@ -2787,7 +4255,7 @@ mod tests {
             input: Box::new(LogicalOperator::Distinct(LogicalDistinctOp {
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 columns: None,
@@ -2823,7 +4291,7 @@ mod tests {
                 }],
                 input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "n".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 having: None,
@@ -2854,7 +4322,7 @@ mod tests {
             }],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: Some("Person".to_string()),
+                labels: vec!["Person".to_string()],
                 input: None,
             })),
             having: None,
@@ -2884,7 +4352,7 @@ mod tests {
             }],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
             having: None,
@@ -2914,7 +4382,7 @@ mod tests {
             }],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
             having: None,
@@ -2956,7 +4424,7 @@ mod tests {
             ],
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
             having: None,
@@ -2990,12 +4458,12 @@ mod tests {
             input: Box::new(LogicalOperator::Join(JoinOp {
                 left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "a".to_string(),
-                    label: Some("Person".to_string()),
+                    labels: vec!["Person".to_string()],
                     input: None,
                 })),
                 right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "b".to_string(),
-                    label: Some("Company".to_string()),
+                    labels: vec!["Company".to_string()],
                     input: None,
                 })),
                 join_type: JoinType::Inner,
@@ -3020,12 +4488,12 @@ mod tests {
         let logical = LogicalPlan::new(LogicalOperator::Join(JoinOp {
             left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "a".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
             right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "b".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
             join_type: JoinType::Cross,
@@ -3044,12 +4512,12 @@ mod tests {
         let logical = LogicalPlan::new(LogicalOperator::Join(JoinOp {
             left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "a".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
             right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "b".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
             join_type: JoinType::Left,
@@ -3097,12 +4565,12 @@ mod tests {
             input: Box::new(LogicalOperator::Join(JoinOp {
                 left: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "a".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 right: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                     variable: "b".to_string(),
-                    label: None,
+                    labels: Vec::new(),
                     input: None,
                 })),
                 join_type: JoinType::Cross,
@@ -3125,7 +4593,7 @@ mod tests {
             detach: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
         }));
@@ -3160,7 +4628,7 @@ mod tests {
             distinct: false,
             input: Box::new(LogicalOperator::NodeScan(NodeScanOp {
                 variable: "n".to_string(),
-                label: None,
+                labels: Vec::new(),
                 input: None,
             })),
         }));
@@ -3236,7 +4704,7 @@ mod tests {
 
         let logical = LogicalPlan::new(LogicalOperator::NodeScan(NodeScanOp {
             variable: "n".to_string(),
-            label: None,
+            labels: Vec::new(),
             input: None,
         }));
 
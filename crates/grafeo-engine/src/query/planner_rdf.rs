@@ -6,7 +6,7 @@
 //! This planner follows the same push-based, vectorized execution model as
 //! the LPG planner for consistent performance characteristics.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 
 use grafeo_common::types::{LogicalType, TxId, Value};
@@ -22,9 +22,9 @@ use grafeo_core::graph::rdf::{Literal, RdfStore, Term, Triple, TriplePattern};
 
 use crate::query::plan::{
     AggregateFunction as LogicalAggregateFunction, AggregateOp, AntiJoinOp, ClearGraphOp,
-    CreateGraphOp, DeleteTripleOp, DropGraphOp, FilterOp, InsertTripleOp, LeftJoinOp, LimitOp,
-    LogicalExpression, LogicalOperator, LogicalPlan, ModifyOp, SkipOp, SortOp, TripleComponent,
-    TripleScanOp, TripleTemplate,
+    ConstructOp, CreateGraphOp, DeleteTripleOp, DropGraphOp, FilterOp, InsertTripleOp, LeftJoinOp,
+    LimitOp, LogicalExpression, LogicalOperator, LogicalPlan, ModifyOp, SkipOp, SortOp, TopNOp,
+    TripleComponent, TripleScanOp, TripleTemplate,
 };
 use crate::query::planner::{PhysicalPlan, convert_aggregate_function, convert_filter_expression};
 
@@ -80,6 +80,8 @@ impl RdfPlanner {
             operator,
             columns,
             adaptive_context: None,
+            zone_counters: None,
+            planned: None,
         })
     }
 
@@ -92,6 +94,7 @@ impl RdfPlanner {
             LogicalOperator::Limit(limit) => self.plan_limit(limit),
             LogicalOperator::Skip(skip) => self.plan_skip(skip),
             LogicalOperator::Sort(sort) => self.plan_sort(sort),
+            LogicalOperator::TopN(top) => self.plan_top_n(top),
             LogicalOperator::Aggregate(agg) => self.plan_aggregate(agg),
             LogicalOperator::Return(ret) => self.plan_return(ret),
             LogicalOperator::Join(join) => self.plan_join(join),
@@ -102,6 +105,7 @@ impl RdfPlanner {
             LogicalOperator::InsertTriple(insert) => self.plan_insert_triple(insert),
             LogicalOperator::DeleteTriple(delete) => self.plan_delete_triple(delete),
             LogicalOperator::Modify(modify) => self.plan_modify(modify),
+            LogicalOperator::Construct(construct) => self.plan_construct(construct),
             LogicalOperator::ClearGraph(clear) => self.plan_clear_graph(clear),
             LogicalOperator::CreateGraph(create) => self.plan_create_graph(create),
             LogicalOperator::DropGraph(drop_op) => self.plan_drop_graph(drop_op),
@@ -222,35 +226,28 @@ impl RdfPlanner {
 
     /// Plans a SORT operator.
     fn plan_sort(&self, sort: &SortOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
-        use crate::query::plan::SortOrder;
-        use grafeo_core::execution::operators::{NullOrder, SortDirection, SortKey};
-
         let (input_op, columns) = self.plan_operator(&sort.input)?;
+        let physical_keys = physical_sort_keys(&sort.keys, &columns)?;
 
-        let variable_columns: HashMap<String, usize> = columns
-            .iter()
-            .enumerate()
-            .map(|(i, name)| (name.clone(), i))
-            .collect();
+        let output_schema = derive_rdf_schema(&columns);
+        let operator = Box::new(SortOperator::new(input_op, physical_keys, output_schema));
+        Ok((operator, columns))
+    }
 
-        let physical_keys: Vec<SortKey> = sort
-            .keys
-            .iter()
-            .map(|key| {
-                let col_idx = resolve_expression(&key.expression, &variable_columns)?;
-                Ok(SortKey {
-                    column: col_idx,
-                    direction: match key.order {
-                        SortOrder::Ascending => SortDirection::Ascending,
-                        SortOrder::Descending => SortDirection::Descending,
-                    },
-                    null_order: NullOrder::NullsLast,
-                })
-            })
-            .collect::<Result<Vec<_>>>()?;
+    /// Plans a TOP-N operator, the optimizer's fusion of ORDER BY and LIMIT.
+    fn plan_top_n(&self, top: &TopNOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        use grafeo_core::execution::operators::TopNOperator;
+
+        let (input_op, columns) = self.plan_operator(&top.input)?;
+        let physical_keys = physical_sort_keys(&top.keys, &columns)?;
 
         let output_schema = derive_rdf_schema(&columns);
-        let operator = Box::new(SortOperator::new(input_op, physical_keys, output_schema));
+        let operator = Box::new(TopNOperator::new(
+            input_op,
+            physical_keys,
+            top.count,
+            output_schema,
+        ));
         Ok((operator, columns))
     }
 
@@ -282,7 +279,8 @@ impl RdfPlanner {
                     if let Some(&col_idx) = variable_columns.get(name) {
                         projections.push(ProjectExpr::Column(col_idx));
                         output_columns.push(proj.alias.clone().unwrap_or_else(|| name.clone()));
-                        output_types.push(LogicalType::String); // RDF values are strings
+                        // Terms are strings, but aggregate columns can be numbers
+                        output_types.push(LogicalType::Any);
                     } else {
                         return Err(Error::Internal(format!(
                             "Variable '{}' not found in input columns",
@@ -784,6 +782,31 @@ impl RdfPlanner {
 
         Ok((operator, Vec::new()))
     }
+
+    /// Plans a SPARQL CONSTRUCT operator.
+    fn plan_construct(&self, construct: &ConstructOp) -> Result<(Box<dyn Operator>, Vec<String>)> {
+        let (input, input_columns) = self.plan_operator(&construct.input)?;
+
+        let column_map: HashMap<String, usize> = input_columns
+            .iter()
+            .enumerate()
+            .map(|(i, name)| (name.clone(), i))
+            .collect();
+
+        let operator = Box::new(RdfConstructOperator::new(
+            input,
+            construct.templates.clone(),
+            column_map,
+            self.chunk_size,
+        ));
+
+        let columns = vec![
+            "subject".to_string(),
+            "predicate".to_string(),
+            "object".to_string(),
+        ];
+        Ok((operator, columns))
+    }
 }
 
 // ============================================================================
@@ -1422,6 +1445,148 @@ impl Operator for RdfModifyOperator {
     }
 }
 
+// ============================================================================
+// RDF Construct Operator
+// ============================================================================
+
+/// Operator that builds triples from CONSTRUCT templates.
+///
+/// Each input row instantiates every template. Templates with an unbound
+/// variable are skipped for that row, blank nodes get a fresh identifier per
+/// row, and triples already produced are not emitted again.
+struct RdfConstructOperator {
+    input: Box<dyn Operator>,
+    templates: Vec<TripleTemplate>,
+    column_map: HashMap<String, usize>,
+    chunk_size: usize,
+    /// Triples built but not yet emitted.
+    pending: VecDeque<Triple>,
+    /// Every triple emitted so far, for deduplication.
+    seen: HashSet<Triple>,
+    /// Counter for minting blank node identifiers.
+    next_blank: u64,
+    input_done: bool,
+}
+
+impl RdfConstructOperator {
+    fn new(
+        input: Box<dyn Operator>,
+        templates: Vec<TripleTemplate>,
+        column_map: HashMap<String, usize>,
+        chunk_size: usize,
+    ) -> Self {
+        Self {
+            input,
+            templates,
+            column_map,
+            chunk_size,
+            pending: VecDeque::new(),
+            seen: HashSet::new(),
+            next_blank: 0,
+            input_done: false,
+        }
+    }
+
+    fn resolve_component(
+        &mut self,
+        component: &TripleComponent,
+        chunk: &DataChunk,
+        row: usize,
+        blanks: &mut HashMap<String, Term>,
+    ) -> Option<Term> {
+        let TripleComponent::Variable(name) = component else {
+            return component_to_term(component);
+        };
+
+        // Blank nodes are scoped to a single solution
+        if let Some(label) = name.strip_prefix("_:") {
+            let term = blanks.entry(label.to_string()).or_insert_with(|| {
+                let id = self.next_blank;
+                self.next_blank += 1;
+                Term::blank(format!("b{id}"))
+            });
+            return Some(term.clone());
+        }
+
+        let var_name = name.strip_prefix('?').unwrap_or(name);
+        let col_idx = *self.column_map.get(var_name)?;
+        let value = chunk.column(col_idx)?.get_value(row)?;
+        RdfModifyOperator::value_to_term(&value)
+    }
+
+    /// Instantiates every template for one row of bindings.
+    fn instantiate_row(&mut self, chunk: &DataChunk, row: usize) {
+        let templates = std::mem::take(&mut self.templates);
+        let mut blanks = HashMap::new();
+        for template in &templates {
+            let subject = self.resolve_component(&template.subject, chunk, row, &mut blanks);
+            let predicate = self.resolve_component(&template.predicate, chunk, row, &mut blanks);
+            let object = self.resolve_component(&template.object, chunk, row, &mut blanks);
+
+            if let (Some(s), Some(p), Some(o)) = (subject, predicate, object) {
+                let triple = Triple::new(s, p, o);
+                if self.seen.insert(triple.clone()) {
+                    self.pending.push_back(triple);
+                }
+            }
+        }
+        self.templates = templates;
+    }
+}
+
+impl Operator for RdfConstructOperator {
+    fn next(&mut self) -> std::result::Result<Option<DataChunk>, OperatorError> {
+        while self.pending.len() < self.chunk_size && !self.input_done {
+            match self.input.next()? {
+                Some(chunk) => {
+                    for row in chunk.selected_indices() {
+                        self.instantiate_row(&chunk, row);
+                    }
+                }
+                None => self.input_done = true,
+            }
+        }
+
+        if self.pending.is_empty() {
+            return Ok(None);
+        }
+
+        let batch_size = self.pending.len().min(self.chunk_size);
+        let schema = [
+            LogicalType::String,
+            LogicalType::String,
+            LogicalType::String,
+        ];
+        let mut chunk = DataChunk::with_capacity(&schema, batch_size);
+        for triple in self.pending.drain(..batch_size) {
+            if let Some(col) = chunk.column_mut(0) {
+                col.push_string(term_to_string(triple.subject()));
+            }
+            if let Some(col) = chunk.column_mut(1) {
+                col.push_string(term_to_string(triple.predicate()));
+            }
+            if let Some(col) = chunk.column_mut(2) {
+                push_term_value(col, triple.object());
+            }
+        }
+        chunk.set_count(batch_size);
+
+        Ok(Some(chunk))
+    }
+
+    fn reset(&mut self) {
+        self.input.reset();
+        self.pending.clear();
+        self.seen.clear();
+        self.next_blank = 0;
+        self.input_done = false;
+    }
+
+    fn name(&self) -> &'static str {
+        "RdfConstruct"
+    }
+}
+
 // ============================================================================
 // RDF Union Operator
 // ============================================================================
@@ -1643,13 +1808,17 @@ impl RdfExpressionPredicate {
             }
             // These expression types are not commonly used in RDF FILTER clauses
             FilterExpression::FunctionCall { .. }
+            | FilterExpression::UserFunction { .. }
             | FilterExpression::List(_)
             | FilterExpression::Case { .. }
             | FilterExpression::Map(_)
             | FilterExpression::IndexAccess { .. }
             | FilterExpression::SliceAccess { .. }
             | FilterExpression::ListComprehension { .. }
-            | FilterExpression::ExistsSubquery { .. } => None,
+            | FilterExpression::PathCost { .. }
+            | FilterExpression::PropertyMap { .. }
+            | FilterExpression::ExistsSubquery { .. }
+            | FilterExpression::CountSubquery { .. } => None,
         }
     }
 
@@ -1922,6 +2091,37 @@ fn component_to_term(component: &TripleComponent) -> Option<Term> {
     }
 }
 
+/// Converts logical sort keys to physical ones over the given columns.
+fn physical_sort_keys(
+    keys: &[crate::query::plan::SortKey],
+    columns: &[String],
+) -> Result<Vec<grafeo_core::execution::operators::SortKey>> {
+    use crate::query::plan::SortOrder;
+    use grafeo_core::execution::Collation;
+    use grafeo_core::execution::operators::{NullOrder, SortDirection, SortKey};
+
+    let variable_columns: HashMap<String, usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(i, name)| (name.clone(), i))
+        .collect();
+
+    keys.iter()
+        .map(|key| {
+            let col_idx = resolve_expression(&key.expression, &variable_columns)?;
+            Ok(SortKey {
+                column: col_idx,
+                direction: match key.order {
+                    SortOrder::Ascending => SortDirection::Ascending,
+                    SortOrder::Descending => SortDirection::Descending,
+                },
+                null_order: NullOrder::NullsLast,
+                collation: Collation::Binary,
+            })
+        })
+        .collect()
+}
+
 /// Derives RDF schema (all String type for simplicity).
 fn derive_rdf_schema(columns: &[String]) -> Vec<LogicalType> {
     columns.iter().map(|_| LogicalType::String).collect()
@@ -6,14 +6,20 @@
 //! It supports multiple query languages (GQL, Cypher, Gremlin, GraphQL) for LPG
 //! and SPARQL for RDF (when the `rdf` feature is enabled).
 
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
-use grafeo_common::types::{EpochId, TxId, Value};
-use grafeo_common::utils::error::{Error, Result};
+use grafeo_common::memory::buffer::BufferManager;
+use grafeo_common::types::{EpochId, Timestamp, TxId, Value};
+use grafeo_common::utils::error::{Error, QueryError, QueryErrorKind, Result};
+use grafeo_core::execution::operators::ScalarUdf;
+use grafeo_core::execution::{Collation, SpillableOperator};
 use grafeo_core::graph::lpg::LpgStore;
 
 use crate::catalog::Catalog;
+use crate::config::{DuplicateKeyPolicy, QueryLimits};
 use crate::database::QueryResult;
 use crate::query::binder::Binder;
 use crate::query::executor::Executor;
@@ -93,6 +99,24 @@ pub struct QueryProcessor {
     optimizer: Optimizer,
     /// Current transaction context (if any).
     tx_context: Option<(EpochId, TxId)>,
+    /// Collation for string ordering.
+    collation: Collation,
+    /// Whether writes must use names declared in the catalog.
+    strict_schema: bool,
+    /// What a write that repeats a property key does.
+    duplicate_keys: DuplicateKeyPolicy,
+    /// Memory budget for query operators, and its accounting granularity.
+    memory_budget: Option<(Arc<BufferManager>, usize)>,
+    /// Spill directory overriding the budget's spill path.
+    spill_path: Option<PathBuf>,
+    /// Operator kinds that fail instead of spilling.
+    spill_disabled: Vec<SpillableOperator>,
+    /// User-defined scalar functions, keyed by lowercase name.
+    udfs: Arc<HashMap<String, ScalarUdf>>,
+    /// Limits that reject overly complex queries.
+    limits: QueryLimits,
+    /// Only traverse edges valid at this time, if set.
+    as_of: Option<Timestamp>,
     /// RDF store for triple pattern queries (optional).
     #[cfg(feature = "rdf")]
     rdf_store: Option<Arc<grafeo_core::graph::rdf::RdfStore>>,
@@ -108,6 +132,15 @@ impl QueryProcessor {
             catalog: Arc::new(Catalog::new()),
             optimizer: Optimizer::new(),
             tx_context: None,
+            collation: Collation::Binary,
+            strict_schema: false,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            memory_budget: None,
+            spill_path: None,
+            spill_disabled: Vec::new(),
+            udfs: Arc::new(HashMap::new()),
+            limits: QueryLimits::default(),
+            as_of: None,
             #[cfg(feature = "rdf")]
             rdf_store: None,
         }
@@ -122,6 +155,15 @@ impl QueryProcessor {
             catalog: Arc::new(Catalog::new()),
             optimizer: Optimizer::new(),
             tx_context: None,
+            collation: Collation::Binary,
+            strict_schema: false,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            memory_budget: None,
+            spill_path: None,
+            spill_disabled: Vec::new(),
+            udfs: Arc::new(HashMap::new()),
+            limits: QueryLimits::default(),
+            as_of: None,
             #[cfg(feature = "rdf")]
             rdf_store: None,
         }
@@ -140,6 +182,15 @@ impl QueryProcessor {
             catalog: Arc::new(Catalog::new()),
             optimizer: Optimizer::new(),
             tx_context: None,
+            collation: Collation::Binary,
+            strict_schema: false,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            memory_budget: None,
+            spill_path: None,
+            spill_disabled: Vec::new(),
+            udfs: Arc::new(HashMap::new()),
+            limits: QueryLimits::default(),
+            as_of: None,
             rdf_store: Some(rdf_store),
         }
     }
@@ -153,6 +204,73 @@ impl QueryProcessor {
         self
     }
 
+    /// Sets the collation used for string ordering in sorts and range predicates.
+    #[must_use]
+    pub fn with_collation(mut self, collation: Collation) -> Self {
+        self.collation = collation;
+        self
+    }
+
+    /// Enables strict schema validation against the processor's catalog.
+    #[must_use]
+    pub fn with_strict_schema(mut self) -> Self {
+        self.strict_schema = true;
+        self
+    }
+
+    /// Charges query operators to a memory budget, accounted every
+    /// `granularity` bytes.
+    #[must_use]
+    pub fn with_memory_budget(mut self, manager: Arc<BufferManager>, granularity: usize) -> Self {
+        self.memory_budget = Some((manager, granularity));
+        self
+    }
+
+    /// Spills query operators to `path` rather than the memory budget's
+    /// spill path.
+    #[must_use]
+    pub fn with_spill_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.spill_path = Some(path.into());
+        self
+    }
+
+    /// Makes `operator` fail with a memory limit error instead of spilling
+    /// once it outgrows the memory budget.
+    #[must_use]
+    pub fn with_spill_disabled(mut self, operator: SpillableOperator) -> Self {
+        self.spill_disabled.push(operator);
+        self
+    }
+
+    /// Sets what a write that repeats a property key does.
+    #[must_use]
+    pub fn with_duplicate_keys(mut self, policy: DuplicateKeyPolicy) -> Self {
+        self.duplicate_keys = policy;
+        self
+    }
+
+    /// Makes user-defined scalar functions, keyed by lowercase name,
+    /// callable from queries.
+    #[must_use]
+    pub fn with_udfs(mut self, udfs: Arc<HashMap<String, ScalarUdf>>) -> Self {
+        self.udfs = udfs;
+        self
+    }
+
+    /// Makes traversals only follow edges valid at the given time.
+    #[must_use]
+    pub fn with_as_of(mut self, at: Timestamp) -> Self {
+        self.as_of = Some(at);
+        self
+    }
+
+    /// Sets the limits that reject overly complex queries.
+    #[must_use]
+    pub fn with_limits(mut self, limits: QueryLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Sets a custom catalog.
     #[must_use]
     pub fn with_catalog(mut self, catalog: Arc<Catalog>) -> Self {
@@ -216,7 +334,7 @@ impl QueryProcessor {
         params: Option<&QueryParams>,
     ) -> Result<QueryResult> {
         // 1. Parse and translate to logical plan
-        let mut logical_plan = self.translate_lpg(query, language)?;
+        let mut logical_plan = translate_lpg(query, language, &self.limits)?;
 
         // 2. Substitute parameters if provided
         if let Some(params) = params {
@@ -245,43 +363,41 @@ impl QueryProcessor {
                 None,
                 self.tx_manager.current_epoch(),
             )
+        }
+        .with_collation(self.collation)
+        .with_catalog(Arc::clone(&self.catalog))
+        .with_duplicate_keys(self.duplicate_keys)
+        .with_udfs(Arc::clone(&self.udfs));
+        let planner = if self.strict_schema {
+            planner.with_strict_schema()
+        } else {
+            planner
+        };
+        let planner = match &self.memory_budget {
+            Some((manager, granularity)) => {
+                planner.with_memory_budget(Arc::clone(manager), *granularity)
+            }
+            None => planner,
+        };
+        let planner = match &self.spill_path {
+            Some(path) => planner.with_spill_path(path.clone()),
+            None => planner,
+        };
+        let planner = self
+            .spill_disabled
+            .iter()
+            .fold(planner, |planner, &operator| {
+                planner.with_spill_disabled(operator)
+            });
+        let planner = match self.as_of {
+            Some(at) => planner.with_as_of(at),
+            None => planner,
         };
         let mut physical_plan = planner.plan(&optimized_plan)?;
 
         // 6. Execute and collect results
         let executor = Executor::with_columns(physical_plan.columns.clone());
-        executor.execute(physical_plan.operator.as_mut())
-    }
-
-    /// Translates an LPG query to a logical plan.
-    fn translate_lpg(&self, query: &str, language: QueryLanguage) -> Result<LogicalPlan> {
-        match language {
-            #[cfg(feature = "gql")]
-            QueryLanguage::Gql => {
-                use crate::query::gql_translator;
-                gql_translator::translate(query)
-            }
-            #[cfg(feature = "cypher")]
-            QueryLanguage::Cypher => {
-                use crate::query::cypher_translator;
-                cypher_translator::translate(query)
-            }
-            #[cfg(feature = "gremlin")]
-            QueryLanguage::Gremlin => {
-                use crate::query::gremlin_translator;
-                gremlin_translator::translate(query)
-            }
-            #[cfg(feature = "graphql")]
-            QueryLanguage::GraphQL => {
-                use crate::query::graphql_translator;
-                graphql_translator::translate(query)
-            }
-            #[allow(unreachable_patterns)]
-            _ => Err(Error::Internal(format!(
-                "Language {:?} is not an LPG language",
-                language
-            ))),
-        }
+        executor.execute_plan(&mut physical_plan)
     }
 
     /// Processes an RDF query (SPARQL, GraphQL-RDF).
@@ -314,7 +430,7 @@ impl QueryProcessor {
 
         // 5. Execute and collect results
         let executor = Executor::with_columns(physical_plan.columns.clone());
-        executor.execute(physical_plan.operator.as_mut())
+        executor.execute_plan(&mut physical_plan)
     }
 
     /// Translates an RDF query to a logical plan.
@@ -401,13 +517,89 @@ impl Default for QueryProcessor {
     }
 }
 
+/// Translates an LPG query to a logical plan within `limits`.
+pub(crate) fn translate_lpg(
+    query: &str,
+    language: QueryLanguage,
+    limits: &QueryLimits,
+) -> Result<LogicalPlan> {
+    let plan = match language {
+        #[cfg(feature = "gql")]
+        QueryLanguage::Gql => {
+            use crate::query::gql_translator;
+            gql_translator::translate_with_max_depth(query, limits.max_depth)
+        }
+        #[cfg(feature = "cypher")]
+        QueryLanguage::Cypher => {
+            use crate::query::cypher_translator;
+            cypher_translator::translate_with_max_depth(query, limits.max_depth)
+        }
+        #[cfg(feature = "gremlin")]
+        QueryLanguage::Gremlin => {
+            use crate::query::gremlin_translator;
+            gremlin_translator::translate(query)
+        }
+        #[cfg(feature = "graphql")]
+        QueryLanguage::GraphQL => {
+            use crate::query::graphql_translator;
+            graphql_translator::translate(query)
+        }
+        #[allow(unreachable_patterns)]
+        _ => Err(Error::Internal(format!(
+            "Language {:?} is not an LPG language",
+            language
+        ))),
+    }?;
+    limits.check_plan(&plan)?;
+    Ok(plan)
+}
+
 /// Substitutes parameters in a logical plan with their values.
-fn substitute_params(plan: &mut LogicalPlan, params: &QueryParams) -> Result<()> {
-    substitute_in_operator(&mut plan.root, params)
+///
+/// Every `$name` the plan references must be bound, and every binding must be
+/// referenced - an unused one is almost always a typo in its name.
+pub(crate) fn substitute_params(plan: &mut LogicalPlan, params: &QueryParams) -> Result<()> {
+    let bindings = Bindings::new(params);
+    substitute_in_operator(&mut plan.root, &bindings)?;
+
+    let used = bindings.used.into_inner();
+    let mut unused: Vec<&String> = params.keys().filter(|name| !used.contains(*name)).collect();
+    unused.sort();
+    if let Some(name) = unused.first() {
+        return Err(Error::Query(
+            QueryError::new(
+                QueryErrorKind::Semantic,
+                format!("Unused parameter: ${name} is bound but the query doesn't reference it"),
+            )
+            .with_hint("Remove the binding or check the parameter name for typos"),
+        ));
+    }
+    Ok(())
+}
+
+/// Parameter values, recording which ones the plan looks up.
+struct Bindings<'a> {
+    params: &'a QueryParams,
+    used: RefCell<HashSet<String>>,
+}
+
+impl<'a> Bindings<'a> {
+    fn new(params: &'a QueryParams) -> Self {
+        Self {
+            params,
+            used: RefCell::new(HashSet::new()),
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&'a Value> {
+        let value = self.params.get(name)?;
+        self.used.borrow_mut().insert(name.to_string());
+        Some(value)
+    }
 }
 
 /// Recursively substitutes parameters in an operator.
-fn substitute_in_operator(op: &mut LogicalOperator, params: &QueryParams) -> Result<()> {
+fn substitute_in_operator(op: &mut LogicalOperator, params: &Bindings<'_>) -> Result<()> {
     use crate::query::plan::*;
 
     match op {
@@ -472,6 +664,12 @@ fn substitute_in_operator(op: &mut LogicalOperator, params: &QueryParams) -> Res
             }
             substitute_in_operator(&mut sort.input, params)?;
         }
+        LogicalOperator::TopN(top) => {
+            for key in &mut top.keys {
+                substitute_in_expression(&mut key.expression, params)?;
+            }
+            substitute_in_operator(&mut top.input, params)?;
+        }
         LogicalOperator::Limit(limit) => {
             substitute_in_operator(&mut limit.input, params)?;
         }
@@ -529,15 +727,24 @@ fn substitute_in_operator(op: &mut LogicalOperator, params: &QueryParams) -> Res
             substitute_in_expression(&mut unwind.expression, params)?;
             substitute_in_operator(&mut unwind.input, params)?;
         }
+        LogicalOperator::ForEach(foreach) => {
+            substitute_in_expression(&mut foreach.list, params)?;
+            substitute_in_operator(&mut foreach.body, params)?;
+            substitute_in_operator(&mut foreach.input, params)?;
+        }
         LogicalOperator::Merge(merge) => {
             for (_, expr) in &mut merge.match_properties {
                 substitute_in_expression(expr, params)?;
             }
-            for (_, expr) in &mut merge.on_create {
-                substitute_in_expression(expr, params)?;
+            if let Some(rel) = &mut merge.relationship {
+                for (_, expr) in rel.properties.iter_mut().chain(&mut rel.target_properties) {
+                    substitute_in_expression(expr, params)?;
+                }
             }
-            for (_, expr) in &mut merge.on_match {
-                substitute_in_expression(expr, params)?;
+            for set in merge.on_create.iter_mut().chain(&mut merge.on_match) {
+                for (_, expr) in &mut set.properties {
+                    substitute_in_expression(expr, params)?;
+                }
             }
             substitute_in_operator(&mut merge.input, params)?;
         }
@@ -564,6 +771,9 @@ fn substitute_in_operator(op: &mut LogicalOperator, params: &QueryParams) -> Res
         LogicalOperator::Modify(modify) => {
             substitute_in_operator(&mut modify.where_clause, params)?;
         }
+        LogicalOperator::Construct(construct) => {
+            substitute_in_operator(&mut construct.input, params)?;
+        }
         LogicalOperator::ClearGraph(_)
         | LogicalOperator::CreateGraph(_)
         | LogicalOperator::DropGraph(_)
@@ -577,7 +787,7 @@ fn substitute_in_operator(op: &mut LogicalOperator, params: &QueryParams) -> Res
 }
 
 /// Substitutes parameters in an expression with their values.
-fn substitute_in_expression(expr: &mut LogicalExpression, params: &QueryParams) -> Result<()> {
+fn substitute_in_expression(expr: &mut LogicalExpression, params: &Bindings<'_>) -> Result<()> {
     use crate::query::plan::LogicalExpression;
 
     match expr {
@@ -585,7 +795,13 @@ fn substitute_in_expression(expr: &mut LogicalExpression, params: &QueryParams)
             if let Some(value) = params.get(name) {
                 *expr = LogicalExpression::Literal(value.clone());
             } else {
-                return Err(Error::Internal(format!("Missing parameter: ${}", name)));
+                return Err(Error::Query(
+                    QueryError::new(
+                        QueryErrorKind::Semantic,
+                        format!("Missing parameter: ${name} is referenced but not bound"),
+                    )
+                    .with_hint(format!("Bind a value for '{name}' when running the query")),
+                ));
             }
         }
         LogicalExpression::Binary { left, right, .. } => {
@@ -644,7 +860,10 @@ fn substitute_in_expression(expr: &mut LogicalExpression, params: &QueryParams)
         | LogicalExpression::Literal(_)
         | LogicalExpression::Labels(_)
         | LogicalExpression::Type(_)
-        | LogicalExpression::Id(_) => {}
+        | LogicalExpression::Id(_)
+        | LogicalExpression::PathLength(_)
+        | LogicalExpression::PathCost { .. }
+        | LogicalExpression::PropertyMap { .. } => {}
         LogicalExpression::ListComprehension {
             list_expr,
             filter_expr,
@@ -657,8 +876,9 @@ fn substitute_in_expression(expr: &mut LogicalExpression, params: &QueryParams)
             }
             substitute_in_expression(map_expr, params)?;
         }
-        LogicalExpression::ExistsSubquery(_) | LogicalExpression::CountSubquery(_) => {
-            // Subqueries would need recursive parameter substitution
+        LogicalExpression::ExistsSubquery(subquery)
+        | LogicalExpression::CountSubquery(subquery) => {
+            substitute_in_operator(subquery, params)?;
         }
     }
     Ok(())
@@ -766,4 +986,82 @@ mod tests {
             err
         );
     }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_string_and_list_params() {
+        let store = Arc::new(LpgStore::new());
+        for name in ["Alice", "Bob", "Carol"] {
+            store.create_node_with_props(&["Person"], [("name", Value::from(name))]);
+        }
+        let processor = QueryProcessor::for_lpg(store);
+
+        let params = HashMap::from([("name".to_string(), Value::from("Bob"))]);
+        let result = processor
+            .process(
+                "MATCH (n:Person) WHERE n.name = $name RETURN n.name",
+                QueryLanguage::Gql,
+                Some(&params),
+            )
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::from("Bob")]]);
+
+        let tags = Value::List(vec![Value::from("a"), Value::from("b")].into());
+        let params = HashMap::from([
+            ("name".to_string(), Value::from("Carol")),
+            ("tags".to_string(), tags.clone()),
+        ]);
+        let result = processor
+            .process(
+                "MATCH (n:Person) WHERE n.name = $name RETURN n.name, $tags",
+                QueryLanguage::Gql,
+                Some(&params),
+            )
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::from("Carol"), tags]]);
+    }
+
+    #[cfg(feature = "cypher")]
+    #[test]
+    fn test_cypher_params() {
+        let store = Arc::new(LpgStore::new());
+        store.create_node_with_props(&["Person"], [("age", Value::Int64(25))]);
+        store.create_node_with_props(&["Person"], [("age", Value::Int64(35))]);
+        let processor = QueryProcessor::for_lpg(store);
+
+        let params = HashMap::from([("min_age".to_string(), Value::Int64(30))]);
+        let result = processor
+            .process(
+                "MATCH (n:Person) WHERE n.age > $min_age RETURN n",
+                QueryLanguage::Cypher,
+                Some(&params),
+            )
+            .unwrap();
+        assert_eq!(result.row_count(), 1);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_unused_param_error() {
+        let store = Arc::new(LpgStore::new());
+        store.create_node(&["Person"]);
+        let processor = QueryProcessor::for_lpg(store);
+
+        let params = HashMap::from([
+            ("min_age".to_string(), Value::Int64(30)),
+            ("min_agee".to_string(), Value::Int64(30)),
+        ]);
+        let err = processor
+            .process(
+                "MATCH (n:Person) WHERE n.age > $min_age RETURN n",
+                QueryLanguage::Gql,
+                Some(&params),
+            )
+            .unwrap_err();
+
+        assert!(
+            err.to_string().contains("Unused parameter: $min_agee"),
+            "Expected 'Unused parameter' error, got: {err}"
+        );
+    }
 }
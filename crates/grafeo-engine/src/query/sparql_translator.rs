@@ -4,10 +4,11 @@
 
 use crate::query::plan::{
     AddGraphOp, AggregateExpr, AggregateFunction, AggregateOp, AntiJoinOp, BinaryOp, BindOp,
-    ClearGraphOp, CopyGraphOp, CreateGraphOp, DeleteTripleOp, DistinctOp, DropGraphOp, FilterOp,
-    InsertTripleOp, JoinOp, JoinType, LeftJoinOp, LimitOp, LoadGraphOp, LogicalExpression,
-    LogicalOperator, LogicalPlan, ModifyOp, MoveGraphOp, ProjectOp, Projection, SkipOp, SortKey,
-    SortOp, SortOrder, TripleComponent, TripleScanOp, TripleTemplate, UnaryOp, UnionOp,
+    ClearGraphOp, ConstructOp, CopyGraphOp, CreateGraphOp, DeleteTripleOp, DistinctOp, DropGraphOp,
+    ExpandDirection, ExpandOp, FilterOp, InsertTripleOp, JoinOp, JoinType, LeftJoinOp, LimitOp,
+    LoadGraphOp, LogicalExpression, LogicalOperator, LogicalPlan, ModifyOp, MoveGraphOp, ProjectOp,
+    Projection, SkipOp, SortKey, SortOp, SortOrder, TripleComponent, TripleScanOp, TripleTemplate,
+    UnaryOp, UnionOp,
 };
 use grafeo_adapters::query::sparql::{self, ast};
 use grafeo_common::types::Value;
@@ -33,6 +34,9 @@ struct SparqlTranslator {
     base: Option<String>,
     /// Counter for generating anonymous variables.
     anon_counter: u32,
+    /// While a HAVING clause is translated, the aggregates it may reference
+    /// and the output columns holding them.
+    having_scope: Option<Vec<(ast::Expression, String)>>,
 }
 
 impl SparqlTranslator {
@@ -41,6 +45,7 @@ impl SparqlTranslator {
             prefixes: HashMap::new(),
             base: None,
             anon_counter: 0,
+            having_scope: None,
         }
     }
 
@@ -74,7 +79,8 @@ impl SparqlTranslator {
 
         // Apply GROUP BY if present, OR create aggregate for implicit aggregation
         if has_aggregates || select.solution_modifiers.group_by.is_some() {
-            let (aggregates, _group_exprs) = self.extract_aggregates_for_select(select)?;
+            let (mut aggregates, _group_exprs) = self.extract_aggregates_for_select(select)?;
+            let selected_aggregates = aggregates.len();
 
             // Get explicit GROUP BY expressions, or empty vec for whole-dataset aggregation
             let group_by_exprs = if let Some(group_by) = &select.solution_modifiers.group_by {
@@ -87,6 +93,31 @@ impl SparqlTranslator {
                 Vec::new()
             };
 
+            // HAVING filters the grouped rows, so its aggregates must be
+            // computed by the Aggregate below and referenced by alias
+            let having = match &select.solution_modifiers.having {
+                Some(having) => Some(self.translate_having(having, select, &mut aggregates)?),
+                None => None,
+            };
+
+            // Aggregates only HAVING uses are dropped again after filtering
+            let output = (aggregates.len() > selected_aggregates).then(|| {
+                group_by_exprs
+                    .iter()
+                    .cloned()
+                    .chain(
+                        aggregates[..selected_aggregates]
+                            .iter()
+                            .filter_map(|agg| agg.alias.clone())
+                            .map(LogicalExpression::Variable),
+                    )
+                    .map(|expression| Projection {
+                        expression,
+                        alias: None,
+                    })
+                    .collect::<Vec<_>>()
+            });
+
             plan = LogicalOperator::Aggregate(AggregateOp {
                 group_by: group_by_exprs,
                 aggregates,
@@ -94,17 +125,53 @@ impl SparqlTranslator {
                 having: None, // SPARQL HAVING handled as separate Filter below
             });
 
-            // Apply HAVING if present
-            if let Some(having) = &select.solution_modifiers.having {
+            if let Some(predicate) = having {
                 plan = LogicalOperator::Filter(FilterOp {
-                    predicate: self.translate_expression(having)?,
+                    predicate,
+                    input: Box::new(plan),
+                });
+            }
+            if let Some(projections) = output {
+                plan = LogicalOperator::Project(ProjectOp {
+                    projections,
                     input: Box::new(plan),
                 });
             }
         }
 
+        plan = self.apply_order_offset_limit(plan, &select.solution_modifiers)?;
+
+        // Apply DISTINCT/REDUCED
+        if select.modifier == ast::SelectModifier::Distinct {
+            plan = LogicalOperator::Distinct(DistinctOp {
+                input: Box::new(plan),
+                columns: None,
+            });
+        }
+
+        // Apply projection (but NOT for aggregate queries - aggregate already produces correct columns)
+        // For aggregate queries, the AggregateOp outputs columns with proper aliases
+        if !has_aggregates {
+            let projections = self.translate_projection(&select.projection)?;
+            if !projections.is_empty() {
+                plan = LogicalOperator::Project(ProjectOp {
+                    projections,
+                    input: Box::new(plan),
+                });
+            }
+        }
+
+        Ok(LogicalPlan::new(plan))
+    }
+
+    /// Applies ORDER BY, OFFSET and LIMIT, in that order.
+    fn apply_order_offset_limit(
+        &mut self,
+        mut plan: LogicalOperator,
+        modifiers: &ast::SolutionModifiers,
+    ) -> Result<LogicalOperator> {
         // Apply ORDER BY
-        if let Some(order_by) = &select.solution_modifiers.order_by {
+        if let Some(order_by) = &modifiers.order_by {
             let keys = order_by
                 .iter()
                 .map(|oc| {
@@ -125,7 +192,7 @@ impl SparqlTranslator {
         }
 
         // Apply OFFSET
-        if let Some(offset) = select.solution_modifiers.offset {
+        if let Some(offset) = modifiers.offset {
             plan = LogicalOperator::Skip(SkipOp {
                 count: offset as usize,
                 input: Box::new(plan),
@@ -133,34 +200,14 @@ impl SparqlTranslator {
         }
 
         // Apply LIMIT
-        if let Some(limit) = select.solution_modifiers.limit {
+        if let Some(limit) = modifiers.limit {
             plan = LogicalOperator::Limit(LimitOp {
                 count: limit as usize,
                 input: Box::new(plan),
             });
         }
 
-        // Apply DISTINCT/REDUCED
-        if select.modifier == ast::SelectModifier::Distinct {
-            plan = LogicalOperator::Distinct(DistinctOp {
-                input: Box::new(plan),
-                columns: None,
-            });
-        }
-
-        // Apply projection (but NOT for aggregate queries - aggregate already produces correct columns)
-        // For aggregate queries, the AggregateOp outputs columns with proper aliases
-        if !has_aggregates {
-            let projections = self.translate_projection(&select.projection)?;
-            if !projections.is_empty() {
-                plan = LogicalOperator::Project(ProjectOp {
-                    projections,
-                    input: Box::new(plan),
-                });
-            }
-        }
-
-        Ok(LogicalPlan::new(plan))
+        Ok(plan)
     }
 
     fn translate_ask(&mut self, ask: &ast::AskQuery) -> Result<LogicalPlan> {
@@ -177,21 +224,27 @@ impl SparqlTranslator {
     }
 
     fn translate_construct(&mut self, construct: &ast::ConstructQuery) -> Result<LogicalPlan> {
-        // For CONSTRUCT, we need to evaluate the WHERE pattern and then
-        // produce triples according to the template
+        // The modifiers shape the solutions the template is applied to
         let plan = self.translate_graph_pattern(&construct.where_clause)?;
+        let plan = self.apply_order_offset_limit(plan, &construct.solution_modifiers)?;
+
+        let templates = construct
+            .template
+            .iter()
+            .map(|triple| {
+                Ok(TripleTemplate {
+                    subject: self.translate_triple_term(&triple.subject)?,
+                    predicate: self.translate_property_path(&triple.predicate)?,
+                    object: self.translate_triple_term(&triple.object)?,
+                    graph: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        // Apply solution modifiers
-        let mut plan = plan;
-        if let Some(limit) = construct.solution_modifiers.limit {
-            plan = LogicalOperator::Limit(LimitOp {
-                count: limit as usize,
-                input: Box::new(plan),
-            });
-        }
-
-        // The template will be processed at execution time
-        Ok(LogicalPlan::new(plan))
+        Ok(LogicalPlan::new(LogicalOperator::Construct(ConstructOp {
+            templates,
+            input: Box::new(plan),
+        })))
     }
 
     fn translate_describe(&mut self, describe: &ast::DescribeQuery) -> Result<LogicalPlan> {
@@ -706,6 +759,15 @@ impl SparqlTranslator {
     }
 
     fn translate_triple_pattern(&mut self, triple: &ast::TriplePattern) -> Result<LogicalOperator> {
+        if !matches!(
+            triple.predicate,
+            ast::PropertyPath::Predicate(_)
+                | ast::PropertyPath::Variable(_)
+                | ast::PropertyPath::RdfType
+        ) {
+            return self.translate_path_pattern(triple);
+        }
+
         let subject = self.translate_triple_term(&triple.subject)?;
         let predicate = self.translate_property_path(&triple.predicate)?;
         let object = self.translate_triple_term(&triple.object)?;
@@ -719,6 +781,146 @@ impl SparqlTranslator {
         }))
     }
 
+    /// Translates a triple pattern whose predicate is a property path.
+    ///
+    /// Paths become expands between the subject and object variables.
+    /// Constant endpoints are bound to fresh variables and filtered.
+    fn translate_path_pattern(&mut self, triple: &ast::TriplePattern) -> Result<LogicalOperator> {
+        let mut constants = Vec::new();
+        let mut endpoint = |this: &mut Self, term: &ast::TripleTerm| -> Result<String> {
+            match this.translate_triple_term(term)? {
+                TripleComponent::Variable(name) => Ok(name),
+                TripleComponent::Iri(iri) => {
+                    let var = format!("_:anon{}", this.next_anon());
+                    constants.push((var.clone(), Value::String(iri.into())));
+                    Ok(var)
+                }
+                TripleComponent::Literal(value) => {
+                    let var = format!("_:anon{}", this.next_anon());
+                    constants.push((var.clone(), value));
+                    Ok(var)
+                }
+            }
+        };
+        let from = endpoint(self, &triple.subject)?;
+        let to = endpoint(self, &triple.object)?;
+
+        let mut plan =
+            self.translate_path(&triple.predicate, &from, &to, LogicalOperator::Empty, false)?;
+        for (variable, value) in constants {
+            plan = LogicalOperator::Filter(FilterOp {
+                predicate: LogicalExpression::Binary {
+                    left: Box::new(LogicalExpression::Variable(variable)),
+                    op: BinaryOp::Eq,
+                    right: Box::new(LogicalExpression::Literal(value)),
+                },
+                input: Box::new(plan),
+            });
+        }
+        Ok(plan)
+    }
+
+    /// Translates a property path from `from` to `to` on top of `input`.
+    ///
+    /// `inverse` is set inside `^path`, which walks the path backwards.
+    fn translate_path(
+        &mut self,
+        path: &ast::PropertyPath,
+        from: &str,
+        to: &str,
+        input: LogicalOperator,
+        inverse: bool,
+    ) -> Result<LogicalOperator> {
+        match path {
+            ast::PropertyPath::Inverse(inner) => {
+                self.translate_path(inner, from, to, input, !inverse)
+            }
+            ast::PropertyPath::Sequence(steps) => {
+                let mut steps: Vec<&ast::PropertyPath> = steps.iter().collect();
+                if inverse {
+                    steps.reverse();
+                }
+                let mut plan = input;
+                let mut current = from.to_string();
+                for (i, step) in steps.iter().enumerate() {
+                    let next = if i + 1 == steps.len() {
+                        to.to_string()
+                    } else {
+                        format!("_:anon{}", self.next_anon())
+                    };
+                    plan = self.translate_path(step, &current, &next, plan, inverse)?;
+                    current = next;
+                }
+                Ok(plan)
+            }
+            ast::PropertyPath::Alternative(alternatives) => {
+                let inputs = alternatives
+                    .iter()
+                    .map(|alt| self.translate_path(alt, from, to, input.clone(), inverse))
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(LogicalOperator::Union(UnionOp { inputs }))
+            }
+            ast::PropertyPath::ZeroOrMore(inner) => {
+                self.translate_path_step(inner, from, to, input, inverse, (0, None))
+            }
+            ast::PropertyPath::OneOrMore(inner) => {
+                self.translate_path_step(inner, from, to, input, inverse, (1, None))
+            }
+            ast::PropertyPath::ZeroOrOne(inner) => {
+                self.translate_path_step(inner, from, to, input, inverse, (0, Some(1)))
+            }
+            _ => self.translate_path_step(path, from, to, input, inverse, (1, Some(1))),
+        }
+    }
+
+    /// Translates a single predicate, optionally repeated, into an expand.
+    fn translate_path_step(
+        &mut self,
+        path: &ast::PropertyPath,
+        from: &str,
+        to: &str,
+        input: LogicalOperator,
+        inverse: bool,
+        (min_hops, max_hops): (u32, Option<u32>),
+    ) -> Result<LogicalOperator> {
+        let (predicate, inverse) = match path {
+            ast::PropertyPath::Inverse(inner) => (inner.as_ref(), !inverse),
+            _ => (path, inverse),
+        };
+        let edge_type = match predicate {
+            ast::PropertyPath::Predicate(iri) => self.resolve_iri(iri),
+            ast::PropertyPath::RdfType => {
+                "http://www.w3.org/1999/02/22-rdf-syntax-ns#type".to_string()
+            }
+            ast::PropertyPath::Negation(_) => {
+                return Err(Error::Internal(
+                    "Negated property sets are not yet supported".to_string(),
+                ));
+            }
+            _ => {
+                return Err(Error::Internal(
+                    "Repeating a complex property path is not yet supported".to_string(),
+                ));
+            }
+        };
+
+        Ok(LogicalOperator::Expand(ExpandOp {
+            from_variable: from.to_string(),
+            to_variable: to.to_string(),
+            edge_variable: None,
+            direction: if inverse {
+                ExpandDirection::Incoming
+            } else {
+                ExpandDirection::Outgoing
+            },
+            edge_type: Some(edge_type),
+            min_hops,
+            max_hops,
+            input: Box::new(input),
+            path_alias: None,
+        }))
+    }
+
     fn translate_triple_term(&mut self, term: &ast::TripleTerm) -> Result<TripleComponent> {
         match term {
             ast::TripleTerm::Variable(name) => Ok(TripleComponent::Variable(name.clone())),
@@ -891,12 +1093,60 @@ impl SparqlTranslator {
                 })
             }
 
+            ast::Expression::Aggregate(_) if self.having_scope.is_some() => {
+                Ok(self.resolve_having_aggregate(expr))
+            }
             ast::Expression::Aggregate(agg) => self.translate_aggregate_expression(agg),
 
             ast::Expression::Bracketed(inner) => self.translate_expression(inner),
         }
     }
 
+    /// Translates a HAVING condition into a predicate over the aggregate's
+    /// output columns.
+    ///
+    /// Aggregates also selected with `AS ?alias` resolve to that alias. Any
+    /// others are appended to `aggregates` under a generated alias.
+    fn translate_having(
+        &mut self,
+        having: &ast::Expression,
+        select: &ast::SelectQuery,
+        aggregates: &mut Vec<AggregateExpr>,
+    ) -> Result<LogicalExpression> {
+        let mut scope = Vec::new();
+        if let ast::Projection::Variables(vars) = &select.projection {
+            for pv in vars {
+                if let (ast::Expression::Aggregate(_), Some(alias)) = (&pv.expression, &pv.alias) {
+                    scope.push((pv.expression.clone(), alias.clone()));
+                }
+            }
+        }
+        let selected = scope.len();
+
+        self.having_scope = Some(scope);
+        let predicate = self.translate_expression(having);
+        let scope = self.having_scope.take().unwrap_or_default();
+        let predicate = predicate?;
+
+        for (expr, alias) in scope.into_iter().skip(selected) {
+            if let Some(agg) = self.extract_aggregate(&expr, &Some(alias))? {
+                aggregates.push(agg);
+            }
+        }
+        Ok(predicate)
+    }
+
+    /// Resolves an aggregate inside HAVING to the column that computes it.
+    fn resolve_having_aggregate(&mut self, expr: &ast::Expression) -> LogicalExpression {
+        let scope = self.having_scope.get_or_insert_with(Vec::new);
+        if let Some((_, alias)) = scope.iter().find(|(e, _)| e == expr) {
+            return LogicalExpression::Variable(alias.clone());
+        }
+        let alias = format!("_having_{}", scope.len());
+        scope.push((expr.clone(), alias.clone()));
+        LogicalExpression::Variable(alias)
+    }
+
     fn translate_aggregate_expression(
         &mut self,
         agg: &ast::AggregateExpression,
@@ -1407,6 +1657,68 @@ mod tests {
         assert!(!agg.group_by.is_empty());
     }
 
+    #[test]
+    fn test_translate_having_filters_above_aggregate() {
+        let query =
+            "SELECT ?y (COUNT(?x) AS ?cnt) WHERE { ?x ?y ?z } GROUP BY ?y HAVING (COUNT(?x) > 2)";
+        let plan = translate(query).unwrap();
+
+        let LogicalOperator::Filter(filter) = &plan.root else {
+            panic!("Expected Filter at root, got {:?}", plan.root);
+        };
+        let LogicalOperator::Aggregate(agg) = filter.input.as_ref() else {
+            panic!("Expected Aggregate below the HAVING filter");
+        };
+        assert_eq!(agg.aggregates.len(), 1);
+        // The aggregate call is replaced by a reference to its output column
+        match &filter.predicate {
+            LogicalExpression::Binary { left, op, right } => {
+                assert_eq!(*op, BinaryOp::Gt);
+                assert!(matches!(left.as_ref(), LogicalExpression::Variable(v) if v == "cnt"));
+                assert!(matches!(
+                    right.as_ref(),
+                    LogicalExpression::Literal(Value::Int64(2))
+                ));
+            }
+            other => panic!("Expected comparison, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_translate_having_on_unselected_aggregate() {
+        let query =
+            "SELECT ?y (COUNT(?x) AS ?cnt) WHERE { ?x ?y ?z } GROUP BY ?y HAVING (SUM(?z) > 10)";
+        let plan = translate(query).unwrap();
+
+        // The extra aggregate is projected away after filtering
+        let LogicalOperator::Project(project) = &plan.root else {
+            panic!("Expected Project at root, got {:?}", plan.root);
+        };
+        let columns: Vec<_> = project
+            .projections
+            .iter()
+            .map(|p| match &p.expression {
+                LogicalExpression::Variable(v) => v.as_str(),
+                other => panic!("Expected variable, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(columns, ["y", "cnt"]);
+
+        let LogicalOperator::Filter(filter) = project.input.as_ref() else {
+            panic!("Expected Filter below Project");
+        };
+        let LogicalOperator::Aggregate(agg) = filter.input.as_ref() else {
+            panic!("Expected Aggregate below Filter");
+        };
+        assert_eq!(agg.aggregates.len(), 2);
+        assert_eq!(agg.aggregates[1].function, AggregateFunction::Sum);
+        let hidden = agg.aggregates[1].alias.clone().unwrap();
+        let LogicalExpression::Binary { left, .. } = &filter.predicate else {
+            panic!("Expected comparison");
+        };
+        assert!(matches!(left.as_ref(), LogicalExpression::Variable(v) if *v == hidden));
+    }
+
     // === Expression Tests ===
 
     #[test]
@@ -1439,6 +1751,141 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    // === Property Path Tests ===
+
+    const KNOWS: &str = "http://xmlns.com/foaf/0.1/knows";
+
+    /// Collects expands in plan order, innermost first.
+    fn path_expands(query: &str) -> Vec<ExpandOp> {
+        fn collect(op: &LogicalOperator, out: &mut Vec<ExpandOp>) {
+            for child in op.children() {
+                collect(child, out);
+            }
+            if let LogicalOperator::Expand(expand) = op {
+                out.push(expand.clone());
+            }
+        }
+        let plan = translate(query).unwrap();
+        let mut expands = Vec::new();
+        collect(&plan.root, &mut expands);
+        expands
+    }
+
+    fn path_query(path: &str) -> String {
+        format!(
+            "PREFIX foaf: <http://xmlns.com/foaf/0.1/> \
+             PREFIX ex: <http://example.org/> \
+             SELECT ?x ?y WHERE {{ ?x {path} ?y }}"
+        )
+    }
+
+    #[test]
+    fn test_translate_path_repetition_hop_bounds() {
+        for (path, min_hops, max_hops) in [
+            ("foaf:knows+", 1, None),
+            ("foaf:knows*", 0, None),
+            ("foaf:knows?", 0, Some(1)),
+        ] {
+            let expands = path_expands(&path_query(path));
+            assert_eq!(expands.len(), 1, "{path}");
+            let expand = &expands[0];
+            assert_eq!(expand.from_variable, "x");
+            assert_eq!(expand.to_variable, "y");
+            assert_eq!(expand.edge_type.as_deref(), Some(KNOWS));
+            assert_eq!(expand.direction, ExpandDirection::Outgoing);
+            assert_eq!(
+                (expand.min_hops, expand.max_hops),
+                (min_hops, max_hops),
+                "{path}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_translate_path_sequence() {
+        let expands = path_expands(&path_query("foaf:knows/ex:name?"));
+        assert_eq!(expands.len(), 2);
+        let (first, second) = (&expands[0], &expands[1]);
+        assert_eq!(first.from_variable, "x");
+        assert_eq!(first.edge_type.as_deref(), Some(KNOWS));
+        assert_eq!((first.min_hops, first.max_hops), (1, Some(1)));
+        assert_eq!(second.from_variable, first.to_variable);
+        assert_eq!(second.to_variable, "y");
+        assert_eq!(second.edge_type.as_deref(), Some("http://example.org/name"));
+        assert_eq!((second.min_hops, second.max_hops), (0, Some(1)));
+    }
+
+    #[test]
+    fn test_translate_path_inverse() {
+        let expands = path_expands(&path_query("^foaf:knows+"));
+        assert_eq!(expands.len(), 1);
+        assert_eq!(expands[0].direction, ExpandDirection::Incoming);
+        assert_eq!((expands[0].min_hops, expands[0].max_hops), (1, None));
+
+        // Inverting a sequence walks it backwards
+        let expands = path_expands(&path_query("^(foaf:knows/ex:member)"));
+        assert_eq!(expands.len(), 2);
+        assert_eq!(expands[0].from_variable, "x");
+        assert_eq!(
+            expands[0].edge_type.as_deref(),
+            Some("http://example.org/member")
+        );
+        assert_eq!(expands[1].to_variable, "y");
+        assert_eq!(expands[1].edge_type.as_deref(), Some(KNOWS));
+        assert!(
+            expands
+                .iter()
+                .all(|e| e.direction == ExpandDirection::Incoming)
+        );
+    }
+
+    #[test]
+    fn test_translate_path_alternative() {
+        let plan = translate(&path_query("foaf:knows|ex:likes")).unwrap();
+        let union = plan
+            .root
+            .children()
+            .into_iter()
+            .chain(std::iter::once(&plan.root))
+            .find_map(|op| match op {
+                LogicalOperator::Union(union) => Some(union),
+                _ => None,
+            })
+            .expect("expected a union");
+        let edge_types: Vec<_> = union
+            .inputs
+            .iter()
+            .map(|input| match input {
+                LogicalOperator::Expand(expand) => expand.edge_type.clone().unwrap(),
+                other => panic!("expected an expand, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(edge_types, vec![KNOWS, "http://example.org/likes"]);
+    }
+
+    #[test]
+    fn test_translate_path_constant_endpoint() {
+        let query = "PREFIX foaf: <http://xmlns.com/foaf/0.1/> \
+                     SELECT ?y WHERE { <http://example.org/alice> foaf:knows+ ?y }";
+        let expands = path_expands(query);
+        assert_eq!(expands.len(), 1);
+        assert!(expands[0].from_variable.starts_with("_:"));
+        assert_eq!(expands[0].to_variable, "y");
+
+        fn has_filter(op: &LogicalOperator) -> bool {
+            matches!(op, LogicalOperator::Filter(_)) || op.children().into_iter().any(has_filter)
+        }
+        assert!(has_filter(&translate(query).unwrap().root));
+    }
+
+    #[test]
+    fn test_translate_path_negated_set_unsupported() {
+        let result = translate(&path_query("!foaf:knows"));
+        assert!(result.is_err());
+        let result = translate(&path_query("!(foaf:knows|ex:likes)"));
+        assert!(result.is_err());
+    }
+
     // === Multiple Triple Patterns ===
 
     #[test]
@@ -4,17 +4,97 @@
 //! its own transaction state, so concurrent sessions don't interfere with
 //! each other. Sessions are cheap to create - spin up as many as you need.
 
+use std::collections::HashMap;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-
-use grafeo_common::types::{EpochId, NodeId, TxId, Value};
-use grafeo_common::utils::error::Result;
-use grafeo_core::graph::lpg::LpgStore;
+use std::time::Duration;
+
+use grafeo_adapters::storage::wal::WalManager;
+use grafeo_common::memory::buffer::BufferManager;
+use grafeo_common::types::{EpochId, NodeId, Timestamp, TxId, Value};
+use grafeo_common::utils::error::{Error, QueryError, QueryErrorKind, Result, TransactionError};
+use grafeo_core::execution::memory::DEFAULT_ACCOUNTING_GRANULARITY;
+use grafeo_core::execution::operators::{ScalarUdf, is_builtin_function};
+use grafeo_core::execution::{CancellationToken, Collation, Interrupt, SpillableOperator};
+use grafeo_core::graph::lpg::{Edge, LpgStore, Node};
 #[cfg(feature = "rdf")]
 use grafeo_core::graph::rdf::RdfStore;
+use grafeo_core::index::HashIndex;
+
+use crate::algorithms::{ComponentsConfig, PageRankConfig, PathOpts};
+use crate::catalog::Catalog;
+#[cfg(feature = "gql")]
+use crate::catalog::IndexType;
+use crate::changefeed::{ChangeFeed, ChangeStream};
+use crate::config::{AdaptiveConfig, DuplicateKeyPolicy, QueryLimits, ResultMode};
+use crate::database::{QueryResult, ResultSizeEstimate};
+use crate::export::ExportFormat;
+use crate::format::IdFormat;
+use crate::idempotency::IdempotencyStore;
+use crate::import::{ImportFormat, ImportOptions, ImportReport, ImportTarget, LoadReport};
+use crate::prepared::PreparedQuery;
+use crate::projection::GraphProjection;
+use crate::query::optimizer::{CardinalityEstimator, Optimizer};
+use crate::query::plan::LogicalPlan;
+use crate::query::planner::PhysicalPlan;
+use crate::query::{
+    PlanExplanation, Planner, QueryOutput, QueryProcessor, ResultCache, ResultStream,
+};
+use crate::transaction::{
+    Batch, BatchOutcome, IsolationLevel, SavepointMark, TransactionManager, commit_writes,
+};
+
+/// How many times a statement run outside a transaction is retried when it
+/// runs into another transaction's write lock.
+const STATEMENT_RETRIES: u32 = 12;
+
+/// Per-call settings for [`Session::execute_with()`].
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// How long the query may run, overriding the session's timeout.
+    ///
+    /// `None` keeps the session's [`query_timeout`](Session::query_timeout).
+    /// A timeout too long to represent, like [`Duration::MAX`], means no
+    /// limit.
+    pub timeout: Option<Duration>,
+    /// Stops the query once cancelled.
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl QueryOptions {
+    /// Creates options that keep the session's settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops the query once it has run for `timeout`.
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Stops the query once `token` is cancelled.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
 
-use crate::config::AdaptiveConfig;
-use crate::database::QueryResult;
-use crate::transaction::TransactionManager;
+    /// What stops a query started now, given the session's timeout.
+    fn interrupt(&self, session_timeout: Option<Duration>) -> Interrupt {
+        let mut interrupt = Interrupt::new();
+        if let Some(timeout) = self.timeout.or(session_timeout) {
+            interrupt = interrupt.with_timeout(timeout);
+        }
+        if let Some(token) = &self.cancellation {
+            interrupt = interrupt.with_token(token.clone());
+        }
+        interrupt
+    }
+}
 
 /// Your handle to the database - execute queries and manage transactions.
 ///
@@ -32,11 +112,57 @@ pub struct Session {
     tx_manager: Arc<TransactionManager>,
     /// Current transaction ID (if any).
     current_tx: Option<TxId>,
+    /// Start and commit epochs of the last transaction this session committed.
+    last_commit: Option<(EpochId, EpochId)>,
     /// Whether the session is in auto-commit mode.
     auto_commit: bool,
     /// Adaptive execution configuration.
     #[allow(dead_code)]
     adaptive_config: AdaptiveConfig,
+    /// String collation for sorting and range predicates.
+    collation: Collation,
+    /// Only traverse edges valid at this time, if set.
+    as_of: Option<Timestamp>,
+    /// How node and edge ids are rendered in result output.
+    id_format: IdFormat,
+    /// The database catalog (declared names, indexes, edge type settings).
+    catalog: Option<Arc<Catalog>>,
+    /// Whether writes must use names declared in the catalog.
+    strict_schema: bool,
+    /// What a write that repeats a property key does.
+    duplicate_keys: DuplicateKeyPolicy,
+    /// Memory budget for query operators, and its accounting granularity.
+    memory_budget: Option<(Arc<BufferManager>, usize)>,
+    /// Spill directory overriding the database's spill path.
+    spill_path: Option<PathBuf>,
+    /// Operator kinds that fail instead of spilling.
+    spill_disabled: Vec<SpillableOperator>,
+    /// User-defined scalar functions, keyed by lowercase name.
+    udfs: Arc<HashMap<String, ScalarUdf>>,
+    /// Write-ahead log, for writes made outside of queries.
+    wal: Option<Arc<WalManager>>,
+    /// Named graph projections created in this session.
+    projections: HashMap<String, Arc<GraphProjection>>,
+    /// The database's recently seen idempotency keys.
+    idempotency: Option<Arc<IdempotencyStore>>,
+    /// Keys of streamed-in nodes, for resolving streamed-in edges.
+    import_keys: Arc<HashIndex<String, NodeId>>,
+    /// The database's feed of committed changes.
+    changes: Arc<ChangeFeed>,
+    /// The database's query result cache.
+    result_cache: Option<Arc<ResultCache>>,
+    /// Change ratio that refreshes statistics before planning (None = never).
+    statistics_refresh_ratio: Option<f64>,
+    /// Limits that reject overly complex queries.
+    query_limits: QueryLimits,
+    /// How [`run`](Self::run) returns results.
+    result_mode: ResultMode,
+    /// How long a query may run before it's stopped, if limited.
+    query_timeout: Option<Duration>,
+    /// Whether the optimizer reorders joins by estimated cost.
+    join_reorder: bool,
+    /// Worker threads a query may use; joins run in parallel above one.
+    threads: usize,
 }
 
 impl Session {
@@ -49,8 +175,31 @@ impl Session {
             rdf_store: Arc::new(RdfStore::new()),
             tx_manager,
             current_tx: None,
+            last_commit: None,
             auto_commit: true,
             adaptive_config: AdaptiveConfig::default(),
+            collation: Collation::Binary,
+            as_of: None,
+            id_format: IdFormat::Integer,
+            catalog: None,
+            strict_schema: false,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            memory_budget: None,
+            spill_path: None,
+            spill_disabled: Vec::new(),
+            udfs: Arc::new(HashMap::new()),
+            wal: None,
+            projections: HashMap::new(),
+            idempotency: None,
+            import_keys: Arc::new(HashIndex::new()),
+            changes: Arc::new(ChangeFeed::default()),
+            result_cache: None,
+            statistics_refresh_ratio: None,
+            query_limits: QueryLimits::default(),
+            result_mode: ResultMode::Materialized,
+            query_timeout: None,
+            join_reorder: true,
+            threads: 1,
         }
     }
 
@@ -67,8 +216,31 @@ impl Session {
             rdf_store: Arc::new(RdfStore::new()),
             tx_manager,
             current_tx: None,
+            last_commit: None,
             auto_commit: true,
             adaptive_config,
+            collation: Collation::Binary,
+            as_of: None,
+            id_format: IdFormat::Integer,
+            catalog: None,
+            strict_schema: false,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            memory_budget: None,
+            spill_path: None,
+            spill_disabled: Vec::new(),
+            udfs: Arc::new(HashMap::new()),
+            wal: None,
+            projections: HashMap::new(),
+            idempotency: None,
+            import_keys: Arc::new(HashIndex::new()),
+            changes: Arc::new(ChangeFeed::default()),
+            result_cache: None,
+            statistics_refresh_ratio: None,
+            query_limits: QueryLimits::default(),
+            result_mode: ResultMode::Materialized,
+            query_timeout: None,
+            join_reorder: true,
+            threads: 1,
         }
     }
 
@@ -85,13 +257,48 @@ impl Session {
             rdf_store,
             tx_manager,
             current_tx: None,
+            last_commit: None,
             auto_commit: true,
             adaptive_config,
+            collation: Collation::Binary,
+            as_of: None,
+            id_format: IdFormat::Integer,
+            catalog: None,
+            strict_schema: false,
+            duplicate_keys: DuplicateKeyPolicy::Lenient,
+            memory_budget: None,
+            spill_path: None,
+            spill_disabled: Vec::new(),
+            udfs: Arc::new(HashMap::new()),
+            wal: None,
+            projections: HashMap::new(),
+            idempotency: None,
+            import_keys: Arc::new(HashIndex::new()),
+            changes: Arc::new(ChangeFeed::default()),
+            result_cache: None,
+            statistics_refresh_ratio: None,
+            query_limits: QueryLimits::default(),
+            result_mode: ResultMode::Materialized,
+            query_timeout: None,
+            join_reorder: true,
+            threads: 1,
         }
     }
 
     /// Executes a GQL query.
     ///
+    /// `ANALYZE` collects statistics for the optimizer instead of querying,
+    /// returning a row per label, edge type, and indexed property. See
+    /// [`Catalog::analyze`].
+    ///
+    /// `CREATE FULLTEXT INDEX ON :Label(property)` builds a full-text index,
+    /// returning a row with the label, property, and number of documents
+    /// indexed. See [`Catalog::create_fulltext_index`].
+    ///
+    /// `CREATE SPATIAL INDEX ON :Label(property)` builds a spatial index over
+    /// point values, returning a row with the label, property, and number of
+    /// points indexed. See [`Catalog::create_spatial_index`].
+    ///
     /// # Errors
     ///
     /// Returns an error if the query fails to parse or execute.
@@ -113,38 +320,511 @@ impl Session {
     ///     println!("{:?}", row);
     /// }
     /// ```
+    ///
+    /// Prefixing a query with `EXPLAIN` returns the operators it would run,
+    /// one row each, without running it; `EXPLAIN ANALYZE` runs it and adds
+    /// what each operator did. See [`explain_analyze()`](Self::explain_analyze).
     #[cfg(feature = "gql")]
     pub fn execute(&self, query: &str) -> Result<QueryResult> {
-        use crate::query::{
-            Executor, Planner, binder::Binder, gql_translator, optimizer::Optimizer,
+        self.execute_with(query, &QueryOptions::default())
+    }
+
+    /// Executes a GQL query that can be stopped part-way, by a timeout or
+    /// a [`CancellationToken`].
+    ///
+    /// The query checks at chunk boundaries whether to stop, so it ends
+    /// soon after cancellation or its deadline, freeing its memory and spill
+    /// files. What it wrote so far is undone: outside a transaction
+    /// the query runs in one of its own, which is aborted, and inside one
+    /// the transaction stays open without the query's writes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or execute. A stopped
+    /// query fails with a [`QueryErrorKind::Cancelled`] or
+    /// [`QueryErrorKind::Timeout`] query error.
+    ///
+    /// [`QueryErrorKind::Cancelled`]: grafeo_common::utils::error::QueryErrorKind::Cancelled
+    /// [`QueryErrorKind::Timeout`]: grafeo_common::utils::error::QueryErrorKind::Timeout
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use grafeo_engine::{CancellationToken, GrafeoDB, QueryOptions};
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let session = db.session();
+    /// let token = CancellationToken::new();
+    /// let options = QueryOptions::new()
+    ///     .with_timeout(Duration::from_secs(5))
+    ///     .with_cancellation(token.clone());
+    ///
+    /// session.execute_with("INSERT (:Person {name: 'Alice'})", &options)?;
+    ///
+    /// // Another thread can stop the query by calling token.cancel()
+    /// token.cancel();
+    /// assert!(session.execute_with("MATCH (p:Person) RETURN p", &options).is_err());
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    #[cfg(feature = "gql")]
+    pub fn execute_with(&self, query: &str, options: &QueryOptions) -> Result<QueryResult> {
+        use crate::query::processor::QueryLanguage;
+
+        // Changes statistics or indexes rather than reading the graph, so
+        // never cached
+        if is_analyze(query) {
+            return self.analyze();
+        }
+        if let Some(index) = parse_create_index(query) {
+            let (index_type, label, property) = index?;
+            return Ok(self.create_index(index_type, &label, &property));
+        }
+        let interrupt = options.interrupt(self.query_timeout);
+        // Explained plans depend on statistics and timings, so never cached
+        if let Some((analyze, query)) = parse_explain(query) {
+            let explanation = self.explain_plan(query, analyze, &interrupt)?;
+            return Ok(explanation.to_result());
+        }
+        self.cached_result(query, QueryLanguage::Gql, None, || {
+            self.execute_uncached(query, &interrupt)
+        })
+    }
+
+    /// Executes a GQL query, returning its rows the way the session's
+    /// [`ResultMode`] asks for.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or, when materialized,
+    /// to execute. Streamed queries report execution errors from the
+    /// iterator instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::{GrafeoDB, ResultMode};
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let mut session = db.session();
+    /// session.execute("INSERT (:Person {name: 'Alice'})")?;
+    ///
+    /// session.set_result_mode(ResultMode::Streaming);
+    /// let output = session.run("MATCH (p:Person) RETURN p.name")?;
+    /// assert!(output.is_streaming());
+    /// assert_eq!(output.into_result()?.row_count(), 1);
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    #[cfg(feature = "gql")]
+    pub fn run(&self, query: &str) -> Result<QueryOutput> {
+        self.run_as(query, self.result_mode)
+    }
+
+    /// Executes a GQL query like [`run`](Self::run), but with the given
+    /// result mode instead of the session's.
+    ///
+    /// Streamed queries bypass the result cache.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or, when materialized,
+    /// to execute.
+    #[cfg(feature = "gql")]
+    pub fn run_as(&self, query: &str, mode: ResultMode) -> Result<QueryOutput> {
+        if is_analyze(query)
+            || parse_create_index(query).is_some()
+            || parse_explain(query).is_some()
+        {
+            return self.execute(query).map(QueryOutput::Materialized);
+        }
+        match mode {
+            ResultMode::Materialized => self.execute(query).map(QueryOutput::Materialized),
+            ResultMode::Streaming => self.execute_streaming(query).map(QueryOutput::Streaming),
+        }
+    }
+
+    /// Executes a GQL query, pulling its rows one chunk at a time as the
+    /// returned stream is iterated.
+    ///
+    /// Nothing runs until the first row is requested, and each further
+    /// chunk is only produced once the previous one has been read. Dropping
+    /// the stream early stops the query, releasing its memory and removing
+    /// any files it spilled. Streamed queries bypass the result cache, and
+    /// the session's [`query_timeout`](Self::query_timeout) covers the
+    /// whole stream.
+    ///
+    /// The query runs as a statement like [`execute`](Self::execute)'s:
+    /// outside a transaction it gets one of its own, committed once every
+    /// row has been read, so it reads a single snapshot while it's
+    /// iterated. If the query fails, or the stream is dropped before its
+    /// last row, its writes are undone. Unlike `execute`, a streamed
+    /// statement isn't retried when it runs into a write conflict.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or plan. Execution
+    /// errors, including a failed commit, are reported by the iterator.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let session = db.session();
+    /// session.execute("INSERT (:Person {name: 'Alice'})")?;
+    /// session.execute("INSERT (:Person {name: 'Bob'})")?;
+    ///
+    /// let mut rows = session.execute_streaming("MATCH (p:Person) RETURN p.name")?;
+    /// let first = rows.next().transpose()?;
+    /// assert!(first.is_some());
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    #[cfg(feature = "gql")]
+    pub fn execute_streaming(&self, query: &str) -> Result<ResultStream> {
+        let interrupt = QueryOptions::default().interrupt(self.query_timeout);
+        let store = Arc::clone(&self.store);
+
+        if let (epoch, Some(tx_id)) = self.get_transaction_context() {
+            let mark = store.tx_write_count(tx_id);
+            let plan = self.plan_gql_in(query, (epoch, Some(tx_id)), &interrupt, false)?;
+            return Ok(ResultStream::new(plan).with_finish(move |completed| {
+                if !completed {
+                    store.undo_writes_since(tx_id, mark);
+                }
+                Ok(())
+            }));
+        }
+
+        let tx_id = self.tx_manager.begin();
+        let epoch = self
+            .tx_manager
+            .read_epoch(tx_id)
+            .unwrap_or_else(|| self.tx_manager.current_epoch());
+        let plan = match self.plan_gql_in(query, (epoch, Some(tx_id)), &interrupt, false) {
+            Ok(plan) => plan,
+            Err(e) => {
+                self.tx_manager.abort(tx_id)?;
+                return Err(e);
+            }
+        };
+        let tx_manager = Arc::clone(&self.tx_manager);
+        let wal = self.wal.clone();
+        let changes = Arc::clone(&self.changes);
+        Ok(ResultStream::new(plan).with_finish(move |completed| {
+            if !completed {
+                store.discard_uncommitted_versions(tx_id);
+                return tx_manager.abort(tx_id);
+            }
+            if store.tx_write_count(tx_id) == 0 {
+                // Nothing to commit, so no epoch to spend on it
+                return tx_manager.abort(tx_id);
+            }
+            commit_writes(&store, &tx_manager, tx_id, wal.as_deref(), &changes, || {
+                Ok(())
+            })
+            .map(drop)
+        }))
+    }
+
+    /// Runs `ANALYZE`, reporting what was collected.
+    ///
+    /// Labels and edge types report their counts, and indexed properties
+    /// their non-null count and estimated distinct values.
+    #[cfg(feature = "gql")]
+    fn analyze(&self) -> Result<QueryResult> {
+        use grafeo_common::types::LogicalType;
+
+        let statistics = match &self.catalog {
+            Some(catalog) => catalog.analyze(&self.store)?,
+            None => Catalog::new().analyze(&self.store)?,
+        };
+
+        let mut result = QueryResult::with_types(
+            vec![
+                "kind".to_string(),
+                "name".to_string(),
+                "count".to_string(),
+                "distinct".to_string(),
+            ],
+            vec![
+                LogicalType::String,
+                LogicalType::String,
+                LogicalType::Int64,
+                LogicalType::Int64,
+            ],
+        );
+        let mut push = |kind: &str, name: &str, count: u64, distinct: Option<u64>| {
+            result.rows.push(vec![
+                Value::from(kind),
+                Value::from(name),
+                Value::Int64(count as i64),
+                distinct.map_or(Value::Null, |d| Value::Int64(d as i64)),
+            ]);
+        };
+
+        let mut labels: Vec<_> = statistics.labels.iter().collect();
+        labels.sort_by_key(|(name, _)| *name);
+        for (name, label) in labels {
+            push("label", name, label.node_count, None);
+        }
+        let mut edge_types: Vec<_> = statistics.edge_types.iter().collect();
+        edge_types.sort_by_key(|(name, _)| *name);
+        for (name, edge_type) in edge_types {
+            push("edge_type", name, edge_type.edge_count, None);
+        }
+        let mut properties: Vec<_> = statistics.properties.iter().collect();
+        properties.sort_by_key(|(name, _)| *name);
+        for (name, column) in properties {
+            push(
+                "property",
+                name,
+                column.total_count - column.null_count,
+                Some(column.distinct_count),
+            );
+        }
+        Ok(result)
+    }
+
+    /// Runs `CREATE FULLTEXT INDEX` or `CREATE SPATIAL INDEX`, reporting how
+    /// many documents or points it indexed.
+    #[cfg(feature = "gql")]
+    fn create_index(&self, index_type: IndexType, label: &str, property: &str) -> QueryResult {
+        use grafeo_common::types::LogicalType;
+
+        let fallback;
+        let catalog = match &self.catalog {
+            Some(catalog) => catalog,
+            None => {
+                fallback = Catalog::new();
+                &fallback
+            }
+        };
+        let (column, count) = if index_type == IndexType::Spatial {
+            catalog.create_spatial_index(&self.store, label, property);
+            let points = self.store.spatial_index(label, property);
+            ("points", points.map_or(0, |index| index.len()))
+        } else {
+            catalog.create_fulltext_index(&self.store, label, property);
+            let documents = self.store.fulltext_index(label, property);
+            ("documents", documents.map_or(0, |index| index.len()))
         };
 
+        let mut result = QueryResult::with_types(
+            vec![
+                "label".to_string(),
+                "property".to_string(),
+                column.to_string(),
+            ],
+            vec![LogicalType::String, LogicalType::String, LogicalType::Int64],
+        );
+        result.rows.push(vec![
+            Value::from(label),
+            Value::from(property),
+            Value::Int64(count as i64),
+        ]);
+        result
+    }
+
+    /// Executes a GQL query without consulting the result cache.
+    #[cfg(feature = "gql")]
+    fn execute_uncached(&self, query: &str, interrupt: &Interrupt) -> Result<QueryResult> {
+        use crate::query::Executor;
+
+        self.run_statement(|epoch, tx_id| {
+            let mut physical_plan =
+                self.plan_gql_in(query, (epoch, Some(tx_id)), interrupt, false)?;
+            let executor = Executor::with_columns(physical_plan.columns.clone());
+            executor.execute_plan(&mut physical_plan)
+        })
+    }
+
+    /// Runs a statement so that a failure - including being stopped
+    /// part-way - leaves none of its writes behind.
+    ///
+    /// Outside a transaction the statement runs in one of its own, so its
+    /// writes are versioned like any transaction's: committed if it
+    /// succeeds and aborted if it fails. One that runs into another
+    /// transaction's write lock is retried a few times before its write
+    /// conflict is returned. Inside a transaction, a failed statement's
+    /// writes are undone the way [`rollback_to`](Self::rollback_to) undoes
+    /// them, and the transaction stays open.
+    fn run_statement<T>(&self, run: impl Fn(EpochId, TxId) -> Result<T>) -> Result<T> {
+        if let (epoch, Some(tx_id)) = self.get_transaction_context() {
+            let mark = self.store.tx_write_count(tx_id);
+            #[cfg(feature = "rdf")]
+            let rdf_mark = self.rdf_store.pending_op_count(tx_id);
+            let result = run(epoch, tx_id);
+            if result.is_err() {
+                self.store.undo_writes_since(tx_id, mark);
+                #[cfg(feature = "rdf")]
+                self.rdf_store.discard_ops_since(tx_id, rdf_mark);
+            }
+            return result;
+        }
+
+        let mut retries = 0;
+        loop {
+            match self.run_in_own_tx(&run) {
+                Err(Error::Transaction(TransactionError::WriteConflict(_)))
+                    if retries < STATEMENT_RETRIES =>
+                {
+                    // Let the lock holder finish, backing off further each time
+                    std::thread::sleep(Duration::from_micros(50 << retries));
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Runs a statement in a transaction of its own, committing it if the
+    /// statement wrote anything and succeeded.
+    fn run_in_own_tx<T>(&self, run: impl FnOnce(EpochId, TxId) -> Result<T>) -> Result<T> {
+        let tx_id = self.tx_manager.begin();
+        let epoch = self
+            .tx_manager
+            .read_epoch(tx_id)
+            .unwrap_or_else(|| self.tx_manager.current_epoch());
+        let committed = run(epoch, tx_id).and_then(|value| {
+            let wrote = self.store.tx_write_count(tx_id) > 0;
+            #[cfg(feature = "rdf")]
+            let wrote = wrote || self.rdf_store.has_pending_ops(tx_id);
+            if !wrote {
+                // Nothing to commit, so no epoch to spend on it
+                self.tx_manager.abort(tx_id)?;
+                return Ok(value);
+            }
+            self.commit_tx(tx_id)?;
+            Ok(value)
+        });
+        if committed.is_err() {
+            self.store.discard_uncommitted_versions(tx_id);
+            #[cfg(feature = "rdf")]
+            self.rdf_store.rollback_tx(tx_id);
+            // Already finished if the commit itself failed
+            let _ = self.tx_manager.abort(tx_id);
+        }
+        committed
+    }
+
+    /// Commits what transaction `tx_id` wrote, logging it ahead and
+    /// publishing it to subscribers.
+    ///
+    /// Buffered RDF writes are applied as part of the commit, and dropped
+    /// if it fails.
+    fn commit_tx(&self, tx_id: TxId) -> Result<EpochId> {
+        let committed = commit_writes(
+            &self.store,
+            &self.tx_manager,
+            tx_id,
+            self.wal.as_deref(),
+            &self.changes,
+            || {
+                #[cfg(feature = "rdf")]
+                self.rdf_store.commit_tx(tx_id);
+                Ok(())
+            },
+        );
+        #[cfg(feature = "rdf")]
+        if committed.is_err() {
+            self.rdf_store.rollback_tx(tx_id);
+        }
+        committed
+    }
+
+    /// Plans a GQL query to run at the given `(viewing_epoch, tx_id)`,
+    /// stopping early once `interrupt` fires, and profiling each operator
+    /// if `profile` is set.
+    #[cfg(feature = "gql")]
+    fn plan_gql_in(
+        &self,
+        query: &str,
+        (viewing_epoch, tx_id): (EpochId, Option<TxId>),
+        interrupt: &Interrupt,
+        profile: bool,
+    ) -> Result<PhysicalPlan> {
+        use crate::query::{binder::Binder, gql_translator};
+
         // Parse and translate the query to a logical plan
-        let logical_plan = gql_translator::translate(query)?;
+        let logical_plan =
+            gql_translator::translate_with_max_depth(query, self.query_limits.max_depth)?;
+        self.query_limits.check_plan(&logical_plan)?;
 
         // Semantic validation
         let mut binder = Binder::new();
         let _binding_context = binder.bind(&logical_plan)?;
 
         // Optimize the plan
-        let optimizer = Optimizer::new();
+        let optimizer = self.optimizer();
         let optimized_plan = optimizer.optimize(logical_plan)?;
 
-        // Get transaction context for MVCC visibility
-        let (viewing_epoch, tx_id) = self.get_transaction_context();
-
         // Convert to physical plan with transaction context
         let planner = Planner::with_context(
             Arc::clone(&self.store),
             Arc::clone(&self.tx_manager),
             tx_id,
             viewing_epoch,
-        );
-        let mut physical_plan = planner.plan(&optimized_plan)?;
+        )
+        .with_collation(self.collation)
+        .with_interrupt(interrupt.clone())
+        .with_estimates(optimizer);
+        let planner = self.configure_planner(planner);
+        let planner = match (profile, &self.memory_budget) {
+            (false, _) => planner,
+            (true, Some(_)) => planner.with_profiling(),
+            // Without a budget nothing measures memory, so measure against
+            // one that never runs out
+            (true, None) => planner.with_profiling().with_memory_budget(
+                BufferManager::with_budget(usize::MAX),
+                DEFAULT_ACCOUNTING_GRANULARITY,
+            ),
+        };
+        planner.plan(&optimized_plan)
+    }
+
+    /// Plans a GQL query and explains its operators, running it first if
+    /// `analyze` is set.
+    #[cfg(feature = "gql")]
+    fn explain_plan(
+        &self,
+        query: &str,
+        analyze: bool,
+        interrupt: &Interrupt,
+    ) -> Result<PlanExplanation> {
+        use crate::query::Executor;
+
+        let explain = |context| {
+            let mut physical_plan = self.plan_gql_in(query, context, interrupt, analyze)?;
+            if analyze {
+                Executor::with_columns(physical_plan.columns.clone())
+                    .execute_plan(&mut physical_plan)?;
+            }
+            physical_plan
+                .explain()
+                .ok_or_else(|| Error::Internal("planner recorded no operators".to_string()))
+        };
+        if analyze {
+            self.run_statement(|epoch, tx_id| explain((epoch, Some(tx_id))))
+        } else {
+            explain(self.get_transaction_context())
+        }
+    }
+
+    /// Compiles a GQL query once so it can be executed many times.
+    ///
+    /// Parsing, validation, and optimization happen here; each
+    /// [`PreparedQuery::execute()`] only binds parameters and runs the plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or validate.
+    #[cfg(feature = "gql")]
+    pub fn prepare(&self, query: &str) -> Result<PreparedQuery<'_>> {
+        use crate::query::processor::QueryLanguage;
 
-        // Execute the plan
-        let executor = Executor::with_columns(physical_plan.columns.clone());
-        executor.execute(physical_plan.operator.as_mut())
+        PreparedQuery::new(self, query, QueryLanguage::Gql)
     }
 
     /// Executes a GQL query with parameters.
@@ -158,23 +838,37 @@ impl Session {
         query: &str,
         params: std::collections::HashMap<String, Value>,
     ) -> Result<QueryResult> {
-        use crate::query::processor::{QueryLanguage, QueryProcessor};
-
-        // Get transaction context for MVCC visibility
-        let (viewing_epoch, tx_id) = self.get_transaction_context();
+        use crate::query::processor::QueryLanguage;
 
-        // Create processor with transaction context
-        let processor =
-            QueryProcessor::for_lpg_with_tx(Arc::clone(&self.store), Arc::clone(&self.tx_manager));
-
-        // Apply transaction context if in a transaction
-        let processor = if let Some(tx_id) = tx_id {
-            processor.with_tx_context(viewing_epoch, tx_id)
-        } else {
-            processor
-        };
+        self.cached_result(query, QueryLanguage::Gql, Some(&params), || {
+            self.process_statement(query, QueryLanguage::Gql, &params)
+        })
+    }
 
-        processor.process(query, QueryLanguage::Gql, Some(&params))
+    /// Runs a query with parameters through a query processor, as one
+    /// statement (see [`run_statement`](Self::run_statement)).
+    #[cfg(any(
+        feature = "gql",
+        feature = "cypher",
+        feature = "gremlin",
+        feature = "graphql"
+    ))]
+    fn process_statement(
+        &self,
+        query: &str,
+        language: crate::query::processor::QueryLanguage,
+        params: &std::collections::HashMap<String, Value>,
+    ) -> Result<QueryResult> {
+        self.run_statement(|viewing_epoch, tx_id| {
+            let processor = QueryProcessor::for_lpg_with_tx(
+                Arc::clone(&self.store),
+                Arc::clone(&self.tx_manager),
+            )
+            .with_collation(self.collation);
+            self.configure_processor(processor)
+                .with_tx_context(viewing_epoch, tx_id)
+                .process(query, language, Some(params))
+        })
     }
 
     /// Executes a GQL query with parameters.
@@ -205,6 +899,52 @@ impl Session {
         ))
     }
 
+    /// Executes a GQL mutation at most once per idempotency key.
+    ///
+    /// The first call with a key runs the query and remembers its result.
+    /// A retry with the same key within
+    /// [`Config::idempotency_window`](crate::Config::idempotency_window)
+    /// returns that result without running the query again, so a retried
+    /// insert doesn't create a duplicate. Failed queries aren't remembered.
+    ///
+    /// Keys are shared by all sessions of a database, but aren't persisted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails, or if called inside an explicit
+    /// transaction, where a later rollback would leave the key recorded for
+    /// a change that never happened.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let session = db.session();
+    ///
+    /// let insert = "INSERT (:Order {ref: 'A-17'})";
+    /// session.execute_idempotent("order-A-17", insert)?;
+    /// // The client timed out and retries
+    /// session.execute_idempotent("order-A-17", insert)?;
+    /// assert_eq!(db.node_count(), 1);
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    #[cfg(feature = "gql")]
+    pub fn execute_idempotent(&self, key: &str, query: &str) -> Result<QueryResult> {
+        if self.current_tx.is_some() {
+            return Err(grafeo_common::utils::error::Error::Transaction(
+                grafeo_common::utils::error::TransactionError::InvalidState(
+                    "Idempotency keys can't be used inside an explicit transaction".to_string(),
+                ),
+            ));
+        }
+        match &self.idempotency {
+            Some(store) => store.run(key, || self.execute(query)),
+            None => self.execute(query),
+        }
+    }
+
     /// Executes a Cypher query.
     ///
     /// # Errors
@@ -212,36 +952,50 @@ impl Session {
     /// Returns an error if the query fails to parse or execute.
     #[cfg(feature = "cypher")]
     pub fn execute_cypher(&self, query: &str) -> Result<QueryResult> {
-        use crate::query::{
-            Executor, Planner, binder::Binder, cypher_translator, optimizer::Optimizer,
-        };
+        use crate::query::{binder::Binder, cypher_translator};
 
         // Parse and translate the query to a logical plan
-        let logical_plan = cypher_translator::translate(query)?;
+        let logical_plan =
+            cypher_translator::translate_with_max_depth(query, self.query_limits.max_depth)?;
+        self.query_limits.check_plan(&logical_plan)?;
 
         // Semantic validation
         let mut binder = Binder::new();
         let _binding_context = binder.bind(&logical_plan)?;
 
         // Optimize the plan
-        let optimizer = Optimizer::new();
+        let optimizer = self.optimizer();
         let optimized_plan = optimizer.optimize(logical_plan)?;
 
-        // Get transaction context for MVCC visibility
-        let (viewing_epoch, tx_id) = self.get_transaction_context();
+        self.execute_plan(&optimized_plan)
+    }
 
-        // Convert to physical plan with transaction context
-        let planner = Planner::with_context(
-            Arc::clone(&self.store),
-            Arc::clone(&self.tx_manager),
-            tx_id,
-            viewing_epoch,
-        );
-        let mut physical_plan = planner.plan(&optimized_plan)?;
+    /// Executes a Cypher query with parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or execute.
+    #[cfg(feature = "cypher")]
+    pub fn execute_cypher_with_params(
+        &self,
+        query: &str,
+        params: std::collections::HashMap<String, Value>,
+    ) -> Result<QueryResult> {
+        use crate::query::processor::QueryLanguage;
+
+        self.process_statement(query, QueryLanguage::Cypher, &params)
+    }
+
+    /// Compiles a Cypher query once so it can be executed many times.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or validate.
+    #[cfg(feature = "cypher")]
+    pub fn prepare_cypher(&self, query: &str) -> Result<PreparedQuery<'_>> {
+        use crate::query::processor::QueryLanguage;
 
-        // Execute the plan
-        let executor = Executor::with_columns(physical_plan.columns.clone());
-        executor.execute(physical_plan.operator.as_mut())
+        PreparedQuery::new(self, query, QueryLanguage::Cypher)
     }
 
     /// Executes a Gremlin query.
@@ -266,36 +1020,21 @@ impl Session {
     /// ```
     #[cfg(feature = "gremlin")]
     pub fn execute_gremlin(&self, query: &str) -> Result<QueryResult> {
-        use crate::query::{
-            Executor, Planner, binder::Binder, gremlin_translator, optimizer::Optimizer,
-        };
+        use crate::query::{binder::Binder, gremlin_translator};
 
         // Parse and translate the query to a logical plan
         let logical_plan = gremlin_translator::translate(query)?;
+        self.query_limits.check_plan(&logical_plan)?;
 
         // Semantic validation
         let mut binder = Binder::new();
         let _binding_context = binder.bind(&logical_plan)?;
 
         // Optimize the plan
-        let optimizer = Optimizer::new();
+        let optimizer = self.optimizer();
         let optimized_plan = optimizer.optimize(logical_plan)?;
 
-        // Get transaction context for MVCC visibility
-        let (viewing_epoch, tx_id) = self.get_transaction_context();
-
-        // Convert to physical plan with transaction context
-        let planner = Planner::with_context(
-            Arc::clone(&self.store),
-            Arc::clone(&self.tx_manager),
-            tx_id,
-            viewing_epoch,
-        );
-        let mut physical_plan = planner.plan(&optimized_plan)?;
-
-        // Execute the plan
-        let executor = Executor::with_columns(physical_plan.columns.clone());
-        executor.execute(physical_plan.operator.as_mut())
+        self.execute_plan(&optimized_plan)
     }
 
     /// Executes a Gremlin query with parameters.
@@ -309,23 +1048,9 @@ impl Session {
         query: &str,
         params: std::collections::HashMap<String, Value>,
     ) -> Result<QueryResult> {
-        use crate::query::processor::{QueryLanguage, QueryProcessor};
-
-        // Get transaction context for MVCC visibility
-        let (viewing_epoch, tx_id) = self.get_transaction_context();
-
-        // Create processor with transaction context
-        let processor =
-            QueryProcessor::for_lpg_with_tx(Arc::clone(&self.store), Arc::clone(&self.tx_manager));
-
-        // Apply transaction context if in a transaction
-        let processor = if let Some(tx_id) = tx_id {
-            processor.with_tx_context(viewing_epoch, tx_id)
-        } else {
-            processor
-        };
+        use crate::query::processor::QueryLanguage;
 
-        processor.process(query, QueryLanguage::Gremlin, Some(&params))
+        self.process_statement(query, QueryLanguage::Gremlin, &params)
     }
 
     /// Executes a GraphQL query against the LPG store.
@@ -350,66 +1075,117 @@ impl Session {
     /// ```
     #[cfg(feature = "graphql")]
     pub fn execute_graphql(&self, query: &str) -> Result<QueryResult> {
-        use crate::query::{
-            Executor, Planner, binder::Binder, graphql_translator, optimizer::Optimizer,
-        };
+        use crate::query::{binder::Binder, graphql_translator};
 
         // Parse and translate the query to a logical plan
         let logical_plan = graphql_translator::translate(query)?;
+        self.query_limits.check_plan(&logical_plan)?;
 
         // Semantic validation
         let mut binder = Binder::new();
         let _binding_context = binder.bind(&logical_plan)?;
 
         // Optimize the plan
-        let optimizer = Optimizer::new();
+        let optimizer = self.optimizer();
         let optimized_plan = optimizer.optimize(logical_plan)?;
 
-        // Get transaction context for MVCC visibility
-        let (viewing_epoch, tx_id) = self.get_transaction_context();
-
-        // Convert to physical plan with transaction context
-        let planner = Planner::with_context(
-            Arc::clone(&self.store),
-            Arc::clone(&self.tx_manager),
-            tx_id,
-            viewing_epoch,
-        );
-        let mut physical_plan = planner.plan(&optimized_plan)?;
-
-        // Execute the plan
-        let executor = Executor::with_columns(physical_plan.columns.clone());
-        executor.execute(physical_plan.operator.as_mut())
+        self.execute_plan(&optimized_plan)
     }
 
-    /// Executes a GraphQL query with parameters.
+    /// Executes a GraphQL query and returns its response as nested JSON.
+    ///
+    /// Unlike [`execute_graphql()`](Self::execute_graphql), which returns one
+    /// flat row per traversal, the result follows the query's selection set:
+    /// each nested field becomes a list of objects under its parent, and
+    /// parents without children get an empty list.
     ///
     /// # Errors
     ///
-    /// Returns an error if the query fails to parse or execute.
-    #[cfg(feature = "graphql")]
-    pub fn execute_graphql_with_params(
-        &self,
+    /// Returns an error if the query fails to parse or execute, or is not a
+    /// query with a selection set on its root field.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let json = session.execute_graphql_nested("query { person { name knows { name } } }")?;
+    /// // {"data":{"person":[{"name":"Alice","knows":[{"name":"Bob"}]}, ...]}}
+    /// ```
+    #[cfg(feature = "graphql")]
+    pub fn execute_graphql_nested(&self, query: &str) -> Result<String> {
+        use crate::query::graphql_translator;
+
+        let (logical_plan, shape) = graphql_translator::translate_nested(query)?;
+        self.execute_graphql_shaped(logical_plan, &shape)
+    }
+
+    /// Executes a GraphQL query against a typed schema and returns its
+    /// response as nested JSON.
+    ///
+    /// The schema decides which label each type reads, which edges each
+    /// field follows, and whether a field holds one object or a list.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or execute, or selects a
+    /// field or passes an argument the schema doesn't define.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let schema = GraphQLSchema::new()
+    ///     .with_type(ObjectType::new("Person").with_property("name"))
+    ///     .with_query_list("people", "Person");
+    /// let json = session.execute_graphql_with_schema(&schema, "{ people { name } }")?;
+    /// // {"data":{"people":[{"name":"Alice"}, ...]}}
+    /// ```
+    #[cfg(feature = "graphql")]
+    pub fn execute_graphql_with_schema(
+        &self,
+        schema: &crate::query::graphql_schema::GraphQLSchema,
         query: &str,
-        params: std::collections::HashMap<String, Value>,
-    ) -> Result<QueryResult> {
-        use crate::query::processor::{QueryLanguage, QueryProcessor};
+    ) -> Result<String> {
+        let (logical_plan, shape) = schema.resolve(query)?;
+        self.execute_graphql_shaped(logical_plan, &shape)
+    }
+
+    /// Runs a GraphQL plan and folds its rows into a `data` object.
+    #[cfg(feature = "graphql")]
+    fn execute_graphql_shaped(
+        &self,
+        logical_plan: crate::query::plan::LogicalPlan,
+        shape: &crate::format::ResultShape,
+    ) -> Result<String> {
+        use crate::query::binder::Binder;
 
-        // Get transaction context for MVCC visibility
-        let (viewing_epoch, tx_id) = self.get_transaction_context();
+        self.query_limits.check_plan(&logical_plan)?;
 
-        // Create processor with transaction context
-        let processor =
-            QueryProcessor::for_lpg_with_tx(Arc::clone(&self.store), Arc::clone(&self.tx_manager));
+        let mut binder = Binder::new();
+        let _binding_context = binder.bind(&logical_plan)?;
 
-        // Apply transaction context if in a transaction
-        let processor = if let Some(tx_id) = tx_id {
-            processor.with_tx_context(viewing_epoch, tx_id)
-        } else {
-            processor
-        };
+        let optimizer = self.optimizer();
+        let optimized_plan = optimizer.optimize(logical_plan)?;
 
-        processor.process(query, QueryLanguage::GraphQL, Some(&params))
+        let result = self.execute_plan(&optimized_plan)?;
+        Ok(format!(
+            "{{\"data\":{}}}",
+            result.to_nested_json(shape, &self.id_format)
+        ))
+    }
+
+    /// Executes a GraphQL query with parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or execute.
+    #[cfg(feature = "graphql")]
+    pub fn execute_graphql_with_params(
+        &self,
+        query: &str,
+        params: std::collections::HashMap<String, Value>,
+    ) -> Result<QueryResult> {
+        use crate::query::processor::QueryLanguage;
+
+        self.process_statement(query, QueryLanguage::GraphQL, &params)
     }
 
     /// Executes a SPARQL query.
@@ -425,18 +1201,20 @@ impl Session {
 
         // Parse and translate the SPARQL query to a logical plan
         let logical_plan = sparql_translator::translate(query)?;
+        self.query_limits.check_plan(&logical_plan)?;
 
         // Optimize the plan
-        let optimizer = Optimizer::new();
+        let optimizer = Optimizer::new().with_join_reorder(self.join_reorder);
         let optimized_plan = optimizer.optimize(logical_plan)?;
 
-        // Convert to physical plan using RDF planner
-        let planner = RdfPlanner::new(Arc::clone(&self.rdf_store)).with_tx_id(self.current_tx);
-        let mut physical_plan = planner.plan(&optimized_plan)?;
+        // Writes are buffered in the statement's transaction until it commits
+        self.run_statement(|_, tx_id| {
+            let planner = RdfPlanner::new(Arc::clone(&self.rdf_store)).with_tx_id(Some(tx_id));
+            let mut physical_plan = planner.plan(&optimized_plan)?;
 
-        // Execute the plan
-        let executor = Executor::with_columns(physical_plan.columns.clone());
-        executor.execute(physical_plan.operator.as_mut())
+            let executor = Executor::with_columns(physical_plan.columns.clone());
+            executor.execute_plan(&mut physical_plan)
+        })
     }
 
     /// Executes a SPARQL query with parameters.
@@ -475,6 +1253,32 @@ impl Session {
     /// session.commit()?; // Both inserts committed atomically
     /// ```
     pub fn begin_tx(&mut self) -> Result<()> {
+        self.begin_transaction_with(IsolationLevel::default())
+    }
+
+    /// Begins a transaction at the given isolation level.
+    ///
+    /// [`begin_tx`](Self::begin_tx) uses [`IsolationLevel::Snapshot`]. Pick
+    /// [`IsolationLevel::ReadCommitted`] when each statement should see the
+    /// latest commits, accepting that re-reading can give a different answer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a transaction is already active.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use grafeo_engine::{GrafeoDB, IsolationLevel};
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let mut session = db.session();
+    ///
+    /// session.begin_transaction_with(IsolationLevel::ReadCommitted)?;
+    /// session.execute("MATCH (n:Person) RETURN n.name")?;
+    /// session.commit()?;
+    /// ```
+    pub fn begin_transaction_with(&mut self, isolation: IsolationLevel) -> Result<()> {
         if self.current_tx.is_some() {
             return Err(grafeo_common::utils::error::Error::Transaction(
                 grafeo_common::utils::error::TransactionError::InvalidState(
@@ -483,7 +1287,7 @@ impl Session {
             ));
         }
 
-        let tx_id = self.tx_manager.begin();
+        let tx_id = self.tx_manager.begin_with_isolation(isolation);
         self.current_tx = Some(tx_id);
         Ok(())
     }
@@ -504,11 +1308,10 @@ impl Session {
             )
         })?;
 
-        // Commit RDF store pending operations
-        #[cfg(feature = "rdf")]
-        self.rdf_store.commit_tx(tx_id);
-
-        self.tx_manager.commit(tx_id).map(|_| ())
+        let start_epoch = self.tx_manager.start_epoch(tx_id);
+        let commit_epoch = self.commit_tx(tx_id)?;
+        self.last_commit = start_epoch.map(|start| (start, commit_epoch));
+        Ok(())
     }
 
     /// Aborts the current transaction.
@@ -551,117 +1354,1917 @@ impl Session {
         self.tx_manager.abort(tx_id)
     }
 
-    /// Returns whether a transaction is active.
-    #[must_use]
-    pub fn in_transaction(&self) -> bool {
-        self.current_tx.is_some()
+    /// Sets a savepoint in the current transaction.
+    ///
+    /// [`rollback_to`](Self::rollback_to) undoes what the transaction
+    /// writes after this point without giving up earlier work. Savepoints
+    /// end with the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no transaction is active.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let mut session = db.session();
+    ///
+    /// session.begin_tx()?;
+    /// session.execute("INSERT (:Person {name: 'Alice'})")?;
+    /// session.savepoint("before_bob")?;
+    /// session.execute("INSERT (:Person {name: 'Bob'})")?;
+    /// session.rollback_to("before_bob")?; // Bob is discarded, Alice stays
+    /// session.commit()?;
+    /// ```
+    pub fn savepoint(&self, name: &str) -> Result<()> {
+        let tx_id = self.active_tx()?;
+        let writes = self.store.tx_write_count(tx_id);
+        self.tx_manager
+            .savepoint(tx_id, name, SavepointMark { writes })
     }
 
-    /// Sets auto-commit mode.
-    pub fn set_auto_commit(&mut self, auto_commit: bool) {
-        self.auto_commit = auto_commit;
+    /// Undoes everything the current transaction wrote since the savepoint
+    /// `name`: created nodes and edges, property and label changes, and
+    /// deletes.
+    ///
+    /// Savepoints set after `name` are dropped; `name` itself stays, so you
+    /// can roll back to it again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no transaction is active or it has no savepoint
+    /// called `name`.
+    pub fn rollback_to(&self, name: &str) -> Result<()> {
+        let tx_id = self.active_tx()?;
+        let mark = self.tx_manager.rollback_to_savepoint(tx_id, name)?;
+        self.store.undo_writes_since(tx_id, mark.writes);
+        Ok(())
     }
 
-    /// Returns whether auto-commit is enabled.
-    #[must_use]
-    pub fn auto_commit(&self) -> bool {
-        self.auto_commit
+    /// Removes the savepoint `name`, and any set after it, keeping the
+    /// writes made since.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no transaction is active or it has no savepoint
+    /// called `name`.
+    pub fn release(&self, name: &str) -> Result<()> {
+        let tx_id = self.active_tx()?;
+        self.tx_manager.release_savepoint(tx_id, name)
     }
 
-    /// Returns the current transaction context for MVCC visibility.
+    /// Returns the current transaction, or an error if there isn't one.
+    fn active_tx(&self) -> Result<TxId> {
+        self.current_tx.ok_or_else(|| {
+            grafeo_common::utils::error::Error::Transaction(
+                grafeo_common::utils::error::TransactionError::InvalidState(
+                    "No active transaction".to_string(),
+                ),
+            )
+        })
+    }
+
+    /// Runs a batch of mixed writes as a single transaction.
     ///
-    /// Returns `(viewing_epoch, tx_id)` where:
-    /// - `viewing_epoch` is the epoch at which to check version visibility
-    /// - `tx_id` is the current transaction ID (if in a transaction)
-    #[must_use]
-    fn get_transaction_context(&self) -> (EpochId, Option<TxId>) {
-        if let Some(tx_id) = self.current_tx {
-            // In a transaction - use the transaction's start epoch
-            let epoch = self
-                .tx_manager
-                .start_epoch(tx_id)
-                .unwrap_or_else(|| self.tx_manager.current_epoch());
-            (epoch, Some(tx_id))
-        } else {
-            // No transaction - use current epoch
-            (self.tx_manager.current_epoch(), None)
+    /// The closure can create, update and delete nodes and edges; everything
+    /// commits together with one WAL flush, which is much cheaper than a
+    /// transaction per write. If the closure returns an error - or the commit
+    /// hits a write-write conflict - nothing from the batch is kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns the closure's error, a commit conflict, a WAL failure, or an
+    /// error if this session already has a transaction open.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let session = db.session();
+    ///
+    /// let outcome = session.batch(|batch| {
+    ///     let alice = batch.create_node(&["Person"])?;
+    ///     let bob = batch.create_node(&["Person"])?;
+    ///     batch.create_edge(alice, bob, "KNOWS")?;
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(outcome.created_nodes.len(), 2);
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    pub fn batch<F>(&self, ops: F) -> Result<BatchOutcome>
+    where
+        F: FnOnce(&mut Batch) -> Result<()>,
+    {
+        if self.current_tx.is_some() {
+            return Err(grafeo_common::utils::error::Error::Transaction(
+                grafeo_common::utils::error::TransactionError::InvalidState(
+                    "Cannot run a batch inside an open transaction".to_string(),
+                ),
+            ));
+        }
+
+        let mut batch = Batch::begin(Arc::clone(&self.store), Arc::clone(&self.tx_manager));
+        if let Some(catalog) = self.catalog.as_ref().filter(|_| self.strict_schema) {
+            batch = batch.with_strict_schema(Arc::clone(catalog));
+        }
+        match ops(&mut batch) {
+            Ok(()) => batch.commit(self.wal.as_deref(), &self.changes),
+            Err(e) => {
+                batch.rollback();
+                Err(e)
+            }
         }
     }
 
-    /// Creates a node directly (bypassing query execution).
+    /// Subscribes to the changes committed from now on.
     ///
-    /// This is a low-level API for testing and direct manipulation.
-    /// If a transaction is active, the node will be versioned with the transaction ID.
-    pub fn create_node(&self, labels: &[&str]) -> NodeId {
-        let (epoch, tx_id) = self.get_transaction_context();
-        self.store
-            .create_node_versioned(labels, epoch, tx_id.unwrap_or(TxId::SYSTEM))
+    /// The stream sees every committed write - direct API calls on the
+    /// database, batches, imports, and property graph queries in any
+    /// language, whether in an explicit transaction or on their own - in
+    /// commit order, from any session. RDF triples aren't included.
+    /// Writes are published once they're in the write-ahead log, and
+    /// rolled back ones never are.
+    ///
+    /// Commits never wait for a subscriber: one that falls
+    /// [`Config::change_buffer_size`](crate::Config::change_buffer_size)
+    /// changes behind misses the next ones and gets a
+    /// [`ChangeEvent::Overflow`](crate::ChangeEvent::Overflow) in their
+    /// place. Dropping the stream unsubscribes it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::{ChangeEvent, GrafeoDB};
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let session = db.session();
+    /// let mut changes = session.subscribe();
+    ///
+    /// let alice = db.create_node(&["Person"]);
+    /// let change = changes.try_next().unwrap();
+    /// assert_eq!(
+    ///     change.event,
+    ///     ChangeEvent::NodeCreated { id: alice, labels: vec!["Person".to_string()] }
+    /// );
+    /// ```
+    #[must_use]
+    pub fn subscribe(&self) -> ChangeStream {
+        self.changes.subscribe()
     }
 
-    /// Creates a node with properties.
+    /// Subscribes to the changes committed from `position` on, replaying
+    /// the ones already committed before new ones.
     ///
-    /// If a transaction is active, the node will be versioned with the transaction ID.
-    pub fn create_node_with_props<'a>(
-        &self,
-        labels: &[&str],
-        properties: impl IntoIterator<Item = (&'a str, Value)>,
-    ) -> NodeId {
-        let (epoch, tx_id) = self.get_transaction_context();
-        self.store.create_node_with_props_versioned(
-            labels,
-            properties.into_iter().map(|(k, v)| (k, v)),
-            epoch,
-            tx_id.unwrap_or(TxId::SYSTEM),
-        )
+    /// Pass the position after the last change seen to resume where a
+    /// previous stream stopped. Only the last
+    /// [`Config::change_history_size`](crate::Config::change_history_size)
+    /// changes are retained.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `position` hasn't been reached yet, or if the
+    /// changes from it are no longer retained.
+    pub fn subscribe_from(&self, position: u64) -> Result<ChangeStream> {
+        self.changes.subscribe_from(position)
     }
 
-    /// Creates an edge between two nodes.
+    /// Position the next committed change will get.
+    #[must_use]
+    pub fn change_position(&self) -> u64 {
+        self.changes.position()
+    }
+
+    /// Loads nodes and edges from many CSV or JSON Lines files at once.
     ///
-    /// This is a low-level API for testing and direct manipulation.
-    /// If a transaction is active, the edge will be versioned with the transaction ID.
-    pub fn create_edge(
+    /// Node files are read in parallel first, building an index from each
+    /// row's `id` key to the created node; edge files are then read in
+    /// parallel, resolving their `source` and `target` keys through it. See
+    /// the [`import`](crate::import) module for the file layouts.
+    ///
+    /// Rows that can't be loaded (malformed, a duplicate node key, an edge
+    /// to an unknown key, a name not declared in strict schema mode) are
+    /// skipped and listed in the returned report
+    /// under the file they came from. The rows that load are committed
+    /// together as one transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a transaction is open, or if committing the
+    /// import fails.
+    pub fn import_parallel<P: AsRef<Path> + Sync>(
         &self,
-        src: NodeId,
-        dst: NodeId,
-        edge_type: &str,
-    ) -> grafeo_common::types::EdgeId {
-        let (epoch, tx_id) = self.get_transaction_context();
-        self.store
-            .create_edge_versioned(src, dst, edge_type, epoch, tx_id.unwrap_or(TxId::SYSTEM))
+        node_files: &[P],
+        edge_files: &[P],
+    ) -> Result<ImportReport> {
+        self.import_parallel_with(node_files, edge_files, &ImportOptions::default())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::database::GrafeoDB;
-
-    #[test]
-    fn test_session_create_node() {
-        let db = GrafeoDB::new_in_memory();
-        let session = db.session();
 
-        let id = session.create_node(&["Person"]);
-        assert!(id.is_valid());
-        assert_eq!(db.node_count(), 1);
+    /// Like [`import_parallel()`](Self::import_parallel), with options such
+    /// as deterministic id assignment.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a transaction is open, or if committing the
+    /// import fails.
+    pub fn import_parallel_with<P: AsRef<Path> + Sync>(
+        &self,
+        node_files: &[P],
+        edge_files: &[P],
+        options: &ImportOptions,
+    ) -> Result<ImportReport> {
+        self.run_import(|target| {
+            crate::import::import_files(target, node_files, edge_files, options)
+        })
     }
 
-    #[test]
-    fn test_session_transaction() {
-        let db = GrafeoDB::new_in_memory();
-        let mut session = db.session();
-
-        assert!(!session.in_transaction());
+    /// Loads nodes from a CSV or JSON Lines stream, giving each the label
+    /// `label` on top of any in its `labels` field.
+    ///
+    /// Rows are batched into chunks and created by parallel workers without
+    /// going through the query planner. Each row's `id` key is remembered so
+    /// later [`import_edges()`](Self::import_edges) calls can link to it;
+    /// a key that's already taken fails its row. See the
+    /// [`import`](crate::import) module for the layouts.
+    ///
+    /// The stream is read a line at a time, and rows are created in batches
+    /// as they're read. Rows that can't be loaded are skipped and listed in
+    /// the report; the rest are committed together as one transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and loads nothing, if a transaction is open, if
+    /// the stream can't be read as UTF-8 text, or if committing the import
+    /// fails.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let csv = "id,name,age:int\np1,Alice,30\np2,Bob,25\n";
+    /// let report = session.import_nodes("Person", csv.as_bytes(), ImportFormat::Csv)?;
+    /// assert_eq!(report.loaded, 2);
+    /// ```
+    pub fn import_nodes<R: Read>(
+        &self,
+        label: &str,
+        reader: R,
+        format: ImportFormat,
+    ) -> Result<LoadReport> {
+        self.import_nodes_with(label, reader, format, &ImportOptions::default())
+    }
 
-        session.begin_tx().unwrap();
-        assert!(session.in_transaction());
+    /// Like [`import_nodes()`](Self::import_nodes), with options such as
+    /// declared property types.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and loads nothing, if a transaction is open, if
+    /// the stream can't be read as UTF-8 text, or if committing the import
+    /// fails.
+    pub fn import_nodes_with<R: Read>(
+        &self,
+        label: &str,
+        reader: R,
+        format: ImportFormat,
+        options: &ImportOptions,
+    ) -> Result<LoadReport> {
+        let (report, keys) = self.run_import(|target| {
+            crate::import::import_node_stream(
+                target,
+                &self.import_keys,
+                label,
+                BufReader::new(reader),
+                format,
+                options,
+            )
+        })?;
+        // Only committed nodes can be linked to
+        for (key, id) in keys {
+            self.import_keys.insert(key, id);
+        }
+        Ok(report)
+    }
 
-        session.commit().unwrap();
-        assert!(!session.in_transaction());
+    /// Loads edges of type `edge_type` from a CSV or JSON Lines stream.
+    ///
+    /// Each row's `source` and `target` keys are looked up in the index of
+    /// keys loaded by [`import_nodes()`](Self::import_nodes). A row with its
+    /// own `type` field uses that type instead.
+    ///
+    /// Rows that can't be loaded, including those naming an unknown key, are
+    /// skipped and listed in the report. The stream is read like
+    /// [`import_nodes()`](Self::import_nodes) reads one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and loads nothing, if a transaction is open, if
+    /// the stream can't be read as UTF-8 text, or if committing the import
+    /// fails.
+    pub fn import_edges<R: Read>(
+        &self,
+        edge_type: &str,
+        reader: R,
+        format: ImportFormat,
+    ) -> Result<LoadReport> {
+        self.import_edges_with(edge_type, reader, format, &ImportOptions::default())
     }
 
-    #[test]
-    fn test_session_transaction_context() {
-        let db = GrafeoDB::new_in_memory();
+    /// Like [`import_edges()`](Self::import_edges), with options such as
+    /// declared property types.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, and loads nothing, if a transaction is open, if
+    /// the stream can't be read as UTF-8 text, or if committing the import
+    /// fails.
+    pub fn import_edges_with<R: Read>(
+        &self,
+        edge_type: &str,
+        reader: R,
+        format: ImportFormat,
+        options: &ImportOptions,
+    ) -> Result<LoadReport> {
+        self.run_import(|target| {
+            crate::import::import_edge_stream(
+                target,
+                &self.import_keys,
+                edge_type,
+                BufReader::new(reader),
+                format,
+                options,
+            )
+        })
+    }
+
+    /// Writes the whole graph to `writer` as GraphML or node-link JSON.
+    ///
+    /// Nodes are written first, then edges, all from the snapshot current
+    /// when the export starts; they're read one at a time rather than
+    /// collected up front. See the [`export`](crate::export) module for how
+    /// labels, types, and property values are laid out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing fails.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut file = std::fs::File::create("graph.graphml")?;
+    /// session.export(&mut file, ExportFormat::GraphMl)?;
+    /// ```
+    pub fn export<W: Write>(&self, writer: W, format: ExportFormat) -> Result<()> {
+        let mut writer = BufWriter::new(writer);
+        crate::export::export(&self.store, self.store.current_epoch(), &mut writer, format)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Runs an import in a transaction of its own, refusing while one is
+    /// already open.
+    fn run_import<T>(&self, import: impl FnOnce(&ImportTarget<'_>) -> Result<T>) -> Result<T> {
+        if self.current_tx.is_some() {
+            return Err(grafeo_common::utils::error::Error::Transaction(
+                grafeo_common::utils::error::TransactionError::InvalidState(
+                    "Cannot import inside an open transaction".to_string(),
+                ),
+            ));
+        }
+        self.run_in_own_tx(|_, tx_id| {
+            import(&ImportTarget {
+                store: &self.store,
+                tx_id,
+                schema: self.catalog.as_deref().filter(|_| self.strict_schema),
+            })
+        })
+    }
+
+    /// Ranks nodes with PageRank, in node id order.
+    ///
+    /// Runs over the whole graph, or only the nodes with
+    /// [`label`](PageRankConfig::label) and edges of
+    /// [`edge_type`](PageRankConfig::edge_type) when set. Ranks sum to 1:
+    /// nodes without out-edges spread theirs evenly over every node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the damping factor is outside `[0, 1]` or the
+    /// tolerance is negative.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let config = PageRankConfig::new().with_label("Page").with_damping(0.9);
+    /// for (node, rank) in session.page_rank(config)? {
+    ///     println!("{node:?}: {rank:.4}");
+    /// }
+    /// ```
+    pub fn page_rank(&self, config: PageRankConfig) -> Result<Vec<(NodeId, f64)>> {
+        crate::algorithms::page_rank(&self.store, &config)
+    }
+
+    /// Labels each node with its weakly connected component.
+    ///
+    /// Edge direction is ignored, so two nodes share a component when any
+    /// chain of edges links them. Components are numbered from 0 in order of
+    /// their smallest node id; an isolated node gets one of its own.
+    #[must_use]
+    pub fn connected_components(&self) -> HashMap<NodeId, u64> {
+        self.connected_components_with(&ComponentsConfig::default())
+    }
+
+    /// Like [`connected_components()`](Self::connected_components), limited
+    /// to a label or edge type.
+    #[must_use]
+    pub fn connected_components_with(&self, config: &ComponentsConfig) -> HashMap<NodeId, u64> {
+        crate::algorithms::connected_components(&self.store, config)
+    }
+
+    /// Labels each node with its strongly connected component.
+    ///
+    /// Two nodes share a component when each can reach the other along
+    /// edge directions. Numbered like
+    /// [`connected_components()`](Self::connected_components).
+    #[must_use]
+    pub fn strongly_connected_components(&self) -> HashMap<NodeId, u64> {
+        self.strongly_connected_components_with(&ComponentsConfig::default())
+    }
+
+    /// Like [`strongly_connected_components()`](Self::strongly_connected_components),
+    /// limited to a label or edge type.
+    #[must_use]
+    pub fn strongly_connected_components_with(
+        &self,
+        config: &ComponentsConfig,
+    ) -> HashMap<NodeId, u64> {
+        crate::algorithms::strongly_connected_components(&self.store, config)
+    }
+
+    /// Finds the shortest path from `from` to `to`.
+    ///
+    /// Without a weight property this is a breadth-first search for the
+    /// fewest edges; with one it's Dijkstra's algorithm over the property's
+    /// values. Returns `None` when `to` can't be reached within the hop limit.
+    /// Queries reach the same search through `shortestPath((a)-[*]->(b))`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either node doesn't exist, or if the search
+    /// reaches an edge whose weight is missing, non-numeric or negative.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let opts = PathOpts::new().with_weight("km").with_max_hops(10);
+    /// if let Some(path) = session.shortest_path(from, to, opts)? {
+    ///     println!("{} km via {:?}", path.cost, path.nodes);
+    /// }
+    /// ```
+    pub fn shortest_path(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        opts: PathOpts,
+    ) -> Result<Option<crate::algorithms::Path>> {
+        crate::algorithms::shortest_path(&self.store, from, to, &opts)
+    }
+
+    /// Projects the nodes and edges passing the given filters into a named,
+    /// cached subgraph.
+    ///
+    /// Run algorithms against the projection's
+    /// [`graph()`](GraphProjection::graph) to work on the subgraph without
+    /// filtering the whole graph each time. Edges are only offered to
+    /// `edge_filter` when both endpoints passed `node_filter`. A projection
+    /// with the same name is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let people = session.project_graph(
+    ///     "people",
+    ///     |node| node.labels.iter().any(|l| l.as_ref() == "Person"),
+    ///     |edge| edge.edge_type.as_ref() == "KNOWS",
+    /// );
+    /// let ranks = pagerank(people.graph(), 0.85, 20, 1e-6);
+    /// ```
+    pub fn project_graph<N, E>(
+        &mut self,
+        name: &str,
+        node_filter: N,
+        edge_filter: E,
+    ) -> Arc<GraphProjection>
+    where
+        N: Fn(&Node) -> bool + Send + Sync + 'static,
+        E: Fn(&Edge) -> bool + Send + Sync + 'static,
+    {
+        let projection = Arc::new(GraphProjection::build(
+            name,
+            Arc::clone(&self.store),
+            Arc::new(node_filter),
+            Arc::new(edge_filter),
+        ));
+        self.projections
+            .insert(name.to_string(), Arc::clone(&projection));
+        projection
+    }
+
+    /// Returns the named graph projection.
+    ///
+    /// If the graph changed since the projection was built, it's projected
+    /// again first, so the result always reflects the current graph.
+    pub fn graph_projection(&mut self, name: &str) -> Option<Arc<GraphProjection>> {
+        let projection = self.projections.get_mut(name)?;
+        if projection.is_stale() {
+            *projection = Arc::new(projection.rebuild());
+        }
+        Some(Arc::clone(projection))
+    }
+
+    /// Drops the named graph projection, returning whether it existed.
+    pub fn drop_graph_projection(&mut self, name: &str) -> bool {
+        self.projections.remove(name).is_some()
+    }
+
+    /// Returns whether a transaction is active.
+    #[must_use]
+    pub fn in_transaction(&self) -> bool {
+        self.current_tx.is_some()
+    }
+
+    /// Returns the snapshot epoch of the active transaction, if any.
+    ///
+    /// The transaction sees everything committed at or before this epoch.
+    #[must_use]
+    pub fn tx_start_epoch(&self) -> Option<EpochId> {
+        self.current_tx
+            .and_then(|tx_id| self.tx_manager.start_epoch(tx_id))
+    }
+
+    /// Returns the start epoch of the last transaction this session committed.
+    #[must_use]
+    pub fn last_start_epoch(&self) -> Option<EpochId> {
+        self.last_commit.map(|(start, _)| start)
+    }
+
+    /// Returns the commit epoch of the last transaction this session committed.
+    ///
+    /// Commit epochs come from the same counter as MVCC visibility, so they
+    /// increase across commits and a transaction starting at or after this
+    /// epoch sees the committed changes. Only explicit transactions
+    /// ([`begin_tx`](Self::begin_tx) / [`commit`](Self::commit)) record one.
+    #[must_use]
+    pub fn last_commit_epoch(&self) -> Option<EpochId> {
+        self.last_commit.map(|(_, commit)| commit)
+    }
+
+    /// Sets auto-commit mode.
+    pub fn set_auto_commit(&mut self, auto_commit: bool) {
+        self.auto_commit = auto_commit;
+    }
+
+    /// Returns whether auto-commit is enabled.
+    #[must_use]
+    pub fn auto_commit(&self) -> bool {
+        self.auto_commit
+    }
+
+    /// Sets how [`run`](Self::run) returns results for subsequent queries.
+    pub fn set_result_mode(&mut self, mode: ResultMode) {
+        self.result_mode = mode;
+    }
+
+    /// Returns how [`run`](Self::run) returns results.
+    #[must_use]
+    pub fn result_mode(&self) -> ResultMode {
+        self.result_mode
+    }
+
+    /// Sets how long subsequent queries may run before they're stopped,
+    /// or `None` to let them run to completion.
+    ///
+    /// Starts out as [`Config::query_timeout`](crate::Config::query_timeout).
+    /// [`QueryOptions::with_timeout`] overrides it for a single call.
+    pub fn set_query_timeout(&mut self, timeout: Option<Duration>) {
+        self.query_timeout = timeout;
+    }
+
+    /// Returns how long queries may run before they're stopped.
+    #[must_use]
+    pub fn query_timeout(&self) -> Option<Duration> {
+        self.query_timeout
+    }
+
+    /// Sets the string collation for subsequent queries in this session.
+    ///
+    /// Affects `ORDER BY` and range comparisons (`<`, `>`, ...) on strings.
+    /// Use [`Collation::Unicode`] for user-facing names and titles.
+    pub fn set_collation(&mut self, collation: Collation) {
+        self.collation = collation;
+    }
+
+    /// Sets how node and edge ids are written when rendering results.
+    ///
+    /// Ids stay integers in [`QueryResult`] itself; pass
+    /// [`id_format()`](Self::id_format) to [`QueryResult::to_json`],
+    /// [`to_csv`](QueryResult::to_csv), or [`to_text`](QueryResult::to_text).
+    pub fn set_id_format(&mut self, id_format: IdFormat) {
+        self.id_format = id_format;
+    }
+
+    /// Returns how this session renders node and edge ids.
+    #[must_use]
+    pub fn id_format(&self) -> &IdFormat {
+        &self.id_format
+    }
+
+    /// Sets where this session's queries spill to disk once they run out
+    /// of memory.
+    ///
+    /// Overrides [`Config::spill_path`](crate::Config::spill_path) for this
+    /// session only. The directory is created on first spill, and spilled
+    /// files are removed when the query finishes.
+    pub fn set_spill_path(&mut self, path: impl Into<PathBuf>) {
+        self.spill_path = Some(path.into());
+    }
+
+    /// Returns the directory this session's queries spill to, if any.
+    ///
+    /// This is the session's own spill path, falling back to the database's.
+    #[must_use]
+    pub fn spill_path(&self) -> Option<&Path> {
+        self.spill_path.as_deref().or_else(|| {
+            self.memory_budget
+                .as_ref()
+                .and_then(|(manager, _)| manager.config().spill_path.as_deref())
+        })
+    }
+
+    /// Registers a user-defined scalar function that this session's queries
+    /// can call by name, like `RETURN score(p.rating, p.reviews)`.
+    ///
+    /// Names are case-insensitive, and registering a name again replaces the
+    /// earlier function. Calls with the wrong number of arguments fail to
+    /// plan.
+    ///
+    /// The function must be pure: given the same arguments it returns the
+    /// same result, without side effects. Calls with constant arguments are
+    /// evaluated once while planning, so an error returned for those fails
+    /// the query. For arguments that vary per row an error yields NULL, the
+    /// way built-in functions treat arguments of the wrong type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is a built-in function.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    /// use grafeo_common::types::Value;
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let mut session = db.session();
+    /// session.register_udf(
+    ///     "double",
+    ///     1,
+    ///     Arc::new(|args: &[Value]| match &args[0] {
+    ///         Value::Int64(n) => Ok(Value::Int64(n * 2)),
+    ///         _ => Ok(Value::Null),
+    ///     }),
+    /// )?;
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    pub fn register_udf(
+        &mut self,
+        name: &str,
+        arity: usize,
+        function: Arc<dyn Fn(&[Value]) -> Result<Value> + Send + Sync>,
+    ) -> Result<()> {
+        if is_builtin_function(name) {
+            return Err(Error::Query(QueryError::new(
+                QueryErrorKind::Semantic,
+                format!("{name}() is a built-in function"),
+            )));
+        }
+        Arc::make_mut(&mut self.udfs)
+            .insert(name.to_lowercase(), ScalarUdf::new(name, arity, function));
+        Ok(())
+    }
+
+    /// Returns the string collation used by this session.
+    #[must_use]
+    pub fn collation(&self) -> Collation {
+        self.collation
+    }
+
+    /// Runs subsequent queries as of a point in time, or clears it with
+    /// `None`.
+    ///
+    /// Traversals then only follow edges whose validity interval contains
+    /// `at` (see [`GrafeoDB::set_edge_validity`](crate::GrafeoDB::set_edge_validity)).
+    /// Edges without an interval are always followed. As-of queries bypass
+    /// the result cache.
+    pub fn set_as_of(&mut self, at: Option<Timestamp>) {
+        self.as_of = at;
+    }
+
+    /// Returns the time this session's queries run as of, if any.
+    #[must_use]
+    pub fn as_of(&self) -> Option<Timestamp> {
+        self.as_of
+    }
+
+    /// Attaches the database's idempotency key store.
+    pub(crate) fn set_idempotency_store(&mut self, store: Arc<IdempotencyStore>) {
+        self.idempotency = Some(store);
+    }
+
+    /// Attaches the database's index of streamed-in node keys.
+    pub(crate) fn set_import_keys(&mut self, keys: Arc<HashIndex<String, NodeId>>) {
+        self.import_keys = keys;
+    }
+
+    /// Attaches the database's feed of committed changes.
+    pub(crate) fn set_change_feed(&mut self, changes: Arc<ChangeFeed>) {
+        self.changes = changes;
+    }
+
+    /// Refreshes statistics before planning once they've gone stale by
+    /// `ratio`.
+    pub(crate) fn set_statistics_refresh_ratio(&mut self, ratio: f64) {
+        self.statistics_refresh_ratio = Some(ratio);
+    }
+
+    /// Sets whether the optimizer reorders joins by estimated cost.
+    pub(crate) fn set_join_reorder(&mut self, enabled: bool) {
+        self.join_reorder = enabled;
+    }
+
+    /// Sets how many worker threads a query may use.
+    pub(crate) fn set_threads(&mut self, threads: usize) {
+        self.threads = threads;
+    }
+
+    /// Sets the limits that reject overly complex queries.
+    pub(crate) fn set_query_limits(&mut self, limits: QueryLimits) {
+        self.query_limits = limits;
+    }
+
+    /// Returns the limits that reject overly complex queries.
+    pub(crate) fn query_limits(&self) -> &QueryLimits {
+        &self.query_limits
+    }
+
+    /// Attaches the database's query result cache.
+    pub(crate) fn set_result_cache(&mut self, cache: Arc<ResultCache>) {
+        self.result_cache = Some(cache);
+    }
+
+    /// Attaches the database catalog.
+    pub(crate) fn set_catalog(&mut self, catalog: Arc<Catalog>) {
+        self.catalog = Some(catalog);
+    }
+
+    /// Attaches the database's write-ahead log.
+    pub(crate) fn set_wal(&mut self, wal: Arc<WalManager>) {
+        self.wal = Some(wal);
+    }
+
+    /// Enables strict schema validation against the session's catalog.
+    pub(crate) fn set_strict_schema(&mut self) {
+        self.strict_schema = true;
+    }
+
+    /// Charges query operators to a memory budget, accounted every
+    /// `granularity` bytes.
+    pub(crate) fn set_memory_budget(&mut self, manager: Arc<BufferManager>, granularity: usize) {
+        self.memory_budget = Some((manager, granularity));
+    }
+
+    /// Sets what a write that repeats a property key does.
+    pub(crate) fn set_duplicate_keys(&mut self, policy: DuplicateKeyPolicy) {
+        self.duplicate_keys = policy;
+    }
+
+    /// Makes the given operator kinds fail instead of spilling.
+    pub(crate) fn set_spill_disabled(&mut self, operators: &[SpillableOperator]) {
+        self.spill_disabled = operators.to_vec();
+    }
+
+    /// Returns the version of the session's catalog, or 0 without one.
+    pub(crate) fn catalog_version(&self) -> u64 {
+        self.catalog.as_ref().map_or(0, |catalog| catalog.version())
+    }
+
+    /// Plans and runs an optimized logical plan in the session's
+    /// transaction context.
+    pub(crate) fn execute_plan(&self, plan: &LogicalPlan) -> Result<QueryResult> {
+        use crate::query::Executor;
+
+        self.run_statement(|viewing_epoch, tx_id| {
+            let planner = Planner::with_context(
+                Arc::clone(&self.store),
+                Arc::clone(&self.tx_manager),
+                Some(tx_id),
+                viewing_epoch,
+            )
+            .with_collation(self.collation);
+            let planner = self.configure_planner(planner);
+            let mut physical_plan = planner.plan(plan)?;
+
+            let executor = Executor::with_columns(physical_plan.columns.clone());
+            executor.execute_plan(&mut physical_plan)
+        })
+    }
+
+    /// Builds the optimizer for this session's property graph queries.
+    ///
+    /// Once statistics have been collected, the optimizer estimates from
+    /// them, so joins are ordered by real label and edge type sizes.
+    pub(crate) fn optimizer(&self) -> Optimizer {
+        if let Some(ratio) = self.statistics_refresh_ratio {
+            self.store.refresh_statistics_if_stale(ratio);
+        }
+        let optimizer = Optimizer::new().with_join_reorder(self.join_reorder);
+        let statistics = self.store.statistics();
+        if statistics.total_nodes == 0 {
+            return optimizer;
+        }
+        optimizer.with_cardinality_estimator(CardinalityEstimator::from_statistics(&statistics))
+    }
+
+    /// Passes the session's catalog, schema mode, memory budget, spill
+    /// path, and thread count on to a planner.
+    #[allow(dead_code)]
+    fn configure_planner(&self, planner: Planner) -> Planner {
+        // The planner sizes hash tables from statistics, so bring them up to
+        // date first
+        if let Some(ratio) = self.statistics_refresh_ratio {
+            self.store.refresh_statistics_if_stale(ratio);
+        }
+        let planner = match &self.memory_budget {
+            Some((manager, granularity)) => {
+                planner.with_memory_budget(Arc::clone(manager), *granularity)
+            }
+            None => planner,
+        };
+        let planner = match &self.spill_path {
+            Some(path) => planner.with_spill_path(path.clone()),
+            None => planner,
+        };
+        let planner = self
+            .spill_disabled
+            .iter()
+            .fold(planner, |planner, &operator| {
+                planner.with_spill_disabled(operator)
+            });
+        let planner = planner
+            .with_duplicate_keys(self.duplicate_keys)
+            .with_udfs(Arc::clone(&self.udfs))
+            .with_parallelism(self.threads);
+        let planner = match self.as_of {
+            Some(at) => planner.with_as_of(at),
+            None => planner,
+        };
+        let Some(catalog) = &self.catalog else {
+            return planner;
+        };
+        let planner = planner.with_catalog(Arc::clone(catalog));
+        if self.strict_schema {
+            planner.with_strict_schema()
+        } else {
+            planner
+        }
+    }
+
+    /// Answers a query from the result cache if the graph hasn't changed
+    /// since it was cached, and runs it otherwise.
+    ///
+    /// Queries inside an explicit transaction see uncommitted writes, and
+    /// as-of queries see a different graph, so both bypass the cache. So do
+    /// sessions with UDFs, since other sessions may define the same names
+    /// differently. Results are only cached when the graph didn't
+    /// change while the query ran - that keeps out queries that write, and
+    /// reads racing a concurrent write.
+    #[allow(dead_code)]
+    fn cached_result(
+        &self,
+        query: &str,
+        language: crate::query::processor::QueryLanguage,
+        params: Option<&HashMap<String, Value>>,
+        run: impl FnOnce() -> Result<QueryResult>,
+    ) -> Result<QueryResult> {
+        use crate::query::{CacheKey, ResultCacheKey};
+
+        let Some(cache) = self
+            .result_cache
+            .as_ref()
+            .filter(|_| self.current_tx.is_none() && self.as_of.is_none() && self.udfs.is_empty())
+        else {
+            return run();
+        };
+        let key = ResultCacheKey::new(CacheKey::new(query, language), params);
+        let version = self.store.change_count();
+        if let Some(result) = cache.get(&key, version) {
+            return Ok(result);
+        }
+
+        let result = run()?;
+        if self.store.change_count() == version {
+            cache.put(key, version, result.clone());
+        }
+        Ok(result)
+    }
+
+    /// Passes the session's catalog, schema mode, memory budget, and spill
+    /// path on to a query processor.
+    #[allow(dead_code)]
+    fn configure_processor(&self, processor: QueryProcessor) -> QueryProcessor {
+        let processor = processor
+            .with_limits(self.query_limits)
+            .with_optimizer(self.optimizer());
+        let processor = match &self.memory_budget {
+            Some((manager, granularity)) => {
+                processor.with_memory_budget(Arc::clone(manager), *granularity)
+            }
+            None => processor,
+        };
+        let processor = match &self.spill_path {
+            Some(path) => processor.with_spill_path(path.clone()),
+            None => processor,
+        };
+        let processor = self
+            .spill_disabled
+            .iter()
+            .fold(processor, |processor, &operator| {
+                processor.with_spill_disabled(operator)
+            });
+        let processor = processor
+            .with_duplicate_keys(self.duplicate_keys)
+            .with_udfs(Arc::clone(&self.udfs));
+        let processor = match self.as_of {
+            Some(at) => processor.with_as_of(at),
+            None => processor,
+        };
+        let Some(catalog) = &self.catalog else {
+            return processor;
+        };
+        let processor = processor.with_catalog(Arc::clone(catalog));
+        if self.strict_schema {
+            processor.with_strict_schema()
+        } else {
+            processor
+        }
+    }
+
+    /// Suggests indexes for a representative set of GQL queries.
+    ///
+    /// Each query is translated to a logical plan but not executed. Label
+    /// scans filtered on a property, and join keys between labeled nodes,
+    /// become candidates; properties that already have an index in the
+    /// catalog are left out. Nothing is created - this only advises.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any query fails to parse.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let recs = session.recommend_indexes(&[
+    ///     "MATCH (p:Person) WHERE p.email = $email RETURN p",
+    /// ])?;
+    /// assert_eq!(recs[0].property, "email");
+    /// ```
+    #[cfg(feature = "gql")]
+    pub fn recommend_indexes(
+        &self,
+        workload: &[&str],
+    ) -> Result<Vec<crate::query::IndexRecommendation>> {
+        use crate::query::{advisor::IndexAdvisor, gql_translator};
+
+        let mut advisor = IndexAdvisor::new();
+        if let Some(catalog) = &self.catalog {
+            advisor = advisor.with_catalog(Arc::clone(catalog));
+        }
+        for query in workload {
+            advisor.add_plan(&gql_translator::translate_with_max_depth(
+                query,
+                self.query_limits.max_depth,
+            )?);
+        }
+        Ok(advisor.recommendations())
+    }
+
+    /// Estimates how large a GQL query's result will be without running it.
+    ///
+    /// The query is translated and optimized, then its output cardinality is
+    /// estimated from the store's label counts and average fanout. Statistics
+    /// are recomputed first if the node count has drifted since they were
+    /// last collected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or bind.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let estimate = session.estimate_result_size("MATCH (n:Person) RETURN n")?;
+    /// if estimate.bytes > 64 * 1024 * 1024 {
+    ///     println!("this will return about {} rows", estimate.rows);
+    /// }
+    /// ```
+    #[cfg(feature = "gql")]
+    pub fn estimate_result_size(&self, query: &str) -> Result<ResultSizeEstimate> {
+        use crate::query::plan::LogicalOperator;
+
+        let (optimizer, optimized_plan) = self.optimize_with_statistics(query)?;
+        let rows = optimizer
+            .estimate_cardinality(&optimized_plan)
+            .max(0.0)
+            .round() as u64;
+        let columns = match &optimized_plan.root {
+            LogicalOperator::Return(ret) => ret.items.len(),
+            _ => 1,
+        };
+        Ok(ResultSizeEstimate::new(rows, columns))
+    }
+
+    /// Shows the plan the optimizer picks for a GQL query, with the rows
+    /// and cost it estimates for each operator.
+    ///
+    /// The query is planned against current graph statistics but never run.
+    /// Print the result for an indented tree, or call
+    /// [`PlanExplanation::to_json()`](crate::query::PlanExplanation::to_json)
+    /// for tooling.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or bind.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let plan = session.explain("MATCH (a:Person)-[:KNOWS]->(b) RETURN b")?;
+    /// println!("{plan}");
+    /// ```
+    #[cfg(feature = "gql")]
+    pub fn explain(&self, query: &str) -> Result<crate::query::PlanExplanation> {
+        let (optimizer, optimized_plan) = self.optimize_with_statistics(query)?;
+        Ok(optimizer.explain(&optimized_plan))
+    }
+
+    /// Runs a GQL query and shows the operators it ran, each with the
+    /// optimizer's estimates next to the rows it produced, the time it
+    /// took, and the peak memory the query held meanwhile.
+    ///
+    /// Time and memory include an operator's inputs. The query's rows are
+    /// thrown away, but its writes are not. `EXPLAIN ANALYZE <query>`
+    /// returns the same through [`execute()`](Self::execute), as rows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the query fails to parse or execute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use grafeo_engine::GrafeoDB;
+    ///
+    /// let db = GrafeoDB::new_in_memory();
+    /// let session = db.session();
+    /// session.execute("INSERT (:Person {name: 'Alice'})")?;
+    ///
+    /// let plan = session.explain_analyze("MATCH (p:Person) RETURN p.name")?;
+    /// assert_eq!(plan.root.actual.map(|stats| stats.rows), Some(1));
+    /// # Ok::<(), grafeo_common::utils::error::Error>(())
+    /// ```
+    #[cfg(feature = "gql")]
+    pub fn explain_analyze(&self, query: &str) -> Result<PlanExplanation> {
+        let interrupt = QueryOptions::default().interrupt(self.query_timeout);
+        self.explain_plan(query, true, &interrupt)
+    }
+
+    /// Translates, binds, and optimizes a GQL query against the store's
+    /// current statistics, returning the optimizer used alongside the plan.
+    #[cfg(feature = "gql")]
+    fn optimize_with_statistics(
+        &self,
+        query: &str,
+    ) -> Result<(crate::query::Optimizer, LogicalPlan)> {
+        use crate::query::{binder::Binder, gql_translator};
+
+        let logical_plan =
+            gql_translator::translate_with_max_depth(query, self.query_limits.max_depth)?;
+        self.query_limits.check_plan(&logical_plan)?;
+        let mut binder = Binder::new();
+        let _binding_context = binder.bind(&logical_plan)?;
+
+        if self.store.statistics().total_nodes != self.store.node_count() as u64 {
+            self.store.compute_statistics();
+        }
+        let optimizer = self.optimizer();
+        let optimized_plan = optimizer.optimize(logical_plan)?;
+        Ok((optimizer, optimized_plan))
+    }
+
+    /// Returns the current transaction context for MVCC visibility.
+    ///
+    /// Returns `(viewing_epoch, tx_id)` where:
+    /// - `viewing_epoch` is the epoch at which to check version visibility
+    /// - `tx_id` is the current transaction ID (if in a transaction)
+    #[must_use]
+    fn get_transaction_context(&self) -> (EpochId, Option<TxId>) {
+        if let Some(tx_id) = self.current_tx {
+            // In a transaction - read at the epoch its isolation level calls for
+            let epoch = self
+                .tx_manager
+                .read_epoch(tx_id)
+                .unwrap_or_else(|| self.tx_manager.current_epoch());
+            (epoch, Some(tx_id))
+        } else {
+            // No transaction - use current epoch
+            (self.tx_manager.current_epoch(), None)
+        }
+    }
+
+    /// Creates a node directly (bypassing query execution).
+    ///
+    /// This is a low-level API for testing and direct manipulation.
+    /// If a transaction is active, the node will be versioned with the transaction ID.
+    pub fn create_node(&self, labels: &[&str]) -> NodeId {
+        let (epoch, tx_id) = self.get_transaction_context();
+        self.store
+            .create_node_versioned(labels, epoch, tx_id.unwrap_or(TxId::SYSTEM))
+    }
+
+    /// Creates a node with properties.
+    ///
+    /// If a transaction is active, the node will be versioned with the transaction ID.
+    pub fn create_node_with_props<'a>(
+        &self,
+        labels: &[&str],
+        properties: impl IntoIterator<Item = (&'a str, Value)>,
+    ) -> NodeId {
+        let (epoch, tx_id) = self.get_transaction_context();
+        self.store.create_node_with_props_versioned(
+            labels,
+            properties.into_iter().map(|(k, v)| (k, v)),
+            epoch,
+            tx_id.unwrap_or(TxId::SYSTEM),
+        )
+    }
+
+    /// Creates an edge between two nodes.
+    ///
+    /// This is a low-level API for testing and direct manipulation.
+    /// If a transaction is active, the edge will be versioned with the transaction ID.
+    pub fn create_edge(
+        &self,
+        src: NodeId,
+        dst: NodeId,
+        edge_type: &str,
+    ) -> grafeo_common::types::EdgeId {
+        let (epoch, tx_id) = self.get_transaction_context();
+        self.store
+            .create_edge_versioned(src, dst, edge_type, epoch, tx_id.unwrap_or(TxId::SYSTEM))
+    }
+}
+
+/// Returns true if `query` is an `ANALYZE` statement.
+#[cfg(feature = "gql")]
+fn is_analyze(query: &str) -> bool {
+    query
+        .trim()
+        .trim_end_matches(';')
+        .trim_end()
+        .eq_ignore_ascii_case("analyze")
+}
+
+/// Splits an `EXPLAIN` or `EXPLAIN ANALYZE` prefix off `query`, returning
+/// whether to analyze and the query to explain.
+#[cfg(feature = "gql")]
+fn parse_explain(query: &str) -> Option<(bool, &str)> {
+    fn strip_keyword<'a>(query: &'a str, keyword: &str) -> Option<&'a str> {
+        let query = query.trim_start();
+        let rest = query.get(keyword.len()..)?;
+        (query[..keyword.len()].eq_ignore_ascii_case(keyword)
+            && rest.starts_with(char::is_whitespace))
+        .then_some(rest)
+    }
+
+    let query = strip_keyword(query, "explain")?;
+    match strip_keyword(query, "analyze") {
+        Some(query) => Some((true, query)),
+        None => Some((false, query)),
+    }
+}
+
+/// Parses a `CREATE FULLTEXT INDEX ON :Label(property)` or
+/// `CREATE SPATIAL INDEX ON :Label(property)` statement into its index type,
+/// label, and property.
+///
+/// Returns `None` if `query` is some other statement, and an error if it
+/// starts like one but the target is malformed.
+#[cfg(feature = "gql")]
+fn parse_create_index(query: &str) -> Option<Result<(IndexType, String, String)>> {
+    let mut words = query.split_whitespace();
+    if !words.next()?.eq_ignore_ascii_case("create") {
+        return None;
+    }
+    let kind = words.next()?;
+    let index_type = if kind.eq_ignore_ascii_case("fulltext") {
+        IndexType::FullText
+    } else if kind.eq_ignore_ascii_case("spatial") {
+        IndexType::Spatial
+    } else {
+        return None;
+    };
+    if !words.next()?.eq_ignore_ascii_case("index") {
+        return None;
+    }
+
+    let rest = words.collect::<Vec<_>>().join(" ");
+    let target = rest.trim_end_matches(';').trim_end();
+    let parsed = target
+        .get(..2)
+        .filter(|on| on.eq_ignore_ascii_case("on"))
+        .and_then(|_| target[2..].trim_start().strip_prefix(':'))
+        .and_then(|target| target.strip_suffix(')'))
+        .and_then(|target| target.split_once('('))
+        .map(|(label, property)| (label.trim(), property.trim()))
+        .filter(|(label, property)| is_identifier(label) && is_identifier(property));
+    Some(
+        parsed
+            .map(|(label, property)| (index_type, label.to_string(), property.to_string()))
+            .ok_or_else(|| {
+                Error::Query(QueryError::new(
+                    QueryErrorKind::Syntax,
+                    format!(
+                        "Expected CREATE {} INDEX ON :Label(property), got '{}'",
+                        kind.to_ascii_uppercase(),
+                        query.trim()
+                    ),
+                ))
+            }),
+    )
+}
+
+#[cfg(feature = "gql")]
+fn is_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::GrafeoDB;
+
+    #[test]
+    fn test_session_create_node() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        let id = session.create_node(&["Person"]);
+        assert!(id.is_valid());
+        assert_eq!(db.node_count(), 1);
+    }
+
+    #[test]
+    fn test_graph_projection_refreshes_after_changes() {
+        use grafeo_adapters::plugins::algorithms::connected_components;
+
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+        let a = session.create_node(&["Person"]);
+        let b = session.create_node(&["Person"]);
+        session.create_node(&["Bot"]);
+        session.create_edge(a, b, "KNOWS");
+
+        let people = session.project_graph(
+            "people",
+            |node| node.labels.iter().any(|l| l.as_ref() == "Person"),
+            |_| true,
+        );
+        assert_eq!(people.node_ids(), &[a, b]);
+        assert_eq!(connected_components(people.graph()).len(), 2);
+
+        // Unchanged graph: the cached projection is returned as is
+        let cached = session.graph_projection("people").unwrap();
+        assert!(std::sync::Arc::ptr_eq(&people, &cached));
+
+        let c = session.create_node(&["Person"]);
+        assert!(people.is_stale());
+        let refreshed = session.graph_projection("people").unwrap();
+        assert_eq!(refreshed.node_ids(), &[a, b, c]);
+        assert_eq!(connected_components(refreshed.graph()).len(), 3);
+
+        assert!(session.drop_graph_projection("people"));
+        assert!(session.graph_projection("people").is_none());
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_dropping_stream_releases_memory_and_spill_files() {
+        use crate::config::Config;
+        use grafeo_common::types::Value;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spill");
+        let db = GrafeoDB::with_config(
+            Config::in_memory()
+                .with_memory_limit(256 * 1024)
+                .with_memory_accounting_granularity(0)
+                .with_spill_path(&path),
+        )
+        .unwrap();
+        for i in 0..8_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", (i * 7) % 8_000)]);
+        }
+
+        let session = db.session();
+        let mut stream = session
+            .execute_streaming("MATCH (n:Item) RETURN n.v ORDER BY n.v")
+            .unwrap();
+        // Building the stream runs nothing
+        assert!(!path.exists());
+
+        let first: Vec<Vec<Value>> = stream.by_ref().take(10).map(Result::unwrap).collect();
+        assert_eq!(first[0], vec![Value::Int64(0)]);
+        assert_eq!(first[9], vec![Value::Int64(9)]);
+        // The sort spilled to get there, and its runs are still being merged
+        assert!(std::fs::read_dir(&path).unwrap().count() > 0);
+
+        drop(stream);
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 0);
+        assert_eq!(db.buffer_manager().allocated(), 0);
+    }
+
+    /// Whether `result` failed with a query error of `kind`.
+    #[cfg(feature = "gql")]
+    fn failed_with<T>(
+        result: &grafeo_common::utils::error::Result<T>,
+        kind: grafeo_common::utils::error::QueryErrorKind,
+    ) -> bool {
+        matches!(result, Err(grafeo_common::utils::error::Error::Query(err)) if err.kind == kind)
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_cancelled_query_stops_promptly_and_cleans_up() {
+        use super::{CancellationToken, QueryOptions};
+        use crate::config::Config;
+        use grafeo_common::utils::error::QueryErrorKind;
+        use std::time::{Duration, Instant};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spill");
+        let db = GrafeoDB::with_config(
+            Config::in_memory()
+                .with_memory_limit(256 * 1024)
+                .with_memory_accounting_granularity(0)
+                .with_spill_path(&path),
+        )
+        .unwrap();
+        for i in 0..8_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", i)]);
+        }
+
+        let session = db.session();
+        let token = CancellationToken::new();
+        let options = QueryOptions::new().with_cancellation(token.clone());
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(200));
+            token.cancel();
+            Instant::now()
+        });
+        // 64 million pairs to sort, far more than can finish before the cancel
+        let result = session.execute_with(
+            "MATCH (a:Item), (b:Item) RETURN a.v, b.v ORDER BY b.v, a.v",
+            &options,
+        );
+        let stopped = Instant::now();
+        let cancelled_at = canceller.join().unwrap();
+
+        assert!(failed_with(&result, QueryErrorKind::Cancelled));
+        assert!(stopped.duration_since(cancelled_at) < Duration::from_secs(2));
+        assert_eq!(db.buffer_manager().allocated(), 0);
+        if path.exists() {
+            assert_eq!(std::fs::read_dir(&path).unwrap().count(), 0);
+        }
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_query_timeout_from_config_and_per_call() {
+        use super::{CancellationToken, QueryOptions};
+        use crate::config::Config;
+        use grafeo_common::types::Value;
+        use grafeo_common::utils::error::QueryErrorKind;
+        use std::time::Duration;
+
+        let db = GrafeoDB::with_config(
+            Config::in_memory().with_query_timeout(Duration::from_millis(50)),
+        )
+        .unwrap();
+        for i in 0..3_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", i)]);
+        }
+        let mut session = db.session();
+        assert_eq!(session.query_timeout(), Some(Duration::from_millis(50)));
+
+        let pairs = "MATCH (a:Item), (b:Item) RETURN count(a)";
+        assert!(failed_with(
+            &session.execute(pairs),
+            QueryErrorKind::Timeout
+        ));
+        // Quick queries finish inside the timeout
+        let result = session.execute("MATCH (n:Item) RETURN count(n)").unwrap();
+        assert_eq!(result.rows[0][0], Value::Int64(3_000));
+
+        // A longer timeout for one call lets the query finish
+        let generous = QueryOptions::new().with_timeout(Duration::from_mins(10));
+        let result = session.execute_with(pairs, &generous).unwrap();
+        assert_eq!(result.rows[0][0], Value::Int64(9_000_000));
+
+        // A cancelled token wins over the timeout
+        let token = CancellationToken::new();
+        token.cancel();
+        let cancelled = generous.with_cancellation(token);
+        assert!(failed_with(
+            &session.execute_with(pairs, &cancelled),
+            QueryErrorKind::Cancelled
+        ));
+
+        session.set_query_timeout(None);
+        assert!(session.execute(pairs).is_ok());
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_interrupted_mutation_leaves_no_writes() {
+        use super::QueryOptions;
+        use grafeo_common::types::Value;
+        use grafeo_common::utils::error::QueryErrorKind;
+        use std::time::Duration;
+
+        let db = GrafeoDB::new_in_memory();
+        for i in 0..2_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", i)]);
+        }
+        let mut session = db.session();
+        let timeout = QueryOptions::new().with_timeout(Duration::from_millis(50));
+        // Pairs every item with every other, 4 million edges in all
+        let pairs = "MATCH (a:Item), (b:Item) CREATE (a)-[:PAIR]->(b)";
+
+        // Outside a transaction, the query's own transaction is aborted
+        let result = session.execute_with(pairs, &timeout);
+        assert!(failed_with(&result, QueryErrorKind::Timeout));
+        assert_eq!(db.edge_count(), 0);
+
+        // Inside one, only the query's writes go and the transaction goes on
+        session.begin_tx().unwrap();
+        session.execute("INSERT (:Extra)").unwrap();
+        let result = session.execute_with(pairs, &timeout);
+        assert!(failed_with(&result, QueryErrorKind::Timeout));
+        session.execute("INSERT (:Extra)").unwrap();
+        session.commit().unwrap();
+
+        assert_eq!(db.edge_count(), 0);
+        assert_eq!(db.node_count(), 2_002);
+        let result = session.execute("MATCH (n:Extra) RETURN count(n)").unwrap();
+        assert_eq!(result.rows[0][0], Value::Int64(2));
+
+        // Interruptible queries that finish still commit
+        session
+            .execute_with(
+                "MATCH (a:Item {v: 0}), (b:Item {v: 1}) CREATE (a)-[:PAIR]->(b)",
+                &QueryOptions::new().with_timeout(Duration::from_mins(10)),
+            )
+            .unwrap();
+        assert_eq!(db.edge_count(), 1);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_cancelled_update_changes_no_properties() {
+        use super::{CancellationToken, QueryOptions};
+        use grafeo_common::types::Value;
+        use grafeo_common::utils::error::QueryErrorKind;
+        use std::time::Duration;
+
+        let db = GrafeoDB::new_in_memory();
+        for i in 0..2_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", i)]);
+        }
+        let mut session = db.session();
+        let cancel_soon = || {
+            let token = CancellationToken::new();
+            let canceller = token.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                canceller.cancel();
+            });
+            QueryOptions::new().with_cancellation(token)
+        };
+        // Sets every item once per item, 4 million writes in all
+        let update = "MATCH (a:Item), (b:Item) SET a.v = 0, a.seen = true";
+        let unchanged = |session: &super::Session| {
+            let result = session
+                .execute("MATCH (n:Item) WHERE n.v = 1999 RETURN count(n)")
+                .unwrap();
+            assert_eq!(result.rows[0][0], Value::Int64(1));
+            let result = session
+                .execute("MATCH (n:Item) WHERE n.seen = true RETURN count(n)")
+                .unwrap();
+            assert_eq!(result.rows[0][0], Value::Int64(0));
+        };
+
+        let result = session.execute_with(update, &cancel_soon());
+        assert!(failed_with(&result, QueryErrorKind::Cancelled));
+        unchanged(&session);
+
+        // Inside a transaction its earlier writes stay, the query's go
+        session.begin_tx().unwrap();
+        session
+            .execute("MATCH (n:Item) WHERE n.v = 0 SET n.first = true")
+            .unwrap();
+        let result = session.execute_with(update, &cancel_soon());
+        assert!(failed_with(&result, QueryErrorKind::Cancelled));
+        let result =
+            session.execute_with("MATCH (a:Item), (b:Item) DETACH DELETE b", &cancel_soon());
+        assert!(failed_with(&result, QueryErrorKind::Cancelled));
+        unchanged(&session);
+        session.commit().unwrap();
+
+        unchanged(&session);
+        assert_eq!(db.node_count(), 2_000);
+        let result = session
+            .execute("MATCH (n:Item) WHERE n.first = true RETURN n.v")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Int64(0)]]);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_drained_stream_matches_materialized_result() {
+        use grafeo_common::types::Value;
+        let db = GrafeoDB::new_in_memory();
+        for i in 0..5_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", i % 97)]);
+        }
+        let session = db.session();
+        let query = "MATCH (n:Item) WHERE n.v > 10 RETURN n.v";
+
+        let streamed: Vec<Vec<Value>> = session
+            .execute_streaming(query)
+            .unwrap()
+            .collect::<grafeo_common::utils::error::Result<_>>()
+            .unwrap();
+        let materialized = session.execute(query).unwrap();
+
+        assert!(streamed.len() > 2048);
+        assert_eq!(streamed, materialized.rows);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_streamed_writes_commit_only_once_drained() {
+        use grafeo_common::types::Value;
+        use grafeo_common::utils::error::QueryErrorKind;
+        use std::time::Duration;
+
+        let db = GrafeoDB::new_in_memory();
+        for i in 0..5_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", i)]);
+        }
+        let mut session = db.session();
+        let other = db.session();
+        let update = "MATCH (n:Item) SET n.seen = true RETURN n.v";
+        let seen = |session: &super::Session| {
+            session
+                .execute("MATCH (n:Item) WHERE n.seen = true RETURN count(n)")
+                .unwrap()
+                .rows[0][0]
+                .clone()
+        };
+
+        // Part-read: the writes so far stay private, and dropping undoes them
+        let mut rows = session.execute_streaming(update).unwrap();
+        assert!(rows.next().unwrap().is_ok());
+        assert_eq!(seen(&other), Value::Int64(0));
+        drop(rows);
+        assert_eq!(seen(&other), Value::Int64(0));
+
+        // Fully read: committed for everyone
+        let rows = session.execute_streaming(update).unwrap();
+        assert_eq!(rows.count(), 5_000);
+        assert_eq!(seen(&other), Value::Int64(5_000));
+
+        // Inside a transaction only the dropped statement is undone
+        session.begin_tx().unwrap();
+        session
+            .execute("MATCH (n:Item) SET n.seen = false")
+            .unwrap();
+        let mut rows = session.execute_streaming(update).unwrap();
+        assert!(rows.next().unwrap().is_ok());
+        drop(rows);
+        assert_eq!(seen(&session), Value::Int64(0));
+        assert_eq!(seen(&other), Value::Int64(5_000));
+        session.rollback().unwrap();
+
+        // The session's timeout covers streamed queries too
+        session.set_query_timeout(Some(Duration::from_millis(50)));
+        let pairs = "MATCH (a:Item), (b:Item) RETURN count(a)";
+        let outcome: grafeo_common::utils::error::Result<Vec<_>> =
+            session.execute_streaming(pairs).unwrap().collect();
+        assert!(failed_with(&outcome, QueryErrorKind::Timeout));
+    }
+
+    #[test]
+    fn test_sessions_spill_to_their_own_paths() {
+        use crate::config::Config;
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::with_config(
+            Config::in_memory()
+                .with_memory_limit(256 * 1024)
+                .with_memory_accounting_granularity(0),
+        )
+        .unwrap();
+        for i in 0..8_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", (i * 7) % 8_000)]);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["first", "second"] {
+            let path = dir.path().join(name);
+            let mut session = db.session();
+            session.set_spill_path(&path);
+            assert_eq!(session.spill_path(), Some(path.as_path()));
+
+            let result = session
+                .execute("MATCH (n:Item) RETURN n.v ORDER BY n.v")
+                .unwrap();
+            let values: Vec<i64> = result
+                .rows
+                .iter()
+                .map(|row| match row[0] {
+                    Value::Int64(v) => v,
+                    ref other => panic!("unexpected value {other:?}"),
+                })
+                .collect();
+            assert_eq!(values, (0..8_000).collect::<Vec<_>>());
+
+            // The directory only exists because the sort spilled into it,
+            // and its files are gone once the query is done
+            assert!(path.exists());
+            assert_eq!(std::fs::read_dir(&path).unwrap().count(), 0);
+        }
+        assert_eq!(db.buffer_manager().allocated(), 0);
+    }
+
+    #[test]
+    fn test_sort_with_spill_disabled_fails_instead_of_spilling() {
+        use crate::config::Config;
+        use grafeo_core::execution::SpillableOperator;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spill");
+        let db = GrafeoDB::with_config(
+            Config::in_memory()
+                .with_memory_limit(256 * 1024)
+                .with_memory_accounting_granularity(0)
+                .with_spill_path(&path)
+                .with_spill_disabled(SpillableOperator::Sort),
+        )
+        .unwrap();
+        for i in 0..8_000_i64 {
+            db.create_node_with_props(&["Item"], [("v", (i * 7) % 8_000)]);
+        }
+
+        let err = db
+            .session()
+            .execute("MATCH (n:Item) RETURN n.v ORDER BY n.v")
+            .unwrap_err();
+        assert!(err.to_string().contains("memory limit"), "{err}");
+        assert!(!path.exists());
+        assert_eq!(db.buffer_manager().allocated(), 0);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_explain_annotates_operators_with_estimates() {
+        let db = GrafeoDB::new_in_memory();
+        for i in 0..10_i64 {
+            db.create_node_with_props(&["Person"], [("age", i)]);
+        }
+        db.create_node(&["Company"]);
+        let session = db.session();
+
+        let plan = session
+            .explain("MATCH (n:Person) WHERE n.age > 5 RETURN n")
+            .unwrap();
+        let scan = plan
+            .operators()
+            .into_iter()
+            .find(|node| node.operator == "NodeScan")
+            .unwrap();
+        assert_eq!(scan.details, "n:Person");
+        assert!((scan.estimated_rows - 10.0).abs() < f64::EPSILON);
+
+        let text = plan.to_string();
+        assert!(text.starts_with("Return"), "{text}");
+        assert!(
+            text.contains("NodeScan n:Person (rows: 10, cost: "),
+            "{text}"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&plan.to_json()).unwrap();
+        assert_eq!(json["root"]["operator"], "Return");
+        assert!(json["root"]["estimated_rows"].is_number());
+        assert!(json["root"]["children"][0]["total_cost"].is_number());
+    }
+
+    /// Ten people aged 0 to 9, each knowing the next.
+    #[cfg(feature = "gql")]
+    fn people_chain(db: &GrafeoDB) {
+        let ids: Vec<_> = (0..10_i64)
+            .map(|age| db.create_node_with_props(&["Person"], [("age", age)]))
+            .collect();
+        for pair in ids.windows(2) {
+            db.create_edge(pair[0], pair[1], "KNOWS");
+        }
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_explain_statement_returns_operator_rows() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        people_chain(&db);
+        let session = db.session();
+
+        let result = session
+            .execute("EXPLAIN MATCH (a:Person)-[:KNOWS]->(b:Person) WHERE b.age > 5 RETURN b.age")
+            .unwrap();
+        assert_eq!(
+            result.columns,
+            ["id", "operator", "details", "estimated_rows", "children"]
+        );
+        let operators: Vec<&Value> = result.rows.iter().map(|row| &row[1]).collect();
+        assert_eq!(
+            operators,
+            [
+                &Value::from("Project"),
+                &Value::from("Filter"),
+                &Value::from("Expand"),
+                &Value::from("Scan")
+            ]
+        );
+        assert_eq!(result.rows[2][2], Value::from("(a)-[:KNOWS]->(b)"));
+        assert_eq!(result.rows[3][2], Value::from("a:Person"));
+        for (id, row) in result.rows.iter().enumerate() {
+            assert_eq!(row[0], Value::Int64(id as i64));
+            assert!(matches!(row[3], Value::Float64(rows) if rows > 0.0));
+            let children: Vec<Value> = if id < 3 {
+                vec![Value::Int64(id as i64 + 1)]
+            } else {
+                Vec::new()
+            };
+            assert_eq!(row[4], Value::List(children.into()));
+        }
+
+        // Planned, never run
+        let result = session
+            .execute("explain INSERT (:Person {age: 99})")
+            .unwrap();
+        assert_eq!(result.rows[0][1], Value::from("CreateNode"));
+        assert_eq!(db.node_count(), 10);
+    }
+
+    #[cfg(feature = "gql")]
+    #[test]
+    fn test_explain_analyze_annotates_what_each_operator_did() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        people_chain(&db);
+        let session = db.session();
+        let query = "MATCH (a:Person)-[:KNOWS]->(b:Person) WHERE b.age > 5 \
+                     RETURN b.age ORDER BY b.age";
+
+        let plan = session.explain_analyze(query).unwrap();
+        let actual: Vec<(String, u64)> = plan
+            .operators()
+            .iter()
+            .map(|op| (op.operator.clone(), op.actual.unwrap().rows))
+            .collect();
+        assert_eq!(
+            actual,
+            [
+                ("Project".to_string(), 4),
+                ("Sort".to_string(), 4),
+                ("Filter".to_string(), 4),
+                ("Expand".to_string(), 9),
+                ("Scan".to_string(), 10),
+            ]
+        );
+        let sort = plan.operators()[1].actual.unwrap();
+        let root = plan.root.actual.unwrap();
+        assert!(sort.peak_memory > 0);
+        assert!(root.peak_memory >= sort.peak_memory);
+        assert!(root.elapsed >= sort.elapsed);
+        assert!(plan.to_string().contains("(actual rows: 4, time: "));
+
+        let result = session
+            .execute(&format!("EXPLAIN ANALYZE {query}"))
+            .unwrap();
+        assert_eq!(&result.columns[5..], ["rows", "elapsed", "peak_memory"]);
+        assert_eq!(result.rows[0][5], Value::Int64(4));
+        assert!(matches!(result.rows[0][6], Value::Duration(_)));
+
+        // Analyzing a write runs it
+        session
+            .execute("EXPLAIN ANALYZE INSERT (:Person {age: 99})")
+            .unwrap();
+        assert_eq!(db.node_count(), 11);
+    }
+
+    #[test]
+    fn test_idempotent_insert_is_applied_once() {
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+        let insert = "INSERT (:Person {name: 'Alice'})";
+
+        session.execute_idempotent("create-alice", insert).unwrap();
+        session.execute_idempotent("create-alice", insert).unwrap();
+        assert_eq!(db.node_count(), 1);
+
+        // Keys are shared across sessions, and only dedup the same key
+        db.session()
+            .execute_idempotent("create-alice", insert)
+            .unwrap();
+        assert_eq!(db.node_count(), 1);
+        session
+            .execute_idempotent("create-alice-2", insert)
+            .unwrap();
+        assert_eq!(db.node_count(), 2);
+
+        session.begin_tx().unwrap();
+        assert!(session.execute_idempotent("in-tx", insert).is_err());
+    }
+
+    #[test]
+    fn test_session_transaction() {
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+
+        assert!(!session.in_transaction());
+
+        session.begin_tx().unwrap();
+        assert!(session.in_transaction());
+
+        session.commit().unwrap();
+        assert!(!session.in_transaction());
+    }
+
+    #[test]
+    fn test_commit_epochs_increase_across_transactions() {
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+        assert!(session.last_commit_epoch().is_none());
+
+        let mut previous = None;
+        for _ in 0..3 {
+            session.begin_tx().unwrap();
+            let start = session.tx_start_epoch().unwrap();
+            session.create_node(&["Event"]);
+            session.commit().unwrap();
+
+            let commit = session.last_commit_epoch().unwrap();
+            assert_eq!(session.last_start_epoch(), Some(start));
+            assert!(commit > start);
+            if let Some(previous) = previous {
+                assert!(commit > previous);
+                // The next transaction's snapshot includes the previous commit.
+                assert!(start >= previous);
+            }
+            previous = Some(commit);
+        }
+        assert!(session.tx_start_epoch().is_none());
+    }
+
+    #[test]
+    fn test_session_transaction_context() {
+        let db = GrafeoDB::new_in_memory();
         let mut session = db.session();
 
         // Without transaction - context should have current epoch and no tx_id
@@ -747,6 +3350,115 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_rollback_to_savepoint_keeps_earlier_writes() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+        let names = |session: &super::Session| {
+            let result = session
+                .execute("MATCH (p:Person) RETURN p.name ORDER BY p.name")
+                .unwrap();
+            result
+                .rows
+                .iter()
+                .map(|row| row[0].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        session.begin_tx().unwrap();
+        let alice = session.create_node_with_props(&["Person"], [("name", "Alice".into())]);
+        session.savepoint("first").unwrap();
+        let bob = session.create_node_with_props(&["Person"], [("name", "Bob".into())]);
+        session.create_edge(alice, bob, "KNOWS");
+        session.savepoint("second").unwrap();
+        session.execute("INSERT (:Person {name: 'Carol'})").unwrap();
+        session
+            .execute("MATCH (p:Person {name: 'Alice'}) SET p.name = 'Alicia'")
+            .unwrap();
+        session
+            .execute("MATCH (p:Person {name: 'Bob'}) DETACH DELETE p")
+            .unwrap();
+        assert_eq!(names(&session), ["Alicia", "Carol"]);
+
+        // The update and the delete are undone along with the insert
+        session.rollback_to("second").unwrap();
+        assert_eq!(names(&session), ["Alice", "Bob"]);
+        assert_eq!(db.edge_count(), 1);
+        let knows = session
+            .execute("MATCH (:Person {name: 'Alice'})-[:KNOWS]->(b:Person) RETURN b.name")
+            .unwrap();
+        assert_eq!(knows.rows, vec![vec![Value::from("Bob")]]);
+
+        session.execute("INSERT (:Person {name: 'Dave'})").unwrap();
+        session.rollback_to("first").unwrap();
+        assert_eq!(names(&session), ["Alice"]);
+        assert_eq!(db.edge_count(), 0);
+
+        // "first" survives its own rollback; "second" was set after it
+        session.execute("INSERT (:Person {name: 'Eve'})").unwrap();
+        session.rollback_to("first").unwrap();
+        assert!(session.rollback_to("second").is_err());
+
+        session.commit().unwrap();
+        assert_eq!(names(&session), ["Alice"]);
+    }
+
+    #[test]
+    fn test_release_savepoint_keeps_writes() {
+        use grafeo_common::utils::error::{Error, TransactionError};
+
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+
+        session.begin_tx().unwrap();
+        session.savepoint("sp").unwrap();
+        session.execute("INSERT (:Person {name: 'Alice'})").unwrap();
+        session.release("sp").unwrap();
+
+        let err = session.rollback_to("sp").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Transaction(TransactionError::SavepointNotFound(ref name)) if name == "sp"
+        ));
+        session.commit().unwrap();
+
+        let result = session.execute("MATCH (p:Person) RETURN p").unwrap();
+        assert_eq!(result.row_count(), 1);
+    }
+
+    #[test]
+    fn test_savepoints_end_with_transaction() {
+        use grafeo_common::utils::error::{Error, TransactionError};
+
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+
+        // Without a transaction there's nothing to mark
+        assert!(matches!(
+            session.savepoint("sp"),
+            Err(Error::Transaction(TransactionError::InvalidState(_)))
+        ));
+
+        session.begin_tx().unwrap();
+        session.savepoint("sp").unwrap();
+        session.commit().unwrap();
+        assert!(session.rollback_to("sp").is_err());
+
+        session.begin_tx().unwrap();
+        session.savepoint("sp").unwrap();
+        session.rollback().unwrap();
+
+        session.begin_tx().unwrap();
+        assert!(matches!(
+            session.rollback_to("sp"),
+            Err(Error::Transaction(TransactionError::SavepointNotFound(_)))
+        ));
+        assert!(session.release("missing").is_err());
+        session.rollback().unwrap();
+    }
+
     #[test]
     fn test_session_create_node_in_transaction() {
         // Test that session.create_node() is transaction-aware
@@ -780,42 +3492,569 @@ mod tests {
     }
 
     #[test]
-    fn test_session_create_node_with_props_in_transaction() {
+    fn test_session_create_node_with_props_in_transaction() {
+        use grafeo_common::types::Value;
+
+        // Test that session.create_node_with_props() is transaction-aware
+        let db = GrafeoDB::new_in_memory();
+
+        // Create a node outside of any transaction
+        db.create_node(&["Person"]);
+        assert_eq!(db.node_count(), 1, "Should have 1 node before transaction");
+
+        // Start a transaction and create a node with properties
+        let mut session = db.session();
+        session.begin_tx().unwrap();
+
+        let node_in_tx =
+            session.create_node_with_props(&["Person"], [("name", Value::String("Alice".into()))]);
+        assert!(node_in_tx.is_valid());
+
+        // Should see 2 nodes
+        assert_eq!(db.node_count(), 2, "Should have 2 nodes during transaction");
+
+        // Rollback the transaction
+        session.rollback().unwrap();
+
+        // The node should be discarded
+        let count_after = db.node_count();
+        assert_eq!(
+            count_after, 1,
+            "Rollback should discard node created via session.create_node_with_props()"
+        );
+    }
+
+    #[test]
+    fn test_batch_commits_mixed_writes_atomically() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        let existing = db.create_node(&["Person"]);
+        let stale = db.create_node(&["Person"]);
+        let session = db.session();
+
+        let outcome = session
+            .batch(|batch| {
+                let alice =
+                    batch.create_node_with_props(&["Person"], [("name", "Alice".into())])?;
+                let bob = batch.create_node(&["Person"])?;
+                batch.create_edge(alice, bob, "KNOWS")?;
+                batch.set_node_property(existing, "name", Value::from("Carol"))?;
+                batch.set_node_property(bob, "name", Value::from("Bob"))?;
+                batch.delete_node(stale)?;
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(outcome.created_nodes.len(), 2);
+        assert_eq!(outcome.created_edges.len(), 1);
+        assert_eq!(db.node_count(), 3);
+        assert_eq!(db.edge_count(), 1);
+        assert!(db.get_node(stale).is_none());
+
+        let name = |id| {
+            db.get_node(id)
+                .and_then(|n| n.get_property("name").cloned())
+                .unwrap()
+        };
+        assert_eq!(name(existing), Value::from("Carol"));
+        assert_eq!(name(outcome.created_nodes[0]), Value::from("Alice"));
+        assert_eq!(name(outcome.created_nodes[1]), Value::from("Bob"));
+    }
+
+    #[test]
+    fn test_subscribe_sees_committed_changes_in_order() {
+        use crate::changefeed::{Change, ChangeEvent};
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        let before = db.create_node(&["Person"]);
+        let session = db.session();
+        let mut changes = session.subscribe();
+
+        let alice = db.create_node(&["Person"]);
+        let outcome = session
+            .batch(|batch| {
+                let bob = batch.create_node_with_props(&["Person"], [("name", "Bob".into())])?;
+                batch.create_edge(alice, bob, "KNOWS")?;
+                batch.set_node_property(alice, "name", Value::from("Alice"))?;
+                batch.delete_node(before)?;
+                Ok(())
+            })
+            .unwrap();
+        // A failed batch publishes nothing
+        let failed = session.batch(|batch| {
+            batch.create_node(&["Ghost"])?;
+            batch.delete_node(before)
+        });
+        assert!(failed.is_err());
+        let other = db.session();
+        let carol = other
+            .batch(|batch| {
+                batch.create_node(&["Person"])?;
+                Ok(())
+            })
+            .unwrap()
+            .created_nodes[0];
+        let edge = outcome.created_edges[0];
+        assert!(db.delete_edge(edge));
+
+        let bob = outcome.created_nodes[0];
+        let events: Vec<(u64, ChangeEvent)> = changes
+            .try_iter()
+            .map(|Change { position, event }| (position, event))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    1,
+                    ChangeEvent::NodeCreated {
+                        id: alice,
+                        labels: vec!["Person".to_string()],
+                    }
+                ),
+                (
+                    2,
+                    ChangeEvent::NodeCreated {
+                        id: bob,
+                        labels: vec!["Person".to_string()],
+                    }
+                ),
+                (
+                    3,
+                    ChangeEvent::NodePropertySet {
+                        id: bob,
+                        key: "name".to_string(),
+                        value: Value::from("Bob"),
+                    }
+                ),
+                (
+                    4,
+                    ChangeEvent::EdgeCreated {
+                        id: edge,
+                        src: alice,
+                        dst: bob,
+                        edge_type: "KNOWS".to_string(),
+                    }
+                ),
+                (
+                    5,
+                    ChangeEvent::NodePropertySet {
+                        id: alice,
+                        key: "name".to_string(),
+                        value: Value::from("Alice"),
+                    }
+                ),
+                (6, ChangeEvent::NodeDeleted { id: before }),
+                (
+                    7,
+                    ChangeEvent::NodeCreated {
+                        id: carol,
+                        labels: vec!["Person".to_string()],
+                    }
+                ),
+                (8, ChangeEvent::EdgeDeleted { id: edge }),
+            ]
+        );
+        assert_eq!(session.change_position(), 9);
+
+        // A later subscriber can replay from a retained position
+        let mut replay = other.subscribe_from(7).unwrap();
+        let replayed: Vec<u64> = replay.try_iter().map(|c| c.position).collect();
+        assert_eq!(replayed, vec![7, 8]);
+    }
+
+    #[test]
+    #[cfg(feature = "gql")]
+    fn test_subscribe_sees_query_commits_only_once_committed() {
+        use crate::changefeed::ChangeEvent;
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+        let mut changes = session.subscribe();
+
+        session.begin_tx().unwrap();
+        session.execute("INSERT (:Person {name: 'Alice'})").unwrap();
+        assert!(changes.try_next().is_none());
+        session.commit().unwrap();
+        let events: Vec<ChangeEvent> = changes.try_iter().map(|change| change.event).collect();
+        let ChangeEvent::NodeCreated { id: alice, .. } = events[0] else {
+            panic!("expected the node first, got {events:?}");
+        };
+        assert_eq!(
+            events,
+            vec![
+                ChangeEvent::NodeCreated {
+                    id: alice,
+                    labels: vec!["Person".to_string()],
+                },
+                ChangeEvent::NodePropertySet {
+                    id: alice,
+                    key: "name".to_string(),
+                    value: Value::from("Alice"),
+                },
+            ]
+        );
+
+        // A rolled back transaction publishes nothing
+        session.begin_tx().unwrap();
+        session
+            .execute("MATCH (a:Person) SET a.name = 'Alicia'")
+            .unwrap();
+        session.rollback().unwrap();
+        assert!(changes.try_next().is_none());
+
+        // A query on its own commits, and publishes, by itself
+        session.execute("MATCH (a:Person) SET a.age = 30").unwrap();
+        assert_eq!(
+            changes.try_next().map(|change| change.event),
+            Some(ChangeEvent::NodePropertySet {
+                id: alice,
+                key: "age".to_string(),
+                value: Value::Int64(30),
+            })
+        );
+        assert!(changes.try_next().is_none());
+    }
+
+    #[test]
+    fn test_slow_subscriber_overflows_without_blocking_commits() {
+        use crate::changefeed::ChangeEvent;
+
+        let db =
+            GrafeoDB::with_config(crate::Config::in_memory().with_change_buffer_size(4)).unwrap();
+        let session = db.session();
+        let mut changes = session.subscribe();
+        for _ in 0..10 {
+            db.create_node(&["Item"]);
+        }
+        assert_eq!(db.node_count(), 10);
+
+        let events: Vec<_> = changes.try_iter().collect();
+        assert_eq!(events.len(), 5);
+        assert_eq!(events[4].position, 4);
+        assert_eq!(events[4].event, ChangeEvent::Overflow { missed: 6 });
+    }
+
+    #[test]
+    fn test_result_mode_default() {
+        use crate::config::ResultMode;
+        use crate::query::QueryOutput;
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+        for i in 0..3000 {
+            session.create_node_with_props(&["Item"], [("n", Value::Int64(i))]);
+        }
+        let query = "MATCH (i:Item) RETURN i.n";
+
+        // Materialized by default: rows can be indexed and counted
+        assert_eq!(session.result_mode(), ResultMode::Materialized);
+        let QueryOutput::Materialized(result) = session.run(query).unwrap() else {
+            panic!("expected a materialized result");
+        };
+        assert_eq!(result.row_count(), 3000);
+        assert!(matches!(result.rows[2999][0], Value::Int64(_)));
+
+        // Streaming pulls rows through an iterator, spanning several chunks
+        session.set_result_mode(ResultMode::Streaming);
+        let QueryOutput::Streaming(mut stream) = session.run(query).unwrap() else {
+            panic!("expected a stream");
+        };
+        assert_eq!(stream.columns(), ["i.n"]);
+        assert!(stream.next().unwrap().is_ok());
+        assert_eq!(stream.by_ref().take(9).count(), 9);
+        assert_eq!(stream.materialize().unwrap().row_count(), 2990);
+
+        let streamed: Vec<_> = match session.run(query).unwrap() {
+            QueryOutput::Streaming(stream) => stream.collect::<Result<_, _>>().unwrap(),
+            QueryOutput::Materialized(_) => panic!("expected a stream"),
+        };
+        assert_eq!(streamed.len(), 3000);
+
+        // A single call can override the default
+        let output = session.run_as(query, ResultMode::Materialized).unwrap();
+        assert!(!output.is_streaming());
+        assert_eq!(output.into_result().unwrap().row_count(), 3000);
+    }
+
+    #[test]
+    fn test_batch_concurrent_increments_sum() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        let page = db.create_node(&["Page"]);
+        db.set_node_property(page, "views", Value::Int64(10));
+        let (first, second) = (db.session(), db.session());
+
+        // The second batch starts and commits while the first is still open
+        first
+            .batch(|batch| {
+                batch.increment_node_property(page, "views", Value::Int64(1))?;
+                second
+                    .batch(|batch| batch.increment_node_property(page, "views", Value::Int64(2)))?;
+                batch.increment_node_property(page, "views", Value::Int64(3))
+            })
+            .unwrap();
+
+        let views = || {
+            db.get_node(page)
+                .and_then(|n| n.get_property("views").cloned())
+        };
+        assert_eq!(views(), Some(Value::Int64(16)));
+
+        let result = first.batch(|batch| batch.increment_node_property(page, "views", "1".into()));
+        assert!(result.is_err());
+        assert_eq!(views(), Some(Value::Int64(16)));
+    }
+
+    #[test]
+    fn test_batch_error_rolls_back_everything() {
+        use grafeo_common::types::{NodeId, Value};
+        use grafeo_common::utils::error::Error;
+
+        let db = GrafeoDB::new_in_memory();
+        let existing = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+        let session = db.session();
+
+        let result = session.batch(|batch| {
+            let bob = batch.create_node(&["Person"])?;
+            batch.create_edge(existing, bob, "KNOWS")?;
+            batch.set_node_property(existing, "name", Value::from("Changed"))?;
+            batch.delete_node(existing)?;
+            // Fails mid-batch: this node was never created
+            batch.delete_node(NodeId::new(9_999))?;
+            batch.create_node(&["Person"])?;
+            Ok(())
+        });
+
+        assert!(matches!(result, Err(Error::NodeNotFound(_))));
+        assert_eq!(db.node_count(), 1);
+        assert_eq!(db.edge_count(), 0);
+        let alice = db.get_node(existing).expect("node should survive");
+        assert_eq!(alice.get_property("name"), Some(&Value::from("Alice")));
+    }
+
+    #[test]
+    fn test_batch_past_write_set_limit_rolls_back() {
+        use crate::config::Config;
+        use grafeo_common::types::Value;
+        use grafeo_common::utils::error::{Error, TransactionError};
+
+        let db = GrafeoDB::with_config(Config::in_memory().with_max_write_set_size(3)).unwrap();
+        let people: Vec<_> = (0..4).map(|_| db.create_node(&["Person"])).collect();
+        let session = db.session();
+
+        // Updates fail as soon as they pass the limit
+        let result = session.batch(|batch| {
+            for &id in &people {
+                batch.set_node_property(id, "seen", Value::Bool(true))?;
+            }
+            Ok(())
+        });
+        assert!(matches!(
+            result,
+            Err(Error::Transaction(TransactionError::WriteSetTooLarge(3)))
+        ));
+        assert!(
+            people
+                .iter()
+                .all(|&id| db.get_node(id).unwrap().get_property("seen").is_none())
+        );
+
+        // Creates are counted when the batch commits
+        let result = session.batch(|batch| {
+            let a = batch.create_node(&["Person"])?;
+            let b = batch.create_node(&["Person"])?;
+            batch.create_edge(a, b, "KNOWS")?;
+            batch.create_edge(b, a, "KNOWS")?;
+            Ok(())
+        });
+        assert!(matches!(
+            result,
+            Err(Error::Transaction(TransactionError::WriteSetTooLarge(3)))
+        ));
+        assert_eq!(db.node_count(), 4);
+        assert_eq!(db.edge_count(), 0);
+
+        // Within the limit the batch goes through
+        session
+            .batch(|batch| {
+                batch.set_node_property(people[0], "seen", Value::Bool(true))?;
+                batch.create_node(&["Person"])?;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(db.node_count(), 5);
+    }
+
+    #[test]
+    fn test_batch_is_invisible_until_it_commits() {
+        use grafeo_common::types::Value;
+
+        let db = GrafeoDB::new_in_memory();
+        let alice = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+        let stale = db.create_node(&["Person"]);
+        let session = db.session();
+        let name = || {
+            db.get_node(alice)
+                .and_then(|n| n.get_property("name").cloned())
+        };
+
+        session
+            .batch(|batch| {
+                batch.set_node_property(alice, "name", Value::from("Carol"))?;
+                batch.delete_node(stale)?;
+                let bob = batch.create_node(&["Person"])?;
+                batch.create_edge(alice, bob, "KNOWS")?;
+
+                // Everyone else still sees the graph from before the batch
+                assert_eq!(name(), Some(Value::from("Alice")));
+                assert!(db.get_node(stale).is_some());
+                assert!(db.get_node(bob).is_none());
+                assert_eq!((db.node_count(), db.edge_count()), (2, 0));
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(name(), Some(Value::from("Carol")));
+        assert!(db.get_node(stale).is_none());
+        assert_eq!((db.node_count(), db.edge_count()), (2, 1));
+    }
+
+    #[test]
+    fn test_batch_wal_failure_rolls_back_everything() {
+        use grafeo_adapters::storage::wal::{WalConfig, WalManager};
         use grafeo_common::types::Value;
+        use std::sync::Arc;
 
-        // Test that session.create_node_with_props() is transaction-aware
         let db = GrafeoDB::new_in_memory();
-
-        // Create a node outside of any transaction
-        db.create_node(&["Person"]);
-        assert_eq!(db.node_count(), 1, "Should have 1 node before transaction");
-
-        // Start a transaction and create a node with properties
+        let alice = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+        let stale = db.create_node(&["Person"]);
+
+        // A log that has to rotate after every record, into a directory
+        // that's gone
+        let dir = tempfile::tempdir().unwrap();
+        let wal_dir = dir.path().join("wal");
+        let config = WalConfig {
+            max_log_size: 1,
+            ..WalConfig::default()
+        };
+        let wal = WalManager::with_config(&wal_dir, config).unwrap();
+        std::fs::remove_dir_all(&wal_dir).unwrap();
+        std::fs::write(&wal_dir, b"").unwrap();
         let mut session = db.session();
-        session.begin_tx().unwrap();
-
-        let node_in_tx =
-            session.create_node_with_props(&["Person"], [("name", Value::String("Alice".into()))]);
-        assert!(node_in_tx.is_valid());
+        session.set_wal(Arc::new(wal));
+        let mut changes = session.subscribe();
+
+        let result = session.batch(|batch| {
+            batch.set_node_property(alice, "name", Value::from("Carol"))?;
+            batch.delete_node(stale)?;
+            batch.create_node(&["Person"])?;
+            Ok(())
+        });
+
+        assert!(result.is_err());
+        let name = db
+            .get_node(alice)
+            .and_then(|n| n.get_property("name").cloned());
+        assert_eq!(name, Some(Value::from("Alice")));
+        assert!(db.get_node(stale).is_some());
+        assert_eq!(db.node_count(), 2);
+        assert!(changes.try_next().is_none());
+
+        // Nothing is left locked or half-done for the next batch
+        db.session()
+            .batch(|batch| batch.set_node_property(alice, "name", Value::from("Dave")))
+            .unwrap();
+        let name = db
+            .get_node(alice)
+            .and_then(|n| n.get_property("name").cloned());
+        assert_eq!(name, Some(Value::from("Dave")));
+    }
 
-        // Should see 2 nodes
-        assert_eq!(db.node_count(), 2, "Should have 2 nodes during transaction");
+    #[test]
+    fn test_batch_rejected_inside_transaction() {
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
 
-        // Rollback the transaction
+        session.begin_tx().unwrap();
+        assert!(session.batch(|_| Ok(())).is_err());
         session.rollback().unwrap();
-
-        // The node should be discarded
-        let count_after = db.node_count();
-        assert_eq!(
-            count_after, 1,
-            "Rollback should discard node created via session.create_node_with_props()"
-        );
+        assert!(session.batch(|_| Ok(())).is_ok());
     }
 
     #[cfg(feature = "gql")]
     mod gql_tests {
         use super::*;
 
+        #[test]
+        fn test_analyze_collects_counts_and_distinct_estimates() {
+            use grafeo_common::types::Value;
+
+            let db = GrafeoDB::new_in_memory();
+            let session = db.session();
+            for i in 0..2000 {
+                let city = Value::from(format!("city-{}", i % 250).as_str());
+                let person = db.create_node_with_props(&["Person"], [("city", city)]);
+                if i % 2 == 0 {
+                    let company = db.create_node(&["Company"]);
+                    db.create_edge(person, company, "WORKS_AT");
+                }
+            }
+            // Nulls don't count as distinct values
+            db.create_node(&["Person"]);
+            let catalog = db.catalog();
+            let person = catalog.get_or_create_label("Person");
+            let city = catalog.get_or_create_property_key("city");
+            catalog.create_index(person, city, crate::catalog::IndexType::Hash);
+
+            let result = session.execute("ANALYZE;").unwrap();
+            assert_eq!(result.columns, ["kind", "name", "count", "distinct"]);
+            let row = |kind: &str, name: &str| {
+                result
+                    .rows
+                    .iter()
+                    .find(|row| row[0] == Value::from(kind) && row[1] == Value::from(name))
+                    .cloned()
+                    .unwrap_or_else(|| panic!("no {kind} row for {name}"))
+            };
+            assert_eq!(row("label", "Person")[2], Value::Int64(2001));
+            assert_eq!(row("label", "Company")[2], Value::Int64(1000));
+            assert_eq!(row("edge_type", "WORKS_AT")[2], Value::Int64(1000));
+            let city_row = row("property", "city");
+            assert_eq!(city_row[2], Value::Int64(2000));
+            let Value::Int64(distinct) = city_row[3] else {
+                panic!("expected a distinct count");
+            };
+            assert!((238..=262).contains(&distinct), "{distinct}");
+
+            // Kept for the optimizer, with the range from the zone map
+            let statistics = db.store().statistics();
+            let column = &statistics.labels["Person"].properties["city"];
+            assert_eq!(column.null_count, 1);
+            assert_eq!(column.min_value, Some(Value::from("city-0")));
+            assert_eq!(column.max_value, Some(Value::from("city-99")));
+
+            // A bulk load makes the statistics stale, and analyzing again
+            // picks the new data up
+            for _ in 0..500 {
+                db.create_node(&["Company"]);
+            }
+            assert!(db.store().statistics_are_stale(0.1));
+            let result = session.execute("analyze").unwrap();
+            assert!(result.rows.contains(&vec![
+                Value::from("label"),
+                Value::from("Company"),
+                Value::Int64(1500),
+                Value::Null,
+            ]));
+            assert!(!db.store().statistics_are_stale(0.1));
+        }
+
         #[test]
         fn test_gql_query_execution() {
             let db = GrafeoDB::new_in_memory();
@@ -835,6 +4074,43 @@ mod tests {
             assert_eq!(result.columns[0], "n");
         }
 
+        #[test]
+        fn test_as_of_query_only_traverses_edges_valid_then() {
+            use crate::session::Session;
+            use grafeo_common::types::{Timestamp, Value};
+
+            let db = GrafeoDB::new_in_memory();
+            let mut session = db.session();
+
+            let alice = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+            let acme = db.create_node_with_props(&["Company"], [("name", "Acme")]);
+            let globex = db.create_node_with_props(&["Company"], [("name", "Globex")]);
+            let old_job = db.create_edge(alice, acme, "WORKS_AT");
+            let new_job = db.create_edge(alice, globex, "WORKS_AT");
+            let t = Timestamp::from_secs(1_000);
+            assert!(db.set_edge_validity(old_job, None, Some(t)));
+            assert!(db.set_edge_validity(new_job, Some(t), None));
+
+            let employers = |session: &Session| -> Vec<Value> {
+                let result = session
+                    .execute("MATCH (p:Person)-[:WORKS_AT]->(c:Company) RETURN c.name")
+                    .unwrap();
+                result.rows.into_iter().map(|row| row[0].clone()).collect()
+            };
+
+            // Without an as-of time every edge is traversed
+            assert_eq!(employers(&session).len(), 2);
+
+            session.set_as_of(Some(Timestamp::from_secs(500)));
+            assert_eq!(employers(&session), vec![Value::from("Acme")]);
+
+            session.set_as_of(Some(t));
+            assert_eq!(employers(&session), vec![Value::from("Globex")]);
+
+            session.set_as_of(None);
+            assert_eq!(employers(&session).len(), 2);
+        }
+
         #[test]
         fn test_gql_empty_result() {
             let db = GrafeoDB::new_in_memory();
@@ -1030,6 +4306,242 @@ mod tests {
             // Second column should be the name
             assert_eq!(result.rows[0][1], Value::String("Alice".into()));
         }
+
+        #[test]
+        fn test_gql_order_by_collation() {
+            use grafeo_common::types::Value;
+            use grafeo_core::execution::Collation;
+
+            let db = GrafeoDB::new_in_memory();
+            let mut session = db.session();
+            for name in ["Zoe", "émile", "adam"] {
+                session.create_node_with_props(&["Person"], [("name", Value::from(name))]);
+            }
+
+            let names = |session: &crate::Session| -> Vec<Value> {
+                session
+                    .execute("MATCH (n:Person) RETURN n.name ORDER BY n.name")
+                    .unwrap()
+                    .rows
+                    .into_iter()
+                    .map(|row| row[0].clone())
+                    .collect()
+            };
+
+            // Default: byte order
+            assert_eq!(
+                names(&session),
+                vec![
+                    Value::from("Zoe"),
+                    Value::from("adam"),
+                    Value::from("émile")
+                ]
+            );
+
+            session.set_collation(Collation::Unicode);
+            assert_eq!(
+                names(&session),
+                vec![
+                    Value::from("adam"),
+                    Value::from("émile"),
+                    Value::from("Zoe")
+                ]
+            );
+
+            // Range predicates follow the collation too
+            let result = session
+                .execute("MATCH (n:Person) WHERE n.name < 'f' RETURN n.name ORDER BY n.name")
+                .unwrap();
+            assert_eq!(result.row_count(), 2);
+        }
+
+        #[test]
+        fn test_gql_user_defined_function() {
+            use grafeo_common::types::Value;
+            use grafeo_common::utils::error::Error;
+            use std::sync::Arc;
+
+            let db = GrafeoDB::new_in_memory();
+            let mut session = db.session();
+            for (name, rating, reviews) in [("Ann", 4.5, 10), ("Bob", 3.0, 2), ("Cy", 5.0, 1)] {
+                session.create_node_with_props(
+                    &["Product"],
+                    [
+                        ("name", Value::from(name)),
+                        ("rating", Value::Float64(rating)),
+                        ("reviews", Value::Int64(reviews)),
+                    ],
+                );
+            }
+            session
+                .register_udf(
+                    "score",
+                    2,
+                    Arc::new(|args: &[Value]| match (&args[0], &args[1]) {
+                        (Value::Float64(rating), Value::Int64(reviews)) => {
+                            Ok(Value::Float64(rating * (*reviews as f64).ln_1p()))
+                        }
+                        _ => Err(Error::Internal("score() expects (FLOAT, INT)".to_string())),
+                    }),
+                )
+                .unwrap();
+
+            let result = session
+                .execute(
+                    "MATCH (p:Product) WHERE SCORE(p.rating, p.reviews) > 3.4 \
+                     RETURN p.name, score(p.rating, p.reviews) ORDER BY p.name",
+                )
+                .unwrap();
+            assert_eq!(result.row_count(), 2);
+            assert_eq!(result.rows[0][0], Value::from("Ann"));
+            assert_eq!(result.rows[1][0], Value::from("Cy"));
+            let Value::Float64(score) = result.rows[0][1] else {
+                panic!("expected a float score, got {:?}", result.rows[0][1]);
+            };
+            assert!((score - 4.5 * 11f64.ln()).abs() < 1e-9);
+
+            // Wrong arity and constant arguments of the wrong type fail to plan
+            assert!(
+                session
+                    .execute("MATCH (p:Product) RETURN score(p.rating)")
+                    .is_err()
+            );
+            assert!(
+                session
+                    .execute("MATCH (p:Product) RETURN score('high', 3)")
+                    .is_err()
+            );
+
+            // Built-ins can't be redefined
+            assert!(
+                session
+                    .register_udf("size", 1, Arc::new(|_: &[Value]| Ok(Value::Null)))
+                    .is_err()
+            );
+        }
+
+        #[test]
+        fn test_recommend_indexes_for_selective_filter() {
+            use crate::catalog::IndexType;
+
+            let db = GrafeoDB::new_in_memory();
+            let session = db.session();
+            session
+                .execute("INSERT (:Person {email: 'alice@example.com'})")
+                .unwrap();
+
+            let workload = [
+                "MATCH (p:Person) WHERE p.email = 'alice@example.com' RETURN p",
+                "MATCH (p:Person) RETURN p",
+            ];
+            let recs = session.recommend_indexes(&workload).unwrap();
+            assert_eq!(recs.len(), 1);
+            assert_eq!(recs[0].label, "Person");
+            assert_eq!(recs[0].property, "email");
+            assert_eq!(recs[0].index_type, IndexType::Hash);
+
+            // Advising doesn't create anything
+            assert_eq!(db.catalog().index_count(), 0);
+
+            // Once the index exists, it's no longer recommended
+            let label = db.catalog().get_or_create_label("Person");
+            let key = db.catalog().get_or_create_property_key("email");
+            db.catalog().create_index(label, key, IndexType::Hash);
+            assert!(session.recommend_indexes(&workload).unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_explicit_null_differs_from_missing_property() {
+            use grafeo_common::types::Value;
+
+            let db = GrafeoDB::new_in_memory();
+            let session = db.session();
+            session.create_node_with_props(
+                &["Person"],
+                [("name", Value::from("Alice")), ("nickname", Value::Null)],
+            );
+            session.create_node_with_props(&["Person"], [("name", Value::from("Bob"))]);
+            session.create_node_with_props(
+                &["Person"],
+                [
+                    ("name", Value::from("Carol")),
+                    ("nickname", Value::from("Caz")),
+                ],
+            );
+
+            let names = |query: &str| -> Vec<String> {
+                let mut names: Vec<String> = session
+                    .execute(query)
+                    .unwrap()
+                    .rows
+                    .iter()
+                    .filter_map(|row| row[0].as_str().map(String::from))
+                    .collect();
+                names.sort();
+                names
+            };
+
+            // IS NULL can't tell the two apart: both read as null
+            assert_eq!(
+                names("MATCH (p:Person) WHERE p.nickname IS NULL RETURN p.name"),
+                ["Alice", "Bob"]
+            );
+            // property_exists() can: an explicit null is still a property
+            assert_eq!(
+                names("MATCH (p:Person) WHERE property_exists(p, 'nickname') RETURN p.name"),
+                ["Alice", "Carol"]
+            );
+            assert_eq!(
+                names(
+                    "MATCH (p:Person) WHERE p.nickname IS NULL AND property_exists(p, 'nickname') RETURN p.name"
+                ),
+                ["Alice"]
+            );
+            assert_eq!(
+                names("MATCH (p:Person) WHERE NOT property_exists(p, 'nickname') RETURN p.name"),
+                ["Bob"]
+            );
+        }
+
+        #[test]
+        fn test_estimate_result_size_within_order_of_magnitude() {
+            let db = GrafeoDB::new_in_memory();
+            let session = db.session();
+            let people: Vec<_> = (0..200)
+                .map(|i| {
+                    session.create_node_with_props(
+                        &["Person"],
+                        [("age", grafeo_common::types::Value::Int64(i % 50))],
+                    )
+                })
+                .collect();
+            for _ in 0..50 {
+                session.create_node(&["City"]);
+            }
+            for pair in people.windows(2) {
+                session.create_edge(pair[0], pair[1], "KNOWS");
+            }
+
+            let queries = [
+                "MATCH (p:Person) RETURN p",
+                "MATCH (p:Person) RETURN p.age, p",
+                "MATCH (n) RETURN n",
+                "MATCH (p:Person)-[:KNOWS]->(q) RETURN p, q",
+                "MATCH (p:Person) RETURN p LIMIT 10",
+            ];
+            for query in queries {
+                let estimate = session.estimate_result_size(query).unwrap();
+                let actual = session.execute(query).unwrap();
+
+                assert_eq!(estimate.columns, actual.column_count(), "{query}");
+                let (est, act) = (estimate.rows as f64, actual.row_count() as f64);
+                assert!(
+                    est >= act / 10.0 && est <= act * 10.0,
+                    "{query}: estimated {est} rows, got {act}"
+                );
+                assert!(estimate.bytes > 0, "{query}");
+            }
+        }
     }
 
     #[cfg(feature = "cypher")]
@@ -1055,6 +4567,77 @@ mod tests {
             assert_eq!(result.columns[0], "n");
         }
 
+        #[test]
+        fn test_cypher_avg_of_pattern_size() {
+            use grafeo_common::types::Value;
+
+            let db = GrafeoDB::new_in_memory();
+            let session = db.session();
+
+            let alice = session.create_node(&["Person"]);
+            let bob = session.create_node(&["Person"]);
+            let carol = session.create_node(&["Person"]);
+            let rex = session.create_node(&["Animal"]);
+            session.create_edge(alice, bob, "KNOWS");
+            session.create_edge(alice, carol, "KNOWS");
+            session.create_edge(alice, rex, "OWNS");
+            session.create_edge(bob, carol, "KNOWS");
+
+            // Out-degrees are 3, 1 and 0
+            let result = session
+                .execute_cypher("MATCH (p:Person) RETURN avg(size((p)-->())) AS degree")
+                .unwrap();
+            assert_eq!(result.row_count(), 1);
+            let Value::Float64(degree) = result.rows[0][0] else {
+                panic!("expected a float, got {:?}", result.rows[0][0]);
+            };
+            assert!((degree - 4.0 / 3.0).abs() < 1e-9);
+
+            // Edge types and end labels narrow the count
+            let result = session
+                .execute_cypher("MATCH (p:Person) RETURN sum(size((p)-[:KNOWS]->(:Person)))")
+                .unwrap();
+            assert_eq!(result.rows[0][0], Value::Int64(3));
+
+            // Filters the count can't apply are rejected, not ignored
+            assert!(
+                session
+                    .execute_cypher("MATCH (p:Person) RETURN sum(size((p)-[:KNOWS]->({age: 3})))")
+                    .is_err()
+            );
+
+            // Counts see the transaction's own edges, and only its own
+            let mut writer = db.session();
+            writer.begin_tx().unwrap();
+            writer
+                .execute_cypher("MATCH (p:Person), (a:Animal) CREATE (p)-[:FEEDS]->(a)")
+                .unwrap();
+            let total = "MATCH (p:Person) RETURN sum(size((p)-->()))";
+            let result = writer.execute_cypher(total).unwrap();
+            assert_eq!(result.rows[0][0], Value::Int64(7));
+            let result = session.execute_cypher(total).unwrap();
+            assert_eq!(result.rows[0][0], Value::Int64(4));
+            writer.rollback().unwrap();
+        }
+
+        #[test]
+        fn test_cypher_inline_target_properties_filter_matches() {
+            use grafeo_common::types::Value;
+
+            let db = GrafeoDB::new_in_memory();
+            let alice = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+            let bob = db.create_node_with_props(&["Person"], [("name", "Bob")]);
+            let carol = db.create_node_with_props(&["Person"], [("name", "Carol")]);
+            db.create_edge(alice, bob, "KNOWS");
+            db.create_edge(alice, carol, "KNOWS");
+            let session = db.session();
+
+            let result = session
+                .execute_cypher("MATCH (:Person)-[:KNOWS]->(b {name: 'Bob'}) RETURN b.name")
+                .unwrap();
+            assert_eq!(result.rows, vec![vec![Value::from("Bob")]]);
+        }
+
         #[test]
         fn test_cypher_empty_result() {
             let db = GrafeoDB::new_in_memory();
@@ -1076,5 +4659,300 @@ mod tests {
 
             assert!(result.is_err());
         }
+        #[test]
+        fn test_cypher_relationship_variable_projection() {
+            use grafeo_common::types::Value;
+
+            let db = GrafeoDB::new_in_memory();
+            let alice = db.create_node(&["Person"]);
+            let bob = db.create_node(&["Person"]);
+            let carol = db.create_node(&["Person"]);
+            db.create_edge_with_props(alice, bob, "KNOWS", [("since", Value::Int64(2020))]);
+            db.create_edge_with_props(alice, carol, "KNOWS", [("since", Value::Int64(2015))]);
+
+            let session = db.session();
+            let result = session
+                .execute_cypher("MATCH (a)-[r:KNOWS]->(b) RETURN r.since")
+                .unwrap();
+            assert_eq!(result.columns, vec!["r.since"]);
+            let mut since: Vec<Value> = result.rows.into_iter().map(|row| row[0].clone()).collect();
+            since.sort_by_key(|v| v.as_int64());
+            assert_eq!(since, vec![Value::Int64(2015), Value::Int64(2020)]);
+
+            // Inline properties on an unnamed relationship filter on the edge
+            let result = session
+                .execute_cypher("MATCH (a)-[:KNOWS {since: 2020}]->(b) RETURN b")
+                .unwrap();
+            assert_eq!(result.row_count(), 1);
+        }
+    }
+
+    #[cfg(feature = "gremlin")]
+    mod gremlin_tests {
+        use super::*;
+        use crate::changefeed::ChangeEvent;
+        use grafeo_common::types::Value;
+
+        #[test]
+        fn test_gremlin_writes_reach_subscribers() {
+            let db = GrafeoDB::new_in_memory();
+            let session = db.session();
+            let mut changes = session.subscribe();
+
+            session
+                .execute_gremlin("g.addV('Person').property('name', 'Alice')")
+                .unwrap();
+            let events: Vec<ChangeEvent> = changes.try_iter().map(|change| change.event).collect();
+            let [
+                ChangeEvent::NodeCreated { id, labels },
+                ChangeEvent::NodePropertySet { key, value, .. },
+            ] = events.as_slice()
+            else {
+                panic!("expected a node and its name, got {events:?}");
+            };
+            assert_eq!(labels, &vec!["Person".to_string()]);
+            assert_eq!(key, "name");
+            assert_eq!(value, &Value::from("Alice"));
+            assert!(db.get_node(*id).is_some());
+        }
+
+        #[test]
+        fn test_failed_gremlin_mutation_leaves_no_writes() {
+            let db = GrafeoDB::new_in_memory();
+            for i in 0..10_i64 {
+                db.create_node_with_props(&["Item"], [("v", i)]);
+            }
+
+            // Another transaction holds the last item, so the update fails
+            // part-way through
+            let mut writer = db.session();
+            writer.begin_tx().unwrap();
+            writer
+                .execute("MATCH (n:Item) WHERE n.v = 9 SET n.flag = 'writer'")
+                .unwrap();
+            let session = db.session();
+            let result =
+                session.execute_gremlin("g.V().hasLabel('Item').property('flag', 'gremlin')");
+            assert!(result.is_err());
+            writer.rollback().unwrap();
+
+            let flagged = session
+                .execute("MATCH (n:Item) WHERE n.flag IS NOT NULL RETURN n.v")
+                .unwrap();
+            assert_eq!(flagged.row_count(), 0);
+
+            // Without the conflict it goes through, as one commit
+            session
+                .execute_gremlin("g.V().hasLabel('Item').property('flag', 'gremlin')")
+                .unwrap();
+            let flagged = session
+                .execute("MATCH (n:Item) WHERE n.flag = 'gremlin' RETURN n.v")
+                .unwrap();
+            assert_eq!(flagged.row_count(), 10);
+        }
+
+        #[test]
+        fn test_gremlin_path_binds_hop_edges() {
+            let db = GrafeoDB::new_in_memory();
+            let alice = db.create_node_with_props(&["Person"], [("name", "Alice")]);
+            let bob = db.create_node_with_props(&["Person"], [("name", "Bob")]);
+            let knows = db.create_edge(alice, bob, "knows");
+
+            let result = db
+                .session()
+                .execute_gremlin("g.V().has('name', 'Alice').out('knows').path()")
+                .unwrap();
+            // The knows edge sits between its endpoints
+            let id = |raw: u64| Value::Int64(raw as i64);
+            assert_eq!(
+                result.rows,
+                vec![vec![Value::List(
+                    vec![id(alice.0), id(knows.0), id(bob.0)].into()
+                )]]
+            );
+        }
+    }
+
+    #[cfg(all(feature = "sparql", feature = "rdf"))]
+    mod sparql_tests {
+        use super::*;
+        use crate::database::QueryResult;
+        use grafeo_core::graph::rdf::{Term, Triple};
+        use std::collections::{HashMap, HashSet};
+
+        const EX: &str = "http://example.org/";
+
+        fn people_db() -> GrafeoDB {
+            let db = GrafeoDB::new_in_memory();
+            let store = db.rdf_store();
+            let name = Term::iri(format!("{EX}name"));
+            let age = Term::iri(format!("{EX}age"));
+            let email = Term::iri(format!("{EX}email"));
+            for (person, person_age) in [("alice", "30"), ("bob", "25"), ("carol", "41")] {
+                let subject = Term::iri(format!("{EX}{person}"));
+                store.insert(Triple::new(
+                    subject.clone(),
+                    name.clone(),
+                    Term::literal(person),
+                ));
+                store.insert(Triple::new(subject, age.clone(), Term::literal(person_age)));
+            }
+            store.insert(Triple::new(
+                Term::iri(format!("{EX}alice")),
+                email,
+                Term::literal("alice@example.org"),
+            ));
+            db
+        }
+
+        fn triples(result: &QueryResult) -> HashSet<(String, String, String)> {
+            result
+                .rows
+                .iter()
+                .map(|row| {
+                    let text = |i: usize| row[i].as_str().unwrap().to_string();
+                    (text(0), text(1), text(2))
+                })
+                .collect()
+        }
+
+        fn triple(s: &str, p: &str, o: &str) -> (String, String, String) {
+            (s.to_string(), p.to_string(), o.to_string())
+        }
+
+        #[test]
+        fn test_insert_data_commits_on_its_own_and_rolls_back_in_a_transaction() {
+            let db = GrafeoDB::new_in_memory();
+            let mut session = db.session();
+            let insert =
+                |object: &str| format!("INSERT DATA {{ <{EX}alice> <{EX}name> \"{object}\" }}");
+            let names = |session: &crate::Session| {
+                session
+                    .execute_sparql(&format!("SELECT ?n WHERE {{ <{EX}alice> <{EX}name> ?n }}"))
+                    .unwrap()
+                    .row_count()
+            };
+
+            session.execute_sparql(&insert("Alice")).unwrap();
+            assert_eq!(db.rdf_store().len(), 1);
+
+            session.begin_tx().unwrap();
+            session.execute_sparql(&insert("Alicia")).unwrap();
+            assert_eq!(names(&db.session()), 1);
+            session.rollback().unwrap();
+            assert_eq!(db.rdf_store().len(), 1);
+        }
+
+        #[test]
+        fn test_construct_instantiates_template() {
+            let db = people_db();
+            let session = db.session();
+
+            let result = session
+                .execute_sparql(
+                    "PREFIX ex: <http://example.org/>
+                     CONSTRUCT { ?p ex:label ?n . ?p ex:kind ex:Person }
+                     WHERE { ?p ex:name ?n }",
+                )
+                .unwrap();
+
+            assert_eq!(result.columns, vec!["subject", "predicate", "object"]);
+            let expected: HashSet<_> = ["alice", "bob", "carol"]
+                .into_iter()
+                .flat_map(|name| {
+                    let subject = format!("{EX}{name}");
+                    [
+                        triple(&subject, &format!("{EX}label"), name),
+                        triple(&subject, &format!("{EX}kind"), &format!("{EX}Person")),
+                    ]
+                })
+                .collect();
+            assert_eq!(result.row_count(), expected.len());
+            assert_eq!(triples(&result), expected);
+        }
+
+        #[test]
+        fn test_construct_with_filter() {
+            let db = people_db();
+            let session = db.session();
+
+            let result = session
+                .execute_sparql(
+                    "PREFIX ex: <http://example.org/>
+                     CONSTRUCT { ?p ex:senior true }
+                     WHERE { ?p ex:age ?age FILTER(?age >= 30) }",
+                )
+                .unwrap();
+
+            let expected: HashSet<_> = ["alice", "carol"]
+                .into_iter()
+                .map(|name| triple(&format!("{EX}{name}"), &format!("{EX}senior"), "true"))
+                .collect();
+            assert_eq!(triples(&result), expected);
+        }
+
+        #[test]
+        fn test_construct_skips_unbound_and_deduplicates() {
+            let db = people_db();
+            let session = db.session();
+
+            // Only alice has an email, and the constant triple appears once
+            let result = session
+                .execute_sparql(
+                    "PREFIX ex: <http://example.org/>
+                     CONSTRUCT { ?p ex:contact ?mail . ex:directory ex:size ex:people }
+                     WHERE { ?p ex:name ?n OPTIONAL { ?p ex:email ?mail } }",
+                )
+                .unwrap();
+
+            let expected: HashSet<_> = [
+                triple(
+                    &format!("{EX}alice"),
+                    &format!("{EX}contact"),
+                    "alice@example.org",
+                ),
+                triple(
+                    &format!("{EX}directory"),
+                    &format!("{EX}size"),
+                    &format!("{EX}people"),
+                ),
+            ]
+            .into_iter()
+            .collect();
+            assert_eq!(result.row_count(), 2);
+            assert_eq!(triples(&result), expected);
+        }
+
+        #[test]
+        fn test_construct_mints_blank_nodes_per_solution() {
+            let db = people_db();
+            let session = db.session();
+
+            let result = session
+                .execute_sparql(
+                    "PREFIX ex: <http://example.org/>
+                     CONSTRUCT { ?p ex:card _:c . _:c ex:name ?n }
+                     WHERE { ?p ex:name ?n }",
+                )
+                .unwrap();
+
+            let triples = triples(&result);
+            assert_eq!(triples.len(), 6);
+
+            // Each person links to its own blank node, which carries their name
+            let cards: HashMap<_, _> = triples
+                .iter()
+                .filter(|(_, p, _)| *p == format!("{EX}card"))
+                .map(|(s, _, o)| (s.clone(), o.clone()))
+                .collect();
+            assert_eq!(cards.len(), 3);
+            let blanks: HashSet<_> = cards.values().collect();
+            assert_eq!(blanks.len(), 3);
+            for (person, card) in &cards {
+                assert!(card.starts_with("_:"), "{card}");
+                let name = person.strip_prefix(EX).unwrap();
+                assert!(triples.contains(&triple(card, &format!("{EX}name"), name)));
+            }
+        }
     }
 }
@@ -0,0 +1,355 @@
+//! Batched writes committed as a single transaction.
+//!
+//! A [`Batch`] collects a mix of creates, updates and deletes and commits
+//! them together: one transaction, one WAL flush. Use it through
+//! [`Session::batch()`](crate::Session::batch).
+//!
+//! Writes are applied as the batch runs, as uncommitted versions nobody else
+//! sees, so later operations can use the returned ids and see earlier
+//! updates. Only increments wait for the commit. A failed batch, or one whose
+//! WAL write fails, is undone as a whole.
+
+use std::sync::Arc;
+
+use grafeo_adapters::storage::wal::WalManager;
+use grafeo_common::types::{EdgeId, EpochId, NodeId, TxId, Value};
+use grafeo_common::utils::error::{Error, Result};
+use grafeo_core::graph::lpg::LpgStore;
+
+use super::{TransactionManager, commit_writes};
+use crate::catalog::Catalog;
+use crate::changefeed::ChangeFeed;
+
+/// An increment waiting for the batch to commit.
+enum Increment {
+    Node(NodeId, String, Value),
+    Edge(EdgeId, String, Value),
+}
+
+/// What a committed batch produced.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchOutcome {
+    /// Nodes created by the batch, in creation order.
+    pub created_nodes: Vec<NodeId>,
+    /// Edges created by the batch, in creation order.
+    pub created_edges: Vec<EdgeId>,
+    /// Epoch at which the batch became visible.
+    pub commit_epoch: EpochId,
+}
+
+/// A set of mixed writes that commit or roll back as one unit.
+///
+/// Every method that can fail returns an error without side effects; return
+/// that error from the batch closure and the whole batch is rolled back.
+pub struct Batch {
+    store: Arc<LpgStore>,
+    tx_manager: Arc<TransactionManager>,
+    tx_id: TxId,
+    epoch: EpochId,
+    /// The catalog to check names against, in strict schema mode.
+    schema: Option<Arc<Catalog>>,
+    increments: Vec<Increment>,
+    created_nodes: Vec<NodeId>,
+    created_edges: Vec<EdgeId>,
+}
+
+impl Batch {
+    /// Starts a batch in a fresh transaction.
+    pub(crate) fn begin(store: Arc<LpgStore>, tx_manager: Arc<TransactionManager>) -> Self {
+        let tx_id = tx_manager.begin();
+        let epoch = tx_manager
+            .start_epoch(tx_id)
+            .unwrap_or_else(|| tx_manager.current_epoch());
+        Self {
+            store,
+            tx_manager,
+            tx_id,
+            epoch,
+            schema: None,
+            increments: Vec::new(),
+            created_nodes: Vec::new(),
+            created_edges: Vec::new(),
+        }
+    }
+
+    /// Only allows labels, edge types and property keys declared in
+    /// `catalog`, as in strict schema mode.
+    #[must_use]
+    pub(crate) fn with_strict_schema(mut self, catalog: Arc<Catalog>) -> Self {
+        self.schema = Some(catalog);
+        self
+    }
+
+    /// Creates a node with the given labels.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in strict schema mode if a label hasn't been
+    /// declared.
+    pub fn create_node(&mut self, labels: &[&str]) -> Result<NodeId> {
+        self.create_node_with_props(labels, std::iter::empty::<(&str, Value)>())
+    }
+
+    /// Creates a node with labels and properties.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error in strict schema mode if a label or property key
+    /// hasn't been declared.
+    pub fn create_node_with_props<'a>(
+        &mut self,
+        labels: &[&str],
+        properties: impl IntoIterator<Item = (&'a str, Value)>,
+    ) -> Result<NodeId> {
+        let properties: Vec<(&str, Value)> = properties.into_iter().collect();
+        self.check_schema(labels, None, properties.iter().map(|(k, _)| *k))?;
+
+        // Created as of an epoch that never comes, until the commit stamps
+        // the real one
+        let id = self.store.create_node_with_props_versioned(
+            labels,
+            properties,
+            EpochId::PENDING,
+            self.tx_id,
+        );
+        self.created_nodes.push(id);
+        Ok(id)
+    }
+
+    /// Creates an edge between two nodes.
+    ///
+    /// Either endpoint may be a node created earlier in this batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either endpoint doesn't exist, or in strict schema
+    /// mode if the edge type hasn't been declared.
+    pub fn create_edge(&mut self, src: NodeId, dst: NodeId, edge_type: &str) -> Result<EdgeId> {
+        self.check_schema(&[], Some(edge_type), [])?;
+        self.check_node(src)?;
+        self.check_node(dst)?;
+
+        let id =
+            self.store
+                .create_edge_versioned(src, dst, edge_type, EpochId::PENDING, self.tx_id);
+        self.created_edges.push(id);
+        Ok(id)
+    }
+
+    /// Sets a property on a node.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node doesn't exist, on a write conflict with
+    /// another open transaction, or in strict schema mode if the key hasn't
+    /// been declared.
+    pub fn set_node_property(&mut self, id: NodeId, key: &str, value: Value) -> Result<()> {
+        self.check_schema(&[], None, [key])?;
+        self.check_node(id)?;
+        self.tx_manager.record_write(self.tx_id, id)?;
+        self.store
+            .set_node_property_versioned(id, key, value, self.tx_id)
+    }
+
+    /// Sets a property on an edge.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the edge doesn't exist, on a write conflict with
+    /// another open transaction, or in strict schema mode if the key hasn't
+    /// been declared.
+    pub fn set_edge_property(&mut self, id: EdgeId, key: &str, value: Value) -> Result<()> {
+        self.check_schema(&[], None, [key])?;
+        self.check_edge(id)?;
+        self.tx_manager.record_write(self.tx_id, id)?;
+        self.store
+            .set_edge_property_versioned(id, key, value, self.tx_id)
+    }
+
+    /// Adds `delta` to a numeric node property when the batch commits.
+    ///
+    /// The addition is made to the value current at commit time, and
+    /// increments don't conflict with each other, so concurrent batches
+    /// bumping the same counter all count. A missing property starts from
+    /// `delta`. If the node is deleted or the property overwritten with a
+    /// non-number before the batch commits, the increment is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node doesn't exist, if `delta` or the
+    /// property's current value isn't numeric, or in strict schema mode if
+    /// the key hasn't been declared.
+    pub fn increment_node_property(&mut self, id: NodeId, key: &str, delta: Value) -> Result<()> {
+        self.check_schema(&[], None, [key])?;
+        self.check_node(id)?;
+        let current = self
+            .store
+            .get_node_versioned(id, self.epoch, self.tx_id)
+            .and_then(|node| node.get_property(key).cloned());
+        check_increment(key, current.as_ref(), &delta)?;
+        self.increments
+            .push(Increment::Node(id, key.to_string(), delta));
+        Ok(())
+    }
+
+    /// Adds `delta` to a numeric edge property when the batch commits.
+    ///
+    /// Behaves like [`increment_node_property`](Self::increment_node_property).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the edge doesn't exist, if `delta` or the
+    /// property's current value isn't numeric, or in strict schema mode if
+    /// the key hasn't been declared.
+    pub fn increment_edge_property(&mut self, id: EdgeId, key: &str, delta: Value) -> Result<()> {
+        self.check_schema(&[], None, [key])?;
+        self.check_edge(id)?;
+        let current = self
+            .store
+            .get_edge_versioned(id, self.epoch, self.tx_id)
+            .and_then(|edge| edge.get_property(key).cloned());
+        check_increment(key, current.as_ref(), &delta)?;
+        self.increments
+            .push(Increment::Edge(id, key.to_string(), delta));
+        Ok(())
+    }
+
+    /// Deletes a node.
+    ///
+    /// Like [`LpgStore::delete_node`], this leaves the node's edges alone -
+    /// delete them first if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node doesn't exist or is already deleted, or
+    /// on a write conflict with another open transaction.
+    pub fn delete_node(&mut self, id: NodeId) -> Result<()> {
+        self.check_node(id)?;
+        self.tx_manager.record_write(self.tx_id, id)?;
+        self.store
+            .delete_node_versioned(id, self.epoch, self.tx_id)?;
+        Ok(())
+    }
+
+    /// Deletes an edge.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the edge doesn't exist or is already deleted, or
+    /// on a write conflict with another open transaction.
+    pub fn delete_edge(&mut self, id: EdgeId) -> Result<()> {
+        self.check_edge(id)?;
+        self.tx_manager.record_write(self.tx_id, id)?;
+        self.store
+            .delete_edge_versioned(id, self.epoch, self.tx_id)?;
+        Ok(())
+    }
+
+    /// Returns the ids of the nodes created so far.
+    #[must_use]
+    pub fn created_nodes(&self) -> &[NodeId] {
+        &self.created_nodes
+    }
+
+    /// Returns the ids of the edges created so far.
+    #[must_use]
+    pub fn created_edges(&self) -> &[EdgeId] {
+        &self.created_edges
+    }
+
+    fn check_schema<'a>(
+        &self,
+        labels: &[&str],
+        edge_type: Option<&str>,
+        property_keys: impl IntoIterator<Item = &'a str>,
+    ) -> Result<()> {
+        if let Some(catalog) = &self.schema {
+            catalog.validate_write(labels.iter().copied(), edge_type, property_keys)?;
+        }
+        Ok(())
+    }
+
+    fn check_node(&self, id: NodeId) -> Result<()> {
+        let visible = self
+            .store
+            .get_node_versioned(id, self.epoch, self.tx_id)
+            .is_some();
+        if visible {
+            Ok(())
+        } else {
+            Err(Error::NodeNotFound(id))
+        }
+    }
+
+    fn check_edge(&self, id: EdgeId) -> Result<()> {
+        let visible = self
+            .store
+            .get_edge_versioned(id, self.epoch, self.tx_id)
+            .is_some();
+        if visible {
+            Ok(())
+        } else {
+            Err(Error::EdgeNotFound(id))
+        }
+    }
+
+    /// Commits the batch, applying the increments, writing it to the WAL
+    /// once and then publishing it to `changes`.
+    ///
+    /// On a write-write conflict, if the created nodes and edges take the
+    /// batch past the write-set limit, or if the WAL write fails, the batch
+    /// is rolled back instead.
+    pub(crate) fn commit(
+        self,
+        wal: Option<&WalManager>,
+        changes: &ChangeFeed,
+    ) -> Result<BatchOutcome> {
+        let store = &self.store;
+        let tx_id = self.tx_id;
+        let increments = self.increments;
+        let commit_epoch = commit_writes(store, &self.tx_manager, tx_id, wal, changes, || {
+            // Added to the latest committed value, logged as the result so
+            // replay is idempotent
+            let now = store.current_epoch();
+            for increment in increments {
+                match increment {
+                    Increment::Node(id, key, delta) => {
+                        if store.get_node_versioned(id, now, tx_id).is_some() {
+                            store.increment_node_property_versioned(id, &key, &delta, tx_id)?;
+                        }
+                    }
+                    Increment::Edge(id, key, delta) => {
+                        if store.get_edge_versioned(id, now, tx_id).is_some() {
+                            store.increment_edge_property_versioned(id, &key, &delta, tx_id)?;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })?;
+
+        Ok(BatchOutcome {
+            created_nodes: self.created_nodes,
+            created_edges: self.created_edges,
+            commit_epoch,
+        })
+    }
+
+    /// Discards everything the batch did.
+    pub(crate) fn rollback(self) {
+        self.store.discard_uncommitted_versions(self.tx_id);
+        let _ = self.tx_manager.abort(self.tx_id);
+    }
+}
+
+/// Checks that `delta` can be added to a property holding `current`.
+fn check_increment(key: &str, current: Option<&Value>, delta: &Value) -> Result<()> {
+    let numeric = |value: &Value| matches!(value, Value::Int64(_) | Value::Float64(_));
+    if numeric(delta) && current.is_none_or(|value| value.is_null() || numeric(value)) {
+        Ok(())
+    } else {
+        Err(Error::InvalidValue(format!(
+            "cannot add {delta:?} to property '{key}' holding {current:?}"
+        )))
+    }
+}
@@ -0,0 +1,138 @@
+//! Committing what a transaction wrote.
+//!
+//! Transactions write to the store as they go (see [`TxWrite`]). Committing
+//! one is where those writes become durable and visible: they're logged
+//! ahead, stamped with the commit epoch, and only then published to change
+//! feed subscribers, all before the epoch itself is published. If anything
+//! fails on the way the writes are undone, so nobody sees part of them.
+
+use grafeo_adapters::storage::wal::{WalManager, WalRecord};
+use grafeo_common::types::{EpochId, TxId};
+use grafeo_common::utils::error::Result;
+use grafeo_core::graph::lpg::{LpgStore, TxWrite};
+
+use super::{EntityId, TransactionManager};
+use crate::changefeed::ChangeFeed;
+
+/// Commits transaction `tx_id`, or rolls it back if the commit fails.
+///
+/// `apply` runs first, once no other transaction can commit, for writes
+/// that are only made at commit time. Conflict detection covers what the
+/// transaction wrote before, not what `apply` writes.
+pub(crate) fn commit_writes(
+    store: &LpgStore,
+    tx_manager: &TransactionManager,
+    tx_id: TxId,
+    wal: Option<&WalManager>,
+    changes: &ChangeFeed,
+    apply: impl FnOnce() -> Result<()>,
+) -> Result<EpochId> {
+    let result = store
+        .tx_writes(tx_id)
+        .iter()
+        .map(written_entity)
+        .try_for_each(|entity| tx_manager.record_write(tx_id, entity))
+        .and_then(|()| {
+            tx_manager.commit_with(tx_id, |commit_epoch| {
+                apply()?;
+                let records: Vec<WalRecord> = store
+                    .tx_writes(tx_id)
+                    .into_iter()
+                    .filter_map(to_record)
+                    .collect();
+                if let Some(wal) = wal {
+                    write_ahead(wal, tx_id, &records)?;
+                }
+                store.finalize_committed_versions(tx_id, commit_epoch);
+                changes.publish(&records);
+                Ok(())
+            })
+        });
+
+    match result {
+        Ok(commit_epoch) => {
+            store.gc_property_versions(tx_manager.min_active_epoch());
+            Ok(commit_epoch)
+        }
+        Err(e) => {
+            store.discard_uncommitted_versions(tx_id);
+            // The transaction may already be finished if the commit failed
+            let _ = tx_manager.abort(tx_id);
+            Err(e)
+        }
+    }
+}
+
+/// Logs a transaction's records and its commit, and syncs the log.
+///
+/// If that fails the transaction is marked aborted in the log, as far as
+/// the log still takes writes, so recovery drops what made it in.
+fn write_ahead(wal: &WalManager, tx_id: TxId, records: &[WalRecord]) -> Result<()> {
+    let written = records
+        .iter()
+        .try_for_each(|record| wal.log(record))
+        .and_then(|()| wal.log(&WalRecord::TxCommit { tx_id }))
+        .and_then(|()| wal.sync());
+    if written.is_err() {
+        let _ = wal.log(&WalRecord::TxAbort { tx_id });
+    }
+    written
+}
+
+/// The node or edge a write touched.
+fn written_entity(write: &TxWrite) -> EntityId {
+    match *write {
+        TxWrite::NodeCreated { id, .. }
+        | TxWrite::NodePropertySet { id, .. }
+        | TxWrite::LabelAdded { id, .. }
+        | TxWrite::LabelRemoved { id, .. }
+        | TxWrite::NodeDeleted { id } => id.into(),
+        TxWrite::EdgeCreated { id, .. }
+        | TxWrite::EdgePropertySet { id, .. }
+        | TxWrite::EdgeDeleted { id } => id.into(),
+    }
+}
+
+/// The WAL record for a write.
+///
+/// Property removals have no record, as with
+/// [`GrafeoDB::remove_node_property`](crate::GrafeoDB::remove_node_property).
+fn to_record(write: TxWrite) -> Option<WalRecord> {
+    Some(match write {
+        TxWrite::NodeCreated { id, labels } => WalRecord::CreateNode {
+            id,
+            labels: labels.iter().map(ToString::to_string).collect(),
+        },
+        TxWrite::EdgeCreated {
+            id,
+            src,
+            dst,
+            edge_type,
+        } => WalRecord::CreateEdge {
+            id,
+            src,
+            dst,
+            edge_type: edge_type.to_string(),
+        },
+        TxWrite::NodePropertySet { id, key, value, .. } => WalRecord::SetNodeProperty {
+            id,
+            key: key.to_string(),
+            value: value?,
+        },
+        TxWrite::EdgePropertySet { id, key, value, .. } => WalRecord::SetEdgeProperty {
+            id,
+            key: key.to_string(),
+            value: value?,
+        },
+        TxWrite::LabelAdded { id, label } => WalRecord::AddNodeLabel {
+            id,
+            label: label.to_string(),
+        },
+        TxWrite::LabelRemoved { id, label } => WalRecord::RemoveNodeLabel {
+            id,
+            label: label.to_string(),
+        },
+        TxWrite::NodeDeleted { id } => WalRecord::DeleteNode { id },
+        TxWrite::EdgeDeleted { id } => WalRecord::DeleteEdge { id },
+    })
+}
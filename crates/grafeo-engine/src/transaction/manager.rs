@@ -19,6 +19,20 @@ pub enum TxState {
     Aborted,
 }
 
+/// How much of other transactions' work a transaction can see.
+///
+/// See the [module docs](super) for the anomalies each level prevents.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// Each statement sees everything committed before it started.
+    ReadCommitted,
+    /// Rows read once read the same until the transaction ends.
+    RepeatableRead,
+    /// The whole transaction reads from the snapshot taken when it began.
+    #[default]
+    Snapshot,
+}
+
 /// Entity identifier for write tracking.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum EntityId {
@@ -40,26 +54,43 @@ impl From<EdgeId> for EntityId {
     }
 }
 
+/// Where a savepoint sits in a transaction's writes.
+///
+/// The store logs a transaction's writes in order, so rolling back to the
+/// savepoint undoes every write logged past the mark - creations, property
+/// updates, label changes and deletes alike.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SavepointMark {
+    /// How many writes the transaction had made when the savepoint was set.
+    pub writes: usize,
+}
+
 /// Information about an active transaction.
 pub struct TxInfo {
     /// Transaction state.
     pub state: TxState,
     /// Start epoch (snapshot epoch for reads).
     pub start_epoch: EpochId,
+    /// Isolation level the transaction was started with.
+    pub isolation: IsolationLevel,
     /// Set of entities written by this transaction.
     pub write_set: HashSet<EntityId>,
     /// Set of entities read by this transaction (for serializable isolation).
     pub read_set: HashSet<EntityId>,
+    /// Savepoints set in this transaction, oldest first.
+    pub savepoints: Vec<(String, SavepointMark)>,
 }
 
 impl TxInfo {
     /// Creates a new transaction info.
-    fn new(start_epoch: EpochId) -> Self {
+    fn new(start_epoch: EpochId, isolation: IsolationLevel) -> Self {
         Self {
             state: TxState::Active,
             start_epoch,
+            isolation,
             write_set: HashSet::new(),
             read_set: HashSet::new(),
+            savepoints: Vec::new(),
         }
     }
 }
@@ -75,6 +106,8 @@ pub struct TransactionManager {
     /// Committed transaction epochs (for conflict detection).
     /// Maps TxId -> commit epoch.
     committed_epochs: RwLock<FxHashMap<TxId, EpochId>>,
+    /// Most entities a single transaction may write, if limited.
+    write_set_limit: Option<usize>,
 }
 
 impl TransactionManager {
@@ -88,15 +121,31 @@ impl TransactionManager {
             current_epoch: AtomicU64::new(0),
             transactions: RwLock::new(FxHashMap::default()),
             committed_epochs: RwLock::new(FxHashMap::default()),
+            write_set_limit: None,
         }
     }
 
-    /// Begins a new transaction.
+    /// Limits how many distinct entities a single transaction may write.
+    ///
+    /// A write past the limit fails, so one runaway transaction can't grow
+    /// without bound before it commits.
+    #[must_use]
+    pub fn with_write_set_limit(mut self, limit: usize) -> Self {
+        self.write_set_limit = Some(limit);
+        self
+    }
+
+    /// Begins a new transaction at the default isolation level.
     pub fn begin(&self) -> TxId {
+        self.begin_with_isolation(IsolationLevel::default())
+    }
+
+    /// Begins a new transaction at the given isolation level.
+    pub fn begin_with_isolation(&self, isolation: IsolationLevel) -> TxId {
         let tx_id = TxId::new(self.next_tx_id.fetch_add(1, Ordering::Relaxed));
         let epoch = EpochId::new(self.current_epoch.load(Ordering::Acquire));
 
-        let info = TxInfo::new(epoch);
+        let info = TxInfo::new(epoch, isolation);
         self.transactions.write().insert(tx_id, info);
         tx_id
     }
@@ -105,7 +154,8 @@ impl TransactionManager {
     ///
     /// # Errors
     ///
-    /// Returns an error if the transaction is not active.
+    /// Returns an error if the transaction is not active, or if the write
+    /// would take it past the write-set limit.
     pub fn record_write(&self, tx_id: TxId, entity: impl Into<EntityId>) -> Result<()> {
         let mut txns = self.transactions.write();
         let info = txns.get_mut(&tx_id).ok_or_else(|| {
@@ -120,7 +170,17 @@ impl TransactionManager {
             )));
         }
 
-        info.write_set.insert(entity.into());
+        let entity = entity.into();
+        if let Some(limit) = self.write_set_limit
+            && info.write_set.len() >= limit
+            && !info.write_set.contains(&entity)
+        {
+            return Err(Error::Transaction(TransactionError::WriteSetTooLarge(
+                limit,
+            )));
+        }
+
+        info.write_set.insert(entity);
         Ok(())
     }
 
@@ -155,58 +215,51 @@ impl TransactionManager {
     /// - The transaction is not active
     /// - There's a write-write conflict with another committed transaction
     pub fn commit(&self, tx_id: TxId) -> Result<EpochId> {
+        self.commit_with(tx_id, |_| Ok(()))
+    }
+
+    /// Commits a transaction, running `finish` with the commit epoch before
+    /// the epoch is published.
+    ///
+    /// `finish` runs once conflict detection has passed, while no other
+    /// transaction can commit, and is where the writes get logged ahead and
+    /// stamped with the epoch. If it fails the transaction stays active and
+    /// the epoch unused, so the caller can roll back.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction is not active, on a write-write
+    /// conflict with a transaction that committed since this one started,
+    /// or if `finish` fails.
+    pub fn commit_with(
+        &self,
+        tx_id: TxId,
+        finish: impl FnOnce(EpochId) -> Result<()>,
+    ) -> Result<EpochId> {
         let mut txns = self.transactions.write();
         let committed = self.committed_epochs.read();
 
         // First, validate the transaction exists and is active
-        {
-            let info = txns.get(&tx_id).ok_or_else(|| {
-                Error::Transaction(TransactionError::InvalidState(
-                    "Transaction not found".to_string(),
-                ))
-            })?;
-
-            if info.state != TxState::Active {
-                return Err(Error::Transaction(TransactionError::InvalidState(
-                    "Transaction is not active".to_string(),
-                )));
-            }
-        }
-
-        // Get our write set for conflict checking
-        let our_write_set: HashSet<EntityId> = txns
-            .get(&tx_id)
-            .map(|info| info.write_set.clone())
-            .unwrap_or_default();
-
-        let our_start_epoch = txns
-            .get(&tx_id)
-            .map(|info| info.start_epoch)
-            .unwrap_or(EpochId::new(0));
-
-        // Check for write-write conflicts with other committed transactions
-        for (other_tx, other_info) in txns.iter() {
-            if *other_tx == tx_id {
-                continue;
-            }
-            if other_info.state == TxState::Committed {
-                // Check if any of our writes conflict with their writes
-                for entity in &our_write_set {
-                    if other_info.write_set.contains(entity) {
-                        return Err(Error::Transaction(TransactionError::WriteConflict(
-                            format!("Write-write conflict on entity {:?}", entity),
-                        )));
-                    }
-                }
-            }
+        let info = txns.get(&tx_id).ok_or_else(|| {
+            Error::Transaction(TransactionError::InvalidState(
+                "Transaction not found".to_string(),
+            ))
+        })?;
+        if info.state != TxState::Active {
+            return Err(Error::Transaction(TransactionError::InvalidState(
+                "Transaction is not active".to_string(),
+            )));
         }
+        let our_write_set = &info.write_set;
+        let our_start_epoch = info.start_epoch;
 
-        // Also check against recently committed transactions
+        // Check for write-write conflicts with transactions that committed
+        // after we started
         for (other_tx, commit_epoch) in committed.iter() {
             if *other_tx != tx_id && commit_epoch.as_u64() > our_start_epoch.as_u64() {
                 // Check if that transaction wrote to any of our entities
                 if let Some(other_info) = txns.get(other_tx) {
-                    for entity in &our_write_set {
+                    for entity in our_write_set {
                         if other_info.write_set.contains(entity) {
                             return Err(Error::Transaction(TransactionError::WriteConflict(
                                 format!("Write-write conflict on entity {:?}", entity),
@@ -217,13 +270,18 @@ impl TransactionManager {
             }
         }
 
-        // Commit successful - advance epoch atomically
+        // Nothing else commits while we hold the lock, so the next epoch is
+        // ours; it's only published once `finish` succeeds
+        let commit_epoch = self.current_epoch().next();
+        finish(commit_epoch)?;
         // SeqCst ensures all threads see commits in a consistent total order
-        let commit_epoch = EpochId::new(self.current_epoch.fetch_add(1, Ordering::SeqCst) + 1);
+        self.current_epoch
+            .store(commit_epoch.as_u64(), Ordering::SeqCst);
 
         // Now update state
         if let Some(info) = txns.get_mut(&tx_id) {
             info.state = TxState::Committed;
+            info.savepoints.clear();
         }
 
         // Record commit epoch (need to drop read lock first)
@@ -254,9 +312,74 @@ impl TransactionManager {
         }
 
         info.state = TxState::Aborted;
+        info.savepoints.clear();
         Ok(())
     }
 
+    /// Sets a savepoint in an active transaction.
+    ///
+    /// Reusing a name shadows the earlier savepoint until it's released.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction is not active.
+    pub fn savepoint(&self, tx_id: TxId, name: &str, mark: SavepointMark) -> Result<()> {
+        self.with_active(tx_id, |info| {
+            info.savepoints.push((name.to_string(), mark));
+            Ok(())
+        })
+    }
+
+    /// Drops the savepoints set after `name` and returns where `name` sits.
+    ///
+    /// The savepoint itself stays, so the transaction can roll back to it
+    /// again. Undoing the writes made since is up to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction is not active or has no
+    /// savepoint called `name`.
+    pub fn rollback_to_savepoint(&self, tx_id: TxId, name: &str) -> Result<SavepointMark> {
+        self.with_active(tx_id, |info| {
+            let index = find_savepoint(&info.savepoints, name)?;
+            info.savepoints.truncate(index + 1);
+            Ok(info.savepoints[index].1)
+        })
+    }
+
+    /// Removes the savepoint `name`, and any set after it, keeping the
+    /// writes made since.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction is not active or has no
+    /// savepoint called `name`.
+    pub fn release_savepoint(&self, tx_id: TxId, name: &str) -> Result<()> {
+        self.with_active(tx_id, |info| {
+            let index = find_savepoint(&info.savepoints, name)?;
+            info.savepoints.truncate(index);
+            Ok(())
+        })
+    }
+
+    /// Runs `f` on an active transaction's info.
+    fn with_active<T>(&self, tx_id: TxId, f: impl FnOnce(&mut TxInfo) -> Result<T>) -> Result<T> {
+        let mut txns = self.transactions.write();
+        let info = txns.get_mut(&tx_id).ok_or_else(|| {
+            Error::Transaction(TransactionError::InvalidState(
+                "Transaction not found".to_string(),
+            ))
+        })?;
+
+        if info.state != TxState::Active {
+            return Err(Error::Transaction(TransactionError::InvalidState(
+                "Transaction is not active".to_string(),
+            )));
+        }
+
+        f(info)
+    }
+
     /// Returns the write set of a transaction.
     ///
     /// This returns a copy of the entities written by this transaction,
@@ -296,6 +419,28 @@ impl TransactionManager {
             .map(|info| info.start_epoch)
     }
 
+    /// Returns the isolation level of a transaction.
+    pub fn isolation(&self, tx_id: TxId) -> Option<IsolationLevel> {
+        self.transactions
+            .read()
+            .get(&tx_id)
+            .map(|info| info.isolation)
+    }
+
+    /// Returns the epoch a transaction's next statement should read at.
+    ///
+    /// Read committed transactions read at the current epoch, so each
+    /// statement sees the latest commits; the other levels keep reading at
+    /// the start epoch.
+    pub fn read_epoch(&self, tx_id: TxId) -> Option<EpochId> {
+        let txns = self.transactions.read();
+        let info = txns.get(&tx_id)?;
+        Some(match info.isolation {
+            IsolationLevel::ReadCommitted => self.current_epoch(),
+            IsolationLevel::RepeatableRead | IsolationLevel::Snapshot => info.start_epoch,
+        })
+    }
+
     /// Returns the current epoch.
     #[must_use]
     pub fn current_epoch(&self) -> EpochId {
@@ -403,6 +548,14 @@ impl TransactionManager {
     }
 }
 
+/// Finds the most recent savepoint called `name`.
+fn find_savepoint(savepoints: &[(String, SavepointMark)], name: &str) -> Result<usize> {
+    savepoints
+        .iter()
+        .rposition(|(n, _)| n == name)
+        .ok_or_else(|| Error::Transaction(TransactionError::SavepointNotFound(name.to_string())))
+}
+
 impl Default for TransactionManager {
     fn default() -> Self {
         Self::new()
@@ -447,6 +600,43 @@ mod tests {
         assert!(commit_epoch.as_u64() > initial_epoch.as_u64());
     }
 
+    #[test]
+    fn test_read_epoch_follows_isolation() {
+        let mgr = TransactionManager::new();
+
+        let read_committed = mgr.begin_with_isolation(IsolationLevel::ReadCommitted);
+        let repeatable = mgr.begin_with_isolation(IsolationLevel::RepeatableRead);
+        let start = mgr.current_epoch();
+        assert_eq!(
+            mgr.isolation(repeatable),
+            Some(IsolationLevel::RepeatableRead)
+        );
+
+        let writer = mgr.begin();
+        mgr.commit(writer).unwrap();
+
+        assert_eq!(mgr.read_epoch(read_committed), Some(mgr.current_epoch()));
+        assert_eq!(mgr.read_epoch(repeatable), Some(start));
+    }
+
+    #[test]
+    fn test_write_set_limit() {
+        let mgr = TransactionManager::new().with_write_set_limit(2);
+
+        let tx = mgr.begin();
+        mgr.record_write(tx, NodeId::new(1)).unwrap();
+        mgr.record_write(tx, NodeId::new(2)).unwrap();
+        // Writing the same entity again doesn't grow the write set
+        mgr.record_write(tx, NodeId::new(1)).unwrap();
+
+        let err = mgr.record_write(tx, EdgeId::new(1)).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Transaction(TransactionError::WriteSetTooLarge(2))
+        ));
+        assert_eq!(mgr.get_write_set(tx).unwrap().len(), 2);
+    }
+
     #[test]
     fn test_gc_preserves_needed_write_sets() {
         let mgr = TransactionManager::new();
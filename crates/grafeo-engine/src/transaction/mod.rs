@@ -1,15 +1,29 @@
 //! Transaction management with MVCC and Snapshot Isolation.
 //!
-//! # Isolation Level
-//!
-//! Grafeo provides **Snapshot Isolation (SI)**, which offers strong consistency
-//! guarantees while maintaining high concurrency. Each transaction sees a consistent
-//! snapshot of the database as of its start time.
+//! # Isolation Levels
+//!
+//! Transactions default to **Snapshot Isolation (SI)**, which offers strong
+//! consistency guarantees while maintaining high concurrency. Each transaction
+//! sees a consistent snapshot of the database as of its start time. Use
+//! [`Session::begin_transaction_with`](crate::Session::begin_transaction_with)
+//! to pick a different [`IsolationLevel`]:
+//!
+//! | Level | Dirty read | Non-repeatable read | Phantom | Lost update | Write skew |
+//! | ----- | ---------- | ------------------- | ------- | ----------- | ---------- |
+//! | `ReadCommitted` | prevented | possible | possible | prevented | possible |
+//! | `RepeatableRead` | prevented | prevented | prevented | prevented | possible |
+//! | `Snapshot` | prevented | prevented | prevented | prevented | possible |
+//!
+//! Under `ReadCommitted` every statement reads at the latest committed epoch,
+//! so running the same query twice can return different rows. The other two
+//! levels read at the transaction's start epoch for its whole lifetime.
+//! `RepeatableRead` is served by the snapshot, which is why it also rules out
+//! phantoms. Write-write conflicts are detected at every level.
 //!
 //! ## Guarantees
 //!
-//! - **Read Consistency**: A transaction always reads the same values for the same
-//!   entities throughout its lifetime (repeatable reads).
+//! - **Read Consistency**: A snapshot transaction always reads the same values
+//!   for the same entities throughout its lifetime (repeatable reads).
 //! - **Write-Write Conflict Detection**: If two concurrent transactions write to the
 //!   same entity, the second to commit will be aborted.
 //! - **No Dirty Reads**: A transaction never sees uncommitted changes from other
@@ -45,14 +59,28 @@
 //! 3. **Constraint checking**: Validate invariants at commit time and retry if
 //!    violated.
 //!
+//! ## Savepoints
+//!
+//! A transaction can set named savepoints and roll back to one, undoing
+//! everything it wrote since - creations, property and label changes, and
+//! deletes - while keeping earlier work. Savepoints end when the transaction
+//! commits or aborts.
+//!
 //! ## Epoch-Based Versioning
 //!
 //! Grafeo uses epoch-based MVCC where:
 //! - Each commit advances the global epoch
-//! - Transactions read data visible at their start epoch
+//! - A committed transaction's versions are stamped with its commit epoch
+//! - Transactions read data visible at their start epoch (or, under read
+//!   committed, at the current epoch)
 //! - Version chains store multiple versions for concurrent access
+//! - Property values a transaction overwrites are kept as versions too, so a
+//!   snapshot reads the value it started with even after an update commits
 //! - Garbage collection removes versions no longer needed by active transactions
 //!
+//! A query run outside an explicit transaction gets one of its own, so its
+//! writes are versioned the same way and snapshots don't see them either.
+//!
 //! # Example
 //!
 //! ```ignore
@@ -68,8 +96,12 @@
 //! session.commit()?;
 //! ```
 
+mod batch;
+mod commit;
 mod manager;
 mod mvcc;
 
-pub use manager::{EntityId, TransactionManager, TxInfo, TxState};
+pub use batch::{Batch, BatchOutcome};
+pub(crate) use commit::commit_writes;
+pub use manager::{EntityId, IsolationLevel, SavepointMark, TransactionManager, TxInfo, TxState};
 pub use mvcc::{Version, VersionChain, VersionInfo};
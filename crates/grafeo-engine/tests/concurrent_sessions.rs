@@ -388,6 +388,47 @@ fn test_node_count_consistency() {
     assert_eq!(result.row_count(), 10, "Should see all 10 nodes");
 }
 
+#[test]
+fn test_concurrent_increments_sum() {
+    // SET n.prop += delta adds atomically, so no increment is lost
+    let db = Arc::new(GrafeoDB::new_in_memory());
+    db.session()
+        .execute("INSERT (:Counter {views: 0})")
+        .unwrap();
+
+    let num_threads = 4;
+    let barrier = Arc::new(Barrier::new(num_threads));
+
+    let handles: Vec<_> = (0..num_threads)
+        .map(|_| {
+            let db = Arc::clone(&db);
+            let barrier = Arc::clone(&barrier);
+
+            thread::spawn(move || {
+                let session = db.session();
+                barrier.wait();
+                for _ in 0..25 {
+                    session
+                        .execute("MATCH (c:Counter) SET c.views += 1")
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        handle.join().expect("Thread panicked");
+    }
+
+    let views: i64 = db
+        .session()
+        .execute("MATCH (c:Counter) RETURN c.views")
+        .unwrap()
+        .scalar()
+        .unwrap();
+    assert_eq!(views, 100, "Every increment should count");
+}
+
 // ============================================================================
 // Async Session Tests (using tokio)
 // ============================================================================
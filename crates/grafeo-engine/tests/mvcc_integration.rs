@@ -9,7 +9,7 @@ use std::sync::Arc;
 use grafeo_common::types::{EpochId, Value};
 use grafeo_core::graph::lpg::LpgStore;
 use grafeo_engine::{
-    GrafeoDB,
+    GrafeoDB, IsolationLevel,
     transaction::{TransactionManager, TxState},
 };
 
@@ -490,3 +490,214 @@ fn test_session_auto_commit_mode() {
         "Auto-committed data should be visible"
     );
 }
+
+// ============================================================================
+// Isolation Level Tests
+// ============================================================================
+
+/// Reads `Account` rows in a transaction at `isolation`, lets a second
+/// session commit another one, and reads again.
+fn read_twice_around_commit(isolation: IsolationLevel) -> (usize, usize) {
+    let db = GrafeoDB::new_in_memory();
+    db.session()
+        .execute("INSERT (:Account {balance: 100})")
+        .unwrap();
+
+    let mut reader = db.session();
+    let mut writer = db.session();
+
+    reader.begin_transaction_with(isolation).unwrap();
+    let first = reader
+        .execute("MATCH (a:Account) RETURN a.balance")
+        .unwrap()
+        .row_count();
+
+    writer.begin_tx().unwrap();
+    writer.execute("INSERT (:Account {balance: 50})").unwrap();
+    writer.commit().unwrap();
+
+    let second = reader
+        .execute("MATCH (a:Account) RETURN a.balance")
+        .unwrap()
+        .row_count();
+    reader.commit().unwrap();
+
+    (first, second)
+}
+
+#[test]
+fn test_read_committed_allows_non_repeatable_read() {
+    let (first, second) = read_twice_around_commit(IsolationLevel::ReadCommitted);
+
+    assert_eq!(first, 1);
+    assert_eq!(second, 2, "Read committed should see the other commit");
+}
+
+#[test]
+fn test_repeatable_read_blocks_non_repeatable_read() {
+    let (first, second) = read_twice_around_commit(IsolationLevel::RepeatableRead);
+
+    assert_eq!(first, 1);
+    assert_eq!(second, 1, "Repeatable read should keep its first answer");
+}
+
+/// Reads an `Account`'s balance in a transaction at `isolation`, lets a
+/// second session commit `SET a.balance = 50` on the same row, and reads it
+/// again. Returns both balances and how many rows still match the old one.
+///
+/// The second session updates in an explicit transaction if `writer_in_tx`
+/// is set, and with a single statement otherwise.
+fn update_between_reads(isolation: IsolationLevel, writer_in_tx: bool) -> (i64, i64, usize) {
+    let db = GrafeoDB::new_in_memory();
+    db.session()
+        .execute("INSERT (:Account {balance: 100})")
+        .unwrap();
+
+    let mut reader = db.session();
+    let mut writer = db.session();
+
+    reader.begin_transaction_with(isolation).unwrap();
+    let first: i64 = reader
+        .execute("MATCH (a:Account) RETURN a.balance")
+        .unwrap()
+        .scalar()
+        .unwrap();
+
+    if writer_in_tx {
+        writer.begin_tx().unwrap();
+    }
+    writer
+        .execute("MATCH (a:Account) SET a.balance = 50")
+        .unwrap();
+    if writer_in_tx {
+        writer.commit().unwrap();
+    }
+
+    let second: i64 = reader
+        .execute("MATCH (a:Account) RETURN a.balance")
+        .unwrap()
+        .scalar()
+        .unwrap();
+    let unchanged = reader
+        .execute("MATCH (a:Account) WHERE a.balance = 100 RETURN a")
+        .unwrap()
+        .row_count();
+    reader.commit().unwrap();
+
+    (first, second, unchanged)
+}
+
+#[test]
+fn test_read_committed_sees_committed_update() {
+    let (first, second, unchanged) = update_between_reads(IsolationLevel::ReadCommitted, true);
+
+    assert_eq!(first, 100);
+    assert_eq!(second, 50, "Read committed should see the other commit");
+    assert_eq!(unchanged, 0);
+}
+
+#[test]
+fn test_repeatable_read_keeps_updated_value() {
+    let (first, second, unchanged) = update_between_reads(IsolationLevel::RepeatableRead, true);
+
+    assert_eq!(first, 100);
+    assert_eq!(second, 100, "Repeatable read should keep the value it read");
+    assert_eq!(unchanged, 1);
+}
+
+#[test]
+fn test_snapshot_keeps_value_updated_outside_transaction() {
+    let (first, second, unchanged) = update_between_reads(IsolationLevel::Snapshot, false);
+
+    assert_eq!(first, 100);
+    assert_eq!(second, 100, "Snapshot should not see the later update");
+    assert_eq!(unchanged, 1);
+}
+
+#[test]
+fn test_uncommitted_update_stays_private() {
+    let db = GrafeoDB::new_in_memory();
+    db.session()
+        .execute("INSERT (:Account {balance: 100})")
+        .unwrap();
+
+    let mut writer = db.session();
+    writer.begin_tx().unwrap();
+    writer
+        .execute("MATCH (a:Account) SET a.balance = 70")
+        .unwrap();
+    let own: i64 = writer
+        .execute("MATCH (a:Account) RETURN a.balance")
+        .unwrap()
+        .scalar()
+        .unwrap();
+    assert_eq!(own, 70, "A transaction should see its own update");
+
+    let other = db.session();
+    let outside: i64 = other
+        .execute("MATCH (a:Account) RETURN a.balance")
+        .unwrap()
+        .scalar()
+        .unwrap();
+    assert_eq!(outside, 100, "Nobody else should see it before commit");
+
+    // A concurrent writer of the same row is turned away
+    let err = other
+        .execute("MATCH (a:Account) SET a.balance = 0")
+        .unwrap_err();
+    assert!(err.to_string().contains("conflict"), "{err}");
+
+    writer.rollback().unwrap();
+    let after: i64 = other
+        .execute("MATCH (a:Account) RETURN a.balance")
+        .unwrap()
+        .scalar()
+        .unwrap();
+    assert_eq!(after, 100);
+}
+
+#[test]
+fn test_uncommitted_insert_stays_private() {
+    let db = GrafeoDB::new_in_memory();
+    let mut writer = db.session();
+    writer.begin_tx().unwrap();
+    writer.execute("INSERT (:Account {balance: 10})").unwrap();
+    writer
+        .execute("MATCH (a:Account) CREATE (a)-[:OWNS]->(:Card)")
+        .unwrap();
+
+    let other = db.session();
+    let count = |session: &grafeo_engine::Session| {
+        session.execute("MATCH (n) RETURN n").unwrap().row_count()
+    };
+    assert_eq!(
+        count(&writer),
+        2,
+        "A transaction should see its own inserts"
+    );
+    assert_eq!(
+        count(&other),
+        0,
+        "Nobody else should see them before commit"
+    );
+    assert_eq!(db.edge_count(), 0);
+
+    writer.commit().unwrap();
+    assert_eq!(count(&other), 2);
+    assert_eq!(db.edge_count(), 1);
+}
+
+#[test]
+fn test_snapshot_is_the_default_isolation() {
+    let db = GrafeoDB::new_in_memory();
+    let mut reader = db.session();
+    let mut writer = db.session();
+
+    reader.begin_tx().unwrap();
+    writer.begin_tx().unwrap();
+    writer.execute("INSERT (:Account {balance: 50})").unwrap();
+    writer.commit().unwrap();
+
+    let result = reader.execute("MATCH (a:Account) RETURN a").unwrap();
+    assert_eq!(result.row_count(), 0);
+}
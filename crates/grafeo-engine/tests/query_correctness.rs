@@ -233,6 +233,56 @@ mod gql_basic_patterns {
         assert_eq!(result.row_count(), 2, "Should find 2 Company nodes");
     }
 
+    #[test]
+    fn test_match_nodes_with_all_labels() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        session.create_node_with_props(
+            &["Person", "Employee"],
+            [("name", Value::String("Alice".into()))],
+        );
+        session.create_node_with_props(&["Person"], [("name", Value::String("Bob".into()))]);
+        session.create_node_with_props(
+            &["Employee", "Contractor"],
+            [("name", Value::String("Carol".into()))],
+        );
+
+        let result = session
+            .execute("MATCH (n:Person:Employee) RETURN n.name")
+            .unwrap();
+        assert_eq!(result.row_count(), 1, "Only Alice has both labels");
+        assert_eq!(result.rows[0][0], Value::String("Alice".into()));
+
+        let result = session.execute("MATCH (n:Person:Robot) RETURN n").unwrap();
+        assert_eq!(result.row_count(), 0);
+    }
+
+    #[test]
+    fn test_match_self_loop() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        let alice =
+            session.create_node_with_props(&["Person"], [("name", Value::String("Alice".into()))]);
+        session.create_edge(alice, alice, "LIKES");
+
+        let result = session
+            .execute("MATCH (a:Person)-[:LIKES]->(b:Person) RETURN a.name, b.name")
+            .unwrap();
+        assert_eq!(result.row_count(), 1);
+        assert_eq!(result.rows[0][1], Value::String("Alice".into()));
+
+        // Undirected patterns see the loop once, not once per end
+        let result = session
+            .execute("MATCH (a:Person)-[:LIKES]-(b) RETURN b")
+            .unwrap();
+        assert_eq!(result.row_count(), 1);
+
+        let result = session
+            .execute("MATCH (a:Person)-[:LIKES*1..4]->(b) RETURN b")
+            .unwrap();
+        assert_eq!(result.row_count(), 4, "one walk per length, bounded by 4");
+    }
+
     #[test]
     fn test_match_with_property_filter() {
         let db = create_social_network();
@@ -332,6 +382,41 @@ mod gql_basic_patterns {
             .unwrap();
         assert_eq!(result.row_count(), 5, "Hub should connect to 5 spokes");
     }
+
+    #[test]
+    fn test_path_length_and_cost_from_variable_length_expand() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        let a = session.create_node_with_props(&["Stop"], [("name", Value::String("A".into()))]);
+        let b = session.create_node_with_props(&["Stop"], [("name", Value::String("B".into()))]);
+        let c = session.create_node_with_props(&["Stop"], [("name", Value::String("C".into()))]);
+        let d = session.create_node_with_props(&["Stop"], [("name", Value::String("D".into()))]);
+        db.create_edge_with_props(a, b, "ROAD", [("weight", Value::Int64(5))]);
+        db.create_edge_with_props(b, c, "ROAD", [("weight", Value::Int64(2))]);
+        db.create_edge_with_props(c, d, "ROAD", [("weight", Value::Int64(7))]);
+
+        let result = session
+            .execute(
+                "MATCH p = (s:Stop {name: 'A'})-[:ROAD*1..3]->(t) \
+                 RETURN t.name, length(p), cost(p, 'weight') ORDER BY length(p) DESC",
+            )
+            .unwrap();
+
+        let rows: Vec<_> = result
+            .rows
+            .iter()
+            .map(|row| (row[0].clone(), row[1].clone(), row[2].clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (Value::String("D".into()), Value::Int64(3), Value::Int64(14)),
+                (Value::String("C".into()), Value::Int64(2), Value::Int64(7)),
+                (Value::String("B".into()), Value::Int64(1), Value::Int64(5)),
+            ]
+        );
+    }
 }
 
 // ============================================================================
@@ -489,6 +574,54 @@ mod gql_joins {
         );
     }
 
+    #[test]
+    fn test_order_by_keys_from_different_variables() {
+        let db = create_social_network();
+        let session = db.session();
+
+        // b.age isn't returned, and breaks ties within each a.name descending
+        let result = session
+            .execute(
+                "MATCH (a:Person)-[:KNOWS]->(b:Person) \
+                 RETURN a.name, b.name ORDER BY a.name ASC, b.age DESC",
+            )
+            .unwrap();
+
+        let rows: Vec<_> = result
+            .rows
+            .iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (Value::String("Alice".into()), Value::String("Carol".into())),
+                (Value::String("Alice".into()), Value::String("Bob".into())),
+                (Value::String("Bob".into()), Value::String("Carol".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_optional_match_keeps_unmatched_rows() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session
+            .execute("MATCH (a:Person) OPTIONAL MATCH (a)-[:KNOWS]->(b) RETURN a.name, b.name")
+            .unwrap();
+
+        // Alice knows two people, Bob one, and Carol nobody
+        assert_eq!(result.row_count(), 4);
+        let carol: Vec<_> = result
+            .rows
+            .iter()
+            .filter(|row| row[0] == Value::String("Carol".into()))
+            .collect();
+        assert_eq!(carol.len(), 1);
+        assert_eq!(carol[0][1], Value::Null);
+    }
+
     #[test]
     #[ignore = "Parser issue with 'Node' label name - reserved keyword handling"]
     fn test_chain_full_traversal() {
@@ -508,183 +641,1299 @@ mod gql_joins {
     }
 }
 
-// ============================================================================
-// GQL Mutation Tests
-// ============================================================================
+// ============================================================================
+// GQL Mutation Tests
+// ============================================================================
+
+#[cfg(feature = "gql")]
+mod gql_mutations {
+    use super::*;
+
+    #[test]
+    fn test_insert_node() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        session
+            .execute("INSERT (:Person {name: 'Alice', age: 30})")
+            .unwrap();
+
+        let result = session
+            .execute("MATCH (n:Person) RETURN n.name, n.age")
+            .unwrap();
+        assert_eq!(result.row_count(), 1, "Should have 1 Person node");
+        // Note: Property values are stored correctly but order may vary
+        // Check name exists
+        assert!(
+            result.rows[0]
+                .iter()
+                .any(|v| *v == Value::String("Alice".into())),
+            "Should find Alice in result"
+        );
+    }
+
+    #[test]
+    fn test_insert_multiple_nodes() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        session.execute("INSERT (:Person {name: 'Alice'})").unwrap();
+        session.execute("INSERT (:Person {name: 'Bob'})").unwrap();
+        session.execute("INSERT (:Person {name: 'Carol'})").unwrap();
+
+        let result = session.execute("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(result.row_count(), 3, "Should have 3 Person nodes");
+    }
+
+    #[test]
+    fn test_transaction_commit() {
+        let db = GrafeoDB::new_in_memory();
+        let mut session = db.session();
+
+        session.begin_tx().unwrap();
+        session.execute("INSERT (:Person {name: 'Alice'})").unwrap();
+        session.commit().unwrap();
+
+        let result = session.execute("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(result.row_count(), 1, "Node should exist after commit");
+    }
+
+    #[test]
+    fn test_set_map_replace_vs_merge() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        session
+            .execute("INSERT (:Person {name: 'Alice', age: 30})")
+            .unwrap();
+        session
+            .execute("INSERT (:Robot {name: 'R2', age: 40})")
+            .unwrap();
+
+        // `=` replaces every property, removing the ones not in the map
+        session
+            .execute("MATCH (n:Person) SET n = {name: 'Alicia'}")
+            .unwrap();
+        let result = session
+            .execute("MATCH (n:Person) RETURN n.name, n.age")
+            .unwrap();
+        assert_eq!(
+            result.rows[0],
+            vec![Value::String("Alicia".into()), Value::Null]
+        );
+
+        // `+=` only overwrites the keys in the map
+        session
+            .execute("MATCH (n:Robot) SET n += {name: 'C3PO'}")
+            .unwrap();
+        let result = session
+            .execute("MATCH (n:Robot) RETURN n.name, n.age")
+            .unwrap();
+        assert_eq!(
+            result.rows[0],
+            vec![Value::String("C3PO".into()), Value::Int64(40)]
+        );
+    }
+}
+
+// ============================================================================
+// Full-Text Index Tests
+// ============================================================================
+
+#[cfg(feature = "gql")]
+mod fulltext_index {
+    use super::*;
+
+    fn create_articles() -> GrafeoDB {
+        let db = GrafeoDB::new_in_memory();
+        for (title, body) in [
+            ("graphs", "Graph databases store nodes and edges"),
+            ("tables", "Relational databases store rows in tables"),
+            ("mixed", "A graph of tables is still a graph"),
+        ] {
+            db.create_node_with_props(&["Article"], [("title", title), ("body", body)]);
+        }
+        db.create_node_with_props(&["Note"], [("title", "note"), ("body", "graph notes")]);
+        db
+    }
+
+    fn titles(session: &grafeo_engine::Session, query: &str) -> Vec<String> {
+        let result = session.execute(query).unwrap();
+        let mut titles: Vec<String> = result
+            .rows
+            .iter()
+            .map(|row| row[0].as_str().unwrap().to_string())
+            .collect();
+        titles.sort();
+        titles
+    }
+
+    #[test]
+    fn test_create_fulltext_index_and_search() {
+        let db = create_articles();
+        let session = db.session();
+
+        let result = session
+            .execute("CREATE FULLTEXT INDEX ON :Article(body)")
+            .unwrap();
+        assert_eq!(result.columns, ["label", "property", "documents"]);
+        assert_eq!(
+            result.rows,
+            vec![vec![
+                Value::from("Article"),
+                Value::from("body"),
+                Value::Int64(3),
+            ]]
+        );
+
+        // Ranked lookups straight from the index
+        let index = db.store().fulltext_index("Article", "body").unwrap();
+        let ranked: Vec<_> = index
+            .search("graph")
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(ranked.len(), 2);
+        let phrase = index.search_phrase("databases store");
+        assert_eq!(phrase.len(), 2);
+
+        // Single-term and phrase predicates go through the index
+        assert_eq!(
+            titles(
+                &session,
+                "MATCH (a:Article) WHERE a.body CONTAINS 'graph' RETURN a.title"
+            ),
+            ["mixed"]
+        );
+        assert_eq!(
+            titles(
+                &session,
+                "MATCH (a:Article) WHERE a.body CONTAINS 'databases store' RETURN a.title"
+            ),
+            ["graphs", "tables"]
+        );
+        assert_eq!(
+            titles(
+                &session,
+                "MATCH (a:Article) WHERE a.body CONTAINS 'ph data' RETURN a.title"
+            ),
+            ["graphs"]
+        );
+        assert!(
+            titles(
+                &session,
+                "MATCH (a:Article) WHERE a.body CONTAINS 'store databases' RETURN a.title"
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_fulltext_index_follows_updates() {
+        let db = create_articles();
+        let session = db.session();
+        session
+            .execute("create fulltext index on :Article(body);")
+            .unwrap();
+
+        session
+            .execute("MATCH (a:Article) WHERE a.title = 'tables' SET a.body = 'graph tables'")
+            .unwrap();
+        session
+            .execute("INSERT (:Article {title: 'new', body: 'a new graph'})")
+            .unwrap();
+        assert_eq!(
+            titles(
+                &session,
+                "MATCH (a:Article) WHERE a.body CONTAINS 'graph' RETURN a.title"
+            ),
+            ["mixed", "new", "tables"]
+        );
+        assert!(
+            titles(
+                &session,
+                "MATCH (a:Article) WHERE a.body CONTAINS 'rows' RETURN a.title"
+            )
+            .is_empty()
+        );
+
+        let index = db.store().fulltext_index("Article", "body").unwrap();
+        assert_eq!(index.len(), 4);
+    }
+
+    #[test]
+    fn test_create_fulltext_index_rejects_bad_targets() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        assert!(
+            session
+                .execute("CREATE FULLTEXT INDEX ON Article(body)")
+                .is_err()
+        );
+        assert!(
+            session
+                .execute("CREATE FULLTEXT INDEX ON :Article")
+                .is_err()
+        );
+    }
+}
+
+// ============================================================================
+// Spatial Tests
+// ============================================================================
+
+#[cfg(feature = "gql")]
+mod spatial {
+    use super::*;
+
+    const RADIUS_QUERY: &str = "MATCH (p:Place) \
+        WHERE point.withinDistance(p.loc, point({x: 0, y: 0}), 5) RETURN p.name";
+
+    fn create_places() -> GrafeoDB {
+        let db = GrafeoDB::new_in_memory();
+        for (name, x, y) in [
+            ("origin", 0.0, 0.0),
+            ("edge", 3.0, 4.0),
+            ("near", -1.0, 2.0),
+            ("far", 10.0, 0.0),
+            ("corner", 4.0, 4.0),
+        ] {
+            db.create_node_with_props(
+                &["Place"],
+                [
+                    ("name", Value::from(name)),
+                    ("loc", Value::point(x, y, Value::CARTESIAN_SRID)),
+                ],
+            );
+        }
+        db
+    }
+
+    fn names(session: &grafeo_engine::Session, query: &str) -> Vec<String> {
+        let result = session.execute(query).unwrap();
+        let mut names: Vec<String> = result
+            .rows
+            .iter()
+            .map(|row| row[0].as_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_radius_query_with_and_without_index() {
+        let db = create_places();
+        let session = db.session();
+        let expected = ["edge", "near", "origin"];
+        assert_eq!(names(&session, RADIUS_QUERY), expected);
+
+        let result = session
+            .execute("CREATE SPATIAL INDEX ON :Place(loc)")
+            .unwrap();
+        assert_eq!(result.columns, ["label", "property", "points"]);
+        assert_eq!(result.rows[0][2], Value::Int64(5));
+        assert_eq!(names(&session, RADIUS_QUERY), expected);
+
+        // The index follows later inserts
+        db.create_node_with_props(
+            &["Place"],
+            [
+                ("name", Value::from("new")),
+                ("loc", Value::point(1.0, 1.0, Value::CARTESIAN_SRID)),
+            ],
+        );
+        assert_eq!(
+            names(&session, RADIUS_QUERY),
+            ["edge", "near", "new", "origin"]
+        );
+
+        let query = "MATCH (p:Place) \
+            WHERE point.withinBBox(p.loc, point({x: 0, y: 0}), point({x: 4, y: 4})) \
+            RETURN p.name";
+        assert_eq!(names(&session, query), ["corner", "edge", "new", "origin"]);
+    }
+
+    #[test]
+    fn test_distance_function() {
+        let db = create_places();
+        let session = db.session();
+        let result = session
+            .execute(
+                "MATCH (p:Place) WHERE p.name = 'edge' \
+                 RETURN distance(p.loc, point({x: 0, y: 0}))",
+            )
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Float64(5.0)]]);
+    }
+
+    #[test]
+    fn test_haversine_and_euclidean_distances() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        // One degree of longitude at the equator
+        let result = session
+            .execute(
+                "UNWIND [0] AS z RETURN \
+                 distance(point({longitude: 0, latitude: 0}), point({longitude: 1, latitude: 0})), \
+                 distance(point({longitude: 0, latitude: 0}), point({longitude: 1, latitude: 0}), 'euclidean')",
+            )
+            .unwrap();
+        let Value::Float64(meters) = result.rows[0][0] else {
+            panic!("expected a distance, got {:?}", result.rows[0][0]);
+        };
+        assert!((meters - 111_195.0).abs() < 10.0, "{meters}");
+        assert_eq!(result.rows[0][1], Value::Float64(1.0));
+    }
+
+    #[test]
+    fn test_mixed_srids_error() {
+        let db = create_places();
+        let session = db.session();
+        let query = "MATCH (p:Place) \
+            WHERE point.withinDistance(p.loc, point({longitude: 0, latitude: 0}), 5) \
+            RETURN p.name";
+        assert!(session.execute(query).is_err());
+
+        session
+            .execute("CREATE SPATIAL INDEX ON :Place(loc)")
+            .unwrap();
+        assert!(session.execute(query).is_err());
+    }
+}
+
+// ============================================================================
+// Temporal Tests
+// ============================================================================
+
+#[cfg(feature = "gql")]
+mod temporal {
+    use super::*;
+    use grafeo_common::types::Timestamp;
+
+    const NEW_YEAR: i32 = 19_723; // 2024-01-01
+
+    fn day(days: i32) -> Timestamp {
+        Timestamp::from_secs(i64::from(days) * 86_400)
+    }
+
+    fn create_events() -> GrafeoDB {
+        let db = GrafeoDB::new_in_memory();
+        for (name, created) in [
+            ("launch", Value::Date(NEW_YEAR + 10)),
+            ("eve", Value::Date(NEW_YEAR - 1)),
+            ("midnight", Value::Timestamp(day(NEW_YEAR))),
+            (
+                "breakfast",
+                Value::Timestamp(day(NEW_YEAR).add_micros(8 * 3_600_000_000)),
+            ),
+            ("archive", Value::Date(NEW_YEAR - 400)),
+        ] {
+            db.create_node_with_props(
+                &["Event"],
+                [("name", Value::from(name)), ("created", created)],
+            );
+        }
+        db
+    }
+
+    fn names(session: &grafeo_engine::Session, query: &str) -> Vec<String> {
+        let result = session.execute(query).unwrap();
+        result
+            .rows
+            .iter()
+            .map(|row| row[0].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_temporal_literals() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        let result = session
+            .execute(
+                "UNWIND [0] AS z RETURN date('2024-01-01'), DATE '2024-02-29', \
+                 datetime('2024-01-01T08:00:00Z'), TIMESTAMP '2024-01-01 08:00:00+01:00', \
+                 duration('P1DT2H'), duration({hours: 26})",
+            )
+            .unwrap();
+        assert_eq!(
+            result.rows[0],
+            vec![
+                Value::Date(NEW_YEAR),
+                Value::Date(NEW_YEAR + 59),
+                Value::Timestamp(day(NEW_YEAR).add_micros(8 * 3_600_000_000)),
+                Value::Timestamp(day(NEW_YEAR).add_micros(7 * 3_600_000_000)),
+                Value::Duration(26 * 3_600_000_000),
+                Value::Duration(26 * 3_600_000_000),
+            ]
+        );
+        assert_eq!(result.rows[0][0].to_string(), "2024-01-01");
+        assert_eq!(result.rows[0][4].to_string(), "P1DT2H");
+
+        // Malformed literals are errors, not nulls
+        for query in [
+            "UNWIND [0] AS z RETURN date('2024-02-30')",
+            "UNWIND [0] AS z RETURN DATE '01/02/2024'",
+            "UNWIND [0] AS z RETURN duration('P1M')",
+        ] {
+            assert!(session.execute(query).is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn test_temporal_ordering_mixes_dates_and_timestamps() {
+        let db = create_events();
+        let session = db.session();
+        assert_eq!(
+            names(&session, "MATCH (e:Event) RETURN e.name ORDER BY e.created"),
+            ["archive", "eve", "midnight", "breakfast", "launch"]
+        );
+
+        // A date equals the timestamp at its midnight
+        assert_eq!(
+            names(
+                &session,
+                "MATCH (e:Event) WHERE e.created = date('2024-01-01') RETURN e.name"
+            ),
+            ["midnight"]
+        );
+        let mut later = names(
+            &session,
+            "MATCH (e:Event) WHERE e.created > date('2024-01-01') RETURN e.name",
+        );
+        later.sort();
+        assert_eq!(later, ["breakfast", "launch"]);
+
+        // Durations never compare with dates, so nothing matches
+        assert!(
+            names(
+                &session,
+                "MATCH (e:Event) WHERE e.created > duration('P1D') RETURN e.name"
+            )
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_temporal_range_scan_uses_zone_maps() {
+        let db = GrafeoDB::new_in_memory();
+        // Dates rise with node ids; the last 999 fall after 2024-01-01
+        for i in 0..10_000 {
+            db.create_node_with_props(&["Item"], [("created", Value::Date(NEW_YEAR - 9_000 + i))]);
+        }
+
+        let result = db
+            .execute("MATCH (n:Item) WHERE n.created > date('2024-01-01') RETURN n.created")
+            .unwrap();
+        assert_eq!(result.row_count(), 999);
+        assert!(
+            result
+                .rows
+                .iter()
+                .all(|row| matches!(row[0], Value::Date(d) if d > NEW_YEAR))
+        );
+        assert_eq!(result.stats.chunks_skipped, 4);
+        assert_eq!(result.stats.chunks_scanned, 1);
+    }
+}
+
+// ============================================================================
+// Degree Tests
+// ============================================================================
+
+#[cfg(feature = "gql")]
+mod degree {
+    use super::*;
+
+    /// Rows of `(name, count)`, sorted by name.
+    fn counts(db: &GrafeoDB, query: &str) -> Vec<(String, i64)> {
+        let mut rows: Vec<(String, i64)> = db
+            .execute(query)
+            .unwrap()
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row[0].as_str().unwrap().to_string(),
+                    row[1].as_int64().unwrap(),
+                )
+            })
+            .collect();
+        rows.sort();
+        rows
+    }
+
+    #[test]
+    fn test_degree_matches_expand_and_count() {
+        let db = create_social_network();
+
+        for (direction, pattern) in [
+            ("OUT", "(n)-[{type}]->(m)"),
+            ("IN", "(n)<-[{type}]-(m)"),
+            ("BOTH", "(n)-[{type}]-(m)"),
+        ] {
+            for edge_type in ["KNOWS", "WORKS_AT"] {
+                let pattern = pattern.replace("{type}", &format!(":{edge_type}"));
+                let expanded = counts(&db, &format!("MATCH {pattern} RETURN n.name, count(m)"));
+                assert!(!expanded.is_empty());
+                let fast = counts(
+                    &db,
+                    &format!(
+                        "MATCH (n) WHERE degree(n, '{direction}', '{edge_type}') > 0 \
+                         RETURN n.name, degree(n, '{direction}', '{edge_type}')"
+                    ),
+                );
+                assert_eq!(fast, expanded, "{direction} {edge_type}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_degree_defaults_to_both_directions() {
+        let db = create_social_network();
+        assert_eq!(
+            counts(&db, "MATCH (n:Person) RETURN n.name, degree(n)"),
+            [
+                ("Alice".to_string(), 3),
+                ("Bob".to_string(), 3),
+                ("Carol".to_string(), 3)
+            ]
+        );
+        assert_eq!(
+            counts(&db, "MATCH (n:Company) RETURN n.name, degree(n, 'out')"),
+            [("Startup".to_string(), 0), ("TechCorp".to_string(), 0)]
+        );
+    }
+}
+
+// ============================================================================
+// Shortest Path Tests
+// ============================================================================
+
+/// Creates a road network where the fewest hops and the fewest km differ.
+///
+/// Structure (every ROAD has a `km` property):
+/// - a -ROAD(1)-> b -ROAD(1)-> c -ROAD(1)-> d
+/// - a -ROAD(5)-> e -ROAD(5)-> d
+/// - d -RAIL-> f
+#[cfg(any(feature = "gql", feature = "cypher"))]
+fn create_road_network() -> GrafeoDB {
+    let db = GrafeoDB::new_in_memory();
+    let ids: Vec<_> = ["a", "b", "c", "d", "e", "f"]
+        .into_iter()
+        .map(|name| db.create_node_with_props(&["City"], [("name", Value::from(name))]))
+        .collect();
+    for (src, dst, km) in [(0, 1, 1), (1, 2, 1), (2, 3, 1), (0, 4, 5), (4, 3, 5)] {
+        db.create_edge_with_props(ids[src], ids[dst], "ROAD", [("km", Value::Int64(km))]);
+    }
+    db.create_edge(ids[3], ids[5], "RAIL");
+    db
+}
+
+#[cfg(feature = "gql")]
+mod shortest_paths {
+    use super::*;
+
+    #[test]
+    fn test_shortest_path_binds_length_and_edges() {
+        let db = create_road_network();
+        let session = db.session();
+
+        let result = session
+            .execute(
+                "MATCH p = shortestPath((a:City {name: 'a'})-[*]->(f:City {name: 'f'})) \
+                 RETURN length(p), cost(p, 'km'), p",
+            )
+            .unwrap();
+        assert_eq!(result.row_count(), 1);
+        assert_eq!(result.rows[0][0], Value::Int64(3));
+        // The RAIL edge has no km, so the total is unknown
+        assert_eq!(result.rows[0][1], Value::Null);
+
+        // The path binds as its edges, first hop first
+        let Value::List(edges) = &result.rows[0][2] else {
+            panic!("expected a list of edges, got {:?}", result.rows[0][2]);
+        };
+        assert_eq!(edges.len(), 3);
+    }
+
+    #[test]
+    fn test_shortest_path_edge_type_and_hop_limit() {
+        let db = create_road_network();
+        let session = db.session();
+
+        let result = session
+            .execute(
+                "MATCH p = shortestPath((a:City {name: 'a'})-[:ROAD*]->(d:City {name: 'd'})) \
+                 RETURN length(p), cost(p, 'km')",
+            )
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Int64(2), Value::Int64(10)]]);
+
+        // Too few hops to reach f, so the path is null
+        let result = session
+            .execute(
+                "MATCH p = shortestPath((a:City {name: 'a'})-[*..2]->(f:City {name: 'f'})) \
+                 RETURN length(p)",
+            )
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Null]]);
+    }
+}
+
+// ============================================================================
+// Cypher Tests
+// ============================================================================
+
+#[cfg(feature = "cypher")]
+mod cypher_tests {
+    use super::*;
+
+    #[test]
+    fn test_shortest_path_over_roads() {
+        let db = create_road_network();
+        let session = db.session();
+
+        let result = session
+            .execute_cypher(
+                "MATCH p = shortestPath((a:City {name: 'a'})-[:ROAD*..3]-(d:City {name: 'd'})) \
+                 RETURN length(p), cost(p, 'km')",
+            )
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Int64(2), Value::Int64(10)]]);
+
+        let result = session
+            .execute_cypher(
+                "MATCH p = shortestPath((f:City {name: 'f'})-[*]->(a:City {name: 'a'})) \
+                 RETURN length(p)",
+            )
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Null]]);
+    }
+
+    #[test]
+    fn test_match_all_nodes() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session.execute_cypher("MATCH (n) RETURN n").unwrap();
+        assert_eq!(result.row_count(), 5, "Should find 5 nodes");
+    }
+
+    #[test]
+    fn test_match_nodes_by_label() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session.execute_cypher("MATCH (n:Person) RETURN n").unwrap();
+        assert_eq!(result.row_count(), 3, "Should find 3 Person nodes");
+    }
+
+    #[test]
+    fn test_match_with_property_filter() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session
+            .execute_cypher("MATCH (n:Person) WHERE n.age > 28 RETURN n.name")
+            .unwrap();
+        assert_eq!(result.row_count(), 2, "Should find 2 people older than 28");
+    }
+
+    #[test]
+    fn test_match_relationship_pattern() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session
+            .execute_cypher("MATCH (a:Person)-[:KNOWS]->(b:Person) RETURN a.name, b.name")
+            .unwrap();
+        assert_eq!(result.row_count(), 3, "Should find 3 KNOWS relationships");
+    }
+
+    #[test]
+    fn test_count() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session
+            .execute_cypher("MATCH (n:Person) RETURN count(n)")
+            .unwrap();
+
+        assert_eq!(result.row_count(), 1);
+        if let Value::Int64(count) = &result.rows[0][0] {
+            assert_eq!(*count, 3);
+        }
+    }
+
+    #[test]
+    fn test_create_node() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        session
+            .execute_cypher("CREATE (:Person {name: 'Alice', age: 30})")
+            .unwrap();
+
+        let result = session
+            .execute_cypher("MATCH (n:Person) RETURN n.name, n.age")
+            .unwrap();
+        assert_eq!(result.row_count(), 1);
+    }
+
+    #[test]
+    fn test_two_hop_path() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session
+            .execute_cypher("MATCH (a:Person)-[:KNOWS]->(b:Person)-[:KNOWS]->(c:Person) RETURN a.name, b.name, c.name")
+            .unwrap();
+
+        assert!(
+            result.row_count() >= 1,
+            "Should find at least one 2-hop path"
+        );
+    }
+
+    #[test]
+    fn test_set_map_replace_vs_merge() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        session
+            .execute_cypher("CREATE (:Person {name: 'Alice', age: 30, city: 'Oslo'})")
+            .unwrap();
+        session
+            .execute_cypher("CREATE (:Robot {name: 'R2', age: 40, city: 'Naboo'})")
+            .unwrap();
+
+        // `=` removes properties the map doesn't mention
+        session
+            .execute_cypher("MATCH (n:Person) SET n = {name: 'Alicia', age: 31}")
+            .unwrap();
+        let result = session
+            .execute_cypher("MATCH (n:Person) RETURN n.name, n.age, n.city")
+            .unwrap();
+        assert_eq!(
+            result.rows[0],
+            vec![
+                Value::String("Alicia".into()),
+                Value::Int64(31),
+                Value::Null
+            ]
+        );
+
+        // `+=` keeps them
+        session
+            .execute_cypher("MATCH (n:Robot) SET n += {name: 'C3PO', age: 41}")
+            .unwrap();
+        let result = session
+            .execute_cypher("MATCH (n:Robot) RETURN n.name, n.age, n.city")
+            .unwrap();
+        assert_eq!(
+            result.rows[0],
+            vec![
+                Value::String("C3PO".into()),
+                Value::Int64(41),
+                Value::String("Naboo".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_merge_creates_then_matches_node() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        let merge = "MERGE (n:Person {name: 'Alice'}) \
+                     ON CREATE SET n.visits = 1 \
+                     ON MATCH SET n.visits = 2";
+
+        // Create path: nothing to match, so the node is created
+        session.execute_cypher(merge).unwrap();
+        assert_eq!(db.node_count(), 1);
+        let result = session
+            .execute_cypher("MATCH (n:Person) RETURN n.visits")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Int64(1)]]);
+
+        // Match path: the same node comes back and only ON MATCH runs
+        session.execute_cypher(merge).unwrap();
+        assert_eq!(db.node_count(), 1);
+        let result = session
+            .execute_cypher("MATCH (n:Person) RETURN n.visits")
+            .unwrap();
+        assert_eq!(result.rows, vec![vec![Value::Int64(2)]]);
+    }
+
+    #[test]
+    fn test_merge_runs_once_per_input_row() {
+        let db = create_social_network();
+        let session = db.session();
+        let nodes = db.node_count();
+
+        // The first row creates the node; the others match it
+        let result = session
+            .execute_cypher("MATCH (p:Person) MERGE (c:Country {name: 'Norway'}) RETURN p, c")
+            .unwrap();
+        assert_eq!(result.row_count(), 3);
+        assert_eq!(db.node_count(), nodes + 1);
+    }
+
+    #[test]
+    fn test_merge_relationship_creates_only_missing_edge() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        session
+            .execute_cypher("CREATE (:Person {name: 'Alice'})")
+            .unwrap();
+        session
+            .execute_cypher("CREATE (:Person {name: 'Bob'})")
+            .unwrap();
+        let merge = "MATCH (a:Person {name: 'Alice'}), (b:Person {name: 'Bob'}) \
+                     MERGE (a)-[r:KNOWS]->(b) \
+                     ON CREATE SET r.since = 2020 \
+                     ON MATCH SET r.seen = true";
+
+        // Both endpoints exist: only the edge is created
+        session.execute_cypher(merge).unwrap();
+        assert_eq!(db.node_count(), 2);
+        assert_eq!(db.edge_count(), 1);
+
+        // Second run matches the edge and takes the ON MATCH branch
+        session.execute_cypher(merge).unwrap();
+        assert_eq!(db.edge_count(), 1);
+        let result = session
+            .execute_cypher("MATCH (:Person)-[r:KNOWS]->(:Person) RETURN r.since, r.seen")
+            .unwrap();
+        assert_eq!(
+            result.rows,
+            vec![vec![Value::Int64(2020), Value::Bool(true)]]
+        );
+    }
+
+    #[test]
+    fn test_merge_relationship_creates_missing_target() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        session
+            .execute_cypher("CREATE (:Person {name: 'Alice'})")
+            .unwrap();
+        let merge = "MATCH (a:Person {name: 'Alice'}) \
+                     MERGE (a)-[:LIVES_IN]->(c:City {name: 'Oslo'})";
+
+        session.execute_cypher(merge).unwrap();
+        assert_eq!(db.node_count(), 2);
+        assert_eq!(db.edge_count(), 1);
 
-#[cfg(feature = "gql")]
-mod gql_mutations {
-    use super::*;
+        session.execute_cypher(merge).unwrap();
+        assert_eq!(db.node_count(), 2);
+        assert_eq!(db.edge_count(), 1);
+    }
 
     #[test]
-    fn test_insert_node() {
+    fn test_unwind_literal_list_feeds_create() {
         let db = GrafeoDB::new_in_memory();
         let session = db.session();
 
         session
-            .execute("INSERT (:Person {name: 'Alice', age: 30})")
+            .execute_cypher("UNWIND [1, 2, 3] AS i CREATE (:Item {position: i})")
             .unwrap();
+        assert_eq!(db.node_count(), 3);
 
         let result = session
-            .execute("MATCH (n:Person) RETURN n.name, n.age")
+            .execute_cypher("MATCH (n:Item) RETURN n.position")
             .unwrap();
-        assert_eq!(result.row_count(), 1, "Should have 1 Person node");
-        // Note: Property values are stored correctly but order may vary
-        // Check name exists
-        assert!(
-            result.rows[0]
-                .iter()
-                .any(|v| *v == Value::String("Alice".into())),
-            "Should find Alice in result"
+        let mut rows = result.rows;
+        rows.sort_by_key(|row| format!("{:?}", row[0]));
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::Int64(1)],
+                vec![Value::Int64(2)],
+                vec![Value::Int64(3)]
+            ]
         );
     }
 
     #[test]
-    fn test_insert_multiple_nodes() {
+    fn test_unwind_rows_of_maps_feeds_create() {
         let db = GrafeoDB::new_in_memory();
         let session = db.session();
 
-        session.execute("INSERT (:Person {name: 'Alice'})").unwrap();
-        session.execute("INSERT (:Person {name: 'Bob'})").unwrap();
-        session.execute("INSERT (:Person {name: 'Carol'})").unwrap();
+        session
+            .execute_cypher(
+                "UNWIND [{name: 'Alice'}, {name: 'Bob'}] AS row \
+                 CREATE (:Person {name: row.name})",
+            )
+            .unwrap();
+        assert_eq!(db.node_count(), 2);
 
-        let result = session.execute("MATCH (n:Person) RETURN n").unwrap();
-        assert_eq!(result.row_count(), 3, "Should have 3 Person nodes");
+        let result = session
+            .execute_cypher("MATCH (n:Person) RETURN n.name")
+            .unwrap();
+        let mut rows = result.rows;
+        rows.sort_by_key(|row| format!("{:?}", row[0]));
+        assert_eq!(
+            rows,
+            vec![
+                vec![Value::String("Alice".into())],
+                vec![Value::String("Bob".into())]
+            ]
+        );
     }
 
     #[test]
-    fn test_transaction_commit() {
-        let db = GrafeoDB::new_in_memory();
-        let mut session = db.session();
+    fn test_unwind_empty_null_and_non_list() {
+        let db = create_social_network();
+        let session = db.session();
 
-        session.begin_tx().unwrap();
-        session.execute("INSERT (:Person {name: 'Alice'})").unwrap();
-        session.commit().unwrap();
+        let result = session.execute_cypher("UNWIND [] AS x RETURN x").unwrap();
+        assert_eq!(result.row_count(), 0);
 
-        let result = session.execute("MATCH (n:Person) RETURN n").unwrap();
-        assert_eq!(result.row_count(), 1, "Node should exist after commit");
+        let result = session.execute_cypher("UNWIND null AS x RETURN x").unwrap();
+        assert_eq!(result.row_count(), 0);
+
+        assert!(session.execute_cypher("UNWIND 42 AS x RETURN x").is_err());
+
+        // Each input row expands into one row per element
+        let result = session
+            .execute_cypher("MATCH (p:Person) UNWIND [1, 2] AS x RETURN p.name, x")
+            .unwrap();
+        assert_eq!(result.row_count(), 6);
     }
 }
 
 // ============================================================================
-// Cypher Tests
+// Gremlin Tests
 // ============================================================================
 
-#[cfg(feature = "cypher")]
-mod cypher_tests {
+#[cfg(feature = "gremlin")]
+mod gremlin_tests {
     use super::*;
 
+    // Note: Many Gremlin tests are currently expected to fail due to variable
+    // binding issues in the Gremlin executor. These tests document expected
+    // behavior for when the executor is fully implemented.
+
     #[test]
-    fn test_match_all_nodes() {
+    fn test_gremlin_parser() {
+        // Verify Gremlin parsing works (execution may fail)
         let db = create_social_network();
         let session = db.session();
 
-        let result = session.execute_cypher("MATCH (n) RETURN n").unwrap();
-        assert_eq!(result.row_count(), 5, "Should find 5 nodes");
+        // Test that we can at least parse basic Gremlin syntax
+        let result = session.execute_gremlin("g.V()");
+        // The translator should work even if execution fails
+        assert!(
+            result.is_ok() || result.is_err(),
+            "Gremlin should be parsed"
+        );
     }
 
     #[test]
-    fn test_match_nodes_by_label() {
+    fn test_not_step() {
         let db = create_social_network();
         let session = db.session();
 
-        let result = session.execute_cypher("MATCH (n:Person) RETURN n").unwrap();
-        assert_eq!(result.row_count(), 3, "Should find 3 Person nodes");
+        // Only Carol has no outgoing KNOWS edge among the people
+        let result = session
+            .execute_gremlin("g.V().hasLabel('Person').not(out('KNOWS')).values('name')")
+            .unwrap();
+        assert_eq!(result.row_count(), 1);
+        assert_eq!(result.rows[0][0], Value::String("Carol".into()));
+
+        // Every person works somewhere, so only the companies have no outgoing edges
+        let result = session.execute_gremlin("g.V().not(out())").unwrap();
+        assert_eq!(result.row_count(), 2, "Should find the 2 companies");
     }
 
     #[test]
-    fn test_match_with_property_filter() {
+    fn test_add_e_to_traversal() {
         let db = create_social_network();
         let session = db.session();
 
+        session
+            .execute_gremlin(
+                "g.V().has('name', 'Carol').addE('FOLLOWS').to(V().has('name', 'Alice'))",
+            )
+            .unwrap();
+
         let result = session
-            .execute_cypher("MATCH (n:Person) WHERE n.age > 28 RETURN n.name")
+            .execute_gremlin("g.V().has('name', 'Carol').out('FOLLOWS').values('name')")
             .unwrap();
-        assert_eq!(result.row_count(), 2, "Should find 2 people older than 28");
+        assert_eq!(result.row_count(), 1);
+        assert_eq!(result.rows[0][0], Value::String("Alice".into()));
     }
 
     #[test]
-    fn test_match_relationship_pattern() {
+    fn test_project_by_properties() {
         let db = create_social_network();
         let session = db.session();
 
         let result = session
-            .execute_cypher("MATCH (a:Person)-[:KNOWS]->(b:Person) RETURN a.name, b.name")
+            .execute_gremlin("g.V().hasLabel('Person').project('name', 'age').by('name').by('age')")
             .unwrap();
-        assert_eq!(result.row_count(), 3, "Should find 3 KNOWS relationships");
+        assert_eq!(result.columns, vec!["name", "age"]);
+        let mut rows: Vec<_> = result
+            .rows
+            .iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect();
+        rows.sort_by_key(|(name, _)| format!("{name:?}"));
+        assert_eq!(
+            rows,
+            vec![
+                (Value::String("Alice".into()), Value::Int64(30)),
+                (Value::String("Bob".into()), Value::Int64(25)),
+                (Value::String("Carol".into()), Value::Int64(35)),
+            ]
+        );
     }
 
     #[test]
-    fn test_count() {
+    fn test_value_map_and_element_map() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let map = |query: &str| {
+            let result = session.execute_gremlin(query).unwrap();
+            assert_eq!(result.row_count(), 1);
+            match &result.rows[0][0] {
+                Value::Map(map) => map
+                    .iter()
+                    .map(|(key, value)| (key.as_str().to_string(), value.clone()))
+                    .collect::<Vec<_>>(),
+                other => panic!("Expected a map, got {other:?}"),
+            }
+        };
+
+        assert_eq!(
+            map("g.V().has('name', 'Alice').valueMap()"),
+            vec![
+                ("age".to_string(), Value::Int64(30)),
+                ("name".to_string(), Value::String("Alice".into())),
+            ]
+        );
+        assert_eq!(
+            map("g.V().has('name', 'Alice').valueMap('name')"),
+            vec![("name".to_string(), Value::String("Alice".into()))]
+        );
+
+        let element = map("g.V().has('name', 'TechCorp').elementMap('founded')");
+        let keys: Vec<_> = element.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["founded", "id", "label"]);
+        assert!(element.contains(&("label".to_string(), Value::String("Company".into()))));
+    }
+
+    #[test]
+    fn test_path_of_traversal() {
         let db = create_social_network();
         let session = db.session();
 
         let result = session
-            .execute_cypher("MATCH (n:Person) RETURN count(n)")
+            .execute_gremlin("g.V().has('name', 'Alice').out('KNOWS').out('KNOWS').path()")
             .unwrap();
-
         assert_eq!(result.row_count(), 1);
-        if let Value::Int64(count) = &result.rows[0][0] {
-            assert_eq!(*count, 3);
-        }
+        let Value::List(path) = &result.rows[0][0] else {
+            panic!("Expected a path list, got {:?}", result.rows[0][0]);
+        };
+        // Alice, then the edge and the person each hop reaches
+        assert_eq!(path.len(), 5);
     }
 
     #[test]
-    fn test_create_node() {
-        let db = GrafeoDB::new_in_memory();
+    fn test_simple_and_cyclic_paths() {
+        let db = create_social_network();
         let session = db.session();
 
-        session
-            .execute_cypher("CREATE (:Person {name: 'Alice', age: 30})")
-            .unwrap();
+        let names = |query: &str| {
+            let result = session.execute_gremlin(query).unwrap();
+            let mut names: Vec<_> = result
+                .rows
+                .iter()
+                .map(|row| format!("{:?}", row[0]))
+                .collect();
+            names.sort();
+            names
+        };
+
+        // Two hops from Alice either reach someone new or come back to her
+        assert_eq!(
+            names(
+                "g.V().has('name', 'Alice').both('KNOWS').both('KNOWS').simplePath().values('name')"
+            ),
+            vec![
+                format!("{:?}", Value::String("Bob".into())),
+                format!("{:?}", Value::String("Carol".into())),
+            ]
+        );
+        assert_eq!(
+            names(
+                "g.V().has('name', 'Alice').both('KNOWS').both('KNOWS').cyclicPath().values('name')"
+            ),
+            vec![format!("{:?}", Value::String("Alice".into())); 2]
+        );
+    }
+
+    #[test]
+    fn test_union_of_branches() {
+        let db = create_social_network();
+        let session = db.session();
 
         let result = session
-            .execute_cypher("MATCH (n:Person) RETURN n.name, n.age")
+            .execute_gremlin(
+                "g.V().has('name', 'Alice').union(out('KNOWS'), out('WORKS_AT')).values('name')",
+            )
             .unwrap();
-        assert_eq!(result.row_count(), 1);
+        let mut names: Vec<_> = result
+            .rows
+            .iter()
+            .map(|row| format!("{:?}", row[0]))
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                format!("{:?}", Value::String("Bob".into())),
+                format!("{:?}", Value::String("Carol".into())),
+                format!("{:?}", Value::String("TechCorp".into())),
+            ]
+        );
     }
 
     #[test]
-    fn test_two_hop_path() {
+    fn test_coalesce_takes_first_non_empty_branch() {
         let db = create_social_network();
         let session = db.session();
 
+        // Carol knows nobody, so only she falls through to her employer
         let result = session
-            .execute_cypher("MATCH (a:Person)-[:KNOWS]->(b:Person)-[:KNOWS]->(c:Person) RETURN a.name, b.name, c.name")
+            .execute_gremlin(
+                "g.V().hasLabel('Person').coalesce(out('KNOWS'), out('WORKS_AT')).values('name')",
+            )
             .unwrap();
+        let mut names: Vec<_> = result
+            .rows
+            .iter()
+            .map(|row| format!("{:?}", row[0]))
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                format!("{:?}", Value::String("Bob".into())),
+                format!("{:?}", Value::String("Carol".into())),
+                format!("{:?}", Value::String("Carol".into())),
+                format!("{:?}", Value::String("Startup".into())),
+            ]
+        );
+    }
 
-        assert!(
-            result.row_count() >= 1,
-            "Should find at least one 2-hop path"
+    #[test]
+    fn test_group_count_by_property() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session
+            .execute_gremlin("g.V().hasLabel('Person').out('WORKS_AT').groupCount().by('name')")
+            .unwrap();
+        assert_eq!(result.rows.len(), 1);
+        let Value::Map(counts) = &result.rows[0][0] else {
+            panic!("Expected a map, got {:?}", result.rows[0][0]);
+        };
+        let counts: Vec<_> = counts
+            .iter()
+            .map(|(name, count)| (name.as_str(), count.clone()))
+            .collect();
+        assert_eq!(
+            counts,
+            vec![("Startup", Value::Int64(1)), ("TechCorp", Value::Int64(2))]
         );
     }
-}
 
-// ============================================================================
-// Gremlin Tests
-// ============================================================================
+    #[test]
+    fn test_group_by_collect_returns_one_map() {
+        let db = create_social_network();
+        let session = db.session();
 
-#[cfg(feature = "gremlin")]
-mod gremlin_tests {
-    use super::*;
+        let result = session
+            .execute_gremlin("g.V().hasLabel('Person').out('KNOWS').group().by('name').by('age')")
+            .unwrap();
+        assert_eq!(result.columns, vec!["group"]);
+        assert_eq!(result.rows.len(), 1);
+        let Value::Map(groups) = &result.rows[0][0] else {
+            panic!("Expected a map, got {:?}", result.rows[0][0]);
+        };
+        let groups: Vec<_> = groups
+            .iter()
+            .map(|(name, ages)| {
+                let Value::List(ages) = ages else {
+                    panic!("Expected a list for {name}, got {ages:?}");
+                };
+                (name.as_str(), ages.to_vec())
+            })
+            .collect();
+        // Carol is known by both Alice and Bob
+        assert_eq!(
+            groups,
+            vec![
+                ("Bob", vec![Value::Int64(25)]),
+                ("Carol", vec![Value::Int64(35), Value::Int64(35)]),
+            ]
+        );
+    }
 
-    // Note: Many Gremlin tests are currently expected to fail due to variable
-    // binding issues in the Gremlin executor. These tests document expected
-    // behavior for when the executor is fully implemented.
+    #[test]
+    fn test_select_labelled_vertices_by_property() {
+        let db = create_social_network();
+        let session = db.session();
+
+        let result = session
+            .execute_gremlin(
+                "g.V().has('name', 'Alice').as('a').out('KNOWS').as('b').select('a', 'b').by('name')",
+            )
+            .unwrap();
+        assert_eq!(result.columns, vec!["a", "b"]);
+        let mut pairs: Vec<_> = result
+            .rows
+            .iter()
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect();
+        pairs.sort_by_key(|(_, b)| format!("{b:?}"));
+        assert_eq!(
+            pairs,
+            vec![
+                (Value::String("Alice".into()), Value::String("Bob".into())),
+                (Value::String("Alice".into()), Value::String("Carol".into())),
+            ]
+        );
+    }
 
     #[test]
-    fn test_gremlin_parser() {
-        // Verify Gremlin parsing works (execution may fail)
+    fn test_side_effect_step_passes_vertices_through() {
         let db = create_social_network();
         let session = db.session();
 
-        // Test that we can at least parse basic Gremlin syntax
-        let result = session.execute_gremlin("g.V()");
-        // The translator should work even if execution fails
-        assert!(
-            result.is_ok() || result.is_err(),
-            "Gremlin should be parsed"
+        let result = session
+            .execute_gremlin(
+                "g.V().hasLabel('Person').sideEffect(property('seen', true)).identity().values('name')",
+            )
+            .unwrap();
+        let mut names: Vec<_> = result.rows.iter().map(|row| row[0].clone()).collect();
+        names.sort_by_key(|name| format!("{name:?}"));
+        assert_eq!(
+            names,
+            vec![
+                Value::String("Alice".into()),
+                Value::String("Bob".into()),
+                Value::String("Carol".into()),
+            ]
         );
+
+        let seen = session
+            .execute("MATCH (p:Person) WHERE p.seen = true RETURN p.name")
+            .unwrap();
+        assert_eq!(seen.row_count(), 3, "sideEffect should set the property");
     }
 
     #[test]
@@ -870,6 +2119,85 @@ mod graphql_tests {
 
         assert!(result.row_count() >= 1, "Should return nested results");
     }
+
+    #[test]
+    fn test_nested_query_returns_nested_json() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        let person = |name: &str| {
+            session.create_node_with_props(&["Person"], [("name", Value::String(name.into()))])
+        };
+        let alice = person("Alice");
+        let bob = person("Bob");
+        let carol = person("Carol");
+        session.create_edge(alice, bob, "knows");
+        session.create_edge(alice, carol, "knows");
+
+        let json = session
+            .execute_graphql_nested("query { person { name knows { name } } }")
+            .unwrap();
+
+        // Carol and Bob know nobody, but still appear with an empty list
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"data":{"person":["#,
+                r#"{"name":"Alice","knows":[{"name":"Bob"},{"name":"Carol"}]},"#,
+                r#"{"name":"Bob","knows":[]},"#,
+                r#"{"name":"Carol","knows":[]}"#,
+                r#"]}}"#
+            )
+        );
+    }
+
+    #[test]
+    fn test_schema_resolution_follows_cardinality() {
+        use grafeo_engine::query::graphql_schema::{GraphQLSchema, ObjectType, RelationField};
+
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+        let named = |label: &str, name: &str| {
+            session.create_node_with_props(&[label], [("name", Value::String(name.into()))])
+        };
+        let alice = named("Person", "Alice");
+        let bob = named("Person", "Bob");
+        let acme = named("Company", "Acme");
+        session.create_edge(alice, bob, "KNOWS");
+        session.create_edge(alice, acme, "WORKS_AT");
+
+        let schema = GraphQLSchema::new()
+            .with_type(
+                ObjectType::new("Person")
+                    .with_property("name")
+                    .with_relation("friends", RelationField::many("KNOWS", "Person"))
+                    .with_relation("employer", RelationField::one("WORKS_AT", "Company")),
+            )
+            .with_type(ObjectType::new("Company").with_property("name"))
+            .with_query_list("people", "Person");
+
+        let json = session
+            .execute_graphql_with_schema(
+                &schema,
+                "{ people { name employer { name } friends { name } } }",
+            )
+            .unwrap();
+
+        // To-one fields are an object or null, to-many fields a list
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"data":{"people":["#,
+                r#"{"name":"Alice","employer":{"name":"Acme"},"friends":[{"name":"Bob"}]},"#,
+                r#"{"name":"Bob","employer":null,"friends":[]}"#,
+                r#"]}}"#
+            )
+        );
+
+        let err = session
+            .execute_graphql_with_schema(&schema, "{ people { salary } }")
+            .unwrap_err();
+        assert!(err.to_string().contains("Cannot query field \"salary\""));
+    }
 }
 
 // ============================================================================
@@ -877,6 +2205,81 @@ mod graphql_tests {
 // ============================================================================
 
 /// Tests that verify consistent results across GQL and Cypher
+#[cfg(all(feature = "gql", feature = "cypher"))]
+mod collection_expressions {
+    use super::*;
+
+    fn single_value(result: grafeo_engine::database::QueryResult) -> Value {
+        assert_eq!(result.row_count(), 1);
+        result.rows[0][0].clone()
+    }
+
+    // Neither language has a bare RETURN, so the queries unwind a single row
+
+    #[test]
+    fn test_list_index_and_map_key() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        for query in [
+            "UNWIND [0] AS z RETURN [1, 2, 3][1]",
+            "UNWIND [0] AS z RETURN {a: 1}.a",
+        ] {
+            let gql = single_value(session.execute(query).unwrap());
+            let cypher = single_value(session.execute_cypher(query).unwrap());
+            assert_eq!(gql, cypher, "{query}");
+        }
+        assert_eq!(
+            single_value(
+                session
+                    .execute("UNWIND [0] AS z RETURN [1, 2, 3][1]")
+                    .unwrap()
+            ),
+            Value::Int64(2)
+        );
+        assert_eq!(
+            single_value(session.execute("UNWIND [0] AS z RETURN {a: 1}.a").unwrap()),
+            Value::Int64(1)
+        );
+        assert_eq!(
+            single_value(
+                session
+                    .execute("UNWIND [0] AS z RETURN {a: {b: 'x'}}['a'].b")
+                    .unwrap()
+            ),
+            Value::String("x".into())
+        );
+    }
+
+    #[test]
+    fn test_index_edge_cases() {
+        let db = GrafeoDB::new_in_memory();
+        let session = db.session();
+
+        // Out of range and null are null
+        for query in [
+            "UNWIND [0] AS z RETURN [1, 2, 3][3]",
+            "UNWIND [0] AS z RETURN [1, 2, 3][-4]",
+            "UNWIND [0] AS z RETURN [1, 2, 3][null]",
+            "UNWIND [0] AS z RETURN {a: 1}.b",
+        ] {
+            assert_eq!(
+                single_value(session.execute(query).unwrap()),
+                Value::Null,
+                "{query}"
+            );
+        }
+
+        // Indexing a non-collection is an error
+        assert!(session.execute("UNWIND [0] AS z RETURN 5[0]").is_err());
+        assert!(
+            session
+                .execute_cypher("UNWIND [0] AS z RETURN 5[0]")
+                .is_err()
+        );
+    }
+}
+
 #[cfg(all(feature = "gql", feature = "cypher"))]
 mod cross_language_consistency_gql_cypher {
     use super::*;
@@ -1086,3 +2489,37 @@ mod cross_language_mutations {
         );
     }
 }
+
+#[test]
+fn test_order_by_limit_uses_top_n_and_matches_full_sort() {
+    let db = GrafeoDB::new_in_memory();
+    for i in 0..500_i64 {
+        // Many ties on score, broken by insertion order like a full sort
+        db.create_node_with_props(&["Item"], [("score", (i * 37) % 20), ("id", i)]);
+    }
+    let session = db.session();
+
+    let plan = session
+        .explain("MATCH (n:Item) RETURN n.id ORDER BY n.score DESC LIMIT 7")
+        .unwrap();
+    assert!(plan.operators().iter().any(|op| op.operator == "TopN"));
+
+    let full = session
+        .execute("MATCH (n:Item) RETURN n.id ORDER BY n.score DESC")
+        .unwrap();
+    for (skip, limit) in [(0, 7), (3, 25), (490, 20)] {
+        let query = if skip == 0 {
+            format!("MATCH (n:Item) RETURN n.id ORDER BY n.score DESC LIMIT {limit}")
+        } else {
+            format!("MATCH (n:Item) RETURN n.id ORDER BY n.score DESC SKIP {skip} LIMIT {limit}")
+        };
+        let top = session.execute(&query).unwrap();
+        let expected: Vec<_> = full.rows.iter().skip(skip).take(limit).cloned().collect();
+        assert_eq!(top.rows, expected, "{query}");
+    }
+
+    let none = session
+        .execute("MATCH (n:Item) RETURN n.id ORDER BY n.score LIMIT 0")
+        .unwrap();
+    assert_eq!(none.row_count(), 0);
+}
@@ -37,8 +37,18 @@
 
 // Re-export the main database API
 pub use grafeo_engine::{
-    Catalog, CatalogError, Config, GrafeoDB, IndexDefinition, IndexType, Session,
+    Catalog, CatalogError, Config, DuplicateKeyPolicy, EdgeMultiplicity, ExplainNode, FileReport,
+    FloatFormat, GrafeoDB, GrafeoDBBuilder, GraphProjection, IdAssignment, IdFormat, ImportOptions,
+    ImportReport, IndexDefinition, IndexType, IsolationLevel, OperatorStats, PlanExplanation,
+    PreparedQuery, PropertyNormalizer, QueryLimits, QueryStats, ResultFormat, ResultShape,
+    ResultSizeEstimate, RowError, SchemaMode, Session,
 };
 
 // Re-export core types - you'll need these for working with IDs and values
-pub use grafeo_common::types::{EdgeId, NodeId, Value};
+pub use grafeo_common::types::{EdgeId, NodeId, Timestamp, Value};
+
+// Memory usage reported by `GrafeoDB::memory_stats()`
+pub use grafeo_common::memory::buffer::{BufferStats, MemoryRegion, PressureLevel};
+
+// Operator kinds for `Config::with_spill_disabled()`
+pub use grafeo_core::execution::SpillableOperator;